@@ -38,6 +38,13 @@
 //! ```text
 //! cargo run --bin sim-fabric --release -- --port-bits-per-tick 128 --frame-overhead-bytes 20 --frame-payload-bytes 1484B --bytes-to-send 1MiB --traffic-pattern all-to-all-fixed --seed 3 --stdout
 //! ```
+//!
+//! The energy used by the fabric and its average power can be reported with
+//! the bandwidth by giving an energy per hop, an energy per bit and an idle
+//! power per node, so that bandwidth and power can be traded off:
+//! ```text
+//! cargo run --bin sim-fabric --release -- --routed --hop-energy-pj 1.5 --bit-energy-pj 0.1 --idle-power-mw 5 --bytes-to-send 1MiB --stdout
+//! ```
 
 pub mod access_gen;
 pub mod source_sink_builder;
@@ -5,7 +5,7 @@
 //! See `lib.rs` for details.
 use std::rc::Rc;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use gwr_components::cli::parse_bytes_string;
 use gwr_components::connect_port;
 use gwr_engine::engine::Engine;
@@ -14,14 +14,19 @@ use gwr_engine::time::clock::Clock;
 use gwr_engine::time::compute_adjusted_value_and_rate;
 use gwr_engine::types::SimError;
 use gwr_engine::{run_simulation, sim_error};
+use gwr_models::energy::EnergyConfig;
+use gwr_models::fabric::crossbar::CrossbarFabric;
+use gwr_models::fabric::deadlock::check_deadlock_free;
+use gwr_models::fabric::fat_tree::{FatTreeConfig, FatTreeFabric};
 use gwr_models::fabric::functional::FunctionalFabric;
 use gwr_models::fabric::node::FabricRoutingAlgorithm;
 use gwr_models::fabric::routed::RoutedFabric;
-use gwr_models::fabric::{Fabric, FabricConfig};
+use gwr_models::fabric::{Fabric, FabricConfig, FabricTopology};
 use gwr_models::memory::memory_access::MemoryAccess;
-use gwr_track::builder::{TrackerArgs, setup_trackers};
+use gwr_track::builder::{TrackerArgs, setup_trackers_from_args};
 use gwr_track::entity::Entity;
-use gwr_track::{Track, error, info};
+use gwr_track::metadata::RunMetadata;
+use gwr_track::{Track, error, info, warn};
 use indicatif::ProgressBar;
 use sim_fabric::access_gen::TrafficPattern;
 use sim_fabric::source_sink_builder::{Sinks, build_source_sinks};
@@ -111,6 +116,62 @@ struct Cli {
     /// Seed for random number generator.
     #[clap(long, default_value_t, value_enum)]
     fabric_routing: FabricRoutingAlgorithm,
+
+    /// How the fabric nodes are connected.
+    #[clap(long, default_value_t, value_enum)]
+    fabric_topology: FabricTopology,
+
+    /// Use a single-stage crossbar fabric where every frame takes this many
+    /// ticks to cross from ingress to egress.
+    #[clap(long)]
+    crossbar_latency: Option<usize>,
+
+    /// Use a fat-tree fabric with this many spines. Each fabric node becomes
+    /// a leaf of the fat-tree.
+    #[clap(long)]
+    fat_tree_spines: Option<usize>,
+
+    /// Ratio of leaf port bandwidth to uplink bandwidth in a fat-tree fabric.
+    #[clap(long, default_value = "1")]
+    fat_tree_oversubscription: usize,
+
+    /// Split frames into flits of this many bytes and use wormhole switching.
+    /// Only supported by the routed model.
+    #[clap(long)]
+    flit_bytes: Option<usize>,
+
+    /// Energy (in pJ) used by each frame or flit for every hop it makes. The
+    /// energy and power of the fabric are reported when any of the energy
+    /// options are set. Only supported by the routed and functional models.
+    #[clap(long)]
+    hop_energy_pj: Option<f64>,
+
+    /// Energy (in pJ) used to move each bit one hop.
+    #[clap(long)]
+    bit_energy_pj: Option<f64>,
+
+    /// Idle power (in mW) drawn by each fabric node.
+    #[clap(long)]
+    idle_power_mw: Option<f64>,
+}
+
+impl Cli {
+    /// Returns the energy model of the fabric if any of the energy options are
+    /// set
+    fn energy_config(&self) -> Option<EnergyConfig> {
+        if self.hop_energy_pj.is_none()
+            && self.bit_energy_pj.is_none()
+            && self.idle_power_mw.is_none()
+        {
+            return None;
+        }
+        Some(
+            EnergyConfig::new()
+                .with_hop_energy_pj(self.hop_energy_pj.unwrap_or_default())
+                .with_bit_energy_pj(self.bit_energy_pj.unwrap_or_default())
+                .with_idle_power_mw(self.idle_power_mw.unwrap_or_default()),
+        )
+    }
 }
 
 /// Install an event to terminate the simulation at the clock tick defined.
@@ -149,7 +210,7 @@ fn start_frame_dump(
 }
 
 fn create_config(engine: &Engine, args: &Cli) -> (Rc<FabricConfig>, usize) {
-    let config = FabricConfig::new(
+    let mut config = FabricConfig::new(
         args.fabric_columns,
         args.fabric_rows,
         args.fabric_ports_per_node,
@@ -159,7 +220,14 @@ fn create_config(engine: &Engine, args: &Cli) -> (Rc<FabricConfig>, usize) {
         args.rx_buffer_bytes,
         args.tx_buffer_bytes,
         args.port_bits_per_tick,
-    );
+    )
+    .with_topology(args.fabric_topology);
+    if let Some(flit_bytes) = args.flit_bytes {
+        config = config.with_flit_bytes(flit_bytes);
+    }
+    if let Some(energy) = args.energy_config() {
+        config = config.with_energy(energy);
+    }
     let config = Rc::new(config);
 
     let num_payload_bytes_to_send = args.bytes_to_send;
@@ -184,8 +252,13 @@ fn create_config(engine: &Engine, args: &Cli) -> (Rc<FabricConfig>, usize) {
 }
 
 fn main() -> Result<(), SimError> {
-    let args = Cli::parse();
-    let tracker: Rc<dyn Track> = setup_trackers(&args.tracker.trackers_config()).unwrap();
+    let matches = Cli::command().get_matches();
+    let mut args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let tracker: Rc<dyn Track> = setup_trackers_from_args(&mut args.tracker, &matches)?;
+    RunMetadata::from_process()
+        .with_seed(args.seed)
+        .with_git_describe()
+        .record(&tracker);
 
     let mut engine = Engine::new(&tracker);
     let spawner = engine.spawner();
@@ -194,15 +267,41 @@ fn main() -> Result<(), SimError> {
     let (config, num_send_frames) = create_config(&engine, &args);
     let num_ports = config.num_ports();
     let top = engine.top().clone();
-    let fabric: Rc<dyn Fabric<MemoryAccess>> = if args.routed {
-        RoutedFabric::new_and_register(
+    let mut routed_fabric = None;
+    let fabric: Rc<dyn Fabric<MemoryAccess>> = if let Some(latency_ticks) = args.crossbar_latency {
+        CrossbarFabric::new_and_register(
             &engine,
             &clock,
             &top,
             "fabric",
             config.clone(),
-            args.fabric_routing,
+            latency_ticks,
+        )?
+    } else if let Some(num_spines) = args.fat_tree_spines {
+        let fat_tree_config =
+            FatTreeConfig::new(num_spines).with_oversubscription(args.fat_tree_oversubscription);
+        FatTreeFabric::new_and_register(
+            &engine,
+            &clock,
+            &top,
+            "fabric",
+            config.clone(),
+            fat_tree_config,
         )?
+    } else if args.routed {
+        if let Err(e) = check_deadlock_free(&config, args.fabric_routing) {
+            warn!(top ; "{e}");
+        }
+        let fabric = RoutedFabric::new_and_register(
+            &engine,
+            &clock,
+            &top,
+            "fabric",
+            config.clone(),
+            args.fabric_routing,
+        )?;
+        routed_fabric = Some(fabric.clone());
+        fabric
     } else {
         FunctionalFabric::new_and_register(&engine, &clock, &top, "fabric", config.clone())?
     };
@@ -276,6 +375,14 @@ fn main() -> Result<(), SimError> {
         args.frame_overhead_bytes,
         args.frame_payload_bytes,
     );
+    if config.energy().is_some() {
+        print_power(&engine, &top, clock.time_now_ns());
+    }
+
+    // Report the route choices and congestion of each link
+    if let Some(routed_fabric) = routed_fabric {
+        routed_fabric.dump_stats();
+    }
     Ok(())
 }
 
@@ -296,3 +403,26 @@ fn print_summary(
     info!(top ; "Pass: Sent {total_sunk_frames} in {time_now_ns:.2}ns.");
     info!(top ; "Payload: {payload_value:.2} ({payload_per_second:.2}/s). Total: {total_value:.2} ({total_per_second:.2}/s).");
 }
+
+/// Report the energy used and average power drawn by the components that model
+/// energy
+fn print_power(engine: &Engine, top: &Rc<Entity>, time_now_ns: f64) {
+    let power = engine.power();
+    if power.accounts().is_empty() {
+        warn!(top ; "Energy is not modelled by this fabric");
+        return;
+    }
+    for account in power.accounts() {
+        info!(top ; "{}: energy {:.2}pJ (dynamic {:.2}pJ, idle {:.2}pJ), average power {:.2}mW.",
+            account.entity().full_name(),
+            account.energy_pj(time_now_ns),
+            account.dynamic_energy_pj(),
+            account.idle_energy_pj(time_now_ns),
+            account.average_power_mw(time_now_ns),
+        );
+    }
+    info!(top ; "Energy: {:.2}pJ. Average power: {:.2}mW.",
+        power.total_energy_pj(time_now_ns),
+        power.average_power_mw(time_now_ns),
+    );
+}
@@ -6,7 +6,7 @@
 use std::rc::Rc;
 
 use byte_unit::{AdjustedByte, Byte, UnitType};
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use gwr_components::cli::parse_bytes_string;
 use gwr_components::flow_controls::limiter::Limiter;
 use gwr_components::sink::Sink;
@@ -19,8 +19,9 @@ use gwr_engine::types::SimError;
 use gwr_engine::{run_simulation, sim_error};
 use gwr_models::fc_pipeline::{FcPipeline, FcPipelineConfig};
 use gwr_models::memory::memory_access::MemoryAccess;
-use gwr_track::builder::{TrackerArgs, setup_trackers};
+use gwr_track::builder::{TrackerArgs, setup_trackers_from_args};
 use gwr_track::entity::Entity;
+use gwr_track::metadata::RunMetadata;
 use gwr_track::{Track, error, info};
 use indicatif::ProgressBar;
 use sim_pipe::frame_gen::FrameGen;
@@ -118,8 +119,12 @@ fn start_frame_dump(
 }
 
 fn main() -> Result<(), SimError> {
-    let args = Cli::parse();
-    let tracker: Rc<dyn Track> = setup_trackers(&args.tracker.trackers_config()).unwrap();
+    let matches = Cli::command().get_matches();
+    let mut args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let tracker: Rc<dyn Track> = setup_trackers_from_args(&mut args.tracker, &matches)?;
+    RunMetadata::from_process()
+        .with_git_describe()
+        .record(&tracker);
 
     let mut engine = Engine::new(&tracker);
     let clock = engine.default_clock();
@@ -4,9 +4,10 @@
 //!
 //! See `lib.rs` for details.
 
-use clap::{CommandFactory, Parser};
+use clap::{CommandFactory, FromArgMatches, Parser};
 use gwr_engine::types::SimError;
-use gwr_track::builder::{TrackerArgs, setup_trackers};
+use gwr_track::builder::{TrackerArgs, setup_trackers_from_args};
+use gwr_track::metadata::RunMetadata;
 use gwr_track::tracker::dev_null_tracker;
 use sim_restaurant::config::{RestaurantArgs, RestaurantConfig, long_arg_name};
 use sim_restaurant::sim::{RunSummary, run_sweep};
@@ -82,14 +83,20 @@ impl CliArgs {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = CliArgs::parse();
+    let matches = CliArgs::command().get_matches();
+    let mut cli = CliArgs::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    cli.tracker.load_track_config(&matches)?;
     cli.validate()?;
 
     let config = cli.sim_config();
 
     let tracker = if cli.tracking_requested() {
-        setup_trackers(&cli.tracker.trackers_config())
-            .map_err(|err| std::io::Error::other(format!("{err:?}")))?
+        let tracker = setup_trackers_from_args(&mut cli.tracker, &matches)?;
+        RunMetadata::from_process()
+            .with_seed(config.seed)
+            .with_git_describe()
+            .record(&tracker);
+        tracker
     } else {
         dev_null_tracker()
     };
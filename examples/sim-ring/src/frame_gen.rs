@@ -6,16 +6,21 @@ use gwr_model_builder::EntityGet;
 use gwr_models::ethernet_frame::{DEST_MAC_BYTES, EthernetFrame, u64_to_mac};
 use gwr_track::entity::Entity;
 
+/// The VLAN used by all tagged frames
+const VLAN_ID: u16 = 1;
+
 /// A frame Generator that can be used by the `Source` to produce frames on
 /// the fly.
 ///
 /// This allows each frame being created to be unique which aids debug of the
-/// system.
+/// system. If any `pcps` are given the frames are VLAN tagged and cycle through
+/// them, so that each source sends a mix of priorities.
 #[derive(EntityGet)]
 pub struct FrameGen {
     entity: Rc<Entity>,
     dest: [u8; DEST_MAC_BYTES],
     payload_bytes: usize,
+    pcps: Vec<u8>,
     num_send_frames: usize,
     num_sent_frames: usize,
 }
@@ -26,12 +31,14 @@ impl FrameGen {
         parent: &Rc<Entity>,
         dest: [u8; DEST_MAC_BYTES],
         payload_bytes: usize,
+        pcps: Vec<u8>,
         num_send_frames: usize,
     ) -> Self {
         Self {
             entity: Rc::new(Entity::new(parent, &format!("gen_{dest:?}"))),
             dest,
             payload_bytes,
+            pcps,
             num_send_frames,
             num_sent_frames: 0,
         }
@@ -47,11 +54,15 @@ impl Iterator for FrameGen {
 
             // Send to the correct `dest`, but set `src` to a unique value to aid debug
             // (frame count).
-            Some(
-                EthernetFrame::new(&self.entity, self.payload_bytes)
-                    .set_dest(self.dest)
-                    .set_src(u64_to_mac(label as u64)),
-            )
+            let frame = EthernetFrame::new(&self.entity, self.payload_bytes)
+                .set_dest(self.dest)
+                .set_src(u64_to_mac(label as u64));
+            if self.pcps.is_empty() {
+                Some(frame)
+            } else {
+                let pcp = self.pcps[label % self.pcps.len()];
+                Some(frame.set_vlan(VLAN_ID, pcp))
+            }
         } else {
             None
         }
@@ -27,6 +27,13 @@
 //! cargo run --bin sim-ring --release -- --bytes-to-send 1MiB --ring-priority 10 --stdout
 //! ```
 //!
+//! Frames can be VLAN tagged with a mix of priorities and the ring nodes given
+//! per-priority Tx queues, in which case the high priority frames overtake the
+//! low priority frames queued at each node:
+//! ```txt
+//! cargo run --bin sim-ring --release -- --ring-priority 10 --pcps 0,7 --priority-queues 2 --stdout
+//! ```
+//!
 //! # Diagram
 //!
 //! ```text
@@ -5,7 +5,7 @@
 //! See `lib.rs` for details.
 use std::rc::Rc;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use gwr_components::cli::parse_bytes_string;
 use gwr_components::connect_port;
 use gwr_engine::engine::Engine;
@@ -13,7 +13,8 @@ use gwr_engine::executor::Spawner;
 use gwr_engine::time::clock::Clock;
 use gwr_engine::types::SimError;
 use gwr_engine::{run_simulation, sim_error};
-use gwr_track::builder::{TrackerArgs, setup_trackers};
+use gwr_track::builder::{TrackerArgs, setup_trackers_from_args};
+use gwr_track::metadata::RunMetadata;
 use gwr_track::{Track, error, info};
 use indicatif::ProgressBar;
 use sim_ring::ring_builder::{
@@ -68,6 +69,16 @@ struct Cli {
     /// Override the default frame payload bytes.
     #[arg(long, default_value = "256", value_parser = parse_bytes_string)]
     frame_payload_bytes: usize,
+
+    /// VLAN tag the frames with these comma-separated Priority Code Points,
+    /// cycling through them to give each source a mix of priorities.
+    #[arg(long, value_delimiter = ',')]
+    pcps: Vec<u8>,
+
+    /// Replace the Tx buffer of each ring node with this many per-priority
+    /// queues (1 to 8), each the size of the Tx buffer.
+    #[arg(long)]
+    priority_queues: Option<usize>,
 }
 
 /// Install an event to terminate the simulation at the clock tick defined.
@@ -106,8 +117,12 @@ fn start_frame_dump(
 }
 
 fn main() -> Result<(), SimError> {
-    let args = Cli::parse();
-    let tracker: Rc<dyn Track> = setup_trackers(&args.tracker.trackers_config()).unwrap();
+    let matches = Cli::command().get_matches();
+    let mut args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let tracker: Rc<dyn Track> = setup_trackers_from_args(&mut args.tracker, &matches)?;
+    RunMetadata::from_process()
+        .with_git_describe()
+        .record(&tracker);
 
     let mut engine = Engine::new(&tracker);
     let spawner = engine.spawner();
@@ -120,6 +135,8 @@ fn main() -> Result<(), SimError> {
         tx_buffer_bytes: args.tx_buffer_bytes,
         frame_payload_bytes: args.frame_payload_bytes,
         num_send_frames: args.bytes_to_send / args.frame_payload_bytes,
+        pcps: args.pcps,
+        num_priority_queues: args.priority_queues,
     };
 
     let top = engine.top().clone();
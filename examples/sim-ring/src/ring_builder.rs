@@ -34,6 +34,8 @@ pub struct Config {
     pub tx_buffer_bytes: usize,
     pub frame_payload_bytes: usize,
     pub num_send_frames: usize,
+    pub pcps: Vec<u8>,
+    pub num_priority_queues: Option<usize>,
 }
 
 struct RoutingAlgorithm(usize);
@@ -52,11 +54,14 @@ where
 
 pub fn build_ring_nodes(engine: &mut Engine, clock: &Clock, config: &Config) -> Nodes {
     let limiter_128_gbps = rc_limiter!(clock, 128);
-    let ring_config = RingConfig::new(
+    let mut ring_config = RingConfig::new(
         config.rx_buffer_bytes,
         config.tx_buffer_bytes,
         limiter_128_gbps.clone(),
     );
+    if let Some(num_queues) = config.num_priority_queues {
+        ring_config = ring_config.with_priority_queues(num_queues);
+    }
     let top = engine.top();
     let ring_nodes: Nodes = (0..config.ring_size)
         .map(|i| {
@@ -91,6 +96,7 @@ pub fn build_source_sinks(engine: &mut Engine, clock: &Clock, config: &Config) -
                 top,
                 u64_to_mac(neighbour_left as u64),
                 config.frame_payload_bytes,
+                config.pcps.clone(),
                 config.num_send_frames,
             ))),
         ));
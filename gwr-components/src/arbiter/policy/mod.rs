@@ -2,8 +2,12 @@
 
 pub mod priority_round_robin;
 pub mod round_robin;
+pub mod traffic_class;
 pub mod weighted_round_robin;
+pub mod wormhole;
 
 pub use priority_round_robin::{Priority, PriorityRoundRobin};
 pub use round_robin::RoundRobin;
+pub use traffic_class::{TrafficClassRoundRobin, TrafficClasses};
 pub use weighted_round_robin::WeightedRoundRobin;
+pub use wormhole::Wormhole;
@@ -0,0 +1,176 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Traffic class arbitration policy
+//!
+//! Inputs are arbitrated according to the
+//! [traffic class](gwr_engine::traits::Routable::traffic_class) of the objects
+//! waiting at them. Each class has a strict priority and a weight:
+//!  - an object is only granted if no object of a higher priority class is
+//!    waiting.
+//!  - classes of the same priority share the output in proportion to their
+//!    weights.
+//!  - inputs with objects of the same class are granted in round-robin order.
+//!
+//! Objects in classes that have not been configured are arbitrated as class 0.
+
+use std::rc::Rc;
+
+use gwr_engine::sim_error;
+use gwr_engine::traits::{Routable, SimObject};
+use gwr_engine::types::SimError;
+use gwr_track::entity::Entity;
+use gwr_track::trace;
+
+use crate::arbiter::Arbitrate;
+
+#[derive(Clone, Copy, Debug)]
+struct ClassSettings {
+    priority: usize,
+    weight: usize,
+}
+
+/// The priority and weight of each traffic class
+#[derive(Clone, Debug, Default)]
+pub struct TrafficClasses {
+    classes: Vec<ClassSettings>,
+}
+
+impl TrafficClasses {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next traffic class, starting with class 0. Classes with a
+    /// higher `priority` always take precedence.
+    #[must_use]
+    pub fn with_class(mut self, priority: usize, weight: usize) -> Self {
+        self.classes.push(ClassSettings { priority, weight });
+        self
+    }
+
+    #[must_use]
+    pub fn num_classes(&self) -> usize {
+        self.classes.len()
+    }
+
+    #[must_use]
+    pub fn priority(&self, class: usize) -> usize {
+        self.classes[class].priority
+    }
+
+    #[must_use]
+    pub fn weight(&self, class: usize) -> usize {
+        self.classes[class].weight
+    }
+}
+
+#[derive(Clone)]
+pub struct TrafficClassRoundRobin {
+    classes: TrafficClasses,
+
+    /// The class to consider first within a priority level
+    class_candidate: usize,
+
+    /// Number of grants given to each class in its current turn
+    grants: Vec<usize>,
+
+    /// The input to consider first within each class
+    input_candidates: Vec<usize>,
+}
+
+impl TrafficClassRoundRobin {
+    pub fn new(classes: TrafficClasses) -> Result<Self, SimError> {
+        let num_classes = classes.num_classes();
+        if num_classes == 0 {
+            return sim_error!("At least one traffic class must be defined");
+        }
+        if classes.classes.iter().any(|class| class.weight == 0) {
+            return sim_error!("Traffic class weights must be greater than 0");
+        }
+
+        Ok(Self {
+            classes,
+            class_candidate: 0,
+            grants: vec![0; num_classes],
+            input_candidates: vec![0; num_classes],
+        })
+    }
+
+    fn class_of<T>(&self, value: &T) -> usize
+    where
+        T: Routable,
+    {
+        let class = value.traffic_class();
+        if class < self.classes.num_classes() {
+            class
+        } else {
+            0
+        }
+    }
+
+    /// Choose the class to grant from those with waiting objects
+    fn select_class(&self, waiting: &[bool]) -> Option<usize> {
+        let num_classes = waiting.len();
+        let max_priority = (0..num_classes)
+            .filter(|&class| waiting[class])
+            .map(|class| self.classes.priority(class))
+            .max()?;
+
+        let mut selected = None;
+        for i in 0..num_classes {
+            let class = (i + self.class_candidate) % num_classes;
+            if !waiting[class] || self.classes.priority(class) != max_priority {
+                continue;
+            }
+            if self.classes.weight(class) > self.grants[class] {
+                return Some(class);
+            } else if selected.is_none() {
+                selected = Some(class);
+            }
+        }
+        selected
+    }
+}
+
+impl<T> Arbitrate<T> for TrafficClassRoundRobin
+where
+    T: SimObject + Routable,
+{
+    fn arbitrate(
+        &mut self,
+        entity: &Rc<Entity>,
+        input_values: &mut [Option<T>],
+    ) -> Option<(usize, T)> {
+        let mut waiting = vec![false; self.classes.num_classes()];
+        for value in input_values.iter().flatten() {
+            waiting[self.class_of(value)] = true;
+        }
+        let class = self.select_class(&waiting)?;
+        trace!(entity ; "tc: grant class {class}");
+
+        if self.classes.weight(class) == self.grants[class] {
+            self.grants[class] = 0;
+        }
+        self.grants[class] += 1;
+        if self.grants[class] == self.classes.weight(class) {
+            self.class_candidate = (class + 1) % self.classes.num_classes();
+        } else {
+            self.class_candidate = class;
+        }
+
+        let num_inputs = input_values.len();
+        for i in 0..num_inputs {
+            let index = (i + self.input_candidates[class]) % num_inputs;
+            if input_values[index]
+                .as_ref()
+                .is_some_and(|value| self.class_of(value) == class)
+            {
+                self.input_candidates[class] = (index + 1) % num_inputs;
+                let value = input_values[index].take().unwrap();
+                return Some((index, value));
+            }
+        }
+        unreachable!("A waiting class always has an input");
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Wormhole arbitration policy
+//!
+//! Once the head flit of a packet has been granted, the same input is granted
+//! until the tail flit of that packet has passed so that the flits of
+//! different packets are never interleaved. The input for each new packet is
+//! chosen by another policy.
+
+use std::rc::Rc;
+
+use gwr_engine::traits::SimObject;
+use gwr_track::entity::Entity;
+
+use crate::arbiter::Arbitrate;
+use crate::packetizer::Flit;
+
+pub struct Wormhole<T>
+where
+    T: SimObject,
+{
+    policy: Box<dyn Arbitrate<Flit<T>>>,
+    locked_input: Option<usize>,
+}
+
+impl<T> Wormhole<T>
+where
+    T: SimObject,
+{
+    #[must_use]
+    pub fn new(policy: Box<dyn Arbitrate<Flit<T>>>) -> Self {
+        Self {
+            policy,
+            locked_input: None,
+        }
+    }
+}
+
+impl<T> Arbitrate<Flit<T>> for Wormhole<T>
+where
+    T: SimObject,
+{
+    fn arbitrate(
+        &mut self,
+        entity: &Rc<Entity>,
+        input_values: &mut [Option<Flit<T>>],
+    ) -> Option<(usize, Flit<T>)> {
+        let (index, flit) = match self.locked_input {
+            Some(index) => (index, input_values[index].take()?),
+            None => self.policy.arbitrate(entity, input_values)?,
+        };
+        self.locked_input = if flit.is_tail() { None } else { Some(index) };
+        Some((index, flit))
+    }
+}
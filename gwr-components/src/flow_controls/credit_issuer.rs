@@ -4,6 +4,9 @@
 //! [credit limiter](crate::flow_controls::credit_limiter)
 //! for an output port.
 //!
+//! The credits issued for each object are given by its [CreditSize], which
+//! must match the one used by the credit limiter.
+//!
 //! # Ports
 //!
 //! This component has the following ports:
@@ -24,7 +27,7 @@ use gwr_track::entity::Entity;
 use gwr_track::trace;
 use gwr_track::tracker::aka::Aka;
 
-use crate::types::Credit;
+use crate::types::{Credit, CreditSize, one_credit};
 use crate::{connect_tx, port_rx, take_option};
 
 #[derive(EntityGet, EntityDisplay)]
@@ -33,6 +36,7 @@ where
     T: SimObject,
 {
     entity: Rc<Entity>,
+    credit_size: RefCell<CreditSize<T>>,
     tx: RefCell<Option<OutPort<T>>>,
     credit_tx: RefCell<Option<OutPort<Credit>>>,
     rx: RefCell<Option<InPort<T>>>,
@@ -55,6 +59,7 @@ where
         let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
         let rc_self = Rc::new(Self {
             entity,
+            credit_size: RefCell::new(one_credit),
             tx: RefCell::new(Some(tx)),
             credit_tx: RefCell::new(Some(credit_tx)),
             rx: RefCell::new(Some(rx)),
@@ -72,6 +77,11 @@ where
         Self::new_and_register_with_renames(engine, clock, parent, name, None)
     }
 
+    /// Set the number of credits issued for each object
+    pub fn set_credit_size(&self, credit_size: CreditSize<T>) {
+        *self.credit_size.borrow_mut() = credit_size;
+    }
+
     pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
         connect_tx!(self.tx, connect ; port_state)
     }
@@ -94,11 +104,13 @@ where
         let mut rx = take_option!(self.rx);
         let mut credit_tx = take_option!(self.credit_tx);
         let mut tx = take_option!(self.tx);
+        let credit_size = *self.credit_size.borrow();
 
         loop {
             let value = rx.get()?.await;
-            trace!(self.entity ; "issue credit");
-            credit_tx.put(Credit(1))?.await;
+            let num_credits = credit_size(&value);
+            trace!(self.entity ; "issue {num_credits} credits");
+            credit_tx.put(Credit(num_credits))?.await;
             tx.put(value)?.await;
         }
     }
@@ -2,6 +2,11 @@
 
 //! Enforce credit limit on an interface between two ports.
 //!
+//! By default each object uses one credit. A [CreditSize] can be set so that
+//! objects use a number of credits that depends on the object, for example one
+//! per byte so that buffers shared by objects of mixed sizes are modelled
+//! accurately.
+//!
 //! # Ports
 //!
 //! This component has the following ports:
@@ -15,17 +20,17 @@ use async_trait::async_trait;
 use gwr_engine::engine::Engine;
 use gwr_engine::executor::Spawner;
 use gwr_engine::port::{InPort, OutPort, PortStateResult};
-use gwr_engine::spawn_subcomponent;
 use gwr_engine::time::clock::Clock;
 use gwr_engine::traits::{Runnable, SimObject};
 use gwr_engine::types::SimResult;
+use gwr_engine::{sim_error, spawn_subcomponent};
 use gwr_model_builder::{EntityDisplay, EntityGet};
 use gwr_resources::Resource;
 use gwr_track::entity::Entity;
 use gwr_track::tracker::aka::Aka;
 use gwr_track::{build_aka, trace};
 
-use crate::types::Credit;
+use crate::types::{Credit, CreditSize, one_credit};
 use crate::{connect_tx, port_rx, take_option};
 
 #[derive(EntityGet, EntityDisplay)]
@@ -79,6 +84,8 @@ where
     entity: Rc<Entity>,
     spawner: Spawner,
     credit: Resource,
+    num_credits: usize,
+    credit_size: RefCell<CreditSize<T>>,
 
     tx: RefCell<Option<OutPort<T>>>,
     credit_rx: RefCell<Option<PortCredit>>,
@@ -116,6 +123,8 @@ where
         let rc_self = Rc::new(Self {
             entity,
             credit,
+            num_credits,
+            credit_size: RefCell::new(one_credit),
             tx: RefCell::new(Some(tx)),
             credit_rx: RefCell::new(Some(credit_rx)),
             rx: RefCell::new(Some(rx)),
@@ -125,6 +134,13 @@ where
         rc_self
     }
 
+    /// Set the number of credits used by each object. This must match the
+    /// [CreditIssuer](crate::flow_controls::credit_issuer::CreditIssuer)
+    /// returning the credits.
+    pub fn set_credit_size(&self, credit_size: CreditSize<T>) {
+        *self.credit_size.borrow_mut() = credit_size;
+    }
+
     pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
         connect_tx!(self.tx, connect ; port_state)
     }
@@ -147,14 +163,25 @@ where
         let mut rx = take_option!(self.rx);
         let mut tx = take_option!(self.tx);
         let credit = self.credit.clone();
+        let credit_size = *self.credit_size.borrow();
 
         spawn_subcomponent!(self.spawner ; self.credit_rx);
 
         loop {
             let value = rx.get()?.await;
 
-            credit.request().await;
-            trace!(self.entity ; "consume credit");
+            let num_credits = credit_size(&value);
+            if num_credits > self.num_credits {
+                return sim_error!(
+                    "{}: {value} needs {num_credits} credits but only {} are available",
+                    self.entity,
+                    self.num_credits
+                );
+            }
+            for _ in 0..num_credits {
+                credit.request().await;
+            }
+            trace!(self.entity ; "consume {num_credits} credits");
 
             tx.put(value)?.await;
         }
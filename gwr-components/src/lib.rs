@@ -7,6 +7,7 @@ pub mod cli;
 pub mod connect;
 pub mod delay;
 pub mod flow_controls;
+pub mod packetizer;
 pub mod queue;
 pub mod router;
 pub mod sink;
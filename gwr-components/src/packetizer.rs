@@ -0,0 +1,364 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Split objects into flits and reassemble them.
+//!
+//! A [Packetizer] splits each object it receives into a sequence of
+//! fixed-size [Flit]s. The first flit is the head, which carries everything
+//! needed to route the packet, and the last flit is the tail, which carries
+//! the original object. A [Depacketizer] reassembles the object when the tail
+//! arrives.
+//!
+//! This allows models to perform wormhole switching where the head flit
+//! allocates a path which the body and tail flits follow.
+//!
+//! # Ports
+//!
+//! Each component has the following ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_engine::engine::Engine;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Routable, Runnable, SimObject, TotalBytes};
+use gwr_engine::types::{AccessType, SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::id::Unique;
+use gwr_track::tracker::aka::Aka;
+use gwr_track::{Id, create_id};
+
+use crate::{connect_tx, port_rx, take_option};
+
+/// The position of a flit within its packet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlitKind {
+    Head,
+    Body,
+    Tail,
+
+    /// A packet that fits in a single flit
+    HeadTail,
+}
+
+impl FlitKind {
+    #[must_use]
+    pub fn is_head(&self) -> bool {
+        matches!(self, FlitKind::Head | FlitKind::HeadTail)
+    }
+
+    #[must_use]
+    pub fn is_tail(&self) -> bool {
+        matches!(self, FlitKind::Tail | FlitKind::HeadTail)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Flit<T>
+where
+    T: SimObject,
+{
+    id: Id,
+    kind: FlitKind,
+    size_bytes: usize,
+    destination: u64,
+    access_type: AccessType,
+    traffic_class: usize,
+    congestion_experienced: bool,
+
+    /// The packet being carried. Only set on the tail flit.
+    packet: Option<T>,
+}
+
+impl<T> Flit<T>
+where
+    T: SimObject,
+{
+    #[must_use]
+    pub fn kind(&self) -> FlitKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub fn is_head(&self) -> bool {
+        self.kind.is_head()
+    }
+
+    #[must_use]
+    pub fn is_tail(&self) -> bool {
+        self.kind.is_tail()
+    }
+
+    /// Take the packet from a tail flit
+    pub fn take_packet(&mut self) -> Option<T> {
+        self.packet.take()
+    }
+}
+
+impl<T> SimObject for Flit<T> where T: SimObject {}
+
+impl<T> Display for Flit<T>
+where
+    T: SimObject,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} flit to {} ({} bytes)",
+            self.kind, self.destination, self.size_bytes
+        )
+    }
+}
+
+impl<T> TotalBytes for Flit<T>
+where
+    T: SimObject,
+{
+    fn total_bytes(&self) -> usize {
+        self.size_bytes
+    }
+}
+
+impl<T> Unique for Flit<T>
+where
+    T: SimObject,
+{
+    fn id(&self) -> Id {
+        self.id
+    }
+}
+
+impl<T> Routable for Flit<T>
+where
+    T: SimObject,
+{
+    fn destination(&self) -> u64 {
+        self.destination
+    }
+
+    fn access_type(&self) -> AccessType {
+        self.access_type
+    }
+
+    fn traffic_class(&self) -> usize {
+        self.traffic_class
+    }
+
+    fn set_congestion_experienced(&mut self) {
+        self.congestion_experienced = true;
+    }
+
+    fn congestion_experienced(&self) -> bool {
+        self.congestion_experienced
+    }
+}
+
+/// Split a packet into flits of at most `flit_bytes`
+pub fn packet_to_flits<T>(entity: &Rc<Entity>, packet: T, flit_bytes: usize) -> Vec<Flit<T>>
+where
+    T: SimObject + Routable,
+{
+    let total_bytes = packet.total_bytes();
+    let num_flits = total_bytes.div_ceil(flit_bytes).max(1);
+    let destination = packet.destination();
+    let access_type = packet.access_type();
+    let traffic_class = packet.traffic_class();
+    let congestion_experienced = packet.congestion_experienced();
+
+    let mut packet = Some(packet);
+    (0..num_flits)
+        .map(|i| {
+            let kind = match (i == 0, i == num_flits - 1) {
+                (true, true) => FlitKind::HeadTail,
+                (true, false) => FlitKind::Head,
+                (false, true) => FlitKind::Tail,
+                (false, false) => FlitKind::Body,
+            };
+            let size_bytes = if kind.is_tail() {
+                total_bytes - i * flit_bytes
+            } else {
+                flit_bytes
+            };
+            Flit {
+                id: create_id!(entity),
+                kind,
+                size_bytes,
+                destination,
+                access_type,
+                traffic_class,
+                congestion_experienced,
+                packet: if kind.is_tail() { packet.take() } else { None },
+            }
+        })
+        .collect()
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct Packetizer<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+    flit_bytes: usize,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<Flit<T>>>>,
+}
+
+impl<T> Packetizer<T>
+where
+    T: SimObject + Routable,
+{
+    /// Returns a `SimError` if `flit_bytes` is 0.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        flit_bytes: usize,
+    ) -> Result<Rc<Self>, SimError> {
+        if flit_bytes == 0 {
+            return sim_error!("Unsupported Packetizer with flit size of 0");
+        }
+        let entity = Rc::new(Entity::new(parent, name));
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            flit_bytes,
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Returns a `SimError` if `flit_bytes` is 0.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        flit_bytes: usize,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, flit_bytes)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<Flit<T>>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for Packetizer<T>
+where
+    T: SimObject + Routable,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let packet = rx.get()?.await;
+            if packet.destinations().is_some() {
+                return sim_error!("{self}: cannot split multicast {packet} into flits");
+            }
+            let packet_id = packet.id();
+            self.entity.track_enter(packet_id);
+            for flit in packet_to_flits(&self.entity, packet, self.flit_bytes) {
+                tx.put(flit)?.await;
+            }
+            self.entity.track_exit(packet_id);
+        }
+    }
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct Depacketizer<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+    rx: RefCell<Option<InPort<Flit<T>>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> Depacketizer<T>
+where
+    T: SimObject + Routable,
+{
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+    ) -> Rc<Self> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        rc_self
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+    ) -> Rc<Self> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<Flit<T>> {
+        port_rx!(self.rx, state)
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for Depacketizer<T>
+where
+    T: SimObject + Routable,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        // A packet is marked as congested if any of its flits were marked
+        let mut congestion_experienced = false;
+        loop {
+            let mut flit = rx.get()?.await;
+            congestion_experienced |= flit.congestion_experienced();
+            if !flit.is_tail() {
+                continue;
+            }
+            match flit.take_packet() {
+                Some(mut packet) => {
+                    if congestion_experienced {
+                        packet.set_congestion_experienced();
+                    }
+                    congestion_experienced = false;
+                    tx.put(packet)?.await;
+                }
+                None => return sim_error!("{self}: tail flit {} has no packet", flit.id()),
+            }
+        }
+    }
+}
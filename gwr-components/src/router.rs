@@ -14,7 +14,9 @@
 //! # Function
 //!
 //! The [Router] will take objects from the single input and send them to the
-//! correct output. A simplified summary of its functionality is:
+//! correct output. Multicast objects are instead copied to each output that
+//! their destinations are reached through. A simplified summary of its
+//! functionality for other objects is:
 //!
 //! ```rust
 //! # use std::rc::Rc;
@@ -51,7 +53,7 @@
 //! # }
 //! ```
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use async_trait::async_trait;
@@ -66,6 +68,7 @@ use gwr_track::entity::Entity;
 use gwr_track::trace;
 use gwr_track::tracker::aka::Aka;
 
+use crate::packetizer::Flit;
 use crate::take_option;
 
 /// Trait required for routing algorithms to implement.
@@ -76,6 +79,53 @@ where
     /// Given an object, return the index of the egress port to map the object
     /// to.
     fn route(&self, object: &T) -> Result<usize, SimError>;
+
+    /// Given a multicast object, return the index of each egress port that
+    /// the object needs to be sent to along with the destinations reached
+    /// through that port.
+    ///
+    /// By default each destination is routed independently using `route()`.
+    fn route_destinations(
+        &self,
+        object: &T,
+        destinations: &[u64],
+    ) -> Result<Vec<(usize, Vec<u64>)>, SimError>
+    where
+        T: Clone,
+    {
+        let mut branches: Vec<(usize, Vec<u64>)> = Vec::new();
+        for &destination in destinations {
+            let mut unicast = object.clone();
+            unicast.set_destinations(vec![destination]);
+            let index = self.route(&unicast)?;
+            match branches.iter_mut().find(|(i, _)| *i == index) {
+                Some((_, branch)) => branch.push(destination),
+                None => branches.push((index, vec![destination])),
+            }
+        }
+        Ok(branches)
+    }
+}
+
+/// Route a multicast object and create a copy of it for each egress port
+/// that its destinations are reached through.
+pub fn replicate<T>(
+    algorithm: &dyn Route<T>,
+    object: &T,
+    destinations: &[u64],
+) -> Result<Vec<(usize, T)>, SimError>
+where
+    T: Routable + Clone,
+{
+    let branches = algorithm.route_destinations(object, destinations)?;
+    Ok(branches
+        .into_iter()
+        .map(|(index, destinations)| {
+            let mut copy = object.clone();
+            copy.set_destinations(destinations);
+            (index, copy)
+        })
+        .collect())
 }
 
 pub struct DefaultAlgorithm {}
@@ -90,6 +140,48 @@ where
     }
 }
 
+/// Routing algorithm for flits that routes each head flit with another
+/// algorithm and sends the body and tail flits that follow the same way.
+pub struct WormholeRoute<T>
+where
+    T: SimObject,
+{
+    algorithm: Box<dyn Route<Flit<T>>>,
+    current: Cell<Option<usize>>,
+}
+
+impl<T> WormholeRoute<T>
+where
+    T: SimObject,
+{
+    #[must_use]
+    pub fn new(algorithm: Box<dyn Route<Flit<T>>>) -> Self {
+        Self {
+            algorithm,
+            current: Cell::new(None),
+        }
+    }
+}
+
+impl<T> Route<Flit<T>> for WormholeRoute<T>
+where
+    T: SimObject,
+{
+    fn route(&self, flit: &Flit<T>) -> Result<usize, SimError> {
+        let index = if flit.is_head() {
+            self.algorithm.route(flit)?
+        } else {
+            match self.current.get() {
+                Some(index) => index,
+                None => return sim_error!("{flit} received without a head flit"),
+            }
+        };
+        self.current
+            .set(if flit.is_tail() { None } else { Some(index) });
+        Ok(index)
+    }
+}
+
 #[derive(EntityGet, EntityDisplay)]
 pub struct Router<T>
 where
@@ -155,6 +247,21 @@ where
     pub fn port_rx(&self) -> PortStateResult<T> {
         self.rx.borrow().as_ref().unwrap().state()
     }
+
+    async fn put_to(&self, tx: &mut [OutPort<T>], tx_index: usize, value: T) -> SimResult {
+        trace!(self.entity ; "Route {} to {}", value.id(), tx_index);
+
+        match tx.get_mut(tx_index) {
+            None => {
+                sim_error!("{self}: {value:?} selected invalid egress index {tx_index}")
+            }
+            Some(tx) => {
+                self.entity.track_exit(value.id());
+                tx.put(value)?.await;
+                Ok(())
+            }
+        }
+    }
 }
 
 #[async_trait(?Send)]
@@ -171,18 +278,15 @@ where
             let value = rx.get()?.await;
             self.entity.track_enter(value.id());
 
-            let tx_index = algorithm.route(&value)?;
-            trace!(self.entity ; "Route {} to {}", value.id(), tx_index);
-
-            match tx.get_mut(tx_index) {
+            match value.destinations() {
                 None => {
-                    return sim_error!(
-                        "{self}: {value:?} selected invalid egress index {tx_index}"
-                    );
+                    let tx_index = algorithm.route(&value)?;
+                    self.put_to(&mut tx, tx_index, value).await?;
                 }
-                Some(tx) => {
-                    self.entity.track_exit(value.id());
-                    tx.put(value)?.await;
+                Some(destinations) => {
+                    for (tx_index, copy) in replicate(algorithm.as_ref(), &value, destinations)? {
+                        self.put_to(&mut tx, tx_index, copy).await?;
+                    }
                 }
             }
         }
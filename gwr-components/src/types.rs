@@ -22,6 +22,21 @@ pub type GetResult<T> = Result<T, SimError>;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Credit(pub usize);
 
+/// Returns the number of credits an object uses
+pub type CreditSize<T> = fn(&T) -> usize;
+
+/// Credit size of objects that use one credit each
+#[must_use]
+pub fn one_credit<T>(_value: &T) -> usize {
+    1
+}
+
+/// Credit size of objects that use one credit per byte
+#[must_use]
+pub fn byte_credits<T: TotalBytes>(value: &T) -> usize {
+    value.total_bytes()
+}
+
 impl TotalBytes for Credit {
     fn total_bytes(&self) -> usize {
         size_of::<usize>()
@@ -1,12 +1,14 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
+use std::fmt::Display;
 use std::rc::Rc;
 use std::vec;
 
-use gwr_components::arbiter::Arbiter;
 use gwr_components::arbiter::policy::{
-    Priority, PriorityRoundRobin, RoundRobin, WeightedRoundRobin,
+    Priority, PriorityRoundRobin, RoundRobin, TrafficClassRoundRobin, TrafficClasses,
+    WeightedRoundRobin,
 };
+use gwr_components::arbiter::{Arbiter, Arbitrate};
 use gwr_components::flow_controls::limiter::Limiter;
 use gwr_components::source::Source;
 use gwr_components::store::{ObjectStore, Store};
@@ -17,7 +19,10 @@ use gwr_components::{connect_port, option_box_repeat, rc_limiter};
 use gwr_engine::port::InPort;
 use gwr_engine::run_simulation;
 use gwr_engine::test_helpers::start_test;
+use gwr_engine::traits::{Routable, SimObject, TotalBytes};
+use gwr_engine::types::AccessType;
 use gwr_track::entity::Entity;
+use gwr_track::id::{Id, Unique};
 
 mod arbiter_harness {
     use gwr_components::build_component_harness;
@@ -404,3 +409,107 @@ fn panic_priority_policy() {
         Box::new(PriorityRoundRobin::from_priorities(priorities.clone(), num_inputs + 1).unwrap()),
     );
 }
+
+#[derive(Clone, Debug)]
+struct ClassTest {
+    input: usize,
+    class: usize,
+}
+
+impl TotalBytes for ClassTest {
+    fn total_bytes(&self) -> usize {
+        8
+    }
+}
+
+impl Routable for ClassTest {
+    fn destination(&self) -> u64 {
+        0
+    }
+    fn access_type(&self) -> AccessType {
+        AccessType::Control
+    }
+    fn traffic_class(&self) -> usize {
+        self.class
+    }
+}
+
+impl Display for ClassTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "class {} from {}", self.class, self.input)
+    }
+}
+
+impl Unique for ClassTest {
+    fn id(&self) -> Id {
+        Id(0)
+    }
+}
+
+impl SimObject for ClassTest {}
+
+/// Arbitrate with every input always waiting and return the inputs granted
+fn traffic_class_grants(
+    entity: &Rc<Entity>,
+    policy: &mut TrafficClassRoundRobin,
+    input_classes: &[usize],
+    num_grants: usize,
+) -> Vec<usize> {
+    let mut input_values: Vec<_> = input_classes.iter().map(|_| None).collect();
+    let mut grants = Vec::with_capacity(num_grants);
+    for _ in 0..num_grants {
+        for (input, class) in input_classes.iter().enumerate() {
+            input_values[input].get_or_insert(ClassTest {
+                input,
+                class: *class,
+            });
+        }
+        let (index, value) = policy.arbitrate(entity, &mut input_values).unwrap();
+        assert_eq!(index, value.input);
+        grants.push(index);
+    }
+    grants
+}
+
+#[test]
+fn traffic_class_strict_priority() {
+    let engine = start_test(file!());
+    let classes = TrafficClasses::new().with_class(0, 1).with_class(1, 1);
+    let mut policy = TrafficClassRoundRobin::new(classes).unwrap();
+
+    // Inputs 1 and 2 carry the high priority class and share the output
+    let grants = traffic_class_grants(engine.top(), &mut policy, &[0, 1, 1], 6);
+    assert_eq!(grants, [1, 2, 1, 2, 1, 2]);
+
+    // Low priority traffic is granted once there is nothing else
+    let mut input_values = vec![Some(ClassTest { input: 0, class: 0 }), None, None];
+    let (index, _) = policy.arbitrate(engine.top(), &mut input_values).unwrap();
+    assert_eq!(index, 0);
+}
+
+#[test]
+fn traffic_class_weights() {
+    let engine = start_test(file!());
+    let classes = TrafficClasses::new().with_class(0, 3).with_class(0, 1);
+    let mut policy = TrafficClassRoundRobin::new(classes).unwrap();
+
+    let grants = traffic_class_grants(engine.top(), &mut policy, &[0, 1], 8);
+    assert_eq!(grants, [0, 0, 0, 1, 0, 0, 0, 1]);
+}
+
+#[test]
+fn traffic_class_unconfigured_is_class_0() {
+    let engine = start_test(file!());
+    let classes = TrafficClasses::new().with_class(1, 1).with_class(0, 1);
+    let mut policy = TrafficClassRoundRobin::new(classes).unwrap();
+
+    // Class 5 is arbitrated as the high priority class 0
+    let grants = traffic_class_grants(engine.top(), &mut policy, &[1, 5], 2);
+    assert_eq!(grants, [1, 1]);
+}
+
+#[test]
+fn invalid_traffic_classes() {
+    assert!(TrafficClassRoundRobin::new(TrafficClasses::new()).is_err());
+    assert!(TrafficClassRoundRobin::new(TrafficClasses::new().with_class(0, 0)).is_err());
+}
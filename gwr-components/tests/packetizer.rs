@@ -0,0 +1,110 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_components::connect_port;
+use gwr_components::packetizer::{Depacketizer, FlitKind, Packetizer, packet_to_flits};
+use gwr_components::sink::Sink;
+use gwr_components::source::Source;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::traits::{Routable, TotalBytes};
+
+#[test]
+fn split_into_flits() {
+    let engine = start_test(file!());
+
+    // An i32 is 4 bytes
+    let flits = packet_to_flits(engine.top(), 7_i32, 3);
+    let kinds: Vec<_> = flits.iter().map(|flit| flit.kind()).collect();
+    assert_eq!(kinds, [FlitKind::Head, FlitKind::Tail]);
+    assert_eq!(flits[0].total_bytes(), 3);
+    assert_eq!(flits[1].total_bytes(), 1);
+    assert!(flits.iter().all(|flit| flit.destination() == 7));
+
+    let mut flits = packet_to_flits(engine.top(), 7_i32, 1);
+    let kinds: Vec<_> = flits.iter().map(|flit| flit.kind()).collect();
+    assert_eq!(
+        kinds,
+        [
+            FlitKind::Head,
+            FlitKind::Body,
+            FlitKind::Body,
+            FlitKind::Tail
+        ]
+    );
+    assert_eq!(flits[3].take_packet(), Some(7));
+}
+
+#[test]
+fn single_flit_packet() {
+    let engine = start_test(file!());
+
+    let mut flits = packet_to_flits(engine.top(), 7_i32, 8);
+    assert_eq!(flits.len(), 1);
+    assert_eq!(flits[0].kind(), FlitKind::HeadTail);
+    assert!(flits[0].is_head());
+    assert!(flits[0].is_tail());
+    assert_eq!(flits[0].total_bytes(), 4);
+    assert_eq!(flits[0].take_packet(), Some(7));
+}
+
+#[test]
+fn packetize_flits() {
+    const NUM_PUTS: usize = 10;
+
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let top = engine.top();
+
+    let source = Source::new_and_register(
+        &engine,
+        top,
+        "source",
+        Some(Box::new((0..NUM_PUTS as i32).map(|i| i % 2))),
+    );
+    let packetizer = Packetizer::new_and_register(&engine, &clock, top, "packetizer", 1).unwrap();
+    let sink = Sink::new_and_register(&engine, &clock, top, "sink");
+
+    connect_port!(source, tx => packetizer, rx).unwrap();
+    connect_port!(packetizer, tx => sink, rx).unwrap();
+
+    run_simulation!(engine);
+
+    assert_eq!(sink.num_sunk(), NUM_PUTS * 4);
+}
+
+#[test]
+fn packetize_depacketize() {
+    const NUM_PUTS: usize = 10;
+
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let top = engine.top();
+
+    let source = Source::new_and_register(
+        &engine,
+        top,
+        "source",
+        Some(Box::new((0..NUM_PUTS as i32).map(|i| i % 2))),
+    );
+    let packetizer = Packetizer::new_and_register(&engine, &clock, top, "packetizer", 3).unwrap();
+    let depacketizer = Depacketizer::new_and_register(&engine, &clock, top, "depacketizer");
+    let sink = Sink::new_and_register(&engine, &clock, top, "sink");
+
+    connect_port!(source, tx => packetizer, rx).unwrap();
+    connect_port!(packetizer, tx => depacketizer, rx).unwrap();
+    connect_port!(depacketizer, tx => sink, rx).unwrap();
+
+    run_simulation!(engine);
+
+    assert_eq!(sink.num_sunk(), NUM_PUTS);
+}
+
+#[test]
+fn zero_flit_bytes() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+
+    let packetizer =
+        Packetizer::<i32>::new_and_register(&engine, &clock, engine.top(), "packetizer", 0);
+    assert!(packetizer.is_err());
+}
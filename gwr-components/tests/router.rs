@@ -1,11 +1,65 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
+use std::fmt::Display;
+
 use gwr_components::connect_port;
-use gwr_components::router::{DefaultAlgorithm, Router};
+use gwr_components::router::{DefaultAlgorithm, Route, Router};
 use gwr_components::sink::Sink;
 use gwr_components::source::Source;
 use gwr_engine::run_simulation;
 use gwr_engine::test_helpers::start_test;
+use gwr_engine::traits::{Routable, SimObject, TotalBytes};
+use gwr_engine::types::{AccessType, SimError};
+use gwr_track::id::{Id, Unique};
+
+#[derive(Clone, Debug)]
+struct MulticastTest {
+    destinations: Vec<u64>,
+}
+
+impl TotalBytes for MulticastTest {
+    fn total_bytes(&self) -> usize {
+        8
+    }
+}
+
+impl Routable for MulticastTest {
+    fn destination(&self) -> u64 {
+        self.destinations[0]
+    }
+    fn access_type(&self) -> AccessType {
+        AccessType::Control
+    }
+    fn destinations(&self) -> Option<&[u64]> {
+        Some(&self.destinations)
+    }
+    fn set_destinations(&mut self, destinations: Vec<u64>) {
+        self.destinations = destinations;
+    }
+}
+
+impl Display for MulticastTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "multicast to {:?}", self.destinations)
+    }
+}
+
+impl Unique for MulticastTest {
+    fn id(&self) -> Id {
+        Id(0)
+    }
+}
+
+impl SimObject for MulticastTest {}
+
+/// Route destinations 0 and 1 to port 0 and all others to port 1
+struct PairAlgorithm;
+
+impl Route<MulticastTest> for PairAlgorithm {
+    fn route(&self, object: &MulticastTest) -> Result<usize, SimError> {
+        Ok(usize::from(object.destination() > 1))
+    }
+}
 
 #[test]
 fn router() {
@@ -37,3 +91,51 @@ fn router() {
     assert_eq!(sink_a.num_sunk(), NUM_PUTS / 2);
     assert_eq!(sink_b.num_sunk(), NUM_PUTS / 2);
 }
+
+#[test]
+fn multicast() {
+    const NUM_PUTS: usize = 10;
+
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+
+    let frame = MulticastTest {
+        destinations: vec![0, 2, 1],
+    };
+    let iter = Box::new(std::iter::repeat_n(frame, NUM_PUTS));
+    let top = engine.top();
+    let source = Source::new_and_register(&engine, top, "source", Some(iter));
+    let router = Router::new_and_register(
+        &engine,
+        &clock,
+        top,
+        "router",
+        3,
+        Box::new(DefaultAlgorithm {}),
+    );
+    let sinks: Vec<_> = (0..3)
+        .map(|i| Sink::new_and_register(&engine, &clock, top, &format!("sink_{i}")))
+        .collect();
+
+    connect_port!(source, tx => router, rx).unwrap();
+    for (i, sink) in sinks.iter().enumerate() {
+        connect_port!(router, tx, i => sink, rx).unwrap();
+    }
+
+    run_simulation!(engine);
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), NUM_PUTS);
+    }
+}
+
+#[test]
+fn multicast_replicates_once_per_egress() {
+    let frame = MulticastTest {
+        destinations: vec![0, 2, 1, 3],
+    };
+    let branches = PairAlgorithm
+        .route_destinations(&frame, frame.destinations().unwrap())
+        .unwrap();
+    assert_eq!(branches, [(0, vec![0, 1]), (1, vec![2, 3])]);
+}
@@ -6,9 +6,10 @@ use std::rc::Rc;
 
 use gwr_track::entity::{Entity, toplevel};
 use gwr_track::tracker::stdout_tracker;
-use gwr_track::{Tracker, trace};
+use gwr_track::{Tracker, error, trace};
 
 use crate::executor::{self, Executor, Spawner};
+use crate::power::PowerAccounting;
 use crate::time::clock::Clock;
 use crate::types::{Component, Eventable, SimResult};
 
@@ -50,6 +51,7 @@ pub struct Engine {
     toplevel: Rc<Entity>,
     tracker: Tracker,
     registry: Registry,
+    power: PowerAccounting,
 }
 
 impl Engine {
@@ -64,6 +66,7 @@ impl Engine {
             toplevel,
             tracker: tracker.clone(),
             registry,
+            power: PowerAccounting::default(),
         }
     }
 
@@ -77,7 +80,7 @@ impl Engine {
 
         // Pass an atomic bool that will never be set to true
         let finished = Rc::new(RefCell::new(false));
-        self.executor.run(&finished)
+        self.run_executor(&finished)
     }
 
     pub fn run_until<T: Default + Copy + 'static>(&mut self, event: Eventable<T>) -> SimResult {
@@ -94,7 +97,17 @@ impl Engine {
             });
         }
 
-        self.executor.run(&finished)
+        self.run_executor(&finished)
+    }
+
+    /// Run the executor, logging any error to trackers that want to see it,
+    /// such as the flight recorder.
+    fn run_executor(&self, finished: &Rc<RefCell<bool>>) -> SimResult {
+        self.executor.run(finished).inspect_err(|e| {
+            if self.tracker.wants_errors() {
+                error!(self.toplevel ; "{e}");
+            }
+        })
     }
 
     #[must_use]
@@ -153,6 +166,12 @@ impl Engine {
     pub fn tracker(&self) -> Tracker {
         self.tracker.clone()
     }
+
+    /// Returns the energy accounts of the components of the simulation
+    #[must_use]
+    pub fn power(&self) -> &PowerAccounting {
+        &self.power
+    }
 }
 
 /// Create a default engine that sends [`Track`](gwr_track::Track) events to
@@ -0,0 +1,216 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Power accounting for the components of a simulation.
+//!
+//! Components that model energy create an [EnergyAccount] with the
+//! [PowerAccounting] of the [Engine](crate::engine::Engine). Each account has
+//! an idle (static) power that is drawn for the whole of the simulation and
+//! accumulates the dynamic energy of the work done by the component.
+//!
+//! Energy is measured in picojoules (pJ) and power in milliwatts (mW), so that
+//! a power drawn for a simulated time in nanoseconds gives an energy in pJ.
+//!
+//! # Example
+//!
+//! ```rust
+//! use gwr_engine::engine::Engine;
+//!
+//! let engine = Engine::default();
+//! let account = engine.power().create_account(engine.top(), 2.0);
+//! account.add_energy_pj(50.0);
+//!
+//! // 2mW for 100ns is 200pJ of idle energy
+//! assert_eq!(engine.power().total_energy_pj(100.0), 250.0);
+//! assert_eq!(engine.power().average_power_mw(100.0), 2.5);
+//! ```
+//!
+//! # Power Switches
+//!
+//! A [PowerSwitch] holds the [PowerState] of a group of components (such as a
+//! power domain of a platform) and notifies an event whenever the state is
+//! changed, so that the state can be changed while a simulation is running.
+//! Components that are gated by a switch wait for it to be turned on before
+//! doing any work.
+//!
+//! ```rust
+//! use gwr_engine::power::{PowerState, PowerSwitch};
+//!
+//! let switch = PowerSwitch::new(PowerState::Off);
+//! assert!(!switch.is_on());
+//! switch.set_state(PowerState::On);
+//! assert_eq!(switch.state(), PowerState::On);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gwr_track::entity::Entity;
+
+use crate::events::repeated::Repeated;
+use crate::traits::Event;
+
+/// The energy used by one component
+pub struct EnergyAccount {
+    entity: Rc<Entity>,
+    idle_power_mw: f64,
+    dynamic_energy_pj: Cell<f64>,
+}
+
+impl EnergyAccount {
+    #[must_use]
+    pub fn entity(&self) -> &Rc<Entity> {
+        &self.entity
+    }
+
+    /// Add dynamic energy used by the component
+    pub fn add_energy_pj(&self, energy_pj: f64) {
+        self.dynamic_energy_pj
+            .set(self.dynamic_energy_pj.get() + energy_pj);
+    }
+
+    /// Returns the dynamic energy used so far
+    #[must_use]
+    pub fn dynamic_energy_pj(&self) -> f64 {
+        self.dynamic_energy_pj.get()
+    }
+
+    #[must_use]
+    pub fn idle_power_mw(&self) -> f64 {
+        self.idle_power_mw
+    }
+
+    /// Returns the idle energy drawn over `time_ns`
+    #[must_use]
+    pub fn idle_energy_pj(&self, time_ns: f64) -> f64 {
+        self.idle_power_mw * time_ns
+    }
+
+    /// Returns the total energy used over `time_ns`
+    #[must_use]
+    pub fn energy_pj(&self, time_ns: f64) -> f64 {
+        self.dynamic_energy_pj() + self.idle_energy_pj(time_ns)
+    }
+
+    /// Returns the average power drawn over `time_ns`
+    #[must_use]
+    pub fn average_power_mw(&self, time_ns: f64) -> f64 {
+        average_power_mw(self.energy_pj(time_ns), time_ns)
+    }
+}
+
+/// The energy accounts of all the components of a simulation
+#[derive(Default)]
+pub struct PowerAccounting {
+    accounts: RefCell<Vec<Rc<EnergyAccount>>>,
+}
+
+impl PowerAccounting {
+    /// Create an account for the energy used by `entity` which draws
+    /// `idle_power_mw` for the whole simulation
+    pub fn create_account(&self, entity: &Rc<Entity>, idle_power_mw: f64) -> Rc<EnergyAccount> {
+        let account = Rc::new(EnergyAccount {
+            entity: entity.clone(),
+            idle_power_mw,
+            dynamic_energy_pj: Cell::new(0.0),
+        });
+        self.accounts.borrow_mut().push(account.clone());
+        account
+    }
+
+    #[must_use]
+    pub fn accounts(&self) -> Vec<Rc<EnergyAccount>> {
+        self.accounts.borrow().clone()
+    }
+
+    /// Returns the total energy used by all accounts over `time_ns`
+    #[must_use]
+    pub fn total_energy_pj(&self, time_ns: f64) -> f64 {
+        self.accounts
+            .borrow()
+            .iter()
+            .map(|account| account.energy_pj(time_ns))
+            .sum()
+    }
+
+    /// Returns the average power drawn by all accounts over `time_ns`
+    #[must_use]
+    pub fn average_power_mw(&self, time_ns: f64) -> f64 {
+        average_power_mw(self.total_energy_pj(time_ns), time_ns)
+    }
+}
+
+fn average_power_mw(energy_pj: f64, time_ns: f64) -> f64 {
+    if time_ns == 0.0 {
+        0.0
+    } else {
+        energy_pj / time_ns
+    }
+}
+
+/// The power state of a group of components
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PowerState {
+    /// Powered and able to do work
+    #[default]
+    On,
+    /// Powered off, no work is done and no state is retained
+    Off,
+    /// Powered down to a level that keeps state but does no work
+    Retention,
+}
+
+impl std::fmt::Display for PowerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PowerState::On => write!(f, "on"),
+            PowerState::Off => write!(f, "off"),
+            PowerState::Retention => write!(f, "retention"),
+        }
+    }
+}
+
+/// A switch controlling the [PowerState] of the components it is shared with
+#[derive(Clone)]
+pub struct PowerSwitch {
+    state: Rc<Cell<PowerState>>,
+    changed: Repeated<PowerState>,
+}
+
+impl PowerSwitch {
+    #[must_use]
+    pub fn new(state: PowerState) -> Self {
+        Self {
+            state: Rc::new(Cell::new(state)),
+            changed: Repeated::new(state),
+        }
+    }
+
+    #[must_use]
+    pub fn state(&self) -> PowerState {
+        self.state.get()
+    }
+
+    #[must_use]
+    pub fn is_on(&self) -> bool {
+        self.state() == PowerState::On
+    }
+
+    /// Change the power state and notify anything listening for changes
+    pub fn set_state(&self, state: PowerState) {
+        self.state.set(state);
+        self.changed.notify_result(state);
+    }
+
+    /// The event that is notified with the new state whenever it is set
+    #[must_use]
+    pub fn changed(&self) -> &Repeated<PowerState> {
+        &self.changed
+    }
+
+    /// Wait until the switch is on, returning immediately if it already is
+    pub async fn wait_until_on(&self) {
+        while !self.is_on() {
+            self.changed.listen().await;
+        }
+    }
+}
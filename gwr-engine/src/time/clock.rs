@@ -275,6 +275,13 @@ impl Clock {
         clock_time.tick as f64 / self.freq_mhz * 1000.0
     }
 
+    /// Convert the given [ClockTick] to a whole number of `ps` for this clock,
+    /// rounded to the nearest picosecond.
+    #[must_use]
+    pub fn to_ps(&self, clock_time: &ClockTick) -> u64 {
+        (clock_time.tick as f64 * 1_000_000.0 / self.freq_mhz).round() as u64
+    }
+
     /// Returns a [ClockDelay] future which must be `await`ed to delay the
     /// specified number of ticks.
     #[must_use = "Futures do nothing unless you `.await` or otherwise use them"]
@@ -8,6 +8,7 @@ use std::rc::Rc;
 
 use gwr_track::entity::Entity;
 use gwr_track::set_time;
+use gwr_track::tracker::types::TimeUnit;
 
 use super::clock::Clock;
 use crate::time::clock::TaskWaker;
@@ -55,7 +56,9 @@ impl SimTime {
             if let Some(clock_time) = next_clock.shared_state.waiting_times.borrow_mut().pop() {
                 let next_ns = next_clock.to_ns(&clock_time);
                 if self.current_ns != next_ns {
-                    set_time!(self.entity ; next_ns);
+                    // Trackers are given whole picoseconds so that the time
+                    // of clocks faster than 1GHz is not rounded
+                    set_time!(self.entity ; next_clock.to_ps(&clock_time), TimeUnit::Ps);
                     self.current_ns = next_ns;
                 }
                 next_clock.advance_time(clock_time);
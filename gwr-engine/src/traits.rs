@@ -24,9 +24,46 @@ pub trait TotalBytes {
 
 /// The `Routable` trait provides an interface to an object to enable it to be
 /// routed
+///
+/// An object can also be multicast to a set of destinations, in which case it
+/// is replicated wherever the routes to its destinations diverge.
 pub trait Routable {
     fn destination(&self) -> u64;
     fn access_type(&self) -> AccessType;
+
+    /// Returns the set of destinations of a multicast object, or `None` for
+    /// an object that is only sent to its `destination()`.
+    fn destinations(&self) -> Option<&[u64]> {
+        None
+    }
+
+    /// Restrict a multicast object to a subset of its destinations.
+    ///
+    /// This is called on each copy when a multicast object is replicated and
+    /// so only needs to be implemented by objects that return a set from
+    /// `destinations()`.
+    fn set_destinations(&mut self, _destinations: Vec<u64>) {}
+
+    /// Returns the traffic class of the object.
+    ///
+    /// Arbiters can use the class to give some traffic, such as
+    /// latency-sensitive control traffic, precedence over other traffic. All
+    /// objects are in class 0 by default.
+    fn traffic_class(&self) -> usize {
+        0
+    }
+
+    /// Mark the object as having passed through a congested buffer.
+    ///
+    /// This is an ECN-style mark that the destination can report back to the
+    /// source. Objects that cannot carry the mark ignore it.
+    fn set_congestion_experienced(&mut self) {}
+
+    /// Returns whether the object has been marked as having passed through a
+    /// congested buffer.
+    fn congestion_experienced(&self) -> bool {
+        false
+    }
 }
 
 /// A super-trait that objects that are passed around the simulation have to
@@ -196,6 +233,8 @@ mod tests {
         assert_eq!(3_usize.access_type(), AccessType::ReadResponse);
         assert_eq!(4_usize.access_type(), AccessType::WriteNonPostedResponse);
         assert_eq!(5_usize.access_type(), AccessType::Control);
+        assert_eq!(7_usize.destinations(), None);
+        assert_eq!(7_usize.traffic_class(), 0);
     }
 
     struct PassiveRunnable;
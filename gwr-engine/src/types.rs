@@ -6,6 +6,8 @@ use std::error::Error;
 use std::fmt;
 use std::rc::Rc;
 
+use gwr_track::tracker::TrackConfigError;
+
 use crate::traits::{Event, Runnable};
 
 /// The return value from a call to [listen()](crate::traits::Event)
@@ -39,6 +41,12 @@ impl fmt::Display for SimError {
 
 impl Error for SimError {}
 
+impl From<TrackConfigError> for SimError {
+    fn from(TrackConfigError(msg): TrackConfigError) -> Self {
+        SimError(msg)
+    }
+}
+
 /// The SimResult is the return type for most simulation functions
 pub type SimResult = Result<(), SimError>;
 
@@ -0,0 +1,77 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gwr_engine::power::{PowerState, PowerSwitch};
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::traits::Event;
+
+#[test]
+fn wait_until_on_blocks_while_off() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+
+    let switch = PowerSwitch::new(PowerState::Off);
+    let woken_at = Rc::new(Cell::new(None));
+
+    {
+        let switch = switch.clone();
+        let clock = clock.clone();
+        let woken_at = woken_at.clone();
+        engine.spawn(async move {
+            switch.wait_until_on().await;
+            woken_at.set(Some(clock.time_now_ns()));
+            Ok(())
+        });
+    }
+
+    {
+        let clock = clock.clone();
+        engine.spawn(async move {
+            // Retention does not allow work to continue
+            clock.wait_ticks(5).await;
+            switch.set_state(PowerState::Retention);
+            clock.wait_ticks(5).await;
+            switch.set_state(PowerState::On);
+            Ok(())
+        });
+    }
+
+    run_simulation!(engine);
+
+    assert_eq!(woken_at.get(), Some(10.0));
+}
+
+#[test]
+fn changes_are_notified() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+
+    let switch = PowerSwitch::new(PowerState::On);
+    assert!(switch.is_on());
+
+    {
+        let switch = switch.clone();
+        engine.spawn(async move {
+            let state = switch.changed().listen().await;
+            assert_eq!(state, PowerState::Off);
+            Ok(())
+        });
+    }
+
+    {
+        let switch = switch.clone();
+        engine.spawn(async move {
+            clock.wait_ticks(1).await;
+            switch.set_state(PowerState::Off);
+            Ok(())
+        });
+    }
+
+    run_simulation!(engine);
+
+    assert_eq!(switch.state(), PowerState::Off);
+    assert_eq!(format!("{}", switch.state()), "off");
+}
@@ -0,0 +1,857 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A collective communication engine.
+//!
+//! The engine performs an all-reduce, broadcast or all-gather across a set of
+//! ranks, for example the PEs of a platform, by sending messages between them
+//! over a fabric. Each rank has its own pair of ports which are connected to the
+//! fabric port given for that rank in the [CollectiveConfig].
+//!
+//! The data is split into chunks of at most
+//! [chunk_bytes](CollectiveConfig::with_chunk_bytes) and each chunk is sent as
+//! a separate message. When [overlap](CollectiveConfig::with_overlap) is
+//! enabled a rank forwards each chunk as soon as it has received it, otherwise
+//! it waits for all chunks of a step before moving on to the next step.
+//!
+//! Algorithms:
+//!  - [Ring](CollectiveAlgorithm::Ring): the ranks form a ring. An all-reduce
+//!    is performed as a reduce-scatter followed by an all-gather, each taking
+//!    `N - 1` steps in which every rank sends `1/N` of the data to the next
+//!    rank. A broadcast is passed along the ring from rank 0. An all-gather
+//!    is the second half of an all-reduce, passing on the share of the data
+//!    of each rank without reducing it.
+//!  - [Tree](CollectiveAlgorithm::Tree): the ranks form a binary tree rooted
+//!    at rank 0. An all-reduce reduces the data up to the root and then
+//!    broadcasts the result back down the tree. An all-gather gathers the
+//!    shares of the ranks of each subtree up to the root in the same way.
+//!
+//! Received chunks that need reducing occupy the reduction unit of the rank
+//! for a time determined by
+//! [reduce_bytes_per_tick](CollectiveConfig::with_reduce_bytes_per_tick).
+//!
+//! The messages are posted [MemoryAccess] writes whose address identifies the
+//! step and chunk being sent. The [transfers](CollectiveConfig::transfers) of
+//! a collective describe the same schedule for models that move the data
+//! themselves, such as a timetable of send and receive tasks.
+//!
+//! # Ports
+//!
+//! For each rank `i` this component has:
+//!  - One [input port](gwr_engine::port::InPort): `rx_i`
+//!  - One [output port](gwr_engine::port::OutPort): `tx_i`
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_engine::engine::Engine;
+use gwr_engine::events::once::Once;
+use gwr_engine::events::repeated::Repeated;
+use gwr_engine::executor::Spawner;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Event, Runnable};
+use gwr_engine::types::{AccessType, SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+use gwr_track::{debug, trace};
+use serde::{Deserialize, Serialize};
+
+use crate::log_stats;
+use crate::memory::memory_access::MemoryAccess;
+use crate::memory::memory_map::DeviceId;
+use crate::memory::traits::AccessMemory;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectiveOp {
+    /// Every rank ends up with the reduction of the data of all ranks
+    AllReduce,
+
+    /// Every rank ends up with the data of rank 0
+    Broadcast,
+
+    /// Every rank ends up with the data of all ranks, each of which starts
+    /// with an equal share of it
+    AllGather,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectiveAlgorithm {
+    #[default]
+    Ring,
+    Tree,
+}
+
+/// The data that one rank sends to another in one step of a collective
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollectiveTransfer {
+    pub src_rank: usize,
+    pub dst_rank: usize,
+    pub step: usize,
+    pub num_bytes: usize,
+
+    /// The steps that `src_rank` must have received from other ranks, as
+    /// `(rank, step)` pairs, before it sends this one
+    pub after: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CollectiveConfig {
+    op: CollectiveOp,
+    algorithm: CollectiveAlgorithm,
+    rank_ports: Vec<usize>,
+    num_bytes: usize,
+    chunk_bytes: usize,
+    overlap: bool,
+    reduce_bytes_per_tick: Option<usize>,
+    overhead_size_bytes: usize,
+}
+
+impl CollectiveConfig {
+    /// Create a config for performing `op` on `num_bytes` of data using
+    /// `algorithm`. Rank `i` is attached to fabric port `rank_ports[i]`.
+    ///
+    /// By default the data is sent as a single chunk, chunks are forwarded as
+    /// soon as they are received and reductions take no time.
+    #[must_use]
+    pub fn new(
+        op: CollectiveOp,
+        algorithm: CollectiveAlgorithm,
+        rank_ports: Vec<usize>,
+        num_bytes: usize,
+    ) -> Self {
+        Self {
+            op,
+            algorithm,
+            rank_ports,
+            num_bytes,
+            chunk_bytes: num_bytes,
+            overlap: true,
+            reduce_bytes_per_tick: None,
+            overhead_size_bytes: 0,
+        }
+    }
+
+    /// Set the maximum number of bytes sent in each message
+    #[must_use]
+    pub fn with_chunk_bytes(mut self, chunk_bytes: usize) -> Self {
+        self.chunk_bytes = chunk_bytes;
+        self
+    }
+
+    /// Choose whether chunks are forwarded as soon as they are received or
+    /// only once the whole step has been received
+    #[must_use]
+    pub fn with_overlap(mut self, overlap: bool) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Set the throughput of the reduction unit of each rank
+    #[must_use]
+    pub fn with_reduce_bytes_per_tick(mut self, bytes_per_tick: usize) -> Self {
+        self.reduce_bytes_per_tick = Some(bytes_per_tick);
+        self
+    }
+
+    /// Set the number of bytes of protocol overhead for each message
+    #[must_use]
+    pub fn with_overhead_size_bytes(mut self, overhead_size_bytes: usize) -> Self {
+        self.overhead_size_bytes = overhead_size_bytes;
+        self
+    }
+
+    #[must_use]
+    pub fn op(&self) -> CollectiveOp {
+        self.op
+    }
+
+    #[must_use]
+    pub fn algorithm(&self) -> CollectiveAlgorithm {
+        self.algorithm
+    }
+
+    #[must_use]
+    pub fn rank_ports(&self) -> &[usize] {
+        &self.rank_ports
+    }
+
+    #[must_use]
+    pub fn num_ranks(&self) -> usize {
+        self.rank_ports.len()
+    }
+
+    #[must_use]
+    pub fn num_bytes(&self) -> usize {
+        self.num_bytes
+    }
+
+    #[must_use]
+    pub fn chunk_bytes(&self) -> usize {
+        self.chunk_bytes
+    }
+
+    #[must_use]
+    pub fn overlap(&self) -> bool {
+        self.overlap
+    }
+
+    #[must_use]
+    pub fn reduce_bytes_per_tick(&self) -> Option<usize> {
+        self.reduce_bytes_per_tick
+    }
+
+    #[must_use]
+    pub fn overhead_size_bytes(&self) -> usize {
+        self.overhead_size_bytes
+    }
+
+    /// Returns the transfers of the collective, with the chunks of each step
+    /// combined, in the order that each rank sends them
+    #[must_use]
+    pub fn transfers(&self) -> Vec<CollectiveTransfer> {
+        let mut transfers = Vec::new();
+        for (rank, schedule) in build_schedules(self).into_iter().enumerate() {
+            let mut rank_transfers: Vec<CollectiveTransfer> = Vec::new();
+            for send in schedule.sends {
+                let after = send.dependencies.iter().map(|dep| (dep.src_rank, dep.step));
+                let existing = rank_transfers
+                    .iter_mut()
+                    .find(|t| t.dst_rank == send.dst_rank && t.step == send.step);
+                let transfer = match existing {
+                    Some(transfer) => {
+                        transfer.num_bytes += send.num_bytes;
+                        transfer
+                    }
+                    None => {
+                        rank_transfers.push(CollectiveTransfer {
+                            src_rank: rank,
+                            dst_rank: send.dst_rank,
+                            step: send.step,
+                            num_bytes: send.num_bytes,
+                            after: Vec::new(),
+                        });
+                        rank_transfers.last_mut().unwrap()
+                    }
+                };
+                for dep in after {
+                    if !transfer.after.contains(&dep) {
+                        transfer.after.push(dep);
+                    }
+                }
+            }
+            transfers.extend(rank_transfers);
+        }
+        transfers
+    }
+}
+
+pub struct CollectiveStatsDisplay {
+    prefix: String,
+    num_messages: usize,
+    bytes_sent: usize,
+    completion_time_ns: Option<f64>,
+}
+
+impl CollectiveStatsDisplay {
+    #[must_use]
+    pub fn new(
+        prefix: impl Into<String>,
+        num_messages: usize,
+        bytes_sent: usize,
+        completion_time_ns: Option<f64>,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            num_messages,
+            bytes_sent,
+            completion_time_ns,
+        }
+    }
+}
+
+impl Display for CollectiveStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.prefix)?;
+        writeln!(
+            f,
+            "  sent {} messages, {} bytes",
+            self.num_messages, self.bytes_sent
+        )?;
+        match self.completion_time_ns {
+            Some(time_ns) => write!(f, "  completed at {time_ns:.1}ns"),
+            None => write!(f, "  not completed"),
+        }
+    }
+}
+
+/// A chunk that must have been received (and reduced) before a send can start
+#[derive(Clone, Copy, Debug)]
+struct Dependency {
+    src_rank: usize,
+    step: usize,
+
+    /// When `None` all chunks of the step must have been received
+    chunk: Option<usize>,
+}
+
+struct SendOp {
+    dst_rank: usize,
+    step: usize,
+    chunk: usize,
+    num_bytes: usize,
+    dependencies: Vec<Dependency>,
+}
+
+/// The chunks a rank receives from one rank in one step
+struct ExpectedStep {
+    num_chunks: usize,
+    reduce: bool,
+}
+
+#[derive(Default)]
+struct RankSchedule {
+    sends: Vec<SendOp>,
+    receives: HashMap<(usize, usize), ExpectedStep>,
+}
+
+impl RankSchedule {
+    /// Add a send of one chunk which depends on the same chunk of each of the
+    /// (rank, step) pairs in `deps`. Unless `overlap` is set the send instead
+    /// depends on all chunks of those steps.
+    fn send(
+        &mut self,
+        dst_rank: usize,
+        step: usize,
+        chunk: usize,
+        num_bytes: usize,
+        deps: &[(usize, usize)],
+        overlap: bool,
+    ) {
+        self.sends.push(SendOp {
+            dst_rank,
+            step,
+            chunk,
+            num_bytes,
+            dependencies: deps
+                .iter()
+                .map(|(src_rank, step)| Dependency {
+                    src_rank: *src_rank,
+                    step: *step,
+                    chunk: overlap.then_some(chunk),
+                })
+                .collect(),
+        });
+    }
+
+    /// Send every chunk to `dst_rank`
+    fn send_chunks(
+        &mut self,
+        dst_rank: usize,
+        step: usize,
+        chunks: &[usize],
+        deps: &[(usize, usize)],
+        overlap: bool,
+    ) {
+        for (chunk, num_bytes) in chunks.iter().enumerate() {
+            self.send(dst_rank, step, chunk, *num_bytes, deps, overlap);
+        }
+    }
+
+    /// Send each chunk to all of `children` in turn
+    fn send_chunks_to_all(
+        &mut self,
+        children: &[usize],
+        step: usize,
+        chunks: &[usize],
+        deps: &[(usize, usize)],
+        overlap: bool,
+    ) {
+        for (chunk, num_bytes) in chunks.iter().enumerate() {
+            for child in children {
+                self.send(*child, step, chunk, *num_bytes, deps, overlap);
+            }
+        }
+    }
+
+    fn receive(&mut self, src_rank: usize, step: usize, num_chunks: usize, reduce: bool) {
+        self.receives
+            .insert((src_rank, step), ExpectedStep { num_chunks, reduce });
+    }
+}
+
+/// Split `num_bytes` into chunks of at most `chunk_bytes`
+fn chunk_sizes(num_bytes: usize, chunk_bytes: usize) -> Vec<usize> {
+    let mut chunks = vec![chunk_bytes; num_bytes / chunk_bytes];
+    if !num_bytes.is_multiple_of(chunk_bytes) {
+        chunks.push(num_bytes % chunk_bytes);
+    }
+    chunks
+}
+
+/// The number of bytes of the share of the data that each rank starts with in
+/// a ring all-reduce or an all-gather
+fn segment_bytes(config: &CollectiveConfig, rank: usize) -> usize {
+    let n = config.num_ranks();
+    config.num_bytes / n + usize::from(rank < config.num_bytes % n)
+}
+
+fn tree_parent(rank: usize) -> usize {
+    (rank - 1) / 2
+}
+
+fn tree_children(rank: usize, num_ranks: usize) -> Vec<usize> {
+    [2 * rank + 1, 2 * rank + 2]
+        .into_iter()
+        .filter(|child| *child < num_ranks)
+        .collect()
+}
+
+fn build_schedules(config: &CollectiveConfig) -> Vec<RankSchedule> {
+    let n = config.num_ranks();
+    let overlap = config.overlap;
+    let mut schedules: Vec<RankSchedule> = (0..n).map(|_| RankSchedule::default()).collect();
+    let chunks = chunk_sizes(config.num_bytes, config.chunk_bytes);
+
+    match (config.algorithm, config.op) {
+        (CollectiveAlgorithm::Ring, CollectiveOp::AllReduce) => {
+            // Split the data into one segment per rank. In step `s` rank `r`
+            // sends segment `r - s` to the next rank, reducing what it
+            // receives during the first `n - 1` steps.
+            let segments: Vec<Vec<usize>> = (0..n)
+                .map(|i| chunk_sizes(segment_bytes(config, i), config.chunk_bytes))
+                .collect();
+            for (rank, schedule) in schedules.iter_mut().enumerate() {
+                let next = (rank + 1) % n;
+                let prev = (rank + n - 1) % n;
+                for step in 0..2 * (n - 1) {
+                    let deps = if step == 0 {
+                        vec![]
+                    } else {
+                        vec![(prev, step - 1)]
+                    };
+                    let segment = &segments[(rank + 2 * n - step) % n];
+                    schedule.send_chunks(next, step, segment, &deps, overlap);
+
+                    let prev_segment = &segments[(prev + 2 * n - step) % n];
+                    schedule.receive(prev, step, prev_segment.len(), step < n - 1);
+                }
+            }
+        }
+        (CollectiveAlgorithm::Ring, CollectiveOp::AllGather) => {
+            // In step `s` rank `r` sends segment `r - s` to the next rank,
+            // starting with its own
+            let segments: Vec<Vec<usize>> = (0..n)
+                .map(|i| chunk_sizes(segment_bytes(config, i), config.chunk_bytes))
+                .collect();
+            for (rank, schedule) in schedules.iter_mut().enumerate() {
+                let next = (rank + 1) % n;
+                let prev = (rank + n - 1) % n;
+                for step in 0..n - 1 {
+                    let deps = if step == 0 {
+                        vec![]
+                    } else {
+                        vec![(prev, step - 1)]
+                    };
+                    let segment = &segments[(rank + n - step) % n];
+                    schedule.send_chunks(next, step, segment, &deps, overlap);
+
+                    let prev_segment = &segments[(prev + n - step) % n];
+                    schedule.receive(prev, step, prev_segment.len(), false);
+                }
+            }
+        }
+        (CollectiveAlgorithm::Ring, CollectiveOp::Broadcast) => {
+            for rank in 0..n.saturating_sub(1) {
+                let deps = if rank == 0 {
+                    vec![]
+                } else {
+                    vec![(rank - 1, rank - 1)]
+                };
+                schedules[rank].send_chunks(rank + 1, rank, &chunks, &deps, overlap);
+                schedules[rank + 1].receive(rank, rank, chunks.len(), false);
+            }
+        }
+        (CollectiveAlgorithm::Tree, CollectiveOp::Broadcast) => {
+            for (rank, schedule) in schedules.iter_mut().enumerate() {
+                let deps = if rank == 0 {
+                    vec![]
+                } else {
+                    let parent = tree_parent(rank);
+                    schedule.receive(parent, 0, chunks.len(), false);
+                    vec![(parent, 0)]
+                };
+                schedule.send_chunks_to_all(&tree_children(rank, n), 0, &chunks, &deps, overlap);
+            }
+        }
+        (CollectiveAlgorithm::Tree, CollectiveOp::AllReduce) => {
+            // Step 0 reduces up the tree, step 1 broadcasts back down it
+            for (rank, schedule) in schedules.iter_mut().enumerate() {
+                let children = tree_children(rank, n);
+                let up_deps: Vec<(usize, usize)> =
+                    children.iter().map(|child| (*child, 0)).collect();
+                for child in &children {
+                    schedule.receive(*child, 0, chunks.len(), true);
+                }
+
+                let down_deps = if rank == 0 {
+                    up_deps
+                } else {
+                    let parent = tree_parent(rank);
+                    schedule.send_chunks(parent, 0, &chunks, &up_deps, overlap);
+                    schedule.receive(parent, 1, chunks.len(), false);
+                    vec![(parent, 1)]
+                };
+                schedule.send_chunks_to_all(&children, 1, &chunks, &down_deps, overlap);
+            }
+        }
+        (CollectiveAlgorithm::Tree, CollectiveOp::AllGather) => {
+            // Step 0 gathers the segments of each subtree up to the root, step
+            // 1 broadcasts all of the data back down the tree
+            let mut subtree_bytes: Vec<usize> = (0..n).map(|i| segment_bytes(config, i)).collect();
+            for rank in (1..n).rev() {
+                subtree_bytes[tree_parent(rank)] += subtree_bytes[rank];
+            }
+            for (rank, schedule) in schedules.iter_mut().enumerate() {
+                let children = tree_children(rank, n);
+                let up_deps: Vec<(usize, usize)> =
+                    children.iter().map(|child| (*child, 0)).collect();
+                for child in &children {
+                    let child_chunks = chunk_sizes(subtree_bytes[*child], config.chunk_bytes);
+                    schedule.receive(*child, 0, child_chunks.len(), false);
+                }
+
+                let down_deps = if rank == 0 {
+                    up_deps
+                } else {
+                    // A subtree has more chunks than the subtrees below it, so
+                    // waits for all of their chunks before sending any
+                    let parent = tree_parent(rank);
+                    let rank_chunks = chunk_sizes(subtree_bytes[rank], config.chunk_bytes);
+                    schedule.send_chunks(parent, 0, &rank_chunks, &up_deps, false);
+                    schedule.receive(parent, 1, chunks.len(), false);
+                    vec![(parent, 1)]
+                };
+                let overlap = overlap && rank != 0;
+                schedule.send_chunks_to_all(&children, 1, &chunks, &down_deps, overlap);
+            }
+        }
+    }
+    schedules
+}
+
+/// The address of a message identifies its step and chunk
+fn message_addr(step: usize, chunk: usize) -> u64 {
+    ((step as u64) << 32) | chunk as u64
+}
+
+fn message_step_chunk(addr: u64) -> (usize, usize) {
+    ((addr >> 32) as usize, (addr & 0xffff_ffff) as usize)
+}
+
+/// The chunks received by a rank
+struct RankState {
+    schedule: RankSchedule,
+
+    /// For each (source rank, step) which chunks have been received
+    received: RefCell<HashMap<(usize, usize), Vec<bool>>>,
+    changed: Repeated<()>,
+}
+
+impl RankState {
+    fn new(schedule: RankSchedule) -> Self {
+        let received = schedule
+            .receives
+            .iter()
+            .map(|(key, expected)| (*key, vec![false; expected.num_chunks]))
+            .collect();
+        Self {
+            schedule,
+            received: RefCell::new(received),
+            changed: Repeated::new(()),
+        }
+    }
+
+    fn is_satisfied(&self, dependency: &Dependency) -> bool {
+        let received = self.received.borrow();
+        let Some(chunks) = received.get(&(dependency.src_rank, dependency.step)) else {
+            return false;
+        };
+        match dependency.chunk {
+            Some(chunk) => chunks.get(chunk).copied().unwrap_or(false),
+            None => chunks.iter().all(|received| *received),
+        }
+    }
+
+    fn all_received(&self) -> bool {
+        self.received
+            .borrow()
+            .values()
+            .all(|chunks| chunks.iter().all(|received| *received))
+    }
+
+    async fn wait_for(&self, dependencies: &[Dependency]) {
+        while !dependencies.iter().all(|dep| self.is_satisfied(dep)) {
+            self.changed.listen().await;
+        }
+    }
+}
+
+#[derive(Default)]
+struct CollectiveStats {
+    num_messages: usize,
+    bytes_sent: usize,
+    completion_time_ns: Option<f64>,
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct CollectiveEngine {
+    entity: Rc<Entity>,
+    clock: Clock,
+    spawner: Spawner,
+    config: CollectiveConfig,
+    rx: RefCell<Vec<Option<InPort<MemoryAccess>>>>,
+    tx: RefCell<Vec<Option<OutPort<MemoryAccess>>>>,
+    stats: Rc<RefCell<CollectiveStats>>,
+}
+
+impl CollectiveEngine {
+    /// Returns a `SimError` if the config is not valid.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: CollectiveConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        if config.num_ranks() == 0 {
+            return sim_error!("{entity}: a collective needs at least one rank");
+        }
+        if config.chunk_bytes == 0 {
+            return sim_error!("{entity}: chunk_bytes must be greater than 0");
+        }
+        if config.reduce_bytes_per_tick == Some(0) {
+            return sim_error!("{entity}: reduce_bytes_per_tick must be greater than 0");
+        }
+
+        let rx = (0..config.num_ranks())
+            .map(|i| {
+                Some(InPort::new_with_renames(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("rx_{i}"),
+                    aka,
+                ))
+            })
+            .collect();
+        let tx = (0..config.num_ranks())
+            .map(|i| Some(OutPort::new_with_renames(&entity, &format!("tx_{i}"), aka)))
+            .collect();
+        let rc_self = Rc::new(Self {
+            entity,
+            clock: clock.clone(),
+            spawner: engine.spawner(),
+            config,
+            rx: RefCell::new(rx),
+            tx: RefCell::new(tx),
+            stats: Rc::new(RefCell::new(CollectiveStats::default())),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: CollectiveConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn connect_port_tx_i(
+        &self,
+        i: usize,
+        port_state: PortStateResult<MemoryAccess>,
+    ) -> SimResult {
+        match self.tx.borrow_mut().get_mut(i) {
+            Some(Some(tx)) => tx.connect(port_state),
+            _ => sim_error!("{self}: no tx port {i}"),
+        }
+    }
+
+    pub fn port_rx_i(&self, i: usize) -> PortStateResult<MemoryAccess> {
+        self.rx.borrow()[i].as_ref().unwrap().state()
+    }
+
+    #[must_use]
+    pub fn config(&self) -> &CollectiveConfig {
+        &self.config
+    }
+
+    /// Returns the number of messages sent by all ranks
+    #[must_use]
+    pub fn num_messages(&self) -> usize {
+        self.stats.borrow().num_messages
+    }
+
+    /// Returns the number of payload bytes sent by all ranks
+    #[must_use]
+    pub fn bytes_sent(&self) -> usize {
+        self.stats.borrow().bytes_sent
+    }
+
+    /// Returns the time at which every rank had completed the collective
+    #[must_use]
+    pub fn completion_time_ns(&self) -> Option<f64> {
+        self.stats.borrow().completion_time_ns
+    }
+
+    pub fn dump_stats(&self) {
+        let stats = self.stats.borrow();
+        log_stats(
+            &self.entity,
+            CollectiveStatsDisplay::new(
+                format!("Collective {}", self.entity.full_name()),
+                stats.num_messages,
+                stats.bytes_sent,
+                stats.completion_time_ns,
+            ),
+        );
+    }
+}
+
+async fn run_rank_rx(
+    entity: Rc<Entity>,
+    clock: Clock,
+    rank: usize,
+    state: Rc<RankState>,
+    reduce_bytes_per_tick: Option<usize>,
+    mut rx: InPort<MemoryAccess>,
+) -> SimResult {
+    loop {
+        let message = rx.get()?.await;
+        let src_rank = message.src_addr() as usize;
+        let (step, chunk) = message_step_chunk(message.dst_addr());
+        trace!(entity ; "rank {rank} received chunk {chunk} of step {step} from rank {src_rank}");
+
+        let Some(expected) = state.schedule.receives.get(&(src_rank, step)) else {
+            return sim_error!(
+                "{entity}: rank {rank} received unexpected step {step} from rank {src_rank}"
+            );
+        };
+        if expected.reduce
+            && let Some(bytes_per_tick) = reduce_bytes_per_tick
+        {
+            // The reduction unit of the rank is busy until the chunk is reduced
+            clock
+                .wait_ticks(message.access_size_bytes().div_ceil(bytes_per_tick) as u64)
+                .await;
+        }
+
+        {
+            let mut received = state.received.borrow_mut();
+            let chunks = received.get_mut(&(src_rank, step)).unwrap();
+            match chunks.get_mut(chunk) {
+                Some(received @ false) => *received = true,
+                _ => {
+                    return sim_error!(
+                        "{entity}: rank {rank} received unexpected chunk {chunk} of step {step} from rank {src_rank}"
+                    );
+                }
+            }
+        }
+        state.changed.notify();
+    }
+}
+
+async fn run_rank_tx(
+    entity: Rc<Entity>,
+    config: CollectiveConfig,
+    rank: usize,
+    state: Rc<RankState>,
+    stats: Rc<RefCell<CollectiveStats>>,
+    mut tx: OutPort<MemoryAccess>,
+) -> SimResult {
+    for send in &state.schedule.sends {
+        state.wait_for(&send.dependencies).await;
+        let message = MemoryAccess::new(
+            &entity,
+            AccessType::WriteRequest,
+            send.num_bytes,
+            message_addr(send.step, send.chunk),
+            rank as u64,
+            DeviceId(config.rank_ports[send.dst_rank] as u64),
+            DeviceId(config.rank_ports[rank] as u64),
+            config.overhead_size_bytes,
+        );
+        trace!(entity ; "rank {rank} sends chunk {} of step {} to rank {}", send.chunk, send.step, send.dst_rank);
+        tx.put(message)?.await;
+
+        let mut stats = stats.borrow_mut();
+        stats.num_messages += 1;
+        stats.bytes_sent += send.num_bytes;
+    }
+    Ok(())
+}
+
+#[async_trait(?Send)]
+impl Runnable for CollectiveEngine {
+    async fn run(&self) -> SimResult {
+        let rxs: Vec<_> = self.rx.borrow_mut().drain(..).collect();
+        let txs: Vec<_> = self.tx.borrow_mut().drain(..).collect();
+        let states: Vec<Rc<RankState>> = build_schedules(&self.config)
+            .into_iter()
+            .map(|schedule| Rc::new(RankState::new(schedule)))
+            .collect();
+
+        let mut sends_done = Vec::with_capacity(states.len());
+        for (rank, ((rx, tx), state)) in rxs.into_iter().zip(txs).zip(&states).enumerate() {
+            {
+                let entity = self.entity.clone();
+                let clock = self.clock.clone();
+                let state = state.clone();
+                let reduce_bytes_per_tick = self.config.reduce_bytes_per_tick;
+                let rx = rx.unwrap();
+                self.spawner.spawn(async move {
+                    run_rank_rx(entity, clock, rank, state, reduce_bytes_per_tick, rx).await
+                });
+            }
+            {
+                let entity = self.entity.clone();
+                let config = self.config.clone();
+                let state = state.clone();
+                let stats = self.stats.clone();
+                let tx = tx.unwrap();
+                let done = Once::default();
+                sends_done.push(done.clone());
+                self.spawner.spawn(async move {
+                    run_rank_tx(entity, config, rank, state, stats, tx).await?;
+                    done.notify()
+                });
+            }
+        }
+
+        for (done, state) in sends_done.iter().zip(&states) {
+            done.listen().await;
+            while !state.all_received() {
+                state.changed.listen().await;
+            }
+        }
+
+        let time_now_ns = self.clock.time_now_ns();
+        debug!(self.entity ; "collective complete at {time_now_ns}ns");
+        self.stats.borrow_mut().completion_time_ns = Some(time_now_ns);
+        Ok(())
+    }
+}
@@ -0,0 +1,601 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! ECN-style congestion marking and reaction.
+//!
+//! End-to-end congestion control is built from three parts:
+//!  - An [EcnMarker] sits in front of a buffer and marks every object that
+//!    arrives while the buffer holds more than a threshold (the congestion
+//!    point). The [routed fabric](crate::fabric::routed::RoutedFabric) and
+//!    [ring node](crate::ring_node::RingNode) add markers to their buffers when
+//!    they are configured with an ECN threshold.
+//!  - A [CongestionNotifier] at the destination reports every marked object
+//!    back to the [RateController] of the source that sent it after a
+//!    notification delay (the notification point).
+//!  - A [ReactionPoint] at the source limits the rate at which objects are sent
+//!    (the reaction point). Its rate is cut by a multiplicative decrease factor
+//!    when congestion is notified and recovers additively, up to the line rate,
+//!    for every recovery interval without a notification.
+//!
+//! A source reduces its rate at most once per recovery interval so that a burst
+//! of marked objects is treated as a single congestion event.
+//!
+//! Objects carry the mark using
+//! [`set_congestion_experienced`](gwr_engine::traits::Routable::set_congestion_experienced).
+//!
+//! # Ports
+//!
+//! Each component has two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::store::Store;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::executor::Spawner;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Routable, Runnable, SimObject};
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::trace;
+use gwr_track::tracker::aka::Aka;
+
+use crate::log_stats;
+
+/// Returns the source that sent an object so that congestion can be notified
+/// to its [RateController].
+pub type SourceOf<T> = fn(&T) -> u64;
+
+const DEFAULT_DECREASE_FACTOR: f64 = 0.5;
+const DEFAULT_RECOVERY_INTERVAL_TICKS: u64 = 100;
+const DEFAULT_MIN_RATE_BITS_PER_TICK: f64 = 1.0;
+
+/// A component that marks objects arriving while a buffer is congested
+#[derive(EntityGet, EntityDisplay)]
+pub struct EcnMarker<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+    buffer: Rc<Store<T>>,
+    threshold: usize,
+    num_marked: Cell<usize>,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> EcnMarker<T>
+where
+    T: SimObject + Routable,
+{
+    /// Mark objects that arrive while `buffer` holds more than `threshold`
+    /// of its capacity units
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        buffer: &Rc<Store<T>>,
+        threshold: usize,
+    ) -> Rc<Self> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            buffer: buffer.clone(),
+            threshold,
+            num_marked: Cell::new(0),
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        rc_self
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        buffer: &Rc<Store<T>>,
+        threshold: usize,
+    ) -> Rc<Self> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, buffer, threshold)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    #[must_use]
+    pub fn num_marked(&self) -> usize {
+        self.num_marked.get()
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for EcnMarker<T>
+where
+    T: SimObject + Routable,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let mut value = rx.get()?.await;
+            let level = self.buffer.capacity_used();
+            if level > self.threshold {
+                trace!(self.entity ; "Marked {value} with buffer level {level}");
+                value.set_congestion_experienced();
+                self.num_marked.set(self.num_marked.get() + 1);
+            }
+            tx.put(value)?.await;
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReactionConfig {
+    line_rate_bits_per_tick: f64,
+    min_rate_bits_per_tick: f64,
+    decrease_factor: f64,
+    recovery_interval_ticks: u64,
+    recovery_step_bits_per_tick: f64,
+}
+
+impl ReactionConfig {
+    /// Send at up to `line_rate_bits_per_tick`. By default the rate is halved
+    /// on congestion and recovers by a tenth of the line rate every 100 ticks.
+    #[must_use]
+    pub fn new(line_rate_bits_per_tick: usize) -> Self {
+        let line_rate_bits_per_tick = line_rate_bits_per_tick as f64;
+        Self {
+            line_rate_bits_per_tick,
+            min_rate_bits_per_tick: DEFAULT_MIN_RATE_BITS_PER_TICK,
+            decrease_factor: DEFAULT_DECREASE_FACTOR,
+            recovery_interval_ticks: DEFAULT_RECOVERY_INTERVAL_TICKS,
+            recovery_step_bits_per_tick: line_rate_bits_per_tick / 10.0,
+        }
+    }
+
+    /// Set the lowest rate that congestion can reduce the source to
+    #[must_use]
+    pub fn with_min_rate_bits_per_tick(mut self, min_rate_bits_per_tick: f64) -> Self {
+        self.min_rate_bits_per_tick = min_rate_bits_per_tick;
+        self
+    }
+
+    /// Set the factor that the rate is multiplied by on congestion
+    #[must_use]
+    pub fn with_decrease_factor(mut self, decrease_factor: f64) -> Self {
+        self.decrease_factor = decrease_factor;
+        self
+    }
+
+    /// Increase the rate by `step_bits_per_tick` for every `interval_ticks`
+    /// without congestion
+    #[must_use]
+    pub fn with_recovery(mut self, interval_ticks: u64, step_bits_per_tick: f64) -> Self {
+        self.recovery_interval_ticks = interval_ticks;
+        self.recovery_step_bits_per_tick = step_bits_per_tick;
+        self
+    }
+
+    #[must_use]
+    pub fn line_rate_bits_per_tick(&self) -> f64 {
+        self.line_rate_bits_per_tick
+    }
+
+    #[must_use]
+    pub fn min_rate_bits_per_tick(&self) -> f64 {
+        self.min_rate_bits_per_tick
+    }
+
+    #[must_use]
+    pub fn decrease_factor(&self) -> f64 {
+        self.decrease_factor
+    }
+
+    #[must_use]
+    pub fn recovery_interval_ticks(&self) -> u64 {
+        self.recovery_interval_ticks
+    }
+
+    #[must_use]
+    pub fn recovery_step_bits_per_tick(&self) -> f64 {
+        self.recovery_step_bits_per_tick
+    }
+
+    fn check(&self, entity: &Rc<Entity>) -> SimResult {
+        if !(self.min_rate_bits_per_tick > 0.0
+            && self.min_rate_bits_per_tick <= self.line_rate_bits_per_tick)
+        {
+            return sim_error!(
+                "{entity}: minimum rate {} must be above 0 and no more than the line rate {}",
+                self.min_rate_bits_per_tick,
+                self.line_rate_bits_per_tick
+            );
+        }
+        if !(self.decrease_factor > 0.0 && self.decrease_factor < 1.0) {
+            return sim_error!(
+                "{entity}: decrease factor {} must be between 0 and 1",
+                self.decrease_factor
+            );
+        }
+        if self.recovery_interval_ticks == 0 {
+            return sim_error!("{entity}: recovery interval must be at least 1 tick");
+        }
+        Ok(())
+    }
+}
+
+/// The rate of a source, which is reduced when congestion is notified and
+/// recovers over time
+pub struct RateController {
+    entity: Rc<Entity>,
+    clock: Clock,
+    config: ReactionConfig,
+    rate_bits_per_tick: Cell<f64>,
+
+    /// Tick from which the next recovery interval is measured
+    recovery_start_tick: Cell<u64>,
+    last_decrease_tick: Cell<Option<u64>>,
+    num_notifications: Cell<usize>,
+    num_decreases: Cell<usize>,
+}
+
+impl RateController {
+    fn new(entity: &Rc<Entity>, clock: &Clock, config: ReactionConfig) -> Self {
+        Self {
+            entity: entity.clone(),
+            clock: clock.clone(),
+            config,
+            rate_bits_per_tick: Cell::new(config.line_rate_bits_per_tick),
+            recovery_start_tick: Cell::new(0),
+            last_decrease_tick: Cell::new(None),
+            num_notifications: Cell::new(0),
+            num_decreases: Cell::new(0),
+        }
+    }
+
+    fn recover(&self) {
+        let tick_now = self.clock.tick_now().tick();
+        let interval = self.config.recovery_interval_ticks;
+        let num_intervals = (tick_now - self.recovery_start_tick.get()) / interval;
+        if num_intervals > 0 {
+            let rate = self.rate_bits_per_tick.get()
+                + num_intervals as f64 * self.config.recovery_step_bits_per_tick;
+            self.rate_bits_per_tick
+                .set(rate.min(self.config.line_rate_bits_per_tick));
+            self.recovery_start_tick
+                .set(self.recovery_start_tick.get() + num_intervals * interval);
+        }
+    }
+
+    /// Report that an object sent by this source was marked as congested
+    pub fn notify_congestion(&self) {
+        self.recover();
+        self.num_notifications.set(self.num_notifications.get() + 1);
+
+        let tick_now = self.clock.tick_now().tick();
+        self.recovery_start_tick.set(tick_now);
+        let can_decrease = self
+            .last_decrease_tick
+            .get()
+            .is_none_or(|last_tick| tick_now - last_tick >= self.config.recovery_interval_ticks);
+        if can_decrease {
+            let rate = self.rate_bits_per_tick.get() * self.config.decrease_factor;
+            self.rate_bits_per_tick
+                .set(rate.max(self.config.min_rate_bits_per_tick));
+            self.last_decrease_tick.set(Some(tick_now));
+            self.num_decreases.set(self.num_decreases.get() + 1);
+            trace!(self.entity ; "Rate reduced to {:.2} bits per tick", self.rate_bits_per_tick.get());
+        }
+    }
+
+    /// Returns the current rate of the source
+    #[must_use]
+    pub fn rate_bits_per_tick(&self) -> f64 {
+        self.recover();
+        self.rate_bits_per_tick.get()
+    }
+
+    #[must_use]
+    pub fn num_notifications(&self) -> usize {
+        self.num_notifications.get()
+    }
+
+    #[must_use]
+    pub fn num_decreases(&self) -> usize {
+        self.num_decreases.get()
+    }
+
+    fn ticks(&self, num_bytes: usize) -> u64 {
+        ((num_bytes * 8) as f64 / self.rate_bits_per_tick()).ceil() as u64
+    }
+}
+
+pub struct ReactionStatsDisplay {
+    prefix: String,
+    rate_bits_per_tick: f64,
+    line_rate_bits_per_tick: f64,
+    num_notifications: usize,
+    num_decreases: usize,
+}
+
+impl ReactionStatsDisplay {
+    #[must_use]
+    pub fn new(
+        prefix: impl Into<String>,
+        rate_bits_per_tick: f64,
+        line_rate_bits_per_tick: f64,
+        num_notifications: usize,
+        num_decreases: usize,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            rate_bits_per_tick,
+            line_rate_bits_per_tick,
+            num_notifications,
+            num_decreases,
+        }
+    }
+}
+
+impl Display for ReactionStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.prefix)?;
+        write!(
+            f,
+            "  Rate: {:.2}/{:.2} bits per tick, notifications: {}, decreases: {}",
+            self.rate_bits_per_tick,
+            self.line_rate_bits_per_tick,
+            self.num_notifications,
+            self.num_decreases
+        )
+    }
+}
+
+/// A component that limits the rate of a source in reaction to congestion
+#[derive(EntityGet, EntityDisplay)]
+pub struct ReactionPoint<T>
+where
+    T: SimObject,
+{
+    entity: Rc<Entity>,
+    clock: Clock,
+    controller: Rc<RateController>,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> ReactionPoint<T>
+where
+    T: SimObject,
+{
+    /// Returns a `SimError` if the configuration is invalid.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: &ReactionConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        config.check(&entity)?;
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let controller = Rc::new(RateController::new(&entity, clock, *config));
+        let rc_self = Rc::new(Self {
+            entity,
+            clock: clock.clone(),
+            controller,
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Returns a `SimError` if the configuration is invalid.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: &ReactionConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Returns the controller to notify of congestion
+    #[must_use]
+    pub fn controller(&self) -> &Rc<RateController> {
+        &self.controller
+    }
+
+    pub fn dump_stats(&self) {
+        let controller = &self.controller;
+        log_stats(
+            &self.entity,
+            ReactionStatsDisplay::new(
+                format!("Reaction {}", self.entity.full_name()),
+                controller.rate_bits_per_tick(),
+                controller.config.line_rate_bits_per_tick,
+                controller.num_notifications(),
+                controller.num_decreases(),
+            ),
+        );
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for ReactionPoint<T>
+where
+    T: SimObject,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            // Get the value but without letting the OutPort complete
+            let value = rx.start_get()?.await;
+            let ticks = self.controller.ticks(value.total_bytes());
+            let start_tick = self.clock.tick_now().tick();
+            tx.put(value)?.await;
+
+            // Time spent blocked downstream counts towards the gap between
+            // values so that back-pressure does not reduce the rate further
+            let elapsed_ticks = self.clock.tick_now().tick() - start_tick;
+            if elapsed_ticks < ticks {
+                self.clock.wait_ticks(ticks - elapsed_ticks).await;
+            }
+
+            // Allow the OutPort to complete
+            rx.finish_get();
+        }
+    }
+}
+
+/// A component that notifies the source of each marked object of congestion
+#[derive(EntityGet, EntityDisplay)]
+pub struct CongestionNotifier<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+    spawner: Spawner,
+    clock: Clock,
+    source_of: SourceOf<T>,
+    notification_delay_ticks: u64,
+    controllers: RefCell<HashMap<u64, Rc<RateController>>>,
+    num_marked: Cell<usize>,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> CongestionNotifier<T>
+where
+    T: SimObject + Routable,
+{
+    /// Notify the source of each marked object, as given by `source_of`, after
+    /// `notification_delay_ticks`
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        source_of: SourceOf<T>,
+        notification_delay_ticks: u64,
+    ) -> Rc<Self> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            spawner: engine.spawner(),
+            clock: clock.clone(),
+            source_of,
+            notification_delay_ticks,
+            controllers: RefCell::new(HashMap::new()),
+            num_marked: Cell::new(0),
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        rc_self
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        source_of: SourceOf<T>,
+        notification_delay_ticks: u64,
+    ) -> Rc<Self> {
+        Self::new_and_register_with_renames(
+            engine,
+            clock,
+            parent,
+            name,
+            None,
+            source_of,
+            notification_delay_ticks,
+        )
+    }
+
+    /// Notify `controller` of congestion seen by objects from `source`
+    pub fn add_source(&self, source: u64, controller: &Rc<RateController>) {
+        self.controllers
+            .borrow_mut()
+            .insert(source, controller.clone());
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Returns the number of marked objects received
+    #[must_use]
+    pub fn num_marked(&self) -> usize {
+        self.num_marked.get()
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for CongestionNotifier<T>
+where
+    T: SimObject + Routable,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let value = rx.get()?.await;
+            if value.congestion_experienced() {
+                self.num_marked.set(self.num_marked.get() + 1);
+                let source = (self.source_of)(&value);
+                let Some(controller) = self.controllers.borrow().get(&source).cloned() else {
+                    return sim_error!("{self}: no reaction point for source {source} of {value}");
+                };
+                let clock = self.clock.clone();
+                let delay_ticks = self.notification_delay_ticks;
+                self.spawner.spawn(async move {
+                    clock.wait_ticks(delay_ticks).await;
+                    controller.notify_congestion();
+                    Ok(())
+                });
+            }
+            tx.put(value)?.await;
+        }
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Energy models for interconnects.
+//!
+//! An [EnergyConfig] describes the energy used to move data:
+//!  - hop energy: used by every object (frame or flit) for each hop it makes
+//!    through a switch or router.
+//!  - bit energy: used for every bit moved across each hop or link.
+//!  - idle power: drawn whether or not data is moving.
+//!
+//! The energy is recorded in an
+//! [EnergyAccount](gwr_engine::power::EnergyAccount) created with the
+//! [power accounting](gwr_engine::power::PowerAccounting) of the engine so
+//! that the energy and power of all components can be reported together.
+//!
+//! # Ports
+//!
+//! The [EnergyTap] has two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::power::EnergyAccount;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Runnable, SimObject};
+use gwr_engine::types::SimResult;
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EnergyConfig {
+    hop_energy_pj: f64,
+    bit_energy_pj: f64,
+    idle_power_mw: f64,
+}
+
+impl EnergyConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the energy used by each object for every hop it makes
+    #[must_use]
+    pub fn with_hop_energy_pj(mut self, hop_energy_pj: f64) -> Self {
+        self.hop_energy_pj = hop_energy_pj;
+        self
+    }
+
+    /// Set the energy used to move each bit across a hop or link
+    #[must_use]
+    pub fn with_bit_energy_pj(mut self, bit_energy_pj: f64) -> Self {
+        self.bit_energy_pj = bit_energy_pj;
+        self
+    }
+
+    /// Set the power drawn whether or not data is moving
+    #[must_use]
+    pub fn with_idle_power_mw(mut self, idle_power_mw: f64) -> Self {
+        self.idle_power_mw = idle_power_mw;
+        self
+    }
+
+    #[must_use]
+    pub fn hop_energy_pj(&self) -> f64 {
+        self.hop_energy_pj
+    }
+
+    #[must_use]
+    pub fn bit_energy_pj(&self) -> f64 {
+        self.bit_energy_pj
+    }
+
+    #[must_use]
+    pub fn idle_power_mw(&self) -> f64 {
+        self.idle_power_mw
+    }
+
+    /// Returns the energy used to move an object of `num_bytes` one hop
+    #[must_use]
+    pub fn transfer_energy_pj(&self, num_bytes: usize) -> f64 {
+        self.hop_energy_pj + (num_bytes * 8) as f64 * self.bit_energy_pj
+    }
+}
+
+/// Records the energy of the transfers made by a component in its account
+#[derive(Clone)]
+pub struct EnergyMeter {
+    account: Rc<EnergyAccount>,
+    config: EnergyConfig,
+}
+
+impl EnergyMeter {
+    #[must_use]
+    pub fn new(account: &Rc<EnergyAccount>, config: EnergyConfig) -> Self {
+        Self {
+            account: account.clone(),
+            config,
+        }
+    }
+
+    /// Record an object of `num_bytes` moving `num_hops`
+    pub fn record_transfer(&self, num_bytes: usize, num_hops: usize) {
+        self.account
+            .add_energy_pj(self.config.transfer_energy_pj(num_bytes) * num_hops as f64);
+    }
+
+    #[must_use]
+    pub fn account(&self) -> &Rc<EnergyAccount> {
+        &self.account
+    }
+}
+
+/// A component that records the energy of every object passing through it as
+/// a single hop
+#[derive(EntityGet, EntityDisplay)]
+pub struct EnergyTap<T>
+where
+    T: SimObject,
+{
+    entity: Rc<Entity>,
+    meter: EnergyMeter,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> EnergyTap<T>
+where
+    T: SimObject,
+{
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        meter: EnergyMeter,
+    ) -> Rc<Self> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let rx = InPort::new(engine, clock, &entity, "rx");
+        let tx = OutPort::new(&entity, "tx");
+        let rc_self = Rc::new(Self {
+            entity,
+            meter,
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        rc_self
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for EnergyTap<T>
+where
+    T: SimObject,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let value = rx.get()?.await;
+            self.meter.record_transfer(value.total_bytes(), 1);
+            tx.put(value)?.await;
+        }
+    }
+}
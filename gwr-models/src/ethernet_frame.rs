@@ -11,12 +11,23 @@ use gwr_track::entity::Entity;
 use gwr_track::id::Unique;
 use gwr_track::{Id, create_id, track_create_object};
 
+use crate::link_compression::Compressible;
+use crate::link_errors::Corruptible;
+use crate::scatter_gather::ScatterGather;
+
 pub const PREAMBLE_BYTES: usize = 7;
 pub const SFD_BYTES: usize = 1;
 pub const DEST_MAC_BYTES: usize = 6;
 pub const SRC_MAC_BYTES: usize = 6;
 pub const FRAME_OVERHEAD_BYTES: usize = PREAMBLE_BYTES + SFD_BYTES + DEST_MAC_BYTES + SRC_MAC_BYTES;
 
+/// Size of the IEEE 802.1Q tag added to VLAN tagged frames
+pub const VLAN_TAG_BYTES: usize = 4;
+/// The largest valid VLAN identifier
+pub const MAX_VLAN_ID: u16 = 0xfff;
+/// The largest valid Priority Code Point
+pub const MAX_PCP: u8 = 7;
+
 #[must_use]
 pub fn mac_to_u64(mac: &[u8; DEST_MAC_BYTES]) -> u64 {
     ((mac[5] as u64) << (8 * 5))
@@ -39,6 +50,12 @@ pub fn u64_to_mac(value: u64) -> [u8; DEST_MAC_BYTES] {
     mac
 }
 
+#[derive(Clone, Copy, Debug)]
+struct VlanTag {
+    vlan_id: u16,
+    pcp: u8,
+}
+
 #[derive(Clone, Debug)]
 pub struct EthernetFrame {
     id: Id,
@@ -49,6 +66,28 @@ pub struct EthernetFrame {
 
     // Currently we don't store any actual frame contents
     payload_size_bytes: usize,
+
+    // Set if the payload is gathered from non-contiguous memory
+    scatter_gather: Option<ScatterGather>,
+
+    // Destinations of a multicast frame
+    multicast_dests: Option<Vec<u64>>,
+
+    traffic_class: usize,
+
+    vlan: Option<VlanTag>,
+
+    // Set if bits of the frame were in error when it crossed a link
+    corrupted: bool,
+
+    // Ratio of the uncompressed to compressed size of the payload
+    compressibility: f64,
+
+    // Set while the payload is compressed crossing a link
+    compressed_payload_bytes: Option<usize>,
+
+    // Set when the frame passes through a congested buffer
+    congestion_experienced: bool,
 }
 
 impl EthernetFrame {
@@ -59,6 +98,14 @@ impl EthernetFrame {
             dst_mac: [0; DEST_MAC_BYTES],
             src_mac: [0; DEST_MAC_BYTES],
             payload_size_bytes,
+            scatter_gather: None,
+            multicast_dests: None,
+            traffic_class: 0,
+            vlan: None,
+            corrupted: false,
+            compressibility: 1.0,
+            compressed_payload_bytes: None,
+            congestion_experienced: false,
         };
         // Having just created the frame the req_type must be valid
         track_create_object!(
@@ -84,6 +131,62 @@ impl EthernetFrame {
         self
     }
 
+    /// Carry the non-contiguous data described by `scatter_gather` as the
+    /// payload. The payload size becomes the total size of the segments.
+    #[must_use]
+    pub fn set_scatter_gather(mut self, scatter_gather: ScatterGather) -> Self {
+        self.payload_size_bytes = scatter_gather.num_bytes();
+        self.scatter_gather = Some(scatter_gather);
+        self
+    }
+
+    /// Send the frame to a set of destinations instead of the destination MAC
+    #[must_use]
+    pub fn set_multicast_dests(mut self, dests: Vec<u64>) -> Self {
+        self.multicast_dests = Some(dests);
+        self
+    }
+
+    /// Set the traffic class used to arbitrate between frames
+    #[must_use]
+    pub fn set_traffic_class(mut self, traffic_class: usize) -> Self {
+        self.traffic_class = traffic_class;
+        self
+    }
+
+    /// Add an IEEE 802.1Q VLAN tag to the frame. The Priority Code Point
+    /// (`pcp`) then defines the traffic class of the frame.
+    ///
+    /// Only the low 12 bits of `vlan_id` and the low 3 bits of `pcp` are
+    /// carried by the tag.
+    #[must_use]
+    pub fn set_vlan(mut self, vlan_id: u16, pcp: u8) -> Self {
+        self.vlan = Some(VlanTag {
+            vlan_id: vlan_id & MAX_VLAN_ID,
+            pcp: pcp & MAX_PCP,
+        });
+        self
+    }
+
+    /// Set the ratio of the uncompressed to compressed size of the payload
+    /// used by links that compress frames. Frames are incompressible (1.0) by
+    /// default.
+    #[must_use]
+    pub fn set_compressibility(mut self, compressibility: f64) -> Self {
+        self.compressibility = compressibility;
+        self
+    }
+
+    #[must_use]
+    pub fn vlan_id(&self) -> Option<u16> {
+        self.vlan.map(|tag| tag.vlan_id)
+    }
+
+    #[must_use]
+    pub fn pcp(&self) -> Option<u8> {
+        self.vlan.map(|tag| tag.pcp)
+    }
+
     #[must_use]
     pub fn get_dst(&self) -> u64 {
         mac_to_u64(&self.dst_mac)
@@ -93,6 +196,16 @@ impl EthernetFrame {
     pub fn get_src(&self) -> u64 {
         mac_to_u64(&self.src_mac)
     }
+
+    #[must_use]
+    pub fn payload_size_bytes(&self) -> usize {
+        self.payload_size_bytes
+    }
+
+    #[must_use]
+    pub fn scatter_gather(&self) -> Option<&ScatterGather> {
+        self.scatter_gather.as_ref()
+    }
 }
 
 impl SimObject for EthernetFrame {}
@@ -109,7 +222,18 @@ impl Display for EthernetFrame {
 
 impl TotalBytes for EthernetFrame {
     fn total_bytes(&self) -> usize {
-        self.payload_size_bytes + PREAMBLE_BYTES + SFD_BYTES + DEST_MAC_BYTES + SRC_MAC_BYTES
+        let tag_bytes = if self.vlan.is_some() {
+            VLAN_TAG_BYTES
+        } else {
+            0
+        };
+        self.compressed_payload_bytes
+            .unwrap_or(self.payload_size_bytes)
+            + PREAMBLE_BYTES
+            + SFD_BYTES
+            + DEST_MAC_BYTES
+            + SRC_MAC_BYTES
+            + tag_bytes
     }
 }
 
@@ -121,13 +245,64 @@ impl Unique for EthernetFrame {
 
 impl Routable for EthernetFrame {
     fn destination(&self) -> u64 {
-        self.get_dst()
+        match self.multicast_dests.as_deref() {
+            Some([first, ..]) => *first,
+            _ => self.get_dst(),
+        }
     }
 
     fn access_type(&self) -> AccessType {
         // Simply return a default value
         AccessType::Control
     }
+
+    fn destinations(&self) -> Option<&[u64]> {
+        self.multicast_dests.as_deref()
+    }
+
+    fn set_destinations(&mut self, destinations: Vec<u64>) {
+        self.multicast_dests = Some(destinations);
+    }
+
+    fn traffic_class(&self) -> usize {
+        match self.vlan {
+            Some(tag) => tag.pcp as usize,
+            None => self.traffic_class,
+        }
+    }
+
+    fn set_congestion_experienced(&mut self) {
+        self.congestion_experienced = true;
+    }
+
+    fn congestion_experienced(&self) -> bool {
+        self.congestion_experienced
+    }
+}
+
+impl Corruptible for EthernetFrame {
+    fn set_corrupted(&mut self) {
+        self.corrupted = true;
+    }
+
+    fn is_corrupted(&self) -> bool {
+        self.corrupted
+    }
+}
+
+impl Compressible for EthernetFrame {
+    fn compressibility(&self) -> f64 {
+        self.compressibility
+    }
+
+    fn compress(&mut self, ratio: f64) {
+        self.compressed_payload_bytes =
+            Some((self.payload_size_bytes as f64 / ratio).ceil() as usize);
+    }
+
+    fn decompress(&mut self) {
+        self.compressed_payload_bytes = None;
+    }
 }
 
 /// Allow Box of any SimObject type to be used
@@ -152,4 +327,40 @@ impl Routable for Box<EthernetFrame> {
     fn access_type(&self) -> AccessType {
         self.as_ref().access_type()
     }
+    fn destinations(&self) -> Option<&[u64]> {
+        self.as_ref().destinations()
+    }
+    fn set_destinations(&mut self, destinations: Vec<u64>) {
+        self.as_mut().set_destinations(destinations);
+    }
+    fn traffic_class(&self) -> usize {
+        self.as_ref().traffic_class()
+    }
+    fn set_congestion_experienced(&mut self) {
+        self.as_mut().set_congestion_experienced();
+    }
+    fn congestion_experienced(&self) -> bool {
+        self.as_ref().congestion_experienced()
+    }
+}
+
+impl Corruptible for Box<EthernetFrame> {
+    fn set_corrupted(&mut self) {
+        self.as_mut().set_corrupted();
+    }
+    fn is_corrupted(&self) -> bool {
+        self.as_ref().is_corrupted()
+    }
+}
+
+impl Compressible for Box<EthernetFrame> {
+    fn compressibility(&self) -> f64 {
+        self.as_ref().compressibility()
+    }
+    fn compress(&mut self, ratio: f64) {
+        self.as_mut().compress(ratio);
+    }
+    fn decompress(&mut self) {
+        self.as_mut().decompress();
+    }
 }
@@ -9,105 +9,346 @@
 //! This component has four ports:
 //!  - Two [input ports](gwr_engine::port::InPort): `rx_a`, `rx_b`,
 //!  - Two [output ports](gwr_engine::port::OutPort): `tx_a`, `tx_b`,
+//!
+//! A link created with a [config](EthernetLinkConfig) can have:
+//!  - [per-priority queues](PriorityQueues) in front of each direction, so that
+//!    higher priority frames are transmitted ahead of lower priority ones.
+//!  - [bit errors](BitErrors) injected into the frames leaving each direction,
+//!    which either corrupt the frames or cause them to be dropped.
+//!  - [compression](crate::link_compression) of the frame payloads, which
+//!    reduces the bytes sent on the wire according to the compressibility of
+//!    each frame.
+//!  - an [energy model](EnergyConfig) where every bit sent in either direction
+//!    uses the bit energy and the link draws the idle power throughout.
 
 use std::rc::Rc;
 
 use async_trait::async_trait;
 use gwr_components::delay::Delay;
 use gwr_components::flow_controls::limiter::Limiter;
+use gwr_components::flow_controls::rate_limiter::RateLimiter;
 use gwr_components::{connect_port, rc_limiter};
 use gwr_engine::engine::Engine;
 use gwr_engine::port::PortStateResult;
+use gwr_engine::power::EnergyAccount;
 use gwr_engine::time::clock::Clock;
-use gwr_engine::traits::SimObject;
+use gwr_engine::traits::{Routable, SimObject};
 use gwr_engine::types::{SimError, SimResult};
 use gwr_model_builder::{EntityDisplay, EntityGet, Runnable};
 use gwr_track::build_aka;
 use gwr_track::entity::Entity;
 use gwr_track::tracker::aka::Aka;
 
+use crate::energy::{EnergyConfig, EnergyMeter, EnergyTap};
+use crate::link_compression::{Compressible, CompressionConfig, Compressor, Decompressor};
+use crate::link_errors::{BitErrorConfig, BitErrors, Corruptible};
+use crate::priority_queues::{PriorityQueueConfig, PriorityQueues};
+
 // Default values for an Ethernet Link
 pub const DELAY_TICKS: usize = 500;
 pub const BITS_PER_TICK: usize = 100;
 
+/// Optional features of an [EthernetLink]
+#[derive(Clone, Default)]
+pub struct EthernetLinkConfig {
+    priority_queues: Option<PriorityQueueConfig>,
+    bit_errors: Option<BitErrorConfig>,
+    compression: Option<CompressionConfig>,
+    energy: Option<EnergyConfig>,
+}
+
+impl EthernetLinkConfig {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add per-priority queues in front of each direction
+    #[must_use]
+    pub fn with_priority_queues(mut self, priority_queues: PriorityQueueConfig) -> Self {
+        self.priority_queues = Some(priority_queues);
+        self
+    }
+
+    /// Inject bit errors into each direction. Direction `b` uses the next seed
+    /// after the one configured so that the two directions are independent.
+    #[must_use]
+    pub fn with_bit_errors(mut self, bit_errors: BitErrorConfig) -> Self {
+        self.bit_errors = Some(bit_errors);
+        self
+    }
+
+    /// Compress the payload of the frames sent in each direction according to
+    /// their compressibility, reducing the bytes on the wire
+    #[must_use]
+    pub fn with_compression(mut self, compression: CompressionConfig) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Record the energy used by the link. Each frame is charged as one hop.
+    #[must_use]
+    pub fn with_energy(mut self, energy: EnergyConfig) -> Self {
+        self.energy = Some(energy);
+        self
+    }
+}
+
+/// The components that carry frames in one direction of the link
+struct Direction<T>
+where
+    T: SimObject + Routable + Corruptible + Compressible,
+{
+    queues: Option<Rc<PriorityQueues<T>>>,
+    compressor: Option<Rc<Compressor<T>>>,
+    limiter: Rc<Limiter<T>>,
+    delay: Rc<Delay<T>>,
+    errors: Option<Rc<BitErrors<T>>>,
+    decompressor: Option<Rc<Decompressor<T>>>,
+}
+
+impl<T> Direction<T>
+where
+    T: SimObject + Routable + Corruptible + Compressible,
+{
+    #[expect(clippy::too_many_arguments)]
+    fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        entity: &Rc<Entity>,
+        aka: Option<&Aka>,
+        end: &str,
+        limiter: Rc<RateLimiter<T>>,
+        config: &EthernetLinkConfig,
+        error_config: Option<BitErrorConfig>,
+        energy_meter: Option<&EnergyMeter>,
+    ) -> Result<Self, SimError> {
+        // The rx port is provided by the queues when they are present, then
+        // the compressor
+        let rx_aka = build_aka!(aka, entity, &[(&format!("rx_{end}"), "rx")]);
+        let queues = config
+            .priority_queues
+            .as_ref()
+            .map(|queue_config| {
+                PriorityQueues::new_and_register_with_renames(
+                    engine,
+                    clock,
+                    entity,
+                    &format!("queues_{end}"),
+                    Some(&rx_aka),
+                    queue_config,
+                )
+            })
+            .transpose()?;
+        let compressor = config
+            .compression
+            .map(|compression_config| {
+                Compressor::new_and_register_with_renames(
+                    engine,
+                    clock,
+                    entity,
+                    &format!("compress_{end}"),
+                    queues.is_none().then_some(&rx_aka),
+                    &compression_config,
+                )
+            })
+            .transpose()?;
+        let limiter = Limiter::new_and_register_with_renames(
+            engine,
+            clock,
+            entity,
+            &format!("limit_{end}"),
+            (queues.is_none() && compressor.is_none()).then_some(&rx_aka),
+            limiter,
+        );
+        match (&queues, &compressor) {
+            (Some(queues), Some(compressor)) => {
+                connect_port!(queues, tx => compressor, rx)
+                    .expect("Internal ports should connect without error");
+                connect_port!(compressor, tx => limiter, rx)
+                    .expect("Internal ports should connect without error");
+            }
+            (Some(queues), None) => {
+                connect_port!(queues, tx => limiter, rx)
+                    .expect("Internal ports should connect without error");
+            }
+            (None, Some(compressor)) => {
+                connect_port!(compressor, tx => limiter, rx)
+                    .expect("Internal ports should connect without error");
+            }
+            (None, None) => {}
+        }
+
+        // The tx port is provided by the decompressor when it is present, then
+        // the bit errors
+        let tx_aka = build_aka!(aka, entity, &[(&format!("tx_{end}"), "tx")]);
+        let delay = Delay::new_and_register_with_renames(
+            engine,
+            clock,
+            entity,
+            end,
+            (error_config.is_none() && compressor.is_none()).then_some(&tx_aka),
+            DELAY_TICKS,
+        );
+        // The energy tap is registered with the engine so it does not need to
+        // be kept once connected
+        let energy = energy_meter.map(|meter| {
+            EnergyTap::new_and_register(
+                engine,
+                clock,
+                entity,
+                &format!("energy_{end}"),
+                meter.clone(),
+            )
+        });
+        match &energy {
+            Some(energy) => {
+                connect_port!(limiter, tx => energy, rx)
+                    .expect("Internal ports should connect without error");
+                connect_port!(energy, tx => delay, rx)
+                    .expect("Internal ports should connect without error");
+            }
+            None => {
+                connect_port!(limiter, tx => delay, rx)
+                    .expect("Internal ports should connect without error");
+            }
+        }
+        let errors = error_config
+            .map(|error_config| {
+                BitErrors::new_and_register_with_renames(
+                    engine,
+                    clock,
+                    entity,
+                    &format!("errors_{end}"),
+                    compressor.is_none().then_some(&tx_aka),
+                    &error_config,
+                )
+            })
+            .transpose()?;
+        if let Some(errors) = &errors {
+            connect_port!(delay, tx => errors, rx)
+                .expect("Internal ports should connect without error");
+        }
+        let decompressor = compressor.is_some().then(|| {
+            Decompressor::new_and_register_with_renames(
+                engine,
+                clock,
+                entity,
+                &format!("decompress_{end}"),
+                Some(&tx_aka),
+            )
+        });
+        if let Some(decompressor) = &decompressor {
+            match &errors {
+                Some(errors) => connect_port!(errors, tx => decompressor, rx),
+                None => connect_port!(delay, tx => decompressor, rx),
+            }
+            .expect("Internal ports should connect without error");
+        }
+
+        Ok(Self {
+            queues,
+            compressor,
+            limiter,
+            delay,
+            errors,
+            decompressor,
+        })
+    }
+
+    fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        match (&self.decompressor, &self.errors) {
+            (Some(decompressor), _) => decompressor.connect_port_tx(port_state),
+            (None, Some(errors)) => errors.connect_port_tx(port_state),
+            (None, None) => self.delay.connect_port_tx(port_state),
+        }
+    }
+
+    fn port_rx(&self) -> PortStateResult<T> {
+        match (&self.queues, &self.compressor) {
+            (Some(queues), _) => queues.port_rx(),
+            (None, Some(compressor)) => compressor.port_rx(),
+            (None, None) => self.limiter.port_rx(),
+        }
+    }
+}
+
 #[derive(EntityGet, EntityDisplay, Runnable)]
 pub struct EthernetLink<T>
 where
-    T: SimObject,
+    T: SimObject + Routable + Corruptible + Compressible,
 {
     entity: Rc<Entity>,
-    limiter_a: Rc<Limiter<T>>,
-    delay_a: Rc<Delay<T>>,
-    limiter_b: Rc<Limiter<T>>,
-    delay_b: Rc<Delay<T>>,
+    a: Direction<T>,
+    b: Direction<T>,
+    energy_account: Option<Rc<EnergyAccount>>,
 }
 
 impl<T> EthernetLink<T>
 where
-    T: SimObject,
+    T: SimObject + Routable + Corruptible + Compressible,
 {
-    pub fn new_and_register_with_renames(
+    fn build(
         engine: &Engine,
         clock: &Clock,
         parent: &Rc<Entity>,
         name: &str,
         aka: Option<&Aka>,
+        config: &EthernetLinkConfig,
     ) -> Result<Rc<Self>, SimError> {
         let entity = Rc::new(Entity::new(parent, name));
         let limiter = rc_limiter!(clock, BITS_PER_TICK);
-        let limiter_a_aka = build_aka!(aka, &entity, &[("rx_a", "rx")]);
-        let limiter_a = Limiter::new_and_register_with_renames(
-            engine,
-            clock,
-            &entity,
-            "limit_a",
-            Some(&limiter_a_aka),
-            limiter.clone(),
-        );
-        let delay_a_aka = build_aka!(aka, &entity, &[("tx_a", "tx")]);
-        let delay_a = Delay::new_and_register_with_renames(
+        let energy_meter = config.energy.map(|energy| {
+            let account = engine
+                .power()
+                .create_account(&entity, energy.idle_power_mw());
+            EnergyMeter::new(&account, energy)
+        });
+        let a = Direction::new_and_register(
             engine,
             clock,
             &entity,
+            aka,
             "a",
-            Some(&delay_a_aka),
-            DELAY_TICKS,
-        );
-        connect_port!(limiter_a, tx => delay_a, rx)
-            .expect("Internal ports should connect without error");
-
-        let limiter_b_aka = build_aka!(aka, &entity, &[("rx_b", "rx")]);
-        let limiter_b: Rc<Limiter<_>> = Limiter::new_and_register_with_renames(
-            engine,
-            clock,
-            &entity,
-            "limit_b",
-            Some(&limiter_b_aka),
             limiter.clone(),
-        );
-        let delay_b_aka = build_aka!(aka, &entity, &[("tx_b", "tx")]);
-        let delay_b = Delay::new_and_register_with_renames(
+            config,
+            config.bit_errors,
+            energy_meter.as_ref(),
+        )?;
+        let b = Direction::new_and_register(
             engine,
             clock,
             &entity,
+            aka,
             "b",
-            Some(&delay_b_aka),
-            DELAY_TICKS,
-        );
-        connect_port!(limiter_b, tx => delay_b, rx)
-            .expect("Internal ports should connect without error");
+            limiter,
+            config,
+            config
+                .bit_errors
+                .map(|errors| errors.with_seed(errors.seed().wrapping_add(1))),
+            energy_meter.as_ref(),
+        )?;
 
         let rc_self = Rc::new(Self {
-            entity: entity.clone(),
-            limiter_a,
-            delay_a,
-            limiter_b,
-            delay_b,
+            entity,
+            a,
+            b,
+            energy_account: energy_meter.map(|meter| meter.account().clone()),
         });
         engine.register(rc_self.clone());
         Ok(rc_self)
     }
 
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::build(engine, clock, parent, name, aka, &EthernetLinkConfig::new())
+    }
+
     pub fn new_and_register(
         engine: &Engine,
         clock: &Clock,
@@ -117,26 +358,102 @@ where
         Self::new_and_register_with_renames(engine, clock, parent, name, None)
     }
 
+    /// Create a link with the optional features enabled in `config`.
+    ///
+    /// Returns a `SimError` if the configuration is invalid.
+    pub fn new_and_register_with_config(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: &EthernetLinkConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::build(engine, clock, parent, name, None, config)
+    }
+
     /// Change the delay value. Can only be done before the simulation has
     /// started.
     pub fn set_delay(&self, delay: usize) -> SimResult {
-        self.delay_a.set_delay(delay)?;
-        self.delay_b.set_delay(delay)
+        self.a.delay.set_delay(delay)?;
+        self.b.delay.set_delay(delay)
+    }
+
+    /// Number of frames corrupted by bit errors in both directions
+    #[must_use]
+    pub fn num_corrupted(&self) -> usize {
+        [&self.a, &self.b]
+            .iter()
+            .filter_map(|direction| direction.errors.as_ref())
+            .map(|errors| errors.num_corrupted())
+            .sum()
+    }
+
+    /// Number of frames dropped due to FCS errors in both directions
+    #[must_use]
+    pub fn num_dropped(&self) -> usize {
+        [&self.a, &self.b]
+            .iter()
+            .filter_map(|direction| direction.errors.as_ref())
+            .map(|errors| errors.num_dropped())
+            .sum()
+    }
+
+    /// Returns the account recording the energy of the link when it has an
+    /// energy model
+    #[must_use]
+    pub fn energy_account(&self) -> Option<&Rc<EnergyAccount>> {
+        self.energy_account.as_ref()
+    }
+
+    /// Total bytes of the frames before compression in both directions
+    #[must_use]
+    pub fn uncompressed_bytes(&self) -> usize {
+        [&self.a, &self.b]
+            .iter()
+            .filter_map(|direction| direction.compressor.as_ref())
+            .map(|compressor| compressor.uncompressed_bytes())
+            .sum()
+    }
+
+    /// Total bytes of the compressed frames sent on the wire in both
+    /// directions
+    #[must_use]
+    pub fn compressed_bytes(&self) -> usize {
+        [&self.a, &self.b]
+            .iter()
+            .filter_map(|direction| direction.compressor.as_ref())
+            .map(|compressor| compressor.compressed_bytes())
+            .sum()
+    }
+
+    pub fn dump_stats(&self) {
+        for errors in [&self.a, &self.b]
+            .iter()
+            .filter_map(|direction| direction.errors.as_ref())
+        {
+            errors.dump_stats();
+        }
+        for compressor in [&self.a, &self.b]
+            .iter()
+            .filter_map(|direction| direction.compressor.as_ref())
+        {
+            compressor.dump_stats();
+        }
     }
 
     pub fn connect_port_tx_a(&self, port_state: PortStateResult<T>) -> SimResult {
-        self.delay_a.connect_port_tx(port_state)
+        self.a.connect_port_tx(port_state)
     }
 
     pub fn connect_port_tx_b(&self, port_state: PortStateResult<T>) -> SimResult {
-        self.delay_b.connect_port_tx(port_state)
+        self.b.connect_port_tx(port_state)
     }
 
     pub fn port_rx_a(&self) -> PortStateResult<T> {
-        self.limiter_a.port_rx()
+        self.a.port_rx()
     }
 
     pub fn port_rx_b(&self) -> PortStateResult<T> {
-        self.limiter_b.port_rx()
+        self.b.port_rx()
     }
 }
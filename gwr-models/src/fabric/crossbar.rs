@@ -0,0 +1,267 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A single-stage crossbar implementation of a fabric.
+//!
+//! Every ingress port is connected directly to every egress port, so the only
+//! contention is between frames heading to the same egress port. Each egress
+//! port has an arbiter which chooses between the ingress ports and every frame
+//! takes a fixed serialization latency to cross the crossbar, regardless of
+//! where its ports are in the [FabricConfig] grid.
+//!
+//! The port indices are the same as those of the mesh fabrics, so a crossbar
+//! can be used as an ideal baseline against which the results of a
+//! [RoutedFabric](crate::fabric::routed::RoutedFabric) can be normalized.
+//!
+//! ```txt
+//!           +---------------------------------------------------------+
+//!           |                       CROSSBAR                          |
+//!  INGRESS -> LIMIT -> BUF -> ROUTER -> ARBITER -> DELAY -> LIMIT -> BUF -> EGRESS
+//!           |                       \  /                              |
+//!           |                        \/  (every router connects to    |
+//!           |                        /\   every arbiter)              |
+//!  INGRESS -> LIMIT -> BUF -> ROUTER -> ARBITER -> DELAY -> LIMIT -> BUF -> EGRESS
+//!           +---------------------------------------------------------+
+//! ```
+
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::arbiter::policy::{RoundRobin, TrafficClassRoundRobin};
+use gwr_components::arbiter::{Arbiter, Arbitrate};
+use gwr_components::delay::Delay;
+use gwr_components::flow_controls::limiter::Limiter;
+use gwr_components::router::{Route, Router};
+use gwr_components::store::{ByteStore, Store};
+use gwr_components::{connect_port, rc_limiter};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::PortStateResult;
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Routable, SimObject};
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet, Runnable};
+use gwr_track::build_aka;
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+
+use crate::fabric::{Fabric, FabricConfig};
+
+/// Returns the position of a fabric port within the populated ports
+fn port_position(port_indices: &[usize], fabric_port: usize) -> Result<usize, SimError> {
+    match port_indices.binary_search(&fabric_port) {
+        Ok(position) => Ok(position),
+        Err(_) => sim_error!("Fabric port {fabric_port} is not populated"),
+    }
+}
+
+struct CrossbarRouter {
+    config: Rc<FabricConfig>,
+}
+
+impl<T> Route<T> for CrossbarRouter
+where
+    T: SimObject + Routable,
+{
+    /// Route directly to the arbiter of the destination port
+    fn route(&self, object: &T) -> Result<usize, SimError> {
+        port_position(self.config.port_indices(), object.destination() as usize)
+    }
+}
+
+#[derive(EntityGet, EntityDisplay, Runnable)]
+pub struct CrossbarFabric<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+
+    ingress_buffer_limiters: Vec<Rc<Limiter<T>>>,
+    egress_buffers: Vec<Rc<Store<T>>>,
+
+    config: Rc<FabricConfig>,
+}
+
+impl<T> CrossbarFabric<T>
+where
+    T: SimObject + Routable,
+{
+    /// Create and register a new crossbar fabric in which every frame takes
+    /// `latency_ticks` to cross from its ingress to its egress port.
+    ///
+    /// The total number of ingress/egress ports must be at least two, otherwise
+    /// there are no valid routes and an error will be returned.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: Rc<FabricConfig>,
+        latency_ticks: usize,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let num_ports = config.num_ports();
+        if num_ports < 2 {
+            return sim_error!("Cannot create fabric with less than 2 ports");
+        }
+        let traffic_classes = config
+            .traffic_classes()
+            .map(|classes| TrafficClassRoundRobin::new(classes.clone()))
+            .transpose()?;
+
+        let arbiters: Vec<_> = config
+            .port_indices()
+            .iter()
+            .map(|i| {
+                let policy: Box<dyn Arbitrate<T>> = match &traffic_classes {
+                    Some(policy) => Box::new(policy.clone()),
+                    None => Box::new(RoundRobin::new()),
+                };
+                Arbiter::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("arb_{i}"),
+                    num_ports,
+                    policy,
+                )
+            })
+            .collect();
+        let routers: Vec<_> = config
+            .port_indices()
+            .iter()
+            .map(|i| {
+                let algorithm = Box::new(CrossbarRouter {
+                    config: config.clone(),
+                });
+                Router::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("router_{i}"),
+                    num_ports,
+                    algorithm,
+                )
+            })
+            .collect();
+
+        for (from, router) in routers.iter().enumerate() {
+            for (to, arbiter) in arbiters.iter().enumerate() {
+                connect_port!(router, tx, to => arbiter, rx, from)
+                    .expect("Internal ports should connect without error");
+            }
+        }
+
+        let port_limiter = rc_limiter!(clock, config.port_bits_per_tick());
+        let mut ingress_buffer_limiters = Vec::with_capacity(num_ports);
+        let mut egress_buffers = Vec::with_capacity(num_ports);
+        for (position, i) in config.port_indices().iter().enumerate() {
+            let ingress_buffer_limiter_aka =
+                build_aka!(aka, &entity, &[(&format!("ingress_{i}"), "rx")]);
+            let ingress_buffer_limiter = Limiter::new_and_register_with_renames(
+                engine,
+                clock,
+                &entity,
+                &format!("limit_ingress_{i}"),
+                Some(&ingress_buffer_limiter_aka),
+                port_limiter.clone(),
+            );
+            let ingress_buffer = ByteStore::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("ingress_buf_{i}"),
+                config.rx_buffer_bytes,
+            )?;
+            connect_port!(ingress_buffer_limiter, tx => ingress_buffer, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(ingress_buffer, tx => routers[position], rx)
+                .expect("Internal ports should connect without error");
+            ingress_buffer_limiters.push(ingress_buffer_limiter);
+
+            let delay = Delay::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("delay_{i}"),
+                latency_ticks,
+            );
+            let egress_buffer_limiter = Limiter::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("limit_egress_{i}"),
+                port_limiter.clone(),
+            );
+            let egress_buffer_aka = build_aka!(aka, &entity, &[(&format!("egress_{i}"), "tx")]);
+            let egress_buffer = ByteStore::new_and_register_with_renames(
+                engine,
+                clock,
+                &entity,
+                &format!("egress_buf_{i}"),
+                Some(&egress_buffer_aka),
+                config.tx_buffer_bytes,
+            )?;
+            connect_port!(arbiters[position], tx => delay, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(delay, tx => egress_buffer_limiter, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(egress_buffer_limiter, tx => egress_buffer, rx)
+                .expect("Internal ports should connect without error");
+            egress_buffers.push(egress_buffer);
+        }
+
+        let rc_self = Rc::new(Self {
+            entity,
+            ingress_buffer_limiters,
+            egress_buffers,
+            config,
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Create and register a new crossbar fabric in which every frame takes
+    /// `latency_ticks` to cross from its ingress to its egress port.
+    ///
+    /// The total number of ingress/egress ports must be at least two, otherwise
+    /// there are no valid routes and an error will be returned.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: Rc<FabricConfig>,
+        latency_ticks: usize,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(
+            engine,
+            clock,
+            parent,
+            name,
+            None,
+            config,
+            latency_ticks,
+        )
+    }
+}
+
+impl<T> Fabric<T> for CrossbarFabric<T>
+where
+    T: SimObject + Routable,
+{
+    fn connect_port_egress_i(&self, i: usize, port_state: PortStateResult<T>) -> SimResult {
+        let position = port_position(self.config.port_indices(), i)?;
+        self.egress_buffers[position].connect_port_tx(port_state)
+    }
+
+    fn port_ingress_i(&self, i: usize) -> PortStateResult<T> {
+        let position = port_position(self.config.port_indices(), i)?;
+        self.ingress_buffer_limiters[position].port_rx()
+    }
+
+    fn col_row_port_to_fabric_port_index(&self, col: usize, row: usize, port: usize) -> usize {
+        self.config
+            .col_row_port_to_fabric_port_index(col, row, port)
+    }
+}
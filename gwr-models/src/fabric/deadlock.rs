@@ -0,0 +1,166 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Check whether a fabric routing algorithm is deadlock free.
+//!
+//! Each link between two nodes of a
+//! [RoutedFabric](crate::fabric::routed::RoutedFabric) is a channel. A channel depends on another channel when the routing
+//! algorithm permits a frame to hold the first while waiting for the second.
+//! Without virtual channels a routing configuration is only deadlock free
+//! when these dependencies contain no cycle.
+//!
+//! For example, [Adaptive](FabricRoutingAlgorithm::Adaptive) routing permits
+//! every turn and so always contains a cycle in a fabric of at least 2x2
+//! nodes, while the turn-model algorithms prohibit enough turns to break all
+//! cycles in a mesh. The wrap-around links of a torus form a cycle with any
+//! of the algorithms.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimResult;
+
+use crate::fabric::FabricConfig;
+use crate::fabric::node::{FabricRoutingAlgorithm, Port};
+
+/// The link leaving a node in a given direction
+type Channel = (usize, usize, usize);
+
+fn neighbour(config: &FabricConfig, (col, row, port): Channel) -> (usize, usize) {
+    let num_columns = config.num_columns();
+    let num_rows = config.num_rows();
+    match Port::DIRECTIONS[port] {
+        Port::ColMinus => ((col + num_columns - 1) % num_columns, row),
+        Port::ColPlus => ((col + 1) % num_columns, row),
+        Port::RowMinus => (col, (row + num_rows - 1) % num_rows),
+        Port::RowPlus => (col, (row + 1) % num_rows),
+        Port::Ingress => unreachable!("Ingress is not a direction"),
+    }
+}
+
+fn permitted_channels(
+    config: &FabricConfig,
+    algorithm: FabricRoutingAlgorithm,
+    node: (usize, usize),
+    dest: (usize, usize),
+    at_ingress: bool,
+) -> impl Iterator<Item = Channel> {
+    let (col_port, row_port) = algorithm.permitted_ports(config, node, dest, at_ingress);
+    col_port
+        .into_iter()
+        .chain(row_port)
+        .map(move |port| (node.0, node.1, port))
+}
+
+/// Build the channel dependencies of every route the algorithm permits
+fn channel_dependencies(
+    config: &FabricConfig,
+    algorithm: FabricRoutingAlgorithm,
+) -> BTreeMap<Channel, BTreeSet<Channel>> {
+    let nodes: Vec<(usize, usize)> = (0..config.num_rows())
+        .flat_map(|row| (0..config.num_columns()).map(move |col| (col, row)))
+        .collect();
+
+    let mut dependencies: BTreeMap<Channel, BTreeSet<Channel>> = BTreeMap::new();
+    for &dest in &nodes {
+        let mut visited = BTreeSet::new();
+        let mut to_visit: Vec<Channel> = nodes
+            .iter()
+            .filter(|&&node| node != dest)
+            .flat_map(|&node| permitted_channels(config, algorithm, node, dest, true))
+            .filter(|&channel| visited.insert(channel))
+            .collect();
+
+        while let Some(channel) = to_visit.pop() {
+            let node = neighbour(config, channel);
+            if node == dest {
+                continue;
+            }
+            for next in permitted_channels(config, algorithm, node, dest, false) {
+                dependencies.entry(channel).or_default().insert(next);
+                if visited.insert(next) {
+                    to_visit.push(next);
+                }
+            }
+        }
+    }
+    dependencies
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+fn find_cycle(dependencies: &BTreeMap<Channel, BTreeSet<Channel>>) -> Option<Vec<Channel>> {
+    let mut visits = HashMap::new();
+    for &start in dependencies.keys() {
+        if visits.contains_key(&start) {
+            continue;
+        }
+
+        // Depth-first search keeping the path and the next successor to try
+        visits.insert(start, Visit::InProgress);
+        let mut path = vec![(start, 0)];
+        while let Some(&(channel, next)) = path.last() {
+            let successor = dependencies
+                .get(&channel)
+                .and_then(|successors| successors.iter().nth(next));
+            let Some(&successor) = successor else {
+                visits.insert(channel, Visit::Done);
+                path.pop();
+                continue;
+            };
+
+            path.last_mut().unwrap().1 += 1;
+            match visits.get(&successor) {
+                Some(Visit::InProgress) => {
+                    let cycle_start = path.iter().position(|&(c, _)| c == successor).unwrap();
+                    return Some(path[cycle_start..].iter().map(|&(c, _)| c).collect());
+                }
+                Some(Visit::Done) => {}
+                None => {
+                    visits.insert(successor, Visit::InProgress);
+                    path.push((successor, 0));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns a cycle of channel dependencies permitted by routing `algorithm`
+/// in the fabric, if there is one.
+///
+/// Each channel is given as the column and row of the node it leaves and the
+/// direction it leaves in.
+#[must_use]
+pub fn dependency_cycle(
+    config: &FabricConfig,
+    algorithm: FabricRoutingAlgorithm,
+) -> Option<Vec<(usize, usize, Port)>> {
+    find_cycle(&channel_dependencies(config, algorithm)).map(|cycle| {
+        cycle
+            .into_iter()
+            .map(|(col, row, port)| (col, row, Port::DIRECTIONS[port]))
+            .collect()
+    })
+}
+
+/// Returns a `SimError` describing a cycle of channel dependencies if routing
+/// `algorithm` in the fabric could deadlock.
+pub fn check_deadlock_free(config: &FabricConfig, algorithm: FabricRoutingAlgorithm) -> SimResult {
+    match dependency_cycle(config, algorithm) {
+        None => Ok(()),
+        Some(cycle) => {
+            let cycle: Vec<String> = cycle
+                .iter()
+                .map(|(col, row, port)| format!("{col},{row} {port}"))
+                .collect();
+            sim_error!(
+                "{algorithm:?} routing permits a cyclic channel dependency: {}",
+                cycle.join(" -> ")
+            )
+        }
+    }
+}
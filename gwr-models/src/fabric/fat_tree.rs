@@ -0,0 +1,640 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A two-stage fat-tree (folded Clos) implementation of a fabric.
+//!
+//! Every node of the [FabricConfig] grid becomes a leaf switch which hosts
+//! the ingress/egress ports of that node. Each leaf has one uplink to every
+//! spine switch, so traffic between two leaves always takes two hops (leaf to
+//! spine and spine to leaf) regardless of where the leaves are. Traffic
+//! between two ports of the same leaf does not leave the leaf.
+//!
+//! The port indices are the same as those of the mesh fabrics, so a fat-tree
+//! can be swapped in for a [RoutedFabric](crate::fabric::routed::RoutedFabric)
+//! to compare the two.
+//!
+//! # Oversubscription
+//!
+//! The bandwidth of each leaf/spine link is set so that the total uplink
+//! bandwidth of a leaf is the total bandwidth of its ports divided by the
+//! configured oversubscription. An oversubscription of 1 gives a non-blocking
+//! fabric.
+//!
+//! # Routing
+//!
+//! The spine used is chosen by the destination port index modulo the number
+//! of spines. This spreads traffic over the spines while keeping all frames
+//! to one destination in order.
+//!
+//! ```txt
+//!                 +-------------+          +-------------+
+//!                 |   SPINE 0   |   ....   |  SPINE S-1  |
+//!                 +-------------+          +-------------+
+//!                   |    ...    \         /     ...    |
+//!                 DELAY         DELAY  DELAY         DELAY
+//!                   |              \   /               |
+//!           +---------------+         +---------------+
+//!           |    LEAF 0     |   ....  |   LEAF L-1    |
+//!           +---------------+         +---------------+
+//!             |  ingress/egress ports    |
+//! ```
+
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::arbiter::Arbiter;
+use gwr_components::arbiter::policy::RoundRobin;
+use gwr_components::delay::Delay;
+use gwr_components::flow_controls::limiter::Limiter;
+use gwr_components::router::{Route, Router};
+use gwr_components::store::{ByteStore, Store};
+use gwr_components::{connect_port, rc_limiter};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::PortStateResult;
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Routable, SimObject};
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet, Runnable};
+use gwr_track::build_aka;
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+
+use crate::fabric::routed::build_node_aka;
+use crate::fabric::{Fabric, FabricConfig};
+
+/// Configuration of the spine stage of a fat-tree fabric
+pub struct FatTreeConfig {
+    /// Number of spine switches
+    num_spines: usize,
+
+    /// Ratio of the port bandwidth of a leaf to its uplink bandwidth
+    oversubscription: usize,
+}
+
+impl FatTreeConfig {
+    #[must_use]
+    pub fn new(num_spines: usize) -> Self {
+        Self {
+            num_spines,
+            oversubscription: 1,
+        }
+    }
+
+    /// Set the ratio of the port bandwidth of a leaf to its uplink bandwidth
+    #[must_use]
+    pub fn with_oversubscription(mut self, oversubscription: usize) -> Self {
+        self.oversubscription = oversubscription;
+        self
+    }
+
+    #[must_use]
+    pub fn num_spines(&self) -> usize {
+        self.num_spines
+    }
+
+    #[must_use]
+    pub fn oversubscription(&self) -> usize {
+        self.oversubscription
+    }
+
+    /// Returns the bandwidth (in bits per tick) of each leaf/spine link
+    #[must_use]
+    pub fn link_bits_per_tick(&self, config: &FabricConfig) -> usize {
+        let leaf_bits_per_tick = config.num_ports_per_node() * config.port_bits_per_tick();
+        leaf_bits_per_tick
+            .div_ceil(self.num_spines * self.oversubscription)
+            .max(1)
+    }
+
+    /// Returns the spine used to reach a destination port
+    #[must_use]
+    pub fn spine_for(&self, dest_fabric_port: usize) -> usize {
+        dest_fabric_port % self.num_spines
+    }
+}
+
+/// Returns the index of the leaf that hosts a node of the fabric grid
+fn leaf_index(config: &FabricConfig, col: usize, row: usize) -> usize {
+    col * config.num_rows() + row
+}
+
+struct LeafRouter {
+    leaf: usize,
+    num_ports: usize,
+
+    /// Number of spines that can be routed to. This is zero for routers that
+    /// receive frames from the spines.
+    num_spines: usize,
+
+    config: Rc<FabricConfig>,
+    fat_tree_config: Rc<FatTreeConfig>,
+}
+
+impl<T> Route<T> for LeafRouter
+where
+    T: SimObject + Routable,
+{
+    /// Route to the local egress port `p` at index `p` or to spine `s` at
+    /// index `num_ports + s`.
+    fn route(&self, object: &T) -> Result<usize, SimError> {
+        let dest_fabric_port = object.destination() as usize;
+        let (dest_col, dest_row, dest_port) = self
+            .config
+            .fabric_port_index_to_col_row_port(dest_fabric_port);
+
+        if leaf_index(&self.config, dest_col, dest_row) == self.leaf {
+            if dest_port >= self.num_ports {
+                return sim_error!("Fabric port {dest_fabric_port} is not populated");
+            }
+            Ok(dest_port)
+        } else if self.num_spines == 0 {
+            sim_error!("Frame for fabric port {dest_fabric_port} sent to wrong leaf")
+        } else {
+            Ok(self.num_ports + self.fat_tree_config.spine_for(dest_fabric_port))
+        }
+    }
+}
+
+struct SpineRouter {
+    config: Rc<FabricConfig>,
+}
+
+impl<T> Route<T> for SpineRouter
+where
+    T: SimObject + Routable,
+{
+    /// Route to the leaf that hosts the destination port
+    fn route(&self, object: &T) -> Result<usize, SimError> {
+        let (dest_col, dest_row, _) = self
+            .config
+            .fabric_port_index_to_col_row_port(object.destination() as usize);
+        Ok(leaf_index(&self.config, dest_col, dest_row))
+    }
+}
+
+/// A leaf switch of a fat-tree fabric.
+///
+/// Frames from the ingress ports are routed either to a local egress port or
+/// to one of the uplinks. Frames from the downlinks are always routed to a
+/// local egress port.
+#[derive(EntityGet, EntityDisplay, Runnable)]
+pub struct FatTreeLeaf<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+
+    downlink_routers: Vec<Rc<Router<T>>>,
+
+    ingress_buffer_limiters: Vec<Rc<Limiter<T>>>,
+    egress_buffers: Vec<Rc<Store<T>>>,
+    uplink_limiters: Vec<Rc<Limiter<T>>>,
+}
+
+impl<T> FatTreeLeaf<T>
+where
+    T: SimObject + Routable,
+{
+    #[expect(clippy::too_many_arguments)]
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        node_col: usize,
+        node_row: usize,
+        config: &Rc<FabricConfig>,
+        fat_tree_config: &Rc<FatTreeConfig>,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let leaf = leaf_index(config, node_col, node_row);
+        let num_ports = config.node_num_ingress_egress_ports(node_col, node_row);
+        let num_spines = fat_tree_config.num_spines();
+
+        let egress_arbiters: Vec<_> = (0..num_ports)
+            .map(|i| {
+                Arbiter::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("arb_egress_{i}"),
+                    num_ports + num_spines,
+                    Box::new(RoundRobin::new()),
+                )
+            })
+            .collect();
+        let uplink_arbiters: Vec<_> = (0..num_spines)
+            .map(|s| {
+                Arbiter::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("arb_uplink_{s}"),
+                    num_ports,
+                    Box::new(RoundRobin::new()),
+                )
+            })
+            .collect();
+        let ingress_routers: Vec<_> = (0..num_ports)
+            .map(|i| {
+                let algorithm = Box::new(LeafRouter {
+                    leaf,
+                    num_ports,
+                    num_spines,
+                    config: config.clone(),
+                    fat_tree_config: fat_tree_config.clone(),
+                });
+                Router::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("router_ingress_{i}"),
+                    num_ports + num_spines,
+                    algorithm,
+                )
+            })
+            .collect();
+        let downlink_routers: Vec<_> = (0..num_spines)
+            .map(|s| {
+                let algorithm = Box::new(LeafRouter {
+                    leaf,
+                    num_ports,
+                    num_spines: 0,
+                    config: config.clone(),
+                    fat_tree_config: fat_tree_config.clone(),
+                });
+                Router::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("router_downlink_{s}"),
+                    num_ports,
+                    algorithm,
+                )
+            })
+            .collect();
+
+        // Ingress routers connect to every egress and uplink arbiter
+        for (i, router) in ingress_routers.iter().enumerate() {
+            for (e, arbiter) in egress_arbiters.iter().enumerate() {
+                connect_port!(router, tx, e => arbiter, rx, i)
+                    .expect("Internal ports should connect without error");
+            }
+            for (s, arbiter) in uplink_arbiters.iter().enumerate() {
+                connect_port!(router, tx, num_ports + s => arbiter, rx, i)
+                    .expect("Internal ports should connect without error");
+            }
+        }
+
+        // Downlink routers connect to every egress arbiter
+        for (s, router) in downlink_routers.iter().enumerate() {
+            for (e, arbiter) in egress_arbiters.iter().enumerate() {
+                connect_port!(router, tx, e => arbiter, rx, num_ports + s)
+                    .expect("Internal ports should connect without error");
+            }
+        }
+
+        let port_limiter = rc_limiter!(clock, config.port_bits_per_tick());
+        let mut ingress_buffer_limiters = Vec::with_capacity(num_ports);
+        let mut egress_buffers = Vec::with_capacity(num_ports);
+        for i in 0..num_ports {
+            let ingress_buffer_limiter_aka =
+                build_aka!(aka, &entity, &[(&format!("ingress_{i}"), "rx")]);
+            let ingress_buffer_limiter = Limiter::new_and_register_with_renames(
+                engine,
+                clock,
+                &entity,
+                &format!("limit_ingress_{i}"),
+                Some(&ingress_buffer_limiter_aka),
+                port_limiter.clone(),
+            );
+            let ingress_buffer = ByteStore::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("ingress_buf_{i}"),
+                config.rx_buffer_bytes,
+            )?;
+            connect_port!(ingress_buffer_limiter, tx => ingress_buffer, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(ingress_buffer, tx => ingress_routers[i], rx)
+                .expect("Internal ports should connect without error");
+            ingress_buffer_limiters.push(ingress_buffer_limiter);
+
+            let egress_buffer_limiter = Limiter::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("limit_egress_{i}"),
+                port_limiter.clone(),
+            );
+            let egress_buffer_aka = build_aka!(aka, &entity, &[(&format!("egress_{i}"), "tx")]);
+            let egress_buffer = ByteStore::new_and_register_with_renames(
+                engine,
+                clock,
+                &entity,
+                &format!("egress_buf_{i}"),
+                Some(&egress_buffer_aka),
+                config.tx_buffer_bytes,
+            )?;
+            connect_port!(egress_arbiters[i], tx => egress_buffer_limiter, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(egress_buffer_limiter, tx => egress_buffer, rx)
+                .expect("Internal ports should connect without error");
+            egress_buffers.push(egress_buffer);
+        }
+
+        let link_limiter = rc_limiter!(clock, fat_tree_config.link_bits_per_tick(config));
+        let mut uplink_limiters = Vec::with_capacity(num_spines);
+        for (s, arbiter) in uplink_arbiters.iter().enumerate() {
+            let uplink_limiter = Limiter::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("limit_uplink_{s}"),
+                link_limiter.clone(),
+            );
+            connect_port!(arbiter, tx => uplink_limiter, rx)
+                .expect("Internal ports should connect without error");
+            uplink_limiters.push(uplink_limiter);
+        }
+
+        let rc_self = Rc::new(Self {
+            entity,
+            downlink_routers,
+            ingress_buffer_limiters,
+            egress_buffers,
+            uplink_limiters,
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    pub fn connect_port_egress_i(&self, i: usize, port_state: PortStateResult<T>) -> SimResult {
+        self.egress_buffers[i].connect_port_tx(port_state)
+    }
+    pub fn port_ingress_i(&self, i: usize) -> PortStateResult<T> {
+        self.ingress_buffer_limiters[i].port_rx()
+    }
+
+    pub fn connect_port_uplink_i(&self, i: usize, port_state: PortStateResult<T>) -> SimResult {
+        self.uplink_limiters[i].connect_port_tx(port_state)
+    }
+    pub fn port_downlink_i(&self, i: usize) -> PortStateResult<T> {
+        self.downlink_routers[i].port_rx()
+    }
+}
+
+/// A spine switch of a fat-tree fabric which routes frames between leaves
+#[derive(EntityGet, EntityDisplay, Runnable)]
+pub struct FatTreeSpine<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+
+    routers: Vec<Rc<Router<T>>>,
+    downlink_limiters: Vec<Rc<Limiter<T>>>,
+}
+
+impl<T> FatTreeSpine<T>
+where
+    T: SimObject + Routable,
+{
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: &Rc<FabricConfig>,
+        fat_tree_config: &FatTreeConfig,
+    ) -> Rc<Self> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let num_leaves = config.num_columns() * config.num_rows();
+
+        let link_limiter = rc_limiter!(clock, fat_tree_config.link_bits_per_tick(config));
+        let mut arbiters = Vec::with_capacity(num_leaves);
+        let mut routers = Vec::with_capacity(num_leaves);
+        let mut downlink_limiters = Vec::with_capacity(num_leaves);
+        for l in 0..num_leaves {
+            let arbiter = Arbiter::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("arb_{l}"),
+                num_leaves,
+                Box::new(RoundRobin::new()),
+            );
+            let downlink_limiter = Limiter::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("limit_downlink_{l}"),
+                link_limiter.clone(),
+            );
+            connect_port!(arbiter, tx => downlink_limiter, rx)
+                .expect("Internal ports should connect without error");
+            arbiters.push(arbiter);
+            downlink_limiters.push(downlink_limiter);
+
+            let algorithm = Box::new(SpineRouter {
+                config: config.clone(),
+            });
+            routers.push(Router::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("router_{l}"),
+                num_leaves,
+                algorithm,
+            ));
+        }
+
+        for (from, router) in routers.iter().enumerate() {
+            for (to, arbiter) in arbiters.iter().enumerate() {
+                connect_port!(router, tx, to => arbiter, rx, from)
+                    .expect("Internal ports should connect without error");
+            }
+        }
+
+        let rc_self = Rc::new(Self {
+            entity,
+            routers,
+            downlink_limiters,
+        });
+        engine.register(rc_self.clone());
+        rc_self
+    }
+
+    pub fn connect_port_downlink_i(&self, i: usize, port_state: PortStateResult<T>) -> SimResult {
+        self.downlink_limiters[i].connect_port_tx(port_state)
+    }
+    pub fn port_uplink_i(&self, i: usize) -> PortStateResult<T> {
+        self.routers[i].port_rx()
+    }
+}
+
+#[derive(EntityGet, EntityDisplay, Runnable)]
+pub struct FatTreeFabric<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+    leaves: Vec<Rc<FatTreeLeaf<T>>>,
+    config: Rc<FabricConfig>,
+}
+
+impl<T> FatTreeFabric<T>
+where
+    T: SimObject + Routable,
+{
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: Rc<FabricConfig>,
+        fat_tree_config: FatTreeConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        if config.max_num_ports() < 2 {
+            return sim_error!("Cannot create fabric with less than 2 ports");
+        }
+        if fat_tree_config.num_spines() == 0 {
+            return sim_error!("Cannot create fat-tree fabric with no spines");
+        }
+        if fat_tree_config.oversubscription() == 0 {
+            return sim_error!("Cannot create fat-tree fabric with an oversubscription of 0");
+        }
+        let fat_tree_config = Rc::new(fat_tree_config);
+
+        let mut leaves = Vec::with_capacity(config.num_columns() * config.num_rows());
+        for c in 0..config.num_columns() {
+            for r in 0..config.num_rows() {
+                let mut new_aka = Aka::default();
+                build_node_aka(&entity, aka, &mut new_aka, c, r, &config);
+                leaves.push(FatTreeLeaf::new_and_register_with_renames(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("leaf_{c}_{r}"),
+                    Some(&new_aka),
+                    c,
+                    r,
+                    &config,
+                    &fat_tree_config,
+                )?);
+            }
+        }
+
+        let spines: Vec<_> = (0..fat_tree_config.num_spines())
+            .map(|s| {
+                FatTreeSpine::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("spine_{s}"),
+                    &config,
+                    &fat_tree_config,
+                )
+            })
+            .collect();
+
+        for (l, leaf) in leaves.iter().enumerate() {
+            for (s, spine) in spines.iter().enumerate() {
+                let delay = Delay::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("leaf_{l}_to_spine_{s}"),
+                    config.cycles_per_hop(),
+                );
+                connect_port!(leaf, uplink, s => delay, rx)
+                    .expect("Internal ports should connect without error");
+                connect_port!(delay, tx => spine, uplink, l)
+                    .expect("Internal ports should connect without error");
+
+                let delay = Delay::new_and_register(
+                    engine,
+                    clock,
+                    &entity,
+                    &format!("spine_{s}_to_leaf_{l}"),
+                    config.cycles_per_hop(),
+                );
+                connect_port!(spine, downlink, l => delay, rx)
+                    .expect("Internal ports should connect without error");
+                connect_port!(delay, tx => leaf, downlink, s)
+                    .expect("Internal ports should connect without error");
+            }
+        }
+
+        let rc_self = Rc::new(Self {
+            entity,
+            leaves,
+            config,
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: Rc<FabricConfig>,
+        fat_tree_config: FatTreeConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(
+            engine,
+            clock,
+            parent,
+            name,
+            None,
+            config,
+            fat_tree_config,
+        )
+    }
+}
+
+impl<T> Fabric<T> for FatTreeFabric<T>
+where
+    T: SimObject + Routable,
+{
+    fn connect_port_egress_i(&self, i: usize, port_state: PortStateResult<T>) -> SimResult {
+        let (c, r, p) = self.config.fabric_port_index_to_col_row_port(i);
+        self.leaves[leaf_index(&self.config, c, r)].connect_port_egress_i(p, port_state)
+    }
+
+    fn port_ingress_i(&self, i: usize) -> PortStateResult<T> {
+        let (c, r, p) = self.config.fabric_port_index_to_col_row_port(i);
+        self.leaves[leaf_index(&self.config, c, r)].port_ingress_i(p)
+    }
+
+    fn col_row_port_to_fabric_port_index(&self, col: usize, row: usize, port: usize) -> usize {
+        self.config
+            .col_row_port_to_fabric_port_index(col, row, port)
+    }
+}
+
+#[test]
+fn link_bandwidth() {
+    let config = FabricConfig::new(2, 2, 4, None, 1, 1, 1, 1, 32);
+
+    // Four 32-bit ports over two spines
+    assert_eq!(FatTreeConfig::new(2).link_bits_per_tick(&config), 64);
+    assert_eq!(
+        FatTreeConfig::new(2)
+            .with_oversubscription(2)
+            .link_bits_per_tick(&config),
+        32
+    );
+    assert_eq!(
+        FatTreeConfig::new(1)
+            .with_oversubscription(256)
+            .link_bits_per_tick(&config),
+        1
+    );
+}
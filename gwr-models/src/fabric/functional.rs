@@ -3,7 +3,11 @@
 //! A functional implementation of a fabric with very basic timing.
 //!
 //! Assumes that all traffic will move a Manhattan distance through the fabric
-//! to get from ingress to egress.
+//! to get from ingress to egress. For a torus the distance in each dimension
+//! is the shorter way around the ring. A multicast object is delivered to each
+//! of its destinations as though it had been sent to each one individually.
+//! If the configuration has an [energy model](crate::energy::EnergyConfig)
+//! then each object is charged the energy of every hop of that distance.
 //!
 //! The fabric is assumed to be rectangular with a configurable `num_rows` and
 //! `num_columns`. The grid has a configurable number of ports at each node
@@ -24,13 +28,14 @@ use std::rc::Rc;
 
 use async_trait::async_trait;
 use gwr_components::flow_controls::limiter::Limiter;
-use gwr_components::router::{DefaultAlgorithm, Route};
+use gwr_components::router::{DefaultAlgorithm, Route, replicate};
 use gwr_components::store::{ByteStore, Store};
 use gwr_components::{connect_port, rc_limiter};
 use gwr_engine::engine::Engine;
 use gwr_engine::events::repeated::Repeated;
 use gwr_engine::executor::Spawner;
 use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::power::EnergyAccount;
 use gwr_engine::sim_error;
 use gwr_engine::time::clock::{Clock, ClockTick};
 use gwr_engine::traits::{Event, Routable, Runnable, SimObject};
@@ -40,23 +45,34 @@ use gwr_track::build_aka;
 use gwr_track::entity::Entity;
 use gwr_track::tracker::aka::Aka;
 
+use crate::energy::EnergyMeter;
 use crate::fabric::{Fabric, FabricConfig};
 
-/// Return the Manhatten time to travel between RX and TX ports specified.
+/// Return the Manhatten number of hops between RX and TX ports specified.
 #[must_use]
-fn manhatten_rx_to_tx_cycles(
+fn manhatten_rx_to_tx_hops(
     config: &FabricConfig,
     rx_port_index: usize,
     tx_port_index: usize,
 ) -> usize {
     let (rx_col, rx_row, _) = config.fabric_port_index_to_col_row_port(rx_port_index);
     let (tx_col, tx_row, _) = config.fabric_port_index_to_col_row_port(tx_port_index);
-    let horizontal_hops = rx_col.abs_diff(tx_col);
-    let vertical_hops = rx_row.abs_diff(tx_row);
+    let horizontal_hops = config.col_hops(rx_col, tx_col);
+    let vertical_hops = config.row_hops(rx_row, tx_row);
+    horizontal_hops + vertical_hops
+}
 
+/// Return the Manhatten time to travel between RX and TX ports specified.
+#[must_use]
+fn manhatten_rx_to_tx_cycles(
+    config: &FabricConfig,
+    rx_port_index: usize,
+    tx_port_index: usize,
+) -> usize {
     // Add one hop for enterring so that there is never a zero-cycle latency which
     // could otherwise be seen between ports on the same fabric node
-    (horizontal_hops + vertical_hops) * config.cycles_per_hop + config.cycles_overhead
+    manhatten_rx_to_tx_hops(config, rx_port_index, tx_port_index) * config.cycles_per_hop
+        + config.cycles_overhead
 }
 
 #[derive(EntityGet, EntityDisplay)]
@@ -70,6 +86,7 @@ where
     tx_buffers: Vec<Rc<Store<T>>>,
     internal_tx: RefCell<Vec<OutPort<T>>>,
     config: Rc<FabricConfig>,
+    energy: Option<EnergyMeter>,
     clock: Clock,
     spawner: Spawner,
 }
@@ -98,6 +115,15 @@ where
             return sim_error!("Cannot create fabric with less than 2 ports");
         }
 
+        // Every node draws the idle power of the fabric
+        let energy = config.energy().map(|energy| {
+            let num_nodes = config.num_columns * config.num_rows;
+            let account = engine
+                .power()
+                .create_account(&entity, energy.idle_power_mw() * num_nodes as f64);
+            EnergyMeter::new(&account, *energy)
+        });
+
         let mut rx_buffer_limiters = Vec::with_capacity(num_ports);
         let mut internal_rx = Vec::with_capacity(num_ports);
         let mut tx_buffers = Vec::with_capacity(num_ports);
@@ -174,6 +200,7 @@ where
             tx_buffers,
             internal_tx: RefCell::new(internal_tx),
             config,
+            energy,
             clock: clock.clone(),
             spawner,
         });
@@ -194,6 +221,13 @@ where
     ) -> Result<Rc<Self>, SimError> {
         Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
     }
+
+    /// Returns the account recording the energy of the fabric when it has an
+    /// energy model
+    #[must_use]
+    pub fn energy_account(&self) -> Option<&Rc<EnergyAccount>> {
+        self.energy.as_ref().map(EnergyMeter::account)
+    }
 }
 
 impl<T> Fabric<T> for FunctionalFabric<T>
@@ -235,6 +269,7 @@ where
             let port_states = port_states.clone();
             let routing_algorithm = routing_algorithm.clone();
             let config = self.config.clone();
+            let energy = self.energy.clone();
 
             self.spawner.spawn(async move {
                 run_rx(
@@ -245,6 +280,7 @@ where
                     port_states,
                     routing_algorithm,
                     config,
+                    energy,
                 )
                 .await
             });
@@ -286,6 +322,7 @@ impl<T> Default for PortState<T> {
     }
 }
 
+#[expect(clippy::too_many_arguments)]
 async fn run_rx<T>(
     entity: Rc<Entity>,
     clock: Clock,
@@ -294,42 +331,90 @@ async fn run_rx<T>(
     port_states: Rc<Vec<PortState<T>>>,
     routing_algorithm: Rc<Box<dyn Route<T>>>,
     config: Rc<FabricConfig>,
+    energy: Option<EnergyMeter>,
 ) -> SimResult
 where
     T: SimObject + Routable,
 {
-    // Use the size of the TX buffer to configure the internal buffering.
-    let max_internal_buffer_bytes = config.tx_buffer_bytes;
-
     loop {
         let value = internal_rx.get()?.await;
-        let value_id = value.id();
-        entity.track_enter(value_id);
-        let value_bytes = value.total_bytes();
+        entity.track_enter(value.id());
+
+        match value.destinations() {
+            None => {
+                let dest_index = routing_algorithm.route(&value)?;
+                send_to_tx(
+                    &clock,
+                    port_index,
+                    dest_index,
+                    value,
+                    &port_states,
+                    &config,
+                    energy.as_ref(),
+                )
+                .await;
+            }
+            Some(destinations) => {
+                // Deliver a copy of a multicast object to each destination
+                let copies = replicate(routing_algorithm.as_ref().as_ref(), &value, destinations)?;
+                for (dest_index, copy) in copies {
+                    send_to_tx(
+                        &clock,
+                        port_index,
+                        dest_index,
+                        copy,
+                        &port_states,
+                        &config,
+                        energy.as_ref(),
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+}
 
-        let dest_index = routing_algorithm.route(&value)?;
-        let delay_ticks = manhatten_rx_to_tx_cycles(&config, port_index, dest_index);
+async fn send_to_tx<T>(
+    clock: &Clock,
+    port_index: usize,
+    dest_index: usize,
+    value: T,
+    port_states: &[PortState<T>],
+    config: &FabricConfig,
+    energy: Option<&EnergyMeter>,
+) where
+    T: SimObject + Routable,
+{
+    // Use the size of the TX buffer to configure the internal buffering.
+    let max_internal_buffer_bytes = config.tx_buffer_bytes;
+    let value_bytes = value.total_bytes();
+    let delay_ticks = manhatten_rx_to_tx_cycles(config, port_index, dest_index);
+    if let Some(energy) = energy {
+        energy.record_transfer(
+            value_bytes,
+            manhatten_rx_to_tx_hops(config, port_index, dest_index),
+        );
+    }
 
-        let mut tick = clock.tick_now();
-        tick.set_tick(tick.tick() + delay_ticks as u64);
+    let mut tick = clock.tick_now();
+    tick.set_tick(tick.tick() + delay_ticks as u64);
 
-        // If the queue to the destination is too full then wait for space
-        while *port_states[dest_index].data_for_tx_bytes.borrow() + value_bytes
-            > max_internal_buffer_bytes
-        {
-            port_states[dest_index]
-                .inputs_waiting_for_room
-                .borrow_mut()
-                .push_back(port_index);
-            port_states[port_index].waiting_for_room.listen().await;
-        }
-        *port_states[dest_index].data_for_tx_bytes.borrow_mut() += value_bytes;
+    // If the queue to the destination is too full then wait for space
+    while *port_states[dest_index].data_for_tx_bytes.borrow() + value_bytes
+        > max_internal_buffer_bytes
+    {
         port_states[dest_index]
-            .data_for_tx
+            .inputs_waiting_for_room
             .borrow_mut()
-            .push_back((value, tick));
-        port_states[dest_index].waiting_for_data.notify();
+            .push_back(port_index);
+        port_states[port_index].waiting_for_room.listen().await;
     }
+    *port_states[dest_index].data_for_tx_bytes.borrow_mut() += value_bytes;
+    port_states[dest_index]
+        .data_for_tx
+        .borrow_mut()
+        .push_back((value, tick));
+    port_states[dest_index].waiting_for_data.notify();
 }
 
 async fn run_tx<T>(
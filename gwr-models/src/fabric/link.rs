@@ -0,0 +1,450 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A link between two nodes of a routed fabric.
+//!
+//! A [FabricLink] delays objects by a fixed number of ticks in the same way as
+//! a [Delay](gwr_components::delay::Delay) while recording congestion
+//! telemetry for the link:
+//!  - utilization: the fraction of the link bandwidth used
+//!  - queue depth: the number of objects on the link
+//!  - stall ticks: the ticks spent waiting for the receiving node to accept an
+//!    object
+//!
+//! If monitoring is enabled for a link then each of these is also emitted as a
+//! value for every monitoring window so that congestion can be viewed over time
+//! in a trace.
+//!
+//! A link created with an [EnergyMeter] records every object crossing it as one
+//! hop.
+//!
+//! # Ports
+//!
+//! This component has the following ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::events::repeated::Repeated;
+use gwr_engine::executor::Spawner;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::time::clock::{Clock, ClockTick};
+use gwr_engine::traits::{Event, Runnable, SimObject};
+use gwr_engine::types::SimResult;
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::{Entity, EntityMonitor};
+
+use crate::energy::EnergyMeter;
+use crate::fabric::node::Port;
+
+/// A snapshot of the telemetry of a link
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LinkStats {
+    bytes: usize,
+    objects: usize,
+    stall_ticks: u64,
+    max_queue_depth: usize,
+    queue_depth_ticks: u64,
+    ticks: u64,
+    bits_per_tick: usize,
+}
+
+impl LinkStats {
+    /// Returns the number of bytes sent over the link
+    #[must_use]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// Returns the number of objects sent over the link
+    #[must_use]
+    pub fn objects(&self) -> usize {
+        self.objects
+    }
+
+    /// Returns the number of ticks spent waiting for the receiver
+    #[must_use]
+    pub fn stall_ticks(&self) -> u64 {
+        self.stall_ticks
+    }
+
+    /// Returns the maximum number of objects that were on the link
+    #[must_use]
+    pub fn max_queue_depth(&self) -> usize {
+        self.max_queue_depth
+    }
+
+    /// Returns the average number of objects on the link over time
+    #[must_use]
+    pub fn mean_queue_depth(&self) -> f64 {
+        if self.ticks == 0 {
+            0.0
+        } else {
+            self.queue_depth_ticks as f64 / self.ticks as f64
+        }
+    }
+
+    /// Returns the fraction of the link bandwidth that was used
+    #[must_use]
+    pub fn utilization(&self) -> f64 {
+        let capacity_bits = self.ticks as f64 * self.bits_per_tick as f64;
+        if capacity_bits == 0.0 {
+            0.0
+        } else {
+            (self.bytes * 8) as f64 / capacity_bits
+        }
+    }
+
+    /// Returns the telemetry for the period since an earlier snapshot
+    #[must_use]
+    fn since(&self, earlier: &LinkStats) -> LinkStats {
+        LinkStats {
+            bytes: self.bytes - earlier.bytes,
+            objects: self.objects - earlier.objects,
+            stall_ticks: self.stall_ticks - earlier.stall_ticks,
+            max_queue_depth: self.max_queue_depth,
+            queue_depth_ticks: self.queue_depth_ticks - earlier.queue_depth_ticks,
+            ticks: self.ticks - earlier.ticks,
+            bits_per_tick: self.bits_per_tick,
+        }
+    }
+}
+
+/// The counters updated by a [FabricLink]
+pub struct LinkCounters {
+    clock: Clock,
+    bits_per_tick: usize,
+    bytes: Cell<usize>,
+    objects: Cell<usize>,
+    stall_ticks: Cell<u64>,
+    queue_depth: Cell<usize>,
+    max_queue_depth: Cell<usize>,
+    queue_depth_ticks: Cell<u64>,
+    last_change_tick: Cell<u64>,
+}
+
+impl LinkCounters {
+    fn new(clock: &Clock, bits_per_tick: usize) -> Self {
+        Self {
+            clock: clock.clone(),
+            bits_per_tick,
+            bytes: Cell::new(0),
+            objects: Cell::new(0),
+            stall_ticks: Cell::new(0),
+            queue_depth: Cell::new(0),
+            max_queue_depth: Cell::new(0),
+            queue_depth_ticks: Cell::new(0),
+            last_change_tick: Cell::new(0),
+        }
+    }
+
+    /// Accumulate the queue depth up to the current tick
+    fn accumulate_queue_depth(&self) -> u64 {
+        let now = self.clock.tick_now().tick();
+        let elapsed = now - self.last_change_tick.get();
+        self.queue_depth_ticks
+            .set(self.queue_depth_ticks.get() + elapsed * self.queue_depth.get() as u64);
+        self.last_change_tick.set(now);
+        now
+    }
+
+    fn entered(&self, bytes: usize) {
+        self.accumulate_queue_depth();
+        self.bytes.set(self.bytes.get() + bytes);
+        self.objects.set(self.objects.get() + 1);
+        let queue_depth = self.queue_depth.get() + 1;
+        self.queue_depth.set(queue_depth);
+        self.max_queue_depth
+            .set(self.max_queue_depth.get().max(queue_depth));
+    }
+
+    fn exited(&self, stall_ticks: u64) {
+        self.accumulate_queue_depth();
+        self.queue_depth.set(self.queue_depth.get() - 1);
+        self.stall_ticks.set(self.stall_ticks.get() + stall_ticks);
+    }
+
+    /// Returns the telemetry of the link up to the current tick
+    #[must_use]
+    pub fn stats(&self) -> LinkStats {
+        let ticks = self.accumulate_queue_depth();
+        LinkStats {
+            bytes: self.bytes.get(),
+            objects: self.objects.get(),
+            stall_ticks: self.stall_ticks.get(),
+            max_queue_depth: self.max_queue_depth.get(),
+            queue_depth_ticks: self.queue_depth_ticks.get(),
+            ticks,
+            bits_per_tick: self.bits_per_tick,
+        }
+    }
+}
+
+/// Monitors used to emit the telemetry of each window
+struct LinkMonitors {
+    window_size_ticks: u64,
+    utilization: EntityMonitor,
+    queue_depth: EntityMonitor,
+    stall_ticks: EntityMonitor,
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct FabricLink<T>
+where
+    T: SimObject,
+{
+    entity: Rc<Entity>,
+    spawner: Spawner,
+    clock: Clock,
+    delay_ticks: usize,
+
+    rx: RefCell<Option<InPort<T>>>,
+    pending: Rc<RefCell<VecDeque<(T, ClockTick)>>>,
+    pending_changed: Repeated<()>,
+    output_changed: Repeated<()>,
+    tx: RefCell<Option<OutPort<T>>>,
+
+    counters: Rc<LinkCounters>,
+    monitors: RefCell<Option<LinkMonitors>>,
+    energy: Option<EnergyMeter>,
+}
+
+impl<T> FabricLink<T>
+where
+    T: SimObject,
+{
+    /// Create a link that delays objects by `delay_ticks` and whose
+    /// utilization is measured against `bits_per_tick`
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        delay_ticks: usize,
+        bits_per_tick: usize,
+    ) -> Rc<Self> {
+        Self::new_and_register_with_energy(
+            engine,
+            clock,
+            parent,
+            name,
+            delay_ticks,
+            bits_per_tick,
+            None,
+        )
+    }
+
+    /// Create a link that also records the energy of the objects crossing it
+    /// with `energy`
+    pub fn new_and_register_with_energy(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        delay_ticks: usize,
+        bits_per_tick: usize,
+        energy: Option<EnergyMeter>,
+    ) -> Rc<Self> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let rx = InPort::new(engine, clock, &entity, "rx");
+        let tx = OutPort::new(&entity, "tx");
+
+        let monitors =
+            entity
+                .tracker
+                .monitoring_window_size_for(entity.id)
+                .map(|window_size_ticks| LinkMonitors {
+                    window_size_ticks: window_size_ticks.max(1),
+                    utilization: EntityMonitor::new(&entity, "utilization_%"),
+                    queue_depth: EntityMonitor::new(&entity, "queue_depth"),
+                    stall_ticks: EntityMonitor::new(&entity, "stall_ticks"),
+                });
+
+        let rc_self = Rc::new(Self {
+            entity,
+            spawner: engine.spawner(),
+            clock: clock.clone(),
+            delay_ticks,
+            rx: RefCell::new(Some(rx)),
+            pending: Rc::new(RefCell::new(VecDeque::new())),
+            pending_changed: Repeated::default(),
+            output_changed: Repeated::default(),
+            tx: RefCell::new(Some(tx)),
+            counters: Rc::new(LinkCounters::new(clock, bits_per_tick)),
+            monitors: RefCell::new(monitors),
+            energy,
+        });
+        engine.register(rc_self.clone());
+        rc_self
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Returns the counters of the link
+    #[must_use]
+    pub fn counters(&self) -> Rc<LinkCounters> {
+        self.counters.clone()
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for FabricLink<T>
+where
+    T: SimObject,
+{
+    async fn run(&self) -> SimResult {
+        let tx = take_option!(self.tx);
+        let entity = self.entity.clone();
+        let clock = self.clock.clone();
+        let pending = self.pending.clone();
+        let pending_changed = self.pending_changed.clone();
+        let output_changed = self.output_changed.clone();
+        let counters = self.counters.clone();
+        self.spawner.spawn(async move {
+            run_tx(
+                entity,
+                tx,
+                &clock,
+                pending,
+                pending_changed,
+                output_changed,
+                &counters,
+            )
+            .await
+        });
+
+        if let Some(monitors) = self.monitors.borrow_mut().take() {
+            let clock = self.clock.clone();
+            let counters = self.counters.clone();
+            self.spawner
+                .spawn(async move { run_monitors(&clock, &counters, monitors).await });
+        }
+
+        let mut rx = take_option!(self.rx);
+        loop {
+            let value = rx.get()?.await;
+            self.entity.track_enter(value.id());
+            self.counters.entered(value.total_bytes());
+            if let Some(energy) = &self.energy {
+                energy.record_transfer(value.total_bytes(), 1);
+            }
+
+            let mut tick = self.clock.tick_now();
+            tick.set_tick(tick.tick() + self.delay_ticks as u64);
+
+            self.pending.borrow_mut().push_back((value, tick));
+            self.pending_changed.notify();
+
+            if self.delay_ticks > 0 {
+                // Enforce back-pressure by waiting until there is room on the link
+                while self.pending.borrow().len() >= self.delay_ticks {
+                    self.output_changed.listen().await;
+                }
+            }
+        }
+    }
+}
+
+async fn run_tx<T>(
+    entity: Rc<Entity>,
+    mut tx: OutPort<T>,
+    clock: &Clock,
+    pending: Rc<RefCell<VecDeque<(T, ClockTick)>>>,
+    pending_changed: Repeated<()>,
+    output_changed: Repeated<()>,
+    counters: &LinkCounters,
+) -> SimResult
+where
+    T: SimObject,
+{
+    loop {
+        let next = pending.borrow_mut().pop_front();
+
+        match next {
+            Some((value, tick)) => {
+                let tick_now = clock.tick_now();
+                if tick.tick() > tick_now.tick() {
+                    clock.wait_ticks(tick.tick() - tick_now.tick()).await;
+                }
+
+                let put_tick = clock.tick_now().tick();
+                entity.track_exit(value.id());
+                tx.put(value)?.await;
+                counters.exited(clock.tick_now().tick() - put_tick);
+                output_changed.notify();
+            }
+            None => {
+                pending_changed.listen().await;
+            }
+        }
+    }
+}
+
+async fn run_monitors(clock: &Clock, counters: &LinkCounters, monitors: LinkMonitors) -> SimResult {
+    let mut previous = counters.stats();
+    loop {
+        clock.wait_ticks_or_exit(monitors.window_size_ticks).await;
+        let stats = counters.stats();
+        let window = stats.since(&previous);
+        monitors
+            .utilization
+            .track_value(window.utilization() * 100.0);
+        monitors.queue_depth.track_value(window.mean_queue_depth());
+        monitors
+            .stall_ticks
+            .track_value(window.stall_ticks() as f64);
+        previous = stats;
+    }
+}
+
+/// Telemetry of every link in a fabric, formatted with one link per line so
+/// that it can be loaded as a table to draw a heat map
+pub struct LinkStatsDisplay {
+    prefix: String,
+    links: Vec<(usize, usize, Port, LinkStats)>,
+}
+
+impl LinkStatsDisplay {
+    /// Create the display from the column and row of the node that each link
+    /// leaves, the direction it leaves in and its telemetry
+    #[must_use]
+    pub fn new(prefix: impl Into<String>, links: Vec<(usize, usize, Port, LinkStats)>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            links,
+        }
+    }
+}
+
+impl Display for LinkStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.prefix)?;
+        write!(
+            f,
+            "  col,row,direction,utilization_%,mean_queue_depth,max_queue_depth,stall_ticks"
+        )?;
+        for (col, row, direction, stats) in &self.links {
+            write!(
+                f,
+                "\n  {col},{row},{direction},{:.2},{:.2},{},{}",
+                stats.utilization() * 100.0,
+                stats.mean_queue_depth(),
+                stats.max_queue_depth(),
+                stats.stall_ticks()
+            )?;
+        }
+        Ok(())
+    }
+}
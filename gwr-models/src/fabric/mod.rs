@@ -6,14 +6,22 @@
 //! collections of nodes with each node allocated P ingress/egress port IDs.
 //! However, if the user limits the number of ports per node then not all
 //! ingress/egress ports will be populated.
+//!
+//! The nodes can either be connected as a mesh or as a torus, where the nodes
+//! at the edges of each row and column are also connected to each other.
 
 use std::cmp::min;
 use std::fmt::Display;
 
+use clap::ValueEnum;
+use gwr_components::arbiter::policy::TrafficClasses;
 use gwr_engine::port::PortStateResult;
 use gwr_engine::traits::{Routable, SimObject};
 use gwr_engine::types::SimResult;
 use gwr_track::entity::GetEntity;
+use serde::{Deserialize, Serialize};
+
+use crate::energy::EnergyConfig;
 
 pub trait Fabric<T>: GetEntity + Display
 where
@@ -29,6 +37,18 @@ pub enum RoutingAlgoritm {
     RowFirst,
 }
 
+#[derive(ValueEnum, Clone, Copy, Default, Debug, Serialize, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FabricTopology {
+    /// Nodes are only connected to their direct neighbours
+    #[default]
+    Mesh,
+
+    /// Nodes at the edges of each row and column are also connected, with
+    /// traffic taking the shortest direction around each ring
+    Torus,
+}
+
 /// Configuration structure for a fabric
 pub struct FabricConfig {
     /// Number of columns in the fabric
@@ -60,6 +80,25 @@ pub struct FabricConfig {
     /// Set the throughput limit on each port (in bits per tick)
     port_bits_per_tick: usize,
 
+    /// How the nodes of the fabric are connected
+    topology: FabricTopology,
+
+    /// Size of the flits that frames are split into for wormhole switching.
+    /// Frames are switched whole when this is not set.
+    flit_bytes: Option<usize>,
+
+    /// Priorities and weights used to arbitrate between traffic classes.
+    /// Traffic classes are ignored when this is not set.
+    traffic_classes: Option<TrafficClasses>,
+
+    /// Energy used to move data through the fabric. Energy is not recorded
+    /// when this is not set.
+    energy: Option<EnergyConfig>,
+
+    /// Buffer level above which objects are marked as having experienced
+    /// congestion. Objects are not marked when this is not set.
+    ecn_threshold_bytes: Option<usize>,
+
     /// Indices of populated ingress/egress ports
     fabric_port_indices: Vec<usize>,
 }
@@ -76,7 +115,20 @@ fn col_row_port_to_fabric_port_index(
 }
 
 #[must_use]
-fn num_x_y_ports(num_columns: usize, num_rows: usize, col: usize, row: usize) -> usize {
+fn num_x_y_ports(
+    topology: FabricTopology,
+    num_columns: usize,
+    num_rows: usize,
+    col: usize,
+    row: usize,
+) -> usize {
+    if topology == FabricTopology::Torus {
+        // Every node has both neighbours in each dimension with more than one node
+        let col_ports = if num_columns > 1 { 2 } else { 0 };
+        let row_ports = if num_rows > 1 { 2 } else { 0 };
+        return col_ports + row_ports;
+    }
+
     let mut num_ports = 4;
     if col == 0 || col == num_columns - 1 {
         // Left/right edge
@@ -93,6 +145,7 @@ fn num_x_y_ports(num_columns: usize, num_rows: usize, col: usize, row: usize) ->
 /// ingress/egress ports there are
 #[must_use]
 fn node_num_ingress_egress_ports(
+    topology: FabricTopology,
     num_columns: usize,
     num_rows: usize,
     num_ports_per_node: usize,
@@ -103,7 +156,7 @@ fn node_num_ingress_egress_ports(
     match ports_per_node_limit {
         None => num_ports_per_node,
         Some(ports_per_node_limit) => {
-            let num_x_y_ports = num_x_y_ports(num_columns, num_rows, col, row);
+            let num_x_y_ports = num_x_y_ports(topology, num_columns, num_rows, col, row);
             let max_ingress_egress_ports = ports_per_node_limit.saturating_sub(num_x_y_ports);
             min(max_ingress_egress_ports, num_ports_per_node)
         }
@@ -111,6 +164,7 @@ fn node_num_ingress_egress_ports(
 }
 
 fn create_populated_indices(
+    topology: FabricTopology,
     num_columns: usize,
     num_rows: usize,
     num_ports_per_node: usize,
@@ -120,6 +174,7 @@ fn create_populated_indices(
     for col in 0..num_columns {
         for row in 0..num_rows {
             let num_ports = node_num_ingress_egress_ports(
+                topology,
                 num_columns,
                 num_rows,
                 num_ports_per_node,
@@ -155,7 +210,9 @@ impl FabricConfig {
         tx_buffer_bytes: usize,
         port_bits_per_tick: usize,
     ) -> Self {
+        let topology = FabricTopology::default();
         let fabric_port_indices = create_populated_indices(
+            topology,
             num_columns,
             num_rows,
             num_ports_per_node,
@@ -171,10 +228,68 @@ impl FabricConfig {
             rx_buffer_bytes,
             tx_buffer_bytes,
             port_bits_per_tick,
+            topology,
+            flit_bytes: None,
+            traffic_classes: None,
+            energy: None,
+            ecn_threshold_bytes: None,
             fabric_port_indices,
         }
     }
 
+    /// Set how the nodes of the fabric are connected
+    #[must_use]
+    pub fn with_topology(mut self, topology: FabricTopology) -> Self {
+        self.topology = topology;
+
+        // The number of x/y ports at a node can change, which changes the
+        // ports that are populated when the ports per node is limited
+        self.fabric_port_indices = create_populated_indices(
+            topology,
+            self.num_columns,
+            self.num_rows,
+            self.num_ports_per_node,
+            self.ports_per_node_limit,
+        );
+        self
+    }
+
+    /// Split frames into flits of `flit_bytes` and switch them using wormhole
+    /// switching. Only supported by the routed fabric and not supported for
+    /// multicast frames.
+    #[must_use]
+    pub fn with_flit_bytes(mut self, flit_bytes: usize) -> Self {
+        self.flit_bytes = Some(flit_bytes);
+        self
+    }
+
+    /// Arbitrate between the traffic classes of frames according to their
+    /// priorities and weights. Only supported by the routed and crossbar
+    /// fabrics.
+    #[must_use]
+    pub fn with_traffic_classes(mut self, traffic_classes: TrafficClasses) -> Self {
+        self.traffic_classes = Some(traffic_classes);
+        self
+    }
+
+    /// Record the energy used by the fabric. Every object charges the hop
+    /// energy for each link it crosses and the idle power is drawn by every
+    /// node. Only supported by the routed and functional fabrics.
+    #[must_use]
+    pub fn with_energy(mut self, energy: EnergyConfig) -> Self {
+        self.energy = Some(energy);
+        self
+    }
+
+    /// Mark objects that arrive at a node buffer holding more than
+    /// `threshold_bytes` as having experienced congestion. Only supported by
+    /// the routed fabric.
+    #[must_use]
+    pub fn with_ecn_threshold_bytes(mut self, threshold_bytes: usize) -> Self {
+        self.ecn_threshold_bytes = Some(threshold_bytes);
+        self
+    }
+
     /// Returns the maximum number of ports in the fabric
     #[must_use]
     pub fn max_num_ports(&self) -> usize {
@@ -217,6 +332,7 @@ impl FabricConfig {
     #[must_use]
     pub fn node_num_ingress_egress_ports(&self, col: usize, row: usize) -> usize {
         node_num_ingress_egress_ports(
+            self.topology,
             self.num_columns,
             self.num_rows,
             self.num_ports_per_node,
@@ -226,6 +342,32 @@ impl FabricConfig {
         )
     }
 
+    /// Returns the number of hops between two columns
+    #[must_use]
+    pub fn col_hops(&self, from: usize, to: usize) -> usize {
+        num_hops(self.topology, self.num_columns, from, to)
+    }
+
+    /// Returns the number of hops between two rows
+    #[must_use]
+    pub fn row_hops(&self, from: usize, to: usize) -> usize {
+        num_hops(self.topology, self.num_rows, from, to)
+    }
+
+    /// Returns whether the shortest route from column `from` to column `to`
+    /// is in the plus direction
+    #[must_use]
+    pub fn route_col_plus(&self, from: usize, to: usize) -> bool {
+        route_plus(self.topology, self.num_columns, from, to)
+    }
+
+    /// Returns whether the shortest route from row `from` to row `to` is in
+    /// the plus direction
+    #[must_use]
+    pub fn route_row_plus(&self, from: usize, to: usize) -> bool {
+        route_plus(self.topology, self.num_rows, from, to)
+    }
+
     #[must_use]
     pub fn max_x(&self) -> usize {
         self.num_columns - 1
@@ -265,9 +407,69 @@ impl FabricConfig {
     pub fn port_bits_per_tick(&self) -> usize {
         self.port_bits_per_tick
     }
+
+    #[must_use]
+    pub fn topology(&self) -> FabricTopology {
+        self.topology
+    }
+
+    #[must_use]
+    pub fn flit_bytes(&self) -> Option<usize> {
+        self.flit_bytes
+    }
+
+    #[must_use]
+    pub fn traffic_classes(&self) -> Option<&TrafficClasses> {
+        self.traffic_classes.as_ref()
+    }
+
+    #[must_use]
+    pub fn energy(&self) -> Option<&EnergyConfig> {
+        self.energy.as_ref()
+    }
+
+    #[must_use]
+    pub fn ecn_threshold_bytes(&self) -> Option<usize> {
+        self.ecn_threshold_bytes
+    }
+}
+
+/// Number of hops in the plus direction from `from` to `to` around a ring of
+/// `n` nodes
+#[must_use]
+fn plus_hops(n: usize, from: usize, to: usize) -> usize {
+    (to + n - from) % n
 }
 
+#[must_use]
+fn num_hops(topology: FabricTopology, n: usize, from: usize, to: usize) -> usize {
+    match topology {
+        FabricTopology::Mesh => from.abs_diff(to),
+        FabricTopology::Torus => {
+            let plus = plus_hops(n, from, to);
+            min(plus, n - plus)
+        }
+    }
+}
+
+/// Ties are broken in the plus direction so that every node on the route
+/// agrees on the direction.
+#[must_use]
+fn route_plus(topology: FabricTopology, n: usize, from: usize, to: usize) -> bool {
+    match topology {
+        FabricTopology::Mesh => from < to,
+        FabricTopology::Torus => {
+            let plus = plus_hops(n, from, to);
+            plus != 0 && plus <= n - plus
+        }
+    }
+}
+
+pub mod crossbar;
+pub mod deadlock;
+pub mod fat_tree;
 pub mod functional;
+pub mod link;
 pub mod node;
 pub mod routed;
 
@@ -296,3 +498,34 @@ fn port_index() {
     assert_eq!(config.col_row_port_to_fabric_port_index(2, 1, 1), 19);
     assert_eq!(config.fabric_port_index_to_col_row_port(19), (2, 1, 1));
 }
+
+#[test]
+fn torus_hops() {
+    let config =
+        FabricConfig::new(5, 4, 1, None, 1, 1, 1, 1, 1).with_topology(FabricTopology::Torus);
+
+    assert_eq!(config.col_hops(0, 4), 1);
+    assert!(!config.route_col_plus(0, 4));
+    assert_eq!(config.col_hops(4, 0), 1);
+    assert!(config.route_col_plus(4, 0));
+    assert_eq!(config.col_hops(1, 3), 2);
+    assert!(config.route_col_plus(1, 3));
+
+    // Ties are broken in the plus direction
+    assert_eq!(config.row_hops(3, 1), 2);
+    assert!(config.route_row_plus(3, 1));
+    assert!(config.route_row_plus(1, 3));
+}
+
+#[test]
+fn torus_ports_per_node_limit() {
+    let mesh = FabricConfig::new(3, 3, 2, Some(5), 1, 1, 1, 1, 1);
+    let torus =
+        FabricConfig::new(3, 3, 2, Some(5), 1, 1, 1, 1, 1).with_topology(FabricTopology::Torus);
+
+    // Corner nodes of a mesh have spare ports, torus nodes do not
+    assert_eq!(mesh.node_num_ingress_egress_ports(0, 0), 2);
+    assert_eq!(torus.node_num_ingress_egress_ports(0, 0), 1);
+    assert_eq!(mesh.num_ports(), 17);
+    assert_eq!(torus.num_ports(), 9);
+}
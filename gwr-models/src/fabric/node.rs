@@ -82,15 +82,17 @@
 //!  +-------------------------------------------+
 //! ```
 
+use std::cell::{Cell, RefCell};
 use std::fmt;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 use async_trait::async_trait;
 use clap::ValueEnum;
-use gwr_components::arbiter::Arbiter;
-use gwr_components::arbiter::policy::RoundRobin;
+use gwr_components::arbiter::policy::{self, RoundRobin, TrafficClassRoundRobin};
+use gwr_components::arbiter::{Arbiter, Arbitrate};
 use gwr_components::flow_controls::limiter::Limiter;
-use gwr_components::router::{Route, Router};
+use gwr_components::packetizer::Flit;
+use gwr_components::router::{Route, Router, WormholeRoute};
 use gwr_components::store::{ByteStore, Store};
 use gwr_components::{connect_port, rc_limiter};
 use gwr_engine::engine::Engine;
@@ -104,6 +106,7 @@ use gwr_track::entity::Entity;
 use gwr_track::tracker::aka::Aka;
 use serde::{Deserialize, Serialize};
 
+use crate::congestion::EcnMarker;
 use crate::fabric::FabricConfig;
 
 #[derive(ValueEnum, Clone, Copy, Default, Debug, Serialize, PartialEq, Deserialize)]
@@ -115,6 +118,114 @@ pub enum FabricRoutingAlgorithm {
 
     /// Route packets to the right row first
     RowFirst,
+
+    /// Route packets along a minimal path, choosing between the column and
+    /// row at each node depending on which has fewer frames in flight.
+    ///
+    /// Note that without virtual channels this can deadlock under heavy load.
+    Adaptive,
+
+    /// Route packets west (`col_minus`) first. Packets heading east choose
+    /// adaptively between the column and row.
+    WestFirst,
+
+    /// Route packets north (`row_minus`) last. Packets not heading north
+    /// choose adaptively between the column and row.
+    NorthLast,
+
+    /// Route packets adaptively while never turning from the row into the
+    /// east (`col_plus`) direction in an even column or from the row into the
+    /// west (`col_minus`) direction in an odd column.
+    OddEven,
+}
+
+impl FabricRoutingAlgorithm {
+    /// Returns the column and row ports that a packet at a node is permitted
+    /// to take towards a different destination node.
+    ///
+    /// At least one port is always returned and each port returned reduces
+    /// the distance to the destination. `at_ingress` indicates whether the
+    /// packet is still at the node where it entered the fabric.
+    #[must_use]
+    pub(crate) fn permitted_ports(
+        &self,
+        config: &FabricConfig,
+        (node_col, node_row): (usize, usize),
+        (dest_col, dest_row): (usize, usize),
+        at_ingress: bool,
+    ) -> (Option<usize>, Option<usize>) {
+        let col_port = (node_col != dest_col).then(|| {
+            if config.route_col_plus(node_col, dest_col) {
+                Port::ColPlus as usize
+            } else {
+                Port::ColMinus as usize
+            }
+        });
+        let row_port = (node_row != dest_row).then(|| {
+            if config.route_row_plus(node_row, dest_row) {
+                Port::RowPlus as usize
+            } else {
+                Port::RowMinus as usize
+            }
+        });
+        let (Some(col), Some(row)) = (col_port, row_port) else {
+            // Only one dimension left to route in
+            return (col_port, row_port);
+        };
+
+        let west = col == Port::ColMinus as usize;
+        let north = row == Port::RowMinus as usize;
+        let odd_col = node_col % 2 == 1;
+        match self {
+            FabricRoutingAlgorithm::ColumnFirst => (Some(col), None),
+            FabricRoutingAlgorithm::RowFirst => (None, Some(row)),
+            FabricRoutingAlgorithm::Adaptive => (Some(col), Some(row)),
+            FabricRoutingAlgorithm::WestFirst if west => (Some(col), None),
+            FabricRoutingAlgorithm::WestFirst => (Some(col), Some(row)),
+            FabricRoutingAlgorithm::NorthLast if north => (Some(col), None),
+            FabricRoutingAlgorithm::NorthLast => (Some(col), Some(row)),
+            FabricRoutingAlgorithm::OddEven if west => (Some(col), (!odd_col).then_some(row)),
+            FabricRoutingAlgorithm::OddEven => {
+                // The last hop east must arrive in an odd column unless the
+                // packet can still turn into the row at that column.
+                let east = dest_col % 2 == 1 || config.col_hops(node_col, dest_col) > 1;
+                let turn = odd_col || at_ingress;
+                (east.then_some(col), turn.then_some(row))
+            }
+        }
+    }
+}
+
+/// State shared between the routers of a node
+#[derive(Default)]
+struct NodeState {
+    /// Number of frames routed in each direction that have not yet reached
+    /// the neighbouring node
+    in_flight: [Cell<usize>; Port::Ingress as usize],
+
+    /// The state of the neighbouring node in each direction
+    neighbours: RefCell<[Option<Weak<NodeState>>; Port::Ingress as usize]>,
+
+    /// Number of adaptive routing choices made for each dimension
+    num_col_first: Cell<usize>,
+    num_row_first: Cell<usize>,
+}
+
+impl NodeState {
+    fn in_flight(&self, port: usize) -> usize {
+        self.in_flight[port].get()
+    }
+
+    /// Record a frame arriving from the neighbour in direction `port`
+    fn arrived_from(&self, port: usize) {
+        if let Some(neighbour) = self.neighbours.borrow()[port]
+            .as_ref()
+            .and_then(Weak::upgrade)
+        {
+            let sent = &neighbour.in_flight[Port::opposite(port)];
+            sent.set(sent.get() - 1);
+        }
+    }
 }
 
 struct NodeRouter {
@@ -123,60 +234,52 @@ struct NodeRouter {
     node_row: usize,
     fabric_algorithm: FabricRoutingAlgorithm,
     config: Rc<FabricConfig>,
+    state: Rc<NodeState>,
 }
 
-impl<T> Route<T> for NodeRouter
-where
-    T: SimObject + Routable,
-{
-    /// Route an object to the right egress port on the router. The [FabricNode]
-    /// is constructed with [Arbiter]s and [Router]s that have N-1 ports
-    /// (where N is the total number of ports on the [FabricNode]). There
-    /// are N-1 ports because it is invalid to route to oneself.
-    ///
-    /// As a result it is necessary to remap indices from the computed egress
-    /// port to the router port. This depends on the index of this router.
-    fn route(&self, object: &T) -> Result<usize, SimError> {
-        let dest_fabric_port = object.destination() as usize;
+impl NodeRouter {
+    /// Return the port of the node that leads towards a fabric port
+    fn node_port(&self, dest_fabric_port: usize) -> usize {
         let (dest_col, dest_row, dest_port) = self
             .config
             .fabric_port_index_to_col_row_port(dest_fabric_port);
-        let dest_port = if (self.node_col == dest_col) && (self.node_row == dest_row) {
+
+        if (self.node_col == dest_col) && (self.node_row == dest_row) {
             // Local egress
-            dest_port + (Port::Ingress as usize)
-        } else if self.node_col == dest_col {
-            // Column reached, route by row.
-            if self.node_row < dest_row {
-                Port::RowPlus as usize
-            } else {
-                Port::RowMinus as usize
-            }
-        } else if self.node_row == dest_row {
-            // Row reached, route by column.
-            if self.node_col < dest_col {
-                Port::ColPlus as usize
-            } else {
-                Port::ColMinus as usize
-            }
-        } else {
-            // Both row/column not reached. Route according to algorithm.
-            match self.fabric_algorithm {
-                FabricRoutingAlgorithm::ColumnFirst => {
-                    if self.node_col < dest_col {
-                        Port::ColPlus as usize
-                    } else {
-                        Port::ColMinus as usize
-                    }
-                }
-                FabricRoutingAlgorithm::RowFirst => {
-                    if self.node_row < dest_row {
-                        Port::RowPlus as usize
-                    } else {
-                        Port::RowMinus as usize
-                    }
+            return dest_port + (Port::Ingress as usize);
+        }
+
+        let permitted = self.fabric_algorithm.permitted_ports(
+            &self.config,
+            (self.node_col, self.node_row),
+            (dest_col, dest_row),
+            self.index >= Port::Ingress as usize,
+        );
+        match permitted {
+            (Some(col_port), Some(row_port)) => {
+                // Choose the direction with fewer frames in flight
+                if self.state.in_flight(row_port) < self.state.in_flight(col_port) {
+                    let choices = &self.state.num_row_first;
+                    choices.set(choices.get() + 1);
+                    row_port
+                } else {
+                    let choices = &self.state.num_col_first;
+                    choices.set(choices.get() + 1);
+                    col_port
                 }
             }
-        };
+            (Some(port), None) | (None, Some(port)) => port,
+            (None, None) => unreachable!("Non-local destinations always have a route"),
+        }
+    }
+
+    /// Record an object being sent out of a port of the node and return the
+    /// index of the router port that leads to it.
+    fn send_to(&self, dest_port: usize) -> usize {
+        if dest_port < Port::Ingress as usize {
+            let in_flight = &self.state.in_flight[dest_port];
+            in_flight.set(in_flight.get() + 1);
+        }
 
         assert_ne!(
             dest_port, self.index,
@@ -197,10 +300,57 @@ where
         // egress[0] | 4     | 3, 3, 3, 3, -, 4,
         // egress[1] | 5     | 4, 4, 4, 4, 4, -,
         if dest_port > self.index {
-            Ok(dest_port - 1)
+            dest_port - 1
         } else {
-            Ok(dest_port)
+            dest_port
+        }
+    }
+
+    /// Record an object arriving at the router
+    fn arrived(&self) {
+        if self.index < Port::Ingress as usize {
+            self.state.arrived_from(self.index);
+        }
+    }
+}
+
+impl<T> Route<T> for NodeRouter
+where
+    T: SimObject + Routable,
+{
+    /// Route an object to the right egress port on the router. The [FabricNode]
+    /// is constructed with [Arbiter]s and [Router]s that have N-1 ports
+    /// (where N is the total number of ports on the [FabricNode]). There
+    /// are N-1 ports because it is invalid to route to oneself.
+    ///
+    /// As a result it is necessary to remap indices from the computed egress
+    /// port to the router port. This depends on the index of this router.
+    fn route(&self, object: &T) -> Result<usize, SimError> {
+        self.arrived();
+        let dest_port = self.node_port(object.destination() as usize);
+        Ok(self.send_to(dest_port))
+    }
+
+    /// Route a multicast object so that it is only replicated at the node
+    /// where the routes to its destinations diverge.
+    fn route_destinations(
+        &self,
+        _object: &T,
+        destinations: &[u64],
+    ) -> Result<Vec<(usize, Vec<u64>)>, SimError> {
+        self.arrived();
+        let mut branches: Vec<(usize, Vec<u64>)> = Vec::new();
+        for &destination in destinations {
+            let dest_port = self.node_port(destination as usize);
+            match branches.iter_mut().find(|(port, _)| *port == dest_port) {
+                Some((_, branch)) => branch.push(destination),
+                None => branches.push((dest_port, vec![destination])),
+            }
         }
+        Ok(branches
+            .into_iter()
+            .map(|(dest_port, branch)| (self.send_to(dest_port), branch))
+            .collect())
     }
 }
 
@@ -214,6 +364,18 @@ pub enum Port {
     Ingress,
 }
 
+impl Port {
+    /// The ports that connect a node to its neighbours, in index order
+    pub const DIRECTIONS: [Port; 4] =
+        [Port::ColMinus, Port::ColPlus, Port::RowMinus, Port::RowPlus];
+
+    /// Return the index of the port on the other side of a link
+    fn opposite(port: usize) -> usize {
+        // Ports come in minus/plus pairs
+        port ^ 1
+    }
+}
+
 impl fmt::Display for Port {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // For to_string() use a name in the form of other entities
@@ -228,6 +390,66 @@ impl fmt::Display for Port {
     }
 }
 
+/// How objects are switched through the routers and arbiters of a node
+pub trait Switching<T>
+where
+    T: SimObject + Routable,
+{
+    /// Wrap the arbitration policy for an output of the node
+    fn policy(&self, policy: Box<dyn Arbitrate<T>>) -> Box<dyn Arbitrate<T>>;
+
+    /// Wrap the routing algorithm for an input of the node
+    fn algorithm(&self, algorithm: Box<dyn Route<T>>) -> Box<dyn Route<T>>;
+}
+
+/// Each object is routed and arbitrated as a whole
+pub struct StoreAndForward;
+
+impl<T> Switching<T> for StoreAndForward
+where
+    T: SimObject + Routable,
+{
+    fn policy(&self, policy: Box<dyn Arbitrate<T>>) -> Box<dyn Arbitrate<T>> {
+        policy
+    }
+
+    fn algorithm(&self, algorithm: Box<dyn Route<T>>) -> Box<dyn Route<T>> {
+        algorithm
+    }
+}
+
+/// The head flit of each packet allocates the path through the node and
+/// holds it until the tail flit has passed
+pub struct Wormhole;
+
+impl<T> Switching<Flit<T>> for Wormhole
+where
+    T: SimObject + Routable,
+{
+    fn policy(&self, policy: Box<dyn Arbitrate<Flit<T>>>) -> Box<dyn Arbitrate<Flit<T>>> {
+        Box::new(policy::Wormhole::new(policy))
+    }
+
+    fn algorithm(&self, algorithm: Box<dyn Route<Flit<T>>>) -> Box<dyn Route<Flit<T>>> {
+        Box::new(WormholeRoute::new(algorithm))
+    }
+}
+
+/// Create the arbitration policy for an output of a node
+fn node_policy<T>(
+    switching: &dyn Switching<T>,
+    traffic_classes: Option<&TrafficClassRoundRobin>,
+) -> Box<dyn Arbitrate<T>>
+where
+    T: SimObject + Routable,
+{
+    let policy: Box<dyn Arbitrate<T>> = match traffic_classes {
+        Some(policy) => Box::new(policy.clone()),
+        None => Box::new(RoundRobin::new()),
+    };
+    switching.policy(policy)
+}
+
 type RouterArbiterResult<T> = (Rc<Arbiter<T>>, Rc<Router<T>>);
 
 #[expect(clippy::too_many_arguments)]
@@ -241,19 +463,22 @@ fn router_arbiter<T>(
     router_arbiter_index: usize,
     node_col: usize,
     node_row: usize,
+    state: Rc<NodeState>,
+    switching: &dyn Switching<T>,
+    traffic_classes: Option<&TrafficClassRoundRobin>,
     name: &str,
 ) -> RouterArbiterResult<T>
 where
     T: SimObject + Routable,
 {
-    let policy = Box::new(RoundRobin::new());
-    let algorithm = Box::new(NodeRouter {
+    let algorithm = switching.algorithm(Box::new(NodeRouter {
         index: router_arbiter_index,
         node_col,
         node_row,
         fabric_algorithm,
         config,
-    });
+        state,
+    }));
     (
         Arbiter::new_and_register(
             engine,
@@ -261,7 +486,7 @@ where
             node,
             &format!("arb_{name}"),
             num_arbiter_router_ports,
-            policy,
+            node_policy(switching, traffic_classes),
         ),
         Router::new_and_register(
             engine,
@@ -288,6 +513,9 @@ fn create_arbiters_routers<T>(
     num_ingress_egress_ports: usize,
     node_col: usize,
     node_row: usize,
+    state: &Rc<NodeState>,
+    switching: &dyn Switching<T>,
+    traffic_classes: Option<&TrafficClassRoundRobin>,
 ) -> RoutersArbitersResult<T>
 where
     T: SimObject + Routable,
@@ -300,10 +528,7 @@ where
     let mut arbiters = Vec::with_capacity(num_arbiters_routers);
     let mut routers = Vec::with_capacity(num_arbiters_routers);
 
-    for (i, port) in vec![Port::ColMinus, Port::ColPlus, Port::RowMinus, Port::RowPlus]
-        .drain(..)
-        .enumerate()
-    {
+    for (i, port) in Port::DIRECTIONS.iter().enumerate() {
         let name = port.to_string();
         let (arbiter, router) = router_arbiter(
             engine,
@@ -315,6 +540,9 @@ where
             i,
             node_col,
             node_row,
+            state.clone(),
+            switching,
+            traffic_classes,
             name.as_str(),
         );
         arbiters.push(arbiter);
@@ -323,22 +551,22 @@ where
 
     for i in 0..num_ingress_egress_ports {
         let ingress_egress_index = i + Port::Ingress as usize;
-        let policy = Box::new(RoundRobin::new());
         arbiters.push(Arbiter::new_and_register(
             engine,
             clock,
             node,
             &format!("arb_{ingress_egress_index}"),
             num_arbiter_router_ports,
-            policy,
+            node_policy(switching, traffic_classes),
         ));
-        let algorithm = Box::new(NodeRouter {
+        let algorithm = switching.algorithm(Box::new(NodeRouter {
             index: ingress_egress_index,
             node_col,
             node_row,
             fabric_algorithm,
             config: config.clone(),
-        });
+            state: state.clone(),
+        }));
         routers.push(Router::new_and_register(
             engine,
             clock,
@@ -352,6 +580,41 @@ where
     (arbiters, routers)
 }
 
+/// Connect a limiter to the buffer it fills, marking the objects that arrive
+/// while the buffer is congested if the fabric has an ECN threshold
+fn connect_limiter_to_buffer<T>(
+    engine: &Engine,
+    clock: &Clock,
+    node: &Rc<Entity>,
+    marker_name: &str,
+    config: &Rc<FabricConfig>,
+    limiter: &Rc<Limiter<T>>,
+    buffer: &Rc<Store<T>>,
+) where
+    T: SimObject + Routable,
+{
+    match config.ecn_threshold_bytes() {
+        Some(threshold_bytes) => {
+            let marker = EcnMarker::new_and_register(
+                engine,
+                clock,
+                node,
+                marker_name,
+                buffer,
+                threshold_bytes,
+            );
+            connect_port!(limiter, tx => marker, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(marker, tx => buffer, rx)
+                .expect("Internal ports should connect without error");
+        }
+        None => {
+            connect_port!(limiter, tx => buffer, rx)
+                .expect("Internal ports should connect without error");
+        }
+    }
+}
+
 type IngressEgressBuffersResult<T> = Result<(Vec<Rc<Limiter<T>>>, Vec<Rc<Store<T>>>), SimError>;
 
 #[expect(clippy::too_many_arguments)]
@@ -391,8 +654,15 @@ where
             &format!("ingress_buf_{i}"),
             config.rx_buffer_bytes,
         )?;
-        connect_port!(ingress_buffer_limiter, tx => ingress_buffer, rx)
-            .expect("Internal ports should connect without error");
+        connect_limiter_to_buffer(
+            engine,
+            clock,
+            node,
+            &format!("mark_ingress_{i}"),
+            config,
+            &ingress_buffer_limiter,
+            &ingress_buffer,
+        );
         connect_port!(ingress_buffer, tx => routers[ingress_egress_index], rx)
             .expect("Internal ports should connect without error");
         ingress_buffer_limiters.push(ingress_buffer_limiter);
@@ -414,8 +684,15 @@ where
             Some(&egress_buffer_aka),
             config.tx_buffer_bytes,
         )?;
-        connect_port!(egress_buffer_limiter, tx => egress_buffer, rx)
-            .expect("Internal ports should connect without error");
+        connect_limiter_to_buffer(
+            engine,
+            clock,
+            node,
+            &format!("mark_egress_{i}"),
+            config,
+            &egress_buffer_limiter,
+            &egress_buffer,
+        );
         connect_port!(arbiters[ingress_egress_index], tx => egress_buffer_limiter, rx)
             .expect("Internal ports should connect without error");
         egress_buffers.push(egress_buffer);
@@ -436,6 +713,8 @@ where
 
     ingress_buffer_limiters: Vec<Rc<Limiter<T>>>,
     egress_buffers: Vec<Rc<Store<T>>>,
+
+    state: Rc<NodeState>,
 }
 
 impl<T> FabricNode<T>
@@ -443,7 +722,7 @@ where
     T: SimObject + Routable,
 {
     #[expect(clippy::too_many_arguments)]
-    pub fn new_and_register_with_renames(
+    pub fn new_and_register_with_switching(
         engine: &Engine,
         clock: &Clock,
         parent: &Rc<Entity>,
@@ -453,10 +732,16 @@ where
         node_row: usize,
         config: &Rc<FabricConfig>,
         fabric_algorithm: FabricRoutingAlgorithm,
+        switching: &dyn Switching<T>,
     ) -> Result<Rc<Self>, SimError> {
         let entity = Rc::new(Entity::new(parent, name));
 
         let num_ingress_egress_ports = config.node_num_ingress_egress_ports(node_col, node_row);
+        let state = Rc::new(NodeState::default());
+        let traffic_classes = config
+            .traffic_classes()
+            .map(|classes| TrafficClassRoundRobin::new(classes.clone()))
+            .transpose()?;
 
         let (arbiters, routers) = create_arbiters_routers(
             engine,
@@ -467,6 +752,9 @@ where
             num_ingress_egress_ports,
             node_col,
             node_row,
+            &state,
+            switching,
+            traffic_classes.as_ref(),
         );
 
         let (ingress_buffer_limiters, egress_buffers) = create_ingress_egress_buffers(
@@ -500,11 +788,38 @@ where
             egress_buffers,
             arbiters,
             routers,
+            state,
         });
         engine.register(rc_self.clone());
         Ok(rc_self)
     }
 
+    #[expect(clippy::too_many_arguments)]
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        node_col: usize,
+        node_row: usize,
+        config: &Rc<FabricConfig>,
+        fabric_algorithm: FabricRoutingAlgorithm,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_switching(
+            engine,
+            clock,
+            parent,
+            name,
+            aka,
+            node_col,
+            node_row,
+            config,
+            fabric_algorithm,
+            &StoreAndForward,
+        )
+    }
+
     #[expect(clippy::too_many_arguments)]
     pub fn new_and_register(
         engine: &Engine,
@@ -529,6 +844,24 @@ where
         )
     }
 
+    /// Set the node connected to `port` so that adaptive routing can track
+    /// the frames in flight on each link
+    pub fn set_neighbour(&self, port: Port, neighbour: &Rc<FabricNode<T>>) {
+        self.state.neighbours.borrow_mut()[port as usize] = Some(Rc::downgrade(&neighbour.state));
+    }
+
+    /// Returns the number of adaptive routing choices that routed by column
+    #[must_use]
+    pub fn num_col_first_choices(&self) -> usize {
+        self.state.num_col_first.get()
+    }
+
+    /// Returns the number of adaptive routing choices that routed by row
+    #[must_use]
+    pub fn num_row_first_choices(&self) -> usize {
+        self.state.num_row_first.get()
+    }
+
     pub fn connect_port_egress_i(&self, i: usize, port_state: PortStateResult<T>) -> SimResult {
         self.egress_buffers[i].connect_port_tx(port_state)
     }
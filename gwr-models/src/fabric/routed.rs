@@ -18,24 +18,47 @@
 //! In order to connect to the fabric use the
 //! `col_row_port_to_fabric_port_index()` function in the configuration
 //! structure to get the index of the port you want to connect to.
+//!
+//! # Switching
+//!
+//! By default each frame is stored and forwarded as a whole at every node. If
+//! the configuration sets a flit size then frames are split into flits by a
+//! [Packetizer] at each ingress port and switched using wormhole switching,
+//! where the head flit allocates the path through each node and the body and
+//! tail flits follow it. Frames are reassembled by a [Depacketizer] at each
+//! egress port.
+//!
+//! # Energy
+//!
+//! If the configuration has an [energy model](crate::energy::EnergyConfig)
+//! then every object crossing a link between two nodes is charged as one hop,
+//! so with wormhole switching the hop energy is used by each flit.
 
+use std::fmt::{self, Display};
 use std::rc::Rc;
 
 use async_trait::async_trait;
-use gwr_components::delay::Delay;
+use gwr_components::packetizer::{Depacketizer, Flit, Packetizer};
 use gwr_components::{connect_dummy_rx, connect_dummy_tx, connect_port};
 use gwr_engine::engine::Engine;
 use gwr_engine::port::PortStateResult;
+use gwr_engine::power::EnergyAccount;
 use gwr_engine::sim_error;
 use gwr_engine::time::clock::Clock;
 use gwr_engine::traits::{Routable, SimObject};
 use gwr_engine::types::{SimError, SimResult};
 use gwr_model_builder::{EntityDisplay, EntityGet, Runnable};
+use gwr_track::build_aka;
 use gwr_track::entity::Entity;
 use gwr_track::tracker::aka::{Aka, populate_aka_from_string};
 
-use crate::fabric::node::{FabricNode, FabricRoutingAlgorithm};
-use crate::fabric::{Fabric, FabricConfig};
+use crate::energy::EnergyMeter;
+use crate::fabric::link::{FabricLink, LinkCounters, LinkStats, LinkStatsDisplay};
+use crate::fabric::node::{
+    FabricNode, FabricRoutingAlgorithm, Port, StoreAndForward, Switching, Wormhole,
+};
+use crate::fabric::{Fabric, FabricConfig, FabricTopology};
+use crate::log_stats;
 
 #[derive(EntityGet, EntityDisplay, Runnable)]
 pub struct RoutedFabric<T>
@@ -43,11 +66,67 @@ where
     T: SimObject + Routable,
 {
     entity: Rc<Entity>,
-    nodes: Vec<Vec<Rc<FabricNode<T>>>>,
+    nodes: RoutedNodes<T>,
+    links: Vec<LinkLocation>,
     config: Rc<FabricConfig>,
+    energy_account: Option<Rc<EnergyAccount>>,
+}
+
+/// The column and row of the node that a link leaves, the direction it leaves
+/// in and the counters of the link
+type LinkLocation = (usize, usize, Port, Rc<LinkCounters>);
+
+type FabricNodes<T> = Vec<Vec<Rc<FabricNode<T>>>>;
+
+/// The nodes of the fabric for each type of switching
+enum RoutedNodes<T>
+where
+    T: SimObject + Routable,
+{
+    StoreAndForward(FabricNodes<T>),
+    Wormhole {
+        nodes: FabricNodes<Flit<T>>,
+        packetizers: Vec<Rc<Packetizer<T>>>,
+        depacketizers: Vec<Rc<Depacketizer<T>>>,
+    },
 }
 
-fn build_node_aka(
+pub struct RouteChoiceStatsDisplay {
+    prefix: String,
+    num_col_first: usize,
+    num_row_first: usize,
+}
+
+impl RouteChoiceStatsDisplay {
+    #[must_use]
+    pub fn new(prefix: impl Into<String>, num_col_first: usize, num_row_first: usize) -> Self {
+        Self {
+            prefix: prefix.into(),
+            num_col_first,
+            num_row_first,
+        }
+    }
+}
+
+impl Display for RouteChoiceStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.num_col_first + self.num_row_first;
+        let col_first_rate = if total == 0 {
+            0.0
+        } else {
+            self.num_col_first as f64 / total as f64 * 100.0
+        };
+
+        writeln!(f, "{}:", self.prefix)?;
+        write!(
+            f,
+            "  Adaptive route choices: {total}, column first: {} ({col_first_rate:.2}%), row first: {}",
+            self.num_col_first, self.num_row_first
+        )
+    }
+}
+
+pub(crate) fn build_node_aka(
     entity: &Rc<Entity>,
     aka: Option<&Aka>,
     new_aka: &mut Aka,
@@ -70,8 +149,11 @@ fn build_node_aka(
     populate_aka_from_string(aka, Some(new_aka), entity, &renames);
 }
 
-type FabricNodesResult<T> = Result<Vec<Vec<Rc<FabricNode<T>>>>, SimError>;
+type FabricNodesResult<T> = Result<FabricNodes<T>, SimError>;
 
+/// Create the grid of nodes. The ingress and egress ports of the nodes are
+/// only given the names of the fabric ports if `fabric_ports` is set.
+#[expect(clippy::too_many_arguments)]
 fn create_nodes<T>(
     engine: &Engine,
     clock: &Clock,
@@ -79,6 +161,8 @@ fn create_nodes<T>(
     aka: Option<&Aka>,
     config: &Rc<FabricConfig>,
     fabric_algorithm: FabricRoutingAlgorithm,
+    switching: &dyn Switching<T>,
+    fabric_ports: bool,
 ) -> FabricNodesResult<T>
 where
     T: SimObject + Routable,
@@ -90,18 +174,22 @@ where
     for c in 0..num_columns {
         let mut col_nodes = Vec::with_capacity(num_rows);
         for r in 0..num_rows {
-            let mut new_aka = Aka::default();
-            build_node_aka(entity, aka, &mut new_aka, c, r, config);
-            let node = FabricNode::new_and_register_with_renames(
+            let new_aka = fabric_ports.then(|| {
+                let mut new_aka = Aka::default();
+                build_node_aka(entity, aka, &mut new_aka, c, r, config);
+                new_aka
+            });
+            let node = FabricNode::new_and_register_with_switching(
                 engine,
                 clock,
                 entity,
                 &format!("node_{c}_{r}"),
-                Some(&new_aka),
+                new_aka.as_ref(),
                 c,
                 r,
                 config,
                 fabric_algorithm,
+                switching,
             )?;
             col_nodes.push(node);
         }
@@ -110,6 +198,17 @@ where
     Ok(nodes)
 }
 
+/// Returns the pairs of adjacent indices to connect in a dimension of `n`
+/// nodes. Each pair is the index of the node on the minus side, the index of
+/// the node on the plus side and a prefix for the link names.
+fn adjacent_pairs(config: &FabricConfig, n: usize) -> Vec<(usize, usize, &'static str)> {
+    let mut pairs: Vec<_> = (1..n).map(|i| (i - 1, i, "")).collect();
+    if config.topology() == FabricTopology::Torus && n > 1 {
+        pairs.push((n - 1, 0, "wrap_"));
+    }
+    pairs
+}
+
 /// Create connections between columns
 fn connect_columns<T>(
     engine: &Engine,
@@ -117,12 +216,12 @@ fn connect_columns<T>(
     entity: &Rc<Entity>,
     config: &Rc<FabricConfig>,
     nodes: &[Vec<Rc<FabricNode<T>>>],
-    delay_ticks: usize,
+    links: &mut Vec<LinkLocation>,
+    energy: Option<&EnergyMeter>,
 ) where
     T: SimObject + Routable,
 {
-    for c in 1..config.num_columns {
-        let c_m1 = c - 1;
+    for (c_m1, c, prefix) in adjacent_pairs(config, config.num_columns) {
         // Clippy suggestion to avoid needless_range_loop results in an unused
         // variable from the iterator and therefore a larger refactor is likely
         // required here. There may be hazards to be aware of when attempting
@@ -130,29 +229,38 @@ fn connect_columns<T>(
         // https://github.com/rust-lang/rust-clippy/issues/16344.
         #[expect(clippy::needless_range_loop)]
         for r in 0..config.num_rows {
-            let delay = Delay::new_and_register(
+            let link = FabricLink::new_and_register_with_energy(
                 engine,
                 clock,
                 entity,
-                &format!("{c_m1}_{r}_to_{c}_{r}"),
-                delay_ticks,
+                &format!("{prefix}{c_m1}_{r}_to_{c}_{r}"),
+                config.cycles_per_hop,
+                config.port_bits_per_tick,
+                energy.cloned(),
             );
-            connect_port!(nodes[c_m1][r], col_plus => delay, rx)
+            connect_port!(nodes[c_m1][r], col_plus => link, rx)
                 .expect("Internal ports should connect without error");
-            connect_port!(delay, tx => nodes[c][r], col_minus)
+            connect_port!(link, tx => nodes[c][r], col_minus)
                 .expect("Internal ports should connect without error");
+            links.push((c_m1, r, Port::ColPlus, link.counters()));
 
-            let delay = Delay::new_and_register(
+            let link = FabricLink::new_and_register_with_energy(
                 engine,
                 clock,
                 entity,
-                &format!("{c}_{r}_to_{c_m1}_{r}"),
-                delay_ticks,
+                &format!("{prefix}{c}_{r}_to_{c_m1}_{r}"),
+                config.cycles_per_hop,
+                config.port_bits_per_tick,
+                energy.cloned(),
             );
-            connect_port!(nodes[c][r], col_minus => delay, rx)
+            connect_port!(nodes[c][r], col_minus => link, rx)
                 .expect("Internal ports should connect without error");
-            connect_port!(delay, tx => nodes[c_m1][r], col_plus)
+            connect_port!(link, tx => nodes[c_m1][r], col_plus)
                 .expect("Internal ports should connect without error");
+            links.push((c, r, Port::ColMinus, link.counters()));
+
+            nodes[c][r].set_neighbour(Port::ColMinus, &nodes[c_m1][r]);
+            nodes[c_m1][r].set_neighbour(Port::ColPlus, &nodes[c][r]);
         }
     }
 }
@@ -164,36 +272,45 @@ fn connect_rows<T>(
     entity: &Rc<Entity>,
     config: &Rc<FabricConfig>,
     nodes: &[Vec<Rc<FabricNode<T>>>],
-    delay_ticks: usize,
+    links: &mut Vec<LinkLocation>,
+    energy: Option<&EnergyMeter>,
 ) where
     T: SimObject + Routable,
 {
     for (c, col) in nodes.iter().enumerate() {
-        for r in 1..config.num_rows {
-            let r_m1 = r - 1;
-            let delay = Delay::new_and_register(
+        for (r_m1, r, prefix) in adjacent_pairs(config, config.num_rows) {
+            let link = FabricLink::new_and_register_with_energy(
                 engine,
                 clock,
                 entity,
-                &format!("{c}_{r_m1}_to_{c}_{r}"),
-                delay_ticks,
+                &format!("{prefix}{c}_{r_m1}_to_{c}_{r}"),
+                config.cycles_per_hop,
+                config.port_bits_per_tick,
+                energy.cloned(),
             );
-            connect_port!(col[r_m1], row_plus => delay, rx)
+            connect_port!(col[r_m1], row_plus => link, rx)
                 .expect("Internal ports should connect without error");
-            connect_port!(delay, tx => col[r], row_minus)
+            connect_port!(link, tx => col[r], row_minus)
                 .expect("Internal ports should connect without error");
+            links.push((c, r_m1, Port::RowPlus, link.counters()));
 
-            let delay = Delay::new_and_register(
+            let link = FabricLink::new_and_register_with_energy(
                 engine,
                 clock,
                 entity,
-                &format!("{c}_{r}_to_{c}_{r_m1}"),
-                delay_ticks,
+                &format!("{prefix}{c}_{r}_to_{c}_{r_m1}"),
+                config.cycles_per_hop,
+                config.port_bits_per_tick,
+                energy.cloned(),
             );
-            connect_port!(col[r], row_minus => delay, rx)
+            connect_port!(col[r], row_minus => link, rx)
                 .expect("Internal ports should connect without error");
-            connect_port!(delay, tx => col[r_m1], row_plus)
+            connect_port!(link, tx => col[r_m1], row_plus)
                 .expect("Internal ports should connect without error");
+            links.push((c, r, Port::RowMinus, link.counters()));
+
+            col[r].set_neighbour(Port::RowMinus, &col[r_m1]);
+            col[r_m1].set_neighbour(Port::RowPlus, &col[r]);
         }
     }
 }
@@ -208,35 +325,142 @@ fn create_dummy_ports<T>(
 ) where
     T: SimObject + Routable,
 {
-    // Connect dummy ports left/right
-    let right = config.num_columns - 1;
-    // Clippy suggestion to avoid needless_range_loop doesn't account for the
-    // way nodes is accessed, i.e. nodes[0][r] and nodes[right][r] and therefore
-    // a larger refactor is likely required here.
-    #[expect(clippy::needless_range_loop)]
-    for r in 0..config.num_rows {
-        connect_dummy_tx!(entity => nodes[0][r], col_minus)
-            .expect("Internal ports should connect without error");
-        connect_dummy_rx!(nodes[0][r], col_minus => engine, clock, entity)
-            .expect("Internal ports should connect without error");
-        connect_dummy_tx!(entity => nodes[right][r], col_plus)
-            .expect("Internal ports should connect without error");
-        connect_dummy_rx!(nodes[right][r], col_plus => engine, clock, entity)
-            .expect("Internal ports should connect without error");
+    let torus = config.topology() == FabricTopology::Torus;
+
+    // Connect dummy ports left/right unless they wrap around
+    if !torus || config.num_columns == 1 {
+        let right = config.num_columns - 1;
+        // Clippy suggestion to avoid needless_range_loop doesn't account for the
+        // way nodes is accessed, i.e. nodes[0][r] and nodes[right][r] and therefore
+        // a larger refactor is likely required here.
+        #[expect(clippy::needless_range_loop)]
+        for r in 0..config.num_rows {
+            connect_dummy_tx!(entity => nodes[0][r], col_minus)
+                .expect("Internal ports should connect without error");
+            connect_dummy_rx!(nodes[0][r], col_minus => engine, clock, entity)
+                .expect("Internal ports should connect without error");
+            connect_dummy_tx!(entity => nodes[right][r], col_plus)
+                .expect("Internal ports should connect without error");
+            connect_dummy_rx!(nodes[right][r], col_plus => engine, clock, entity)
+                .expect("Internal ports should connect without error");
+        }
+    }
+
+    // Connect dummy ports top/bottom unless they wrap around
+    if !torus || config.num_rows == 1 {
+        let bottom = config.num_rows - 1;
+        for col in nodes {
+            connect_dummy_tx!(entity => col[0], row_minus)
+                .expect("Internal ports should connect without error");
+            connect_dummy_rx!(col[0], row_minus => engine, clock, entity)
+                .expect("Internal ports should connect without error");
+            connect_dummy_tx!(entity => col[bottom], row_plus)
+                .expect("Internal ports should connect without error");
+            connect_dummy_rx!(col[bottom], row_plus => engine, clock, entity)
+                .expect("Internal ports should connect without error");
+        }
     }
+}
+
+/// Create the grid of nodes and the links between them
+#[expect(clippy::too_many_arguments)]
+fn create_network<T>(
+    engine: &Engine,
+    clock: &Clock,
+    entity: &Rc<Entity>,
+    aka: Option<&Aka>,
+    config: &Rc<FabricConfig>,
+    fabric_algorithm: FabricRoutingAlgorithm,
+    switching: &dyn Switching<T>,
+    fabric_ports: bool,
+    links: &mut Vec<LinkLocation>,
+    energy: Option<&EnergyMeter>,
+) -> FabricNodesResult<T>
+where
+    T: SimObject + Routable,
+{
+    let nodes = create_nodes(
+        engine,
+        clock,
+        entity,
+        aka,
+        config,
+        fabric_algorithm,
+        switching,
+        fabric_ports,
+    )?;
+    connect_columns(engine, clock, entity, config, &nodes, links, energy);
+    connect_rows(engine, clock, entity, config, &nodes, links, energy);
+    create_dummy_ports(engine, clock, entity, config, &nodes);
+    Ok(nodes)
+}
+
+/// Create a network that switches flits and put a packetizer and depacketizer
+/// on each fabric port
+#[expect(clippy::too_many_arguments)]
+fn create_wormhole_network<T>(
+    engine: &Engine,
+    clock: &Clock,
+    entity: &Rc<Entity>,
+    aka: Option<&Aka>,
+    config: &Rc<FabricConfig>,
+    fabric_algorithm: FabricRoutingAlgorithm,
+    flit_bytes: usize,
+    links: &mut Vec<LinkLocation>,
+    energy: Option<&EnergyMeter>,
+) -> Result<RoutedNodes<T>, SimError>
+where
+    T: SimObject + Routable,
+{
+    let nodes = create_network(
+        engine,
+        clock,
+        entity,
+        aka,
+        config,
+        fabric_algorithm,
+        &Wormhole,
+        false,
+        links,
+        energy,
+    )?;
 
-    // Connect dummy ports top/bottom
-    let bottom = config.num_rows - 1;
-    for col in nodes {
-        connect_dummy_tx!(entity => col[0], row_minus)
-            .expect("Internal ports should connect without error");
-        connect_dummy_rx!(col[0], row_minus => engine, clock, entity)
-            .expect("Internal ports should connect without error");
-        connect_dummy_tx!(entity => col[bottom], row_plus)
-            .expect("Internal ports should connect without error");
-        connect_dummy_rx!(col[bottom], row_plus => engine, clock, entity)
-            .expect("Internal ports should connect without error");
+    let num_ports = config.max_num_ports();
+    let mut packetizers = Vec::with_capacity(num_ports);
+    let mut depacketizers = Vec::with_capacity(num_ports);
+    for i in 0..num_ports {
+        let packetizer_aka = build_aka!(aka, entity, &[(&format!("ingress_{i}"), "rx")]);
+        let packetizer = Packetizer::new_and_register_with_renames(
+            engine,
+            clock,
+            entity,
+            &format!("packetizer_{i}"),
+            Some(&packetizer_aka),
+            flit_bytes,
+        )?;
+        let depacketizer_aka = build_aka!(aka, entity, &[(&format!("egress_{i}"), "tx")]);
+        let depacketizer = Depacketizer::new_and_register_with_renames(
+            engine,
+            clock,
+            entity,
+            &format!("depacketizer_{i}"),
+            Some(&depacketizer_aka),
+        );
+
+        let (c, r, p) = config.fabric_port_index_to_col_row_port(i);
+        if p < config.node_num_ingress_egress_ports(c, r) {
+            packetizer.connect_port_tx(nodes[c][r].port_ingress_i(p))?;
+            nodes[c][r].connect_port_egress_i(p, depacketizer.port_rx())?;
+        }
+        packetizers.push(packetizer);
+        depacketizers.push(depacketizer);
     }
+
+    Ok(RoutedNodes::Wormhole {
+        nodes,
+        packetizers,
+        depacketizers,
+    })
 }
 
 impl<T> RoutedFabric<T>
@@ -258,29 +482,48 @@ where
             return sim_error!("Cannot create fabric with less than 2 ports");
         }
 
-        let nodes = create_nodes(engine, clock, &entity, aka, &config, fabric_algorithm)?;
-        connect_columns(
-            engine,
-            clock,
-            &entity,
-            &config,
-            &nodes,
-            config.cycles_per_hop,
-        );
-        connect_rows(
-            engine,
-            clock,
-            &entity,
-            &config,
-            &nodes,
-            config.cycles_per_hop,
-        );
-        create_dummy_ports(engine, clock, &entity, &config, &nodes);
+        // Every node draws the idle power of the fabric
+        let energy = config.energy().map(|energy| {
+            let num_nodes = config.num_columns * config.num_rows;
+            let account = engine
+                .power()
+                .create_account(&entity, energy.idle_power_mw() * num_nodes as f64);
+            EnergyMeter::new(&account, *energy)
+        });
+
+        let mut links = Vec::new();
+        let nodes = match config.flit_bytes() {
+            None => RoutedNodes::StoreAndForward(create_network(
+                engine,
+                clock,
+                &entity,
+                aka,
+                &config,
+                fabric_algorithm,
+                &StoreAndForward,
+                true,
+                &mut links,
+                energy.as_ref(),
+            )?),
+            Some(flit_bytes) => create_wormhole_network(
+                engine,
+                clock,
+                &entity,
+                aka,
+                &config,
+                fabric_algorithm,
+                flit_bytes,
+                &mut links,
+                energy.as_ref(),
+            )?,
+        };
 
         let rc_self = Rc::new(Self {
             entity,
             nodes,
+            links,
             config,
+            energy_account: energy.map(|meter| meter.account().clone()),
         });
 
         engine.register(rc_self.clone());
@@ -305,6 +548,75 @@ where
             fabric_algorithm,
         )
     }
+
+    /// Returns the number of adaptive routing choices that routed by column
+    #[must_use]
+    pub fn num_col_first_choices(&self) -> usize {
+        match &self.nodes {
+            RoutedNodes::StoreAndForward(nodes) => nodes
+                .iter()
+                .flatten()
+                .map(|node| node.num_col_first_choices())
+                .sum(),
+            RoutedNodes::Wormhole { nodes, .. } => nodes
+                .iter()
+                .flatten()
+                .map(|node| node.num_col_first_choices())
+                .sum(),
+        }
+    }
+
+    /// Returns the number of adaptive routing choices that routed by row
+    #[must_use]
+    pub fn num_row_first_choices(&self) -> usize {
+        match &self.nodes {
+            RoutedNodes::StoreAndForward(nodes) => nodes
+                .iter()
+                .flatten()
+                .map(|node| node.num_row_first_choices())
+                .sum(),
+            RoutedNodes::Wormhole { nodes, .. } => nodes
+                .iter()
+                .flatten()
+                .map(|node| node.num_row_first_choices())
+                .sum(),
+        }
+    }
+
+    /// Returns the telemetry of each link between nodes along with the
+    /// column and row of the node it leaves and the direction it leaves in
+    #[must_use]
+    pub fn link_stats(&self) -> Vec<(usize, usize, Port, LinkStats)> {
+        self.links
+            .iter()
+            .map(|(col, row, direction, counters)| (*col, *row, *direction, counters.stats()))
+            .collect()
+    }
+
+    /// Returns the account recording the energy of the fabric when it has an
+    /// energy model
+    #[must_use]
+    pub fn energy_account(&self) -> Option<&Rc<EnergyAccount>> {
+        self.energy_account.as_ref()
+    }
+
+    pub fn dump_stats(&self) {
+        log_stats(
+            &self.entity,
+            RouteChoiceStatsDisplay::new(
+                format!("Fabric {}", self.entity.full_name()),
+                self.num_col_first_choices(),
+                self.num_row_first_choices(),
+            ),
+        );
+        log_stats(
+            &self.entity,
+            LinkStatsDisplay::new(
+                format!("Fabric {} links", self.entity.full_name()),
+                self.link_stats(),
+            ),
+        );
+    }
 }
 
 impl<T> Fabric<T> for RoutedFabric<T>
@@ -312,13 +624,25 @@ where
     T: SimObject + Routable,
 {
     fn connect_port_egress_i(&self, i: usize, port_state: PortStateResult<T>) -> SimResult {
-        let (c, r, p) = self.config.fabric_port_index_to_col_row_port(i);
-        self.nodes[c][r].connect_port_egress_i(p, port_state)
+        match &self.nodes {
+            RoutedNodes::StoreAndForward(nodes) => {
+                let (c, r, p) = self.config.fabric_port_index_to_col_row_port(i);
+                nodes[c][r].connect_port_egress_i(p, port_state)
+            }
+            RoutedNodes::Wormhole { depacketizers, .. } => {
+                depacketizers[i].connect_port_tx(port_state)
+            }
+        }
     }
 
     fn port_ingress_i(&self, i: usize) -> PortStateResult<T> {
-        let (c, r, p) = self.config.fabric_port_index_to_col_row_port(i);
-        self.nodes[c][r].port_ingress_i(p)
+        match &self.nodes {
+            RoutedNodes::StoreAndForward(nodes) => {
+                let (c, r, p) = self.config.fabric_port_index_to_col_row_port(i);
+                nodes[c][r].port_ingress_i(p)
+            }
+            RoutedNodes::Wormhole { packetizers, .. } => packetizers[i].port_rx(),
+        }
     }
 
     fn col_row_port_to_fabric_port_index(&self, col: usize, row: usize, port: usize) -> usize {
@@ -9,6 +9,12 @@
 //! throughput, the buffer should be large enough to overcome the round trip
 //! latency of the credit loop.
 //!
+//! Credits are accounted in buffer entries by default, so every frame uses one
+//! credit whatever its size. When the buffer is shared by frames of mixed sizes
+//! the credits can instead be [accounted in bytes](CreditUnit::Bytes), where
+//! each frame uses one credit per byte of its
+//! [total size](gwr_engine::traits::TotalBytes::total_bytes).
+//!
 //! # Ports
 //!
 //! This component has two ports:
@@ -22,8 +28,8 @@ use async_trait::async_trait;
 use gwr_components::delay::Delay;
 use gwr_components::flow_controls::credit_issuer::CreditIssuer;
 use gwr_components::flow_controls::credit_limiter::CreditLimiter;
-use gwr_components::store::ObjectStore;
-use gwr_components::types::Credit;
+use gwr_components::store::{ByteStore, ObjectStore};
+use gwr_components::types::{Credit, byte_credits};
 use gwr_components::{connect_port, connect_tx, port_rx};
 use gwr_engine::engine::Engine;
 use gwr_engine::port::PortStateResult;
@@ -35,11 +41,23 @@ use gwr_track::build_aka;
 use gwr_track::entity::Entity;
 use gwr_track::tracker::aka::Aka;
 
+/// The unit in which the buffer size and credits of a pipeline are accounted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CreditUnit {
+    /// One credit per frame
+    #[default]
+    Entries,
+
+    /// One credit per byte of each frame
+    Bytes,
+}
+
 /// Configuration for a flow-controlled pipeline.
 pub struct FcPipelineConfig {
     buffer_size: usize,
     data_delay_ticks: usize,
     credit_delay_ticks: usize,
+    credit_unit: CreditUnit,
 }
 
 impl FcPipelineConfig {
@@ -49,8 +67,26 @@ impl FcPipelineConfig {
             buffer_size,
             data_delay_ticks,
             credit_delay_ticks,
+            credit_unit: CreditUnit::default(),
         }
     }
+
+    /// Set the unit of the buffer size and credits
+    #[must_use]
+    pub fn with_credit_unit(mut self, credit_unit: CreditUnit) -> Self {
+        self.credit_unit = credit_unit;
+        self
+    }
+
+    #[must_use]
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    #[must_use]
+    pub fn credit_unit(&self) -> CreditUnit {
+        self.credit_unit
+    }
 }
 
 /// The Flow-Controlled Pipeline.
@@ -96,8 +132,15 @@ where
         // never have to stall at their outputs
         data_delay.set_error_on_output_stall();
 
-        let buffer =
-            ObjectStore::new_and_register(engine, clock, &entity, "buf", config.buffer_size)?;
+        let buffer = match config.credit_unit {
+            CreditUnit::Entries => {
+                ObjectStore::new_and_register(engine, clock, &entity, "buf", config.buffer_size)?
+            }
+            CreditUnit::Bytes => {
+                credit_limiter.set_credit_size(byte_credits);
+                ByteStore::new_and_register(engine, clock, &entity, "buf", config.buffer_size)?
+            }
+        };
 
         connect_port!(credit_limiter, tx => data_delay, rx)
             .expect("Internal ports should connect without error");
@@ -112,6 +155,9 @@ where
             "credit_issuer",
             Some(&credit_issuer_aka),
         );
+        if config.credit_unit == CreditUnit::Bytes {
+            credit_issuer.set_credit_size(byte_credits);
+        }
         let credit_delay = Delay::new_and_register(
             engine,
             clock,
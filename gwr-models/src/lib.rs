@@ -8,14 +8,22 @@ use std::rc::Rc;
 use gwr_track::entity::Entity;
 use gwr_track::info;
 
+pub mod collective;
+pub mod congestion;
+pub mod energy;
 pub mod ethernet_frame;
 pub mod ethernet_link;
 pub mod fabric;
 pub mod fc_pipeline;
+pub mod link_compression;
+pub mod link_errors;
 pub mod memory;
+pub mod pcap;
+pub mod priority_queues;
 pub mod processing_element;
 pub mod registers;
 pub mod ring_node;
+pub mod scatter_gather;
 pub mod test_helpers;
 
 pub fn log_stats(entity: &Rc<Entity>, stats: impl Display) {
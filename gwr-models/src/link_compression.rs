@@ -0,0 +1,301 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Compression of the frames crossing a link.
+//!
+//! Each frame has a [compressibility](Compressible::compressibility), the ratio
+//! of the uncompressed to compressed size of its payload. A [Compressor] at the
+//! start of a link compresses the payload of each frame by its compressibility,
+//! limited to the maximum ratio that the compression hardware supports, which
+//! reduces the bytes that are sent on the wire. A [Decompressor] at the end of
+//! the link restores the original size of the frame.
+//!
+//! # Ports
+//!
+//! Both components have two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Runnable, SimObject};
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::trace;
+use gwr_track::tracker::aka::Aka;
+
+use crate::log_stats;
+
+/// Objects whose payload can be compressed while crossing a link
+pub trait Compressible {
+    /// The ratio of the uncompressed to compressed size of the payload. A
+    /// value of 1.0 means that the payload is incompressible.
+    fn compressibility(&self) -> f64;
+
+    /// Compress the payload by `ratio`, reducing its total bytes
+    fn compress(&mut self, ratio: f64);
+
+    /// Restore the uncompressed size of the payload
+    fn decompress(&mut self);
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionConfig {
+    max_ratio: f64,
+}
+
+impl CompressionConfig {
+    /// Compress payloads by up to `max_ratio`
+    #[must_use]
+    pub fn new(max_ratio: f64) -> Self {
+        Self { max_ratio }
+    }
+
+    #[must_use]
+    pub fn max_ratio(&self) -> f64 {
+        self.max_ratio
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct CompressionMetrics {
+    num_frames: usize,
+    uncompressed_bytes: usize,
+    compressed_bytes: usize,
+}
+
+pub struct CompressionStatsDisplay {
+    prefix: String,
+    num_frames: usize,
+    uncompressed_bytes: usize,
+    compressed_bytes: usize,
+}
+
+impl CompressionStatsDisplay {
+    #[must_use]
+    pub fn new(
+        prefix: impl Into<String>,
+        num_frames: usize,
+        uncompressed_bytes: usize,
+        compressed_bytes: usize,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            num_frames,
+            uncompressed_bytes,
+            compressed_bytes,
+        }
+    }
+}
+
+impl Display for CompressionStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ratio = if self.compressed_bytes == 0 {
+            1.0
+        } else {
+            self.uncompressed_bytes as f64 / self.compressed_bytes as f64
+        };
+        writeln!(f, "{}:", self.prefix)?;
+        write!(
+            f,
+            "  Frames: {}, uncompressed bytes: {}, compressed bytes: {}, ratio: {ratio:.2}",
+            self.num_frames, self.uncompressed_bytes, self.compressed_bytes
+        )
+    }
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct Compressor<T>
+where
+    T: SimObject + Compressible,
+{
+    entity: Rc<Entity>,
+    config: CompressionConfig,
+    metrics: RefCell<CompressionMetrics>,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> Compressor<T>
+where
+    T: SimObject + Compressible,
+{
+    /// Returns a `SimError` if the maximum ratio is less than 1.0.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: &CompressionConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        if config.max_ratio.is_nan() || config.max_ratio < 1.0 {
+            return sim_error!(
+                "{entity}: maximum compression ratio {} must be at least 1.0",
+                config.max_ratio
+            );
+        }
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            config: *config,
+            metrics: RefCell::new(CompressionMetrics::default()),
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Returns a `SimError` if the maximum ratio is less than 1.0.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: &CompressionConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    #[must_use]
+    pub fn num_frames(&self) -> usize {
+        self.metrics.borrow().num_frames
+    }
+
+    /// Returns the total bytes of the frames before compression
+    #[must_use]
+    pub fn uncompressed_bytes(&self) -> usize {
+        self.metrics.borrow().uncompressed_bytes
+    }
+
+    /// Returns the total bytes of the frames sent on the wire
+    #[must_use]
+    pub fn compressed_bytes(&self) -> usize {
+        self.metrics.borrow().compressed_bytes
+    }
+
+    pub fn dump_stats(&self) {
+        let metrics = self.metrics.borrow();
+        log_stats(
+            &self.entity,
+            CompressionStatsDisplay::new(
+                format!("Compression {}", self.entity.full_name()),
+                metrics.num_frames,
+                metrics.uncompressed_bytes,
+                metrics.compressed_bytes,
+            ),
+        );
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for Compressor<T>
+where
+    T: SimObject + Compressible,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let mut frame = rx.get()?.await;
+            let uncompressed_bytes = frame.total_bytes();
+            let ratio = frame.compressibility().clamp(1.0, self.config.max_ratio);
+            frame.compress(ratio);
+            let compressed_bytes = frame.total_bytes();
+            trace!(self.entity ; "Compressed {frame} from {uncompressed_bytes} to {compressed_bytes} bytes");
+            {
+                let mut metrics = self.metrics.borrow_mut();
+                metrics.num_frames += 1;
+                metrics.uncompressed_bytes += uncompressed_bytes;
+                metrics.compressed_bytes += compressed_bytes;
+            }
+            tx.put(frame)?.await;
+        }
+    }
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct Decompressor<T>
+where
+    T: SimObject + Compressible,
+{
+    entity: Rc<Entity>,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> Decompressor<T>
+where
+    T: SimObject + Compressible,
+{
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+    ) -> Rc<Self> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        rc_self
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+    ) -> Rc<Self> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for Decompressor<T>
+where
+    T: SimObject + Compressible,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let mut frame = rx.get()?.await;
+            frame.decompress();
+            tx.put(frame)?.await;
+        }
+    }
+}
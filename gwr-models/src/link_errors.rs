@@ -0,0 +1,275 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Bit-error injection for links.
+//!
+//! Every bit of a frame crossing a link is in error with the configured bit
+//! error rate (BER), so a frame of `n` bits is corrupted with probability
+//! `1 - (1 - BER)^n`. What happens to a corrupted frame depends on whether the
+//! Frame Check Sequence (FCS) is modelled:
+//!  - with the FCS the receiver detects the error and the frame is dropped.
+//!  - without the FCS the frame is delivered but marked as
+//!    [corrupted](Corruptible::is_corrupted) so that higher-level protocols
+//!    can detect it.
+//!
+//! # Ports
+//!
+//! This component has two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Runnable, SimObject};
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::debug;
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::log_stats;
+
+/// Objects that can record that they were corrupted in transit
+pub trait Corruptible {
+    fn set_corrupted(&mut self);
+    fn is_corrupted(&self) -> bool;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BitErrorConfig {
+    bit_error_rate: f64,
+    seed: u64,
+    model_fcs: bool,
+}
+
+impl BitErrorConfig {
+    #[must_use]
+    pub fn new(bit_error_rate: f64, seed: u64) -> Self {
+        Self {
+            bit_error_rate,
+            seed,
+            model_fcs: false,
+        }
+    }
+
+    /// Model the Frame Check Sequence so that corrupted frames are dropped
+    #[must_use]
+    pub fn with_fcs(mut self) -> Self {
+        self.model_fcs = true;
+        self
+    }
+
+    /// Returns a copy of the config using a different seed
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    #[must_use]
+    pub fn bit_error_rate(&self) -> f64 {
+        self.bit_error_rate
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[must_use]
+    pub fn model_fcs(&self) -> bool {
+        self.model_fcs
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct BitErrorMetrics {
+    num_frames: usize,
+    num_corrupted: usize,
+    num_dropped: usize,
+}
+
+pub struct BitErrorStatsDisplay {
+    prefix: String,
+    num_frames: usize,
+    num_corrupted: usize,
+    num_dropped: usize,
+}
+
+impl BitErrorStatsDisplay {
+    #[must_use]
+    pub fn new(
+        prefix: impl Into<String>,
+        num_frames: usize,
+        num_corrupted: usize,
+        num_dropped: usize,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            num_frames,
+            num_corrupted,
+            num_dropped,
+        }
+    }
+}
+
+impl Display for BitErrorStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.prefix)?;
+        write!(
+            f,
+            "  Frames: {}, corrupted: {}, dropped: {}",
+            self.num_frames, self.num_corrupted, self.num_dropped
+        )
+    }
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct BitErrors<T>
+where
+    T: SimObject + Corruptible,
+{
+    entity: Rc<Entity>,
+    config: BitErrorConfig,
+    rng: RefCell<StdRng>,
+    metrics: RefCell<BitErrorMetrics>,
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> BitErrors<T>
+where
+    T: SimObject + Corruptible,
+{
+    /// Returns a `SimError` if the bit error rate is not in the range [0, 1].
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: &BitErrorConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        if !(0.0..=1.0).contains(&config.bit_error_rate) {
+            return sim_error!(
+                "{entity}: bit error rate {} must be in the range [0, 1]",
+                config.bit_error_rate
+            );
+        }
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            config: *config,
+            rng: RefCell::new(StdRng::seed_from_u64(config.seed)),
+            metrics: RefCell::new(BitErrorMetrics::default()),
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Returns a `SimError` if the bit error rate is not in the range [0, 1].
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: &BitErrorConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Decide whether a frame of `num_bits` contains any errors
+    fn is_errored(&self, num_bits: usize) -> bool {
+        if self.config.bit_error_rate == 0.0 {
+            return false;
+        }
+        let probability = -(num_bits as f64 * (-self.config.bit_error_rate).ln_1p()).exp_m1();
+        self.rng
+            .borrow_mut()
+            .random_bool(probability.clamp(0.0, 1.0))
+    }
+
+    #[must_use]
+    pub fn num_frames(&self) -> usize {
+        self.metrics.borrow().num_frames
+    }
+
+    #[must_use]
+    pub fn num_corrupted(&self) -> usize {
+        self.metrics.borrow().num_corrupted
+    }
+
+    #[must_use]
+    pub fn num_dropped(&self) -> usize {
+        self.metrics.borrow().num_dropped
+    }
+
+    pub fn dump_stats(&self) {
+        let metrics = self.metrics.borrow();
+        log_stats(
+            &self.entity,
+            BitErrorStatsDisplay::new(
+                format!("Bit errors {}", self.entity.full_name()),
+                metrics.num_frames,
+                metrics.num_corrupted,
+                metrics.num_dropped,
+            ),
+        );
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for BitErrors<T>
+where
+    T: SimObject + Corruptible,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let mut frame = rx.get()?.await;
+            let errored = self.is_errored(frame.total_bytes() * 8);
+            {
+                let mut metrics = self.metrics.borrow_mut();
+                metrics.num_frames += 1;
+                if errored {
+                    metrics.num_corrupted += 1;
+                    if self.config.model_fcs {
+                        metrics.num_dropped += 1;
+                    }
+                }
+            }
+            if errored {
+                if self.config.model_fcs {
+                    debug!(self.entity ; "Dropped {frame} with FCS error");
+                    self.entity.track_enter(frame.id());
+                    continue;
+                }
+                debug!(self.entity ; "Corrupted {frame}");
+                frame.set_corrupted();
+            }
+            tx.put(frame)?.await;
+        }
+    }
+}
@@ -0,0 +1,506 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A per-initiator memory bandwidth regulator.
+//!
+//! The regulator sits on the request path in front of a
+//! [`Memory`](crate::memory::Memory) or [`Cache`](crate::memory::cache::Cache)
+//! and limits the number of bytes that each initiator (identified by the source
+//! device of an access) can send within a fixed window of ticks.
+//!
+//! Requests are buffered per initiator and forwarded in round-robin order
+//! between the initiators that are within their budget. Once an initiator has
+//! used its budget for the current window the [`BandwidthLimit`] determines
+//! what happens to its remaining requests.
+//!
+//! Responses do not pass through the regulator.
+//!
+//! # Ports
+//!
+//! This component has two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::pin::pin;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use futures::{FutureExt, select};
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::events::repeated::Repeated;
+use gwr_engine::executor::Spawner;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::time::compute_adjusted_value_and_rate;
+use gwr_engine::traits::{Event, Runnable, SimObject};
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::trace;
+use gwr_track::tracker::aka::Aka;
+
+use crate::log_stats;
+use crate::memory::memory_map::DeviceId;
+use crate::memory::traits::AccessMemory;
+
+/// What happens to the requests of an initiator that has used its budget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BandwidthLimit {
+    /// Requests are held until the next window.
+    Hard,
+
+    /// Requests are only forwarded when no initiator that is within its budget
+    /// has a request waiting.
+    Soft,
+}
+
+#[derive(Clone)]
+pub struct BandwidthRegulatorConfig {
+    num_entries: usize,
+    window_ticks: u64,
+    budget_bytes: usize,
+    limit: BandwidthLimit,
+    initiator_budgets: Vec<(DeviceId, usize)>,
+}
+
+impl BandwidthRegulatorConfig {
+    /// Create a configuration where every initiator has a budget of
+    /// `budget_bytes` per window of `window_ticks`.
+    #[must_use]
+    pub fn new(
+        num_entries: usize,
+        window_ticks: u64,
+        budget_bytes: usize,
+        limit: BandwidthLimit,
+    ) -> Self {
+        Self {
+            num_entries,
+            window_ticks,
+            budget_bytes,
+            limit,
+            initiator_budgets: Vec::new(),
+        }
+    }
+
+    /// Override the budget for a specific initiator.
+    #[must_use]
+    pub fn with_initiator_budget(mut self, initiator: DeviceId, budget_bytes: usize) -> Self {
+        self.initiator_budgets.push((initiator, budget_bytes));
+        self
+    }
+
+    fn budget_for(&self, initiator: DeviceId) -> usize {
+        self.initiator_budgets
+            .iter()
+            .rev()
+            .find(|(device, _)| *device == initiator)
+            .map_or(self.budget_bytes, |(_, budget)| *budget)
+    }
+}
+
+#[derive(Clone, Default)]
+struct InitiatorMetrics {
+    bytes_forwarded: usize,
+    num_forwarded: usize,
+    num_throttled: usize,
+}
+
+pub struct BandwidthRegulatorStatsDisplay {
+    prefix: String,
+    time_now_ns: f64,
+    initiators: Vec<(DeviceId, usize, usize, usize)>,
+}
+
+impl BandwidthRegulatorStatsDisplay {
+    /// Create a display of the stats for each initiator given as a tuple of
+    /// `(initiator, bytes_forwarded, num_forwarded, num_throttled)`.
+    #[must_use]
+    pub fn new(
+        prefix: impl Into<String>,
+        time_now_ns: f64,
+        initiators: Vec<(DeviceId, usize, usize, usize)>,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            time_now_ns,
+            initiators,
+        }
+    }
+}
+
+impl Display for BandwidthRegulatorStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.prefix)?;
+        for (initiator, bytes_forwarded, num_forwarded, num_throttled) in &self.initiators {
+            let (value, per_second) =
+                compute_adjusted_value_and_rate(self.time_now_ns, *bytes_forwarded);
+            write!(
+                f,
+                "\n  Initiator {}: {bytes_forwarded} bytes, {value:.2}, {per_second:.2}/s, \
+                 {num_forwarded} forwarded, {num_throttled} throttled",
+                initiator.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct Initiator<T> {
+    device: DeviceId,
+    budget_bytes: usize,
+    window: u64,
+    used_bytes: usize,
+    throttled_window: Option<u64>,
+    requests: VecDeque<T>,
+    metrics: InitiatorMetrics,
+}
+
+/// What the regulator should do next.
+enum Forward {
+    Initiator(usize),
+    AfterTicks(u64),
+    Hold,
+}
+
+struct BandwidthRegulatorState<T>
+where
+    T: SimObject + AccessMemory,
+{
+    entity: Rc<Entity>,
+    clock: Clock,
+    config: BandwidthRegulatorConfig,
+    initiators: RefCell<Vec<Initiator<T>>>,
+    next_initiator: RefCell<usize>,
+    changed: Repeated<()>,
+}
+
+impl<T> BandwidthRegulatorState<T>
+where
+    T: SimObject + AccessMemory,
+{
+    fn occupancy(&self) -> usize {
+        self.initiators
+            .borrow()
+            .iter()
+            .map(|initiator| initiator.requests.len())
+            .sum()
+    }
+
+    fn is_full(&self) -> bool {
+        self.occupancy() >= self.config.num_entries
+    }
+
+    fn window_now(&self) -> u64 {
+        self.clock.tick_now().tick() / self.config.window_ticks
+    }
+
+    fn push(&self, access: T) {
+        let device = access.src_device();
+        self.entity.track_enter(access.id());
+
+        let mut initiators = self.initiators.borrow_mut();
+        let index = match initiators.iter().position(|i| i.device == device) {
+            Some(index) => index,
+            None => {
+                initiators.push(Initiator {
+                    device,
+                    budget_bytes: self.config.budget_for(device),
+                    window: 0,
+                    used_bytes: 0,
+                    throttled_window: None,
+                    requests: VecDeque::new(),
+                    metrics: InitiatorMetrics::default(),
+                });
+                initiators.len() - 1
+            }
+        };
+        initiators[index].requests.push_back(access);
+        drop(initiators);
+        self.changed.notify();
+    }
+
+    /// Choose the next initiator to forward a request from.
+    fn next_forward(&self) -> Forward {
+        let window = self.window_now();
+        let mut initiators = self.initiators.borrow_mut();
+        for initiator in initiators.iter_mut() {
+            if initiator.window != window {
+                initiator.window = window;
+                initiator.used_bytes = 0;
+            }
+        }
+
+        let num_initiators = initiators.len();
+        let start = *self.next_initiator.borrow();
+        let waiting: Vec<usize> = (0..num_initiators)
+            .map(|i| (start + i) % num_initiators)
+            .filter(|i| !initiators[*i].requests.is_empty())
+            .collect();
+        if waiting.is_empty() {
+            return Forward::Hold;
+        }
+
+        if let Some(index) = waiting
+            .iter()
+            .find(|i| initiators[**i].used_bytes < initiators[**i].budget_bytes)
+        {
+            return Forward::Initiator(*index);
+        }
+
+        // All waiting initiators have used their budget
+        for index in &waiting {
+            let initiator = &mut initiators[*index];
+            if initiator.throttled_window != Some(window) {
+                initiator.throttled_window = Some(window);
+                initiator.metrics.num_throttled += 1;
+            }
+        }
+        match self.config.limit {
+            BandwidthLimit::Soft => Forward::Initiator(waiting[0]),
+            BandwidthLimit::Hard => {
+                let next_window_tick = (window + 1) * self.config.window_ticks;
+                Forward::AfterTicks(next_window_tick - self.clock.tick_now().tick())
+            }
+        }
+    }
+
+    fn pop(&self, index: usize) -> Option<T> {
+        let mut initiators = self.initiators.borrow_mut();
+        let num_initiators = initiators.len();
+        let initiator = &mut initiators[index];
+        let access = initiator.requests.pop_front()?;
+        let total_bytes = access.total_bytes();
+        initiator.used_bytes += total_bytes;
+        initiator.metrics.bytes_forwarded += total_bytes;
+        initiator.metrics.num_forwarded += 1;
+        drop(initiators);
+
+        *self.next_initiator.borrow_mut() = (index + 1) % num_initiators;
+        self.entity.track_exit(access.id());
+        self.changed.notify();
+        Some(access)
+    }
+
+    fn initiator_metrics(&self, initiator: DeviceId) -> InitiatorMetrics {
+        self.initiators
+            .borrow()
+            .iter()
+            .find(|i| i.device == initiator)
+            .map(|i| i.metrics.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct BandwidthRegulator<T>
+where
+    T: SimObject + AccessMemory,
+{
+    entity: Rc<Entity>,
+    spawner: Spawner,
+    state: Rc<BandwidthRegulatorState<T>>,
+
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> BandwidthRegulator<T>
+where
+    T: SimObject + AccessMemory,
+{
+    /// Create an instance of the regulator and register it with the Engine.
+    ///
+    /// Returns a [`SimError`] if the configuration has no entries, a zero
+    /// length window, or a zero byte budget with a hard limit as that would
+    /// hold requests forever.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: BandwidthRegulatorConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        if config.num_entries == 0 {
+            return sim_error!("Unsupported BandwidthRegulator with 0 entries");
+        }
+        if config.window_ticks == 0 {
+            return sim_error!("Unsupported BandwidthRegulator with 0 tick window");
+        }
+        if config.limit == BandwidthLimit::Hard {
+            if config.budget_bytes == 0 {
+                return sim_error!(
+                    "Unsupported BandwidthRegulator with 0 byte budget and hard limit"
+                );
+            }
+            if let Some((initiator, _)) = config
+                .initiator_budgets
+                .iter()
+                .find(|(_, budget_bytes)| *budget_bytes == 0)
+            {
+                return sim_error!(
+                    "Unsupported BandwidthRegulator with 0 byte budget for initiator {} and hard limit",
+                    initiator.0
+                );
+            }
+        }
+
+        let entity = Rc::new(Entity::new(parent, name));
+        entity.track_capacity(config.num_entries, "entries");
+
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+
+        let state = Rc::new(BandwidthRegulatorState {
+            entity: entity.clone(),
+            clock: clock.clone(),
+            config,
+            initiators: RefCell::new(Vec::new()),
+            next_initiator: RefCell::new(0),
+            changed: Repeated::default(),
+        });
+
+        let rc_self = Rc::new(Self {
+            entity,
+            spawner: engine.spawner(),
+            state,
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Create an instance of the regulator and register it with the Engine.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: BandwidthRegulatorConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Return the number of requests currently held in the regulator.
+    #[must_use]
+    pub fn occupancy(&self) -> usize {
+        self.state.occupancy()
+    }
+
+    /// Return the number of bytes forwarded for an initiator.
+    #[must_use]
+    pub fn bytes_forwarded(&self, initiator: DeviceId) -> usize {
+        self.state.initiator_metrics(initiator).bytes_forwarded
+    }
+
+    /// Return the number of requests forwarded for an initiator.
+    #[must_use]
+    pub fn num_forwarded(&self, initiator: DeviceId) -> usize {
+        self.state.initiator_metrics(initiator).num_forwarded
+    }
+
+    /// Return the number of windows in which an initiator had requests waiting
+    /// after using its budget.
+    #[must_use]
+    pub fn num_throttled(&self, initiator: DeviceId) -> usize {
+        self.state.initiator_metrics(initiator).num_throttled
+    }
+
+    pub fn dump_stats(&self, time_now_ns: f64) {
+        let initiators = self
+            .state
+            .initiators
+            .borrow()
+            .iter()
+            .map(|i| {
+                (
+                    i.device,
+                    i.metrics.bytes_forwarded,
+                    i.metrics.num_forwarded,
+                    i.metrics.num_throttled,
+                )
+            })
+            .collect();
+        log_stats(
+            &self.entity,
+            BandwidthRegulatorStatsDisplay::new(
+                format!("Bandwidth regulator {}", self.entity.full_name()),
+                time_now_ns,
+                initiators,
+            ),
+        );
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for BandwidthRegulator<T>
+where
+    T: SimObject + AccessMemory,
+{
+    async fn run(&self) -> SimResult {
+        let rx = take_option!(self.rx);
+        let state = self.state.clone();
+        self.spawner.spawn(async move { run_rx(rx, state).await });
+
+        let tx = take_option!(self.tx);
+        run_tx(tx, self.state.clone()).await
+    }
+}
+
+async fn run_rx<T>(mut rx: InPort<T>, state: Rc<BandwidthRegulatorState<T>>) -> SimResult
+where
+    T: SimObject + AccessMemory,
+{
+    let changed = state.changed.clone();
+    loop {
+        if state.is_full() {
+            changed.listen().await;
+            continue;
+        }
+
+        let access = rx.get()?.await;
+        trace!(state.entity ; "Request {}", access);
+        state.push(access);
+    }
+}
+
+async fn run_tx<T>(mut tx: OutPort<T>, state: Rc<BandwidthRegulatorState<T>>) -> SimResult
+where
+    T: SimObject + AccessMemory,
+{
+    let changed = state.changed.clone();
+    loop {
+        match state.next_forward() {
+            Forward::Hold => {
+                changed.listen().await;
+            }
+            Forward::AfterTicks(ticks) => {
+                let mut changed = pin!(changed.listen().fuse());
+                let mut window_end = pin!(state.clock.wait_ticks(ticks).fuse());
+                select! {
+                    () = changed => {}
+                    _ = window_end => {}
+                }
+            }
+            Forward::Initiator(index) => {
+                tx.try_put()?.await;
+                if let Some(access) = state.pop(index) {
+                    tx.put(access)?.await;
+                }
+            }
+        }
+    }
+}
@@ -56,8 +56,11 @@ use gwr_track::tracker::aka::Aka;
 use gwr_track::{build_aka, trace};
 
 use crate::log_stats;
+use crate::memory::ecc::{FaultInjectionConfig, FaultInjector};
+use crate::memory::latency::{LatencyHistogram, LatencyTracker};
 #[cfg(test)]
 use crate::memory::memory_access::MemoryAccess;
+use crate::memory::memory_map::DeviceId;
 use crate::memory::traits::{AccessMemory, ReadMemory};
 
 type Tag = u64;
@@ -70,6 +73,8 @@ pub struct CacheConfig {
     num_sets: usize,
     num_ways: usize,
     delay_ticks: usize,
+    fault_injection: Option<FaultInjectionConfig>,
+    latency_histograms: bool,
 }
 
 impl CacheConfig {
@@ -87,8 +92,24 @@ impl CacheConfig {
             num_sets,
             num_ways,
             delay_ticks,
+            fault_injection: None,
+            latency_histograms: false,
         }
     }
+
+    /// Enable fault injection and ECC checking of the cache contents
+    #[must_use]
+    pub fn with_fault_injection(mut self, fault_injection: FaultInjectionConfig) -> Self {
+        self.fault_injection = Some(fault_injection);
+        self
+    }
+
+    /// Record a histogram of response latencies for each initiator
+    #[must_use]
+    pub fn with_latency_histograms(mut self) -> Self {
+        self.latency_histograms = true;
+        self
+    }
 }
 
 #[derive(Clone, Default)]
@@ -327,6 +348,8 @@ where
     spawner: Spawner,
     metrics: Rc<RefCell<CacheMetrics>>,
     contents: Rc<RefCell<CacheContents<T>>>,
+    faults: Option<Rc<FaultInjector>>,
+    latency: Option<Rc<LatencyTracker>>,
 
     response_delay: RefCell<Option<Rc<Delay<T>>>>,
     request_delay: RefCell<Option<Rc<Delay<T>>>>,
@@ -335,6 +358,7 @@ where
     mem_rx: RefCell<Option<InPort<T>>>,
 
     bw_bytes_per_cycle: usize,
+    delay_ticks: usize,
 
     // Internal ports
     req: RefCell<Option<OutPort<T>>>,
@@ -356,6 +380,7 @@ where
         config: CacheConfig,
     ) -> Result<Rc<Self>, SimError> {
         let bw_bytes_per_cycle = config.bw_bytes_per_cycle;
+        let delay_ticks = config.delay_ticks;
         let entity = Rc::new(Entity::new(parent, name));
 
         let policy = Box::new(RoundRobin::new());
@@ -404,6 +429,14 @@ where
         let dev_rx = InPort::new_with_renames(engine, clock, &entity, "dev_rx", aka);
         let mem_rx = InPort::new_with_renames(engine, clock, &entity, "mem_rx", aka);
 
+        let faults = match config.fault_injection.clone() {
+            Some(fault_injection) => Some(Rc::new(FaultInjector::new(&entity, fault_injection)?)),
+            None => None,
+        };
+        let latency = config
+            .latency_histograms
+            .then(|| Rc::new(LatencyTracker::new(&entity)));
+
         let spawner = engine.spawner();
         let rc_self = Rc::new(Self {
             entity,
@@ -411,11 +444,14 @@ where
             spawner,
             metrics: Rc::new(RefCell::new(CacheMetrics::default())),
             contents: Rc::new(RefCell::new(CacheContents::new(config))),
+            faults,
+            latency,
             response_delay: RefCell::new(Some(response_delay)),
             request_delay: RefCell::new(Some(request_delay)),
             dev_rx: RefCell::new(Some(dev_rx)),
             mem_rx: RefCell::new(Some(mem_rx)),
             bw_bytes_per_cycle,
+            delay_ticks,
 
             req: RefCell::new(Some(req)),
             rsp_arb_0: RefCell::new(Some(rsp_arb_0)),
@@ -472,6 +508,19 @@ where
         self.metrics.borrow().num_misses
     }
 
+    /// Returns the fault injector if fault injection is enabled
+    #[must_use]
+    pub fn fault_injector(&self) -> Option<&FaultInjector> {
+        self.faults.as_deref()
+    }
+
+    /// Returns the response latency histogram for an initiator if latency
+    /// histograms are enabled and it has received any responses
+    #[must_use]
+    pub fn latency_histogram(&self, initiator: DeviceId) -> Option<LatencyHistogram> {
+        self.latency.as_ref()?.histogram(initiator)
+    }
+
     pub fn dump_stats(&self, time_now_ns: f64) {
         let metrics = self.metrics.borrow();
         log_stats(
@@ -485,6 +534,12 @@ where
                 metrics.num_misses,
             ),
         );
+        if let Some(faults) = &self.faults {
+            faults.dump_stats();
+        }
+        if let Some(latency) = &self.latency {
+            latency.dump_stats();
+        }
     }
 }
 
@@ -497,7 +552,10 @@ where
     clock: Clock,
     contents: Rc<RefCell<CacheContents<T>>>,
     metrics: Rc<RefCell<CacheMetrics>>,
+    faults: Option<Rc<FaultInjector>>,
+    latency: Option<Rc<LatencyTracker>>,
     bw_bytes_per_cycle: usize,
+    delay_ticks: usize,
 }
 
 #[async_trait(?Send)]
@@ -514,7 +572,10 @@ where
                 clock: self.clock.clone(),
                 contents: self.contents.clone(),
                 metrics: self.metrics.clone(),
+                faults: self.faults.clone(),
+                latency: self.latency.clone(),
                 bw_bytes_per_cycle: self.bw_bytes_per_cycle,
+                delay_ticks: self.delay_ticks,
             };
             let req = take_option!(self.req);
             let rsp_arb_1 = take_option!(self.rsp_arb_1);
@@ -529,7 +590,10 @@ where
             clock: self.clock.clone(),
             contents: self.contents.clone(),
             metrics: self.metrics.clone(),
+            faults: self.faults.clone(),
+            latency: self.latency.clone(),
             bw_bytes_per_cycle: self.bw_bytes_per_cycle,
+            delay_ticks: self.delay_ticks,
         };
         let rsp_arb_0 = take_option!(self.rsp_arb_0);
         run_mem_rx(state, rsp_arb_0).await
@@ -545,8 +609,11 @@ where
     T: SimObject + AccessMemory,
 {
     loop {
-        let request = state.rx.get()?.await;
+        let mut request = state.rx.get()?.await;
         trace!(state.entity ; "Device request {}", request);
+        if state.latency.is_some() && request.issue_tick().is_none() {
+            request.set_issue_tick(state.clock.tick_now().tick());
+        }
         let total_bytes = request.total_bytes();
         handle_request(&state, &mut req, &mut rsp_arb_1, request).await?;
         let ticks = total_bytes.div_ceil(state.bw_bytes_per_cycle);
@@ -574,8 +641,13 @@ where
             let line_state = state.contents.borrow().state_for(addr);
             match line_state {
                 Some(EntryState::ValidData) => {
-                    let response = request.to_response(state.contents.as_ref())?;
-                    rsp_arb_1.put(response)?.await;
+                    let mut response = request.to_response(state.contents.as_ref())?;
+                    if let Some(faults) = &state.faults {
+                        let tick = state.clock.tick_now().tick();
+                        let outcome = faults.read(tick, addr, request.access_size_bytes());
+                        faults.poison(outcome, &mut response);
+                    }
+                    send_response(state, rsp_arb_1, response).await?;
                     state.metrics.borrow_mut().num_hits += 1;
                 }
                 Some(EntryState::Allocated) => {
@@ -595,8 +667,12 @@ where
         }
 
         AccessType::WriteRequest | AccessType::WriteNonPostedRequest => {
-            state.metrics.borrow_mut().payload_bytes_written += request.access_size_bytes();
+            state.metrics.borrow_mut().payload_bytes_written += request.enabled_bytes();
             state.contents.borrow_mut().invalidate(addr);
+            if let Some(faults) = &state.faults {
+                let tick = state.clock.tick_now().tick();
+                faults.write(tick, addr, request.access_size_bytes());
+            }
             req.put(request)?.await;
         }
 
@@ -611,6 +687,28 @@ where
     Ok(())
 }
 
+/// Send a response to the arbiter, recording its latency if enabled
+async fn send_response<T>(
+    state: &RxHandlingState<T>,
+    port: &mut OutPort<T>,
+    response: T,
+) -> SimResult
+where
+    T: SimObject + AccessMemory,
+{
+    let initiator = response.dst_device();
+    let issue_tick = response.issue_tick();
+    port.put(response)?.await;
+    if let Some(latency) = &state.latency
+        && let Some(issue_tick) = issue_tick
+    {
+        // The response will leave the cache after the fixed delay
+        let done_tick = state.clock.tick_now().tick() + state.delay_ticks as u64;
+        latency.record(initiator, done_tick - issue_tick);
+    }
+    Ok(())
+}
+
 async fn run_mem_rx<T>(mut state: RxHandlingState<T>, mut rsp_arb_0: OutPort<T>) -> SimResult
 where
     T: SimObject + AccessMemory,
@@ -640,7 +738,7 @@ where
         }
         AccessType::WriteNonPostedResponse => {
             // Forward this response back to the memory (via the arbiter)
-            rsp_arb_0.put(access)?.await;
+            send_response(state, rsp_arb_0, access).await?;
         }
         AccessType::ReadRequest | AccessType::WriteRequest | AccessType::WriteNonPostedRequest => {
             return sim_error!(
@@ -653,19 +751,28 @@ where
                 .contents
                 .borrow_mut()
                 .set_data_valid(access.dst_addr());
+            if let Some(faults) = &state.faults {
+                // Filling the line overwrites any faults in the old contents
+                let tick = state.clock.tick_now().tick();
+                faults.write(tick, access.dst_addr(), access.access_size_bytes());
+            }
             let matching = state
                 .contents
                 .borrow_mut()
                 .get_requests_waiting_for_response(&access);
+            let poisoned = access.is_poisoned();
 
             // Forward this response back to the memory (via the arbiter)
-            rsp_arb_0.put(access)?.await;
+            send_response(state, rsp_arb_0, access).await?;
 
             // Forward on any other waiting reads that were waiting for this response
             if let Some(m) = matching {
                 for x in m {
-                    let response = x.to_response(state.contents.as_ref())?;
-                    rsp_arb_0.put(response)?.await;
+                    let mut response = x.to_response(state.contents.as_ref())?;
+                    if poisoned {
+                        response.set_poisoned();
+                    }
+                    send_response(state, rsp_arb_0, response).await?;
                 }
             }
         }
@@ -0,0 +1,473 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A CXL.mem-style device memory protocol layer.
+//!
+//! The layer sits between a host and a remote
+//! [`Memory`](crate::memory::Memory) device and carries accesses across a link
+//! using the channels of the CXL.mem protocol:
+//!  - Master to Subordinate (M2S) `Req`: requests without data (reads).
+//!  - Master to Subordinate (M2S) `RwD`: requests with data (writes).
+//!  - Subordinate to Master (S2M) `NDR`: responses without data (write
+//!    completions).
+//!  - Subordinate to Master (S2M) `DRS`: responses with data (read data).
+//!
+//! Each channel is buffered separately so that there is no ordering between
+//! channels, e.g. reads can overtake writes that are waiting for the link. The
+//! channels of each direction then share the bandwidth of the link. Every
+//! message crossing the link is delayed by the host port, the link itself and
+//! the device port, so the round trip adds twice the
+//! [one-way latency](CxlMemConfig::one_way_latency_ticks) to the latency of
+//! the memory.
+//!
+//! Posted writes are carried on the `RwD` channel but, as the host does not
+//! expect a completion for them, no `NDR` is returned.
+//!
+//! # Ports
+//!
+//! This component has four ports:
+//!  - `host_rx`: host-side [input port](gwr_engine::port::InPort) for requests
+//!  - `host_tx`: host-side [output port](gwr_engine::port::OutPort) for
+//!    responses
+//!  - `dev_tx`: device-side [output port](gwr_engine::port::OutPort) for
+//!    requests
+//!  - `dev_rx`: device-side [input port](gwr_engine::port::InPort) for
+//!    responses
+//!
+//! # Diagram
+//!
+//! ```text
+//!            +---------------------------------------------------------+
+//!            |              /-> m2s_req -\                             |
+//! host_rx -> | m2s_router -+             +-> m2s_arb -> m2s_limit ->  |
+//!            |              \-> m2s_rwd -/                  m2s_delay  | -> dev_tx
+//!            |                                                         |
+//!            |              /-> s2m_ndr -\                             |
+//!  dev_rx -> | s2m_router -+             +-> s2m_arb -> s2m_limit ->  |
+//!            |              \-> s2m_drs -/                  s2m_delay  | -> host_tx
+//!            +---------------------------------------------------------+
+//! ```
+
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::arbiter::Arbiter;
+use gwr_components::arbiter::policy::RoundRobin;
+use gwr_components::delay::Delay;
+use gwr_components::flow_controls::limiter::Limiter;
+use gwr_components::router::{Route, Router};
+use gwr_components::store::ByteStore;
+use gwr_components::{connect_port, rc_limiter};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::PortStateResult;
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::SimObject;
+use gwr_engine::types::{AccessType, SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet, Runnable};
+use gwr_track::build_aka;
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+
+use crate::log_stats;
+use crate::memory::traits::AccessMemory;
+
+/// The channels of the CXL.mem protocol.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CxlChannel {
+    /// Host to device requests without data.
+    Req,
+
+    /// Host to device requests with data.
+    RwD,
+
+    /// Device to host responses without data.
+    Ndr,
+
+    /// Device to host responses with data.
+    Drs,
+}
+
+impl CxlChannel {
+    /// Returns the channel that carries accesses of the given type
+    #[must_use]
+    pub fn for_access_type(access_type: AccessType) -> Self {
+        match access_type {
+            AccessType::ReadRequest | AccessType::Control => CxlChannel::Req,
+            AccessType::WriteRequest | AccessType::WriteNonPostedRequest => CxlChannel::RwD,
+            AccessType::WriteNonPostedResponse => CxlChannel::Ndr,
+            AccessType::ReadResponse => CxlChannel::Drs,
+        }
+    }
+
+    /// Returns whether the channel carries messages from host to device
+    #[must_use]
+    pub fn is_m2s(&self) -> bool {
+        matches!(self, CxlChannel::Req | CxlChannel::RwD)
+    }
+
+    /// The name used for the buffer of the channel
+    fn short_name(&self) -> &'static str {
+        match self {
+            CxlChannel::Req => "req",
+            CxlChannel::RwD => "rwd",
+            CxlChannel::Ndr => "ndr",
+            CxlChannel::Drs => "drs",
+        }
+    }
+
+    /// The index of the channel within its direction
+    fn index(&self) -> usize {
+        match self {
+            CxlChannel::Req | CxlChannel::Ndr => 0,
+            CxlChannel::RwD | CxlChannel::Drs => 1,
+        }
+    }
+}
+
+impl Display for CxlChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CxlChannel::Req => write!(f, "M2S Req"),
+            CxlChannel::RwD => write!(f, "M2S RwD"),
+            CxlChannel::Ndr => write!(f, "S2M NDR"),
+            CxlChannel::Drs => write!(f, "S2M DRS"),
+        }
+    }
+}
+
+/// The channels of each direction, in index order
+const M2S_CHANNELS: [CxlChannel; 2] = [CxlChannel::Req, CxlChannel::RwD];
+const S2M_CHANNELS: [CxlChannel; 2] = [CxlChannel::Ndr, CxlChannel::Drs];
+
+#[derive(Clone, Copy, Debug)]
+pub struct CxlMemConfig {
+    link_bits_per_tick: usize,
+    link_latency_ticks: usize,
+    channel_buffer_bytes: usize,
+    host_port_latency_ticks: usize,
+    device_port_latency_ticks: usize,
+}
+
+impl CxlMemConfig {
+    /// Create a config for a link that carries `link_bits_per_tick` in each
+    /// direction with a flight time of `link_latency_ticks`. Each channel is
+    /// buffered with `channel_buffer_bytes`.
+    #[must_use]
+    pub fn new(
+        link_bits_per_tick: usize,
+        link_latency_ticks: usize,
+        channel_buffer_bytes: usize,
+    ) -> Self {
+        Self {
+            link_bits_per_tick,
+            link_latency_ticks,
+            channel_buffer_bytes,
+            host_port_latency_ticks: 0,
+            device_port_latency_ticks: 0,
+        }
+    }
+
+    /// Add the latency of the host port to every message
+    #[must_use]
+    pub fn with_host_port_latency(mut self, ticks: usize) -> Self {
+        self.host_port_latency_ticks = ticks;
+        self
+    }
+
+    /// Add the latency of the device port to every message
+    #[must_use]
+    pub fn with_device_port_latency(mut self, ticks: usize) -> Self {
+        self.device_port_latency_ticks = ticks;
+        self
+    }
+
+    #[must_use]
+    pub fn link_bits_per_tick(&self) -> usize {
+        self.link_bits_per_tick
+    }
+
+    #[must_use]
+    pub fn link_latency_ticks(&self) -> usize {
+        self.link_latency_ticks
+    }
+
+    #[must_use]
+    pub fn channel_buffer_bytes(&self) -> usize {
+        self.channel_buffer_bytes
+    }
+
+    #[must_use]
+    pub fn host_port_latency_ticks(&self) -> usize {
+        self.host_port_latency_ticks
+    }
+
+    #[must_use]
+    pub fn device_port_latency_ticks(&self) -> usize {
+        self.device_port_latency_ticks
+    }
+
+    /// Returns the latency added to a message in either direction, excluding
+    /// the time taken to serialize it onto the link
+    #[must_use]
+    pub fn one_way_latency_ticks(&self) -> usize {
+        self.host_port_latency_ticks + self.link_latency_ticks + self.device_port_latency_ticks
+    }
+}
+
+pub struct CxlMemStatsDisplay {
+    prefix: String,
+    num_messages: [usize; 4],
+}
+
+impl CxlMemStatsDisplay {
+    #[must_use]
+    pub fn new(prefix: impl Into<String>, num_messages: [usize; 4]) -> Self {
+        Self {
+            prefix: prefix.into(),
+            num_messages,
+        }
+    }
+}
+
+impl Display for CxlMemStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.prefix)?;
+        writeln!(
+            f,
+            "  {}: {}, {}: {}",
+            CxlChannel::Req,
+            self.num_messages[0],
+            CxlChannel::RwD,
+            self.num_messages[1]
+        )?;
+        write!(
+            f,
+            "  {}: {}, {}: {}",
+            CxlChannel::Ndr,
+            self.num_messages[2],
+            CxlChannel::Drs,
+            self.num_messages[3]
+        )
+    }
+}
+
+/// Routes each access to the channel that carries it, counting the messages
+/// sent on each channel.
+struct ChannelRouter {
+    name: String,
+    channels: [CxlChannel; 2],
+    num_messages: Rc<RefCell<[usize; 2]>>,
+}
+
+impl<T> Route<T> for ChannelRouter
+where
+    T: AccessMemory,
+{
+    fn route(&self, object: &T) -> Result<usize, SimError> {
+        let access_type = object.access_type();
+        let channel = CxlChannel::for_access_type(access_type);
+        if !self.channels.contains(&channel) {
+            return sim_error!("{}: {access_type} cannot be sent on {channel}", self.name);
+        }
+        let index = channel.index();
+        self.num_messages.borrow_mut()[index] += 1;
+        Ok(index)
+    }
+}
+
+/// The components carrying one direction of the link
+struct Direction<T>
+where
+    T: SimObject + AccessMemory,
+{
+    router: Rc<Router<T>>,
+    delay: Rc<Delay<T>>,
+    num_messages: Rc<RefCell<[usize; 2]>>,
+}
+
+impl<T> Direction<T>
+where
+    T: SimObject + AccessMemory,
+{
+    #[expect(clippy::too_many_arguments)]
+    fn new(
+        engine: &Engine,
+        clock: &Clock,
+        entity: &Rc<Entity>,
+        aka: Option<&Aka>,
+        prefix: &str,
+        ports: (&str, &str),
+        channels: [CxlChannel; 2],
+        config: &CxlMemConfig,
+    ) -> Result<Self, SimError> {
+        let num_messages = Rc::new(RefCell::new([0; 2]));
+        let router_aka = build_aka!(aka, entity, &[(ports.0, "rx")]);
+        let router = Router::new_and_register_with_renames(
+            engine,
+            clock,
+            entity,
+            &format!("{prefix}_router"),
+            Some(&router_aka),
+            channels.len(),
+            Box::new(ChannelRouter {
+                name: format!("{entity}"),
+                channels,
+                num_messages: num_messages.clone(),
+            }),
+        );
+
+        let arbiter = Arbiter::new_and_register(
+            engine,
+            clock,
+            entity,
+            &format!("{prefix}_arb"),
+            channels.len(),
+            Box::new(RoundRobin::new()),
+        );
+        for (i, channel) in channels.iter().enumerate() {
+            let buffer = ByteStore::new_and_register(
+                engine,
+                clock,
+                entity,
+                &format!("{prefix}_{}", channel.short_name()),
+                config.channel_buffer_bytes,
+            )?;
+            connect_port!(router, tx, i => buffer, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(buffer, tx => arbiter, rx, i)
+                .expect("Internal ports should connect without error");
+        }
+
+        let limiter = Limiter::new_and_register(
+            engine,
+            clock,
+            entity,
+            &format!("{prefix}_limit"),
+            rc_limiter!(clock, config.link_bits_per_tick),
+        );
+        let delay_aka = build_aka!(aka, entity, &[(ports.1, "tx")]);
+        let delay = Delay::new_and_register_with_renames(
+            engine,
+            clock,
+            entity,
+            &format!("{prefix}_delay"),
+            Some(&delay_aka),
+            config.one_way_latency_ticks(),
+        );
+        connect_port!(arbiter, tx => limiter, rx)
+            .expect("Internal ports should connect without error");
+        connect_port!(limiter, tx => delay, rx)
+            .expect("Internal ports should connect without error");
+
+        Ok(Self {
+            router,
+            delay,
+            num_messages,
+        })
+    }
+}
+
+#[derive(EntityGet, EntityDisplay, Runnable)]
+pub struct CxlMemLink<T>
+where
+    T: SimObject + AccessMemory,
+{
+    entity: Rc<Entity>,
+    m2s: Direction<T>,
+    s2m: Direction<T>,
+}
+
+impl<T> CxlMemLink<T>
+where
+    T: SimObject + AccessMemory,
+{
+    /// Returns a `SimError` if a channel buffer cannot be created.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: &CxlMemConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let m2s = Direction::new(
+            engine,
+            clock,
+            &entity,
+            aka,
+            "m2s",
+            ("host_rx", "dev_tx"),
+            M2S_CHANNELS,
+            config,
+        )?;
+        let s2m = Direction::new(
+            engine,
+            clock,
+            &entity,
+            aka,
+            "s2m",
+            ("dev_rx", "host_tx"),
+            S2M_CHANNELS,
+            config,
+        )?;
+        let rc_self = Rc::new(Self { entity, m2s, s2m });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Returns a `SimError` if a channel buffer cannot be created.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: &CxlMemConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn port_host_rx(&self) -> PortStateResult<T> {
+        self.m2s.router.port_rx()
+    }
+
+    pub fn connect_port_host_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        self.s2m.delay.connect_port_tx(port_state)
+    }
+
+    pub fn port_dev_rx(&self) -> PortStateResult<T> {
+        self.s2m.router.port_rx()
+    }
+
+    pub fn connect_port_dev_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        self.m2s.delay.connect_port_tx(port_state)
+    }
+
+    /// Returns the number of messages that have been sent on a channel
+    #[must_use]
+    pub fn num_messages(&self, channel: CxlChannel) -> usize {
+        let direction = if channel.is_m2s() {
+            &self.m2s
+        } else {
+            &self.s2m
+        };
+        direction.num_messages.borrow()[channel.index()]
+    }
+
+    pub fn dump_stats(&self) {
+        log_stats(
+            &self.entity,
+            CxlMemStatsDisplay::new(
+                format!("CXL.mem {}", self.entity.full_name()),
+                [
+                    self.num_messages(CxlChannel::Req),
+                    self.num_messages(CxlChannel::RwD),
+                    self.num_messages(CxlChannel::Ndr),
+                    self.num_messages(CxlChannel::Drs),
+                ],
+            ),
+        );
+    }
+}
@@ -0,0 +1,390 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Memory error injection and ECC modelling.
+//!
+//! A [`FaultInjector`] can be attached to a [`Memory`](crate::memory::Memory)
+//! or [`Cache`](crate::memory::cache::Cache) in order to model the
+//! reliability, availability and serviceability (RAS) behaviour of a system.
+//!
+//! Faults are introduced in two ways:
+//!  - Random transient bit flips applied to data as it is read, with each bit
+//!    flipping independently with the configured probability.
+//!  - Scripted faults that flip a number of bits in the ECC word containing a
+//!    given address from a given tick onwards. These persist in the storage
+//!    until the word is written or a correctable error is scrubbed by a read.
+//!
+//! Every ECC word covered by a read is checked using the configured
+//! [`EccScheme`] and the worst [`EccOutcome`] is reported for the access.
+//! Read responses with an error that was detected but could not be corrected
+//! are [poisoned](crate::memory::traits::AccessMemory::set_poisoned) so that
+//! the requester is told that the data is bad.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use gwr_track::entity::Entity;
+use gwr_track::{debug, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::log_stats;
+use crate::memory::traits::AccessMemory;
+
+/// The error correcting code used to protect each word of storage
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EccScheme {
+    /// No protection, all errors go undetected
+    None,
+
+    /// A single parity bit, detects any odd number of flipped bits
+    Parity,
+
+    /// Single error correct, double error detect
+    Secded,
+}
+
+/// The result of checking a word with an [`EccScheme`]
+///
+/// Outcomes are ordered by their effect on an access so that the outcome of
+/// reading a number of words can be found with `max()`. An error detected in
+/// any word poisons the whole access, so it takes precedence over an error
+/// that was not detected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EccOutcome {
+    /// No bits were flipped
+    Clean,
+
+    /// Bits were flipped and the data was corrected
+    Corrected,
+
+    /// Bits were flipped and the error was not detected
+    Undetected,
+
+    /// Bits were flipped and the error was detected but could not be corrected
+    Detected,
+}
+
+impl EccScheme {
+    /// Determine the outcome of reading a word with `num_flipped_bits` errors
+    #[must_use]
+    pub fn check(&self, num_flipped_bits: usize) -> EccOutcome {
+        match (self, num_flipped_bits) {
+            (_, 0) => EccOutcome::Clean,
+            (EccScheme::None, _) => EccOutcome::Undetected,
+            (EccScheme::Parity, n) if n % 2 == 1 => EccOutcome::Detected,
+            (EccScheme::Parity, _) => EccOutcome::Undetected,
+            (EccScheme::Secded, 1) => EccOutcome::Corrected,
+            (EccScheme::Secded, 2) => EccOutcome::Detected,
+            (EccScheme::Secded, _) => EccOutcome::Undetected,
+        }
+    }
+}
+
+/// A fault that flips `num_bits` bits in the word containing `addr` at `tick`
+#[derive(Copy, Clone, Debug)]
+pub struct ScriptedFault {
+    tick: u64,
+    addr: u64,
+    num_bits: usize,
+}
+
+impl ScriptedFault {
+    #[must_use]
+    pub fn new(tick: u64, addr: u64, num_bits: usize) -> Self {
+        Self {
+            tick,
+            addr,
+            num_bits,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FaultInjectionConfig {
+    ecc: EccScheme,
+    word_size_bytes: usize,
+    bit_flip_rate: f64,
+    seed: u64,
+    scripted_faults: Vec<ScriptedFault>,
+}
+
+impl FaultInjectionConfig {
+    #[must_use]
+    pub fn new(ecc: EccScheme, word_size_bytes: usize, bit_flip_rate: f64, seed: u64) -> Self {
+        Self {
+            ecc,
+            word_size_bytes,
+            bit_flip_rate,
+            seed,
+            scripted_faults: Vec::new(),
+        }
+    }
+
+    /// Add a fault to be injected at a specific time and address
+    #[must_use]
+    pub fn with_scripted_fault(mut self, fault: ScriptedFault) -> Self {
+        self.scripted_faults.push(fault);
+        self
+    }
+}
+
+#[derive(Clone, Default)]
+struct EccMetrics {
+    num_bits_flipped: usize,
+    num_corrected: usize,
+    num_detected: usize,
+    num_undetected: usize,
+    num_poisoned: usize,
+}
+
+pub struct EccStatsDisplay {
+    prefix: String,
+    num_bits_flipped: usize,
+    num_corrected: usize,
+    num_detected: usize,
+    num_undetected: usize,
+    num_poisoned: usize,
+}
+
+impl EccStatsDisplay {
+    #[must_use]
+    pub fn new(
+        prefix: impl Into<String>,
+        num_bits_flipped: usize,
+        num_corrected: usize,
+        num_detected: usize,
+        num_undetected: usize,
+        num_poisoned: usize,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            num_bits_flipped,
+            num_corrected,
+            num_detected,
+            num_undetected,
+            num_poisoned,
+        }
+    }
+}
+
+impl Display for EccStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.prefix)?;
+        writeln!(f, "  Bits flipped: {}", self.num_bits_flipped)?;
+        writeln!(
+            f,
+            "  Errors corrected: {}, detected: {}, undetected: {}",
+            self.num_corrected, self.num_detected, self.num_undetected
+        )?;
+        write!(f, "  Responses poisoned: {}", self.num_poisoned)
+    }
+}
+
+/// Injects faults into the words of a storage model and checks them on read
+pub struct FaultInjector {
+    entity: Rc<Entity>,
+    ecc: EccScheme,
+    word_size_bytes: usize,
+    bit_flip_rate: f64,
+    rng: RefCell<StdRng>,
+
+    /// Scripted faults that have not yet reached their injection tick
+    pending: RefCell<VecDeque<ScriptedFault>>,
+
+    /// Number of bits currently flipped in each stored word
+    stored: RefCell<HashMap<u64, usize>>,
+
+    metrics: RefCell<EccMetrics>,
+}
+
+impl FaultInjector {
+    pub fn new(entity: &Rc<Entity>, config: FaultInjectionConfig) -> Result<Self, SimError> {
+        if config.word_size_bytes == 0 {
+            return sim_error!("{entity}: ECC word size must be non-zero");
+        }
+        if !(0.0..=1.0).contains(&config.bit_flip_rate) {
+            return sim_error!(
+                "{entity}: bit flip rate {} must be in the range [0, 1]",
+                config.bit_flip_rate
+            );
+        }
+
+        let mut scripted_faults = config.scripted_faults;
+        scripted_faults.sort_by_key(|fault| fault.tick);
+
+        Ok(Self {
+            entity: entity.clone(),
+            ecc: config.ecc,
+            word_size_bytes: config.word_size_bytes,
+            bit_flip_rate: config.bit_flip_rate,
+            rng: RefCell::new(StdRng::seed_from_u64(config.seed)),
+            pending: RefCell::new(scripted_faults.into()),
+            stored: RefCell::new(HashMap::new()),
+            metrics: RefCell::new(EccMetrics::default()),
+        })
+    }
+
+    fn word_range(&self, addr: u64, size_bytes: usize) -> std::ops::Range<u64> {
+        let word_size = self.word_size_bytes as u64;
+        let first = addr / word_size;
+        let last = (addr + size_bytes.max(1) as u64).div_ceil(word_size);
+        first..last
+    }
+
+    /// Move any scripted faults that are due into storage
+    fn inject_scripted(&self, tick: u64) {
+        let mut pending = self.pending.borrow_mut();
+        let mut stored = self.stored.borrow_mut();
+        while let Some(fault) = pending.front()
+            && fault.tick <= tick
+        {
+            let word = fault.addr / self.word_size_bytes as u64;
+            *stored.entry(word).or_default() += fault.num_bits;
+            self.metrics.borrow_mut().num_bits_flipped += fault.num_bits;
+            debug!(self.entity ; "Injected {} bit fault at 0x{:x}", fault.num_bits, fault.addr);
+            pending.pop_front();
+        }
+    }
+
+    /// Randomly flip bits of a read, returning the number of flips per word
+    fn random_flips(&self, num_words: usize) -> Vec<usize> {
+        let mut flips = vec![0; num_words];
+        if self.bit_flip_rate == 0.0 {
+            return flips;
+        }
+
+        // Rather than sampling every bit, skip ahead by a geometrically
+        // distributed number of bits to the next flipped bit
+        let word_bits = self.word_size_bytes * 8;
+        let num_bits = num_words * word_bits;
+        let log_keep = (1.0 - self.bit_flip_rate).ln();
+        let mut rng = self.rng.borrow_mut();
+        let mut bit = 0;
+        loop {
+            let u: f64 = rng.random();
+            bit += ((1.0 - u).ln() / log_keep).floor() as usize;
+            if bit >= num_bits {
+                break;
+            }
+            flips[bit / word_bits] += 1;
+            bit += 1;
+        }
+        self.metrics.borrow_mut().num_bits_flipped += flips.iter().sum::<usize>();
+        flips
+    }
+
+    /// Record a write to storage, which overwrites any stored faults
+    pub fn write(&self, tick: u64, addr: u64, size_bytes: usize) {
+        self.inject_scripted(tick);
+        let mut stored = self.stored.borrow_mut();
+        for word in self.word_range(addr, size_bytes) {
+            stored.remove(&word);
+        }
+    }
+
+    /// Check a read from storage and return the worst outcome of all the
+    /// words read
+    #[must_use]
+    pub fn read(&self, tick: u64, addr: u64, size_bytes: usize) -> EccOutcome {
+        self.inject_scripted(tick);
+
+        let words = self.word_range(addr, size_bytes);
+        let transient = self.random_flips((words.end - words.start) as usize);
+
+        let mut worst = EccOutcome::Clean;
+        let mut stored = self.stored.borrow_mut();
+        let mut metrics = self.metrics.borrow_mut();
+        for (word, num_transient) in words.zip(transient) {
+            let num_stored = stored.get(&word).copied().unwrap_or_default();
+            let outcome = self.ecc.check(num_stored + num_transient);
+            let word_addr = word * self.word_size_bytes as u64;
+            match outcome {
+                EccOutcome::Clean => {}
+                EccOutcome::Corrected => {
+                    // Corrected data is written back, scrubbing the fault
+                    stored.remove(&word);
+                    metrics.num_corrected += 1;
+                    debug!(self.entity ; "Corrected error at 0x{word_addr:x}");
+                }
+                EccOutcome::Detected => {
+                    metrics.num_detected += 1;
+                    warn!(self.entity ; "Uncorrectable error detected at 0x{word_addr:x}");
+                }
+                EccOutcome::Undetected => {
+                    metrics.num_undetected += 1;
+                    debug!(self.entity ; "Undetected error at 0x{word_addr:x}");
+                }
+            }
+            worst = worst.max(outcome);
+        }
+        worst
+    }
+
+    /// Poison a read `response` if the `outcome` of reading its data was an
+    /// error that could not be corrected
+    pub fn poison<T: AccessMemory>(&self, outcome: EccOutcome, response: &mut T) {
+        if outcome == EccOutcome::Detected {
+            response.set_poisoned();
+            self.metrics.borrow_mut().num_poisoned += 1;
+        }
+    }
+
+    #[must_use]
+    pub fn num_bits_flipped(&self) -> usize {
+        self.metrics.borrow().num_bits_flipped
+    }
+
+    #[must_use]
+    pub fn num_corrected(&self) -> usize {
+        self.metrics.borrow().num_corrected
+    }
+
+    #[must_use]
+    pub fn num_detected(&self) -> usize {
+        self.metrics.borrow().num_detected
+    }
+
+    #[must_use]
+    pub fn num_undetected(&self) -> usize {
+        self.metrics.borrow().num_undetected
+    }
+
+    #[must_use]
+    pub fn num_poisoned(&self) -> usize {
+        self.metrics.borrow().num_poisoned
+    }
+
+    pub fn dump_stats(&self) {
+        let metrics = self.metrics.borrow();
+        log_stats(
+            &self.entity,
+            EccStatsDisplay::new(
+                format!("ECC {}", self.entity.full_name()),
+                metrics.num_bits_flipped,
+                metrics.num_corrected,
+                metrics.num_detected,
+                metrics.num_undetected,
+                metrics.num_poisoned,
+            ),
+        );
+    }
+}
+
+#[test]
+fn secded_outcomes() {
+    assert_eq!(EccScheme::Secded.check(0), EccOutcome::Clean);
+    assert_eq!(EccScheme::Secded.check(1), EccOutcome::Corrected);
+    assert_eq!(EccScheme::Secded.check(2), EccOutcome::Detected);
+    assert_eq!(EccScheme::Secded.check(3), EccOutcome::Undetected);
+}
+
+#[test]
+fn parity_outcomes() {
+    assert_eq!(EccScheme::Parity.check(1), EccOutcome::Detected);
+    assert_eq!(EccScheme::Parity.check(2), EccOutcome::Undetected);
+    assert_eq!(EccScheme::Parity.check(3), EccOutcome::Detected);
+}
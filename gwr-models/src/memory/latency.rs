@@ -0,0 +1,257 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Per-initiator access latency histograms.
+//!
+//! A [`LatencyTracker`] records the latency of every response a memory
+//! component returns, grouped by the device that the response is returned
+//! to. Each sample is also emitted as a value on a per-initiator monitor so
+//! that latency over time can be viewed alongside the rest of a trace.
+//!
+//! Latency is measured from the issue tick carried by the access. Accesses
+//! that were not stamped by their initiator are stamped when they first
+//! arrive at a component that tracks latency.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, EntityMonitor};
+
+use crate::log_stats;
+use crate::memory::memory_map::DeviceId;
+
+/// A histogram of latencies using power-of-two sized buckets
+///
+/// Bucket 0 holds zero latencies and bucket `i` holds latencies in the range
+/// `[2^(i-1), 2^i)`.
+#[derive(Clone, Debug, Default)]
+pub struct LatencyHistogram {
+    buckets: Vec<usize>,
+    count: usize,
+    sum: u64,
+    min: u64,
+    max: u64,
+}
+
+impl LatencyHistogram {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn bucket_for(latency: u64) -> usize {
+        (u64::BITS - latency.leading_zeros()) as usize
+    }
+
+    /// Return the inclusive upper bound of a bucket
+    fn bucket_max(bucket: usize) -> u64 {
+        match bucket {
+            0 => 0,
+            b => u64::MAX >> (u64::BITS as usize - b),
+        }
+    }
+
+    pub fn record(&mut self, latency_ticks: u64) {
+        let bucket = Self::bucket_for(latency_ticks);
+        if self.buckets.len() <= bucket {
+            self.buckets.resize(bucket + 1, 0);
+        }
+        self.buckets[bucket] += 1;
+
+        if self.count == 0 || latency_ticks < self.min {
+            self.min = latency_ticks;
+        }
+        self.max = self.max.max(latency_ticks);
+        self.sum += latency_ticks;
+        self.count += 1;
+    }
+
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    #[must_use]
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    #[must_use]
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum as f64 / self.count as f64
+        }
+    }
+
+    /// Return an upper bound on the latency at percentile `p` (in the range
+    /// 0 to 100). The bound is the top of the bucket containing the
+    /// percentile, limited to the maximum latency seen.
+    #[must_use]
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let target = ((p / 100.0) * self.count as f64).ceil().max(1.0) as usize;
+        let mut seen = 0;
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Self::bucket_max(bucket).min(self.max);
+            }
+        }
+        self.max
+    }
+
+    /// Return the `(min, max, count)` of each non-empty bucket
+    pub fn buckets(&self) -> impl Iterator<Item = (u64, u64, usize)> + '_ {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(bucket, count)| {
+                let min = match bucket {
+                    0 => 0,
+                    b => 1u64 << (b - 1),
+                };
+                (min, Self::bucket_max(bucket), *count)
+            })
+    }
+}
+
+pub struct LatencyStatsDisplay {
+    prefix: String,
+    histograms: Vec<(DeviceId, LatencyHistogram)>,
+}
+
+impl LatencyStatsDisplay {
+    #[must_use]
+    pub fn new(prefix: impl Into<String>, histograms: Vec<(DeviceId, LatencyHistogram)>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            histograms,
+        }
+    }
+}
+
+impl Display for LatencyStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.prefix)?;
+        for (device, histogram) in &self.histograms {
+            write!(
+                f,
+                "\n  Device {}: {} responses, latency min: {}, mean: {:.2}, p50: {}, p99: {}, max: {}",
+                device.0,
+                histogram.count(),
+                histogram.min(),
+                histogram.mean(),
+                histogram.percentile(50.0),
+                histogram.percentile(99.0),
+                histogram.max()
+            )?;
+            for (min, max, count) in histogram.buckets() {
+                write!(f, "\n    [{min}, {max}]: {count}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct InitiatorLatency {
+    monitor: EntityMonitor,
+    histogram: LatencyHistogram,
+}
+
+/// Records the latency of responses for each initiator
+pub struct LatencyTracker {
+    entity: Rc<Entity>,
+    initiators: RefCell<HashMap<DeviceId, InitiatorLatency>>,
+}
+
+impl LatencyTracker {
+    #[must_use]
+    pub fn new(entity: &Rc<Entity>) -> Self {
+        Self {
+            entity: entity.clone(),
+            initiators: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Record a response returned to `initiator` with the given latency
+    pub fn record(&self, initiator: DeviceId, latency_ticks: u64) {
+        let mut initiators = self.initiators.borrow_mut();
+        let latency = initiators
+            .entry(initiator)
+            .or_insert_with(|| InitiatorLatency {
+                monitor: EntityMonitor::new(&self.entity, &format!("latency_dev{}", initiator.0)),
+                histogram: LatencyHistogram::new(),
+            });
+        latency.histogram.record(latency_ticks);
+        latency.monitor.track_value(latency_ticks as f64);
+    }
+
+    /// Return the latency histogram of an initiator
+    #[must_use]
+    pub fn histogram(&self, initiator: DeviceId) -> Option<LatencyHistogram> {
+        self.initiators
+            .borrow()
+            .get(&initiator)
+            .map(|latency| latency.histogram.clone())
+    }
+
+    pub fn dump_stats(&self) {
+        let mut histograms: Vec<_> = self
+            .initiators
+            .borrow()
+            .iter()
+            .map(|(device, latency)| (*device, latency.histogram.clone()))
+            .collect();
+        histograms.sort_by_key(|(device, _)| device.0);
+        log_stats(
+            &self.entity,
+            LatencyStatsDisplay::new(format!("Latency {}", self.entity.full_name()), histograms),
+        );
+    }
+}
+
+#[test]
+fn histogram_buckets() {
+    let mut histogram = LatencyHistogram::new();
+    for latency in [0, 1, 2, 3, 4, 7, 8, 100] {
+        histogram.record(latency);
+    }
+    assert_eq!(histogram.count(), 8);
+    assert_eq!(histogram.min(), 0);
+    assert_eq!(histogram.max(), 100);
+    assert_eq!(
+        histogram.buckets().collect::<Vec<_>>(),
+        vec![
+            (0, 0, 1),
+            (1, 1, 1),
+            (2, 3, 2),
+            (4, 7, 2),
+            (8, 15, 1),
+            (64, 127, 1)
+        ]
+    );
+}
+
+#[test]
+fn histogram_percentiles() {
+    let mut histogram = LatencyHistogram::new();
+    for _ in 0..99 {
+        histogram.record(10);
+    }
+    histogram.record(1000);
+    assert_eq!(histogram.percentile(50.0), 15);
+    assert_eq!(histogram.percentile(99.0), 15);
+    assert_eq!(histogram.percentile(100.0), 1000);
+    assert_eq!(LatencyHistogram::new().percentile(50.0), 0);
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2025 Graphcore Ltd. All rights reserved.
 
 use std::fmt::Display;
+use std::ops::Range;
 use std::rc::Rc;
 
 use gwr_engine::sim_error;
@@ -13,6 +14,71 @@ use gwr_track::{Id, create_id, track_create_object};
 use crate::memory::CacheHintType;
 use crate::memory::memory_map::DeviceId;
 use crate::memory::traits::{AccessMemory, ReadMemory};
+use crate::scatter_gather::ScatterGather;
+
+/// Per-byte write enables for a masked (partial) write.
+///
+/// Each entry determines whether the corresponding byte of the access is
+/// written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ByteEnables {
+    enables: Vec<bool>,
+}
+
+impl ByteEnables {
+    #[must_use]
+    pub fn new(enables: Vec<bool>) -> Self {
+        Self { enables }
+    }
+
+    /// Create enables for an access of `num_bytes` where only the bytes in
+    /// `range` are written.
+    #[must_use]
+    pub fn from_range(num_bytes: usize, range: Range<usize>) -> Self {
+        Self {
+            enables: (0..num_bytes).map(|i| range.contains(&i)).collect(),
+        }
+    }
+
+    /// Return the number of bytes covered by the enables.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.enables.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.enables.is_empty()
+    }
+
+    /// Return the number of bytes that are written.
+    #[must_use]
+    pub fn num_enabled(&self) -> usize {
+        self.enables.iter().filter(|enabled| **enabled).count()
+    }
+
+    #[must_use]
+    pub fn is_enabled(&self, byte: usize) -> bool {
+        self.enables.get(byte).copied().unwrap_or(false)
+    }
+
+    fn concat(
+        first: Option<&Self>,
+        first_len: usize,
+        second: Option<&Self>,
+        second_len: usize,
+    ) -> Self {
+        let mut enables = match first {
+            Some(first) => first.enables.clone(),
+            None => vec![true; first_len],
+        };
+        match second {
+            Some(second) => enables.extend_from_slice(&second.enables),
+            None => enables.extend(std::iter::repeat_n(true, second_len)),
+        }
+        Self { enables }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct MemoryAccess {
@@ -28,15 +94,38 @@ pub struct MemoryAccess {
 
     /// Non-data overhead. Control/Read accesses don't contain any data.
     overhead_size_bytes: usize,
+
+    /// Only set for masked writes which don't write every byte of the access.
+    byte_enables: Option<ByteEnables>,
+
+    /// Only set for accesses of non-contiguous data, in which case the
+    /// destination address is that of the first segment.
+    scatter_gather: Option<ScatterGather>,
+
+    /// Only set for accesses that carry the values of the data, such as
+    /// register reads and writes.
+    data: Option<Vec<u8>>,
+
+    /// The tick at which the access was issued, used to measure latency.
+    issue_tick: Option<u64>,
+
+    /// Set on read responses whose data contains an error that was detected
+    /// but could not be corrected.
+    poisoned: bool,
+
+    traffic_class: usize,
 }
 
 impl Display for MemoryAccess {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}: {}@{:x}",
-            self.access_type, self.access_size_bytes, self.dst_addr
-        )
+        match &self.scatter_gather {
+            Some(scatter_gather) => write!(f, "{}: {scatter_gather}", self.access_type),
+            None => write!(
+                f,
+                "{}: {}@{:x}",
+                self.access_type, self.access_size_bytes, self.dst_addr
+            ),
+        }
     }
 }
 
@@ -46,9 +135,10 @@ impl TotalBytes for MemoryAccess {
             AccessType::Control | AccessType::ReadRequest | AccessType::WriteNonPostedResponse => {
                 self.overhead_size_bytes
             }
-            AccessType::WriteRequest
-            | AccessType::WriteNonPostedRequest
-            | AccessType::ReadResponse => self.access_size_bytes + self.overhead_size_bytes,
+            AccessType::WriteRequest | AccessType::WriteNonPostedRequest => {
+                self.enabled_bytes() + self.overhead_size_bytes
+            }
+            AccessType::ReadResponse => self.access_size_bytes + self.overhead_size_bytes,
         }
     }
 }
@@ -84,6 +174,33 @@ impl AccessMemory for MemoryAccess {
         self.access_size_bytes
     }
 
+    fn enabled_bytes(&self) -> usize {
+        match &self.byte_enables {
+            Some(byte_enables) => byte_enables.num_enabled(),
+            None => self.access_size_bytes,
+        }
+    }
+
+    fn issue_tick(&self) -> Option<u64> {
+        self.issue_tick
+    }
+
+    fn scatter_gather(&self) -> Option<&ScatterGather> {
+        self.scatter_gather.as_ref()
+    }
+
+    fn set_issue_tick(&mut self, tick: u64) {
+        self.issue_tick = Some(tick);
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    fn set_poisoned(&mut self) {
+        self.poisoned = true;
+    }
+
     fn to_response(&self, _mem: &impl ReadMemory) -> Result<Self, SimError> {
         let response_type = match self.access_type {
             AccessType::Control => AccessType::Control,
@@ -106,6 +223,59 @@ impl AccessMemory for MemoryAccess {
             src_device: self.dst_device,
             cache_hint: self.cache_hint,
             overhead_size_bytes: self.overhead_size_bytes,
+            byte_enables: self.byte_enables.clone(),
+            scatter_gather: self.scatter_gather.clone(),
+            data: None,
+            issue_tick: self.issue_tick,
+            poisoned: false,
+            traffic_class: self.traffic_class,
+        })
+    }
+
+    fn combine(&self, other: &Self) -> Option<Self> {
+        if self.access_type != AccessType::WriteRequest
+            || other.access_type != AccessType::WriteRequest
+            || self.dst_device != other.dst_device
+            || self.src_device != other.src_device
+            || self.scatter_gather.is_some()
+            || other.scatter_gather.is_some()
+            || self.data.is_some()
+            || other.data.is_some()
+        {
+            return None;
+        }
+
+        let (first, second) = if self.dst_addr <= other.dst_addr {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        if first.dst_addr + first.access_size_bytes as u64 != second.dst_addr {
+            return None;
+        }
+
+        let byte_enables = if first.byte_enables.is_none() && second.byte_enables.is_none() {
+            None
+        } else {
+            Some(ByteEnables::concat(
+                first.byte_enables.as_ref(),
+                first.access_size_bytes,
+                second.byte_enables.as_ref(),
+                second.access_size_bytes,
+            ))
+        };
+
+        // The combined access keeps the identity of this access
+        Some(MemoryAccess {
+            access_size_bytes: self.access_size_bytes + other.access_size_bytes,
+            dst_addr: first.dst_addr,
+            src_addr: first.src_addr,
+            byte_enables,
+            issue_tick: match (self.issue_tick, other.issue_tick) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+            ..self.clone()
         })
     }
 }
@@ -118,6 +288,9 @@ impl Routable for MemoryAccess {
     fn access_type(&self) -> AccessType {
         self.access_type
     }
+    fn traffic_class(&self) -> usize {
+        self.traffic_class
+    }
 }
 
 impl MemoryAccess {
@@ -132,6 +305,111 @@ impl MemoryAccess {
         dst_device: DeviceId,
         src_device: DeviceId,
         overhead_size_bytes: usize,
+    ) -> Self {
+        Self::create(
+            created_by,
+            access_type,
+            access_size_bytes,
+            dst_addr,
+            src_addr,
+            dst_device,
+            src_device,
+            overhead_size_bytes,
+            None,
+            None,
+        )
+    }
+
+    /// Create a write that only writes the bytes set in `byte_enables`.
+    ///
+    /// The size of the access is the length of the `byte_enables`.
+    #[expect(clippy::too_many_arguments)]
+    pub fn new_masked_write(
+        created_by: &Rc<Entity>,
+        access_type: AccessType,
+        byte_enables: ByteEnables,
+        dst_addr: u64,
+        src_addr: u64,
+        dst_device: DeviceId,
+        src_device: DeviceId,
+        overhead_size_bytes: usize,
+    ) -> Result<Self, SimError> {
+        match access_type {
+            AccessType::WriteRequest | AccessType::WriteNonPostedRequest => {}
+            AccessType::Control
+            | AccessType::ReadRequest
+            | AccessType::ReadResponse
+            | AccessType::WriteNonPostedResponse => {
+                return sim_error!("{access_type}: unsupported by new_masked_write()");
+            }
+        }
+        if byte_enables.is_empty() {
+            return sim_error!("Unsupported masked write with no bytes");
+        }
+
+        Ok(Self::create(
+            created_by,
+            access_type,
+            byte_enables.len(),
+            dst_addr,
+            src_addr,
+            dst_device,
+            src_device,
+            overhead_size_bytes,
+            Some(byte_enables),
+            None,
+        ))
+    }
+
+    /// Create an access of the non-contiguous data described by
+    /// `scatter_gather`.
+    ///
+    /// The size of the access is the total size of the segments and the
+    /// destination address is that of the first segment.
+    pub fn new_scatter_gather(
+        created_by: &Rc<Entity>,
+        access_type: AccessType,
+        scatter_gather: ScatterGather,
+        src_addr: u64,
+        dst_device: DeviceId,
+        src_device: DeviceId,
+        overhead_size_bytes: usize,
+    ) -> Result<Self, SimError> {
+        match access_type {
+            AccessType::ReadRequest
+            | AccessType::WriteRequest
+            | AccessType::WriteNonPostedRequest => {}
+            AccessType::Control | AccessType::ReadResponse | AccessType::WriteNonPostedResponse => {
+                return sim_error!("{access_type}: unsupported by new_scatter_gather()");
+            }
+        }
+
+        Ok(Self::create(
+            created_by,
+            access_type,
+            scatter_gather.num_bytes(),
+            scatter_gather.first_addr(),
+            src_addr,
+            dst_device,
+            src_device,
+            overhead_size_bytes,
+            None,
+            Some(scatter_gather),
+        ))
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    fn create(
+        created_by: &Rc<Entity>,
+        access_type: AccessType,
+        access_size_bytes: usize,
+        dst_addr: u64,
+        src_addr: u64,
+        dst_device: DeviceId,
+        src_device: DeviceId,
+        overhead_size_bytes: usize,
+        byte_enables: Option<ByteEnables>,
+        scatter_gather: Option<ScatterGather>,
     ) -> Self {
         let access = Self {
             created_by: created_by.clone(),
@@ -144,6 +422,12 @@ impl MemoryAccess {
             src_device,
             cache_hint: CacheHintType::Allocate,
             overhead_size_bytes,
+            byte_enables,
+            scatter_gather,
+            data: None,
+            issue_tick: None,
+            poisoned: false,
+            traffic_class: 0,
         };
         track_create_object!(
             created_by;
@@ -155,6 +439,41 @@ impl MemoryAccess {
         );
         access
     }
+
+    /// Stamp the access with the tick at which it was issued.
+    #[must_use]
+    pub fn with_issue_tick(mut self, tick: u64) -> Self {
+        self.issue_tick = Some(tick);
+        self
+    }
+
+    /// Set the traffic class used to arbitrate between accesses. Responses
+    /// are in the same class as their request.
+    #[must_use]
+    pub fn with_traffic_class(mut self, traffic_class: usize) -> Self {
+        self.traffic_class = traffic_class;
+        self
+    }
+
+    /// Attach the values of the data, stored little-endian. Writes carry the
+    /// data written and read responses the data read.
+    #[must_use]
+    pub fn with_data(mut self, data: Vec<u8>) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Return the values of the data if they are carried by the access.
+    #[must_use]
+    pub fn data(&self) -> Option<&[u8]> {
+        self.data.as_deref()
+    }
+
+    /// Return the byte enables if this is a masked write.
+    #[must_use]
+    pub fn byte_enables(&self) -> Option<&ByteEnables> {
+        self.byte_enables.as_ref()
+    }
 }
 
 impl SimObject for MemoryAccess {}
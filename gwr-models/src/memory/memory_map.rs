@@ -4,15 +4,93 @@ use std::collections::BTreeMap;
 
 use gwr_engine::sim_error;
 use gwr_engine::types::SimError;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct DeviceId(pub u64);
 
+/// How consecutive granules of an interleaved region are assigned to devices
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterleaveHash {
+    /// Granules are assigned to devices in round-robin order
+    #[default]
+    Stride,
+
+    /// The round-robin device index is XORed with the higher address bits so
+    /// that large power-of-two strides are spread across devices. Requires a
+    /// power-of-two number of devices.
+    Xor,
+}
+
+/// Describes how a region is interleaved across a number of devices
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interleave {
+    granule_bytes: u64,
+    num_ways: usize,
+    hash: InterleaveHash,
+}
+
+impl Interleave {
+    #[must_use]
+    pub fn new(granule_bytes: u64, num_ways: usize, hash: InterleaveHash) -> Self {
+        Self {
+            granule_bytes,
+            num_ways,
+            hash,
+        }
+    }
+
+    #[must_use]
+    pub fn granule_bytes(&self) -> u64 {
+        self.granule_bytes
+    }
+
+    #[must_use]
+    pub fn num_ways(&self) -> usize {
+        self.num_ways
+    }
+
+    #[must_use]
+    pub fn hash(&self) -> InterleaveHash {
+        self.hash
+    }
+
+    /// Map an offset into the interleaved region to the index of the device
+    /// it belongs to and the offset within that device.
+    #[must_use]
+    pub fn way_and_offset(&self, offset: u64) -> (usize, u64) {
+        let num_ways = self.num_ways as u64;
+        let granule = offset / self.granule_bytes;
+        let row = granule / num_ways;
+        let way = match self.hash {
+            InterleaveHash::Stride => granule % num_ways,
+            InterleaveHash::Xor => {
+                // Fold all the row bits down into the way selection bits
+                let way_bits = num_ways.trailing_zeros();
+                let mut folded = 0;
+                let mut remaining = row;
+                while way_bits > 0 && remaining != 0 {
+                    folded ^= remaining & (num_ways - 1);
+                    remaining >>= way_bits;
+                }
+                (granule % num_ways) ^ folded
+            }
+        };
+        let device_offset = row * self.granule_bytes + offset % self.granule_bytes;
+        (way as usize, device_offset)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct MemoryRegion {
     pub start: u64,
     pub end: u64,
-    pub device: DeviceId,
+
+    /// The devices the region maps to. There is only more than one device if
+    /// the region is interleaved.
+    pub devices: Vec<DeviceId>,
+    pub interleave: Option<Interleave>,
 }
 
 pub struct MemoryMap {
@@ -36,6 +114,56 @@ impl MemoryMap {
 
     /// Map a [start, start+size-1] region to a device.
     pub fn insert(&mut self, start: u64, size: u64, device: DeviceId) -> Result<(), SimError> {
+        self.insert_region(start, size, vec![device], None)
+    }
+
+    /// Map a [start, start+size-1] region interleaved across a set of devices.
+    ///
+    /// The region is split into granules of `granule_bytes` which are
+    /// distributed across the devices according to the `hash`. The region must
+    /// start on a granule boundary.
+    pub fn insert_interleaved(
+        &mut self,
+        start: u64,
+        size: u64,
+        granule_bytes: u64,
+        devices: Vec<DeviceId>,
+        hash: InterleaveHash,
+    ) -> Result<(), SimError> {
+        let num_ways = devices.len();
+        if num_ways == 0 {
+            return sim_error!("No devices to interleave at {start}");
+        }
+        if granule_bytes == 0 {
+            return sim_error!("Invalid interleave granule size {granule_bytes}");
+        }
+        if !start.is_multiple_of(granule_bytes) {
+            return sim_error!(
+                "Interleaved region start 0x{start:x} is not aligned to granules of {granule_bytes} bytes"
+            );
+        }
+        if !size.is_multiple_of(granule_bytes * num_ways as u64) {
+            return sim_error!(
+                "Interleaved region size {size} is not a multiple of {num_ways} granules of {granule_bytes} bytes"
+            );
+        }
+        if hash == InterleaveHash::Xor && !num_ways.is_power_of_two() {
+            return sim_error!(
+                "XOR interleave requires a power-of-two number of devices, not {num_ways}"
+            );
+        }
+
+        let interleave = Interleave::new(granule_bytes, num_ways, hash);
+        self.insert_region(start, size, devices, Some(interleave))
+    }
+
+    fn insert_region(
+        &mut self,
+        start: u64,
+        size: u64,
+        devices: Vec<DeviceId>,
+        interleave: Option<Interleave>,
+    ) -> Result<(), SimError> {
         let end = if size > 0 {
             start + size - 1
         } else {
@@ -56,7 +184,12 @@ impl MemoryMap {
             return sim_error!("Region overlap at {end}");
         }
 
-        let region = MemoryRegion { start, end, device };
+        let region = MemoryRegion {
+            start,
+            end,
+            devices,
+            interleave,
+        };
         self.regions.insert(start, region);
         Ok(())
     }
@@ -72,11 +205,17 @@ impl MemoryMap {
     pub fn lookup(&self, addr: u64) -> Option<(DeviceId, u64)> {
         // Find region with greatest start <= addr
         let (_, region) = self.regions.range(..=addr).next_back()?;
-        if addr <= region.end {
-            let offset = addr - region.start;
-            Some((region.device, offset))
-        } else {
-            None
+        if addr > region.end {
+            return None;
+        }
+
+        let offset = addr - region.start;
+        match &region.interleave {
+            None => Some((region.devices[0], offset)),
+            Some(interleave) => {
+                let (way, device_offset) = interleave.way_and_offset(offset);
+                Some((region.devices[way], device_offset))
+            }
         }
     }
 
@@ -93,7 +232,7 @@ impl MemoryMap {
 
 #[cfg(test)]
 mod tests {
-    use crate::memory::memory_map::{DeviceId, MemoryMap};
+    use crate::memory::memory_map::{DeviceId, InterleaveHash, MemoryMap};
 
     fn setup_map() -> MemoryMap {
         let mut memory_map = MemoryMap::new();
@@ -169,4 +308,58 @@ mod tests {
         let mut memory_map = setup_map();
         memory_map.insert(0x0000_8000, 0x0, DeviceId(4)).unwrap();
     }
+
+    #[test]
+    fn interleaved_stride_lookup() {
+        let mut memory_map = MemoryMap::new();
+        let devices = vec![DeviceId(1), DeviceId(2), DeviceId(3), DeviceId(4)];
+        memory_map
+            .insert_interleaved(0x1_0000, 0x4000, 0x100, devices, InterleaveHash::Stride)
+            .unwrap();
+
+        assert_eq!(memory_map.lookup(0x1_0000), Some((DeviceId(1), 0x0)));
+        assert_eq!(memory_map.lookup(0x1_0104), Some((DeviceId(2), 0x4)));
+        assert_eq!(memory_map.lookup(0x1_0300), Some((DeviceId(4), 0x0)));
+        assert_eq!(memory_map.lookup(0x1_0400), Some((DeviceId(1), 0x100)));
+        assert_eq!(memory_map.lookup(0x1_3fff), Some((DeviceId(4), 0xfff)));
+        assert!(memory_map.lookup(0x1_4000).is_none());
+    }
+
+    #[test]
+    fn interleaved_xor_spreads_strides() {
+        let mut memory_map = MemoryMap::new();
+        let devices = vec![DeviceId(1), DeviceId(2), DeviceId(3), DeviceId(4)];
+        memory_map
+            .insert_interleaved(0x0, 0x4000, 0x100, devices, InterleaveHash::Xor)
+            .unwrap();
+
+        // A stride of one row of granules always hits the same device with a
+        // stride hash but is spread across all devices with an XOR hash
+        let mut devices: Vec<_> = (0..4)
+            .map(|row| memory_map.lookup(row * 0x400).unwrap().0)
+            .collect();
+        devices.sort_by_key(|device| device.0);
+        devices.dedup();
+        assert_eq!(devices.len(), 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "power-of-two")]
+    fn interleaved_xor_needs_power_of_two() {
+        let mut memory_map = MemoryMap::new();
+        let devices = vec![DeviceId(1), DeviceId(2), DeviceId(3)];
+        memory_map
+            .insert_interleaved(0x0, 0x3000, 0x100, devices, InterleaveHash::Xor)
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "not aligned to granules of 256 bytes")]
+    fn interleaved_start_must_be_aligned() {
+        let mut memory_map = MemoryMap::new();
+        let devices = vec![DeviceId(1), DeviceId(2)];
+        memory_map
+            .insert_interleaved(0x80, 0x2000, 0x100, devices, InterleaveHash::Stride)
+            .unwrap();
+    }
 }
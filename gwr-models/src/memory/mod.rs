@@ -20,12 +20,22 @@ use gwr_track::tracker::aka::Aka;
 use gwr_track::{build_aka, debug};
 
 use crate::log_stats;
+use crate::memory::ecc::{EccOutcome, FaultInjectionConfig, FaultInjector};
+use crate::memory::latency::{LatencyHistogram, LatencyTracker};
+use crate::memory::memory_map::{DeviceId, Interleave};
 use crate::memory::traits::{AccessMemory, ReadMemory};
+use crate::scatter_gather::Segment;
 
+pub mod bw_regulator;
 pub mod cache;
+pub mod cxl;
+pub mod ecc;
+pub mod latency;
 pub mod memory_access;
 pub mod memory_access_gen;
 pub mod memory_map;
+pub mod store_buffer;
+pub mod trace_replay;
 pub mod traits;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -40,6 +50,17 @@ pub struct MemoryConfig {
     capacity_bytes: usize,
     bw_bytes_per_cycle: usize,
     delay_ticks: usize,
+    fault_injection: Option<FaultInjectionConfig>,
+    interleave: Option<InterleavedWay>,
+    latency_histograms: bool,
+}
+
+/// The part of an interleaved region that a memory is responsible for
+#[derive(Clone)]
+struct InterleavedWay {
+    region_start: u64,
+    interleave: Interleave,
+    way: usize,
 }
 
 impl MemoryConfig {
@@ -55,8 +76,43 @@ impl MemoryConfig {
             capacity_bytes,
             bw_bytes_per_cycle,
             delay_ticks,
+            fault_injection: None,
+            interleave: None,
+            latency_histograms: false,
         }
     }
+
+    /// Make the memory one `way` of a region starting at `region_start` that
+    /// is interleaved across a number of memories. The memory then accepts
+    /// accesses to the addresses of the region that map to it.
+    #[must_use]
+    pub fn with_interleave(
+        mut self,
+        region_start: u64,
+        interleave: Interleave,
+        way: usize,
+    ) -> Self {
+        self.interleave = Some(InterleavedWay {
+            region_start,
+            interleave,
+            way,
+        });
+        self
+    }
+
+    /// Enable fault injection and ECC checking of the memory contents
+    #[must_use]
+    pub fn with_fault_injection(mut self, fault_injection: FaultInjectionConfig) -> Self {
+        self.fault_injection = Some(fault_injection);
+        self
+    }
+
+    /// Record a histogram of response latencies for each initiator
+    #[must_use]
+    pub fn with_latency_histograms(mut self) -> Self {
+        self.latency_histograms = true;
+        self
+    }
 }
 
 #[derive(Clone, Default)]
@@ -119,6 +175,8 @@ where
     clock: Clock,
     config: MemoryConfig,
     stats: RefCell<MemoryStats>,
+    faults: Option<FaultInjector>,
+    latency: Option<LatencyTracker>,
 
     response_delay: Rc<Delay<T>>,
     response_tx: RefCell<Option<OutPort<T>>>,
@@ -157,11 +215,21 @@ where
             .connect(response_delay.port_rx())
             .expect("Internal ports should connect without error");
 
+        let faults = match config.fault_injection.clone() {
+            Some(fault_injection) => Some(FaultInjector::new(&entity, fault_injection)?),
+            None => None,
+        };
+        let latency = config
+            .latency_histograms
+            .then(|| LatencyTracker::new(&entity));
+
         let rc_self = Rc::new(Self {
             entity,
             clock: clock.clone(),
             config,
             stats: RefCell::new(MemoryStats::default()),
+            faults,
+            latency,
             response_delay,
             rx: RefCell::new(Some(rx)),
             response_tx: RefCell::new(Some(response_tx)),
@@ -208,6 +276,79 @@ where
         self.config.capacity_bytes
     }
 
+    /// Returns the fault injector if fault injection is enabled
+    #[must_use]
+    pub fn fault_injector(&self) -> Option<&FaultInjector> {
+        self.faults.as_ref()
+    }
+
+    /// Returns the response latency histogram for an initiator if latency
+    /// histograms are enabled and it has received any responses
+    #[must_use]
+    pub fn latency_histogram(&self, initiator: DeviceId) -> Option<LatencyHistogram> {
+        self.latency.as_ref()?.histogram(initiator)
+    }
+
+    /// Send a response, recording its latency if enabled
+    async fn send_response(&self, response_tx: &mut OutPort<T>, response: T) -> SimResult {
+        let initiator = response.dst_device();
+        let issue_tick = response.issue_tick();
+        response_tx.put(response)?.await;
+        if let Some(latency) = &self.latency
+            && let Some(issue_tick) = issue_tick
+        {
+            // The response will leave the memory after the fixed delay
+            let done_tick = self.clock.tick_now().tick() + self.config.delay_ticks as u64;
+            latency.record(initiator, done_tick - issue_tick);
+        }
+        Ok(())
+    }
+
+    /// Return an error if any byte of the segment lies outside this memory
+    fn check_segment(&self, segment: &Segment) -> SimResult {
+        let begin = segment.addr;
+        let end = segment.last_addr();
+
+        let config = &self.config;
+        match &config.interleave {
+            None => {
+                let memory_end = config.base_address + config.capacity_bytes as u64;
+                if begin < config.base_address || end >= memory_end {
+                    return sim_error!(
+                        "{}: out of bounds memory access received [0x{begin:x},0x{end:x}] not in [0x{:x},0x{memory_end:x}]",
+                        self.entity,
+                        config.base_address,
+                    );
+                }
+            }
+            Some(interleaved) => {
+                let region_end = interleaved.region_start
+                    + (config.capacity_bytes * interleaved.interleave.num_ways()) as u64;
+                if begin < interleaved.region_start || end >= region_end {
+                    return sim_error!(
+                        "{}: out of bounds memory access received [0x{begin:x},0x{end:x}] not in [0x{:x},0x{region_end:x}]",
+                        self.entity,
+                        interleaved.region_start,
+                    );
+                }
+                let (begin_way, _) = interleaved
+                    .interleave
+                    .way_and_offset(begin - interleaved.region_start);
+                let (end_way, _) = interleaved
+                    .interleave
+                    .way_and_offset(end - interleaved.region_start);
+                if begin_way != interleaved.way || end_way != interleaved.way {
+                    return sim_error!(
+                        "{}: memory access [0x{begin:x},0x{end:x}] does not map to interleave way {}",
+                        self.entity,
+                        interleaved.way
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn dump_stats(&self, time_now_ns: f64) {
         let stats = self.stats.borrow();
         log_stats(
@@ -219,6 +360,12 @@ where
                 stats.bytes_written,
             ),
         );
+        if let Some(faults) = &self.faults {
+            faults.dump_stats();
+        }
+        if let Some(latency) = &self.latency {
+            latency.dump_stats();
+        }
     }
 }
 
@@ -232,36 +379,57 @@ where
         let mut response_tx = take_option!(self.response_tx);
 
         loop {
-            let access = rx.get()?.await;
+            let mut access = rx.get()?.await;
             debug!(self.entity ; "Memory access {}", access);
+            if self.latency.is_some() && access.issue_tick().is_none() {
+                access.set_issue_tick(self.clock.tick_now().tick());
+            }
 
-            let begin = access.dst_addr();
             let payload_bytes = access.access_size_bytes();
-            let end = begin + (payload_bytes as u64) - 1;
-
-            let config = &self.config;
-            assert!(
-                begin >= config.base_address
-                    && end < (config.base_address + config.capacity_bytes as u64),
-                "Out of bounds memory access received [0x{begin:x},0x{end:x}] not in [0x{:x},0x{:x}]",
-                config.base_address,
-                config.base_address + config.capacity_bytes as u64
-            );
+            let segments = match access.scatter_gather() {
+                Some(scatter_gather) => scatter_gather.segments().to_vec(),
+                None => vec![Segment::new(access.dst_addr(), payload_bytes)],
+            };
+            for segment in &segments {
+                self.check_segment(segment)?;
+            }
 
             let access_type = access.access_type();
+            let mut read_outcome = EccOutcome::Clean;
+            if let Some(faults) = &self.faults {
+                let tick = self.clock.tick_now().tick();
+                for segment in &segments {
+                    match access_type {
+                        AccessType::ReadRequest => {
+                            let outcome = faults.read(tick, segment.addr, segment.num_bytes);
+                            read_outcome = read_outcome.max(outcome);
+                        }
+                        AccessType::WriteRequest | AccessType::WriteNonPostedRequest => {
+                            faults.write(tick, segment.addr, segment.num_bytes);
+                        }
+                        AccessType::ReadResponse
+                        | AccessType::WriteNonPostedResponse
+                        | AccessType::Control => {}
+                    }
+                }
+            }
+
             match access_type {
                 AccessType::ReadRequest => {
                     self.stats.borrow_mut().bytes_read += payload_bytes;
-                    let response = access.to_response(self)?;
-                    response_tx.put(response)?.await;
+                    let mut response = access.to_response(self)?;
+                    if let Some(faults) = &self.faults {
+                        faults.poison(read_outcome, &mut response);
+                    }
+                    self.send_response(&mut response_tx, response).await?;
                 }
                 AccessType::WriteRequest => {
-                    self.stats.borrow_mut().bytes_written += payload_bytes;
+                    self.stats.borrow_mut().bytes_written += access.enabled_bytes();
                 }
                 AccessType::WriteNonPostedRequest => {
-                    self.stats.borrow_mut().bytes_written += payload_bytes;
+                    self.stats.borrow_mut().bytes_written += access.enabled_bytes();
                     let response = access.to_response(self)?;
-                    response_tx.put(response)?.await;
+                    self.send_response(&mut response_tx, response).await?;
                 }
                 AccessType::ReadResponse | AccessType::WriteNonPostedResponse => {
                     return sim_error!("{}: unsupported {access_type} received", self.entity);
@@ -271,7 +439,8 @@ where
                 }
             }
 
-            let ticks = payload_bytes.div_ceil(config.bw_bytes_per_cycle) as u64;
+            // Masked writes still occupy the memory for the full access size
+            let ticks = payload_bytes.div_ceil(self.config.bw_bytes_per_cycle) as u64;
             self.clock.wait_ticks(ticks).await;
         }
     }
@@ -0,0 +1,521 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A write-combining store buffer.
+//!
+//! The store buffer sits on the request path between a device (e.g. a
+//! processing element) and its cache. Posted writes are held in the buffer and
+//! later writes to adjacent addresses within the same line are combined into
+//! them. Buffered entries are drained to the `tx` port according to the
+//! configured [`DrainPolicy`].
+//!
+//! Reads and any other accesses are never combined. They are queued in order
+//! behind the buffered writes and force everything ahead of them to drain so
+//! that a read always observes earlier writes. Responses do not pass through
+//! the store buffer.
+//!
+//! ```text
+//!  --------------------------
+//!  |         Device         |
+//!  --------------------------
+//!       |              ^
+//!  -----v-------       |
+//!  |    rx     |       |
+//!  |  Store    |       |
+//!  |  Buffer   |       |
+//!  |    tx     |       |
+//!  -----|-------       |
+//!       v              |
+//!  --------------------------
+//!  |         Cache          |
+//!  --------------------------
+//! ```
+//!
+//! # Ports
+//!
+//! This component has two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::pin::pin;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use futures::{FutureExt, select};
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::events::repeated::Repeated;
+use gwr_engine::executor::Spawner;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Event, Runnable, SimObject};
+use gwr_engine::types::{AccessType, SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::trace;
+use gwr_track::tracker::aka::Aka;
+
+use crate::log_stats;
+use crate::memory::traits::AccessMemory;
+
+/// Policy that determines when buffered writes are drained.
+///
+/// Regardless of the policy, the buffer always drains when it is full or when
+/// a non-write access is waiting behind buffered writes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrainPolicy {
+    /// Drain entries as soon as the `tx` port can accept them.
+    Eager,
+
+    /// Hold entries until the occupancy reaches the given number of entries.
+    Watermark(usize),
+
+    /// Hold each entry for at least the given number of ticks.
+    Timeout(u64),
+}
+
+#[derive(Clone)]
+pub struct StoreBufferConfig {
+    num_entries: usize,
+    line_size_bytes: usize,
+    bw_bytes_per_cycle: usize,
+    drain_policy: DrainPolicy,
+}
+
+impl StoreBufferConfig {
+    #[must_use]
+    pub fn new(
+        num_entries: usize,
+        line_size_bytes: usize,
+        bw_bytes_per_cycle: usize,
+        drain_policy: DrainPolicy,
+    ) -> Self {
+        Self {
+            num_entries,
+            line_size_bytes,
+            bw_bytes_per_cycle,
+            drain_policy,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct StoreBufferMetrics {
+    num_writes: usize,
+    num_combined: usize,
+    num_drained: usize,
+    max_occupancy: usize,
+    occupancy_ticks: u64,
+    last_update_tick: u64,
+}
+
+pub struct StoreBufferStatsDisplay {
+    prefix: String,
+    num_writes: usize,
+    num_combined: usize,
+    num_drained: usize,
+    max_occupancy: usize,
+    average_occupancy: f64,
+}
+
+impl StoreBufferStatsDisplay {
+    #[must_use]
+    pub fn new(
+        prefix: impl Into<String>,
+        num_writes: usize,
+        num_combined: usize,
+        num_drained: usize,
+        max_occupancy: usize,
+        average_occupancy: f64,
+    ) -> Self {
+        Self {
+            prefix: prefix.into(),
+            num_writes,
+            num_combined,
+            num_drained,
+            max_occupancy,
+            average_occupancy,
+        }
+    }
+}
+
+impl Display for StoreBufferStatsDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let combine_rate = if self.num_writes == 0 {
+            0.0
+        } else {
+            self.num_combined as f64 / self.num_writes as f64 * 100.0
+        };
+
+        writeln!(f, "{}:", self.prefix)?;
+        writeln!(
+            f,
+            "  Writes: {}, combined: {}, combine rate: {combine_rate:.2}%",
+            self.num_writes, self.num_combined
+        )?;
+        writeln!(f, "  Drained: {}", self.num_drained)?;
+        write!(
+            f,
+            "  Occupancy: max {}, average {:.2}",
+            self.max_occupancy, self.average_occupancy
+        )
+    }
+}
+
+struct Entry<T> {
+    access: T,
+    arrival_tick: u64,
+}
+
+/// When the head of the buffer should next be drained.
+enum Drain {
+    Now,
+    AfterTicks(u64),
+    Hold,
+}
+
+struct StoreBufferState<T>
+where
+    T: SimObject + AccessMemory,
+{
+    entity: Rc<Entity>,
+    clock: Clock,
+    config: StoreBufferConfig,
+    entries: RefCell<VecDeque<Entry<T>>>,
+    metrics: RefCell<StoreBufferMetrics>,
+    changed: Repeated<()>,
+}
+
+impl<T> StoreBufferState<T>
+where
+    T: SimObject + AccessMemory,
+{
+    fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.len() >= self.config.num_entries
+    }
+
+    fn line_of(&self, addr: u64) -> u64 {
+        addr / self.config.line_size_bytes as u64
+    }
+
+    /// Accumulate the occupancy over time up to the current tick.
+    fn update_occupancy(&self) {
+        let now = self.clock.tick_now().tick();
+        let len = self.len();
+        let mut metrics = self.metrics.borrow_mut();
+        metrics.occupancy_ticks += len as u64 * (now - metrics.last_update_tick);
+        metrics.last_update_tick = now;
+        metrics.max_occupancy = metrics.max_occupancy.max(len);
+    }
+
+    /// Try to combine a write into a buffered write.
+    ///
+    /// Only writes behind the most recent non-write access are considered so
+    /// that no write is ever moved ahead of a read. The search also stops at
+    /// the youngest write that overlaps this one so that overlapping writes
+    /// still reach the cache in the order they arrived.
+    fn try_combine(&self, access: &T) -> bool {
+        if access.access_type() != AccessType::WriteRequest {
+            return false;
+        }
+
+        let mut entries = self.entries.borrow_mut();
+        for entry in entries.iter_mut().rev() {
+            if entry.access.access_type() != AccessType::WriteRequest {
+                break;
+            }
+            if let Some(combined) = entry.access.combine(access) {
+                let first = combined.dst_addr();
+                let last = first + combined.access_size_bytes() as u64 - 1;
+                if self.line_of(first) == self.line_of(last) {
+                    trace!(self.entity ; "Combined {} into {}", access, combined);
+                    entry.access = combined;
+                    return true;
+                }
+            }
+            if overlaps(&entry.access, access) {
+                break;
+            }
+        }
+        false
+    }
+
+    fn push(&self, access: T) {
+        self.update_occupancy();
+        self.entity.track_enter(access.id());
+        self.entries.borrow_mut().push_back(Entry {
+            access,
+            arrival_tick: self.clock.tick_now().tick(),
+        });
+        self.update_occupancy();
+        self.changed.notify();
+    }
+
+    fn pop_front(&self) -> Option<T> {
+        self.update_occupancy();
+        let entry = self.entries.borrow_mut().pop_front()?;
+        self.entity.track_exit(entry.access.id());
+        self.changed.notify();
+        Some(entry.access)
+    }
+
+    fn next_drain(&self) -> Drain {
+        let entries = self.entries.borrow();
+        let Some(head) = entries.front() else {
+            return Drain::Hold;
+        };
+
+        if entries.len() >= self.config.num_entries
+            || entries
+                .iter()
+                .any(|entry| entry.access.access_type() != AccessType::WriteRequest)
+        {
+            return Drain::Now;
+        }
+
+        match self.config.drain_policy {
+            DrainPolicy::Eager => Drain::Now,
+            DrainPolicy::Watermark(level) => {
+                if entries.len() >= level {
+                    Drain::Now
+                } else {
+                    Drain::Hold
+                }
+            }
+            DrainPolicy::Timeout(ticks) => {
+                let age = self.clock.tick_now().tick() - head.arrival_tick;
+                if age >= ticks {
+                    Drain::Now
+                } else {
+                    Drain::AfterTicks(ticks - age)
+                }
+            }
+        }
+    }
+}
+
+/// Return whether two accesses touch any of the same bytes.
+fn overlaps<T: AccessMemory>(a: &T, b: &T) -> bool {
+    let a_end = a.dst_addr() + a.access_size_bytes() as u64;
+    let b_end = b.dst_addr() + b.access_size_bytes() as u64;
+    a.dst_addr() < b_end && b.dst_addr() < a_end
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct StoreBuffer<T>
+where
+    T: SimObject + AccessMemory,
+{
+    entity: Rc<Entity>,
+    spawner: Spawner,
+    state: Rc<StoreBufferState<T>>,
+
+    rx: RefCell<Option<InPort<T>>>,
+    tx: RefCell<Option<OutPort<T>>>,
+}
+
+impl<T> StoreBuffer<T>
+where
+    T: SimObject + AccessMemory,
+{
+    /// Create an instance of the store buffer and register it with the Engine.
+    ///
+    /// Returns a [`SimError`] if the configuration has no entries, a zero line
+    /// size or zero bandwidth.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: StoreBufferConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        if config.num_entries == 0 {
+            return sim_error!("Unsupported StoreBuffer with 0 entries");
+        }
+        if config.line_size_bytes == 0 || config.bw_bytes_per_cycle == 0 {
+            return sim_error!("Unsupported StoreBuffer with 0 line size or bandwidth");
+        }
+
+        let entity = Rc::new(Entity::new(parent, name));
+        entity.track_capacity(config.num_entries, "entries");
+
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+
+        let state = Rc::new(StoreBufferState {
+            entity: entity.clone(),
+            clock: clock.clone(),
+            config,
+            entries: RefCell::new(VecDeque::new()),
+            metrics: RefCell::new(StoreBufferMetrics::default()),
+            changed: Repeated::default(),
+        });
+
+        let rc_self = Rc::new(Self {
+            entity,
+            spawner: engine.spawner(),
+            state,
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Create an instance of the store buffer and register it with the Engine.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: StoreBufferConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Return the number of entries currently held in the buffer.
+    #[must_use]
+    pub fn occupancy(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Return the number of writes received.
+    #[must_use]
+    pub fn num_writes(&self) -> usize {
+        self.state.metrics.borrow().num_writes
+    }
+
+    /// Return the number of writes that were combined into a buffered write.
+    #[must_use]
+    pub fn num_combined(&self) -> usize {
+        self.state.metrics.borrow().num_combined
+    }
+
+    /// Return the number of accesses sent out of the `tx` port.
+    #[must_use]
+    pub fn num_drained(&self) -> usize {
+        self.state.metrics.borrow().num_drained
+    }
+
+    /// Return the highest occupancy seen.
+    #[must_use]
+    pub fn max_occupancy(&self) -> usize {
+        self.state.metrics.borrow().max_occupancy
+    }
+
+    /// Return the time-averaged occupancy up to the current time.
+    #[must_use]
+    pub fn average_occupancy(&self) -> f64 {
+        self.state.update_occupancy();
+        let metrics = self.state.metrics.borrow();
+        if metrics.last_update_tick == 0 {
+            0.0
+        } else {
+            metrics.occupancy_ticks as f64 / metrics.last_update_tick as f64
+        }
+    }
+
+    pub fn dump_stats(&self) {
+        let average_occupancy = self.average_occupancy();
+        let metrics = self.state.metrics.borrow();
+        log_stats(
+            &self.entity,
+            StoreBufferStatsDisplay::new(
+                format!("Store buffer {}", self.entity.full_name()),
+                metrics.num_writes,
+                metrics.num_combined,
+                metrics.num_drained,
+                metrics.max_occupancy,
+                average_occupancy,
+            ),
+        );
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> Runnable for StoreBuffer<T>
+where
+    T: SimObject + AccessMemory,
+{
+    async fn run(&self) -> SimResult {
+        let rx = take_option!(self.rx);
+        let state = self.state.clone();
+        self.spawner.spawn(async move { run_rx(rx, state).await });
+
+        let tx = take_option!(self.tx);
+        run_tx(tx, self.state.clone()).await
+    }
+}
+
+async fn run_rx<T>(mut rx: InPort<T>, state: Rc<StoreBufferState<T>>) -> SimResult
+where
+    T: SimObject + AccessMemory,
+{
+    let changed = state.changed.clone();
+    loop {
+        if state.is_full() {
+            changed.listen().await;
+            continue;
+        }
+
+        let access = rx.get()?.await;
+        trace!(state.entity ; "Request {}", access);
+        if access.access_type() == AccessType::WriteRequest {
+            state.metrics.borrow_mut().num_writes += 1;
+        }
+
+        if state.try_combine(&access) {
+            state.metrics.borrow_mut().num_combined += 1;
+        } else {
+            state.push(access);
+        }
+    }
+}
+
+async fn run_tx<T>(mut tx: OutPort<T>, state: Rc<StoreBufferState<T>>) -> SimResult
+where
+    T: SimObject + AccessMemory,
+{
+    let changed = state.changed.clone();
+    loop {
+        match state.next_drain() {
+            Drain::Hold => {
+                changed.listen().await;
+            }
+            Drain::AfterTicks(ticks) => {
+                let mut changed = pin!(changed.listen().fuse());
+                let mut timeout = pin!(state.clock.wait_ticks(ticks).fuse());
+                select! {
+                    () = changed => {}
+                    _ = timeout => {}
+                }
+            }
+            Drain::Now => {
+                tx.try_put()?.await;
+                if let Some(access) = state.pop_front() {
+                    let total_bytes = access.total_bytes();
+                    state.metrics.borrow_mut().num_drained += 1;
+                    tx.put(access)?.await;
+                    let ticks = total_bytes.div_ceil(state.config.bw_bytes_per_cycle);
+                    state.clock.wait_ticks(ticks as u64).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,433 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Replay a captured memory access trace.
+//!
+//! The [`TraceReplay`] component issues a [`MemoryAccess`] for each
+//! [`TraceRecord`] at the tick given in the trace so that captured workloads
+//! can drive the memory system without a full task graph. If the component
+//! is back-pressured then later records are issued as soon as possible and
+//! counted as late.
+//!
+//! Traces can be provided in two formats:
+//!  - CSV with one `tick,addr,size,op` record per line. Addresses may be
+//!    decimal or `0x` prefixed hex and `op` is one of `R`, `W` or `WNP`
+//!    (non-posted write). Blank lines and lines starting with `#` are ignored.
+//!  - Binary with fixed size little-endian records of `tick: u64`,
+//!    `addr: u64`, `size: u32` and `op: u32` (0 = read, 1 = write,
+//!    2 = non-posted write).
+//!
+//! Records must be in non-decreasing tick order.
+//!
+//! # Ports
+//!
+//! This component has two ports:
+//!  - One [output port](gwr_engine::port::OutPort): `tx` for requests
+//!  - One [input port](gwr_engine::port::InPort): `rx` for responses
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::executor::Spawner;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::Runnable;
+use gwr_engine::types::{AccessType, SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::trace;
+use gwr_track::tracker::aka::Aka;
+
+use crate::memory::memory_access::MemoryAccess;
+use crate::memory::memory_map::MemoryMap;
+use crate::memory::traits::AccessMemory;
+
+/// Size of each record in the binary trace format
+pub const BINARY_RECORD_SIZE_BYTES: usize = 24;
+
+/// A single access from a memory trace
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceRecord {
+    pub tick: u64,
+    pub addr: u64,
+    pub size_bytes: usize,
+    pub access_type: AccessType,
+}
+
+impl TraceRecord {
+    #[must_use]
+    pub fn new(tick: u64, addr: u64, size_bytes: usize, access_type: AccessType) -> Self {
+        Self {
+            tick,
+            addr,
+            size_bytes,
+            access_type,
+        }
+    }
+}
+
+fn parse_u64(value: &str) -> Option<u64> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+fn parse_op(op: &str) -> Option<AccessType> {
+    match op.to_ascii_uppercase().as_str() {
+        "R" => Some(AccessType::ReadRequest),
+        "W" => Some(AccessType::WriteRequest),
+        "WNP" => Some(AccessType::WriteNonPostedRequest),
+        _ => None,
+    }
+}
+
+fn check_ordered(records: &[TraceRecord]) -> Result<(), SimError> {
+    for (i, pair) in records.windows(2).enumerate() {
+        if pair[1].tick < pair[0].tick {
+            return sim_error!(
+                "Trace record {} at tick {} is before the previous record at tick {}",
+                i + 1,
+                pair[1].tick,
+                pair[0].tick
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Parse a trace in the CSV format
+pub fn parse_csv_trace(contents: &str) -> Result<Vec<TraceRecord>, SimError> {
+    let mut records = Vec::new();
+    for (line_num, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [tick, addr, size, op] = fields[..] else {
+            return sim_error!(
+                "Trace line {}: expected 4 fields (tick,addr,size,op), found {}",
+                line_num + 1,
+                fields.len()
+            );
+        };
+
+        let (Some(tick), Some(addr), Some(size_bytes)) =
+            (parse_u64(tick), parse_u64(addr), parse_u64(size))
+        else {
+            return sim_error!("Trace line {}: invalid number in '{line}'", line_num + 1);
+        };
+        let Some(access_type) = parse_op(op) else {
+            return sim_error!(
+                "Trace line {}: unknown op '{op}', expected R, W or WNP",
+                line_num + 1
+            );
+        };
+        records.push(TraceRecord::new(
+            tick,
+            addr,
+            size_bytes as usize,
+            access_type,
+        ));
+    }
+    check_ordered(&records)?;
+    Ok(records)
+}
+
+/// Parse a trace in the binary format
+pub fn parse_binary_trace(contents: &[u8]) -> Result<Vec<TraceRecord>, SimError> {
+    if !contents.len().is_multiple_of(BINARY_RECORD_SIZE_BYTES) {
+        return sim_error!(
+            "Binary trace length {} is not a multiple of the {BINARY_RECORD_SIZE_BYTES} byte record size",
+            contents.len()
+        );
+    }
+
+    let mut records = Vec::with_capacity(contents.len() / BINARY_RECORD_SIZE_BYTES);
+    for (i, chunk) in contents.chunks_exact(BINARY_RECORD_SIZE_BYTES).enumerate() {
+        let tick = u64::from_le_bytes(chunk[0..8].try_into().unwrap());
+        let addr = u64::from_le_bytes(chunk[8..16].try_into().unwrap());
+        let size_bytes = u32::from_le_bytes(chunk[16..20].try_into().unwrap());
+        let access_type = match u32::from_le_bytes(chunk[20..24].try_into().unwrap()) {
+            0 => AccessType::ReadRequest,
+            1 => AccessType::WriteRequest,
+            2 => AccessType::WriteNonPostedRequest,
+            op => return sim_error!("Binary trace record {i}: unknown op {op}"),
+        };
+        records.push(TraceRecord::new(
+            tick,
+            addr,
+            size_bytes as usize,
+            access_type,
+        ));
+    }
+    check_ordered(&records)?;
+    Ok(records)
+}
+
+/// Read a trace from a file, files with a `.csv` extension are parsed as CSV
+/// and all others as binary
+pub fn read_trace_file(path: &Path) -> Result<Vec<TraceRecord>, SimError> {
+    let is_csv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    if is_csv {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => parse_csv_trace(&contents),
+            Err(e) => sim_error!("Failed to read trace {}: {e}", path.display()),
+        }
+    } else {
+        match std::fs::read(path) {
+            Ok(contents) => parse_binary_trace(&contents),
+            Err(e) => sim_error!("Failed to read trace {}: {e}", path.display()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TraceReplayConfig {
+    src_addr: u64,
+    overhead_size_bytes: usize,
+}
+
+impl TraceReplayConfig {
+    #[must_use]
+    pub fn new(src_addr: u64, overhead_size_bytes: usize) -> Self {
+        Self {
+            src_addr,
+            overhead_size_bytes,
+        }
+    }
+}
+
+#[derive(Default)]
+struct TraceReplayMetrics {
+    num_issued: usize,
+    num_late: usize,
+    num_responses: usize,
+    payload_bytes_received: usize,
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct TraceReplay {
+    entity: Rc<Entity>,
+    spawner: Spawner,
+    clock: Clock,
+    config: TraceReplayConfig,
+    memory_map: Rc<MemoryMap>,
+    records: RefCell<Option<Vec<TraceRecord>>>,
+    rx: RefCell<Option<InPort<MemoryAccess>>>,
+    tx: RefCell<Option<OutPort<MemoryAccess>>>,
+    metrics: Rc<RefCell<TraceReplayMetrics>>,
+}
+
+impl TraceReplay {
+    #[expect(clippy::too_many_arguments)]
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: TraceReplayConfig,
+        memory_map: &Rc<MemoryMap>,
+        records: Vec<TraceRecord>,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        check_ordered(&records)?;
+        if memory_map.lookup(config.src_addr).is_none() {
+            return sim_error!(
+                "{entity}: source address 0x{:x} is not in the memory map",
+                config.src_addr
+            );
+        }
+
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            spawner: engine.spawner(),
+            clock: clock.clone(),
+            config,
+            memory_map: memory_map.clone(),
+            records: RefCell::new(Some(records)),
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+            metrics: Rc::new(RefCell::new(TraceReplayMetrics::default())),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: TraceReplayConfig,
+        memory_map: &Rc<MemoryMap>,
+        records: Vec<TraceRecord>,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(
+            engine, clock, parent, name, None, config, memory_map, records,
+        )
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<MemoryAccess>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<MemoryAccess> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Number of trace records issued
+    #[must_use]
+    pub fn num_issued(&self) -> usize {
+        self.metrics.borrow().num_issued
+    }
+
+    /// Number of trace records issued after their trace tick
+    #[must_use]
+    pub fn num_late(&self) -> usize {
+        self.metrics.borrow().num_late
+    }
+
+    #[must_use]
+    pub fn num_responses(&self) -> usize {
+        self.metrics.borrow().num_responses
+    }
+
+    #[must_use]
+    pub fn payload_bytes_received(&self) -> usize {
+        self.metrics.borrow().payload_bytes_received
+    }
+
+    fn create_access(&self, record: &TraceRecord) -> Result<MemoryAccess, SimError> {
+        let Some((dst_device, _)) = self.memory_map.lookup(record.addr) else {
+            return sim_error!(
+                "{}: trace address 0x{:x} is not in the memory map",
+                self.entity,
+                record.addr
+            );
+        };
+        let (src_device, _) = self.memory_map.lookup(self.config.src_addr).unwrap();
+        Ok(MemoryAccess::new(
+            &self.entity,
+            record.access_type,
+            record.size_bytes,
+            record.addr,
+            self.config.src_addr,
+            dst_device,
+            src_device,
+            self.config.overhead_size_bytes,
+        ))
+    }
+}
+
+#[async_trait(?Send)]
+impl Runnable for TraceReplay {
+    async fn run(&self) -> SimResult {
+        let Some(records) = self.records.borrow_mut().take() else {
+            return Ok(());
+        };
+
+        let rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+
+        {
+            let metrics = self.metrics.clone();
+            self.spawner.spawn(async move {
+                run_responses(rx, metrics).await?;
+                Ok(())
+            });
+        }
+
+        for record in &records {
+            let now = self.clock.tick_now().tick();
+            if record.tick > now {
+                self.clock.wait_ticks(record.tick - now).await;
+            } else if record.tick < now {
+                self.metrics.borrow_mut().num_late += 1;
+            }
+
+            let access = self
+                .create_access(record)?
+                .with_issue_tick(self.clock.tick_now().tick());
+            trace!(self.entity ; "Replay {} at tick {}", access, record.tick);
+            self.metrics.borrow_mut().num_issued += 1;
+            tx.put(access)?.await;
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_responses(
+    mut rx: InPort<MemoryAccess>,
+    metrics: Rc<RefCell<TraceReplayMetrics>>,
+) -> SimResult {
+    loop {
+        let response = rx.get()?.await;
+        let mut metrics = metrics.borrow_mut();
+        metrics.num_responses += 1;
+        metrics.payload_bytes_received += response.access_size_bytes();
+    }
+}
+
+#[test]
+fn csv_trace_parses() {
+    let records = parse_csv_trace(
+        "# tick,addr,size,op\n\
+         0,0x1000,64,R\n\
+         \n\
+         5, 4096, 32, w\n\
+         5,0x2000,8,WNP\n",
+    )
+    .unwrap();
+    assert_eq!(
+        records,
+        vec![
+            TraceRecord::new(0, 0x1000, 64, AccessType::ReadRequest),
+            TraceRecord::new(5, 0x1000, 32, AccessType::WriteRequest),
+            TraceRecord::new(5, 0x2000, 8, AccessType::WriteNonPostedRequest),
+        ]
+    );
+}
+
+#[test]
+fn csv_trace_errors() {
+    assert!(parse_csv_trace("0,0x1000,64").is_err());
+    assert!(parse_csv_trace("0,0x1000,64,X").is_err());
+    assert!(parse_csv_trace("0,0xzz,64,R").is_err());
+    assert!(parse_csv_trace("5,0x1000,64,R\n4,0x1000,64,R").is_err());
+}
+
+#[test]
+fn binary_trace_parses() {
+    let mut contents = Vec::new();
+    for (tick, addr, size, op) in [(1u64, 0x40u64, 64u32, 0u32), (3, 0x80, 16, 2)] {
+        contents.extend_from_slice(&tick.to_le_bytes());
+        contents.extend_from_slice(&addr.to_le_bytes());
+        contents.extend_from_slice(&size.to_le_bytes());
+        contents.extend_from_slice(&op.to_le_bytes());
+    }
+    let records = parse_binary_trace(&contents).unwrap();
+    assert_eq!(
+        records,
+        vec![
+            TraceRecord::new(1, 0x40, 64, AccessType::ReadRequest),
+            TraceRecord::new(3, 0x80, 16, AccessType::WriteNonPostedRequest),
+        ]
+    );
+    assert!(parse_binary_trace(&contents[1..]).is_err());
+}
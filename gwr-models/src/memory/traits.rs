@@ -5,6 +5,7 @@ use gwr_engine::types::SimError;
 
 use crate::memory::CacheHintType;
 use crate::memory::memory_map::DeviceId;
+use crate::scatter_gather::ScatterGather;
 
 pub trait ReadMemory {
     fn read(&self) -> Vec<u8>;
@@ -30,11 +31,36 @@ where
     /// Return the size of the access in bytes
     fn access_size_bytes(&self) -> usize;
 
+    /// Return the number of bytes actually written by the access. This is
+    /// less than the access size for masked writes.
+    fn enabled_bytes(&self) -> usize;
+
     /// Returns the appropriate response for a request
     fn to_response(&self, mem: &impl ReadMemory) -> Result<Self, SimError>
     where
         Self: Sized;
 
+    /// Return the segments accessed if the access is of non-contiguous data
+    fn scatter_gather(&self) -> Option<&ScatterGather>;
+
+    /// Return the tick at which the access was issued, if it has been stamped
+    fn issue_tick(&self) -> Option<u64>;
+
+    /// Stamp the access with the tick at which it was issued
+    fn set_issue_tick(&mut self, tick: u64);
+
+    /// Return true if the data of this access contains an uncorrectable error
+    fn is_poisoned(&self) -> bool;
+
+    /// Mark the data of this access as containing an uncorrectable error
+    fn set_poisoned(&mut self);
+
     /// Returns the requested caching behaviour of a request
     fn cache_hint(&self) -> CacheHintType;
+
+    /// Returns a single access covering both this access and `other` if they
+    /// are writes that can be combined, otherwise `None`
+    fn combine(&self, other: &Self) -> Option<Self>
+    where
+        Self: Sized;
 }
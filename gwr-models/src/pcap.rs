@@ -0,0 +1,393 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Exchange Ethernet frames with standard network tooling using pcap files.
+//!
+//! Captures in the classic libpcap format with an Ethernet link type can be
+//! read with [read_pcap_file] and turned into [EthernetFrame]s by
+//! [PcapFrames], which can be used as the data generator of a
+//! [Source](gwr_components::source::Source). The frames are generated in
+//! capture order and as fast as the source is able to send them, the capture
+//! timestamps are not used.
+//!
+//! A [PcapTap] passes frames straight through and writes each one to a pcap
+//! file along with the simulation time at which it was seen, so traffic can be
+//! inspected with tools like Wireshark. Place a tap in front of a
+//! [Sink](gwr_components::sink::Sink) to capture all the frames that arrive at
+//! it.
+//!
+//! The simulated frames have no contents, so written frames contain the MAC
+//! addresses, any VLAN tag and a payload of zeros that starts with the local
+//! experimental EtherType. The preamble, SFD and FCS are not captured.
+//!
+//! # Ports
+//!
+//! The [PcapTap] has two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::Runnable;
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+
+use crate::ethernet_frame::{
+    DEST_MAC_BYTES, EthernetFrame, SRC_MAC_BYTES, VLAN_TAG_BYTES, u64_to_mac,
+};
+
+/// Magic number of a pcap file with microsecond timestamps
+pub const PCAP_MAGIC_US: u32 = 0xa1b2_c3d4;
+/// Magic number of a pcap file with nanosecond timestamps
+pub const PCAP_MAGIC_NS: u32 = 0xa1b2_3c4d;
+/// The pcap link type of Ethernet captures
+pub const LINKTYPE_ETHERNET: u32 = 1;
+/// The EtherType that identifies an IEEE 802.1Q VLAN tag
+pub const ETHERTYPE_VLAN: u16 = 0x8100;
+/// The EtherType written into the payload of captured frames
+pub const ETHERTYPE_LOCAL_EXPERIMENTAL: u16 = 0x88b5;
+
+const FILE_HEADER_BYTES: usize = 24;
+const RECORD_HEADER_BYTES: usize = 16;
+const MAC_HEADER_BYTES: usize = DEST_MAC_BYTES + SRC_MAC_BYTES;
+const SNAPLEN: u32 = 65535;
+
+/// A single packet from a pcap capture
+#[derive(Clone, Debug, PartialEq)]
+pub struct PcapPacket {
+    pub timestamp_ns: u64,
+
+    /// Length of the packet on the wire, which can be more than was captured
+    pub orig_len: usize,
+    pub data: Vec<u8>,
+}
+
+impl PcapPacket {
+    #[must_use]
+    pub fn new(timestamp_ns: u64, orig_len: usize, data: Vec<u8>) -> Self {
+        Self {
+            timestamp_ns,
+            orig_len,
+            data,
+        }
+    }
+}
+
+/// Parse the contents of a pcap file in either byte order
+pub fn parse_pcap(contents: &[u8]) -> Result<Vec<PcapPacket>, SimError> {
+    if contents.len() < FILE_HEADER_BYTES {
+        return sim_error!("pcap file is too short for the file header");
+    }
+    let magic = u32::from_le_bytes(contents[0..4].try_into().unwrap());
+    let (little_endian, ns_per_frac) = match (magic, magic.swap_bytes()) {
+        (PCAP_MAGIC_US, _) => (true, 1000),
+        (PCAP_MAGIC_NS, _) => (true, 1),
+        (_, PCAP_MAGIC_US) => (false, 1000),
+        (_, PCAP_MAGIC_NS) => (false, 1),
+        _ => return sim_error!("Unknown pcap magic number 0x{magic:08x}"),
+    };
+    let read_u32 = |bytes: &[u8]| {
+        let bytes = bytes.try_into().unwrap();
+        if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        }
+    };
+
+    let link_type = read_u32(&contents[20..24]) & 0xffff;
+    if link_type != LINKTYPE_ETHERNET {
+        return sim_error!(
+            "Unsupported pcap link type {link_type}, only Ethernet (1) is supported"
+        );
+    }
+
+    let mut packets = Vec::new();
+    let mut offset = FILE_HEADER_BYTES;
+    while offset < contents.len() {
+        let i = packets.len();
+        let Some(header) = contents.get(offset..offset + RECORD_HEADER_BYTES) else {
+            return sim_error!("pcap record {i} has a truncated header");
+        };
+        let ts_sec = read_u32(&header[0..4]) as u64;
+        let ts_frac = read_u32(&header[4..8]) as u64;
+        let incl_len = read_u32(&header[8..12]) as usize;
+        let orig_len = read_u32(&header[12..16]) as usize;
+        offset += RECORD_HEADER_BYTES;
+
+        let Some(data) = contents.get(offset..offset + incl_len) else {
+            return sim_error!("pcap record {i} has truncated data");
+        };
+        offset += incl_len;
+        packets.push(PcapPacket::new(
+            ts_sec * 1_000_000_000 + ts_frac * ns_per_frac,
+            orig_len,
+            data.to_vec(),
+        ));
+    }
+    Ok(packets)
+}
+
+pub fn read_pcap_file(path: &Path) -> Result<Vec<PcapPacket>, SimError> {
+    match std::fs::read(path) {
+        Ok(contents) => parse_pcap(&contents),
+        Err(e) => sim_error!("Failed to read pcap {}: {e}", path.display()),
+    }
+}
+
+/// Create the frame captured in a packet
+pub fn packet_to_frame(
+    created_by: &Rc<Entity>,
+    packet: &PcapPacket,
+) -> Result<EthernetFrame, SimError> {
+    let data = &packet.data;
+    if data.len() < MAC_HEADER_BYTES {
+        return sim_error!(
+            "pcap packet of {} bytes is too short for the MAC addresses",
+            data.len()
+        );
+    }
+    let dst_mac: [u8; DEST_MAC_BYTES] = data[0..DEST_MAC_BYTES].try_into().unwrap();
+    let src_mac: [u8; SRC_MAC_BYTES] = data[DEST_MAC_BYTES..MAC_HEADER_BYTES].try_into().unwrap();
+
+    let tci = match data.get(MAC_HEADER_BYTES..MAC_HEADER_BYTES + VLAN_TAG_BYTES) {
+        Some(tag) if u16::from_be_bytes([tag[0], tag[1]]) == ETHERTYPE_VLAN => {
+            Some(u16::from_be_bytes([tag[2], tag[3]]))
+        }
+        _ => None,
+    };
+    let header_bytes = MAC_HEADER_BYTES + tci.map_or(0, |_| VLAN_TAG_BYTES);
+    let payload_size_bytes = packet.orig_len.saturating_sub(header_bytes);
+
+    let frame = EthernetFrame::new(created_by, payload_size_bytes)
+        .set_dest(dst_mac)
+        .set_src(src_mac);
+    Ok(match tci {
+        Some(tci) => frame.set_vlan(tci & 0xfff, (tci >> 13) as u8),
+        None => frame,
+    })
+}
+
+/// The bytes of a frame as they appear in a capture
+#[must_use]
+pub fn frame_to_bytes(frame: &EthernetFrame) -> Vec<u8> {
+    let payload_size_bytes = frame.payload_size_bytes();
+    let mut data = Vec::with_capacity(MAC_HEADER_BYTES + VLAN_TAG_BYTES + payload_size_bytes);
+    data.extend_from_slice(&u64_to_mac(frame.get_dst()));
+    data.extend_from_slice(&u64_to_mac(frame.get_src()));
+    if let (Some(vlan_id), Some(pcp)) = (frame.vlan_id(), frame.pcp()) {
+        data.extend_from_slice(&ETHERTYPE_VLAN.to_be_bytes());
+        data.extend_from_slice(&(((pcp as u16) << 13) | vlan_id).to_be_bytes());
+    }
+    let payload_start = data.len();
+    data.resize(payload_start + payload_size_bytes, 0);
+    if payload_size_bytes >= 2 {
+        data[payload_start..payload_start + 2]
+            .copy_from_slice(&ETHERTYPE_LOCAL_EXPERIMENTAL.to_be_bytes());
+    }
+    data
+}
+
+/// Generate the frames of a capture, for use as the data generator of a
+/// [Source](gwr_components::source::Source)
+pub struct PcapFrames {
+    frames: std::vec::IntoIter<EthernetFrame>,
+}
+
+impl PcapFrames {
+    /// Returns a `SimError` if any of the packets cannot be converted to a
+    /// frame.
+    pub fn new(created_by: &Rc<Entity>, packets: &[PcapPacket]) -> Result<Self, SimError> {
+        let frames = packets
+            .iter()
+            .map(|packet| packet_to_frame(created_by, packet))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            frames: frames.into_iter(),
+        })
+    }
+}
+
+impl Iterator for PcapFrames {
+    type Item = EthernetFrame;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.frames.next()
+    }
+}
+
+/// Writes frames to a pcap file with nanosecond timestamps
+pub struct PcapWriter<W>
+where
+    W: Write,
+{
+    writer: W,
+}
+
+impl<W> PcapWriter<W>
+where
+    W: Write,
+{
+    /// Create a writer and write the pcap file header
+    pub fn new(mut writer: W) -> Result<Self, SimError> {
+        let mut header = Vec::with_capacity(FILE_HEADER_BYTES);
+        header.extend_from_slice(&PCAP_MAGIC_NS.to_le_bytes());
+        header.extend_from_slice(&2_u16.to_le_bytes());
+        header.extend_from_slice(&4_u16.to_le_bytes());
+        header.extend_from_slice(&0_i32.to_le_bytes());
+        header.extend_from_slice(&0_u32.to_le_bytes());
+        header.extend_from_slice(&SNAPLEN.to_le_bytes());
+        header.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+        match writer.write_all(&header) {
+            Ok(()) => Ok(Self { writer }),
+            Err(e) => sim_error!("Failed to write pcap header: {e}"),
+        }
+    }
+
+    pub fn write_frame(&mut self, timestamp_ns: u64, frame: &EthernetFrame) -> SimResult {
+        let data = frame_to_bytes(frame);
+        let incl_len = data.len().min(SNAPLEN as usize);
+        let mut record = Vec::with_capacity(RECORD_HEADER_BYTES + incl_len);
+        record.extend_from_slice(&((timestamp_ns / 1_000_000_000) as u32).to_le_bytes());
+        record.extend_from_slice(&((timestamp_ns % 1_000_000_000) as u32).to_le_bytes());
+        record.extend_from_slice(&(incl_len as u32).to_le_bytes());
+        record.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        record.extend_from_slice(&data[..incl_len]);
+        match self.writer.write_all(&record) {
+            Ok(()) => Ok(()),
+            Err(e) => sim_error!("Failed to write pcap record: {e}"),
+        }
+    }
+
+    pub fn flush(&mut self) -> SimResult {
+        match self.writer.flush() {
+            Ok(()) => Ok(()),
+            Err(e) => sim_error!("Failed to flush pcap: {e}"),
+        }
+    }
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct PcapTap {
+    entity: Rc<Entity>,
+    clock: Clock,
+    writer: RefCell<PcapWriter<Box<dyn Write>>>,
+    num_captured: RefCell<usize>,
+    rx: RefCell<Option<InPort<EthernetFrame>>>,
+    tx: RefCell<Option<OutPort<EthernetFrame>>>,
+}
+
+impl PcapTap {
+    /// Create a tap that writes the frames passing through it to `writer`.
+    ///
+    /// Returns a `SimError` if the pcap file header cannot be written.
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        writer: Box<dyn Write>,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        let writer = PcapWriter::new(writer)?;
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            clock: clock.clone(),
+            writer: RefCell::new(writer),
+            num_captured: RefCell::new(0),
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Create a tap that writes the frames passing through it to `writer`.
+    ///
+    /// Returns a `SimError` if the pcap file header cannot be written.
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        writer: Box<dyn Write>,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, writer)
+    }
+
+    /// Create a tap that writes the frames passing through it to the file at
+    /// `path`. The file is flushed when the tap is dropped or
+    /// [flush](Self::flush) is called.
+    pub fn new_and_register_to_file(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        path: &Path,
+    ) -> Result<Rc<Self>, SimError> {
+        let file = match File::create(path) {
+            Ok(file) => file,
+            Err(e) => return sim_error!("Failed to create pcap {}: {e}", path.display()),
+        };
+        Self::new_and_register(engine, clock, parent, name, Box::new(BufWriter::new(file)))
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<EthernetFrame>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<EthernetFrame> {
+        port_rx!(self.rx, state)
+    }
+
+    /// Number of frames written to the capture
+    #[must_use]
+    pub fn num_captured(&self) -> usize {
+        *self.num_captured.borrow()
+    }
+
+    pub fn flush(&self) -> SimResult {
+        self.writer.borrow_mut().flush()
+    }
+}
+
+#[async_trait(?Send)]
+impl Runnable for PcapTap {
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+        loop {
+            let frame = rx.get()?.await;
+            let timestamp_ns = self.clock.time_now_ns() as u64;
+            self.writer.borrow_mut().write_frame(timestamp_ns, &frame)?;
+            *self.num_captured.borrow_mut() += 1;
+            tx.put(frame)?.await;
+        }
+    }
+}
+
+#[test]
+fn pcap_errors() {
+    assert!(parse_pcap(&[0; 10]).is_err());
+    assert!(parse_pcap(&[0; FILE_HEADER_BYTES]).is_err());
+
+    // A valid header with a truncated record
+    let mut contents = Vec::new();
+    PcapWriter::new(&mut contents).unwrap();
+    assert_eq!(parse_pcap(&contents).unwrap(), vec![]);
+    contents.extend_from_slice(&[0; 8]);
+    assert!(parse_pcap(&contents).is_err());
+}
@@ -0,0 +1,217 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Per-priority egress queues.
+//!
+//! Objects are placed in one of a number of queues according to their
+//! [traffic class](gwr_engine::traits::Routable::traffic_class), which for a
+//! VLAN tagged [EthernetFrame](crate::ethernet_frame::EthernetFrame) is its
+//! Priority Code Point (PCP). The eight priorities are mapped to the queues
+//! using the recommended priority to traffic class mapping of IEEE 802.1Q and
+//! the queues are served in strict priority order, so a higher priority object
+//! never waits behind a lower priority one that is blocked at the output.
+//!
+//! Traffic classes beyond the eight priorities are treated as priority 0.
+//!
+//! # Ports
+//!
+//! This component has two ports:
+//!  - One [input port](gwr_engine::port::InPort): `rx`
+//!  - One [output port](gwr_engine::port::OutPort): `tx`
+//!
+//! # Diagram
+//!
+//! ```text
+//!    +-----------------------------------------------+
+//!    |               /-> queue_0 --\                 |
+//! -> | rx -> router -+-> ...     --+-> arbiter -> tx | ->
+//!    |               \-> queue_N --/                 |
+//!    +-----------------------------------------------+
+//! ```
+
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::arbiter::Arbiter;
+use gwr_components::arbiter::policy::PriorityRoundRobin;
+use gwr_components::connect_port;
+use gwr_components::router::{Route, Router};
+use gwr_components::store::ByteStore;
+use gwr_engine::engine::Engine;
+use gwr_engine::port::PortStateResult;
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Routable, SimObject};
+use gwr_engine::types::{SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet, Runnable};
+use gwr_track::build_aka;
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+
+/// The number of priorities that can be carried by a VLAN tag.
+pub const NUM_PRIORITIES: usize = 8;
+
+/// The queue used by each priority (row) for each number of queues (column)
+/// as recommended by IEEE 802.1Q. Note that priority 1 (background) is below
+/// priority 0 (best effort).
+const PRIORITY_TO_QUEUE: [[usize; NUM_PRIORITIES]; NUM_PRIORITIES] = [
+    [0, 0, 0, 0, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0],
+    [0, 0, 0, 1, 1, 2, 2, 2],
+    [0, 0, 0, 1, 1, 2, 3, 3],
+    [0, 1, 1, 2, 2, 3, 4, 4],
+    [0, 1, 1, 2, 2, 3, 4, 5],
+    [0, 1, 2, 3, 3, 4, 5, 6],
+    [0, 1, 2, 3, 4, 5, 6, 7],
+];
+
+/// Returns the queue that objects of the given `priority` are placed in when
+/// there are `num_queues` queues. Higher queues are served first.
+#[must_use]
+pub fn priority_to_queue(priority: usize, num_queues: usize) -> usize {
+    let priority = if priority < NUM_PRIORITIES {
+        priority
+    } else {
+        0
+    };
+    PRIORITY_TO_QUEUE[priority][num_queues - 1]
+}
+
+/// Configuration for a set of per-priority queues.
+#[derive(Clone, Copy, Debug)]
+pub struct PriorityQueueConfig {
+    num_queues: usize,
+    queue_bytes: usize,
+}
+
+impl PriorityQueueConfig {
+    #[must_use]
+    pub fn new(num_queues: usize, queue_bytes: usize) -> Self {
+        Self {
+            num_queues,
+            queue_bytes,
+        }
+    }
+
+    #[must_use]
+    pub fn num_queues(&self) -> usize {
+        self.num_queues
+    }
+
+    #[must_use]
+    pub fn queue_bytes(&self) -> usize {
+        self.queue_bytes
+    }
+}
+
+struct PriorityRouter {
+    num_queues: usize,
+}
+
+impl<T> Route<T> for PriorityRouter
+where
+    T: Routable,
+{
+    fn route(&self, object: &T) -> Result<usize, SimError> {
+        Ok(priority_to_queue(object.traffic_class(), self.num_queues))
+    }
+}
+
+#[derive(EntityGet, EntityDisplay, Runnable)]
+pub struct PriorityQueues<T>
+where
+    T: SimObject + Routable,
+{
+    entity: Rc<Entity>,
+    router: Rc<Router<T>>,
+    arbiter: Rc<Arbiter<T>>,
+}
+
+impl<T> PriorityQueues<T>
+where
+    T: SimObject + Routable,
+{
+    /// Returns a `SimError` if the number of queues is not between 1 and
+    /// [NUM_PRIORITIES].
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: &PriorityQueueConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        let num_queues = config.num_queues;
+        if !(1..=NUM_PRIORITIES).contains(&num_queues) {
+            return sim_error!(
+                "Unsupported number of priority queues {num_queues} (must be 1 to {NUM_PRIORITIES})"
+            );
+        }
+        let entity = Rc::new(Entity::new(parent, name));
+
+        let router_aka = build_aka!(aka, &entity, &[("rx", "rx")]);
+        let router = Router::new_and_register_with_renames(
+            engine,
+            clock,
+            &entity,
+            "router",
+            Some(&router_aka),
+            num_queues,
+            Box::new(PriorityRouter { num_queues }),
+        );
+
+        // Higher queues are given higher priority
+        let policy = PriorityRoundRobin::from_priorities((0..num_queues).collect(), num_queues)?;
+        let arbiter_aka = build_aka!(aka, &entity, &[("tx", "tx")]);
+        let arbiter = Arbiter::new_and_register_with_renames(
+            engine,
+            clock,
+            &entity,
+            "arb",
+            Some(&arbiter_aka),
+            num_queues,
+            Box::new(policy),
+        );
+
+        for i in 0..num_queues {
+            let queue = ByteStore::new_and_register(
+                engine,
+                clock,
+                &entity,
+                &format!("queue_{i}"),
+                config.queue_bytes,
+            )?;
+            connect_port!(router, tx, i => queue, rx)
+                .expect("Internal ports should connect without error");
+            connect_port!(queue, tx => arbiter, rx, i)
+                .expect("Internal ports should connect without error");
+        }
+
+        let rc_self = Rc::new(Self {
+            entity,
+            router,
+            arbiter,
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    /// Returns a `SimError` if the number of queues is not between 1 and
+    /// [NUM_PRIORITIES].
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: &PriorityQueueConfig,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<T>) -> SimResult {
+        self.arbiter.connect_port_tx(port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<T> {
+        self.router.port_rx()
+    }
+}
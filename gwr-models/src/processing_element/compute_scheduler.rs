@@ -0,0 +1,109 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Priority scheduling of the compute unit of a PE.
+//!
+//! When preemption is enabled the compute unit of a PE is shared by its tasks.
+//! Tasks waiting for the unit are granted it in priority order (first come,
+//! first served within a priority). A running task checks for waiting tasks
+//! every `preemption_ticks` and yields the unit if one of them has a higher
+//! priority, waiting to be resumed once it is the highest priority task again.
+
+use std::cell::RefCell;
+
+use gwr_engine::events::once::Once;
+use gwr_engine::traits::Event;
+use gwr_engine::types::SimResult;
+
+struct Waiter {
+    priority: usize,
+    seq: u64,
+    granted: Once<()>,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    busy: bool,
+    waiting: Vec<Waiter>,
+    next_seq: u64,
+    num_preemptions: usize,
+}
+
+pub(crate) struct ComputeScheduler {
+    preemption_ticks: u64,
+    state: RefCell<SchedulerState>,
+}
+
+impl ComputeScheduler {
+    pub(crate) fn new(preemption_ticks: usize) -> Self {
+        Self {
+            preemption_ticks: preemption_ticks.max(1) as u64,
+            state: RefCell::new(SchedulerState::default()),
+        }
+    }
+
+    /// The maximum number of ticks a task can compute for before checking
+    /// whether it should yield
+    pub(crate) fn preemption_ticks(&self) -> u64 {
+        self.preemption_ticks
+    }
+
+    /// Wait until the compute unit is granted to a task of `priority`
+    pub(crate) async fn acquire(&self, priority: usize) {
+        let granted = {
+            let mut state = self.state.borrow_mut();
+            if !state.busy {
+                state.busy = true;
+                return;
+            }
+            let granted = Once::default();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiting.push(Waiter {
+                priority,
+                seq,
+                granted: granted.clone(),
+            });
+            granted
+        };
+        granted.listen().await;
+    }
+
+    /// Returns whether a task of `priority` should yield the compute unit
+    pub(crate) fn should_yield(&self, priority: usize) -> bool {
+        self.state
+            .borrow()
+            .waiting
+            .iter()
+            .any(|waiter| waiter.priority > priority)
+    }
+
+    /// Release the compute unit, handing it directly to the highest priority
+    /// waiting task. `preempted` indicates that the releasing task has not
+    /// finished and will try to acquire the unit again.
+    pub(crate) fn release(&self, preempted: bool) -> SimResult {
+        let mut state = self.state.borrow_mut();
+        if preempted {
+            state.num_preemptions += 1;
+        }
+        let next = state
+            .waiting
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.priority.cmp(&b.priority).then(b.seq.cmp(&a.seq)))
+            .map(|(i, _)| i);
+        match next {
+            Some(i) => {
+                let waiter = state.waiting.remove(i);
+                waiter.granted.notify()
+            }
+            None => {
+                state.busy = false;
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn num_preemptions(&self) -> usize {
+        self.state.borrow().num_preemptions
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Cost models that determine how long a Processing Element takes to perform
+//! a compute task.
+//!
+//! By default the duration of a compute task is determined by the throughput
+//! of each [MachineOp] given in the
+//! [ProcessingElementConfig](crate::processing_element::ProcessingElementConfig).
+//! A [CostModel] can be used instead so that PEs with different
+//! micro-architectures can be modelled within the same platform.
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+
+use crate::processing_element::task::ComputeOp;
+use crate::processing_element::{MachineOp, MachineOpCounts};
+
+const MACHINE_OPS: [MachineOp; 3] = [MachineOp::Add, MachineOp::Compare, MachineOp::Mul];
+
+/// Trait implemented by models of the time taken to perform a compute task
+pub trait CostModel {
+    /// Returns the number of ticks taken by `op` to perform `machine_ops`
+    fn compute_ticks(
+        &self,
+        op: &ComputeOp,
+        machine_ops: &MachineOpCounts,
+    ) -> Result<usize, SimError>;
+}
+
+/// A cost model in which every machine operation has a fixed latency and a
+/// number of operations can be issued each tick.
+///
+/// When the model is pipelined the throughput and latency are split so that a
+/// new set of operations can be issued every tick and the latency is only seen
+/// by the last operation. Otherwise each set of operations must complete
+/// before the next can be issued.
+#[derive(Clone, Debug)]
+pub struct LatencyTableCostModel {
+    issue_width: usize,
+    pipelined: bool,
+    add_latency_ticks: usize,
+    compare_latency_ticks: usize,
+    mul_latency_ticks: usize,
+}
+
+impl LatencyTableCostModel {
+    /// Create a pipelined model that issues `issue_width` operations per tick,
+    /// each of which has a latency of one tick.
+    #[must_use]
+    pub fn new(issue_width: usize) -> Self {
+        Self {
+            issue_width,
+            pipelined: true,
+            add_latency_ticks: 1,
+            compare_latency_ticks: 1,
+            mul_latency_ticks: 1,
+        }
+    }
+
+    /// Set the latency of a machine operation
+    #[must_use]
+    pub fn with_latency(mut self, op: MachineOp, ticks: usize) -> Self {
+        match op {
+            MachineOp::Add => self.add_latency_ticks = ticks,
+            MachineOp::Compare => self.compare_latency_ticks = ticks,
+            MachineOp::Mul => self.mul_latency_ticks = ticks,
+        }
+        self
+    }
+
+    /// Choose whether operations can be issued before earlier operations have
+    /// completed
+    #[must_use]
+    pub fn with_pipelining(mut self, pipelined: bool) -> Self {
+        self.pipelined = pipelined;
+        self
+    }
+
+    #[must_use]
+    pub fn issue_width(&self) -> usize {
+        self.issue_width
+    }
+
+    #[must_use]
+    pub fn pipelined(&self) -> bool {
+        self.pipelined
+    }
+
+    #[must_use]
+    pub fn latency_ticks(&self, op: MachineOp) -> usize {
+        match op {
+            MachineOp::Add => self.add_latency_ticks,
+            MachineOp::Compare => self.compare_latency_ticks,
+            MachineOp::Mul => self.mul_latency_ticks,
+        }
+    }
+}
+
+impl CostModel for LatencyTableCostModel {
+    fn compute_ticks(
+        &self,
+        _op: &ComputeOp,
+        machine_ops: &MachineOpCounts,
+    ) -> Result<usize, SimError> {
+        if self.issue_width == 0 {
+            return sim_error!("invalid compute issue width 0");
+        }
+
+        let used_ops = MACHINE_OPS.iter().filter(|op| machine_ops.count(**op) > 0);
+        if self.pipelined {
+            // The last operation is issued once all the others have been and
+            // the slowest type of operation determines when it completes
+            let issue_ticks = machine_ops.total().div_ceil(self.issue_width);
+            let max_latency = used_ops
+                .map(|op| self.latency_ticks(*op))
+                .max()
+                .unwrap_or(0);
+            Ok((issue_ticks + max_latency).saturating_sub(1))
+        } else {
+            Ok(used_ops
+                .map(|op| {
+                    machine_ops.count(*op).div_ceil(self.issue_width) * self.latency_ticks(*op)
+                })
+                .sum())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEMM_OPS: MachineOpCounts = MachineOpCounts {
+        adds: 10,
+        compares: 0,
+        muls: 12,
+    };
+
+    #[test]
+    fn pipelined_latency_is_seen_once() {
+        let model = LatencyTableCostModel::new(4).with_latency(MachineOp::Mul, 5);
+        // 22 ops issued over 6 ticks with the last taking 5 ticks
+        assert_eq!(
+            model.compute_ticks(&ComputeOp::Gemm, &GEMM_OPS).unwrap(),
+            10
+        );
+        assert_eq!(
+            model
+                .compute_ticks(&ComputeOp::Gemm, &MachineOpCounts::default())
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn unpipelined_latency_is_seen_by_every_issue() {
+        let model = LatencyTableCostModel::new(4)
+            .with_latency(MachineOp::Mul, 5)
+            .with_latency(MachineOp::Add, 2)
+            .with_pipelining(false);
+        // 3 issues of muls and 3 issues of adds
+        assert_eq!(
+            model.compute_ticks(&ComputeOp::Gemm, &GEMM_OPS).unwrap(),
+            3 * 5 + 3 * 2
+        );
+    }
+
+    #[test]
+    fn zero_issue_width_is_an_error() {
+        let model = LatencyTableCostModel::new(0);
+        assert!(model.compute_ticks(&ComputeOp::Add, &GEMM_OPS).is_err());
+    }
+}
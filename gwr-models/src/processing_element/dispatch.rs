@@ -13,4 +13,18 @@ pub trait Dispatch {
     fn ready_task_indices(&self, pe_name: &str) -> Result<(bool, Vec<usize>), SimError>;
     async fn wait_for_change(&self);
     fn total_tasks_for_pe(&self, pe_name: &str) -> usize;
+
+    /// Returns the priority of a task. Higher priority tasks are started first
+    /// and, when a PE has preemption enabled, can preempt the computation of
+    /// lower priority tasks. All tasks have priority 0 by default.
+    fn task_priority(&self, _task_idx: usize) -> usize {
+        0
+    }
+
+    /// Returns the number of ticks that the computation of a compute task
+    /// takes when it is decided by the dispatcher. Otherwise, which is the
+    /// default, it is decided by the PE that runs the task.
+    fn compute_ticks(&self, _task_idx: usize) -> Result<Option<usize>, SimError> {
+        Ok(None)
+    }
 }
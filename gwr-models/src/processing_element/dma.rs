@@ -0,0 +1,106 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! The DMA engine of a Processing Element (PE).
+//!
+//! The DMA engine performs the explicit copies between the scratchpad of the
+//! PE and the rest of the memory system. Each of its channels performs one
+//! copy at a time, issuing the memory accesses through the LSU of the PE.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gwr_engine::types::{AccessType, SimResult};
+use gwr_resources::Resource;
+use gwr_resources::base::ResourceGuard;
+use gwr_track::entity::EntityGroup;
+
+use crate::processing_element::ProcessingElementActivityLanes;
+use crate::processing_element::load_store_unit::LoadStoreUnit;
+use crate::processing_element::scratchpad::Scratchpad;
+use crate::processing_element::task::{DmaOp, DmaTaskConfig};
+
+pub(crate) struct DmaEngine {
+    lsu: Rc<LoadStoreUnit>,
+    scratchpad: Rc<Scratchpad>,
+    channels: Resource,
+    num_copies: Cell<usize>,
+}
+
+impl DmaEngine {
+    pub(crate) fn new(lsu: &Rc<LoadStoreUnit>, scratchpad: &Rc<Scratchpad>) -> Self {
+        Self {
+            lsu: lsu.clone(),
+            scratchpad: scratchpad.clone(),
+            channels: Resource::new(scratchpad.config().num_dma_channels()),
+            num_copies: Cell::new(0),
+        }
+    }
+
+    /// Perform the copy once a channel is available
+    pub(crate) async fn copy(
+        &self,
+        config: &DmaTaskConfig,
+        activity_lanes: &ProcessingElementActivityLanes,
+        group: &Rc<EntityGroup>,
+    ) -> SimResult {
+        let num_bytes = config.num_bytes;
+        match config.op {
+            DmaOp::CopyIn => self.scratchpad.check_range(config.dst_addr, num_bytes)?,
+            DmaOp::CopyOut => self.scratchpad.check_range(config.src_addr, num_bytes)?,
+        }
+
+        let _channel_guard = ResourceGuard::new(self.channels.clone()).await;
+        match config.op {
+            DmaOp::CopyIn => {
+                self.lsu
+                    .do_transfer(
+                        AccessType::ReadRequest,
+                        num_bytes,
+                        config.src_addr,
+                        &activity_lanes.lsu_read,
+                        &format!("{} copy in read", config.id),
+                        group,
+                    )
+                    .await?;
+                self.scratchpad
+                    .access(
+                        AccessType::WriteRequest,
+                        num_bytes,
+                        config.dst_addr,
+                        &activity_lanes.scratchpad,
+                        &format!("{} copy in write", config.id),
+                        group,
+                    )
+                    .await?;
+            }
+            DmaOp::CopyOut => {
+                self.scratchpad
+                    .access(
+                        AccessType::ReadRequest,
+                        num_bytes,
+                        config.src_addr,
+                        &activity_lanes.scratchpad,
+                        &format!("{} copy out read", config.id),
+                        group,
+                    )
+                    .await?;
+                self.lsu
+                    .do_transfer(
+                        AccessType::WriteNonPostedRequest,
+                        num_bytes,
+                        config.dst_addr,
+                        &activity_lanes.lsu_write,
+                        &format!("{} copy out write", config.id),
+                        group,
+                    )
+                    .await?;
+            }
+        }
+        self.num_copies.set(self.num_copies.get() + 1);
+        Ok(())
+    }
+
+    pub(crate) fn num_copies(&self) -> usize {
+        self.num_copies.get()
+    }
+}
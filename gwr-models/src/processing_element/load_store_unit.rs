@@ -4,6 +4,10 @@
 //!
 //! The LSU supports a user-defined number of outstanding operations
 //! and can be shared by multiple simultaneous tasks within the PE.
+//!
+//! The LSU also sends data directly to other PEs as posted writes addressed to
+//! their device ID and counts the bytes that other PEs send to it, so that a
+//! PE can wait to receive data from another PE.
 
 //! # Ports
 //!
@@ -13,7 +17,7 @@
 
 use std::cell::RefCell;
 use std::cmp::min;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::rc::Rc;
 
@@ -26,7 +30,7 @@ use gwr_engine::executor::Spawner;
 use gwr_engine::port::{InPort, OutPort, PortStateResult};
 use gwr_engine::sim_error;
 use gwr_engine::time::clock::{Clock, phase};
-use gwr_engine::traits::{Event, Runnable};
+use gwr_engine::traits::{Event, Routable, Runnable};
 use gwr_engine::types::{AccessType, SimError, SimResult};
 use gwr_model_builder::{EntityDisplay, EntityGet};
 use gwr_resources::Resource;
@@ -84,6 +88,13 @@ struct LsuState {
 
     /// Ensure that the LSU is only used by one `Task` at a time
     serialiser: Resource,
+
+    /// Bytes received from other PEs that have not been consumed yet, by the
+    /// device ID of the sender
+    received_bytes: RefCell<HashMap<DeviceId, usize>>,
+
+    /// Event to notify anyone waiting to receive data that more has arrived
+    data_received: Repeated<()>,
 }
 
 impl LsuState {
@@ -109,12 +120,14 @@ impl LsuState {
         }
     }
 
-    // Create the memory access to be sent to the memory subsystem
+    // Create the memory access to be sent to the memory subsystem, or to the
+    // given device
     fn create_memory_access(
         &self,
         access_type: AccessType,
         access_size_bytes: usize,
         dst_addr: u64,
+        dst_device: Option<DeviceId>,
         request_slot_idx: usize,
     ) -> Result<MemoryAccess, SimError> {
         let overhead_size_bytes = self.overhead_size_bytes;
@@ -123,9 +136,9 @@ impl LsuState {
         // on reply
         let src_addr = request_slot_idx as u64;
 
-        let dst_device = match self.memory_map.lookup(dst_addr) {
-            Some((dst_device, _)) => dst_device,
-            None => return sim_error!("0x{dst_addr:x} not mapped"),
+        let dst_device = match (dst_device, self.memory_map.lookup(dst_addr)) {
+            (Some(dst_device), _) | (None, Some((dst_device, _))) => dst_device,
+            (None, None) => return sim_error!("0x{dst_addr:x} not mapped"),
         };
         let src_device = self.device_id;
 
@@ -207,7 +220,13 @@ impl LsuState {
                 }
             };
             debug!(self.entity ; "Make memory access {request} for slot {slot_idx}");
+            // Posted writes have no response so they are complete once sent
+            let posted =
+                (request.access_type() == AccessType::WriteRequest).then(|| request.clone());
             tx.put(request)?.await;
+            if let Some(request) = posted {
+                self.put_response_in_active_request_slot(request)?;
+            }
             true
         } else {
             false
@@ -215,6 +234,16 @@ impl LsuState {
         Ok(handled_request)
     }
 
+    // Record data sent by another PE and wake anyone waiting for it
+    fn receive_data(&self, access: &MemoryAccess) {
+        *self
+            .received_bytes
+            .borrow_mut()
+            .entry(access.src_device())
+            .or_default() += access.access_size_bytes();
+        self.data_received.notify();
+    }
+
     fn put_response_in_active_request_slot(&self, response: MemoryAccess) -> SimResult {
         let idx = response.src_addr() as usize;
         let mut guard = self.active_request_slots.borrow_mut();
@@ -286,6 +315,8 @@ impl LoadStoreUnit {
             new_request: Repeated::new(()),
             slot_available: Repeated::new(()),
             serialiser: Resource::new(1),
+            received_bytes: RefCell::new(HashMap::new()),
+            data_received: Repeated::new(()),
         };
         let spawner = engine.spawner();
         let rc_self = Rc::new(Self {
@@ -327,6 +358,92 @@ impl LoadStoreUnit {
             );
         }
 
+        self.do_transfer(
+            access_type,
+            access_size_bytes,
+            dst_addr,
+            activity_lanes,
+            activity_name,
+            group,
+        )
+        .await
+    }
+
+    /// Perform a memory access that does not pass through the SRAM of the PE.
+    ///
+    /// This is used by the DMA engine which moves data directly to/from the
+    /// scratchpad.
+    pub(crate) async fn do_transfer(
+        &self,
+        access_type: AccessType,
+        access_size_bytes: usize,
+        dst_addr: u64,
+        activity_lanes: &Rc<RefCell<ActivityLanes>>,
+        activity_name: &str,
+        group: &Rc<EntityGroup>,
+    ) -> SimResult {
+        self.issue_accesses(
+            access_type,
+            access_size_bytes,
+            dst_addr,
+            None,
+            activity_lanes,
+            activity_name,
+            group,
+        )
+        .await
+    }
+
+    /// Send data to another PE as posted writes to its device ID.
+    ///
+    /// The send is complete once the data has left the LSU.
+    pub(crate) async fn do_send(
+        &self,
+        dst_device: DeviceId,
+        num_bytes: usize,
+        activity_lanes: &Rc<RefCell<ActivityLanes>>,
+        activity_name: &str,
+        group: &Rc<EntityGroup>,
+    ) -> SimResult {
+        self.issue_accesses(
+            AccessType::WriteRequest,
+            num_bytes,
+            0,
+            Some(dst_device),
+            activity_lanes,
+            activity_name,
+            group,
+        )
+        .await
+    }
+
+    /// Wait until the given number of bytes has been received from another PE
+    /// and consume them.
+    pub(crate) async fn receive(&self, src_device: DeviceId, num_bytes: usize) {
+        loop {
+            {
+                let mut received_bytes = self.state.received_bytes.borrow_mut();
+                let available = received_bytes.entry(src_device).or_default();
+                if *available >= num_bytes {
+                    *available -= num_bytes;
+                    return;
+                }
+            }
+            self.state.data_received.listen().await;
+        }
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    async fn issue_accesses(
+        &self,
+        access_type: AccessType,
+        access_size_bytes: usize,
+        dst_addr: u64,
+        dst_device: Option<DeviceId>,
+        activity_lanes: &Rc<RefCell<ActivityLanes>>,
+        activity_name: &str,
+        group: &Rc<EntityGroup>,
+    ) -> SimResult {
         let mut bytes_remaining = access_size_bytes;
         let mut access_address = dst_addr;
 
@@ -362,6 +479,7 @@ impl LoadStoreUnit {
                 access_type,
                 access_size_bytes,
                 access_address,
+                dst_device,
                 request_slot_idx,
             )?;
 
@@ -415,8 +533,13 @@ impl Runnable for LoadStoreUnit {
 
 async fn run_rx(state: Rc<LsuState>, mut rx: InPort<MemoryAccess>) -> SimResult {
     loop {
-        let response = rx.get()?.await;
-        state.put_response_in_active_request_slot(response)?;
+        let access = rx.get()?.await;
+        if access.access_type() == AccessType::WriteRequest {
+            // Posted writes are only received from other PEs
+            state.receive_data(&access);
+        } else {
+            state.put_response_in_active_request_slot(access)?;
+        }
     }
 }
 
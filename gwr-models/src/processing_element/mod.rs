@@ -9,6 +9,7 @@
 //!  - Load/Store
 //!  - Internal Buffers
 //!  - Compute
+//!  - An optional [scratchpad](scratchpad) with a DMA engine
 //!
 //! Identifies all operation nodes (load/store/compute) that can execute
 //! because their dependencies are satisfied (or they have no dependencies).
@@ -29,6 +30,8 @@ use async_trait::async_trait;
 use gwr_engine::engine::Engine;
 use gwr_engine::executor::Spawner;
 use gwr_engine::port::PortStateResult;
+use gwr_engine::power::PowerSwitch;
+use gwr_engine::sim_error;
 use gwr_engine::time::clock::{Clock, phase};
 use gwr_engine::traits::Runnable;
 use gwr_engine::types::{AccessType, SimError, SimResult};
@@ -40,19 +43,30 @@ use gwr_track::tracker::aka::Aka;
 use crate::log_stats;
 use crate::memory::memory_access::MemoryAccess;
 use crate::memory::memory_map::{DeviceId, MemoryMap};
+use crate::processing_element::compute_scheduler::ComputeScheduler;
+use crate::processing_element::cost_model::CostModel;
 use crate::processing_element::dispatch::Dispatch;
+use crate::processing_element::dma::DmaEngine;
 use crate::processing_element::flop_monitor::FlopMonitor;
 use crate::processing_element::load_store_unit::LoadStoreUnit;
 use crate::processing_element::operators::TensorView;
-use crate::processing_element::task::{ComputeTaskConfig, MemoryOp, MemoryTaskConfig, Task};
-
+use crate::processing_element::scratchpad::{Scratchpad, ScratchpadConfig};
+use crate::processing_element::task::{
+    ComputeTaskConfig, DmaTaskConfig, MemoryOp, MemoryTaskConfig, RecvTaskConfig, SendTaskConfig,
+    Task,
+};
+
+mod compute_scheduler;
+pub mod cost_model;
 pub mod dispatch;
+mod dma;
 mod flop_monitor;
 mod load_store_unit;
 pub mod operators;
+pub mod scratchpad;
 pub mod task;
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum MachineOp {
     Add,
     Compare,
@@ -72,6 +86,15 @@ impl MachineOpCounts {
         self.adds + self.compares + self.muls
     }
 
+    #[must_use]
+    pub fn count(&self, op: MachineOp) -> usize {
+        match op {
+            MachineOp::Add => self.adds,
+            MachineOp::Compare => self.compares,
+            MachineOp::Mul => self.muls,
+        }
+    }
+
     pub fn add_assign(&mut self, other: Self) {
         self.adds += other.adds;
         self.compares += other.compares;
@@ -146,6 +169,18 @@ pub struct ProcessingElementConfig {
 
     /// Number of compare operations per tick
     pub compares_per_tick: f64,
+
+    /// Model used to determine the duration of compute tasks. When not set the
+    /// duration is determined by the number of operations per tick.
+    pub cost_model: Option<Rc<dyn CostModel>>,
+
+    /// When set, compute tasks share a single compute unit in priority order
+    /// and a running task can be preempted by a higher priority task after
+    /// this many ticks. When not set compute tasks run concurrently.
+    pub preemption_ticks: Option<usize>,
+
+    /// Local scratchpad memory that is filled and drained by DMA tasks
+    pub scratchpad: Option<ScratchpadConfig>,
 }
 
 pub struct ComputeCapabilities {
@@ -153,6 +188,7 @@ pub struct ComputeCapabilities {
     muls_per_tick: f64,
     compares_per_tick: f64,
     sram_bytes: usize,
+    cost_model: Option<Rc<dyn CostModel>>,
 }
 
 impl ComputeCapabilities {
@@ -261,6 +297,7 @@ struct ProcessingElementActivityLanes {
     compute: Rc<RefCell<ActivityLanes>>,
     lsu_read: Rc<RefCell<ActivityLanes>>,
     lsu_write: Rc<RefCell<ActivityLanes>>,
+    scratchpad: Rc<RefCell<ActivityLanes>>,
 }
 
 impl ProcessingElementActivityLanes {
@@ -275,7 +312,11 @@ impl ProcessingElementActivityLanes {
                 entity.clone(),
                 "lane::lsu_read",
             ))),
-            lsu_write: Rc::new(RefCell::new(ActivityLanes::new(entity, "lane::lsu_write"))),
+            lsu_write: Rc::new(RefCell::new(ActivityLanes::new(
+                entity.clone(),
+                "lane::lsu_write",
+            ))),
+            scratchpad: Rc::new(RefCell::new(ActivityLanes::new(entity, "lane::scratchpad"))),
         }
     }
 
@@ -296,6 +337,10 @@ pub struct ProcessingElement {
     activity_lanes: Rc<ProcessingElementActivityLanes>,
     dispatcher: RefCell<Option<Dispatcher>>,
     flop_monitor: Option<Rc<FlopMonitor>>,
+    compute_scheduler: Option<Rc<ComputeScheduler>>,
+    scratchpad: Option<Rc<Scratchpad>>,
+    dma: Option<Rc<DmaEngine>>,
+    power_switch: RefCell<Option<PowerSwitch>>,
 }
 
 impl ProcessingElement {
@@ -315,6 +360,15 @@ impl ProcessingElement {
         let lsu = LoadStoreUnit::new_and_register(
             engine, clock, &entity, aka, pe_config, memory_map, device_id,
         )?;
+        let scratchpad = match &pe_config.scratchpad {
+            Some(config) => Some(Rc::new(Scratchpad::new(
+                &entity, clock, config, memory_map,
+            )?)),
+            None => None,
+        };
+        let dma = scratchpad
+            .as_ref()
+            .map(|scratchpad| Rc::new(DmaEngine::new(&lsu, scratchpad)));
         let monitor_window_size = entity.tracker.monitoring_window_size_for(entity.id);
         let flop_monitor = monitor_window_size.map(|window_size_ticks| {
             FlopMonitor::new_and_register(engine, &entity, clock, window_size_ticks)
@@ -331,12 +385,19 @@ impl ProcessingElement {
                 muls_per_tick: pe_config.muls_per_tick,
                 compares_per_tick: pe_config.compares_per_tick,
                 sram_bytes: pe_config.sram_bytes,
+                cost_model: pe_config.cost_model.clone(),
             }),
             stats: Rc::new(RefCell::new(ProcessingElementStats::default())),
             activity_lanes: Rc::new(ProcessingElementActivityLanes::new(entity.clone())),
 
             dispatcher: RefCell::new(None),
             flop_monitor,
+            compute_scheduler: pe_config
+                .preemption_ticks
+                .map(|ticks| Rc::new(ComputeScheduler::new(ticks))),
+            scratchpad,
+            dma,
+            power_switch: RefCell::new(None),
         });
         engine.register(rc_self.clone());
         Ok(rc_self)
@@ -360,6 +421,12 @@ impl ProcessingElement {
         *self.dispatcher.borrow_mut() = Some(dispatcher.clone());
     }
 
+    /// Gate the PE with a power switch so that it only starts tasks while the
+    /// switch is on
+    pub fn set_power_switch(&self, power_switch: &PowerSwitch) {
+        *self.power_switch.borrow_mut() = Some(power_switch.clone());
+    }
+
     pub fn connect_port_tx(&self, port_state: PortStateResult<MemoryAccess>) -> SimResult {
         self.lsu.connect_port_tx(port_state)
     }
@@ -386,6 +453,36 @@ impl ProcessingElement {
         self.stats.borrow().machine_ops
     }
 
+    /// Returns the number of times a compute task has been preempted
+    #[must_use]
+    pub fn num_preemptions(&self) -> usize {
+        self.compute_scheduler
+            .as_ref()
+            .map_or(0, |scheduler| scheduler.num_preemptions())
+    }
+
+    /// Returns the number of bytes read from the scratchpad
+    #[must_use]
+    pub fn scratchpad_bytes_read(&self) -> usize {
+        self.scratchpad
+            .as_ref()
+            .map_or(0, |scratchpad| scratchpad.bytes_read())
+    }
+
+    /// Returns the number of bytes written to the scratchpad
+    #[must_use]
+    pub fn scratchpad_bytes_written(&self) -> usize {
+        self.scratchpad
+            .as_ref()
+            .map_or(0, |scratchpad| scratchpad.bytes_written())
+    }
+
+    /// Returns the number of DMA copies completed
+    #[must_use]
+    pub fn num_dma_copies(&self) -> usize {
+        self.dma.as_ref().map_or(0, |dma| dma.num_copies())
+    }
+
     pub fn dump_stats(&self, time_now_ns: f64) {
         let stats = self.stats.borrow();
         log_stats(
@@ -409,6 +506,8 @@ impl Runnable for ProcessingElement {
             .ok_or_else(|| SimError("Started without dispatcher".to_string()))?
             .clone();
 
+        let power_switch = self.power_switch.borrow().clone();
+
         let pe_name = self.entity.name.as_str();
         let (mut complete, mut ready_node_indices) = dispatcher.ready_task_indices(pe_name)?;
 
@@ -420,7 +519,13 @@ impl Runnable for ProcessingElement {
                 // Wait for something to change
                 dispatcher.wait_for_change().await;
             } else {
-                // Spawn all so they can run in parallel
+                if let Some(power_switch) = &power_switch {
+                    // Tasks are not started while the PE is powered down
+                    power_switch.wait_until_on().await;
+                }
+                // Spawn all so they can run in parallel, highest priority first
+                ready_node_indices
+                    .sort_by_key(|task_idx| std::cmp::Reverse(dispatcher.task_priority(*task_idx)));
                 for task_idx in ready_node_indices.drain(..) {
                     dispatcher.set_task_active(task_idx)?;
 
@@ -432,6 +537,9 @@ impl Runnable for ProcessingElement {
                     let entity = self.entity.clone();
                     let activity_lanes = self.activity_lanes.clone();
                     let flop_monitor = self.flop_monitor.clone();
+                    let compute_scheduler = self.compute_scheduler.clone();
+                    let scratchpad = self.scratchpad.clone();
+                    let dma = self.dma.clone();
                     self.spawner.spawn(async move {
                         handle_task(
                             entity,
@@ -442,6 +550,9 @@ impl Runnable for ProcessingElement {
                             stats,
                             activity_lanes,
                             flop_monitor,
+                            compute_scheduler,
+                            scratchpad,
+                            dma,
                             task_idx,
                         )
                         .await
@@ -466,6 +577,9 @@ async fn handle_task(
     stats: Rc<RefCell<ProcessingElementStats>>,
     activity_lanes: Rc<ProcessingElementActivityLanes>,
     flop_monitor: Option<Rc<FlopMonitor>>,
+    compute_scheduler: Option<Rc<ComputeScheduler>>,
+    scratchpad: Option<Rc<Scratchpad>>,
+    dma: Option<Rc<DmaEngine>>,
     task_idx: usize,
 ) -> SimResult {
     let task = dispatcher.task_by_id(task_idx)?;
@@ -479,6 +593,8 @@ async fn handle_task(
             stats,
             activity_lanes,
             flop_monitor,
+            compute_scheduler,
+            scratchpad,
             &config,
         )
         .await
@@ -490,6 +606,27 @@ async fn handle_task(
                     SimError(format!("{entity} had error on task {}:\n{err}", config.id))
                 })
         }
+        Task::DmaTask { config } => {
+            handle_dma_task(dispatcher, dma, activity_lanes, task_idx, &config)
+                .await
+                .map_err(|err| {
+                    SimError(format!("{entity} had error on task {}:\n{err}", config.id))
+                })
+        }
+        Task::SendTask { config } => {
+            handle_send_task(dispatcher, lsu, activity_lanes, task_idx, &config)
+                .await
+                .map_err(|err| {
+                    SimError(format!("{entity} had error on task {}:\n{err}", config.id))
+                })
+        }
+        Task::RecvTask { config } => {
+            handle_recv_task(dispatcher, lsu, activity_lanes, task_idx, &config)
+                .await
+                .map_err(|err| {
+                    SimError(format!("{entity} had error on task {}:\n{err}", config.id))
+                })
+        }
         Task::SyncTask { .. } => {
             todo!();
         }
@@ -508,6 +645,42 @@ fn tensor_view_base_addr(view: &TensorView) -> Result<u64, SimError> {
     Ok(base_addr + byte_offset)
 }
 
+/// Read or write a tensor view. Views that lie within the scratchpad are
+/// accessed there, all others are accessed through the LSU.
+async fn access_tensor_view(
+    lsu: &LoadStoreUnit,
+    scratchpad: Option<&Scratchpad>,
+    access_type: AccessType,
+    view: &TensorView,
+    activity_lanes: &ProcessingElementActivityLanes,
+    activity_name: &str,
+    group: &Rc<EntityGroup>,
+) -> SimResult {
+    let num_bytes = tensor_view_num_bytes(view);
+    let addr = tensor_view_base_addr(view)?;
+    if let Some(scratchpad) = scratchpad
+        && scratchpad.config().contains(addr, num_bytes)
+    {
+        return scratchpad
+            .access(
+                access_type,
+                num_bytes,
+                addr,
+                &activity_lanes.scratchpad,
+                activity_name,
+                group,
+            )
+            .await;
+    }
+
+    let lanes = match access_type {
+        AccessType::ReadRequest => &activity_lanes.lsu_read,
+        _ => &activity_lanes.lsu_write,
+    };
+    lsu.do_access(access_type, num_bytes, addr, lanes, activity_name, group)
+        .await
+}
+
 #[expect(clippy::too_many_arguments)]
 async fn handle_compute_task(
     clock: Clock,
@@ -518,8 +691,11 @@ async fn handle_compute_task(
     stats: Rc<RefCell<ProcessingElementStats>>,
     activity_lanes: Rc<ProcessingElementActivityLanes>,
     flop_monitor: Option<Rc<FlopMonitor>>,
+    compute_scheduler: Option<Rc<ComputeScheduler>>,
+    scratchpad: Option<Rc<Scratchpad>>,
     config: &ComputeTaskConfig,
 ) -> SimResult {
+    let priority = dispatcher.task_priority(task_idx);
     let total_num_bytes: usize = config
         .inputs
         .iter()
@@ -538,47 +714,70 @@ async fn handle_compute_task(
             .create_partitions(&config.inputs, &config.outputs, num_partitions)?;
     let group = activity_lanes.create_group(&format!("{} operation", config.id));
 
-    for partition in partitions {
+    // The ticks given by the dispatcher are shared between the partitions
+    let num_partitions = partitions.len();
+    let dispatched_ticks = dispatcher.compute_ticks(task_idx)?;
+    for (partition_idx, partition) in partitions.into_iter().enumerate() {
         for (idx, view) in partition.inputs.iter().enumerate() {
             let Some(view) = view else {
                 continue;
             };
-            lsu.do_access(
+            access_tensor_view(
+                &lsu,
+                scratchpad.as_deref(),
                 AccessType::ReadRequest,
-                tensor_view_num_bytes(view),
-                tensor_view_base_addr(view)?,
-                &activity_lanes.lsu_read,
+                view,
+                &activity_lanes,
                 &format!("{} tensor {idx} read", config.id),
                 &group,
             )
             .await?;
         }
 
-        let compute_ticks = config.op.compute_delay_ticks(
-            &compute_capabilities,
-            &partition.inputs,
-            &partition.outputs,
-        )?;
         let machine_ops = config
             .op
             .compute_machine_ops(&partition.inputs, &partition.outputs)?;
-        let compute_flops = machine_ops.total();
-        if let Some(flop_monitor) = &flop_monitor {
-            flop_monitor.record_interval(compute_ticks as u64, compute_flops as f64);
-        }
-        {
-            // Lanes cannot support overlapping activity. If a lane will be released
-            // in the current clock cycle then we want to re-use it rather than allocate
-            // a new lane. Hence we wait here for the end of the current clock cycle
-            // to ensure all lanes that will be released in this cycle have been.
-            clock.wait_phase(phase::END).await;
-
-            let _activity = ActivityLanes::begin_in_group(
-                &activity_lanes.compute,
-                &format!("{} compute", config.id),
-                &group,
-            );
-            clock.wait_ticks(compute_ticks as u64).await;
+        let compute_ticks = match (dispatched_ticks, &compute_capabilities.cost_model) {
+            (Some(ticks), _) => {
+                ticks * (partition_idx + 1) / num_partitions
+                    - ticks * partition_idx / num_partitions
+            }
+            (None, Some(cost_model)) => cost_model.compute_ticks(&config.op, &machine_ops)?,
+            (None, None) => config.op.compute_delay_ticks(
+                &compute_capabilities,
+                &partition.inputs,
+                &partition.outputs,
+            )?,
+        };
+        let compute = Compute {
+            clock: &clock,
+            activity_lanes: &activity_lanes,
+            flop_monitor: flop_monitor.as_deref(),
+            name: format!("{} compute", config.id),
+            group: &group,
+            total_ticks: compute_ticks as u64,
+            total_flops: machine_ops.total(),
+        };
+        match &compute_scheduler {
+            None => compute.run_for(compute.total_ticks).await,
+            Some(scheduler) => {
+                let mut remaining_ticks = compute.total_ticks;
+                loop {
+                    scheduler.acquire(priority).await;
+                    loop {
+                        let ticks = remaining_ticks.min(scheduler.preemption_ticks());
+                        compute.run_for(ticks).await;
+                        remaining_ticks -= ticks;
+                        if remaining_ticks == 0 || scheduler.should_yield(priority) {
+                            break;
+                        }
+                    }
+                    scheduler.release(remaining_ticks > 0)?;
+                    if remaining_ticks == 0 {
+                        break;
+                    }
+                }
+            }
         }
         stats.borrow_mut().machine_ops.add_assign(machine_ops);
 
@@ -586,11 +785,12 @@ async fn handle_compute_task(
             let Some(view) = view else {
                 continue;
             };
-            lsu.do_access(
+            access_tensor_view(
+                &lsu,
+                scratchpad.as_deref(),
                 AccessType::WriteNonPostedRequest,
-                tensor_view_num_bytes(view),
-                tensor_view_base_addr(view)?,
-                &activity_lanes.lsu_write,
+                view,
+                &activity_lanes,
                 &format!("{} tensor {idx} write", config.id),
                 &group,
             )
@@ -602,6 +802,41 @@ async fn handle_compute_task(
     Ok(())
 }
 
+/// The computation of one partition of a compute task
+struct Compute<'a> {
+    clock: &'a Clock,
+    activity_lanes: &'a ProcessingElementActivityLanes,
+    flop_monitor: Option<&'a FlopMonitor>,
+    name: String,
+    group: &'a Rc<EntityGroup>,
+    total_ticks: u64,
+    total_flops: usize,
+}
+
+impl Compute<'_> {
+    /// Perform `ticks` of the computation
+    async fn run_for(&self, ticks: u64) {
+        if let Some(flop_monitor) = self.flop_monitor {
+            let flops = if ticks == self.total_ticks {
+                self.total_flops as f64
+            } else {
+                self.total_flops as f64 * ticks as f64 / self.total_ticks as f64
+            };
+            flop_monitor.record_interval(ticks, flops);
+        }
+
+        // Lanes cannot support overlapping activity. If a lane will be released
+        // in the current clock cycle then we want to re-use it rather than allocate
+        // a new lane. Hence we wait here for the end of the current clock cycle
+        // to ensure all lanes that will be released in this cycle have been.
+        self.clock.wait_phase(phase::END).await;
+
+        let _activity =
+            ActivityLanes::begin_in_group(&self.activity_lanes.compute, &self.name, self.group);
+        self.clock.wait_ticks(ticks).await;
+    }
+}
+
 // Spawn the handling of memory nodes so that thye can run in parallel.
 async fn handle_memory_task(
     dispatcher: Dispatcher,
@@ -639,6 +874,60 @@ async fn handle_memory_task(
     Ok(())
 }
 
+async fn handle_send_task(
+    dispatcher: Dispatcher,
+    lsu: Rc<LoadStoreUnit>,
+    activity_lanes: Rc<ProcessingElementActivityLanes>,
+    task_idx: usize,
+    config: &SendTaskConfig,
+) -> SimResult {
+    let group = activity_lanes.create_group(&format!("{} operation", config.id));
+    lsu.do_send(
+        config.dst_device,
+        config.num_bytes,
+        &activity_lanes.lsu_write,
+        &format!("{} send", config.id),
+        &group,
+    )
+    .await?;
+    dispatcher.set_task_completed(task_idx)?;
+    Ok(())
+}
+
+async fn handle_recv_task(
+    dispatcher: Dispatcher,
+    lsu: Rc<LoadStoreUnit>,
+    activity_lanes: Rc<ProcessingElementActivityLanes>,
+    task_idx: usize,
+    config: &RecvTaskConfig,
+) -> SimResult {
+    let group = activity_lanes.create_group(&format!("{} operation", config.id));
+    let _activity_guard = ActivityLanes::begin_in_group(
+        &activity_lanes.lsu_read,
+        &format!("{} receive", config.id),
+        &group,
+    );
+    lsu.receive(config.src_device, config.num_bytes).await;
+    dispatcher.set_task_completed(task_idx)?;
+    Ok(())
+}
+
+async fn handle_dma_task(
+    dispatcher: Dispatcher,
+    dma: Option<Rc<DmaEngine>>,
+    activity_lanes: Rc<ProcessingElementActivityLanes>,
+    task_idx: usize,
+    config: &DmaTaskConfig,
+) -> SimResult {
+    let Some(dma) = dma else {
+        return sim_error!("DMA task on a PE without a scratchpad");
+    };
+    let group = activity_lanes.create_group(&format!("{} operation", config.id));
+    dma.copy(config, &activity_lanes, &group).await?;
+    dispatcher.set_task_completed(task_idx)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -650,6 +939,7 @@ mod tests {
             muls_per_tick: 2.5,
             compares_per_tick: 4.0,
             sram_bytes: 1024,
+            cost_model: None,
         };
 
         assert_eq!(
@@ -679,6 +969,7 @@ mod tests {
             muls_per_tick: -1.0,
             compares_per_tick: f64::INFINITY,
             sram_bytes: 1024,
+            cost_model: None,
         };
 
         assert!(
@@ -702,6 +993,7 @@ mod tests {
             muls_per_tick: 1.0,
             compares_per_tick: 1.0,
             sram_bytes: 1024,
+            cost_model: None,
         };
 
         assert!(
@@ -432,6 +432,7 @@ mod tests {
             muls_per_tick: 100.0,
             compares_per_tick: 200.0,
             sram_bytes: 1024,
+            cost_model: None,
         });
         let operator = OperatorAdd {};
         let delay_ticks = operator
@@ -448,6 +449,7 @@ mod tests {
             muls_per_tick: 100.0,
             compares_per_tick: 100.0,
             sram_bytes: 1024,
+            cost_model: None,
         });
         let delay_ticks = operator
             .compute_delay_ticks(
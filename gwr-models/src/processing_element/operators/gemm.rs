@@ -618,6 +618,7 @@ mod tests {
             muls_per_tick: 1.0,
             compares_per_tick: 100.0,
             sram_bytes: 1024,
+            cost_model: None,
         });
         let delay_ticks = operator
             .compute_delay_ticks(
@@ -639,6 +640,7 @@ mod tests {
             muls_per_tick: 1.0,
             compares_per_tick: 100.0,
             sram_bytes: 1024,
+            cost_model: None,
         });
         let delay_ticks = operator
             .compute_delay_ticks(
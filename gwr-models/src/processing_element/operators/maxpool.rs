@@ -94,6 +94,11 @@ impl OperatorMaxPool {
         }
     }
 
+    /// Returns the shape of the output when pooling an input
+    pub fn output_shape<T: HasShape>(&self, input: &T) -> Result<Shape, SimError> {
+        Ok(self.output_shape_and_resolved_params(input)?.0)
+    }
+
     fn auto_pad(&self) -> AutoPad {
         self.auto_pad.unwrap_or_default()
     }
@@ -1078,6 +1083,7 @@ mod tests {
             muls_per_tick: 100.0,
             compares_per_tick: 0.5,
             sram_bytes: 1024,
+            cost_model: None,
         });
 
         let delay = op
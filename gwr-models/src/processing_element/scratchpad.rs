@@ -0,0 +1,186 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A software-managed scratchpad memory local to a Processing Element (PE).
+//!
+//! The scratchpad has its own address range that is private to the PE and is
+//! not part of the memory map. Data is moved between the scratchpad and the
+//! rest of the memory system by explicit DMA tasks. Compute tasks whose
+//! tensors lie within the scratchpad access them there rather than through
+//! the LSU.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::{Clock, phase};
+use gwr_engine::types::{AccessType, SimError, SimResult};
+use gwr_resources::Resource;
+use gwr_resources::base::ResourceGuard;
+use gwr_track::entity::{Entity, EntityGroup};
+
+use crate::memory::memory_map::MemoryMap;
+use crate::processing_element::ActivityLanes;
+
+#[derive(Clone, Debug)]
+pub struct ScratchpadConfig {
+    base_address: u64,
+    capacity_bytes: usize,
+    bw_bytes_per_tick: usize,
+    num_dma_channels: usize,
+}
+
+impl ScratchpadConfig {
+    /// Create a scratchpad that is accessed by a single DMA channel
+    #[must_use]
+    pub fn new(base_address: u64, capacity_bytes: usize, bw_bytes_per_tick: usize) -> Self {
+        Self {
+            base_address,
+            capacity_bytes,
+            bw_bytes_per_tick,
+            num_dma_channels: 1,
+        }
+    }
+
+    /// Set the number of DMA copies that can be in progress at once
+    #[must_use]
+    pub fn with_dma_channels(mut self, num_dma_channels: usize) -> Self {
+        self.num_dma_channels = num_dma_channels;
+        self
+    }
+
+    #[must_use]
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    #[must_use]
+    pub fn capacity_bytes(&self) -> usize {
+        self.capacity_bytes
+    }
+
+    #[must_use]
+    pub fn bw_bytes_per_tick(&self) -> usize {
+        self.bw_bytes_per_tick
+    }
+
+    #[must_use]
+    pub fn num_dma_channels(&self) -> usize {
+        self.num_dma_channels
+    }
+
+    /// Returns whether all `num_bytes` starting at `addr` lie within the
+    /// scratchpad
+    #[must_use]
+    pub fn contains(&self, addr: u64, num_bytes: usize) -> bool {
+        addr >= self.base_address
+            && addr + num_bytes as u64 <= self.base_address + self.capacity_bytes as u64
+    }
+}
+
+#[derive(Default)]
+struct ScratchpadStats {
+    bytes_read: usize,
+    bytes_written: usize,
+}
+
+pub(crate) struct Scratchpad {
+    entity: Rc<Entity>,
+    clock: Clock,
+    config: ScratchpadConfig,
+
+    /// Ensure that the scratchpad only performs one access at a time
+    port: Resource,
+    stats: RefCell<ScratchpadStats>,
+}
+
+impl Scratchpad {
+    pub(crate) fn new(
+        entity: &Rc<Entity>,
+        clock: &Clock,
+        config: &ScratchpadConfig,
+        memory_map: &MemoryMap,
+    ) -> Result<Self, SimError> {
+        if config.capacity_bytes == 0 {
+            return sim_error!("{entity}: scratchpad capacity must be greater than 0");
+        }
+        if config.bw_bytes_per_tick == 0 {
+            return sim_error!("{entity}: scratchpad bandwidth must be greater than 0");
+        }
+        if config.num_dma_channels == 0 {
+            return sim_error!("{entity}: scratchpad must have at least one DMA channel");
+        }
+
+        let start = config.base_address;
+        let end = start + config.capacity_bytes as u64 - 1;
+        if memory_map
+            .regions()
+            .any(|region| region.start <= end && region.end >= start)
+        {
+            return sim_error!(
+                "{entity}: scratchpad 0x{start:x}-0x{end:x} overlaps the memory map"
+            );
+        }
+
+        Ok(Self {
+            entity: entity.clone(),
+            clock: clock.clone(),
+            config: config.clone(),
+            port: Resource::new(1),
+            stats: RefCell::new(ScratchpadStats::default()),
+        })
+    }
+
+    pub(crate) fn config(&self) -> &ScratchpadConfig {
+        &self.config
+    }
+
+    pub(crate) fn check_range(&self, addr: u64, num_bytes: usize) -> SimResult {
+        if !self.config.contains(addr, num_bytes) {
+            let start = self.config.base_address;
+            return sim_error!(
+                "{}: access of {num_bytes} bytes at 0x{addr:x} is outside the scratchpad 0x{start:x}-0x{:x}",
+                self.entity,
+                start + self.config.capacity_bytes as u64 - 1
+            );
+        }
+        Ok(())
+    }
+
+    /// Read or write `num_bytes` of the scratchpad starting at `addr`
+    pub(crate) async fn access(
+        &self,
+        access_type: AccessType,
+        num_bytes: usize,
+        addr: u64,
+        activity_lanes: &Rc<RefCell<ActivityLanes>>,
+        activity_name: &str,
+        group: &Rc<EntityGroup>,
+    ) -> SimResult {
+        self.check_range(addr, num_bytes)?;
+
+        let _port_guard = ResourceGuard::new(self.port.clone()).await;
+
+        // Lanes cannot support overlapping activity so wait for any lanes being
+        // released in this clock cycle to be released.
+        self.clock.wait_phase(phase::END).await;
+        let _activity = ActivityLanes::begin_in_group(activity_lanes, activity_name, group);
+        self.clock
+            .wait_ticks(num_bytes.div_ceil(self.config.bw_bytes_per_tick) as u64)
+            .await;
+
+        let mut stats = self.stats.borrow_mut();
+        match access_type {
+            AccessType::ReadRequest => stats.bytes_read += num_bytes,
+            _ => stats.bytes_written += num_bytes,
+        }
+        Ok(())
+    }
+
+    pub(crate) fn bytes_read(&self) -> usize {
+        self.stats.borrow().bytes_read
+    }
+
+    pub(crate) fn bytes_written(&self) -> usize {
+        self.stats.borrow().bytes_written
+    }
+}
@@ -6,6 +6,7 @@ use gwr_engine::types::SimError;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Serialize, Serializer};
 
+use crate::memory::memory_map::DeviceId;
 use crate::processing_element::operators::add::OperatorAdd;
 use crate::processing_element::operators::gemm::OperatorGemm;
 use crate::processing_element::operators::maxpool::OperatorMaxPool;
@@ -135,6 +136,47 @@ pub enum MemoryOp {
     Store,
 }
 
+/// Explicit copies between the scratchpad of a PE and the rest of the memory
+/// system performed by the DMA engine of the PE
+#[derive(Debug, Clone)]
+pub struct DmaTaskConfig {
+    /// Only needed as a debug aid
+    pub id: String,
+    pub op: DmaOp,
+    pub src_addr: u64,
+    pub dst_addr: u64,
+    pub num_bytes: usize,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DmaOp {
+    /// Copy from memory into the scratchpad
+    CopyIn,
+    /// Copy from the scratchpad out to memory
+    CopyOut,
+}
+
+/// A transfer of data over the fabric to another PE
+#[derive(Debug, Clone)]
+pub struct SendTaskConfig {
+    /// Only needed as a debug aid
+    pub id: String,
+    /// The device ID of the PE that the data is sent to
+    pub dst_device: DeviceId,
+    pub num_bytes: usize,
+}
+
+/// A wait for the data sent by another PE
+#[derive(Debug, Clone)]
+pub struct RecvTaskConfig {
+    /// Only needed as a debug aid
+    pub id: String,
+    /// The device ID of the PE that the data is sent by
+    pub src_device: DeviceId,
+    pub num_bytes: usize,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum SyncRegion {
     Local,
@@ -145,5 +187,8 @@ pub enum SyncRegion {
 pub enum Task {
     ComputeTask { config: ComputeTaskConfig },
     MemoryTask { config: MemoryTaskConfig },
+    DmaTask { config: DmaTaskConfig },
+    SendTask { config: SendTaskConfig },
+    RecvTask { config: RecvTaskConfig },
     SyncTask { region: SyncRegion },
 }
@@ -83,6 +83,20 @@ impl Field {
         value & !((mask) << self.offset)
     }
 
+    /// Return the bits of the register covered by this field
+    #[must_use]
+    pub fn mask(&self) -> u64 {
+        let mask: u64 = (1 << self.num_bits) - 1;
+        mask << self.offset
+    }
+
+    /// Return `field_value` placed in the bits of the register covered by
+    /// this field
+    #[must_use]
+    pub fn place(&self, field_value: u64) -> u64 {
+        (field_value << self.offset) & self.mask()
+    }
+
     #[must_use]
     pub fn value(&self, value: u64) -> u64 {
         let mask: u64 = (1 << self.num_bits) - 1;
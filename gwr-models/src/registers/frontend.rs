@@ -0,0 +1,293 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A bus frontend that exposes a register file as a memory-mapped target.
+//!
+//! The frontend receives [MemoryAccess] requests, for example from a PE or
+//! DMA engine over a fabric, and decodes their address to a register of the
+//! [RegisterFile]. The offset of the address from the base address of the
+//! frontend is used as the index of the register.
+//!
+//! Writes must carry their [data](MemoryAccess::data) and read responses are
+//! returned with the value of the register as their data. Each access reads or
+//! writes a single register of up to 8 bytes. Masked writes only update the
+//! enabled bytes of the register.
+//!
+//! Accesses to offsets without a register are reported as errors.
+//!
+//! # Ports
+//!
+//! This component has:
+//!  - One [input port](gwr_engine::port::InPort): `rx` for requests
+//!  - One [output port](gwr_engine::port::OutPort): `tx` for responses
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use async_trait::async_trait;
+use gwr_components::{connect_tx, port_rx, take_option};
+use gwr_engine::engine::Engine;
+use gwr_engine::port::{InPort, OutPort, PortStateResult};
+use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Routable, Runnable};
+use gwr_engine::types::{AccessType, SimError, SimResult};
+use gwr_model_builder::{EntityDisplay, EntityGet};
+use gwr_track::debug;
+use gwr_track::entity::Entity;
+use gwr_track::tracker::aka::Aka;
+
+use crate::memory::memory_access::MemoryAccess;
+use crate::memory::traits::{AccessMemory, ReadMemory};
+use crate::registers::regfile::RegisterFile;
+
+/// The largest access supported, which is the size of the register values
+const MAX_ACCESS_BYTES: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct RegisterFrontendConfig {
+    base_address: u64,
+    size_bytes: usize,
+    access_ticks: usize,
+}
+
+impl RegisterFrontendConfig {
+    /// Create a frontend decoding `size_bytes` of addresses from
+    /// `base_address` that takes one tick per access
+    #[must_use]
+    pub fn new(base_address: u64, size_bytes: usize) -> Self {
+        Self {
+            base_address,
+            size_bytes,
+            access_ticks: 1,
+        }
+    }
+
+    /// Set the number of ticks each register access takes
+    #[must_use]
+    pub fn with_access_ticks(mut self, access_ticks: usize) -> Self {
+        self.access_ticks = access_ticks;
+        self
+    }
+
+    #[must_use]
+    pub fn base_address(&self) -> u64 {
+        self.base_address
+    }
+
+    #[must_use]
+    pub fn size_bytes(&self) -> usize {
+        self.size_bytes
+    }
+
+    #[must_use]
+    pub fn access_ticks(&self) -> usize {
+        self.access_ticks
+    }
+}
+
+#[derive(Default)]
+struct RegisterFrontendStats {
+    num_reads: usize,
+    num_writes: usize,
+}
+
+#[derive(EntityGet, EntityDisplay)]
+pub struct RegisterFrontend<R>
+where
+    R: RegisterFile + 'static,
+{
+    entity: Rc<Entity>,
+    clock: Clock,
+    config: RegisterFrontendConfig,
+    regs: Rc<R>,
+    stats: RefCell<RegisterFrontendStats>,
+
+    rx: RefCell<Option<InPort<MemoryAccess>>>,
+    tx: RefCell<Option<OutPort<MemoryAccess>>>,
+}
+
+impl<R> RegisterFrontend<R>
+where
+    R: RegisterFile + 'static,
+{
+    pub fn new_and_register_with_renames(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        aka: Option<&Aka>,
+        config: RegisterFrontendConfig,
+        regs: &Rc<R>,
+    ) -> Result<Rc<Self>, SimError> {
+        let entity = Rc::new(Entity::new(parent, name));
+        if config.size_bytes == 0 {
+            return sim_error!("{entity}: size_bytes must be greater than 0");
+        }
+
+        let rx = InPort::new_with_renames(engine, clock, &entity, "rx", aka);
+        let tx = OutPort::new_with_renames(&entity, "tx", aka);
+        let rc_self = Rc::new(Self {
+            entity,
+            clock: clock.clone(),
+            config,
+            regs: regs.clone(),
+            stats: RefCell::new(RegisterFrontendStats::default()),
+            rx: RefCell::new(Some(rx)),
+            tx: RefCell::new(Some(tx)),
+        });
+        engine.register(rc_self.clone());
+        Ok(rc_self)
+    }
+
+    pub fn new_and_register(
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        config: RegisterFrontendConfig,
+        regs: &Rc<R>,
+    ) -> Result<Rc<Self>, SimError> {
+        Self::new_and_register_with_renames(engine, clock, parent, name, None, config, regs)
+    }
+
+    pub fn connect_port_tx(&self, port_state: PortStateResult<MemoryAccess>) -> SimResult {
+        connect_tx!(self.tx, connect ; port_state)
+    }
+
+    pub fn port_rx(&self) -> PortStateResult<MemoryAccess> {
+        port_rx!(self.rx, state)
+    }
+
+    #[must_use]
+    pub fn num_reads(&self) -> usize {
+        self.stats.borrow().num_reads
+    }
+
+    #[must_use]
+    pub fn num_writes(&self) -> usize {
+        self.stats.borrow().num_writes
+    }
+
+    /// Return the index of the register accessed, checking that the access
+    /// is supported
+    fn decode(&self, access: &MemoryAccess) -> Result<u64, SimError> {
+        let addr = access.dst_addr();
+        let num_bytes = access.access_size_bytes();
+        if access.scatter_gather().is_some() {
+            return sim_error!(
+                "{}: unsupported scatter-gather register access at 0x{addr:x}",
+                self.entity
+            );
+        }
+        if num_bytes == 0 || num_bytes > MAX_ACCESS_BYTES {
+            return sim_error!(
+                "{}: unsupported register access of {num_bytes} bytes at 0x{addr:x}",
+                self.entity
+            );
+        }
+
+        let base = self.config.base_address;
+        if addr < base || addr + num_bytes as u64 > base + self.config.size_bytes as u64 {
+            return sim_error!(
+                "{}: access to 0x{addr:x} is outside the registers 0x{base:x}-0x{:x}",
+                self.entity,
+                base + self.config.size_bytes as u64 - 1
+            );
+        }
+
+        let index = addr - base;
+        if !self.regs.contains(index) {
+            return sim_error!(
+                "{}: {} of reserved register offset 0x{index:x}",
+                self.entity,
+                access.access_type()
+            );
+        }
+        Ok(index)
+    }
+
+    /// Write the register, only updating the enabled bytes of masked writes
+    fn write(&self, access: &MemoryAccess, index: u64) -> SimResult {
+        let Some(data) = access.data() else {
+            return sim_error!("{}: register write without data", self.entity);
+        };
+
+        let num_bytes = access.access_size_bytes();
+        let mut value = if num_bytes < MAX_ACCESS_BYTES {
+            self.regs.value(index) & (!0 << (8 * num_bytes))
+        } else {
+            0
+        };
+        for byte in 0..num_bytes {
+            let enabled = access
+                .byte_enables()
+                .is_none_or(|byte_enables| byte_enables.is_enabled(byte));
+            let byte_value = if enabled {
+                data.get(byte).copied().unwrap_or(0)
+            } else {
+                (self.regs.value(index) >> (8 * byte)) as u8
+            };
+            value |= (byte_value as u64) << (8 * byte);
+        }
+
+        debug!(self.entity ; "Write 0x{value:x} to register 0x{index:x}");
+        self.regs.write(&self.clock, index, value);
+        self.stats.borrow_mut().num_writes += 1;
+        Ok(())
+    }
+
+    fn read(&self, access: &MemoryAccess, index: u64) -> Vec<u8> {
+        let value = self.regs.read(index);
+        debug!(self.entity ; "Read 0x{value:x} from register 0x{index:x}");
+        self.stats.borrow_mut().num_reads += 1;
+        value.to_le_bytes()[..access.access_size_bytes()].to_vec()
+    }
+}
+
+#[async_trait(?Send)]
+impl<R> Runnable for RegisterFrontend<R>
+where
+    R: RegisterFile + 'static,
+{
+    async fn run(&self) -> SimResult {
+        let mut rx = take_option!(self.rx);
+        let mut tx = take_option!(self.tx);
+
+        loop {
+            let access = rx.get()?.await;
+            debug!(self.entity ; "Register access {}", access);
+            let index = self.decode(&access)?;
+
+            let access_type = access.access_type();
+            match access_type {
+                AccessType::ReadRequest => {
+                    self.clock.wait_ticks(self.config.access_ticks as u64).await;
+                    let data = self.read(&access, index);
+                    tx.put(access.to_response(self)?.with_data(data))?.await;
+                }
+                AccessType::WriteRequest | AccessType::WriteNonPostedRequest => {
+                    // Writes are resolved while the clock advances for the access
+                    self.write(&access, index)?;
+                    self.clock.wait_ticks(self.config.access_ticks as u64).await;
+                    if access_type == AccessType::WriteNonPostedRequest {
+                        tx.put(access.to_response(self)?)?.await;
+                    }
+                }
+                AccessType::Control
+                | AccessType::ReadResponse
+                | AccessType::WriteNonPostedResponse => {
+                    return sim_error!("{}: unsupported {access_type} received", self.entity);
+                }
+            }
+        }
+    }
+}
+
+impl<R> ReadMemory for RegisterFrontend<R>
+where
+    R: RegisterFile + 'static,
+{
+    fn read(&self) -> Vec<u8> {
+        Vec::new()
+    }
+}
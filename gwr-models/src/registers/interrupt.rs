@@ -0,0 +1,383 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Interrupts generated from register fields.
+//!
+//! Each interrupt source is described by a triplet of fields:
+//!  - A `status` field that the hardware sets when the event occurs.
+//!  - A `mask` field that enables the source when set.
+//!  - A `clear` field that clears the corresponding `status` bits when ones
+//!    are written to it. The `clear` field must be in a different register to
+//!    the `status` field.
+//!
+//! The [`build_register_interrupts!`](crate::build_register_interrupts) macro
+//! builds the interrupts of a register file. Its [InterruptLine] is asserted
+//! whenever any unmasked status bit is set.
+
+use std::cell::Cell;
+
+use gwr_engine::events::repeated::Repeated;
+use gwr_engine::traits::Event;
+
+/// Returns whether an interrupt source is pending
+pub type PendingFn = Box<dyn Fn() -> bool>;
+
+/// A level-sensitive interrupt line
+pub struct InterruptLine {
+    asserted: Cell<bool>,
+    num_assertions: Cell<usize>,
+    changed: Repeated<bool>,
+}
+
+impl InterruptLine {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            asserted: Cell::new(false),
+            num_assertions: Cell::new(0),
+            changed: Repeated::new(false),
+        }
+    }
+
+    #[must_use]
+    pub fn is_asserted(&self) -> bool {
+        self.asserted.get()
+    }
+
+    /// Return the number of times the line has been asserted
+    #[must_use]
+    pub fn num_assertions(&self) -> usize {
+        self.num_assertions.get()
+    }
+
+    /// Return the event that is notified with the new level of the line
+    /// whenever it changes
+    #[must_use]
+    pub fn changed(&self) -> Repeated<bool> {
+        self.changed.clone()
+    }
+
+    /// Drive the line to `level`
+    pub fn set_level(&self, level: bool) {
+        if self.asserted.replace(level) != level {
+            if level {
+                self.num_assertions.set(self.num_assertions.get() + 1);
+            }
+            self.changed.notify_result(level);
+        }
+    }
+
+    /// Wait until the line is asserted
+    pub async fn wait_asserted(&self) {
+        while !self.is_asserted() {
+            self.changed.listen().await;
+        }
+    }
+}
+
+impl Default for InterruptLine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Build the interrupts of a register file created by
+/// [`build_register_file!`](crate::build_register_file).
+///
+/// Each source is given as `name: status, mask, clear` where each field is
+/// given as `register.field`. This creates an `<Name>Interrupts` struct whose
+/// [InterruptLine] is updated whenever the registers are resolved after a
+/// `write` or `set` through it.
+#[macro_export]
+macro_rules! build_register_interrupts {
+    (
+        $(#[$($attrs:tt)*])*
+        $name:ident, $regfile:ident ;
+        $(
+            $(#[$($source_attrs:tt)*])*
+            $source:ident :
+                $status_reg:ident . $status:ident,
+                $mask_reg:ident . $mask:ident,
+                $clear_reg:ident . $clear:ident
+        ),+ $(,)*
+    ) => {
+    $crate::registers::paste! {
+        $(#[$($attrs)*])*
+        #[doc=concat!("\n\nThe interrupt sources are:\n")]
+        $(
+            #[doc=concat!("  - ", stringify!($source), ": status `", stringify!($status_reg), ".", stringify!($status),
+                "`, mask `", stringify!($mask_reg), ".", stringify!($mask),
+                "`, clear `", stringify!($clear_reg), ".", stringify!($clear), "`.")]
+        )+
+        pub struct [< $name Interrupts >] {
+            line: $crate::registers::interrupt::InterruptLine,
+            sources: Vec<(&'static str, $crate::registers::interrupt::PendingFn)>,
+        }
+
+        impl [< $name Interrupts >] {
+            #[must_use] pub fn new(regs: &[< $regfile Regs >]) -> std::rc::Rc<Self> {
+                let sources: Vec<(&'static str, $crate::registers::interrupt::PendingFn)> = vec![
+                    $(
+                    {
+                        let status = regs.[< $status_reg:lower >].state().clone();
+                        let mask = regs.[< $mask_reg:lower >].state().clone();
+                        let pending = move || {
+                            status.$status.value(status.value()) & mask.$mask.value(mask.value()) != 0
+                        };
+                        (stringify!($source), Box::new(pending))
+                    },
+                    )+
+                ];
+                std::rc::Rc::new(Self {
+                    line: $crate::registers::interrupt::InterruptLine::new(),
+                    sources,
+                })
+            }
+
+            /// Return the interrupt line
+            #[must_use] pub fn line(&self) -> &$crate::registers::interrupt::InterruptLine {
+                &self.line
+            }
+
+            /// Return whether the source has its status set and is not masked
+            #[must_use] pub fn is_pending(&self, source: &str) -> bool {
+                self.sources.iter().any(|(name, pending)| *name == source && pending())
+            }
+
+            /// Return the names of all sources that are pending
+            #[must_use] pub fn pending(&self) -> Vec<&'static str> {
+                self.sources
+                    .iter()
+                    .filter(|(_, pending)| pending())
+                    .map(|(name, _)| *name)
+                    .collect()
+            }
+
+            /// Write to a register as instructions would. Writing ones to a
+            /// `clear` field clears the corresponding `status` bits.
+            pub fn write(
+                self: &std::rc::Rc<Self>,
+                resolver: &impl gwr_engine::traits::Resolver,
+                regs: &[< $regfile Regs >],
+                index: u64,
+                value: u64,
+            ) {
+                regs.write(resolver, index, value);
+
+                // Combine the bits cleared in each status register
+                let mut cleared: Vec<(u64, u64)> = Vec::new();
+                $(
+                if Some(index) == [< $regfile Regs >]::index_of(stringify!($clear_reg)) {
+                    let clear = regs.[< $clear_reg:lower >].state().$clear.value(value);
+                    let bits = regs.[< $status_reg:lower >].state().$status.place(clear);
+                    let status_index = [< $regfile Regs >]::index_of(stringify!($status_reg)).unwrap();
+                    match cleared.iter_mut().find(|(i, _)| *i == status_index) {
+                        Some((_, cleared_bits)) => *cleared_bits |= bits,
+                        None => cleared.push((status_index, bits)),
+                    }
+                }
+                )+
+                for (status_index, bits) in cleared {
+                    if bits != 0 {
+                        regs.set(resolver, status_index, regs.value(status_index) & !bits);
+                    }
+                }
+                resolver.add_resolve(self.clone());
+            }
+
+            /// Set the value of a register as the hardware would, for example
+            /// to raise the status of a source
+            pub fn set(
+                self: &std::rc::Rc<Self>,
+                resolver: &impl gwr_engine::traits::Resolver,
+                regs: &[< $regfile Regs >],
+                index: u64,
+                value: u64,
+            ) {
+                regs.set(resolver, index, value);
+                resolver.add_resolve(self.clone());
+            }
+
+            /// Drive the interrupt line from the current register values
+            pub fn update(&self) {
+                self.line.set_level(self.sources.iter().any(|(_, pending)| pending()));
+            }
+        }
+
+        impl gwr_engine::traits::Resolve for [< $name Interrupts >] {
+            fn resolve(&self) {
+                self.update();
+            }
+        }
+    }}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use gwr_engine::run_simulation;
+    use gwr_engine::test_helpers::start_test;
+    use gwr_engine::traits::Resolve;
+
+    use crate::registers::register::Register;
+    use crate::registers::state::{RegisterState, UpdatePriority};
+    use crate::registers::test_helpers::TestResolver;
+    use crate::{
+        build_register_file, build_register_state, build_register_states, build_register_view,
+    };
+
+    build_register_state!(
+        /// Interrupt status
+        IntStatus, 32 ;
+        done: 1, 0,
+        error: 1, 0,
+    );
+
+    build_register_state!(
+        /// Interrupt mask
+        IntMask, 32 ;
+        done: 1, 0,
+        error: 1, 0,
+    );
+
+    build_register_state!(
+        /// Interrupt clear
+        IntClear, 32 ;
+        done: 1, 0,
+        error: 1, 0,
+    );
+
+    build_register_states!(
+        /// All register state
+        IrqStates ; IntStatus, 1, IntMask, 1, IntClear, 1,
+    );
+
+    build_register_view!(
+        IntStatus, IntStatusState, IntStatusStatePerms, High ;
+        done: ReadVolatileOnly,
+        error: ReadVolatileOnly,
+    );
+
+    build_register_view!(
+        IntMask, IntMaskState, IntMaskStatePerms, High ;
+        done: ReadWrite,
+        error: ReadWrite,
+    );
+
+    build_register_view!(
+        IntClear, IntClearState, IntClearStatePerms, High ;
+        done: WriteOneCommits,
+        error: WriteOneCommits,
+    );
+
+    build_register_file!(
+        /// Registers with interrupts
+        Irq, IrqStates ;
+        int_status: 0x0, IntStatus, IntStatus,
+        int_mask: 0x4, IntMask, IntMask,
+        int_clear: 0x8, IntClear, IntClear,
+    );
+
+    build_register_interrupts!(
+        /// Test interrupts
+        Irq, Irq ;
+        done: int_status.done, int_mask.done, int_clear.done,
+        error: int_status.error, int_mask.error, int_clear.error,
+    );
+
+    const STATUS: u64 = irq_indices::INTSTATUS;
+    const MASK: u64 = irq_indices::INTMASK;
+    const CLEAR: u64 = irq_indices::INTCLEAR;
+
+    #[test]
+    fn masked_status_does_not_interrupt() {
+        let resolver = TestResolver::new();
+        let states = IrqStates::new();
+        let regs = IrqRegs::new(&states, 0);
+        let interrupts = IrqInterrupts::new(&regs);
+
+        interrupts.set(&resolver, &regs, STATUS, 0b11);
+        resolver.resolve();
+        assert_eq!(regs.int_status.value(), 0b11);
+        assert!(!interrupts.line().is_asserted());
+        assert!(interrupts.pending().is_empty());
+
+        // Unmasking a source with its status set asserts the line
+        interrupts.write(&resolver, &regs, MASK, 0b10);
+        resolver.resolve();
+        assert!(interrupts.line().is_asserted());
+        assert_eq!(interrupts.pending(), ["error"]);
+        assert!(!interrupts.is_pending("done"));
+    }
+
+    #[test]
+    fn clear_deasserts() {
+        let resolver = TestResolver::new();
+        let states = IrqStates::new();
+        let regs = IrqRegs::new(&states, 0);
+        let interrupts = IrqInterrupts::new(&regs);
+
+        interrupts.write(&resolver, &regs, MASK, 0b11);
+        interrupts.set(&resolver, &regs, STATUS, 0b11);
+        resolver.resolve();
+        assert!(interrupts.line().is_asserted());
+        assert_eq!(interrupts.pending(), ["done", "error"]);
+
+        // Clearing one source leaves the other pending
+        interrupts.write(&resolver, &regs, CLEAR, 0b01);
+        resolver.resolve();
+        assert_eq!(regs.read(STATUS), 0b10);
+        assert!(interrupts.line().is_asserted());
+
+        interrupts.write(&resolver, &regs, CLEAR, 0b10);
+        resolver.resolve();
+        assert_eq!(regs.int_status.value(), 0);
+        assert!(!interrupts.line().is_asserted());
+        assert_eq!(interrupts.line().num_assertions(), 1);
+
+        // Resetting the registers masks all sources
+        interrupts.set(&resolver, &regs, STATUS, 0b11);
+        regs.reset_sync(&resolver);
+        resolver.resolve();
+        interrupts.update();
+        assert!(!interrupts.line().is_asserted());
+    }
+
+    #[test]
+    fn wait_for_interrupt() {
+        let mut engine = start_test(file!());
+        let clock = engine.default_clock();
+        let states = IrqStates::new();
+        let regs = Rc::new(IrqRegs::new(&states, 0));
+        let interrupts = IrqInterrupts::new(&regs);
+        let interrupted_at = Rc::new(Cell::new(None));
+
+        {
+            let clock = clock.clone();
+            let interrupts = interrupts.clone();
+            let interrupted_at = interrupted_at.clone();
+            engine.spawn(async move {
+                interrupts.line().wait_asserted().await;
+                interrupted_at.set(Some(clock.tick_now().tick()));
+                Ok(())
+            });
+        }
+
+        {
+            let clock = clock.clone();
+            engine.spawn(async move {
+                interrupts.write(&clock, &regs, MASK, 0b01);
+                clock.wait_ticks(10).await;
+                interrupts.set(&clock, &regs, STATUS, 0b01);
+
+                // The status is resolved when the clock next advances
+                clock.wait_ticks(1).await;
+                Ok(())
+            });
+        }
+
+        run_simulation!(engine);
+        assert_eq!(interrupted_at.get(), Some(11));
+    }
+}
@@ -3,9 +3,12 @@
 //! Control and Status Registers builders.
 
 pub mod field;
+pub mod frontend;
+pub mod interrupt;
 pub mod regfile;
 pub mod register;
 pub mod state;
+pub mod systemrdl;
 
 pub mod test_helpers;
 
@@ -13,6 +16,7 @@ pub mod test_helpers;
 pub use paste::paste;
 
 /// Register access permissions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Permission {
     /// Writes ignored. Reads return constant value.
     ReadOnly,
@@ -332,6 +336,31 @@ pub mod tests {
         assert_eq!(*cb_handler.written_count.borrow(), 0);
     }
 
+    #[test]
+    fn traced_accesses() {
+        let (test_tracker, tracker) = gwr_track::test_init!(10);
+        let top = gwr_track::entity::toplevel(&tracker, "top");
+        let entity = Rc::new(gwr_track::entity::Entity::new(&top, "csrs"));
+
+        let resolver = TestResolver::new();
+        let csr_states = TestCsrStates::new();
+        let csrs = TestCsrsRwRegs::new(&csr_states, 0).with_tracing(&entity);
+        let events_before = test_tracker.events().len();
+
+        csrs.write(&resolver, testcsrsrw_indices::CSR, 0xffff_ffff);
+        resolver.resolve();
+        let _ = csrs.read(testcsrsrw_indices::CSR);
+
+        // The write reports the value after the field permissions are applied
+        assert_eq!(
+            test_tracker.events()[events_before..],
+            [
+                format!("{}: register write Csr@0x0: 0xcc01 -> 0xccff", entity.id),
+                format!("{}: register read Csr@0x0: 0xccff", entity.id),
+            ]
+        );
+    }
+
     #[test]
     fn write_one_commit() {
         // Ensure that the `WriteOneCommit` field doesn't get changed, but that a
@@ -2,6 +2,26 @@
 
 //! Control and Status Registers.
 
+use gwr_engine::traits::Resolver;
+
+/// Access to the registers of a register file by index.
+///
+/// This is implemented by all register files built with
+/// [`build_register_file!`](crate::build_register_file).
+pub trait RegisterFile {
+    /// Write to a register as instructions would
+    fn write(&self, resolver: &impl Resolver, index: u64, value: u64);
+
+    /// Read a register as instructions would
+    fn read(&self, index: u64) -> u64;
+
+    /// Return the value of a register without triggering `read` callbacks
+    fn value(&self, index: u64) -> u64;
+
+    /// Returns whether there is a register at `index`
+    fn contains(&self, index: u64) -> bool;
+}
+
 #[macro_export]
 macro_rules! build_register_file {
     (
@@ -40,6 +60,44 @@ macro_rules! build_register_file {
                 }
             }
 
+            /// Emit a trace event through `entity` for every `read` and
+            /// `write` of the registers
+            #[allow(dead_code)]
+            #[must_use] pub fn with_tracing(mut self, entity: &std::rc::Rc<gwr_track::entity::Entity>) -> Self {
+                $(
+                let tracer = $crate::registers::register::RegisterTracer::new(entity, $index, stringify!($reg_name));
+                self.[< $reg_name:lower >].install_write_cb(tracer.clone());
+                self.[< $reg_name:lower >].install_read_cb(tracer);
+                )+
+                self
+            }
+
+            /// Set the value of a register without triggering `write` callbacks
+            pub fn set(&self, resolver: &impl gwr_engine::traits::Resolver, index: u64, value: u64) {
+                match index {
+                    $( $index => self.[< $reg_name:lower >].set(resolver, value), )+
+                    // ignore missing indices
+                    _ => {},
+                }
+            }
+
+            /// Return the value of a register without triggering `read` callbacks
+            #[must_use] pub fn value(&self, index: u64) -> u64 {
+                match index {
+                    $( $index => self.[< $reg_name:lower >].value(), )+
+                    // ignore missing indices
+                    _ => {0},
+                }
+            }
+
+            /// Return the index of the register with the given name
+            #[must_use] pub fn index_of(name: &str) -> Option<u64> {
+                match name {
+                    $( stringify!($reg_name) => Some($index), )+
+                    _ => None,
+                }
+            }
+
             #[must_use] pub fn read(&self, index: u64) -> u64 {
                 match index {
                     $( $index => self.[< $reg_name:lower >].read(), )+
@@ -55,5 +113,23 @@ macro_rules! build_register_file {
                 )+
             }
         }
+
+        impl $crate::registers::regfile::RegisterFile for [< $regfile Regs >] {
+            fn write(&self, resolver: &impl gwr_engine::traits::Resolver, index: u64, value: u64) {
+                [< $regfile Regs >]::write(self, resolver, index, value);
+            }
+
+            fn read(&self, index: u64) -> u64 {
+                [< $regfile Regs >]::read(self, index)
+            }
+
+            fn value(&self, index: u64) -> u64 {
+                [< $regfile Regs >]::value(self, index)
+            }
+
+            fn contains(&self, index: u64) -> bool {
+                matches!(index, $( $index )|+)
+            }
+        }
     }}
 }
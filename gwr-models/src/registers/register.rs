@@ -5,6 +5,7 @@
 use std::rc::Rc;
 
 use gwr_engine::traits::Resolver;
+use gwr_track::entity::{Entity, RegisterAccess};
 
 /// Interface to a [`Register`]
 pub trait Register {
@@ -50,6 +51,50 @@ pub trait Read {
 pub type WrittenCallback = Rc<dyn Written + 'static>;
 pub type ReadCallback = Rc<dyn Read + 'static>;
 
+/// Callbacks that emit a [RegisterAccess] trace event through an entity for
+/// every `read` and `write` of a register.
+pub struct RegisterTracer {
+    entity: Rc<Entity>,
+    offset: u64,
+    view: &'static str,
+}
+
+impl RegisterTracer {
+    #[must_use]
+    pub fn new(entity: &Rc<Entity>, offset: u64, view: &'static str) -> Rc<Self> {
+        Rc::new(Self {
+            entity: entity.clone(),
+            offset,
+            view,
+        })
+    }
+}
+
+impl Written for RegisterTracer {
+    fn written(&self, old_value: u64, _value_written: u64, new_value: u64) {
+        if self.entity.trace_enabled() {
+            self.entity.track_register_access(RegisterAccess::write(
+                self.offset,
+                self.view,
+                old_value,
+                new_value,
+            ));
+        }
+    }
+}
+
+impl Read for RegisterTracer {
+    fn read(&self, value_read: u64) {
+        if self.entity.trace_enabled() {
+            self.entity.track_register_access(RegisterAccess::read(
+                self.offset,
+                self.view,
+                value_read,
+            ));
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! build_register_view {
     (
@@ -91,6 +136,12 @@ macro_rules! build_register_view {
                 }
             }
 
+            /// Return the underlying state, which gives access to the fields
+            #[allow(dead_code)]
+            pub fn state(&self) -> &std::rc::Rc<$state> {
+                &self.state
+            }
+
             /// Install a callback function to be called whenever a `write` completes
             #[allow(dead_code)]
             pub fn install_write_cb(&mut self, cb: $crate::registers::register::WrittenCallback) {
@@ -0,0 +1,879 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Import of register definitions from SystemRDL.
+//!
+//! A [RegisterMap] is parsed from the SystemRDL description of an `addrmap`
+//! and can then generate the [`build_register_state!`](crate::build_register_state),
+//! [`build_register_states!`](crate::build_register_states),
+//! [`build_register_view!`](crate::build_register_view) and
+//! [`build_register_file!`](crate::build_register_file) invocations that
+//! model it. This is typically done from a `build.rs` script with the
+//! generated code brought into the model with `include!`.
+//!
+//! Only the subset of SystemRDL needed to describe flat register maps is
+//! supported:
+//!  - A single `addrmap` containing `reg` instances, optionally at an explicit
+//!    `@` offset. Registers without an offset follow the previous register.
+//!  - Named or anonymous `reg` and `field` definitions.
+//!  - Fields given as `[msb:lsb]`, `[width]` or a single bit, with an optional
+//!    reset value.
+//!  - The `regwidth`, `sw`, `hw`, `reset`, `singlepulse` and `desc`
+//!    properties. Other properties are ignored.
+//!
+//! The `sw` and `hw` access of each field determine its [Permission].
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+
+use crate::registers::Permission;
+
+const DEFAULT_REGWIDTH: usize = 32;
+
+/// The register [Field](crate::registers::field::Field) masks cannot cover
+/// all 64 bits
+const MAX_FIELD_BITS: usize = 64;
+
+/// A field of an imported register
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDesc {
+    name: String,
+    desc: Option<String>,
+    lsb: usize,
+    num_bits: usize,
+    reset_value: u64,
+    permission: Permission,
+}
+
+impl FieldDesc {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn desc(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
+    #[must_use]
+    pub fn lsb(&self) -> usize {
+        self.lsb
+    }
+
+    #[must_use]
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    #[must_use]
+    pub fn reset_value(&self) -> u64 {
+        self.reset_value
+    }
+
+    #[must_use]
+    pub fn permission(&self) -> Permission {
+        self.permission
+    }
+}
+
+/// A register of an imported register map
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisterDesc {
+    name: String,
+    desc: Option<String>,
+    offset: u64,
+    num_bits: usize,
+    fields: Vec<FieldDesc>,
+}
+
+impl RegisterDesc {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn desc(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
+    /// Return the byte offset of the register within the register map
+    #[must_use]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    #[must_use]
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Return the fields of the register ordered by their least significant
+    /// bit
+    #[must_use]
+    pub fn fields(&self) -> &[FieldDesc] {
+        &self.fields
+    }
+
+    /// Return the value of the register at reset
+    #[must_use]
+    pub fn reset_value(&self) -> u64 {
+        self.fields
+            .iter()
+            .fold(0, |value, field| value | (field.reset_value << field.lsb))
+    }
+}
+
+/// A register map imported from a SystemRDL `addrmap`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisterMap {
+    name: String,
+    desc: Option<String>,
+    registers: Vec<RegisterDesc>,
+}
+
+impl RegisterMap {
+    /// Parse the register map from SystemRDL source
+    pub fn from_systemrdl(source: &str) -> Result<Self, SimError> {
+        let tokens = tokenize(source)?;
+        Parser::new(tokens).parse_root()
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn desc(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
+    /// Return the registers ordered by their offset
+    #[must_use]
+    pub fn registers(&self) -> &[RegisterDesc] {
+        &self.registers
+    }
+
+    #[must_use]
+    pub fn register(&self, name: &str) -> Option<&RegisterDesc> {
+        self.registers.iter().find(|register| register.name == name)
+    }
+
+    /// Generate the Rust source that builds the register map.
+    ///
+    /// Each register has its own state and view named after the register in
+    /// `UpperCamelCase`. The register file is named after the `addrmap` and
+    /// each register is indexed by its byte offset. Any gaps between fields
+    /// are filled with [Reserved](Permission::Reserved) fields.
+    #[must_use]
+    pub fn to_rust(&self) -> String {
+        let mut rust = String::new();
+        let map_name = camel_case(&self.name);
+        let states_name = format!("{map_name}States");
+
+        writeln!(
+            rust,
+            "// Generated from the SystemRDL addrmap `{}`. Do not edit.",
+            self.name
+        )
+        .unwrap();
+        rust += "
+use gwr_engine::traits::Resolve;
+use gwr_models::registers::register::Register;
+use gwr_models::registers::state::{RegisterState, UpdatePriority};
+use gwr_models::{
+    build_register_file, build_register_state, build_register_states, build_register_view,
+};
+";
+
+        for register in &self.registers {
+            let reg_name = camel_case(&register.name);
+            let fields = fields_with_reserved(register);
+
+            rust += "\nbuild_register_state!(\n";
+            write_docs(&mut rust, "    ", register.desc.as_deref());
+            writeln!(rust, "    {reg_name}, {} ;", register.num_bits).unwrap();
+            for field in &fields {
+                write_docs(&mut rust, "    ", field.desc.as_deref());
+                writeln!(
+                    rust,
+                    "    {}: {}, 0x{:x},",
+                    field.name, field.num_bits, field.reset_value
+                )
+                .unwrap();
+            }
+            rust += ");\n";
+
+            rust += "\nbuild_register_view!(\n";
+            write_docs(&mut rust, "    ", register.desc.as_deref());
+            writeln!(
+                rust,
+                "    {reg_name}, {reg_name}State, {reg_name}StatePerms, High ;"
+            )
+            .unwrap();
+            for field in &fields {
+                writeln!(rust, "    {}: {:?},", field.name, field.permission).unwrap();
+            }
+            rust += ");\n";
+        }
+
+        rust += "\nbuild_register_states!(\n";
+        write_docs(&mut rust, "    ", self.desc.as_deref());
+        writeln!(rust, "    {states_name} ;").unwrap();
+        for register in &self.registers {
+            writeln!(rust, "    {}, 1,", camel_case(&register.name)).unwrap();
+        }
+        rust += ");\n";
+
+        rust += "\nbuild_register_file!(\n";
+        write_docs(&mut rust, "    ", self.desc.as_deref());
+        writeln!(rust, "    {map_name}, {states_name} ;").unwrap();
+        for register in &self.registers {
+            let reg_name = camel_case(&register.name);
+            writeln!(
+                rust,
+                "    {}: 0x{:x}, {reg_name}, {reg_name},",
+                register.name, register.offset
+            )
+            .unwrap();
+        }
+        rust += ");\n";
+        rust
+    }
+}
+
+fn write_docs(rust: &mut String, indent: &str, desc: Option<&str>) {
+    if let Some(desc) = desc {
+        for line in desc.lines().map(str::trim) {
+            if line.is_empty() {
+                writeln!(rust, "{indent}///").unwrap();
+            } else {
+                writeln!(rust, "{indent}/// {line}").unwrap();
+            }
+        }
+    }
+}
+
+/// Return the fields of the register with reserved fields added to fill the
+/// gaps between them, as the register state macros pack fields from bit 0
+fn fields_with_reserved(register: &RegisterDesc) -> Vec<FieldDesc> {
+    let mut fields = Vec::new();
+    let mut next_lsb = 0;
+    for field in &register.fields {
+        if field.lsb > next_lsb {
+            fields.push(FieldDesc {
+                name: format!("reserved_{next_lsb}"),
+                desc: Some("Reserved".to_string()),
+                lsb: next_lsb,
+                num_bits: field.lsb - next_lsb,
+                reset_value: 0,
+                permission: Permission::Reserved,
+            });
+        }
+        fields.push(field.clone());
+        next_lsb = field.lsb + field.num_bits;
+    }
+    fields
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(u64),
+    Str(String),
+    Punct(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "'{ident}'"),
+            Token::Number(number) => write!(f, "'{number}'"),
+            Token::Str(string) => write!(f, "\"{string}\""),
+            Token::Punct(c) => write!(f, "'{c}'"),
+        }
+    }
+}
+
+/// Split the source into tokens, each with the line it starts on
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, SimError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' {
+            let start_line = line;
+            let mut string = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                if chars[i] == '\n' {
+                    line += 1;
+                }
+                string.push(chars[i]);
+                i += 1;
+            }
+            if i == chars.len() {
+                return sim_error!("SystemRDL line {start_line}: unterminated string");
+            }
+            i += 1;
+            tokens.push((Token::Str(string), start_line));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), line));
+        } else if c.is_ascii_digit() || c == '\'' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || "_'".contains(chars[i])) {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            match parse_number(&text) {
+                Some(number) => tokens.push((Token::Number(number), line)),
+                None => return sim_error!("SystemRDL line {line}: invalid number '{text}'"),
+            }
+        } else {
+            tokens.push((Token::Punct(c), line));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parse a decimal, `0x`/`0b` prefixed or Verilog-style (`8'hff`) number
+fn parse_number(text: &str) -> Option<u64> {
+    let text = text.replace('_', "");
+    let (radix, digits) = if let Some((_, value)) = text.split_once('\'') {
+        let mut chars = value.chars();
+        let radix = match chars.next()?.to_ascii_lowercase() {
+            'b' => 2,
+            'o' => 8,
+            'd' => 10,
+            'h' => 16,
+            _ => return None,
+        };
+        (radix, chars.as_str().to_string())
+    } else if let Some(hex) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        (16, hex.to_string())
+    } else if let Some(bin) = text.strip_prefix("0b").or(text.strip_prefix("0B")) {
+        (2, bin.to_string())
+    } else {
+        (10, text)
+    };
+    u64::from_str_radix(&digits, radix).ok()
+}
+
+#[derive(Clone, Debug)]
+enum PropertyValue {
+    Ident(String),
+    Number(u64),
+    Str(String),
+}
+
+/// The properties of a `field` definition
+#[derive(Clone, Debug)]
+struct FieldType {
+    desc: Option<String>,
+    sw: String,
+    hw: String,
+    singlepulse: bool,
+    reset_value: Option<u64>,
+}
+
+impl Default for FieldType {
+    fn default() -> Self {
+        Self {
+            desc: None,
+            sw: "rw".to_string(),
+            hw: "rw".to_string(),
+            singlepulse: false,
+            reset_value: None,
+        }
+    }
+}
+
+/// The contents of a `reg` definition
+#[derive(Clone, Debug)]
+struct RegType {
+    desc: Option<String>,
+    num_bits: usize,
+    fields: Vec<FieldDesc>,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    field_types: HashMap<String, FieldType>,
+    reg_types: HashMap<String, RegType>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<(Token, usize)>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            field_types: HashMap::new(),
+            reg_types: HashMap::new(),
+        }
+    }
+
+    fn line(&self) -> usize {
+        match self.tokens.get(self.pos) {
+            Some((_, line)) => *line,
+            None => self.tokens.last().map_or(1, |(_, line)| *line),
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, SimError> {
+        sim_error!("SystemRDL line {}: {message}", self.line())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<Token, SimError> {
+        match self.tokens.get(self.pos) {
+            Some((token, _)) => {
+                self.pos += 1;
+                Ok(token.clone())
+            }
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), SimError> {
+        match self.next()? {
+            Token::Punct(p) if p == c => Ok(()),
+            token => {
+                self.pos -= 1;
+                self.error(&format!("expected '{c}' but found {token}"))
+            }
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, SimError> {
+        match self.next()? {
+            Token::Ident(ident) => Ok(ident),
+            token => {
+                self.pos -= 1;
+                self.error(&format!("expected a name but found {token}"))
+            }
+        }
+    }
+
+    fn expect_number(&mut self) -> Result<u64, SimError> {
+        match self.next()? {
+            Token::Number(number) => Ok(number),
+            token => {
+                self.pos -= 1;
+                self.error(&format!("expected a number but found {token}"))
+            }
+        }
+    }
+
+    /// Returns whether the next tokens are a property assignment
+    fn at_property(&self) -> bool {
+        matches!(self.peek(), Some(Token::Ident(_)))
+            && matches!(
+                self.peek_at(1),
+                Some(Token::Punct('=')) | Some(Token::Punct(';'))
+            )
+    }
+
+    /// Parse `name = value;` or `name;` which sets a boolean property
+    fn parse_property(&mut self) -> Result<(String, PropertyValue), SimError> {
+        let name = self.expect_ident()?;
+        if self.is_punct(';') {
+            self.pos += 1;
+            return Ok((name, PropertyValue::Ident("true".to_string())));
+        }
+        self.expect_punct('=')?;
+        let value = match self.next()? {
+            Token::Ident(ident) => PropertyValue::Ident(ident),
+            Token::Number(number) => PropertyValue::Number(number),
+            Token::Str(string) => PropertyValue::Str(string),
+            token => {
+                self.pos -= 1;
+                return self.error(&format!("unsupported value {token} for '{name}'"));
+            }
+        };
+        self.expect_punct(';')?;
+        Ok((name, value))
+    }
+
+    fn parse_root(&mut self) -> Result<RegisterMap, SimError> {
+        let mut maps = Vec::new();
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Ident(ident) if ident == "addrmap" => maps.push(self.parse_addrmap()?),
+                Token::Ident(ident) if ident == "reg" => {
+                    self.pos += 1;
+                    self.parse_reg_definition()?;
+                }
+                Token::Ident(ident) if ident == "field" => {
+                    self.pos += 1;
+                    self.parse_field_definition()?;
+                }
+                token => return self.error(&format!("unsupported {token} at the top level")),
+            }
+        }
+        match maps.len() {
+            1 => Ok(maps.pop().unwrap()),
+            num_maps => sim_error!("SystemRDL must contain exactly one addrmap, found {num_maps}"),
+        }
+    }
+
+    fn parse_addrmap(&mut self) -> Result<RegisterMap, SimError> {
+        self.pos += 1;
+        let name = self.expect_ident()?;
+        self.expect_punct('{')?;
+
+        let mut desc = None;
+        let mut registers: Vec<RegisterDesc> = Vec::new();
+        let mut next_offset = 0;
+        while !self.is_punct('}') {
+            if self.at_property() {
+                if let (property, PropertyValue::Str(value)) = self.parse_property()?
+                    && property == "desc"
+                {
+                    desc = Some(value);
+                }
+                continue;
+            }
+
+            let reg_type = match self.expect_ident()?.as_str() {
+                "reg" => match self.parse_reg_definition()? {
+                    Some(reg_type) => reg_type,
+                    // Named definitions are instantiated separately
+                    None => continue,
+                },
+                "addrmap" | "regfile" | "mem" => {
+                    self.pos -= 1;
+                    return self.error("nested components are not supported");
+                }
+                type_name => match self.reg_types.get(type_name) {
+                    Some(reg_type) => reg_type.clone(),
+                    None => {
+                        self.pos -= 1;
+                        return self.error(&format!("unknown register type '{type_name}'"));
+                    }
+                },
+            };
+
+            let line = self.line();
+            for (inst_name, offset) in self.parse_reg_instances()? {
+                let offset = offset.unwrap_or(next_offset);
+                let end = offset + reg_type.num_bits.div_ceil(8) as u64;
+                if let Some(other) = registers.iter().find(|r| r.name == inst_name) {
+                    return sim_error!(
+                        "SystemRDL line {line}: duplicate register '{}'",
+                        other.name
+                    );
+                }
+                if let Some(other) = registers
+                    .iter()
+                    .find(|r| r.offset < end && offset < r.offset + r.num_bits.div_ceil(8) as u64)
+                {
+                    return sim_error!(
+                        "SystemRDL line {line}: register '{inst_name}' at 0x{offset:x} overlaps '{}'",
+                        other.name
+                    );
+                }
+                next_offset = end;
+                registers.push(RegisterDesc {
+                    name: inst_name,
+                    desc: reg_type.desc.clone(),
+                    offset,
+                    num_bits: reg_type.num_bits,
+                    fields: reg_type.fields.clone(),
+                });
+            }
+        }
+        self.expect_punct('}')?;
+        self.expect_punct(';')?;
+
+        registers.sort_by_key(|register| register.offset);
+        Ok(RegisterMap {
+            name,
+            desc,
+            registers,
+        })
+    }
+
+    /// Parse `name [@ offset] {, name [@ offset]} ;`
+    fn parse_reg_instances(&mut self) -> Result<Vec<(String, Option<u64>)>, SimError> {
+        let mut instances = Vec::new();
+        loop {
+            let name = self.expect_ident()?;
+            if self.is_punct('[') {
+                return self.error("register arrays are not supported");
+            }
+            let offset = if self.is_punct('@') {
+                self.pos += 1;
+                Some(self.expect_number()?)
+            } else {
+                None
+            };
+            instances.push((name, offset));
+            if self.is_punct(',') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        self.expect_punct(';')?;
+        Ok(instances)
+    }
+
+    /// Parse the remainder of a `reg` definition. Named definitions are stored
+    /// for later instantiation and return `None`.
+    fn parse_reg_definition(&mut self) -> Result<Option<RegType>, SimError> {
+        let type_name = match self.peek() {
+            Some(Token::Ident(_)) => Some(self.expect_ident()?),
+            _ => None,
+        };
+        self.expect_punct('{')?;
+        let reg_type = self.parse_reg_body()?;
+        self.expect_punct('}')?;
+
+        match type_name {
+            Some(type_name) => {
+                self.expect_punct(';')?;
+                self.reg_types.insert(type_name, reg_type);
+                Ok(None)
+            }
+            None => Ok(Some(reg_type)),
+        }
+    }
+
+    fn parse_reg_body(&mut self) -> Result<RegType, SimError> {
+        let mut desc = None;
+        let mut num_bits = DEFAULT_REGWIDTH;
+        let mut fields: Vec<FieldDesc> = Vec::new();
+        let mut next_lsb = 0;
+        while !self.is_punct('}') {
+            if self.at_property() {
+                match self.parse_property()? {
+                    (property, PropertyValue::Str(value)) if property == "desc" => {
+                        desc = Some(value);
+                    }
+                    (property, PropertyValue::Number(value)) if property == "regwidth" => {
+                        num_bits = value as usize;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            let field_type = match self.expect_ident()?.as_str() {
+                "field" => match self.parse_field_definition()? {
+                    Some(field_type) => field_type,
+                    None => continue,
+                },
+                type_name => match self.field_types.get(type_name) {
+                    Some(field_type) => field_type.clone(),
+                    None => {
+                        self.pos -= 1;
+                        return self.error(&format!("unknown field type '{type_name}'"));
+                    }
+                },
+            };
+            let permission = self.field_permission(&field_type)?;
+
+            loop {
+                let name = self.expect_ident()?;
+                let (lsb, field_bits) = self.parse_field_bits(next_lsb)?;
+                let reset_value = if self.is_punct('=') {
+                    self.pos += 1;
+                    self.expect_number()?
+                } else {
+                    field_type.reset_value.unwrap_or(0)
+                };
+                if field_bits >= MAX_FIELD_BITS {
+                    return self.error(&format!(
+                        "field '{name}' must be narrower than {MAX_FIELD_BITS} bits"
+                    ));
+                }
+                if reset_value >> field_bits != 0 {
+                    return self.error(&format!(
+                        "reset value 0x{reset_value:x} does not fit in field '{name}'"
+                    ));
+                }
+                if let Some(other) = fields
+                    .iter()
+                    .find(|f| f.lsb < lsb + field_bits && lsb < f.lsb + f.num_bits)
+                {
+                    return self.error(&format!("field '{name}' overlaps field '{}'", other.name));
+                }
+                next_lsb = lsb + field_bits;
+                fields.push(FieldDesc {
+                    name,
+                    desc: field_type.desc.clone(),
+                    lsb,
+                    num_bits: field_bits,
+                    reset_value,
+                    permission,
+                });
+                if self.is_punct(',') {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+            self.expect_punct(';')?;
+        }
+
+        fields.sort_by_key(|field| field.lsb);
+        if let Some(field) = fields.last()
+            && field.lsb + field.num_bits > num_bits
+        {
+            return self.error(&format!(
+                "field '{}' does not fit in a {num_bits}-bit register",
+                field.name
+            ));
+        }
+        Ok(RegType {
+            desc,
+            num_bits,
+            fields,
+        })
+    }
+
+    /// Parse `[msb:lsb]` or `[width]`. Fields without an explicit position are
+    /// placed at `next_lsb` and a missing width is a single bit.
+    fn parse_field_bits(&mut self, next_lsb: usize) -> Result<(usize, usize), SimError> {
+        if !self.is_punct('[') {
+            return Ok((next_lsb, 1));
+        }
+        self.pos += 1;
+        let first = self.expect_number()? as usize;
+        let bits = if self.is_punct(':') {
+            self.pos += 1;
+            let second = self.expect_number()? as usize;
+            let (msb, lsb) = (first.max(second), first.min(second));
+            (lsb, msb - lsb + 1)
+        } else {
+            if first == 0 {
+                return self.error("fields must be at least one bit wide");
+            }
+            (next_lsb, first)
+        };
+        self.expect_punct(']')?;
+        Ok(bits)
+    }
+
+    /// Parse the remainder of a `field` definition. Named definitions are
+    /// stored for later instantiation and return `None`.
+    fn parse_field_definition(&mut self) -> Result<Option<FieldType>, SimError> {
+        let type_name = match self.peek() {
+            Some(Token::Ident(_)) => Some(self.expect_ident()?),
+            _ => None,
+        };
+        self.expect_punct('{')?;
+        let mut field_type = FieldType::default();
+        while !self.is_punct('}') {
+            if !self.at_property() {
+                let token = self.next()?;
+                self.pos -= 1;
+                return self.error(&format!("unexpected {token} in field"));
+            }
+            match self.parse_property()? {
+                (property, PropertyValue::Str(value)) if property == "desc" => {
+                    field_type.desc = Some(value);
+                }
+                (property, PropertyValue::Ident(value)) if property == "sw" => {
+                    field_type.sw = value;
+                }
+                (property, PropertyValue::Ident(value)) if property == "hw" => {
+                    field_type.hw = value;
+                }
+                (property, PropertyValue::Ident(value)) if property == "singlepulse" => {
+                    field_type.singlepulse = value == "true";
+                }
+                (property, PropertyValue::Number(value)) if property == "reset" => {
+                    field_type.reset_value = Some(value);
+                }
+                _ => {}
+            }
+        }
+        self.expect_punct('}')?;
+
+        match type_name {
+            Some(type_name) => {
+                self.expect_punct(';')?;
+                self.field_types.insert(type_name, field_type);
+                Ok(None)
+            }
+            None => Ok(Some(field_type)),
+        }
+    }
+
+    fn field_permission(&self, field_type: &FieldType) -> Result<Permission, SimError> {
+        let hw_writes = match field_type.hw.as_str() {
+            "rw" | "wr" | "w" => true,
+            "r" | "na" => false,
+            hw => return self.error(&format!("unsupported hw access '{hw}'")),
+        };
+        let permission = match field_type.sw.as_str() {
+            "rw" | "wr" | "w" if field_type.singlepulse => Permission::WriteOneCommits,
+            "rw" | "wr" if hw_writes => Permission::ReadVolatileWrite,
+            "rw" | "wr" => Permission::ReadWrite,
+            "r" if hw_writes => Permission::ReadVolatileOnly,
+            "r" => Permission::ReadOnly,
+            "w" => Permission::WriteOnly,
+            "na" => Permission::Reserved,
+            sw => return self.error(&format!("unsupported sw access '{sw}'")),
+        };
+        Ok(permission)
+    }
+}
@@ -22,6 +22,12 @@
 //! -> | io_rx ----------------------------/                         |
 //!    +-------------------------------------------------------------+
 //! ```
+//!
+//! The Tx buffer can be replaced by [per-priority queues](PriorityQueues) so
+//! that higher priority values leave the node ahead of lower priority ones.
+//!
+//! Values entering the Tx buffer can be [marked](EcnMarker) as having
+//! experienced congestion when the buffer is above an ECN threshold.
 
 use std::rc::Rc;
 
@@ -34,6 +40,7 @@ use gwr_components::router::{Route, Router};
 use gwr_components::store::{ByteStore, Store};
 use gwr_engine::engine::Engine;
 use gwr_engine::port::PortStateResult;
+use gwr_engine::sim_error;
 use gwr_engine::time::clock::Clock;
 use gwr_engine::traits::{Routable, SimObject};
 use gwr_engine::types::{SimError, SimResult};
@@ -42,6 +49,9 @@ use gwr_track::build_aka;
 use gwr_track::entity::Entity;
 use gwr_track::tracker::aka::Aka;
 
+use crate::congestion::EcnMarker;
+use crate::priority_queues::{PriorityQueueConfig, PriorityQueues};
+
 /// The port index used for ring connections.
 pub const RING_INDEX: usize = 0;
 /// The port index used for I/O connections.
@@ -54,6 +64,8 @@ where
     rx_buffer_bytes: usize,
     tx_buffer_bytes: usize,
     write_limiter: Rc<RateLimiter<T>>,
+    num_priority_queues: Option<usize>,
+    ecn_threshold_bytes: Option<usize>,
 }
 
 impl<T> RingConfig<T>
@@ -70,8 +82,35 @@ where
             rx_buffer_bytes,
             tx_buffer_bytes,
             write_limiter,
+            num_priority_queues: None,
+            ecn_threshold_bytes: None,
         }
     }
+
+    /// Replace the Tx buffer with `num_queues` per-priority queues, each of
+    /// which can hold the Tx buffer bytes.
+    #[must_use]
+    pub fn with_priority_queues(mut self, num_queues: usize) -> Self {
+        self.num_priority_queues = Some(num_queues);
+        self
+    }
+
+    /// Mark values that arrive at the Tx buffer while it holds more than
+    /// `threshold_bytes` as having experienced congestion. Not supported with
+    /// per-priority queues.
+    #[must_use]
+    pub fn with_ecn_threshold_bytes(mut self, threshold_bytes: usize) -> Self {
+        self.ecn_threshold_bytes = Some(threshold_bytes);
+        self
+    }
+}
+
+enum TxBuffer<T>
+where
+    T: SimObject + Routable,
+{
+    Single(Rc<Store<T>>),
+    PerPriority(Rc<PriorityQueues<T>>),
 }
 
 #[derive(EntityGet, EntityDisplay, Runnable)]
@@ -81,7 +120,7 @@ where
 {
     entity: Rc<Entity>,
     rx_buffer_limiter: Rc<Limiter<T>>,
-    tx_buffer: Rc<Store<T>>,
+    tx_buffer: TxBuffer<T>,
     arbiter: Rc<Arbiter<T>>,
     router: Rc<Router<T>>,
 }
@@ -125,16 +164,57 @@ where
             config.write_limiter.clone(),
         );
         let tx_buffer_aka = build_aka!(aka, &entity, &[("ring_tx", "tx")]);
-        let tx_buffer = ByteStore::new_and_register_with_renames(
-            engine,
-            clock,
-            &entity,
-            "tx_buf",
-            Some(&tx_buffer_aka),
-            config.tx_buffer_bytes,
-        )?;
-        connect_port!(tx_buffer_limiter, tx => tx_buffer, rx)
-            .expect("Internal ports should connect without error");
+        let tx_buffer = match config.num_priority_queues {
+            None => {
+                let tx_buffer = ByteStore::new_and_register_with_renames(
+                    engine,
+                    clock,
+                    &entity,
+                    "tx_buf",
+                    Some(&tx_buffer_aka),
+                    config.tx_buffer_bytes,
+                )?;
+                match config.ecn_threshold_bytes {
+                    Some(threshold_bytes) => {
+                        let marker = EcnMarker::new_and_register(
+                            engine,
+                            clock,
+                            &entity,
+                            "mark_tx",
+                            &tx_buffer,
+                            threshold_bytes,
+                        );
+                        connect_port!(tx_buffer_limiter, tx => marker, rx)
+                            .expect("Internal ports should connect without error");
+                        connect_port!(marker, tx => tx_buffer, rx)
+                            .expect("Internal ports should connect without error");
+                    }
+                    None => {
+                        connect_port!(tx_buffer_limiter, tx => tx_buffer, rx)
+                            .expect("Internal ports should connect without error");
+                    }
+                }
+                TxBuffer::Single(tx_buffer)
+            }
+            Some(num_queues) => {
+                if config.ecn_threshold_bytes.is_some() {
+                    return sim_error!(
+                        "{entity}: ECN marking is not supported with per-priority queues"
+                    );
+                }
+                let tx_queues = PriorityQueues::new_and_register_with_renames(
+                    engine,
+                    clock,
+                    &entity,
+                    "tx_queues",
+                    Some(&tx_buffer_aka),
+                    &PriorityQueueConfig::new(num_queues, config.tx_buffer_bytes),
+                )?;
+                connect_port!(tx_buffer_limiter, tx => tx_queues, rx)
+                    .expect("Internal ports should connect without error");
+                TxBuffer::PerPriority(tx_queues)
+            }
+        };
 
         let router_aka = build_aka!(aka, &entity, &[("io_tx", &format!("tx_{IO_INDEX}"))]);
         let router = Router::new_and_register_with_renames(
@@ -197,7 +277,10 @@ where
     }
 
     pub fn connect_port_ring_tx(&self, port_state: PortStateResult<T>) -> SimResult {
-        self.tx_buffer.connect_port_tx(port_state)
+        match &self.tx_buffer {
+            TxBuffer::Single(tx_buffer) => tx_buffer.connect_port_tx(port_state),
+            TxBuffer::PerPriority(tx_queues) => tx_queues.connect_port_tx(port_state),
+        }
     }
 
     pub fn connect_port_io_tx(&self, port_state: PortStateResult<T>) -> SimResult {
@@ -0,0 +1,114 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Scatter-gather descriptors for non-contiguous transfers.
+//!
+//! A [ScatterGather] list describes a transfer as a series of address/length
+//! segments so that DMA and network models can carry non-contiguous data in a
+//! single [MemoryAccess](crate::memory::memory_access::MemoryAccess) or
+//! [EthernetFrame](crate::ethernet_frame::EthernetFrame) rather than
+//! flattening it into one access per segment.
+
+use std::fmt::Display;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+
+/// A contiguous region of a scatter-gather transfer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub addr: u64,
+    pub num_bytes: usize,
+}
+
+impl Segment {
+    #[must_use]
+    pub fn new(addr: u64, num_bytes: usize) -> Self {
+        Self { addr, num_bytes }
+    }
+
+    /// Return the address of the last byte of the segment
+    #[must_use]
+    pub fn last_addr(&self) -> u64 {
+        self.addr + self.num_bytes as u64 - 1
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}@{:x}", self.num_bytes, self.addr)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScatterGather {
+    segments: Vec<Segment>,
+}
+
+impl ScatterGather {
+    /// Create a scatter-gather list from its segments.
+    ///
+    /// There must be at least one segment and every segment must contain at
+    /// least one byte.
+    pub fn new(segments: Vec<Segment>) -> Result<Self, SimError> {
+        if segments.is_empty() {
+            return sim_error!("Unsupported scatter-gather list with no segments");
+        }
+        if let Some(segment) = segments.iter().find(|segment| segment.num_bytes == 0) {
+            return sim_error!(
+                "Unsupported empty scatter-gather segment at 0x{:x}",
+                segment.addr
+            );
+        }
+        Ok(Self { segments })
+    }
+
+    /// Create a list of `num_segments` segments of `segment_bytes` each where
+    /// the start of each segment is `stride` bytes after the previous one.
+    pub fn strided(
+        addr: u64,
+        segment_bytes: usize,
+        stride: u64,
+        num_segments: usize,
+    ) -> Result<Self, SimError> {
+        Self::new(
+            (0..num_segments as u64)
+                .map(|i| Segment::new(addr + i * stride, segment_bytes))
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    #[must_use]
+    pub fn num_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Return the total number of bytes across all segments
+    #[must_use]
+    pub fn num_bytes(&self) -> usize {
+        self.segments.iter().map(|segment| segment.num_bytes).sum()
+    }
+
+    /// Return the address of the first segment
+    #[must_use]
+    pub fn first_addr(&self) -> u64 {
+        self.segments[0].addr
+    }
+}
+
+impl Display for ScatterGather {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{segment}")?;
+        }
+        write!(f, "]")
+    }
+}
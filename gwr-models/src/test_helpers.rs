@@ -9,7 +9,7 @@ use gwr_engine::types::AccessType;
 use gwr_track::entity::Entity;
 
 use crate::memory::CacheHintType;
-use crate::memory::memory_access::MemoryAccess;
+use crate::memory::memory_access::{ByteEnables, MemoryAccess};
 use crate::memory::memory_map::{DeviceId, MemoryMap};
 use crate::memory::traits::AccessMemory;
 
@@ -297,6 +297,7 @@ pub struct MemoryTxn {
     dst_addr: u64,
     src_addr: Option<u64>,
     bytes: Option<usize>,
+    enabled_bytes: Option<usize>,
     total_bytes: Option<usize>,
     destination: Option<u64>,
     dst_device: Option<DeviceId>,
@@ -312,6 +313,7 @@ impl MemoryTxn {
             dst_addr,
             src_addr: None,
             bytes: None,
+            enabled_bytes: None,
             total_bytes: None,
             destination: None,
             dst_device: None,
@@ -362,6 +364,12 @@ impl MemoryTxn {
         self
     }
 
+    #[must_use]
+    pub fn with_enabled_bytes(mut self, enabled_bytes: usize) -> Self {
+        self.enabled_bytes = Some(enabled_bytes);
+        self
+    }
+
     #[must_use]
     pub fn with_total_bytes(mut self, total_bytes: usize) -> Self {
         self.total_bytes = Some(total_bytes);
@@ -429,6 +437,13 @@ where
                 "{check_id}: byte count mismatch for actual {actual:?}",
             );
         }
+        if let Some(enabled_bytes) = self.enabled_bytes {
+            assert_eq!(
+                actual.enabled_bytes(),
+                enabled_bytes,
+                "{check_id}: enabled byte count mismatch for actual {actual:?}",
+            );
+        }
         if let Some(total_bytes) = self.total_bytes {
             assert_eq!(
                 actual.total_bytes(),
@@ -475,6 +490,7 @@ where
         MemoryTxn::new(self.access_type(), self.dst_addr())
             .with_src_addr(self.src_addr())
             .with_bytes(self.access_size_bytes())
+            .with_enabled_bytes(self.enabled_bytes())
             .with_total_bytes(self.total_bytes())
             .with_destination(self.destination())
             .with_dst_device(self.dst_device())
@@ -555,6 +571,30 @@ pub fn create_write(
     )
 }
 
+#[must_use]
+pub fn create_masked_write(
+    created_by: &Rc<Entity>,
+    memory_map: &Rc<MemoryMap>,
+    byte_enables: ByteEnables,
+    dst_addr: u64,
+    src_addr: u64,
+    overhead_size_bytes: usize,
+) -> MemoryAccess {
+    let (dst_device, _) = memory_map.lookup(dst_addr).unwrap();
+    let (src_device, _) = memory_map.lookup(src_addr).unwrap();
+    MemoryAccess::new_masked_write(
+        created_by,
+        AccessType::WriteRequest,
+        byte_enables,
+        dst_addr,
+        src_addr,
+        dst_device,
+        src_device,
+        overhead_size_bytes,
+    )
+    .unwrap()
+}
+
 #[must_use]
 pub fn create_write_np(
     created_by: &Rc<Entity>,
@@ -0,0 +1,174 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::build_model_harness;
+use gwr_models::memory::bw_regulator::{
+    BandwidthLimit, BandwidthRegulator, BandwidthRegulatorConfig,
+};
+use gwr_models::memory::memory_access::MemoryAccess;
+use gwr_models::memory::memory_map::{DeviceId, MemoryMap};
+use gwr_models::test_helpers::{MemoryTxn, create_write};
+
+const MEMORY_ADDR: u64 = 0x80000;
+const PE_A_ADDR: u64 = 0x1000;
+const PE_B_ADDR: u64 = 0x2000;
+const PE_A: DeviceId = DeviceId(1);
+const PE_B: DeviceId = DeviceId(2);
+
+const NUM_ENTRIES: usize = 8;
+const WINDOW_TICKS: u64 = 100;
+const WRITE_SIZE_BYTES: usize = 48;
+const OVERHEAD_SIZE_BYTES: usize = 16;
+const WRITE_TOTAL_BYTES: usize = WRITE_SIZE_BYTES + OVERHEAD_SIZE_BYTES;
+
+build_model_harness! {
+    harness RegulatorHarness<T> {
+        component: regulator: Rc<BandwidthRegulator<T>>,
+        rx ports: {
+            Rx<T> => rx,
+        },
+        tx ports: {
+            Tx<T> => tx,
+        },
+    }
+}
+
+fn create_memory_map() -> Rc<MemoryMap> {
+    let mut memory_map = MemoryMap::new();
+    memory_map.insert(PE_A_ADDR, 0x1000, PE_A).unwrap();
+    memory_map.insert(PE_B_ADDR, 0x1000, PE_B).unwrap();
+    memory_map
+        .insert(MEMORY_ADDR, 0x10000, DeviceId(0))
+        .unwrap();
+    Rc::new(memory_map)
+}
+
+fn create_regulator(
+    engine: &mut Engine,
+    limit: BandwidthLimit,
+) -> Rc<BandwidthRegulator<MemoryAccess>> {
+    let clock = engine.default_clock();
+    let config = BandwidthRegulatorConfig::new(NUM_ENTRIES, WINDOW_TICKS, WRITE_TOTAL_BYTES, limit);
+    BandwidthRegulator::new_and_register(engine, &clock, engine.top(), "regulator", config).unwrap()
+}
+
+fn write(engine: &Engine, dst_addr: u64, src_addr: u64) -> MemoryAccess {
+    create_write(
+        engine.top(),
+        &create_memory_map(),
+        WRITE_SIZE_BYTES,
+        dst_addr,
+        src_addr,
+        OVERHEAD_SIZE_BYTES,
+    )
+}
+
+#[test]
+fn hard_limit_holds_until_next_window() {
+    let mut engine = start_test(file!());
+    let regulator = create_regulator(&mut engine, BandwidthLimit::Hard);
+    let a0 = write(&engine, MEMORY_ADDR, PE_A_ADDR);
+    let a1 = write(&engine, MEMORY_ADDR + 0x40, PE_A_ADDR);
+
+    let mut harness = RegulatorHarness::new(engine, regulator.clone());
+    harness.run_steps([
+        send_rx!(a0),
+        send_rx!(a1),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR)),
+        expect_no_traffic!(&[Port::Tx], WINDOW_TICKS - 10),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR + 0x40)),
+    ]);
+
+    assert_eq!(harness.engine.time_now_ns(), WINDOW_TICKS as f64);
+    assert_eq!(regulator.num_forwarded(PE_A), 2);
+    assert_eq!(regulator.bytes_forwarded(PE_A), 2 * WRITE_TOTAL_BYTES);
+    assert_eq!(regulator.num_throttled(PE_A), 1);
+}
+
+#[test]
+fn hard_limit_isolates_initiators() {
+    let mut engine = start_test(file!());
+    let regulator = create_regulator(&mut engine, BandwidthLimit::Hard);
+    let a0 = write(&engine, MEMORY_ADDR, PE_A_ADDR);
+    let a1 = write(&engine, MEMORY_ADDR + 0x40, PE_A_ADDR);
+    let a2 = write(&engine, MEMORY_ADDR + 0x80, PE_A_ADDR);
+    let b0 = write(&engine, MEMORY_ADDR + 0x100, PE_B_ADDR);
+
+    // The noisy initiator A cannot delay B once it has used its budget
+    let mut harness = RegulatorHarness::new(engine, regulator.clone());
+    harness.run_steps([
+        send_rx!(a0),
+        send_rx!(a1),
+        send_rx!(a2),
+        send_rx!(b0),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR)),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR + 0x100)),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR + 0x40)),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR + 0x80)),
+    ]);
+
+    assert_eq!(harness.engine.time_now_ns(), (2 * WINDOW_TICKS) as f64);
+    assert_eq!(regulator.num_forwarded(PE_A), 3);
+    assert_eq!(regulator.num_forwarded(PE_B), 1);
+    assert_eq!(regulator.num_throttled(PE_A), 2);
+    assert_eq!(regulator.num_throttled(PE_B), 0);
+}
+
+#[test]
+fn soft_limit_uses_spare_bandwidth() {
+    let mut engine = start_test(file!());
+    let regulator = create_regulator(&mut engine, BandwidthLimit::Soft);
+    let a0 = write(&engine, MEMORY_ADDR, PE_A_ADDR);
+    let a1 = write(&engine, MEMORY_ADDR + 0x40, PE_A_ADDR);
+    let a2 = write(&engine, MEMORY_ADDR + 0x80, PE_A_ADDR);
+
+    let mut harness = RegulatorHarness::new(engine, regulator.clone());
+    harness.run_steps([
+        send_rx!(a0),
+        send_rx!(a1),
+        send_rx!(a2),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR)),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR + 0x40)),
+        expect_tx!(MemoryTxn::write_req(MEMORY_ADDR + 0x80)),
+    ]);
+
+    assert!(harness.engine.time_now_ns() < WINDOW_TICKS as f64);
+    assert_eq!(regulator.num_forwarded(PE_A), 3);
+    assert_eq!(regulator.num_throttled(PE_A), 1);
+}
+
+#[test]
+fn hard_limit_rejects_zero_budget() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = BandwidthRegulatorConfig::new(NUM_ENTRIES, WINDOW_TICKS, 0, BandwidthLimit::Hard);
+    let result: Result<Rc<BandwidthRegulator<MemoryAccess>>, _> =
+        BandwidthRegulator::new_and_register(&engine, &clock, engine.top(), "regulator", config);
+    match result {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "Unsupported BandwidthRegulator with 0 byte budget and hard limit"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+
+    let config = BandwidthRegulatorConfig::new(
+        NUM_ENTRIES,
+        WINDOW_TICKS,
+        WRITE_TOTAL_BYTES,
+        BandwidthLimit::Hard,
+    )
+    .with_initiator_budget(PE_B, 0);
+    let result: Result<Rc<BandwidthRegulator<MemoryAccess>>, _> =
+        BandwidthRegulator::new_and_register(&engine, &clock, engine.top(), "regulator", config);
+    match result {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "Unsupported BandwidthRegulator with 0 byte budget for initiator 2 and hard limit"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
@@ -251,6 +251,40 @@ mod dev_cache_harness {
         assert_eq!(memory.bytes_written(), 0);
     }
 
+    #[test]
+    fn cache_latency_histograms() {
+        let mut engine = start_test(file!());
+        let clock = engine.default_clock();
+        let config = cache_config().with_latency_histograms();
+        let cache =
+            Cache::new_and_register(&engine, &clock, engine.top(), "cache", config).unwrap();
+        create_and_connect_memory(&mut engine, &cache);
+        let mut harness = CacheDevHarness::<MemoryAccess>::new(engine, cache.clone());
+        let memory_map = Rc::new(create_default_memory_map());
+
+        let read = create_read(
+            cache.entity(),
+            &memory_map,
+            ACCESS_SIZE_BYTES,
+            DST_ADDR,
+            SRC_ADDR,
+            OVERHEAD_SIZE_BYTES,
+        );
+        harness.run_steps([
+            send_dev_rx!(read.clone()),
+            expect_dev_tx!(MemoryTxn::read_rsp(DST_ADDR)),
+            send_dev_rx!(read),
+            expect_dev_tx!(MemoryTxn::read_rsp(DST_ADDR)),
+        ]);
+
+        // The miss has to go through the request, memory and response delays
+        let (src_device, _) = memory_map.lookup(SRC_ADDR).unwrap();
+        let histogram = cache.latency_histogram(src_device).unwrap();
+        assert_eq!(histogram.count(), 2);
+        assert_eq!(histogram.min(), DELAY_TICKS as u64);
+        assert_eq!(histogram.max(), 3 * DELAY_TICKS as u64);
+    }
+
     /// Ensure that the cache holds as many tags as it has ways
     #[test]
     fn cache_ways() {
@@ -0,0 +1,171 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_components::connect_port;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::collective::{
+    CollectiveAlgorithm, CollectiveConfig, CollectiveEngine, CollectiveOp,
+};
+use gwr_models::fabric::functional::FunctionalFabric;
+use gwr_models::fabric::{Fabric, FabricConfig};
+
+const NUM_RANKS: usize = 4;
+const NUM_BYTES: usize = 4096;
+
+/// Run a collective across ranks attached to a row of fabric nodes
+fn run_collective(config: CollectiveConfig) -> Rc<CollectiveEngine> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let top = engine.top().clone();
+
+    let num_ranks = config.num_ranks();
+    let fabric_config = Rc::new(FabricConfig::new(
+        num_ranks, 1, 1, None, 2, 1, 8192, 8192, 128,
+    ));
+    let fabric =
+        FunctionalFabric::new_and_register(&engine, &clock, &top, "fabric", fabric_config).unwrap();
+    let collective =
+        CollectiveEngine::new_and_register(&engine, &clock, &top, "collective", config).unwrap();
+    for i in 0..num_ranks {
+        connect_port!(collective, tx, i => fabric, ingress, i).unwrap();
+        connect_port!(fabric, egress, i => collective, rx, i).unwrap();
+    }
+
+    run_simulation!(engine);
+    collective
+}
+
+fn config(op: CollectiveOp, algorithm: CollectiveAlgorithm) -> CollectiveConfig {
+    CollectiveConfig::new(op, algorithm, (0..NUM_RANKS).collect(), NUM_BYTES)
+}
+
+#[test]
+fn ring_all_reduce() {
+    let collective = run_collective(
+        config(CollectiveOp::AllReduce, CollectiveAlgorithm::Ring).with_chunk_bytes(256),
+    );
+    assert!(collective.completion_time_ns().is_some());
+
+    // Each rank sends a quarter of the data in each of the 6 steps
+    let steps = 2 * (NUM_RANKS - 1);
+    assert_eq!(
+        collective.bytes_sent(),
+        NUM_RANKS * steps * NUM_BYTES / NUM_RANKS
+    );
+    assert_eq!(collective.num_messages(), NUM_RANKS * steps * 4);
+}
+
+#[test]
+fn tree_all_reduce() {
+    let collective = run_collective(config(CollectiveOp::AllReduce, CollectiveAlgorithm::Tree));
+    assert!(collective.completion_time_ns().is_some());
+
+    // Every rank other than the root sends the data up and receives it back
+    assert_eq!(collective.bytes_sent(), 2 * (NUM_RANKS - 1) * NUM_BYTES);
+    assert_eq!(collective.num_messages(), 2 * (NUM_RANKS - 1));
+}
+
+#[test]
+fn broadcast() {
+    for algorithm in [CollectiveAlgorithm::Ring, CollectiveAlgorithm::Tree] {
+        let collective =
+            run_collective(config(CollectiveOp::Broadcast, algorithm).with_chunk_bytes(1024));
+        assert!(collective.completion_time_ns().is_some());
+        assert_eq!(collective.bytes_sent(), (NUM_RANKS - 1) * NUM_BYTES);
+        assert_eq!(collective.num_messages(), (NUM_RANKS - 1) * 4);
+    }
+}
+
+#[test]
+fn all_gather() {
+    // Each rank sends its quarter of the data to the 3 other ranks
+    let collective = run_collective(
+        config(CollectiveOp::AllGather, CollectiveAlgorithm::Ring).with_chunk_bytes(256),
+    );
+    assert!(collective.completion_time_ns().is_some());
+    assert_eq!(collective.bytes_sent(), (NUM_RANKS - 1) * NUM_BYTES);
+
+    // Ranks 1 and 2 send the quarters of their subtrees up to the root, which
+    // then sends all of the data back down to the 3 other ranks
+    let collective = run_collective(
+        config(CollectiveOp::AllGather, CollectiveAlgorithm::Tree).with_chunk_bytes(256),
+    );
+    assert!(collective.completion_time_ns().is_some());
+    assert_eq!(
+        collective.bytes_sent(),
+        NUM_BYTES + (NUM_RANKS - 1) * NUM_BYTES
+    );
+}
+
+#[test]
+fn transfers_combine_the_chunks_of_each_step() {
+    let transfers = config(CollectiveOp::AllReduce, CollectiveAlgorithm::Ring)
+        .with_chunk_bytes(256)
+        .transfers();
+    let steps = 2 * (NUM_RANKS - 1);
+    assert_eq!(transfers.len(), NUM_RANKS * steps);
+    for transfer in &transfers {
+        let prev = (transfer.src_rank + NUM_RANKS - 1) % NUM_RANKS;
+        assert_eq!(transfer.dst_rank, (transfer.src_rank + 1) % NUM_RANKS);
+        assert_eq!(transfer.num_bytes, NUM_BYTES / NUM_RANKS);
+        if transfer.step == 0 {
+            assert!(transfer.after.is_empty());
+        } else {
+            assert_eq!(transfer.after, [(prev, transfer.step - 1)]);
+        }
+    }
+
+    let transfers = config(CollectiveOp::Broadcast, CollectiveAlgorithm::Tree).transfers();
+    let pairs: Vec<(usize, usize)> = transfers
+        .iter()
+        .map(|transfer| (transfer.src_rank, transfer.dst_rank))
+        .collect();
+    assert_eq!(pairs, [(0, 1), (0, 2), (1, 3)]);
+    assert_eq!(transfers[2].after, [(0, 0)]);
+}
+
+#[test]
+fn overlapped_chunks_are_pipelined() {
+    let chunked = config(CollectiveOp::Broadcast, CollectiveAlgorithm::Ring).with_chunk_bytes(256);
+    let serial_ns = run_collective(chunked.clone().with_overlap(false))
+        .completion_time_ns()
+        .unwrap();
+    let pipelined_ns = run_collective(chunked).completion_time_ns().unwrap();
+
+    // Without overlap each rank waits for all 16 chunks before forwarding any
+    // of them, so each of the 3 hops takes at least the time to send 15 chunks
+    let chunk_ticks = 256 * 8 / 128;
+    assert!(serial_ns >= (3 * 15 * chunk_ticks) as f64);
+    assert!(pipelined_ns < serial_ns);
+}
+
+#[test]
+fn reduction_takes_time() {
+    let all_reduce = || config(CollectiveOp::AllReduce, CollectiveAlgorithm::Ring);
+    let free_ns = run_collective(all_reduce()).completion_time_ns().unwrap();
+    let reduce_ns = run_collective(all_reduce().with_reduce_bytes_per_tick(4))
+        .completion_time_ns()
+        .unwrap();
+
+    // Each rank must reduce a quarter of the data in each of the 3
+    // reduce-scatter steps before forwarding it
+    let reduce_ticks = (NUM_RANKS - 1) * NUM_BYTES / NUM_RANKS / 4;
+    assert!(reduce_ns > free_ns);
+    assert!(reduce_ns >= reduce_ticks as f64);
+}
+
+#[test]
+fn zero_chunk_bytes() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = config(CollectiveOp::Broadcast, CollectiveAlgorithm::Ring).with_chunk_bytes(0);
+    match CollectiveEngine::new_and_register(&engine, &clock, engine.top(), "collective", config) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "top::collective: chunk_bytes must be greater than 0"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_components::arbiter::policy::RoundRobin;
+use gwr_components::flow_controls::limiter::Limiter;
+use gwr_components::router::Route;
+use gwr_components::sink::Sink;
+use gwr_components::source::Source;
+use gwr_components::{connect_port, option_box_repeat, rc_limiter};
+use gwr_engine::engine::Engine;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::Routable;
+use gwr_engine::types::SimError;
+use gwr_models::congestion::{CongestionNotifier, ReactionConfig, ReactionPoint};
+use gwr_models::ethernet_frame::{EthernetFrame, u64_to_mac};
+use gwr_models::fabric::node::FabricRoutingAlgorithm;
+use gwr_models::fabric::routed::RoutedFabric;
+use gwr_models::fabric::{Fabric, FabricConfig};
+use gwr_models::ring_node::{RING_INDEX, RingConfig, RingNode};
+
+const NUM_FRAMES: usize = 100;
+const PAYLOAD_BYTES: usize = 64;
+const PORT_BITS_PER_TICK: usize = 128;
+const ECN_THRESHOLD_BYTES: usize = 256;
+const NOTIFICATION_DELAY_TICKS: u64 = 10;
+const NUM_SOURCES: usize = 2;
+
+fn source_of(frame: &EthernetFrame) -> u64 {
+    frame.get_src()
+}
+
+/// A reaction that never reduces the rate of the source
+fn no_reaction() -> ReactionConfig {
+    ReactionConfig::new(PORT_BITS_PER_TICK).with_min_rate_bits_per_tick(PORT_BITS_PER_TICK as f64)
+}
+
+struct IncastResult {
+    num_marked: usize,
+    num_sunk: usize,
+    reaction_points: Vec<Rc<ReactionPoint<EthernetFrame>>>,
+}
+
+/// Run two sources at the line rate into a single fabric port so that the
+/// ingress buffers of the sources fill
+fn run_incast(config: FabricConfig, reaction: &ReactionConfig) -> IncastResult {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let top = engine.top().clone();
+    let dest = NUM_SOURCES;
+
+    let fabric = RoutedFabric::new_and_register(
+        &engine,
+        &clock,
+        &top,
+        "fabric",
+        Rc::new(config),
+        FabricRoutingAlgorithm::ColumnFirst,
+    )
+    .unwrap();
+
+    let notifier = CongestionNotifier::new_and_register(
+        &engine,
+        &clock,
+        &top,
+        "notifier",
+        source_of,
+        NOTIFICATION_DELAY_TICKS,
+    );
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+    connect_port!(fabric, egress, dest => notifier, rx).unwrap();
+    connect_port!(notifier, tx => sink, rx).unwrap();
+
+    let mut reaction_points = Vec::new();
+    for i in 0..NUM_SOURCES {
+        let source = Source::new_and_register(&engine, &top, &format!("source_{i}"), None);
+        let frame = EthernetFrame::new(&top, PAYLOAD_BYTES)
+            .set_dest(u64_to_mac(dest as u64))
+            .set_src(u64_to_mac(i as u64));
+        source.set_generator(option_box_repeat!(frame; NUM_FRAMES));
+
+        let reaction_point =
+            ReactionPoint::new_and_register(&engine, &clock, &top, &format!("rp_{i}"), reaction)
+                .unwrap();
+        notifier.add_source(i as u64, reaction_point.controller());
+        connect_port!(source, tx => reaction_point, rx).unwrap();
+        connect_port!(reaction_point, tx => fabric, ingress, i).unwrap();
+        reaction_points.push(reaction_point);
+    }
+
+    // The remaining ports are idle
+    let idle_source = Source::new_and_register(&engine, &top, "idle_source", None);
+    connect_port!(idle_source, tx => fabric, ingress, dest).unwrap();
+    for i in 0..NUM_SOURCES {
+        let idle_sink = Sink::new_and_register(&engine, &clock, &top, &format!("idle_sink_{i}"));
+        connect_port!(fabric, egress, i => idle_sink, rx).unwrap();
+    }
+
+    run_simulation!(engine);
+    IncastResult {
+        num_marked: notifier.num_marked(),
+        num_sunk: sink.num_sunk(),
+        reaction_points,
+    }
+}
+
+fn fabric_config() -> FabricConfig {
+    FabricConfig::new(
+        1,
+        1,
+        NUM_SOURCES + 1,
+        None,
+        1,
+        1,
+        1024,
+        1024,
+        PORT_BITS_PER_TICK,
+    )
+}
+
+#[test]
+fn no_marking_without_threshold() {
+    let result = run_incast(fabric_config(), &no_reaction());
+    assert_eq!(result.num_sunk, NUM_SOURCES * NUM_FRAMES);
+    assert_eq!(result.num_marked, 0);
+    for reaction_point in &result.reaction_points {
+        assert_eq!(reaction_point.controller().num_notifications(), 0);
+    }
+}
+
+#[test]
+fn fabric_marks_congested_frames() {
+    let config = fabric_config().with_ecn_threshold_bytes(ECN_THRESHOLD_BYTES);
+    let result = run_incast(config, &no_reaction());
+    assert_eq!(result.num_sunk, NUM_SOURCES * NUM_FRAMES);
+    assert!(result.num_marked > 0);
+
+    // The rate of the sources cannot drop below the line rate
+    let notifications: usize = result
+        .reaction_points
+        .iter()
+        .map(|reaction_point| reaction_point.controller().num_notifications())
+        .sum();
+    assert_eq!(notifications, result.num_marked);
+    for reaction_point in &result.reaction_points {
+        assert_eq!(
+            reaction_point.controller().rate_bits_per_tick(),
+            PORT_BITS_PER_TICK as f64
+        );
+    }
+}
+
+#[test]
+fn wormhole_fabric_marks_congested_frames() {
+    let config = fabric_config()
+        .with_flit_bytes(16)
+        .with_ecn_threshold_bytes(ECN_THRESHOLD_BYTES);
+    let result = run_incast(config, &no_reaction());
+    assert_eq!(result.num_sunk, NUM_SOURCES * NUM_FRAMES);
+    assert!(result.num_marked > 0);
+}
+
+#[test]
+fn reaction_reduces_marking() {
+    let config = || fabric_config().with_ecn_threshold_bytes(ECN_THRESHOLD_BYTES);
+    let unreactive = run_incast(config(), &no_reaction());
+
+    let reaction = ReactionConfig::new(PORT_BITS_PER_TICK).with_recovery(50, 1.0);
+    let reactive = run_incast(config(), &reaction);
+    assert_eq!(reactive.num_sunk, NUM_SOURCES * NUM_FRAMES);
+    for reaction_point in &reactive.reaction_points {
+        assert!(reaction_point.controller().num_decreases() > 0);
+    }
+    assert!(reactive.num_marked < unreactive.num_marked);
+}
+
+#[test]
+fn rate_decreases_once_per_interval_and_recovers() {
+    let line_rate = 64;
+    let interval_ticks = 100;
+    let step_bits_per_tick = 1.0;
+
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let top = engine.top().clone();
+
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    let frame = EthernetFrame::new(&top, PAYLOAD_BYTES);
+    source.set_generator(option_box_repeat!(frame; NUM_FRAMES));
+    let config = ReactionConfig::new(line_rate).with_recovery(interval_ticks, step_bits_per_tick);
+    let reaction_point =
+        ReactionPoint::new_and_register(&engine, &clock, &top, "rp", &config).unwrap();
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+    connect_port!(source, tx => reaction_point, rx).unwrap();
+    connect_port!(reaction_point, tx => sink, rx).unwrap();
+
+    // A burst of notifications only reduces the rate once
+    let controller = reaction_point.controller().clone();
+    controller.notify_congestion();
+    controller.notify_congestion();
+    assert_eq!(controller.num_notifications(), 2);
+    assert_eq!(controller.num_decreases(), 1);
+    assert_eq!(controller.rate_bits_per_tick(), line_rate as f64 / 2.0);
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), NUM_FRAMES);
+
+    // The rate recovers by one step per interval up to the line rate
+    let num_intervals = clock.tick_now().tick() / interval_ticks;
+    let expected_rate =
+        (line_rate as f64 / 2.0 + num_intervals as f64 * step_bits_per_tick).min(line_rate as f64);
+    assert_eq!(controller.rate_bits_per_tick(), expected_rate);
+    assert!(controller.rate_bits_per_tick() > line_rate as f64 / 2.0);
+}
+
+#[test]
+fn invalid_reaction_config() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = ReactionConfig::new(PORT_BITS_PER_TICK).with_decrease_factor(1.5);
+    let result = ReactionPoint::<EthernetFrame>::new_and_register(
+        &engine,
+        &clock,
+        engine.top(),
+        "rp",
+        &config,
+    );
+    assert!(result.is_err());
+
+    let config = ReactionConfig::new(PORT_BITS_PER_TICK).with_min_rate_bits_per_tick(0.0);
+    let result = ReactionPoint::<EthernetFrame>::new_and_register(
+        &engine,
+        &clock,
+        engine.top(),
+        "rp",
+        &config,
+    );
+    assert!(result.is_err());
+}
+
+struct ToRing;
+
+impl<T> Route<T> for ToRing
+where
+    T: Routable,
+{
+    fn route(&self, _obj: &T) -> Result<usize, SimError> {
+        Ok(RING_INDEX)
+    }
+}
+
+fn ring_node(
+    engine: &Engine,
+    clock: &Clock,
+    config: &RingConfig<EthernetFrame>,
+) -> Result<Rc<RingNode<EthernetFrame>>, SimError> {
+    RingNode::new_and_register(
+        engine,
+        clock,
+        engine.top(),
+        "ring_node",
+        config,
+        Box::new(ToRing),
+        Box::new(RoundRobin::new()),
+    )
+}
+
+#[test]
+fn ring_node_marks_and_source_reacts() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let top = engine.top().clone();
+
+    let config = RingConfig::new(1024, 1024, rc_limiter!(&clock, PORT_BITS_PER_TICK))
+        .with_ecn_threshold_bytes(ECN_THRESHOLD_BYTES);
+    let ring_node = ring_node(&engine, &clock, &config).unwrap();
+
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    let frame = EthernetFrame::new(&top, PAYLOAD_BYTES);
+    source.set_generator(option_box_repeat!(frame; NUM_FRAMES));
+    let reaction = ReactionConfig::new(PORT_BITS_PER_TICK).with_recovery(1000, 8.0);
+    let reaction_point =
+        ReactionPoint::new_and_register(&engine, &clock, &top, "rp", &reaction).unwrap();
+    connect_port!(source, tx => reaction_point, rx).unwrap();
+    connect_port!(reaction_point, tx => ring_node, io_rx).unwrap();
+
+    // The next node on the ring drains at a quarter of the rate
+    let ring_limiter = Limiter::new_and_register(
+        &engine,
+        &clock,
+        &top,
+        "ring_limiter",
+        rc_limiter!(&clock, PORT_BITS_PER_TICK / 4),
+    );
+    let notifier = CongestionNotifier::new_and_register(
+        &engine,
+        &clock,
+        &top,
+        "notifier",
+        source_of,
+        NOTIFICATION_DELAY_TICKS,
+    );
+    notifier.add_source(0, reaction_point.controller());
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+    let io_sink = Sink::new_and_register(&engine, &clock, &top, "io_sink");
+    connect_port!(ring_node, ring_tx => ring_limiter, rx).unwrap();
+    connect_port!(ring_limiter, tx => notifier, rx).unwrap();
+    connect_port!(notifier, tx => sink, rx).unwrap();
+    connect_port!(ring_node, io_tx => io_sink, rx).unwrap();
+    let ring_source = Source::new_and_register(&engine, &top, "ring_source", None);
+    connect_port!(ring_source, tx => ring_node, ring_rx).unwrap();
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), NUM_FRAMES);
+    assert!(notifier.num_marked() > 0);
+    assert!(reaction_point.controller().num_decreases() > 0);
+}
+
+#[test]
+fn ring_node_ecn_with_priority_queues_fails() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = RingConfig::new(1024, 1024, rc_limiter!(&clock, PORT_BITS_PER_TICK))
+        .with_priority_queues(2)
+        .with_ecn_threshold_bytes(ECN_THRESHOLD_BYTES);
+    assert!(ring_node(&engine, &clock, &config).is_err());
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_components::connect_port;
+use gwr_components::sink::Sink;
+use gwr_components::source::Source;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::types::AccessType;
+use gwr_models::memory::cxl::{CxlChannel, CxlMemConfig, CxlMemLink};
+use gwr_models::memory::memory_access::MemoryAccess;
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_models::memory::{Memory, MemoryConfig};
+use gwr_models::test_helpers::{
+    create_default_memory_map, create_read, create_write, create_write_np,
+};
+use gwr_track::entity::{Entity, GetEntity};
+
+const DST_ADDR: u64 = 0x80000;
+const SRC_ADDR: u64 = DST_ADDR + 0x1000;
+const CAPACITY_BYTES: usize = 0x40000;
+const BW_BYTES_PER_CYCLE: usize = 32;
+const DELAY_TICKS: usize = 8;
+const ACCESS_SIZE_BYTES: usize = 64;
+const OVERHEAD_SIZE_BYTES: usize = 16;
+
+/// Pass the accesses created by `create_accesses` through a CXL.mem link to a
+/// memory and return the responses received and the time taken
+fn run_cxl_test(
+    config: &CxlMemConfig,
+    create_accesses: impl FnOnce(&Rc<Entity>) -> Vec<MemoryAccess>,
+) -> (usize, f64, Rc<CxlMemLink<MemoryAccess>>) {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let top = engine.top().clone();
+
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    source.set_generator(Some(Box::new(create_accesses(source.entity()).into_iter())));
+    let cxl = CxlMemLink::new_and_register(&engine, &clock, &top, "cxl", config).unwrap();
+    let memory_config =
+        MemoryConfig::new(DST_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS);
+    let memory = Memory::new_and_register(&engine, &clock, &top, "memory", memory_config).unwrap();
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+
+    connect_port!(source, tx => cxl, host_rx).unwrap();
+    connect_port!(cxl, dev_tx => memory, rx).unwrap();
+    connect_port!(memory, tx => cxl, dev_rx).unwrap();
+    connect_port!(cxl, host_tx => sink, rx).unwrap();
+
+    run_simulation!(engine);
+    (sink.num_sunk(), engine.time_now_ns(), cxl)
+}
+
+fn single_read(created_by: &Rc<Entity>) -> Vec<MemoryAccess> {
+    let memory_map = Rc::new(create_default_memory_map());
+    vec![create_read(
+        created_by,
+        &memory_map,
+        ACCESS_SIZE_BYTES,
+        DST_ADDR,
+        SRC_ADDR,
+        OVERHEAD_SIZE_BYTES,
+    )]
+}
+
+#[test]
+fn latency_adders() {
+    let base = CxlMemConfig::new(64, 10, 1024);
+    let (num_responses, base_ns, _) = run_cxl_test(&base, single_read);
+    assert_eq!(num_responses, 1);
+
+    let config = CxlMemConfig::new(64, 20, 1024)
+        .with_host_port_latency(5)
+        .with_device_port_latency(7);
+    assert_eq!(config.one_way_latency_ticks(), 32);
+    let (num_responses, time_ns, _) = run_cxl_test(&config, single_read);
+    assert_eq!(num_responses, 1);
+
+    // The 1GHz default clock means that ticks and ns are the same
+    let extra_ticks = config.one_way_latency_ticks() - base.one_way_latency_ticks();
+    assert_eq!(time_ns - base_ns, (2 * extra_ticks) as f64);
+}
+
+#[test]
+fn channels() {
+    let num_each = 10;
+    let config = CxlMemConfig::new(64, 10, 1024);
+    let (num_responses, _, cxl) = run_cxl_test(&config, |created_by| {
+        let memory_map = Rc::new(create_default_memory_map());
+        let mut accesses = Vec::new();
+        for _ in 0..num_each {
+            for create_fn in [create_read, create_write, create_write_np] {
+                accesses.push(create_fn(
+                    created_by,
+                    &memory_map,
+                    ACCESS_SIZE_BYTES,
+                    DST_ADDR,
+                    SRC_ADDR,
+                    OVERHEAD_SIZE_BYTES,
+                ));
+            }
+        }
+        accesses
+    });
+
+    // Posted writes are not completed
+    assert_eq!(num_responses, 2 * num_each);
+    assert_eq!(cxl.num_messages(CxlChannel::Req), num_each);
+    assert_eq!(cxl.num_messages(CxlChannel::RwD), 2 * num_each);
+    assert_eq!(cxl.num_messages(CxlChannel::Ndr), num_each);
+    assert_eq!(cxl.num_messages(CxlChannel::Drs), num_each);
+}
+
+#[test]
+fn link_bandwidth_is_shared() {
+    let num_reads = 50;
+    let bits_per_tick = 8;
+    let config = CxlMemConfig::new(bits_per_tick, 0, 1024);
+    let (num_responses, time_ns, _) = run_cxl_test(&config, |created_by| {
+        (0..num_reads)
+            .flat_map(|_| single_read(created_by))
+            .collect()
+    });
+    assert_eq!(num_responses, num_reads);
+
+    // Every read response carries its data back over the link
+    let response_bytes = ACCESS_SIZE_BYTES + OVERHEAD_SIZE_BYTES;
+    let min_ticks = num_reads * response_bytes * 8 / bits_per_tick;
+    assert!(time_ns >= min_ticks as f64);
+}
+
+#[test]
+#[should_panic(expected = "top::cxl: ReadResponse cannot be sent on S2M DRS")]
+fn response_from_host_is_an_error() {
+    let config = CxlMemConfig::new(64, 10, 1024);
+    run_cxl_test(&config, |created_by| {
+        vec![MemoryAccess::new(
+            created_by,
+            AccessType::ReadResponse,
+            ACCESS_SIZE_BYTES,
+            DST_ADDR,
+            SRC_ADDR,
+            DeviceId(0),
+            DeviceId(0),
+            OVERHEAD_SIZE_BYTES,
+        )]
+    });
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_components::sink::Sink;
+use gwr_components::source::Source;
+use gwr_components::{connect_port, option_box_repeat};
+use gwr_engine::engine::Engine;
+use gwr_engine::power::EnergyAccount;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::traits::TotalBytes;
+use gwr_models::energy::EnergyConfig;
+use gwr_models::ethernet_frame::{EthernetFrame, u64_to_mac};
+use gwr_models::ethernet_link::{EthernetLink, EthernetLinkConfig};
+use gwr_models::fabric::functional::FunctionalFabric;
+use gwr_models::fabric::node::FabricRoutingAlgorithm;
+use gwr_models::fabric::routed::RoutedFabric;
+use gwr_models::fabric::{Fabric, FabricConfig};
+use gwr_track::entity::GetEntity;
+
+const HOP_ENERGY_PJ: f64 = 2.0;
+const BIT_ENERGY_PJ: f64 = 0.5;
+const IDLE_POWER_MW: f64 = 3.0;
+const NUM_FRAMES: usize = 10;
+const PAYLOAD_BYTES: usize = 64;
+
+fn energy_config() -> EnergyConfig {
+    EnergyConfig::new()
+        .with_hop_energy_pj(HOP_ENERGY_PJ)
+        .with_bit_energy_pj(BIT_ENERGY_PJ)
+        .with_idle_power_mw(IDLE_POWER_MW)
+}
+
+/// A fabric of two nodes, one hop apart, with one port each
+fn fabric_config(flit_bytes: Option<usize>) -> Rc<FabricConfig> {
+    let mut config =
+        FabricConfig::new(2, 1, 1, None, 1, 1, 1024, 1024, 128).with_energy(energy_config());
+    if let Some(flit_bytes) = flit_bytes {
+        config = config.with_flit_bytes(flit_bytes);
+    }
+    Rc::new(config)
+}
+
+fn frame(engine: &Engine, dest: u64) -> EthernetFrame {
+    EthernetFrame::new(engine.top(), PAYLOAD_BYTES).set_dest(u64_to_mac(dest))
+}
+
+/// Send frames from each port of a two port fabric to the other
+fn run_fabric(mut engine: Engine, fabric: Rc<dyn Fabric<EthernetFrame>>) -> Engine {
+    let clock = engine.default_clock();
+    let top = engine.top().clone();
+    for i in 0..2 {
+        let source = Source::new_and_register(&engine, &top, &format!("source_{i}"), None);
+        let frame = frame(&engine, 1 - i as u64);
+        source.set_generator(option_box_repeat!(frame; NUM_FRAMES));
+        connect_port!(source, tx => fabric, ingress, i).unwrap();
+
+        let sink = Sink::new_and_register(&engine, &clock, &top, &format!("sink_{i}"));
+        connect_port!(fabric, egress, i => sink, rx).unwrap();
+    }
+    run_simulation!(engine);
+    engine
+}
+
+fn check_fabric_energy(engine: &Engine, account: &EnergyAccount) {
+    let frame_bytes = frame(engine, 0).total_bytes();
+    let transfer_pj = HOP_ENERGY_PJ + (frame_bytes * 8) as f64 * BIT_ENERGY_PJ;
+    assert_eq!(
+        account.dynamic_energy_pj(),
+        (2 * NUM_FRAMES) as f64 * transfer_pj
+    );
+
+    // Both nodes draw the idle power
+    let time_now_ns = engine.time_now_ns();
+    assert_eq!(account.idle_power_mw(), 2.0 * IDLE_POWER_MW);
+    assert_eq!(
+        engine.power().total_energy_pj(time_now_ns),
+        account.dynamic_energy_pj() + 2.0 * IDLE_POWER_MW * time_now_ns
+    );
+}
+
+#[test]
+fn routed_fabric_energy() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let fabric = RoutedFabric::new_and_register(
+        &engine,
+        &clock,
+        &engine.top().clone(),
+        "fabric",
+        fabric_config(None),
+        FabricRoutingAlgorithm::ColumnFirst,
+    )
+    .unwrap();
+
+    let engine = run_fabric(engine, fabric.clone());
+    check_fabric_energy(&engine, fabric.energy_account().unwrap());
+}
+
+#[test]
+fn functional_fabric_energy() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let fabric = FunctionalFabric::new_and_register(
+        &engine,
+        &clock,
+        &engine.top().clone(),
+        "fabric",
+        fabric_config(None),
+    )
+    .unwrap();
+
+    let engine = run_fabric(engine, fabric.clone());
+    check_fabric_energy(&engine, fabric.energy_account().unwrap());
+}
+
+#[test]
+fn wormhole_hop_energy_is_per_flit() {
+    let flit_bytes = 16;
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let fabric = RoutedFabric::new_and_register(
+        &engine,
+        &clock,
+        &engine.top().clone(),
+        "fabric",
+        fabric_config(Some(flit_bytes)),
+        FabricRoutingAlgorithm::ColumnFirst,
+    )
+    .unwrap();
+
+    let engine = run_fabric(engine, fabric.clone());
+    let account = fabric.energy_account().unwrap();
+
+    // Every flit pays the hop energy, while the bit energy only depends on the
+    // bytes moved
+    let frame_bytes = frame(&engine, 0).total_bytes();
+    let num_flits = frame_bytes.div_ceil(flit_bytes);
+    let frame_pj = num_flits as f64 * HOP_ENERGY_PJ + (frame_bytes * 8) as f64 * BIT_ENERGY_PJ;
+    assert_eq!(
+        account.dynamic_energy_pj(),
+        (2 * NUM_FRAMES) as f64 * frame_pj
+    );
+}
+
+#[test]
+fn no_energy_without_config() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = Rc::new(FabricConfig::new(2, 1, 1, None, 1, 1, 1024, 1024, 128));
+    let fabric = RoutedFabric::<EthernetFrame>::new_and_register(
+        &engine,
+        &clock,
+        &engine.top().clone(),
+        "fabric",
+        config,
+        FabricRoutingAlgorithm::ColumnFirst,
+    )
+    .unwrap();
+
+    assert!(fabric.energy_account().is_none());
+    assert!(engine.power().accounts().is_empty());
+}
+
+#[test]
+fn ethernet_link_energy() {
+    let num_frames_b = 4;
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+
+    let source_a = Source::new_and_register(&engine, &top, "src_a", None);
+    let frame_a = EthernetFrame::new(source_a.entity(), PAYLOAD_BYTES);
+    let frame_bytes = frame_a.total_bytes();
+    source_a.set_generator(option_box_repeat!(frame_a; NUM_FRAMES));
+    let source_b = Source::new_and_register(&engine, &top, "src_b", None);
+    let frame_b = EthernetFrame::new(source_b.entity(), PAYLOAD_BYTES);
+    source_b.set_generator(option_box_repeat!(frame_b; num_frames_b));
+
+    let config = EthernetLinkConfig::new().with_energy(energy_config());
+    let link =
+        EthernetLink::new_and_register_with_config(&engine, &clock, &top, "link", &config).unwrap();
+    let sink_a = Sink::new_and_register(&engine, &clock, &top, "sink_a");
+    let sink_b = Sink::new_and_register(&engine, &clock, &top, "sink_b");
+
+    connect_port!(source_a, tx => link, rx_a).unwrap();
+    connect_port!(source_b, tx => link, rx_b).unwrap();
+    connect_port!(link, tx_a => sink_a, rx).unwrap();
+    connect_port!(link, tx_b => sink_b, rx).unwrap();
+
+    run_simulation!(engine);
+    assert_eq!(sink_a.num_sunk(), NUM_FRAMES);
+    assert_eq!(sink_b.num_sunk(), num_frames_b);
+
+    // Each frame crosses the link once in either direction
+    let account = link.energy_account().unwrap();
+    let transfer_pj = HOP_ENERGY_PJ + (frame_bytes * 8) as f64 * BIT_ENERGY_PJ;
+    assert_eq!(
+        account.dynamic_energy_pj(),
+        (NUM_FRAMES + num_frames_b) as f64 * transfer_pj
+    );
+
+    let time_now_ns = clock.time_now_ns();
+    assert_eq!(
+        account.idle_energy_pj(time_now_ns),
+        IDLE_POWER_MW * time_now_ns
+    );
+    assert_eq!(
+        engine.power().average_power_mw(time_now_ns),
+        account.energy_pj(time_now_ns) / time_now_ns
+    );
+}
@@ -1,16 +1,22 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use gwr_components::sink::Sink;
 use gwr_components::source::Source;
 use gwr_components::{connect_port, option_box_repeat};
+use gwr_engine::port::InPort;
 use gwr_engine::run_simulation;
 use gwr_engine::test_helpers::start_test;
 use gwr_engine::time::clock::Clock;
-use gwr_models::ethernet_frame::{EthernetFrame, FRAME_OVERHEAD_BYTES};
-use gwr_models::ethernet_link::{self, EthernetLink};
-use gwr_track::entity::GetEntity;
+use gwr_engine::traits::{Routable, TotalBytes};
+use gwr_models::ethernet_frame::{EthernetFrame, FRAME_OVERHEAD_BYTES, VLAN_TAG_BYTES};
+use gwr_models::ethernet_link::{self, EthernetLink, EthernetLinkConfig};
+use gwr_models::link_compression::CompressionConfig;
+use gwr_models::link_errors::{BitErrorConfig, Corruptible};
+use gwr_models::priority_queues::{NUM_PRIORITIES, PriorityQueueConfig, priority_to_queue};
+use gwr_track::entity::{Entity, GetEntity};
 
 fn run_test(
     num_put_a: usize,
@@ -166,3 +172,345 @@ fn change_delay_after_simulation_started_errors() {
         "top::link::a: can't change the delay after the simulation has started"
     );
 }
+
+fn run_priority_test(
+    queue_config: Option<PriorityQueueConfig>,
+    pcps: &[u8],
+    num_frames_per_pcp: usize,
+) -> Vec<u8> {
+    let mut engine = start_test(file!());
+
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+
+    let frames: Vec<_> = pcps
+        .iter()
+        .flat_map(|&pcp| {
+            let top = top.clone();
+            (0..num_frames_per_pcp).map(move |_| EthernetFrame::new(&top, 128).set_vlan(1, pcp))
+        })
+        .collect();
+    let source_a = Source::new_and_register(&engine, &top, "src_a", None);
+    source_a.set_generator(Some(Box::new(frames.into_iter())));
+    let source_b = Source::new_and_register(&engine, &top, "src_b", None);
+
+    let mut config = EthernetLinkConfig::new();
+    if let Some(queue_config) = queue_config {
+        config = config.with_priority_queues(queue_config);
+    }
+    let link =
+        EthernetLink::new_and_register_with_config(&engine, &clock, &top, "link", &config).unwrap();
+    let sink_b = Sink::new_and_register(&engine, &clock, &top, "sink_b");
+
+    connect_port!(source_a, tx => link, rx_a).unwrap();
+    connect_port!(source_b, tx => link, rx_b).unwrap();
+    connect_port!(link, tx_b => sink_b, rx).unwrap();
+
+    let mut port = InPort::new(&engine, &clock, &Rc::new(Entity::new(&top, "port")), "rx");
+    link.connect_port_tx_a(port.state()).unwrap();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let pcps_received = received.clone();
+    let num_frames = pcps.len() * num_frames_per_pcp;
+    engine.spawn(async move {
+        for _ in 0..num_frames {
+            let frame: EthernetFrame = port.get()?.await;
+            pcps_received.borrow_mut().push(frame.pcp().unwrap());
+        }
+        Ok(())
+    });
+
+    run_simulation!(engine);
+    received.take()
+}
+
+#[test]
+fn vlan_tag() {
+    let engine = start_test(file!());
+    let frame = EthernetFrame::new(engine.top(), 128);
+    assert_eq!(frame.vlan_id(), None);
+    assert_eq!(frame.pcp(), None);
+
+    let tagged = frame.clone().set_traffic_class(2).set_vlan(0x1234, 5);
+    assert_eq!(tagged.vlan_id(), Some(0x234));
+    assert_eq!(tagged.pcp(), Some(5));
+    assert_eq!(tagged.total_bytes(), frame.total_bytes() + VLAN_TAG_BYTES);
+
+    // The PCP of a tagged frame overrides its traffic class
+    assert_eq!(tagged.traffic_class(), 5);
+}
+
+#[test]
+fn priority_to_queue_mapping() {
+    // With a single queue everything shares it
+    assert!((0..NUM_PRIORITIES).all(|pcp| priority_to_queue(pcp, 1) == 0));
+
+    // With all eight queues background traffic is below best effort
+    let queues: Vec<_> = (0..NUM_PRIORITIES)
+        .map(|pcp| priority_to_queue(pcp, NUM_PRIORITIES))
+        .collect();
+    assert_eq!(queues, [1, 0, 2, 3, 4, 5, 6, 7]);
+
+    // Unknown priorities are treated as best effort
+    assert_eq!(priority_to_queue(9, 4), priority_to_queue(0, 4));
+}
+
+#[test]
+fn priority_queues() {
+    let num_frames = 10;
+
+    // Without queues the frames stay in order
+    let pcps = run_priority_test(None, &[0, 7], num_frames);
+    let first_high = pcps.iter().position(|&pcp| pcp == 7).unwrap();
+    assert_eq!(first_high, num_frames);
+
+    // With queues the high priority frames overtake the waiting low priority
+    // frames
+    let queue_config = PriorityQueueConfig::new(2, 4096);
+    let pcps = run_priority_test(Some(queue_config), &[0, 7], num_frames);
+    let last_high = pcps.iter().rposition(|&pcp| pcp == 7).unwrap();
+    assert!(last_high < num_frames + 2, "{pcps:?}");
+}
+
+#[test]
+fn priority_queues_background() {
+    let num_frames = 10;
+
+    // Background (PCP 1) traffic is overtaken by best effort (PCP 0) traffic
+    let queue_config = PriorityQueueConfig::new(NUM_PRIORITIES, 4096);
+    let pcps = run_priority_test(Some(queue_config), &[1, 0], num_frames);
+    let last_best_effort = pcps.iter().rposition(|&pcp| pcp == 0).unwrap();
+    assert!(last_best_effort < num_frames + 2, "{pcps:?}");
+}
+
+#[test]
+#[should_panic(expected = "Unsupported number of priority queues 0 (must be 1 to 8)")]
+fn invalid_priority_queues() {
+    run_priority_test(Some(PriorityQueueConfig::new(0, 4096)), &[0], 1);
+}
+
+/// Send frames from a to b through a link with bit errors and return the
+/// number of frames received and how many of those were corrupted.
+fn run_bit_error_test(
+    error_config: BitErrorConfig,
+    num_frames: usize,
+) -> (usize, usize, Rc<EthernetLink<EthernetFrame>>) {
+    let mut engine = start_test(file!());
+
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+
+    let source_a = Source::new_and_register(&engine, &top, "src_a", None);
+    let frame_a = EthernetFrame::new(source_a.entity(), 128);
+    source_a.set_generator(option_box_repeat!(frame_a; num_frames));
+    let source_b = Source::new_and_register(&engine, &top, "src_b", None);
+
+    let config = EthernetLinkConfig::new().with_bit_errors(error_config);
+    let link =
+        EthernetLink::new_and_register_with_config(&engine, &clock, &top, "link", &config).unwrap();
+    let sink_b = Sink::new_and_register(&engine, &clock, &top, "sink_b");
+
+    connect_port!(source_a, tx => link, rx_a).unwrap();
+    connect_port!(source_b, tx => link, rx_b).unwrap();
+    connect_port!(link, tx_b => sink_b, rx).unwrap();
+
+    let mut port = InPort::new(&engine, &clock, &Rc::new(Entity::new(&top, "port")), "rx");
+    link.connect_port_tx_a(port.state()).unwrap();
+    let counts = Rc::new(RefCell::new((0, 0)));
+    let received = counts.clone();
+    // Frames that are dropped are never received, leaving this task waiting
+    engine.spawn(async move {
+        for _ in 0..num_frames {
+            let frame: EthernetFrame = port.get()?.await;
+            let mut received = received.borrow_mut();
+            received.0 += 1;
+            if frame.is_corrupted() {
+                received.1 += 1;
+            }
+        }
+        Ok(())
+    });
+
+    run_simulation!(engine);
+    link.dump_stats();
+    let (num_received, num_corrupted) = *counts.borrow();
+    (num_received, num_corrupted, link)
+}
+
+#[test]
+fn bit_errors_corrupt_frames() {
+    let num_frames = 1000;
+
+    // Each frame is 1184 bits, so roughly 11% of frames contain an error
+    let (num_received, num_corrupted, link) =
+        run_bit_error_test(BitErrorConfig::new(1e-4, 1), num_frames);
+    assert_eq!(num_received, num_frames);
+    assert_eq!(num_corrupted, link.num_corrupted());
+    assert_eq!(link.num_dropped(), 0);
+    assert!((60..170).contains(&num_corrupted), "{num_corrupted}");
+}
+
+#[test]
+fn bit_errors_with_fcs_drop_frames() {
+    let num_frames = 1000;
+
+    let config = BitErrorConfig::new(1e-4, 1).with_fcs();
+    let (num_received, num_corrupted, link) = run_bit_error_test(config, num_frames);
+    assert_eq!(num_corrupted, 0);
+    assert!(link.num_dropped() > 0);
+    assert_eq!(link.num_dropped(), link.num_corrupted());
+    assert_eq!(num_received + link.num_dropped(), num_frames);
+}
+
+#[test]
+fn bit_errors_are_repeatable() {
+    let config = BitErrorConfig::new(1e-4, 7);
+    let (_, first, _) = run_bit_error_test(config, 500);
+    let (_, second, _) = run_bit_error_test(config, 500);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn bit_error_rate_limits() {
+    let (num_received, num_corrupted, _) = run_bit_error_test(BitErrorConfig::new(0.0, 1), 100);
+    assert_eq!(num_received, 100);
+    assert_eq!(num_corrupted, 0);
+
+    let (num_received, _, link) = run_bit_error_test(BitErrorConfig::new(1.0, 1).with_fcs(), 100);
+    assert_eq!(num_received, 0);
+    assert_eq!(link.num_dropped(), 100);
+}
+
+#[test]
+fn invalid_bit_error_rate() {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let config = EthernetLinkConfig::new().with_bit_errors(BitErrorConfig::new(1.5, 1));
+    let result: Result<Rc<EthernetLink<EthernetFrame>>, _> =
+        EthernetLink::new_and_register_with_config(&engine, &clock, engine.top(), "link", &config);
+    match result {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "top::link::errors_a: bit error rate 1.5 must be in the range [0, 1]"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
+
+/// Send frames from a to b through a link with the given config and return
+/// the frames received and the link.
+fn run_compression_test(
+    config: &EthernetLinkConfig,
+    compressibility: f64,
+    num_frames: usize,
+    payload_bytes: usize,
+) -> (Vec<EthernetFrame>, Rc<EthernetLink<EthernetFrame>>, Clock) {
+    let mut engine = start_test(file!());
+
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+
+    let source_a = Source::new_and_register(&engine, &top, "src_a", None);
+    let frame_a =
+        EthernetFrame::new(source_a.entity(), payload_bytes).set_compressibility(compressibility);
+    source_a.set_generator(option_box_repeat!(frame_a; num_frames));
+    let source_b = Source::new_and_register(&engine, &top, "src_b", None);
+
+    let link =
+        EthernetLink::new_and_register_with_config(&engine, &clock, &top, "link", config).unwrap();
+    let sink_b = Sink::new_and_register(&engine, &clock, &top, "sink_b");
+
+    connect_port!(source_a, tx => link, rx_a).unwrap();
+    connect_port!(source_b, tx => link, rx_b).unwrap();
+    connect_port!(link, tx_b => sink_b, rx).unwrap();
+
+    let mut port = InPort::new(&engine, &clock, &Rc::new(Entity::new(&top, "port")), "rx");
+    link.connect_port_tx_a(port.state()).unwrap();
+    let frames = Rc::new(RefCell::new(Vec::new()));
+    let received = frames.clone();
+    engine.spawn(async move {
+        for _ in 0..num_frames {
+            let frame: EthernetFrame = port.get()?.await;
+            received.borrow_mut().push(frame);
+        }
+        Ok(())
+    });
+
+    run_simulation!(engine);
+    link.dump_stats();
+    (frames.take(), link, clock)
+}
+
+#[test]
+fn compression_reduces_bytes_on_the_wire() {
+    let num_frames = 100;
+    let payload_bytes = 1000;
+    let config = EthernetLinkConfig::new().with_compression(CompressionConfig::new(4.0));
+    let (frames, link, clock) = run_compression_test(&config, 2.0, num_frames, payload_bytes);
+
+    // Frames are restored to their original size after the link
+    assert_eq!(frames.len(), num_frames);
+    let frame_bytes = payload_bytes + FRAME_OVERHEAD_BYTES;
+    assert!(
+        frames
+            .iter()
+            .all(|frame| frame.total_bytes() == frame_bytes)
+    );
+    assert_eq!(link.uncompressed_bytes(), num_frames * frame_bytes);
+
+    // Only half of the payload is sent on the wire
+    let wire_bytes = payload_bytes / 2 + FRAME_OVERHEAD_BYTES;
+    assert_eq!(link.compressed_bytes(), num_frames * wire_bytes);
+    let frame_ticks = (wire_bytes * 8).div_ceil(ethernet_link::BITS_PER_TICK);
+    let expected_time = ethernet_link::DELAY_TICKS + frame_ticks * (num_frames - 1);
+    assert_eq!(clock.time_now_ns(), expected_time as f64);
+}
+
+#[test]
+fn compression_is_limited_by_max_ratio() {
+    let payload_bytes = 1000;
+    let config = EthernetLinkConfig::new().with_compression(CompressionConfig::new(2.0));
+    let (_, link, _) = run_compression_test(&config, 8.0, 1, payload_bytes);
+    assert_eq!(
+        link.compressed_bytes(),
+        payload_bytes / 2 + FRAME_OVERHEAD_BYTES
+    );
+}
+
+#[test]
+fn incompressible_frames() {
+    let payload_bytes = 1000;
+    let config = EthernetLinkConfig::new().with_compression(CompressionConfig::new(4.0));
+    let (_, link, _) = run_compression_test(&config, 1.0, 10, payload_bytes);
+    assert_eq!(link.compressed_bytes(), link.uncompressed_bytes());
+}
+
+#[test]
+fn compression_with_queues_and_bit_errors() {
+    let num_frames = 10;
+    let config = EthernetLinkConfig::new()
+        .with_priority_queues(PriorityQueueConfig::new(2, 4096))
+        .with_bit_errors(BitErrorConfig::new(0.0, 1))
+        .with_compression(CompressionConfig::new(4.0));
+    let (frames, link, _) = run_compression_test(&config, 4.0, num_frames, 400);
+    assert_eq!(frames.len(), num_frames);
+    assert_eq!(
+        link.compressed_bytes(),
+        num_frames * (100 + FRAME_OVERHEAD_BYTES)
+    );
+}
+
+#[test]
+fn invalid_compression_ratio() {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let config = EthernetLinkConfig::new().with_compression(CompressionConfig::new(0.5));
+    let result: Result<Rc<EthernetLink<EthernetFrame>>, _> =
+        EthernetLink::new_and_register_with_config(&engine, &clock, engine.top(), "link", &config);
+    match result {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "top::link::compress_a: maximum compression ratio 0.5 must be at least 1.0"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
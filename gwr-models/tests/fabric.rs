@@ -1,25 +1,33 @@
 // Copyright (c) 2025 Graphcore Ltd. All rights reserved.
 
+use std::cell::RefCell;
 use std::fmt::Debug;
 use std::rc::Rc;
 
+use gwr_components::arbiter::policy::TrafficClasses;
 use gwr_components::connect_port;
 use gwr_components::sink::Sink;
 use gwr_components::source::Source;
 use gwr_engine::engine::Engine;
+use gwr_engine::port::InPort;
 use gwr_engine::run_simulation;
 use gwr_engine::test_helpers::start_test;
-use gwr_engine::traits::TotalBytes;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::traits::{Routable, TotalBytes};
 use gwr_engine::types::AccessType;
 use gwr_models::build_model_harness;
 use gwr_models::ethernet_frame::{EthernetFrame, SRC_MAC_BYTES, u64_to_mac};
+use gwr_models::fabric::crossbar::CrossbarFabric;
+use gwr_models::fabric::deadlock::{check_deadlock_free, dependency_cycle};
+use gwr_models::fabric::fat_tree::{FatTreeConfig, FatTreeFabric};
 use gwr_models::fabric::functional::FunctionalFabric;
-use gwr_models::fabric::node::FabricRoutingAlgorithm;
+use gwr_models::fabric::node::{FabricRoutingAlgorithm, Port};
 use gwr_models::fabric::routed::RoutedFabric;
-use gwr_models::fabric::{Fabric, FabricConfig};
+use gwr_models::fabric::{Fabric, FabricConfig, FabricTopology};
 use gwr_models::memory::memory_access::MemoryAccess;
 use gwr_models::memory::memory_map::DeviceId;
 use gwr_models::test_helpers::MemoryTxn;
+use gwr_track::entity::Entity;
 
 trait ToDest {
     fn to_dest(&self, source_index: usize, frame_index: usize) -> [u8; SRC_MAC_BYTES];
@@ -79,19 +87,41 @@ fn run_test(
     let fabric =
         FunctionalFabric::new_and_register(&engine, &clock, top, "fabric", config.clone()).unwrap();
 
-    let num_ports = config.num_ports();
+    run_frames(
+        &mut engine,
+        &clock,
+        fabric,
+        config.num_ports(),
+        to_dest,
+        num_frames,
+        payload_bytes,
+    )
+}
+
+/// Connect a source and sink to every port of a fabric and run until all
+/// frames have been sent
+fn run_frames(
+    engine: &mut Engine,
+    clock: &Clock,
+    fabric: Rc<dyn Fabric<EthernetFrame>>,
+    num_ports: usize,
+    to_dest: &impl ToDest,
+    num_frames: usize,
+    payload_bytes: usize,
+) -> Vec<Rc<Sink<EthernetFrame>>> {
+    let top = engine.top().clone();
     let mut sources = Vec::with_capacity(num_ports);
     let mut sinks = Vec::with_capacity(num_ports);
 
     for i in 0..num_ports {
-        let source = Source::new_and_register(&engine, top, &format!("source_{i}"), None);
+        let source = Source::new_and_register(engine, &top, &format!("source_{i}"), None);
         source.set_generator(Some(Box::new(
-            build_frames(&engine, i, to_dest, num_frames, payload_bytes).into_iter(),
+            build_frames(engine, i, to_dest, num_frames, payload_bytes).into_iter(),
         )));
         connect_port!(source, tx => fabric, ingress, i).unwrap();
         sources.push(source);
 
-        let sink = Sink::new_and_register(&engine, &clock, top, &format!("sink_{i}"));
+        let sink = Sink::new_and_register(engine, clock, &top, &format!("sink_{i}"));
         connect_port!(fabric, egress, i => sink, rx).unwrap();
         sinks.push(sink);
     }
@@ -101,6 +131,14 @@ fn run_test(
 }
 
 fn default_config() -> Rc<FabricConfig> {
+    Rc::new(default_config_builder())
+}
+
+fn torus_config() -> Rc<FabricConfig> {
+    Rc::new(default_config_builder().with_topology(FabricTopology::Torus))
+}
+
+fn default_config_builder() -> FabricConfig {
     let num_columns = 3;
     let num_rows = 4;
     let num_ports_per_node = 2;
@@ -110,7 +148,7 @@ fn default_config() -> Rc<FabricConfig> {
     let tx_buffer_bytes = 1024;
     let port_bits_per_tick = 128;
 
-    let config = FabricConfig::new(
+    FabricConfig::new(
         num_columns,
         num_rows,
         num_ports_per_node,
@@ -120,8 +158,7 @@ fn default_config() -> Rc<FabricConfig> {
         rx_buffer_bytes,
         tx_buffer_bytes,
         port_bits_per_tick,
-    );
-    Rc::new(config)
+    )
 }
 
 #[test]
@@ -158,11 +195,25 @@ fn all_to_all() {
 }
 
 #[test]
-fn latency() {
-    // Test sending a single frame across the fabric
+fn torus_all_to_all() {
+    let num_frames = 100;
     let payload_bytes = 256;
 
-    let config = default_config();
+    let config = torus_config();
+    let num_ports = config.num_ports();
+
+    let to_dest = ToNext { num_ports };
+    let sinks = run_test(&config, &to_dest, num_frames, payload_bytes);
+
+    for sink in sinks.iter().take(num_ports) {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+}
+
+/// Send a single frame from one corner of the fabric to the other and return
+/// the number of ticks spent in the fabric after the ingress limiter.
+fn corner_to_corner_ticks(config: &Rc<FabricConfig>) -> usize {
+    let payload_bytes = 256;
     let num_ports = config.num_ports();
 
     let mut engine = start_test(file!());
@@ -215,10 +266,883 @@ fn latency() {
     }
 
     let ticks_through_limiter = frame_bits.div_ceil(config.port_bits_per_tick());
-    let num_hops = (num_columns - 1) + (num_rows - 1);
-    let ticks_through_fabric = num_hops * config.cycles_per_hop() + config.cycles_overhead();
-    let ticks = ticks_through_limiter + ticks_through_fabric;
-    assert_eq!(clock.tick_now().tick(), ticks as u64);
+    clock.tick_now().tick() as usize - ticks_through_limiter
+}
+
+#[test]
+fn latency() {
+    // Test sending a single frame across the fabric
+    let config = default_config();
+    let ticks = corner_to_corner_ticks(&config);
+
+    let num_hops = (config.num_columns() - 1) + (config.num_rows() - 1);
+    assert_eq!(
+        ticks,
+        num_hops * config.cycles_per_hop() + config.cycles_overhead()
+    );
+}
+
+#[test]
+fn torus_latency() {
+    // Opposite corners of a torus are neighbours in both dimensions
+    let config = torus_config();
+    let ticks = corner_to_corner_ticks(&config);
+    assert_eq!(
+        ticks,
+        2 * config.cycles_per_hop() + config.cycles_overhead()
+    );
+}
+
+struct ToOpposite {
+    num_ports: usize,
+}
+
+impl ToDest for ToOpposite {
+    fn to_dest(&self, source_index: usize, _frame_index: usize) -> [u8; SRC_MAC_BYTES] {
+        let dest = (source_index + self.num_ports / 2) % self.num_ports;
+        u64_to_mac(dest as u64)
+    }
+}
+
+/// Run frames through a fat-tree fabric and return the sinks and the tick at
+/// which the simulation completed
+fn run_fat_tree_test(
+    fat_tree_config: FatTreeConfig,
+    to_dest: &impl ToDest,
+    num_frames: usize,
+    payload_bytes: usize,
+) -> (Vec<Rc<Sink<EthernetFrame>>>, u64) {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top();
+
+    let config = default_config();
+    let fabric = FatTreeFabric::new_and_register(
+        &engine,
+        &clock,
+        top,
+        "fabric",
+        config.clone(),
+        fat_tree_config,
+    )
+    .unwrap();
+
+    let sinks = run_frames(
+        &mut engine,
+        &clock,
+        fabric,
+        config.num_ports(),
+        to_dest,
+        num_frames,
+        payload_bytes,
+    );
+    (sinks, clock.tick_now().tick())
+}
+
+#[test]
+fn fat_tree_all_to_one() {
+    let num_frames = 100;
+    let num_ports = default_config().num_ports();
+
+    let (sinks, _) = run_fat_tree_test(FatTreeConfig::new(2), &FixedDest(0), num_frames, 256);
+
+    assert_eq!(sinks[0].num_sunk(), num_ports * num_frames);
+    for sink in sinks.iter().skip(1) {
+        assert_eq!(sink.num_sunk(), 0);
+    }
+}
+
+#[test]
+fn fat_tree_all_to_all() {
+    let num_frames = 100;
+    let num_ports = default_config().num_ports();
+
+    let to_dest = ToNext { num_ports };
+    let (sinks, _) = run_fat_tree_test(FatTreeConfig::new(2), &to_dest, num_frames, 256);
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+}
+
+#[test]
+fn fat_tree_oversubscription() {
+    let num_frames = 50;
+    let num_ports = default_config().num_ports();
+
+    // All traffic has to cross the spines
+    let to_dest = ToOpposite { num_ports };
+    let (sinks, full_ticks) = run_fat_tree_test(FatTreeConfig::new(2), &to_dest, num_frames, 256);
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+
+    let (sinks, oversubscribed_ticks) = run_fat_tree_test(
+        FatTreeConfig::new(2).with_oversubscription(4),
+        &to_dest,
+        num_frames,
+        256,
+    );
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+
+    // Spine links have a quarter of the bandwidth of the ports
+    assert!(oversubscribed_ticks > 3 * full_ticks);
+}
+
+#[test]
+fn fat_tree_hops_are_independent_of_distance() {
+    let config = default_config();
+    let tick_to = |col, row| {
+        let mut engine = start_test(file!());
+        let clock = engine.clock_ghz(1.0);
+        let top = engine.top();
+        let fabric = FatTreeFabric::new_and_register(
+            &engine,
+            &clock,
+            top,
+            "fabric",
+            config.clone(),
+            FatTreeConfig::new(1),
+        )
+        .unwrap();
+
+        let dest_index = fabric.col_row_port_to_fabric_port_index(col, row, 0);
+        let mut sinks = Vec::new();
+        for i in 0..config.num_ports() {
+            let source = Source::new_and_register(&engine, top, &format!("source_{i}"), None);
+            if i == 0 {
+                let frames = build_frames(&engine, 0, &FixedDest(dest_index as u64), 1, 256);
+                source.set_generator(Some(Box::new(frames.into_iter())));
+            }
+            connect_port!(source, tx => fabric, ingress, i).unwrap();
+            let sink = Sink::new_and_register(&engine, &clock, top, &format!("sink_{i}"));
+            connect_port!(fabric, egress, i => sink, rx).unwrap();
+            sinks.push(sink);
+        }
+
+        run_simulation!(engine);
+        assert_eq!(sinks[dest_index].num_sunk(), 1);
+        clock.tick_now().tick()
+    };
+
+    let local_tick = tick_to(0, 0);
+    let neighbour_tick = tick_to(0, 1);
+    let corner_tick = tick_to(config.num_columns() - 1, config.num_rows() - 1);
+
+    assert_eq!(neighbour_tick, corner_tick);
+    assert!(neighbour_tick >= local_tick + 2 * config.cycles_per_hop() as u64);
+}
+
+#[test]
+fn fat_tree_without_spines_is_an_error() {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top();
+
+    let result = FatTreeFabric::<usize>::new_and_register(
+        &engine,
+        &clock,
+        top,
+        "fabric",
+        default_config(),
+        FatTreeConfig::new(0),
+    );
+
+    let Err(err) = result else {
+        panic!("Expected fat-tree without spines to return an error");
+    };
+    assert!(
+        format!("{err}").contains("no spines"),
+        "Unexpected error: {err}"
+    );
+}
+
+fn run_crossbar_test(
+    config: &Rc<FabricConfig>,
+    latency_ticks: usize,
+    to_dest: &impl ToDest,
+    num_frames: usize,
+    payload_bytes: usize,
+) -> (Vec<Rc<Sink<EthernetFrame>>>, u64) {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top();
+
+    let fabric = CrossbarFabric::new_and_register(
+        &engine,
+        &clock,
+        top,
+        "fabric",
+        config.clone(),
+        latency_ticks,
+    )
+    .unwrap();
+
+    let sinks = run_frames(
+        &mut engine,
+        &clock,
+        fabric,
+        config.num_ports(),
+        to_dest,
+        num_frames,
+        payload_bytes,
+    );
+    (sinks, clock.tick_now().tick())
+}
+
+#[test]
+fn crossbar_all_to_one() {
+    let num_frames = 100;
+    let num_ports = default_config().num_ports();
+
+    let (sinks, _) = run_crossbar_test(&default_config(), 5, &FixedDest(0), num_frames, 256);
+
+    assert_eq!(sinks[0].num_sunk(), num_ports * num_frames);
+    for sink in sinks.iter().skip(1) {
+        assert_eq!(sink.num_sunk(), 0);
+    }
+}
+
+#[test]
+fn crossbar_all_to_all() {
+    let num_frames = 100;
+    let num_ports = default_config().num_ports();
+
+    let to_dest = ToOpposite { num_ports };
+    let (sinks, _) = run_crossbar_test(&default_config(), 5, &to_dest, num_frames, 256);
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+}
+
+#[test]
+fn crossbar_latency() {
+    // A single frame takes the same time to cross the crossbar wherever its
+    // destination is, so the run time only depends on the latency
+    let num_ports = default_config().num_ports();
+    let to_dest = ToOpposite { num_ports };
+    let (_, ticks) = run_crossbar_test(&default_config(), 5, &to_dest, 1, 256);
+    let (_, slower_ticks) = run_crossbar_test(&default_config(), 25, &to_dest, 1, 256);
+    assert_eq!(slower_ticks, ticks + 20);
+
+    let (_, next_ticks) = run_crossbar_test(&default_config(), 5, &ToNext { num_ports }, 1, 256);
+    assert_eq!(next_ticks, ticks);
+}
+
+#[test]
+fn crossbar_is_a_baseline_for_routed() {
+    let num_frames = 20;
+    let config = default_config();
+    let num_ports = config.num_ports();
+
+    let to_dest = ToOpposite { num_ports };
+    let (_, crossbar_ticks) =
+        run_crossbar_test(&config, config.cycles_per_hop(), &to_dest, num_frames, 256);
+
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let fabric = RoutedFabric::new_and_register(
+        &engine,
+        &clock,
+        engine.top(),
+        "fabric",
+        config.clone(),
+        FabricRoutingAlgorithm::ColumnFirst,
+    )
+    .unwrap();
+    run_frames(
+        &mut engine,
+        &clock,
+        fabric,
+        num_ports,
+        &to_dest,
+        num_frames,
+        256,
+    );
+    let routed_ticks = clock.tick_now().tick();
+
+    assert!(crossbar_ticks < routed_ticks);
+}
+
+#[test]
+fn crossbar_with_unpopulated_ports() {
+    let num_frames = 10;
+
+    // Limiting the ports per node leaves some ports at the centre unpopulated
+    let config = Rc::new(FabricConfig::new(3, 3, 2, Some(5), 5, 1, 1024, 1024, 128));
+    let port_indices = config.port_indices().clone();
+    assert!(port_indices.len() < config.max_num_ports());
+
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+    let fabric =
+        CrossbarFabric::new_and_register(&engine, &clock, &top, "fabric", config.clone(), 5)
+            .unwrap();
+
+    let mut sinks = Vec::new();
+    for (position, &i) in port_indices.iter().enumerate() {
+        let dest = port_indices[(position + 1) % port_indices.len()];
+        let source = Source::new_and_register(&engine, &top, &format!("source_{i}"), None);
+        let frames = build_frames(&engine, i, &FixedDest(dest as u64), num_frames, 256);
+        source.set_generator(Some(Box::new(frames.into_iter())));
+        connect_port!(source, tx => fabric, ingress, i).unwrap();
+
+        let sink = Sink::new_and_register(&engine, &clock, &top, &format!("sink_{i}"));
+        connect_port!(fabric, egress, i => sink, rx).unwrap();
+        sinks.push(sink);
+    }
+
+    let unpopulated = (0..config.max_num_ports())
+        .find(|i| !port_indices.contains(i))
+        .unwrap();
+    assert!(fabric.port_ingress_i(unpopulated).is_err());
+
+    run_simulation!(engine);
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+}
+
+#[test]
+fn crossbar_with_one_port_is_an_error() {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top();
+
+    let config = Rc::new(FabricConfig::new(1, 1, 1, None, 5, 1, 1024, 1024, 128));
+    let result =
+        CrossbarFabric::<usize>::new_and_register(&engine, &clock, top, "fabric", config, 5);
+
+    let Err(err) = result else {
+        panic!("Expected crossbar with one port to return an error");
+    };
+    assert!(
+        format!("{err}").contains("less than 2 ports"),
+        "Unexpected error: {err}"
+    );
+}
+
+/// Run frames through a routed fabric and return the sinks and fabric
+fn run_routed_test(
+    config: &Rc<FabricConfig>,
+    algorithm: FabricRoutingAlgorithm,
+    to_dest: &impl ToDest,
+    num_frames: usize,
+    payload_bytes: usize,
+) -> (
+    Vec<Rc<Sink<EthernetFrame>>>,
+    Rc<RoutedFabric<EthernetFrame>>,
+) {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top();
+
+    let fabric =
+        RoutedFabric::new_and_register(&engine, &clock, top, "fabric", config.clone(), algorithm)
+            .unwrap();
+
+    let sinks = run_frames(
+        &mut engine,
+        &clock,
+        fabric.clone(),
+        config.num_ports(),
+        to_dest,
+        num_frames,
+        payload_bytes,
+    );
+    (sinks, fabric)
+}
+
+#[test]
+fn routed_adaptive_all_to_all() {
+    let num_frames = 20;
+    let num_ports = default_config().num_ports();
+
+    let to_dest = ToOpposite { num_ports };
+    let (sinks, fabric) = run_routed_test(
+        &default_config(),
+        FabricRoutingAlgorithm::Adaptive,
+        &to_dest,
+        num_frames,
+        256,
+    );
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+
+    // Congestion on the columns pushes some frames onto the rows first
+    assert!(fabric.num_col_first_choices() > 0);
+    assert!(fabric.num_row_first_choices() > 0);
+    fabric.dump_stats();
+}
+
+#[test]
+fn routed_dimension_order_makes_no_adaptive_choices() {
+    let num_frames = 20;
+    let num_ports = default_config().num_ports();
+
+    let to_dest = ToOpposite { num_ports };
+    let (sinks, fabric) = run_routed_test(
+        &default_config(),
+        FabricRoutingAlgorithm::ColumnFirst,
+        &to_dest,
+        num_frames,
+        256,
+    );
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+    assert_eq!(fabric.num_col_first_choices(), 0);
+    assert_eq!(fabric.num_row_first_choices(), 0);
+}
+
+const TURN_MODELS: [FabricRoutingAlgorithm; 3] = [
+    FabricRoutingAlgorithm::WestFirst,
+    FabricRoutingAlgorithm::NorthLast,
+    FabricRoutingAlgorithm::OddEven,
+];
+
+#[test]
+fn routed_turn_models_all_to_all() {
+    let num_frames = 20;
+    let num_ports = default_config().num_ports();
+
+    for algorithm in TURN_MODELS {
+        let to_dest = ToOpposite { num_ports };
+        let (sinks, _) = run_routed_test(&default_config(), algorithm, &to_dest, num_frames, 256);
+
+        for sink in &sinks {
+            assert_eq!(sink.num_sunk(), num_frames, "{algorithm:?}");
+        }
+    }
+}
+
+#[test]
+fn deadlock_free_routing() {
+    let large_mesh = FabricConfig::new(6, 5, 1, None, 5, 1, 1024, 1024, 128);
+    for config in [&*default_config(), &large_mesh] {
+        for algorithm in [
+            FabricRoutingAlgorithm::ColumnFirst,
+            FabricRoutingAlgorithm::RowFirst,
+        ]
+        .into_iter()
+        .chain(TURN_MODELS)
+        {
+            assert!(
+                dependency_cycle(config, algorithm).is_none(),
+                "{algorithm:?}"
+            );
+            check_deadlock_free(config, algorithm).unwrap();
+        }
+    }
+}
+
+#[test]
+fn adaptive_routing_can_deadlock() {
+    let config = default_config();
+    let cycle = dependency_cycle(&config, FabricRoutingAlgorithm::Adaptive).unwrap();
+
+    // The smallest cycle turns through all four directions
+    assert!(cycle.len() >= 4);
+    let err = check_deadlock_free(&config, FabricRoutingAlgorithm::Adaptive).unwrap_err();
+    assert!(
+        format!("{err}").contains("cyclic channel dependency"),
+        "Unexpected error: {err}"
+    );
+}
+
+#[test]
+fn torus_routing_can_deadlock() {
+    let config = torus_config();
+    for algorithm in [FabricRoutingAlgorithm::ColumnFirst]
+        .into_iter()
+        .chain(TURN_MODELS)
+    {
+        assert!(
+            check_deadlock_free(&config, algorithm).is_err(),
+            "{algorithm:?}"
+        );
+    }
+}
+
+fn wormhole_config() -> Rc<FabricConfig> {
+    Rc::new(default_config_builder().with_flit_bytes(64))
+}
+
+#[test]
+fn routed_wormhole_all_to_all() {
+    let num_frames = 20;
+    let num_ports = default_config().num_ports();
+
+    let to_dest = ToOpposite { num_ports };
+    let (sinks, _) = run_routed_test(
+        &wormhole_config(),
+        FabricRoutingAlgorithm::ColumnFirst,
+        &to_dest,
+        num_frames,
+        256,
+    );
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+}
+
+#[test]
+fn routed_wormhole_adaptive_all_to_all() {
+    let num_frames = 20;
+    let num_ports = default_config().num_ports();
+
+    let to_dest = ToOpposite { num_ports };
+    let (sinks, fabric) = run_routed_test(
+        &wormhole_config(),
+        FabricRoutingAlgorithm::Adaptive,
+        &to_dest,
+        num_frames,
+        256,
+    );
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+    assert!(fabric.num_col_first_choices() + fabric.num_row_first_choices() > 0);
+}
+
+#[test]
+fn routed_wormhole_frames_larger_than_buffers() {
+    let num_frames = 5;
+    let num_ports = default_config().num_ports();
+
+    // Only the flits need to fit in the node buffers
+    let to_dest = ToNext { num_ports };
+    let (sinks, _) = run_routed_test(
+        &wormhole_config(),
+        FabricRoutingAlgorithm::ColumnFirst,
+        &to_dest,
+        num_frames,
+        1500,
+    );
+
+    for sink in &sinks {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Cannot store")]
+fn routed_store_and_forward_frames_larger_than_buffers() {
+    let num_ports = default_config().num_ports();
+
+    let to_dest = ToNext { num_ports };
+    run_routed_test(
+        &default_config(),
+        FabricRoutingAlgorithm::ColumnFirst,
+        &to_dest,
+        5,
+        1500,
+    );
+}
+
+/// Send all frames to port 0, apart from those from port 0 itself
+struct ToZero;
+
+impl ToDest for ToZero {
+    fn to_dest(&self, source_index: usize, _frame_index: usize) -> [u8; SRC_MAC_BYTES] {
+        u64_to_mac(u64::from(source_index == 0))
+    }
+}
+
+#[test]
+fn routed_link_telemetry() {
+    let num_frames = 20;
+    let config = default_config();
+    let (_, fabric) = run_routed_test(
+        &config,
+        FabricRoutingAlgorithm::ColumnFirst,
+        &ToZero,
+        num_frames,
+        256,
+    );
+
+    // Each pair of adjacent nodes is connected by a link in each direction
+    let links = fabric.link_stats();
+    let num_col_links = (config.num_columns() - 1) * config.num_rows() * 2;
+    let num_row_links = config.num_columns() * (config.num_rows() - 1) * 2;
+    assert_eq!(links.len(), num_col_links + num_row_links);
+
+    for (_, _, _, stats) in &links {
+        assert!(stats.utilization() <= 1.0);
+        assert!(stats.mean_queue_depth() <= stats.max_queue_depth() as f64);
+    }
+
+    // All traffic is sent to node (0, 0) so links leading away from it are
+    // unused. With column first routing the row link into it carries the
+    // traffic from every other row and so is the most heavily used.
+    for (col, row, direction, stats) in &links {
+        if matches!(direction, Port::ColPlus | Port::RowPlus) {
+            assert_eq!(stats.bytes(), 0, "{col},{row},{direction}");
+        }
+    }
+    let (col, row, direction, busiest) = links
+        .iter()
+        .max_by(|a, b| a.3.utilization().total_cmp(&b.3.utilization()))
+        .unwrap();
+    assert_eq!(
+        (*col, *row, direction.to_string()),
+        (0, 1, "row_minus".to_string())
+    );
+    assert!(busiest.stall_ticks() > 0);
+    fabric.dump_stats();
+}
+
+/// Send bulk frames in class 0 from port 1 and control frames in class 1 from
+/// port 2 of a single node fabric to port 0. Returns the classes of the frames
+/// in the order that they arrive at port 0.
+fn run_traffic_classes(config: FabricConfig, num_frames: usize) -> Vec<usize> {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+
+    let fabric = RoutedFabric::new_and_register(
+        &engine,
+        &clock,
+        &top,
+        "fabric",
+        Rc::new(config),
+        FabricRoutingAlgorithm::ColumnFirst,
+    )
+    .unwrap();
+
+    for i in 0..3 {
+        let frames: Vec<_> = (0..num_frames)
+            .filter(|_| i > 0)
+            .map(|_| {
+                EthernetFrame::new(&top, 256)
+                    .set_dest(u64_to_mac(0))
+                    .set_traffic_class(i - 1)
+            })
+            .collect();
+        let source = Source::new_and_register(&engine, &top, &format!("source_{i}"), None);
+        source.set_generator(Some(Box::new(frames.into_iter())));
+        connect_port!(source, tx => fabric, ingress, i).unwrap();
+    }
+    for i in 1..3 {
+        let sink = Sink::new_and_register(&engine, &clock, &top, &format!("sink_{i}"));
+        connect_port!(fabric, egress, i => sink, rx).unwrap();
+    }
+
+    let mut port = InPort::new(&engine, &clock, &Rc::new(Entity::new(&top, "port")), "rx");
+    fabric.connect_port_egress_i(0, port.state()).unwrap();
+    let classes = Rc::new(RefCell::new(Vec::new()));
+    let received = classes.clone();
+    engine.spawn(async move {
+        for _ in 0..2 * num_frames {
+            let frame: EthernetFrame = port.get()?.await;
+            received.borrow_mut().push(frame.traffic_class());
+        }
+        Ok(())
+    });
+
+    run_simulation!(engine);
+    classes.take()
+}
+
+fn traffic_class_config() -> FabricConfig {
+    FabricConfig::new(1, 1, 3, None, 5, 1, 1024, 1024, 128)
+}
+
+#[test]
+fn routed_traffic_class_priority() {
+    let num_frames = 20;
+
+    // Without traffic classes the egress port alternates between the inputs
+    let classes = run_traffic_classes(traffic_class_config(), num_frames);
+    let last_control = classes.iter().rposition(|&class| class == 1).unwrap();
+    assert!(last_control >= 2 * num_frames - 2);
+
+    // With strict priority the control frames overtake the bulk frames
+    let traffic_classes = TrafficClasses::new().with_class(0, 1).with_class(1, 1);
+    let config = traffic_class_config().with_traffic_classes(traffic_classes);
+    let classes = run_traffic_classes(config, num_frames);
+    let last_control = classes.iter().rposition(|&class| class == 1).unwrap();
+    assert!(last_control < num_frames + 2, "{classes:?}");
+}
+
+#[test]
+fn routed_traffic_class_weights() {
+    let num_frames = 20;
+
+    // Bulk traffic gets three times the bandwidth of control traffic
+    let traffic_classes = TrafficClasses::new().with_class(0, 3).with_class(0, 1);
+    let config = traffic_class_config().with_traffic_classes(traffic_classes);
+    let classes = run_traffic_classes(config, num_frames);
+    let num_control = classes[..16].iter().filter(|&&class| class == 1).count();
+    assert!((3..=5).contains(&num_control), "{classes:?}");
+}
+
+#[test]
+#[should_panic(expected = "weights must be greater than 0")]
+fn routed_invalid_traffic_classes() {
+    let config =
+        traffic_class_config().with_traffic_classes(TrafficClasses::new().with_class(0, 0));
+    run_traffic_classes(config, 1);
+}
+
+/// Send multicast frames from the first port of a fabric to all other ports
+fn run_multicast(
+    engine: &mut Engine,
+    clock: &Clock,
+    fabric: Rc<dyn Fabric<EthernetFrame>>,
+    num_ports: usize,
+    num_frames: usize,
+) -> Vec<Rc<Sink<EthernetFrame>>> {
+    let top = engine.top().clone();
+
+    let mut sinks = Vec::with_capacity(num_ports);
+    for i in 0..num_ports {
+        let source = Source::new_and_register(engine, &top, &format!("source_{i}"), None);
+        if i == 0 {
+            let dests: Vec<u64> = (1..num_ports as u64).collect();
+            let frames: Vec<_> = (0..num_frames)
+                .map(|_| EthernetFrame::new(&top, 256).set_multicast_dests(dests.clone()))
+                .collect();
+            source.set_generator(Some(Box::new(frames.into_iter())));
+        }
+        connect_port!(source, tx => fabric, ingress, i).unwrap();
+
+        let sink = Sink::new_and_register(engine, clock, &top, &format!("sink_{i}"));
+        connect_port!(fabric, egress, i => sink, rx).unwrap();
+        sinks.push(sink);
+    }
+
+    run_simulation!(engine);
+    sinks
+}
+
+fn check_multicast(sinks: &[Rc<Sink<EthernetFrame>>], num_frames: usize) {
+    assert_eq!(sinks[0].num_sunk(), 0);
+    for sink in &sinks[1..] {
+        assert_eq!(sink.num_sunk(), num_frames);
+    }
+}
+
+#[test]
+fn functional_multicast() {
+    let num_frames = 10;
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let config = default_config();
+    let fabric =
+        FunctionalFabric::new_and_register(&engine, &clock, engine.top(), "fabric", config.clone())
+            .unwrap();
+
+    let sinks = run_multicast(&mut engine, &clock, fabric, config.num_ports(), num_frames);
+    check_multicast(&sinks, num_frames);
+}
+
+fn run_routed_multicast(
+    config: &Rc<FabricConfig>,
+    algorithm: FabricRoutingAlgorithm,
+    num_frames: usize,
+) -> Vec<Rc<Sink<EthernetFrame>>> {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let fabric = RoutedFabric::new_and_register(
+        &engine,
+        &clock,
+        engine.top(),
+        "fabric",
+        config.clone(),
+        algorithm,
+    )
+    .unwrap();
+
+    run_multicast(&mut engine, &clock, fabric, config.num_ports(), num_frames)
+}
+
+#[test]
+fn routed_multicast() {
+    let num_frames = 10;
+    let sinks = run_routed_multicast(
+        &default_config(),
+        FabricRoutingAlgorithm::ColumnFirst,
+        num_frames,
+    );
+    check_multicast(&sinks, num_frames);
+}
+
+#[test]
+fn routed_adaptive_multicast() {
+    let num_frames = 10;
+    let sinks = run_routed_multicast(
+        &default_config(),
+        FabricRoutingAlgorithm::Adaptive,
+        num_frames,
+    );
+    check_multicast(&sinks, num_frames);
+}
+
+#[test]
+fn torus_multicast() {
+    let num_frames = 10;
+    let sinks = run_routed_multicast(
+        &torus_config(),
+        FabricRoutingAlgorithm::RowFirst,
+        num_frames,
+    );
+    check_multicast(&sinks, num_frames);
+}
+
+#[test]
+#[should_panic(expected = "cannot split multicast")]
+fn routed_wormhole_multicast_is_an_error() {
+    run_routed_multicast(&wormhole_config(), FabricRoutingAlgorithm::ColumnFirst, 1);
+}
+
+#[test]
+fn crossbar_multicast() {
+    let num_frames = 10;
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let config = default_config();
+    let fabric = CrossbarFabric::new_and_register(
+        &engine,
+        &clock,
+        engine.top(),
+        "fabric",
+        config.clone(),
+        5,
+    )
+    .unwrap();
+
+    let sinks = run_multicast(&mut engine, &clock, fabric, config.num_ports(), num_frames);
+    check_multicast(&sinks, num_frames);
+}
+
+#[test]
+fn fat_tree_multicast() {
+    let num_frames = 10;
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let config = default_config();
+    let fabric = FatTreeFabric::new_and_register(
+        &engine,
+        &clock,
+        engine.top(),
+        "fabric",
+        config.clone(),
+        FatTreeConfig::new(2),
+    )
+    .unwrap();
+
+    let sinks = run_multicast(&mut engine, &clock, fabric, config.num_ports(), num_frames);
+    check_multicast(&sinks, num_frames);
 }
 
 mod routed_fabric_harness {
@@ -315,6 +1239,63 @@ mod routed_fabric_harness {
             ),
         ])]);
     }
+
+    /// Route a single access between opposite corners of a 3x3 fabric and
+    /// return the tick at which it arrives
+    fn routed_corner_to_corner_tick(topology: FabricTopology) -> u64 {
+        let mut engine = start_test(file!());
+        let clock = engine.clock_ghz(1.0);
+        let top = engine.top();
+        let config = Rc::new(
+            FabricConfig::new(3, 3, 1, None, 2, 1, 1024, 1024, 128).with_topology(topology),
+        );
+        let fabric = RoutedFabric::new_and_register(
+            &engine,
+            &clock,
+            top,
+            "fabric",
+            config.clone(),
+            FabricRoutingAlgorithm::ColumnFirst,
+        )
+        .unwrap();
+
+        let ingress_idx = fabric.col_row_port_to_fabric_port_index(0, 0, 0);
+        let egress_idx = fabric.col_row_port_to_fabric_port_index(2, 2, 0);
+        let addr = 0x1000;
+        let access = MemoryAccess::new(
+            top,
+            AccessType::ReadRequest,
+            128,
+            addr,
+            ingress_idx as u64,
+            DeviceId(egress_idx as u64),
+            DeviceId(ingress_idx as u64),
+            16,
+        );
+
+        let mut harness = RoutedFabricHarness::new(
+            engine,
+            fabric,
+            config.max_num_ports(),
+            config.max_num_ports(),
+        );
+        harness.run_steps([
+            send_ingress!(ingress_idx, access),
+            expect_egress!(egress_idx, MemoryTxn::read_req(addr)),
+        ]);
+        clock.tick_now().tick()
+    }
+
+    #[test]
+    fn routed_torus_uses_wrap_around_links() {
+        let mesh_tick = routed_corner_to_corner_tick(FabricTopology::Mesh);
+        let torus_tick = routed_corner_to_corner_tick(FabricTopology::Torus);
+
+        // The torus route takes one hop in each dimension rather than two
+        let cycles_per_hop = 2;
+        assert!(torus_tick < mesh_tick);
+        assert!(mesh_tick - torus_tick >= 2 * cycles_per_hop);
+    }
 }
 
 #[test]
@@ -1,16 +1,19 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
+use std::rc::Rc;
+
 use gwr_components::flow_controls::limiter::Limiter;
 use gwr_components::sink::Sink;
 use gwr_components::source::Source;
-use gwr_components::{connect_port, option_box_repeat, rc_limiter};
+use gwr_components::{connect_port, rc_limiter};
 use gwr_engine::run_simulation;
 use gwr_engine::test_helpers::start_test;
-use gwr_models::fc_pipeline::{FcPipeline, FcPipelineConfig};
+use gwr_engine::traits::SimObject;
+use gwr_models::ethernet_frame::{EthernetFrame, FRAME_OVERHEAD_BYTES};
+use gwr_models::fc_pipeline::{CreditUnit, FcPipeline, FcPipelineConfig};
+use gwr_track::entity::{Entity, GetEntity};
 
 mod fc_pipeline_harness {
-    use std::rc::Rc;
-
     use gwr_components::build_component_harness;
 
     use super::*;
@@ -93,6 +96,19 @@ fn test_fc_pipeline_throughput(
     credit_delay: usize,
     num_puts: usize,
 ) -> usize {
+    let pipe_config = FcPipelineConfig::new(buffer_size, data_delay, credit_delay);
+    run_fc_pipeline(&pipe_config, |_| vec![101; num_puts])
+}
+
+/// Send the values built by `build_values` through a pipeline and return the
+/// tick at which they have all been received
+fn run_fc_pipeline<T>(
+    pipe_config: &FcPipelineConfig,
+    build_values: impl FnOnce(&Rc<Entity>) -> Vec<T>,
+) -> usize
+where
+    T: SimObject,
+{
     let mut engine = start_test(file!());
     let clock = engine.default_clock();
     let top = engine.top();
@@ -104,12 +120,11 @@ fn test_fc_pipeline_throughput(
 
     // Create a pair of tasks that use a pipeline
     let source = Source::new_and_register(&engine, top, "source", None);
-    let word = 101;
-    source.set_generator(option_box_repeat!(word ; num_puts));
+    let values = build_values(source.entity());
+    let num_puts = values.len();
+    source.set_generator(Some(Box::new(values.into_iter())));
 
-    let pipe_config = FcPipelineConfig::new(buffer_size, data_delay, credit_delay);
-    let pipeline =
-        FcPipeline::new_and_register(&engine, &clock, top, "pipe", &pipe_config).unwrap();
+    let pipeline = FcPipeline::new_and_register(&engine, &clock, top, "pipe", pipe_config).unwrap();
     let sink = Sink::new_and_register(&engine, &clock, top, "sink");
 
     connect_port!(source, tx => limiter, rx).unwrap();
@@ -138,3 +153,57 @@ fn throughput() {
     let clock_tick = test_fc_pipeline_throughput(1, 1, 2, num_puts);
     assert_eq!(clock_tick, num_puts * 3);
 }
+
+#[test]
+fn byte_credits_match_entries_for_equal_sizes() {
+    // Each i32 uses 4 bytes so a buffer of 8 bytes holds two entries
+    let num_puts = 10;
+    let data_delay = 1;
+    let pipe_config = FcPipelineConfig::new(8, data_delay, 1).with_credit_unit(CreditUnit::Bytes);
+    let clock_tick = run_fc_pipeline(&pipe_config, |_| vec![101; num_puts]);
+    assert_eq!(
+        clock_tick,
+        test_fc_pipeline_throughput(2, data_delay, 1, num_puts)
+    );
+    assert_eq!(clock_tick, num_puts + data_delay);
+}
+
+fn mixed_size_frames(created_by: &Rc<Entity>, num_frames: usize) -> Vec<EthernetFrame> {
+    (0..num_frames)
+        .map(|i| {
+            let payload_bytes = if i % 2 == 0 { 16 } else { 240 };
+            EthernetFrame::new(created_by, payload_bytes)
+        })
+        .collect()
+}
+
+#[test]
+fn byte_credits_with_mixed_sizes() {
+    let num_frames = 10;
+    let large_frame_bytes = 240 + FRAME_OVERHEAD_BYTES;
+    let credit_delay = 100;
+
+    // A buffer that only holds one large frame has no room for the frames
+    // either side of it, so every frame waits for the credits of the one
+    // before to return. Counting entries would have let them all in.
+    let small_buffer = FcPipelineConfig::new(large_frame_bytes, 1, credit_delay)
+        .with_credit_unit(CreditUnit::Bytes);
+    let large_buffer = FcPipelineConfig::new(num_frames * large_frame_bytes, 1, credit_delay)
+        .with_credit_unit(CreditUnit::Bytes);
+
+    let small_buffer_ticks =
+        run_fc_pipeline(&small_buffer, |top| mixed_size_frames(top, num_frames));
+    let large_buffer_ticks =
+        run_fc_pipeline(&large_buffer, |top| mixed_size_frames(top, num_frames));
+    assert!(small_buffer_ticks > num_frames * credit_delay);
+    assert!(large_buffer_ticks < 2 * credit_delay);
+}
+
+#[test]
+#[should_panic(
+    expected = "top::pipe::credit_limiter: 101 needs 4 credits but only 2 are available"
+)]
+fn byte_credits_frame_larger_than_buffer() {
+    let pipe_config = FcPipelineConfig::new(2, 1, 1).with_credit_unit(CreditUnit::Bytes);
+    run_fc_pipeline(&pipe_config, |_| vec![101]);
+}
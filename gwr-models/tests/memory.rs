@@ -1,21 +1,25 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
+use std::cell::RefCell;
 use std::cmp::max;
+use std::ops::Range;
 use std::rc::Rc;
 
 use gwr_components::sink::Sink;
 use gwr_components::source::Source;
 use gwr_components::{connect_port, option_box_repeat};
 use gwr_engine::engine::Engine;
+use gwr_engine::port::InPort;
 use gwr_engine::run_simulation;
 use gwr_engine::test_helpers::start_test;
-use gwr_engine::traits::SimObject;
-use gwr_models::memory::memory_access::MemoryAccess;
-use gwr_models::memory::memory_map::MemoryMap;
+use gwr_engine::traits::{Routable, SimObject};
+use gwr_models::memory::ecc::{EccScheme, FaultInjectionConfig, ScriptedFault};
+use gwr_models::memory::memory_access::{ByteEnables, MemoryAccess};
+use gwr_models::memory::memory_map::{DeviceId, MemoryMap};
 use gwr_models::memory::traits::AccessMemory;
 use gwr_models::memory::{Memory, MemoryConfig};
 use gwr_models::test_helpers::{
-    create_default_memory_map, create_read, create_write, create_write_np,
+    create_default_memory_map, create_masked_write, create_read, create_write, create_write_np,
 };
 use gwr_track::entity::{Entity, GetEntity};
 
@@ -34,6 +38,13 @@ where
     T: SimObject + AccessMemory,
 {
     let config = MemoryConfig::new(DST_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS);
+    create_memory_with_config(engine, config)
+}
+
+fn create_memory_with_config<T>(engine: &mut Engine, config: MemoryConfig) -> Rc<Memory<T>>
+where
+    T: SimObject + AccessMemory,
+{
     let clock = engine.default_clock();
     let top = engine.top();
 
@@ -44,10 +55,20 @@ where
 fn setup_system(
     num_accesses: usize,
     create_fn: fn(&Rc<Entity>, &Rc<MemoryMap>, usize, u64, u64, usize) -> MemoryAccess,
+) -> (Engine, Rc<Sink<MemoryAccess>>, Rc<Memory<MemoryAccess>>) {
+    let config = MemoryConfig::new(DST_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS);
+    setup_system_with_config(num_accesses, create_fn, config)
+}
+
+#[expect(clippy::type_complexity)]
+fn setup_system_with_config(
+    num_accesses: usize,
+    create_fn: fn(&Rc<Entity>, &Rc<MemoryMap>, usize, u64, u64, usize) -> MemoryAccess,
+    config: MemoryConfig,
 ) -> (Engine, Rc<Sink<MemoryAccess>>, Rc<Memory<MemoryAccess>>) {
     let mut engine = start_test(file!());
     let clock = engine.default_clock();
-    let memory = create_memory(&mut engine);
+    let memory = create_memory_with_config(&mut engine, config);
     let memory_map = Rc::new(create_default_memory_map());
     let top = engine.top();
 
@@ -119,6 +140,281 @@ fn memory_write_np() {
     let last_event_time = max(last_bw_limit_event, last_packet_ack);
     assert_eq!(engine.time_now_ns(), last_event_time as f64);
 }
+
+const MASKED_BYTES: Range<usize> = 4..12;
+
+#[test]
+fn memory_masked_write() {
+    let num_accesses = 100;
+    let (mut engine, sink, memory) = setup_system(
+        num_accesses,
+        |created_by, memory_map, num_bytes, dst, src, overhead| {
+            create_masked_write(
+                created_by,
+                memory_map,
+                ByteEnables::from_range(num_bytes, MASKED_BYTES),
+                dst,
+                src,
+                overhead,
+            )
+        },
+    );
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), 0);
+
+    // Only the enabled bytes are written but the memory is still busy for the
+    // full access
+    assert_eq!(memory.bytes_written(), num_accesses * MASKED_BYTES.len());
+    let last_bw_limit_event = CYCLES_PER_ACCESS * num_accesses as u64;
+    assert_eq!(engine.time_now_ns(), last_bw_limit_event as f64);
+}
+
+const ECC_WORD_SIZE_BYTES: usize = 8;
+
+fn run_reads_with_faults(
+    fault_injection: FaultInjectionConfig,
+    dst_addrs: &[u64],
+) -> Rc<Memory<MemoryAccess>> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = MemoryConfig::new(DST_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS)
+        .with_fault_injection(fault_injection);
+    let memory = create_memory_with_config(&mut engine, config);
+    let memory_map = Rc::new(create_default_memory_map());
+    let top = engine.top();
+
+    let source = Source::new_and_register(&engine, top, "source", None);
+    let reads: Vec<_> = dst_addrs
+        .iter()
+        .map(|dst_addr| {
+            create_read(
+                source.entity(),
+                &memory_map,
+                ACCESS_SIZE_BYTES,
+                *dst_addr,
+                SRC_ADDR,
+                OVERHEAD_SIZE_BYTES,
+            )
+        })
+        .collect();
+    source.set_generator(Some(Box::new(reads.into_iter())));
+    let sink = Sink::new_and_register(&engine, &clock, top, "sink");
+
+    connect_port!(source, tx => memory, rx).unwrap();
+    connect_port!(memory, tx => sink, rx).unwrap();
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), dst_addrs.len());
+    memory
+}
+
+#[test]
+fn ecc_corrects_single_bit_fault() {
+    let fault_injection = FaultInjectionConfig::new(EccScheme::Secded, ECC_WORD_SIZE_BYTES, 0.0, 0)
+        .with_scripted_fault(ScriptedFault::new(0, DST_ADDR + 8, 1));
+
+    // The corrected word is scrubbed so the second read is clean
+    let memory = run_reads_with_faults(fault_injection, &[DST_ADDR, DST_ADDR]);
+    let faults = memory.fault_injector().unwrap();
+    assert_eq!(faults.num_bits_flipped(), 1);
+    assert_eq!(faults.num_corrected(), 1);
+    assert_eq!(faults.num_detected(), 0);
+    assert_eq!(faults.num_undetected(), 0);
+}
+
+#[test]
+fn ecc_detects_double_bit_fault() {
+    let fault_injection = FaultInjectionConfig::new(EccScheme::Secded, ECC_WORD_SIZE_BYTES, 0.0, 0)
+        .with_scripted_fault(ScriptedFault::new(0, DST_ADDR, 2));
+
+    // Uncorrectable errors remain in the memory
+    let memory = run_reads_with_faults(fault_injection, &[DST_ADDR, DST_ADDR]);
+    let faults = memory.fault_injector().unwrap();
+    assert_eq!(faults.num_corrected(), 0);
+    assert_eq!(faults.num_detected(), 2);
+}
+
+#[test]
+fn uncorrectable_errors_poison_read_responses() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let fault_injection = FaultInjectionConfig::new(EccScheme::Secded, ECC_WORD_SIZE_BYTES, 0.0, 0)
+        .with_scripted_fault(ScriptedFault::new(0, DST_ADDR, 2));
+    let config = MemoryConfig::new(DST_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS)
+        .with_fault_injection(fault_injection);
+    let memory: Rc<Memory<MemoryAccess>> = create_memory_with_config(&mut engine, config);
+    let memory_map = Rc::new(create_default_memory_map());
+    let top = engine.top().clone();
+
+    // Only the first read covers the faulty word
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    let reads: Vec<_> = [DST_ADDR, DST_ADDR + ACCESS_SIZE_BYTES as u64]
+        .iter()
+        .map(|dst_addr| {
+            create_read(
+                source.entity(),
+                &memory_map,
+                ACCESS_SIZE_BYTES,
+                *dst_addr,
+                SRC_ADDR,
+                OVERHEAD_SIZE_BYTES,
+            )
+        })
+        .collect();
+    source.set_generator(Some(Box::new(reads.into_iter())));
+    connect_port!(source, tx => memory, rx).unwrap();
+
+    let mut port = InPort::new(&engine, &clock, &Rc::new(Entity::new(&top, "port")), "rx");
+    memory.connect_port_tx(port.state()).unwrap();
+    let poisoned = Rc::new(RefCell::new(Vec::new()));
+    let received = poisoned.clone();
+    engine.spawn(async move {
+        for _ in 0..2 {
+            let response: MemoryAccess = port.get()?.await;
+            received.borrow_mut().push(response.is_poisoned());
+        }
+        Ok(())
+    });
+
+    run_simulation!(engine);
+    assert_eq!(*poisoned.borrow(), [true, false]);
+    assert_eq!(memory.fault_injector().unwrap().num_poisoned(), 1);
+}
+
+#[test]
+fn scripted_fault_waits_for_tick() {
+    let fault_injection = FaultInjectionConfig::new(EccScheme::Secded, ECC_WORD_SIZE_BYTES, 0.0, 0)
+        .with_scripted_fault(ScriptedFault::new(1000, DST_ADDR, 1));
+
+    let memory = run_reads_with_faults(fault_injection, &[DST_ADDR]);
+    let faults = memory.fault_injector().unwrap();
+    assert_eq!(faults.num_bits_flipped(), 0);
+    assert_eq!(faults.num_corrected(), 0);
+}
+
+#[test]
+fn random_faults_are_undetected_without_ecc() {
+    let fault_injection = FaultInjectionConfig::new(EccScheme::None, ECC_WORD_SIZE_BYTES, 0.01, 1);
+
+    let memory = run_reads_with_faults(fault_injection, &[DST_ADDR; 100]);
+    let faults = memory.fault_injector().unwrap();
+    assert!(faults.num_bits_flipped() > 0);
+    assert!(faults.num_undetected() > 0);
+    assert_eq!(faults.num_corrected(), 0);
+    assert_eq!(faults.num_detected(), 0);
+}
+
+const INITIATOR_ADDRS: [u64; 2] = [0x1000_0000, 0x2000_0000];
+
+/// Issue reads stamped at tick 0 alternately from two initiators
+fn run_latency_reads(num_accesses: usize) -> Rc<Memory<MemoryAccess>> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = MemoryConfig::new(DST_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS)
+        .with_latency_histograms();
+    let memory = create_memory_with_config(&mut engine, config);
+    let top = engine.top();
+
+    let mut memory_map = MemoryMap::new();
+    memory_map
+        .insert(DST_ADDR, CAPACITY_BYTES as u64, DeviceId(0))
+        .unwrap();
+    memory_map
+        .insert(INITIATOR_ADDRS[0], 0x1000, DeviceId(1))
+        .unwrap();
+    memory_map
+        .insert(INITIATOR_ADDRS[1], 0x1000, DeviceId(2))
+        .unwrap();
+    let memory_map = Rc::new(memory_map);
+
+    let source = Source::new_and_register(&engine, top, "source", None);
+    let reads: Vec<_> = (0..num_accesses)
+        .map(|i| {
+            create_read(
+                source.entity(),
+                &memory_map,
+                ACCESS_SIZE_BYTES,
+                DST_ADDR,
+                INITIATOR_ADDRS[i % 2],
+                OVERHEAD_SIZE_BYTES,
+            )
+            .with_issue_tick(0)
+        })
+        .collect();
+    source.set_generator(Some(Box::new(reads.into_iter())));
+    let sink = Sink::new_and_register(&engine, &clock, top, "sink");
+
+    connect_port!(source, tx => memory, rx).unwrap();
+    connect_port!(memory, tx => sink, rx).unwrap();
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), num_accesses);
+    memory
+}
+
+#[test]
+fn latency_histograms_per_initiator() {
+    let num_accesses = 10;
+    let memory = run_latency_reads(num_accesses);
+
+    // Each read queues behind the previous ones so latency grows
+    let first = memory.latency_histogram(DeviceId(1)).unwrap();
+    assert_eq!(first.count(), num_accesses / 2);
+    assert_eq!(first.min(), DELAY_TICKS as u64);
+    assert_eq!(
+        first.max(),
+        (num_accesses as u64 - 2) * CYCLES_PER_ACCESS + DELAY_TICKS as u64
+    );
+
+    let second = memory.latency_histogram(DeviceId(2)).unwrap();
+    assert_eq!(second.count(), num_accesses / 2);
+    assert_eq!(second.min(), CYCLES_PER_ACCESS + DELAY_TICKS as u64);
+    assert_eq!(
+        second.max(),
+        (num_accesses as u64 - 1) * CYCLES_PER_ACCESS + DELAY_TICKS as u64
+    );
+
+    assert!(memory.latency_histogram(DeviceId(0)).is_none());
+}
+
+#[test]
+fn unstamped_accesses_are_stamped_on_arrival() {
+    let num_accesses = 10;
+    let (mut engine, _sink, memory) = setup_system_with_config(
+        num_accesses,
+        create_read,
+        MemoryConfig::new(DST_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS)
+            .with_latency_histograms(),
+    );
+
+    run_simulation!(engine);
+    let histogram = memory.latency_histogram(DeviceId(0)).unwrap();
+    assert_eq!(histogram.count(), num_accesses);
+    assert_eq!(histogram.min(), DELAY_TICKS as u64);
+    assert_eq!(histogram.max(), DELAY_TICKS as u64);
+}
+
+#[test]
+fn responses_keep_traffic_class() {
+    let mut engine = start_test(file!());
+    let memory = create_memory::<MemoryAccess>(&mut engine);
+    let memory_map = Rc::new(create_default_memory_map());
+
+    let request = create_read(
+        engine.top(),
+        &memory_map,
+        ACCESS_SIZE_BYTES,
+        DST_ADDR,
+        SRC_ADDR,
+        OVERHEAD_SIZE_BYTES,
+    )
+    .with_traffic_class(2);
+    let response = request.to_response(&*memory).unwrap();
+    assert_eq!(request.traffic_class(), 2);
+    assert_eq!(response.traffic_class(), 2);
+}
+
 mod memory_harness {
     use gwr_models::build_model_harness;
     use gwr_models::test_helpers::MemoryTxn;
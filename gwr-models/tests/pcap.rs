@@ -0,0 +1,189 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+
+use gwr_components::connect_port;
+use gwr_components::delay::Delay;
+use gwr_components::sink::Sink;
+use gwr_components::source::Source;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::ethernet_frame::{EthernetFrame, u64_to_mac};
+use gwr_models::pcap::{
+    PCAP_MAGIC_US, PcapFrames, PcapPacket, PcapTap, frame_to_bytes, packet_to_frame, parse_pcap,
+    read_pcap_file,
+};
+use gwr_track::entity::Entity;
+
+/// A writer that allows the test to read back what a tap has written
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn test_frames(created_by: &Rc<Entity>) -> Vec<EthernetFrame> {
+    (0..6)
+        .map(|i| {
+            let frame = EthernetFrame::new(created_by, 46 + i * 100)
+                .set_dest(u64_to_mac(0x10 + i as u64))
+                .set_src(u64_to_mac(0x20 + i as u64));
+            if i % 2 == 1 {
+                frame.set_vlan(100 + i as u16, i as u8)
+            } else {
+                frame
+            }
+        })
+        .collect()
+}
+
+fn assert_same_frames(a: &[EthernetFrame], b: &[EthernetFrame]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        assert_eq!(a.get_dst(), b.get_dst());
+        assert_eq!(a.get_src(), b.get_src());
+        assert_eq!(a.vlan_id(), b.vlan_id());
+        assert_eq!(a.pcp(), b.pcp());
+        assert_eq!(a.payload_size_bytes(), b.payload_size_bytes());
+    }
+}
+
+#[test]
+fn frame_bytes() {
+    let engine = start_test(file!());
+    let frame = EthernetFrame::new(engine.top(), 46)
+        .set_dest([1, 2, 3, 4, 5, 6])
+        .set_src([7, 8, 9, 10, 11, 12])
+        .set_vlan(0x123, 5);
+    let data = frame_to_bytes(&frame);
+    assert_eq!(data.len(), 12 + 4 + 46);
+    assert_eq!(data[0..12], [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+    assert_eq!(data[12..16], [0x81, 0x00, 0xa1, 0x23]);
+    assert_eq!(data[16..18], [0x88, 0xb5]);
+}
+
+#[test]
+fn tap_round_trip() {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+
+    let frames = test_frames(&top);
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    source.set_generator(Some(Box::new(frames.clone().into_iter())));
+    let delay = Delay::new_and_register(&engine, &clock, &top, "delay", 10);
+    let buffer = SharedBuffer::default();
+    let tap =
+        PcapTap::new_and_register(&engine, &clock, &top, "tap", Box::new(buffer.clone())).unwrap();
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+
+    connect_port!(source, tx => delay, rx).unwrap();
+    connect_port!(delay, tx => tap, rx).unwrap();
+    connect_port!(tap, tx => sink, rx).unwrap();
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), frames.len());
+    assert_eq!(tap.num_captured(), frames.len());
+
+    let packets = parse_pcap(&buffer.0.borrow()).unwrap();
+    assert_eq!(packets.len(), frames.len());
+    assert_eq!(packets[0].timestamp_ns, 10);
+    assert!(
+        packets
+            .windows(2)
+            .all(|pair| pair[0].timestamp_ns <= pair[1].timestamp_ns)
+    );
+
+    let captured: Vec<_> = PcapFrames::new(&top, &packets).unwrap().collect();
+    assert_same_frames(&frames, &captured);
+}
+
+#[test]
+fn replay_from_file() {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+    let frames = test_frames(&top);
+
+    // Write a capture file through a tap
+    let path = std::env::temp_dir().join(format!("gwr_pcap_{}.pcap", std::process::id()));
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    source.set_generator(Some(Box::new(frames.clone().into_iter())));
+    let tap = PcapTap::new_and_register_to_file(&engine, &clock, &top, "tap", &path).unwrap();
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+    connect_port!(source, tx => tap, rx).unwrap();
+    connect_port!(tap, tx => sink, rx).unwrap();
+    run_simulation!(engine);
+    tap.flush().unwrap();
+
+    // Replay the capture through a source
+    let packets = read_pcap_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    source.set_generator(Some(Box::new(PcapFrames::new(&top, &packets).unwrap())));
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+    connect_port!(source, tx => sink, rx).unwrap();
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), frames.len());
+}
+
+#[test]
+fn big_endian_microsecond_capture() {
+    let engine = start_test(file!());
+
+    let mut contents = Vec::new();
+    contents.extend_from_slice(&PCAP_MAGIC_US.to_be_bytes());
+    contents.extend_from_slice(&2_u16.to_be_bytes());
+    contents.extend_from_slice(&4_u16.to_be_bytes());
+    contents.extend_from_slice(&[0; 8]);
+    contents.extend_from_slice(&64_u32.to_be_bytes());
+    contents.extend_from_slice(&1_u32.to_be_bytes());
+
+    // A 100 byte packet of which only the first 20 bytes were captured
+    let data: Vec<u8> = (0..20).collect();
+    contents.extend_from_slice(&3_u32.to_be_bytes());
+    contents.extend_from_slice(&250_u32.to_be_bytes());
+    contents.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    contents.extend_from_slice(&100_u32.to_be_bytes());
+    contents.extend_from_slice(&data);
+
+    let packets = parse_pcap(&contents).unwrap();
+    assert_eq!(
+        packets,
+        vec![PcapPacket::new(3_000_250_000, 100, data.clone())]
+    );
+    let frame = packet_to_frame(engine.top(), &packets[0]).unwrap();
+    assert_eq!(u64_to_mac(frame.get_dst()), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(u64_to_mac(frame.get_src()), [6, 7, 8, 9, 10, 11]);
+    assert_eq!(frame.vlan_id(), None);
+    assert_eq!(frame.payload_size_bytes(), 88);
+}
+
+#[test]
+fn unsupported_link_type() {
+    let mut contents = Vec::new();
+    contents.extend_from_slice(&PCAP_MAGIC_US.to_le_bytes());
+    contents.extend_from_slice(&[0; 16]);
+    contents.extend_from_slice(&105_u32.to_le_bytes());
+    match parse_pcap(&contents) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "Unsupported pcap link type 105, only Ethernet (1) is supported"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::port::{InPort, OutPort};
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::traits::Routable;
+use gwr_engine::types::AccessType;
+use gwr_models::memory::memory_access::{ByteEnables, MemoryAccess};
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_models::registers::frontend::{RegisterFrontend, RegisterFrontendConfig};
+use gwr_models::registers::register::Register;
+use gwr_track::entity::Entity;
+
+/// The registers generated from `dma.rdl`
+mod dma {
+    include!("registers/dma_regs.rs");
+}
+
+const BASE_ADDR: u64 = 0x4000;
+const SIZE_BYTES: usize = 0x100;
+const CTRL: u64 = BASE_ADDR + dma::dma_indices::CTRL;
+const SRC_ADDR: u64 = BASE_ADDR + dma::dma_indices::SRCADDR;
+
+type Frontend = RegisterFrontend<dma::DmaRegs>;
+
+fn read(engine: &Engine, addr: u64, num_bytes: usize) -> MemoryAccess {
+    MemoryAccess::new(
+        engine.top(),
+        AccessType::ReadRequest,
+        num_bytes,
+        addr,
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        0,
+    )
+}
+
+fn write(engine: &Engine, access_type: AccessType, addr: u64, data: &[u8]) -> MemoryAccess {
+    MemoryAccess::new(
+        engine.top(),
+        access_type,
+        data.len(),
+        addr,
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        0,
+    )
+    .with_data(data.to_vec())
+}
+
+/// Build a frontend to the DMA registers that is sent `accesses` and return
+/// the responses it sends back
+fn setup_system(
+    engine: &mut Engine,
+    accesses: Vec<MemoryAccess>,
+) -> (
+    Rc<Frontend>,
+    Rc<dma::DmaRegs>,
+    Rc<RefCell<Vec<MemoryAccess>>>,
+) {
+    let clock = engine.default_clock();
+    let top = engine.top().clone();
+    let states = dma::DmaStates::new();
+    let regs = Rc::new(dma::DmaRegs::new(&states, 0));
+    let config = RegisterFrontendConfig::new(BASE_ADDR, SIZE_BYTES).with_access_ticks(2);
+    let frontend =
+        Frontend::new_and_register(engine, &clock, &top, "frontend", config, &regs).unwrap();
+
+    let mut tx = OutPort::new(&top, "tx");
+    tx.connect(frontend.port_rx()).unwrap();
+    engine.spawn(async move {
+        for access in accesses {
+            tx.put(access)?.await;
+        }
+        Ok(())
+    });
+
+    let mut rx = InPort::new(
+        engine,
+        &clock,
+        &Rc::new(Entity::new(&top, "responses")),
+        "rx",
+    );
+    frontend.connect_port_tx(rx.state()).unwrap();
+    let responses = Rc::new(RefCell::new(Vec::new()));
+    {
+        let responses = responses.clone();
+        engine.spawn(async move {
+            loop {
+                let response = rx.get()?.await;
+                responses.borrow_mut().push(response);
+            }
+        });
+    }
+    (frontend, regs, responses)
+}
+
+#[test]
+fn read_reset_values() {
+    let mut engine = start_test(file!());
+    let accesses = vec![read(&engine, CTRL, 4), read(&engine, CTRL, 1)];
+    let (frontend, _, responses) = setup_system(&mut engine, accesses);
+
+    run_simulation!(engine);
+    let responses = responses.borrow();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].access_type(), AccessType::ReadResponse);
+    assert_eq!(responses[0].data(), Some(&[0x81, 0, 0, 0][..]));
+    assert_eq!(responses[1].data(), Some(&[0x81][..]));
+    assert_eq!(frontend.num_reads(), 2);
+}
+
+#[test]
+fn write_then_read() {
+    let mut engine = start_test(file!());
+    let accesses = vec![
+        write(
+            &engine,
+            AccessType::WriteRequest,
+            SRC_ADDR,
+            &[0xbc, 0x9a, 0x78, 0x56, 0x34, 0x12, 0, 0],
+        ),
+        write(
+            &engine,
+            AccessType::WriteNonPostedRequest,
+            CTRL,
+            &[0xf0, 0, 0, 0],
+        ),
+        read(&engine, SRC_ADDR, 8),
+    ];
+    let (frontend, regs, responses) = setup_system(&mut engine, accesses);
+
+    run_simulation!(engine);
+
+    // Posted writes have no response
+    let responses = responses.borrow();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        responses[0].access_type(),
+        AccessType::WriteNonPostedResponse
+    );
+    assert_eq!(
+        responses[1].data(),
+        Some(&[0xbc, 0x9a, 0x78, 0x56, 0x34, 0x12, 0, 0][..])
+    );
+    assert_eq!(regs.src_addr.value(), 0x1234_5678_9abc);
+    assert_eq!(regs.ctrl.value(), 0xf0);
+    assert_eq!(frontend.num_writes(), 2);
+
+    // Each access takes two ticks
+    assert_eq!(engine.time_now_ns(), 6.0);
+}
+
+#[test]
+fn masked_write() {
+    let mut engine = start_test(file!());
+    let full = write(
+        &engine,
+        AccessType::WriteRequest,
+        SRC_ADDR,
+        &[0xbc, 0x9a, 0x78, 0x56, 0x34, 0x12, 0, 0],
+    );
+    let mut byte_enables = vec![false; 8];
+    byte_enables[1] = true;
+    let masked = MemoryAccess::new_masked_write(
+        engine.top(),
+        AccessType::WriteRequest,
+        ByteEnables::new(byte_enables),
+        SRC_ADDR,
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        0,
+    )
+    .unwrap()
+    .with_data(vec![0xff; 8]);
+    let (_, regs, _) = setup_system(&mut engine, vec![full, masked]);
+
+    run_simulation!(engine);
+
+    // Only the enabled byte is written
+    assert_eq!(regs.src_addr.value(), 0x1234_5678_ffbc);
+}
+
+#[test]
+#[should_panic(expected = "top::frontend: ReadRequest of reserved register offset 0xc")]
+fn reserved_offset() {
+    let mut engine = start_test(file!());
+    let accesses = vec![read(&engine, BASE_ADDR + 0xc, 4)];
+    let (_, _, _) = setup_system(&mut engine, accesses);
+    run_simulation!(engine);
+}
+
+#[test]
+#[should_panic(expected = "top::frontend: access to 0x4100 is outside the registers 0x4000-0x40ff")]
+fn outside_registers() {
+    let mut engine = start_test(file!());
+    let accesses = vec![read(&engine, BASE_ADDR + SIZE_BYTES as u64, 4)];
+    let (_, _, _) = setup_system(&mut engine, accesses);
+    run_simulation!(engine);
+}
+
+#[test]
+#[should_panic(expected = "top::frontend: register write without data")]
+fn write_without_data() {
+    let mut engine = start_test(file!());
+    let accesses = vec![MemoryAccess::new(
+        engine.top(),
+        AccessType::WriteRequest,
+        4,
+        CTRL,
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        0,
+    )];
+    let (_, _, _) = setup_system(&mut engine, accesses);
+    run_simulation!(engine);
+}
+
+#[test]
+#[should_panic(expected = "top::frontend: unsupported register access of 16 bytes at 0x4008")]
+fn access_too_large() {
+    let mut engine = start_test(file!());
+    let accesses = vec![read(&engine, SRC_ADDR, 16)];
+    let (_, _, _) = setup_system(&mut engine, accesses);
+    run_simulation!(engine);
+}
@@ -0,0 +1,100 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+// Generated from the SystemRDL addrmap `dma`. Do not edit.
+
+use gwr_engine::traits::Resolve;
+use gwr_models::registers::register::Register;
+use gwr_models::registers::state::{RegisterState, UpdatePriority};
+use gwr_models::{
+    build_register_file, build_register_state, build_register_states, build_register_view,
+};
+
+build_register_state!(
+    /// Channel control
+    Ctrl, 32 ;
+    /// Enable the channel
+    enable: 1, 0x1,
+    /// Reserved
+    reserved_1: 3, 0x0,
+    /// Burst length in beats
+    burst_len: 4, 0x8,
+    /// Reserved
+    reserved_8: 23, 0x0,
+    /// Start a transfer
+    start: 1, 0x0,
+);
+
+build_register_view!(
+    /// Channel control
+    Ctrl, CtrlState, CtrlStatePerms, High ;
+    enable: ReadWrite,
+    reserved_1: Reserved,
+    burst_len: ReadWrite,
+    reserved_8: Reserved,
+    start: WriteOneCommits,
+);
+
+build_register_state!(
+    /// Channel status
+    Status, 32 ;
+    /// Set by the hardware
+    busy: 1, 0x0,
+    /// Set by the hardware
+    done: 1, 0x0,
+    /// Reserved
+    reserved_2: 6, 0x0,
+    version: 8, 0x12,
+);
+
+build_register_view!(
+    /// Channel status
+    Status, StatusState, StatusStatePerms, High ;
+    busy: ReadVolatileOnly,
+    done: ReadVolatileOnly,
+    reserved_2: Reserved,
+    version: ReadOnly,
+);
+
+build_register_state!(
+    /// Source address
+    SrcAddr, 64 ;
+    /// Address in bytes
+    addr: 48, 0x0,
+);
+
+build_register_view!(
+    /// Source address
+    SrcAddr, SrcAddrState, SrcAddrStatePerms, High ;
+    addr: ReadVolatileWrite,
+);
+
+build_register_state!(
+    /// Source address
+    DstAddr, 64 ;
+    /// Address in bytes
+    addr: 48, 0x0,
+);
+
+build_register_view!(
+    /// Source address
+    DstAddr, DstAddrState, DstAddrStatePerms, High ;
+    addr: ReadVolatileWrite,
+);
+
+build_register_states!(
+    /// DMA engine registers
+    DmaStates ;
+    Ctrl, 1,
+    Status, 1,
+    SrcAddr, 1,
+    DstAddr, 1,
+);
+
+build_register_file!(
+    /// DMA engine registers
+    Dma, DmaStates ;
+    ctrl: 0x0, Ctrl, Ctrl,
+    status: 0x4, Status, Status,
+    src_addr: 0x8, SrcAddr, SrcAddr,
+    dst_addr: 0x10, DstAddr, DstAddr,
+);
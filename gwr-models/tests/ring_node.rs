@@ -1,13 +1,16 @@
 // Copyright (c) 2025 Graphcore Ltd. All rights reserved.
 
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use gwr_components::arbiter::policy::WeightedRoundRobin;
+use gwr_components::flow_controls::limiter::Limiter;
 use gwr_components::router::Route;
 use gwr_components::sink::Sink;
+use gwr_components::source::Source;
 use gwr_components::{connect_port, rc_limiter};
 use gwr_engine::engine::Engine;
-use gwr_engine::port::OutPort;
+use gwr_engine::port::{InPort, OutPort};
 use gwr_engine::run_simulation;
 use gwr_engine::test_helpers::start_test;
 use gwr_engine::time::clock::Clock;
@@ -15,6 +18,7 @@ use gwr_engine::traits::Routable;
 use gwr_engine::types::SimError;
 use gwr_models::ethernet_frame::{EthernetFrame, SRC_MAC_BYTES, mac_to_u64};
 use gwr_models::ring_node::{IO_INDEX, RING_INDEX, RingConfig, RingNode};
+use gwr_track::entity::Entity;
 
 struct TestAlgorithm(u64);
 
@@ -174,3 +178,86 @@ fn all_to_ring() {
     let num_sunk = io_sink.num_sunk();
     assert_eq!(num_sunk, 0);
 }
+
+fn run_priority_test(num_priority_queues: Option<usize>, num_frames: usize) -> Vec<u8> {
+    let mut engine = start_test(file!());
+    let clock = engine.clock_ghz(1.0);
+    let top = engine.top().clone();
+
+    // Ring traffic is background and I/O traffic is high priority, all
+    // continuing around the ring
+    let dest = [0, 1, 2, 3, 4, 5];
+    let make_frames = |pcp| {
+        (0..num_frames)
+            .map(|_| {
+                EthernetFrame::new(&top, 256)
+                    .set_dest(dest)
+                    .set_vlan(1, pcp)
+            })
+            .collect::<Vec<_>>()
+    };
+    let ring_frames = make_frames(1);
+    let io_frames = make_frames(7);
+
+    let limiter_128b_per_tick = rc_limiter!(&clock, 128);
+    let mut config = RingConfig::new(8192, 8192, limiter_128b_per_tick);
+    if let Some(num_queues) = num_priority_queues {
+        config = config.with_priority_queues(num_queues);
+    }
+    let ring_node = RingNode::new_and_register(
+        &engine,
+        &clock,
+        &top,
+        "dut",
+        &config,
+        Box::new(TestAlgorithm(!mac_to_u64(&dest))),
+        Box::new(WeightedRoundRobin::new(vec![1, 1], 2).unwrap()),
+    )
+    .unwrap();
+
+    for (name, frames, port) in [
+        ("ring_src", ring_frames, ring_node.port_ring_rx()),
+        ("io_src", io_frames, ring_node.port_io_rx()),
+    ] {
+        let source = Source::new_and_register(&engine, &top, name, None);
+        source.set_generator(Some(Box::new(frames.into_iter())));
+        source.connect_port_tx(port).unwrap();
+    }
+    let io_sink = Sink::new_and_register(&engine, &clock, &top, "io_sink");
+    connect_port!(ring_node, io_tx => io_sink, rx).unwrap();
+
+    // Slow the ring output down so that frames queue in the Tx buffer
+    let ring_limiter =
+        Limiter::new_and_register(&engine, &clock, &top, "ring_limit", rc_limiter!(&clock, 16));
+    connect_port!(ring_node, ring_tx => ring_limiter, rx).unwrap();
+    let mut port = InPort::new(&engine, &clock, &Rc::new(Entity::new(&top, "port")), "rx");
+    ring_limiter.connect_port_tx(port.state()).unwrap();
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let pcps = received.clone();
+    engine.spawn(async move {
+        for _ in 0..2 * num_frames {
+            let frame: EthernetFrame = port.get()?.await;
+            pcps.borrow_mut().push(frame.pcp().unwrap());
+        }
+        Ok(())
+    });
+
+    run_simulation!(engine);
+    received.take()
+}
+
+#[test]
+fn priority_queues() {
+    let num_frames = 20;
+
+    // With a single Tx buffer the arbiter alternates between ring and I/O
+    let pcps = run_priority_test(None, num_frames);
+    let last_high = pcps.iter().rposition(|&pcp| pcp == 7).unwrap();
+    assert!(last_high >= 2 * num_frames - 4, "{pcps:?}");
+
+    // With per-priority queues the high priority frames overtake the
+    // background frames waiting in the Tx buffer
+    let pcps = run_priority_test(Some(2), num_frames);
+    let last_high = pcps.iter().rposition(|&pcp| pcp == 7).unwrap();
+    assert!(last_high < num_frames + 4, "{pcps:?}");
+}
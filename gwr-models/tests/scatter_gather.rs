@@ -0,0 +1,207 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_components::sink::Sink;
+use gwr_components::source::Source;
+use gwr_components::{connect_port, option_box_repeat};
+use gwr_engine::engine::Engine;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::traits::TotalBytes;
+use gwr_engine::types::AccessType;
+use gwr_models::ethernet_frame::{EthernetFrame, FRAME_OVERHEAD_BYTES};
+use gwr_models::memory::memory_access::MemoryAccess;
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_models::memory::traits::AccessMemory;
+use gwr_models::memory::{Memory, MemoryConfig};
+use gwr_models::scatter_gather::{ScatterGather, Segment};
+use gwr_track::entity::GetEntity;
+
+const BASE_ADDR: u64 = 0x80000;
+const CAPACITY_BYTES: usize = 0x10000;
+const BW_BYTES_PER_CYCLE: usize = 32;
+const DELAY_TICKS: usize = 8;
+const SEGMENT_BYTES: usize = 32;
+const STRIDE: u64 = 0x100;
+const NUM_SEGMENTS: usize = 4;
+
+fn strided_segments(addr: u64) -> ScatterGather {
+    ScatterGather::strided(addr, SEGMENT_BYTES, STRIDE, NUM_SEGMENTS).unwrap()
+}
+
+fn setup_system(
+    access_type: AccessType,
+    scatter_gather: ScatterGather,
+    num_accesses: usize,
+) -> (Engine, Rc<Sink<MemoryAccess>>, Rc<Memory<MemoryAccess>>) {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let config = MemoryConfig::new(BASE_ADDR, CAPACITY_BYTES, BW_BYTES_PER_CYCLE, DELAY_TICKS);
+    let top = engine.top().clone();
+    let memory = Memory::new_and_register(&engine, &clock, &top, "memory", config).unwrap();
+
+    let source = Source::new_and_register(&engine, &top, "source", None);
+    let to_put = MemoryAccess::new_scatter_gather(
+        source.entity(),
+        access_type,
+        scatter_gather,
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        0,
+    )
+    .unwrap();
+    source.set_generator(option_box_repeat!(to_put ; num_accesses));
+    let sink = Sink::new_and_register(&engine, &clock, &top, "sink");
+
+    connect_port!(source, tx => memory, rx).unwrap();
+    connect_port!(memory, tx => sink, rx).unwrap();
+    (engine, sink, memory)
+}
+
+#[test]
+fn segments() {
+    let scatter_gather = strided_segments(BASE_ADDR);
+    assert_eq!(scatter_gather.num_segments(), NUM_SEGMENTS);
+    assert_eq!(scatter_gather.num_bytes(), NUM_SEGMENTS * SEGMENT_BYTES);
+    assert_eq!(scatter_gather.first_addr(), BASE_ADDR);
+    assert_eq!(
+        scatter_gather.segments()[3],
+        Segment::new(BASE_ADDR + 3 * STRIDE, SEGMENT_BYTES)
+    );
+    assert_eq!(
+        scatter_gather.to_string(),
+        "[32@80000, 32@80100, 32@80200, 32@80300]"
+    );
+}
+
+#[test]
+fn invalid_segments() {
+    match ScatterGather::new(vec![]) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "Unsupported scatter-gather list with no segments"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+    match ScatterGather::new(vec![Segment::new(0x0, 8), Segment::new(0x100, 0)]) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "Unsupported empty scatter-gather segment at 0x100"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
+
+#[test]
+fn scatter_gather_read() {
+    let num_accesses = 10;
+    let num_bytes = NUM_SEGMENTS * SEGMENT_BYTES;
+    let (mut engine, sink, memory) = setup_system(
+        AccessType::ReadRequest,
+        strided_segments(BASE_ADDR),
+        num_accesses,
+    );
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), num_accesses);
+    assert_eq!(memory.bytes_read(), num_accesses * num_bytes);
+
+    // The memory is occupied for the total size of the segments, as it would
+    // be for a contiguous access of the same size
+    let cycles_per_access = num_bytes.div_ceil(BW_BYTES_PER_CYCLE);
+    let last_response = cycles_per_access * (num_accesses - 1) + DELAY_TICKS;
+    assert_eq!(
+        engine.time_now_ns(),
+        last_response.max(cycles_per_access * num_accesses) as f64
+    );
+}
+
+#[test]
+fn scatter_gather_write() {
+    let num_accesses = 10;
+    let (mut engine, sink, memory) = setup_system(
+        AccessType::WriteRequest,
+        strided_segments(BASE_ADDR),
+        num_accesses,
+    );
+
+    run_simulation!(engine);
+    assert_eq!(sink.num_sunk(), 0);
+    assert_eq!(
+        memory.bytes_written(),
+        num_accesses * NUM_SEGMENTS * SEGMENT_BYTES
+    );
+}
+
+#[test]
+#[should_panic(expected = "Out of bounds memory access received [0x8fff0,0x9000f]")]
+fn segment_out_of_bounds() {
+    // Only the last segment lies outside the memory
+    let scatter_gather = strided_segments(BASE_ADDR + CAPACITY_BYTES as u64 - 3 * STRIDE - 16);
+    let (mut engine, _, _) = setup_system(AccessType::ReadRequest, scatter_gather, 1);
+    run_simulation!(engine);
+}
+
+#[test]
+fn scatter_gather_access() {
+    let engine = start_test(file!());
+    let scatter_gather = strided_segments(BASE_ADDR);
+    let write = MemoryAccess::new_scatter_gather(
+        engine.top(),
+        AccessType::WriteRequest,
+        scatter_gather.clone(),
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        16,
+    )
+    .unwrap();
+    assert_eq!(write.dst_addr(), BASE_ADDR);
+    assert_eq!(write.access_size_bytes(), NUM_SEGMENTS * SEGMENT_BYTES);
+    assert_eq!(write.total_bytes(), NUM_SEGMENTS * SEGMENT_BYTES + 16);
+    assert_eq!(write.scatter_gather(), Some(&scatter_gather));
+
+    // Scatter-gather accesses are never combined with other writes
+    let contiguous = MemoryAccess::new(
+        engine.top(),
+        AccessType::WriteRequest,
+        SEGMENT_BYTES,
+        BASE_ADDR + SEGMENT_BYTES as u64,
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        16,
+    );
+    assert!(write.combine(&contiguous).is_none());
+
+    match MemoryAccess::new_scatter_gather(
+        engine.top(),
+        AccessType::ReadResponse,
+        scatter_gather,
+        0,
+        DeviceId(0),
+        DeviceId(1),
+        16,
+    ) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "ReadResponse: unsupported by new_scatter_gather()"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
+
+#[test]
+fn scatter_gather_frame() {
+    let engine = start_test(file!());
+    let scatter_gather = strided_segments(BASE_ADDR);
+    let frame = EthernetFrame::new(engine.top(), 0).set_scatter_gather(scatter_gather.clone());
+    assert_eq!(frame.payload_size_bytes(), NUM_SEGMENTS * SEGMENT_BYTES);
+    assert_eq!(
+        frame.total_bytes(),
+        NUM_SEGMENTS * SEGMENT_BYTES + FRAME_OVERHEAD_BYTES
+    );
+    assert_eq!(frame.scatter_gather(), Some(&scatter_gather));
+}
@@ -0,0 +1,208 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::build_model_harness;
+use gwr_models::memory::memory_access::{ByteEnables, MemoryAccess};
+use gwr_models::memory::store_buffer::{DrainPolicy, StoreBuffer, StoreBufferConfig};
+use gwr_models::test_helpers::{
+    MemoryTxn, create_default_memory_map, create_masked_write, create_read, create_write,
+};
+
+const BASE_ADDRESS: u64 = 0x80000;
+const SRC_ADDR: u64 = BASE_ADDRESS + 0x1000;
+
+const NUM_ENTRIES: usize = 4;
+const LINE_SIZE_BYTES: usize = 64;
+const BW_BYTES_PER_CYCLE: usize = 64;
+const WRITE_SIZE_BYTES: usize = 8;
+const OVERHEAD_SIZE_BYTES: usize = 16;
+
+build_model_harness! {
+    harness StoreBufferHarness<T> {
+        component: store_buffer: Rc<StoreBuffer<T>>,
+        rx ports: {
+            Rx<T> => rx,
+        },
+        tx ports: {
+            Tx<T> => tx,
+        },
+    }
+}
+
+fn create_store_buffer(engine: &mut Engine, policy: DrainPolicy) -> Rc<StoreBuffer<MemoryAccess>> {
+    let clock = engine.default_clock();
+    let config = StoreBufferConfig::new(NUM_ENTRIES, LINE_SIZE_BYTES, BW_BYTES_PER_CYCLE, policy);
+    StoreBuffer::new_and_register(engine, &clock, engine.top(), "store_buffer", config).unwrap()
+}
+
+fn write(engine: &Engine, dst_addr: u64) -> MemoryAccess {
+    let memory_map = Rc::new(create_default_memory_map());
+    create_write(
+        engine.top(),
+        &memory_map,
+        WRITE_SIZE_BYTES,
+        dst_addr,
+        SRC_ADDR,
+        OVERHEAD_SIZE_BYTES,
+    )
+}
+
+#[test]
+fn adjacent_writes_are_combined() {
+    let mut engine = start_test(file!());
+    let store_buffer = create_store_buffer(&mut engine, DrainPolicy::Timeout(100));
+    let writes: Vec<_> = (0..LINE_SIZE_BYTES / WRITE_SIZE_BYTES)
+        .map(|i| write(&engine, BASE_ADDRESS + (i * WRITE_SIZE_BYTES) as u64))
+        .collect();
+
+    let mut harness = StoreBufferHarness::new(engine, store_buffer.clone());
+    let mut steps: Vec<_> = writes.into_iter().map(|w| send_rx!(w)).collect();
+    steps.push(expect_tx!(
+        MemoryTxn::write_req(BASE_ADDRESS).with_bytes(LINE_SIZE_BYTES)
+    ));
+    harness.run_steps(steps);
+
+    assert_eq!(
+        store_buffer.num_writes(),
+        LINE_SIZE_BYTES / WRITE_SIZE_BYTES
+    );
+    assert_eq!(
+        store_buffer.num_combined(),
+        LINE_SIZE_BYTES / WRITE_SIZE_BYTES - 1
+    );
+    assert_eq!(store_buffer.num_drained(), 1);
+    assert_eq!(store_buffer.max_occupancy(), 1);
+}
+
+#[test]
+fn writes_are_not_combined_across_lines() {
+    let mut engine = start_test(file!());
+    let store_buffer = create_store_buffer(&mut engine, DrainPolicy::Timeout(100));
+    let last_in_line = BASE_ADDRESS + (LINE_SIZE_BYTES - WRITE_SIZE_BYTES) as u64;
+    let first_in_next_line = BASE_ADDRESS + LINE_SIZE_BYTES as u64;
+    let w0 = write(&engine, last_in_line);
+    let w1 = write(&engine, first_in_next_line);
+
+    let mut harness = StoreBufferHarness::new(engine, store_buffer.clone());
+    harness.run_steps([
+        send_rx!(w0),
+        send_rx!(w1),
+        expect_tx!(MemoryTxn::write_req(last_in_line).with_bytes(WRITE_SIZE_BYTES)),
+        expect_tx!(MemoryTxn::write_req(first_in_next_line).with_bytes(WRITE_SIZE_BYTES)),
+    ]);
+
+    assert_eq!(store_buffer.num_combined(), 0);
+    assert_eq!(store_buffer.num_drained(), 2);
+}
+
+#[test]
+fn writes_are_not_combined_past_overlapping_writes() {
+    let mut engine = start_test(file!());
+    let store_buffer = create_store_buffer(&mut engine, DrainPolicy::Timeout(100));
+    let half = (WRITE_SIZE_BYTES / 2) as u64;
+    let a = write(&engine, BASE_ADDRESS);
+    let b = write(&engine, BASE_ADDRESS + half);
+    let a_prime = write(&engine, BASE_ADDRESS + 2 * half);
+
+    // A' is adjacent to A but overlaps the younger B, so combining it into A
+    // would let B overwrite it
+    let mut harness = StoreBufferHarness::new(engine, store_buffer.clone());
+    harness.run_steps([
+        send_rx!(a),
+        send_rx!(b),
+        send_rx!(a_prime),
+        expect_tx!(MemoryTxn::write_req(BASE_ADDRESS).with_bytes(WRITE_SIZE_BYTES)),
+        expect_tx!(MemoryTxn::write_req(BASE_ADDRESS + half).with_bytes(WRITE_SIZE_BYTES)),
+        expect_tx!(MemoryTxn::write_req(BASE_ADDRESS + 2 * half).with_bytes(WRITE_SIZE_BYTES)),
+    ]);
+
+    assert_eq!(store_buffer.num_combined(), 0);
+    assert_eq!(store_buffer.num_drained(), 3);
+}
+
+#[test]
+fn watermark_holds_writes() {
+    let mut engine = start_test(file!());
+    let store_buffer = create_store_buffer(&mut engine, DrainPolicy::Watermark(3));
+    let line = LINE_SIZE_BYTES as u64;
+    let writes: Vec<_> = (0..3)
+        .map(|i| write(&engine, BASE_ADDRESS + i * line))
+        .collect();
+
+    let mut harness = StoreBufferHarness::new(engine, store_buffer.clone());
+    harness.run_steps([
+        send_rx!(writes[0].clone()),
+        send_rx!(writes[1].clone()),
+        expect_no_traffic!(&[Port::Tx], 100),
+        send_rx!(writes[2].clone()),
+        expect_tx!(MemoryTxn::write_req(BASE_ADDRESS)),
+    ]);
+
+    // Once below the watermark the remaining writes are held
+    assert_eq!(store_buffer.occupancy(), 2);
+    assert_eq!(store_buffer.max_occupancy(), 3);
+}
+
+#[test]
+fn read_forces_drain() {
+    let mut engine = start_test(file!());
+    let store_buffer = create_store_buffer(&mut engine, DrainPolicy::Watermark(NUM_ENTRIES));
+    let memory_map = Rc::new(create_default_memory_map());
+    let line = LINE_SIZE_BYTES as u64;
+    let w0 = write(&engine, BASE_ADDRESS);
+    let w1 = write(&engine, BASE_ADDRESS + line);
+    let read = create_read(
+        engine.top(),
+        &memory_map,
+        WRITE_SIZE_BYTES,
+        BASE_ADDRESS,
+        SRC_ADDR,
+        OVERHEAD_SIZE_BYTES,
+    );
+
+    let mut harness = StoreBufferHarness::new(engine, store_buffer.clone());
+    harness.run_steps([
+        send_rx!(w0),
+        send_rx!(w1),
+        send_rx!(read),
+        expect_tx!(MemoryTxn::write_req(BASE_ADDRESS)),
+        expect_tx!(MemoryTxn::write_req(BASE_ADDRESS + line)),
+        expect_tx!(MemoryTxn::read_req(BASE_ADDRESS)),
+    ]);
+
+    assert_eq!(store_buffer.num_drained(), 3);
+    assert_eq!(store_buffer.occupancy(), 0);
+}
+
+#[test]
+fn masked_writes_are_combined() {
+    let mut engine = start_test(file!());
+    let store_buffer = create_store_buffer(&mut engine, DrainPolicy::Timeout(100));
+    let memory_map = Rc::new(create_default_memory_map());
+    let w0 = write(&engine, BASE_ADDRESS);
+    let w1 = create_masked_write(
+        engine.top(),
+        &memory_map,
+        ByteEnables::from_range(WRITE_SIZE_BYTES, 0..2),
+        BASE_ADDRESS + WRITE_SIZE_BYTES as u64,
+        SRC_ADDR,
+        OVERHEAD_SIZE_BYTES,
+    );
+
+    let mut harness = StoreBufferHarness::new(engine, store_buffer.clone());
+    harness.run_steps([
+        send_rx!(w0),
+        send_rx!(w1),
+        expect_tx!(
+            MemoryTxn::write_req(BASE_ADDRESS)
+                .with_bytes(2 * WRITE_SIZE_BYTES)
+                .with_enabled_bytes(WRITE_SIZE_BYTES + 2)
+                .with_total_bytes(WRITE_SIZE_BYTES + 2 + OVERHEAD_SIZE_BYTES)
+        ),
+    ]);
+
+    assert_eq!(store_buffer.num_combined(), 1);
+}
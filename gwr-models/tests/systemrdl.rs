@@ -0,0 +1,165 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_engine::traits::Resolve;
+use gwr_models::registers::Permission;
+use gwr_models::registers::register::Register;
+use gwr_models::registers::systemrdl::RegisterMap;
+use gwr_models::registers::test_helpers::TestResolver;
+
+const DMA_RDL: &str = include_str!("registers/dma.rdl");
+
+/// The registers generated from `dma.rdl`
+mod dma {
+    include!("registers/dma_regs.rs");
+}
+
+fn expect_error(rdl: &str, expected: &str) {
+    match RegisterMap::from_systemrdl(rdl) {
+        Err(e) => assert_eq!(e.to_string(), expected),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
+
+#[test]
+fn parse_registers() {
+    let map = RegisterMap::from_systemrdl(DMA_RDL).unwrap();
+    assert_eq!(map.name(), "dma");
+    assert_eq!(map.desc(), Some("DMA engine registers"));
+
+    let names: Vec<_> = map.registers().iter().map(|r| r.name()).collect();
+    assert_eq!(names, ["ctrl", "status", "src_addr", "dst_addr"]);
+
+    // Registers without an offset follow the previous register
+    let offsets: Vec<_> = map.registers().iter().map(|r| r.offset()).collect();
+    assert_eq!(offsets, [0x0, 0x4, 0x8, 0x10]);
+
+    let ctrl = map.register("ctrl").unwrap();
+    assert_eq!(ctrl.num_bits(), 32);
+    assert_eq!(ctrl.reset_value(), 0x81);
+    let start = &ctrl.fields()[2];
+    assert_eq!(start.name(), "start");
+    assert_eq!(start.lsb(), 31);
+    assert_eq!(start.num_bits(), 1);
+    assert_eq!(start.permission(), Permission::WriteOneCommits);
+
+    let status = map.register("status").unwrap();
+    let permissions: Vec<_> = status.fields().iter().map(|f| f.permission()).collect();
+    assert_eq!(
+        permissions,
+        [
+            Permission::ReadVolatileOnly,
+            Permission::ReadVolatileOnly,
+            Permission::ReadOnly
+        ]
+    );
+    assert_eq!(status.fields()[1].lsb(), 1);
+    assert_eq!(status.fields()[1].desc(), Some("Set by the hardware"));
+
+    let src_addr = map.register("src_addr").unwrap();
+    assert_eq!(src_addr.num_bits(), 64);
+    assert_eq!(
+        src_addr.fields()[0].permission(),
+        Permission::ReadVolatileWrite
+    );
+}
+
+#[test]
+fn generated_code_is_up_to_date() {
+    let map = RegisterMap::from_systemrdl(DMA_RDL).unwrap();
+
+    // The checked-in code starts with a copyright notice that is not generated
+    let expected = include_str!("registers/dma_regs.rs");
+    let (_, expected) = expected.split_once("\n\n").unwrap();
+    assert_eq!(map.to_rust(), expected);
+}
+
+#[test]
+fn generated_registers() {
+    let map = RegisterMap::from_systemrdl(DMA_RDL).unwrap();
+    let resolver = TestResolver::new();
+    let states = dma::DmaStates::new();
+    let regs = dma::DmaRegs::new(&states, 0);
+
+    let ctrl = map.register("ctrl").unwrap();
+    assert_eq!(regs.ctrl.value(), ctrl.reset_value());
+    assert_eq!(regs.status.value(), 0x1200);
+
+    // Writes to the reserved bits and the single pulse `start` are dropped
+    regs.write(&resolver, ctrl.offset(), 0xffff_ffff);
+    resolver.resolve();
+    assert_eq!(regs.ctrl.value(), 0xf1);
+    assert_eq!(regs.read(dma::dma_indices::CTRL), 0xf1);
+
+    // Read-only fields ignore writes
+    regs.status.write(&resolver, 0xffff);
+    resolver.resolve();
+    assert_eq!(regs.status.value(), 0x1200);
+
+    regs.src_addr.write(&resolver, 0x1234_5678_9abc);
+    resolver.resolve();
+    assert_eq!(regs.read(dma::dma_indices::SRCADDR), 0x1234_5678_9abc);
+}
+
+#[test]
+fn unknown_field_type() {
+    expect_error(
+        "addrmap top {\n  reg { flag enable; } ctrl;\n};",
+        "SystemRDL line 2: unknown field type 'flag'",
+    );
+}
+
+#[test]
+fn overlapping_fields() {
+    expect_error(
+        "addrmap top {
+  reg {
+    field {} a[3:0];
+    field {} b[2:2];
+  } ctrl;
+};",
+        "SystemRDL line 4: field 'b' overlaps field 'a'",
+    );
+}
+
+#[test]
+fn field_outside_register() {
+    expect_error(
+        "addrmap top {\n  reg { regwidth = 8; field {} a[8:0]; } ctrl;\n};",
+        "SystemRDL line 2: field 'a' does not fit in a 8-bit register",
+    );
+}
+
+#[test]
+fn reset_value_too_large() {
+    expect_error(
+        "addrmap top {\n  reg { field {} a[1:0] = 4; } ctrl;\n};",
+        "SystemRDL line 2: reset value 0x4 does not fit in field 'a'",
+    );
+}
+
+#[test]
+fn overlapping_registers() {
+    expect_error(
+        "addrmap top {
+  reg { field {} a; } ctrl @ 0x0;
+  reg { field {} a; } status @ 0x0;
+};",
+        "SystemRDL line 3: register 'status' at 0x0 overlaps 'ctrl'",
+    );
+}
+
+#[test]
+fn no_addrmap() {
+    expect_error(
+        "reg ctrl_t { field {} a; };",
+        "SystemRDL must contain exactly one addrmap, found 0",
+    );
+}
+
+#[test]
+fn nested_addrmap() {
+    expect_error(
+        "addrmap top {\n  regfile { } rf;\n};",
+        "SystemRDL line 2: nested components are not supported",
+    );
+}
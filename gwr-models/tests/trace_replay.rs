@@ -0,0 +1,110 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_components::connect_port;
+use gwr_engine::engine::Engine;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::types::AccessType;
+use gwr_models::memory::memory_access::MemoryAccess;
+use gwr_models::memory::memory_map::{DeviceId, MemoryMap};
+use gwr_models::memory::trace_replay::{
+    TraceRecord, TraceReplay, TraceReplayConfig, parse_csv_trace,
+};
+use gwr_models::memory::{Memory, MemoryConfig};
+use gwr_models::test_helpers::create_default_memory_map;
+
+const BASE_ADDRESS: u64 = 0x80000;
+const SRC_ADDR: u64 = BASE_ADDRESS + 0x1000;
+const CAPACITY_BYTES: usize = 0x40000;
+const BW_BYTES_PER_CYCLE: usize = 32;
+const DELAY_TICKS: usize = 8;
+const OVERHEAD_SIZE_BYTES: usize = 16;
+
+fn setup_system(
+    engine: &mut Engine,
+    memory_map: MemoryMap,
+    records: Vec<TraceRecord>,
+) -> (Rc<TraceReplay>, Rc<Memory<MemoryAccess>>) {
+    let clock = engine.default_clock();
+    let top = engine.top();
+    let memory_map = Rc::new(memory_map);
+
+    let config = TraceReplayConfig::new(SRC_ADDR, OVERHEAD_SIZE_BYTES);
+    let replay =
+        TraceReplay::new_and_register(engine, &clock, top, "replay", config, &memory_map, records)
+            .unwrap();
+    let config = MemoryConfig::new(
+        BASE_ADDRESS,
+        CAPACITY_BYTES,
+        BW_BYTES_PER_CYCLE,
+        DELAY_TICKS,
+    );
+    let memory = Memory::new_and_register(engine, &clock, top, "memory", config).unwrap();
+
+    connect_port!(replay, tx => memory, rx).unwrap();
+    connect_port!(memory, tx => replay, rx).unwrap();
+
+    (replay, memory)
+}
+
+#[test]
+fn trace_drives_memory() {
+    let mut engine = start_test(file!());
+    let records = parse_csv_trace(
+        "# tick,addr,size,op\n\
+         0,0x80000,64,R\n\
+         100,0x80040,32,W\n\
+         200,0x80080,16,WNP\n\
+         1000,0x800c0,64,R\n",
+    )
+    .unwrap();
+    let (replay, memory) = setup_system(&mut engine, create_default_memory_map(), records);
+
+    run_simulation!(engine);
+
+    assert_eq!(replay.num_issued(), 4);
+    assert_eq!(replay.num_late(), 0);
+
+    // Posted writes do not get a response
+    assert_eq!(replay.num_responses(), 3);
+    assert_eq!(replay.payload_bytes_received(), 64 + 16 + 64);
+    assert_eq!(memory.bytes_read(), 128);
+    assert_eq!(memory.bytes_written(), 48);
+
+    // The last read cannot complete before it is issued
+    let clock = engine.default_clock();
+    assert!(clock.tick_now().tick() > 1000);
+}
+
+#[test]
+fn backpressure_makes_records_late() {
+    let mut engine = start_test(file!());
+    let num_records = 10;
+    let records = (0..num_records)
+        .map(|i| TraceRecord::new(0, BASE_ADDRESS + i * 256, 256, AccessType::ReadRequest))
+        .collect();
+    let (replay, memory) = setup_system(&mut engine, create_default_memory_map(), records);
+
+    run_simulation!(engine);
+
+    assert_eq!(replay.num_issued(), num_records as usize);
+    assert_eq!(replay.num_responses(), num_records as usize);
+    assert!(replay.num_late() > 0);
+    assert_eq!(memory.bytes_read(), num_records as usize * 256);
+}
+
+#[test]
+fn unmapped_address_is_an_error() {
+    let mut engine = start_test(file!());
+    let mut memory_map = MemoryMap::new();
+    memory_map
+        .insert(BASE_ADDRESS, CAPACITY_BYTES as u64, DeviceId(0))
+        .unwrap();
+    let records = parse_csv_trace("0,0x10,64,R").unwrap();
+    let (_replay, _memory) = setup_system(&mut engine, memory_map, records);
+
+    let err = engine.run().unwrap_err();
+    assert!(format!("{err}").contains("0x10 is not in the memory map"));
+}
@@ -1,5 +1,9 @@
 // Copyright (c) 2026 Graphcore Ltd. All rights reserved.
 
+/// The trait used to decode and encode the [protos], re-exported so that
+/// users do not need to depend on a matching version of `prost`
+pub use prost::Message;
+
 /// Auto-generated ONNX module
 ///
 /// The contents of this file are created by `build.rs` at compile-time. They
@@ -0,0 +1,172 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Introspection of the memory maps of a [Platform](crate::Platform).
+//!
+//! An [AddressMap] describes a memory map as it was assembled when the platform
+//! was built: the address range of each region, the names and [DeviceId]s of
+//! the memories that it maps to and how it is interleaved. Its [Display]
+//! implementation gives a formatted dump, for example:
+//!
+//! ```text
+//! Address map 'pe_memory_map' (used by pe0, pe1):
+//!   0x0-0x3fff_ffff: hbm0 (device 2)
+//!   0x4000_0000-0xbfff_ffff: interleaved across hbm1 (device 3), hbm2 (device 4) in 0x1000 byte granules (stride)
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use gwr_models::memory::memory_map::{DeviceId, Interleave, InterleaveHash, MemoryMap};
+
+use crate::DeviceIds;
+use crate::types::MemoryMapSection;
+use crate::yaml::u64_hex_str;
+
+/// A region of an [AddressMap]
+#[derive(Clone, Debug)]
+pub struct AddressRegion {
+    start: u64,
+    end: u64,
+    devices: Vec<(String, DeviceId)>,
+    interleave: Option<Interleave>,
+}
+
+impl AddressRegion {
+    /// Returns the first address of the region
+    #[must_use]
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the last address of the region
+    #[must_use]
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Returns the name and ID of each device that the region maps to. There is
+    /// only more than one device if the region is interleaved.
+    #[must_use]
+    pub fn devices(&self) -> &[(String, DeviceId)] {
+        &self.devices
+    }
+
+    #[must_use]
+    pub fn interleave(&self) -> Option<&Interleave> {
+        self.interleave.as_ref()
+    }
+}
+
+impl Display for AddressRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let devices: Vec<String> = self
+            .devices
+            .iter()
+            .map(|(name, device_id)| format!("{name} (device {})", device_id.0))
+            .collect();
+        write!(f, "{}-{}: ", u64_hex_str(self.start), u64_hex_str(self.end))?;
+        match &self.interleave {
+            None => write!(f, "{}", devices.join(", ")),
+            Some(interleave) => {
+                let hash = match interleave.hash() {
+                    InterleaveHash::Stride => "stride",
+                    InterleaveHash::Xor => "xor",
+                };
+                write!(
+                    f,
+                    "interleaved across {} in {} byte granules ({hash})",
+                    devices.join(", "),
+                    u64_hex_str(interleave.granule_bytes())
+                )
+            }
+        }
+    }
+}
+
+/// A memory map of a platform and the PEs that use it
+#[derive(Clone, Debug)]
+pub struct AddressMap {
+    name: String,
+    pes: Vec<String>,
+    regions: Vec<AddressRegion>,
+}
+
+impl AddressMap {
+    pub(crate) fn new(
+        section: &MemoryMapSection,
+        memory_map: &MemoryMap,
+        device_ids: &DeviceIds,
+        pes: Vec<String>,
+    ) -> Self {
+        // Device IDs are only unique within a fabric, so the names are found
+        // from the devices of this memory map
+        let device_names: HashMap<DeviceId, &str> = section
+            .devices
+            .iter()
+            .filter_map(|device| Some((*device_ids.get(&device.name)?, device.name.as_str())))
+            .collect();
+
+        let regions = memory_map
+            .regions()
+            .map(|region| AddressRegion {
+                start: region.start,
+                end: region.end,
+                devices: region
+                    .devices
+                    .iter()
+                    .map(|device_id| {
+                        let name = device_names.get(device_id).copied().unwrap_or("?");
+                        (name.to_string(), *device_id)
+                    })
+                    .collect(),
+                interleave: region.interleave,
+            })
+            .collect();
+
+        Self {
+            name: section.name.clone(),
+            pes,
+            regions,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the names of the PEs that use the memory map
+    #[must_use]
+    pub fn pes(&self) -> &[String] {
+        &self.pes
+    }
+
+    /// Returns the regions of the memory map in address order
+    #[must_use]
+    pub fn regions(&self) -> &[AddressRegion] {
+        &self.regions
+    }
+
+    /// Returns the region containing an address
+    #[must_use]
+    pub fn region_containing(&self, addr: u64) -> Option<&AddressRegion> {
+        self.regions
+            .iter()
+            .find(|region| region.start <= addr && addr <= region.end)
+    }
+}
+
+impl Display for AddressMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let used_by = if self.pes.is_empty() {
+            "unused".to_string()
+        } else {
+            format!("used by {}", self.pes.join(", "))
+        };
+        writeln!(f, "Address map '{}' ({used_by}):", self.name)?;
+        for region in &self.regions {
+            writeln!(f, "  {region}")?;
+        }
+        Ok(())
+    }
+}
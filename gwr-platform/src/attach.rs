@@ -0,0 +1,152 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Attachment of components to the nodes of a fabric without choosing their
+//! fabric ports.
+//!
+//! Rather than giving both ends of every connection to a fabric, a connection
+//! can list the components to attach to a fabric and the node that each one is
+//! attached to:
+//!
+//! ```yaml
+//! connections:
+//!   - fabric: fabric0
+//!     attach:
+//!       - pe.pe0@(0,0)
+//!       - dma.dma0@(0,0)
+//!       - mem.hbm0@(1,1)
+//! ```
+//!
+//! Each component is given the lowest port of its node that is not used by
+//! any other connection or group link, so port indices (and therefore device
+//! IDs) do not have to be worked out by hand. Fabric ports given without a
+//! port index in a `connect` use port 0. The attachments are expanded into
+//! `connect` entries such as `[pe.pe0, fabric.fabric0@(0,0).0]` before the
+//! configuration is validated.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use regex::Regex;
+
+use crate::builder::DEFAULT_FABRIC_PORTS_PER_NODE;
+use crate::connect::FABRIC_PORT_RE;
+use crate::types::{ConnectSection, PlatformConfig};
+use crate::validate::check_fabric_port;
+
+/// Matches an attachment of the form:
+///   kind.name@(col,row)
+static ATTACH_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([a-z]+\.[A-Za-z0-9_.]+)@\((\d+),(\d+)\)$").unwrap());
+
+/// A port of a fabric node as (fabric, col, row, port)
+type NodePort = (String, usize, usize, usize);
+
+/// Returns the fabric ports that are used by the `connect` entries and group
+/// links of a configuration
+fn used_ports(cfg: &PlatformConfig) -> HashSet<NodePort> {
+    let endpoints = cfg
+        .connections
+        .iter()
+        .flatten()
+        .flat_map(|c| &c.connect)
+        .chain(cfg.group_links.iter().flatten().flat_map(|l| &l.connect));
+
+    endpoints
+        .filter_map(|endpoint| {
+            let caps = FABRIC_PORT_RE.captures(endpoint)?;
+            let col = caps[2].parse().ok()?;
+            let row = caps[3].parse().ok()?;
+            let port = caps.get(4).map_or(Some(0), |m| m.as_str().parse().ok())?;
+            Some((caps[1].to_string(), col, row, port))
+        })
+        .collect()
+}
+
+/// Replace the connections of a configuration that attach components to a
+/// fabric with a `connect` entry for each component.
+pub(crate) fn expand_attachments(cfg: &mut PlatformConfig) -> Result<(), SimError> {
+    if !cfg
+        .connections
+        .iter()
+        .flatten()
+        .any(|c| c.fabric.is_some() || c.attach.is_some())
+    {
+        return Ok(());
+    }
+
+    let mut used = used_ports(cfg);
+    let connections = cfg.connections.take().unwrap_or_default();
+    let mut expanded = Vec::with_capacity(connections.len());
+    for connection in connections {
+        let (fabric_name, attach) = match (connection.fabric, connection.attach) {
+            (None, None) => {
+                expanded.push(ConnectSection {
+                    connect: connection.connect,
+                    fabric: None,
+                    attach: None,
+                });
+                continue;
+            }
+            (Some(fabric_name), Some(attach)) if connection.connect.is_empty() => {
+                (fabric_name, attach)
+            }
+            (Some(_), Some(_)) => {
+                return sim_error!("A connection cannot have both 'connect' and 'attach'");
+            }
+            (None, Some(_)) => return sim_error!("A connection with 'attach' needs a 'fabric'"),
+            (Some(fabric_name), None) => {
+                return sim_error!("The connection to fabric '{fabric_name}' has no 'attach'");
+            }
+        };
+
+        let Some(fabric) = cfg
+            .fabrics
+            .iter()
+            .flatten()
+            .find(|fabric| fabric.name == fabric_name)
+        else {
+            return sim_error!("Unknown Fabric '{fabric_name}' in connection");
+        };
+        let ports_per_node = fabric
+            .fabric_ports_per_node
+            .unwrap_or(DEFAULT_FABRIC_PORTS_PER_NODE);
+
+        for attachment in attach {
+            let Some(caps) = ATTACH_RE.captures(&attachment) else {
+                return sim_error!(
+                    "Unable to parse attachment '{attachment}' (expected kind.name@(col,row))"
+                );
+            };
+            let parse = |s: &str| {
+                s.parse::<usize>().map_err(|e| {
+                    SimError(format!("Unable to parse attachment '{attachment}': {e}"))
+                })
+            };
+            let col = parse(&caps[2])?;
+            let row = parse(&caps[3])?;
+            check_fabric_port(fabric, col, row, 0)
+                .map_err(|problem| SimError(format!("Attachment '{attachment}' {problem}")))?;
+
+            let Some(port) = (0..ports_per_node)
+                .find(|port| !used.contains(&(fabric_name.clone(), col, row, *port)))
+            else {
+                return sim_error!(
+                    "No free port for '{attachment}' on node ({col},{row}) of fabric '{fabric_name}' (nodes have {ports_per_node} ports)"
+                );
+            };
+            used.insert((fabric_name.clone(), col, row, port));
+            expanded.push(ConnectSection {
+                connect: vec![
+                    caps[1].to_string(),
+                    format!("fabric.{fabric_name}@({col},{row}).{port}"),
+                ],
+                fabric: None,
+                attach: None,
+            });
+        }
+    }
+    cfg.connections = Some(expanded);
+    Ok(())
+}
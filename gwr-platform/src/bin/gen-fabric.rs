@@ -4,14 +4,16 @@ use std::fs;
 use std::path::PathBuf;
 
 use clap::Parser;
+use gwr_models::fabric::FabricTopology;
 use gwr_models::fabric::node::FabricRoutingAlgorithm;
 use gwr_platform::builder::{
     DEFAULT_CACHE_LINE_SIZE_BYTES, DEFAULT_FABRIC_PORT_BITS_PER_TICK,
     DEFAULT_FABRIC_PORTS_PER_NODE, DEFAULT_FABRIC_ROUTING, DEFAULT_FABRIC_RX_BUFFER_BYTES,
-    DEFAULT_FABRIC_TICKS_OVERHEAD, DEFAULT_FABRIC_TICKS_PER_HOP, DEFAULT_FABRIC_TX_BUFFER_BYTES,
-    DEFAULT_HBM_DELAY_TICKS, DEFAULT_HBM_SIZE_BYTES, DEFAULT_PE_ADDS_PER_TICK,
-    DEFAULT_PE_COMPARES_PER_TICK, DEFAULT_PE_LSU_ACCESS_BYTES, DEFAULT_PE_MULS_PER_TICK,
-    DEFAULT_PE_NUM_ACTIVE_REQUESTS, DEFAULT_PE_OVERHEAD_SIZE_BYTES, DEFAULT_PE_SRAM_BYTES,
+    DEFAULT_FABRIC_TICKS_OVERHEAD, DEFAULT_FABRIC_TICKS_PER_HOP, DEFAULT_FABRIC_TOPOLOGY,
+    DEFAULT_FABRIC_TX_BUFFER_BYTES, DEFAULT_HBM_DELAY_TICKS, DEFAULT_HBM_SIZE_BYTES,
+    DEFAULT_PE_ADDS_PER_TICK, DEFAULT_PE_COMPARES_PER_TICK, DEFAULT_PE_LSU_ACCESS_BYTES,
+    DEFAULT_PE_MULS_PER_TICK, DEFAULT_PE_NUM_ACTIVE_REQUESTS, DEFAULT_PE_OVERHEAD_SIZE_BYTES,
+    DEFAULT_PE_SRAM_BYTES,
 };
 use gwr_platform::types::{
     CacheConfigSection, CacheSection, ConnectSection, FabricKind, FabricSection,
@@ -56,6 +58,9 @@ struct Args {
     #[arg(long, value_enum, default_value_t = DEFAULT_FABRIC_ROUTING)]
     fabric_routing: FabricRoutingAlgorithm,
 
+    #[arg(long, value_enum, default_value_t = DEFAULT_FABRIC_TOPOLOGY)]
+    fabric_topology: FabricTopology,
+
     #[arg(long, default_value_t = 2)]
     num_columns: usize,
 
@@ -195,6 +200,8 @@ fn build_fabrics(args: &Args) -> Vec<FabricSection> {
         tx_buffer_bytes: Some(DEFAULT_FABRIC_TX_BUFFER_BYTES),
         port_bits_per_tick: Some(DEFAULT_FABRIC_PORT_BITS_PER_TICK),
         routing: Some(args.fabric_routing),
+        topology: Some(args.fabric_topology),
+        clock_mhz: None,
     }]
 }
 
@@ -215,6 +222,7 @@ fn build_cache(
             num_sets: Some(num_sets),
             delay_ticks: Some(latency),
         },
+        clock_mhz: None,
     }
 }
 
@@ -262,6 +270,7 @@ fn build_memories(args: &Args) -> Vec<MemorySection> {
                 capacity_bytes: args.hbm_size as u64,
                 bw_bytes_per_cycle: None,
                 delay_ticks: Some(DEFAULT_HBM_DELAY_TICKS),
+                clock_mhz: None,
             };
             base += args.hbm_size;
             mem
@@ -287,6 +296,8 @@ fn build_connections(args: &Args) -> Result<Vec<ConnectSection>, String> {
         for pair in entities.windows(2) {
             connections.push(ConnectSection {
                 connect: vec![pair[0].clone(), pair[1].clone()],
+                fabric: None,
+                attach: None,
             });
         }
     }
@@ -297,6 +308,8 @@ fn build_connections(args: &Args) -> Result<Vec<ConnectSection>, String> {
                 format!("mem.hbm{i}"),
                 format!("fabric.{FABRIC_NAME}@({column},{row})"),
             ],
+            fabric: None,
+            attach: None,
         });
     }
 
@@ -312,6 +325,9 @@ fn build_pe_config(args: &Args) -> ProcessingElementConfigSection {
         adds_per_tick: Some(args.pe_adds_per_tick),
         muls_per_tick: Some(args.pe_muls_per_tick),
         compares_per_tick: Some(args.pe_compares_per_tick),
+        cost_model: None,
+        preemption_ticks: None,
+        scratchpad: None,
     }
 }
 
@@ -332,6 +348,7 @@ fn build_processing_elements(
             name: create_name("pe", column, row),
             memory_map: PE_MEMORY_MAP_NAME.to_string(),
             config: pe_config.clone(),
+            clock_mhz: None,
         })
         .collect())
 }
@@ -340,17 +357,26 @@ fn build_platform(args: &Args) -> Result<PlatformConfig, String> {
     let memory_map = MemoryMapSection {
         name: PE_MEMORY_MAP_NAME.to_string(),
         devices: build_memory_map_ranges(args),
+        interleave: None,
     };
     let pe_config = build_pe_config(args);
 
     Ok(PlatformConfig {
+        includes: None,
         memory_maps: vec![memory_map],
         defaults: None,
         processing_elements: Some(build_processing_elements(args, &pe_config)?),
+        dma_engines: None,
+        accelerators: None,
         caches: build_caches(args)?,
         fabrics: Some(build_fabrics(args)),
         memories: Some(build_memories(args)),
         connections: Some(build_connections(args)?),
+        devices: None,
+        topologies: None,
+        groups: None,
+        group_links: None,
+        power_domains: None,
     })
 }
 
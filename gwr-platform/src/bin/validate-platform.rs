@@ -1,10 +1,15 @@
 // Copyright (c) 2026 Graphcore Ltd. All rights reserved.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use gwr_engine::engine::Engine;
 use gwr_platform::Platform;
+use gwr_platform::diff::diff_configs;
+use gwr_platform::format::PlatformFormat;
+use gwr_platform::overrides::Override;
+use gwr_platform::template::env_variables;
+use gwr_platform::validate::parse_platform_config_as;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -15,17 +20,47 @@ struct Args {
     #[arg(long, default_value = "platform.yaml")]
     platform: PathBuf,
 
+    /// Override a value in the platform file, e.g. `--set pe0.config.lsu_access_bytes=64` or `--set variables.num_pes=8`.
+    #[arg(long = "set", value_name = "PATH=VALUE")]
+    overrides: Vec<Override>,
+
+    /// Format of the platform file (detected from its extension by default).
+    #[arg(long, value_enum)]
+    platform_format: Option<PlatformFormat>,
+
     /// Print the constructed platform after validation.
     #[arg(long, default_value_t = false)]
     print_platform: bool,
+
+    /// Print the address map of each memory map of the platform.
+    #[arg(long, default_value_t = false)]
+    address_map: bool,
+
+    /// Print the changes from the platform to another platform file.
+    #[arg(long, value_name = "OTHER_PLATFORM")]
+    diff: Option<PathBuf>,
+
+    /// Write a Graphviz DOT graph of the constructed platform to this file.
+    #[arg(long)]
+    dot: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
+    // Variables set in the environment are applied before the overrides so
+    // that the command line takes precedence
+    args.overrides.splice(0..0, env_variables()?);
 
     let mut engine = Engine::default();
     let clock = engine.default_clock();
-    let platform = Platform::from_file(&engine, &clock, &args.platform)?;
+    let platform = Platform::from_file_as(
+        &engine,
+        &clock,
+        &args.platform,
+        args.platform_format
+            .unwrap_or_else(|| PlatformFormat::from_path(&args.platform)),
+        &args.overrides,
+    )?;
 
     println!(
         "Validated '{}' with {} PEs, {} caches, {} memories, and {} fabrics.",
@@ -40,5 +75,31 @@ fn main() -> Result<()> {
         println!("{platform}");
     }
 
+    if args.address_map {
+        for address_map in platform.address_maps() {
+            print!("{address_map}");
+        }
+    }
+
+    if let Some(other_path) = &args.diff {
+        let source = std::fs::read_to_string(other_path)?;
+        let other = parse_platform_config_as(
+            &source,
+            PlatformFormat::from_path(other_path),
+            other_path.parent().unwrap_or(Path::new(".")),
+            &args.overrides,
+        )?;
+        let diff = diff_configs(platform.config(), &other);
+        if diff.is_empty() {
+            println!("No differences from '{}'.", other_path.display());
+        } else {
+            print!("{diff}");
+        }
+    }
+
+    if let Some(dot_path) = &args.dot {
+        std::fs::write(dot_path, platform.to_dot())?;
+    }
+
     Ok(())
 }
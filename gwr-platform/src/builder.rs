@@ -5,21 +5,28 @@ use std::hash::BuildHasher;
 use std::rc::Rc;
 
 use gwr_engine::engine::Engine;
+use gwr_engine::sim_error;
 use gwr_engine::time::clock::Clock;
 use gwr_engine::types::SimError;
 use gwr_models::fabric::functional::FunctionalFabric;
 use gwr_models::fabric::node::FabricRoutingAlgorithm;
 use gwr_models::fabric::routed::RoutedFabric;
-use gwr_models::fabric::{Fabric, FabricConfig};
+use gwr_models::fabric::{Fabric, FabricConfig, FabricTopology};
 use gwr_models::memory::cache::{Cache, CacheConfig};
 use gwr_models::memory::memory_access::MemoryAccess;
-use gwr_models::memory::memory_map::MemoryMap;
+use gwr_models::memory::memory_map::{Interleave, MemoryMap};
 use gwr_models::memory::{Memory, MemoryConfig};
-use gwr_models::processing_element::{ProcessingElement, ProcessingElementConfig};
+use gwr_models::processing_element::cost_model::{CostModel, LatencyTableCostModel};
+use gwr_models::processing_element::scratchpad::ScratchpadConfig;
+use gwr_models::processing_element::{MachineOp, ProcessingElement, ProcessingElementConfig};
 use gwr_track::entity::{Entity, GetEntity};
 
-use crate::types::{FabricKind, MemoryMapSection, PlatformConfig, ProcessingElementConfigSection};
-use crate::{Caches, DeviceIds, Fabrics, Memories, NameToIdxMap, ProcessingElements};
+use crate::device::DeviceRegistry;
+use crate::types::{
+    CostModelSection, DmaEngineConfigSection, FabricKind, MemoryMapSection, MemorySection,
+    PlatformConfig, ProcessingElementConfigSection, ScratchpadSection,
+};
+use crate::{Caches, DeviceIds, Devices, Fabrics, Memories, NameToIdxMap, ProcessingElements};
 
 pub fn build_memory_map(
     cfg: &MemoryMapSection,
@@ -28,6 +35,8 @@ pub fn build_memory_map(
     device_ids: &DeviceIds,
 ) -> Result<MemoryMap, SimError> {
     let mut memory_map = MemoryMap::new();
+    let mut interleaved_devices = Vec::new();
+    let mut ranges: Vec<(u64, u64, &str)> = Vec::new();
     for device in &cfg.devices {
         let memory_idx = memories_idx_by_id
             .get(device.name.as_str())
@@ -36,10 +45,44 @@ pub fn build_memory_map(
         let device_id = *device_ids
             .get(&device.name)
             .ok_or_else(|| SimError(format!("Unknown device '{}'", device.name)))?;
-        memory_map.insert(
-            memory.base_address(),
-            memory.capacity_bytes() as u64,
-            device_id,
+        if cfg.interleave.is_some() {
+            interleaved_devices.push((memory, device_id));
+        } else {
+            // Report overlapping memories by name rather than by address
+            let start = memory.base_address();
+            let end = start + (memory.capacity_bytes() as u64).saturating_sub(1);
+            if let Some((other_start, other_end, other)) = ranges
+                .iter()
+                .find(|(other_start, other_end, _)| start <= *other_end && *other_start <= end)
+            {
+                return sim_error!(
+                    "Memory map '{}': '{}' (0x{start:x}-0x{end:x}) overlaps '{other}' (0x{other_start:x}-0x{other_end:x})",
+                    cfg.name,
+                    device.name
+                );
+            }
+            ranges.push((start, end, &device.name));
+            memory_map.insert(
+                memory.base_address(),
+                memory.capacity_bytes() as u64,
+                device_id,
+            )?;
+        }
+    }
+
+    if let Some(interleave) = &cfg.interleave
+        && let Some((first, _)) = interleaved_devices.first()
+    {
+        let size: u64 = interleaved_devices
+            .iter()
+            .map(|(memory, _)| memory.capacity_bytes() as u64)
+            .sum();
+        memory_map.insert_interleaved(
+            first.base_address(),
+            size,
+            interleave.granule_bytes,
+            interleaved_devices.iter().map(|(_, id)| *id).collect(),
+            interleave.hash.unwrap_or_default(),
         )?;
     }
     Ok(memory_map)
@@ -67,6 +110,43 @@ pub const DEFAULT_PE_ADDS_PER_TICK: f64 = 16.0;
 pub const DEFAULT_PE_MULS_PER_TICK: f64 = 4.0;
 pub const DEFAULT_PE_COMPARES_PER_TICK: f64 = DEFAULT_PE_ADDS_PER_TICK;
 pub const DEFAULT_PE_OVERHEAD_SIZE_BYTES: usize = 8;
+pub const DEFAULT_PE_ISSUE_WIDTH: usize = 1;
+pub const DEFAULT_PE_OP_LATENCY_TICKS: usize = 1;
+pub const DEFAULT_PE_SCRATCHPAD_BW_BYTES_PER_CYCLE: usize = 64;
+pub const DEFAULT_PE_DMA_CHANNELS: usize = 1;
+
+fn build_cost_model(cfg: &CostModelSection) -> Result<Rc<dyn CostModel>, SimError> {
+    let issue_width = cfg.issue_width.unwrap_or(DEFAULT_PE_ISSUE_WIDTH);
+    if issue_width == 0 {
+        return sim_error!("PE cost model issue_width must be greater than 0");
+    }
+    let cost_model = LatencyTableCostModel::new(issue_width)
+        .with_pipelining(cfg.pipelined.unwrap_or(true))
+        .with_latency(
+            MachineOp::Add,
+            cfg.add_latency_ticks.unwrap_or(DEFAULT_PE_OP_LATENCY_TICKS),
+        )
+        .with_latency(
+            MachineOp::Mul,
+            cfg.mul_latency_ticks.unwrap_or(DEFAULT_PE_OP_LATENCY_TICKS),
+        )
+        .with_latency(
+            MachineOp::Compare,
+            cfg.compare_latency_ticks
+                .unwrap_or(DEFAULT_PE_OP_LATENCY_TICKS),
+        );
+    Ok(Rc::new(cost_model))
+}
+
+fn build_scratchpad_config(cfg: &ScratchpadSection) -> ScratchpadConfig {
+    ScratchpadConfig::new(
+        cfg.base_address,
+        cfg.capacity_bytes as usize,
+        cfg.bw_bytes_per_cycle
+            .unwrap_or(DEFAULT_PE_SCRATCHPAD_BW_BYTES_PER_CYCLE),
+    )
+    .with_dma_channels(cfg.dma_channels.unwrap_or(DEFAULT_PE_DMA_CHANNELS))
+}
 
 fn build_pe_config(
     cfg: &ProcessingElementConfigSection,
@@ -85,6 +165,7 @@ fn build_pe_config(
     let compares_per_tick = cfg
         .compares_per_tick
         .unwrap_or(DEFAULT_PE_COMPARES_PER_TICK);
+    let cost_model = cfg.cost_model.as_ref().map(build_cost_model).transpose()?;
 
     Ok(ProcessingElementConfig {
         num_active_requests,
@@ -94,9 +175,52 @@ fn build_pe_config(
         adds_per_tick,
         muls_per_tick,
         compares_per_tick,
+        cost_model,
+        preemption_ticks: cfg.preemption_ticks,
+        scratchpad: cfg.scratchpad.as_ref().map(build_scratchpad_config),
     })
 }
 
+/// Returns the clock of a component, which is the platform `clock` unless the
+/// component sets its own `clock_mhz`
+pub(crate) fn component_clock(
+    engine: &Engine,
+    clock: &Clock,
+    name: &str,
+    clock_mhz: Option<f64>,
+) -> Result<Clock, SimError> {
+    match clock_mhz {
+        None => Ok(clock.clone()),
+        Some(mhz) if mhz.is_finite() && mhz > 0.0 => Ok(engine.executor.get_clock(mhz)),
+        Some(mhz) => sim_error!("Invalid clock_mhz {mhz} for '{name}' (must be greater than 0)"),
+    }
+}
+
+/// Build a PE, DMA engine or accelerator that uses the named memory map
+#[expect(clippy::too_many_arguments)]
+fn build_pe<S: BuildHasher>(
+    engine: &Engine,
+    clock: &Clock,
+    parent: &Rc<Entity>,
+    name: &str,
+    memory_map: &str,
+    pe_config: &ProcessingElementConfig,
+    clock_mhz: Option<f64>,
+    memory_maps: &HashMap<String, Rc<MemoryMap>, S>,
+    device_ids: &DeviceIds,
+) -> Result<Rc<ProcessingElement>, SimError> {
+    let memory_map = memory_maps
+        .get(memory_map)
+        .ok_or_else(|| SimError(format!("Unknown memory map '{memory_map}'")))?;
+    let device_id = *device_ids
+        .get(name)
+        .ok_or_else(|| SimError(format!("Unknown device '{name}'")))?;
+    let clock = &component_clock(engine, clock, name, clock_mhz)?;
+    ProcessingElement::new_and_register(
+        engine, clock, parent, name, memory_map, pe_config, device_id,
+    )
+}
+
 pub fn build_pes<S: BuildHasher>(
     engine: &Engine,
     clock: &Clock,
@@ -106,36 +230,98 @@ pub fn build_pes<S: BuildHasher>(
     device_ids: &DeviceIds,
 ) -> Result<(ProcessingElements, NameToIdxMap), SimError> {
     let mut processing_elements = Vec::new();
-    if let Some(pes) = &cfg.processing_elements {
-        for pe_section in pes {
-            let memory_map = memory_maps
-                .get(pe_section.memory_map.as_str())
-                .ok_or_else(|| {
-                    SimError(format!("Unknown memory map '{}'", pe_section.memory_map))
-                })?;
-            let device_id = *device_ids
-                .get(&pe_section.name)
-                .ok_or_else(|| SimError(format!("Unknown device '{}'", pe_section.name)))?;
-            let pe_config = build_pe_config(&pe_section.config)?;
-            processing_elements.push(ProcessingElement::new_and_register(
-                engine,
-                clock,
-                parent,
-                pe_section.name.as_str(),
-                memory_map,
-                &pe_config,
-                device_id,
-            )?);
-        }
-    }
-    let mut pes_idx_by_id = HashMap::new();
-    for (i, pe) in processing_elements.iter().enumerate() {
-        let name = pe.entity().name.to_string();
-        pes_idx_by_id.insert(name, i);
+    for pe_section in cfg.processing_elements.iter().flatten() {
+        processing_elements.push(build_pe(
+            engine,
+            clock,
+            parent,
+            &pe_section.name,
+            &pe_section.memory_map,
+            &build_pe_config(&pe_section.config)?,
+            pe_section.clock_mhz,
+            memory_maps,
+            device_ids,
+        )?);
     }
+    let pes_idx_by_id = processing_elements
+        .iter()
+        .enumerate()
+        .map(|(i, pe)| (pe.entity().name.to_string(), i))
+        .collect();
     Ok((processing_elements, pes_idx_by_id))
 }
 
+/// A DMA engine is a PE without any compute capability, so it can only
+/// perform memory and DMA tasks
+fn build_dma_engine_config(cfg: &DmaEngineConfigSection) -> ProcessingElementConfig {
+    ProcessingElementConfig {
+        num_active_requests: cfg
+            .num_active_requests
+            .unwrap_or(DEFAULT_PE_NUM_ACTIVE_REQUESTS),
+        lsu_access_bytes: cfg.lsu_access_bytes.unwrap_or(DEFAULT_PE_LSU_ACCESS_BYTES),
+        overhead_size_bytes: cfg
+            .overhead_size_bytes
+            .unwrap_or(DEFAULT_PE_OVERHEAD_SIZE_BYTES),
+        sram_bytes: 0,
+        adds_per_tick: 0.0,
+        muls_per_tick: 0.0,
+        compares_per_tick: 0.0,
+        cost_model: None,
+        preemption_ticks: None,
+        scratchpad: Some(build_scratchpad_config(&cfg.scratchpad)),
+    }
+}
+
+pub fn build_dma_engines<S: BuildHasher>(
+    engine: &Engine,
+    clock: &Clock,
+    parent: &Rc<Entity>,
+    cfg: &PlatformConfig,
+    memory_maps: &HashMap<String, Rc<MemoryMap>, S>,
+    device_ids: &DeviceIds,
+) -> Result<ProcessingElements, SimError> {
+    let mut dma_engines = Vec::new();
+    for dma_section in cfg.dma_engines.iter().flatten() {
+        dma_engines.push(build_pe(
+            engine,
+            clock,
+            parent,
+            &dma_section.name,
+            &dma_section.memory_map,
+            &build_dma_engine_config(&dma_section.config),
+            dma_section.clock_mhz,
+            memory_maps,
+            device_ids,
+        )?);
+    }
+    Ok(dma_engines)
+}
+
+pub fn build_accelerators<S: BuildHasher>(
+    engine: &Engine,
+    clock: &Clock,
+    parent: &Rc<Entity>,
+    cfg: &PlatformConfig,
+    memory_maps: &HashMap<String, Rc<MemoryMap>, S>,
+    device_ids: &DeviceIds,
+) -> Result<ProcessingElements, SimError> {
+    let mut accelerators = Vec::new();
+    for accelerator_section in cfg.accelerators.iter().flatten() {
+        accelerators.push(build_pe(
+            engine,
+            clock,
+            parent,
+            &accelerator_section.name,
+            &accelerator_section.memory_map,
+            &build_pe_config(&accelerator_section.config)?,
+            accelerator_section.clock_mhz,
+            memory_maps,
+            device_ids,
+        )?);
+    }
+    Ok(accelerators)
+}
+
 pub const DEFAULT_CACHE_LINE_SIZE_BYTES: usize = 32;
 pub const DEFAULT_CACHE_BW_BYTES_PER_CYCLE: usize = 32;
 pub const DEFAULT_CACHE_NUM_WAYS: usize = 4;
@@ -179,6 +365,8 @@ pub fn build_caches(
                 num_ways,
                 delay_ticks,
             );
+            let clock =
+                &component_clock(engine, clock, &cache_section.name, cache_section.clock_mhz)?;
             caches.push(Cache::new_and_register(
                 engine,
                 clock,
@@ -205,6 +393,7 @@ pub const DEFAULT_FABRIC_RX_BUFFER_BYTES: usize = 256;
 pub const DEFAULT_FABRIC_TX_BUFFER_BYTES: usize = 256;
 pub const DEFAULT_FABRIC_PORT_BITS_PER_TICK: usize = 32 * 8; // 32 bytes per cycle
 pub const DEFAULT_FABRIC_ROUTING: FabricRoutingAlgorithm = FabricRoutingAlgorithm::ColumnFirst;
+pub const DEFAULT_FABRIC_TOPOLOGY: FabricTopology = FabricTopology::Mesh;
 
 pub fn build_fabrics(
     engine: &Engine,
@@ -236,19 +425,29 @@ pub fn build_fabrics(
                 .port_bits_per_tick
                 .unwrap_or(DEFAULT_FABRIC_PORT_BITS_PER_TICK);
             let fabric_algorithm = fabric_section.routing.unwrap_or(DEFAULT_FABRIC_ROUTING);
+            let topology = fabric_section.topology.unwrap_or(DEFAULT_FABRIC_TOPOLOGY);
 
-            let config = Rc::new(FabricConfig::new(
-                fabric_columns,
-                fabric_rows,
-                fabric_ports_per_node,
-                None,
-                ticks_per_hop,
-                ticks_overhead,
-                rx_buffer_bytes,
-                tx_buffer_bytes,
-                port_bits_per_tick,
-            ));
+            let config = Rc::new(
+                FabricConfig::new(
+                    fabric_columns,
+                    fabric_rows,
+                    fabric_ports_per_node,
+                    None,
+                    ticks_per_hop,
+                    ticks_overhead,
+                    rx_buffer_bytes,
+                    tx_buffer_bytes,
+                    port_bits_per_tick,
+                )
+                .with_topology(topology),
+            );
 
+            let clock = &component_clock(
+                engine,
+                clock,
+                &fabric_section.name,
+                fabric_section.clock_mhz,
+            )?;
             let fabric: Rc<dyn Fabric<MemoryAccess>> = match fabric_section.kind {
                 FabricKind::Functional => FunctionalFabric::new_and_register(
                     engine,
@@ -279,6 +478,189 @@ pub fn build_fabrics(
     Ok((fabrics, fabrics_idx_by_id))
 }
 
+/// Build the custom devices of a configuration with the builders of their
+/// kinds in the `registry`
+pub fn build_devices(
+    engine: &Engine,
+    clock: &Clock,
+    parent: &Rc<Entity>,
+    cfg: &PlatformConfig,
+    device_ids: &DeviceIds,
+    registry: &DeviceRegistry,
+) -> Result<(Devices, NameToIdxMap), SimError> {
+    let mut devices = Vec::new();
+    for device_section in cfg.devices.iter().flatten() {
+        let Some(builder) = registry.builder(&device_section.kind) else {
+            return sim_error!(
+                "Unknown device kind '{}' for '{}' (registered kinds: {})",
+                device_section.kind,
+                device_section.name,
+                registry.kinds().join(", ")
+            );
+        };
+        let device_id = *device_ids
+            .get(&device_section.name)
+            .ok_or_else(|| SimError(format!("Unknown device '{}'", device_section.name)))?;
+        let clock = &component_clock(
+            engine,
+            clock,
+            &device_section.name,
+            device_section.clock_mhz,
+        )?;
+        let config = device_section.config.clone().unwrap_or_default();
+        let device = builder
+            .build(
+                engine,
+                clock,
+                parent,
+                &device_section.name,
+                device_id,
+                &config,
+            )
+            .map_err(|e| SimError(format!("Device '{}': {e}", device_section.name)))?;
+        devices.push(device);
+    }
+
+    let mut devices_idx_by_id = HashMap::new();
+    for (i, device) in devices.iter().enumerate() {
+        let name = device.entity().name.to_string();
+        devices_idx_by_id.insert(name, i);
+    }
+
+    Ok((devices, devices_idx_by_id))
+}
+
+/// Where a memory sits within an interleaved memory map
+struct InterleavedWay {
+    region_start: u64,
+    interleave: Interleave,
+    way: usize,
+}
+
+/// The largest access made through a memory map by the PEs, DMA engines and
+/// accelerators that use it
+fn max_access_bytes(cfg: &PlatformConfig, memory_map: &str) -> Option<usize> {
+    let pes = cfg
+        .processing_elements
+        .iter()
+        .flatten()
+        .filter(|pe| pe.memory_map == memory_map)
+        .map(|pe| pe.config.lsu_access_bytes);
+    let accelerators = cfg
+        .accelerators
+        .iter()
+        .flatten()
+        .filter(|accelerator| accelerator.memory_map == memory_map)
+        .map(|accelerator| accelerator.config.lsu_access_bytes);
+    let dma_engines = cfg
+        .dma_engines
+        .iter()
+        .flatten()
+        .filter(|dma_engine| dma_engine.memory_map == memory_map)
+        .map(|dma_engine| dma_engine.config.lsu_access_bytes);
+    pes.chain(accelerators)
+        .chain(dma_engines)
+        .map(|bytes| bytes.unwrap_or(DEFAULT_PE_LSU_ACCESS_BYTES))
+        .max()
+}
+
+/// Determine which memories are interleaved and check that the memories of
+/// each interleaved memory map are contiguous and of equal capacity, and that
+/// the accesses made through it cannot cross a granule
+fn find_interleaved_ways(
+    cfg: &PlatformConfig,
+) -> Result<HashMap<String, InterleavedWay>, SimError> {
+    let mut interleaved_ways = HashMap::new();
+    let memory_sections: HashMap<&str, &MemorySection> = cfg
+        .memories
+        .iter()
+        .flatten()
+        .map(|memory| (memory.name.as_str(), memory))
+        .collect();
+
+    for memory_map in &cfg.memory_maps {
+        let Some(interleave_section) = &memory_map.interleave else {
+            continue;
+        };
+
+        let mut sections = Vec::new();
+        for device in &memory_map.devices {
+            let section = memory_sections
+                .get(device.name.as_str())
+                .ok_or_else(|| SimError(format!("Unknown memory '{}'", device.name)))?;
+            sections.push(*section);
+        }
+        let Some(first) = sections.first() else {
+            continue;
+        };
+
+        let granule_bytes = interleave_section.granule_bytes;
+        if granule_bytes == 0 {
+            return sim_error!(
+                "Memory map '{}': invalid interleave granule size 0",
+                memory_map.name
+            );
+        }
+        if !first.base_address.is_multiple_of(granule_bytes) {
+            return sim_error!(
+                "Memory map '{}': interleaved memory '{}' start 0x{:x} is not aligned to granules of {granule_bytes} bytes",
+                memory_map.name,
+                first.name,
+                first.base_address
+            );
+        }
+        if let Some(access_bytes) = max_access_bytes(cfg, &memory_map.name)
+            && !granule_bytes.is_multiple_of(access_bytes as u64)
+        {
+            return sim_error!(
+                "Memory map '{}': interleave granule of {granule_bytes} bytes is not a multiple of the {access_bytes} byte accesses made through it",
+                memory_map.name
+            );
+        }
+
+        let interleave = Interleave::new(
+            granule_bytes,
+            sections.len(),
+            interleave_section.hash.unwrap_or_default(),
+        );
+        for (way, section) in sections.iter().enumerate() {
+            if section.capacity_bytes != first.capacity_bytes {
+                return sim_error!(
+                    "Memory map '{}': interleaved memory '{}' capacity differs from '{}'",
+                    memory_map.name,
+                    section.name,
+                    first.name
+                );
+            }
+            let expected_base = first.base_address + way as u64 * first.capacity_bytes;
+            if section.base_address != expected_base {
+                return sim_error!(
+                    "Memory map '{}': interleaved memory '{}' should start at 0x{expected_base:x}",
+                    memory_map.name,
+                    section.name
+                );
+            }
+
+            let interleaved_way = InterleavedWay {
+                region_start: first.base_address,
+                interleave,
+                way,
+            };
+            if let Some(existing) = interleaved_ways.insert(section.name.clone(), interleaved_way)
+                && (existing.region_start != first.base_address
+                    || existing.interleave != interleave
+                    || existing.way != way)
+            {
+                return sim_error!(
+                    "Memory '{}' is interleaved differently by multiple memory maps",
+                    section.name
+                );
+            }
+        }
+    }
+    Ok(interleaved_ways)
+}
+
 pub const DEFAULT_HBM_DELAY_TICKS: usize = 10;
 pub const DEFAULT_HBM_BW_BYTES_PER_CYCLE: usize = 32;
 pub const DEFAULT_HBM_SIZE_BYTES: usize = 1024 * 1024 * 1024;
@@ -290,6 +672,7 @@ pub fn build_memories(
     cfg: &PlatformConfig,
 ) -> Result<(Memories, NameToIdxMap), SimError> {
     let mut memories = Vec::new();
+    let interleaved_ways = find_interleaved_ways(cfg)?;
     if let Some(memories_section) = &cfg.memories {
         for memory_section in memories_section {
             let base_address = memory_section.base_address;
@@ -300,12 +683,25 @@ pub fn build_memories(
             let delay_ticks = memory_section
                 .delay_ticks
                 .unwrap_or(DEFAULT_HBM_DELAY_TICKS);
-            let config = MemoryConfig::new(
+            let mut config = MemoryConfig::new(
                 base_address,
                 capacity_bytes,
                 bw_bytes_per_cycle,
                 delay_ticks,
             );
+            if let Some(interleaved) = interleaved_ways.get(&memory_section.name) {
+                config = config.with_interleave(
+                    interleaved.region_start,
+                    interleaved.interleave,
+                    interleaved.way,
+                );
+            }
+            let clock = &component_clock(
+                engine,
+                clock,
+                &memory_section.name,
+                memory_section.clock_mhz,
+            )?;
             memories.push(Memory::new_and_register(
                 engine,
                 clock,
@@ -333,7 +729,8 @@ mod tests {
     use super::{build_memories, build_memory_maps};
     use crate::DeviceIds;
     use crate::types::{
-        MemoryDeviceSection, MemoryKind, MemoryMapSection, MemorySection, PlatformConfig,
+        InterleaveSection, MemoryDeviceSection, MemoryKind, MemoryMapSection, MemorySection,
+        PlatformConfig,
     };
 
     #[test]
@@ -341,14 +738,18 @@ mod tests {
         let mut engine = start_test(file!());
         let clock = engine.default_clock();
         let cfg = PlatformConfig {
+            includes: None,
             memory_maps: vec![MemoryMapSection {
                 name: "mm0".to_string(),
                 devices: vec![MemoryDeviceSection {
                     name: "hbm0".to_string(),
                 }],
+                interleave: None,
             }],
             defaults: None,
             processing_elements: None,
+            dma_engines: None,
+            accelerators: None,
             caches: None,
             fabrics: None,
             memories: Some(vec![MemorySection {
@@ -358,8 +759,14 @@ mod tests {
                 capacity_bytes: 0x2000,
                 bw_bytes_per_cycle: None,
                 delay_ticks: None,
+                clock_mhz: None,
             }]),
             connections: None,
+            devices: None,
+            topologies: None,
+            groups: None,
+            group_links: None,
+            power_domains: None,
         };
         let device_ids = DeviceIds::from([("hbm0".to_string(), DeviceId(7))]);
         let (memories, memories_idx_by_id) = build_memories(&engine, &clock, engine.top(), &cfg)
@@ -374,4 +781,67 @@ mod tests {
         assert_eq!(memory_map.lookup(0x5fff), Some((DeviceId(7), 0x1fff)));
         assert_eq!(memory_map.lookup(0x6000), None);
     }
+
+    #[test]
+    fn builds_interleaved_memory_maps() {
+        let mut engine = start_test(file!());
+        let clock = engine.default_clock();
+        let memory = |name: &str, base_address| MemorySection {
+            name: name.to_string(),
+            kind: MemoryKind::HBM,
+            base_address,
+            capacity_bytes: 0x1000,
+            bw_bytes_per_cycle: None,
+            delay_ticks: None,
+            clock_mhz: None,
+        };
+        let cfg = PlatformConfig {
+            includes: None,
+            memory_maps: vec![MemoryMapSection {
+                name: "mm0".to_string(),
+                devices: vec![
+                    MemoryDeviceSection {
+                        name: "hbm0".to_string(),
+                    },
+                    MemoryDeviceSection {
+                        name: "hbm1".to_string(),
+                    },
+                ],
+                interleave: Some(InterleaveSection {
+                    granule_bytes: 0x100,
+                    hash: None,
+                }),
+            }],
+            defaults: None,
+            processing_elements: None,
+            dma_engines: None,
+            accelerators: None,
+            caches: None,
+            fabrics: None,
+            memories: Some(vec![memory("hbm0", 0x4000), memory("hbm1", 0x5000)]),
+            connections: None,
+            devices: None,
+            topologies: None,
+            groups: None,
+            group_links: None,
+            power_domains: None,
+        };
+        let device_ids = DeviceIds::from([
+            ("hbm0".to_string(), DeviceId(0)),
+            ("hbm1".to_string(), DeviceId(1)),
+        ]);
+        let (memories, memories_idx_by_id) = build_memories(&engine, &clock, engine.top(), &cfg)
+            .expect("memory build should succeed");
+
+        let memory_maps = build_memory_maps(&cfg, &memories, &memories_idx_by_id, &device_ids)
+            .expect("memory maps should build");
+        let memory_map = memory_maps.get("mm0").expect("memory map should exist");
+
+        assert_eq!(memory_map.num_regions(), 1);
+        assert_eq!(memory_map.lookup(0x4000), Some((DeviceId(0), 0)));
+        assert_eq!(memory_map.lookup(0x4100), Some((DeviceId(1), 0)));
+        assert_eq!(memory_map.lookup(0x4200), Some((DeviceId(0), 0x100)));
+        assert_eq!(memory_map.lookup(0x5fff), Some((DeviceId(1), 0xfff)));
+        assert_eq!(memory_map.lookup(0x6000), None);
+    }
 }
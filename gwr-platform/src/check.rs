@@ -0,0 +1,204 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Checks of a built [Platform] that find problems which would otherwise only
+//! appear once a simulation is run:
+//!  - PEs, DMA engines, accelerators, memories, devices or cache ports that
+//!    are not connected.
+//!  - memories in the memory map of a PE, DMA engine or accelerator that cannot
+//!    be reached from it.
+//!  - PEs, memories and devices attached to a fabric port that differs from
+//!    their device ID. Fabrics route by device ID, so the two must match.
+
+use std::collections::{HashMap, HashSet};
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimResult;
+use gwr_track::entity::GetEntity;
+
+use crate::Platform;
+use crate::connect::{Component, Endpoint};
+
+/// Returns the `dev` or `mem` port of a cache that is connected to `other`,
+/// using the same defaults as when the connection is made
+fn cache_port<'a>(cache: &'a Endpoint, other: &Endpoint, cache_is_from: bool) -> &'a str {
+    if let Some(port) = &cache.port {
+        return port;
+    }
+    match other.component {
+        Component::Pe => "dev",
+        Component::Cache if cache_is_from => "mem",
+        Component::Cache => "dev",
+        Component::Memory | Component::Device | Component::Fabric { .. } => "mem",
+    }
+}
+
+impl Platform {
+    /// Check the platform for problems that would otherwise only be found
+    /// when it is run, returning an error that lists all of them.
+    ///
+    /// This does not run the engine, so can be used to quickly validate
+    /// platform configurations.
+    pub fn check(&self) -> SimResult {
+        let connected = self.connected_endpoints();
+        let mut cache_ports: HashSet<(String, &str)> = HashSet::new();
+        for (from, to) in &self.connections {
+            if from.component == Component::Cache {
+                cache_ports.insert((from.entity.full_name(), cache_port(from, to, true)));
+            }
+            if to.component == Component::Cache {
+                cache_ports.insert((to.entity.full_name(), cache_port(to, from, false)));
+            }
+        }
+
+        let mut problems = Vec::new();
+        for pe in &self.processing_elements {
+            if !connected.contains_key(&pe.entity().full_name()) {
+                let name = &pe.entity().name;
+                problems.push(format!("{} '{name}' is not connected", self.pe_kind(name)));
+            }
+        }
+        for memory in &self.memories {
+            if !connected.contains_key(&memory.entity().full_name()) {
+                problems.push(format!(
+                    "Memory '{}' is not connected",
+                    memory.entity().name
+                ));
+            }
+        }
+        for device in &self.devices {
+            if !connected.contains_key(&device.entity().full_name()) {
+                problems.push(format!(
+                    "Device '{}' is not connected",
+                    device.entity().name
+                ));
+            }
+        }
+        for cache in &self.caches {
+            for port in ["dev", "mem"] {
+                if !cache_ports.contains(&(cache.entity().full_name(), port)) {
+                    problems.push(format!(
+                        "Cache '{}' port '{port}' is not connected",
+                        cache.entity().name
+                    ));
+                }
+            }
+        }
+
+        problems.extend(self.unreachable_memories(&connected));
+        self.check_device_ids(&connected, &mut problems);
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+        problems.sort();
+        sim_error!("Invalid platform:\n  {}", problems.join("\n  "))
+    }
+
+    /// Returns the endpoints connected to each component, by full name
+    pub(crate) fn connected_endpoints(&self) -> HashMap<String, Vec<&Endpoint>> {
+        let mut connected: HashMap<String, Vec<&Endpoint>> = HashMap::new();
+        for (from, to) in &self.connections {
+            connected
+                .entry(from.entity.full_name())
+                .or_default()
+                .push(to);
+            connected
+                .entry(to.entity.full_name())
+                .or_default()
+                .push(from);
+        }
+        connected
+    }
+
+    /// Returns a problem for every memory in the memory map of a PE that
+    /// cannot be reached from the PE through caches and fabrics
+    pub(crate) fn unreachable_memories(
+        &self,
+        connected: &HashMap<String, Vec<&Endpoint>>,
+    ) -> Vec<String> {
+        let mut problems = Vec::new();
+        for pe in &self.processing_elements {
+            let pe_name = &pe.entity().name;
+            let Ok(address_map) = self.pe_address_map(pe_name) else {
+                continue;
+            };
+
+            let mut reachable = HashSet::new();
+            let mut visited = HashSet::from([pe.entity().full_name()]);
+            let mut to_visit = vec![pe.entity().full_name()];
+            while let Some(name) = to_visit.pop() {
+                for endpoint in connected.get(&name).into_iter().flatten() {
+                    match endpoint.component {
+                        Component::Pe | Component::Device => {}
+                        Component::Memory => {
+                            reachable.insert(endpoint.entity.name.clone());
+                        }
+                        Component::Cache | Component::Fabric { .. } => {
+                            if visited.insert(endpoint.entity.full_name()) {
+                                to_visit.push(endpoint.entity.full_name());
+                            }
+                        }
+                    }
+                }
+            }
+
+            let memory_names = address_map
+                .regions()
+                .iter()
+                .flat_map(|region| region.devices().iter().map(|(name, _)| name));
+            for memory_name in memory_names {
+                if !reachable.contains(memory_name) {
+                    problems.push(format!(
+                        "{} '{pe_name}' cannot reach memory '{memory_name}' in its memory map",
+                        self.pe_kind(pe_name)
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    /// Check that the PEs and memories attached to fabrics, either directly or
+    /// through caches, are on the fabric port with the index of their device ID
+    fn check_device_ids(
+        &self,
+        connected: &HashMap<String, Vec<&Endpoint>>,
+        problems: &mut Vec<String>,
+    ) {
+        let devices = self
+            .processing_elements
+            .iter()
+            .map(|pe| pe.entity())
+            .chain(self.memories.iter().map(|memory| memory.entity()))
+            .chain(self.devices.iter().map(|device| device.entity()));
+
+        for entity in devices {
+            let Some(device_id) = self.device_ids.get(&entity.name) else {
+                continue;
+            };
+
+            let mut visited = HashSet::from([entity.full_name()]);
+            let mut to_visit = vec![entity.full_name()];
+            while let Some(name) = to_visit.pop() {
+                for endpoint in connected.get(&name).into_iter().flatten() {
+                    match endpoint.component {
+                        Component::Pe | Component::Memory | Component::Device => {}
+                        Component::Cache => {
+                            if visited.insert(endpoint.entity.full_name()) {
+                                to_visit.push(endpoint.entity.full_name());
+                            }
+                        }
+                        Component::Fabric { port_idx } => {
+                            if port_idx as u64 != device_id.0 {
+                                problems.push(format!(
+                                    "'{}' has device ID {} but is on port {port_idx} of fabric '{}'",
+                                    entity.name, device_id.0, endpoint.entity.name
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,15 @@
 // Copyright (c) 2026 Graphcore Ltd. All rights reserved.
 
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
-use std::str::Split;
 use std::sync::LazyLock;
 
+use gwr_components::delay::Delay;
+use gwr_components::flow_controls::limiter::Limiter;
+use gwr_components::rc_limiter;
+use gwr_engine::engine::Engine;
 use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
 use gwr_engine::types::{SimError, SimResult};
 use gwr_models::fabric::Fabric;
 use gwr_models::memory::Memory;
@@ -12,10 +17,12 @@ use gwr_models::memory::cache::Cache;
 use gwr_models::memory::memory_access::MemoryAccess;
 use gwr_models::processing_element::ProcessingElement;
 use gwr_track::debug;
-use gwr_track::entity::GetEntity;
+use gwr_track::entity::{Entity, GetEntity};
 use regex::Regex;
 
 use crate::Platform;
+use crate::builder::{DEFAULT_FABRIC_PORTS_PER_NODE, component_clock};
+use crate::device::PlatformDevice;
 use crate::types::PlatformConfig;
 
 pub enum PortId<'a> {
@@ -29,20 +36,180 @@ pub enum PortId<'a> {
     Mem {
         memory: &'a Rc<Memory<MemoryAccess>>,
     },
+    Device {
+        device: &'a Rc<dyn PlatformDevice>,
+    },
     FabricTile {
         fabric: &'a Rc<dyn Fabric<MemoryAccess>>,
         port_idx: usize,
+        col: usize,
+        row: usize,
+        port: usize,
     },
 }
 
+impl PortId<'_> {
+    fn endpoint(&self) -> Endpoint {
+        match self {
+            PortId::Pe { pe } => Endpoint::new(pe.entity(), Component::Pe, None),
+            PortId::Cache { cache, port } => {
+                Endpoint::new(cache.entity(), Component::Cache, port.map(str::to_string))
+            }
+            PortId::Mem { memory } => Endpoint::new(memory.entity(), Component::Memory, None),
+            PortId::Device { device } => Endpoint::new(device.entity(), Component::Device, None),
+            PortId::FabricTile {
+                fabric,
+                port_idx,
+                col,
+                row,
+                port,
+            } => Endpoint::new(
+                fabric.entity(),
+                Component::Fabric {
+                    port_idx: *port_idx,
+                },
+                Some(format!("({col},{row}).{port}")),
+            ),
+        }
+    }
+}
+
+/// The kind of component at one end of a connection
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Component {
+    Pe,
+    Cache,
+    Memory,
+    Device,
+    Fabric { port_idx: usize },
+}
+
+/// One end of a connection made between the components of a platform
+#[derive(Clone, Debug)]
+pub struct Endpoint {
+    pub(crate) entity: Rc<Entity>,
+    pub(crate) component: Component,
+    pub(crate) port: Option<String>,
+}
+
+impl Endpoint {
+    fn new(entity: &Rc<Entity>, component: Component, port: Option<String>) -> Self {
+        Self {
+            entity: entity.clone(),
+            component,
+            port,
+        }
+    }
+}
+
+/// Matches a Fabric port ID of the form:
+///   fabric.name@(col,row)[.port]
+pub(crate) static FABRIC_PORT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^fabric\.([A-Za-z0-9_.]+)@\((\d+),(\d+)\)(?:\.(.*))?$").unwrap());
+
+/// A component (as `kind.name`) or fabric port in the connections of a
+/// configuration
+#[derive(Clone)]
+enum ConfigNode {
+    Component(String),
+    FabricPort(usize),
+}
+
+/// Returns the fabric port that each PE, memory and device of a configuration
+/// is attached to, either directly or through caches, as the index of the port
+/// in its fabric. Connections that cannot be parsed are ignored as they are
+/// reported when the connections are made.
+pub(crate) fn fabric_port_indices(cfg: &PlatformConfig) -> HashMap<String, usize> {
+    let fabric_port_index = |s: &str| {
+        let caps = FABRIC_PORT_RE.captures(s)?;
+        let fabric = cfg.fabrics.iter().flatten().find(|f| f.name == caps[1])?;
+        let col: usize = caps[2].parse().ok()?;
+        let row: usize = caps[3].parse().ok()?;
+        let port: usize = caps.get(4).map_or(Some(0), |m| m.as_str().parse().ok())?;
+        let ports_per_node = fabric
+            .fabric_ports_per_node
+            .unwrap_or(DEFAULT_FABRIC_PORTS_PER_NODE);
+        Some(port + row * ports_per_node + col * fabric.rows * ports_per_node)
+    };
+    let is_cache = |name: &str| cfg.caches.iter().flatten().any(|c| c.name == name);
+
+    // The component names (as `kind.name`) and fabric ports that are connected
+    // to each component
+    let mut connected: HashMap<String, Vec<ConfigNode>> = HashMap::new();
+    for c in cfg.connections.iter().flatten() {
+        let [from, to] = c.connect.as_slice() else {
+            continue;
+        };
+        let node = |s: &str| {
+            if let Some(idx) = fabric_port_index(s) {
+                return Some(ConfigNode::FabricPort(idx));
+            }
+            match s.split_once('.')? {
+                ("cache", rest) => Some(ConfigNode::Component(format!(
+                    "cache.{}",
+                    split_name_and_port(rest, is_cache).0
+                ))),
+                _ => Some(ConfigNode::Component(s.to_string())),
+            }
+        };
+        let (Some(from), Some(to)) = (node(from), node(to)) else {
+            continue;
+        };
+        for (a, b) in [(&from, &to), (&to, &from)] {
+            if let ConfigNode::Component(a) = a {
+                connected.entry(a.clone()).or_default().push(b.clone());
+            }
+        }
+    }
+
+    let devices = cfg
+        .processing_elements
+        .iter()
+        .flatten()
+        .map(|pe| ("pe", &pe.name))
+        .chain(
+            cfg.dma_engines
+                .iter()
+                .flatten()
+                .map(|dma| ("dma", &dma.name)),
+        )
+        .chain(
+            cfg.accelerators
+                .iter()
+                .flatten()
+                .map(|acc| ("acc", &acc.name)),
+        )
+        .chain(cfg.memories.iter().flatten().map(|mem| ("mem", &mem.name)))
+        .chain(cfg.devices.iter().flatten().map(|dev| ("dev", &dev.name)));
+
+    let mut port_indices = HashMap::new();
+    for (kind, name) in devices {
+        let mut visited = HashSet::from([format!("{kind}.{name}")]);
+        let mut to_visit = vec![format!("{kind}.{name}")];
+        'search: while let Some(node) = to_visit.pop() {
+            for other in connected.get(&node).into_iter().flatten() {
+                match other {
+                    ConfigNode::FabricPort(idx) => {
+                        port_indices.insert(name.clone(), *idx);
+                        break 'search;
+                    }
+                    ConfigNode::Component(other) if other.starts_with("cache.") => {
+                        if visited.insert(other.clone()) {
+                            to_visit.push(other.clone());
+                        }
+                    }
+                    ConfigNode::Component(_) => {}
+                }
+            }
+        }
+    }
+    port_indices
+}
+
 /// Parse a Fabric port ID of the form:
 ///   fabric.name@(col,row)[.port]
 fn parse_fabric_port_id<'a>(platform: &'a Platform, s: &'a str) -> Result<PortId<'a>, SimError> {
-    static FABRIC_RE: LazyLock<Regex> = LazyLock::new(|| {
-        Regex::new(r"^fabric\.([A-Za-z0-9_]+)@\((\d+),(\d+)\)(?:\.(.*))?$").unwrap()
-    });
-
-    if let Some(caps) = FABRIC_RE.captures(s) {
+    if let Some(caps) = FABRIC_PORT_RE.captures(s) {
         let name = &caps[1];
         let col = caps[2].parse().map_err(|e| SimError(format!("{e}")))?;
         let row = caps[3].parse().map_err(|e| SimError(format!("{e}")))?;
@@ -56,61 +223,93 @@ fn parse_fabric_port_id<'a>(platform: &'a Platform, s: &'a str) -> Result<PortId
 
         let fabric = platform.fabric(name)?;
         let port_idx = fabric.col_row_port_to_fabric_port_index(col, row, port);
-        Ok(PortId::FabricTile { fabric, port_idx })
+        Ok(PortId::FabricTile {
+            fabric,
+            port_idx,
+            col,
+            row,
+            port,
+        })
     } else {
         sim_error!("Unable to parse Fabric port '{s}'")
     }
 }
 
-pub fn parse_port_id<'a>(
-    platform: &'a Platform,
-    s: &'a str,
-) -> Result<(PortId<'a>, Split<'a, char>), SimError> {
-    let mut parts = s.split('.');
-    let kind = parts
-        .next()
-        .ok_or_else(|| SimError(format!("Failed to parse kind in '{s}'")))?;
+/// Split `name[.port]` into a name and optional port. The names of the
+/// components of included platforms contain dots, so the whole string is used
+/// as the name if `is_name` accepts it.
+pub(crate) fn split_name_and_port(s: &str, is_name: impl Fn(&str) -> bool) -> (&str, Option<&str>) {
+    if is_name(s) {
+        return (s, None);
+    }
+    match s.rsplit_once('.') {
+        Some((name, port)) => (name, Some(port)),
+        None => (s, None),
+    }
+}
+
+pub fn parse_port_id<'a>(platform: &'a Platform, s: &'a str) -> Result<PortId<'a>, SimError> {
+    let Some((kind, rest)) = s.split_once('.') else {
+        return sim_error!("Failed to parse name in '{s}'");
+    };
 
     if kind == "fabric" {
-        return Ok((parse_fabric_port_id(platform, s)?, parts));
+        return parse_fabric_port_id(platform, s);
     }
 
     // Parse ports IDs of the form: kind.name[.port]
-    let name = parts
-        .next()
-        .ok_or_else(|| SimError(format!("Failed to parse name in '{s}'")))?;
-    let port = parts.next();
-    if parts.next().is_some() {
-        return sim_error!("Failed to parse '{s}' - extra tokens");
-    }
-
-    Ok((
-        match kind {
-            "pe" => {
-                let pe = match port {
-                    Some(_) => return sim_error!("Cannot specify a port for PE"),
-                    None => platform.pe(name)?,
-                };
-                PortId::Pe { pe }
-            }
-            "cache" => {
-                let cache = platform.cache(name)?;
-                PortId::Cache { cache, port }
-            }
-            "mem" => {
-                let memory = match port {
-                    Some(_) => return sim_error!("Cannot specify a port for Memory"),
-                    None => platform.memory(name)?,
-                };
-                PortId::Mem { memory }
-            }
-            _ => return sim_error!("Failed to parse '{s}' - unsupported kind"),
-        },
-        parts,
-    ))
+    Ok(match kind {
+        "pe" => {
+            let pe = match split_name_and_port(rest, |name| platform.pe(name).is_ok()) {
+                (_, Some(_)) => return sim_error!("Cannot specify a port for PE"),
+                (name, None) => platform.pe(name)?,
+            };
+            PortId::Pe { pe }
+        }
+        "dma" => {
+            let pe = match split_name_and_port(rest, |name| platform.dma_engine(name).is_ok()) {
+                (_, Some(_)) => return sim_error!("Cannot specify a port for DMA engine"),
+                (name, None) => platform.dma_engine(name)?,
+            };
+            PortId::Pe { pe }
+        }
+        "acc" => {
+            let pe = match split_name_and_port(rest, |name| platform.accelerator(name).is_ok()) {
+                (_, Some(_)) => return sim_error!("Cannot specify a port for accelerator"),
+                (name, None) => platform.accelerator(name)?,
+            };
+            PortId::Pe { pe }
+        }
+        "cache" => {
+            let (name, port) = split_name_and_port(rest, |name| platform.cache(name).is_ok());
+            let cache = platform.cache(name)?;
+            PortId::Cache { cache, port }
+        }
+        "mem" => {
+            let memory = match split_name_and_port(rest, |name| platform.memory(name).is_ok()) {
+                (_, Some(_)) => return sim_error!("Cannot specify a port for Memory"),
+                (name, None) => platform.memory(name)?,
+            };
+            PortId::Mem { memory }
+        }
+        "dev" => {
+            let device = match split_name_and_port(rest, |name| platform.device(name).is_ok()) {
+                (_, Some(_)) => return sim_error!("Cannot specify a port for Device"),
+                (name, None) => platform.device(name)?,
+            };
+            PortId::Device { device }
+        }
+        _ => return sim_error!("Failed to parse '{s}' - unsupported kind"),
+    })
 }
 
-pub fn connect_ports(platform: &Platform, cfg: &PlatformConfig) -> SimResult {
+/// Make the connections of a configuration, returning the endpoints of each
+/// connection made
+pub fn connect_ports(
+    platform: &Platform,
+    cfg: &PlatformConfig,
+) -> Result<Vec<(Endpoint, Endpoint)>, SimError> {
+    let mut connected = Vec::new();
     if let Some(connections) = &cfg.connections {
         for c in connections {
             if c.connect.len() != 2 {
@@ -120,22 +319,100 @@ pub fn connect_ports(platform: &Platform, cfg: &PlatformConfig) -> SimResult {
                 );
             }
 
-            let (from, _) = parse_port_id(platform, &c.connect[0])?;
-            let (to, _) = parse_port_id(platform, &c.connect[1])?;
+            let from = parse_port_id(platform, &c.connect[0])?;
+            let to = parse_port_id(platform, &c.connect[1])?;
             connect_port(platform, &from, &to)?;
+            connected.push((from.endpoint(), to.endpoint()));
+        }
+    }
+    Ok(connected)
+}
+
+/// Make the links between the fabrics of groups, returning the endpoints of
+/// each link made. Each direction of a link is a [Limiter] followed by a
+/// [Delay].
+pub fn connect_group_links(
+    engine: &Engine,
+    clock: &Clock,
+    platform: &Platform,
+    cfg: &PlatformConfig,
+) -> Result<Vec<(Endpoint, Endpoint)>, SimError> {
+    let mut connected = Vec::new();
+    for (i, (link, section)) in platform
+        .group_links
+        .iter()
+        .zip(cfg.group_links.iter().flatten())
+        .enumerate()
+    {
+        let [from_group, to_group] = link.groups();
+        let name = format!("group_link{i}_{from_group}_{to_group}");
+        let clock = &component_clock(engine, clock, &name, section.clock_mhz)?;
+        let bits_per_tick = link.bits_per_tick();
+        let delay_ticks = link.delay_ticks();
+
+        let [from, to] = link.endpoints();
+        let (from, to) = (parse_port_id(platform, from)?, parse_port_id(platform, to)?);
+        let (
+            PortId::FabricTile {
+                fabric: from_fabric,
+                port_idx: from_port_idx,
+                ..
+            },
+            PortId::FabricTile {
+                fabric: to_fabric,
+                port_idx: to_port_idx,
+                ..
+            },
+        ) = (&from, &to)
+        else {
+            return sim_error!("Group links can only connect Fabric ports");
+        };
+
+        for (direction, (tx_fabric, tx_port_idx), (rx_fabric, rx_port_idx)) in [
+            (
+                format!("{from_group}_to_{to_group}"),
+                (from_fabric, *from_port_idx),
+                (to_fabric, *to_port_idx),
+            ),
+            (
+                format!("{to_group}_to_{from_group}"),
+                (to_fabric, *to_port_idx),
+                (from_fabric, *from_port_idx),
+            ),
+        ] {
+            let limiter = Limiter::new_and_register(
+                engine,
+                clock,
+                platform.entity(),
+                &format!("group_link{i}_{direction}_limiter"),
+                rc_limiter!(clock, bits_per_tick),
+            );
+            let delay = Delay::new_and_register(
+                engine,
+                clock,
+                platform.entity(),
+                &format!("group_link{i}_{direction}_delay"),
+                delay_ticks,
+            );
+            debug!(platform.entity() ; "Connect {}.{} to {}.{} with {} tick delay and {} bits per tick", tx_fabric, tx_port_idx, rx_fabric, rx_port_idx, delay_ticks, bits_per_tick);
+            tx_fabric.connect_port_egress_i(tx_port_idx, limiter.port_rx())?;
+            limiter.connect_port_tx(delay.port_rx())?;
+            delay.connect_port_tx(rx_fabric.port_ingress_i(rx_port_idx))?;
         }
+        connected.push((from.endpoint(), to.endpoint()));
     }
-    Ok(())
+    Ok(connected)
 }
 
 fn connect_port(platform: &Platform, from: &PortId, to: &PortId) -> SimResult {
     match from {
         PortId::Pe { pe } => connect_pe_to(platform, pe, to),
         PortId::Cache { cache, port } => connect_cache_to(platform, cache, *port, to),
-        PortId::FabricTile { fabric, port_idx } => {
-            connect_fabric_to(platform, fabric, *port_idx, to)
-        }
+        PortId::FabricTile {
+            fabric, port_idx, ..
+        } => connect_fabric_to(platform, fabric, *port_idx, to),
         PortId::Mem { memory } => connect_memory_to(platform, memory, to),
+        PortId::Device { device } => connect_device_to(platform, device, to),
     }
 }
 
@@ -145,10 +422,11 @@ fn connect_pe_to(platform: &Platform, pe: &Rc<ProcessingElement>, to: &PortId) -
             sim_error!("Cannot connect a PE directly to a PE")
         }
         PortId::Cache { cache, port } => connect_pe_to_cache(platform, pe, cache, *port),
-        PortId::FabricTile { fabric, port_idx } => {
-            connect_pe_to_fabric(platform, pe, fabric, *port_idx)
-        }
+        PortId::FabricTile {
+            fabric, port_idx, ..
+        } => connect_pe_to_fabric(platform, pe, fabric, *port_idx),
         PortId::Mem { memory } => connect_pe_to_memory(platform, pe, memory),
+        PortId::Device { device } => connect_device_to(platform, device, &PortId::Pe { pe }),
     }
 }
 
@@ -164,10 +442,18 @@ fn connect_cache_to(
             cache: to_cache,
             port,
         } => connect_cache_to_cache(platform, cache, cache_port, to_cache, *port),
-        PortId::FabricTile { fabric, port_idx } => {
-            connect_cache_to_fabric(platform, cache, cache_port, fabric, *port_idx)
-        }
+        PortId::FabricTile {
+            fabric, port_idx, ..
+        } => connect_cache_to_fabric(platform, cache, cache_port, fabric, *port_idx),
         PortId::Mem { memory } => connect_cache_to_memory(platform, cache, cache_port, memory),
+        PortId::Device { device } => connect_device_to(
+            platform,
+            device,
+            &PortId::Cache {
+                cache,
+                port: cache_port,
+            },
+        ),
     }
 }
 
@@ -185,10 +471,14 @@ fn connect_fabric_to(
         PortId::FabricTile {
             fabric: to_fabric,
             port_idx: to_port_idx,
+            ..
         } => connect_fabric_to_fabric(platform, fabric, fabric_port_idx, to_fabric, *to_port_idx),
         PortId::Mem { memory } => {
             connect_memory_to_fabric(platform, memory, fabric, fabric_port_idx)
         }
+        PortId::Device { device } => {
+            connect_device_to_fabric(platform, device, fabric, fabric_port_idx)
+        }
     }
 }
 
@@ -200,15 +490,69 @@ fn connect_memory_to(
     match to {
         PortId::Pe { pe } => connect_pe_to_memory(platform, pe, memory),
         PortId::Cache { cache, port } => connect_cache_to_memory(platform, cache, *port, memory),
-        PortId::FabricTile { fabric, port_idx } => {
-            connect_memory_to_fabric(platform, memory, fabric, *port_idx)
-        }
+        PortId::FabricTile {
+            fabric, port_idx, ..
+        } => connect_memory_to_fabric(platform, memory, fabric, *port_idx),
         PortId::Mem { .. } => {
             sim_error!("Cannot connect a Memory directly to a Memory")
         }
+        PortId::Device { device } => connect_device_to(platform, device, &PortId::Mem { memory }),
+    }
+}
+
+/// Devices send and receive memory accesses, so are connected in both
+/// directions to whatever they are connected to
+fn connect_device_to(
+    platform: &Platform,
+    device: &Rc<dyn PlatformDevice>,
+    to: &PortId,
+) -> SimResult {
+    match to {
+        PortId::Pe { pe } => {
+            debug!(platform.entity() ; "Connect {} to {}", device.entity(), pe);
+            device.connect_port_tx(pe.port_rx())?;
+            pe.connect_port_tx(device.port_rx())
+        }
+        PortId::Cache { cache, port } => match port {
+            Some("dev") => {
+                debug!(platform.entity() ; "Connect {} to {}.dev", device.entity(), cache);
+                device.connect_port_tx(cache.port_dev_rx())?;
+                cache.connect_port_dev_tx(device.port_rx())
+            }
+            None | Some("mem") => {
+                debug!(platform.entity() ; "Connect {} to {}.mem", device.entity(), cache);
+                device.connect_port_tx(cache.port_mem_rx())?;
+                cache.connect_port_mem_tx(device.port_rx())
+            }
+            Some(port) => sim_error!("Unknown port '{port}' on Cache"),
+        },
+        PortId::FabricTile {
+            fabric, port_idx, ..
+        } => connect_device_to_fabric(platform, device, fabric, *port_idx),
+        PortId::Mem { memory } => {
+            debug!(platform.entity() ; "Connect {} to {}", device.entity(), memory);
+            device.connect_port_tx(memory.port_rx())?;
+            memory.connect_port_tx(device.port_rx())
+        }
+        PortId::Device { device: to_device } => {
+            debug!(platform.entity() ; "Connect {} to {}", device.entity(), to_device.entity());
+            device.connect_port_tx(to_device.port_rx())?;
+            to_device.connect_port_tx(device.port_rx())
+        }
     }
 }
 
+fn connect_device_to_fabric(
+    platform: &Platform,
+    device: &Rc<dyn PlatformDevice>,
+    fabric: &Rc<dyn Fabric<MemoryAccess>>,
+    fabric_port_idx: usize,
+) -> SimResult {
+    debug!(platform.entity() ; "Connect {} to {}.{}", device.entity(), fabric, fabric_port_idx);
+    device.connect_port_tx(fabric.port_ingress_i(fabric_port_idx))?;
+    fabric.connect_port_egress_i(fabric_port_idx, device.port_rx())
+}
+
 fn connect_pe_to_cache(
     platform: &Platform,
     pe: &Rc<ProcessingElement>,
@@ -0,0 +1,185 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Custom device kinds that are added to a [Platform](crate::Platform) by
+//! downstream crates.
+//!
+//! The `devices` section of a platform configuration lists devices of kinds
+//! that are not built into this crate, such as accelerators or NICs:
+//!
+//! ```yaml
+//! devices:
+//!   - name: nic0
+//!     kind: nic
+//!     config:
+//!       link_gbps: 100
+//!
+//! connections:
+//!   - connect:
+//!       - dev.nic0
+//!       - fabric.fabric0@(0,0).1
+//! ```
+//!
+//! Each kind is registered with a [DeviceRegistry] along with a
+//! [DeviceBuilder] that creates the device from its `config`, which is passed
+//! through as a YAML value. The registry is given to
+//! [Platform::from_config](crate::Platform::from_config):
+//!
+//! ```rust
+//! # use std::rc::Rc;
+//! # use gwr_engine::engine::Engine;
+//! # use gwr_engine::time::clock::Clock;
+//! # use gwr_engine::types::SimError;
+//! # use gwr_models::memory::memory_map::DeviceId;
+//! # use gwr_models::memory::{Memory, MemoryConfig};
+//! # use gwr_platform::Platform;
+//! # use gwr_platform::device::{DeviceRegistry, PlatformDevice};
+//! # use gwr_platform::validate::parse_platform_config;
+//! # use gwr_track::entity::Entity;
+//! # fn main() -> Result<(), SimError> {
+//! let registry = DeviceRegistry::new().with_device_kind(
+//!     "sram",
+//!     |engine: &Engine,
+//!      clock: &Clock,
+//!      parent: &Rc<Entity>,
+//!      name: &str,
+//!      _device_id: DeviceId,
+//!      config: &serde_yaml::Value| {
+//!         let capacity_bytes = config["capacity_bytes"].as_u64().unwrap_or(0x1000);
+//!         let config = MemoryConfig::new(0, capacity_bytes as usize, 32, 1);
+//!         let device: Rc<dyn PlatformDevice> =
+//!             Memory::new_and_register(engine, clock, parent, name, config)?;
+//!         Ok(device)
+//!     },
+//! );
+//!
+//! let cfg = parse_platform_config(
+//!     "
+//! memory_maps: []
+//! devices:
+//!   - name: sram0
+//!     kind: sram
+//!     config:
+//!       capacity_bytes: 0x2000
+//! ",
+//! )?;
+//! let mut engine = Engine::default();
+//! let clock = engine.default_clock();
+//! let platform = Platform::from_config(&engine, &clock, &cfg, &registry)?;
+//! assert!(platform.device("sram0").is_ok());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Devices are given [DeviceId]s in the same way as PEs and memories and can
+//! be connected to PEs, caches, fabrics, memories and other devices with the
+//! `dev.name` endpoint. A device connected to a cache uses its `mem` port
+//! unless `cache.name.dev` is given.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::port::PortStateResult;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::types::{SimError, SimResult};
+use gwr_models::memory::Memory;
+use gwr_models::memory::memory_access::MemoryAccess;
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_track::entity::{Entity, GetEntity};
+use serde_yaml::Value;
+
+/// A device that can be connected to the other components of a platform
+pub trait PlatformDevice: GetEntity {
+    /// Returns the port that receives memory accesses
+    fn port_rx(&self) -> PortStateResult<MemoryAccess>;
+
+    /// Connect the port that sends memory accesses
+    fn connect_port_tx(&self, port_state: PortStateResult<MemoryAccess>) -> SimResult;
+}
+
+impl PlatformDevice for Memory<MemoryAccess> {
+    fn port_rx(&self) -> PortStateResult<MemoryAccess> {
+        Memory::port_rx(self)
+    }
+
+    fn connect_port_tx(&self, port_state: PortStateResult<MemoryAccess>) -> SimResult {
+        Memory::connect_port_tx(self, port_state)
+    }
+}
+
+/// Creates the devices of a registered kind
+pub trait DeviceBuilder {
+    /// Create and register a device from its `config`, which is
+    /// [Value::Null] if the configuration does not have one
+    fn build(
+        &self,
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        device_id: DeviceId,
+        config: &Value,
+    ) -> Result<Rc<dyn PlatformDevice>, SimError>;
+}
+
+impl<F> DeviceBuilder for F
+where
+    F: Fn(
+        &Engine,
+        &Clock,
+        &Rc<Entity>,
+        &str,
+        DeviceId,
+        &Value,
+    ) -> Result<Rc<dyn PlatformDevice>, SimError>,
+{
+    fn build(
+        &self,
+        engine: &Engine,
+        clock: &Clock,
+        parent: &Rc<Entity>,
+        name: &str,
+        device_id: DeviceId,
+        config: &Value,
+    ) -> Result<Rc<dyn PlatformDevice>, SimError> {
+        self(engine, clock, parent, name, device_id, config)
+    }
+}
+
+/// The [DeviceBuilder] of each custom device kind
+#[derive(Clone, Default)]
+pub struct DeviceRegistry {
+    builders: HashMap<String, Rc<dyn DeviceBuilder>>,
+}
+
+impl DeviceRegistry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the builder of a device kind, replacing any existing builder
+    /// of the same kind
+    #[must_use]
+    pub fn with_device_kind(
+        mut self,
+        kind: impl Into<String>,
+        builder: impl DeviceBuilder + 'static,
+    ) -> Self {
+        self.builders.insert(kind.into(), Rc::new(builder));
+        self
+    }
+
+    /// Returns the registered device kinds in alphabetical order
+    #[must_use]
+    pub fn kinds(&self) -> Vec<&str> {
+        let mut kinds: Vec<&str> = self.builders.keys().map(String::as_str).collect();
+        kinds.sort_unstable();
+        kinds
+    }
+
+    #[must_use]
+    pub fn builder(&self, kind: &str) -> Option<&Rc<dyn DeviceBuilder>> {
+        self.builders.get(kind)
+    }
+}
@@ -0,0 +1,328 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Differences between two platform configurations.
+//!
+//! Components are matched by name and connections by their endpoints, in
+//! either order, so a [PlatformDiff] lists the components and connections
+//! that were added or removed and every field of a component that changed.
+//! Configurations are compared after their includes and topologies have been
+//! expanded, so changes to included files are reported against the
+//! components that they create. Its [Display] implementation gives one change
+//! per line, for example:
+//!
+//! ```text
+//! + PE 'pe2'
+//! - memory 'hbm1'
+//! ~ PE 'pe0': config.lsu_access_bytes 32 -> 64
+//! ~ fabric 'fabric0': ticks_per_hop (unset) -> 4
+//! + connection fabric.fabric0@(1,1) <-> pe.pe2
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+use serde_yaml::Value;
+
+use crate::types::PlatformConfig;
+
+/// A change between two platform configurations
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// A component or connection that only exists in the new configuration
+    Added { kind: &'static str, name: String },
+
+    /// A component or connection that only exists in the old configuration
+    Removed { kind: &'static str, name: String },
+
+    /// A field of a component that differs. `from` or `to` is `None` if the
+    /// field is not set.
+    Changed {
+        kind: &'static str,
+        name: String,
+        field: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let described = |kind: &str, name: &str| match kind {
+            "defaults" => kind.to_string(),
+            "connection" | "group link" => format!("{kind} {name}"),
+            _ => format!("{kind} '{name}'"),
+        };
+        match self {
+            Change::Added { kind, name } => write!(f, "+ {}", described(kind, name)),
+            Change::Removed { kind, name } => write!(f, "- {}", described(kind, name)),
+            Change::Changed {
+                kind,
+                name,
+                field,
+                from,
+                to,
+            } => {
+                let unset = "(unset)".to_string();
+                write!(
+                    f,
+                    "~ {}: {field} {} -> {}",
+                    described(kind, name),
+                    from.as_ref().unwrap_or(&unset),
+                    to.as_ref().unwrap_or(&unset)
+                )
+            }
+        }
+    }
+}
+
+/// The changes between two platform configurations
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlatformDiff {
+    changes: Vec<Change>,
+}
+
+impl PlatformDiff {
+    /// Returns the changes, grouped by the kind of component
+    #[must_use]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Returns whether the configurations are the same
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for PlatformDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Flatten a value into the dotted paths of its leaves and their values.
+/// Unset (null) values are left out.
+fn flatten(prefix: &str, value: &Value, fields: &mut BTreeMap<String, String>) {
+    let path = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+    match value {
+        Value::Null => {}
+        Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                let key = match key {
+                    Value::String(key) => key.clone(),
+                    key => leaf_string(key),
+                };
+                flatten(&path(&key), value, fields);
+            }
+        }
+        Value::Sequence(values) => {
+            for (i, value) in values.iter().enumerate() {
+                flatten(&format!("{prefix}[{i}]"), value, fields);
+            }
+        }
+        Value::Tagged(tagged) => flatten(&path(&tagged.tag.to_string()), &tagged.value, fields),
+        leaf => {
+            fields.insert(prefix.to_string(), leaf_string(leaf));
+        }
+    }
+}
+
+fn leaf_string(value: &Value) -> String {
+    serde_yaml::to_string(value)
+        .map(|s| s.trim_end().to_string())
+        .unwrap_or_default()
+}
+
+/// Returns the fields of a section, leaving out those that identify it
+fn fields_of<T: Serialize>(section: &T) -> BTreeMap<String, String> {
+    let mut fields = BTreeMap::new();
+    if let Ok(value) = serde_yaml::to_value(section) {
+        flatten("", &value, &mut fields);
+    }
+    fields.retain(|field, _| field != "name" && !field.starts_with("connect["));
+    fields
+}
+
+/// Add the changes between the fields of two versions of a component
+fn diff_fields(
+    changes: &mut Vec<Change>,
+    kind: &'static str,
+    name: &str,
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) {
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    for field in fields {
+        let (from, to) = (old.get(field), new.get(field));
+        if from != to {
+            changes.push(Change::Changed {
+                kind,
+                name: name.to_string(),
+                field: field.clone(),
+                from: from.cloned(),
+                to: to.cloned(),
+            });
+        }
+    }
+}
+
+/// Add the changes between two lists of components that are matched by name
+fn diff_components<T: Serialize>(
+    changes: &mut Vec<Change>,
+    kind: &'static str,
+    old: &[(String, T)],
+    new: &[(String, T)],
+) {
+    let find = |components: &'_ [(String, T)], name: &str| {
+        components
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, component)| fields_of(component))
+    };
+    for (name, _) in old {
+        if find(new, name).is_none() {
+            changes.push(Change::Removed {
+                kind,
+                name: name.clone(),
+            });
+        }
+    }
+    for (name, component) in new {
+        let new_fields = fields_of(component);
+        match find(old, name) {
+            Some(old_fields) => diff_fields(changes, kind, name, &old_fields, &new_fields),
+            None => changes.push(Change::Added {
+                kind,
+                name: name.clone(),
+            }),
+        }
+    }
+}
+
+/// Returns the name of a connection, which is the same for either order of
+/// its endpoints
+fn connection_name(connect: &[String]) -> String {
+    let mut endpoints = connect.to_vec();
+    endpoints.sort();
+    endpoints.join(" <-> ")
+}
+
+fn named<'a, T: 'a>(
+    sections: impl IntoIterator<Item = &'a T>,
+    name: impl Fn(&T) -> String,
+) -> Vec<(String, &'a T)> {
+    sections.into_iter().map(|s| (name(s), s)).collect()
+}
+
+/// Returns the changes from the `old` configuration to the `new` one
+#[must_use]
+pub fn diff_configs(old: &PlatformConfig, new: &PlatformConfig) -> PlatformDiff {
+    let mut changes = Vec::new();
+
+    diff_fields(
+        &mut changes,
+        "defaults",
+        "",
+        &fields_of(&old.defaults),
+        &fields_of(&new.defaults),
+    );
+    diff_components(
+        &mut changes,
+        "memory map",
+        &named(&old.memory_maps, |m| m.name.clone()),
+        &named(&new.memory_maps, |m| m.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "PE",
+        &named(old.processing_elements.iter().flatten(), |pe| {
+            pe.name.clone()
+        }),
+        &named(new.processing_elements.iter().flatten(), |pe| {
+            pe.name.clone()
+        }),
+    );
+    diff_components(
+        &mut changes,
+        "DMA engine",
+        &named(old.dma_engines.iter().flatten(), |d| d.name.clone()),
+        &named(new.dma_engines.iter().flatten(), |d| d.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "accelerator",
+        &named(old.accelerators.iter().flatten(), |a| a.name.clone()),
+        &named(new.accelerators.iter().flatten(), |a| a.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "cache",
+        &named(old.caches.iter().flatten(), |c| c.name.clone()),
+        &named(new.caches.iter().flatten(), |c| c.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "fabric",
+        &named(old.fabrics.iter().flatten(), |f| f.name.clone()),
+        &named(new.fabrics.iter().flatten(), |f| f.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "memory",
+        &named(old.memories.iter().flatten(), |m| m.name.clone()),
+        &named(new.memories.iter().flatten(), |m| m.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "device",
+        &named(old.devices.iter().flatten(), |d| d.name.clone()),
+        &named(new.devices.iter().flatten(), |d| d.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "connection",
+        &named(old.connections.iter().flatten(), |c| {
+            connection_name(&c.connect)
+        }),
+        &named(new.connections.iter().flatten(), |c| {
+            connection_name(&c.connect)
+        }),
+    );
+    diff_components(
+        &mut changes,
+        "group",
+        &named(old.groups.iter().flatten(), |g| g.name.clone()),
+        &named(new.groups.iter().flatten(), |g| g.name.clone()),
+    );
+    diff_components(
+        &mut changes,
+        "group link",
+        &named(old.group_links.iter().flatten(), |l| {
+            connection_name(&l.connect)
+        }),
+        &named(new.group_links.iter().flatten(), |l| {
+            connection_name(&l.connect)
+        }),
+    );
+    diff_components(
+        &mut changes,
+        "power domain",
+        &named(old.power_domains.iter().flatten(), |d| d.name.clone()),
+        &named(new.power_domains.iter().flatten(), |d| d.name.clone()),
+    );
+
+    PlatformDiff { changes }
+}
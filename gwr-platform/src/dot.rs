@@ -0,0 +1,92 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Export of a built [Platform] as a Graphviz DOT graph.
+//!
+//! Each PE, DMA engine, accelerator, cache, fabric, memory and device is a node
+//! identified by the full name of its entity and each connection is an edge
+//! labelled with the ports that it connects, for example the `dev`/`mem` port
+//! of a cache or the `(col,row).port` of a fabric. Connections are bidirectional so the graph is undirected.
+
+use std::fmt::Write as _;
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, GetEntity};
+
+use crate::Platform;
+use crate::connect::Endpoint;
+
+/// Quote a string as a DOT ID
+fn quoted(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_node(dot: &mut String, entity: &Rc<Entity>, shape: &str) {
+    writeln!(
+        dot,
+        "  {} [label={}, shape={shape}];",
+        quoted(&entity.full_name()),
+        quoted(&entity.name)
+    )
+    .unwrap();
+}
+
+fn write_edge(dot: &mut String, from: &Endpoint, to: &Endpoint) {
+    let mut attributes = Vec::new();
+    if let Some(port) = &from.port {
+        attributes.push(format!("taillabel={}", quoted(port)));
+    }
+    if let Some(port) = &to.port {
+        attributes.push(format!("headlabel={}", quoted(port)));
+    }
+    write!(
+        dot,
+        "  {} -- {}",
+        quoted(&from.entity.full_name()),
+        quoted(&to.entity.full_name())
+    )
+    .unwrap();
+    if !attributes.is_empty() {
+        write!(dot, " [{}]", attributes.join(", ")).unwrap();
+    }
+    writeln!(dot, ";").unwrap();
+}
+
+impl Platform {
+    /// Returns a Graphviz DOT graph of the components of the platform and the
+    /// connections between them.
+    ///
+    /// The graph can be rendered with, for example, `dot -Tsvg`.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "graph platform {{").unwrap();
+        for pe in &self.processing_elements {
+            let name = &pe.entity().name;
+            let shape = if self.is_dma_engine(name) {
+                "cds"
+            } else if self.is_accelerator(name) {
+                "hexagon"
+            } else {
+                "box"
+            };
+            write_node(&mut dot, pe.entity(), shape);
+        }
+        for cache in &self.caches {
+            write_node(&mut dot, cache.entity(), "box3d");
+        }
+        for fabric in &self.fabrics {
+            write_node(&mut dot, fabric.entity(), "octagon");
+        }
+        for memory in &self.memories {
+            write_node(&mut dot, memory.entity(), "cylinder");
+        }
+        for device in &self.devices {
+            write_node(&mut dot, device.entity(), "component");
+        }
+        for (from, to) in &self.connections {
+            write_edge(&mut dot, from, to);
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
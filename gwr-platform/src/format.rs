@@ -0,0 +1,32 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! The file formats of platform configurations.
+//!
+//! Platforms are normally described in YAML, but JSON and TOML descriptions
+//! with the same structure are also accepted, which suits tools that generate
+//! platforms.
+
+use std::path::Path;
+
+use clap::ValueEnum;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum PlatformFormat {
+    #[default]
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl PlatformFormat {
+    /// Returns the format of a file from its extension, which is YAML unless
+    /// the extension is `.json` or `.toml`
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+}
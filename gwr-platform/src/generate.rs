@@ -0,0 +1,161 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Expansion of the `topologies` section of a platform configuration.
+//!
+//! Each topology creates a fabric with the name of the topology and a PE per
+//! fabric port that it uses, connected to port 0 of its fabric node:
+//!  - `mesh`: a PE `{name}_pe_{column}_{row}` at every node of a mesh fabric.
+//!  - `ring`: a PE `{name}_pe_{i}` at every node of a single row torus fabric.
+//!  - `star`: a PE `{name}_pe_{i}` on port `i` of a single node fabric.
+//!
+//! Any other fabric ports are left for connections in the `connections`
+//! section, for example to memories.
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use gwr_models::fabric::FabricTopology;
+
+use crate::types::{
+    ConnectSection, FabricKind, FabricSection, PlatformConfig, ProcessingElementSection,
+    TopologyFabricSection, TopologyPeSection, TopologySection,
+};
+
+pub const DEFAULT_TOPOLOGY_FABRIC_KIND: FabricKind = FabricKind::Routed;
+
+/// Replace the `topologies` of a configuration with the PEs, fabrics and
+/// connections that they describe.
+pub fn expand_topologies(cfg: &mut PlatformConfig) -> Result<(), SimError> {
+    let Some(topologies) = cfg.topologies.take() else {
+        return Ok(());
+    };
+
+    for topology in &topologies {
+        match topology {
+            TopologySection::Mesh(mesh) => {
+                check_size(&mesh.name, "columns", mesh.columns)?;
+                check_size(&mesh.name, "rows", mesh.rows)?;
+                let fabric = fabric_section(
+                    &mesh.name,
+                    mesh.columns,
+                    mesh.rows,
+                    FabricTopology::Mesh,
+                    mesh.fabric.as_ref(),
+                    1,
+                )?;
+                let nodes = (0..mesh.columns).flat_map(|column| {
+                    (0..mesh.rows).map(move |row| {
+                        (
+                            format!("{}_pe_{column}_{row}", mesh.name),
+                            format!("fabric.{}@({column},{row})", mesh.name),
+                        )
+                    })
+                });
+                add_topology(cfg, fabric, &mesh.pe, nodes);
+            }
+            TopologySection::Ring(ring) => {
+                check_size(&ring.name, "size", ring.size)?;
+                let fabric = fabric_section(
+                    &ring.name,
+                    ring.size,
+                    1,
+                    FabricTopology::Torus,
+                    ring.fabric.as_ref(),
+                    1,
+                )?;
+                let nodes = (0..ring.size).map(|i| {
+                    (
+                        format!("{}_pe_{i}", ring.name),
+                        format!("fabric.{}@({i},0)", ring.name),
+                    )
+                });
+                add_topology(cfg, fabric, &ring.pe, nodes);
+            }
+            TopologySection::Star(star) => {
+                check_size(&star.name, "size", star.size)?;
+                let fabric = fabric_section(
+                    &star.name,
+                    1,
+                    1,
+                    FabricTopology::Mesh,
+                    star.fabric.as_ref(),
+                    star.size,
+                )?;
+                let nodes = (0..star.size).map(|i| {
+                    (
+                        format!("{}_pe_{i}", star.name),
+                        format!("fabric.{}@(0,0).{i}", star.name),
+                    )
+                });
+                add_topology(cfg, fabric, &star.pe, nodes);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_size(name: &str, field: &str, value: usize) -> Result<(), SimError> {
+    if value == 0 {
+        return sim_error!("Topology '{name}': {field} must be greater than 0");
+    }
+    Ok(())
+}
+
+/// Create the fabric of a topology, which needs at least
+/// `min_ports_per_node` ports on each node for its PEs
+fn fabric_section(
+    name: &str,
+    columns: usize,
+    rows: usize,
+    topology: FabricTopology,
+    fabric: Option<&TopologyFabricSection>,
+    min_ports_per_node: usize,
+) -> Result<FabricSection, SimError> {
+    let fabric = fabric.cloned().unwrap_or_default();
+    let ports_per_node = fabric.fabric_ports_per_node.unwrap_or(min_ports_per_node);
+    if ports_per_node < min_ports_per_node {
+        return sim_error!(
+            "Topology '{name}': fabric_ports_per_node {ports_per_node} is less than the {min_ports_per_node} ports needed for its PEs"
+        );
+    }
+    Ok(FabricSection {
+        name: name.to_string(),
+        kind: fabric.kind.unwrap_or(DEFAULT_TOPOLOGY_FABRIC_KIND),
+        columns,
+        rows,
+        fabric_ports_per_node: Some(ports_per_node),
+        ticks_per_hop: fabric.ticks_per_hop,
+        ticks_overhead: fabric.ticks_overhead,
+        rx_buffer_bytes: fabric.rx_buffer_bytes,
+        tx_buffer_bytes: fabric.tx_buffer_bytes,
+        port_bits_per_tick: fabric.port_bits_per_tick,
+        routing: fabric.routing,
+        topology: Some(topology),
+        clock_mhz: fabric.clock_mhz,
+    })
+}
+
+/// Add a fabric and a PE connected to each of the `(pe_name, fabric_port)`
+/// nodes of a topology
+fn add_topology(
+    cfg: &mut PlatformConfig,
+    fabric: FabricSection,
+    pe: &TopologyPeSection,
+    nodes: impl Iterator<Item = (String, String)>,
+) {
+    let pes = cfg.processing_elements.get_or_insert_with(Vec::new);
+    let connections = cfg.connections.get_or_insert_with(Vec::new);
+    for (pe_name, fabric_port) in nodes {
+        connections.push(ConnectSection {
+            connect: vec![format!("pe.{pe_name}"), fabric_port],
+            fabric: None,
+            attach: None,
+        });
+        pes.push(ProcessingElementSection {
+            name: pe_name,
+            memory_map: pe.memory_map.clone(),
+            config: pe.config.clone(),
+            clock_mhz: pe.clock_mhz,
+        });
+    }
+    cfg.fabrics.get_or_insert_with(Vec::new).push(fabric);
+}
@@ -0,0 +1,258 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Groups of the components of a [Platform](crate::Platform), such as dies or
+//! sockets.
+//!
+//! Each group in the `groups` section of a configuration lists its members,
+//! which are either the names of components or prefixes that match every
+//! component whose name starts with `member.`. This makes it simple to group
+//! the components of an included platform:
+//!
+//! ```yaml
+//! includes:
+//!   - path: die.yaml
+//!     prefix: die0
+//!   - path: die.yaml
+//!     prefix: die1
+//!
+//! groups:
+//!   - name: die0
+//!     members: [die0]
+//!   - name: die1
+//!     members: [die1]
+//!
+//! group_links:
+//!   - connect:
+//!       - fabric.die0.fabric0@(1,0).2
+//!       - fabric.die1.fabric0@(0,0).2
+//!     delay_ticks: 50
+//!     bits_per_tick: 128
+//! ```
+//!
+//! Each group has its own fabrics and the fabrics of different groups are
+//! joined by the `group_links`, which add `delay_ticks` of latency and limit
+//! the bandwidth to `bits_per_tick` in each direction.
+
+use crate::connect::FABRIC_PORT_RE;
+use crate::types::{GroupLinkSection, PlatformConfig};
+
+pub const DEFAULT_GROUP_LINK_DELAY_TICKS: usize = 20;
+pub const DEFAULT_GROUP_LINK_BITS_PER_TICK: usize = 16 * 8; // 16 bytes per cycle
+
+/// Returns whether a group member matches the name of a component
+pub(crate) fn member_matches(member: &str, name: &str) -> bool {
+    name == member
+        || name
+            .strip_prefix(member)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// A group of the components of a platform
+#[derive(Clone, Debug, Default)]
+pub struct Group {
+    name: String,
+    pes: Vec<String>,
+    dma_engines: Vec<String>,
+    accelerators: Vec<String>,
+    caches: Vec<String>,
+    fabrics: Vec<String>,
+    memories: Vec<String>,
+    devices: Vec<String>,
+}
+
+impl Group {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the names of the PEs in the group
+    #[must_use]
+    pub fn pes(&self) -> &[String] {
+        &self.pes
+    }
+
+    /// Returns the names of the DMA engines in the group
+    #[must_use]
+    pub fn dma_engines(&self) -> &[String] {
+        &self.dma_engines
+    }
+
+    /// Returns the names of the accelerators in the group
+    #[must_use]
+    pub fn accelerators(&self) -> &[String] {
+        &self.accelerators
+    }
+
+    /// Returns the names of the caches in the group
+    #[must_use]
+    pub fn caches(&self) -> &[String] {
+        &self.caches
+    }
+
+    /// Returns the names of the fabrics in the group
+    #[must_use]
+    pub fn fabrics(&self) -> &[String] {
+        &self.fabrics
+    }
+
+    /// Returns the names of the memories in the group
+    #[must_use]
+    pub fn memories(&self) -> &[String] {
+        &self.memories
+    }
+
+    /// Returns the names of the custom devices in the group
+    #[must_use]
+    pub fn devices(&self) -> &[String] {
+        &self.devices
+    }
+}
+
+/// A link between the fabrics of two groups
+#[derive(Clone, Debug)]
+pub struct GroupLink {
+    groups: [String; 2],
+    endpoints: [String; 2],
+    delay_ticks: usize,
+    bits_per_tick: usize,
+}
+
+impl GroupLink {
+    fn new(section: &GroupLinkSection, groups: &[Group]) -> Self {
+        let group_of = |endpoint: &String| {
+            let fabric = FABRIC_PORT_RE
+                .captures(endpoint)
+                .map(|caps| caps[1].to_string())
+                .unwrap_or_default();
+            groups
+                .iter()
+                .find(|group| group.fabrics.contains(&fabric))
+                .map(|group| group.name.clone())
+                .unwrap_or_default()
+        };
+        let endpoints = [section.connect[0].clone(), section.connect[1].clone()];
+        Self {
+            groups: [group_of(&endpoints[0]), group_of(&endpoints[1])],
+            endpoints,
+            delay_ticks: section
+                .delay_ticks
+                .unwrap_or(DEFAULT_GROUP_LINK_DELAY_TICKS),
+            bits_per_tick: section
+                .bits_per_tick
+                .unwrap_or(DEFAULT_GROUP_LINK_BITS_PER_TICK),
+        }
+    }
+
+    /// Returns the names of the two groups that the link connects
+    #[must_use]
+    pub fn groups(&self) -> [&str; 2] {
+        [&self.groups[0], &self.groups[1]]
+    }
+
+    /// Returns the fabric ports at each end of the link
+    #[must_use]
+    pub fn endpoints(&self) -> [&str; 2] {
+        [&self.endpoints[0], &self.endpoints[1]]
+    }
+
+    /// Returns whether the link connects groups `a` and `b`
+    #[must_use]
+    pub fn connects(&self, a: &str, b: &str) -> bool {
+        (self.groups[0] == a && self.groups[1] == b) || (self.groups[0] == b && self.groups[1] == a)
+    }
+
+    #[must_use]
+    pub fn delay_ticks(&self) -> usize {
+        self.delay_ticks
+    }
+
+    #[must_use]
+    pub fn bits_per_tick(&self) -> usize {
+        self.bits_per_tick
+    }
+}
+
+/// Returns the groups of a validated configuration
+pub(crate) fn build_groups(cfg: &PlatformConfig) -> Vec<Group> {
+    let members_of = |members: &[String], names: Vec<&String>| -> Vec<String> {
+        names
+            .into_iter()
+            .filter(|name| members.iter().any(|member| member_matches(member, name)))
+            .cloned()
+            .collect()
+    };
+
+    cfg.groups
+        .iter()
+        .flatten()
+        .map(|section| Group {
+            name: section.name.clone(),
+            pes: members_of(
+                &section.members,
+                cfg.processing_elements
+                    .iter()
+                    .flatten()
+                    .map(|pe| &pe.name)
+                    .collect(),
+            ),
+            dma_engines: members_of(
+                &section.members,
+                cfg.dma_engines
+                    .iter()
+                    .flatten()
+                    .map(|dma| &dma.name)
+                    .collect(),
+            ),
+            accelerators: members_of(
+                &section.members,
+                cfg.accelerators
+                    .iter()
+                    .flatten()
+                    .map(|acc| &acc.name)
+                    .collect(),
+            ),
+            caches: members_of(
+                &section.members,
+                cfg.caches
+                    .iter()
+                    .flatten()
+                    .map(|cache| &cache.name)
+                    .collect(),
+            ),
+            fabrics: members_of(
+                &section.members,
+                cfg.fabrics
+                    .iter()
+                    .flatten()
+                    .map(|fabric| &fabric.name)
+                    .collect(),
+            ),
+            memories: members_of(
+                &section.members,
+                cfg.memories
+                    .iter()
+                    .flatten()
+                    .map(|memory| &memory.name)
+                    .collect(),
+            ),
+            devices: members_of(
+                &section.members,
+                cfg.devices
+                    .iter()
+                    .flatten()
+                    .map(|device| &device.name)
+                    .collect(),
+            ),
+        })
+        .collect()
+}
+
+/// Returns the links between the groups of a validated configuration
+pub(crate) fn build_group_links(cfg: &PlatformConfig, groups: &[Group]) -> Vec<GroupLink> {
+    cfg.group_links
+        .iter()
+        .flatten()
+        .map(|section| GroupLink::new(section, groups))
+        .collect()
+}
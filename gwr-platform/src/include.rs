@@ -0,0 +1,180 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Composition of platforms from the sub-platforms in the `includes` section
+//! of a platform configuration.
+//!
+//! Each include names a platform file and a `prefix`. The file is parsed and
+//! validated on its own and its components are then added to the including
+//! platform with `prefix.` before their names, so a PE `pe0` included with the
+//! prefix `die0` becomes `die0.pe0` and is connected as `pe.die0.pe0`. Fabric
+//! ports are named in the same way, e.g. `fabric.die0.fabric0@(0,0)`.
+//!
+//! An include can also set the [variables](crate::template) of the platform
+//! that it includes with a `variables` mapping.
+//!
+//! Included files may include other files, in which case the prefixes are
+//! nested. The paths of included files are relative to the file that includes
+//! them and their [format](crate::format::PlatformFormat) is determined by
+//! their extension.
+
+use std::path::{Path, PathBuf};
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+
+use crate::format::PlatformFormat;
+use crate::overrides::Override;
+use crate::types::PlatformConfig;
+use crate::validate::parse_included_platform_config;
+
+/// Replace the `includes` of a configuration with the components of the
+/// platforms that they include.
+///
+/// The `overrides` whose paths start with the prefix of an include are applied
+/// to the included platform. The `stack` holds the files currently being
+/// included so that cycles can be detected.
+pub(crate) fn resolve_includes(
+    cfg: &mut PlatformConfig,
+    dir: &Path,
+    overrides: &[Override],
+    stack: &mut Vec<PathBuf>,
+) -> Result<(), SimError> {
+    let Some(includes) = cfg.includes.take() else {
+        return Ok(());
+    };
+
+    for include in includes.iter().rev() {
+        if include.prefix.is_empty()
+            || !include
+                .prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return sim_error!(
+                "Invalid prefix '{}' for '{}' (only letters, digits and '_' are allowed)",
+                include.prefix,
+                include.path
+            );
+        }
+
+        let path = dir.join(&include.path);
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| SimError(format!("Unable to read {}: {e}", path.display())))?;
+        if stack.contains(&canonical) {
+            return sim_error!("{} is included recursively", path.display());
+        }
+
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| SimError(format!("Unable to read {}: {e}", path.display())))?;
+        // The variables given by the include are applied before the overrides
+        // so that the overrides take precedence
+        let mut included_overrides = Vec::new();
+        for (name, value) in include.variables.iter().flatten() {
+            let Some(name) = name.as_str() else {
+                return sim_error!("Invalid variable name {name:?} for '{}'", include.path);
+            };
+            included_overrides.push(Override::new(&format!("variables.{name}"), value.clone())?);
+        }
+        included_overrides.extend(
+            overrides
+                .iter()
+                .filter_map(|o| o.strip_prefix(&include.prefix)),
+        );
+        stack.push(canonical);
+        let included = parse_included_platform_config(
+            &source,
+            PlatformFormat::from_path(&path),
+            path.parent().unwrap_or(Path::new(".")),
+            &included_overrides,
+            stack,
+        )
+        .map_err(|e| SimError(format!("{}: {e}", path.display())))?;
+        stack.pop();
+
+        prepend(cfg, with_prefix(included, &include.prefix));
+    }
+    Ok(())
+}
+
+/// Add `prefix.` before every name in a configuration and every reference to
+/// a name
+fn with_prefix(mut cfg: PlatformConfig, prefix: &str) -> PlatformConfig {
+    let prefixed = |name: &mut String| *name = format!("{prefix}.{name}");
+
+    for memory_map in &mut cfg.memory_maps {
+        prefixed(&mut memory_map.name);
+        for device in &mut memory_map.devices {
+            prefixed(&mut device.name);
+        }
+    }
+    for pe in cfg.processing_elements.iter_mut().flatten() {
+        prefixed(&mut pe.name);
+        prefixed(&mut pe.memory_map);
+    }
+    for dma in cfg.dma_engines.iter_mut().flatten() {
+        prefixed(&mut dma.name);
+        prefixed(&mut dma.memory_map);
+    }
+    for acc in cfg.accelerators.iter_mut().flatten() {
+        prefixed(&mut acc.name);
+        prefixed(&mut acc.memory_map);
+    }
+    for cache in cfg.caches.iter_mut().flatten() {
+        prefixed(&mut cache.name);
+    }
+    for fabric in cfg.fabrics.iter_mut().flatten() {
+        prefixed(&mut fabric.name);
+    }
+    for memory in cfg.memories.iter_mut().flatten() {
+        prefixed(&mut memory.name);
+    }
+    for device in cfg.devices.iter_mut().flatten() {
+        prefixed(&mut device.name);
+    }
+    let prefixed_endpoint = |endpoint: &mut String| {
+        if let Some((kind, name)) = endpoint.split_once('.') {
+            *endpoint = format!("{kind}.{prefix}.{name}");
+        }
+    };
+    for connection in cfg.connections.iter_mut().flatten() {
+        connection.connect.iter_mut().for_each(prefixed_endpoint);
+    }
+    for group in cfg.groups.iter_mut().flatten() {
+        prefixed(&mut group.name);
+        group.members.iter_mut().for_each(prefixed);
+    }
+    for link in cfg.group_links.iter_mut().flatten() {
+        link.connect.iter_mut().for_each(prefixed_endpoint);
+    }
+    for domain in cfg.power_domains.iter_mut().flatten() {
+        prefixed(&mut domain.name);
+        domain.members.iter_mut().for_each(prefixed);
+    }
+    cfg
+}
+
+/// Add the components of an included configuration before those of `cfg`
+fn prepend(cfg: &mut PlatformConfig, included: PlatformConfig) {
+    fn prepend_section<T>(section: &mut Option<Vec<T>>, included: Option<Vec<T>>) {
+        if let Some(mut included) = included {
+            included.extend(section.take().unwrap_or_default());
+            *section = Some(included);
+        }
+    }
+
+    let mut memory_maps = included.memory_maps;
+    memory_maps.append(&mut cfg.memory_maps);
+    cfg.memory_maps = memory_maps;
+    prepend_section(&mut cfg.processing_elements, included.processing_elements);
+    prepend_section(&mut cfg.dma_engines, included.dma_engines);
+    prepend_section(&mut cfg.accelerators, included.accelerators);
+    prepend_section(&mut cfg.caches, included.caches);
+    prepend_section(&mut cfg.fabrics, included.fabrics);
+    prepend_section(&mut cfg.memories, included.memories);
+    prepend_section(&mut cfg.devices, included.devices);
+    prepend_section(&mut cfg.connections, included.connections);
+    prepend_section(&mut cfg.groups, included.groups);
+    prepend_section(&mut cfg.group_links, included.group_links);
+    prepend_section(&mut cfg.power_domains, included.power_domains);
+}
@@ -2,7 +2,7 @@
 
 #![doc = include_str!(gwr_build::generated_crate_docs_path!())]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Display};
 use std::path::Path;
 use std::rc::Rc;
@@ -23,20 +23,47 @@ use gwr_models::processing_element::{
     MachineOpCounts, ProcessingElement, ProcessingElementStatsDisplay,
 };
 use gwr_track::entity::{Entity, GetEntity};
+use gwr_track::warn;
 
-use crate::builder::{build_caches, build_fabrics, build_memories, build_memory_maps, build_pes};
-use crate::connect::connect_ports;
+use crate::address_map::AddressMap;
+use crate::builder::{
+    build_accelerators, build_caches, build_devices, build_dma_engines, build_fabrics,
+    build_memories, build_memory_maps, build_pes,
+};
+use crate::connect::{Endpoint, connect_group_links, connect_ports, fabric_port_indices};
+use crate::device::{DeviceRegistry, PlatformDevice};
+use crate::diff::{PlatformDiff, diff_configs};
+use crate::format::PlatformFormat;
+use crate::group::{Group, GroupLink, build_group_links, build_groups};
+use crate::overrides::Override;
+use crate::power::{PowerDomain, build_power_domains};
 use crate::types::PlatformConfig;
+use crate::validate::{parse_platform_config, parse_platform_config_as};
 
+pub mod address_map;
+pub mod attach;
 pub mod builder;
+mod check;
 mod connect;
+pub mod device;
+pub mod diff;
+mod dot;
+pub mod format;
+pub mod generate;
+pub mod group;
+mod include;
+pub mod overrides;
+pub mod power;
+pub mod template;
 pub mod types;
+pub mod validate;
 pub mod yaml;
 
 type ProcessingElements = Vec<Rc<ProcessingElement>>;
 type Caches = Vec<Rc<Cache<MemoryAccess>>>;
 type Fabrics = Vec<Rc<dyn Fabric<MemoryAccess>>>;
 type Memories = Vec<Rc<Memory<MemoryAccess>>>;
+type Devices = Vec<Rc<dyn PlatformDevice>>;
 type DeviceIds = HashMap<String, DeviceId>;
 type NameToIdxMap = HashMap<String, usize>;
 
@@ -45,12 +72,23 @@ pub struct Platform {
     entity: Rc<Entity>,
     processing_elements: ProcessingElements,
     pes_idx_by_id: NameToIdxMap,
+    dma_engines_idx_by_id: NameToIdxMap,
+    accelerators_idx_by_id: NameToIdxMap,
     caches: Caches,
     caches_idx_by_id: NameToIdxMap,
     fabrics: Fabrics,
     fabrics_idx_by_id: NameToIdxMap,
     memories: Memories,
     memories_idx_by_id: NameToIdxMap,
+    devices: Devices,
+    devices_idx_by_id: NameToIdxMap,
+    connections: Vec<(Endpoint, Endpoint)>,
+    device_ids: DeviceIds,
+    address_maps: Vec<AddressMap>,
+    groups: Vec<Group>,
+    group_links: Vec<GroupLink>,
+    power_domains: Vec<PowerDomain>,
+    config: PlatformConfig,
 }
 
 impl fmt::Debug for Platform {
@@ -62,14 +100,43 @@ impl fmt::Debug for Platform {
 }
 
 impl Platform {
+    /// Build a platform from a file whose [format](PlatformFormat) is
+    /// determined by its extension. Included files are found relative to the
+    /// directory of the file.
     pub fn from_file(
         engine: &Engine,
         clock: &Clock,
         platform_path: &Path,
+    ) -> Result<Self, SimError> {
+        Platform::from_file_with_overrides(engine, clock, platform_path, &[])
+    }
+
+    /// Build a platform from a file after applying
+    /// [overrides](crate::overrides) to it.
+    pub fn from_file_with_overrides(
+        engine: &Engine,
+        clock: &Clock,
+        platform_path: &Path,
+        overrides: &[Override],
+    ) -> Result<Self, SimError> {
+        let format = PlatformFormat::from_path(platform_path);
+        Platform::from_file_as(engine, clock, platform_path, format, overrides)
+    }
+
+    /// Build a platform from a file in the given format after applying
+    /// [overrides](crate::overrides) to it.
+    pub fn from_file_as(
+        engine: &Engine,
+        clock: &Clock,
+        platform_path: &Path,
+        format: PlatformFormat,
+        overrides: &[Override],
     ) -> Result<Self, SimError> {
         let s = std::fs::read_to_string(platform_path)
             .map_err(|e| SimError(format!("Unable to read {}: {e}", platform_path.display())))?;
-        Platform::from_string(engine, clock, &s)
+        let dir = platform_path.parent().unwrap_or(Path::new("."));
+        let cfg = parse_platform_config_as(&s, format, dir, overrides)?;
+        Platform::from_config(engine, clock, &cfg, &DeviceRegistry::new())
     }
 
     pub fn from_string(
@@ -77,36 +144,98 @@ impl Platform {
         clock: &Clock,
         platform_config: &str,
     ) -> Result<Self, SimError> {
-        let cfg: PlatformConfig = serde_yaml::from_str(platform_config)
-            .map_err(|e| SimError(format!("serde_yaml::from_str failed: {e}")))?;
-        Platform::build(engine, clock, &cfg)
+        let cfg = parse_platform_config(platform_config)?;
+        Platform::from_config(engine, clock, &cfg, &DeviceRegistry::new())
+    }
+
+    /// Build a platform from a string in the given format
+    pub fn from_string_as(
+        engine: &Engine,
+        clock: &Clock,
+        platform_config: &str,
+        format: PlatformFormat,
+    ) -> Result<Self, SimError> {
+        let cfg = parse_platform_config_as(platform_config, format, Path::new("."), &[])?;
+        Platform::from_config(engine, clock, &cfg, &DeviceRegistry::new())
     }
 
-    fn build(engine: &Engine, clock: &Clock, cfg: &PlatformConfig) -> Result<Self, SimError> {
+    /// Build a platform from a parsed configuration whose custom
+    /// [devices](crate::device) are created by the builders in `registry`
+    pub fn from_config(
+        engine: &Engine,
+        clock: &Clock,
+        cfg: &PlatformConfig,
+        registry: &DeviceRegistry,
+    ) -> Result<Self, SimError> {
         let device_ids = assign_device_ids(cfg)?;
 
         let top = engine.top();
         let (memories, memories_idx_by_id) = build_memories(engine, clock, top, cfg)?;
         let memory_maps = build_memory_maps(cfg, &memories, &memories_idx_by_id, &device_ids)?;
-        let (processing_elements, pes_idx_by_id) =
+        let (mut processing_elements, mut pes_idx_by_id) =
             build_pes(engine, clock, top, cfg, &memory_maps, &device_ids)?;
+        let dma_engines = build_dma_engines(engine, clock, top, cfg, &memory_maps, &device_ids)?;
+        let dma_engines_idx_by_id =
+            append_pes(&mut processing_elements, &mut pes_idx_by_id, dma_engines);
+        let accelerators = build_accelerators(engine, clock, top, cfg, &memory_maps, &device_ids)?;
+        let accelerators_idx_by_id =
+            append_pes(&mut processing_elements, &mut pes_idx_by_id, accelerators);
         let (caches, caches_idx_by_id) = build_caches(engine, clock, top, cfg)?;
         let (fabrics, fabrics_idx_by_id) = build_fabrics(engine, clock, top, cfg)?;
+        let (devices, devices_idx_by_id) =
+            build_devices(engine, clock, top, cfg, &device_ids, registry)?;
+        let address_maps = cfg
+            .memory_maps
+            .iter()
+            .map(|section| {
+                let pes = pe_memory_maps(cfg)
+                    .filter(|(_, memory_map)| *memory_map == section.name)
+                    .map(|(pe, _)| pe.to_string())
+                    .collect();
+                AddressMap::new(section, &memory_maps[&section.name], &device_ids, pes)
+            })
+            .collect();
+        let groups = build_groups(cfg);
+        let group_links = build_group_links(cfg, &groups);
+        let power_domains = build_power_domains(engine, clock, cfg);
+        for domain in &power_domains {
+            for component in domain.components() {
+                if let Some(idx) = pes_idx_by_id.get(component) {
+                    processing_elements[*idx].set_power_switch(domain.switch());
+                }
+            }
+        }
 
         let parent = engine.top();
         let entity = Rc::new(Entity::new(parent, "platform"));
-        let platform = Platform {
+        let mut platform = Platform {
             entity,
             processing_elements,
             pes_idx_by_id,
+            dma_engines_idx_by_id,
+            accelerators_idx_by_id,
             caches,
             caches_idx_by_id,
             fabrics,
             fabrics_idx_by_id,
             memories,
             memories_idx_by_id,
+            devices,
+            devices_idx_by_id,
+            connections: Vec::new(),
+            device_ids,
+            address_maps,
+            groups,
+            group_links,
+            power_domains,
+            config: cfg.clone(),
         };
-        connect_ports(&platform, cfg)?;
+        platform.connections = connect_ports(&platform, cfg)?;
+        let group_links = connect_group_links(engine, clock, &platform, cfg)?;
+        platform.connections.extend(group_links);
+        for problem in platform.unreachable_memories(&platform.connected_endpoints()) {
+            warn!(platform.entity ; "{problem}");
+        }
         Ok(platform)
     }
 
@@ -131,6 +260,15 @@ impl Platform {
         }
     }
 
+    pub fn device_idx_from_name(&self, device_name: &str) -> Result<usize, SimError> {
+        match self.devices_idx_by_id.get(device_name) {
+            Some(idx) => Ok(*idx),
+            None => sim_error!("No Device '{device_name}'"),
+        }
+    }
+
+    /// Returns the index of a PE, DMA engine or accelerator. They all perform
+    /// the tasks of a timetable, so are found by the same name.
     pub fn pe_idx_from_name(&self, pe_name: &str) -> Result<usize, SimError> {
         match self.pes_idx_by_id.get(pe_name) {
             Some(idx) => Ok(*idx),
@@ -138,6 +276,15 @@ impl Platform {
         }
     }
 
+    /// Returns the device ID that the fabric routes to for a PE, DMA engine,
+    /// accelerator, memory or device
+    pub fn device_id(&self, name: &str) -> Result<DeviceId, SimError> {
+        match self.device_ids.get(name) {
+            Some(device_id) => Ok(*device_id),
+            None => sim_error!("No device ID for '{name}'"),
+        }
+    }
+
     #[must_use]
     pub fn num_caches(&self) -> usize {
         self.caches_idx_by_id.keys().len()
@@ -153,19 +300,59 @@ impl Platform {
         self.memories_idx_by_id.keys().len()
     }
 
+    #[must_use]
+    pub fn num_devices(&self) -> usize {
+        self.devices_idx_by_id.keys().len()
+    }
+
+    /// Returns the number of PEs, not including DMA engines and accelerators
     #[must_use]
     pub fn num_pes(&self) -> usize {
-        self.pes_idx_by_id.keys().len()
+        self.pe_names().len()
+    }
+
+    #[must_use]
+    pub fn num_dma_engines(&self) -> usize {
+        self.dma_engines_idx_by_id.keys().len()
+    }
+
+    #[must_use]
+    pub fn num_accelerators(&self) -> usize {
+        self.accelerators_idx_by_id.keys().len()
     }
 
+    /// Returns the names of the PEs, not including DMA engines and
+    /// accelerators
     #[must_use]
     pub fn pe_names(&self) -> Vec<String> {
         self.pes_idx_by_id
             .keys()
+            .filter(|pe_name| !self.is_dma_engine(pe_name) && !self.is_accelerator(pe_name))
             .map(|pe_name| pe_name.to_string())
             .collect()
     }
 
+    #[must_use]
+    pub fn is_dma_engine(&self, name: &str) -> bool {
+        self.dma_engines_idx_by_id.contains_key(name)
+    }
+
+    #[must_use]
+    pub fn is_accelerator(&self, name: &str) -> bool {
+        self.accelerators_idx_by_id.contains_key(name)
+    }
+
+    /// Returns how a PE, DMA engine or accelerator is described in messages
+    pub(crate) fn pe_kind(&self, name: &str) -> &'static str {
+        if self.is_dma_engine(name) {
+            "DMA engine"
+        } else if self.is_accelerator(name) {
+            "Accelerator"
+        } else {
+            "PE"
+        }
+    }
+
     pub fn cache(&self, cache_name: &str) -> Result<&Rc<Cache<MemoryAccess>>, SimError> {
         let idx = self.cache_idx_from_name(cache_name)?;
         Ok(&self.caches[idx])
@@ -181,11 +368,131 @@ impl Platform {
         Ok(&self.memories[idx])
     }
 
+    pub fn device(&self, device_name: &str) -> Result<&Rc<dyn PlatformDevice>, SimError> {
+        let idx = self.device_idx_from_name(device_name)?;
+        Ok(&self.devices[idx])
+    }
+
+    /// Returns a PE, DMA engine or accelerator
     pub fn pe(&self, pe_name: &str) -> Result<&Rc<ProcessingElement>, SimError> {
         let idx = self.pe_idx_from_name(pe_name)?;
         Ok(&self.processing_elements[idx])
     }
 
+    pub fn dma_engine(&self, dma_engine_name: &str) -> Result<&Rc<ProcessingElement>, SimError> {
+        match self.dma_engines_idx_by_id.get(dma_engine_name) {
+            Some(idx) => Ok(&self.processing_elements[*idx]),
+            None => sim_error!("No DMA engine '{dma_engine_name}'"),
+        }
+    }
+
+    pub fn accelerator(&self, accelerator_name: &str) -> Result<&Rc<ProcessingElement>, SimError> {
+        match self.accelerators_idx_by_id.get(accelerator_name) {
+            Some(idx) => Ok(&self.processing_elements[*idx]),
+            None => sim_error!("No accelerator '{accelerator_name}'"),
+        }
+    }
+
+    /// Returns the memory maps of the platform in the order they are defined
+    #[must_use]
+    pub fn address_maps(&self) -> &[AddressMap] {
+        &self.address_maps
+    }
+
+    pub fn address_map(&self, memory_map_name: &str) -> Result<&AddressMap, SimError> {
+        match self
+            .address_maps
+            .iter()
+            .find(|m| m.name() == memory_map_name)
+        {
+            Some(address_map) => Ok(address_map),
+            None => sim_error!("No memory map '{memory_map_name}'"),
+        }
+    }
+
+    /// Returns the memory map used by a PE, DMA engine or accelerator
+    pub fn pe_address_map(&self, pe_name: &str) -> Result<&AddressMap, SimError> {
+        match self
+            .address_maps
+            .iter()
+            .find(|m| m.pes().iter().any(|pe| pe == pe_name))
+        {
+            Some(address_map) => Ok(address_map),
+            None => sim_error!("No PE '{pe_name}'"),
+        }
+    }
+
+    /// Returns the groups of the platform in the order they are defined
+    #[must_use]
+    pub fn groups(&self) -> &[Group] {
+        &self.groups
+    }
+
+    pub fn group(&self, group_name: &str) -> Result<&Group, SimError> {
+        match self.groups.iter().find(|g| g.name() == group_name) {
+            Some(group) => Ok(group),
+            None => sim_error!("No group '{group_name}'"),
+        }
+    }
+
+    /// Returns the group that contains a PE, DMA engine or accelerator, if any
+    #[must_use]
+    pub fn pe_group(&self, pe_name: &str) -> Option<&Group> {
+        self.groups.iter().find(|g| {
+            g.pes()
+                .iter()
+                .chain(g.dma_engines())
+                .chain(g.accelerators())
+                .any(|pe| pe == pe_name)
+        })
+    }
+
+    /// Returns the group that contains a memory, if any
+    #[must_use]
+    pub fn memory_group(&self, memory_name: &str) -> Option<&Group> {
+        self.groups
+            .iter()
+            .find(|g| g.memories().iter().any(|memory| memory == memory_name))
+    }
+
+    /// Returns the links between the fabrics of groups
+    #[must_use]
+    pub fn group_links(&self) -> &[GroupLink] {
+        &self.group_links
+    }
+
+    /// Returns the power domains of the platform in the order they are defined
+    #[must_use]
+    pub fn power_domains(&self) -> &[PowerDomain] {
+        &self.power_domains
+    }
+
+    pub fn power_domain(&self, domain_name: &str) -> Result<&PowerDomain, SimError> {
+        match self.power_domains.iter().find(|d| d.name() == domain_name) {
+            Some(domain) => Ok(domain),
+            None => sim_error!("No power domain '{domain_name}'"),
+        }
+    }
+
+    /// Returns the power domain that contains a component, if any
+    #[must_use]
+    pub fn component_power_domain(&self, component: &str) -> Option<&PowerDomain> {
+        self.power_domains.iter().find(|d| d.contains(component))
+    }
+
+    /// Returns the configuration that the platform was built from, with its
+    /// includes and topologies expanded
+    #[must_use]
+    pub fn config(&self) -> &PlatformConfig {
+        &self.config
+    }
+
+    /// Returns the changes from this platform to `other`
+    #[must_use]
+    pub fn diff(&self, other: &Platform) -> PlatformDiff {
+        diff_configs(&self.config, &other.config)
+    }
+
     pub fn attach_dispatcher(&self, dispatcher: &Rc<dyn Dispatch>) {
         for pe in &self.processing_elements {
             pe.set_dispatcher(dispatcher);
@@ -207,9 +514,44 @@ impl Platform {
         }
     }
 
+    /// Returns the total bytes read from all memories
+    #[must_use]
+    pub fn total_memory_bytes_read(&self) -> usize {
+        self.memories.iter().map(|mem| mem.bytes_read()).sum()
+    }
+
+    /// Returns the total bytes written to all memories
+    #[must_use]
+    pub fn total_memory_bytes_written(&self) -> usize {
+        self.memories.iter().map(|mem| mem.bytes_written()).sum()
+    }
+
+    /// Returns the total hits of all caches
+    #[must_use]
+    pub fn total_cache_hits(&self) -> usize {
+        self.total_cache_stat(Cache::num_hits)
+    }
+
+    /// Returns the total misses of all caches
+    #[must_use]
+    pub fn total_cache_misses(&self) -> usize {
+        self.total_cache_stat(Cache::num_misses)
+    }
+
+    /// Returns the total machine operations of all PEs
+    #[must_use]
+    pub fn total_machine_ops(&self) -> MachineOpCounts {
+        self.processing_elements
+            .iter()
+            .fold(MachineOpCounts::default(), |mut total, pe| {
+                total.add_assign(pe.machine_ops());
+                total
+            })
+    }
+
     fn dump_memory_totals(&self, time_now_ns: f64) {
-        let total_bytes_read: usize = self.memories.iter().map(|mem| mem.bytes_read()).sum();
-        let total_bytes_written: usize = self.memories.iter().map(|mem| mem.bytes_written()).sum();
+        let total_bytes_read = self.total_memory_bytes_read();
+        let total_bytes_written = self.total_memory_bytes_written();
 
         log_stats(
             &self.entity,
@@ -232,8 +574,8 @@ impl Platform {
     fn dump_cache_totals(&self, time_now_ns: f64) {
         let total_payload_bytes_read = self.total_cache_stat(Cache::payload_bytes_read);
         let total_payload_bytes_written = self.total_cache_stat(Cache::payload_bytes_written);
-        let total_hits = self.total_cache_stat(Cache::num_hits);
-        let total_misses = self.total_cache_stat(Cache::num_misses);
+        let total_hits = self.total_cache_hits();
+        let total_misses = self.total_cache_misses();
         log_stats(
             &self.entity,
             CacheStatsDisplay::new(
@@ -248,13 +590,7 @@ impl Platform {
     }
 
     fn dump_pe_totals(&self, time_now_ns: f64) {
-        let machine_ops: MachineOpCounts =
-            self.processing_elements
-                .iter()
-                .fold(MachineOpCounts::default(), |mut total, pe| {
-                    total.add_assign(pe.machine_ops());
-                    total
-                });
+        let machine_ops = self.total_machine_ops();
         log_stats(
             &self.entity,
             ProcessingElementStatsDisplay::new(
@@ -300,29 +636,70 @@ impl Display for Platform {
     }
 }
 
+/// Add DMA engines or accelerators after the PEs, returning the index of each
+/// of them
+fn append_pes(
+    processing_elements: &mut ProcessingElements,
+    pes_idx_by_id: &mut NameToIdxMap,
+    others: ProcessingElements,
+) -> NameToIdxMap {
+    let mut idx_by_id = NameToIdxMap::new();
+    for pe in others {
+        let name = pe.entity().name.to_string();
+        idx_by_id.insert(name.clone(), processing_elements.len());
+        pes_idx_by_id.insert(name, processing_elements.len());
+        processing_elements.push(pe);
+    }
+    idx_by_id
+}
+
+/// Returns the name and memory map of every PE, DMA engine and accelerator of
+/// a configuration
+pub(crate) fn pe_memory_maps(cfg: &PlatformConfig) -> impl Iterator<Item = (&str, &str)> {
+    let pes = cfg
+        .processing_elements
+        .iter()
+        .flatten()
+        .map(|pe| (&pe.name, &pe.memory_map));
+    let dma_engines = cfg
+        .dma_engines
+        .iter()
+        .flatten()
+        .map(|dma| (&dma.name, &dma.memory_map));
+    let accelerators = cfg
+        .accelerators
+        .iter()
+        .flatten()
+        .map(|acc| (&acc.name, &acc.memory_map));
+    pes.chain(dma_engines)
+        .chain(accelerators)
+        .map(|(name, memory_map)| (name.as_str(), memory_map.as_str()))
+}
+
+/// Assign a device ID to each PE, DMA engine, accelerator, memory and device.
+/// Fabrics route by device ID, so a device attached to a fabric is given the
+/// index of its fabric port and the other devices are given the lowest IDs
+/// that remain.
 fn assign_device_ids(cfg: &PlatformConfig) -> Result<DeviceIds, SimError> {
-    let mut device_id = 0;
+    let port_indices = fabric_port_indices(cfg);
+    let used: HashSet<u64> = port_indices.values().map(|idx| *idx as u64).collect();
+    let mut next_free = (0..).filter(|id| !used.contains(id));
+
+    let names = pe_memory_maps(cfg)
+        .map(|(name, _)| name)
+        .chain(cfg.memories.iter().flatten().map(|mem| mem.name.as_str()))
+        .chain(cfg.devices.iter().flatten().map(|dev| dev.name.as_str()));
     let mut device_ids = DeviceIds::new();
-    if let Some(pes) = &cfg.processing_elements {
-        for pe in pes {
-            if device_ids
-                .insert(pe.name.to_string(), DeviceId(device_id))
-                .is_some()
-            {
-                return sim_error!("Duplicate device name {}", pe.name);
-            }
-            device_id += 1;
-        }
-    }
-    if let Some(mems) = &cfg.memories {
-        for mem in mems {
-            if device_ids
-                .insert(mem.name.to_string(), DeviceId(device_id))
-                .is_some()
-            {
-                return sim_error!("Duplicate device name {}", mem.name);
-            }
-            device_id += 1;
+    for name in names {
+        let device_id = match port_indices.get(name) {
+            Some(idx) => *idx as u64,
+            None => next_free.next().unwrap(),
+        };
+        if device_ids
+            .insert(name.to_string(), DeviceId(device_id))
+            .is_some()
+        {
+            return sim_error!("Duplicate device name {name}");
         }
     }
     Ok(device_ids)
@@ -0,0 +1,199 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Overrides of the values in a platform configuration.
+//!
+//! An override has the form `path=value` where the `path` is a dotted path
+//! into the YAML of the configuration and the `value` is parsed as YAML. Each
+//! segment of the path is either:
+//!  - the key of a mapping, e.g. `config` or `lsu_access_bytes`.
+//!  - the index of an element of a list or the `name` of the element, e.g.
+//!    `processing_elements.0` or `processing_elements.pe0`.
+//!
+//! The first segment may also be the name of an element of any of the
+//! top-level sections, so `pe0.config.lsu_access_bytes=64` is the same as
+//! `processing_elements.pe0.config.lsu_access_bytes=64`, or the `prefix` of an
+//! include, in which case the rest of the path is applied to the included
+//! platform.
+//!
+//! The last key of a path may be added to a mapping that does not contain it,
+//! which allows optional values to be set.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use serde_yaml::Value;
+
+/// A `path=value` override of a value in a platform configuration
+#[derive(Clone, Debug, PartialEq)]
+pub struct Override {
+    path: Vec<String>,
+    value: Value,
+}
+
+impl Override {
+    /// Create an override that sets the value at a dotted `path`
+    pub fn new(path: &str, value: Value) -> Result<Self, SimError> {
+        if path.is_empty() || path.split('.').any(str::is_empty) {
+            return sim_error!("Invalid override path '{path}'");
+        }
+        Ok(Self {
+            path: path.split('.').map(str::to_string).collect(),
+            value,
+        })
+    }
+
+    #[must_use]
+    pub fn path(&self) -> String {
+        self.path.join(".")
+    }
+
+    #[must_use]
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Returns the override with its first path segment removed if that
+    /// segment is `prefix`
+    pub(crate) fn strip_prefix(&self, prefix: &str) -> Option<Override> {
+        match self.path.split_first() {
+            Some((first, rest)) if first == prefix && !rest.is_empty() => Some(Override {
+                path: rest.to_vec(),
+                value: self.value.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Override {
+    type Err = SimError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((path, value)) = s.split_once('=') else {
+            return sim_error!("Invalid override '{s}' (expected 'path=value')");
+        };
+        if path.is_empty() || path.split('.').any(str::is_empty) {
+            return sim_error!("Invalid path '{path}' in override '{s}'");
+        }
+        let value = serde_yaml::from_str(value)
+            .map_err(|e| SimError(format!("Invalid value in override '{s}': {e}")))?;
+        Ok(Self {
+            path: path.split('.').map(str::to_string).collect(),
+            value,
+        })
+    }
+}
+
+impl Display for Override {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = serde_yaml::to_string(&self.value).map_err(|_| fmt::Error)?;
+        write!(f, "{}={}", self.path(), value.trim_end())
+    }
+}
+
+/// Returns the prefixes of the includes of a configuration
+fn include_prefixes(config: &Value) -> Vec<&str> {
+    config
+        .get("includes")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(|include| include.get("prefix").and_then(Value::as_str))
+        .collect()
+}
+
+/// Apply the overrides that are not for included platforms to a configuration
+pub(crate) fn apply_overrides(config: &mut Value, overrides: &[Override]) -> Result<(), SimError> {
+    let prefixes: Vec<String> = include_prefixes(config)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    for o in overrides {
+        if prefixes
+            .iter()
+            .any(|prefix| o.strip_prefix(prefix).is_some())
+        {
+            continue;
+        }
+        let target = lookup(config, &o.path).map_err(|e| SimError(format!("{o}: {e}")))?;
+        *target = o.value.clone();
+    }
+    Ok(())
+}
+
+/// Returns the element of a list with the index or name `segment`
+fn element<'a>(list: &'a mut [Value], segment: &str) -> Option<&'a mut Value> {
+    if let Ok(index) = segment.parse::<usize>() {
+        return list.get_mut(index);
+    }
+    list.iter_mut()
+        .find(|element| element.get("name").and_then(Value::as_str) == Some(segment))
+}
+
+fn lookup<'a>(config: &'a mut Value, path: &[String]) -> Result<&'a mut Value, String> {
+    let (first, rest) = path.split_first().ok_or("empty path")?;
+
+    // Allow the first segment to name an element of any top-level section
+    let named_in_section = config.as_mapping().and_then(|sections| {
+        if sections.contains_key(first.as_str()) {
+            return None;
+        }
+        sections.iter().find_map(|(section, list)| {
+            let list = list.as_sequence()?;
+            list.iter()
+                .any(|element| element.get("name").and_then(Value::as_str) == Some(first))
+                .then(|| section.clone())
+        })
+    });
+
+    let mut value = match named_in_section {
+        Some(section) => {
+            let list = config[&section].as_sequence_mut().unwrap();
+            element(list, first).unwrap()
+        }
+        None => step(config, first, rest.is_empty())?,
+    };
+    for (i, segment) in rest.iter().enumerate() {
+        value = step(value, segment, i == rest.len() - 1)?;
+    }
+    Ok(value)
+}
+
+/// Move one segment down a path, adding the key to a mapping if it is the
+/// last segment
+fn step<'a>(value: &'a mut Value, segment: &str, last: bool) -> Result<&'a mut Value, String> {
+    match value {
+        Value::Mapping(mapping) => {
+            if !mapping.contains_key(segment) {
+                if !last {
+                    return Err(format!("no '{segment}' found"));
+                }
+                mapping.insert(Value::String(segment.to_string()), Value::Null);
+            }
+            Ok(mapping.get_mut(segment).unwrap())
+        }
+        Value::Sequence(list) => {
+            element(list, segment).ok_or_else(|| format!("no element '{segment}' found"))
+        }
+        // An empty value, e.g. `config:`, is treated as an empty mapping
+        Value::Null if last => {
+            *value = Value::Mapping(serde_yaml::Mapping::new());
+            step(value, segment, last)
+        }
+        _ => Err(format!("cannot find '{segment}' in a {}", kind(value))),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Sequence(_) => "list",
+        Value::Mapping(_) => "mapping",
+        Value::Tagged(_) => "tagged value",
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Power domains of the components of a [Platform](crate::Platform).
+//!
+//! Each domain in the `power_domains` section of a configuration lists its
+//! members in the same way as a [group](crate::group), an optional supply
+//! voltage, the state the domain starts in and any changes to its state at
+//! ticks of the platform clock:
+//!
+//! ```yaml
+//! power_domains:
+//!   - name: pd0
+//!     members: [pe0, dma0]
+//!     voltage_mv: 750
+//!     state: off
+//!     transitions:
+//!       - at_ticks: 1000
+//!         state: on
+//!       - at_ticks: 5000
+//!         state: retention
+//! ```
+//!
+//! The state of a domain is held by a [PowerSwitch] which notifies an event
+//! whenever it is changed, so the state can also be changed while the
+//! simulation is running with [PowerDomain::set_state]. The PEs, DMA engines
+//! and accelerators of a domain only start tasks while the domain is on. The
+//! other members of a domain are recorded with it but are not gated.
+//!
+//! Transitions are applied at their tick even if the rest of the simulation
+//! has finished, so that a domain which is turned on later can release the
+//! work that is waiting for it.
+
+use gwr_engine::engine::Engine;
+use gwr_engine::power::{PowerState, PowerSwitch};
+use gwr_engine::time::clock::Clock;
+
+use crate::group::member_matches;
+use crate::types::PlatformConfig;
+use crate::validate::component_names;
+
+/// A power domain of the components of a platform
+#[derive(Clone)]
+pub struct PowerDomain {
+    name: String,
+    components: Vec<String>,
+    voltage_mv: Option<f64>,
+    switch: PowerSwitch,
+}
+
+impl PowerDomain {
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the names of the components in the domain
+    #[must_use]
+    pub fn components(&self) -> &[String] {
+        &self.components
+    }
+
+    #[must_use]
+    pub fn contains(&self, component: &str) -> bool {
+        self.components.iter().any(|c| c == component)
+    }
+
+    #[must_use]
+    pub fn voltage_mv(&self) -> Option<f64> {
+        self.voltage_mv
+    }
+
+    #[must_use]
+    pub fn state(&self) -> PowerState {
+        self.switch.state()
+    }
+
+    /// Change the state of the domain
+    pub fn set_state(&self, state: PowerState) {
+        self.switch.set_state(state);
+    }
+
+    /// Returns the switch that holds the state of the domain
+    #[must_use]
+    pub fn switch(&self) -> &PowerSwitch {
+        &self.switch
+    }
+}
+
+/// Build the power domains of a configuration and schedule their transitions
+pub(crate) fn build_power_domains(
+    engine: &Engine,
+    clock: &Clock,
+    cfg: &PlatformConfig,
+) -> Vec<PowerDomain> {
+    cfg.power_domains
+        .iter()
+        .flatten()
+        .map(|section| {
+            let components = component_names(cfg)
+                .filter(|name| section.members.iter().any(|m| member_matches(m, name)))
+                .map(str::to_string)
+                .collect();
+            let state = section.state.map_or(PowerState::On, PowerState::from);
+            let domain = PowerDomain {
+                name: section.name.clone(),
+                components,
+                voltage_mv: section.voltage_mv,
+                switch: PowerSwitch::new(state),
+            };
+
+            if let Some(transitions) = &section.transitions {
+                let transitions: Vec<(u64, PowerState)> = transitions
+                    .iter()
+                    .map(|t| (t.at_ticks, t.state.into()))
+                    .collect();
+                let switch = domain.switch.clone();
+                let clock = clock.clone();
+                engine.spawn(async move {
+                    for (at_ticks, state) in transitions {
+                        let now = clock.tick_now().tick();
+                        if at_ticks > now {
+                            clock.wait_ticks(at_ticks - now).await;
+                        }
+                        switch.set_state(state);
+                    }
+                    Ok(())
+                });
+            }
+            domain
+        })
+        .collect()
+}
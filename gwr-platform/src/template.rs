@@ -0,0 +1,349 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Variables and arithmetic in platform configurations.
+//!
+//! A configuration can declare `variables` with default values and use them
+//! in `${...}` expressions anywhere in its values, so that one template covers
+//! a family of platforms:
+//!
+//! ```yaml
+//! variables:
+//!   columns: 2
+//!   buffer_kib: 4
+//!
+//! fabrics:
+//!   - name: fabric0
+//!     kind: routed
+//!     columns: ${columns}
+//!     rows: 1
+//!     rx_buffer_bytes: ${buffer_kib * 1024}
+//! ```
+//!
+//! A value that is a single expression is replaced by the value of the
+//! expression, so `${columns}` above is the number 2. Expressions within a
+//! longer string are replaced by their text, e.g. `fabric.fabric0@(${c},0)`.
+//! Expressions support integers and floats, the operators `+`, `-`, `*`, `/`
+//! and `%` and parentheses. Division of two integers is integer division.
+//!
+//! The values of the variables are set in the same way as any other value, so
+//! `--set variables.columns=4` (or a sweep of the same path) builds a wider
+//! platform. [env_variables] returns the overrides for the
+//! `GWR_PLATFORM_VAR_<name>` environment variables. A variable declared
+//! without a value must be given one. Variables may use the variables declared
+//! before them and an include can set the variables of the platform that it
+//! includes with its own `variables`.
+//!
+//! Expressions in flow collections, such as `[${a}, ${b}]`, must be quoted
+//! because `{` and `}` are part of the YAML syntax of flow collections.
+
+use std::collections::HashMap;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use serde_yaml::Value;
+
+use crate::overrides::Override;
+
+/// The prefix of environment variables that set the variables of a platform
+pub const ENV_VARIABLE_PREFIX: &str = "GWR_PLATFORM_VAR_";
+
+/// Returns whether a configuration may declare or use variables
+pub(crate) fn is_template(source: &str) -> bool {
+    source.contains("${") || source.lines().any(|line| line.starts_with("variables:"))
+}
+
+/// Returns the overrides that set the variables given by
+/// `GWR_PLATFORM_VAR_<name>` environment variables
+pub fn env_variables() -> Result<Vec<Override>, SimError> {
+    std::env::vars()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(ENV_VARIABLE_PREFIX)
+                .map(|name| (name.to_string(), value))
+        })
+        .map(|(name, value)| {
+            let value = serde_yaml::from_str(&value).map_err(|e| {
+                SimError(format!(
+                    "Invalid value for {ENV_VARIABLE_PREFIX}{name} '{value}': {e}"
+                ))
+            })?;
+            Override::new(&format!("variables.{name}"), value)
+        })
+        .collect()
+}
+
+/// Make sure that a configuration has a `variables` mapping so that
+/// overrides of variables can be applied to it
+pub(crate) fn declare_variables(config: &mut Value) {
+    if let Some(mapping) = config.as_mapping_mut()
+        && !mapping.contains_key("variables")
+    {
+        mapping.insert(
+            Value::String("variables".to_string()),
+            Value::Mapping(serde_yaml::Mapping::new()),
+        );
+    }
+}
+
+/// Remove the `variables` of a configuration and replace every `${...}`
+/// expression in its values with the value of the expression
+pub(crate) fn expand_template(config: &mut Value) -> Result<(), SimError> {
+    let declared = match config.as_mapping_mut() {
+        Some(mapping) => mapping.remove("variables"),
+        None => None,
+    };
+
+    let mut variables = HashMap::new();
+    match declared {
+        None | Some(Value::Null) => {}
+        Some(Value::Mapping(declared)) => {
+            for (name, mut value) in declared {
+                let Some(name) = name.as_str().map(str::to_string) else {
+                    return sim_error!("Invalid variable name {name:?}");
+                };
+                if value.is_null() {
+                    return sim_error!("Variable '{name}' has no value");
+                }
+                expand_value(&mut value, &variables)?;
+                variables.insert(name, value);
+            }
+        }
+        Some(_) => return sim_error!("'variables' must be a mapping of names to values"),
+    }
+    expand_value(config, &variables)
+}
+
+fn expand_value(value: &mut Value, variables: &HashMap<String, Value>) -> Result<(), SimError> {
+    match value {
+        Value::String(s) if s.contains("${") => {
+            *value = expand_string(s, variables)?;
+        }
+        Value::Sequence(list) => {
+            for element in list {
+                expand_value(element, variables)?;
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (_, element) in mapping.iter_mut() {
+                expand_value(element, variables)?;
+            }
+        }
+        Value::Tagged(tagged) => expand_value(&mut tagged.value, variables)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand the expressions in a string, keeping the value of the expression if
+/// it is the whole string
+fn expand_string(s: &str, variables: &HashMap<String, Value>) -> Result<Value, SimError> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            return sim_error!("Unterminated expression in '{s}'");
+        };
+        let expr = &rest[start + 2..start + len];
+        let value = evaluate(expr, variables)
+            .map_err(|e| SimError(format!("Unable to expand '${{{expr}}}' in '{s}': {e}")))?;
+        if start == 0 && len + 1 == rest.len() && out.is_empty() {
+            return Ok(value);
+        }
+        out.push_str(&rest[..start]);
+        out.push_str(&scalar_text(&value).ok_or_else(|| {
+            SimError(format!(
+                "Unable to expand '${{{expr}}}' in '{s}': the value is not a scalar"
+            ))
+        })?);
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// A number in an expression
+#[derive(Clone, Copy, Debug)]
+enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl Number {
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(i) => i as f64,
+            Number::Float(f) => f,
+        }
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value.as_i64() {
+            Some(i) => Some(Number::Int(i)),
+            None => value.as_f64().map(Number::Float),
+        }
+    }
+
+    fn to_value(self) -> Value {
+        match self {
+            Number::Int(i) => Value::Number(i.into()),
+            Number::Float(f) => Value::Number(f.into()),
+        }
+    }
+
+    fn apply(self, op: char, rhs: Number) -> Result<Number, String> {
+        match (self, rhs) {
+            (Number::Int(a), Number::Int(b)) => {
+                let result = match op {
+                    '+' => a.checked_add(b),
+                    '-' => a.checked_sub(b),
+                    '*' => a.checked_mul(b),
+                    '/' | '%' if b == 0 => return Err("division by zero".to_string()),
+                    '/' => a.checked_div(b),
+                    _ => a.checked_rem(b),
+                };
+                result
+                    .map(Number::Int)
+                    .ok_or_else(|| "integer overflow".to_string())
+            }
+            (a, b) => {
+                let (a, b) = (a.as_f64(), b.as_f64());
+                Ok(Number::Float(match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a % b,
+                }))
+            }
+        }
+    }
+}
+
+/// Evaluate an expression. A lone variable keeps its value, whatever its type.
+fn evaluate(expr: &str, variables: &HashMap<String, Value>) -> Result<Value, String> {
+    let trimmed = expr.trim();
+    if is_identifier(trimmed) {
+        return variables
+            .get(trimmed)
+            .cloned()
+            .ok_or_else(|| format!("undefined variable '{trimmed}'"));
+    }
+
+    let mut parser = Parser {
+        chars: expr.chars().collect(),
+        pos: 0,
+        variables,
+    };
+    let number = parser.expr()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(format!("unexpected '{}'", parser.chars[parser.pos]));
+    }
+    Ok(number.to_value())
+}
+
+fn is_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A recursive descent parser of arithmetic expressions
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    variables: &'a HashMap<String, Value>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expr(&mut self) -> Result<Number, String> {
+        let mut value = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            value = value.apply(op, self.term()?)?;
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Number, String> {
+        let mut value = self.unary()?;
+        while let Some(op @ ('*' | '/' | '%')) = self.peek() {
+            self.pos += 1;
+            value = value.apply(op, self.unary()?)?;
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<Number, String> {
+        if self.peek() == Some('-') {
+            self.pos += 1;
+            return Number::Int(0).apply('-', self.unary()?);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Number, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.expr()?;
+                if self.peek() != Some(')') {
+                    return Err("missing ')'".to_string());
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let token = self.token(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+                parse_number(&token).ok_or_else(|| format!("invalid number '{token}'"))
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let name = self.token(|c| c.is_ascii_alphanumeric() || c == '_');
+                let value = self
+                    .variables
+                    .get(&name)
+                    .ok_or_else(|| format!("undefined variable '{name}'"))?;
+                Number::from_value(value).ok_or_else(|| format!("'{name}' is not a number"))
+            }
+            Some(c) => Err(format!("unexpected '{c}'")),
+            None => Err("missing value".to_string()),
+        }
+    }
+
+    fn token(&mut self, matches: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| matches(*c)) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+fn parse_number(token: &str) -> Option<Number> {
+    let token = token.replace('_', "");
+    if let Some(hex) = token.strip_prefix("0x") {
+        return i64::from_str_radix(hex, 16).ok().map(Number::Int);
+    }
+    match token.parse::<i64>() {
+        Ok(i) => Some(Number::Int(i)),
+        Err(_) => token.parse::<f64>().ok().map(Number::Float),
+    }
+}
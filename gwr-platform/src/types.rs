@@ -2,7 +2,10 @@
 
 use byte_unit::Byte;
 use clap::ValueEnum;
+use gwr_engine::power::PowerState;
+use gwr_models::fabric::FabricTopology;
 use gwr_models::fabric::node::FabricRoutingAlgorithm;
+use gwr_models::memory::memory_map::InterleaveHash;
 use serde::{Deserialize, Serialize, de};
 use serde_yaml::Value;
 
@@ -76,46 +79,81 @@ where
     Ok(Some(parse_u64_byte_str(deserializer)?))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct PlatformConfig {
+    pub includes: Option<Vec<IncludeSection>>,
     pub memory_maps: Vec<MemoryMapSection>,
     pub defaults: Option<DefaultsSection>,
     pub processing_elements: Option<Vec<ProcessingElementSection>>,
+    pub dma_engines: Option<Vec<DmaEngineSection>>,
+    pub accelerators: Option<Vec<AcceleratorSection>>,
     pub caches: Option<Vec<CacheSection>>,
     pub fabrics: Option<Vec<FabricSection>>,
     pub memories: Option<Vec<MemorySection>>,
+    pub devices: Option<Vec<DeviceSection>>,
     pub connections: Option<Vec<ConnectSection>>,
+    #[serde(
+        default,
+        deserialize_with = "serde_yaml::with::singleton_map_recursive::deserialize"
+    )]
+    pub topologies: Option<Vec<TopologySection>>,
+    pub groups: Option<Vec<GroupSection>>,
+    pub group_links: Option<Vec<GroupLinkSection>>,
+    pub power_domains: Option<Vec<PowerDomainSection>>,
+}
+
+/// A platform file whose components are added with `prefix.` before their
+/// names
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct IncludeSection {
+    pub path: String,
+    pub prefix: String,
+    pub variables: Option<serde_yaml::Mapping>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct DefaultsSection {
     pub pe_config: Option<ProcessingElementConfigSection>,
     pub cache_config: Option<CacheConfigSection>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct MemoryMapSection {
     pub name: String,
     pub devices: Vec<MemoryDeviceSection>,
+    pub interleave: Option<InterleaveSection>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Interleave the devices of a memory map rather than mapping them one after
+/// the other. The devices must be contiguous and of equal capacity.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct InterleaveSection {
+    #[serde(deserialize_with = "parse_u64_byte_str")]
+    pub granule_bytes: u64,
+    pub hash: Option<InterleaveHash>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct MemoryDeviceSection {
     pub name: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ProcessingElementSection {
     pub name: String,
     pub memory_map: String,
     pub config: ProcessingElementConfigSection,
+    pub clock_mhz: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct ProcessingElementConfigSection {
     pub num_active_requests: Option<usize>,
@@ -126,16 +164,74 @@ pub struct ProcessingElementConfigSection {
     pub adds_per_tick: Option<f64>,
     pub muls_per_tick: Option<f64>,
     pub compares_per_tick: Option<f64>,
+    pub cost_model: Option<CostModelSection>,
+    pub preemption_ticks: Option<usize>,
+    pub scratchpad: Option<ScratchpadSection>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// A standalone DMA engine that performs the DMA tasks of a timetable. Its
+/// copies are staged in its `scratchpad`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DmaEngineSection {
+    pub name: String,
+    pub memory_map: String,
+    pub config: DmaEngineConfigSection,
+    pub clock_mhz: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DmaEngineConfigSection {
+    pub num_active_requests: Option<usize>,
+    pub lsu_access_bytes: Option<usize>,
+    pub overhead_size_bytes: Option<usize>,
+    pub scratchpad: ScratchpadSection,
+}
+
+/// A fixed-function accelerator that performs the compute tasks of a
+/// timetable. It is configured in the same way as a PE.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AcceleratorSection {
+    pub name: String,
+    pub memory_map: String,
+    pub config: ProcessingElementConfigSection,
+    pub clock_mhz: Option<f64>,
+}
+
+/// A scratchpad memory local to a PE that is filled and drained by DMA tasks
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ScratchpadSection {
+    #[serde(deserialize_with = "parse_u64_byte_str")]
+    pub base_address: u64,
+    #[serde(deserialize_with = "parse_u64_byte_str")]
+    pub capacity_bytes: u64,
+    pub bw_bytes_per_cycle: Option<usize>,
+    pub dma_channels: Option<usize>,
+}
+
+/// A latency table cost model for the compute tasks of a PE
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CostModelSection {
+    pub issue_width: Option<usize>,
+    pub pipelined: Option<bool>,
+    pub add_latency_ticks: Option<usize>,
+    pub mul_latency_ticks: Option<usize>,
+    pub compare_latency_ticks: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct CacheSection {
     pub name: String,
     pub config: CacheConfigSection,
+    pub clock_mhz: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct CacheConfigSection {
     pub bw_bytes_per_cycle: Option<usize>,
@@ -145,7 +241,7 @@ pub struct CacheConfigSection {
     pub delay_ticks: Option<usize>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct FabricSection {
     pub name: String,
@@ -159,9 +255,11 @@ pub struct FabricSection {
     pub tx_buffer_bytes: Option<usize>,
     pub port_bits_per_tick: Option<usize>,
     pub routing: Option<FabricRoutingAlgorithm>,
+    pub topology: Option<FabricTopology>,
+    pub clock_mhz: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct MemorySection {
     pub name: String,
@@ -172,6 +270,19 @@ pub struct MemorySection {
     pub capacity_bytes: u64,
     pub bw_bytes_per_cycle: Option<usize>,
     pub delay_ticks: Option<usize>,
+    pub clock_mhz: Option<f64>,
+}
+
+/// A device of a kind that is registered with a
+/// [DeviceRegistry](crate::device::DeviceRegistry). Its `config` is passed to
+/// the builder of the kind as it is.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DeviceSection {
+    pub name: String,
+    pub kind: String,
+    pub config: Option<Value>,
+    pub clock_mhz: Option<f64>,
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, Serialize, ValueEnum)]
@@ -188,8 +299,140 @@ pub enum MemoryKind {
     DDR,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// A connection between two components or, when `attach` is given, the
+/// [attachment](crate::attach) of components to the nodes of `fabric`
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ConnectSection {
+    #[serde(default)]
     pub connect: Vec<String>,
+    pub fabric: Option<String>,
+    pub attach: Option<Vec<String>>,
+}
+
+/// A group of components, such as a die or socket. Each member is the name of
+/// a component or a prefix that matches every component whose name starts
+/// with `member.`, such as the prefix of an included platform.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GroupSection {
+    pub name: String,
+    pub members: Vec<String>,
+}
+
+/// A link between fabric ports of two different groups with a latency and
+/// bandwidth in each direction
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GroupLinkSection {
+    pub connect: Vec<String>,
+    pub delay_ticks: Option<usize>,
+    pub bits_per_tick: Option<usize>,
+    pub clock_mhz: Option<f64>,
+}
+
+/// A [power domain](crate::power) of components that share a supply voltage
+/// and are powered on and off together. Members are given in the same way as
+/// the members of a [GroupSection].
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PowerDomainSection {
+    pub name: String,
+    pub members: Vec<String>,
+    pub voltage_mv: Option<f64>,
+    pub state: Option<PowerStateKind>,
+    pub transitions: Option<Vec<PowerTransitionSection>>,
+}
+
+/// A change to the state of a power domain at a tick of the platform clock
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PowerTransitionSection {
+    pub at_ticks: u64,
+    pub state: PowerStateKind,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerStateKind {
+    On,
+    Off,
+    Retention,
+}
+
+impl From<PowerStateKind> for PowerState {
+    fn from(kind: PowerStateKind) -> Self {
+        match kind {
+            PowerStateKind::On => PowerState::On,
+            PowerStateKind::Off => PowerState::Off,
+            PowerStateKind::Retention => PowerState::Retention,
+        }
+    }
+}
+
+/// A regular arrangement of PEs around a fabric that is expanded into PEs,
+/// a fabric and the connections between them
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum TopologySection {
+    /// A PE at every node of a `columns` x `rows` mesh fabric
+    Mesh(MeshTopologySection),
+
+    /// `size` PEs around a ring fabric
+    Ring(RingTopologySection),
+
+    /// `size` PEs connected to a single fabric node
+    Star(StarTopologySection),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MeshTopologySection {
+    pub name: String,
+    pub columns: usize,
+    pub rows: usize,
+    pub pe: TopologyPeSection,
+    pub fabric: Option<TopologyFabricSection>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RingTopologySection {
+    pub name: String,
+    pub size: usize,
+    pub pe: TopologyPeSection,
+    pub fabric: Option<TopologyFabricSection>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct StarTopologySection {
+    pub name: String,
+    pub size: usize,
+    pub pe: TopologyPeSection,
+    pub fabric: Option<TopologyFabricSection>,
+}
+
+/// The PEs created by a topology
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TopologyPeSection {
+    pub memory_map: String,
+    pub config: ProcessingElementConfigSection,
+    pub clock_mhz: Option<f64>,
+}
+
+/// The fabric created by a topology
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TopologyFabricSection {
+    pub kind: Option<FabricKind>,
+    pub fabric_ports_per_node: Option<usize>,
+    pub ticks_per_hop: Option<usize>,
+    pub ticks_overhead: Option<usize>,
+    pub rx_buffer_bytes: Option<usize>,
+    pub tx_buffer_bytes: Option<usize>,
+    pub port_bits_per_tick: Option<usize>,
+    pub routing: Option<FabricRoutingAlgorithm>,
+    pub clock_mhz: Option<f64>,
 }
@@ -0,0 +1,784 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Validation of platform configuration files.
+//!
+//! A configuration is first checked against the schema of [PlatformConfig],
+//! which rejects unknown keys and values of the wrong type. The names that it
+//! references are then checked so that, for example, a connection naming a PE
+//! that does not exist is reported before anything is built.
+//!
+//! Every problem is reported as a [Diagnostic] giving the line and column in
+//! the configuration where it was found. The locations of reference errors are
+//! found by searching the section of the configuration that contains them, so
+//! they point at the first matching occurrence when YAML aliases are used.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+
+use gwr_engine::types::SimError;
+use serde_yaml::Value;
+
+use crate::attach::expand_attachments;
+use crate::builder::DEFAULT_FABRIC_PORTS_PER_NODE;
+use crate::connect::{FABRIC_PORT_RE, split_name_and_port};
+use crate::format::PlatformFormat;
+use crate::generate::expand_topologies;
+use crate::group::member_matches;
+use crate::include::resolve_includes;
+use crate::overrides::{Override, apply_overrides};
+use crate::template::{declare_variables, expand_template, is_template};
+use crate::types::{FabricSection, PlatformConfig};
+
+/// A problem found in a platform configuration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    line: Option<usize>,
+    column: Option<usize>,
+    message: String,
+}
+
+impl Diagnostic {
+    /// The one-based line of the problem, if known
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// The one-based column of the problem, if known
+    #[must_use]
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "line {line}, column {column}: {}", self.message)
+            }
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Parse and validate a platform configuration, resolving its includes
+/// relative to the current directory and expanding any topologies that it
+/// contains.
+///
+/// Returns a `SimError` listing every [Diagnostic] if the configuration is
+/// invalid.
+pub fn parse_platform_config(source: &str) -> Result<PlatformConfig, SimError> {
+    parse_platform_config_in(source, Path::new("."))
+}
+
+/// Parse and validate a platform configuration, resolving its includes
+/// relative to `dir`.
+pub fn parse_platform_config_in(source: &str, dir: &Path) -> Result<PlatformConfig, SimError> {
+    parse_platform_config_with_overrides(source, dir, &[])
+}
+
+/// Parse and validate a platform configuration, resolving its includes
+/// relative to `dir`, after applying [overrides](crate::overrides) to it.
+pub fn parse_platform_config_with_overrides(
+    source: &str,
+    dir: &Path,
+    overrides: &[Override],
+) -> Result<PlatformConfig, SimError> {
+    parse_platform_config_as(source, PlatformFormat::Yaml, dir, overrides)
+}
+
+/// Parse and validate a platform configuration in the given format,
+/// resolving its includes relative to `dir`, after applying
+/// [overrides](crate::overrides) to it.
+pub fn parse_platform_config_as(
+    source: &str,
+    format: PlatformFormat,
+    dir: &Path,
+    overrides: &[Override],
+) -> Result<PlatformConfig, SimError> {
+    parse_included_platform_config(source, format, dir, overrides, &mut Vec::new())
+}
+
+pub(crate) fn parse_included_platform_config(
+    source: &str,
+    format: PlatformFormat,
+    dir: &Path,
+    overrides: &[Override],
+    stack: &mut Vec<PathBuf>,
+) -> Result<PlatformConfig, SimError> {
+    let mut cfg: PlatformConfig =
+        if format == PlatformFormat::Yaml && overrides.is_empty() && !is_template(source) {
+            serde_yaml::from_str(source).map_err(|e| diagnostics_error(&[schema_diagnostic(&e)]))?
+        } else {
+            // The locations of schema errors are lost once the source has been
+            // converted to a YAML value
+            let mut value = parse_value(source, format).map_err(|d| diagnostics_error(&[d]))?;
+            declare_variables(&mut value);
+            apply_overrides(&mut value, overrides)?;
+            expand_template(&mut value).map_err(|e| {
+                diagnostics_error(&[Diagnostic {
+                    line: None,
+                    column: None,
+                    message: e.0,
+                }])
+            })?;
+            serde_yaml::from_value(value)
+                .map_err(|e| diagnostics_error(&[schema_diagnostic(&e)]))?
+        };
+    resolve_includes(&mut cfg, dir, overrides, stack)?;
+    expand_topologies(&mut cfg)
+        .and_then(|()| expand_attachments(&mut cfg))
+        .map_err(|e| {
+            diagnostics_error(&[Diagnostic {
+                line: None,
+                column: None,
+                message: e.0,
+            }])
+        })?;
+    let diagnostics = check_references(&cfg, source);
+    if diagnostics.is_empty() {
+        Ok(cfg)
+    } else {
+        Err(diagnostics_error(&diagnostics))
+    }
+}
+
+/// Check that every name referenced by a parsed configuration exists and that
+/// names are unique, using `source` to locate the problems.
+#[must_use]
+pub fn check_references(cfg: &PlatformConfig, source: &str) -> Vec<Diagnostic> {
+    let mut checker = ReferenceChecker::new(source);
+    checker.check_memory_maps(cfg);
+    checker.check_processing_elements(cfg);
+    checker.check_dma_engines(cfg);
+    checker.check_accelerators(cfg);
+    checker.check_unique_names(
+        "caches",
+        "cache",
+        cfg.caches.iter().flatten().map(|cache| cache.name.as_str()),
+    );
+    checker.check_unique_names(
+        "fabrics",
+        "fabric",
+        cfg.fabrics
+            .iter()
+            .flatten()
+            .map(|fabric| fabric.name.as_str()),
+    );
+    checker.check_memories(cfg);
+    checker.check_devices(cfg);
+    checker.check_connections(cfg);
+    checker.check_groups(cfg);
+    checker.check_group_links(cfg);
+    checker.check_power_domains(cfg);
+    checker.diagnostics
+}
+
+fn diagnostics_error(diagnostics: &[Diagnostic]) -> SimError {
+    let mut message = "Invalid platform configuration:".to_string();
+    for diagnostic in diagnostics {
+        message.push_str(&format!("\n  {diagnostic}"));
+    }
+    SimError(message)
+}
+
+/// Parse a configuration in any format as a YAML value
+fn parse_value(source: &str, format: PlatformFormat) -> Result<Value, Diagnostic> {
+    match format {
+        PlatformFormat::Yaml => serde_yaml::from_str(source).map_err(|e| schema_diagnostic(&e)),
+        PlatformFormat::Json => serde_json::from_str(source).map_err(|e| {
+            let message = e.to_string();
+            let suffix = format!(" at line {} column {}", e.line(), e.column());
+            Diagnostic {
+                line: Some(e.line()),
+                column: Some(e.column()),
+                message: message
+                    .strip_suffix(&suffix)
+                    .map_or(message.clone(), str::to_string),
+            }
+        }),
+        PlatformFormat::Toml => toml::from_str(source).map_err(|e| {
+            let (line, column) = match e.span() {
+                Some(span) => {
+                    let before = &source[..span.start];
+                    let line = before.matches('\n').count() + 1;
+                    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+                    (Some(line), Some(column))
+                }
+                None => (None, None),
+            };
+            Diagnostic {
+                line,
+                column,
+                message: e.message().to_string(),
+            }
+        }),
+    }
+}
+
+fn schema_diagnostic(error: &serde_yaml::Error) -> Diagnostic {
+    let message = error.to_string();
+    match error.location() {
+        Some(location) => {
+            // Remove the location from the message as it is reported separately
+            let suffix = format!(" at line {} column {}", location.line(), location.column());
+            Diagnostic {
+                line: Some(location.line()),
+                column: Some(location.column()),
+                message: message
+                    .strip_suffix(&suffix)
+                    .map_or(message.clone(), str::to_string),
+            }
+        }
+        None => Diagnostic {
+            line: None,
+            column: None,
+            message,
+        },
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+struct ReferenceChecker<'a> {
+    lines: Vec<&'a str>,
+    diagnostics: Vec<Diagnostic>,
+
+    /// The number of times each name has been seen in each section so that
+    /// repeated names are located at the right occurrence
+    occurrences: HashMap<(&'static str, String), usize>,
+    devices: HashSet<String>,
+}
+
+impl<'a> ReferenceChecker<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            lines: source.lines().collect(),
+            diagnostics: Vec::new(),
+            occurrences: HashMap::new(),
+            devices: HashSet::new(),
+        }
+    }
+
+    /// Returns the range of lines of a top-level section
+    fn section_lines(&self, section: &str) -> Option<(usize, usize)> {
+        let header = format!("{section}:");
+        let start = self
+            .lines
+            .iter()
+            .position(|line| line.starts_with(&header))?;
+        let end = self.lines[start + 1..]
+            .iter()
+            .position(|line| line.starts_with(|c: char| is_name_char(c)))
+            .map_or(self.lines.len(), |offset| start + 1 + offset);
+        Some((start, end))
+    }
+
+    /// Find the `occurrence`th use of `name` in a section, returning its
+    /// one-based line and column
+    fn locate(&self, section: &str, name: &str, occurrence: usize) -> Option<(usize, usize)> {
+        let (start, end) = self.section_lines(section)?;
+        (start..end)
+            .flat_map(|index| {
+                let line = self.lines[index];
+                line.match_indices(name)
+                    .filter(move |(column, _)| {
+                        let before = line[..*column].chars().next_back();
+                        let after = line[column + name.len()..].chars().next();
+                        !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+                    })
+                    .map(move |(column, _)| (index + 1, column + 1))
+            })
+            .nth(occurrence)
+    }
+
+    /// Record a use of `name` in a section, returning its location
+    fn seen(&mut self, section: &'static str, name: &str) -> Option<(usize, usize)> {
+        let occurrence = self
+            .occurrences
+            .entry((section, name.to_string()))
+            .or_default();
+        let location_occurrence = *occurrence;
+        *occurrence += 1;
+        self.locate(section, name, location_occurrence)
+    }
+
+    fn report(&mut self, location: Option<(usize, usize)>, message: String) {
+        self.diagnostics.push(Diagnostic {
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            message,
+        });
+    }
+
+    fn check_unique_names<'n>(
+        &mut self,
+        section: &'static str,
+        kind: &str,
+        names: impl Iterator<Item = &'n str>,
+    ) {
+        let mut seen = HashSet::new();
+        for name in names {
+            let location = self.seen(section, name);
+            if !seen.insert(name) {
+                self.report(location, format!("Duplicate {kind} name {name}"));
+            }
+        }
+    }
+
+    fn check_device_name(&mut self, section: &'static str, name: &str) {
+        let location = self.seen(section, name);
+        if !self.devices.insert(name.to_string()) {
+            self.report(location, format!("Duplicate device name {name}"));
+        }
+    }
+
+    fn check_memory_maps(&mut self, cfg: &PlatformConfig) {
+        let memories: HashSet<&str> = cfg
+            .memories
+            .iter()
+            .flatten()
+            .map(|memory| memory.name.as_str())
+            .collect();
+        let mut names = HashSet::new();
+        for memory_map in &cfg.memory_maps {
+            let location = self.seen("memory_maps", &memory_map.name);
+            if !names.insert(memory_map.name.as_str()) {
+                self.report(
+                    location,
+                    format!("Duplicate memory map name {}", memory_map.name),
+                );
+            }
+            for device in &memory_map.devices {
+                let location = self.seen("memory_maps", &device.name);
+                if !memories.contains(device.name.as_str()) {
+                    self.report(
+                        location,
+                        format!(
+                            "Unknown memory '{}' in memory map '{}'",
+                            device.name, memory_map.name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_processing_elements(&mut self, cfg: &PlatformConfig) {
+        for pe in cfg.processing_elements.iter().flatten() {
+            self.check_memory_map_user(cfg, "processing_elements", "PE", &pe.name, &pe.memory_map);
+        }
+    }
+
+    fn check_dma_engines(&mut self, cfg: &PlatformConfig) {
+        for dma in cfg.dma_engines.iter().flatten() {
+            self.check_memory_map_user(
+                cfg,
+                "dma_engines",
+                "DMA engine",
+                &dma.name,
+                &dma.memory_map,
+            );
+        }
+    }
+
+    fn check_accelerators(&mut self, cfg: &PlatformConfig) {
+        for acc in cfg.accelerators.iter().flatten() {
+            self.check_memory_map_user(
+                cfg,
+                "accelerators",
+                "accelerator",
+                &acc.name,
+                &acc.memory_map,
+            );
+        }
+    }
+
+    /// Check a PE, DMA engine or accelerator, which has a device name and
+    /// uses a memory map
+    fn check_memory_map_user(
+        &mut self,
+        cfg: &PlatformConfig,
+        section: &'static str,
+        kind: &str,
+        name: &str,
+        memory_map: &str,
+    ) {
+        self.check_device_name(section, name);
+        let location = self.seen(section, memory_map);
+        if !cfg.memory_maps.iter().any(|m| m.name == memory_map) {
+            self.report(
+                location,
+                format!("Unknown memory map '{memory_map}' for {kind} '{name}'"),
+            );
+        }
+    }
+
+    fn check_memories(&mut self, cfg: &PlatformConfig) {
+        for memory in cfg.memories.iter().flatten() {
+            self.check_device_name("memories", &memory.name);
+        }
+    }
+
+    fn check_devices(&mut self, cfg: &PlatformConfig) {
+        for device in cfg.devices.iter().flatten() {
+            self.check_device_name("devices", &device.name);
+        }
+    }
+
+    fn check_connections(&mut self, cfg: &PlatformConfig) {
+        for connection in cfg.connections.iter().flatten() {
+            let location = self.seen("connections", "connect");
+            if connection.connect.len() != 2 {
+                self.report(
+                    location,
+                    format!(
+                        "Invalid 'connect' with {} entries (only 2 expected)",
+                        connection.connect.len()
+                    ),
+                );
+            }
+            for endpoint in &connection.connect {
+                let location = self.seen("connections", endpoint);
+                if let Err(message) = check_endpoint(cfg, endpoint) {
+                    self.report(location, message);
+                }
+            }
+        }
+    }
+
+    fn check_groups(&mut self, cfg: &PlatformConfig) {
+        let components: Vec<&str> = component_names(cfg).collect();
+        let mut names = HashSet::new();
+        let mut component_groups: HashMap<&str, &str> = HashMap::new();
+        for group in cfg.groups.iter().flatten() {
+            let location = self.seen("groups", &group.name);
+            if !names.insert(group.name.as_str()) {
+                self.report(location, format!("Duplicate group name {}", group.name));
+            }
+            for member in &group.members {
+                let location = self.seen("groups", member);
+                let mut matched = false;
+                for &component in &components {
+                    if !member_matches(member, component) {
+                        continue;
+                    }
+                    matched = true;
+                    match component_groups.insert(component, &group.name) {
+                        Some(other) if other != group.name => self.report(
+                            location,
+                            format!("'{component}' is in groups '{other}' and '{}'", group.name),
+                        ),
+                        _ => {}
+                    }
+                }
+                if !matched {
+                    self.report(
+                        location,
+                        format!("Unknown member '{member}' in group '{}'", group.name),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_power_domains(&mut self, cfg: &PlatformConfig) {
+        let components: Vec<&str> = component_names(cfg).collect();
+        let mut names = HashSet::new();
+        let mut component_domains: HashMap<&str, &str> = HashMap::new();
+        for domain in cfg.power_domains.iter().flatten() {
+            let location = self.seen("power_domains", &domain.name);
+            if !names.insert(domain.name.as_str()) {
+                self.report(
+                    location,
+                    format!("Duplicate power domain name {}", domain.name),
+                );
+            }
+            if let Some(voltage_mv) = domain.voltage_mv
+                && voltage_mv <= 0.0
+            {
+                self.report(
+                    location,
+                    format!(
+                        "Power domain '{}' must have a positive voltage_mv",
+                        domain.name
+                    ),
+                );
+            }
+            let transitions = domain.transitions.iter().flatten();
+            if transitions
+                .clone()
+                .zip(transitions.skip(1))
+                .any(|(a, b)| b.at_ticks < a.at_ticks)
+            {
+                self.report(
+                    location,
+                    format!(
+                        "The transitions of power domain '{}' must be in order of at_ticks",
+                        domain.name
+                    ),
+                );
+            }
+            for member in &domain.members {
+                let location = self.seen("power_domains", member);
+                let mut matched = false;
+                for &component in &components {
+                    if !member_matches(member, component) {
+                        continue;
+                    }
+                    matched = true;
+                    match component_domains.insert(component, &domain.name) {
+                        Some(other) if other != domain.name => self.report(
+                            location,
+                            format!(
+                                "'{component}' is in power domains '{other}' and '{}'",
+                                domain.name
+                            ),
+                        ),
+                        _ => {}
+                    }
+                }
+                if !matched {
+                    self.report(
+                        location,
+                        format!(
+                            "Unknown member '{member}' in power domain '{}'",
+                            domain.name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_group_links(&mut self, cfg: &PlatformConfig) {
+        let fabric_group = |fabric: &str| {
+            cfg.groups
+                .iter()
+                .flatten()
+                .find(|group| {
+                    group
+                        .members
+                        .iter()
+                        .any(|member| member_matches(member, fabric))
+                })
+                .map(|group| group.name.as_str())
+        };
+        for link in cfg.group_links.iter().flatten() {
+            let location = self.seen("group_links", "connect");
+            if link.connect.len() != 2 {
+                self.report(
+                    location,
+                    format!(
+                        "Invalid 'connect' with {} entries (only 2 expected)",
+                        link.connect.len()
+                    ),
+                );
+                continue;
+            }
+            if link.bits_per_tick == Some(0) {
+                self.report(
+                    location,
+                    "Invalid bits_per_tick 0 for group link (must be greater than 0)".to_string(),
+                );
+            }
+
+            let mut groups = Vec::new();
+            for endpoint in &link.connect {
+                let location = self.seen("group_links", endpoint);
+                if !endpoint.starts_with("fabric.") {
+                    self.report(
+                        location,
+                        format!("Group link endpoint '{endpoint}' is not a Fabric port"),
+                    );
+                } else if let Err(message) = check_fabric_endpoint(cfg, endpoint) {
+                    self.report(location, message);
+                } else {
+                    let fabric = &FABRIC_PORT_RE.captures(endpoint).expect("checked above")[1];
+                    match fabric_group(fabric) {
+                        Some(group) => groups.push(group),
+                        None => self.report(
+                            location,
+                            format!("Fabric '{fabric}' of group link is not in a group"),
+                        ),
+                    }
+                }
+            }
+            if let [from, to] = groups.as_slice()
+                && from == to
+            {
+                self.report(
+                    location,
+                    format!("Group link connects group '{from}' to itself"),
+                );
+            }
+        }
+    }
+}
+
+/// Returns the names of every PE, DMA engine, accelerator, cache, fabric,
+/// memory and device of a configuration
+pub(crate) fn component_names(cfg: &PlatformConfig) -> impl Iterator<Item = &str> {
+    let pes = cfg
+        .processing_elements
+        .iter()
+        .flatten()
+        .map(|pe| &pe.name)
+        .chain(cfg.dma_engines.iter().flatten().map(|dma| &dma.name))
+        .chain(cfg.accelerators.iter().flatten().map(|acc| &acc.name));
+    let caches = cfg.caches.iter().flatten().map(|cache| &cache.name);
+    let fabrics = cfg.fabrics.iter().flatten().map(|fabric| &fabric.name);
+    let memories = cfg.memories.iter().flatten().map(|memory| &memory.name);
+    let devices = cfg.devices.iter().flatten().map(|device| &device.name);
+    pes.chain(caches)
+        .chain(fabrics)
+        .chain(memories)
+        .chain(devices)
+        .map(String::as_str)
+}
+
+fn names<T>(sections: Option<&Vec<T>>, name: impl Fn(&T) -> &str) -> HashSet<&str> {
+    sections.into_iter().flatten().map(name).collect()
+}
+
+/// Check that a connection endpoint names something that exists
+fn check_endpoint(cfg: &PlatformConfig, endpoint: &str) -> Result<(), String> {
+    if endpoint.starts_with("fabric.") {
+        return check_fabric_endpoint(cfg, endpoint);
+    }
+
+    let Some((kind, rest)) = endpoint.split_once('.') else {
+        return Err(format!("Unable to parse connection endpoint '{endpoint}'"));
+    };
+
+    match kind {
+        "pe" => {
+            let pes = names(cfg.processing_elements.as_ref(), |pe| &pe.name);
+            let (name, port) = split_name_and_port(rest, |name| pes.contains(name));
+            if !pes.contains(name) {
+                return Err(format!("Unknown PE '{name}' in connection"));
+            }
+            if port.is_some() {
+                return Err(format!("Cannot specify a port for PE '{name}'"));
+            }
+        }
+        "dma" => {
+            let dma_engines = names(cfg.dma_engines.as_ref(), |dma| &dma.name);
+            let (name, port) = split_name_and_port(rest, |name| dma_engines.contains(name));
+            if !dma_engines.contains(name) {
+                return Err(format!("Unknown DMA engine '{name}' in connection"));
+            }
+            if port.is_some() {
+                return Err(format!("Cannot specify a port for DMA engine '{name}'"));
+            }
+        }
+        "acc" => {
+            let accelerators = names(cfg.accelerators.as_ref(), |acc| &acc.name);
+            let (name, port) = split_name_and_port(rest, |name| accelerators.contains(name));
+            if !accelerators.contains(name) {
+                return Err(format!("Unknown accelerator '{name}' in connection"));
+            }
+            if port.is_some() {
+                return Err(format!("Cannot specify a port for accelerator '{name}'"));
+            }
+        }
+        "cache" => {
+            let caches = names(cfg.caches.as_ref(), |cache| &cache.name);
+            let (name, port) = split_name_and_port(rest, |name| caches.contains(name));
+            if !caches.contains(name) {
+                return Err(format!("Unknown Cache '{name}' in connection"));
+            }
+            if let Some(port) = port
+                && port != "dev"
+                && port != "mem"
+            {
+                return Err(format!(
+                    "Unknown port '{port}' on Cache '{name}' (expected 'dev' or 'mem')"
+                ));
+            }
+        }
+        "mem" => {
+            let memories = names(cfg.memories.as_ref(), |memory| &memory.name);
+            let (name, port) = split_name_and_port(rest, |name| memories.contains(name));
+            if !memories.contains(name) {
+                return Err(format!("Unknown Memory '{name}' in connection"));
+            }
+            if port.is_some() {
+                return Err(format!("Cannot specify a port for Memory '{name}'"));
+            }
+        }
+        "dev" => {
+            let devices = names(cfg.devices.as_ref(), |device| &device.name);
+            let (name, port) = split_name_and_port(rest, |name| devices.contains(name));
+            if !devices.contains(name) {
+                return Err(format!("Unknown Device '{name}' in connection"));
+            }
+            if port.is_some() {
+                return Err(format!("Cannot specify a port for Device '{name}'"));
+            }
+        }
+        _ => {
+            return Err(format!(
+                "Unsupported kind '{kind}' in connection endpoint '{endpoint}'"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_fabric_endpoint(cfg: &PlatformConfig, endpoint: &str) -> Result<(), String> {
+    let Some(caps) = FABRIC_PORT_RE.captures(endpoint) else {
+        return Err(format!("Unable to parse Fabric port '{endpoint}'"));
+    };
+    let name = &caps[1];
+    let Some(fabric) = cfg
+        .fabrics
+        .iter()
+        .flatten()
+        .find(|fabric| fabric.name == name)
+    else {
+        return Err(format!("Unknown Fabric '{name}' in connection"));
+    };
+
+    let parse = |s: &str| {
+        s.parse::<usize>()
+            .map_err(|e| format!("Unable to parse Fabric port '{endpoint}': {e}"))
+    };
+    let col = parse(&caps[2])?;
+    let row = parse(&caps[3])?;
+    let port = caps.get(4).map_or(Ok(0), |port| parse(port.as_str()))?;
+    check_fabric_port(fabric, col, row, port)
+        .map_err(|problem| format!("Fabric port '{endpoint}' {problem}"))
+}
+
+pub(crate) fn check_fabric_port(
+    fabric: &FabricSection,
+    col: usize,
+    row: usize,
+    port: usize,
+) -> Result<(), String> {
+    if col >= fabric.columns || row >= fabric.rows {
+        return Err(format!(
+            "is outside the {}x{} fabric",
+            fabric.columns, fabric.rows
+        ));
+    }
+    let ports_per_node = fabric
+        .fabric_ports_per_node
+        .unwrap_or(DEFAULT_FABRIC_PORTS_PER_NODE);
+    if port >= ports_per_node {
+        return Err(format!(
+            "uses port {port} but nodes only have {ports_per_node} ports"
+        ));
+    }
+    Ok(())
+}
@@ -9,7 +9,9 @@ use std::fmt::Write as _;
 use serde::Serialize;
 use serde_yaml::Value;
 
-use crate::types::{CacheConfigSection, PlatformConfig, ProcessingElementConfigSection};
+use crate::types::{
+    CacheConfigSection, PlatformConfig, ProcessingElementConfigSection, ScratchpadSection,
+};
 
 /// Format a `u64` as lowercase hexadecimal with a `0x` prefix and underscores
 /// inserted every 4 hex digits (grouped from the right).
@@ -17,7 +19,7 @@ use crate::types::{CacheConfigSection, PlatformConfig, ProcessingElementConfigSe
 /// Examples:
 /// - `0x1000000` -> `0x100_0000`
 /// - `0x100000000` -> `0x1_0000_0000`
-fn u64_hex_str(v: u64) -> String {
+pub(crate) fn u64_hex_str(v: u64) -> String {
     let hex = format!("{v:x}");
     let mut out = String::with_capacity(2 + hex.len() + ((hex.len().saturating_sub(1)) / 4));
     out.push_str("0x");
@@ -93,6 +95,13 @@ fn emit_memory_maps(
         for range in &memory_map.devices {
             emit_line(&mut out, format_args!("- name: {}", range.name), 3)?;
         }
+        if let Some(interleave) = &memory_map.interleave {
+            emit_line(&mut out, "interleave:", 2)?;
+            emit_kv(&mut out, "granule_bytes", interleave.granule_bytes, 3)?;
+            if let Some(hash) = interleave.hash {
+                emit_kv(&mut out, "hash", serializable_to_str(&hash)?, 3)?;
+            }
+        }
     }
     Ok(Some(out))
 }
@@ -124,49 +133,191 @@ fn emit_processing_elements(
 
         emit_line(&mut out, format_args!("- name: {}", pe.name), 1)?;
         emit_line(&mut out, format_args!("memory_map: {}", pe.memory_map), 2)?;
+        emit_optional_kv(&mut out, "clock_mhz", pe.clock_mhz, 2)?;
         if emitted_anchors[config_idx] {
             emit_line(&mut out, format_args!("config: *{anchor}"), 2)?;
         } else {
             emitted_anchors[config_idx] = true;
-            if config.num_active_requests.is_none()
-                && config.lsu_access_bytes.is_none()
-                && config.overhead_size_bytes.is_none()
-                && config.sram_bytes.is_none()
-                && config.adds_per_tick.is_none()
-                && config.muls_per_tick.is_none()
-                && config.compares_per_tick.is_none()
-            {
+            if is_empty_pe_config(config) {
                 emit_line(&mut out, format_args!("config: &{anchor} {{}}"), 2)?;
             } else {
                 emit_line(&mut out, format_args!("config: &{anchor}"), 2)?;
-                emit_optional_kv(
-                    &mut out,
-                    "num_active_requests",
-                    config.num_active_requests,
-                    3,
-                )?;
-                emit_optional_kv(&mut out, "lsu_access_bytes", config.lsu_access_bytes, 3)?;
-                emit_optional_kv(
-                    &mut out,
-                    "overhead_size_bytes",
-                    config.overhead_size_bytes,
-                    3,
-                )?;
-                emit_optional_kv(
-                    &mut out,
-                    "sram_bytes",
-                    config.sram_bytes.map(u64_hex_str),
-                    3,
-                )?;
-                emit_optional_kv(&mut out, "adds_per_tick", config.adds_per_tick, 3)?;
-                emit_optional_kv(&mut out, "muls_per_tick", config.muls_per_tick, 3)?;
-                emit_optional_kv(&mut out, "compares_per_tick", config.compares_per_tick, 3)?;
+                emit_pe_config_fields(&mut out, config, 3)?;
             }
         }
     }
     Ok(Some(out))
 }
 
+fn is_empty_pe_config(config: &ProcessingElementConfigSection) -> bool {
+    config.num_active_requests.is_none()
+        && config.lsu_access_bytes.is_none()
+        && config.overhead_size_bytes.is_none()
+        && config.sram_bytes.is_none()
+        && config.adds_per_tick.is_none()
+        && config.muls_per_tick.is_none()
+        && config.compares_per_tick.is_none()
+        && config.cost_model.is_none()
+        && config.preemption_ticks.is_none()
+        && config.scratchpad.is_none()
+}
+
+fn emit_pe_config_fields(
+    out: &mut String,
+    config: &ProcessingElementConfigSection,
+    indent_level: usize,
+) -> Result<(), std::fmt::Error> {
+    emit_optional_kv(
+        out,
+        "num_active_requests",
+        config.num_active_requests,
+        indent_level,
+    )?;
+    emit_optional_kv(
+        out,
+        "lsu_access_bytes",
+        config.lsu_access_bytes,
+        indent_level,
+    )?;
+    emit_optional_kv(
+        out,
+        "overhead_size_bytes",
+        config.overhead_size_bytes,
+        indent_level,
+    )?;
+    emit_optional_kv(
+        out,
+        "sram_bytes",
+        config.sram_bytes.map(u64_hex_str),
+        indent_level,
+    )?;
+    emit_optional_kv(out, "adds_per_tick", config.adds_per_tick, indent_level)?;
+    emit_optional_kv(out, "muls_per_tick", config.muls_per_tick, indent_level)?;
+    emit_optional_kv(
+        out,
+        "compares_per_tick",
+        config.compares_per_tick,
+        indent_level,
+    )?;
+    emit_optional_kv(
+        out,
+        "preemption_ticks",
+        config.preemption_ticks,
+        indent_level,
+    )?;
+    if let Some(cost_model) = &config.cost_model {
+        emit_line(out, "cost_model:", indent_level)?;
+        let indent_level = indent_level + 1;
+        emit_optional_kv(out, "issue_width", cost_model.issue_width, indent_level)?;
+        emit_optional_kv(out, "pipelined", cost_model.pipelined, indent_level)?;
+        emit_optional_kv(
+            out,
+            "add_latency_ticks",
+            cost_model.add_latency_ticks,
+            indent_level,
+        )?;
+        emit_optional_kv(
+            out,
+            "mul_latency_ticks",
+            cost_model.mul_latency_ticks,
+            indent_level,
+        )?;
+        emit_optional_kv(
+            out,
+            "compare_latency_ticks",
+            cost_model.compare_latency_ticks,
+            indent_level,
+        )?;
+    }
+    if let Some(scratchpad) = &config.scratchpad {
+        emit_scratchpad(out, scratchpad, indent_level)?;
+    }
+    Ok(())
+}
+
+fn emit_scratchpad(
+    out: &mut String,
+    scratchpad: &ScratchpadSection,
+    indent_level: usize,
+) -> Result<(), std::fmt::Error> {
+    emit_line(out, "scratchpad:", indent_level)?;
+    let indent_level = indent_level + 1;
+    emit_kv(
+        out,
+        "base_address",
+        u64_hex_str(scratchpad.base_address),
+        indent_level,
+    )?;
+    emit_kv(
+        out,
+        "capacity_bytes",
+        u64_hex_str(scratchpad.capacity_bytes),
+        indent_level,
+    )?;
+    emit_optional_kv(
+        out,
+        "bw_bytes_per_cycle",
+        scratchpad.bw_bytes_per_cycle,
+        indent_level,
+    )?;
+    emit_optional_kv(out, "dma_channels", scratchpad.dma_channels, indent_level)
+}
+
+fn emit_dma_engines(
+    platform: &PlatformConfig,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(dma_engines) = &platform.dma_engines else {
+        return Ok(None);
+    };
+
+    let mut out = start_section("dma_engines")?;
+
+    for dma in dma_engines {
+        emit_line(&mut out, format_args!("- name: {}", dma.name), 1)?;
+        emit_line(&mut out, format_args!("memory_map: {}", dma.memory_map), 2)?;
+        emit_optional_kv(&mut out, "clock_mhz", dma.clock_mhz, 2)?;
+        emit_line(&mut out, "config:", 2)?;
+        emit_optional_kv(
+            &mut out,
+            "num_active_requests",
+            dma.config.num_active_requests,
+            3,
+        )?;
+        emit_optional_kv(&mut out, "lsu_access_bytes", dma.config.lsu_access_bytes, 3)?;
+        emit_optional_kv(
+            &mut out,
+            "overhead_size_bytes",
+            dma.config.overhead_size_bytes,
+            3,
+        )?;
+        emit_scratchpad(&mut out, &dma.config.scratchpad, 3)?;
+    }
+    Ok(Some(out))
+}
+
+fn emit_accelerators(
+    platform: &PlatformConfig,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(accelerators) = &platform.accelerators else {
+        return Ok(None);
+    };
+
+    let mut out = start_section("accelerators")?;
+
+    for acc in accelerators {
+        emit_line(&mut out, format_args!("- name: {}", acc.name), 1)?;
+        emit_line(&mut out, format_args!("memory_map: {}", acc.memory_map), 2)?;
+        emit_optional_kv(&mut out, "clock_mhz", acc.clock_mhz, 2)?;
+        if is_empty_pe_config(&acc.config) {
+            emit_line(&mut out, "config: {}", 2)?;
+        } else {
+            emit_line(&mut out, "config:", 2)?;
+            emit_pe_config_fields(&mut out, &acc.config, 3)?;
+        }
+    }
+    Ok(Some(out))
+}
+
 fn emit_fabrics(platform: &PlatformConfig) -> Result<Option<String>, Box<dyn std::error::Error>> {
     let Some(fabrics) = &platform.fabrics else {
         return Ok(None);
@@ -183,6 +334,7 @@ fn emit_fabrics(platform: &PlatformConfig) -> Result<Option<String>, Box<dyn std
         )?;
         emit_kv(&mut out, "columns", fabric.columns, 2)?;
         emit_kv(&mut out, "rows", fabric.rows, 2)?;
+        emit_optional_kv(&mut out, "clock_mhz", fabric.clock_mhz, 2)?;
         emit_optional_kv(
             &mut out,
             "fabric_ports_per_node",
@@ -201,6 +353,13 @@ fn emit_fabrics(platform: &PlatformConfig) -> Result<Option<String>, Box<dyn std
                 2,
             )?;
         }
+        if let Some(topology) = fabric.topology {
+            emit_line(
+                &mut out,
+                format_args!("topology: {}", serializable_to_str(&topology)?),
+                2,
+            )?;
+        }
     }
     Ok(Some(out))
 }
@@ -229,6 +388,7 @@ fn emit_caches(platform: &PlatformConfig) -> Result<Option<String>, Box<dyn std:
         let config = &cache.config;
 
         emit_line(&mut out, format_args!("- name: {}", cache.name), 1)?;
+        emit_optional_kv(&mut out, "clock_mhz", cache.clock_mhz, 2)?;
         if emitted_anchors[config_idx] {
             emit_line(&mut out, format_args!("config: *{anchor}"), 2)?;
         } else {
@@ -281,6 +441,28 @@ fn emit_memories(platform: &PlatformConfig) -> Result<Option<String>, Box<dyn st
         )?;
         emit_optional_kv(&mut out, "bw_bytes_per_cycle", memory.bw_bytes_per_cycle, 2)?;
         emit_optional_kv(&mut out, "delay_ticks", memory.delay_ticks, 2)?;
+        emit_optional_kv(&mut out, "clock_mhz", memory.clock_mhz, 2)?;
+    }
+    Ok(Some(out))
+}
+
+fn emit_devices(platform: &PlatformConfig) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(devices) = &platform.devices else {
+        return Ok(None);
+    };
+
+    let mut out = start_section("devices")?;
+
+    for device in devices {
+        emit_line(&mut out, format_args!("- name: {}", device.name), 1)?;
+        emit_kv(&mut out, "kind", &device.kind, 2)?;
+        emit_optional_kv(&mut out, "clock_mhz", device.clock_mhz, 2)?;
+        if let Some(config) = &device.config {
+            emit_line(&mut out, "config:", 2)?;
+            for line in serde_yaml::to_string(config)?.lines() {
+                emit_line(&mut out, line, 3)?;
+            }
+        }
     }
     Ok(Some(out))
 }
@@ -295,6 +477,14 @@ fn emit_connections(
     let mut out = start_section("connections")?;
 
     for connection in connections {
+        if let (Some(fabric), Some(attach)) = (&connection.fabric, &connection.attach) {
+            emit_line(&mut out, format_args!("- fabric: {fabric}"), 1)?;
+            emit_line(&mut out, "attach:", 2)?;
+            for attachment in attach {
+                emit_line(&mut out, format_args!("- {attachment}"), 3)?;
+            }
+            continue;
+        }
         emit_line(&mut out, "- connect:", 1)?;
         for endpoint in &connection.connect {
             emit_line(&mut out, format_args!("- {endpoint}"), 3)?;
@@ -303,6 +493,86 @@ fn emit_connections(
     Ok(Some(out))
 }
 
+fn emit_groups(platform: &PlatformConfig) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(groups) = &platform.groups else {
+        return Ok(None);
+    };
+
+    let mut out = start_section("groups")?;
+
+    for group in groups {
+        emit_line(&mut out, format_args!("- name: {}", group.name), 1)?;
+        emit_line(&mut out, "members:", 2)?;
+        for member in &group.members {
+            emit_line(&mut out, format_args!("- {member}"), 3)?;
+        }
+    }
+    Ok(Some(out))
+}
+
+fn emit_group_links(
+    platform: &PlatformConfig,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(group_links) = &platform.group_links else {
+        return Ok(None);
+    };
+
+    let mut out = start_section("group_links")?;
+
+    for link in group_links {
+        emit_line(&mut out, "- connect:", 1)?;
+        for endpoint in &link.connect {
+            emit_line(&mut out, format_args!("- {endpoint}"), 3)?;
+        }
+        emit_optional_kv(&mut out, "delay_ticks", link.delay_ticks, 2)?;
+        emit_optional_kv(&mut out, "bits_per_tick", link.bits_per_tick, 2)?;
+        emit_optional_kv(&mut out, "clock_mhz", link.clock_mhz, 2)?;
+    }
+    Ok(Some(out))
+}
+
+fn emit_power_domains(
+    platform: &PlatformConfig,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let Some(power_domains) = &platform.power_domains else {
+        return Ok(None);
+    };
+
+    let mut out = start_section("power_domains")?;
+
+    for domain in power_domains {
+        emit_line(&mut out, format_args!("- name: {}", domain.name), 1)?;
+        emit_line(&mut out, "members:", 2)?;
+        for member in &domain.members {
+            emit_line(&mut out, format_args!("- {member}"), 3)?;
+        }
+        emit_optional_kv(&mut out, "voltage_mv", domain.voltage_mv, 2)?;
+        if let Some(state) = &domain.state {
+            emit_line(
+                &mut out,
+                format_args!("state: {}", serializable_to_str(state)?),
+                2,
+            )?;
+        }
+        if let Some(transitions) = &domain.transitions {
+            emit_line(&mut out, "transitions:", 2)?;
+            for transition in transitions {
+                emit_line(
+                    &mut out,
+                    format_args!("- at_ticks: {}", transition.at_ticks),
+                    3,
+                )?;
+                emit_line(
+                    &mut out,
+                    format_args!("state: {}", serializable_to_str(&transition.state)?),
+                    4,
+                )?;
+            }
+        }
+    }
+    Ok(Some(out))
+}
+
 fn emit_optional_section(out: &mut String, section: Option<String>) {
     if let Some(section) = section {
         if !out.is_empty() {
@@ -319,10 +589,16 @@ pub fn platform_to_yaml_str(
 
     emit_optional_section(&mut out, emit_memory_maps(platform)?);
     emit_optional_section(&mut out, emit_processing_elements(platform)?);
+    emit_optional_section(&mut out, emit_dma_engines(platform)?);
+    emit_optional_section(&mut out, emit_accelerators(platform)?);
     emit_optional_section(&mut out, emit_fabrics(platform)?);
     emit_optional_section(&mut out, emit_caches(platform)?);
     emit_optional_section(&mut out, emit_memories(platform)?);
+    emit_optional_section(&mut out, emit_devices(platform)?);
     emit_optional_section(&mut out, emit_connections(platform)?);
+    emit_optional_section(&mut out, emit_groups(platform)?);
+    emit_optional_section(&mut out, emit_group_links(platform)?);
+    emit_optional_section(&mut out, emit_power_domains(platform)?);
 
     Ok(out)
 }
@@ -341,6 +617,7 @@ mod tests {
             devices: vec![MemoryDeviceSection {
                 name: "hbm0".to_string(),
             }],
+            interleave: None,
         }
     }
 
@@ -354,6 +631,9 @@ mod tests {
             adds_per_tick: Some(16.0),
             muls_per_tick: Some(4.0),
             compares_per_tick: None,
+            cost_model: None,
+            preemption_ticks: None,
+            scratchpad: None,
         };
         let unique_config = ProcessingElementConfigSection {
             num_active_requests: Some(16),
@@ -363,8 +643,12 @@ mod tests {
             adds_per_tick: Some(32.0),
             muls_per_tick: Some(8.0),
             compares_per_tick: Some(16.0),
+            cost_model: None,
+            preemption_ticks: None,
+            scratchpad: None,
         };
         let platform = PlatformConfig {
+            includes: None,
             memory_maps: vec![test_memory_map()],
             defaults: None,
             processing_elements: Some(vec![
@@ -372,22 +656,32 @@ mod tests {
                     name: "pe0".to_string(),
                     memory_map: "memory_map".to_string(),
                     config: shared_config.clone(),
+                    clock_mhz: None,
                 },
                 ProcessingElementSection {
                     name: "pe1".to_string(),
                     memory_map: "memory_map".to_string(),
                     config: unique_config.clone(),
+                    clock_mhz: None,
                 },
                 ProcessingElementSection {
                     name: "pe2".to_string(),
                     memory_map: "memory_map".to_string(),
                     config: shared_config.clone(),
+                    clock_mhz: None,
                 },
             ]),
+            dma_engines: None,
+            accelerators: None,
             caches: None,
             fabrics: None,
             memories: None,
             connections: None,
+            devices: None,
+            topologies: None,
+            groups: None,
+            group_links: None,
+            power_domains: None,
         };
 
         let yaml = platform_to_yaml_str(&platform).expect("yaml generation should succeed");
@@ -424,6 +718,9 @@ mod tests {
             adds_per_tick: None,
             muls_per_tick: None,
             compares_per_tick: None,
+            cost_model: None,
+            preemption_ticks: None,
+            scratchpad: None,
         };
         let empty_cache_config = CacheConfigSection {
             bw_bytes_per_cycle: None,
@@ -433,28 +730,41 @@ mod tests {
             delay_ticks: None,
         };
         let platform = PlatformConfig {
+            includes: None,
             memory_maps: vec![test_memory_map()],
             defaults: None,
             processing_elements: Some(vec![ProcessingElementSection {
                 name: "pe0".to_string(),
                 memory_map: "memory_map".to_string(),
                 config: empty_pe_config.clone(),
+                clock_mhz: None,
             }]),
+            dma_engines: None,
+            accelerators: None,
             caches: Some(vec![
                 CacheSection {
                     name: "l1a".to_string(),
                     config: empty_cache_config.clone(),
+                    clock_mhz: None,
                 },
                 CacheSection {
                     name: "l1b".to_string(),
                     config: empty_cache_config.clone(),
+                    clock_mhz: None,
                 },
             ]),
             fabrics: None,
             memories: None,
             connections: Some(vec![ConnectSection {
                 connect: vec!["pe.pe0".to_string(), "cache.l1a.dev".to_string()],
+                fabric: None,
+                attach: None,
             }]),
+            devices: None,
+            topologies: None,
+            groups: None,
+            group_links: None,
+            power_domains: None,
         };
 
         let yaml = platform_to_yaml_str(&platform).expect("yaml generation should succeed");
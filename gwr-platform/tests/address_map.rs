@@ -0,0 +1,123 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_engine::test_helpers::start_test;
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_platform::Platform;
+
+const INTERLEAVED_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+  - name: mm1
+    devices:
+      - name: hbm1
+      - name: hbm2
+    interleave:
+      granule_bytes: 0x1000
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm1
+    config:
+  - name: pe2
+    memory_map: mm1
+    config:
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+  - name: hbm1
+    kind: hbm
+    base_address: 1GiB
+    capacity_bytes: 1GiB
+  - name: hbm2
+    kind: hbm
+    base_address: 2GiB
+    capacity_bytes: 1GiB
+";
+
+#[test]
+fn address_maps_are_dumped() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, INTERLEAVED_PLATFORM).unwrap();
+
+    let dump: String = platform
+        .address_maps()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        dump,
+        "Address map 'mm0' (used by pe0):
+  0x0-0x3fff_ffff: hbm0 (device 3)
+Address map 'mm1' (used by pe1, pe2):
+  0x4000_0000-0xbfff_ffff: interleaved across hbm1 (device 4), hbm2 (device 5) in 0x1000 byte granules (stride)
+"
+    );
+}
+
+#[test]
+fn address_maps_can_be_queried() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, INTERLEAVED_PLATFORM).unwrap();
+
+    let address_map = platform.pe_address_map("pe2").unwrap();
+    assert_eq!(address_map.name(), "mm1");
+
+    let region = address_map.region_containing(0x8000_0000).unwrap();
+    assert_eq!(region.start(), 0x4000_0000);
+    assert_eq!(region.end(), 0xbfff_ffff);
+    assert_eq!(
+        region.devices(),
+        [
+            ("hbm1".to_string(), DeviceId(4)),
+            ("hbm2".to_string(), DeviceId(5))
+        ]
+    );
+    assert_eq!(region.interleave().unwrap().num_ways(), 2);
+    assert!(address_map.region_containing(0).is_none());
+
+    assert!(platform.address_map("mm0").is_ok());
+    assert!(platform.address_map("mm2").is_err());
+}
+
+#[test]
+fn overlapping_memories_are_reported() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+      - name: hbm1
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x2000
+  - name: hbm1
+    kind: hbm
+    base_address: 0x1000
+    capacity_bytes: 0x2000
+",
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        format!("{err}"),
+        "Memory map 'mm0': 'hbm1' (0x1000-0x2fff) overlaps 'hbm0' (0x0-0x1fff)"
+    );
+}
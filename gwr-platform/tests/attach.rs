@@ -0,0 +1,124 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_engine::test_helpers::start_test;
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_platform::Platform;
+use gwr_platform::validate::parse_platform_config;
+
+const ATTACH_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+  - name: pe2
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 2
+    rows: 1
+    fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - pe.pe2@(1,0)
+  - connect:
+      - mem.hbm0
+      - fabric.fabric0@(1,0)
+";
+
+#[test]
+fn attachments_are_given_free_ports() {
+    let cfg = parse_platform_config(ATTACH_PLATFORM).unwrap();
+    let connections: Vec<&[String]> = cfg
+        .connections
+        .iter()
+        .flatten()
+        .map(|c| c.connect.as_slice())
+        .collect();
+
+    // Port 0 of node (1,0) is used by the memory, so pe2 is given port 1
+    assert_eq!(
+        connections,
+        [
+            ["pe.pe0", "fabric.fabric0@(0,0).0"],
+            ["pe.pe1", "fabric.fabric0@(0,0).1"],
+            ["pe.pe2", "fabric.fabric0@(1,0).1"],
+            ["mem.hbm0", "fabric.fabric0@(1,0)"],
+        ]
+    );
+    assert!(cfg.connections.iter().flatten().all(|c| c.attach.is_none()));
+}
+
+#[test]
+fn attached_platforms_are_built() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, ATTACH_PLATFORM).unwrap();
+    platform.check().unwrap();
+
+    let address_map = platform.address_map("mm0").unwrap();
+    assert_eq!(
+        address_map.regions()[0].devices(),
+        [("hbm0".to_string(), DeviceId(2))]
+    );
+}
+
+#[test]
+fn invalid_attachments_are_rejected() {
+    let check = |from: &str, to: &str, expected: &str| {
+        let source = ATTACH_PLATFORM.replace(from, to);
+        let err = format!("{}", parse_platform_config(&source).unwrap_err());
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        "      - pe.pe2@(1,0)\n",
+        "      - pe.pe2@(0,0)\n",
+        "No free port for 'pe.pe2@(0,0)' on node (0,0) of fabric 'fabric0' (nodes have 2 ports)",
+    );
+    check(
+        "  - fabric: fabric0\n",
+        "  - fabric: fabric1\n",
+        "Unknown Fabric 'fabric1' in connection",
+    );
+    check(
+        "pe.pe2@(1,0)",
+        "pe.pe2@(2,0)",
+        "Attachment 'pe.pe2@(2,0)' is outside the 2x1 fabric",
+    );
+    check(
+        "pe.pe2@(1,0)",
+        "pe.pe2",
+        "Unable to parse attachment 'pe.pe2' (expected kind.name@(col,row))",
+    );
+    check(
+        "  - fabric: fabric0\n",
+        "  - connect: [pe.pe0, pe.pe1]\n    fabric: fabric0\n",
+        "A connection cannot have both 'connect' and 'attach'",
+    );
+    check(
+        "pe.pe1@(0,0)",
+        "pe.pe3@(0,0)",
+        "Unknown PE 'pe3' in connection",
+    );
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::path::Path;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+
+fn check(platform_config: &str) -> String {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, platform_config).unwrap();
+    match platform.check() {
+        Ok(()) => String::new(),
+        Err(err) => format!("{err}"),
+    }
+}
+
+#[test]
+fn examples_pass_check() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    for entry in fs::read_dir(examples).unwrap() {
+        let path = entry.unwrap().path();
+        let mut engine = start_test(file!());
+        let clock = engine.default_clock();
+        let platform = Platform::from_file(&engine, &clock, &path).unwrap();
+        if let Err(err) = platform.check() {
+            panic!("{}: {err}", path.display());
+        }
+    }
+}
+
+#[test]
+fn unconnected_components_are_reported() {
+    let err = check(
+        "
+memory_maps:
+  - name: mm0
+    devices: []
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+caches:
+  - name: c0
+    config:
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+connections:
+  - connect:
+    - pe.pe0
+    - cache.c0
+",
+    );
+
+    assert_eq!(
+        err,
+        "Invalid platform:
+  Cache 'c0' port 'mem' is not connected
+  Memory 'hbm0' is not connected"
+    );
+}
+
+#[test]
+fn unreachable_memory_is_reported() {
+    let err = check(
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+      - name: hbm1
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+  - name: hbm1
+    kind: hbm
+    base_address: 1GiB
+    capacity_bytes: 1GiB
+
+connections:
+  - connect:
+    - pe.pe0
+    - mem.hbm0
+  - connect:
+    - pe.pe1
+    - mem.hbm1
+",
+    );
+
+    assert_eq!(
+        err,
+        "Invalid platform:
+  PE 'pe0' cannot reach memory 'hbm1' in its memory map
+  PE 'pe1' cannot reach memory 'hbm0' in its memory map"
+    );
+}
+
+#[test]
+fn memories_are_reachable_through_caches_and_fabrics() {
+    // The device IDs of pe0 and hbm0 are the indices of their fabric ports, 3
+    // and 1, so requests and responses are routed to them
+    let err = check(
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+caches:
+  - name: l1
+    config:
+
+fabrics:
+  - name: f0
+    kind: functional
+    columns: 2
+    rows: 1
+    fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+connections:
+  - connect:
+    - pe.pe0
+    - cache.l1.dev
+  - connect:
+    - cache.l1.mem
+    - fabric.f0@(1,0).1
+  - connect:
+    - mem.hbm0
+    - fabric.f0@(0,0).1
+",
+    );
+
+    assert_eq!(err, "");
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::sim_error;
+use gwr_engine::test_helpers::start_test;
+use gwr_engine::time::clock::Clock;
+use gwr_engine::types::SimError;
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_models::memory::{Memory, MemoryConfig};
+use gwr_platform::Platform;
+use gwr_platform::device::{DeviceRegistry, PlatformDevice};
+use gwr_platform::validate::parse_platform_config;
+use gwr_track::entity::Entity;
+use serde_yaml::Value;
+
+const DEVICE_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+devices:
+  - name: sram0
+    kind: sram
+    config:
+      capacity_bytes: 0x2000
+
+connections:
+  - connect:
+      - pe.pe0
+      - fabric.fabric0@(0,0).0
+  - connect:
+      - mem.hbm0
+      - fabric.fabric0@(0,0).1
+  - connect:
+      - dev.sram0
+      - fabric.fabric0@(0,0).2
+";
+
+/// A device kind that is a memory of the configured capacity
+fn build_sram(
+    engine: &Engine,
+    clock: &Clock,
+    parent: &Rc<Entity>,
+    name: &str,
+    device_id: DeviceId,
+    config: &Value,
+) -> Result<Rc<dyn PlatformDevice>, SimError> {
+    assert_eq!(device_id, DeviceId(2));
+    let Some(capacity_bytes) = config["capacity_bytes"].as_u64() else {
+        return sim_error!("capacity_bytes must be set");
+    };
+    let config = MemoryConfig::new(0, capacity_bytes as usize, 32, 1);
+    let device: Rc<dyn PlatformDevice> =
+        Memory::new_and_register(engine, clock, parent, name, config)?;
+    Ok(device)
+}
+
+#[test]
+fn registered_devices_are_built_and_connected() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let registry = DeviceRegistry::new().with_device_kind("sram", build_sram);
+    let cfg = parse_platform_config(DEVICE_PLATFORM).unwrap();
+    let platform = Platform::from_config(&engine, &clock, &cfg, &registry).unwrap();
+
+    assert_eq!(platform.num_devices(), 1);
+    assert_eq!(platform.device("sram0").unwrap().entity().name, "sram0");
+    assert!(platform.device("sram1").is_err());
+    platform.check().unwrap();
+
+    let dot = platform.to_dot();
+    assert!(
+        dot.contains(r#"[label="sram0", shape=component];"#),
+        "{dot}"
+    );
+}
+
+#[test]
+fn unregistered_device_kinds_are_rejected() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(&engine, &clock, DEVICE_PLATFORM).unwrap_err();
+    assert_eq!(
+        format!("{err}"),
+        "Unknown device kind 'sram' for 'sram0' (registered kinds: )"
+    );
+
+    let registry = DeviceRegistry::new()
+        .with_device_kind("nic", build_sram)
+        .with_device_kind("accelerator", build_sram);
+    let cfg = parse_platform_config(DEVICE_PLATFORM).unwrap();
+    let err = Platform::from_config(&engine, &clock, &cfg, &registry).unwrap_err();
+    assert_eq!(
+        format!("{err}"),
+        "Unknown device kind 'sram' for 'sram0' (registered kinds: accelerator, nic)"
+    );
+}
+
+#[test]
+fn device_builder_errors_are_reported() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let registry = DeviceRegistry::new().with_device_kind("sram", build_sram);
+    let source = DEVICE_PLATFORM.replace("capacity_bytes: 0x2000", "size: 0x2000");
+    let cfg = parse_platform_config(&source).unwrap();
+    let err = Platform::from_config(&engine, &clock, &cfg, &registry).unwrap_err();
+    assert_eq!(
+        format!("{err}"),
+        "Device 'sram0': capacity_bytes must be set"
+    );
+}
+
+#[test]
+fn invalid_device_references_are_rejected() {
+    let source = DEVICE_PLATFORM
+        .replace("- name: sram0", "- name: pe0")
+        .replace("- dev.sram0", "- dev.sram1");
+    let err = parse_platform_config(&source).unwrap_err();
+    let err = format!("{err}");
+
+    assert!(err.contains("Duplicate device name pe0"), "{err}");
+    assert!(
+        err.contains("Unknown Device 'sram1' in connection"),
+        "{err}"
+    );
+}
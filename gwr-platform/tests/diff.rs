@@ -0,0 +1,127 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::path::Path;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::diff::{Change, diff_configs};
+use gwr_platform::validate::parse_platform_config;
+use gwr_track::tracker::dev_null_tracker;
+
+const BASE_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+      lsu_access_bytes: 32
+  - name: pe1
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 2
+    rows: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+connections:
+  - connect:
+      - pe.pe0
+      - fabric.fabric0@(0,0)
+  - connect:
+      - pe.pe1
+      - fabric.fabric0@(0,1)
+  - connect:
+      - mem.hbm0
+      - fabric.fabric0@(1,1)
+";
+
+#[test]
+fn identical_platforms_have_no_changes() {
+    let old = parse_platform_config(BASE_PLATFORM).unwrap();
+
+    // Reordering the endpoints of a connection does not change it
+    let new = parse_platform_config(&BASE_PLATFORM.replace(
+        "      - pe.pe1\n      - fabric.fabric0@(0,1)",
+        "      - fabric.fabric0@(0,1)\n      - pe.pe1",
+    ))
+    .unwrap();
+
+    let diff = diff_configs(&old, &new);
+    assert!(diff.is_empty(), "{diff}");
+}
+
+#[test]
+fn changes_are_reported() {
+    let old = parse_platform_config(BASE_PLATFORM).unwrap();
+    let new = parse_platform_config(
+        &BASE_PLATFORM
+            .replace("lsu_access_bytes: 32", "lsu_access_bytes: 64")
+            .replace("    rows: 2\n", "    rows: 2\n    ticks_per_hop: 4\n")
+            .replace(
+                "  - name: pe1\n    memory_map: mm0\n    config:\n",
+                "  - name: pe2\n    memory_map: mm0\n    config:\n",
+            )
+            .replace("- pe.pe1", "- pe.pe2"),
+    )
+    .unwrap();
+
+    let diff = diff_configs(&old, &new);
+    assert_eq!(
+        diff.to_string(),
+        "- PE 'pe1'
+~ PE 'pe0': config.lsu_access_bytes 32 -> 64
++ PE 'pe2'
+~ fabric 'fabric0': ticks_per_hop (unset) -> 4
+- connection fabric.fabric0@(0,1) <-> pe.pe1
++ connection fabric.fabric0@(0,1) <-> pe.pe2
+"
+    );
+    assert_eq!(
+        diff.changes()[0],
+        Change::Removed {
+            kind: "PE",
+            name: "pe1".to_string()
+        }
+    );
+}
+
+#[test]
+fn platforms_can_be_diffed() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let die = Platform::from_file(&engine, &clock, &examples.join("die.yaml")).unwrap();
+
+    let mut other_engine = Engine::new(&dev_null_tracker());
+    let other_clock = other_engine.default_clock();
+    let two_die =
+        Platform::from_file(&other_engine, &other_clock, &examples.join("two_die.yaml")).unwrap();
+
+    let diff = die.diff(&two_die);
+    let changes = diff.to_string();
+    assert!(changes.contains("- PE 'fabric0_pe_0_0'\n"), "{changes}");
+    assert!(
+        changes.contains("+ PE 'die1.fabric0_pe_0_0'\n"),
+        "{changes}"
+    );
+    assert!(changes.contains("+ group 'die0'\n"), "{changes}");
+    assert!(
+        changes
+            .contains("+ group link fabric.die0.fabric0@(1,0).2 <-> fabric.die1.fabric0@(0,0).2\n"),
+        "{changes}"
+    );
+    assert!(die.diff(&die).is_empty());
+}
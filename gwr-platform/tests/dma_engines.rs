@@ -0,0 +1,162 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_engine::test_helpers::start_test;
+use gwr_models::memory::memory_map::DeviceId;
+use gwr_platform::Platform;
+use gwr_platform::validate::parse_platform_config;
+use gwr_platform::yaml::platform_to_yaml_str;
+use gwr_track::entity::GetEntity;
+
+const DMA_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+dma_engines:
+  - name: dma0
+    memory_map: mm0
+    config:
+      scratchpad:
+        base_address: 0x8000_0000
+        capacity_bytes: 0x1000
+        dma_channels: 2
+
+accelerators:
+  - name: acc0
+    memory_map: mm0
+    config:
+      muls_per_tick: 64
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 4
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+connections:
+  - connect:
+      - pe.pe0
+      - fabric.fabric0@(0,0).0
+  - connect:
+      - acc.acc0
+      - fabric.fabric0@(0,0).1
+  - connect:
+      - dma.dma0
+      - fabric.fabric0@(0,0).2
+  - connect:
+      - mem.hbm0
+      - fabric.fabric0@(0,0).3
+";
+
+#[test]
+fn dma_engines_and_accelerators_are_built_and_connected() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, DMA_PLATFORM).unwrap();
+    platform.check().unwrap();
+
+    assert_eq!(platform.num_pes(), 1);
+    assert_eq!(platform.pe_names(), vec!["pe0".to_string()]);
+    assert_eq!(platform.num_dma_engines(), 1);
+    assert_eq!(platform.num_accelerators(), 1);
+    assert_eq!(platform.dma_engine("dma0").unwrap().entity().name, "dma0");
+    assert_eq!(platform.accelerator("acc0").unwrap().entity().name, "acc0");
+    assert!(platform.dma_engine("acc0").is_err());
+    assert!(platform.is_dma_engine("dma0"));
+    assert!(!platform.is_dma_engine("pe0"));
+
+    // They are found by name in the same way as PEs so that a timetable can
+    // target them
+    assert!(platform.pe("dma0").is_ok());
+    assert!(platform.pe("acc0").is_ok());
+
+    // Device IDs come from the fabric ports and both use the memory map
+    let address_map = platform.address_map("mm0").unwrap();
+    assert_eq!(address_map.pes(), ["pe0", "dma0", "acc0"]);
+    assert_eq!(
+        platform.pe_address_map("dma0").unwrap().name(),
+        address_map.name()
+    );
+    let hbm0 = &address_map.regions()[0].devices()[0];
+    assert_eq!(hbm0.1, DeviceId(3));
+
+    let dot = platform.to_dot();
+    assert!(dot.contains(r#"[label="dma0", shape=cds];"#), "{dot}");
+    assert!(dot.contains(r#"[label="acc0", shape=hexagon];"#), "{dot}");
+}
+
+#[test]
+fn unconnected_dma_engines_are_reported() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let source = DMA_PLATFORM.replace(
+        "  - connect:
+      - dma.dma0
+      - fabric.fabric0@(0,0).2
+",
+        "",
+    );
+    let platform = Platform::from_string(&engine, &clock, &source).unwrap();
+    let err = format!("{}", platform.check().unwrap_err());
+    assert!(err.contains("DMA engine 'dma0' is not connected"), "{err}");
+    assert!(
+        err.contains("DMA engine 'dma0' cannot reach memory 'hbm0' in its memory map"),
+        "{err}"
+    );
+}
+
+#[test]
+fn invalid_dma_engine_references_are_rejected() {
+    let source = DMA_PLATFORM
+        .replace(
+            "- name: dma0\n    memory_map: mm0",
+            "- name: dma0\n    memory_map: mm1",
+        )
+        .replace("- name: acc0", "- name: dma0")
+        .replace("- dma.dma0", "- dma.dma1")
+        .replace("- acc.acc0", "- acc.acc1");
+    let err = parse_platform_config(&source).unwrap_err();
+    let err = format!("{err}");
+
+    assert!(
+        err.contains("Unknown memory map 'mm1' for DMA engine 'dma0'"),
+        "{err}"
+    );
+    assert!(err.contains("Duplicate device name dma0"), "{err}");
+    assert!(
+        err.contains("Unknown DMA engine 'dma1' in connection"),
+        "{err}"
+    );
+    assert!(
+        err.contains("Unknown accelerator 'acc1' in connection"),
+        "{err}"
+    );
+}
+
+#[test]
+fn dma_engines_and_accelerators_round_trip_through_yaml() {
+    let cfg = parse_platform_config(DMA_PLATFORM).unwrap();
+    let yaml = platform_to_yaml_str(&cfg).unwrap();
+    let reparsed = parse_platform_config(&yaml).unwrap();
+
+    let dma = &reparsed.dma_engines.as_ref().unwrap()[0];
+    assert_eq!(dma.name, "dma0");
+    assert_eq!(dma.config.scratchpad.base_address, 0x8000_0000);
+    assert_eq!(dma.config.scratchpad.dma_channels, Some(2));
+    let acc = &reparsed.accelerators.as_ref().unwrap()[0];
+    assert_eq!(acc.name, "acc0");
+    assert_eq!(acc.config.muls_per_tick, Some(64.0));
+}
@@ -0,0 +1,51 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::path::Path;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+
+#[test]
+fn dot_contains_components_and_connections() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_file(
+        &engine,
+        &clock,
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/simple_pe_cache_mem.yaml"),
+    )
+    .unwrap();
+
+    let dot = platform.to_dot();
+    assert!(dot.starts_with("graph platform {\n"), "{dot}");
+    assert!(dot.contains(r#"[label="pe0", shape=box];"#), "{dot}");
+    assert!(dot.contains(r#"[label="l1_0", shape=box3d];"#), "{dot}");
+    assert!(dot.contains(r#"[label="mem0", shape=cylinder];"#), "{dot}");
+    assert!(dot.contains(r#"::pe0" -- "#), "{dot}");
+    assert!(dot.contains(r#"::l1_0" [headlabel="dev"];"#), "{dot}");
+    assert!(dot.contains(r#"::l1_0" -- "#), "{dot}");
+    assert!(dot.contains(r#"[taillabel="mem"];"#), "{dot}");
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn dot_labels_fabric_ports() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_file(
+        &engine,
+        &clock,
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/two_die.yaml"),
+    )
+    .unwrap();
+
+    let dot = platform.to_dot();
+    assert!(
+        dot.contains(r#"[label="die0.fabric0", shape=octagon];"#),
+        "{dot}"
+    );
+    assert!(
+        dot.contains(r#"::die1.fabric0" [taillabel="(1,0).2", headlabel="(0,0).2"];"#),
+        "{dot}"
+    );
+}
@@ -2,6 +2,8 @@
 
 use gwr_engine::test_helpers::start_test;
 use gwr_platform::Platform;
+use gwr_platform::types::PlatformConfig;
+use gwr_platform::validate::check_references;
 
 #[test]
 fn unknown_top_level_field_is_rejected() {
@@ -44,6 +46,30 @@ processing_elements:
     assert!(format!("{err}").contains("unknown field `lsu_acess_bytes`"));
 }
 
+#[test]
+fn invalid_clock_mhz_is_rejected() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: mm0
+    devices: []
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    clock_mhz: 0
+    config:
+",
+    )
+    .unwrap_err();
+
+    assert!(format!("{err}").contains("Invalid clock_mhz 0 for 'pe0'"));
+}
+
 #[test]
 fn defaults_pe_config_anchor_is_allowed() {
     let mut engine = start_test(file!());
@@ -297,3 +323,217 @@ connections:
     )
     .unwrap();
 }
+
+#[test]
+fn interleaved_memories_must_be_contiguous() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: mem0
+      - name: mem1
+    interleave:
+      granule_bytes: 256
+
+memories:
+  - name: mem0
+    kind: hbm
+    base_address: 0x1000_0000
+    capacity_bytes: 0x1000
+  - name: mem1
+    kind: hbm
+    base_address: 0x2000_0000
+    capacity_bytes: 0x1000
+",
+    )
+    .unwrap_err();
+
+    assert!(format!("{err}").contains("interleaved memory 'mem1' should start at 0x10001000"));
+}
+
+#[test]
+fn interleave_granule_must_hold_whole_accesses() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: mem0
+      - name: mem1
+    interleave:
+      granule_bytes: 48
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+      lsu_access_bytes: 32
+
+memories:
+  - name: mem0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1200
+  - name: mem1
+    kind: hbm
+    base_address: 0x1200
+    capacity_bytes: 0x1200
+",
+    )
+    .unwrap_err();
+
+    assert!(format!("{err}").contains(
+        "interleave granule of 48 bytes is not a multiple of the 32 byte accesses made through it"
+    ));
+}
+
+#[test]
+fn unknown_pe_in_connection_is_located() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1024
+
+connections:
+  - connect:
+    - pe.pe1
+    - mem.hbm0
+",
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err}").contains("line 20, column 7: Unknown PE 'pe1' in connection"),
+        "{err}"
+    );
+}
+
+#[test]
+fn type_error_is_located() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps: []
+
+fabrics:
+  - name: fabric0
+    kind: functional
+    columns: two
+    rows: 1
+",
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err}").contains("line 7, column 14: fabrics[0].columns: invalid type"),
+        "{err}"
+    );
+}
+
+#[test]
+fn all_reference_errors_are_reported() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm1
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: functional
+    columns: 2
+    rows: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1024
+
+connections:
+  - connect:
+    - pe.pe0
+    - fabric.fabric0@(2,0)
+  - connect:
+    - cache.l2
+    - mem.hbm0
+",
+    )
+    .unwrap_err();
+
+    let message = format!("{err}");
+    assert!(
+        message.contains("line 9, column 17: Unknown memory map 'mm1' for PE 'pe0'"),
+        "{message}"
+    );
+    assert!(
+        message.contains(
+            "line 27, column 7: Fabric port 'fabric.fabric0@(2,0)' is outside the 2x2 fabric"
+        ),
+        "{message}"
+    );
+    assert!(
+        message.contains("line 29, column 7: Unknown Cache 'l2' in connection"),
+        "{message}"
+    );
+}
+
+#[test]
+fn check_references_locates_problems() {
+    let source = "
+memory_maps: []
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+";
+    let cfg: PlatformConfig = serde_yaml::from_str(source).unwrap();
+    let diagnostics = check_references(&cfg, source);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line(), Some(6));
+    assert_eq!(diagnostics[0].column(), Some(17));
+    assert_eq!(
+        diagnostics[0].message(),
+        "Unknown memory map 'mm0' for PE 'pe0'"
+    );
+}
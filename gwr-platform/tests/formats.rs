@@ -0,0 +1,130 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::path::Path;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::format::PlatformFormat;
+
+#[test]
+fn format_is_detected_from_extension() {
+    assert_eq!(
+        PlatformFormat::from_path(Path::new("platform.json")),
+        PlatformFormat::Json
+    );
+    assert_eq!(
+        PlatformFormat::from_path(Path::new("platform.TOML")),
+        PlatformFormat::Toml
+    );
+    assert_eq!(
+        PlatformFormat::from_path(Path::new("platform.yml")),
+        PlatformFormat::Yaml
+    );
+}
+
+#[test]
+fn json_platform_builds() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_file(
+        &engine,
+        &clock,
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/simple_pe_cache_mem.json"),
+    )
+    .unwrap();
+
+    assert_eq!(platform.num_pes(), 1);
+    assert_eq!(platform.num_caches(), 1);
+    assert_eq!(platform.num_memories(), 1);
+}
+
+#[test]
+fn toml_platform_builds() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string_as(
+        &engine,
+        &clock,
+        r#"
+[[memory_maps]]
+name = "mm0"
+devices = [{ name = "hbm0" }]
+
+[[processing_elements]]
+name = "pe0"
+memory_map = "mm0"
+config = { lsu_access_bytes = 32 }
+
+[[memories]]
+name = "hbm0"
+kind = "hbm"
+base_address = 0
+capacity_bytes = "1MiB"
+
+[[topologies]]
+ring = { name = "ring", size = 2, pe = { memory_map = "mm0", config = {} } }
+
+[[connections]]
+connect = ["pe.pe0", "mem.hbm0"]
+"#,
+        PlatformFormat::Toml,
+    )
+    .unwrap();
+
+    assert_eq!(platform.num_pes(), 3);
+    assert_eq!(platform.num_fabrics(), 1);
+}
+
+#[test]
+fn json_errors_are_located() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string_as(
+        &engine,
+        &clock,
+        "{\n  \"memory_maps\": [],\n  \"processing_elements\": [,]\n}",
+        PlatformFormat::Json,
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err}").contains("line 3, column 27: expected value"),
+        "{err}"
+    );
+}
+
+#[test]
+fn toml_errors_are_located() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string_as(
+        &engine,
+        &clock,
+        "memory_maps = []\nprocessing_elements = [\n",
+        PlatformFormat::Toml,
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err}").contains("line 2, column 24: unclosed array"),
+        "{err}"
+    );
+}
+
+#[test]
+fn unknown_fields_are_rejected_in_json() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let err = Platform::from_string_as(
+        &engine,
+        &clock,
+        r#"{ "memory_maps": [], "processing_elementz": [] }"#,
+        PlatformFormat::Json,
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err}").contains("unknown field `processing_elementz`"),
+        "{err}"
+    );
+}
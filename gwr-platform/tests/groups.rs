@@ -0,0 +1,167 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::path::Path;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::group::DEFAULT_GROUP_LINK_BITS_PER_TICK;
+
+const TWO_GROUP_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+  - name: fabric1
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+connections:
+  - connect:
+      - pe.pe0
+      - fabric.fabric0@(0,0).0
+  - connect:
+      - mem.hbm0
+      - fabric.fabric0@(0,0).1
+  - connect:
+      - pe.pe1
+      - fabric.fabric1@(0,0).0
+
+groups:
+  - name: socket0
+    members: [pe0, fabric0, hbm0]
+  - name: socket1
+    members: [pe1, fabric1]
+
+group_links:
+  - connect:
+      - fabric.fabric0@(0,0).2
+      - fabric.fabric1@(0,0).2
+    delay_ticks: 100
+";
+
+#[test]
+fn groups_of_included_platforms() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_file(
+        &engine,
+        &clock,
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/two_die.yaml"),
+    )
+    .unwrap();
+
+    let die1 = platform.group("die1").unwrap();
+    assert_eq!(die1.pes(), ["die1.fabric0_pe_0_0", "die1.fabric0_pe_1_0"]);
+    assert_eq!(die1.fabrics(), ["die1.fabric0"]);
+    assert_eq!(die1.memories(), ["die1.hbm0"]);
+
+    assert_eq!(
+        platform.pe_group("die0.fabric0_pe_1_0").unwrap().name(),
+        "die0"
+    );
+    assert_eq!(platform.memory_group("die1.hbm0").unwrap().name(), "die1");
+    assert!(platform.pe_group("die2.fabric0_pe_0_0").is_none());
+    assert!(platform.group("die2").is_err());
+
+    let [link] = platform.group_links() else {
+        panic!("expected one group link");
+    };
+    assert_eq!(link.groups(), ["die0", "die1"]);
+    assert!(link.connects("die1", "die0"));
+    assert_eq!(link.delay_ticks(), 50);
+    assert_eq!(link.bits_per_tick(), 128);
+    platform.check().unwrap();
+}
+
+#[test]
+fn groups_of_named_components() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, TWO_GROUP_PLATFORM).unwrap();
+
+    let names: Vec<_> = platform.groups().iter().map(|g| g.name()).collect();
+    assert_eq!(names, ["socket0", "socket1"]);
+    assert_eq!(platform.pe_group("pe1").unwrap().name(), "socket1");
+    assert_eq!(platform.memory_group("hbm0").unwrap().pes(), ["pe0"]);
+
+    let link = &platform.group_links()[0];
+    assert_eq!(
+        link.endpoints(),
+        ["fabric.fabric0@(0,0).2", "fabric.fabric1@(0,0).2"]
+    );
+    assert_eq!(link.delay_ticks(), 100);
+    assert_eq!(link.bits_per_tick(), DEFAULT_GROUP_LINK_BITS_PER_TICK);
+
+    // The memory of pe1 is reached through the link
+    platform.check().unwrap();
+}
+
+#[test]
+fn invalid_groups_are_rejected() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let source = TWO_GROUP_PLATFORM
+        .replace(
+            "members: [pe1, fabric1]",
+            "members: [pe1, fabric1, hbm0, hbm1]",
+        )
+        .replace("- fabric.fabric0@(0,0).2", "- fabric.fabric1@(0,0).1");
+    let err = Platform::from_string(&engine, &clock, &source).unwrap_err();
+    let err = format!("{err}");
+
+    assert!(
+        err.contains("'hbm0' is in groups 'socket0' and 'socket1'"),
+        "{err}"
+    );
+    assert!(
+        err.contains("Unknown member 'hbm1' in group 'socket1'"),
+        "{err}"
+    );
+    assert!(
+        err.contains("Group link connects group 'socket1' to itself"),
+        "{err}"
+    );
+}
+
+#[test]
+fn group_links_must_connect_fabrics_of_groups() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let source = TWO_GROUP_PLATFORM
+        .replace("members: [pe1, fabric1]", "members: [pe1]")
+        .replace("- fabric.fabric0@(0,0).2", "- mem.hbm0");
+    let err = Platform::from_string(&engine, &clock, &source).unwrap_err();
+    let err = format!("{err}");
+
+    assert!(
+        err.contains("Group link endpoint 'mem.hbm0' is not a Fabric port"),
+        "{err}"
+    );
+    assert!(
+        err.contains("Fabric 'fabric1' of group link is not in a group"),
+        "{err}"
+    );
+}
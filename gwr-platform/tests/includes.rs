@@ -0,0 +1,160 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::validate::parse_platform_config_in;
+
+fn examples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("examples")
+}
+
+/// Create a directory containing the given platform files
+fn write_platforms(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(test_name);
+    fs::create_dir_all(&dir).unwrap();
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn included_names_are_prefixed() {
+    let source = fs::read_to_string(examples_dir().join("two_die.yaml")).unwrap();
+    let cfg = parse_platform_config_in(&source, &examples_dir()).unwrap();
+
+    let pe_names: Vec<_> = cfg
+        .processing_elements
+        .unwrap()
+        .into_iter()
+        .map(|pe| (pe.name, pe.memory_map))
+        .collect();
+    assert_eq!(
+        pe_names,
+        [
+            (
+                "die0.fabric0_pe_0_0".to_string(),
+                "die0.pe_memory_map".to_string()
+            ),
+            (
+                "die0.fabric0_pe_1_0".to_string(),
+                "die0.pe_memory_map".to_string()
+            ),
+            (
+                "die1.fabric0_pe_0_0".to_string(),
+                "die1.pe_memory_map".to_string()
+            ),
+            (
+                "die1.fabric0_pe_1_0".to_string(),
+                "die1.pe_memory_map".to_string()
+            ),
+        ]
+    );
+    assert_eq!(cfg.memory_maps[1].devices[0].name, "die1.hbm0");
+
+    let connections = cfg.connections.unwrap();
+    assert_eq!(
+        connections[0].connect,
+        ["mem.die0.hbm0", "fabric.die0.fabric0@(0,0).1"]
+    );
+    assert_eq!(connections.len(), 6);
+
+    let groups = cfg.groups.unwrap();
+    assert_eq!(groups[0].members, ["die0"]);
+    let group_links = cfg.group_links.unwrap();
+    assert_eq!(
+        group_links[0].connect,
+        ["fabric.die0.fabric0@(1,0).2", "fabric.die1.fabric0@(0,0).2"]
+    );
+}
+
+#[test]
+fn included_platform_builds() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform =
+        Platform::from_file(&engine, &clock, &examples_dir().join("two_die.yaml")).unwrap();
+
+    assert_eq!(platform.num_pes(), 4);
+    assert_eq!(platform.num_fabrics(), 2);
+    assert_eq!(platform.num_memories(), 2);
+    assert!(platform.pe("die1.fabric0_pe_1_0").is_ok());
+    assert!(platform.memory("die0.hbm0").is_ok());
+}
+
+#[test]
+fn errors_in_included_files_are_reported() {
+    let dir = write_platforms(
+        "errors_in_included_files_are_reported",
+        &[
+            (
+                "top.yaml",
+                "
+includes:
+  - path: sub.yaml
+    prefix: sub
+memory_maps: []
+",
+            ),
+            (
+                "sub.yaml",
+                "
+memory_maps: []
+connections:
+  - connect:
+    - pe.pe0
+    - mem.mem0
+",
+            ),
+        ],
+    );
+
+    let source = fs::read_to_string(dir.join("top.yaml")).unwrap();
+    let err = parse_platform_config_in(&source, &dir).unwrap_err();
+    let message = format!("{err}");
+    assert!(
+        message.contains("sub.yaml: Invalid platform configuration"),
+        "{message}"
+    );
+    assert!(
+        message.contains("line 5, column 7: Unknown PE 'pe0' in connection"),
+        "{message}"
+    );
+}
+
+#[test]
+fn include_cycles_are_rejected() {
+    let dir = write_platforms(
+        "include_cycles_are_rejected",
+        &[
+            (
+                "a.yaml",
+                "
+includes:
+  - path: b.yaml
+    prefix: b
+memory_maps: []
+",
+            ),
+            (
+                "b.yaml",
+                "
+includes:
+  - path: a.yaml
+    prefix: a
+memory_maps: []
+",
+            ),
+        ],
+    );
+
+    let source = fs::read_to_string(dir.join("a.yaml")).unwrap();
+    let err = parse_platform_config_in(&source, &dir).unwrap_err();
+    assert!(
+        format!("{err}").contains("b.yaml is included recursively"),
+        "{err}"
+    );
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::path::Path;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::overrides::Override;
+use gwr_platform::validate::parse_platform_config_with_overrides;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+defaults:
+  pe_config: &pe_config
+    lsu_access_bytes: 32
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config: *pe_config
+  - name: pe1
+    memory_map: mm0
+    config: *pe_config
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1KiB
+";
+
+fn overrides(overrides: &[&str]) -> Vec<Override> {
+    overrides.iter().map(|o| o.parse().unwrap()).collect()
+}
+
+#[test]
+fn override_is_parsed() {
+    let o: Override = "pe0.config.lsu_access_bytes=64".parse().unwrap();
+    assert_eq!(o.path(), "pe0.config.lsu_access_bytes");
+    assert_eq!(o.value().as_u64(), Some(64));
+    assert_eq!(o.to_string(), "pe0.config.lsu_access_bytes=64");
+
+    let err = "pe0.config".parse::<Override>().unwrap_err();
+    assert!(format!("{err}").contains("expected 'path=value'"), "{err}");
+}
+
+#[test]
+fn values_are_overridden_by_name_and_index() {
+    let cfg = parse_platform_config_with_overrides(
+        PLATFORM,
+        Path::new("."),
+        &overrides(&[
+            "pe0.config.lsu_access_bytes=64",
+            "processing_elements.1.config.sram_bytes=64KiB",
+            "memories.hbm0.capacity_bytes=0x1000",
+        ]),
+    )
+    .unwrap();
+
+    // Only the named PE is changed even though the configs share an anchor
+    let pes = cfg.processing_elements.unwrap();
+    assert_eq!(pes[0].config.lsu_access_bytes, Some(64));
+    assert_eq!(pes[1].config.lsu_access_bytes, Some(32));
+    assert_eq!(pes[1].config.sram_bytes, Some(64 * 1024));
+    assert_eq!(cfg.memories.unwrap()[0].capacity_bytes, 0x1000);
+}
+
+#[test]
+fn unknown_paths_are_rejected() {
+    let err = parse_platform_config_with_overrides(
+        PLATFORM,
+        Path::new("."),
+        &overrides(&["pe2.config.lsu_access_bytes=64"]),
+    )
+    .unwrap_err();
+    assert!(
+        format!("{err}").contains("pe2.config.lsu_access_bytes=64: no 'pe2' found"),
+        "{err}"
+    );
+
+    let err = parse_platform_config_with_overrides(
+        PLATFORM,
+        Path::new("."),
+        &overrides(&["pe0.config.lsu_acess_bytes=64"]),
+    )
+    .unwrap_err();
+    assert!(
+        format!("{err}").contains("unknown field `lsu_acess_bytes`"),
+        "{err}"
+    );
+}
+
+#[test]
+fn included_platforms_are_overridden() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_file_with_overrides(
+        &engine,
+        &clock,
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/two_die.yaml"),
+        &overrides(&["die1.topologies.0.mesh.columns=3"]),
+    )
+    .unwrap();
+
+    assert_eq!(platform.num_pes(), 5);
+    assert!(platform.pe("die1.fabric0_pe_2_0").is_ok());
+    assert!(platform.pe("die0.fabric0_pe_2_0").is_err());
+}
@@ -0,0 +1,155 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_engine::power::PowerState;
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::types::PowerStateKind;
+use gwr_platform::validate::parse_platform_config;
+use gwr_platform::yaml::platform_to_yaml_str;
+
+const POWER_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+dma_engines:
+  - name: dma0
+    memory_map: mm0
+    config:
+      scratchpad:
+        base_address: 0x8000_0000
+        capacity_bytes: 0x1000
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 4
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1GiB
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - dma.dma0@(0,0)
+      - mem.hbm0@(0,0)
+
+power_domains:
+  - name: always_on
+    members: [pe0, hbm0, fabric0]
+    voltage_mv: 800
+  - name: gated
+    members:
+      - pe1
+      - dma0
+    voltage_mv: 650
+    state: off
+    transitions:
+      - at_ticks: 100
+        state: retention
+      - at_ticks: 200
+        state: on
+";
+
+#[test]
+fn power_domains_are_built() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, POWER_PLATFORM).unwrap();
+
+    assert_eq!(platform.power_domains().len(), 2);
+    let gated = platform.power_domain("gated").unwrap();
+    assert_eq!(gated.components(), ["pe1", "dma0"]);
+    assert_eq!(gated.voltage_mv(), Some(650.0));
+    assert_eq!(gated.state(), PowerState::Off);
+    assert_eq!(
+        platform.power_domain("always_on").unwrap().state(),
+        PowerState::On
+    );
+    assert_eq!(
+        platform.component_power_domain("hbm0").unwrap().name(),
+        "always_on"
+    );
+    assert!(platform.component_power_domain("fabric1").is_none());
+    assert!(platform.power_domain("missing").is_err());
+}
+
+#[test]
+fn power_states_can_be_changed() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, POWER_PLATFORM).unwrap();
+
+    let gated = platform.power_domain("gated").unwrap();
+    assert!(!gated.switch().is_on());
+    gated.set_state(PowerState::Retention);
+    assert_eq!(gated.state(), PowerState::Retention);
+    gated.set_state(PowerState::On);
+    assert!(gated.switch().is_on());
+}
+
+#[test]
+fn invalid_power_domains_are_rejected() {
+    let check = |from: &str, to: &str, expected: &str| {
+        let source = POWER_PLATFORM.replace(from, to);
+        let err = format!("{}", parse_platform_config(&source).unwrap_err());
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        "members: [pe0, hbm0, fabric0]",
+        "members: [pe0, pe1]",
+        "'pe1' is in power domains 'always_on' and 'gated'",
+    );
+    check(
+        "members: [pe0, hbm0, fabric0]",
+        "members: [pe2]",
+        "Unknown member 'pe2' in power domain 'always_on'",
+    );
+    check(
+        "- name: gated",
+        "- name: always_on",
+        "Duplicate power domain name always_on",
+    );
+    check(
+        "voltage_mv: 650",
+        "voltage_mv: 0",
+        "Power domain 'gated' must have a positive voltage_mv",
+    );
+    check(
+        "at_ticks: 200",
+        "at_ticks: 50",
+        "The transitions of power domain 'gated' must be in order of at_ticks",
+    );
+}
+
+#[test]
+fn power_domains_round_trip_through_yaml() {
+    let cfg = parse_platform_config(POWER_PLATFORM).unwrap();
+    let yaml = platform_to_yaml_str(&cfg).unwrap();
+    let reparsed = parse_platform_config(&yaml).unwrap();
+
+    let gated = &reparsed.power_domains.as_ref().unwrap()[1];
+    assert_eq!(gated.name, "gated");
+    assert_eq!(gated.members, ["pe1", "dma0"]);
+    assert_eq!(gated.state, Some(PowerStateKind::Off));
+    let transitions = gated.transitions.as_ref().unwrap();
+    assert_eq!(transitions[0].at_ticks, 100);
+    assert_eq!(transitions[0].state, PowerStateKind::Retention);
+}
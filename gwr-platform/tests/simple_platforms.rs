@@ -254,3 +254,49 @@ connections:
     // and 4 cache hits (5ns each)
     assert_eq!(clock.time_now_ns(), 140.0);
 }
+
+#[test]
+fn simple_pe_mem_memory_clock() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+      num_active_requests: 1
+      lsu_access_bytes: 32
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0x1_0000_0000
+    capacity_bytes: 16GiB
+    delay_ticks: 10
+    clock_mhz: 500
+
+connections:
+  - connect:
+    - pe.pe0
+    - mem.hbm0
+",
+    )
+    .unwrap();
+
+    let dispatcher = build_dispatcher();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+
+    // The same accesses as `simple_pe_mem_one_request`, but the memory delay of
+    // 10 ticks is now 20ns at 500MHz rather than 10ns
+    assert_eq!(engine.time_now_ns(), 160.0);
+}
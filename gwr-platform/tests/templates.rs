@@ -0,0 +1,171 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::path::Path;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::overrides::Override;
+use gwr_platform::validate::{
+    parse_platform_config, parse_platform_config_in, parse_platform_config_with_overrides,
+};
+
+const TEMPLATE: &str = "
+variables:
+  columns: 2
+  buffer_kib: 4
+  ports: ${columns + 1}
+  memory: hbm0
+
+memory_maps:
+  - name: mm0
+    devices:
+      - name: ${memory}
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+      lsu_access_bytes: ${buffer_kib * 16}
+      adds_per_tick: ${buffer_kib / 8.0}
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: ${columns}
+    rows: 1
+    fabric_ports_per_node: ${ports}
+    rx_buffer_bytes: ${buffer_kib * 1024}
+
+memories:
+  - name: ${memory}
+    kind: hbm
+    base_address: 0
+    capacity_bytes: ${buffer_kib}KiB
+
+connections:
+  - connect:
+      - pe.pe0
+      - fabric.fabric0@(0,0)
+  - connect:
+      - mem.${memory}
+      - fabric.fabric0@(${columns - 1},0)
+";
+
+fn overrides(overrides: &[&str]) -> Vec<Override> {
+    overrides.iter().map(|o| o.parse().unwrap()).collect()
+}
+
+#[test]
+fn variables_are_expanded() {
+    let cfg = parse_platform_config(TEMPLATE).unwrap();
+
+    let fabric = &cfg.fabrics.as_ref().unwrap()[0];
+    assert_eq!(fabric.columns, 2);
+    assert_eq!(fabric.fabric_ports_per_node, Some(3));
+    assert_eq!(fabric.rx_buffer_bytes, Some(4096));
+
+    let pe = &cfg.processing_elements.as_ref().unwrap()[0];
+    let config = &pe.config;
+    assert_eq!(config.lsu_access_bytes, Some(64));
+    assert_eq!(config.adds_per_tick, Some(0.5));
+
+    let memory = &cfg.memories.as_ref().unwrap()[0];
+    assert_eq!(memory.name, "hbm0");
+    assert_eq!(memory.capacity_bytes, 4096);
+    assert_eq!(
+        cfg.connections.as_ref().unwrap()[1].connect,
+        ["mem.hbm0", "fabric.fabric0@(1,0)"]
+    );
+}
+
+#[test]
+fn variables_are_set_by_overrides() {
+    let cfg = parse_platform_config_with_overrides(
+        TEMPLATE,
+        Path::new("."),
+        &overrides(&["variables.columns=4", "variables.memory=ddr0"]),
+    )
+    .unwrap();
+
+    let fabric = &cfg.fabrics.as_ref().unwrap()[0];
+    assert_eq!(fabric.columns, 4);
+    assert_eq!(fabric.fabric_ports_per_node, Some(5));
+    assert_eq!(cfg.memories.as_ref().unwrap()[0].name, "ddr0");
+    assert_eq!(
+        cfg.connections.as_ref().unwrap()[1].connect,
+        ["mem.ddr0", "fabric.fabric0@(3,0)"]
+    );
+}
+
+#[test]
+fn templated_platforms_are_built() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, TEMPLATE).unwrap();
+    platform.check().unwrap();
+    assert!(platform.memory("hbm0").is_ok());
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    let check = |from: &str, to: &str, expected: &str| {
+        let source = TEMPLATE.replace(from, to);
+        let err = format!("{}", parse_platform_config(&source).unwrap_err());
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        "columns: 2\n",
+        "columns:\n",
+        "Variable 'columns' has no value",
+    );
+    check(
+        "${buffer_kib * 1024}",
+        "${buffer_kb * 1024}",
+        "Unable to expand '${buffer_kb * 1024}' in '${buffer_kb * 1024}': undefined variable 'buffer_kb'",
+    );
+    check(
+        "${buffer_kib * 1024}",
+        "${buffer_kib / (columns - 2)}",
+        "division by zero",
+    );
+    check(
+        "${buffer_kib * 1024}",
+        "${memory + 1}",
+        "'memory' is not a number",
+    );
+    check("${buffer_kib * 1024}", "${(columns}", "missing ')'");
+}
+
+#[test]
+fn includes_set_the_variables_of_included_platforms() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("includes_set_variables");
+    fs::create_dir_all(&dir).unwrap();
+    let die = TEMPLATE.replace("columns: 2\n", "columns:\n");
+    fs::write(dir.join("die.yaml"), die).unwrap();
+    let top = "
+memory_maps: []
+
+includes:
+  - path: die.yaml
+    prefix: die0
+    variables:
+      columns: 3
+  - path: die.yaml
+    prefix: die1
+    variables:
+      columns: 5
+";
+
+    let cfg = parse_platform_config_in(top, &dir).unwrap();
+    let columns: Vec<usize> = cfg.fabrics.iter().flatten().map(|f| f.columns).collect();
+    assert_eq!(columns, [3, 5]);
+
+    // Overrides of an included platform take precedence over its include
+    let cfg =
+        parse_platform_config_with_overrides(top, &dir, &overrides(&["die1.variables.columns=6"]))
+            .unwrap();
+    let columns: Vec<usize> = cfg.fabrics.iter().flatten().map(|f| f.columns).collect();
+    assert_eq!(columns, [3, 6]);
+}
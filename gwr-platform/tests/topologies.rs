@@ -0,0 +1,182 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_platform::validate::parse_platform_config;
+
+const MESH_PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+defaults:
+  pe_config: &pe_config
+    lsu_access_bytes: 32
+
+topologies:
+  - mesh:
+      name: tile
+      columns: 3
+      rows: 2
+      pe:
+        memory_map: mm0
+        config: *pe_config
+      fabric:
+        kind: routed
+        fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 1MiB
+
+connections:
+  - connect:
+    - mem.hbm0
+    - fabric.tile@(2,1).1
+";
+
+#[test]
+fn mesh_is_expanded() {
+    let cfg = parse_platform_config(MESH_PLATFORM).unwrap();
+    assert!(cfg.topologies.is_none());
+
+    let pes = cfg.processing_elements.unwrap();
+    assert_eq!(pes.len(), 6);
+    assert_eq!(pes[0].name, "tile_pe_0_0");
+    assert_eq!(pes[5].name, "tile_pe_2_1");
+    assert!(pes.iter().all(|pe| pe.memory_map == "mm0"));
+
+    let fabrics = cfg.fabrics.unwrap();
+    assert_eq!(fabrics.len(), 1);
+    assert_eq!(fabrics[0].name, "tile");
+    assert_eq!(fabrics[0].columns, 3);
+    assert_eq!(fabrics[0].rows, 2);
+    assert_eq!(fabrics[0].fabric_ports_per_node, Some(2));
+
+    // The hand-written connection comes first
+    let connections = cfg.connections.unwrap();
+    assert_eq!(connections.len(), 7);
+    assert_eq!(
+        connections[6].connect,
+        ["pe.tile_pe_2_1", "fabric.tile@(2,1)"]
+    );
+}
+
+#[test]
+fn mesh_platform_builds() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Platform::from_string(&engine, &clock, MESH_PLATFORM).unwrap();
+
+    assert_eq!(platform.num_pes(), 6);
+    assert_eq!(platform.num_fabrics(), 1);
+    assert!(platform.pe("tile_pe_1_1").is_ok());
+}
+
+#[test]
+fn ring_and_star_are_expanded() {
+    let cfg = parse_platform_config(
+        "
+memory_maps:
+  - name: mm0
+    devices: []
+
+topologies:
+  - ring:
+      name: ring
+      size: 4
+      pe:
+        memory_map: mm0
+        config: {}
+  - star:
+      name: star
+      size: 3
+      pe:
+        memory_map: mm0
+        config: {}
+",
+    )
+    .unwrap();
+
+    let pes = cfg.processing_elements.unwrap();
+    assert_eq!(pes.len(), 7);
+    assert_eq!(pes[3].name, "ring_pe_3");
+    assert_eq!(pes[6].name, "star_pe_2");
+
+    let fabrics = cfg.fabrics.unwrap();
+    assert_eq!((fabrics[0].columns, fabrics[0].rows), (4, 1));
+    assert_eq!((fabrics[1].columns, fabrics[1].rows), (1, 1));
+    assert_eq!(fabrics[1].fabric_ports_per_node, Some(3));
+
+    let connections = cfg.connections.unwrap();
+    assert_eq!(
+        connections[3].connect,
+        ["pe.ring_pe_3", "fabric.ring@(3,0)"]
+    );
+    assert_eq!(
+        connections[6].connect,
+        ["pe.star_pe_2", "fabric.star@(0,0).2"]
+    );
+}
+
+#[test]
+fn star_needs_a_port_per_pe() {
+    let err = parse_platform_config(
+        "
+memory_maps:
+  - name: mm0
+    devices: []
+
+topologies:
+  - star:
+      name: star
+      size: 3
+      pe:
+        memory_map: mm0
+        config: {}
+      fabric:
+        fabric_ports_per_node: 2
+",
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err}").contains(
+            "Topology 'star': fabric_ports_per_node 2 is less than the 3 ports needed for its PEs"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn generated_names_are_checked() {
+    let err = parse_platform_config(
+        "
+memory_maps:
+  - name: mm0
+    devices: []
+
+processing_elements:
+  - name: ring_pe_0
+    memory_map: mm0
+    config:
+
+topologies:
+  - ring:
+      name: ring
+      size: 2
+      pe:
+        memory_map: mm0
+        config: {}
+",
+    )
+    .unwrap_err();
+
+    assert!(
+        format!("{err}").contains("Duplicate device name ring_pe_0"),
+        "{err}"
+    );
+}
@@ -2,13 +2,19 @@
 
 use std::collections::HashMap;
 use std::error;
-use std::path::PathBuf;
-use std::sync::mpsc::channel;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
 use std::sync::{Arc, Mutex};
 
-use crate::filter::{Filter, start_background_filter};
+use crate::compare::Comparison;
+use crate::entity_tree::EntityTree;
+use crate::export::export_events;
+use crate::filter::{Filter, TimeRange, start_background_filter};
+use crate::find::{Find, FindDirection, FindPattern};
+use crate::live::{LiveSource, LiveTail, start_live_load};
 use crate::renderer::Renderer;
 use crate::rocket::SHARED_STATE;
+use crate::stats::Stats;
 use crate::{bin_loader, log_parser};
 
 #[cfg(test)]
@@ -20,6 +26,9 @@ pub const CHUNK_SIZE: usize = 50000;
 /// Size to start Vec with to prevent continually resizing
 pub const INITIAL_SIZE: usize = CHUNK_SIZE;
 
+/// The file name offered when exporting the lines shown
+pub const DEFAULT_EXPORT_PATH: &str = "events.csv";
+
 pub trait ToTime {
     fn time(&self) -> f64;
 }
@@ -44,6 +53,8 @@ pub enum EventLine {
         level: log::Level,
         id: u64,
         msg: String,
+        // Where the message was written from, if the trace records it.
+        call_site: Option<String>,
         time: f64,
     },
     Enter {
@@ -105,13 +116,57 @@ impl ToFullness for EventLine {
     }
 }
 
+impl EventLine {
+    /// Return the name of the kind of event.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EventLine::Create { .. } => "create",
+            EventLine::Connect { .. } => "connect",
+            EventLine::Log { .. } => "log",
+            EventLine::Enter { .. } => "enter",
+            EventLine::Exit { .. } => "exit",
+            EventLine::Value { .. } => "value",
+            EventLine::ActivityBegin { .. } => "activity_begin",
+            EventLine::ActivityEnd { .. } => "activity_end",
+        }
+    }
+
+    /// Return the ID of the entity that the event is for, which is the one
+    /// connected from for a connection.
+    pub fn entity_id(&self) -> u64 {
+        match self {
+            EventLine::Connect { from_id, .. } => *from_id,
+            EventLine::Create { id, .. }
+            | EventLine::Log { id, .. }
+            | EventLine::Enter { id, .. }
+            | EventLine::Exit { id, .. }
+            | EventLine::Value { id, .. }
+            | EventLine::ActivityBegin { id, .. }
+            | EventLine::ActivityEnd { id, .. } => *id,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum InputState {
+    Bookmark,
     Default,
+    EntityTree,
+    Export,
+    Find,
     Goto,
     Help,
     Numbers,
     Search,
+    Stats,
+    TimeRange,
+}
+
+/// A named line of the trace to return to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub name: String,
+    pub absolute_index: usize,
 }
 
 /// Application result type.
@@ -125,6 +180,31 @@ pub struct App {
     pub filter: Arc<Mutex<Filter>>,
     pub input_state: InputState,
     pub numbers: String,
+    pub find: Find,
+
+    /// The text being entered for a bookmark name, time range or file name.
+    pub prompt: String,
+
+    /// Whether the text entered could not be used.
+    pub prompt_invalid: bool,
+
+    /// The bookmarks, in the order of the lines they are at.
+    pub bookmarks: Vec<Bookmark>,
+
+    /// The hierarchy of entities, with those hidden and levels shown.
+    pub entity_tree: EntityTree,
+
+    /// The outcome of the last action that reports one, until the next key.
+    pub status: Option<String>,
+
+    /// Following a trace that is still being written, if it is.
+    pub live: Option<LiveTail>,
+
+    /// A second trace that this one is compared against, if there is one.
+    pub compare: Option<Comparison>,
+
+    /// The summary of the lines shown, while it is being viewed.
+    pub stats: Option<Stats>,
     trace_absolute_index: usize,
     last_renderer_absolute_index: Option<usize>,
     fullness_absolute_index: Option<usize>,
@@ -152,6 +232,33 @@ impl App {
                 filter.clone(),
             );
         }
+        Self::start(rx, renderer, filter, None)
+    }
+
+    /// Constructs a new instance of [`App`] that follows a trace that is still
+    /// being written, showing new events as they arrive.
+    #[must_use]
+    pub fn new_live(source: LiveSource) -> Self {
+        let (tx, rx) = channel();
+        let renderer = Arc::new(Mutex::new(Renderer::new()));
+        let filter = Arc::new(Mutex::new(Filter::new(tx)));
+
+        let live = match start_live_load(source, renderer.clone(), filter.clone()) {
+            Ok(buffer) => Some(LiveTail::new(buffer)),
+            Err(e) => {
+                println!("Error: {e}");
+                None
+            }
+        };
+        Self::start(rx, renderer, filter, live)
+    }
+
+    fn start(
+        rx: Receiver<()>,
+        renderer: Arc<Mutex<Renderer>>,
+        filter: Arc<Mutex<Filter>>,
+        live: Option<LiveTail>,
+    ) -> Self {
         start_background_filter(rx, renderer.clone(), filter.clone());
 
         Self {
@@ -160,6 +267,15 @@ impl App {
             filter,
             input_state: InputState::Default,
             numbers: String::new(),
+            find: Find::default(),
+            prompt: String::new(),
+            prompt_invalid: false,
+            bookmarks: Vec::new(),
+            entity_tree: EntityTree::default(),
+            status: None,
+            live,
+            compare: None,
+            stats: None,
             trace_absolute_index: 0,
             last_renderer_absolute_index: None,
             fullness_absolute_index: None,
@@ -167,6 +283,13 @@ impl App {
         }
     }
 
+    /// Compare the trace against another one, shown alongside it.
+    #[must_use]
+    pub fn with_comparison(mut self, comparison: Comparison) -> Self {
+        self.compare = Some(comparison);
+        self
+    }
+
     /// Handles the tick event of the terminal.
     pub fn tick(&mut self) {
         if let Some(s) = SHARED_STATE.lock().unwrap().command.take() {
@@ -182,9 +305,46 @@ impl App {
             self.move_to_absolute_line(line_number);
         }
 
+        self.update_live();
+        if let Some(compare) = self.compare.as_mut() {
+            compare.update(&self.renderer.lock().unwrap());
+        }
         self.publish_position();
     }
 
+    /// Show the events of a live trace that have arrived, and keep the view at
+    /// the end of the lines shown unless paused.
+    fn update_live(&mut self) {
+        let Some(live) = self.live.as_mut() else {
+            return;
+        };
+
+        if let Some(arrived) = live.take_events() {
+            {
+                let mut renderer = self.renderer.lock().unwrap();
+                renderer.append_lines(arrived.events);
+                renderer.extend_id_to_name(arrived.id_to_name.clone());
+                renderer.extend_id_to_details(arrived.id_to_details.clone());
+            }
+            let mut filter = self.filter.lock().unwrap();
+            filter.extend_id_to_name(arrived.id_to_name);
+            filter.extend_id_to_details(arrived.id_to_details);
+            filter.lines_added();
+        }
+
+        let mut renderer = self.renderer.lock().unwrap();
+        if live.should_follow(renderer.num_render_lines) {
+            renderer.move_bottom();
+        }
+    }
+
+    /// Stop or start showing the new events of a live trace.
+    pub fn toggle_live_paused(&mut self) {
+        if let Some(live) = self.live.as_mut() {
+            live.toggle_paused();
+        }
+    }
+
     /// Set running to false to quit the application.
     pub fn quit(&mut self) {
         self.running = false;
@@ -338,6 +498,288 @@ impl App {
         guard.set_frame_size(frame_height);
     }
 
+    /// Start finding text, from the current line.
+    pub fn start_find(&mut self) {
+        let origin = self.renderer.lock().unwrap().render_index();
+        self.find = Find::new(origin);
+        self.set_state(InputState::Find);
+    }
+
+    pub fn push_find_char(&mut self, c: char) {
+        self.find.text.push(c);
+        self.find_from_origin();
+    }
+
+    pub fn backspace_find_char(&mut self) {
+        self.find.text.pop();
+        self.find_from_origin();
+    }
+
+    /// Stop finding text and return to the line the find started from.
+    pub fn cancel_find(&mut self) {
+        self.find.text.clear();
+        self.find.found = true;
+        self.move_to_line(self.find.origin + 1);
+        self.set_state(InputState::Default);
+    }
+
+    /// Move to the next line that matches the find text.
+    pub fn find_next(&mut self) {
+        let from = self.renderer.lock().unwrap().render_index() + 1;
+        self.find_from(from, FindDirection::Forward);
+    }
+
+    /// Move to the previous line that matches the find text.
+    pub fn find_previous(&mut self) {
+        let from = {
+            let renderer = self.renderer.lock().unwrap();
+            match renderer.render_index() {
+                0 => renderer.num_render_lines.saturating_sub(1),
+                index => index - 1,
+            }
+        };
+        self.find_from(from, FindDirection::Backward);
+    }
+
+    /// Show a summary of the lines shown.
+    pub fn open_stats(&mut self) {
+        self.stats = Some(Stats::new(&self.renderer.lock().unwrap()));
+        self.set_state(InputState::Stats);
+    }
+
+    pub fn close_stats(&mut self) {
+        self.stats = None;
+        self.set_state(InputState::Default);
+    }
+
+    /// Switch between pairing the lines of the compared traces by time and by
+    /// their sequence of events.
+    pub fn toggle_alignment(&mut self) {
+        if let Some(compare) = self.compare.as_mut() {
+            compare.toggle_alignment();
+        }
+    }
+
+    /// Move to the next line at which the compared traces diverge.
+    pub fn next_divergence(&mut self) {
+        let from = self.renderer.lock().unwrap().render_index() + 1;
+        self.move_to_divergence(from, FindDirection::Forward);
+    }
+
+    /// Move to the previous line at which the compared traces diverge.
+    pub fn previous_divergence(&mut self) {
+        let from = {
+            let renderer = self.renderer.lock().unwrap();
+            match renderer.render_index() {
+                0 => renderer.num_render_lines.saturating_sub(1),
+                index => index - 1,
+            }
+        };
+        self.move_to_divergence(from, FindDirection::Backward);
+    }
+
+    fn move_to_divergence(&mut self, from: usize, direction: FindDirection) {
+        let Some(compare) = self.compare.as_ref() else {
+            return;
+        };
+        let mut renderer = self.renderer.lock().unwrap();
+        if let Some(render_index) =
+            renderer.find_line_where(from, direction, |index| compare.is_divergent(index))
+        {
+            renderer.move_to_index(render_index);
+        }
+    }
+
+    /// Return what is being looked for, if anything.
+    pub fn find_pattern(&self) -> Option<FindPattern> {
+        let use_regex = self.filter.lock().unwrap().regex_enabled();
+        FindPattern::new(&self.find.text, use_regex)
+    }
+
+    /// Find the text as it is typed, so the first match after where the find
+    /// started is always shown.
+    fn find_from_origin(&mut self) {
+        if self.find.text.is_empty() {
+            self.find.found = true;
+            self.move_to_line(self.find.origin + 1);
+        } else {
+            self.find_from(self.find.origin, FindDirection::Forward);
+        }
+    }
+
+    fn find_from(&mut self, from: usize, direction: FindDirection) {
+        let Some(pattern) = self.find_pattern() else {
+            return;
+        };
+        let mut renderer = self.renderer.lock().unwrap();
+        match renderer.find_line(&pattern, from, direction) {
+            Some(render_index) => {
+                renderer.move_to_index(render_index);
+                self.find.found = true;
+            }
+            None => self.find.found = false,
+        }
+    }
+
+    /// Start entering text for a bookmark name, time range or file name.
+    pub fn start_prompt(&mut self, state: InputState) {
+        self.prompt = match state {
+            InputState::TimeRange => self
+                .filter
+                .lock()
+                .unwrap()
+                .time_range
+                .map(|time_range| time_range.to_string())
+                .unwrap_or_default(),
+            InputState::Export => DEFAULT_EXPORT_PATH.to_owned(),
+            _ => String::new(),
+        };
+        self.prompt_invalid = false;
+        self.set_state(state);
+    }
+
+    pub fn push_prompt_char(&mut self, c: char) {
+        self.prompt.push(c);
+        self.prompt_invalid = false;
+    }
+
+    pub fn backspace_prompt_char(&mut self) {
+        self.prompt.pop();
+        self.prompt_invalid = false;
+    }
+
+    /// Use the text entered, unless it is invalid.
+    pub fn accept_prompt(&mut self) {
+        match self.state() {
+            InputState::Bookmark => {
+                let name = self.prompt.trim().to_owned();
+                if name.is_empty() {
+                    self.prompt_invalid = true;
+                    return;
+                }
+                self.add_bookmark(&name);
+            }
+            InputState::TimeRange => {
+                let text = self.prompt.trim();
+                let time_range = if text.is_empty() {
+                    None
+                } else {
+                    match text.parse::<TimeRange>() {
+                        Ok(time_range) => Some(time_range),
+                        Err(_) => {
+                            self.prompt_invalid = true;
+                            return;
+                        }
+                    }
+                };
+                self.filter.lock().unwrap().set_time_range(time_range);
+            }
+            InputState::Export => {
+                let path = self.prompt.trim().to_owned();
+                if path.is_empty() {
+                    self.prompt_invalid = true;
+                    return;
+                }
+                self.export(Path::new(&path));
+            }
+            _ => {}
+        }
+        self.prompt.clear();
+        self.set_state(InputState::Default);
+    }
+
+    pub fn cancel_prompt(&mut self) {
+        self.prompt.clear();
+        self.prompt_invalid = false;
+        self.set_state(InputState::Default);
+    }
+
+    /// Write the lines currently shown to the file at `path`, reporting the
+    /// outcome in the status.
+    pub fn export(&mut self, path: &Path) {
+        let result = export_events(&self.renderer.lock().unwrap(), path);
+        self.status = Some(match result {
+            Ok(count) => format!("Wrote {count} events to {}", path.display()),
+            Err(e) => format!("Failed to write {}: {e}", path.display()),
+        });
+    }
+
+    /// Add a bookmark called `name` at the current line, replacing any
+    /// bookmark already there.
+    pub fn add_bookmark(&mut self, name: &str) {
+        let absolute_index = self.renderer.lock().unwrap().current_absolute_index();
+        let bookmark = Bookmark {
+            name: name.to_owned(),
+            absolute_index,
+        };
+        match self
+            .bookmarks
+            .binary_search_by_key(&absolute_index, |bookmark| bookmark.absolute_index)
+        {
+            Ok(i) => self.bookmarks[i] = bookmark,
+            Err(i) => self.bookmarks.insert(i, bookmark),
+        }
+    }
+
+    /// Return the name of the bookmark at an absolute trace index, if any.
+    #[must_use]
+    pub fn bookmark_at(&self, absolute_index: usize) -> Option<&str> {
+        self.bookmarks
+            .binary_search_by_key(&absolute_index, |bookmark| bookmark.absolute_index)
+            .ok()
+            .map(|i| self.bookmarks[i].name.as_str())
+    }
+
+    /// Move to the first bookmark after the current line.
+    pub fn next_bookmark(&mut self) {
+        let current = self.renderer.lock().unwrap().current_absolute_index();
+        if let Some(bookmark) = self
+            .bookmarks
+            .iter()
+            .find(|bookmark| bookmark.absolute_index > current)
+        {
+            self.move_to_absolute_line(bookmark.absolute_index + 1);
+        }
+    }
+
+    /// Move to the last bookmark before the current line.
+    pub fn previous_bookmark(&mut self) {
+        let current = self.renderer.lock().unwrap().current_absolute_index();
+        if let Some(bookmark) = self
+            .bookmarks
+            .iter()
+            .rev()
+            .find(|bookmark| bookmark.absolute_index < current)
+        {
+            self.move_to_absolute_line(bookmark.absolute_index + 1);
+        }
+    }
+
+    /// Show the entity tree, with any entities loaded since it was last shown.
+    pub fn open_entity_tree(&mut self) {
+        let renderer = self.renderer.lock().unwrap();
+        self.entity_tree.update(renderer.id_to_name());
+        drop(renderer);
+        self.set_state(InputState::EntityTree);
+    }
+
+    pub fn toggle_entity_hidden(&mut self) {
+        self.entity_tree.toggle_selected_hidden();
+        self.apply_entity_tree();
+    }
+
+    pub fn set_entity_level(&mut self, level: Option<log::Level>) {
+        self.entity_tree.set_selected_level(level);
+        self.apply_entity_tree();
+    }
+
+    fn apply_entity_tree(&mut self) {
+        self.filter
+            .lock()
+            .unwrap()
+            .set_entity_filter(self.entity_tree.entity_filter());
+    }
+
     pub fn push_number_char(&mut self, c: char) {
         self.numbers.push(c);
     }
@@ -9,8 +9,11 @@ use std::sync::{Arc, Mutex};
 
 use serial_test::serial;
 
-use crate::app::{App, InputState};
+use crate::app::{App, EventLine, InputState};
+use crate::entity_tree::EntityTree;
 use crate::filter::Filter;
+use crate::find::Find;
+use crate::live::{LiveTail, SharedLiveBuffer};
 use crate::renderer::Renderer;
 use crate::rocket::SHARED_STATE;
 
@@ -47,6 +50,15 @@ fn create_test_app_with(renderer: Renderer, filter: Filter) -> App {
         filter: Arc::new(Mutex::new(filter)),
         input_state: InputState::Default,
         numbers: String::new(),
+        find: Find::default(),
+        prompt: String::new(),
+        prompt_invalid: false,
+        bookmarks: Vec::new(),
+        entity_tree: EntityTree::default(),
+        status: None,
+        live: None,
+        compare: None,
+        stats: None,
         trace_absolute_index: 0,
         last_renderer_absolute_index: None,
         fullness_absolute_index: None,
@@ -482,3 +494,282 @@ fn number_buffer_helpers_work() {
     app.clear_numbers();
     assert!(app.numbers.is_empty());
 }
+
+/// Build an `App` showing log lines, of which lines 2, 5 and 7 are errors.
+fn create_find_test_app() -> (App, Receiver<()>) {
+    let (app, rx) = create_test_app();
+    {
+        let mut r = app.renderer.lock().unwrap();
+        let lines = (0..10)
+            .map(|i| EventLine::Log {
+                level: log::Level::Info,
+                id: 1,
+                msg: if [2, 5, 7].contains(&i) {
+                    format!("error {i}")
+                } else {
+                    format!("ok {i}")
+                },
+                call_site: None,
+                time: f64::from(i),
+            })
+            .collect();
+        r.add_chunk(lines);
+        r.set_render_indices((0..10).collect());
+    }
+    (app, rx)
+}
+
+fn current_render_index(app: &App) -> usize {
+    app.renderer.lock().unwrap().render_index()
+}
+
+#[test]
+#[serial]
+fn find_moves_to_first_match_as_text_is_typed() {
+    let (mut app, _rx) = create_find_test_app();
+    app.move_to_line(4);
+
+    app.start_find();
+    assert_eq!(app.state(), InputState::Find);
+    for c in "error".chars() {
+        app.push_find_char(c);
+    }
+    assert!(app.find.found);
+    assert_eq!(current_render_index(&app), 5);
+
+    app.push_find_char('!');
+    assert!(!app.find.found);
+    assert_eq!(current_render_index(&app), 5);
+
+    app.backspace_find_char();
+    assert!(app.find.found);
+    assert_eq!(current_render_index(&app), 5);
+}
+
+#[test]
+#[serial]
+fn find_next_and_previous_wrap_around() {
+    let (mut app, _rx) = create_find_test_app();
+
+    app.start_find();
+    for c in "error".chars() {
+        app.push_find_char(c);
+    }
+    app.set_state(InputState::Default);
+    assert_eq!(current_render_index(&app), 2);
+
+    app.find_next();
+    assert_eq!(current_render_index(&app), 5);
+    app.find_next();
+    assert_eq!(current_render_index(&app), 7);
+    app.find_next();
+    assert_eq!(current_render_index(&app), 2);
+
+    app.find_previous();
+    assert_eq!(current_render_index(&app), 7);
+    app.find_previous();
+    assert_eq!(current_render_index(&app), 5);
+}
+
+#[test]
+#[serial]
+fn cancel_find_returns_to_origin() {
+    let (mut app, _rx) = create_find_test_app();
+    app.move_to_line(4);
+
+    app.start_find();
+    app.push_find_char('7');
+    assert_eq!(current_render_index(&app), 7);
+
+    app.cancel_find();
+    assert_eq!(app.state(), InputState::Default);
+    assert!(app.find.text.is_empty());
+    assert_eq!(current_render_index(&app), 3);
+}
+
+#[test]
+#[serial]
+fn bookmarks_are_added_and_navigated() {
+    let (mut app, _rx) = create_find_test_app();
+
+    app.move_to_line(6);
+    app.start_prompt(InputState::Bookmark);
+    for c in "late".chars() {
+        app.push_prompt_char(c);
+    }
+    app.accept_prompt();
+    assert_eq!(app.state(), InputState::Default);
+
+    app.move_to_line(3);
+    app.add_bookmark("early");
+    assert_eq!(app.bookmark_at(2), Some("early"));
+    assert_eq!(app.bookmark_at(5), Some("late"));
+    assert_eq!(app.bookmark_at(3), None);
+
+    app.move_top();
+    app.next_bookmark();
+    assert_eq!(current_render_index(&app), 2);
+    app.next_bookmark();
+    assert_eq!(current_render_index(&app), 5);
+    app.next_bookmark();
+    assert_eq!(current_render_index(&app), 5);
+
+    app.previous_bookmark();
+    assert_eq!(current_render_index(&app), 2);
+
+    // A bookmark at the same line is replaced
+    app.add_bookmark("first");
+    assert_eq!(app.bookmarks.len(), 2);
+    assert_eq!(app.bookmark_at(2), Some("first"));
+}
+
+#[test]
+#[serial]
+fn empty_bookmark_name_is_invalid() {
+    let (mut app, _rx) = create_find_test_app();
+
+    app.start_prompt(InputState::Bookmark);
+    app.push_prompt_char(' ');
+    app.accept_prompt();
+    assert!(app.prompt_invalid);
+    assert_eq!(app.state(), InputState::Bookmark);
+
+    app.cancel_prompt();
+    assert_eq!(app.state(), InputState::Default);
+    assert!(app.bookmarks.is_empty());
+}
+
+#[test]
+#[serial]
+fn time_range_prompt_sets_filter() {
+    let (mut app, _rx) = create_find_test_app();
+
+    app.start_prompt(InputState::TimeRange);
+    for c in "3..x".chars() {
+        app.push_prompt_char(c);
+    }
+    app.accept_prompt();
+    assert!(app.prompt_invalid);
+    assert_eq!(app.filter.lock().unwrap().time_range, None);
+
+    app.backspace_prompt_char();
+    app.push_prompt_char('6');
+    app.accept_prompt();
+    assert_eq!(app.state(), InputState::Default);
+    assert_eq!(
+        app.filter.lock().unwrap().time_range.map(|r| r.to_string()),
+        Some("3ns..6ns".to_string())
+    );
+
+    // The current range is shown to be edited, and clearing it removes it
+    app.start_prompt(InputState::TimeRange);
+    assert_eq!(app.prompt, "3ns..6ns");
+    app.prompt.clear();
+    app.accept_prompt();
+    assert_eq!(app.filter.lock().unwrap().time_range, None);
+}
+
+#[test]
+#[serial]
+fn entity_tree_is_loaded_from_renderer() {
+    let (mut app, _rx) = create_test_app();
+    app.renderer
+        .lock()
+        .unwrap()
+        .extend_id_to_name(HashMap::from([
+            (1, "top".to_string()),
+            (2, "top::node".to_string()),
+        ]));
+
+    app.open_entity_tree();
+    assert_eq!(app.state(), InputState::EntityTree);
+    assert_eq!(app.entity_tree.rows().len(), 1);
+
+    app.toggle_entity_hidden();
+    assert!(app.entity_tree.rows()[0].hidden);
+}
+
+#[test]
+#[serial]
+fn stats_summarise_lines_shown() {
+    let (mut app, _rx) = create_find_test_app();
+    app.renderer
+        .lock()
+        .unwrap()
+        .set_render_indices(vec![2, 5, 7]);
+
+    app.open_stats();
+    assert_eq!(app.state(), InputState::Stats);
+    assert_eq!(app.stats.as_ref().unwrap().num_events, 3);
+
+    app.close_stats();
+    assert_eq!(app.state(), InputState::Default);
+    assert!(app.stats.is_none());
+}
+
+#[test]
+#[serial]
+fn export_writes_lines_shown() {
+    let (mut app, _rx) = create_find_test_app();
+    app.renderer
+        .lock()
+        .unwrap()
+        .set_render_indices(vec![2, 5, 7]);
+
+    app.start_prompt(InputState::Export);
+    assert_eq!(app.prompt, "events.csv");
+    app.prompt.clear();
+    app.accept_prompt();
+    assert!(app.prompt_invalid);
+    assert_eq!(app.state(), InputState::Export);
+
+    let path = std::env::temp_dir().join(format!("spotter-export-{}.csv", std::process::id()));
+    for c in path.to_str().unwrap().chars() {
+        app.push_prompt_char(c);
+    }
+    app.accept_prompt();
+    assert_eq!(app.state(), InputState::Default);
+    assert_eq!(
+        app.status,
+        Some(format!("Wrote 3 events to {}", path.display()))
+    );
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(contents.lines().count(), 4);
+    assert!(contents.lines().skip(1).all(|line| line.contains("error")));
+}
+
+#[test]
+#[serial]
+fn live_events_are_shown_unless_paused() {
+    let (mut app, _rx) = create_test_app();
+    let buffer = SharedLiveBuffer::default();
+    app.live = Some(LiveTail::new(buffer.clone()));
+    let push_value = |time: f64| {
+        buffer.lock().unwrap().push(
+            EventLine::Value {
+                id: 1,
+                value: time,
+                time,
+            },
+            &mut HashMap::from([(1, "top".to_string())]),
+            &mut HashMap::new(),
+        );
+    };
+
+    push_value(1.0);
+    app.tick();
+    assert_eq!(app.renderer.lock().unwrap().num_lines, 1);
+    assert_eq!(app.renderer.lock().unwrap().id_to_name().len(), 1);
+
+    app.toggle_live_paused();
+    push_value(2.0);
+    app.tick();
+    assert_eq!(app.renderer.lock().unwrap().num_lines, 1);
+    assert_eq!(app.live.as_ref().unwrap().status(), "paused, 1 new");
+
+    app.toggle_live_paused();
+    app.tick();
+    assert_eq!(app.renderer.lock().unwrap().num_lines, 2);
+}
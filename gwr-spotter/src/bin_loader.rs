@@ -1,18 +1,20 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::io::BufRead;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
 use gwr_track::Id;
-use gwr_track::entity::Capacity;
+use gwr_track::compression::open_trace;
+use gwr_track::entity::{Capacity, RegisterAccess};
 use gwr_track::trace_visitor::{TraceVisitor, process_capnp};
+use gwr_track::tracker::types::{CallSite, TimeUnit};
 
 use crate::app::{CHUNK_SIZE, EventLine};
 use crate::filter::Filter;
+use crate::live::SharedLiveBuffer;
 use crate::renderer::Renderer;
 use crate::rocket::SHARED_STATE;
 
@@ -28,6 +30,9 @@ struct BinLoader {
     group_memberships: HashMap<u64, u64>,
     activity_lanes: HashMap<u64, u64>,
     current_time_ns: f64,
+
+    /// Where events go as they arrive when following a live trace.
+    live: Option<SharedLiveBuffer>,
 }
 
 impl BinLoader {
@@ -44,12 +49,22 @@ impl BinLoader {
             group_memberships: HashMap::new(),
             activity_lanes: HashMap::new(),
             current_time_ns: 0.0,
+            live: None,
         }
     }
 
     /// Add an individual event. If required, push the current events to the
     /// renderer.
     fn add_event(&mut self, event_line: EventLine) {
+        if let Some(live) = &self.live {
+            live.lock().unwrap().push(
+                event_line,
+                self.id_to_name.as_mut().unwrap(),
+                self.id_to_details.as_mut().unwrap(),
+            );
+            return;
+        }
+
         self.events.as_mut().unwrap().push(event_line);
 
         if self.events.as_ref().unwrap().len() == CHUNK_SIZE {
@@ -98,6 +113,17 @@ impl TraceVisitor for BinLoader {
             level,
             id: id.0,
             msg: message.to_owned(),
+            call_site: None,
+            time: self.current_time_ns,
+        });
+    }
+
+    fn log_at(&mut self, id: Id, level: log::Level, call_site: &CallSite, message: &str) {
+        self.add_event(EventLine::Log {
+            level,
+            id: id.0,
+            msg: message.to_owned(),
+            call_site: Some(call_site.to_string()),
             time: self.current_time_ns,
         });
     }
@@ -303,8 +329,28 @@ impl TraceVisitor for BinLoader {
             .set_capacity(id.0, capacity.value as u64, capacity.units);
     }
 
-    fn time(&mut self, _id: Id, time_ns: f64) {
-        self.current_time_ns = time_ns;
+    fn register_access(&mut self, id: Id, access: RegisterAccess) {
+        // Register accesses are shown alongside the trace messages
+        self.add_event(EventLine::Log {
+            level: log::Level::Trace,
+            id: id.0,
+            msg: access.to_string(),
+            call_site: None,
+            time: self.current_time_ns,
+        });
+    }
+
+    fn metadata(&mut self, key: &str, value: &str) {
+        SHARED_STATE
+            .lock()
+            .unwrap()
+            .metadata
+            .push(format!("{key}={value}"));
+        self.renderer.lock().unwrap().add_metadata(key, value);
+    }
+
+    fn time(&mut self, _id: Id, ticks: u64, time_unit: TimeUnit) {
+        self.current_time_ns = time_unit.convert(ticks, TimeUnit::Ns);
     }
 }
 
@@ -313,8 +359,8 @@ pub fn start_background_load(
     renderer: Arc<Mutex<Renderer>>,
     filter: Arc<Mutex<Filter>>,
 ) {
-    let file = match File::open(bin_file_path) {
-        Ok(file) => file,
+    let reader = match open_trace(bin_file_path) {
+        Ok(reader) => reader,
         Err(e) => {
             println!("Error: {e}");
             return;
@@ -322,9 +368,21 @@ pub fn start_background_load(
     };
 
     thread::spawn(move || {
-        let reader = BufReader::new(file);
         let mut bin_loader = BinLoader::new(renderer, filter);
         process_capnp(reader, &mut bin_loader);
         bin_loader.finish();
     });
 }
+
+/// Load the events of a binary trace that is still being written into `live`
+/// as they arrive, until it ends.
+pub fn load_live(
+    reader: impl BufRead,
+    renderer: Arc<Mutex<Renderer>>,
+    filter: Arc<Mutex<Filter>>,
+    live: SharedLiveBuffer,
+) {
+    let mut bin_loader = BinLoader::new(renderer, filter);
+    bin_loader.live = Some(live);
+    process_capnp(reader, &mut bin_loader);
+}
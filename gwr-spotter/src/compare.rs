@@ -0,0 +1,436 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, channel};
+use std::sync::{Arc, Mutex};
+
+use crate::app::{EventLine, ToTime};
+use crate::filter::Filter;
+use crate::renderer::Renderer;
+use crate::{bin_loader, log_parser};
+
+/// How the lines of two traces are paired up to be compared.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alignment {
+    /// Pair the events at the same simulated time, in the order they happened.
+    Time,
+
+    /// Pair each event with the same occurrence of the same event in the
+    /// other trace, whenever it happened.
+    Sequence,
+}
+
+impl Alignment {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Time => Self::Sequence,
+            Self::Sequence => Self::Time,
+        }
+    }
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Time => write!(f, "time"),
+            Self::Sequence => write!(f, "sequence"),
+        }
+    }
+}
+
+/// The pairing of the lines of a trace with those of another.
+#[derive(Debug, Default, PartialEq)]
+struct Pairing {
+    /// For each line, the line of the other trace it is paired with.
+    partners: Vec<Option<usize>>,
+
+    /// For each line, whether the traces diverge there.
+    divergent: Vec<bool>,
+
+    num_divergent: usize,
+}
+
+/// A second trace that the one being viewed is compared against.
+pub struct Comparison {
+    pub renderer: Arc<Mutex<Renderer>>,
+    pub path: PathBuf,
+    pub alignment: Alignment,
+
+    /// The second trace is not filtered, but its filter must still be able to
+    /// send notifications as it is loaded.
+    _notifications: Receiver<()>,
+
+    pairing: Pairing,
+
+    /// The numbers of lines in the traces, and the alignment, that the
+    /// pairing was made for.
+    paired: Option<(usize, usize, Alignment)>,
+
+    /// The numbers of lines in the traces when they were last updated.
+    last_num_lines: (usize, usize),
+}
+
+impl Comparison {
+    /// Start loading the trace to compare against in the background, as a
+    /// textual log if `is_log` is set and a binary trace otherwise.
+    pub fn new(path: PathBuf, is_log: bool) -> Self {
+        let (tx, rx) = channel();
+        let renderer = Arc::new(Mutex::new(Renderer::new()));
+        let filter = Arc::new(Mutex::new(Filter::new(tx)));
+        if is_log {
+            log_parser::start_background_load(path.as_path(), renderer.clone(), filter);
+        } else {
+            bin_loader::start_background_load(path.as_path(), renderer.clone(), filter);
+        }
+
+        Self {
+            renderer,
+            path,
+            alignment: Alignment::Time,
+            _notifications: rx,
+            pairing: Pairing::default(),
+            paired: None,
+            last_num_lines: (0, 0),
+        }
+    }
+
+    pub fn toggle_alignment(&mut self) {
+        self.alignment = self.alignment.toggle();
+    }
+
+    /// Pair the lines of the traces again if they have changed.
+    ///
+    /// This waits until the traces have stopped growing, so they are not
+    /// paired repeatedly while they are loaded.
+    pub fn update(&mut self, renderer: &Renderer) {
+        let other = self.renderer.lock().unwrap();
+        let num_lines = (renderer.num_lines, other.num_lines);
+        let settled = num_lines == self.last_num_lines;
+        self.last_num_lines = num_lines;
+        if !settled || self.paired == Some((num_lines.0, num_lines.1, self.alignment)) {
+            return;
+        }
+
+        // Lines that are being filtered are not available, so try again later
+        let (Some(keys), Some(other_keys)) = (event_keys(renderer), event_keys(&other)) else {
+            return;
+        };
+        let partners = match self.alignment {
+            Alignment::Time => {
+                let times: Vec<f64> = keys.iter().map(|(_, time)| *time).collect();
+                let other_times: Vec<f64> = other_keys.iter().map(|(_, time)| *time).collect();
+                align_by_time(&times, &other_times)
+            }
+            Alignment::Sequence => {
+                let keys: Vec<u64> = keys.iter().map(|(key, _)| *key).collect();
+                let other_keys: Vec<u64> = other_keys.iter().map(|(key, _)| *key).collect();
+                align_by_sequence(&keys, &other_keys)
+            }
+        };
+        let divergent = find_divergence(&partners, &keys, &other_keys);
+        let num_divergent = divergent.iter().filter(|d| **d).count();
+        self.pairing = Pairing {
+            partners,
+            divergent,
+            num_divergent,
+        };
+        self.paired = Some((num_lines.0, num_lines.1, self.alignment));
+    }
+
+    /// Return the line of the other trace paired with a line of this one.
+    pub fn partner(&self, line_index: usize) -> Option<usize> {
+        self.pairing.partners.get(line_index).copied().flatten()
+    }
+
+    /// Returns whether the traces diverge at a line of this one.
+    pub fn is_divergent(&self, line_index: usize) -> bool {
+        self.pairing
+            .divergent
+            .get(line_index)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Return the number of lines at which the traces diverge.
+    pub fn num_divergent(&self) -> usize {
+        self.pairing.num_divergent
+    }
+}
+
+fn hash_entity(renderer: &Renderer, id: u64, hasher: &mut DefaultHasher) {
+    match renderer.id_to_name().get(&id) {
+        Some(name) if name != "object" => name.hash(hasher),
+        _ => id.hash(hasher),
+    }
+}
+
+/// Identify an event by what happened rather than when.
+///
+/// Entities are identified by name rather than ID so that traces of models
+/// that create different entities can still be compared.
+fn event_key(renderer: &Renderer, line: &EventLine) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    match line {
+        EventLine::Create { id, .. } => {
+            "create".hash(&mut hasher);
+            hash_entity(renderer, *id, &mut hasher);
+        }
+        EventLine::Connect { from_id, to_id, .. } => {
+            "connect".hash(&mut hasher);
+            hash_entity(renderer, *from_id, &mut hasher);
+            hash_entity(renderer, *to_id, &mut hasher);
+        }
+        EventLine::Log { level, id, msg, .. } => {
+            "log".hash(&mut hasher);
+            level.hash(&mut hasher);
+            hash_entity(renderer, *id, &mut hasher);
+            msg.hash(&mut hasher);
+        }
+        EventLine::Enter {
+            id,
+            entered,
+            fullness,
+            ..
+        } => {
+            "enter".hash(&mut hasher);
+            hash_entity(renderer, *id, &mut hasher);
+            entered.hash(&mut hasher);
+            fullness.hash(&mut hasher);
+        }
+        EventLine::Exit {
+            id,
+            exited,
+            fullness,
+            ..
+        } => {
+            "exit".hash(&mut hasher);
+            hash_entity(renderer, *id, &mut hasher);
+            exited.hash(&mut hasher);
+            fullness.hash(&mut hasher);
+        }
+        EventLine::Value { id, value, .. } => {
+            "value".hash(&mut hasher);
+            hash_entity(renderer, *id, &mut hasher);
+            value.to_bits().hash(&mut hasher);
+        }
+        EventLine::ActivityBegin { id, name, .. } => {
+            "activity_begin".hash(&mut hasher);
+            hash_entity(renderer, *id, &mut hasher);
+            name.hash(&mut hasher);
+        }
+        EventLine::ActivityEnd { id, .. } => {
+            "activity_end".hash(&mut hasher);
+            hash_entity(renderer, *id, &mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Return the key and time of every line, or `None` if some of the lines are
+/// not available.
+fn event_keys(renderer: &Renderer) -> Option<Vec<(u64, f64)>> {
+    (0..renderer.num_lines)
+        .map(|index| {
+            let line = renderer.line_from_index(index)?;
+            Some((event_key(renderer, line), line.time()))
+        })
+        .collect()
+}
+
+/// Pair the `n`th event at each time with the `n`th event at the same time in
+/// the other trace.
+fn align_by_time(times: &[f64], other_times: &[f64]) -> Vec<Option<usize>> {
+    let mut partners = Vec::with_capacity(times.len());
+    let mut other_index = 0;
+    let mut index = 0;
+    while index < times.len() {
+        let time = times[index];
+        while other_index < other_times.len() && other_times[other_index] < time {
+            other_index += 1;
+        }
+        let mut other_at_time = other_index;
+        loop {
+            let partner = other_times
+                .get(other_at_time)
+                .filter(|other_time| **other_time == time)
+                .map(|_| other_at_time);
+            partners.push(partner);
+            other_at_time += 1;
+            index += 1;
+            if index == times.len() || times[index] != time {
+                break;
+            }
+        }
+    }
+    partners
+}
+
+/// Pair the `n`th occurrence of each event with the `n`th occurrence of the
+/// same event in the other trace.
+fn align_by_sequence(keys: &[u64], other_keys: &[u64]) -> Vec<Option<usize>> {
+    let mut occurrences: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (index, key) in other_keys.iter().enumerate() {
+        occurrences.entry(*key).or_default().push(index);
+    }
+
+    let mut seen: HashMap<u64, usize> = HashMap::new();
+    keys.iter()
+        .map(|key| {
+            let count = seen.entry(*key).or_default();
+            let partner = occurrences
+                .get(key)
+                .and_then(|indices| indices.get(*count))
+                .copied();
+            *count += 1;
+            partner
+        })
+        .collect()
+}
+
+/// Mark the lines where the traces diverge.
+///
+/// A line diverges if it has no partner, its partner is a different event, or
+/// its partner does not follow on from that of the line before, which shows
+/// that the other trace has events in between that this one does not.
+fn find_divergence(
+    partners: &[Option<usize>],
+    keys: &[(u64, f64)],
+    other_keys: &[(u64, f64)],
+) -> Vec<bool> {
+    let mut next_partner = 0;
+    partners
+        .iter()
+        .zip(keys)
+        .map(|(partner, (key, _))| {
+            let Some(partner) = partner else {
+                return true;
+            };
+            let divergent = *partner != next_partner || other_keys[*partner].0 != *key;
+            next_partner = partner + 1;
+            divergent
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_at_same_time_are_paired_in_order() {
+        let times = [1.0, 1.0, 2.0, 4.0, 4.0];
+        let other_times = [1.0, 2.0, 2.0, 3.0, 4.0, 4.0, 4.0];
+        assert_eq!(
+            align_by_time(&times, &other_times),
+            [Some(0), None, Some(1), Some(4), Some(5)]
+        );
+    }
+
+    #[test]
+    fn repeated_events_are_paired_in_order() {
+        let keys = [7, 8, 7, 9, 7];
+        let other_keys = [7, 7, 8, 9];
+        assert_eq!(
+            align_by_sequence(&keys, &other_keys),
+            [Some(0), Some(2), Some(1), Some(3), None]
+        );
+    }
+
+    #[test]
+    fn divergence_is_where_pairing_breaks() {
+        let keys = [(1, 0.0), (2, 0.0), (3, 1.0), (4, 2.0)];
+
+        // The other trace has an extra event before the third one, and a
+        // different fourth one
+        let other_keys = [(1, 0.0), (2, 0.0), (5, 0.5), (3, 1.0), (6, 2.0)];
+        let partners = [Some(0), Some(1), Some(3), Some(4)];
+        assert_eq!(
+            find_divergence(&partners, &keys, &other_keys),
+            [false, false, true, true]
+        );
+
+        let partners = [Some(0), Some(1), Some(3), None];
+        assert_eq!(
+            find_divergence(&partners, &keys, &other_keys),
+            [false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn events_are_identified_by_entity_name() {
+        let mut renderer = Renderer::new();
+        renderer.extend_id_to_name(HashMap::from([
+            (1, "top::a".to_owned()),
+            (2, "top::a".to_owned()),
+            (3, "top::b".to_owned()),
+        ]));
+        let value = |id, time| EventLine::Value {
+            id,
+            value: 1.0,
+            time,
+        };
+        assert_eq!(
+            event_key(&renderer, &value(1, 0.0)),
+            event_key(&renderer, &value(2, 5.0))
+        );
+        assert_ne!(
+            event_key(&renderer, &value(1, 0.0)),
+            event_key(&renderer, &value(3, 0.0))
+        );
+    }
+
+    fn renderer_of(values: &[(f64, f64)]) -> Renderer {
+        let mut renderer = Renderer::new();
+        renderer.extend_id_to_name(HashMap::from([(1, "top".to_owned())]));
+        renderer.add_chunk(
+            values
+                .iter()
+                .map(|(time, value)| EventLine::Value {
+                    id: 1,
+                    value: *value,
+                    time: *time,
+                })
+                .collect(),
+        );
+        renderer
+    }
+
+    #[test]
+    fn traces_are_paired_once_loaded() {
+        let renderer = renderer_of(&[(1.0, 1.0), (2.0, 2.0), (3.0, 3.0)]);
+        let mut compare = Comparison {
+            renderer: Arc::new(Mutex::new(renderer_of(&[
+                (1.0, 1.0),
+                (2.0, 2.0),
+                (2.5, 9.0),
+                (3.0, 3.0),
+            ]))),
+            path: PathBuf::from("other.bin"),
+            alignment: Alignment::Time,
+            _notifications: channel().1,
+            pairing: Pairing::default(),
+            paired: None,
+            last_num_lines: (0, 0),
+        };
+
+        // Still loading as far as the comparison knows
+        compare.update(&renderer);
+        assert_eq!(compare.partner(0), None);
+
+        compare.update(&renderer);
+        assert_eq!(compare.partner(2), Some(3));
+        assert!(!compare.is_divergent(1));
+        assert!(compare.is_divergent(2));
+        assert_eq!(compare.num_divergent(), 1);
+
+        compare.toggle_alignment();
+        compare.update(&renderer);
+        assert_eq!(compare.paired, Some((3, 4, Alignment::Sequence)));
+        assert_eq!(compare.partner(2), Some(3));
+        assert_eq!(compare.num_divergent(), 1);
+    }
+}
@@ -0,0 +1,328 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::app::EventLine;
+
+/// The name given to objects, which are not entities so are not in the tree.
+const OBJECT_NAME: &str = "object";
+
+/// The separator between the parts of the full name of an entity.
+const SEPARATOR: &str = "::";
+
+/// Which entities, and which levels of their log messages, are shown.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EntityFilter {
+    hidden: HashSet<u64>,
+
+    /// The most verbose level of log messages shown for each entity that is
+    /// limited.
+    levels: HashMap<u64, log::Level>,
+}
+
+impl EntityFilter {
+    pub fn is_empty(&self) -> bool {
+        self.hidden.is_empty() && self.levels.is_empty()
+    }
+
+    fn shows(&self, id: u64, level: Option<log::Level>) -> bool {
+        if self.hidden.contains(&id) {
+            return false;
+        }
+        match (level, self.levels.get(&id)) {
+            (Some(level), Some(max_level)) => level <= *max_level,
+            _ => true,
+        }
+    }
+
+    /// Returns whether a line is shown, based on the entity it is for.
+    pub fn shows_line(&self, line: &EventLine) -> bool {
+        match line {
+            EventLine::Log { id, level, .. } => self.shows(*id, Some(*level)),
+            EventLine::Connect { from_id, .. } => self.shows(*from_id, None),
+            EventLine::Create { id, .. }
+            | EventLine::Enter { id, .. }
+            | EventLine::Exit { id, .. }
+            | EventLine::Value { id, .. }
+            | EventLine::ActivityBegin { id, .. }
+            | EventLine::ActivityEnd { id, .. } => self.shows(*id, None),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    /// The full name of the entity.
+    path: String,
+
+    /// The IDs of the entities with this full name. Parts of the hierarchy
+    /// that were never created as entities have none.
+    ids: Vec<u64>,
+
+    children: BTreeMap<String, Node>,
+    expanded: bool,
+    hidden: bool,
+
+    /// The most verbose level of log messages shown for this entity and
+    /// those below it, if limited.
+    level: Option<log::Level>,
+}
+
+/// A row of the tree as it is shown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeRow {
+    pub depth: usize,
+    pub name: String,
+    pub path: String,
+    pub has_children: bool,
+    pub expanded: bool,
+
+    /// Whether this entity, or one above it, is hidden.
+    pub hidden: bool,
+
+    /// The level of log messages shown, from this entity or the nearest one
+    /// above it that is limited.
+    pub level: Option<log::Level>,
+}
+
+/// The hierarchy of entities, built from their full names.
+#[derive(Default)]
+pub struct EntityTree {
+    root: Node,
+    known_ids: HashSet<u64>,
+
+    /// The index of the selected row.
+    pub selected: usize,
+}
+
+impl EntityTree {
+    /// Add the entities that are not already in the tree.
+    pub fn update(&mut self, id_to_name: &HashMap<u64, String>) {
+        for (id, name) in id_to_name {
+            if name != OBJECT_NAME && self.known_ids.insert(*id) {
+                self.insert(*id, name);
+            }
+        }
+    }
+
+    fn insert(&mut self, id: u64, name: &str) {
+        let mut node = &mut self.root;
+        for part in name.split(SEPARATOR) {
+            let path = if node.path.is_empty() {
+                part.to_owned()
+            } else {
+                format!("{}{SEPARATOR}{part}", node.path)
+            };
+            node = node
+                .children
+                .entry(part.to_owned())
+                .or_insert_with(|| Node {
+                    path,
+                    ..Node::default()
+                });
+        }
+        node.ids.push(id);
+    }
+
+    /// Return the rows of the tree that are shown, with those below collapsed
+    /// entities left out.
+    pub fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        for child in self.root.children.values() {
+            add_rows(child, 0, false, None, &mut rows);
+        }
+        rows
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let num_rows = self.rows().len();
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(num_rows.saturating_sub(1));
+    }
+
+    fn selected_node(&mut self) -> Option<&mut Node> {
+        let path = self.rows().get(self.selected)?.path.clone();
+        let mut node = &mut self.root;
+        for part in path.split(SEPARATOR) {
+            node = node.children.get_mut(part)?;
+        }
+        Some(node)
+    }
+
+    pub fn set_selected_expanded(&mut self, expanded: bool) {
+        if let Some(node) = self.selected_node() {
+            node.expanded = expanded;
+        }
+    }
+
+    /// Hide the selected entity and those below it, or show them again.
+    pub fn toggle_selected_hidden(&mut self) {
+        if let Some(node) = self.selected_node() {
+            node.hidden = !node.hidden;
+        }
+    }
+
+    /// Limit the log messages of the selected entity and those below it to
+    /// `level`, or stop limiting them.
+    pub fn set_selected_level(&mut self, level: Option<log::Level>) {
+        if let Some(node) = self.selected_node() {
+            node.level = level;
+        }
+    }
+
+    /// Return the filter that applies the hidden entities and levels.
+    pub fn entity_filter(&self) -> EntityFilter {
+        let mut entity_filter = EntityFilter::default();
+        add_to_filter(&self.root, false, None, &mut entity_filter);
+        entity_filter
+    }
+}
+
+fn add_rows(
+    node: &Node,
+    depth: usize,
+    parent_hidden: bool,
+    parent_level: Option<log::Level>,
+    rows: &mut Vec<TreeRow>,
+) {
+    let hidden = parent_hidden || node.hidden;
+    let level = node.level.or(parent_level);
+    rows.push(TreeRow {
+        depth,
+        name: node.path.rsplit(SEPARATOR).next().unwrap().to_owned(),
+        path: node.path.clone(),
+        has_children: !node.children.is_empty(),
+        expanded: node.expanded,
+        hidden,
+        level,
+    });
+    if node.expanded {
+        for child in node.children.values() {
+            add_rows(child, depth + 1, hidden, level, rows);
+        }
+    }
+}
+
+fn add_to_filter(
+    node: &Node,
+    parent_hidden: bool,
+    parent_level: Option<log::Level>,
+    entity_filter: &mut EntityFilter,
+) {
+    let hidden = parent_hidden || node.hidden;
+    let level = node.level.or(parent_level);
+    for id in &node.ids {
+        if hidden {
+            entity_filter.hidden.insert(*id);
+        }
+        if let Some(level) = level {
+            entity_filter.levels.insert(*id, level);
+        }
+    }
+    for child in node.children.values() {
+        add_to_filter(child, hidden, level, entity_filter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_tree() -> EntityTree {
+        let mut tree = EntityTree::default();
+        tree.update(&HashMap::from([
+            (1, "top".to_owned()),
+            (2, "top::fabric".to_owned()),
+            (3, "top::fabric::node_0".to_owned()),
+            (4, "top::fabric::node_1".to_owned()),
+            (5, "top::memory".to_owned()),
+            (6, OBJECT_NAME.to_owned()),
+        ]));
+        tree
+    }
+
+    fn log_line(id: u64, level: log::Level) -> EventLine {
+        EventLine::Log {
+            level,
+            id,
+            msg: String::new(),
+            call_site: None,
+            time: 0.0,
+        }
+    }
+
+    #[test]
+    fn rows_follow_expanded_entities() {
+        let mut tree = build_tree();
+        let names = |tree: &EntityTree| -> Vec<String> {
+            tree.rows().into_iter().map(|row| row.path).collect()
+        };
+        assert_eq!(names(&tree), ["top"]);
+
+        tree.set_selected_expanded(true);
+        assert_eq!(names(&tree), ["top", "top::fabric", "top::memory"]);
+
+        tree.move_selection(1);
+        tree.set_selected_expanded(true);
+        assert_eq!(
+            names(&tree),
+            [
+                "top",
+                "top::fabric",
+                "top::fabric::node_0",
+                "top::fabric::node_1",
+                "top::memory"
+            ]
+        );
+
+        tree.move_selection(10);
+        assert_eq!(tree.selected, 4);
+        tree.move_selection(-10);
+        assert_eq!(tree.selected, 0);
+    }
+
+    #[test]
+    fn hidden_subtrees_are_filtered() {
+        let mut tree = build_tree();
+        tree.set_selected_expanded(true);
+        tree.move_selection(1);
+        tree.toggle_selected_hidden();
+
+        let rows = tree.rows();
+        assert!(!rows[0].hidden);
+        assert!(rows[1].hidden);
+        assert!(!rows[2].hidden);
+
+        let entity_filter = tree.entity_filter();
+        assert!(entity_filter.shows_line(&log_line(1, log::Level::Trace)));
+        assert!(!entity_filter.shows_line(&log_line(2, log::Level::Error)));
+        assert!(!entity_filter.shows_line(&log_line(4, log::Level::Error)));
+        assert!(entity_filter.shows_line(&log_line(5, log::Level::Trace)));
+
+        tree.toggle_selected_hidden();
+        assert!(tree.entity_filter().is_empty());
+    }
+
+    #[test]
+    fn levels_apply_to_subtrees() {
+        let mut tree = build_tree();
+        tree.set_selected_level(Some(log::Level::Warn));
+        tree.set_selected_expanded(true);
+        tree.move_selection(1);
+        tree.set_selected_level(Some(log::Level::Debug));
+
+        let entity_filter = tree.entity_filter();
+        assert!(entity_filter.shows_line(&log_line(1, log::Level::Warn)));
+        assert!(!entity_filter.shows_line(&log_line(1, log::Level::Info)));
+        assert!(entity_filter.shows_line(&log_line(3, log::Level::Debug)));
+        assert!(!entity_filter.shows_line(&log_line(3, log::Level::Trace)));
+        assert!(!entity_filter.shows_line(&log_line(5, log::Level::Info)));
+        assert!(entity_filter.shows_line(&EventLine::Value {
+            id: 5,
+            value: 1.0,
+            time: 0.0
+        }));
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::app::{EventLine, ToTime};
+use crate::renderer::Renderer;
+
+/// The format events are exported in, chosen by the extension of the file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Returns JSON for files with a `.json` extension, and CSV otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+const CSV_HEADER: &str = "line,time_ns,kind,id,entity,level,text";
+
+/// An event as it is exported.
+struct ExportedEvent<'a> {
+    /// The line number in the whole trace.
+    line: usize,
+    time_ns: f64,
+    kind: &'static str,
+    id: u64,
+    entity: &'a str,
+    level: Option<log::Level>,
+
+    /// The line as it is shown.
+    text: String,
+}
+
+impl ExportedEvent<'_> {
+    fn to_json(&self) -> Value {
+        json!({
+            "line": self.line,
+            "time_ns": self.time_ns,
+            "kind": self.kind,
+            "id": self.id,
+            "entity": self.entity,
+            "level": self.level.map(|level| level.as_str()),
+            "text": self.text,
+        })
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.line,
+            self.time_ns,
+            self.kind,
+            self.id,
+            csv_field(self.entity),
+            self.level.map(|level| level.as_str()).unwrap_or_default(),
+            csv_field(&self.text)
+        )
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Return the events of the lines currently shown, in order.
+fn shown_events(renderer: &Renderer) -> Vec<ExportedEvent<'_>> {
+    let Some(indices) = renderer.render_indices.as_ref() else {
+        return Vec::new();
+    };
+    indices
+        .iter()
+        .filter_map(|index| {
+            let line = renderer.line_from_index(*index)?;
+            let id = line.entity_id();
+            let level = match line {
+                EventLine::Log { level, .. } => Some(*level),
+                _ => None,
+            };
+            Some(ExportedEvent {
+                line: index + 1,
+                time_ns: line.time(),
+                kind: line.kind(),
+                id,
+                entity: renderer.id_to_name().get(&id).map_or("", String::as_str),
+                level,
+                text: renderer.render_line(*index),
+            })
+        })
+        .collect()
+}
+
+/// Write the events of the lines currently shown to `writer`.
+///
+/// Returns the number of events written.
+pub fn write_events(
+    renderer: &Renderer,
+    format: ExportFormat,
+    writer: &mut dyn Write,
+) -> io::Result<usize> {
+    let events = shown_events(renderer);
+    match format {
+        ExportFormat::Csv => {
+            writeln!(writer, "{CSV_HEADER}")?;
+            for event in &events {
+                writeln!(writer, "{}", event.to_csv())?;
+            }
+        }
+        ExportFormat::Json => {
+            let events: Vec<Value> = events.iter().map(ExportedEvent::to_json).collect();
+            serde_json::to_writer_pretty(&mut *writer, &events)?;
+            writeln!(writer)?;
+        }
+    }
+    writer.flush()?;
+    Ok(events.len())
+}
+
+/// Write the events of the lines currently shown to the file at `path`, in
+/// the format given by its extension.
+///
+/// Returns the number of events written.
+pub fn export_events(renderer: &Renderer, path: &Path) -> io::Result<usize> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_events(renderer, ExportFormat::from_path(path), &mut writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn build_renderer() -> Renderer {
+        let mut renderer = Renderer::new();
+        renderer.extend_id_to_name(HashMap::from([(3, "top::node, 0".to_owned())]));
+        renderer.add_chunk(vec![
+            EventLine::Log {
+                level: log::Level::Warn,
+                id: 3,
+                msg: "said \"hello\"".to_owned(),
+                call_site: None,
+                time: 1.5,
+            },
+            EventLine::Value {
+                id: 3,
+                value: 4.0,
+                time: 2.0,
+            },
+            EventLine::ActivityEnd { id: 3, time: 3.0 },
+        ]);
+        // Only the first two lines pass the filter
+        renderer.set_render_indices(vec![0, 1]);
+        renderer
+    }
+
+    #[test]
+    fn format_follows_extension() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.JSON")),
+            ExportFormat::Json
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.csv")),
+            ExportFormat::Csv
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("out")), ExportFormat::Csv);
+    }
+
+    #[test]
+    fn shown_events_are_written_as_csv() {
+        let mut output = Vec::new();
+        let count = write_events(&build_renderer(), ExportFormat::Csv, &mut output).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "line,time_ns,kind,id,entity,level,text\n\
+             1,1.5,log,3,\"top::node, 0\",WARN,\"3: top::node, 0: said \"\"hello\"\" @1.5ns\"\n\
+             2,2,value,3,\"top::node, 0\",,\"3: top::node, 0: 4 @2.0ns\"\n"
+        );
+    }
+
+    #[test]
+    fn shown_events_are_written_as_json() {
+        let mut output = Vec::new();
+        write_events(&build_renderer(), ExportFormat::Json, &mut output).unwrap();
+        let events: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(events.as_array().unwrap().len(), 2);
+        assert_eq!(events[0]["kind"], "log");
+        assert_eq!(events[0]["level"], "WARN");
+        assert_eq!(events[0]["text"], "3: top::node, 0: said \"hello\" @1.5ns");
+        assert_eq!(events[1]["line"], 2);
+        assert_eq!(events[1]["time_ns"], 2.0);
+        assert_eq!(events[1]["level"], Value::Null);
+    }
+}
@@ -1,16 +1,76 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
 use regex::Regex;
 
-use crate::app::{CHUNK_SIZE, EventLine, INITIAL_SIZE};
+use crate::app::{CHUNK_SIZE, EventLine, INITIAL_SIZE, ToTime};
+use crate::entity_tree::EntityFilter;
 use crate::renderer::Renderer;
 use crate::rocket::SHARED_STATE;
 
+/// A window of simulated time, either end of which may be open.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TimeRange {
+    pub start_ns: Option<f64>,
+    pub end_ns: Option<f64>,
+}
+
+impl TimeRange {
+    pub fn contains(&self, time_ns: f64) -> bool {
+        self.start_ns.is_none_or(|start_ns| time_ns >= start_ns)
+            && self.end_ns.is_none_or(|end_ns| time_ns <= end_ns)
+    }
+}
+
+impl FromStr for TimeRange {
+    type Err = String;
+
+    /// Parse `<start>..<end>` in ns, where either end can be left out.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((start, end)) = s.split_once("..") else {
+            return Err(format!("Expected <start>..<end>, got '{s}'"));
+        };
+        let parse = |time: &str| -> Result<Option<f64>, String> {
+            let time = time.trim().trim_end_matches("ns");
+            if time.is_empty() {
+                return Ok(None);
+            }
+            time.parse()
+                .map(Some)
+                .map_err(|_| format!("Unable to parse time '{time}'"))
+        };
+        let range = Self {
+            start_ns: parse(start)?,
+            end_ns: parse(end)?,
+        };
+        if let (Some(start_ns), Some(end_ns)) = (range.start_ns, range.end_ns)
+            && start_ns > end_ns
+        {
+            return Err(format!("Start {start_ns}ns is after end {end_ns}ns"));
+        }
+        Ok(range)
+    }
+}
+
+impl fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start_ns) = self.start_ns {
+            write!(f, "{start_ns}ns")?;
+        }
+        write!(f, "..")?;
+        if let Some(end_ns) = self.end_ns {
+            write!(f, "{end_ns}ns")?;
+        }
+        Ok(())
+    }
+}
+
 pub struct Filter {
     id_re: Regex,
 
@@ -19,6 +79,13 @@ pub struct Filter {
     pub search_cursor_pos: usize,
     pub use_regex: bool,
 
+    /// If set, only lines in this window of time are shown.
+    pub time_range: Option<TimeRange>,
+
+    /// The entities hidden, and levels of log messages shown, in the entity
+    /// tree.
+    entity_filter: Arc<EntityFilter>,
+
     notify_filter: Sender<()>,
 
     id_to_name: Option<HashMap<u64, String>>,
@@ -34,6 +101,8 @@ struct SearchState {
     id_to_name: HashMap<u64, String>,
     id_to_details: HashMap<u64, String>,
     filter_id: Option<u64>,
+    time_range: Option<TimeRange>,
+    entity_filter: Arc<EntityFilter>,
 }
 
 enum FilterPass {
@@ -86,6 +155,16 @@ impl SearchState {
     }
 
     pub fn search_matches(&self, line: &EventLine) -> bool {
+        if let Some(time_range) = &self.time_range
+            && !time_range.contains(line.time())
+        {
+            return false;
+        }
+
+        if !self.entity_filter.shows_line(line) {
+            return false;
+        }
+
         match line {
             EventLine::Create { id, .. } => self.id_matches(id),
             EventLine::Connect { from_id, to_id, .. } => {
@@ -125,6 +204,8 @@ impl Filter {
             search: String::new(),
             search_cursor_pos: 0,
             use_regex: true,
+            time_range: None,
+            entity_filter: Arc::new(EntityFilter::default()),
         }
     }
 
@@ -224,6 +305,23 @@ impl Filter {
         self.use_regex
     }
 
+    pub fn set_time_range(&mut self, time_range: Option<TimeRange>) {
+        self.time_range = time_range;
+        self.notify_filter.send(()).unwrap();
+    }
+
+    /// Filter again now that lines have been added to the end of the trace.
+    pub fn lines_added(&self) {
+        self.notify_filter.send(()).unwrap();
+    }
+
+    pub fn set_entity_filter(&mut self, entity_filter: EntityFilter) {
+        if *self.entity_filter != entity_filter {
+            self.entity_filter = Arc::new(entity_filter);
+            self.notify_filter.send(()).unwrap();
+        }
+    }
+
     fn start_search(&mut self) -> SearchState {
         let mut filter_id = None;
         let mut search = self.search.to_owned();
@@ -252,6 +350,8 @@ impl Filter {
             filter_id,
             search_re,
             search,
+            time_range: self.time_range,
+            entity_filter: self.entity_filter.clone(),
             id_to_name: self.id_to_name.take().unwrap(),
             id_to_details: self.id_to_details.take().unwrap(),
         }
@@ -363,7 +463,9 @@ fn run_filter_pass(
 mod tests {
     use std::collections::HashMap;
 
-    use super::SearchState;
+    use std::sync::Arc;
+
+    use super::{SearchState, TimeRange};
     use crate::app::EventLine;
 
     fn build_search_state(search: &str) -> SearchState {
@@ -374,6 +476,8 @@ mod tests {
             id_to_name: HashMap::new(),
             id_to_details: HashMap::new(),
             filter_id: None,
+            time_range: None,
+            entity_filter: Arc::default(),
         }
     }
 
@@ -394,4 +498,50 @@ mod tests {
             time: 0.0,
         }));
     }
+
+    #[test]
+    fn lines_outside_the_time_range_are_hidden() {
+        let mut search_state = build_search_state("");
+        search_state.time_range = Some("10..20".parse().unwrap());
+
+        let log = |time| EventLine::Log {
+            level: log::Level::Info,
+            id: 1,
+            msg: "hello".to_owned(),
+            call_site: None,
+            time,
+        };
+        assert!(!search_state.search_matches(&log(9.5)));
+        assert!(search_state.search_matches(&log(10.0)));
+        assert!(search_state.search_matches(&log(20.0)));
+        assert!(!search_state.search_matches(&log(20.5)));
+    }
+
+    #[test]
+    fn time_ranges_are_parsed() {
+        assert_eq!(
+            "100..2000".parse::<TimeRange>(),
+            Ok(TimeRange {
+                start_ns: Some(100.0),
+                end_ns: Some(2000.0),
+            })
+        );
+        assert_eq!(
+            "1.5ns..".parse::<TimeRange>(),
+            Ok(TimeRange {
+                start_ns: Some(1.5),
+                end_ns: None,
+            })
+        );
+        assert_eq!(
+            "..".parse::<TimeRange>(),
+            Ok(TimeRange {
+                start_ns: None,
+                end_ns: None,
+            })
+        );
+        assert!("100".parse::<TimeRange>().is_err());
+        assert!("20..10".parse::<TimeRange>().is_err());
+        assert!("a..b".parse::<TimeRange>().is_err());
+    }
 }
@@ -0,0 +1,106 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::ops::Range;
+
+use regex::Regex;
+
+/// Text to look for in the lines shown.
+pub enum FindPattern {
+    Text(String),
+    Regex(Regex),
+}
+
+impl FindPattern {
+    /// Create the pattern for `text`, which is used as a regular expression
+    /// when `use_regex` is set and it is a valid one.
+    ///
+    /// Returns `None` if there is nothing to look for.
+    pub fn new(text: &str, use_regex: bool) -> Option<Self> {
+        if text.is_empty() {
+            return None;
+        }
+        if use_regex && let Ok(re) = Regex::new(text) {
+            return Some(Self::Regex(re));
+        }
+        Some(Self::Text(text.to_owned()))
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        match self {
+            Self::Text(text) => line.contains(text.as_str()),
+            Self::Regex(re) => re.is_match(line),
+        }
+    }
+
+    /// Return the byte ranges of `line` that match, to be highlighted.
+    pub fn match_ranges(&self, line: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Text(text) => line
+                .match_indices(text.as_str())
+                .map(|(start, matched)| start..start + matched.len())
+                .collect(),
+            Self::Regex(re) => re
+                .find_iter(line)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FindDirection {
+    Forward,
+    Backward,
+}
+
+/// The state of finding text in the lines shown.
+#[derive(Debug, Default)]
+pub struct Find {
+    /// The text being looked for.
+    pub text: String,
+
+    /// The line that the find was started from, to return to if it is
+    /// cancelled.
+    pub origin: usize,
+
+    /// Whether the most recent find matched a line.
+    pub found: bool,
+}
+
+impl Find {
+    pub fn new(origin: usize) -> Self {
+        Self {
+            text: String::new(),
+            origin,
+            found: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FindPattern;
+
+    #[test]
+    fn empty_text_finds_nothing() {
+        assert!(FindPattern::new("", false).is_none());
+        assert!(FindPattern::new("", true).is_none());
+    }
+
+    #[test]
+    fn text_matches_are_highlighted() {
+        let pattern = FindPattern::new("ab", false).unwrap();
+        assert!(pattern.is_match("xxabyyab"));
+        assert_eq!(pattern.match_ranges("xxabyyab"), [2..4, 6..8]);
+    }
+
+    #[test]
+    fn invalid_regex_is_used_as_text() {
+        let pattern = FindPattern::new("top::node[", true).unwrap();
+        assert!(pattern.is_match("1: top::node[0]: error"));
+
+        let pattern = FindPattern::new(r"node\d+", true).unwrap();
+        assert_eq!(pattern.match_ranges("top::node12: x"), [5..11]);
+    }
+}
@@ -8,12 +8,19 @@ pub const TOGGLE_RE: KeyCode = KeyCode::F(1);
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    app.status = None;
     match app.state() {
+        InputState::Bookmark | InputState::Export | InputState::TimeRange => {
+            handle_key_events_prompt(key_event, app)
+        }
         InputState::Default => handle_key_events_default(key_event, app),
+        InputState::EntityTree => handle_key_events_entity_tree(key_event, app),
+        InputState::Find => handle_key_events_find(key_event, app),
         InputState::Goto => handle_key_events_goto(key_event, app),
         InputState::Help => handle_key_events_help(key_event, app),
         InputState::Numbers => handle_key_events_numbers(key_event, app),
         InputState::Search => handle_key_events_search(key_event, app),
+        InputState::Stats => handle_key_events_stats(key_event, app),
     }
 }
 
@@ -84,6 +91,56 @@ pub fn handle_key_events_default(key_event: KeyEvent, app: &mut App) -> AppResul
                 app.set_state(InputState::Search);
             }
 
+            KeyCode::Char('s') => {
+                app.start_find();
+            }
+            KeyCode::Char(']') => {
+                app.find_next();
+            }
+            KeyCode::Char('[') => {
+                app.find_previous();
+            }
+
+            KeyCode::Char('e') => {
+                app.open_entity_tree();
+            }
+
+            KeyCode::Char('T') => {
+                app.start_prompt(InputState::TimeRange);
+            }
+
+            KeyCode::Char('m') => {
+                app.start_prompt(InputState::Bookmark);
+            }
+            KeyCode::Char('b') => {
+                app.next_bookmark();
+            }
+            KeyCode::Char('B') => {
+                app.previous_bookmark();
+            }
+
+            KeyCode::Char('x') => {
+                app.start_prompt(InputState::Export);
+            }
+
+            KeyCode::Char('p') => {
+                app.toggle_live_paused();
+            }
+
+            KeyCode::Char('c') => {
+                app.toggle_alignment();
+            }
+            KeyCode::Char('}') => {
+                app.next_divergence();
+            }
+            KeyCode::Char('{') => {
+                app.previous_divergence();
+            }
+
+            KeyCode::Char('S') => {
+                app.open_stats();
+            }
+
             TOGGLE_RE => {
                 app.filter.lock().unwrap().toggle_regex();
             }
@@ -124,6 +181,11 @@ pub fn handle_key_events_help(_key_event: KeyEvent, app: &mut App) -> AppResult<
     Ok(())
 }
 
+pub fn handle_key_events_stats(_key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    app.close_stats();
+    Ok(())
+}
+
 pub fn handle_key_events_search(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
     if key_event.modifiers == KeyModifiers::CONTROL {
         match key_event.code {
@@ -176,6 +238,106 @@ pub fn handle_key_events_search(key_event: KeyEvent, app: &mut App) -> AppResult
     Ok(())
 }
 
+pub fn handle_key_events_find(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    if key_event.modifiers == KeyModifiers::CONTROL {
+        return handle_key_events_default(key_event, app);
+    }
+
+    match key_event.code {
+        KeyCode::Char(c) => {
+            app.push_find_char(c);
+        }
+        KeyCode::Backspace => {
+            app.backspace_find_char();
+        }
+        KeyCode::Enter => {
+            app.set_state(InputState::Default);
+        }
+        KeyCode::Esc => {
+            app.cancel_find();
+        }
+
+        KeyCode::Down => {
+            app.find_next();
+        }
+        KeyCode::Up => {
+            app.find_previous();
+        }
+
+        _ => {
+            return handle_key_events_default(key_event, app);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_key_events_prompt(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    if key_event.modifiers == KeyModifiers::CONTROL {
+        return handle_key_events_default(key_event, app);
+    }
+
+    match key_event.code {
+        KeyCode::Char(c) => {
+            app.push_prompt_char(c);
+        }
+        KeyCode::Backspace => {
+            app.backspace_prompt_char();
+        }
+        KeyCode::Enter => {
+            app.accept_prompt();
+        }
+        KeyCode::Esc => {
+            app.cancel_prompt();
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+pub fn handle_key_events_entity_tree(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    if key_event.modifiers == KeyModifiers::CONTROL {
+        return handle_key_events_default(key_event, app);
+    }
+
+    match key_event.code {
+        KeyCode::Down | KeyCode::Char('j') => {
+            app.entity_tree.move_selection(1);
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.entity_tree.move_selection(-1);
+        }
+        KeyCode::Right | KeyCode::Char('l') => {
+            app.entity_tree.set_selected_expanded(true);
+        }
+        KeyCode::Left | KeyCode::Char('h') => {
+            app.entity_tree.set_selected_expanded(false);
+        }
+        KeyCode::Char(' ') => {
+            app.toggle_entity_hidden();
+        }
+
+        // Limit the log messages shown
+        KeyCode::Char('0') => {
+            app.set_entity_level(None);
+        }
+        KeyCode::Char(c @ '1'..='5') => {
+            let level = log::Level::iter().nth(c as usize - '1' as usize);
+            app.set_entity_level(level);
+        }
+
+        KeyCode::Esc | KeyCode::Char('e') => {
+            app.set_state(InputState::Default);
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
 pub fn handle_key_events_numbers(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
     match key_event.code {
         KeyCode::Char('g') | KeyCode::Char('G') => {
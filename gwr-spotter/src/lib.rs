@@ -4,9 +4,13 @@
 pub mod app;
 
 mod bin_loader;
+mod entity_tree;
+mod export;
 mod filter;
+mod find;
 mod log_parser;
 mod renderer;
+mod stats;
 
 /// Terminal events handler.
 pub mod event;
@@ -20,6 +24,12 @@ pub mod tui;
 /// Event handler.
 pub mod handler;
 
+/// Following traces that are still being written.
+pub mod live;
+
+/// Comparison of a trace against another one.
+pub mod compare;
+
 pub mod rocket;
 
 /// Perfetto output generator.
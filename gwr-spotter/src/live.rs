@@ -0,0 +1,312 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::mem;
+use std::net::TcpListener;
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use gwr_track::compression::read_trace;
+use gwr_track::socket::SocketAddress;
+
+use crate::app::EventLine;
+use crate::filter::Filter;
+use crate::renderer::Renderer;
+use crate::{bin_loader, log_parser};
+
+/// How long to wait before looking for more of a file that is being written.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Where to read a trace that is still being written from.
+#[derive(Clone, Debug)]
+pub enum LiveSource {
+    /// A textual log file.
+    Log(PathBuf),
+
+    /// A capnp-based binary trace file.
+    Bin(PathBuf),
+
+    /// The binary trace events sent by a simulation run with `--socket`
+    /// pointing at this address, which is listened on.
+    Socket(SocketAddress),
+}
+
+/// A reader of a file that is still being written, which waits for more to be
+/// written when it reaches the end rather than ending.
+struct FollowReader {
+    file: File,
+}
+
+impl Read for FollowReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            let num_read = self.file.read(buf)?;
+            if num_read > 0 {
+                return Ok(num_read);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// The events that have arrived from a live trace, waiting to be shown.
+#[derive(Default)]
+pub struct LiveBuffer {
+    pub events: Vec<EventLine>,
+    pub id_to_name: HashMap<u64, String>,
+    pub id_to_details: HashMap<u64, String>,
+
+    /// Whether the trace has ended, so no more events will arrive.
+    pub ended: bool,
+}
+
+pub type SharedLiveBuffer = Arc<Mutex<LiveBuffer>>;
+
+impl LiveBuffer {
+    /// Add an event, along with the names and details of any entities seen
+    /// since the last one.
+    pub fn push(
+        &mut self,
+        event_line: EventLine,
+        id_to_name: &mut HashMap<u64, String>,
+        id_to_details: &mut HashMap<u64, String>,
+    ) {
+        self.events.push(event_line);
+        self.id_to_name.extend(id_to_name.drain());
+        self.id_to_details.extend(id_to_details.drain());
+    }
+
+    fn take(&mut self) -> Self {
+        Self {
+            events: mem::take(&mut self.events),
+            id_to_name: mem::take(&mut self.id_to_name),
+            id_to_details: mem::take(&mut self.id_to_details),
+            ended: self.ended,
+        }
+    }
+}
+
+/// The state of following a trace that is still being written.
+pub struct LiveTail {
+    buffer: SharedLiveBuffer,
+
+    /// Whether new events are held back so that the lines shown stay still.
+    pub paused: bool,
+
+    /// The number of lines shown when the view last moved to the end.
+    followed_render_lines: usize,
+}
+
+impl LiveTail {
+    pub fn new(buffer: SharedLiveBuffer) -> Self {
+        Self {
+            buffer,
+            paused: false,
+            followed_render_lines: 0,
+        }
+    }
+
+    pub fn toggle_paused(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Take the events that have arrived, unless paused or there are none.
+    pub fn take_events(&mut self) -> Option<LiveBuffer> {
+        if self.paused {
+            return None;
+        }
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.events.is_empty() {
+            return None;
+        }
+        Some(buffer.take())
+    }
+
+    /// Returns whether the view should move to the end of the lines shown,
+    /// because lines have been added since it last did.
+    pub fn should_follow(&mut self, num_render_lines: usize) -> bool {
+        if self.paused || num_render_lines == self.followed_render_lines {
+            return false;
+        }
+        self.followed_render_lines = num_render_lines;
+        true
+    }
+
+    /// Return a summary of the state, to be shown with the position.
+    pub fn status(&self) -> String {
+        let buffer = self.buffer.lock().unwrap();
+        let num_waiting = buffer.events.len();
+        if self.paused {
+            format!("paused, {num_waiting} new")
+        } else if buffer.ended && num_waiting == 0 {
+            "ended".to_owned()
+        } else {
+            "live".to_owned()
+        }
+    }
+}
+
+type Connect = Box<dyn FnOnce() -> io::Result<Box<dyn BufRead + Send>> + Send>;
+
+/// Open the file or socket now, so that errors are reported straight away,
+/// and return how to start reading from it.
+fn open(source: &LiveSource) -> io::Result<Connect> {
+    match source {
+        LiveSource::Log(path) | LiveSource::Bin(path) => {
+            let file = File::open(path)?;
+            Ok(Box::new(move || {
+                read_trace(BufReader::new(FollowReader { file }))
+            }))
+        }
+        LiveSource::Socket(SocketAddress::Tcp(host_port)) => {
+            let listener = TcpListener::bind(host_port)?;
+            Ok(Box::new(move || {
+                let (stream, _) = listener.accept()?;
+                Ok(Box::new(BufReader::new(stream)) as Box<dyn BufRead + Send>)
+            }))
+        }
+        #[cfg(unix)]
+        LiveSource::Socket(SocketAddress::Unix(path)) => {
+            // Remove a socket left behind by an earlier run
+            if path
+                .symlink_metadata()
+                .is_ok_and(|metadata| metadata.file_type().is_socket())
+            {
+                std::fs::remove_file(path)?;
+            }
+            let listener = UnixListener::bind(path)?;
+            Ok(Box::new(move || {
+                let (stream, _) = listener.accept()?;
+                Ok(Box::new(BufReader::new(stream)) as Box<dyn BufRead + Send>)
+            }))
+        }
+    }
+}
+
+/// Start reading the events of a trace that is still being written, adding
+/// them to the buffer returned as they arrive.
+pub fn start_live_load(
+    source: LiveSource,
+    renderer: Arc<Mutex<Renderer>>,
+    filter: Arc<Mutex<Filter>>,
+) -> io::Result<SharedLiveBuffer> {
+    let connect = open(&source)?;
+    let live = SharedLiveBuffer::default();
+
+    let thread_live = live.clone();
+    thread::spawn(move || {
+        match connect() {
+            Ok(reader) => match source {
+                LiveSource::Log(_) => log_parser::load_live(reader, thread_live.clone()),
+                LiveSource::Bin(_) | LiveSource::Socket(_) => {
+                    bin_loader::load_live(reader, renderer, filter, thread_live.clone());
+                }
+            },
+            Err(e) => {
+                let err_line = EventLine::Log {
+                    level: log::Level::Error,
+                    id: 0,
+                    msg: e.to_string(),
+                    call_site: None,
+                    time: 0.0,
+                };
+                thread_live.lock().unwrap().events.push(err_line);
+            }
+        }
+        thread_live.lock().unwrap().ended = true;
+    });
+
+    Ok(live)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(time: f64) -> EventLine {
+        EventLine::Value {
+            id: 1,
+            value: 0.0,
+            time,
+        }
+    }
+
+    #[test]
+    fn events_are_held_while_paused() {
+        let buffer = SharedLiveBuffer::default();
+        let mut tail = LiveTail::new(buffer.clone());
+        let mut id_to_name = HashMap::from([(1, "top".to_owned())]);
+        buffer
+            .lock()
+            .unwrap()
+            .push(value(1.0), &mut id_to_name, &mut HashMap::new());
+        assert!(id_to_name.is_empty());
+
+        tail.toggle_paused();
+        assert!(tail.take_events().is_none());
+        assert_eq!(tail.status(), "paused, 1 new");
+
+        tail.toggle_paused();
+        let taken = tail.take_events().unwrap();
+        assert_eq!(taken.events.len(), 1);
+        assert_eq!(taken.id_to_name.get(&1).map(String::as_str), Some("top"));
+        assert!(tail.take_events().is_none());
+        assert_eq!(tail.status(), "live");
+
+        buffer.lock().unwrap().ended = true;
+        assert_eq!(tail.status(), "ended");
+    }
+
+    #[test]
+    fn view_follows_new_lines_unless_paused() {
+        let mut tail = LiveTail::new(SharedLiveBuffer::default());
+        assert!(tail.should_follow(10));
+        assert!(!tail.should_follow(10));
+
+        tail.toggle_paused();
+        assert!(!tail.should_follow(20));
+        tail.toggle_paused();
+        assert!(tail.should_follow(20));
+    }
+
+    #[test]
+    fn followed_file_is_read_as_it_grows() {
+        let path = std::env::temp_dir().join(format!("spotter-follow-{}.log", std::process::id()));
+        std::fs::write(&path, "1:INFO: first\n").unwrap();
+
+        let mut reader = BufReader::new(FollowReader {
+            file: File::open(&path).unwrap(),
+        });
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "1:INFO: first\n");
+
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            thread::sleep(POLL_INTERVAL);
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(writer_path)
+                .unwrap();
+            io::Write::write_all(&mut file, b"1:INFO: second\n").unwrap();
+        });
+
+        // Waits for the line to be written rather than ending
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        writer.join().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(line, "1:INFO: second\n");
+    }
+}
@@ -1,19 +1,20 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
+use gwr_track::compression::open_trace;
 use itertools::Itertools;
 use log::Level;
 use regex::Regex;
 
 use crate::app::{CHUNK_SIZE, EventLine};
 use crate::filter::Filter;
+use crate::live::SharedLiveBuffer;
 use crate::renderer::Renderer;
 use crate::rocket::SHARED_STATE;
 
@@ -81,6 +82,7 @@ impl LogParser {
                     level: Level::from_str(level_str).unwrap(),
                     id,
                     msg: msg.to_owned(),
+                    call_site: None,
                     time: self.current_time,
                 }
             }
@@ -116,6 +118,7 @@ impl LogParser {
                 level: log::Level::Trace,
                 id: 0,
                 msg: msg.to_owned(),
+                call_site: None,
                 time: self.current_time,
             };
         }
@@ -145,6 +148,7 @@ impl LogParser {
             level: log::Level::Trace,
             id: 0,
             msg: msg.to_owned(),
+            call_site: None,
             time: self.current_time,
         }
     }
@@ -241,6 +245,7 @@ impl LogParser {
             level: log::Level::from_str(level_str).unwrap(),
             id: id_str.parse().unwrap(),
             msg: text_str.to_owned(),
+            call_site: None,
             time: self.current_time,
         })
     }
@@ -352,6 +357,7 @@ impl LogParser {
             level: log::Level::Trace,
             id,
             msg: msg.to_owned(),
+            call_site: None,
             time: self.current_time,
         })
     }
@@ -367,6 +373,7 @@ impl LogParser {
             level: log::Level::Trace,
             id,
             msg: msg.to_owned(),
+            call_site: None,
             time: self.current_time,
         })
     }
@@ -402,8 +409,8 @@ pub fn start_background_load(
     renderer: Arc<Mutex<Renderer>>,
     filter: Arc<Mutex<Filter>>,
 ) {
-    let file = match File::open(log_file_path) {
-        Ok(file) => file,
+    let reader = match open_trace(log_file_path) {
+        Ok(reader) => reader,
         Err(e) => {
             println!("Error: {e}");
             return;
@@ -423,7 +430,6 @@ pub fn start_background_load(
         // on an entity and decide if it is a source.
         let mut id_is_source = HashMap::new();
 
-        let reader = BufReader::new(file);
         for chunk in &reader.lines().chunks(CHUNK_SIZE) {
             let mut events = Vec::with_capacity(CHUNK_SIZE);
             let mut id_to_name = HashMap::new();
@@ -443,6 +449,7 @@ pub fn start_background_load(
                             level: log::Level::Error,
                             id: 0,
                             msg: e.to_string(),
+                            call_site: None,
                             time: 0.0,
                         };
                         events.push(err_line);
@@ -465,6 +472,38 @@ pub fn start_background_load(
     });
 }
 
+/// Load the lines of a log that is still being written into `live` as they
+/// arrive, until it ends.
+pub fn load_live(reader: impl BufRead, live: SharedLiveBuffer) {
+    let mut parser = LogParser::new();
+    let mut id_to_fullness = HashMap::new();
+    let mut id_is_source = HashMap::new();
+    let mut id_to_name = HashMap::new();
+    let mut id_to_details = HashMap::new();
+
+    for l in reader.lines() {
+        let event = match l {
+            Ok(line) => parser.parse_line(
+                line.as_str(),
+                &mut id_to_name,
+                &mut id_to_details,
+                &mut id_to_fullness,
+                &mut id_is_source,
+            ),
+            Err(e) => EventLine::Log {
+                level: log::Level::Error,
+                id: 0,
+                msg: e.to_string(),
+                call_site: None,
+                time: 0.0,
+            },
+        };
+        live.lock()
+            .unwrap()
+            .push(event, &mut id_to_name, &mut id_to_details);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
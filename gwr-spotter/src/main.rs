@@ -7,12 +7,15 @@ use std::{io, thread};
 
 use clap::{Args, Parser};
 use gwr_spotter::app::{App, AppResult};
+use gwr_spotter::compare::Comparison;
 use gwr_spotter::event::{Event, EventHandler};
 use gwr_spotter::handler::handle_key_events;
+use gwr_spotter::live::LiveSource;
 #[cfg(feature = "perfetto")]
 use gwr_spotter::perfetto;
 use gwr_spotter::rocket::rocket;
 use gwr_spotter::tui::Tui;
+use gwr_track::socket::SocketAddress;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use tokio::runtime::Runtime;
@@ -28,6 +31,11 @@ struct InputOptions {
     /// Provide a capnp-based binary trace
     #[arg(long, group = "perfetto_compat")]
     bin: Option<PathBuf>,
+
+    /// Listen on an address (<host>:<port> or unix:<path>) for the binary
+    /// trace events of a simulation run with `--socket`
+    #[arg(long)]
+    listen: Option<SocketAddress>,
 }
 
 /// Command-line arguments.
@@ -37,6 +45,16 @@ struct Cli {
     #[command(flatten)]
     input: InputOptions,
 
+    /// Keep reading the log or binary trace as it is written, showing new
+    /// events as they arrive
+    #[arg(long)]
+    follow: bool,
+
+    /// Compare against a second trace of the same kind, shown alongside with
+    /// the lines where they diverge highlighted
+    #[arg(long, conflicts_with = "listen")]
+    compare: Option<PathBuf>,
+
     /// Generate Perfetto output from GWR binary trace with this name
     ///
     /// gwr-spotter will exit having produced the Perfetto trace.
@@ -74,7 +92,25 @@ async fn main() -> AppResult<()> {
     spawn_rocket();
 
     // Create an application.
-    let mut app = App::new(args.input.log, args.input.bin);
+    let live_source = if let Some(address) = args.input.listen {
+        Some(LiveSource::Socket(address))
+    } else if args.follow {
+        args.input
+            .log
+            .clone()
+            .map(LiveSource::Log)
+            .or_else(|| args.input.bin.clone().map(LiveSource::Bin))
+    } else {
+        None
+    };
+    let is_log = args.input.log.is_some();
+    let mut app = match live_source {
+        Some(source) => App::new_live(source),
+        None => App::new(args.input.log, args.input.bin),
+    };
+    if let Some(compare_path) = args.compare {
+        app = app.with_comparison(Comparison::new(compare_path, is_log));
+    }
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
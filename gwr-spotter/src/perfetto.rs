@@ -2,12 +2,15 @@
 
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Write};
+use std::io::Write;
 use std::path::Path;
 
 use gwr_track::Id;
+use gwr_track::compression::open_trace;
+use gwr_track::entity::RegisterAccess;
 use gwr_track::perfetto_trace_builder::PerfettoTraceBuilder;
 use gwr_track::trace_visitor::{TraceVisitor, process_capnp};
+use gwr_track::tracker::types::TimeUnit;
 
 struct PerfettoGenerator {
     output: File,
@@ -192,21 +195,40 @@ impl TraceVisitor for PerfettoGenerator {
         }
     }
 
-    fn time(&mut self, _id: Id, time_ns: f64) {
-        self.current_time_ns = time_ns as u64;
+    fn register_access(&mut self, id: Id, access: RegisterAccess) {
+        let trace_packets = self.trace_builder.build_register_access_trace_packets(
+            self.current_time_ns,
+            id,
+            &access,
+        );
+        let buf = self.trace_builder.build_trace_to_bytes(trace_packets);
+        self.output
+            .write_all(&buf)
+            .expect("`output` should be writable file");
+    }
+
+    fn metadata(&mut self, key: &str, value: &str) {
+        let trace_packet = self.trace_builder.build_metadata_trace_packet(key, value);
+        let buf = self.trace_builder.build_trace_to_bytes(vec![trace_packet]);
+        self.output
+            .write_all(&buf)
+            .expect("`output` should be writable file");
+    }
+
+    fn time(&mut self, _id: Id, ticks: u64, time_unit: TimeUnit) {
+        self.current_time_ns = time_unit.convert_ticks(ticks, TimeUnit::Ns);
     }
 }
 
 pub fn generate_perfetto_trace(input_bin_file_path: &Path, output_file_path: &Path) {
-    let file = match File::open(input_bin_file_path) {
-        Ok(file) => file,
+    let reader = match open_trace(input_bin_file_path) {
+        Ok(reader) => reader,
         Err(e) => {
             println!("Error: {e}");
             return;
         }
     };
 
-    let reader = BufReader::new(file);
     let mut perfetto_gen = PerfettoGenerator::new(output_file_path);
     process_capnp(reader, &mut perfetto_gen);
     perfetto_gen.finish();
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::fmt::Write;
 
 use crate::app::{CHUNK_SIZE, EventLine, INITIAL_SIZE, ToFullness, ToTime};
+use crate::find::{FindDirection, FindPattern};
 
 const UNKNOWN: &str = "???";
 
@@ -13,12 +14,18 @@ pub struct Renderer {
     id_to_capacity_units: HashMap<u64, String>,
     id_to_details: HashMap<u64, String>,
 
+    /// The metadata of the run, in the order it was recorded
+    metadata: Vec<(String, String)>,
+
     /// Current location within the file
     render_index: usize,
 
     /// Vector of blocks of lines that can be taken out for processing
     blocks: Vec<Option<Vec<EventLine>>>,
 
+    /// Lines to be appended once the last block is restored
+    pending_lines: Vec<EventLine>,
+
     /// Total number of lines (sum of blocks)
     pub num_lines: usize,
 
@@ -45,7 +52,9 @@ impl Renderer {
             id_to_capacity: HashMap::with_capacity(INITIAL_SIZE),
             id_to_capacity_units: HashMap::with_capacity(INITIAL_SIZE),
             id_to_details: HashMap::with_capacity(INITIAL_SIZE),
+            metadata: Vec::new(),
             blocks: Vec::with_capacity(INITIAL_SIZE),
+            pending_lines: Vec::new(),
             render_indices: None,
             num_render_lines: 0,
 
@@ -74,6 +83,11 @@ impl Renderer {
         }
     }
 
+    /// Return the index of the current rendered line.
+    pub fn render_index(&self) -> usize {
+        self.render_index
+    }
+
     /// Return the absolute trace index for the current rendered line.
     pub fn current_absolute_index(&self) -> usize {
         self.render_index_to_absolute_index(self.render_index)
@@ -107,6 +121,7 @@ impl Renderer {
 
     pub fn restore_chunk(&mut self, block_index: usize, chunk: Option<Vec<EventLine>>) {
         self.blocks[block_index] = chunk;
+        self.append_pending_lines();
     }
 
     fn name_id<'a>(&'a self, id: &u64, tmp: &'a mut String) -> &'a str {
@@ -256,9 +271,19 @@ impl Renderer {
                 (format!("{name}: {value}").to_owned(), time)
             }
 
-            EventLine::Log { id, msg, time, .. } => {
+            EventLine::Log {
+                id,
+                msg,
+                call_site,
+                time,
+                ..
+            } => {
                 let name = self.name_id(id, &mut tmp0);
-                (format!("{name}: {msg}").to_owned(), time)
+                let suffix = call_site
+                    .as_ref()
+                    .map(|call_site| format!(" ({call_site})"))
+                    .unwrap_or_default();
+                (format!("{name}: {msg}{suffix}").to_owned(), time)
             }
 
             EventLine::ActivityBegin {
@@ -326,6 +351,41 @@ impl Renderer {
         self.blocks.push(Some(lines));
     }
 
+    /// Add lines to the end of a trace that is still being written.
+    ///
+    /// The last block is filled before new ones are started so that every
+    /// block but the last stays full. If the last block has been taken out to
+    /// be filtered then the lines are held until it is restored.
+    pub fn append_lines(&mut self, lines: Vec<EventLine>) {
+        self.pending_lines.extend(lines);
+        self.append_pending_lines();
+    }
+
+    fn append_pending_lines(&mut self) {
+        if self.pending_lines.is_empty() {
+            return;
+        }
+
+        if let Some(last_block) = self.blocks.last_mut() {
+            let Some(last_block) = last_block else {
+                return;
+            };
+            let num_to_fill = (CHUNK_SIZE - last_block.len()).min(self.pending_lines.len());
+            last_block.extend(self.pending_lines.drain(..num_to_fill));
+            self.num_lines += num_to_fill;
+        }
+
+        while !self.pending_lines.is_empty() {
+            let num_to_fill = CHUNK_SIZE.min(self.pending_lines.len());
+            let block: Vec<EventLine> = self.pending_lines.drain(..num_to_fill).collect();
+            self.add_chunk(block);
+        }
+    }
+
+    pub fn id_to_name(&self) -> &HashMap<u64, String> {
+        &self.id_to_name
+    }
+
     pub fn extend_id_to_name(&mut self, id_to_name: HashMap<u64, String>) {
         self.id_to_name.extend(id_to_name);
     }
@@ -339,6 +399,53 @@ impl Renderer {
         self.id_to_details.extend(id_to_details);
     }
 
+    pub fn add_metadata(&mut self, key: &str, value: &str) {
+        self.metadata.push((key.to_owned(), value.to_owned()));
+    }
+
+    pub fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+
+    /// Find the first rendered line that matches `pattern`, starting at the
+    /// render index `from` and wrapping around the lines shown.
+    ///
+    /// Returns the render index of the line found.
+    pub fn find_line(
+        &self,
+        pattern: &FindPattern,
+        from: usize,
+        direction: FindDirection,
+    ) -> Option<usize> {
+        self.find_line_where(from, direction, |index| {
+            pattern.is_match(&self.render_line(index))
+        })
+    }
+
+    /// Find the first rendered line for which `is_match` returns true, given
+    /// its absolute index, starting at the render index `from` and wrapping
+    /// around the lines shown.
+    ///
+    /// Returns the render index of the line found.
+    pub fn find_line_where(
+        &self,
+        from: usize,
+        direction: FindDirection,
+        is_match: impl Fn(usize) -> bool,
+    ) -> Option<usize> {
+        let num_lines = self.num_render_lines;
+        if num_lines == 0 {
+            return None;
+        }
+        let from = from % num_lines;
+        (0..num_lines)
+            .map(|offset| match direction {
+                FindDirection::Forward => (from + offset) % num_lines,
+                FindDirection::Backward => (from + num_lines - offset) % num_lines,
+            })
+            .find(|render_index| is_match(self.render_index_to_absolute_index(*render_index)))
+    }
+
     fn render_index_to_absolute_index(&self, line: usize) -> usize {
         if let Some(indices) = &self.render_indices {
             if let Some(index) = indices.get(line) {
@@ -500,4 +607,26 @@ mod tests {
             "7: pe0::lane::compute::0: activity end @15.0ns"
         );
     }
+
+    #[test]
+    fn appended_lines_fill_last_block() {
+        let value = |i: usize| EventLine::Value {
+            id: 1,
+            value: i as f64,
+            time: i as f64,
+        };
+        let mut renderer = Renderer::new();
+        renderer.append_lines((0..CHUNK_SIZE - 1).map(value).collect());
+
+        // Lines appended while the last block is being filtered are held back
+        let chunk = renderer.take_chunk(0);
+        renderer.append_lines((CHUNK_SIZE - 1..CHUNK_SIZE + 2).map(value).collect());
+        assert_eq!(renderer.num_lines, CHUNK_SIZE - 1);
+
+        renderer.restore_chunk(0, chunk);
+        assert_eq!(renderer.num_lines, CHUNK_SIZE + 2);
+        for i in [0, CHUNK_SIZE - 1, CHUNK_SIZE + 1] {
+            assert_eq!(renderer.line_time(i), i as f64);
+        }
+    }
 }
@@ -12,6 +12,7 @@ pub struct SharedState {
     pub capacities: Vec<String>,
     pub fullnesses: Vec<String>,
     pub connections: Vec<String>,
+    pub metadata: Vec<String>,
     pub command: Option<String>,
     pub selected: Option<u64>,
     pub current_line: usize,
@@ -27,6 +28,7 @@ impl SharedState {
             capacities: Vec::new(),
             fullnesses: Vec::new(),
             connections: Vec::new(),
+            metadata: Vec::new(),
             command: None,
             selected: None,
             current_line: 0,
@@ -41,6 +43,7 @@ impl SharedState {
         self.capacities.clear();
         self.fullnesses.clear();
         self.connections.clear();
+        self.metadata.clear();
         self.command = None;
         self.selected = None;
         self.current_line = 0;
@@ -118,6 +121,11 @@ fn connections() -> String {
     SHARED_STATE.lock().unwrap().connections.join("\n")
 }
 
+#[get("/metadata")]
+fn metadata() -> String {
+    SHARED_STATE.lock().unwrap().metadata.join("\n")
+}
+
 #[get("/select/<id>")]
 async fn select(id: RocketId) -> String {
     let mut guard = SHARED_STATE.lock().unwrap();
@@ -158,6 +166,7 @@ pub fn rocket() -> _ {
             capacities,
             fullnesses,
             connections,
+            metadata,
             select,
             selected,
             position,
@@ -0,0 +1,265 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::app::{EventLine, ToTime};
+use crate::renderer::Renderer;
+
+/// The number of windows of time that the lines shown are divided into.
+const NUM_WINDOWS: usize = 240;
+
+/// The number of entities with the most events that are listed.
+const NUM_TOP_TALKERS: usize = 10;
+
+/// The number of log levels, from error to trace.
+pub const NUM_LEVELS: usize = 5;
+
+/// The events of an entity.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EntityCounts {
+    pub name: String,
+    pub total: usize,
+
+    /// The number of log messages at each level, from error to trace.
+    pub levels: [usize; NUM_LEVELS],
+}
+
+/// The values recorded by a monitor over time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorCurve {
+    pub name: String,
+
+    /// The mean value in each window of time that has any, at the middle of
+    /// the window.
+    pub points: Vec<(f64, f64)>,
+    pub max_value: f64,
+}
+
+/// A summary of the lines shown.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub num_events: usize,
+    pub start_ns: f64,
+    pub end_ns: f64,
+
+    /// The number of events of each kind.
+    pub kinds: BTreeMap<&'static str, usize>,
+
+    /// The number of log messages at each level, from error to trace.
+    pub levels: [usize; NUM_LEVELS],
+
+    /// The entities with the most events, most first.
+    pub top_talkers: Vec<EntityCounts>,
+
+    /// The number of events in each window of time.
+    pub rate: Vec<u64>,
+
+    /// The values of each monitor, by name. Only monitors record values.
+    pub monitors: Vec<MonitorCurve>,
+}
+
+impl Stats {
+    /// Summarise the lines currently shown.
+    pub fn new(renderer: &Renderer) -> Self {
+        let Some(indices) = renderer.render_indices.as_ref() else {
+            return Self::default();
+        };
+        let (Some(first), Some(last)) = (indices.first(), indices.last()) else {
+            return Self::default();
+        };
+        let start_ns = renderer.line_time(*first);
+        let end_ns = renderer.line_time(*last);
+        let window_ns = (end_ns - start_ns) / NUM_WINDOWS as f64;
+        let window_of = |time: f64| {
+            if window_ns > 0.0 {
+                (((time - start_ns) / window_ns) as usize).min(NUM_WINDOWS - 1)
+            } else {
+                0
+            }
+        };
+
+        let mut stats = Self {
+            start_ns,
+            end_ns,
+            rate: vec![0; NUM_WINDOWS],
+            ..Self::default()
+        };
+        let mut entities: HashMap<u64, EntityCounts> = HashMap::new();
+        let mut monitor_sums: HashMap<u64, Vec<(f64, usize)>> = HashMap::new();
+        for index in indices {
+            let Some(line) = renderer.line_from_index(*index) else {
+                continue;
+            };
+            let window = window_of(line.time());
+            stats.num_events += 1;
+            stats.rate[window] += 1;
+            *stats.kinds.entry(line.kind()).or_default() += 1;
+
+            let counts = entities.entry(line.entity_id()).or_default();
+            counts.total += 1;
+            match line {
+                EventLine::Log { level, .. } => {
+                    let level = *level as usize - 1;
+                    stats.levels[level] += 1;
+                    counts.levels[level] += 1;
+                }
+                EventLine::Value { id, value, .. } => {
+                    let sums = monitor_sums
+                        .entry(*id)
+                        .or_insert_with(|| vec![(0.0, 0); NUM_WINDOWS]);
+                    sums[window].0 += value;
+                    sums[window].1 += 1;
+                }
+                _ => {}
+            }
+        }
+
+        let name_of = |id: u64| {
+            renderer
+                .id_to_name()
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| id.to_string())
+        };
+
+        let mut top_talkers: Vec<(u64, EntityCounts)> = entities.into_iter().collect();
+        top_talkers.sort_by(|(a_id, a), (b_id, b)| b.total.cmp(&a.total).then(a_id.cmp(b_id)));
+        stats.top_talkers = top_talkers
+            .into_iter()
+            .take(NUM_TOP_TALKERS)
+            .map(|(id, counts)| EntityCounts {
+                name: name_of(id),
+                ..counts
+            })
+            .collect();
+
+        stats.monitors = monitor_sums
+            .into_iter()
+            .map(|(id, sums)| {
+                let points: Vec<(f64, f64)> = sums
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, count))| *count > 0)
+                    .map(|(window, (sum, count))| {
+                        (
+                            start_ns + (window as f64 + 0.5) * window_ns,
+                            sum / *count as f64,
+                        )
+                    })
+                    .collect();
+                let max_value = points.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+                MonitorCurve {
+                    name: name_of(id),
+                    points,
+                    max_value,
+                }
+            })
+            .collect();
+        stats.monitors.sort_by(|a, b| a.name.cmp(&b.name));
+        stats
+    }
+}
+
+/// Combine the counts in `values` so that there are no more than `width`,
+/// preserving their total.
+pub fn resample(values: &[u64], width: usize) -> Vec<u64> {
+    if width == 0 || values.len() <= width {
+        return values.to_vec();
+    }
+    (0..width)
+        .map(|i| {
+            let start = i * values.len() / width;
+            let end = (i + 1) * values.len() / width;
+            values[start..end].iter().sum()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_line(id: u64, level: log::Level, time: f64) -> EventLine {
+        EventLine::Log {
+            level,
+            id,
+            msg: String::new(),
+            call_site: None,
+            time,
+        }
+    }
+
+    fn build_renderer() -> Renderer {
+        let mut renderer = Renderer::new();
+        renderer.extend_id_to_name(HashMap::from([
+            (1, "top::a".to_owned()),
+            (2, "top::b".to_owned()),
+            (3, "top::monitor".to_owned()),
+        ]));
+        renderer.add_chunk(vec![
+            log_line(1, log::Level::Error, 0.0),
+            log_line(2, log::Level::Info, 0.0),
+            log_line(2, log::Level::Info, 10.0),
+            EventLine::Value {
+                id: 3,
+                value: 2.0,
+                time: 10.0,
+            },
+            EventLine::Value {
+                id: 3,
+                value: 4.0,
+                time: 10.0,
+            },
+            log_line(2, log::Level::Trace, 20.0),
+            log_line(1, log::Level::Trace, 30.0),
+        ]);
+        renderer
+    }
+
+    #[test]
+    fn lines_shown_are_counted() {
+        let mut renderer = build_renderer();
+
+        // The last line is not shown
+        renderer.set_render_indices((0..6).collect());
+        let stats = Stats::new(&renderer);
+        assert_eq!(stats.num_events, 6);
+        assert_eq!(stats.end_ns, 20.0);
+        assert_eq!(stats.kinds.get("log"), Some(&4));
+        assert_eq!(stats.kinds.get("value"), Some(&2));
+        assert_eq!(stats.levels, [1, 0, 2, 0, 1]);
+        assert_eq!(stats.rate.iter().sum::<u64>(), 6);
+        assert_eq!(stats.rate[0], 2);
+        assert_eq!(stats.rate[NUM_WINDOWS - 1], 1);
+
+        assert_eq!(
+            stats.top_talkers[0],
+            EntityCounts {
+                name: "top::b".to_owned(),
+                total: 3,
+                levels: [0, 0, 2, 0, 1],
+            }
+        );
+        assert_eq!(stats.top_talkers[1].name, "top::monitor");
+        assert_eq!(stats.top_talkers[2].total, 1);
+    }
+
+    #[test]
+    fn monitor_values_are_averaged() {
+        let mut renderer = build_renderer();
+        renderer.set_render_indices((0..7).collect());
+        let stats = Stats::new(&renderer);
+        assert_eq!(stats.monitors.len(), 1);
+        assert_eq!(stats.monitors[0].name, "top::monitor");
+        assert_eq!(stats.monitors[0].points.len(), 1);
+        assert_eq!(stats.monitors[0].points[0].1, 3.0);
+        assert_eq!(stats.monitors[0].max_value, 3.0);
+    }
+
+    #[test]
+    fn resampling_preserves_total() {
+        assert_eq!(resample(&[1, 2, 3], 5), [1, 2, 3]);
+        assert_eq!(resample(&[1, 2, 3, 4, 5, 6], 3), [3, 7, 11]);
+        assert_eq!(resample(&[1, 2, 3, 4, 5], 2), [3, 12]);
+    }
+}
@@ -6,11 +6,20 @@ use std::vec;
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{BarChart, Block, BorderType, Borders, Paragraph};
+use ratatui::widgets::{
+    Axis, BarChart, Block, BorderType, Borders, Chart, Dataset, GraphType, Paragraph, Row,
+    Sparkline, Table,
+};
 
 use crate::app::{App, InputState};
+use crate::find::FindPattern;
 use crate::handler::TOGGLE_RE;
+use crate::stats::{Stats, resample};
+
+/// The height of the chart of each monitor in the statistics view.
+const MONITOR_CHART_HEIGHT: u16 = 8;
 
 /// Renders the user interface widgets.
 pub fn render(app: &mut App, frame: &mut Frame) {
@@ -27,6 +36,11 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         return;
     }
 
+    if app.state() == InputState::Stats {
+        render_stats(app, frame, log_area);
+        return;
+    }
+
     if app.state() == InputState::Search {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -38,6 +52,42 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         render_search(app, frame, search_area);
     }
 
+    if app.state() == InputState::Find {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Max(3)].as_ref())
+            .split(log_area);
+        log_area = chunks[0];
+        let find_area = chunks[1];
+
+        render_find(app, frame, find_area);
+    }
+
+    if matches!(
+        app.state(),
+        InputState::Bookmark | InputState::Export | InputState::TimeRange
+    ) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Max(3)].as_ref())
+            .split(log_area);
+        log_area = chunks[0];
+        let prompt_area = chunks[1];
+
+        render_prompt(app, frame, prompt_area);
+    }
+
+    if app.state() == InputState::EntityTree {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+            .split(log_area);
+        let tree_area = chunks[0];
+        log_area = chunks[1];
+
+        render_entity_tree(app, frame, tree_area);
+    }
+
     if app.filter.lock().unwrap().id_defined() {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
@@ -48,9 +98,210 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         render_chart(app, frame, chart_area);
     }
 
+    if app.compare.is_some() {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(log_area);
+        log_area = chunks[0];
+        let compare_area = chunks[1];
+        render_compare(app, frame, compare_area);
+    }
+
     render_log(app, frame, log_area);
 }
 
+fn stats_block(title: String) -> Block<'static> {
+    Block::default()
+        .title(title)
+        .title_alignment(Alignment::Left)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+}
+
+/// Render the summary of the lines shown.
+fn render_stats(app: &mut App, frame: &mut Frame, area: Rect) {
+    let Some(stats) = app.stats.as_ref() else {
+        return;
+    };
+    let style = Style::default().fg(Color::Cyan).bg(Color::Black);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(14),
+                Constraint::Length(7),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+    let top_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
+        .split(chunks[0]);
+
+    let mut text = vec![
+        Line::from(format!(
+            "{} events from {:.1}ns to {:.1}ns",
+            stats.num_events, stats.start_ns, stats.end_ns
+        )),
+        Line::from(""),
+    ];
+    for (kind, count) in &stats.kinds {
+        text.push(Line::from(format!("{kind}: {count}")));
+    }
+    text.push(Line::from(""));
+    let levels: Vec<String> = log::Level::iter()
+        .zip(stats.levels)
+        .map(|(level, count)| format!("{level}: {count}"))
+        .collect();
+    text.push(Line::from(levels.join(", ")));
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(stats_block("Statistics of the lines shown".to_owned()))
+            .style(style),
+        top_chunks[0],
+    );
+
+    let header = Row::new(["Entity", "Total", "Error", "Warn", "Info", "Debug", "Trace"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let rows = stats.top_talkers.iter().map(|counts| {
+        let mut cells = vec![counts.name.clone(), counts.total.to_string()];
+        cells.extend(counts.levels.iter().map(ToString::to_string));
+        Row::new(cells)
+    });
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Length(6),
+    ];
+    frame.render_widget(
+        Table::new(rows, widths)
+            .header(header)
+            .block(stats_block("Top talkers".to_owned()))
+            .style(style),
+        top_chunks[1],
+    );
+
+    let rate = resample(&stats.rate, chunks[1].width.saturating_sub(2) as usize);
+    let max_rate = rate.iter().max().copied().unwrap_or_default();
+    frame.render_widget(
+        Sparkline::default()
+            .block(stats_block(format!(
+                "Events over time (at most {max_rate} per column)"
+            )))
+            .data(&rate)
+            .style(style),
+        chunks[1],
+    );
+
+    render_monitors(stats, frame, chunks[2]);
+}
+
+/// Render the values of as many monitors as there is room for.
+fn render_monitors(stats: &Stats, frame: &mut Frame, area: Rect) {
+    let style = Style::default().fg(Color::Cyan).bg(Color::Black);
+    if stats.monitors.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No monitor values in the lines shown")
+                .block(stats_block("Monitors".to_owned()))
+                .style(style),
+            area,
+        );
+        return;
+    }
+
+    let num_charts = usize::from(area.height / MONITOR_CHART_HEIGHT).clamp(1, stats.monitors.len());
+    let chart_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, num_charts as u32); num_charts])
+        .split(area);
+    for (curve, chart_area) in stats.monitors.iter().zip(chart_areas.iter()) {
+        let dataset = Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Yellow))
+            .data(&curve.points);
+        let chart = Chart::new(vec![dataset])
+            .block(stats_block(format!(
+                "{} (max {:.3})",
+                curve.name, curve.max_value
+            )))
+            .x_axis(
+                Axis::default()
+                    .bounds([stats.start_ns, stats.end_ns])
+                    .labels([
+                        format!("{:.1}ns", stats.start_ns),
+                        format!("{:.1}ns", stats.end_ns),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, curve.max_value])
+                    .labels(["0".to_owned(), format!("{:.3}", curve.max_value)]),
+            )
+            .style(style);
+        frame.render_widget(chart, *chart_area);
+    }
+}
+
+/// The style of the lines at which compared traces diverge.
+fn divergent_style() -> Style {
+    Style::default().bg(Color::Red).fg(Color::White)
+}
+
+/// Render the lines of the compared trace that are paired with those shown.
+fn render_compare(app: &mut App, frame: &mut Frame, area: Rect) {
+    let Some(compare) = app.compare.as_ref() else {
+        return;
+    };
+    let renderer = app.renderer.lock().unwrap();
+    let mut other = compare.renderer.lock().unwrap();
+    other.print_names = renderer.print_names;
+    other.print_objects = renderer.print_objects;
+    other.print_details = renderer.print_details;
+    other.print_times = renderer.print_times;
+
+    let mut text = Vec::new();
+    for index in renderer.into_iter().take(area.height as usize) {
+        let line = match compare.partner(index) {
+            Some(partner) => Line::from(other.render_line(partner)),
+            None => Line::from("~"),
+        };
+        if compare.is_divergent(index) {
+            text.push(line.style(divergent_style()));
+        } else {
+            text.push(line);
+        }
+    }
+    let title = format!(
+        "{} [aligned by {}, {} divergent]",
+        compare.path.display(),
+        compare.alignment,
+        compare.num_divergent()
+    );
+
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(title)
+                    .title_alignment(Alignment::Left)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .style(Style::default().fg(Color::Cyan).bg(Color::Black))
+            .alignment(Alignment::Left),
+        area,
+    );
+}
+
 fn get_search_and_cursor(app: &App) -> (String, usize) {
     let guard = app.filter.lock().unwrap();
     let mut search = guard.search.to_owned();
@@ -101,23 +352,193 @@ fn render_search(app: &mut App, frame: &mut Frame, area: Rect) {
     );
 }
 
+fn render_find(app: &mut App, frame: &mut Frame, area: Rect) {
+    let title = if app.find.found {
+        "Find"
+    } else {
+        "Find (not found)"
+    };
+
+    let text = vec![Line::from(vec![
+        Span::from(app.find.text.as_str()),
+        Span::styled(" ", Style::default().bg(Color::Red).fg(Color::Black)),
+    ])];
+
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(title)
+                    .title_alignment(Alignment::Left)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .style(Style::default().fg(Color::Cyan).bg(Color::Black))
+            .alignment(Alignment::Left),
+        area,
+    );
+}
+
+fn render_entity_tree(app: &mut App, frame: &mut Frame, area: Rect) {
+    let rows = app.entity_tree.rows();
+    let selected = app.entity_tree.selected;
+
+    // Keep the selected row in view, less the borders
+    let height = (area.height as usize).saturating_sub(2).max(1);
+    let first_row = (selected + 1).saturating_sub(height);
+
+    let mut text = Vec::new();
+    for (index, row) in rows.iter().enumerate().skip(first_row).take(height) {
+        let marker = match (row.has_children, row.expanded) {
+            (false, _) => " ",
+            (true, false) => "+",
+            (true, true) => "-",
+        };
+        let visible = if row.hidden { " " } else { "x" };
+        let mut line = format!(
+            "{}{marker} [{visible}] {}",
+            "  ".repeat(row.depth),
+            row.name
+        );
+        if let Some(level) = row.level {
+            line.push_str(&format!(" <= {level}"));
+        }
+
+        let mut style = Style::default();
+        if row.hidden {
+            style = style.fg(Color::DarkGray);
+        }
+        if index == selected {
+            style = style.bg(Color::Blue).fg(Color::White);
+        }
+        text.push(Line::from(Span::styled(line, style)));
+    }
+
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("Entities")
+                    .title_alignment(Alignment::Left)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .style(Style::default().fg(Color::Cyan).bg(Color::Black))
+            .alignment(Alignment::Left),
+        area,
+    );
+}
+
+fn render_prompt(app: &mut App, frame: &mut Frame, area: Rect) {
+    let title = match app.state() {
+        InputState::Bookmark => "Bookmark name",
+        InputState::Export => "Export the lines shown to a file (.csv or .json)",
+        _ => "Time range in ns, as <start>..<end> (empty to clear)",
+    };
+    let title = if app.prompt_invalid {
+        format!("{title} (invalid)")
+    } else {
+        title.to_owned()
+    };
+
+    let text = vec![Line::from(vec![
+        Span::from(app.prompt.as_str()),
+        Span::styled(" ", Style::default().bg(Color::Red).fg(Color::Black)),
+    ])];
+
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(title)
+                    .title_alignment(Alignment::Left)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .style(Style::default().fg(Color::Cyan).bg(Color::Black))
+            .alignment(Alignment::Left),
+        area,
+    );
+}
+
+/// Split a line into spans with the parts matching the find text highlighted,
+/// after the name of its bookmark if it has one.
+fn highlight_matches(
+    line: String,
+    bookmark: Option<&str>,
+    pattern: Option<&FindPattern>,
+) -> Line<'static> {
+    let mut spans = Vec::new();
+    if let Some(bookmark) = bookmark {
+        spans.push(Span::styled(
+            format!("[{bookmark}]"),
+            Style::default().bg(Color::Magenta).fg(Color::White),
+        ));
+        spans.push(Span::from(" "));
+    }
+
+    let Some(pattern) = pattern else {
+        spans.push(Span::from(line));
+        return Line::from(spans);
+    };
+
+    let mut end = 0;
+    for range in pattern.match_ranges(&line) {
+        if range.start > end {
+            spans.push(Span::from(line[end..range.start].to_owned()));
+        }
+        spans.push(Span::styled(
+            line[range.clone()].to_owned(),
+            Style::default().bg(Color::Yellow).fg(Color::Black),
+        ));
+        end = range.end;
+    }
+    if end < line.len() {
+        spans.push(Span::from(line[end..].to_owned()));
+    }
+    Line::from(spans)
+}
+
 fn render_log(app: &mut App, frame: &mut Frame, area: Rect) {
     // Update the renderer with the current frame size.
     app.set_frame_size(area.height as usize);
 
+    let pattern = app.find_pattern();
+    let time_range = app.filter.lock().unwrap().time_range;
     let renderer = app.renderer.lock().unwrap();
-    let mut text = String::new();
+    let mut text = Vec::new();
     for index in renderer.into_iter().take(area.height as usize) {
-        text.push_str(renderer.render_line(index).as_str());
-        text.push('\n');
+        let line = highlight_matches(
+            renderer.render_line(index),
+            app.bookmark_at(index),
+            pattern.as_ref(),
+        );
+        if app
+            .compare
+            .as_ref()
+            .is_some_and(|compare| compare.is_divergent(index))
+        {
+            text.push(line.style(divergent_style()));
+        } else {
+            text.push(line);
+        }
     }
-    let pos = format!(
+    let mut pos = format!(
         "{}/{}/{}",
         renderer.current_render_line_number(),
         renderer.num_render_lines,
         renderer.num_lines
     )
     .to_owned();
+    if let Some(time_range) = time_range {
+        pos.push_str(&format!(" [{time_range}]"));
+    }
+    if let Some(live) = &app.live {
+        pos.push_str(&format!(" [{}]", live.status()));
+    }
+    if let Some(status) = &app.status {
+        pos.push_str(&format!(" - {status}"));
+    }
 
     let title = match app.state() {
         InputState::Goto => "g".to_owned(),
@@ -193,14 +614,29 @@ impl<'a> HelpRender<'a> {
         ]));
     }
 
+    fn add_text_line(&mut self, text: String) {
+        self.lines.push(Line::from(vec![
+            Span::from(self.indent),
+            Span::styled(text, self.style_text),
+        ]));
+    }
+
     fn add_blank_line(&mut self) {
         self.lines.push(Line::from(vec![Span::from("")]));
     }
 }
 
-fn render_help(_app: &mut App, frame: &mut Frame, area: Rect) {
+fn render_help(app: &mut App, frame: &mut Frame, area: Rect) {
     let mut renderer = HelpRender::new();
 
+    let metadata = app.renderer.lock().unwrap().metadata().to_vec();
+    if !metadata.is_empty() {
+        renderer.add_header("Run:", vec![]);
+        for (key, value) in metadata {
+            renderer.add_text_line(format!("{key}: {value}"));
+        }
+    }
+
     let re = format!("{TOGGLE_RE:?}").replace('(', "").replace(')', "");
     renderer.add_header(
         "Search:",
@@ -217,6 +653,70 @@ fn render_help(_app: &mut App, frame: &mut Frame, area: Rect) {
     renderer.add_command_help_line("ctrl+e", "move to end of search text");
     renderer.add_command_help_line(re.as_str(), "toggle regular-expression mode");
 
+    renderer.add_header(
+        "Find:",
+        vec!["Move to the lines containing a text string, as it is typed."],
+    );
+    renderer.add_command_help_line("s", "enable find window");
+    renderer.add_command_help_line("]", "move to the next matching line");
+    renderer.add_command_help_line("[", "move to the previous matching line");
+    renderer.add_command_help_line(
+        "up/down-arrow",
+        "in the find window, move to the previous/next matching line",
+    );
+    renderer.add_command_help_line("Esc", "clear the find text and return to where it started");
+
+    renderer.add_header("Time range and bookmarks:", vec![]);
+    renderer.add_command_help_line("T", "only show the lines in a window of time");
+    renderer.add_command_help_line("m", "add a named bookmark at the current line");
+    renderer.add_command_help_line("b/B", "move to the next/previous bookmark");
+
+    renderer.add_header(
+        "Entities:",
+        vec!["Show the hierarchy of entities to hide them or limit their log messages."],
+    );
+    renderer.add_command_help_line("e", "show/hide the entity tree");
+    renderer.add_command_help_line("up/down-arrow", "select an entity");
+    renderer.add_command_help_line("right/left-arrow", "expand/collapse an entity");
+    renderer.add_command_help_line("space", "hide/show an entity and those below it");
+    renderer.add_command_help_line(
+        "[1-5]",
+        "only show log messages from error (1) to trace (5) for an entity and those below it",
+    );
+    renderer.add_command_help_line("0", "show all log messages for an entity");
+
+    renderer.add_header(
+        "Export:",
+        vec!["Write the lines shown, after all filtering, to a CSV or JSON file."],
+    );
+    renderer.add_command_help_line("x", "enter the file to write, ending .json for JSON");
+
+    renderer.add_header(
+        "Statistics:",
+        vec!["Summarise the lines shown, after all filtering."],
+    );
+    renderer.add_command_help_line(
+        "S",
+        "show event counts, top talkers, the event rate and monitor values",
+    );
+
+    renderer.add_header(
+        "Live:",
+        vec!["When following a trace that is still being written, the view stays at the end."],
+    );
+    renderer.add_command_help_line("p", "pause/resume showing new events");
+
+    renderer.add_header(
+        "Compare:",
+        vec!["When comparing against a second trace, lines where they diverge are highlighted."],
+    );
+    renderer.add_command_help_line(
+        "c",
+        "switch between aligning the traces by time and by sequence",
+    );
+    renderer.add_command_help_line("}", "move to the next line where the traces diverge");
+    renderer.add_command_help_line("{", "move to the previous line where the traces diverge");
+
     renderer.add_header("Navigation:", vec![]);
     renderer.add_command_help_line("up/down-arrow", "move up/down a single line");
     renderer.add_command_help_line("PgUp/PgDn", "move up/down a block of lines");
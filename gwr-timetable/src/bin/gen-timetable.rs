@@ -21,7 +21,7 @@
 
 use std::collections::HashSet;
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 use byte_unit::{Byte, UnitType};
@@ -38,7 +38,11 @@ use gwr_models::processing_element::operators::{
     ExpansionDirection, HasShape, Operator, Shape, Tensor, TensorPartition, partition_tensors,
 };
 use gwr_models::processing_element::task::ComputeOp;
+use gwr_platform::format::PlatformFormat;
+use gwr_platform::overrides::Override;
+use gwr_platform::template::env_variables;
 use gwr_platform::types::PlatformConfig;
+use gwr_platform::validate::parse_platform_config_as;
 use gwr_timetable::timetable_file::{
     EdgeKind, EdgeSection, NodeSection, TensorConfigSection, TensorViewSection, TimetableFile,
     dtype_num_bytes,
@@ -140,7 +144,15 @@ struct Cli {
     #[arg(long)]
     platform: PathBuf,
 
-    /// Timetable file to write
+    /// Override a value in the platform file, e.g. `--set pe0.config.lsu_access_bytes=64` or `--set variables.num_pes=8`
+    #[arg(long = "set", value_name = "PATH=VALUE")]
+    overrides: Vec<Override>,
+
+    /// Format of the platform file (detected from its extension by default)
+    #[arg(long, value_enum)]
+    platform_format: Option<PlatformFormat>,
+
+    /// Timetable file to write, as JSON if its extension is `.json` and YAML otherwise
     #[arg(long)]
     out: PathBuf,
 
@@ -862,6 +874,11 @@ impl Generator {
                 pe: Some(pe),
                 input_views,
                 output_views,
+                priority: None,
+                deadline_ns: None,
+                relative_deadline_ns: None,
+                iterations: None,
+                buffer: None,
             });
         }
 
@@ -962,6 +979,8 @@ impl Generator {
             from: from.to_string(),
             to: to.to_string(),
             kind,
+            bytes: None,
+            buffer: None,
         });
     }
 
@@ -1096,27 +1115,42 @@ fn generate(mut generator: Generator) -> Result<TimetableFile> {
     Ok(TimetableFile {
         nodes: generator.nodes,
         edges: generator.edges,
+        subgraphs: Vec::new(),
+        streams: Vec::new(),
     })
 }
 
 fn main() -> Result<()> {
-    let args = Cli::parse();
+    let mut args = Cli::parse();
     init_logging(args.debug);
+    // Variables set in the environment are applied before the overrides so
+    // that the command line takes precedence
+    args.overrides.splice(0..0, env_variables()?);
 
-    let platform_yaml = fs::read_to_string(&args.platform)
+    let platform_source = fs::read_to_string(&args.platform)
         .map_err(|e| error_from_str(format!("failed to read {}: {e}", args.platform.display())))?;
-    let platform: PlatformConfig = serde_yaml::from_str(&platform_yaml).map_err(|e| {
-        error_from_str(format!(
-            "failed to parse platform YAML using gwr_platform::types::PlatformConfig: {e}"
-        ))
-    })?;
+    let platform_format = args
+        .platform_format
+        .unwrap_or_else(|| PlatformFormat::from_path(&args.platform));
+    let platform: PlatformConfig = parse_platform_config_as(
+        &platform_source,
+        platform_format,
+        args.platform.parent().unwrap_or(Path::new(".")),
+        &args.overrides,
+    )
+    .map_err(|e| error_from_str(format!("failed to parse {}: {e}", args.platform.display())))?;
 
     let out_path = args.out.clone();
     let generator = Generator::new(args, &platform)?;
     let timetable = generate(generator)?;
-    let yaml = serde_yaml::to_string(&timetable)
-        .map_err(|e| error_from_str(format!("failed to serialise timetable YAML: {e}")))?;
-    fs::write(&out_path, yaml)
+    let contents = if out_path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::to_string_pretty(&timetable)
+            .map_err(|e| error_from_str(format!("failed to serialise timetable JSON: {e}")))?
+    } else {
+        serde_yaml::to_string(&timetable)
+            .map_err(|e| error_from_str(format!("failed to serialise timetable YAML: {e}")))?
+    };
+    fs::write(&out_path, contents)
         .map_err(|e| error_from_str(format!("failed to write {}: {e}", out_path.display())))?;
     info!("Wrote graph to {}", out_path.display());
     Ok(())
@@ -1129,6 +1163,8 @@ mod tests {
     fn valid_args() -> Cli {
         Cli {
             platform: PathBuf::from("platform.yaml"),
+            overrides: Vec::new(),
+            platform_format: None,
             out: PathBuf::from("timetable.yaml"),
             debug: false,
             depth: 1,
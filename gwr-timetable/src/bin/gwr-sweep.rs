@@ -0,0 +1,171 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Run a timetable on a platform for every combination of a grid of platform
+//! parameters and collate the statistics of each run into a single CSV file.
+//!
+//! The parameters are given in a YAML grid file and/or with `--param`, for
+//! example:
+//!   cargo run --bin gwr-sweep --
+//!     --platform gwr-platform/examples/platform.yaml
+//!     --timetable gwr-timetable/examples/small.yaml
+//!     --param hbm0.delay_ticks=10,20,40
+//!     --jobs 4 --output sweep.csv
+//!
+//! With `--jobs` greater than 1 the points of the sweep are run in separate
+//! processes, each of which runs this binary with `--point`.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+
+use clap::{Parser, ValueEnum};
+use gwr_platform::format::PlatformFormat;
+use gwr_platform::template::env_variables;
+use gwr_timetable::sweep::{Parameter, ParameterGrid, csv_header, csv_row, run_point};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Command-line arguments.
+#[derive(Parser)]
+#[command(about = "Run a timetable over a grid of platform parameters and collate the results")]
+struct Cli {
+    /// Timetable YAML file
+    #[arg(long, default_value = "timetable.yaml")]
+    timetable: PathBuf,
+
+    /// Platform YAML file
+    #[arg(long, default_value = "platform.yaml")]
+    platform: PathBuf,
+
+    /// Format of the platform file (detected from its extension by default)
+    #[arg(long, value_enum)]
+    platform_format: Option<PlatformFormat>,
+
+    /// YAML file with the values of each parameter to sweep
+    #[arg(long)]
+    grid: Option<PathBuf>,
+
+    /// Values of a parameter to sweep, e.g. `--param hbm0.delay_ticks=10,20,40`
+    #[arg(long = "param", value_name = "PATH=VALUE,...")]
+    params: Vec<String>,
+
+    /// Number of points of the sweep to run in parallel processes
+    #[arg(long, default_value = "1")]
+    jobs: usize,
+
+    /// Write the CSV to this file rather than stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Run a single point of the sweep and print its CSV row
+    #[arg(long, hide = true)]
+    point: Option<usize>,
+}
+
+impl Cli {
+    fn platform_format(&self) -> PlatformFormat {
+        self.platform_format
+            .unwrap_or_else(|| PlatformFormat::from_path(&self.platform))
+    }
+
+    fn grid(&self) -> Result<ParameterGrid> {
+        let mut grid = match &self.grid {
+            Some(path) => ParameterGrid::from_file(path)?,
+            None => ParameterGrid::new(),
+        };
+        for param in &self.params {
+            grid = grid.with_parameter(param.parse::<Parameter>()?);
+        }
+        Ok(grid)
+    }
+
+    fn run_point(&self, grid: &ParameterGrid, index: usize) -> Result<String> {
+        // Variables set in the environment are applied before the parameters
+        // of the point so that the parameters take precedence. Only the
+        // parameters have columns in the CSV.
+        let point = grid.point(index)?;
+        let mut overrides = env_variables()?;
+        overrides.extend(point.iter().cloned());
+        let result = run_point(
+            &self.platform,
+            self.platform_format(),
+            &self.timetable,
+            &overrides,
+        )?;
+        Ok(csv_row(&self.platform, &self.timetable, &point, &result))
+    }
+
+    /// Start a process that runs one point of the sweep
+    fn spawn_point(&self, index: usize) -> Result<Child> {
+        let mut command = Command::new(std::env::current_exe()?);
+        command
+            .arg("--timetable")
+            .arg(&self.timetable)
+            .arg("--platform")
+            .arg(&self.platform)
+            .arg("--platform-format")
+            .arg(
+                self.platform_format()
+                    .to_possible_value()
+                    .ok_or("unnamed platform format")?
+                    .get_name(),
+            );
+        if let Some(grid) = &self.grid {
+            command.arg("--grid").arg(grid);
+        }
+        for param in &self.params {
+            command.arg("--param").arg(param);
+        }
+        command.arg("--point").arg(index.to_string());
+        Ok(command.stdout(Stdio::piped()).spawn()?)
+    }
+}
+
+fn wait_for_point(index: usize, child: Child) -> Result<String> {
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!("Sweep point {index} failed").into());
+    }
+    Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+}
+
+fn main() -> Result<()> {
+    let args = Cli::parse();
+    let grid = args.grid()?;
+
+    if let Some(index) = args.point {
+        println!("{}", args.run_point(&grid, index)?);
+        return Ok(());
+    }
+
+    let num_points = grid.num_points();
+    let mut rows = vec![csv_header(&grid)];
+    if args.jobs <= 1 {
+        for index in 0..num_points {
+            eprintln!("Running sweep point {}/{num_points}", index + 1);
+            rows.push(args.run_point(&grid, index)?);
+        }
+    } else {
+        let mut running = VecDeque::new();
+        for index in 0..num_points {
+            if running.len() == args.jobs
+                && let Some((index, child)) = running.pop_front()
+            {
+                rows.push(wait_for_point(index, child)?);
+            }
+            eprintln!("Running sweep point {}/{num_points}", index + 1);
+            running.push_back((index, args.spawn_point(index)?));
+        }
+        for (index, child) in running {
+            rows.push(wait_for_point(index, child)?);
+        }
+    }
+
+    let csv = rows.join("\n") + "\n";
+    match &args.output {
+        Some(path) => fs::write(path, csv)?,
+        None => print!("{csv}"),
+    }
+    Ok(())
+}
@@ -26,8 +26,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let input = args.timetable;
     let output = args.mermaid;
 
-    let yaml = fs::read_to_string(&input)?;
-    let timetable: TimetableFile = serde_yaml::from_str(&yaml)?;
+    let timetable = TimetableFile::from_file(&input)?;
     let mermaid = render_mermaid_from_parts(&timetable.nodes, &timetable.edges, &HashMap::new());
 
     fs::write(output, mermaid)?;
@@ -0,0 +1,290 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Buffers that tasks hold while they run, and the occupancy of the SRAM of
+//! each PE and of each memory that holds them.
+//!
+//! A Compute, Memory, DMA, Send or Recv node can give a `buffer` of a number
+//! of `bytes` that it holds from when it starts until it completes, such as
+//! the working space of a kernel. An edge can also give a `buffer`, which is
+//! held by the node at its end that runs on a PE: the node that it leads to,
+//! or the node that it comes from when it leads to a tensor. A buffer is in
+//! the SRAM of the PE of the node (its `sram_bytes`), unless it names a
+//! `memory` of the platform.
+//!
+//! A timetable is rejected if one of its tasks needs more space than a PE or
+//! memory has. While it runs, the [CapacityPolicy] decides what happens when
+//! there is not enough space left to start a task:
+//!
+//!  - `error`: the run fails, which is the default
+//!  - `stall`: the task waits until other tasks free enough space
+//!
+//! The peak occupancy of each PE and memory is given by a [BufferReport].
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+use clap::ValueEnum;
+use gwr_engine::sim_error;
+use gwr_engine::types::{SimError, SimResult};
+use gwr_platform::Platform;
+use gwr_platform::builder::DEFAULT_PE_SRAM_BYTES;
+
+use crate::timetable_file::{BufferSection, EdgeSection};
+use crate::types::Node;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum CapacityPolicy {
+    #[default]
+    Error,
+    Stall,
+}
+
+/// The peak occupancy of the SRAM of a PE or of a memory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BufferOccupancy {
+    /// The PE or memory
+    pub name: String,
+    /// Whether the buffers are in the SRAM of a PE rather than in a memory
+    pub sram: bool,
+    pub capacity_bytes: u64,
+    pub peak_bytes: u64,
+}
+
+impl BufferOccupancy {
+    fn description(&self) -> String {
+        if self.sram {
+            format!("the SRAM of PE '{}'", self.name)
+        } else {
+            format!("memory '{}'", self.name)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BufferReport {
+    pub occupancies: Vec<BufferOccupancy>,
+}
+
+impl BufferReport {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.occupancies.is_empty()
+    }
+}
+
+impl fmt::Display for BufferReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Peak buffer occupancy:")?;
+        for occupancy in &self.occupancies {
+            writeln!(
+                f,
+                "  {}: {} of {} bytes ({:.1}%)",
+                occupancy.description(),
+                occupancy.peak_bytes,
+                occupancy.capacity_bytes,
+                100.0 * occupancy.peak_bytes as f64 / occupancy.capacity_bytes.max(1) as f64
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The space in use in each PE and memory that holds buffers
+pub(crate) struct Buffers {
+    /// Each PE and memory, with its peak occupancy
+    stores: RefCell<Vec<BufferOccupancy>>,
+    in_use_bytes: RefCell<Vec<u64>>,
+    /// The bytes that each task holds in each store while it runs
+    task_buffers: Vec<Vec<(usize, u64)>>,
+}
+
+/// Returns the SRAM capacity of a PE, which DMA engines do not have
+fn sram_bytes(platform: &Platform, pe: &str) -> Option<u64> {
+    let config = platform.config();
+    config
+        .processing_elements
+        .iter()
+        .flatten()
+        .find(|section| section.name == pe)
+        .map(|section| &section.config)
+        .or_else(|| {
+            config
+                .accelerators
+                .iter()
+                .flatten()
+                .find(|section| section.name == pe)
+                .map(|section| &section.config)
+        })
+        .map(|config| config.sram_bytes.unwrap_or(DEFAULT_PE_SRAM_BYTES))
+}
+
+impl Buffers {
+    pub(crate) fn new(
+        platform: &Platform,
+        nodes: &[Node],
+        edges: &[EdgeSection],
+        node_idx_by_id: &HashMap<String, usize>,
+        total_tasks: usize,
+    ) -> Result<Self, SimError> {
+        let mut stores: Vec<BufferOccupancy> = Vec::new();
+        let mut task_buffers: Vec<Vec<(usize, u64)>> = vec![Vec::new(); total_tasks];
+        let mut add_buffer = |node_idx: usize, buffer: &BufferSection| -> SimResult {
+            let node_section = &nodes[node_idx].node_section;
+            let (name, sram) = match (&buffer.memory, node_section.pe()) {
+                (Some(memory), _) => (memory, false),
+                (None, Some(pe)) => (pe, true),
+                (None, None) => {
+                    return sim_error!(
+                        "Node '{}' has a buffer in SRAM but no PE",
+                        node_section.id()
+                    );
+                }
+            };
+            let store_idx = match stores
+                .iter()
+                .position(|store| &store.name == name && store.sram == sram)
+            {
+                Some(store_idx) => store_idx,
+                None => {
+                    let capacity_bytes = if sram {
+                        sram_bytes(platform, name)
+                    } else {
+                        platform
+                            .config()
+                            .memories
+                            .iter()
+                            .flatten()
+                            .find(|memory| &memory.name == name)
+                            .map(|memory| memory.capacity_bytes)
+                    };
+                    let Some(capacity_bytes) = capacity_bytes else {
+                        let id = node_section.id();
+                        if sram {
+                            return sim_error!(
+                                "Node '{id}' has a buffer in PE '{name}' which has no SRAM"
+                            );
+                        }
+                        return sim_error!("Node '{id}' has a buffer in unknown memory '{name}'");
+                    };
+                    stores.push(BufferOccupancy {
+                        name: name.clone(),
+                        sram,
+                        capacity_bytes,
+                        peak_bytes: 0,
+                    });
+                    stores.len() - 1
+                }
+            };
+
+            let buffers = &mut task_buffers[node_idx];
+            match buffers.iter_mut().find(|(idx, _)| *idx == store_idx) {
+                Some((_, bytes)) => *bytes += buffer.bytes,
+                None => buffers.push((store_idx, buffer.bytes)),
+            }
+            Ok(())
+        };
+
+        for (node_idx, node) in nodes.iter().enumerate() {
+            if let Some(buffer) = node.node_section.buffer() {
+                add_buffer(node_idx, buffer)?;
+            }
+        }
+        for edge in edges {
+            let Some(buffer) = &edge.buffer else {
+                continue;
+            };
+            let to_idx = node_idx_by_id[edge.to_node_id()];
+            let from_idx = node_idx_by_id[edge.from_node_id()];
+            let node_idx = if nodes[to_idx].node_section.pe().is_some() {
+                to_idx
+            } else if nodes[from_idx].node_section.pe().is_some() {
+                from_idx
+            } else {
+                return sim_error!(
+                    "Edge '{} -> {}' has a buffer but no node on a PE to hold it",
+                    edge.from,
+                    edge.to
+                );
+            };
+            add_buffer(node_idx, buffer)?;
+        }
+
+        for (node, buffers) in nodes.iter().zip(&task_buffers) {
+            for (store_idx, bytes) in buffers {
+                let store = &stores[*store_idx];
+                if *bytes > store.capacity_bytes {
+                    return sim_error!(
+                        "Node '{}' needs {bytes} bytes of buffers in {} which only has {} bytes",
+                        node.node_section.id(),
+                        store.description(),
+                        store.capacity_bytes
+                    );
+                }
+            }
+        }
+
+        Ok(Self {
+            in_use_bytes: RefCell::new(vec![0; stores.len()]),
+            stores: RefCell::new(stores),
+            task_buffers,
+        })
+    }
+
+    /// Keep the tasks, in order, that there is space for when each is
+    /// started after the ones before it
+    pub(crate) fn retain_fitting(&self, task_indices: &mut Vec<usize>) {
+        let stores = self.stores.borrow();
+        let mut in_use_bytes = self.in_use_bytes.borrow().clone();
+        task_indices.retain(|task_idx| {
+            let buffers = &self.task_buffers[*task_idx];
+            let fits = buffers.iter().all(|(store_idx, bytes)| {
+                in_use_bytes[*store_idx] + bytes <= stores[*store_idx].capacity_bytes
+            });
+            if fits {
+                for (store_idx, bytes) in buffers {
+                    in_use_bytes[*store_idx] += bytes;
+                }
+            }
+            fits
+        });
+    }
+
+    /// Take the space for the buffers of a task that is starting
+    pub(crate) fn acquire(&self, task_idx: usize, task_id: &str) -> SimResult {
+        let mut stores = self.stores.borrow_mut();
+        let mut in_use_bytes = self.in_use_bytes.borrow_mut();
+        let buffers = &self.task_buffers[task_idx];
+        for (store_idx, bytes) in buffers {
+            let store = &stores[*store_idx];
+            let free_bytes = store.capacity_bytes - in_use_bytes[*store_idx];
+            if *bytes > free_bytes {
+                return sim_error!(
+                    "Task '{task_id}' needs {bytes} bytes of buffers in {} but only {free_bytes} of its {} bytes are free",
+                    store.description(),
+                    store.capacity_bytes
+                );
+            }
+        }
+        for (store_idx, bytes) in buffers {
+            in_use_bytes[*store_idx] += bytes;
+            let store = &mut stores[*store_idx];
+            store.peak_bytes = store.peak_bytes.max(in_use_bytes[*store_idx]);
+        }
+        Ok(())
+    }
+
+    /// Free the space of the buffers of a task that has completed
+    pub(crate) fn release(&self, task_idx: usize) {
+        let mut in_use_bytes = self.in_use_bytes.borrow_mut();
+        for (store_idx, bytes) in &self.task_buffers[task_idx] {
+            in_use_bytes[*store_idx] -= bytes;
+        }
+    }
+
+    pub(crate) fn report(&self) -> BufferReport {
+        BufferReport {
+            occupancies: self.stores.borrow().clone(),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Checkpoints of the progress of a timetable, so that a long run can be
+//! resumed rather than started again from its first node.
+//!
+//! A [TimetableCheckpoint] records the tasks that have completed, the tasks
+//! that were running and the ready tasks queued on each PE, together with the
+//! iterations left of each loop and the outputs selected by Condition nodes.
+//! Tasks are named by their IDs, so a checkpoint is saved as JSON and applied
+//! to a timetable built from the same file with
+//! [Timetable::with_checkpoint](crate::Timetable::with_checkpoint).
+//!
+//! The engine does not save the state of the models of the platform, so a
+//! resumed timetable starts again at time zero and the tasks that were
+//! running when the checkpoint was taken are run again from their start.
+//! Condition nodes with a probability that have yet to be evaluated draw from
+//! a fresh generator.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use gwr_engine::types::SimError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimetableCheckpoint {
+    /// The simulated time at which the checkpoint was taken
+    pub time_ns: f64,
+    /// The tasks and tensors that have completed in the current iteration of
+    /// their loop
+    pub completed: Vec<String>,
+    /// The tasks that were running, which are run again on resuming
+    pub active: Vec<String>,
+    /// The tasks ready to run on each PE, in the order that they became ready
+    pub ready: BTreeMap<String, Vec<String>>,
+    /// The nodes that were skipped because a Condition node did not select
+    /// them
+    pub skipped: Vec<String>,
+    /// The output selected by each Condition node that has been evaluated
+    pub selected_outputs: BTreeMap<String, usize>,
+    /// The number of iterations left of each loop, including the current one
+    pub remaining_iterations: BTreeMap<String, usize>,
+}
+
+impl TimetableCheckpoint {
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a checkpoint can always be serialized")
+    }
+
+    pub fn from_json(source: &str) -> Result<Self, SimError> {
+        serde_json::from_str(source)
+            .map_err(|e| SimError(format!("Failed to parse timetable checkpoint: {e}")))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, SimError> {
+        let source = fs::read_to_string(path)
+            .map_err(|e| SimError(format!("Unable to read {}: {e}", path.display())))?;
+        Self::from_json(&source)
+    }
+}
+
+impl fmt::Display for TimetableCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let num_ready: usize = self.ready.values().map(Vec::len).sum();
+        write!(
+            f,
+            "Checkpoint at {}ns: {} completed, {} active, {} ready",
+            self.time_ns,
+            self.completed.len(),
+            self.active.len(),
+            num_ready
+        )
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Expansion of Collective nodes into the Send and Recv nodes that perform
+//! them.
+//!
+//! A Collective node performs an all-reduce, broadcast or all-gather of a
+//! number of bytes across a list of PEs, which are its ranks in order. It is
+//! replaced by a Send and a Recv node for each
+//! [transfer](gwr_models::collective::CollectiveConfig::transfers) of the
+//! [collective engine](gwr_models::collective) model with the same algorithm,
+//! so the data crosses the fabric of the platform. A Send waits for the Recv
+//! nodes of the data that it forwards, and the Sends and Recvs between each
+//! pair of PEs run in the order of their steps.
+//!
+//! The edges into a Collective node are connected to the Sends that do not
+//! wait for any data, and the edges out of it to the last Send and Recv of
+//! each PE, so the nodes after it wait for every PE to complete.
+//!
+//! The Send and Recv nodes are named after the Collective node, the step and
+//! the PEs, such as `grads_send0_pe0_pe1` and `grads_recv0_pe1_pe0`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimResult;
+use gwr_models::collective::CollectiveConfig;
+
+use crate::timetable_file::{
+    EdgeKind, EdgeSection, NodeSection, RecvConfigSection, SendConfigSection, TimetableFile,
+};
+
+/// The nodes that a Collective node was expanded into
+struct Expansion {
+    /// The nodes that the edges into the collective are connected to
+    inputs: Vec<String>,
+    /// The nodes that the edges out of the collective are connected from
+    outputs: Vec<String>,
+    all: Vec<String>,
+}
+
+fn control_edge(from: &str, to: &str) -> EdgeSection {
+    EdgeSection {
+        from: from.to_string(),
+        to: to.to_string(),
+        kind: EdgeKind::Control,
+        bytes: None,
+        buffer: None,
+    }
+}
+
+/// Add the Send and Recv nodes of a collective and the edges between them
+fn expand_collective(
+    id: &str,
+    pes: &[String],
+    config: &CollectiveConfig,
+    nodes: &mut Vec<NodeSection>,
+    edges: &mut Vec<EdgeSection>,
+) -> Expansion {
+    let send_id =
+        |step: usize, src: usize, dst: usize| format!("{id}_send{step}_{}_{}", pes[src], pes[dst]);
+    let recv_id =
+        |step: usize, src: usize, dst: usize| format!("{id}_recv{step}_{}_{}", pes[dst], pes[src]);
+
+    let transfers: Vec<_> = config
+        .transfers()
+        .into_iter()
+        .filter(|transfer| transfer.num_bytes > 0)
+        .collect();
+    let received: HashSet<(usize, usize, usize)> = transfers
+        .iter()
+        .map(|t| (t.src_rank, t.dst_rank, t.step))
+        .collect();
+
+    let mut expansion = Expansion {
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+        all: Vec::new(),
+    };
+    let mut has_successor = HashSet::new();
+    let mut pair_steps: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for transfer in &transfers {
+        let (src, dst, step) = (transfer.src_rank, transfer.dst_rank, transfer.step);
+        let send = send_id(step, src, dst);
+        let recv = recv_id(step, src, dst);
+        nodes.push(NodeSection::Send {
+            id: send.clone(),
+            pe: Some(pes[src].clone()),
+            config: SendConfigSection {
+                to: pes[dst].clone(),
+                bytes: transfer.num_bytes,
+            },
+            iterations: None,
+            deadline_ns: None,
+            relative_deadline_ns: None,
+            buffer: None,
+        });
+        nodes.push(NodeSection::Recv {
+            id: recv.clone(),
+            pe: Some(pes[dst].clone()),
+            config: RecvConfigSection {
+                from: pes[src].clone(),
+                bytes: transfer.num_bytes,
+            },
+            iterations: None,
+            deadline_ns: None,
+            relative_deadline_ns: None,
+            buffer: None,
+        });
+
+        let mut waits = false;
+        for (after_rank, after_step) in &transfer.after {
+            if received.contains(&(*after_rank, src, *after_step)) {
+                let after = recv_id(*after_step, *after_rank, src);
+                edges.push(control_edge(&after, &send));
+                has_successor.insert(after);
+                waits = true;
+            }
+        }
+        if !waits {
+            expansion.inputs.push(send.clone());
+        }
+        pair_steps.entry((src, dst)).or_default().push(step);
+        expansion.all.push(send);
+        expansion.all.push(recv);
+    }
+
+    // The data between two PEs is received in the order that it is sent
+    for ((src, dst), mut steps) in pair_steps {
+        steps.sort_unstable();
+        for pair in steps.windows(2) {
+            let (before, after) = (pair[0], pair[1]);
+            edges.push(control_edge(
+                &send_id(before, src, dst),
+                &send_id(after, src, dst),
+            ));
+            edges.push(control_edge(
+                &recv_id(before, src, dst),
+                &recv_id(after, src, dst),
+            ));
+            has_successor.insert(send_id(before, src, dst));
+            has_successor.insert(recv_id(before, src, dst));
+        }
+    }
+
+    expansion.outputs = expansion
+        .all
+        .iter()
+        .filter(|id| !has_successor.contains(*id))
+        .cloned()
+        .collect();
+    expansion
+}
+
+/// Replace each Collective node with the Send and Recv nodes that perform it
+pub(crate) fn expand_collectives(timetable_file: &mut TimetableFile) -> SimResult {
+    let tensor_ids: HashSet<String> = timetable_file
+        .nodes
+        .iter()
+        .filter(|node| matches!(node, NodeSection::Tensor { .. }))
+        .map(|node| node.id().clone())
+        .collect();
+
+    let mut nodes = Vec::with_capacity(timetable_file.nodes.len());
+    let mut edges = Vec::new();
+    let mut expansions = HashMap::new();
+    for node in timetable_file.nodes.drain(..) {
+        let NodeSection::Collective {
+            id,
+            op,
+            pes,
+            config,
+        } = node
+        else {
+            nodes.push(node);
+            continue;
+        };
+
+        if pes.len() < 2 {
+            return sim_error!("Collective node '{id}' needs at least 2 PEs");
+        }
+        let mut seen = HashSet::new();
+        if let Some(pe) = pes.iter().find(|pe| !seen.insert(*pe)) {
+            return sim_error!("Collective node '{id}' has PE '{pe}' more than once");
+        }
+        let collective_config =
+            CollectiveConfig::new(op, config.algorithm, (0..pes.len()).collect(), config.bytes);
+        let expansion = expand_collective(&id, &pes, &collective_config, &mut nodes, &mut edges);
+        expansions.insert(id, expansion);
+    }
+    timetable_file.nodes = nodes;
+    if expansions.is_empty() {
+        return Ok(());
+    }
+
+    for edge in timetable_file.edges.drain(..) {
+        let (from_id, to_id) = (edge.from_node_id(), edge.to_node_id());
+        let (collective_id, other_id, expanded_edges): (_, _, Vec<(&str, &str)>) =
+            if let Some(expansion) = expansions.get(from_id) {
+                let edges = expansion
+                    .outputs
+                    .iter()
+                    .map(|from| (from.as_str(), edge.to.as_str()))
+                    .collect();
+                (from_id, to_id, edges)
+            } else if let Some(expansion) = expansions.get(to_id) {
+                let edges = expansion
+                    .inputs
+                    .iter()
+                    .map(|to| (edge.from.as_str(), to.as_str()))
+                    .collect();
+                (to_id, from_id, edges)
+            } else {
+                edges.push(edge);
+                continue;
+            };
+        if !tensor_ids.contains(other_id) {
+            return sim_error!(
+                "Collective node '{collective_id}' can only be connected to Tensor nodes"
+            );
+        }
+        for (from, to) in expanded_edges {
+            edges.push(EdgeSection {
+                from: from.to_string(),
+                to: to.to_string(),
+                kind: edge.kind,
+                bytes: edge.bytes,
+                buffer: edge.buffer.clone(),
+            });
+        }
+    }
+    timetable_file.edges = edges;
+
+    let expand_members = |members: &mut Vec<String>| {
+        *members = members
+            .drain(..)
+            .flat_map(|node_id| match expansions.get(&node_id) {
+                Some(expansion) => expansion.all.clone(),
+                None => vec![node_id],
+            })
+            .collect();
+    };
+    for subgraph in &mut timetable_file.subgraphs {
+        expand_members(&mut subgraph.nodes);
+    }
+    for stream in &mut timetable_file.streams {
+        expand_members(&mut stream.nodes);
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Hooks for external performance models to decide how long compute tasks
+//! take.
+//!
+//! A cost model registered with [Timetable::with_cost_model] is called for
+//! each compute task before a PE runs it. It is given the node, the tensors
+//! that the node reads and writes, and the configuration of the PE that runs
+//! it, and returns the number of ticks that the computation takes. When it
+//! returns `None` the PE decides, using its own
+//! [cost model](gwr_models::processing_element::cost_model) or throughput,
+//! so a model only needs to cover the nodes that it knows about.
+//!
+//! The time taken to read and write the tensors is still simulated by the
+//! PE, so the cost model only replaces the time spent computing.
+//!
+//! [Timetable::with_cost_model]: crate::Timetable::with_cost_model
+
+use gwr_engine::types::SimError;
+use gwr_models::processing_element::operators::TensorView;
+use gwr_platform::types::ProcessingElementConfigSection;
+
+use crate::timetable_file::NodeSection;
+
+/// The task that a cost model is asked about
+pub struct TaskCostContext<'a> {
+    pub node: &'a NodeSection,
+    pub inputs: &'a [Option<TensorView>],
+    pub outputs: &'a [Option<TensorView>],
+    /// The name of the PE that runs the task
+    pub pe: &'a str,
+    pub pe_config: &'a ProcessingElementConfigSection,
+}
+
+/// A function that returns the number of ticks that a compute task takes, or
+/// `None` to leave it to the PE
+pub type CostModelFn = dyn Fn(&TaskCostContext<'_>) -> Result<Option<usize>, SimError>;
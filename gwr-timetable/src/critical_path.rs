@@ -0,0 +1,150 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Critical path analysis of a timetable after it has run.
+//!
+//! Each task is given the time that it took in the simulation, from being
+//! started by its PE to completing. A task could start as soon as the last of
+//! the tasks that it depends on has finished, and must start early enough
+//! that the tasks after it do not delay the end of the timetable. The
+//! difference between the two is the slack of the task: how much longer it
+//! could have taken without changing the end-to-end time. The tasks of the
+//! critical path have no slack, so they are the tasks to speed up.
+//!
+//! A task that runs more than once, in a loop, is timed from its first start
+//! to its last completion.
+
+use std::fmt;
+
+/// The simulated times of a task
+#[derive(Clone, Debug)]
+pub struct TaskTiming {
+    pub id: String,
+    pub start_ns: f64,
+    pub end_ns: f64,
+    /// How much longer the task could have taken without delaying the end of
+    /// the timetable
+    pub slack_ns: f64,
+}
+
+impl TaskTiming {
+    #[must_use]
+    pub fn duration_ns(&self) -> f64 {
+        self.end_ns - self.start_ns
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CriticalPathReport {
+    /// The times of the tasks that ran, in the order that they started
+    pub tasks: Vec<TaskTiming>,
+    /// The IDs of the tasks on the critical path, in order
+    pub critical_path: Vec<String>,
+    /// The time from the start of the timetable to the end of its critical
+    /// path
+    pub length_ns: f64,
+}
+
+impl fmt::Display for CriticalPathReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Critical path ({:.1}ns):", self.length_ns)?;
+        writeln!(f, "  {}", self.critical_path.join(" -> "))?;
+        writeln!(f, "Tasks:")?;
+        for task in &self.tasks {
+            writeln!(
+                f,
+                "  {}: start {:.1}ns, end {:.1}ns, slack {:.1}ns",
+                task.id, task.start_ns, task.end_ns, task.slack_ns
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The graph of a timetable with the simulated times of its tasks
+pub(crate) struct TimedGraph {
+    /// The ID of each task, tensor and condition
+    pub ids: Vec<String>,
+    pub successors: Vec<Vec<usize>>,
+    /// The start and end times of the tasks that ran
+    pub times: Vec<Option<(f64, f64)>>,
+}
+
+impl TimedGraph {
+    pub(crate) fn critical_path_report(self) -> CriticalPathReport {
+        let num_nodes = self.ids.len();
+        let duration = |idx: usize| self.times[idx].map_or(0.0, |(start, end)| end - start);
+        let mut predecessors = vec![Vec::new(); num_nodes];
+        for (idx, successors) in self.successors.iter().enumerate() {
+            for successor_idx in successors {
+                predecessors[*successor_idx].push(idx);
+            }
+        }
+
+        // Visit the nodes in dependency order to find the earliest times
+        let mut num_unresolved: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+        let mut order: Vec<usize> = (0..num_nodes)
+            .filter(|idx| num_unresolved[*idx] == 0)
+            .collect();
+        let mut i = 0;
+        while let Some(idx) = order.get(i).copied() {
+            for successor_idx in &self.successors[idx] {
+                num_unresolved[*successor_idx] -= 1;
+                if num_unresolved[*successor_idx] == 0 {
+                    order.push(*successor_idx);
+                }
+            }
+            i += 1;
+        }
+
+        let mut earliest_end = vec![0.0_f64; num_nodes];
+        for idx in &order {
+            let earliest_start = predecessors[*idx]
+                .iter()
+                .map(|p| earliest_end[*p])
+                .fold(0.0, f64::max);
+            earliest_end[*idx] = earliest_start + duration(*idx);
+        }
+        let length_ns = earliest_end.iter().copied().fold(0.0, f64::max);
+
+        let mut latest_end = vec![length_ns; num_nodes];
+        for idx in order.iter().rev() {
+            latest_end[*idx] = self.successors[*idx]
+                .iter()
+                .map(|s| latest_end[*s] - duration(*s))
+                .fold(length_ns, f64::min);
+        }
+
+        // Follow the predecessors that finish last back from the end
+        let mut critical_path = Vec::new();
+        let mut current =
+            (0..num_nodes).max_by(|a, b| earliest_end[*a].total_cmp(&earliest_end[*b]));
+        while let Some(idx) = current {
+            if self.times[idx].is_some() {
+                critical_path.push(self.ids[idx].clone());
+            }
+            current = predecessors[idx]
+                .iter()
+                .copied()
+                .max_by(|a, b| earliest_end[*a].total_cmp(&earliest_end[*b]));
+        }
+        critical_path.reverse();
+
+        let mut tasks: Vec<TaskTiming> = (0..num_nodes)
+            .filter_map(|idx| {
+                self.times[idx].map(|(start_ns, end_ns)| TaskTiming {
+                    id: self.ids[idx].clone(),
+                    start_ns,
+                    end_ns,
+                    slack_ns: latest_end[idx] - earliest_end[idx],
+                })
+            })
+            .collect();
+        tasks.sort_by(|a, b| a.start_ns.total_cmp(&b.start_ns));
+
+        CriticalPathReport {
+            tasks,
+            critical_path,
+            length_ns,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Deadlines by which tasks should complete, and what happens when they are
+//! missed.
+//!
+//! A Compute, Memory, DMA, Send or Recv node can give a `deadline_ns`, which
+//! is a simulated time, or a `relative_deadline_ns`, which is a time from the
+//! start of its graph: when the first task of its [stream](crate::streams)
+//! started, or the first task of the timetable for a node that is not in a
+//! stream. A task in a loop has the same deadline for every iteration.
+//!
+//! Deadlines are checked as tasks complete when the timetable is created
+//! [with a clock](crate::Timetable::with_clock), and the [DeadlineAction]
+//! decides what happens when one is missed:
+//!
+//!  - `warn`: a warning is logged and the miss is recorded, which is the
+//!    default
+//!  - `record`: the miss is only recorded
+//!  - `fail`: the run fails
+//!
+//! The recorded misses are given by a [DeadlineReport]. Deadlines also order
+//! the tasks of the `earliest-deadline` [scheduling
+//! policy](crate::scheduling).
+
+use std::fmt;
+
+use clap::ValueEnum;
+
+/// The time by which a task should complete, in nanoseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deadline {
+    /// A simulated time
+    Absolute(u64),
+    /// A time from the start of the graph of the task
+    Relative(u64),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DeadlineAction {
+    #[default]
+    Warn,
+    Record,
+    Fail,
+}
+
+/// A run of a task that completed after its deadline
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeadlineMiss {
+    pub task: String,
+    pub deadline_ns: f64,
+    pub end_ns: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeadlineReport {
+    /// The number of runs of tasks with a deadline
+    pub num_checked: usize,
+    pub misses: Vec<DeadlineMiss>,
+}
+
+impl fmt::Display for DeadlineReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Met {} of {} deadlines",
+            self.num_checked - self.misses.len(),
+            self.num_checked
+        )?;
+        for miss in &self.misses {
+            writeln!(
+                f,
+                "  {} completed at {}ns, {}ns after its deadline of {}ns",
+                miss.task,
+                miss.end_ns,
+                miss.end_ns - miss.deadline_ns,
+                miss.deadline_ns
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,697 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Loading of timetable graphs from Graphviz DOT files.
+//!
+//! Each node of a `digraph` is a node of the timetable whose `kind` and other
+//! fields are given as attributes. Attribute values are parsed as YAML, so
+//! lists and views are written in the same way as in a YAML timetable:
+//!
+//! ```dot
+//! digraph tiny {
+//!     node [kind=tensor, dtype=fp16, shape="[3, 224, 224]"];
+//!     tensor_A [addr=0x1_0000_0000];
+//!     tensor_B [addr=0x1_1000_0000];
+//!     tensor_C [addr=0x1_2000_0000, dtype=fp32];
+//!
+//!     add [kind=compute, op=add, pe=pe_0_0, shape=box];
+//!
+//!     tensor_A -> add:0;
+//!     tensor_B -> add:1;
+//!     add -> tensor_C;
+//! }
+//! ```
+//!
+//! The fields of a tensor's `config` (`addr`, `dtype` and `shape`), of a
+//! memory node's `config` (`view`), of a DMA node's `config` (`input_view`
+//! and `output_view`), of a send or receive node's `config` (`to` or `from`
+//! and `bytes`), of a condition node's `config` (`predicate` and `seed`) and
+//! of a collective node's `config` (`bytes` and `algorithm`) are given
+//! directly as attributes of the node, or as a YAML mapping in a `config`
+//! attribute. Attributes that are not fields of the node, such as
+//! `label` and `color`, are ignored so that graphs exported from other tools
+//! can be loaded. As `shape` is also the Graphviz shape of a node, it is only
+//! used as the shape of a tensor when it is a list.
+//!
+//! When a compute node does not give its `input_views` or `output_views`, it
+//! has an unrestricted view for each of its data edges, or for each port up
+//! to the highest port of its edges. The port of an edge end is given with
+//! `node:port` and edges are `data` edges unless they have `kind=control`.
+//! Edges from a condition node do not count as inputs of a compute node, and
+//! their port is the output of the condition that selects the node.
+//! The number of bytes carried by an edge is given with `bytes`, and the
+//! [buffer](crate::buffers) of a node or edge with `buffer` as a YAML mapping,
+//! such as `buffer="{bytes: 4096}"`.
+//!
+//! Defaults set with `node [...]` and `edge [...]` apply to the nodes and edges
+//! that follow them in the same subgraph, and subgraphs are flattened. A
+//! subgraph with an `iterations` attribute is a loop of the nodes that it
+//! mentions, which run that many times:
+//!
+//! ```dot
+//! subgraph step {
+//!     iterations=4;
+//!     load_weights -> weights -> matmul;
+//! }
+//! ```
+//!
+//! A single node is repeated with its own `iterations` attribute.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use serde_yaml::{Mapping, Value};
+
+use crate::timetable_file::{EdgeSection, NodeSection, SubgraphSection, TimetableFile};
+
+/// Attributes of a node or edge, in the order that they were first set
+type Attributes = Vec<(String, String)>;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Id(String),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Equals,
+    Semicolon,
+    Comma,
+    Colon,
+    DirectedEdge,
+    UndirectedEdge,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Id(id) => write!(f, "'{id}'"),
+            Token::LBrace => write!(f, "'{{'"),
+            Token::RBrace => write!(f, "'}}'"),
+            Token::LBracket => write!(f, "'['"),
+            Token::RBracket => write!(f, "']'"),
+            Token::Equals => write!(f, "'='"),
+            Token::Semicolon => write!(f, "';'"),
+            Token::Comma => write!(f, "','"),
+            Token::Colon => write!(f, "':'"),
+            Token::DirectedEdge => write!(f, "'->'"),
+            Token::UndirectedEdge => write!(f, "'--'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, SimError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut at_line_start = true;
+    while pos < chars.len() {
+        let c = chars[pos];
+        let next = chars.get(pos + 1).copied();
+        if c == '\n' {
+            at_line_start = true;
+            pos += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+        // `#` lines are preprocessor output and are ignored like comments
+        if (c == '#' && at_line_start) || (c == '/' && next == Some('/')) {
+            while pos < chars.len() && chars[pos] != '\n' {
+                pos += 1;
+            }
+            continue;
+        }
+        at_line_start = false;
+        if c == '/' && next == Some('*') {
+            let Some(len) = chars[pos + 2..].windows(2).position(|w| w == ['*', '/']) else {
+                return sim_error!("Unterminated comment in DOT graph");
+            };
+            pos += len + 4;
+            continue;
+        }
+
+        let token = match c {
+            '{' => Token::LBrace,
+            '}' => Token::RBrace,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '=' => Token::Equals,
+            ';' => Token::Semicolon,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '-' if next == Some('>') => {
+                pos += 1;
+                Token::DirectedEdge
+            }
+            '-' if next == Some('-') => {
+                pos += 1;
+                Token::UndirectedEdge
+            }
+            '"' => {
+                let mut s = String::new();
+                pos += 1;
+                loop {
+                    match chars.get(pos) {
+                        None => return sim_error!("Unterminated string in DOT graph"),
+                        Some('"') => break,
+                        Some('\\') if chars.get(pos + 1) == Some(&'"') => {
+                            s.push('"');
+                            pos += 1;
+                        }
+                        Some('\\') if chars.get(pos + 1) == Some(&'\n') => pos += 1,
+                        Some(c) => s.push(*c),
+                    }
+                    pos += 1;
+                }
+                Token::Id(s)
+            }
+            '<' => {
+                // An HTML string, which is only used for labels
+                let mut depth = 0;
+                let start = pos;
+                loop {
+                    match chars.get(pos) {
+                        None => return sim_error!("Unterminated HTML string in DOT graph"),
+                        Some('<') => depth += 1,
+                        Some('>') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Some(_) => {}
+                    }
+                    pos += 1;
+                }
+                Token::Id(chars[start + 1..pos].iter().collect())
+            }
+            c if is_id_char(c) || c == '-' => {
+                let start = pos;
+                pos += 1;
+                while chars.get(pos).is_some_and(|c| is_id_char(*c)) {
+                    pos += 1;
+                }
+                tokens.push(Token::Id(chars[start..pos].iter().collect()));
+                continue;
+            }
+            c => return sim_error!("Unexpected '{c}' in DOT graph"),
+        };
+        tokens.push(token);
+        pos += 1;
+    }
+    Ok(tokens)
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+/// The end of an edge, which is a node with an optional port
+#[derive(Clone)]
+struct EdgeEnd {
+    node: String,
+    port: Option<usize>,
+}
+
+impl EdgeEnd {
+    fn name(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}.{port}", self.node),
+            None => self.node.clone(),
+        }
+    }
+}
+
+/// The default attributes of a subgraph
+#[derive(Clone, Default)]
+struct Defaults {
+    node: Attributes,
+    edge: Attributes,
+}
+
+#[derive(Default)]
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    nodes: Vec<(String, Attributes)>,
+    node_idx_by_id: HashMap<String, usize>,
+    edges: Vec<(EdgeEnd, EdgeEnd, Attributes)>,
+    /// The nodes in the order they are mentioned, to find those of subgraphs
+    mentioned: Vec<String>,
+    /// The attributes of the graph or subgraph being parsed
+    graph_attributes: Attributes,
+    /// The subgraphs that have `iterations`, with their nodes
+    subgraphs: Vec<(String, Vec<String>, String)>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<(), SimError> {
+        match self.next() {
+            Some(t) if t == *token => Ok(()),
+            Some(t) => sim_error!("Expected {token} but found {t} in DOT graph"),
+            None => sim_error!("Expected {token} at the end of the DOT graph"),
+        }
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    fn id(&mut self) -> Result<String, SimError> {
+        match self.next() {
+            Some(Token::Id(id)) => Ok(id),
+            Some(t) => sim_error!("Expected an ID but found {t} in DOT graph"),
+            None => sim_error!("Expected an ID at the end of the DOT graph"),
+        }
+    }
+
+    fn graph(&mut self) -> Result<(), SimError> {
+        if self.keyword("strict") {
+            self.pos += 1;
+        }
+        if self.keyword("graph") {
+            return sim_error!("Timetable graphs must be a digraph");
+        }
+        if !self.keyword("digraph") {
+            return sim_error!("Expected a digraph in DOT graph");
+        }
+        self.pos += 1;
+        if matches!(self.peek(), Some(Token::Id(_))) {
+            self.pos += 1;
+        }
+        self.expect(&Token::LBrace)?;
+        self.statements(&mut Defaults::default())?;
+        self.expect(&Token::RBrace)?;
+        if let Some(token) = self.peek() {
+            return sim_error!("Unexpected {token} after the end of the DOT graph");
+        }
+        Ok(())
+    }
+
+    fn statements(&mut self, defaults: &mut Defaults) -> Result<(), SimError> {
+        while !matches!(self.peek(), Some(Token::RBrace) | None) {
+            self.statement(defaults)?;
+            self.eat(&Token::Semicolon);
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, defaults: &mut Defaults) -> Result<(), SimError> {
+        for (keyword, is_node) in [("node", Some(true)), ("edge", Some(false)), ("graph", None)] {
+            if self.keyword(keyword) && self.tokens.get(self.pos + 1) == Some(&Token::LBracket) {
+                self.pos += 1;
+                let attributes = self.attributes()?;
+                match is_node {
+                    Some(true) => merge(&mut defaults.node, attributes),
+                    Some(false) => merge(&mut defaults.edge, attributes),
+                    None => merge(&mut self.graph_attributes, attributes),
+                }
+                return Ok(());
+            }
+        }
+
+        // Graph attributes of the form `ID = ID`
+        if matches!(self.peek(), Some(Token::Id(_)))
+            && self.tokens.get(self.pos + 1) == Some(&Token::Equals)
+        {
+            let key = self.id()?;
+            self.pos += 1;
+            let value = self.id()?;
+            merge(&mut self.graph_attributes, vec![(key, value)]);
+            return Ok(());
+        }
+
+        let mut ends = self.edge_operand(defaults)?;
+        if !matches!(
+            self.peek(),
+            Some(Token::DirectedEdge | Token::UndirectedEdge)
+        ) {
+            // A node statement (or a subgraph)
+            if let [end] = ends.as_slice()
+                && self.peek() == Some(&Token::LBracket)
+            {
+                let node = end.node.clone();
+                let attributes = self.attributes()?;
+                self.add_node(&node, &defaults.node, attributes);
+            }
+            return Ok(());
+        }
+
+        let mut chain = Vec::new();
+        while let Some(token) = self.peek() {
+            match token {
+                Token::DirectedEdge => {}
+                Token::UndirectedEdge => {
+                    return sim_error!("Timetable graphs must use directed edges ('->')");
+                }
+                _ => break,
+            }
+            self.pos += 1;
+            let to = self.edge_operand(defaults)?;
+            chain.push(std::mem::replace(&mut ends, to));
+        }
+        chain.push(ends);
+
+        let mut attributes = defaults.edge.clone();
+        if self.peek() == Some(&Token::LBracket) {
+            merge(&mut attributes, self.attributes()?);
+        }
+        for pair in chain.windows(2) {
+            for from in &pair[0] {
+                for to in &pair[1] {
+                    self.edges
+                        .push((from.clone(), to.clone(), attributes.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a node or subgraph at one end of an edge, returning the nodes
+    fn edge_operand(&mut self, defaults: &Defaults) -> Result<Vec<EdgeEnd>, SimError> {
+        if self.keyword("subgraph") || self.peek() == Some(&Token::LBrace) {
+            let mut name = None;
+            if self.keyword("subgraph") {
+                self.pos += 1;
+                if matches!(self.peek(), Some(Token::Id(_))) {
+                    name = Some(self.id()?);
+                }
+            }
+            let first_mentioned = self.mentioned.len();
+            let outer_attributes = std::mem::take(&mut self.graph_attributes);
+            self.expect(&Token::LBrace)?;
+            self.statements(&mut defaults.clone())?;
+            self.expect(&Token::RBrace)?;
+            let attributes = std::mem::replace(&mut self.graph_attributes, outer_attributes);
+            let mut ends: Vec<EdgeEnd> = Vec::new();
+            for node in &self.mentioned[first_mentioned..] {
+                if !ends.iter().any(|end| end.node == *node) {
+                    ends.push(EdgeEnd {
+                        node: node.clone(),
+                        port: None,
+                    });
+                }
+            }
+            if let Some(iterations) = attribute(&attributes, "iterations") {
+                let name = name.unwrap_or_else(|| format!("subgraph{}", self.subgraphs.len()));
+                let nodes = ends.iter().map(|end| end.node.clone()).collect();
+                self.subgraphs.push((name, nodes, iterations.to_string()));
+            }
+            return Ok(ends);
+        }
+
+        let node = self.id()?;
+        let mut port = None;
+        while self.eat(&Token::Colon) {
+            // Only numeric ports are edge indices, others (such as compass
+            // points) only affect drawing
+            let id = self.id()?;
+            if port.is_none() {
+                port = id.parse().ok();
+            }
+        }
+        self.add_node(&node, &defaults.node, Vec::new());
+        Ok(vec![EdgeEnd { node, port }])
+    }
+
+    fn attributes(&mut self) -> Result<Attributes, SimError> {
+        let mut attributes = Vec::new();
+        while self.eat(&Token::LBracket) {
+            while !self.eat(&Token::RBracket) {
+                let key = self.id()?;
+                let value = if self.eat(&Token::Equals) {
+                    self.id()?
+                } else {
+                    "true".to_string()
+                };
+                merge(&mut attributes, vec![(key, value)]);
+                if !self.eat(&Token::Comma) {
+                    self.eat(&Token::Semicolon);
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn add_node(&mut self, id: &str, defaults: &Attributes, attributes: Attributes) {
+        self.mentioned.push(id.to_string());
+        let idx = match self.node_idx_by_id.get(id) {
+            Some(idx) => *idx,
+            None => {
+                self.node_idx_by_id.insert(id.to_string(), self.nodes.len());
+                self.nodes.push((id.to_string(), defaults.clone()));
+                self.nodes.len() - 1
+            }
+        };
+        merge(&mut self.nodes[idx].1, attributes);
+    }
+}
+
+/// Set attributes, replacing the values of any that are already set
+fn merge(attributes: &mut Attributes, new: Attributes) {
+    for (key, value) in new {
+        match attributes.iter_mut().find(|(k, _)| *k == key) {
+            Some(existing) => existing.1 = value,
+            None => attributes.push((key, value)),
+        }
+    }
+}
+
+fn attribute<'a>(attributes: &'a Attributes, key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn parse_value(id: &str, key: &str, value: &str) -> Result<Value, SimError> {
+    serde_yaml::from_str(value).map_err(|e| {
+        SimError(format!(
+            "Unable to parse attribute '{key}' of '{id}' ('{value}'): {e}"
+        ))
+    })
+}
+
+/// Returns the number of views of the edges at one side of a node: one for
+/// each edge, or one for each port up to the highest port given
+fn num_views<'a>(ends: impl Iterator<Item = &'a EdgeEnd>) -> usize {
+    let ports: Vec<Option<usize>> = ends.map(|end| end.port).collect();
+    match ports.iter().flatten().max() {
+        Some(max) => max + 1,
+        None => ports.len(),
+    }
+}
+
+fn node_section(
+    id: &str,
+    attributes: &Attributes,
+    edges: &[(EdgeEnd, EdgeEnd, Attributes)],
+    conditions: &HashSet<&str>,
+) -> Result<NodeSection, SimError> {
+    let Some(kind) = attribute(attributes, "kind") else {
+        return sim_error!("Node '{id}' has no kind");
+    };
+    let (fields, config_fields): (&[&str], &[&str]) = match kind {
+        "compute" => (
+            &[
+                "op",
+                "pe",
+                "input_views",
+                "output_views",
+                "priority",
+                "deadline_ns",
+                "relative_deadline_ns",
+                "iterations",
+                "buffer",
+            ],
+            &[],
+        ),
+        "memory" => (
+            &[
+                "op",
+                "pe",
+                "deadline_ns",
+                "relative_deadline_ns",
+                "iterations",
+                "buffer",
+            ],
+            &["view"],
+        ),
+        "dma" => (
+            &[
+                "op",
+                "pe",
+                "deadline_ns",
+                "relative_deadline_ns",
+                "iterations",
+                "buffer",
+            ],
+            &["input_view", "output_view"],
+        ),
+        "send" => (
+            &[
+                "pe",
+                "deadline_ns",
+                "relative_deadline_ns",
+                "iterations",
+                "buffer",
+            ],
+            &["to", "bytes"],
+        ),
+        "recv" => (
+            &[
+                "pe",
+                "deadline_ns",
+                "relative_deadline_ns",
+                "iterations",
+                "buffer",
+            ],
+            &["from", "bytes"],
+        ),
+        "condition" => (&[], &["predicate", "seed"]),
+        "collective" => (&["op", "pes"], &["bytes", "algorithm"]),
+        "tensor" => (&[], &["addr", "dtype", "shape"]),
+        _ => return sim_error!("Node '{id}' has unknown kind '{kind}'"),
+    };
+
+    let mut node = Mapping::new();
+    node.insert("id".into(), id.into());
+    node.insert("kind".into(), kind.into());
+    let mut config = match attribute(attributes, "config") {
+        Some(config) => match parse_value(id, "config", config)? {
+            Value::Mapping(config) => config,
+            _ => return sim_error!("The config of '{id}' must be a mapping"),
+        },
+        None => Mapping::new(),
+    };
+    for (key, value) in attributes {
+        let key = key.as_str();
+        if fields.contains(&key) {
+            node.insert(key.into(), parse_value(id, key, value)?);
+        } else if config_fields.contains(&key) {
+            let value = parse_value(id, key, value)?;
+            // `shape` is also the Graphviz shape of a node
+            if key != "shape" || value.is_sequence() {
+                config.insert(key.into(), value);
+            }
+        }
+    }
+    if kind != "compute" {
+        node.insert("config".into(), Value::Mapping(config));
+    } else {
+        let is_data = |attributes: &Attributes| attribute(attributes, "kind") != Some("control");
+        let views = |n: usize| Value::Sequence(vec![Value::Null; n]);
+        if !node.contains_key("input_views") {
+            // Edges from conditions do not carry data
+            let inputs = edges
+                .iter()
+                .filter(|(from, to, a)| {
+                    to.node == id && is_data(a) && !conditions.contains(from.node.as_str())
+                })
+                .map(|(_, to, _)| to);
+            node.insert("input_views".into(), views(num_views(inputs)));
+        }
+        if !node.contains_key("output_views") {
+            let outputs = edges
+                .iter()
+                .filter(|(from, _, a)| from.node == id && is_data(a))
+                .map(|(from, _, _)| from);
+            node.insert("output_views".into(), views(num_views(outputs)));
+        }
+    }
+
+    serde_yaml::from_value(Value::Mapping(node))
+        .map_err(|e| SimError(format!("Invalid node '{id}': {e}")))
+}
+
+fn edge_section(
+    from: &EdgeEnd,
+    to: &EdgeEnd,
+    attributes: &Attributes,
+) -> Result<EdgeSection, SimError> {
+    let (from, to) = (from.name(), to.name());
+    let mut edge = Mapping::new();
+    edge.insert("from".into(), from.as_str().into());
+    edge.insert("to".into(), to.as_str().into());
+    edge.insert(
+        "kind".into(),
+        attribute(attributes, "kind").unwrap_or("data").into(),
+    );
+    let id = format!("{from} -> {to}");
+    for key in ["bytes", "buffer"] {
+        if let Some(value) = attribute(attributes, key) {
+            edge.insert(key.into(), parse_value(&id, key, value)?);
+        }
+    }
+    serde_yaml::from_value(Value::Mapping(edge))
+        .map_err(|e| SimError(format!("Invalid edge '{from}' -> '{to}': {e}")))
+}
+
+/// Parse a timetable graph from the source of a DOT file
+pub(crate) fn parse_dot(source: &str) -> Result<TimetableFile, SimError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        ..Parser::default()
+    };
+    parser.graph()?;
+
+    let conditions = parser
+        .nodes
+        .iter()
+        .filter(|(_, attributes)| attribute(attributes, "kind") == Some("condition"))
+        .map(|(id, _)| id.as_str())
+        .collect();
+    let nodes = parser
+        .nodes
+        .iter()
+        .map(|(id, attributes)| node_section(id, attributes, &parser.edges, &conditions))
+        .collect::<Result<_, _>>()?;
+    let edges = parser
+        .edges
+        .iter()
+        .map(|(from, to, attributes)| edge_section(from, to, attributes))
+        .collect::<Result<_, _>>()?;
+    let subgraphs = parser
+        .subgraphs
+        .into_iter()
+        .map(|(id, nodes, iterations)| {
+            let iterations = parse_value(&id, "iterations", &iterations)?;
+            let iterations = serde_yaml::from_value(iterations)
+                .map_err(|e| SimError(format!("Invalid iterations of subgraph '{id}': {e}")))?;
+            Ok(SubgraphSection {
+                id,
+                nodes,
+                iterations,
+            })
+        })
+        .collect::<Result<_, SimError>>()?;
+    Ok(TimetableFile {
+        nodes,
+        edges,
+        subgraphs,
+        streams: Vec::new(),
+    })
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! The schedule of the tasks that each PE executed.
+//!
+//! While the timetable runs, each task is shown as a slice on a track of the
+//! PE that runs it, so the schedule can be seen directly in Perfetto. A PE
+//! that runs tasks at the same time is given more tracks. Once the timetable
+//! has run, a [GanttChart] holds the same slices to be saved as JSON.
+
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, EntityLane};
+use serde::Serialize;
+
+/// One execution of a task
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GanttSlice {
+    pub task: String,
+    pub start_ns: f64,
+    pub end_ns: f64,
+}
+
+/// The tasks executed by a PE, in the order that they started
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PeSchedule {
+    pub pe: String,
+    pub tasks: Vec<GanttSlice>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct GanttChart {
+    pub pes: Vec<PeSchedule>,
+}
+
+impl GanttChart {
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a Gantt chart can always be serialized")
+    }
+}
+
+/// The tracks of a PE with the task running on each
+pub(crate) struct PeTaskLanes {
+    entity: Rc<Entity>,
+    lanes: Vec<(EntityLane, Option<usize>)>,
+}
+
+impl PeTaskLanes {
+    pub(crate) fn new(parent: &Rc<Entity>, pe_name: &str) -> Self {
+        Self {
+            entity: Rc::new(Entity::new(parent, pe_name)),
+            lanes: Vec::new(),
+        }
+    }
+
+    /// Show a task as running on the first free track
+    pub(crate) fn begin(&mut self, task_idx: usize, task_id: &str) {
+        let lane_idx = match self.lanes.iter().position(|(_, task)| task.is_none()) {
+            Some(lane_idx) => lane_idx,
+            None => {
+                let lane_idx = self.lanes.len();
+                let lane = EntityLane::new(&self.entity, &format!("tasks::{lane_idx}"));
+                self.lanes.push((lane, None));
+                lane_idx
+            }
+        };
+        let (lane, task) = &mut self.lanes[lane_idx];
+        lane.begin(task_id);
+        *task = Some(task_idx);
+    }
+
+    pub(crate) fn end(&mut self, task_idx: usize) {
+        if let Some((lane, task)) = self
+            .lanes
+            .iter_mut()
+            .find(|(_, task)| *task == Some(task_idx))
+        {
+            lane.end();
+            *task = None;
+        }
+    }
+}
@@ -8,37 +8,66 @@
 //!     --platform gwr-platform/examples/platform.yaml
 //!     --timetable gwr-timetable/examples/small.yaml
 //!     --stdout --stdout-level debug
-use std::cell::RefCell;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::rc::Rc;
 
 use async_trait::async_trait;
 use gwr_engine::events::repeated::Repeated;
 use gwr_engine::sim_error;
+use gwr_engine::time::clock::Clock;
 use gwr_engine::traits::Event;
 use gwr_engine::types::{SimError, SimResult};
 use gwr_model_builder::EntityGet;
+use gwr_models::log_stats;
 use gwr_models::processing_element::MachineOpCounts;
 use gwr_models::processing_element::dispatch::Dispatch;
 use gwr_models::processing_element::operators::{Tensor, TensorView};
 use gwr_models::processing_element::task::{
-    ComputeOp, ComputeTaskConfig, MemoryOp, MemoryTaskConfig, Task,
+    ComputeOp, ComputeTaskConfig, DmaOp, DmaTaskConfig, MemoryOp, MemoryTaskConfig, RecvTaskConfig,
+    SendTaskConfig, Task,
 };
 use gwr_platform::Platform;
 use gwr_track::entity::Entity;
-use gwr_track::{debug, info, trace};
-
+use gwr_track::{debug, info, trace, warn};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub mod buffers;
+pub mod checkpoint;
+pub mod collective;
+pub mod cost_hook;
+pub mod critical_path;
+pub mod deadlines;
+pub mod dot;
+pub mod gantt;
+pub mod mapping;
 pub mod mermaid;
+pub mod onnx;
+pub mod random_graph;
+pub mod scheduling;
+pub mod streams;
+pub mod sweep;
 pub mod timetable_file;
 pub mod types;
+pub mod utilization;
 use timetable_file::{NodeSection, TimetableFile};
-use types::Node;
-
+use types::{Loop, Node, Transfer};
+
+use crate::buffers::{BufferReport, Buffers, CapacityPolicy};
+use crate::checkpoint::TimetableCheckpoint;
+use crate::cost_hook::{CostModelFn, TaskCostContext};
+use crate::critical_path::{CriticalPathReport, TimedGraph};
+use crate::deadlines::{Deadline, DeadlineAction, DeadlineMiss, DeadlineReport};
+use crate::gantt::{GanttChart, GanttSlice, PeSchedule, PeTaskLanes};
 use crate::mermaid::{MermaidNodeStatus, render_mermaid_from_parts};
+use crate::scheduling::{SchedulingPolicy, critical_path_lengths};
 use crate::timetable_file::{
-    EdgeSection, MemoryConfigSection, TensorConfigSection, TensorViewSection, dtype_num_bytes,
+    ConditionConfigSection, ConditionPredicate, DmaConfigSection, EdgeSection, MemoryConfigSection,
+    StreamSection, SubgraphSection, TensorConfigSection, TensorViewSection, dtype_num_bytes,
 };
+use crate::utilization::{PeUtilization, UtilizationReport};
 
 fn validate_access_in_range(
     node_id: &str,
@@ -115,6 +144,19 @@ fn tensor_view_offset(
     }
 }
 
+/// The address and number of bytes of a view of a tensor
+fn tensor_view_address_num_bytes(
+    tensor_config: &TensorConfigSection,
+    view: Option<&TensorViewSection>,
+) -> (u64, usize) {
+    let offset_num_elements = tensor_view_offset(tensor_config, view);
+    let view_num_elements = tensor_view_num_elements(tensor_config, view);
+    let address =
+        tensor_config.addr + dtype_num_bytes(&tensor_config.dtype, offset_num_elements) as u64;
+    let num_bytes = dtype_num_bytes(&tensor_config.dtype, view_num_elements);
+    (address, num_bytes)
+}
+
 fn tensor_view_num_elements(
     tensor_config: &TensorConfigSection,
     view: Option<&TensorViewSection>,
@@ -131,6 +173,9 @@ pub struct Timetable {
     platform: Rc<Platform>,
     nodes: Vec<Node>,
     edges: Vec<EdgeSection>,
+    // Transfers are tasks that follow the nodes, so the task index of a
+    // transfer is its index plus the number of nodes.
+    transfers: Vec<Transfer>,
     node_pe_indices: Vec<Option<usize>>,
     completed_node_indices: RefCell<HashSet<usize>>,
     active_node_indices: RefCell<HashSet<usize>>,
@@ -141,6 +186,46 @@ pub struct Timetable {
     remaining_nodes_per_pe: RefCell<HashMap<usize, usize>>,
     unresolved_input_counts: RefCell<Vec<usize>>,
     ready_nodes_changed: Repeated<()>,
+    scheduling_policy: SchedulingPolicy,
+    // The order in which tasks became ready, for the FIFO policy
+    ready_sequence: RefCell<Vec<u64>>,
+    next_ready_sequence: Cell<u64>,
+    critical_path_lengths: Vec<usize>,
+    loops: Vec<Loop>,
+    // The loop of each task and tensor that is part of one
+    loop_indices: Vec<Option<usize>>,
+    remaining_iterations: RefCell<Vec<usize>>,
+    // The output selected by each Condition node that has been evaluated
+    selected_outputs: RefCell<HashMap<usize, usize>>,
+    // The nodes that completed without running because they were on a path
+    // that a Condition node did not select
+    skipped_node_indices: RefCell<HashSet<usize>>,
+    condition_rngs: RefCell<HashMap<usize, StdRng>>,
+    // The clock used to time tasks, and the first start and last end of each
+    // task that has run
+    clock: Option<Clock>,
+    task_times: RefCell<Vec<(Option<f64>, Option<f64>)>>,
+    // Every run of a task, as the task index with its start and end, and the
+    // start of each task that is running
+    executions: RefCell<Vec<(usize, f64, f64)>>,
+    execution_starts: RefCell<HashMap<usize, f64>>,
+    // The tracks that show the tasks running on each PE
+    pe_names: HashMap<usize, String>,
+    pe_task_lanes: RefCell<HashMap<usize, PeTaskLanes>>,
+    cost_model: Option<Rc<CostModelFn>>,
+    streams: Vec<StreamSection>,
+    task_streams: Vec<Option<usize>>,
+    // One more than the highest priority of a node, so that the priority of
+    // a stream outranks the priorities of the nodes within it
+    num_node_priorities: usize,
+    buffers: Buffers,
+    capacity_policy: CapacityPolicy,
+    deadline_action: DeadlineAction,
+    // The time that the first task of each stream, or of the nodes that are
+    // not in a stream, started
+    graph_start_ns: RefCell<HashMap<Option<usize>, f64>>,
+    num_deadlines_checked: Cell<usize>,
+    deadline_misses: RefCell<Vec<DeadlineMiss>>,
 }
 
 impl fmt::Debug for Timetable {
@@ -185,6 +270,133 @@ fn update_edge_indices(
     Ok(())
 }
 
+/// Create a transfer for each edge that carries bytes from a tensor produced
+/// on one PE to a node on another PE.
+///
+/// Data that is produced on the PE that consumes it, or that is in memory
+/// before the timetable starts, does not need to be moved.
+fn build_transfers(
+    nodes: &[Node],
+    node_pe_indices: &[Option<usize>],
+    node_idx_by_id: &HashMap<String, usize>,
+    edges: &[EdgeSection],
+) -> Result<Vec<Transfer>, SimError> {
+    let mut transfers = Vec::new();
+    for edge in edges {
+        let Some(num_bytes) = edge.bytes else {
+            continue;
+        };
+        let id = format!("{} -> {}", edge.from, edge.to);
+        let tensor_idx = node_idx_by_id[edge.from_node_id()];
+        let consumer_idx = node_idx_by_id[edge.to_node_id()];
+        let NodeSection::Tensor {
+            id: tensor_id,
+            config,
+        } = &nodes[tensor_idx].node_section
+        else {
+            return sim_error!("Edge '{id}' carries bytes so must be from a Tensor node");
+        };
+        let Some(pe_idx) = node_pe_indices[consumer_idx] else {
+            return sim_error!("Edge '{id}' carries bytes so must be to a node on a PE");
+        };
+        let tensor_num_bytes = dtype_num_bytes(&config.dtype, config.num_elements());
+        if num_bytes > tensor_num_bytes {
+            return sim_error!(
+                "Edge '{id}' carries {num_bytes} bytes but tensor '{tensor_id}' is only {tensor_num_bytes} bytes"
+            );
+        }
+
+        let crosses_pes = nodes[tensor_idx]
+            .inputs
+            .iter()
+            .flatten()
+            .any(|producer_idx| node_pe_indices[*producer_idx].is_some_and(|p| p != pe_idx));
+        if num_bytes == 0 || !crosses_pes {
+            continue;
+        }
+        transfers.push(Transfer {
+            id,
+            tensor_idx,
+            consumer_idx,
+            pe_idx,
+            addr: config.addr,
+            num_bytes,
+        });
+    }
+    Ok(transfers)
+}
+
+/// Create a loop for each node with `iterations` and for each subgraph.
+///
+/// A loop also contains the tensors that its tasks pass to each other and the
+/// transfers between them, as these are produced again by every iteration.
+fn build_loops(
+    nodes: &[Node],
+    node_idx_by_id: &HashMap<String, usize>,
+    subgraphs: &[SubgraphSection],
+    transfers: &[Transfer],
+) -> Result<(Vec<Loop>, Vec<Option<usize>>), SimError> {
+    let mut loops = Vec::new();
+    for node in nodes {
+        if let Some(iterations) = node.node_section.iterations() {
+            let id = node.node_section.id();
+            loops.push(Loop {
+                id: id.clone(),
+                members: BTreeSet::from([node_idx_by_id[id]]),
+                iterations,
+            });
+        }
+    }
+    for subgraph in subgraphs {
+        loops.push(Loop {
+            id: subgraph.id.clone(),
+            members: subgraph
+                .nodes
+                .iter()
+                .map(|node_id| node_idx_by_id[node_id])
+                .collect(),
+            iterations: subgraph.iterations,
+        });
+    }
+
+    for l in &mut loops {
+        let passed_tensors: Vec<usize> = (0..nodes.len())
+            .filter(|idx| {
+                let node = &nodes[*idx];
+                matches!(node.node_section, NodeSection::Tensor { .. })
+                    && node.inputs.iter().flatten().any(|i| l.members.contains(i))
+                    && node.outputs.iter().flatten().any(|i| l.members.contains(i))
+            })
+            .collect();
+        l.members.extend(passed_tensors);
+        for (i, transfer) in transfers.iter().enumerate() {
+            if l.members.contains(&transfer.tensor_idx)
+                && l.members.contains(&transfer.consumer_idx)
+            {
+                l.members.insert(nodes.len() + i);
+            }
+        }
+    }
+
+    let mut loop_indices = vec![None; nodes.len() + transfers.len()];
+    for (loop_idx, l) in loops.iter().enumerate() {
+        for idx in &l.members {
+            if let Some(other_idx) = loop_indices[*idx].replace(loop_idx) {
+                let id = match nodes.get(*idx) {
+                    Some(node) => node.node_section.id(),
+                    None => &transfers[*idx - nodes.len()].id,
+                };
+                return sim_error!(
+                    "Node '{id}' is in more than one loop ('{}' and '{}')",
+                    loops[other_idx].id,
+                    l.id
+                );
+            }
+        }
+    }
+    Ok((loops, loop_indices))
+}
+
 type InOutTensorViews = (Vec<Option<TensorView>>, Vec<Option<TensorView>>);
 
 impl Timetable {
@@ -199,6 +411,7 @@ impl Timetable {
         mut timetable_file: TimetableFile,
         platform: &Rc<Platform>,
     ) -> Result<Self, SimError> {
+        timetable_file.expand_collectives()?;
         timetable_file.validate(platform)?;
 
         let entity = Rc::new(Entity::new(parent, "timetable"));
@@ -206,6 +419,7 @@ impl Timetable {
         let mut nodes_per_pe = HashMap::new();
         let mut node_pe_indices = Vec::with_capacity(timetable_file.nodes.len());
         let mut nodes = Vec::with_capacity(timetable_file.nodes.len());
+        let mut pe_names = HashMap::new();
 
         for (i, node_section) in timetable_file.nodes.drain(..).enumerate() {
             let (id, pe) = node_section.id_pe();
@@ -217,6 +431,7 @@ impl Timetable {
                     .entry(pe_idx)
                     .or_insert_with(BTreeSet::new)
                     .insert(i);
+                pe_names.insert(pe_idx, pe_id.clone());
                 Some(pe_idx)
             } else {
                 None
@@ -226,6 +441,7 @@ impl Timetable {
                 node_section,
                 inputs: Vec::new(),
                 outputs: Vec::new(),
+                conditions: Vec::new(),
             });
             node_pe_indices.push(pe_idx);
         }
@@ -238,13 +454,19 @@ impl Timetable {
             let (to_node_id, to_edge_idx) = edge_section.to_node_and_edge()?;
             let to_node_idx = node_idx_by_id.get(to_node_id).unwrap();
 
-            update_edge_indices(*from_node_idx, to_edge_idx, &mut nodes[*to_node_idx].inputs)
-                .map_err(|err| {
+            // Conditions do not provide data, so they are kept apart from the
+            // inputs of the nodes that they select
+            if let NodeSection::Condition { .. } = nodes[*from_node_idx].node_section {
+                nodes[*to_node_idx].conditions.push(*from_node_idx);
+            } else {
+                update_edge_indices(*from_node_idx, to_edge_idx, &mut nodes[*to_node_idx].inputs)
+                    .map_err(|err| {
                     SimError(format!(
                         "Node {from_node_idx} '{}': {err}",
                         nodes[*from_node_idx].node_section.id()
                     ))
                 })?;
+            }
             update_edge_indices(
                 *to_node_idx,
                 from_edge_idx,
@@ -258,10 +480,71 @@ impl Timetable {
             })?;
         }
 
-        let timetable = Self {
+        let transfers = build_transfers(
+            &nodes,
+            &node_pe_indices,
+            &node_idx_by_id,
+            &timetable_file.edges,
+        )?;
+        for (i, transfer) in transfers.iter().enumerate() {
+            nodes_per_pe
+                .entry(transfer.pe_idx)
+                .or_insert_with(BTreeSet::new)
+                .insert(nodes.len() + i);
+            node_pe_indices.push(Some(transfer.pe_idx));
+        }
+        let buffers = Buffers::new(
+            platform,
+            &nodes,
+            &timetable_file.edges,
+            &node_idx_by_id,
+            nodes.len() + transfers.len(),
+        )?;
+        let (loops, loop_indices) = build_loops(
+            &nodes,
+            &node_idx_by_id,
+            &timetable_file.subgraphs,
+            &transfers,
+        )?;
+
+        // The stream of each task, with transfers in the stream of the node
+        // that waits for them
+        let mut task_streams = vec![None; nodes.len()];
+        for (stream_idx, stream) in timetable_file.streams.iter().enumerate() {
+            for node_id in &stream.nodes {
+                task_streams[node_idx_by_id[node_id]] = Some(stream_idx);
+            }
+        }
+        for transfer in &transfers {
+            task_streams.push(task_streams[transfer.consumer_idx]);
+        }
+
+        let num_node_priorities = nodes
+            .iter()
+            .filter_map(|node| match &node.node_section {
+                NodeSection::Compute { priority, .. } => *priority,
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let condition_rngs = nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, node)| match &node.node_section {
+                NodeSection::Condition { config, .. } => {
+                    Some((idx, StdRng::seed_from_u64(config.seed)))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut timetable = Self {
             entity,
             nodes,
             edges: timetable_file.edges,
+            transfers,
             node_pe_indices,
             platform: platform.clone(),
             completed_node_indices: RefCell::new(HashSet::new()),
@@ -271,16 +554,562 @@ impl Timetable {
             remaining_nodes_per_pe: RefCell::new(HashMap::new()),
             unresolved_input_counts: RefCell::new(Vec::new()),
             ready_nodes_changed: Repeated::new(()),
+            scheduling_policy: SchedulingPolicy::default(),
+            ready_sequence: RefCell::new(Vec::new()),
+            next_ready_sequence: Cell::new(0),
+            critical_path_lengths: Vec::new(),
+            remaining_iterations: RefCell::new(loops.iter().map(|l| l.iterations).collect()),
+            loops,
+            loop_indices,
+            selected_outputs: RefCell::new(HashMap::new()),
+            skipped_node_indices: RefCell::new(HashSet::new()),
+            condition_rngs: RefCell::new(condition_rngs),
+            clock: None,
+            task_times: RefCell::new(Vec::new()),
+            executions: RefCell::new(Vec::new()),
+            execution_starts: RefCell::new(HashMap::new()),
+            pe_names,
+            pe_task_lanes: RefCell::new(HashMap::new()),
+            cost_model: None,
+            streams: timetable_file.streams,
+            task_streams,
+            num_node_priorities,
+            buffers,
+            capacity_policy: CapacityPolicy::default(),
+            deadline_action: DeadlineAction::default(),
+            graph_start_ns: RefCell::new(HashMap::new()),
+            num_deadlines_checked: Cell::new(0),
+            deadline_misses: RefCell::new(Vec::new()),
         };
 
         timetable.validate()?;
 
+        let successors: Vec<Vec<usize>> = (0..timetable.total_tasks())
+            .map(|idx| timetable.task_outputs(idx))
+            .collect();
+        let num_nodes = timetable.nodes.len();
+        timetable.critical_path_lengths = critical_path_lengths(&successors, |idx| {
+            idx >= num_nodes || timetable.node_pe_indices[idx].is_some()
+        });
+
+        *timetable.task_times.borrow_mut() = vec![(None, None); timetable.total_tasks()];
         timetable.update_complete_tensors();
         timetable.initialize_scheduler_state();
+        for idx in 0..timetable.nodes.len() {
+            timetable.update_condition(idx)?;
+        }
 
         Ok(timetable)
     }
 
+    /// Set the policy that orders the ready tasks of each PE
+    #[must_use]
+    pub fn with_scheduling_policy(mut self, policy: SchedulingPolicy) -> Self {
+        self.scheduling_policy = policy;
+        self
+    }
+
+    #[must_use]
+    pub fn scheduling_policy(&self) -> SchedulingPolicy {
+        self.scheduling_policy
+    }
+
+    /// Set whether a task that there is not enough space for the buffers of
+    /// fails the run or waits for space to be freed
+    #[must_use]
+    pub fn with_capacity_policy(mut self, policy: CapacityPolicy) -> Self {
+        self.capacity_policy = policy;
+        self
+    }
+
+    /// Set what happens when a task completes after its deadline
+    #[must_use]
+    pub fn with_deadline_action(mut self, action: DeadlineAction) -> Self {
+        self.deadline_action = action;
+        self
+    }
+
+    /// Time tasks with a clock so that a [CriticalPathReport] can be made
+    /// once the timetable has run
+    #[must_use]
+    pub fn with_clock(mut self, clock: &Clock) -> Self {
+        self.clock = Some(clock.clone());
+        self
+    }
+
+    /// Decide how long compute tasks take with an external performance model
+    /// rather than the model of the PE that runs them
+    #[must_use]
+    pub fn with_cost_model(
+        mut self,
+        cost_model: impl Fn(&TaskCostContext<'_>) -> Result<Option<usize>, SimError> + 'static,
+    ) -> Self {
+        self.cost_model = Some(Rc::new(cost_model));
+        self
+    }
+
+    /// Returns the ID of a task, tensor or condition
+    fn task_id(&self, task_idx: usize) -> &str {
+        match self.transfer(task_idx) {
+            Some(transfer) => &transfer.id,
+            None => self.nodes[task_idx].node_section.id(),
+        }
+    }
+
+    /// Analyse the critical path of the timetable using the simulated times
+    /// of its tasks
+    pub fn critical_path_report(&self) -> Result<CriticalPathReport, SimError> {
+        if self.clock.is_none() {
+            return sim_error!("Tasks are only timed by a timetable created with a clock");
+        }
+        let task_times = self.task_times.borrow();
+        let graph = TimedGraph {
+            ids: (0..self.total_tasks())
+                .map(|idx| self.task_id(idx).to_string())
+                .collect(),
+            successors: (0..self.total_tasks())
+                .map(|idx| self.task_outputs(idx))
+                .collect(),
+            times: task_times
+                .iter()
+                .map(|(start, end)| start.zip(*end))
+                .collect(),
+        };
+        Ok(graph.critical_path_report())
+    }
+
+    /// Returns the tasks that each PE executed with their simulated times. A
+    /// task that runs more than once, in a loop, has a slice for every run.
+    pub fn gantt_chart(&self) -> Result<GanttChart, SimError> {
+        if self.clock.is_none() {
+            return sim_error!("Tasks are only timed by a timetable created with a clock");
+        }
+        let mut pes: BTreeMap<&str, Vec<GanttSlice>> = BTreeMap::new();
+        for (task_idx, start_ns, end_ns) in self.executions.borrow().iter() {
+            let Some(pe_idx) = self.node_pe_indices[*task_idx] else {
+                continue;
+            };
+            pes.entry(&self.pe_names[&pe_idx])
+                .or_default()
+                .push(GanttSlice {
+                    task: self.task_id(*task_idx).to_string(),
+                    start_ns: *start_ns,
+                    end_ns: *end_ns,
+                });
+        }
+        let pes = pes
+            .into_iter()
+            .map(|(pe, mut tasks)| {
+                tasks.sort_by(|a, b| a.start_ns.total_cmp(&b.start_ns));
+                PeSchedule {
+                    pe: pe.to_string(),
+                    tasks,
+                }
+            })
+            .collect();
+        Ok(GanttChart { pes })
+    }
+
+    /// Record the progress of the timetable, so that a run can be resumed
+    /// from it with [Timetable::with_checkpoint]
+    #[must_use]
+    pub fn checkpoint(&self) -> TimetableCheckpoint {
+        let task_ids = |task_indices: &HashSet<usize>| {
+            let mut task_indices: Vec<usize> = task_indices.iter().copied().collect();
+            task_indices.sort_unstable();
+            task_indices
+                .into_iter()
+                .map(|idx| self.task_id(idx).to_string())
+                .collect()
+        };
+
+        let ready_sequence = self.ready_sequence.borrow();
+        let ready = self
+            .ready_nodes_per_pe
+            .borrow()
+            .iter()
+            .filter(|(_, task_indices)| !task_indices.is_empty())
+            .map(|(pe_idx, task_indices)| {
+                let mut task_indices: Vec<usize> = task_indices.iter().copied().collect();
+                task_indices.sort_by_key(|idx| ready_sequence[*idx]);
+                let task_ids = task_indices
+                    .into_iter()
+                    .map(|idx| self.task_id(idx).to_string())
+                    .collect();
+                (self.pe_names[pe_idx].clone(), task_ids)
+            })
+            .collect();
+
+        TimetableCheckpoint {
+            time_ns: self.clock.as_ref().map_or(0.0, Clock::time_now_ns),
+            completed: task_ids(&self.completed_node_indices.borrow()),
+            active: task_ids(&self.active_node_indices.borrow()),
+            ready,
+            skipped: task_ids(&self.skipped_node_indices.borrow()),
+            selected_outputs: self
+                .selected_outputs
+                .borrow()
+                .iter()
+                .map(|(idx, output)| (self.task_id(*idx).to_string(), *output))
+                .collect(),
+            remaining_iterations: self
+                .loops
+                .iter()
+                .zip(self.remaining_iterations.borrow().iter())
+                .map(|(l, remaining)| (l.id.clone(), *remaining))
+                .collect(),
+        }
+    }
+
+    /// Continue from a checkpoint of a timetable built from the same file
+    /// rather than starting from its first node. The tasks that were running
+    /// when the checkpoint was taken are run again.
+    pub fn with_checkpoint(self, checkpoint: &TimetableCheckpoint) -> Result<Self, SimError> {
+        {
+            let task_idx_by_id: HashMap<&str, usize> = (0..self.total_tasks())
+                .map(|idx| (self.task_id(idx), idx))
+                .collect();
+            let task_idx = |id: &String| {
+                task_idx_by_id.get(id.as_str()).copied().ok_or_else(|| {
+                    SimError(format!(
+                        "Checkpoint names task '{id}' which is not in the timetable"
+                    ))
+                })
+            };
+            let task_indices = |ids: &[String]| -> Result<HashSet<usize>, SimError> {
+                ids.iter().map(task_idx).collect()
+            };
+
+            let mut remaining_iterations: Vec<usize> =
+                self.loops.iter().map(|l| l.iterations).collect();
+            for (loop_id, remaining) in &checkpoint.remaining_iterations {
+                let Some(loop_idx) = self.loops.iter().position(|l| &l.id == loop_id) else {
+                    return sim_error!(
+                        "Checkpoint names loop '{loop_id}' which is not in the timetable"
+                    );
+                };
+                let iterations = self.loops[loop_idx].iterations;
+                if *remaining == 0 || *remaining > iterations {
+                    return sim_error!(
+                        "Checkpoint has {remaining} iterations left of loop '{loop_id}' which runs {iterations} times"
+                    );
+                }
+                remaining_iterations[loop_idx] = *remaining;
+            }
+            let selected_outputs = checkpoint
+                .selected_outputs
+                .iter()
+                .map(|(id, output)| Ok((task_idx(id)?, *output)))
+                .collect::<Result<_, SimError>>()?;
+
+            *self.completed_node_indices.borrow_mut() = task_indices(&checkpoint.completed)?;
+            *self.skipped_node_indices.borrow_mut() = task_indices(&checkpoint.skipped)?;
+            *self.selected_outputs.borrow_mut() = selected_outputs;
+            *self.remaining_iterations.borrow_mut() = remaining_iterations;
+            self.active_node_indices.borrow_mut().clear();
+
+            // The tasks that were running go first, then the tasks that were
+            // ready in the order that they became ready
+            let mut position_by_task_idx = HashMap::new();
+            for (position, id) in checkpoint
+                .active
+                .iter()
+                .chain(checkpoint.ready.values().flatten())
+                .enumerate()
+            {
+                position_by_task_idx
+                    .entry(task_idx(id)?)
+                    .or_insert(position);
+            }
+            self.update_complete_tensors();
+            self.initialize_scheduler_state();
+
+            let mut ready: Vec<usize> = self
+                .ready_nodes_per_pe
+                .borrow()
+                .values()
+                .flatten()
+                .copied()
+                .collect();
+            let mut ready_sequence = self.ready_sequence.borrow_mut();
+            ready.sort_by_key(|idx| {
+                let position = position_by_task_idx.get(idx).copied();
+                (position.unwrap_or(usize::MAX), ready_sequence[*idx])
+            });
+            for (sequence, idx) in (0..).zip(&ready) {
+                ready_sequence[*idx] = sequence;
+            }
+            self.next_ready_sequence.set(ready.len() as u64);
+        }
+        for idx in 0..self.nodes.len() {
+            self.update_condition(idx)?;
+        }
+        Ok(self)
+    }
+
+    /// Returns the number of runs of tasks that were checked against their
+    /// deadlines and the runs that missed them
+    pub fn deadline_report(&self) -> Result<DeadlineReport, SimError> {
+        if self.clock.is_none() {
+            return sim_error!("Tasks are only timed by a timetable created with a clock");
+        }
+        Ok(DeadlineReport {
+            num_checked: self.num_deadlines_checked.get(),
+            misses: self.deadline_misses.borrow().clone(),
+        })
+    }
+
+    /// Returns the time that each PE spent running tasks, stalled waiting for
+    /// its tasks to be ready and idle after its last task, up to now. PEs
+    /// that ran no tasks are idle throughout.
+    pub fn utilization_report(&self) -> Result<UtilizationReport, SimError> {
+        let Some(clock) = &self.clock else {
+            return sim_error!("Tasks are only timed by a timetable created with a clock");
+        };
+        let mut runs: BTreeMap<String, Vec<(f64, f64)>> = self
+            .platform
+            .pe_names()
+            .into_iter()
+            .map(|pe| (pe, Vec::new()))
+            .collect();
+        for (task_idx, start_ns, end_ns) in self.executions.borrow().iter() {
+            let Some(pe_idx) = self.node_pe_indices[*task_idx] else {
+                continue;
+            };
+            runs.entry(self.pe_names[&pe_idx].clone())
+                .or_default()
+                .push((*start_ns, *end_ns));
+        }
+        let total_ns = clock.time_now_ns();
+        Ok(UtilizationReport {
+            total_ns,
+            pes: runs
+                .into_iter()
+                .map(|(pe, runs)| PeUtilization::new(&pe, runs, total_ns))
+                .collect(),
+        })
+    }
+
+    /// Returns the peak occupancy of the SRAM of each PE and of each memory
+    /// that holds the buffers of tasks
+    #[must_use]
+    pub fn buffer_report(&self) -> BufferReport {
+        self.buffers.report()
+    }
+
+    /// Returns the IDs of the streams of the timetable
+    #[must_use]
+    pub fn stream_ids(&self) -> Vec<&str> {
+        self.streams
+            .iter()
+            .map(|stream| stream.id.as_str())
+            .collect()
+    }
+
+    /// Returns the time at which the last task of a stream completed, or
+    /// `None` if none of its tasks has run
+    pub fn stream_end_ns(&self, stream_id: &str) -> Result<Option<f64>, SimError> {
+        if self.clock.is_none() {
+            return sim_error!("Tasks are only timed by a timetable created with a clock");
+        }
+        let Some(stream_idx) = self.streams.iter().position(|s| s.id == stream_id) else {
+            return sim_error!("Unknown stream '{stream_id}'");
+        };
+        let task_times = self.task_times.borrow();
+        Ok(self
+            .task_streams
+            .iter()
+            .zip(task_times.iter())
+            .filter(|(task_stream, _)| **task_stream == Some(stream_idx))
+            .filter_map(|(_, (_, end_ns))| *end_ns)
+            .reduce(f64::max))
+    }
+
+    /// Returns the number of tasks on the longest path from a task to the end
+    /// of the timetable, including the task itself
+    #[must_use]
+    pub fn critical_path_length(&self, task_idx: usize) -> usize {
+        self.critical_path_lengths[task_idx]
+    }
+
+    /// Returns the number of times that a task runs
+    #[must_use]
+    pub fn task_iterations(&self, task_idx: usize) -> usize {
+        self.loop_indices[task_idx].map_or(1, |loop_idx| self.loops[loop_idx].iterations)
+    }
+
+    /// Returns whether a task or tensor is held back from a successor outside
+    /// its loop because the loop has iterations left to run
+    fn is_held(&self, idx: usize, successor_idx: usize) -> bool {
+        match self.loop_indices[idx] {
+            Some(loop_idx) => {
+                self.remaining_iterations.borrow()[loop_idx] > 1
+                    && self.loop_indices[successor_idx] != Some(loop_idx)
+            }
+            None => false,
+        }
+    }
+
+    /// Start the next iteration of the loop of a task if all of its members
+    /// have completed
+    fn repeat_loop(&self, task_idx: usize) -> SimResult {
+        let Some(loop_idx) = self.loop_indices[task_idx] else {
+            return Ok(());
+        };
+        let members = &self.loops[loop_idx].members;
+        {
+            let mut remaining_iterations = self.remaining_iterations.borrow_mut();
+            let completed_node_indices = self.completed_node_indices.borrow();
+            if remaining_iterations[loop_idx] <= 1
+                || !members
+                    .iter()
+                    .all(|idx| completed_node_indices.contains(idx))
+            {
+                return Ok(());
+            }
+            remaining_iterations[loop_idx] -= 1;
+        }
+        debug!(self.entity; "loop {}: next iteration", self.loops[loop_idx].id);
+
+        self.completed_node_indices
+            .borrow_mut()
+            .retain(|idx| !members.contains(idx));
+        self.skipped_node_indices
+            .borrow_mut()
+            .retain(|idx| !members.contains(idx));
+        self.selected_outputs
+            .borrow_mut()
+            .retain(|idx, _| !members.contains(idx));
+        for idx in members {
+            let Some(pe_idx) = self.node_pe_indices[*idx] else {
+                continue;
+            };
+            let unresolved_inputs = self.unresolved_inputs(*idx);
+            self.unresolved_input_counts.borrow_mut()[*idx] = unresolved_inputs;
+            if unresolved_inputs == 0 {
+                self.release_task(pe_idx, *idx)?;
+            }
+        }
+        for idx in members {
+            if self.node_pe_indices[*idx].is_none() && self.update_complete_tensor(*idx) {
+                self.mark_successors_updated(*idx)?;
+            }
+        }
+        for idx in members {
+            self.update_condition(*idx)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of inputs that a task is still waiting for
+    fn unresolved_inputs(&self, task_idx: usize) -> usize {
+        let completed_node_indices = self.completed_node_indices.borrow();
+        self.task_inputs(task_idx)
+            .into_iter()
+            .filter(|input_idx| {
+                !completed_node_indices.contains(input_idx) || self.is_held(*input_idx, task_idx)
+            })
+            .count()
+    }
+
+    /// Returns the time by which a task should complete, if it has a
+    /// deadline. A relative deadline is from the start of the graph of the
+    /// task, or from time zero until the graph has started.
+    fn task_deadline_ns(&self, task_idx: usize) -> Option<f64> {
+        match self.nodes.get(task_idx)?.node_section.deadline()? {
+            Deadline::Absolute(deadline_ns) => Some(deadline_ns as f64),
+            Deadline::Relative(deadline_ns) => {
+                let start_ns = self
+                    .graph_start_ns
+                    .borrow()
+                    .get(&self.task_streams[task_idx])
+                    .copied()
+                    .unwrap_or(0.0);
+                Some(start_ns + deadline_ns as f64)
+            }
+        }
+    }
+
+    /// Count a run of a task against its deadline, if it has one, and act on
+    /// a miss
+    fn check_deadline(&self, task_idx: usize, end_ns: f64) -> SimResult {
+        let Some(deadline_ns) = self.task_deadline_ns(task_idx) else {
+            return Ok(());
+        };
+        self.num_deadlines_checked
+            .set(self.num_deadlines_checked.get() + 1);
+        if end_ns <= deadline_ns {
+            return Ok(());
+        }
+
+        let task = self.task_id(task_idx);
+        self.deadline_misses.borrow_mut().push(DeadlineMiss {
+            task: task.to_string(),
+            deadline_ns,
+            end_ns,
+        });
+        match self.deadline_action {
+            DeadlineAction::Warn => {
+                warn!(self.entity; "task {task} completed at {end_ns}ns, after its deadline of {deadline_ns}ns");
+            }
+            DeadlineAction::Record => {}
+            DeadlineAction::Fail => {
+                return sim_error!(
+                    "Task '{task}' completed at {end_ns}ns, after its deadline of {deadline_ns}ns"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the priority of the stream of a task
+    fn stream_priority(&self, task_idx: usize) -> usize {
+        self.task_streams[task_idx].map_or(0, |stream_idx| self.streams[stream_idx].priority)
+    }
+
+    /// Order ready tasks, which are given in the order of their indices,
+    /// according to the scheduling policy
+    fn order_ready_tasks(&self, task_indices: &mut [usize]) {
+        match self.scheduling_policy {
+            // Ordered by priority below
+            SchedulingPolicy::Priority => {}
+            SchedulingPolicy::Fifo => {
+                let ready_sequence = self.ready_sequence.borrow();
+                task_indices.sort_by_key(|idx| ready_sequence[*idx]);
+            }
+            SchedulingPolicy::CriticalPath => {
+                task_indices.sort_by_key(|idx| std::cmp::Reverse(self.critical_path_lengths[*idx]));
+            }
+            SchedulingPolicy::EarliestDeadline => {
+                // Tasks without a deadline go last
+                task_indices.sort_by(|a, b| {
+                    let deadline_ns =
+                        |idx: &usize| self.task_deadline_ns(*idx).unwrap_or(f64::INFINITY);
+                    deadline_ns(a).total_cmp(&deadline_ns(b))
+                });
+            }
+        }
+        // Higher priority streams go first under every policy, which the sort
+        // keeps the order of the policy within
+        task_indices.sort_by_key(|idx| std::cmp::Reverse(self.task_priority(*idx)));
+    }
+
+    /// Record that a task has become ready
+    fn set_task_ready(
+        &self,
+        ready_nodes_per_pe: &mut HashMap<usize, BTreeSet<usize>>,
+        pe_idx: usize,
+        task_idx: usize,
+    ) {
+        let sequence = self.next_ready_sequence.get();
+        self.next_ready_sequence.set(sequence + 1);
+        self.ready_sequence.borrow_mut()[task_idx] = sequence;
+        ready_nodes_per_pe
+            .entry(pe_idx)
+            .or_default()
+            .insert(task_idx);
+    }
+
     fn make_tensor_view(
         tensor_config: &TensorConfigSection,
         view: Option<&TensorViewSection>,
@@ -297,7 +1126,9 @@ impl Timetable {
     }
 
     fn validate(&self) -> SimResult {
-        for node in &self.nodes {
+        // The bytes sent and received between each pair of PEs
+        let mut sent_bytes: BTreeMap<(&str, &str), (usize, usize)> = BTreeMap::new();
+        for (idx, node) in self.nodes.iter().enumerate() {
             match &node.node_section {
                 NodeSection::Memory { id, op, config, .. } => match op {
                     MemoryOp::Load => {
@@ -315,12 +1146,41 @@ impl Timetable {
                 } => {
                     self.validate_compute_node(node, id, input_views, output_views)?;
                 }
+                NodeSection::Dma { id, config, .. } => {
+                    self.validate_dma_node(id, node, config)?;
+                }
+                NodeSection::Send { id, pe, config, .. } => {
+                    let pe = self.validate_comm_node("Send", id, pe.as_deref(), &config.to)?;
+                    sent_bytes.entry((pe, &config.to)).or_default().0 +=
+                        config.bytes * self.task_iterations(idx);
+                }
+                NodeSection::Recv { id, pe, config, .. } => {
+                    let pe = self.validate_comm_node("Recv", id, pe.as_deref(), &config.from)?;
+                    sent_bytes.entry((&config.from, pe)).or_default().1 +=
+                        config.bytes * self.task_iterations(idx);
+                }
+                NodeSection::Condition { id, config } => {
+                    self.validate_condition_node(id, node, config)?;
+                }
+                NodeSection::Collective { id, .. } => {
+                    return sim_error!("Collective node '{id}' has not been expanded");
+                }
                 NodeSection::Tensor { .. } => {
                     // Nothing for now
                 }
             }
         }
 
+        // Data that is sent but never received, or waited for but never sent,
+        // would leave the timetable unable to complete
+        for ((from, to), (sent, received)) in sent_bytes {
+            if sent != received {
+                return sim_error!(
+                    "PE '{from}' sends {sent} bytes to PE '{to}' but '{to}' receives {received} bytes from '{from}'"
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -338,6 +1198,51 @@ impl Timetable {
         })?
     }
 
+    fn validate_condition_node(
+        &self,
+        id: &str,
+        node: &Node,
+        config: &ConditionConfigSection,
+    ) -> SimResult {
+        for input_idx in node.inputs.iter().flatten() {
+            if !matches!(
+                self.nodes[*input_idx].node_section,
+                NodeSection::Tensor { .. }
+            ) {
+                return sim_error!("Condition node '{id}' can only have inputs from Tensor nodes");
+            }
+        }
+        for output_idx in node.outputs.iter().flatten() {
+            if self.node_pe_indices[*output_idx].is_none() {
+                return sim_error!("Condition node '{id}' can only select nodes that run on a PE");
+            }
+        }
+
+        let num_outputs = node.outputs.len();
+        match config.predicate {
+            ConditionPredicate::Probability(p) => {
+                if !(0.0..=1.0).contains(&p) {
+                    return sim_error!(
+                        "Condition node '{id}' has probability {p} which is not between 0 and 1"
+                    );
+                }
+                if num_outputs > 2 {
+                    return sim_error!(
+                        "Condition node '{id}' has {num_outputs} outputs but a probability selects one of 2"
+                    );
+                }
+            }
+            ConditionPredicate::Select(output) => {
+                if output >= num_outputs {
+                    return sim_error!(
+                        "Condition node '{id}' selects output {output} but has {num_outputs} outputs"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn validate_compute_node(
         &self,
         node: &Node,
@@ -434,6 +1339,61 @@ impl Timetable {
         validate_access_in_range(id, "Store", store_config, config)
     }
 
+    /// Check that a Send or Recv node runs on a PE and communicates with a
+    /// different PE, returning the PE that it runs on
+    fn validate_comm_node<'a>(
+        &self,
+        kind: &str,
+        id: &str,
+        pe: Option<&'a str>,
+        peer: &str,
+    ) -> Result<&'a str, SimError> {
+        let Some(pe) = pe else {
+            return sim_error!("{kind} node '{id}' must run on a PE");
+        };
+        if self.platform.pe_idx_from_name(peer).is_err() {
+            return sim_error!("{kind} node '{id}' communicates with unknown PE '{peer}'");
+        }
+        if pe == peer {
+            return sim_error!("{kind} node '{id}' communicates with its own PE '{pe}'");
+        }
+        Ok(pe)
+    }
+
+    /// Returns the config of the tensor node connected to the only edge in
+    /// `edges`
+    fn get_single_tensor_config(&self, edges: &[Option<usize>]) -> Option<&TensorConfigSection> {
+        let [Some(tensor_idx)] = edges else {
+            return None;
+        };
+        match &self.nodes[*tensor_idx].node_section {
+            NodeSection::Tensor { config, .. } => Some(config),
+            _ => None,
+        }
+    }
+
+    fn validate_dma_node(&self, id: &str, dma_node: &Node, config: &DmaConfigSection) -> SimResult {
+        let Some(input_config) = self.get_single_tensor_config(&dma_node.inputs) else {
+            return sim_error!("DMA node '{id}' must be connected from a single Tensor node");
+        };
+        let Some(output_config) = self.get_single_tensor_config(&dma_node.outputs) else {
+            return sim_error!("DMA node '{id}' must be connected to a single Tensor node");
+        };
+        validate_view_in_range(id, "input", config.input_view.as_ref(), input_config)?;
+        validate_view_in_range(id, "output", config.output_view.as_ref(), output_config)?;
+
+        let (_, input_num_bytes) =
+            tensor_view_address_num_bytes(input_config, config.input_view.as_ref());
+        let (_, output_num_bytes) =
+            tensor_view_address_num_bytes(output_config, config.output_view.as_ref());
+        if input_num_bytes != output_num_bytes {
+            return sim_error!(
+                "DMA node '{id}' copies {input_num_bytes} bytes from its input but {output_num_bytes} bytes to its output"
+            );
+        }
+        Ok(())
+    }
+
     /// Check a given tensor index and move it if it is now complete.
     fn update_complete_tensor(&self, tensor_idx: usize) -> bool {
         let mut completed_node_indices = self.completed_node_indices.borrow_mut();
@@ -445,7 +1405,7 @@ impl Timetable {
 
         // Look for an input node that is not complete
         for idx in tensor_node.inputs.iter().flatten() {
-            if !completed_node_indices.contains(idx) {
+            if !completed_node_indices.contains(idx) || self.is_held(*idx, tensor_idx) {
                 return false;
             }
         }
@@ -453,6 +1413,9 @@ impl Timetable {
         // No active inputs remain, this is now complete
         self.active_node_indices.borrow_mut().remove(&tensor_idx);
         completed_node_indices.insert(tensor_idx);
+        if self.is_skipped(tensor_idx) {
+            self.skipped_node_indices.borrow_mut().insert(tensor_idx);
+        }
         true
     }
 
@@ -466,31 +1429,28 @@ impl Timetable {
     }
 
     fn initialize_scheduler_state(&self) {
-        let completed_node_indices = self.completed_node_indices.borrow();
-        let mut unresolved_input_counts = vec![0; self.nodes.len()];
+        *self.ready_sequence.borrow_mut() = vec![0; self.total_tasks()];
+        let mut unresolved_input_counts = vec![0; self.total_tasks()];
         let mut ready_nodes_per_pe: HashMap<usize, BTreeSet<usize>> = HashMap::new();
         let mut remaining_nodes_per_pe = HashMap::new();
 
         for (pe_idx, node_indices) in &self.nodes_per_pe {
             let mut remaining_nodes = 0;
             for node_idx in node_indices {
-                if completed_node_indices.contains(node_idx) {
+                // A task in a loop runs once more for each iteration left,
+                // less the current one if it has already completed it
+                let completed = self.completed_node_indices.borrow().contains(node_idx);
+                let runs = self.loop_indices[*node_idx]
+                    .map_or(1, |loop_idx| self.remaining_iterations.borrow()[loop_idx]);
+                remaining_nodes += runs - usize::from(completed);
+                if completed {
                     continue;
                 }
 
-                remaining_nodes += 1;
-                let unresolved_inputs = self.nodes[*node_idx]
-                    .inputs
-                    .iter()
-                    .flatten()
-                    .filter(|input_idx| !completed_node_indices.contains(input_idx))
-                    .count();
+                let unresolved_inputs = self.unresolved_inputs(*node_idx);
                 unresolved_input_counts[*node_idx] = unresolved_inputs;
                 if unresolved_inputs == 0 {
-                    ready_nodes_per_pe
-                        .entry(*pe_idx)
-                        .or_default()
-                        .insert(*node_idx);
+                    self.set_task_ready(&mut ready_nodes_per_pe, *pe_idx, *node_idx);
                 }
             }
             remaining_nodes_per_pe.insert(*pe_idx, remaining_nodes);
@@ -501,40 +1461,187 @@ impl Timetable {
         *self.remaining_nodes_per_pe.borrow_mut() = remaining_nodes_per_pe;
     }
 
-    fn mark_dependency_completed(&self, node_idx: usize) {
+    fn mark_dependency_completed(&self, node_idx: usize) -> SimResult {
         let Some(pe_idx) = self.node_pe_indices[node_idx] else {
-            return;
+            return self.update_condition(node_idx);
         };
         if self.completed_node_indices.borrow().contains(&node_idx)
             || self.active_node_indices.borrow().contains(&node_idx)
         {
-            return;
+            return Ok(());
+        }
+
+        {
+            let mut unresolved_input_counts = self.unresolved_input_counts.borrow_mut();
+            let unresolved_inputs = &mut unresolved_input_counts[node_idx];
+            if *unresolved_inputs == 0 {
+                return Ok(());
+            }
+            *unresolved_inputs -= 1;
+            if *unresolved_inputs != 0 {
+                return Ok(());
+            }
         }
+        self.release_task(pe_idx, node_idx)
+    }
 
-        let mut unresolved_input_counts = self.unresolved_input_counts.borrow_mut();
-        let unresolved_inputs = &mut unresolved_input_counts[node_idx];
-        if *unresolved_inputs == 0 {
-            return;
+    fn mark_successors_updated(&self, node_idx: usize) -> SimResult {
+        for output_node_idx in self.task_outputs(node_idx) {
+            if !self.is_held(node_idx, output_node_idx) {
+                self.mark_dependency_completed(output_node_idx)?;
+            }
         }
+        Ok(())
+    }
 
-        *unresolved_inputs -= 1;
-        if *unresolved_inputs == 0 {
-            self.ready_nodes_per_pe
+    /// Make a task that has no unresolved inputs ready, or complete it without
+    /// running it if it is on a path that was not taken
+    fn release_task(&self, pe_idx: usize, task_idx: usize) -> SimResult {
+        if self.is_skipped(task_idx) {
+            debug!(self.entity; "task{task_idx}: skipped");
+            self.skipped_node_indices.borrow_mut().insert(task_idx);
+            return self.set_task_completed(task_idx);
+        }
+        self.set_task_ready(&mut self.ready_nodes_per_pe.borrow_mut(), pe_idx, task_idx);
+        Ok(())
+    }
+
+    /// Returns whether an input of a node is on a path that was not taken,
+    /// either because it was skipped or because it is a Condition node that
+    /// selected another output
+    fn is_skipped_input(&self, input_idx: usize, node_idx: usize) -> bool {
+        if self.skipped_node_indices.borrow().contains(&input_idx) {
+            return true;
+        }
+        match self.selected_outputs.borrow().get(&input_idx) {
+            Some(output) => self.nodes[input_idx].outputs.get(*output) != Some(&Some(node_idx)),
+            None => false,
+        }
+    }
+
+    /// Returns whether a node is skipped, which it is when every input that is
+    /// produced while the timetable runs is on a path that was not taken.
+    ///
+    /// Tensors that are complete before the timetable starts do not decide
+    /// whether a node runs, so a node that only reads them always runs.
+    fn is_skipped(&self, node_idx: usize) -> bool {
+        let mut produced_inputs = self
+            .task_inputs(node_idx)
+            .into_iter()
+            .filter(|input_idx| match self.nodes.get(*input_idx) {
+                Some(node) => {
+                    !matches!(node.node_section, NodeSection::Tensor { .. })
+                        || node.inputs.iter().flatten().next().is_some()
+                }
+                None => true,
+            })
+            .peekable();
+        produced_inputs.peek().is_some()
+            && produced_inputs.all(|input_idx| self.is_skipped_input(input_idx, node_idx))
+    }
+
+    /// Evaluate a Condition node once all of its inputs have completed, and
+    /// release the nodes of the output that it selects
+    fn update_condition(&self, condition_idx: usize) -> SimResult {
+        let Some(NodeSection::Condition { id, config }) =
+            self.nodes.get(condition_idx).map(|node| &node.node_section)
+        else {
+            return Ok(());
+        };
+        if self.unresolved_inputs(condition_idx) != 0
+            || self
+                .completed_node_indices
+                .borrow()
+                .contains(&condition_idx)
+        {
+            return Ok(());
+        }
+
+        if self.is_skipped(condition_idx) {
+            debug!(self.entity; "condition {id}: skipped");
+            self.skipped_node_indices.borrow_mut().insert(condition_idx);
+        } else {
+            let output = match config.predicate {
+                ConditionPredicate::Probability(p) => {
+                    let mut condition_rngs = self.condition_rngs.borrow_mut();
+                    let rng = condition_rngs.get_mut(&condition_idx).unwrap();
+                    usize::from(!rng.random_bool(p))
+                }
+                ConditionPredicate::Select(output) => output,
+            };
+            debug!(self.entity; "condition {id}: selected output {output}");
+            self.selected_outputs
                 .borrow_mut()
-                .entry(pe_idx)
-                .or_default()
-                .insert(node_idx);
+                .insert(condition_idx, output);
+        }
+        self.completed_node_indices
+            .borrow_mut()
+            .insert(condition_idx);
+        self.mark_successors_updated(condition_idx)?;
+        self.repeat_loop(condition_idx)
+    }
+
+    /// Returns the transfer with the given task index
+    fn transfer(&self, task_idx: usize) -> Option<&Transfer> {
+        task_idx
+            .checked_sub(self.nodes.len())
+            .map(|transfer_idx| &self.transfers[transfer_idx])
+    }
+
+    /// Returns the task indices of the transfers that match a predicate
+    fn transfer_indices(&self, f: impl Fn(&Transfer) -> bool) -> impl Iterator<Item = usize> {
+        self.transfers
+            .iter()
+            .enumerate()
+            .filter(move |(_, transfer)| f(transfer))
+            .map(|(i, _)| self.nodes.len() + i)
+    }
+
+    /// Returns the indices of the tasks and tensors that a task waits for
+    fn task_inputs(&self, task_idx: usize) -> Vec<usize> {
+        match self.transfer(task_idx) {
+            Some(transfer) => vec![transfer.tensor_idx],
+            None => self.nodes[task_idx]
+                .inputs
+                .iter()
+                .flatten()
+                .chain(&self.nodes[task_idx].conditions)
+                .copied()
+                .chain(self.transfer_indices(|transfer| transfer.consumer_idx == task_idx))
+                .collect(),
         }
     }
 
-    fn mark_successors_updated(&self, node_idx: usize) {
-        for output_node_idx in self.nodes[node_idx].outputs.iter().flatten() {
-            self.mark_dependency_completed(*output_node_idx);
+    /// Returns the indices of the tasks and tensors that wait for a task
+    fn task_outputs(&self, task_idx: usize) -> Vec<usize> {
+        match self.transfer(task_idx) {
+            Some(transfer) => vec![transfer.consumer_idx],
+            None => self.nodes[task_idx]
+                .outputs
+                .iter()
+                .flatten()
+                .copied()
+                .chain(self.transfer_indices(|transfer| transfer.tensor_idx == task_idx))
+                .collect(),
         }
     }
 
+    /// Returns the number of tasks, including the transfers of the data
+    /// carried by edges
     pub fn total_tasks(&self) -> usize {
-        self.nodes.len()
+        self.nodes.len() + self.transfers.len()
+    }
+
+    #[must_use]
+    pub fn transfers(&self) -> &[Transfer] {
+        &self.transfers
+    }
+
+    /// Returns whether a node completed without running because it was on a
+    /// path that a Condition node did not select
+    #[must_use]
+    pub fn was_skipped(&self, node_idx: usize) -> bool {
+        self.skipped_node_indices.borrow().contains(&node_idx)
     }
 
     #[must_use]
@@ -550,12 +1657,25 @@ impl Timetable {
         // Note we assume that the graph has been validated so that we can simply unwrap
         // the result
         let tensor_config = self.get_tensor_node_config(memory_node).unwrap();
-        let offset_num_elements = tensor_view_offset(tensor_config, config.view.as_ref());
-        let view_num_elements = tensor_view_num_elements(tensor_config, config.view.as_ref());
-        let address =
-            tensor_config.addr + dtype_num_bytes(&tensor_config.dtype, offset_num_elements) as u64;
-        let num_bytes = dtype_num_bytes(&tensor_config.dtype, view_num_elements);
-        (address, num_bytes)
+        tensor_view_address_num_bytes(tensor_config, config.view.as_ref())
+    }
+
+    /// Returns the source address, destination address and number of bytes
+    /// copied by a DMA node
+    fn dma_copy_addresses_num_bytes(
+        &self,
+        dma_node: &Node,
+        config: &DmaConfigSection,
+    ) -> (u64, u64, usize) {
+        // Note we assume that the graph has been validated so that we can simply unwrap
+        // the result
+        let input_config = self.get_single_tensor_config(&dma_node.inputs).unwrap();
+        let output_config = self.get_single_tensor_config(&dma_node.outputs).unwrap();
+        let (src_addr, num_bytes) =
+            tensor_view_address_num_bytes(input_config, config.input_view.as_ref());
+        let (dst_addr, _) =
+            tensor_view_address_num_bytes(output_config, config.output_view.as_ref());
+        (src_addr, dst_addr, num_bytes)
     }
 
     pub fn get_input_output_tensors(&self, node_idx: usize) -> Result<InOutTensorViews, SimError> {
@@ -620,7 +1740,7 @@ impl Timetable {
         }
 
         let num_completed = self.completed_node_indices.borrow().len();
-        let num_tasks = self.nodes.len();
+        let num_tasks = self.total_tasks();
         if num_completed != num_tasks {
             return sim_error!(
                 "{num_completed} tasks completed out of a total of {num_tasks} tasks."
@@ -637,6 +1757,11 @@ impl Timetable {
         let mut num_compute_nodes = 0;
         let mut num_tensor_nodes = 0;
         let mut num_memory_nodes = 0;
+        let mut num_dma_nodes = 0;
+        let mut total_dma_bytes = 0;
+        let mut num_send_nodes = 0;
+        let mut num_condition_nodes = 0;
+        let mut total_send_bytes = 0;
         for (idx, node) in self.nodes.iter().enumerate() {
             match &node.node_section {
                 NodeSection::Memory { op, config, .. } => {
@@ -658,6 +1783,17 @@ impl Timetable {
                     }
                     num_compute_nodes += 1;
                 }
+                NodeSection::Dma { config, .. } => {
+                    let (_, _, num_bytes) = self.dma_copy_addresses_num_bytes(node, config);
+                    total_dma_bytes += num_bytes;
+                    num_dma_nodes += 1;
+                }
+                NodeSection::Send { config, .. } => {
+                    total_send_bytes += config.bytes;
+                    num_send_nodes += 1;
+                }
+                NodeSection::Recv { .. } | NodeSection::Collective { .. } => {}
+                NodeSection::Condition { .. } => num_condition_nodes += 1,
                 NodeSection::Tensor { .. } => num_tensor_nodes += 1,
             }
         }
@@ -667,6 +1803,25 @@ impl Timetable {
             "  {num_compute_nodes} compute nodes, {num_tensor_nodes} tensor nodes, {num_memory_nodes} memory nodes"
         );
         info!(self.entity ; "  loads {total_load_bytes} bytes, stores {total_store_bytes} bytes");
+        info!(self.entity ; "  {num_dma_nodes} dma nodes copying {total_dma_bytes} bytes");
+        info!(self.entity ; "  {num_send_nodes} send nodes sending {total_send_bytes} bytes");
+        info!(self.entity ;
+            "  {} transfers moving {} bytes",
+            self.transfers.len(),
+            self.transfers.iter().map(|t| t.num_bytes).sum::<usize>()
+        );
+        info!(self.entity ;
+            "  {num_condition_nodes} condition nodes, {} nodes skipped",
+            self.skipped_node_indices.borrow().len()
+        );
+        info!(self.entity ;
+            "  {} loops, {} task runs in total",
+            self.loops.len(),
+            (0..self.total_tasks())
+                .filter(|idx| self.node_pe_indices[*idx].is_some())
+                .map(|idx| self.task_iterations(idx))
+                .sum::<usize>()
+        );
         info!(self.entity ;
             "  machine ops {} total, {} add, {} mul, {} compare",
             machine_ops.total(),
@@ -674,6 +1829,9 @@ impl Timetable {
             machine_ops.muls,
             machine_ops.compares
         );
+        if self.clock.is_some() {
+            log_stats(&self.entity, self.utilization_report()?);
+        }
 
         Ok(())
     }
@@ -688,7 +1846,9 @@ impl Timetable {
             .iter()
             .enumerate()
             .filter_map(|(idx, node)| match &node.node_section {
-                NodeSection::Compute { id, .. } | NodeSection::Tensor { id, .. } => {
+                NodeSection::Compute { id, .. }
+                | NodeSection::Condition { id, .. }
+                | NodeSection::Tensor { id, .. } => {
                     let status = if completed.contains(&idx) {
                         MermaidNodeStatus::Complete
                     } else if active.contains(&idx) {
@@ -698,7 +1858,11 @@ impl Timetable {
                     };
                     Some((id.clone(), status))
                 }
-                NodeSection::Memory { .. } => None,
+                NodeSection::Memory { .. }
+                | NodeSection::Dma { .. }
+                | NodeSection::Send { .. }
+                | NodeSection::Recv { .. }
+                | NodeSection::Collective { .. } => None,
             })
             .collect()
     }
@@ -733,6 +1897,18 @@ fn build_compute_task(
     }
 }
 
+fn build_dma_task(id: &str, op: DmaOp, src_addr: u64, dst_addr: u64, num_bytes: usize) -> Task {
+    Task::DmaTask {
+        config: DmaTaskConfig {
+            id: id.to_string(),
+            op,
+            src_addr,
+            dst_addr,
+            num_bytes,
+        },
+    }
+}
+
 fn build_memory_task(id: &str, op: MemoryOp, addr: u64, num_bytes: usize) -> Task {
     Task::MemoryTask {
         config: MemoryTaskConfig {
@@ -747,6 +1923,14 @@ fn build_memory_task(id: &str, op: MemoryOp, addr: u64, num_bytes: usize) -> Tas
 #[async_trait(?Send)]
 impl Dispatch for Timetable {
     fn task_by_id(&self, task_idx: usize) -> Result<Task, SimError> {
+        if let Some(transfer) = self.transfer(task_idx) {
+            return Ok(build_memory_task(
+                &transfer.id,
+                MemoryOp::Load,
+                transfer.addr,
+                transfer.num_bytes,
+            ));
+        }
         let node = &self.nodes[task_idx];
         match &node.node_section {
             NodeSection::Compute { id, op, .. } => {
@@ -757,12 +1941,92 @@ impl Dispatch for Timetable {
                 let (address, num_bytes) = self.memory_access_address_num_bytes(node, config);
                 Ok(build_memory_task(id, *op, address, num_bytes))
             }
+            NodeSection::Dma { id, op, config, .. } => {
+                let (src_addr, dst_addr, num_bytes) =
+                    self.dma_copy_addresses_num_bytes(node, config);
+                Ok(build_dma_task(id, *op, src_addr, dst_addr, num_bytes))
+            }
+            NodeSection::Send { id, config, .. } => Ok(Task::SendTask {
+                config: SendTaskConfig {
+                    id: id.clone(),
+                    dst_device: self.platform.device_id(&config.to)?,
+                    num_bytes: config.bytes,
+                },
+            }),
+            NodeSection::Recv { id, config, .. } => Ok(Task::RecvTask {
+                config: RecvTaskConfig {
+                    id: id.clone(),
+                    src_device: self.platform.device_id(&config.from)?,
+                    num_bytes: config.bytes,
+                },
+            }),
+            NodeSection::Condition { .. } => {
+                sim_error!("Task Index {task_idx} refers to a Condition node")
+            }
+            NodeSection::Collective { .. } => {
+                sim_error!("Task Index {task_idx} refers to a Collective node")
+            }
             NodeSection::Tensor { .. } => {
                 sim_error!("Task Index {task_idx} refers to a Tensor node")
             }
         }
     }
 
+    fn task_priority(&self, task_idx: usize) -> usize {
+        let stream_priority = self.stream_priority(task_idx);
+        if self.scheduling_policy != SchedulingPolicy::Priority {
+            // The PE orders tasks by priority, so the tasks of a stream have
+            // the same priority to keep the order of the policy
+            return stream_priority;
+        }
+        let node_priority = match self.nodes.get(task_idx).map(|node| &node.node_section) {
+            Some(NodeSection::Compute { priority, .. }) => priority.unwrap_or(0),
+            _ => 0,
+        };
+        stream_priority * self.num_node_priorities + node_priority
+    }
+
+    fn compute_ticks(&self, task_idx: usize) -> Result<Option<usize>, SimError> {
+        let Some(cost_model) = &self.cost_model else {
+            return Ok(None);
+        };
+        let Some(node) = self.nodes.get(task_idx) else {
+            return Ok(None);
+        };
+        let Some(pe) = node.node_section.pe() else {
+            return Ok(None);
+        };
+        let config = self.platform.config();
+        let pe_config = config
+            .processing_elements
+            .iter()
+            .flatten()
+            .find(|section| &section.name == pe)
+            .map(|section| &section.config)
+            .or_else(|| {
+                config
+                    .accelerators
+                    .iter()
+                    .flatten()
+                    .find(|section| &section.name == pe)
+                    .map(|section| &section.config)
+            });
+        let Some(pe_config) = pe_config else {
+            return sim_error!(
+                "No configuration for PE '{pe}' of node '{}'",
+                node.node_section.id()
+            );
+        };
+        let (inputs, outputs) = self.get_input_output_tensors(task_idx)?;
+        cost_model(&TaskCostContext {
+            node: &node.node_section,
+            inputs: &inputs,
+            outputs: &outputs,
+            pe,
+            pe_config,
+        })
+    }
+
     fn set_task_active(&self, node_idx: usize) -> SimResult {
         debug!(self.entity; "task{node_idx}: active");
         if let Some(pe_idx) = self.node_pe_indices[node_idx] {
@@ -772,6 +2036,25 @@ impl Dispatch for Timetable {
                 .or_default()
                 .remove(&node_idx);
         }
+        if let Some(pe_idx) = self.node_pe_indices[node_idx] {
+            self.pe_task_lanes
+                .borrow_mut()
+                .entry(pe_idx)
+                .or_insert_with(|| PeTaskLanes::new(&self.entity, &self.pe_names[&pe_idx]))
+                .begin(node_idx, self.task_id(node_idx));
+        }
+        if let Some(clock) = &self.clock {
+            let now_ns = clock.time_now_ns();
+            self.task_times.borrow_mut()[node_idx]
+                .0
+                .get_or_insert(now_ns);
+            self.execution_starts.borrow_mut().insert(node_idx, now_ns);
+            self.graph_start_ns
+                .borrow_mut()
+                .entry(self.task_streams[node_idx])
+                .or_insert(now_ns);
+        }
+        self.buffers.acquire(node_idx, self.task_id(node_idx))?;
         self.active_node_indices.borrow_mut().insert(node_idx);
         self.ready_nodes_changed.notify();
         Ok(())
@@ -784,7 +2067,6 @@ impl Dispatch for Timetable {
             return Ok(());
         }
 
-        let node = &self.nodes[node_idx];
         if let Some(pe_idx) = self.node_pe_indices[node_idx] {
             self.ready_nodes_per_pe
                 .borrow_mut()
@@ -801,15 +2083,50 @@ impl Dispatch for Timetable {
             }
             *remaining_nodes -= 1;
         }
-        self.active_node_indices.borrow_mut().remove(&node_idx);
+        if let Some(pe_idx) = self.node_pe_indices[node_idx]
+            && let Some(lanes) = self.pe_task_lanes.borrow_mut().get_mut(&pe_idx)
+        {
+            lanes.end(node_idx);
+        }
+        if let Some(clock) = &self.clock {
+            let now_ns = clock.time_now_ns();
+            let times = &mut self.task_times.borrow_mut()[node_idx];
+            if times.0.is_some() {
+                times.1 = Some(now_ns);
+            }
+            let start_ns = self.execution_starts.borrow_mut().remove(&node_idx);
+            if let Some(start_ns) = start_ns {
+                self.executions
+                    .borrow_mut()
+                    .push((node_idx, start_ns, now_ns));
+                self.check_deadline(node_idx, now_ns)?;
+            }
+        }
+        if self.active_node_indices.borrow_mut().remove(&node_idx) {
+            self.buffers.release(node_idx);
+        }
         self.completed_node_indices.borrow_mut().insert(node_idx);
-        self.mark_successors_updated(node_idx);
+        self.mark_successors_updated(node_idx)?;
 
+        let Some(node) = self.nodes.get(node_idx) else {
+            // Transfers have no output tensors
+            self.repeat_loop(node_idx)?;
+            self.ready_nodes_changed.notify();
+            return Ok(());
+        };
         match node.node_section {
-            NodeSection::Compute { .. } => {
-                for tensor_node_idx in node.outputs.iter().flatten() {
+            NodeSection::Compute { .. }
+            | NodeSection::Dma { .. }
+            | NodeSection::Send { .. }
+            | NodeSection::Recv { .. } => {
+                // Control edges can also lead to tasks, which complete by
+                // running
+                let tensor_node_indices = node.outputs.iter().flatten().filter(|idx| {
+                    matches!(self.nodes[**idx].node_section, NodeSection::Tensor { .. })
+                });
+                for tensor_node_idx in tensor_node_indices {
                     if self.update_complete_tensor(*tensor_node_idx) {
-                        self.mark_successors_updated(*tensor_node_idx);
+                        self.mark_successors_updated(*tensor_node_idx)?;
                     }
                 }
             }
@@ -818,13 +2135,16 @@ impl Dispatch for Timetable {
                     // Only stores are completing their output tensors
                     let tensor_node_idx = node.get_output_tensor_node_idx().unwrap();
                     if self.update_complete_tensor(tensor_node_idx) {
-                        self.mark_successors_updated(tensor_node_idx);
+                        self.mark_successors_updated(tensor_node_idx)?;
                     }
                 }
             }
-            NodeSection::Tensor { .. } => {}
+            NodeSection::Condition { .. }
+            | NodeSection::Collective { .. }
+            | NodeSection::Tensor { .. } => {}
         }
 
+        self.repeat_loop(node_idx)?;
         self.ready_nodes_changed.notify();
         Ok(())
     }
@@ -839,12 +2159,16 @@ impl Dispatch for Timetable {
             .copied()
             .unwrap_or_default()
             == 0;
-        let ready_node_indices = self
+        let mut ready_node_indices: Vec<usize> = self
             .ready_nodes_per_pe
             .borrow()
             .get(&pe_idx)
             .map(|nodes| nodes.iter().copied().collect())
             .unwrap_or_default();
+        self.order_ready_tasks(&mut ready_node_indices);
+        if self.capacity_policy == CapacityPolicy::Stall {
+            self.buffers.retain_fitting(&mut ready_node_indices);
+        }
 
         debug!(self.entity; "PE {pe_id}: done: {pe_done}, ready indices: {ready_node_indices:?}");
         Ok((pe_done, ready_node_indices))
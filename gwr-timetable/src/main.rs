@@ -6,16 +6,27 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use gwr_engine::engine::Engine;
+use gwr_engine::events::once::Once;
 use gwr_engine::executor::Spawner;
 use gwr_engine::time::clock::Clock;
 use gwr_models::processing_element::dispatch::Dispatch;
 use gwr_platform::Platform;
+use gwr_platform::format::PlatformFormat;
+use gwr_platform::overrides::Override;
+use gwr_platform::template::env_variables;
 use gwr_timetable::Timetable;
+use gwr_timetable::buffers::CapacityPolicy;
+use gwr_timetable::checkpoint::TimetableCheckpoint;
+use gwr_timetable::deadlines::DeadlineAction;
+use gwr_timetable::mapping::MappingPolicy;
+use gwr_timetable::scheduling::SchedulingPolicy;
+use gwr_timetable::streams::ConcurrentGraph;
 use gwr_timetable::timetable_file::TimetableFile;
 use gwr_track::Track;
-use gwr_track::builder::{TrackerArgs, setup_trackers};
+use gwr_track::builder::{TrackerArgs, setup_trackers_from_args};
+use gwr_track::metadata::RunMetadata;
 use indicatif::ProgressBar;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -37,14 +48,73 @@ struct Cli {
     #[arg(long, default_value = "1000")]
     progress_ticks: usize,
 
-    /// Timetable YAML file
+    /// Timetable YAML file, a JSON or Graphviz DOT file with a `.json`, `.dot` or `.gv`
+    /// extension, or an ONNX model with a `.onnx` extension. Give more than one to run them at the same time, as streams
+    /// named after their files.
     #[arg(long, default_value = "timetable.yaml")]
-    timetable: PathBuf,
+    timetable: Vec<PathBuf>,
+
+    /// Priority of the stream of each timetable, in the order that they are given,
+    /// when more than one is run. Streams are given priority 0 by default.
+    #[arg(long)]
+    priority: Vec<usize>,
 
     /// Platform YAML file
     #[arg(long, default_value = "platform.yaml")]
     platform: PathBuf,
 
+    /// Override a value in the platform file, e.g. `--set pe0.config.lsu_access_bytes=64` or `--set variables.num_pes=8`
+    #[arg(long = "set", value_name = "PATH=VALUE")]
+    overrides: Vec<Override>,
+
+    /// Format of the platform file (detected from its extension by default)
+    #[arg(long, value_enum)]
+    platform_format: Option<PlatformFormat>,
+
+    /// How the nodes that are not given a PE are assigned one
+    #[arg(long, value_enum, default_value_t)]
+    mapping_policy: MappingPolicy,
+
+    /// The order in which the ready tasks of each PE are started
+    #[arg(long, value_enum, default_value_t)]
+    scheduling_policy: SchedulingPolicy,
+
+    /// Whether a task that there is not enough space for the buffers of fails
+    /// the run or waits for space to be freed
+    #[arg(long, value_enum, default_value_t)]
+    capacity_policy: CapacityPolicy,
+
+    /// What happens when a task completes after its deadline
+    #[arg(long, value_enum, default_value_t)]
+    deadline_action: DeadlineAction,
+
+    /// Check the platform and timetable without running the simulation
+    #[arg(long, default_value = "false")]
+    check: bool,
+
+    /// Print the critical path of the timetable and the slack of each task
+    /// after the run
+    #[arg(long, default_value = "false")]
+    critical_path: bool,
+
+    /// Write the tasks that each PE executed, with their start and end times,
+    /// to this JSON file after the run
+    #[arg(long)]
+    gantt: Option<PathBuf>,
+
+    /// Write a checkpoint of the progress of the timetable to this JSON file
+    /// once the run stops
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// Stop the run after this number of ticks to write the checkpoint
+    #[arg(long, requires = "checkpoint")]
+    checkpoint_ticks: Option<u64>,
+
+    /// Resume the timetable from a checkpoint written by an earlier run
+    #[arg(long)]
+    resume: Option<PathBuf>,
+
     /// Enable dumping of summary statistics
     #[arg(long, default_value = "false")]
     dump_stats: bool,
@@ -92,32 +162,98 @@ fn write_error_mermaid(timetable: &Timetable, path: &Path) {
     }
 }
 
+/// Load a timetable, or combine several to run at the same time
+fn load_timetables(paths: &[PathBuf], priorities: &[usize]) -> Result<TimetableFile> {
+    if priorities.len() > paths.len() {
+        return Err(format!(
+            "{} priorities given for {} timetables",
+            priorities.len(),
+            paths.len()
+        )
+        .into());
+    }
+    if let [path] = paths
+        && priorities.is_empty()
+    {
+        return Ok(TimetableFile::from_file(path)?);
+    }
+
+    let mut graphs = Vec::with_capacity(paths.len());
+    for (i, path) in paths.iter().enumerate() {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().replace('.', "_"))
+            .unwrap_or_else(|| format!("graph{i}"));
+        let graph = ConcurrentGraph::new(&name, TimetableFile::from_file(path)?)
+            .with_priority(priorities.get(i).copied().unwrap_or(0));
+        graphs.push(graph);
+    }
+    Ok(TimetableFile::concurrent(graphs)?)
+}
+
 fn main() -> Result<()> {
-    let mut args = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    args.tracker.load_track_config(&matches)?;
     args.tracker
         .ensure_visiblity(args.dump_stats, "--dump-stats", log::Level::Info);
+    // Variables set in the environment are applied before the overrides so
+    // that the command line takes precedence
+    args.overrides.splice(0..0, env_variables()?);
 
-    let tracker: Rc<dyn Track> = setup_trackers(&args.tracker.trackers_config()).unwrap();
+    let tracker: Rc<dyn Track> = setup_trackers_from_args(&mut args.tracker, &matches)?;
+    let mut metadata = RunMetadata::from_process()
+        .with_git_describe()
+        .with_file_hash("platform", &args.platform)?;
+    for path in &args.timetable {
+        metadata = metadata.with_file_hash("timetable", path)?;
+    }
+    metadata.record(&tracker);
     let mut engine = Engine::new(&tracker);
     let clock = engine.default_clock();
-    let platform = Rc::new(Platform::from_file(
+    let platform_format = args
+        .platform_format
+        .unwrap_or_else(|| PlatformFormat::from_path(&args.platform));
+    let platform = Rc::new(Platform::from_file_as(
         &engine,
         &clock,
         Path::new(&args.platform),
+        platform_format,
+        &args.overrides,
     )?);
 
     println!("Loaded platform:\n{platform}");
 
-    let timetable_file = TimetableFile::from_file(&args.timetable)?;
+    let mut timetable_file = load_timetables(&args.timetable, &args.priority)?;
+    let mapping = timetable_file.map_to_pes(&platform, args.mapping_policy)?;
+    if !mapping.is_empty() {
+        print!("{mapping}");
+    }
     let num_nodes = timetable_file.nodes.len();
     let num_edges = timetable_file.edges.len();
 
-    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform)?);
+    let mut timetable = Timetable::new(engine.top(), timetable_file, &platform)?
+        .with_scheduling_policy(args.scheduling_policy)
+        .with_capacity_policy(args.capacity_policy)
+        .with_deadline_action(args.deadline_action)
+        .with_clock(&clock);
+    if let Some(path) = &args.resume {
+        let checkpoint = TimetableCheckpoint::from_file(path)?;
+        println!("Resuming from {checkpoint}");
+        timetable = timetable.with_checkpoint(&checkpoint)?;
+    }
+    let timetable = Rc::new(timetable);
     let dispatcher: Rc<dyn Dispatch> = timetable.clone();
     platform.attach_dispatcher(&dispatcher);
 
     println!("Loaded timetable with {num_nodes} nodes, {num_edges} edges.");
 
+    if args.check {
+        platform.check()?;
+        println!("Checked platform and timetable.");
+        return Ok(());
+    }
+
     let mut progress_bar = None;
     if args.progress {
         let total_expected_tasks = timetable.total_tasks();
@@ -133,7 +269,27 @@ fn main() -> Result<()> {
         );
     }
 
-    let run_result = engine.run();
+    let run_result = match args.checkpoint_ticks {
+        Some(ticks) => {
+            let stop = Once::with_value(());
+            {
+                let clock = clock.clone();
+                let stop = stop.clone();
+                engine.spawn(async move {
+                    clock.wait_ticks_or_exit(ticks).await;
+                    stop.notify()
+                });
+            }
+            engine.run_until(Box::new(stop))
+        }
+        None => engine.run(),
+    };
+
+    if let Some(path) = &args.checkpoint {
+        let checkpoint = timetable.checkpoint();
+        fs::write(path, checkpoint.to_json())?;
+        println!("Wrote {checkpoint} to '{}'", path.display());
+    }
 
     if let Some(progress_bar) = progress_bar {
         progress_bar.finish();
@@ -145,12 +301,39 @@ fn main() -> Result<()> {
     }
 
     println!("Ran simulation. Time now {}ns", clock.time_now_ns());
+    if args.checkpoint_ticks.is_some() && timetable.check_tasks_complete().is_err() {
+        return Ok(());
+    }
+    for stream_id in timetable.stream_ids() {
+        if let Some(end_ns) = timetable.stream_end_ns(stream_id)? {
+            println!("Stream {stream_id} finished at {end_ns}ns");
+        }
+    }
 
     if let Err(err) = timetable.check_tasks_complete() {
         write_error_mermaid(&timetable, &args.error_mermaid);
         return Err(err.into());
     }
 
+    let deadline_report = timetable.deadline_report()?;
+    if deadline_report.num_checked > 0 {
+        print!("{deadline_report}");
+    }
+
+    let buffer_report = timetable.buffer_report();
+    if !buffer_report.is_empty() {
+        print!("{buffer_report}");
+    }
+
+    if args.critical_path {
+        print!("{}", timetable.critical_path_report()?);
+    }
+
+    if let Some(path) = &args.gantt {
+        fs::write(path, timetable.gantt_chart()?.to_json())?;
+        println!("Wrote Gantt chart to '{}'", path.display());
+    }
+
     if args.dump_stats {
         timetable.dump_stats()?;
         platform.dump_stats(clock.time_now_ns());
@@ -0,0 +1,224 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Policies for assigning the nodes of a timetable that are not given a `pe`
+//! to the PEs of a platform.
+//!
+//! Compute, Memory and DMA nodes can leave out their `pe`, and are then
+//! assigned one by [TimetableFile::map_to_pes] before the timetable is built.
+//! The [MappingPolicy] decides how:
+//!
+//!  - `round-robin`: each node in turn is given the next PE, which is the
+//!    default
+//!  - `load-balanced`: each node is given the PE with the least work so far
+//!  - `locality`: each node is given a PE in the same [group] as the memories
+//!    that hold its tensors, and the one with the least work of those
+//!
+//! The work of a node is the number of bytes of the tensors that it reads and
+//! writes, for each time that it runs. The work of the nodes that are given a
+//! PE in the timetable is counted, so the mapped nodes fill in around them.
+//! Send and Recv nodes name the PEs that they communicate with, so must be
+//! given a PE.
+//!
+//! [group]: gwr_platform::group
+
+use std::collections::HashMap;
+use std::fmt;
+
+use clap::ValueEnum;
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use gwr_platform::Platform;
+
+use crate::timetable_file::{NodeSection, TimetableFile};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum MappingPolicy {
+    #[default]
+    RoundRobin,
+    LoadBalanced,
+    Locality,
+}
+
+/// The PEs chosen for the nodes that were not given one
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeMapping {
+    /// The ID of each mapped node and its PE, in the order of the nodes
+    pub assignments: Vec<(String, String)>,
+}
+
+impl PeMapping {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// Returns the PE that a node was mapped to
+    #[must_use]
+    pub fn pe(&self, node_id: &str) -> Option<&str> {
+        self.assignments
+            .iter()
+            .find(|(id, _)| id == node_id)
+            .map(|(_, pe)| pe.as_str())
+    }
+}
+
+impl fmt::Display for PeMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Mapped {} nodes to PEs:", self.assignments.len())?;
+        for (node_id, pe) in &self.assignments {
+            writeln!(f, "  {node_id} -> {pe}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The work of each node, from the tensors on its edges
+fn node_work(timetable_file: &TimetableFile) -> HashMap<&str, usize> {
+    let tensor_bytes: HashMap<&str, usize> = timetable_file
+        .nodes
+        .iter()
+        .filter_map(|node| match node {
+            NodeSection::Tensor { id, config } => Some((id.as_str(), config.num_bytes())),
+            _ => None,
+        })
+        .collect();
+
+    let mut work: HashMap<&str, usize> = HashMap::new();
+    for edge in &timetable_file.edges {
+        let (from, to) = (edge.from_node_id(), edge.to_node_id());
+        if let Some(bytes) = tensor_bytes.get(from) {
+            *work.entry(to).or_default() += bytes;
+        }
+        if let Some(bytes) = tensor_bytes.get(to) {
+            *work.entry(from).or_default() += bytes;
+        }
+    }
+    for node in &timetable_file.nodes {
+        if let Some(node_work) = work.get_mut(node.id().as_str()) {
+            *node_work *= node.iterations().unwrap_or(1);
+        }
+    }
+    work
+}
+
+/// The addresses of the tensors on the edges of each node
+fn node_tensor_addrs(timetable_file: &TimetableFile) -> HashMap<&str, Vec<(u64, usize)>> {
+    let tensors: HashMap<&str, (u64, usize)> = timetable_file
+        .nodes
+        .iter()
+        .filter_map(|node| match node {
+            NodeSection::Tensor { id, config } => {
+                Some((id.as_str(), (config.addr, config.num_bytes())))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut addrs: HashMap<&str, Vec<(u64, usize)>> = HashMap::new();
+    for edge in &timetable_file.edges {
+        let (from, to) = (edge.from_node_id(), edge.to_node_id());
+        if let Some(tensor) = tensors.get(from) {
+            addrs.entry(to).or_default().push(*tensor);
+        }
+        if let Some(tensor) = tensors.get(to) {
+            addrs.entry(from).or_default().push(*tensor);
+        }
+    }
+    addrs
+}
+
+/// Returns the number of bytes of the tensors that are held by memories in
+/// the same group as a PE
+fn local_bytes(platform: &Platform, pe_name: &str, tensors: &[(u64, usize)]) -> usize {
+    let Some(pe_group) = platform.pe_group(pe_name) else {
+        return 0;
+    };
+    let Ok(address_map) = platform.pe_address_map(pe_name) else {
+        return 0;
+    };
+    tensors
+        .iter()
+        .filter(|(addr, _)| {
+            address_map.region_containing(*addr).is_some_and(|region| {
+                region.devices().iter().any(|(memory, _)| {
+                    platform
+                        .memory_group(memory)
+                        .is_some_and(|group| group.name() == pe_group.name())
+                })
+            })
+        })
+        .map(|(_, num_bytes)| num_bytes)
+        .sum()
+}
+
+/// Assign a PE to each Compute, Memory and DMA node that does not have one
+pub(crate) fn map_to_pes(
+    timetable_file: &mut TimetableFile,
+    platform: &Platform,
+    policy: MappingPolicy,
+) -> Result<PeMapping, SimError> {
+    let mut pe_names = platform.pe_names();
+    pe_names.sort_by_key(|pe_name| platform.pe_idx_from_name(pe_name).unwrap_or(usize::MAX));
+
+    let work = node_work(timetable_file);
+    let tensor_addrs = node_tensor_addrs(timetable_file);
+    let mut pe_work: HashMap<&str, usize> = HashMap::new();
+    let mut unmapped = Vec::new();
+    for (idx, node) in timetable_file.nodes.iter().enumerate() {
+        let node_work = work.get(node.id().as_str()).copied().unwrap_or(0);
+        match node {
+            NodeSection::Compute { pe: None, .. }
+            | NodeSection::Memory { pe: None, .. }
+            | NodeSection::Dma { pe: None, .. } => unmapped.push((idx, node_work)),
+            _ => {
+                if let Some(pe) = node.pe() {
+                    *pe_work.entry(pe.as_str()).or_default() += node_work;
+                }
+            }
+        }
+    }
+    if unmapped.is_empty() {
+        return Ok(PeMapping::default());
+    }
+    if pe_names.is_empty() {
+        return sim_error!(
+            "No PEs to map node '{}' to",
+            timetable_file.nodes[unmapped[0].0].id()
+        );
+    }
+
+    let mut pe_work: Vec<usize> = pe_names
+        .iter()
+        .map(|pe_name| pe_work.get(pe_name.as_str()).copied().unwrap_or(0))
+        .collect();
+    let mut chosen = Vec::with_capacity(unmapped.len());
+    for (i, (idx, node_work)) in unmapped.iter().enumerate() {
+        let least_work = |pe_idx: &usize| (pe_work[*pe_idx], *pe_idx);
+        let pe_idx = match policy {
+            MappingPolicy::RoundRobin => i % pe_names.len(),
+            MappingPolicy::LoadBalanced => (0..pe_names.len()).min_by_key(least_work).unwrap(),
+            MappingPolicy::Locality => {
+                let node_id = timetable_file.nodes[*idx].id().as_str();
+                let tensors = tensor_addrs.get(node_id).map_or(&[][..], Vec::as_slice);
+                (0..pe_names.len())
+                    .min_by_key(|pe_idx| {
+                        let local = local_bytes(platform, &pe_names[*pe_idx], tensors);
+                        (usize::MAX - local, least_work(pe_idx))
+                    })
+                    .unwrap()
+            }
+        };
+        pe_work[pe_idx] += node_work;
+        chosen.push((*idx, pe_idx));
+    }
+
+    let mut mapping = PeMapping::default();
+    for (idx, pe_idx) in chosen {
+        let node = &mut timetable_file.nodes[idx];
+        mapping
+            .assignments
+            .push((node.id().clone(), pe_names[pe_idx].clone()));
+        node.set_pe(&pe_names[pe_idx]);
+    }
+    Ok(mapping)
+}
@@ -6,7 +6,9 @@ use std::hash::BuildHasher;
 
 use gwr_models::processing_element::operators::shape_string;
 
-use crate::timetable_file::{EdgeSection, NodeSection, TensorConfigSection, TensorViewSection};
+use crate::timetable_file::{
+    ConditionPredicate, EdgeSection, NodeSection, TensorConfigSection, TensorViewSection,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MermaidNodeStatus {
@@ -83,6 +85,65 @@ fn render_node_label(node: &NodeSection) -> String {
                 ))
             )
         }
+        NodeSection::Dma { op, pe, config, .. } => {
+            let pe = pe.as_deref().unwrap_or("?");
+            let input_str = create_view_string("input", std::slice::from_ref(&config.input_view));
+            let output_str =
+                create_view_string("output", std::slice::from_ref(&config.output_view));
+            format!(
+                "[\"{}\"]",
+                escape_mermaid_label(&format!(
+                    "{input_str}\n{:?}\n{}\n{pe}\n\n{output_str}",
+                    op,
+                    node.id()
+                ))
+            )
+        }
+        NodeSection::Send { pe, config, .. } => {
+            let pe = pe.as_deref().unwrap_or("?");
+            format!(
+                "[\"{}\"]",
+                escape_mermaid_label(&format!(
+                    "Send\n{}\n{pe} -> {}\n{} bytes",
+                    node.id(),
+                    config.to,
+                    config.bytes
+                ))
+            )
+        }
+        NodeSection::Recv { pe, config, .. } => {
+            let pe = pe.as_deref().unwrap_or("?");
+            format!(
+                "[\"{}\"]",
+                escape_mermaid_label(&format!(
+                    "Recv\n{}\n{} -> {pe}\n{} bytes",
+                    node.id(),
+                    config.from,
+                    config.bytes
+                ))
+            )
+        }
+        NodeSection::Collective {
+            op, pes, config, ..
+        } => format!(
+            "[\"{}\"]",
+            escape_mermaid_label(&format!(
+                "{op:?}\n{}\n{}\n{} bytes",
+                node.id(),
+                pes.join(", "),
+                config.bytes
+            ))
+        ),
+        NodeSection::Condition { config, .. } => {
+            let predicate = match config.predicate {
+                ConditionPredicate::Probability(p) => format!("p = {p}"),
+                ConditionPredicate::Select(output) => format!("select {output}"),
+            };
+            format!(
+                "{{\"{}\"}}",
+                escape_mermaid_label(&format!("Condition\n{}\n{predicate}", node.id()))
+            )
+        }
         NodeSection::Memory { op, config, .. } => {
             let extra = match &config.view {
                 Some(view) => {
@@ -152,13 +213,19 @@ pub fn render_mermaid_from_parts<T: BuildHasher>(
                 Some(MermaidNodeStatus::Pending) => "tensorPending",
                 None => "tensor",
             },
-            NodeSection::Memory { .. } => "memory",
-            NodeSection::Compute { id, .. } => match statuses.get(id) {
-                Some(MermaidNodeStatus::Active) => "computeActive",
-                Some(MermaidNodeStatus::Complete) => "computeComplete",
-                Some(MermaidNodeStatus::Pending) => "computePending",
-                None => "compute",
-            },
+            NodeSection::Memory { .. }
+            | NodeSection::Dma { .. }
+            | NodeSection::Send { .. }
+            | NodeSection::Recv { .. }
+            | NodeSection::Collective { .. } => "memory",
+            NodeSection::Compute { id, .. } | NodeSection::Condition { id, .. } => {
+                match statuses.get(id) {
+                    Some(MermaidNodeStatus::Active) => "computeActive",
+                    Some(MermaidNodeStatus::Complete) => "computeComplete",
+                    Some(MermaidNodeStatus::Pending) => "computePending",
+                    None => "compute",
+                }
+            }
         };
         class_members
             .entry(class_name)
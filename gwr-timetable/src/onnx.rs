@@ -0,0 +1,556 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Import of [ONNX](https://onnx.ai) models as timetables.
+//!
+//! Each supported operator of the model's graph becomes a compute node and
+//! each tensor that they use becomes a tensor node, so a model can be run on
+//! candidate platforms without writing its timetable by hand:
+//!
+//!  - `MatMul` and `Gemm` become `gemm`
+//!  - `MaxPool` becomes `maxpool`, with the same attributes
+//!  - the element-wise `Add`, `Sub`, `Mul` and `Div` become `add`, as do the
+//!    element-wise activations such as `Relu`, which read their input twice
+//!
+//! Other operators are reported as errors. The compute nodes are not given a
+//! PE, so they are assigned one by [TimetableFile::map_to_pes], and their
+//! FLOPs and bytes are estimated from the shapes of their tensors.
+//!
+//! The shape of every tensor must be known, from the initializers, inputs,
+//! outputs and `value_info` of the graph or from the shapes of the inputs of
+//! the operator that produces it. Models can be prepared with ONNX shape
+//! inference. Dimensions that are named rather than sized, such as a batch
+//! dimension, are given the [batch size](OnnxImportOptions::with_batch_size).
+//! Only the size of a tensor matters to the simulation, so the inputs of a
+//! `Gemm` that are transposed are given the transposed shape, and a bias of
+//! fewer than 2 dimensions is given leading dimensions of 1.
+//!
+//! The tensors are placed one after another in memory, and the IDs of nodes
+//! are the ONNX names with any characters other than letters, digits and `_`
+//! replaced by `_`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use gwr_models::processing_element::operators::dtype::DataType;
+use gwr_models::processing_element::operators::maxpool::{AutoPad, OperatorMaxPool};
+use gwr_models::processing_element::operators::{Tensor, TensorView};
+use gwr_models::processing_element::task::ComputeOp;
+use gwr_onnx::Message;
+use gwr_onnx::protos::tensor_shape_proto::dimension;
+use gwr_onnx::protos::{AttributeProto, GraphProto, ModelProto, NodeProto, type_proto};
+
+use crate::timetable_file::{
+    EdgeKind, EdgeSection, NodeSection, TensorConfigSection, TimetableFile,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OnnxImportOptions {
+    batch_size: usize,
+    base_addr: u64,
+    alignment_bytes: u64,
+}
+
+impl Default for OnnxImportOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1,
+            base_addr: 0,
+            alignment_bytes: 64,
+        }
+    }
+}
+
+impl OnnxImportOptions {
+    /// Set the size of the dimensions that are named rather than sized, which
+    /// is 1 by default
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the address of the first tensor, which is 0 by default
+    #[must_use]
+    pub fn with_base_addr(mut self, base_addr: u64) -> Self {
+        self.base_addr = base_addr;
+        self
+    }
+
+    /// Set the alignment of the address of each tensor, which is 64 bytes by
+    /// default
+    #[must_use]
+    pub fn with_alignment_bytes(mut self, alignment_bytes: u64) -> Self {
+        self.alignment_bytes = alignment_bytes.max(1);
+        self
+    }
+}
+
+/// The estimated work of a compute node imported from ONNX
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComputeEstimate {
+    pub id: String,
+    pub op: &'static str,
+    pub flops: usize,
+    /// The number of bytes of the tensors that it reads and writes
+    pub bytes: usize,
+}
+
+#[derive(Debug)]
+pub struct OnnxImport {
+    pub timetable_file: TimetableFile,
+    /// The work of each compute node, in the order of the nodes of the model
+    pub estimates: Vec<ComputeEstimate>,
+}
+
+impl fmt::Display for OnnxImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total_flops: usize = self.estimates.iter().map(|e| e.flops).sum();
+        let total_bytes: usize = self.estimates.iter().map(|e| e.bytes).sum();
+        writeln!(
+            f,
+            "Imported {} ONNX nodes ({total_flops} FLOPs, {total_bytes} bytes):",
+            self.estimates.len()
+        )?;
+        for estimate in &self.estimates {
+            writeln!(
+                f,
+                "  {} ({}): {} FLOPs, {} bytes",
+                estimate.id, estimate.op, estimate.flops, estimate.bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the data type of an ONNX `TensorProto.DataType`
+fn data_type(elem_type: i32) -> Option<DataType> {
+    match elem_type {
+        1 => Some(DataType::Fp32),
+        2 | 3 | 9 => Some(DataType::Int8),
+        4 | 5 => Some(DataType::Int16),
+        6 | 12 => Some(DataType::Int32),
+        7 | 13 => Some(DataType::Int64),
+        10 => Some(DataType::Fp16),
+        16 => Some(DataType::Bf16),
+        17..=20 => Some(DataType::Fp8),
+        21 | 22 => Some(DataType::Int4),
+        23 => Some(DataType::Fp4),
+        _ => None,
+    }
+}
+
+/// How an ONNX operator is imported
+enum Operator {
+    Gemm { trans_a: bool, trans_b: bool },
+    MaxPool(OperatorMaxPool),
+    Binary,
+    Unary,
+}
+
+const UNARY_OPS: [&str; 12] = [
+    "Abs",
+    "Ceil",
+    "Elu",
+    "Erf",
+    "Exp",
+    "Floor",
+    "Gelu",
+    "LeakyRelu",
+    "Neg",
+    "Relu",
+    "Sigmoid",
+    "Tanh",
+];
+
+fn attribute<'a>(node: &'a NodeProto, name: &str) -> Option<&'a AttributeProto> {
+    node.attribute
+        .iter()
+        .find(|attribute| attribute.name() == name)
+}
+
+fn usize_ints(attribute: Option<&AttributeProto>) -> Option<Vec<usize>> {
+    attribute.map(|attribute| {
+        attribute
+            .ints
+            .iter()
+            .map(|i| usize::try_from(*i).unwrap_or(0))
+            .collect()
+    })
+}
+
+fn operator(node: &NodeProto, name: &str) -> Result<Operator, SimError> {
+    let flag = |name: &str| attribute(node, name).is_some_and(|attribute| attribute.i() != 0);
+    match node.op_type() {
+        "MatMul" => Ok(Operator::Gemm {
+            trans_a: false,
+            trans_b: false,
+        }),
+        "Gemm" => Ok(Operator::Gemm {
+            trans_a: flag("transA"),
+            trans_b: flag("transB"),
+        }),
+        "MaxPool" => {
+            let Some(kernel_shape) = usize_ints(attribute(node, "kernel_shape")) else {
+                return sim_error!("ONNX MaxPool node '{name}' has no kernel_shape");
+            };
+            let auto_pad = match attribute(node, "auto_pad").map(AttributeProto::s) {
+                None | Some(b"NOTSET" | b"") => None,
+                Some(b"VALID") => Some(AutoPad::Valid),
+                Some(b"SAME_UPPER") => Some(AutoPad::SameUpper),
+                Some(b"SAME_LOWER") => Some(AutoPad::SameLower),
+                Some(other) => {
+                    return sim_error!(
+                        "ONNX MaxPool node '{name}' has unknown auto_pad '{}'",
+                        String::from_utf8_lossy(other)
+                    );
+                }
+            };
+            Ok(Operator::MaxPool(OperatorMaxPool {
+                auto_pad,
+                ceil_mode: attribute(node, "ceil_mode").map(|a| a.i() != 0),
+                dilations: usize_ints(attribute(node, "dilations")),
+                kernel_shape,
+                pads: usize_ints(attribute(node, "pads")),
+                storage_order: None,
+                strides: usize_ints(attribute(node, "strides")),
+            }))
+        }
+        "Add" | "Sub" | "Mul" | "Div" => Ok(Operator::Binary),
+        op if UNARY_OPS.contains(&op) => Ok(Operator::Unary),
+        op => sim_error!("ONNX node '{name}' has op '{op}' which is not supported"),
+    }
+}
+
+/// Returns the shape of the result of an element-wise operation on two
+/// shapes, with the usual broadcasting
+fn broadcast(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    let rank = a.len().max(b.len());
+    let dim = |shape: &[usize], i: usize| {
+        (i + shape.len())
+            .checked_sub(rank)
+            .map_or(1, |idx| shape[idx])
+    };
+    (0..rank)
+        .map(|i| match (dim(a, i), dim(b, i)) {
+            (a, b) if a == b || b == 1 => Some(a),
+            (1, b) => Some(b),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Swap the last two dimensions of a shape
+fn transposed(mut shape: Vec<usize>) -> Vec<usize> {
+    let rank = shape.len();
+    if rank >= 2 {
+        shape.swap(rank - 2, rank - 1);
+    }
+    shape
+}
+
+/// Returns a node ID made from an ONNX name that is not yet used
+fn unique_id(name: &str, fallback: &str, used: &mut HashSet<String>) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if id.trim_matches('_').is_empty() {
+        id = fallback.to_string();
+    }
+    let mut unique = id.clone();
+    let mut suffix = 1;
+    while !used.insert(unique.clone()) {
+        unique = format!("{id}_{suffix}");
+        suffix += 1;
+    }
+    unique
+}
+
+struct TensorInfo {
+    id: String,
+    dtype: DataType,
+    shape: Vec<usize>,
+}
+
+struct Importer {
+    /// The data type and shape of the tensors that the graph describes
+    known: HashMap<String, (DataType, Vec<usize>)>,
+    /// The tensors that are used, in the order that they are first used
+    tensors: Vec<TensorInfo>,
+    tensor_idx_by_name: HashMap<String, usize>,
+    used_ids: HashSet<String>,
+}
+
+impl Importer {
+    fn new(graph: &GraphProto, options: OnnxImportOptions) -> Result<Self, SimError> {
+        let mut known = HashMap::new();
+        for info in graph
+            .input
+            .iter()
+            .chain(&graph.output)
+            .chain(&graph.value_info)
+        {
+            let Some(type_proto::Value::TensorType(tensor_type)) =
+                info.r#type.as_ref().and_then(|t| t.value.as_ref())
+            else {
+                continue;
+            };
+            let Some(shape) = &tensor_type.shape else {
+                continue;
+            };
+            let Some(dtype) = data_type(tensor_type.elem_type()) else {
+                return sim_error!(
+                    "ONNX tensor '{}' has data type {} which is not supported",
+                    info.name(),
+                    tensor_type.elem_type()
+                );
+            };
+            let shape = shape
+                .dim
+                .iter()
+                .map(|dim| match dim.value {
+                    Some(dimension::Value::DimValue(value)) if value > 0 => value as usize,
+                    _ => options.batch_size,
+                })
+                .collect();
+            known.insert(info.name().to_string(), (dtype, shape));
+        }
+        for initializer in &graph.initializer {
+            let Some(dtype) = data_type(initializer.data_type()) else {
+                return sim_error!(
+                    "ONNX tensor '{}' has data type {} which is not supported",
+                    initializer.name(),
+                    initializer.data_type()
+                );
+            };
+            let shape = initializer
+                .dims
+                .iter()
+                .map(|dim| usize::try_from(*dim).unwrap_or(0))
+                .collect();
+            known.insert(initializer.name().to_string(), (dtype, shape));
+        }
+        Ok(Self {
+            known,
+            tensors: Vec::new(),
+            tensor_idx_by_name: HashMap::new(),
+            used_ids: HashSet::new(),
+        })
+    }
+
+    /// Returns the index of a tensor, adding it with the given data type and
+    /// shape if it is not yet used and its shape is not known
+    fn tensor_idx(
+        &mut self,
+        name: &str,
+        inferred: Option<(DataType, Vec<usize>)>,
+    ) -> Option<usize> {
+        if let Some(idx) = self.tensor_idx_by_name.get(name) {
+            return Some(*idx);
+        }
+        let (dtype, shape) = self.known.get(name).cloned().or(inferred)?;
+        let id = unique_id(name, "tensor", &mut self.used_ids);
+        self.tensors.push(TensorInfo { id, dtype, shape });
+        self.tensor_idx_by_name
+            .insert(name.to_string(), self.tensors.len() - 1);
+        Some(self.tensors.len() - 1)
+    }
+
+    /// Returns the shape of the output of an operator
+    fn output_shape(
+        &self,
+        operator: &Operator,
+        inputs: &[usize],
+        name: &str,
+    ) -> Result<Vec<usize>, SimError> {
+        let shape = |i: usize| &self.tensors[inputs[i]].shape;
+        let shape = match operator {
+            Operator::Gemm { .. } => {
+                let (a, b) = (shape(0), shape(1));
+                if a.len() < 2 || b.len() < 2 {
+                    return sim_error!("ONNX node '{name}' multiplies tensors of fewer than 2D");
+                }
+                broadcast(&a[..a.len() - 2], &b[..b.len() - 2]).map(|mut batch| {
+                    batch.extend([a[a.len() - 2], b[b.len() - 1]]);
+                    batch
+                })
+            }
+            Operator::MaxPool(maxpool) => {
+                let input = Tensor::new(shape(0), &DataType::Fp32, 0);
+                Some(maxpool.output_shape(&input)?.get_dims().clone())
+            }
+            Operator::Binary => broadcast(shape(0), shape(1)),
+            Operator::Unary => Some(shape(0).clone()),
+        };
+        match shape {
+            Some(shape) => Ok(shape),
+            None => sim_error!("ONNX node '{name}' has inputs whose shapes do not match"),
+        }
+    }
+
+    fn tensor_view(&self, idx: usize) -> TensorView {
+        let tensor = &self.tensors[idx];
+        TensorView::new_full(Tensor::new(&tensor.shape, &tensor.dtype, 0))
+    }
+}
+
+/// Convert an ONNX model into a timetable
+pub fn import_onnx(bytes: &[u8], options: OnnxImportOptions) -> Result<OnnxImport, SimError> {
+    let model = ModelProto::decode(bytes)
+        .map_err(|e| SimError(format!("Failed to decode ONNX model: {e}")))?;
+    let Some(graph) = model.graph else {
+        return sim_error!("ONNX model has no graph");
+    };
+
+    let mut importer = Importer::new(&graph, options)?;
+    let mut compute_nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut estimates = Vec::new();
+    for (node_idx, node) in graph.node.iter().enumerate() {
+        let name = if node.name().is_empty() {
+            format!("{}{node_idx}", node.op_type())
+        } else {
+            node.name().to_string()
+        };
+        let operator = operator(node, &name)?;
+
+        let input_names: Vec<&String> = node.input.iter().filter(|i| !i.is_empty()).collect();
+        let num_inputs = match operator {
+            Operator::Gemm { .. } | Operator::Binary => 2,
+            Operator::MaxPool(_) | Operator::Unary => 1,
+        };
+        if input_names.len() < num_inputs {
+            return sim_error!(
+                "ONNX node '{name}' has {} inputs but needs {num_inputs}",
+                input_names.len()
+            );
+        }
+        let mut inputs = Vec::with_capacity(input_names.len());
+        for input_name in &input_names {
+            let Some(idx) = importer.tensor_idx(input_name, None) else {
+                return sim_error!(
+                    "ONNX tensor '{input_name}' used by node '{name}' has no known shape"
+                );
+            };
+            inputs.push(idx);
+        }
+        let inputs = match operator {
+            Operator::Gemm { trans_a, trans_b } => {
+                for (i, transpose) in [trans_a, trans_b].into_iter().enumerate() {
+                    if transpose {
+                        let tensor = &mut importer.tensors[inputs[i]];
+                        tensor.shape = transposed(std::mem::take(&mut tensor.shape));
+                    }
+                }
+                // MatMul has no input C and Gemm may have one, which the gemm
+                // operator needs to be at least 2D
+                inputs.truncate(3);
+                if let Some(c) = inputs.get(2) {
+                    let shape = &mut importer.tensors[*c].shape;
+                    while shape.len() < 2 {
+                        shape.insert(0, 1);
+                    }
+                }
+                inputs
+            }
+            Operator::MaxPool(_) => vec![inputs[0]],
+            Operator::Binary => inputs[..2].to_vec(),
+            // Read the input twice as an element-wise add does
+            Operator::Unary => vec![inputs[0], inputs[0]],
+        };
+
+        let Some(output_name) = node.output.first() else {
+            return sim_error!("ONNX node '{name}' has no output");
+        };
+        let dtype = importer.tensors[inputs[0]].dtype;
+        let shape = importer.output_shape(&operator, &inputs, &name)?;
+        let output = importer
+            .tensor_idx(output_name, Some((dtype, shape)))
+            .expect("an inferred shape is always given");
+
+        let op = match operator {
+            Operator::Gemm { .. } => ComputeOp::Gemm,
+            Operator::MaxPool(maxpool) => ComputeOp::MaxPool(maxpool),
+            Operator::Binary | Operator::Unary => ComputeOp::Add,
+        };
+        let id = unique_id(&name, "node", &mut importer.used_ids);
+        let input_views: Vec<Option<TensorView>> = inputs
+            .iter()
+            .map(|idx| Some(importer.tensor_view(*idx)))
+            .collect();
+        let output_views = [Some(importer.tensor_view(output))];
+        let flops = op
+            .compute_flops(&input_views, &output_views)
+            .map_err(|e| SimError(format!("ONNX node '{name}': {e}")))?;
+        let bytes = input_views
+            .iter()
+            .chain(&output_views)
+            .flatten()
+            .map(TensorView::num_bytes)
+            .sum();
+        estimates.push(ComputeEstimate {
+            id: id.clone(),
+            op: op.trace_name(),
+            flops,
+            bytes,
+        });
+
+        for (i, idx) in inputs.iter().enumerate() {
+            edges.push(EdgeSection {
+                from: importer.tensors[*idx].id.clone(),
+                to: format!("{id}.{i}"),
+                kind: EdgeKind::Data,
+                bytes: None,
+                buffer: None,
+            });
+        }
+        edges.push(EdgeSection {
+            from: id.clone(),
+            to: importer.tensors[output].id.clone(),
+            kind: EdgeKind::Data,
+            bytes: None,
+            buffer: None,
+        });
+        compute_nodes.push(NodeSection::Compute {
+            id,
+            op,
+            pe: None,
+            input_views: vec![None; inputs.len()],
+            output_views: vec![None],
+            priority: None,
+            deadline_ns: None,
+            relative_deadline_ns: None,
+            iterations: None,
+            buffer: None,
+        });
+    }
+
+    // Place the tensors one after another
+    let mut nodes = Vec::with_capacity(importer.tensors.len() + compute_nodes.len());
+    let mut addr = options.base_addr;
+    for tensor in importer.tensors {
+        let config = TensorConfigSection {
+            addr,
+            dtype: tensor.dtype,
+            shape: tensor.shape,
+        };
+        addr = (addr + config.num_bytes() as u64).next_multiple_of(options.alignment_bytes);
+        nodes.push(NodeSection::Tensor {
+            id: tensor.id,
+            config,
+        });
+    }
+    nodes.extend(compute_nodes);
+
+    Ok(OnnxImport {
+        timetable_file: TimetableFile {
+            nodes,
+            edges,
+            subgraphs: Vec::new(),
+            streams: Vec::new(),
+        },
+        estimates,
+    })
+}
@@ -0,0 +1,296 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Random layered graphs, for stress-testing scheduling policies and the
+//! dispatch of tasks without writing timetables by hand.
+//!
+//! A graph starts with a layer of input tensors. Each following layer has the
+//! same number of tasks, and each task reads between 1 and the
+//! [fan-in](RandomGraphOptions::with_max_fan_in) tensors of the layer before
+//! and writes one tensor of its own layer. Each tensor is read by at most the
+//! [fan-out](RandomGraphOptions::with_max_fan_out) tasks, with the tensors
+//! that are read least chosen first.
+//!
+//! A task is a Memory node with the
+//! [memory fraction](RandomGraphOptions::with_memory_fraction) probability
+//! and a Compute node otherwise:
+//!
+//!  - a Memory node is a `store` of its tensor that waits for the tensors
+//!    that it reads, so reads any number of them
+//!  - a Compute node is an `add`, which reads a single tensor twice, or a
+//!    `gemm`, which reads 2 tensors or 3 with a bias, so reads at most 3
+//!
+//! Every tensor has the same square shape, so any tensors can be combined.
+//! The tasks are not given a PE, so they are assigned one by
+//! [TimetableFile::map_to_pes]. The same options and seed always give the same
+//! graph.
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use gwr_models::processing_element::operators::dtype::DataType;
+use gwr_models::processing_element::task::{ComputeOp, MemoryOp};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::timetable_file::{
+    EdgeKind, EdgeSection, MemoryConfigSection, NodeSection, TensorConfigSection, TimetableFile,
+};
+
+/// The alignment of the address of each tensor
+const ALIGNMENT_BYTES: u64 = 64;
+
+/// The most tensors that a Compute node can read
+const MAX_COMPUTE_FAN_IN: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RandomGraphOptions {
+    seed: u64,
+    num_layers: usize,
+    width: usize,
+    max_fan_in: usize,
+    max_fan_out: usize,
+    memory_fraction: f64,
+    tensor_size: usize,
+    base_addr: u64,
+}
+
+impl Default for RandomGraphOptions {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            num_layers: 4,
+            width: 4,
+            max_fan_in: 2,
+            max_fan_out: 2,
+            memory_fraction: 0.25,
+            tensor_size: 16,
+            base_addr: 0,
+        }
+    }
+}
+
+impl RandomGraphOptions {
+    /// Set the seed of the random choices, which is 0 by default
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the number of layers of tasks, which is 4 by default
+    #[must_use]
+    pub fn with_num_layers(mut self, num_layers: usize) -> Self {
+        self.num_layers = num_layers;
+        self
+    }
+
+    /// Set the number of tasks in each layer, and of input tensors, which is
+    /// 4 by default
+    #[must_use]
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the most tensors that a task reads, which is 2 by default
+    #[must_use]
+    pub fn with_max_fan_in(mut self, max_fan_in: usize) -> Self {
+        self.max_fan_in = max_fan_in;
+        self
+    }
+
+    /// Set the most tasks that read a tensor, which is 2 by default
+    #[must_use]
+    pub fn with_max_fan_out(mut self, max_fan_out: usize) -> Self {
+        self.max_fan_out = max_fan_out;
+        self
+    }
+
+    /// Set the probability that a task is a Memory node rather than a Compute
+    /// node, which is 0.25 by default
+    #[must_use]
+    pub fn with_memory_fraction(mut self, memory_fraction: f64) -> Self {
+        self.memory_fraction = memory_fraction;
+        self
+    }
+
+    /// Set the number of rows and of columns of every tensor, which is 16 by
+    /// default
+    #[must_use]
+    pub fn with_tensor_size(mut self, tensor_size: usize) -> Self {
+        self.tensor_size = tensor_size;
+        self
+    }
+
+    /// Set the address of the first tensor, which is 0 by default
+    #[must_use]
+    pub fn with_base_addr(mut self, base_addr: u64) -> Self {
+        self.base_addr = base_addr;
+        self
+    }
+
+    fn validate(&self) -> Result<(), SimError> {
+        if self.num_layers == 0 || self.width == 0 || self.tensor_size == 0 {
+            return sim_error!(
+                "A random graph needs at least 1 layer, 1 task per layer and tensors of at least 1 element"
+            );
+        }
+        if self.max_fan_in == 0 || self.max_fan_out == 0 {
+            return sim_error!("A random graph needs a fan-in and a fan-out of at least 1");
+        }
+        if !(0.0..=1.0).contains(&self.memory_fraction) {
+            return sim_error!(
+                "A random graph has memory fraction {} which is not between 0 and 1",
+                self.memory_fraction
+            );
+        }
+        Ok(())
+    }
+}
+
+struct Generator {
+    options: RandomGraphOptions,
+    rng: StdRng,
+    nodes: Vec<NodeSection>,
+    edges: Vec<EdgeSection>,
+    next_addr: u64,
+}
+
+impl Generator {
+    fn add_tensor(&mut self, id: String) {
+        let config = TensorConfigSection {
+            addr: self.next_addr,
+            dtype: DataType::Fp32,
+            shape: vec![self.options.tensor_size; 2],
+        };
+        self.next_addr =
+            (self.next_addr + config.num_bytes() as u64).next_multiple_of(ALIGNMENT_BYTES);
+        self.nodes.push(NodeSection::Tensor { id, config });
+    }
+
+    fn add_edge(&mut self, from: &str, to: String) {
+        self.edges.push(EdgeSection {
+            from: from.to_string(),
+            to,
+            kind: EdgeKind::Data,
+            bytes: None,
+            buffer: None,
+        });
+    }
+
+    /// Choose up to `max_inputs` of the tensors of the previous layer, leaving
+    /// enough reads of them for each of the `num_later` tasks of the layer
+    /// still to be generated to read one
+    fn choose_inputs(
+        &mut self,
+        uses: &mut [usize],
+        max_inputs: usize,
+        num_later: usize,
+    ) -> Vec<usize> {
+        let max_fan_out = self.options.max_fan_out;
+        let spare_reads = uses.iter().map(|used| max_fan_out - used).sum::<usize>() - num_later;
+        let mut candidates: Vec<usize> = (0..uses.len())
+            .filter(|idx| uses[*idx] < max_fan_out)
+            .collect();
+        candidates.shuffle(&mut self.rng);
+        candidates.sort_by_key(|idx| uses[*idx]);
+        candidates.truncate(max_inputs.min(spare_reads));
+        for idx in &candidates {
+            uses[*idx] += 1;
+        }
+        candidates
+    }
+
+    fn add_task(&mut self, id: String, is_memory: bool, inputs: &[String], output: String) {
+        if is_memory {
+            for input in inputs {
+                self.add_edge(input, id.clone());
+            }
+            self.nodes.push(NodeSection::Memory {
+                id: id.clone(),
+                op: MemoryOp::Store,
+                pe: None,
+                config: MemoryConfigSection { view: None },
+                iterations: None,
+                deadline_ns: None,
+                relative_deadline_ns: None,
+                buffer: None,
+            });
+        } else {
+            let (op, inputs) = match inputs {
+                // Read the input twice as an element-wise add does
+                [input] => (ComputeOp::Add, vec![input, input]),
+                [_, _] if self.rng.random_bool(0.5) => (ComputeOp::Add, inputs.iter().collect()),
+                _ => (ComputeOp::Gemm, inputs.iter().collect()),
+            };
+            for (i, input) in inputs.iter().enumerate() {
+                self.add_edge(input, format!("{id}.{i}"));
+            }
+            self.nodes.push(NodeSection::Compute {
+                id: id.clone(),
+                op,
+                pe: None,
+                input_views: vec![None; inputs.len()],
+                output_views: vec![None],
+                priority: None,
+                deadline_ns: None,
+                relative_deadline_ns: None,
+                iterations: None,
+                buffer: None,
+            });
+        }
+        self.add_edge(&id, output.clone());
+        self.add_tensor(output);
+    }
+}
+
+/// Generate a random layered graph
+pub fn random_graph(options: RandomGraphOptions) -> Result<TimetableFile, SimError> {
+    options.validate()?;
+    let mut generator = Generator {
+        options,
+        rng: StdRng::seed_from_u64(options.seed),
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        next_addr: options.base_addr,
+    };
+
+    let mut layer_tensors: Vec<String> = (0..options.width)
+        .map(|i| format!("tensor_0_{i}"))
+        .collect();
+    for id in &layer_tensors {
+        generator.add_tensor(id.clone());
+    }
+
+    for layer in 1..=options.num_layers {
+        let mut uses = vec![0; layer_tensors.len()];
+        let mut next_tensors = Vec::with_capacity(options.width);
+        for i in 0..options.width {
+            let is_memory = generator.rng.random_bool(options.memory_fraction);
+            let (id, max_inputs) = if is_memory {
+                (format!("store_{layer}_{i}"), options.max_fan_in)
+            } else {
+                (
+                    format!("compute_{layer}_{i}"),
+                    options.max_fan_in.min(MAX_COMPUTE_FAN_IN),
+                )
+            };
+            let num_inputs = generator.rng.random_range(1..=max_inputs);
+            let inputs: Vec<String> = generator
+                .choose_inputs(&mut uses, num_inputs, options.width - i - 1)
+                .into_iter()
+                .map(|idx| layer_tensors[idx].clone())
+                .collect();
+            let output = format!("tensor_{layer}_{i}");
+            generator.add_task(id, is_memory, &inputs, output.clone());
+            next_tensors.push(output);
+        }
+        layer_tensors = next_tensors;
+    }
+
+    Ok(TimetableFile {
+        nodes: generator.nodes,
+        edges: generator.edges,
+        subgraphs: Vec::new(),
+        streams: Vec::new(),
+    })
+}
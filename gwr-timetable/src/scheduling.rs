@@ -0,0 +1,70 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Policies for the order in which the ready tasks of a PE are started.
+//!
+//! A PE starts all of its ready tasks at once, but the tasks that are started
+//! first are the first to use its load/store unit and compute resources. The
+//! [SchedulingPolicy] of a [Timetable](crate::Timetable) decides that order:
+//!
+//!  - `priority`: tasks with a higher `priority` first, which is the default
+//!  - `fifo`: tasks in the order in which they became ready
+//!  - `critical-path`: tasks with the longest chain of tasks after them first
+//!  - `earliest-deadline`: tasks with the earliest [deadline](crate::deadlines)
+//!    first, then tasks without a deadline
+//!
+//! Tasks that are equal under a policy are started in the order of the nodes
+//! of the timetable. The `priority` of a node is only used by the `priority`
+//! policy, so under the other policies no task preempts another. Under every
+//! policy the tasks of higher priority [streams](crate::streams) are started
+//! first, and can preempt those of lower priority streams.
+
+use clap::ValueEnum;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SchedulingPolicy {
+    #[default]
+    Priority,
+    Fifo,
+    CriticalPath,
+    EarliestDeadline,
+}
+
+/// Returns the number of tasks on the longest path from each task to the end
+/// of the graph, including the task itself.
+///
+/// `successors` gives the tasks and tensors that wait for each one and
+/// `is_task` whether each one is a task rather than a tensor. Tasks that are
+/// part of a cycle are given a length of 0.
+pub(crate) fn critical_path_lengths(
+    successors: &[Vec<usize>],
+    is_task: impl Fn(usize) -> bool,
+) -> Vec<usize> {
+    let mut lengths = vec![0; successors.len()];
+    let mut num_unresolved: Vec<usize> = successors.iter().map(Vec::len).collect();
+    let mut predecessors = vec![Vec::new(); successors.len()];
+    for (idx, outputs) in successors.iter().enumerate() {
+        for output_idx in outputs {
+            predecessors[*output_idx].push(idx);
+        }
+    }
+
+    // Resolve the graph from its end, once all successors of a task are known
+    let mut resolved: Vec<usize> = (0..successors.len())
+        .filter(|idx| num_unresolved[*idx] == 0)
+        .collect();
+    while let Some(idx) = resolved.pop() {
+        let longest_after = successors[idx]
+            .iter()
+            .map(|output_idx| lengths[*output_idx])
+            .max()
+            .unwrap_or(0);
+        lengths[idx] = longest_after + usize::from(is_task(idx));
+        for predecessor_idx in &predecessors[idx] {
+            num_unresolved[*predecessor_idx] -= 1;
+            if num_unresolved[*predecessor_idx] == 0 {
+                resolved.push(*predecessor_idx);
+            }
+        }
+    }
+    lengths
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Concurrent graphs that contend for the same platform.
+//!
+//! Several graphs, such as the workloads of different users, can be run by
+//! one [Timetable](crate::Timetable) by combining them with
+//! [TimetableFile::concurrent]. The IDs of the nodes of each graph are given
+//! the name of the graph as a prefix, so `add` in graph `infer` becomes
+//! `infer_add`, and the nodes of each graph form a stream with the priority
+//! of the graph.
+//!
+//! The ready tasks of a PE are started in the order of the priorities of
+//! their streams, and then in the order of its
+//! [SchedulingPolicy](crate::scheduling::SchedulingPolicy). On a PE with
+//! preemption enabled a task of a higher priority stream can preempt the
+//! computation of a task of a lower priority stream.
+
+use std::collections::HashSet;
+
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+
+use crate::timetable_file::{StreamSection, TimetableFile};
+
+/// A graph to be run at the same time as others
+#[derive(Debug)]
+pub struct ConcurrentGraph {
+    pub name: String,
+    pub timetable_file: TimetableFile,
+    pub priority: usize,
+}
+
+impl ConcurrentGraph {
+    #[must_use]
+    pub fn new(name: &str, timetable_file: TimetableFile) -> Self {
+        Self {
+            name: name.to_string(),
+            timetable_file,
+            priority: 0,
+        }
+    }
+
+    /// Set the priority of the tasks of the graph, which is 0 by default
+    #[must_use]
+    pub fn with_priority(mut self, priority: usize) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+pub(crate) fn combine_graphs(graphs: Vec<ConcurrentGraph>) -> Result<TimetableFile, SimError> {
+    let mut combined = TimetableFile {
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        subgraphs: Vec::new(),
+        streams: Vec::new(),
+    };
+    let mut names = HashSet::new();
+    for graph in graphs {
+        let name = graph.name;
+        if name.contains('.') {
+            return sim_error!("Graph name '{name}' may not contain '.'");
+        }
+        if !names.insert(name.clone()) {
+            return sim_error!("Graph name '{name}' is used more than once");
+        }
+        let mut timetable_file = graph.timetable_file;
+        if !timetable_file.streams.is_empty() {
+            return sim_error!("Graph '{name}' already has streams");
+        }
+
+        let prefixed = |id: &str| format!("{name}_{id}");
+        let mut stream = StreamSection {
+            id: name.clone(),
+            nodes: Vec::with_capacity(timetable_file.nodes.len()),
+            priority: graph.priority,
+        };
+        for mut node in timetable_file.nodes.drain(..) {
+            let id = prefixed(node.id());
+            stream.nodes.push(id.clone());
+            node.set_id(id);
+            combined.nodes.push(node);
+        }
+        for mut edge in timetable_file.edges.drain(..) {
+            edge.from = prefixed(&edge.from);
+            edge.to = prefixed(&edge.to);
+            combined.edges.push(edge);
+        }
+        for mut subgraph in timetable_file.subgraphs.drain(..) {
+            subgraph.id = prefixed(&subgraph.id);
+            for node_id in &mut subgraph.nodes {
+                *node_id = prefixed(node_id);
+            }
+            combined.subgraphs.push(subgraph);
+        }
+        combined.streams.push(stream);
+    }
+    Ok(combined)
+}
@@ -0,0 +1,278 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Sweeps of a [Timetable] over a grid of platform parameters.
+//!
+//! A [ParameterGrid] holds a list of values for each of a set of
+//! [override](gwr_platform::overrides) paths. Each combination of values is a
+//! point of the sweep which is run with [run_point] and recorded as a row of a
+//! CSV file that includes the platform, timetable and parameter values it was
+//! run with. A grid can be read from YAML of the form:
+//!
+//! ```yaml
+//! parameters:
+//!   pe0.config.lsu_access_bytes: [32, 64]
+//!   hbm0.delay_ticks: [10, 20, 40]
+//! ```
+
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::sim_error;
+use gwr_engine::types::SimError;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_platform::format::PlatformFormat;
+use gwr_platform::overrides::Override;
+use gwr_track::tracker::dev_null_tracker;
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::Timetable;
+use crate::timetable_file::TimetableFile;
+
+/// The values to sweep a platform parameter over
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameter {
+    path: String,
+    values: Vec<Value>,
+}
+
+impl Parameter {
+    #[must_use]
+    pub fn new(path: impl Into<String>, values: Vec<Value>) -> Self {
+        Self {
+            path: path.into(),
+            values,
+        }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    #[must_use]
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+}
+
+/// Parse a parameter of the form `path=value,value,...`
+impl FromStr for Parameter {
+    type Err = SimError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((path, values)) = s.split_once('=') else {
+            return sim_error!("Invalid parameter '{s}' (expected 'path=value,value,...')");
+        };
+        let values = values
+            .split(',')
+            .map(|value| {
+                serde_yaml::from_str(value)
+                    .map_err(|e| SimError(format!("Invalid value '{value}' in '{s}': {e}")))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(path, values))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParameterGridFile {
+    parameters: Mapping,
+}
+
+/// The parameters of a sweep, each point of which is a combination of one
+/// value of every parameter
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParameterGrid {
+    parameters: Vec<Parameter>,
+}
+
+impl ParameterGrid {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_file(grid_path: &Path) -> Result<Self, SimError> {
+        let s = std::fs::read_to_string(grid_path)
+            .map_err(|e| SimError(format!("Unable to read {}: {e}", grid_path.display())))?;
+        Self::from_string(&s)
+    }
+
+    pub fn from_string(grid_str: &str) -> Result<Self, SimError> {
+        let file: ParameterGridFile = serde_yaml::from_str(grid_str)
+            .map_err(|e| SimError(format!("serde_yaml::from_str failed: {e}")))?;
+        let mut grid = Self::new();
+        for (path, values) in file.parameters {
+            let Value::String(path) = path else {
+                return sim_error!("Invalid parameter path {path:?}");
+            };
+            let values = match values {
+                Value::Sequence(values) => values,
+                value => vec![value],
+            };
+            grid = grid.with_parameter(Parameter::new(path, values));
+        }
+        Ok(grid)
+    }
+
+    #[must_use]
+    pub fn with_parameter(mut self, parameter: Parameter) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    #[must_use]
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    /// Returns the number of points in the grid
+    #[must_use]
+    pub fn num_points(&self) -> usize {
+        self.parameters.iter().map(|p| p.values.len()).product()
+    }
+
+    /// Returns the overrides for a point of the grid. The points are ordered
+    /// with the values of the last parameter changing fastest.
+    pub fn point(&self, index: usize) -> Result<Vec<Override>, SimError> {
+        if index >= self.num_points() {
+            return sim_error!(
+                "Sweep point {index} is out of range ({} points)",
+                self.num_points()
+            );
+        }
+        let mut remaining = index;
+        let mut overrides = Vec::with_capacity(self.parameters.len());
+        for parameter in self.parameters.iter().rev() {
+            let value = &parameter.values[remaining % parameter.values.len()];
+            remaining /= parameter.values.len();
+            overrides.push(Override::new(&parameter.path, value.clone())?);
+        }
+        overrides.reverse();
+        Ok(overrides)
+    }
+}
+
+/// The statistics of a run of one point of a sweep
+#[derive(Clone, Debug, PartialEq)]
+pub struct SweepResult {
+    pub time_ns: f64,
+    pub memory_bytes_read: usize,
+    pub memory_bytes_written: usize,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    pub flops: usize,
+}
+
+impl SweepResult {
+    /// The CSV column names of the statistics
+    pub const COLUMNS: [&str; 6] = [
+        "time_ns",
+        "memory_bytes_read",
+        "memory_bytes_written",
+        "cache_hits",
+        "cache_misses",
+        "flops",
+    ];
+
+    /// Returns the statistics in the order of [SweepResult::COLUMNS]
+    #[must_use]
+    pub fn fields(&self) -> Vec<String> {
+        vec![
+            self.time_ns.to_string(),
+            self.memory_bytes_read.to_string(),
+            self.memory_bytes_written.to_string(),
+            self.cache_hits.to_string(),
+            self.cache_misses.to_string(),
+            self.flops.to_string(),
+        ]
+    }
+}
+
+/// Build a platform with the given overrides and run a timetable on it
+pub fn run_point(
+    platform_path: &Path,
+    platform_format: PlatformFormat,
+    timetable_path: &Path,
+    overrides: &[Override],
+) -> Result<SweepResult, SimError> {
+    let mut engine = Engine::new(&dev_null_tracker());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_file_as(
+        &engine,
+        &clock,
+        platform_path,
+        platform_format,
+        overrides,
+    )?);
+
+    let timetable_file = TimetableFile::from_file(timetable_path)?;
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform)?);
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    engine.run()?;
+    timetable.check_tasks_complete()?;
+
+    Ok(SweepResult {
+        time_ns: engine.time_now_ns(),
+        memory_bytes_read: platform.total_memory_bytes_read(),
+        memory_bytes_written: platform.total_memory_bytes_written(),
+        cache_hits: platform.total_cache_hits(),
+        cache_misses: platform.total_cache_misses(),
+        flops: platform.total_machine_ops().total(),
+    })
+}
+
+/// Quote a CSV field if it contains a separator, quote or newline
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Returns the CSV header for the results of a sweep over a grid
+#[must_use]
+pub fn csv_header(grid: &ParameterGrid) -> String {
+    let columns = ["platform", "timetable"]
+        .into_iter()
+        .map(str::to_string)
+        .chain(grid.parameters().iter().map(|p| p.path().to_string()))
+        .chain(SweepResult::COLUMNS.into_iter().map(str::to_string));
+    columns
+        .map(|column| csv_field(&column))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Returns the CSV row for the result of a point of a sweep
+#[must_use]
+pub fn csv_row(
+    platform_path: &Path,
+    timetable_path: &Path,
+    overrides: &[Override],
+    result: &SweepResult,
+) -> String {
+    let values = overrides.iter().map(|o| {
+        serde_yaml::to_string(o.value())
+            .map(|value| value.trim_end().to_string())
+            .unwrap_or_default()
+    });
+    [
+        platform_path.display().to_string(),
+        timetable_path.display().to_string(),
+    ]
+    .into_iter()
+    .chain(values)
+    .chain(result.fields())
+    .map(|field| csv_field(&field))
+    .collect::<Vec<_>>()
+    .join(",")
+}
@@ -2,29 +2,60 @@
 
 //! Types that map directly to the YAML file contents
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::rc::Rc;
 
 use gwr_engine::sim_error;
 use gwr_engine::types::{SimError, SimResult};
+use gwr_models::collective::{CollectiveAlgorithm, CollectiveOp};
 use gwr_models::processing_element::operators::dtype::DataType;
-use gwr_models::processing_element::task::{ComputeOp, MemoryOp};
+use gwr_models::processing_element::task::{ComputeOp, DmaOp, MemoryOp};
 use gwr_platform::Platform;
 use serde::{Deserialize, Serialize};
 
+use crate::collective::expand_collectives;
+use crate::deadlines::Deadline;
+use crate::dot::parse_dot;
+use crate::mapping::{MappingPolicy, PeMapping, map_to_pes};
+use crate::onnx::{OnnxImportOptions, import_onnx};
+use crate::random_graph::{RandomGraphOptions, random_graph};
+use crate::streams::{ConcurrentGraph, combine_graphs};
+
+/// The JSON Schema of timetables, for tools that generate JSON timetables to
+/// check their output against. YAML timetables have the same structure.
+pub const TIMETABLE_JSON_SCHEMA: &str = include_str!("../schema/timetable.schema.json");
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct TimetableFile {
     pub nodes: Vec<NodeSection>,
     pub edges: Vec<EdgeSection>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subgraphs: Vec<SubgraphSection>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub streams: Vec<StreamSection>,
 }
 
 impl TimetableFile {
+    /// Load a timetable from a YAML file, from a [DOT](crate::dot) file if
+    /// its extension is `.dot` or `.gv`, or from an [ONNX](crate::onnx) model
+    /// if its extension is `.onnx`
     pub fn from_file(graph_path: &Path) -> Result<Self, SimError> {
+        if graph_path.extension().and_then(|ext| ext.to_str()) == Some("onnx") {
+            let bytes = std::fs::read(graph_path)
+                .map_err(|e| SimError(format!("Unable to read {}: {e}", graph_path.display())))?;
+            return Self::from_onnx(&bytes)
+                .map_err(|e| SimError(format!("{}: {e}", graph_path.display())));
+        }
         let s = std::fs::read_to_string(graph_path)
             .map_err(|e| SimError(format!("Unable to read {}: {e}", graph_path.display())))?;
-        Self::from_string(&s)
+        match graph_path.extension().and_then(|ext| ext.to_str()) {
+            Some("dot" | "gv") => {
+                Self::from_dot(&s).map_err(|e| SimError(format!("{}: {e}", graph_path.display())))
+            }
+            _ => Self::from_string(&s),
+        }
     }
 
     pub fn from_string(graph_str: &str) -> Result<Self, SimError> {
@@ -32,6 +63,57 @@ impl TimetableFile {
             .map_err(|e| SimError(format!("serde_yaml::from_str failed: {e}")))
     }
 
+    /// Parse a timetable from JSON with the structure described by
+    /// [TIMETABLE_JSON_SCHEMA]
+    pub fn from_json(graph_str: &str) -> Result<Self, SimError> {
+        serde_json::from_str(graph_str)
+            .map_err(|e| SimError(format!("serde_json::from_str failed: {e}")))
+    }
+
+    /// Returns the timetable as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, SimError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| SimError(format!("serde_json::to_string failed: {e}")))
+    }
+
+    /// Parse a timetable from a Graphviz [DOT](crate::dot) graph
+    pub fn from_dot(graph_str: &str) -> Result<Self, SimError> {
+        parse_dot(graph_str)
+    }
+
+    /// Convert an [ONNX](crate::onnx) model into a timetable with the default
+    /// import options
+    pub fn from_onnx(bytes: &[u8]) -> Result<Self, SimError> {
+        Ok(import_onnx(bytes, OnnxImportOptions::default())?.timetable_file)
+    }
+
+    /// Generate a [random](crate::random_graph) layered graph
+    pub fn random(options: RandomGraphOptions) -> Result<Self, SimError> {
+        random_graph(options)
+    }
+
+    /// Assign a PE to each node that is not given one, returning the PEs that
+    /// were chosen
+    pub fn map_to_pes(
+        &mut self,
+        platform: &Platform,
+        policy: MappingPolicy,
+    ) -> Result<PeMapping, SimError> {
+        map_to_pes(self, platform, policy)
+    }
+
+    /// Replace each Collective node with the Send and Recv nodes that perform
+    /// it
+    pub fn expand_collectives(&mut self) -> SimResult {
+        expand_collectives(self)
+    }
+
+    /// Combine graphs into one timetable in which they run at the same time,
+    /// each as a stream with its own priority
+    pub fn concurrent(graphs: Vec<ConcurrentGraph>) -> Result<Self, SimError> {
+        combine_graphs(graphs)
+    }
+
     pub fn validate(&self, platform: &Rc<Platform>) -> SimResult {
         let mut errors = Vec::new();
 
@@ -45,10 +127,30 @@ impl TimetableFile {
                 errors.push(format!("Duplicate Node ID '{id}'"));
             }
 
-            if let Some(node_pe_id) = &pe
-                && platform.pe_idx_from_name(node_pe_id).is_err()
-            {
-                errors.push(format!("Node '{id}' contains invalid PE ID '{node_pe_id}'"));
+            if let (Some(_), Some(_)) = node.deadlines() {
+                errors.push(format!(
+                    "Node '{id}' has both a deadline_ns and a relative_deadline_ns"
+                ));
+            }
+
+            if let Some(node_pe_id) = &pe {
+                if platform.pe_idx_from_name(node_pe_id).is_err() {
+                    errors.push(format!("Node '{id}' contains invalid PE ID '{node_pe_id}'"));
+                } else if platform.is_dma_engine(node_pe_id)
+                    && matches!(node, NodeSection::Compute { .. })
+                {
+                    // DMA engines have no compute units
+                    errors.push(format!(
+                        "Compute node '{id}' cannot run on DMA engine '{node_pe_id}'"
+                    ));
+                }
+            } else if matches!(
+                node,
+                NodeSection::Compute { .. } | NodeSection::Memory { .. } | NodeSection::Dma { .. }
+            ) {
+                errors.push(format!(
+                    "Node '{id}' has no PE, so must be mapped to one before it can run"
+                ));
             }
         }
 
@@ -69,14 +171,154 @@ impl TimetableFile {
             }
         }
 
-        // TODO:
-        // - check for cycles in graph
+        // Each node can only be in one loop, either its own or a subgraph
+        let mut loop_ids = HashMap::new();
+        for node in &self.nodes {
+            if let Some(iterations) = node.iterations() {
+                if iterations == 0 {
+                    errors.push(format!("Node '{}' must run at least once", node.id()));
+                }
+                loop_ids.insert(node.id().as_str(), node.id().as_str());
+            }
+        }
+        for subgraph in &self.subgraphs {
+            if subgraph.iterations == 0 {
+                errors.push(format!("Subgraph '{}' must run at least once", subgraph.id));
+            }
+            for node_id in &subgraph.nodes {
+                if !node_ids.contains(node_id) {
+                    errors.push(format!(
+                        "Unknown node '{node_id}' in subgraph '{}'",
+                        subgraph.id
+                    ));
+                } else if let Some(loop_id) = loop_ids.insert(node_id.as_str(), &subgraph.id) {
+                    errors.push(format!(
+                        "Node '{node_id}' is in more than one loop ('{loop_id}' and '{}')",
+                        subgraph.id
+                    ));
+                }
+            }
+        }
+
+        // Each node can only be in one stream
+        let mut stream_ids = HashMap::new();
+        for stream in &self.streams {
+            for node_id in &stream.nodes {
+                if !node_ids.contains(node_id) {
+                    errors.push(format!(
+                        "Unknown node '{node_id}' in stream '{}'",
+                        stream.id
+                    ));
+                } else if let Some(stream_id) = stream_ids.insert(node_id.as_str(), &stream.id) {
+                    errors.push(format!(
+                        "Node '{node_id}' is in more than one stream ('{stream_id}' and '{}')",
+                        stream.id
+                    ));
+                }
+            }
+        }
+
+        errors.extend(self.dependency_errors());
 
         if !errors.is_empty() {
             return sim_error!("Failed to validate graph:\n{}", errors.join("\n"));
         }
         Ok(())
     }
+
+    /// Returns an error for each cycle of dependencies between the nodes and
+    /// one for the nodes that can never run because they depend on a cycle
+    fn dependency_errors(&self) -> Vec<String> {
+        let num_nodes = self.nodes.len();
+        let node_idx_by_id: HashMap<&str, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (node.id().as_str(), idx))
+            .collect();
+        let mut successors = vec![Vec::new(); num_nodes];
+        let mut num_predecessors = vec![0; num_nodes];
+        for edge in &self.edges {
+            let (Some(from_idx), Some(to_idx)) = (
+                node_idx_by_id.get(edge.from_node_id()),
+                node_idx_by_id.get(edge.to_node_id()),
+            ) else {
+                // Edges to unknown nodes have already been reported
+                continue;
+            };
+            successors[*from_idx].push(*to_idx);
+            num_predecessors[*to_idx] += 1;
+        }
+
+        // Remove the nodes that can run in turn, which leaves the nodes that
+        // are part of a cycle or depend on one
+        let mut can_run: Vec<usize> = (0..num_nodes)
+            .filter(|idx| num_predecessors[*idx] == 0)
+            .collect();
+        while let Some(idx) = can_run.pop() {
+            for successor_idx in &successors[idx] {
+                num_predecessors[*successor_idx] -= 1;
+                if num_predecessors[*successor_idx] == 0 {
+                    can_run.push(*successor_idx);
+                }
+            }
+        }
+        let blocked: Vec<bool> = num_predecessors.iter().map(|count| *count > 0).collect();
+
+        // Search the blocked nodes depth-first, where each edge back to a node
+        // on the current path closes a cycle
+        let mut errors = Vec::new();
+        let mut on_cycle = vec![false; num_nodes];
+        let mut visited = vec![false; num_nodes];
+        let mut on_path = vec![false; num_nodes];
+        for start_idx in (0..num_nodes).filter(|idx| blocked[*idx]) {
+            if visited[start_idx] {
+                continue;
+            }
+            visited[start_idx] = true;
+            on_path[start_idx] = true;
+            let mut path = vec![(start_idx, 0)];
+            while let Some((idx, next)) = path.last_mut() {
+                let Some(successor_idx) = successors[*idx].get(*next).copied() else {
+                    on_path[*idx] = false;
+                    path.pop();
+                    continue;
+                };
+                *next += 1;
+                if on_path[successor_idx] {
+                    let cycle_start = path
+                        .iter()
+                        .position(|(path_idx, _)| *path_idx == successor_idx)
+                        .unwrap();
+                    let mut cycle: Vec<&str> = path[cycle_start..]
+                        .iter()
+                        .map(|(path_idx, _)| {
+                            on_cycle[*path_idx] = true;
+                            self.nodes[*path_idx].id().as_str()
+                        })
+                        .collect();
+                    cycle.push(self.nodes[successor_idx].id());
+                    errors.push(format!("Cycle of dependencies: {}", cycle.join(" -> ")));
+                } else if blocked[successor_idx] && !visited[successor_idx] {
+                    visited[successor_idx] = true;
+                    on_path[successor_idx] = true;
+                    path.push((successor_idx, 0));
+                }
+            }
+        }
+
+        let unreachable: Vec<String> = (0..num_nodes)
+            .filter(|idx| blocked[*idx] && !on_cycle[*idx])
+            .map(|idx| format!("'{}'", self.nodes[idx].id()))
+            .collect();
+        if !unreachable.is_empty() {
+            errors.push(format!(
+                "Nodes {} can never run as they depend on a cycle",
+                unreachable.join(", ")
+            ));
+        }
+        errors
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -90,6 +332,23 @@ pub enum NodeSection {
         pe: Option<String>,
         input_views: Vec<Option<TensorViewSection>>,
         output_views: Vec<Option<TensorViewSection>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        priority: Option<usize>,
+        /// The time by which the task should complete, used by the
+        /// earliest-deadline scheduling policy and checked as it completes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline_ns: Option<u64>,
+        /// The time by which the task should complete, from the start of its
+        /// graph, see [deadlines](crate::deadlines)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relative_deadline_ns: Option<u64>,
+        /// The number of times that the node runs before its outputs are
+        /// complete
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        iterations: Option<usize>,
+        /// Space held while the task runs, see [buffers](crate::buffers)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        buffer: Option<BufferSection>,
     },
     #[serde(rename = "memory")]
     Memory {
@@ -97,6 +356,85 @@ pub enum NodeSection {
         op: MemoryOp,
         pe: Option<String>,
         config: MemoryConfigSection,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        iterations: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline_ns: Option<u64>,
+        /// The time by which the task should complete, from the start of its
+        /// graph, see [deadlines](crate::deadlines)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relative_deadline_ns: Option<u64>,
+        /// Space held while the task runs, see [buffers](crate::buffers)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        buffer: Option<BufferSection>,
+    },
+    #[serde(rename = "dma")]
+    Dma {
+        id: String,
+        op: DmaOp,
+        pe: Option<String>,
+        #[serde(default)]
+        config: DmaConfigSection,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        iterations: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline_ns: Option<u64>,
+        /// The time by which the task should complete, from the start of its
+        /// graph, see [deadlines](crate::deadlines)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relative_deadline_ns: Option<u64>,
+        /// Space held while the task runs, see [buffers](crate::buffers)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        buffer: Option<BufferSection>,
+    },
+    #[serde(rename = "send")]
+    Send {
+        id: String,
+        pe: Option<String>,
+        config: SendConfigSection,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        iterations: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline_ns: Option<u64>,
+        /// The time by which the task should complete, from the start of its
+        /// graph, see [deadlines](crate::deadlines)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relative_deadline_ns: Option<u64>,
+        /// Space held while the task runs, see [buffers](crate::buffers)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        buffer: Option<BufferSection>,
+    },
+    #[serde(rename = "recv")]
+    Recv {
+        id: String,
+        pe: Option<String>,
+        config: RecvConfigSection,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        iterations: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deadline_ns: Option<u64>,
+        /// The time by which the task should complete, from the start of its
+        /// graph, see [deadlines](crate::deadlines)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        relative_deadline_ns: Option<u64>,
+        /// Space held while the task runs, see [buffers](crate::buffers)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        buffer: Option<BufferSection>,
+    },
+    #[serde(rename = "condition")]
+    Condition {
+        id: String,
+        config: ConditionConfigSection,
+    },
+    /// Replaced by the Send and Recv nodes that perform it when the timetable
+    /// is built, see [collective](crate::collective)
+    #[serde(rename = "collective")]
+    Collective {
+        id: String,
+        op: CollectiveOp,
+        /// The PEs that take part, which are the ranks of the collective
+        pes: Vec<String>,
+        config: CollectiveConfigSection,
     },
     #[serde(rename = "tensor")]
     Tensor {
@@ -125,6 +463,83 @@ pub struct MemoryConfigSection {
     pub view: Option<TensorViewSection>,
 }
 
+/// The views of the input and output tensors of a DMA copy. When a view is not
+/// given the entire tensor is copied.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DmaConfigSection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_view: Option<TensorViewSection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_view: Option<TensorViewSection>,
+}
+
+/// The data that a Send node sends over the fabric to another PE. The Send
+/// completes once the data has left its PE.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SendConfigSection {
+    /// The PE that the data is sent to
+    pub to: String,
+    #[serde(deserialize_with = "gwr_platform::types::parse_usize_byte_str")]
+    pub bytes: usize,
+}
+
+/// The data that a Recv node waits to receive from another PE. The data sent
+/// from one PE to another is received in the order that it was sent.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecvConfigSection {
+    /// The PE that the data is sent by
+    pub from: String,
+    #[serde(deserialize_with = "gwr_platform::types::parse_usize_byte_str")]
+    pub bytes: usize,
+}
+
+/// Space that a task holds while it runs, in the SRAM of its PE or in a
+/// memory of the platform
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BufferSection {
+    #[serde(deserialize_with = "gwr_platform::types::parse_u64_byte_str")]
+    pub bytes: u64,
+    /// The memory that holds the buffer, rather than the SRAM of the PE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+}
+
+/// The data of a Collective node
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CollectiveConfigSection {
+    /// The number of bytes that each PE ends up with
+    #[serde(deserialize_with = "gwr_platform::types::parse_usize_byte_str")]
+    pub bytes: usize,
+    #[serde(default)]
+    pub algorithm: CollectiveAlgorithm,
+}
+
+/// How a Condition node selects the output whose successors run. The
+/// successors of the other outputs are skipped.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum ConditionPredicate {
+    /// Select output 0 with this probability and output 1 otherwise
+    Probability(f64),
+    /// Select the output with this index, such as a value read from a
+    /// register
+    Select(usize),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConditionConfigSection {
+    pub predicate: ConditionPredicate,
+    /// The seed of the random numbers used by a `probability` predicate
+    #[serde(default)]
+    pub seed: u64,
+}
+
 /// Assuming best-case packing, how many bytes would num_elements of the given
 /// dtype consume
 #[must_use]
@@ -167,16 +582,128 @@ impl NodeSection {
         match self {
             NodeSection::Compute { id, .. } => id,
             NodeSection::Memory { id, .. } => id,
+            NodeSection::Dma { id, .. } => id,
+            NodeSection::Send { id, .. } => id,
+            NodeSection::Recv { id, .. } => id,
+            NodeSection::Condition { id, .. } => id,
+            NodeSection::Collective { id, .. } => id,
             NodeSection::Tensor { id, .. } => id,
         }
     }
 
+    pub(crate) fn set_id(&mut self, new_id: String) {
+        match self {
+            NodeSection::Compute { id, .. }
+            | NodeSection::Memory { id, .. }
+            | NodeSection::Dma { id, .. }
+            | NodeSection::Send { id, .. }
+            | NodeSection::Recv { id, .. }
+            | NodeSection::Condition { id, .. }
+            | NodeSection::Collective { id, .. }
+            | NodeSection::Tensor { id, .. } => *id = new_id,
+        }
+    }
+
     #[must_use]
     pub fn id_pe(&self) -> (&String, &Option<String>) {
         match self {
             NodeSection::Compute { id, pe, .. } => (id, pe),
             NodeSection::Memory { id, pe, .. } => (id, pe),
-            NodeSection::Tensor { id, .. } => (id, &None),
+            NodeSection::Dma { id, pe, .. } => (id, pe),
+            NodeSection::Send { id, pe, .. } => (id, pe),
+            NodeSection::Recv { id, pe, .. } => (id, pe),
+            NodeSection::Condition { id, .. }
+            | NodeSection::Collective { id, .. }
+            | NodeSection::Tensor { id, .. } => (id, &None),
+        }
+    }
+
+    /// Returns the number of times that a node runs, if it is given
+    #[must_use]
+    pub fn iterations(&self) -> Option<usize> {
+        match self {
+            NodeSection::Compute { iterations, .. }
+            | NodeSection::Memory { iterations, .. }
+            | NodeSection::Dma { iterations, .. }
+            | NodeSection::Send { iterations, .. }
+            | NodeSection::Recv { iterations, .. } => *iterations,
+            NodeSection::Condition { .. }
+            | NodeSection::Collective { .. }
+            | NodeSection::Tensor { .. } => None,
+        }
+    }
+
+    /// Returns the absolute and relative deadlines of a node
+    fn deadlines(&self) -> (Option<u64>, Option<u64>) {
+        match self {
+            NodeSection::Compute {
+                deadline_ns,
+                relative_deadline_ns,
+                ..
+            }
+            | NodeSection::Memory {
+                deadline_ns,
+                relative_deadline_ns,
+                ..
+            }
+            | NodeSection::Dma {
+                deadline_ns,
+                relative_deadline_ns,
+                ..
+            }
+            | NodeSection::Send {
+                deadline_ns,
+                relative_deadline_ns,
+                ..
+            }
+            | NodeSection::Recv {
+                deadline_ns,
+                relative_deadline_ns,
+                ..
+            } => (*deadline_ns, *relative_deadline_ns),
+            NodeSection::Condition { .. }
+            | NodeSection::Collective { .. }
+            | NodeSection::Tensor { .. } => (None, None),
+        }
+    }
+
+    /// Returns the time by which a node should complete, if it has one
+    #[must_use]
+    pub fn deadline(&self) -> Option<Deadline> {
+        match self.deadlines() {
+            (Some(deadline_ns), _) => Some(Deadline::Absolute(deadline_ns)),
+            (None, Some(deadline_ns)) => Some(Deadline::Relative(deadline_ns)),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the buffer that a node holds while it runs, if it has one
+    #[must_use]
+    pub fn buffer(&self) -> Option<&BufferSection> {
+        match self {
+            NodeSection::Compute { buffer, .. }
+            | NodeSection::Memory { buffer, .. }
+            | NodeSection::Dma { buffer, .. }
+            | NodeSection::Send { buffer, .. }
+            | NodeSection::Recv { buffer, .. } => buffer.as_ref(),
+            NodeSection::Condition { .. }
+            | NodeSection::Collective { .. }
+            | NodeSection::Tensor { .. } => None,
+        }
+    }
+
+    /// Assign the node to a PE. Tensor and Condition nodes do not run on a
+    /// PE, so are left unchanged.
+    pub fn set_pe(&mut self, pe_name: &str) {
+        match self {
+            NodeSection::Compute { pe, .. }
+            | NodeSection::Memory { pe, .. }
+            | NodeSection::Dma { pe, .. }
+            | NodeSection::Send { pe, .. }
+            | NodeSection::Recv { pe, .. } => *pe = Some(pe_name.to_string()),
+            NodeSection::Condition { .. }
+            | NodeSection::Collective { .. }
+            | NodeSection::Tensor { .. } => {}
         }
     }
 
@@ -185,12 +712,17 @@ impl NodeSection {
         match self {
             NodeSection::Compute { pe, .. } => pe,
             NodeSection::Memory { pe, .. } => pe,
-            NodeSection::Tensor { .. } => &None,
+            NodeSection::Dma { pe, .. } => pe,
+            NodeSection::Send { pe, .. } => pe,
+            NodeSection::Recv { pe, .. } => pe,
+            NodeSection::Condition { .. }
+            | NodeSection::Collective { .. }
+            | NodeSection::Tensor { .. } => &None,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum EdgeKind {
     Data,
@@ -203,6 +735,39 @@ pub struct EdgeSection {
     pub from: String,
     pub to: String,
     pub kind: EdgeKind,
+    /// The number of bytes that the edge carries. An edge from a tensor to a
+    /// node on a different PE to the one that produced the tensor is preceded
+    /// by a transfer of this many bytes to the PE of the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<usize>,
+    /// Space held while the task at the end of the edge that runs on a PE
+    /// runs, see [buffers](crate::buffers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffer: Option<BufferSection>,
+}
+
+/// A group of nodes that runs a number of times, such as the steps of a
+/// training loop. Each iteration starts once every node of the previous
+/// iteration has completed, and the nodes outside the group that depend on it
+/// wait for the last iteration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubgraphSection {
+    pub id: String,
+    pub nodes: Vec<String>,
+    pub iterations: usize,
+}
+
+/// A group of nodes, such as one workload of several that share a platform,
+/// whose tasks are started before those of lower priority streams. The tasks
+/// of nodes that are not in a stream have priority 0.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct StreamSection {
+    pub id: String,
+    pub nodes: Vec<String>,
+    #[serde(default)]
+    pub priority: usize,
 }
 
 impl EdgeSection {
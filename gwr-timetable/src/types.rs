@@ -3,6 +3,8 @@
 //! Set of types used within the timetable that will wrap up any serializable /
 //! deserializable types that are used directly in the YAML file.
 
+use std::collections::BTreeSet;
+
 use gwr_models::processing_element::task::MemoryOp;
 
 use crate::timetable_file::NodeSection;
@@ -13,6 +15,8 @@ pub struct Node {
     pub node_section: NodeSection,
     pub inputs: Vec<Option<usize>>,
     pub outputs: Vec<Option<usize>>,
+    /// The Condition nodes that select whether this node runs
+    pub conditions: Vec<usize>,
 }
 
 impl Node {
@@ -59,3 +63,28 @@ impl Node {
         }
     }
 }
+
+/// A movement of the data carried by an edge from the PE that produced a
+/// tensor to the PE of the node that consumes it.
+pub struct Transfer {
+    /// The edge that the transfer was created for, as `from -> to`
+    pub id: String,
+    /// The index of the tensor node that the data is read from
+    pub tensor_idx: usize,
+    /// The index of the node that waits for the transfer
+    pub consumer_idx: usize,
+    /// The index of the PE that performs the transfer
+    pub pe_idx: usize,
+    pub addr: u64,
+    pub num_bytes: usize,
+}
+
+/// A group of tasks and tensors that runs a number of times before the nodes
+/// that depend on it can start.
+pub struct Loop {
+    /// The ID of the subgraph, or of the node that repeats on its own
+    pub id: String,
+    /// The indices of the tasks and tensors that are part of the loop
+    pub members: BTreeSet<usize>,
+    pub iterations: usize,
+}
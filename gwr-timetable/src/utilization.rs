@@ -0,0 +1,106 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! How each PE spent the time of a run.
+//!
+//! Once a timetable has run, the time of each PE is split into:
+//!
+//!  - busy: at least one of its tasks was running
+//!  - stalled: none of its tasks was running but it had tasks left to run,
+//!    which were waiting for the tasks that they depend on (or for space for
+//!    their [buffers](crate::buffers))
+//!  - idle: it had run all of its tasks
+//!
+//! The utilization of a PE is the fraction of the run that it was busy.
+
+use std::fmt;
+
+/// The time that a PE spent busy, stalled and idle
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeUtilization {
+    pub pe: String,
+    /// The number of runs of its tasks
+    pub num_tasks: usize,
+    pub busy_ns: f64,
+    pub stalled_ns: f64,
+    pub idle_ns: f64,
+}
+
+impl PeUtilization {
+    /// Create the breakdown of a PE from the start and end of each run of its
+    /// tasks in a run that lasted `total_ns`
+    pub(crate) fn new(pe: &str, mut runs: Vec<(f64, f64)>, total_ns: f64) -> Self {
+        runs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut busy_ns = 0.0;
+        let mut busy_until_ns = 0.0_f64;
+        for (start_ns, end_ns) in &runs {
+            let start_ns = start_ns.max(busy_until_ns);
+            if *end_ns > start_ns {
+                busy_ns += end_ns - start_ns;
+            }
+            busy_until_ns = busy_until_ns.max(*end_ns);
+        }
+        let finish_ns = busy_until_ns.min(total_ns);
+        Self {
+            pe: pe.to_string(),
+            num_tasks: runs.len(),
+            busy_ns,
+            stalled_ns: (finish_ns - busy_ns).max(0.0),
+            idle_ns: total_ns - finish_ns,
+        }
+    }
+
+    /// Returns the fraction of the run that the PE was busy
+    #[must_use]
+    pub fn utilization(&self) -> f64 {
+        let total_ns = self.busy_ns + self.stalled_ns + self.idle_ns;
+        if total_ns > 0.0 {
+            self.busy_ns / total_ns
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UtilizationReport {
+    /// The length of the run
+    pub total_ns: f64,
+    pub pes: Vec<PeUtilization>,
+}
+
+impl UtilizationReport {
+    /// Returns the fraction of the time of all PEs that they were busy
+    #[must_use]
+    pub fn utilization(&self) -> f64 {
+        let total_ns = self.total_ns * self.pes.len() as f64;
+        if total_ns > 0.0 {
+            self.pes.iter().map(|pe| pe.busy_ns).sum::<f64>() / total_ns
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for UtilizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "PE utilization {:.1}% over {}ns:",
+            100.0 * self.utilization(),
+            self.total_ns
+        )?;
+        for pe in &self.pes {
+            writeln!(
+                f,
+                "  {}: {:.1}% busy {}ns, stalled {}ns, idle {}ns ({} tasks)",
+                pe.pe,
+                100.0 * pe.utilization(),
+                pe.busy_ns,
+                pe.stalled_ns,
+                pe.idle_ns,
+                pe.num_tasks
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::buffers::{BufferOccupancy, CapacityPolicy};
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{add, edge, tensor};
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+      sram_bytes: 4KiB
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// Two adds that both read a and could run at the same time, with the given
+/// buffer for each and for the edge into input 0 of x
+fn timetable_yaml(buffer: &str, edge_buffer: &str) -> String {
+    let buffer = if buffer.is_empty() {
+        String::new()
+    } else {
+        format!("\n    buffer: {buffer}")
+    };
+    let edge_buffer = if edge_buffer.is_empty() {
+        String::new()
+    } else {
+        format!("\n    buffer: {edge_buffer}")
+    };
+    let nodes = [
+        tensor("a", 0, 64),
+        add("x", "pe0", &buffer),
+        add("y", "pe0", &buffer),
+        tensor("t_x", 0x1000, 64),
+        tensor("t_y", 0x2000, 64),
+    ];
+    let edges = [
+        format!("{}{edge_buffer}", edge("a", "x.0")),
+        edge("a", "x.1"),
+        edge("x", "t_x"),
+        edge("a", "y.0"),
+        edge("a", "y.1"),
+        edge("y", "t_y"),
+    ];
+    format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat())
+}
+
+fn build(source: &str) -> Result<Timetable, String> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    Timetable::new(engine.top(), timetable_file, &platform).map_err(|e| format!("{e}"))
+}
+
+fn run(source: &str, policy: CapacityPolicy) -> Result<(Rc<Timetable>, f64), String> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    let timetable = Rc::new(
+        Timetable::new(engine.top(), timetable_file, &platform)
+            .unwrap()
+            .with_capacity_policy(policy),
+    );
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    engine.run().map_err(|e| format!("{e}"))?;
+    timetable.check_tasks_complete().unwrap();
+    Ok((timetable, engine.time_now_ns()))
+}
+
+#[test]
+fn tasks_that_do_not_fit_together_fail_the_run() {
+    let err = run(&timetable_yaml("{bytes: 3KiB}", ""), CapacityPolicy::Error).unwrap_err();
+    assert!(
+        err.contains("needs 3072 bytes of buffers in the SRAM of PE 'pe0' but only 1024 of its 4096 bytes are free"),
+        "{err}"
+    );
+
+    // Both fit at once
+    let (timetable, _) = run(&timetable_yaml("{bytes: 2KiB}", ""), CapacityPolicy::Error).unwrap();
+    assert_eq!(
+        timetable.buffer_report().occupancies,
+        [BufferOccupancy {
+            name: "pe0".to_string(),
+            sram: true,
+            capacity_bytes: 4096,
+            peak_bytes: 4096,
+        }]
+    );
+}
+
+#[test]
+fn tasks_that_do_not_fit_together_stall() {
+    let (_, parallel_ns) = run(&timetable_yaml("", ""), CapacityPolicy::Stall).unwrap();
+    let (timetable, stalled_ns) =
+        run(&timetable_yaml("{bytes: 3KiB}", ""), CapacityPolicy::Stall).unwrap();
+    assert_eq!(timetable.buffer_report().occupancies[0].peak_bytes, 3072);
+    assert!(stalled_ns > parallel_ns, "{stalled_ns} <= {parallel_ns}");
+
+    let report = format!("{}", timetable.buffer_report());
+    assert!(
+        report.contains("the SRAM of PE 'pe0': 3072 of 4096 bytes (75.0%)"),
+        "{report}"
+    );
+}
+
+#[test]
+fn edge_and_memory_buffers_are_counted() {
+    let (timetable, _) = run(
+        &timetable_yaml("{bytes: 1MiB, memory: hbm0}", "{bytes: 1KiB}"),
+        CapacityPolicy::Error,
+    )
+    .unwrap();
+    let occupancies = timetable.buffer_report().occupancies;
+    let peak = |name: &str| {
+        occupancies
+            .iter()
+            .find(|occupancy| occupancy.name == name)
+            .unwrap()
+            .peak_bytes
+    };
+    assert_eq!(peak("hbm0"), 2 * 1024 * 1024);
+    assert_eq!(peak("pe0"), 1024);
+}
+
+#[test]
+fn infeasible_buffers_are_rejected() {
+    let check = |source: String, expected: &str| {
+        let err = build(&source).unwrap_err();
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        timetable_yaml("{bytes: 3KiB}", "{bytes: 2KiB}"),
+        "Node 'x' needs 5120 bytes of buffers in the SRAM of PE 'pe0' which only has 4096 bytes",
+    );
+    check(
+        timetable_yaml("{bytes: 1GiB, memory: hbm0}", ""),
+        "Node 'x' needs 1073741824 bytes of buffers in memory 'hbm0' which only has 268435456 bytes",
+    );
+    check(
+        timetable_yaml("{bytes: 1KiB, memory: ddr0}", ""),
+        "Node 'x' has a buffer in unknown memory 'ddr0'",
+    );
+}
+
+#[test]
+fn buffers_are_parsed_from_dot() {
+    let source = r#"
+digraph {
+    a [kind=tensor, addr=0, dtype=fp32, shape="[64]"];
+    x [kind=compute, op=add, pe=pe0, buffer="{bytes: 5KiB}"];
+    t_x [kind=tensor, addr=4096, dtype=fp32, shape="[64]"];
+    a -> x:0;
+    a -> x:1;
+    x -> t_x;
+}
+"#;
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_dot(source).unwrap();
+    let err = Timetable::new(engine.top(), timetable_file, &platform).unwrap_err();
+    assert!(
+        format!("{err}").contains("Node 'x' needs 5120 bytes"),
+        "{err}"
+    );
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::engine::Engine;
+use gwr_engine::events::once::Once;
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::checkpoint::TimetableCheckpoint;
+use gwr_timetable::scheduling::SchedulingPolicy;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{PLATFORM, add, edge, tensor};
+
+/// A chain of adds, each reading the output of the one before
+fn chain_yaml(length: usize, subgraph: &str) -> String {
+    let mut nodes = tensor("t0", 0, 64);
+    let mut edges = String::new();
+    for i in 1..=length {
+        nodes.push_str(&add(&format!("add{i}"), "pe0", ""));
+        nodes.push_str(&tensor(&format!("t{i}"), 0x1000 * i as u64, 64));
+        edges.push_str(&edge(&format!("t{}", i - 1), &format!("add{i}.0")));
+        edges.push_str(&edge(&format!("t{}", i - 1), &format!("add{i}.1")));
+        edges.push_str(&edge(&format!("add{i}"), &format!("t{i}")));
+    }
+    format!("nodes:{nodes}\n\nedges:{edges}\n{subgraph}")
+}
+
+fn build(
+    engine: &mut Engine,
+    source: &str,
+    checkpoint: Option<&TimetableCheckpoint>,
+) -> Result<Rc<Timetable>, String> {
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    let mut timetable = Timetable::new(engine.top(), timetable_file, &platform)
+        .unwrap()
+        .with_scheduling_policy(SchedulingPolicy::Fifo)
+        .with_clock(&clock);
+    if let Some(checkpoint) = checkpoint {
+        timetable = timetable
+            .with_checkpoint(checkpoint)
+            .map_err(|e| format!("{e}"))?;
+    }
+    let timetable = Rc::new(timetable);
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+    Ok(timetable)
+}
+
+/// Run a timetable to the end and return the time that it took
+fn run(source: &str, checkpoint: Option<&TimetableCheckpoint>) -> (Rc<Timetable>, f64) {
+    let mut engine = start_test(file!());
+    let timetable = build(&mut engine, source, checkpoint).unwrap();
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    (timetable, engine.time_now_ns())
+}
+
+/// Run a timetable for a number of ticks and take a checkpoint
+fn run_to_checkpoint(source: &str, ticks: u64) -> TimetableCheckpoint {
+    let mut engine = start_test(file!());
+    let timetable = build(&mut engine, source, None).unwrap();
+    let stop = Once::with_value(());
+    {
+        let clock = engine.default_clock();
+        let stop = stop.clone();
+        engine.spawn(async move {
+            clock.wait_ticks(ticks).await;
+            stop.notify()
+        });
+    }
+    engine.run_until(Box::new(stop)).unwrap();
+    timetable.checkpoint()
+}
+
+fn executed_tasks(timetable: &Timetable) -> Vec<String> {
+    timetable
+        .gantt_chart()
+        .unwrap()
+        .pes
+        .into_iter()
+        .flat_map(|pe| pe.tasks)
+        .map(|slice| slice.task)
+        .collect()
+}
+
+#[test]
+fn a_resumed_run_only_runs_the_remaining_tasks() {
+    let source = chain_yaml(8, "");
+    let (_, full_ns) = run(&source, None);
+
+    let checkpoint = run_to_checkpoint(&source, (full_ns / 2.0) as u64);
+    assert!(checkpoint.completed.contains(&"add1".to_string()));
+    assert!(!checkpoint.completed.contains(&"add8".to_string()));
+    assert_eq!(checkpoint.active.len(), 1);
+
+    let checkpoint = TimetableCheckpoint::from_json(&checkpoint.to_json()).unwrap();
+    let (timetable, resumed_ns) = run(&source, Some(&checkpoint));
+    let executed = executed_tasks(&timetable);
+    assert!(!executed.contains(&"add1".to_string()));
+    assert!(executed.contains(&checkpoint.active[0]));
+    assert!(executed.contains(&"add8".to_string()));
+    assert!(resumed_ns < full_ns);
+}
+
+#[test]
+fn loops_resume_with_the_iterations_left() {
+    let source = chain_yaml(
+        2,
+        "
+subgraphs:
+  - id: step
+    nodes: [add1, t1, add2]
+    iterations: 6
+",
+    );
+    let (_, full_ns) = run(&source, None);
+
+    let checkpoint = run_to_checkpoint(&source, (full_ns / 2.0) as u64);
+    let remaining = checkpoint.remaining_iterations["step"];
+    assert!(remaining > 1 && remaining < 6, "{remaining}");
+
+    let (timetable, _) = run(&source, Some(&checkpoint));
+    let executed = executed_tasks(&timetable);
+    let runs = executed.iter().filter(|task| *task == "add2").count();
+    assert!(runs == remaining || runs == remaining - 1, "{runs}");
+}
+
+#[test]
+fn invalid_checkpoints_are_rejected() {
+    let check = |source: &str, checkpoint: TimetableCheckpoint, expected: &str| {
+        let mut engine = start_test(file!());
+        let err = build(&mut engine, source, Some(&checkpoint)).unwrap_err();
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        &chain_yaml(2, ""),
+        TimetableCheckpoint {
+            completed: vec!["add9".to_string()],
+            ..TimetableCheckpoint::default()
+        },
+        "Checkpoint names task 'add9' which is not in the timetable",
+    );
+    check(
+        &chain_yaml(
+            2,
+            "\nsubgraphs:\n  - id: step\n    nodes: [add1]\n    iterations: 3\n",
+        ),
+        TimetableCheckpoint {
+            remaining_iterations: [("step".to_string(), 4)].into(),
+            ..TimetableCheckpoint::default()
+        },
+        "Checkpoint has 4 iterations left of loop 'step' which runs 3 times",
+    );
+    assert!(TimetableCheckpoint::from_json("{").is_err());
+}
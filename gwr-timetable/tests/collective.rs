@@ -0,0 +1,203 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::{NodeSection, TimetableFile};
+
+pub mod common;
+use common::{edge, run, tensor};
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+  - name: pe2
+    memory_map: mm0
+    config:
+  - name: pe3
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 5
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - pe.pe2@(0,0)
+      - pe.pe3@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// The gradients g are reduced across the PEs into r
+fn timetable_yaml(op: &str, algorithm: &str, pes: &str, bytes: &str) -> String {
+    let nodes = [
+        tensor("g", 0, 1024),
+        format!(
+            "
+  - id: grads
+    kind: collective
+    op: {op}
+    pes: {pes}
+    config:
+      bytes: {bytes}
+      algorithm: {algorithm}"
+        ),
+        tensor("r", 0x1000, 1024),
+    ];
+    let edges = [edge("g", "grads"), edge("grads", "r")];
+    format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat())
+}
+
+fn all_reduce(algorithm: &str, bytes: &str) -> String {
+    timetable_yaml("all_reduce", algorithm, "[pe0, pe1, pe2, pe3]", bytes)
+}
+
+fn build(source: &str) -> Result<Timetable, String> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    Timetable::new(engine.top(), timetable_file, &platform).map_err(|e| format!("{e}"))
+}
+
+#[test]
+fn collectives_are_expanded_into_sends_and_receives() {
+    let mut timetable_file = TimetableFile::from_string(&all_reduce("ring", "4KiB")).unwrap();
+    timetable_file.expand_collectives().unwrap();
+
+    // Each of the 4 PEs sends a quarter of the data in each of the 6 steps
+    let sends: Vec<&NodeSection> = timetable_file
+        .nodes
+        .iter()
+        .filter(|node| matches!(node, NodeSection::Send { .. }))
+        .collect();
+    assert_eq!(sends.len(), 4 * 6);
+    for send in &sends {
+        let NodeSection::Send { config, .. } = send else {
+            unreachable!();
+        };
+        assert_eq!(config.bytes, 1024);
+    }
+    let ids: Vec<&str> = timetable_file
+        .nodes
+        .iter()
+        .map(|node| node.id().as_str())
+        .collect();
+    assert!(ids.contains(&"grads_send0_pe0_pe1"), "{ids:?}");
+    assert!(ids.contains(&"grads_recv5_pe0_pe3"), "{ids:?}");
+    assert!(!ids.contains(&"grads"));
+
+    // The output waits for the last send and receive of every PE
+    let into_r = timetable_file
+        .edges
+        .iter()
+        .filter(|edge| edge.to == "r")
+        .count();
+    assert_eq!(into_r, 2 * 4);
+}
+
+#[test]
+fn collectives_run_across_the_fabric() {
+    for algorithm in ["ring", "tree"] {
+        for op in ["all_reduce", "broadcast", "all_gather"] {
+            let source = timetable_yaml(op, algorithm, "[pe0, pe1, pe2, pe3]", "4KiB");
+            run(
+                file!(),
+                PLATFORM,
+                TimetableFile::from_string(&source).unwrap(),
+            );
+        }
+        let source = timetable_yaml("all_reduce", algorithm, "[pe2, pe0, pe3]", "1000");
+        run(
+            file!(),
+            PLATFORM,
+            TimetableFile::from_string(&source).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn larger_collectives_take_longer() {
+    let small = TimetableFile::from_string(&all_reduce("ring", "4KiB")).unwrap();
+    let large = TimetableFile::from_string(&all_reduce("ring", "64KiB")).unwrap();
+    let small_ns = run(file!(), PLATFORM, small);
+    let large_ns = run(file!(), PLATFORM, large);
+    assert!(small_ns < large_ns, "{small_ns} >= {large_ns}");
+}
+
+#[test]
+fn dot_collectives() {
+    let mut timetable_file = TimetableFile::from_dot(
+        "digraph {
+            node [kind=tensor, dtype=fp32, shape=\"[1024]\"];
+            g [addr=0];
+            r [addr=0x1000];
+            grads [kind=collective, op=broadcast, pes=\"[pe0, pe1]\", bytes=4KiB, algorithm=tree];
+            g -> grads -> r;
+        }",
+    )
+    .unwrap();
+    timetable_file.expand_collectives().unwrap();
+    let ids: Vec<&str> = timetable_file
+        .nodes
+        .iter()
+        .map(|node| node.id().as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        ["g", "r", "grads_send0_pe0_pe1", "grads_recv0_pe1_pe0"]
+    );
+}
+
+#[test]
+fn invalid_collectives_are_rejected() {
+    let check = |source: String, expected: &str| {
+        let err = build(&source).unwrap_err();
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        timetable_yaml("all_reduce", "ring", "[pe0]", "4KiB"),
+        "Collective node 'grads' needs at least 2 PEs",
+    );
+    check(
+        timetable_yaml("all_reduce", "ring", "[pe0, pe1, pe0]", "4KiB"),
+        "Collective node 'grads' has PE 'pe0' more than once",
+    );
+    check(
+        timetable_yaml("all_reduce", "ring", "[pe0, pe7]", "4KiB"),
+        "Node 'grads_recv0_pe7_pe0' contains invalid PE ID 'pe7'",
+    );
+    check(
+        all_reduce("ring", "4KiB").replace(
+            "  - from: g\n    to: grads",
+            "  - from: grads\n    to: grads",
+        ),
+        "Collective node 'grads' can only be connected to Tensor nodes",
+    );
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Fixtures shared by the timetable tests.
+//!
+//! Most functions return the YAML of a single timetable node or edge so that
+//! tests can concatenate them under `nodes:` and `edges:`.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+/// A platform with a single PE and memory attached to a routed fabric
+pub const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// A tensor of `num_elements` fp32 values at `addr`
+#[must_use]
+pub fn tensor(id: &str, addr: u64, num_elements: usize) -> String {
+    format!(
+        "
+  - id: {id}
+    kind: tensor
+    config:
+      addr: {addr}
+      dtype: fp32
+      shape: [{num_elements}]"
+    )
+}
+
+/// An add of two inputs on `pe`, followed by any `extra` fields of the node
+#[must_use]
+pub fn add(id: &str, pe: &str, extra: &str) -> String {
+    format!(
+        "
+  - id: {id}
+    kind: compute
+    op: add
+    pe: {pe}
+    input_views: [null, null]
+    output_views: [null]{extra}"
+    )
+}
+
+/// A DMA of kind `op` performed by `pe`
+#[must_use]
+pub fn dma(id: &str, op: &str, pe: &str) -> String {
+    format!(
+        "
+  - id: {id}
+    kind: dma
+    op: {op}
+    pe: {pe}"
+    )
+}
+
+/// A data edge
+#[must_use]
+pub fn edge(from: &str, to: &str) -> String {
+    format!(
+        "
+  - from: {from}
+    to: {to}
+    kind: data"
+    )
+}
+
+/// The edges from `inputs` into the two inputs of the add `id` and from the
+/// add to its `output`
+#[must_use]
+pub fn add_edges(inputs: [&str; 2], id: &str, output: &str) -> String {
+    [
+        edge(inputs[0], &format!("{id}.0")),
+        edge(inputs[1], &format!("{id}.1")),
+        edge(id, output),
+    ]
+    .concat()
+}
+
+/// Run `timetable_file` to completion on the platform described by
+/// `platform_yaml` and return the time at which it finished.
+///
+/// The `test_file` names the trace of the run, as with `start_test`.
+pub fn run(test_file: &str, platform_yaml: &str, timetable_file: TimetableFile) -> f64 {
+    let mut engine = start_test(test_file);
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, platform_yaml).unwrap());
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    clock.time_now_ns()
+}
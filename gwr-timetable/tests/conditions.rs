@@ -0,0 +1,166 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{PLATFORM, add, edge, tensor};
+
+/// The condition c selects x (output 0) or y (output 1), which y2 follows,
+/// and z joins the two paths
+fn timetable_yaml(predicate: &str) -> String {
+    let nodes = [
+        tensor("a", 0, 64),
+        format!(
+            "
+  - id: c
+    kind: condition
+    config:
+      predicate: {predicate}"
+        ),
+        add("x", "pe0", ""),
+        add("y", "pe0", ""),
+        add("y2", "pe0", ""),
+        add("z", "pe0", ""),
+        tensor("t_x", 0x1000, 64),
+        tensor("t_y", 0x2000, 64),
+        tensor("t_y2", 0x3000, 64),
+        tensor("t_z", 0x4000, 64),
+    ];
+    let edges = [
+        edge("a", "c"),
+        edge("c.0", "x"),
+        edge("c.1", "y"),
+        edge("a", "x.0"),
+        edge("a", "x.1"),
+        edge("x", "t_x"),
+        edge("a", "y.0"),
+        edge("a", "y.1"),
+        edge("y", "t_y"),
+        edge("t_y", "y2.0"),
+        edge("t_y", "y2.1"),
+        edge("y2", "t_y2"),
+        edge("t_x", "z.0"),
+        edge("t_y2", "z.1"),
+        edge("z", "t_z"),
+    ];
+    format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat())
+}
+
+const X: usize = 2;
+const Y: usize = 3;
+const Y2: usize = 4;
+const Z: usize = 5;
+
+fn build(source: &str) -> Result<Timetable, String> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    Timetable::new(engine.top(), timetable_file, &platform).map_err(|e| format!("{e}"))
+}
+
+fn run(source: &str) -> Rc<Timetable> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    timetable
+}
+
+fn ready(timetable: &Timetable) -> Vec<usize> {
+    timetable.ready_task_indices("pe0").unwrap().1
+}
+
+#[test]
+fn only_the_selected_path_runs() {
+    let timetable = build(&timetable_yaml("{select: 1}")).unwrap();
+    assert_eq!(ready(&timetable), [Y]);
+    assert!(timetable.was_skipped(X));
+
+    timetable.set_task_active(Y).unwrap();
+    timetable.set_task_completed(Y).unwrap();
+    assert_eq!(ready(&timetable), [Y2]);
+    timetable.set_task_active(Y2).unwrap();
+    timetable.set_task_completed(Y2).unwrap();
+
+    // z joins the paths, so runs as one of its inputs was produced
+    assert_eq!(ready(&timetable), [Z]);
+    timetable.set_task_active(Z).unwrap();
+    timetable.set_task_completed(Z).unwrap();
+    assert_eq!(timetable.ready_task_indices("pe0").unwrap(), (true, vec![]));
+    timetable.check_tasks_complete().unwrap();
+}
+
+#[test]
+fn nodes_that_only_follow_skipped_nodes_are_skipped() {
+    let timetable = run(&timetable_yaml("{select: 0}"));
+    assert!(!timetable.was_skipped(X));
+    assert!(timetable.was_skipped(Y));
+    assert!(timetable.was_skipped(Y2));
+    assert!(!timetable.was_skipped(Z));
+}
+
+#[test]
+fn probabilities_select_an_output() {
+    let timetable = run(&timetable_yaml("{probability: 1.0}"));
+    assert!(!timetable.was_skipped(X));
+    assert!(timetable.was_skipped(Y));
+
+    let timetable = run(&timetable_yaml("{probability: 0.0}"));
+    assert!(timetable.was_skipped(X));
+    assert!(!timetable.was_skipped(Y));
+}
+
+#[test]
+fn conditions_in_loops_are_evaluated_every_iteration() {
+    let source = format!(
+        "{}
+subgraphs:
+  - id: step
+    nodes: [c, x, y, y2, z]
+    iterations: 8
+",
+        timetable_yaml("{probability: 0.5}")
+    );
+    run(&source);
+}
+
+#[test]
+fn invalid_conditions_are_rejected() {
+    let check = |source: String, expected: &str| {
+        let err = build(&source).unwrap_err();
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        timetable_yaml("{probability: 1.5}"),
+        "Condition node 'c' has probability 1.5 which is not between 0 and 1",
+    );
+    check(
+        timetable_yaml("{select: 2}"),
+        "Condition node 'c' selects output 2 but has 2 outputs",
+    );
+    check(
+        // w is not selected by c, so its output does not form a cycle
+        timetable_yaml("{select: 0}")
+            .replace("\n\nedges:", &format!("{}\n\nedges:", add("w", "pe0", "")))
+            .replace(
+                "  - from: a\n    to: c\n",
+                "  - from: a\n    to: w.0\n    kind: data\n  - from: a\n    to: w.1\n    kind: data\n  - from: w\n    to: c\n",
+            ),
+        "Condition node 'c' can only have inputs from Tensor nodes",
+    );
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::cost_hook::TaskCostContext;
+use gwr_timetable::timetable_file::{NodeSection, TimetableFile};
+
+const TIMETABLE_YAML: &str = "
+nodes:
+  - id: input_a
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [4]
+
+  - id: input_b
+    kind: tensor
+    config:
+      addr: 0x400
+      dtype: fp32
+      shape: [4]
+
+  - id: add
+    kind: compute
+    op: add
+    pe: pe0
+    input_views:
+      -
+      -
+    output_views:
+      -
+
+  - id: output
+    kind: tensor
+    config:
+      addr: 0x800
+      dtype: fp32
+      shape: [4]
+
+edges:
+  - from: input_a
+    to: add.0
+    kind: data
+
+  - from: input_b
+    to: add.1
+    kind: data
+
+  - from: add
+    to: output
+    kind: data
+";
+
+const PLATFORM_YAML: &str = "
+memory_maps:
+  - name: default
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: default
+    config:
+      adds_per_tick: 16.0
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - connect:
+      - pe.pe0
+      - mem.hbm0
+";
+
+type CostModel = Box<dyn Fn(&TaskCostContext<'_>) -> Option<usize>>;
+
+/// Run the timetable with an optional cost model and return the time taken
+fn run_with_cost_model(cost_model: Option<CostModel>) -> f64 {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM_YAML).unwrap());
+    let timetable_file = TimetableFile::from_string(TIMETABLE_YAML).unwrap();
+    let mut timetable = Timetable::new(engine.top(), timetable_file, &platform).unwrap();
+    if let Some(cost_model) = cost_model {
+        timetable = timetable.with_cost_model(move |task| Ok(cost_model(task)));
+    }
+    let timetable = Rc::new(timetable);
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    engine.time_now_ns()
+}
+
+#[test]
+fn cost_model_decides_compute_time() {
+    // The PE performs the 4 adds in a single tick
+    let default_ns = run_with_cost_model(None);
+    let modelled_ns = run_with_cost_model(Some(Box::new(|_| Some(500))));
+    assert_eq!(modelled_ns - default_ns, 499.0);
+}
+
+#[test]
+fn cost_model_is_given_the_task_and_pe() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let seen_by_model = seen.clone();
+    let deferred_ns = run_with_cost_model(Some(Box::new(move |task| {
+        let NodeSection::Compute { op, .. } = task.node else {
+            panic!("only compute tasks are modelled");
+        };
+        seen_by_model.borrow_mut().push((
+            task.node.id().clone(),
+            op.trace_name(),
+            task.inputs.len(),
+            task.outputs.len(),
+            task.pe.to_string(),
+            task.pe_config.adds_per_tick,
+        ));
+        None
+    })));
+    assert_eq!(
+        *seen.borrow(),
+        [(
+            "add".to_string(),
+            "add",
+            2,
+            1,
+            "pe0".to_string(),
+            Some(16.0)
+        )]
+    );
+
+    // Returning None leaves the time to the PE
+    assert_eq!(deferred_ns, run_with_cost_model(None));
+}
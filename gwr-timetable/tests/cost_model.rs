@@ -0,0 +1,156 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+const TIMETABLE_YAML: &str = "
+nodes:
+  - id: input_a
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [4]
+
+  - id: input_b
+    kind: tensor
+    config:
+      addr: 0x400
+      dtype: fp32
+      shape: [4]
+
+  - id: add
+    kind: compute
+    op: add
+    pe: pe0
+    input_views:
+      -
+      -
+    output_views:
+      -
+
+  - id: output
+    kind: tensor
+    config:
+      addr: 0x800
+      dtype: fp32
+      shape: [4]
+
+edges:
+  - from: input_a
+    to: add.0
+    kind: data
+
+  - from: input_b
+    to: add.1
+    kind: data
+
+  - from: add
+    to: output
+    kind: data
+";
+
+/// Run the timetable on a PE with the given config and return the time taken
+fn run_with_pe_config(pe_config: &str) -> f64 {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform_yaml = format!(
+        "
+memory_maps:
+  - name: default
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: default
+    config:
+{pe_config}
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - connect:
+      - pe.pe0
+      - mem.hbm0
+"
+    );
+    let platform = Rc::new(Platform::from_string(&engine, &clock, &platform_yaml).unwrap());
+    let timetable_file = TimetableFile::from_string(TIMETABLE_YAML).unwrap();
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    engine.time_now_ns()
+}
+
+#[test]
+fn cost_model_determines_compute_time() {
+    // The default throughput performs the 4 adds in a single tick
+    let default_ns = run_with_pe_config("      adds_per_tick: 16.0");
+
+    // Unpipelined adds with a latency of 100 ticks issued one at a time
+    let slow_ns = run_with_pe_config(
+        "      cost_model:
+        issue_width: 1
+        pipelined: false
+        add_latency_ticks: 100",
+    );
+    assert_eq!(slow_ns - default_ns, 399.0);
+
+    // Pipelined adds only see the latency once
+    let pipelined_ns = run_with_pe_config(
+        "      cost_model:
+        issue_width: 1
+        add_latency_ticks: 100",
+    );
+    assert_eq!(pipelined_ns - default_ns, 102.0);
+}
+
+#[test]
+fn zero_issue_width_is_rejected() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let result = Platform::from_string(
+        &engine,
+        &clock,
+        "
+memory_maps:
+  - name: default
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: default
+    config:
+      cost_model:
+        issue_width: 0
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+",
+    );
+    match result {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "PE cost model issue_width must be greater than 0"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{PLATFORM, add, add_edges, tensor};
+
+/// x and then y are on the critical path, while w is short and independent
+fn timetable_file() -> TimetableFile {
+    let nodes = [
+        tensor("a", 0, 4096),
+        tensor("b", 0x10000, 64),
+        tensor("t_x", 0x20000, 4096),
+        tensor("t_y", 0x30000, 4096),
+        tensor("t_w", 0x40000, 64),
+        add("x", "pe0", ""),
+        add("y", "pe0", ""),
+        add("w", "pe0", ""),
+    ];
+    let edges = [
+        add_edges(["a", "a"], "x", "t_x"),
+        add_edges(["t_x", "t_x"], "y", "t_y"),
+        add_edges(["b", "b"], "w", "t_w"),
+    ];
+    let source = format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat());
+    TimetableFile::from_string(&source).unwrap()
+}
+
+#[test]
+fn critical_path_uses_simulated_times() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable = Rc::new(
+        Timetable::new(engine.top(), timetable_file(), &platform)
+            .unwrap()
+            .with_clock(&clock),
+    );
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+    run_simulation!(engine);
+
+    let report = timetable.critical_path_report().unwrap();
+    assert_eq!(report.critical_path, ["x", "y"]);
+    assert!(report.length_ns > 0.0);
+    assert!(report.length_ns <= clock.time_now_ns());
+
+    let task = |id: &str| report.tasks.iter().find(|task| task.id == id).unwrap();
+    for id in ["x", "y", "w"] {
+        assert!(task(id).duration_ns() > 0.0, "{id}");
+    }
+    assert!(task("x").end_ns <= task("y").start_ns);
+    assert!(task("x").slack_ns.abs() < 1e-6);
+    assert!(task("y").slack_ns.abs() < 1e-6);
+    assert!(task("w").slack_ns > 0.0);
+    assert!(format!("{report}").contains("x -> y"));
+}
+
+#[test]
+fn tasks_are_only_timed_with_a_clock() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable = Timetable::new(engine.top(), timetable_file(), &platform).unwrap();
+    let err = timetable.critical_path_report().unwrap_err();
+    assert!(format!("{err}").contains("created with a clock"), "{err}");
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::deadlines::DeadlineAction;
+use gwr_timetable::timetable_file::TimetableFile;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// A chain of adds, each reading the output of the one before, with the extra
+/// fields given for each add
+fn chain_yaml(deadlines: &[&str], rest: &str) -> String {
+    let mut nodes = String::from(
+        "
+  - id: t0
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [64]",
+    );
+    let mut edges = String::new();
+    for (i, deadline) in deadlines.iter().enumerate() {
+        let i = i + 1;
+        nodes.push_str(&format!(
+            "
+  - id: add{i}
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]{deadline}
+  - id: t{i}
+    kind: tensor
+    config:
+      addr: {}
+      dtype: fp32
+      shape: [64]",
+            0x1000 * i
+        ));
+        edges.push_str(&format!(
+            "
+  - from: t{0}
+    to: add{i}.0
+    kind: data
+  - from: t{0}
+    to: add{i}.1
+    kind: data
+  - from: add{i}
+    to: t{i}
+    kind: data",
+            i - 1
+        ));
+    }
+    format!("nodes:{nodes}\n\nedges:{edges}\n{rest}")
+}
+
+fn run(source: &str, action: DeadlineAction) -> Result<Rc<Timetable>, String> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    let timetable = Rc::new(
+        Timetable::new(engine.top(), timetable_file, &platform)
+            .map_err(|e| format!("{e}"))?
+            .with_deadline_action(action)
+            .with_clock(&clock),
+    );
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    engine.run().map_err(|e| format!("{e}"))?;
+    timetable.check_tasks_complete().unwrap();
+    Ok(timetable)
+}
+
+fn start_ns(timetable: &Timetable, task: &str) -> f64 {
+    timetable
+        .gantt_chart()
+        .unwrap()
+        .pes
+        .iter()
+        .flat_map(|pe| &pe.tasks)
+        .find(|slice| slice.task == task)
+        .unwrap()
+        .start_ns
+}
+
+#[test]
+fn missed_deadlines_are_recorded() {
+    let source = chain_yaml(
+        &["\n    deadline_ns: 1000000", "", "\n    deadline_ns: 1"],
+        "",
+    );
+    let timetable = run(&source, DeadlineAction::Record).unwrap();
+    let report = timetable.deadline_report().unwrap();
+    assert_eq!(report.num_checked, 2);
+    assert_eq!(report.misses.len(), 1);
+    assert_eq!(report.misses[0].task, "add3");
+    assert_eq!(report.misses[0].deadline_ns, 1.0);
+
+    let report = format!("{report}");
+    assert!(report.starts_with("Met 1 of 2 deadlines"), "{report}");
+    assert!(report.contains("add3 completed at"), "{report}");
+}
+
+#[test]
+fn missed_deadlines_can_fail_the_run() {
+    let source = chain_yaml(&["", "\n    deadline_ns: 1"], "");
+    let err = run(&source, DeadlineAction::Fail).unwrap_err();
+    assert!(err.contains("Task 'add2' completed at"), "{err}");
+    assert!(err.contains("after its deadline of 1ns"), "{err}");
+
+    // Warnings do not stop the run
+    let timetable = run(&source, DeadlineAction::Warn).unwrap();
+    assert_eq!(timetable.deadline_report().unwrap().misses.len(), 1);
+}
+
+#[test]
+fn relative_deadlines_are_from_the_start_of_the_graph() {
+    // add2 is in a stream of its own, which starts once add1 has completed
+    let source = chain_yaml(
+        &[
+            "\n    relative_deadline_ns: 1000000",
+            "\n    relative_deadline_ns: 1",
+        ],
+        "
+streams:
+  - id: first
+    nodes: [add1]
+  - id: second
+    nodes: [add2]
+",
+    );
+    let timetable = run(&source, DeadlineAction::Record).unwrap();
+    let report = timetable.deadline_report().unwrap();
+    assert_eq!(report.num_checked, 2);
+    assert_eq!(report.misses.len(), 1);
+    let second_start_ns = start_ns(&timetable, "add2");
+    assert!(second_start_ns > 0.0);
+    assert_eq!(report.misses[0].deadline_ns, second_start_ns + 1.0);
+}
+
+#[test]
+fn nodes_cannot_have_both_kinds_of_deadline() {
+    let source = chain_yaml(&["\n    deadline_ns: 10\n    relative_deadline_ns: 10"], "");
+    let err = run(&source, DeadlineAction::Record).unwrap_err();
+    assert!(
+        err.contains("Node 'add1' has both a deadline_ns and a relative_deadline_ns"),
+        "{err}"
+    );
+}
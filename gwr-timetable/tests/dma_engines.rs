@@ -0,0 +1,109 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{add, add_edges, dma, edge, tensor};
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+dma_engines:
+  - name: dma0
+    memory_map: mm0
+    config:
+      scratchpad:
+        base_address: 0x8000_0000
+        capacity_bytes: 0x1000
+
+accelerators:
+  - name: acc0
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - connect:
+      - dma.dma0
+      - fabric.fabric0@(0,0).0
+  - connect:
+      - acc.acc0
+      - fabric.fabric0@(0,0).1
+  - connect:
+      - mem.hbm0
+      - fabric.fabric0@(0,0).2
+";
+
+/// Stage a tensor through the scratchpad of the DMA engine into a copy in
+/// memory, then add the copy to itself on the accelerator
+fn timetable_yaml(add_pe: &str) -> String {
+    let nodes = [
+        tensor("a", 0x0, 64),
+        tensor("a_spm", 0x8000_0000, 64),
+        tensor("b", 0x1000, 64),
+        tensor("c", 0x2000, 64),
+        dma("copy_in", "copy_in", "dma0"),
+        dma("copy_out", "copy_out", "dma0"),
+        add("add", add_pe, ""),
+    ];
+    let edges = [
+        edge("a", "copy_in"),
+        edge("copy_in", "a_spm"),
+        edge("a_spm", "copy_out"),
+        edge("copy_out", "b"),
+        add_edges(["b", "b"], "add", "c"),
+    ];
+    format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat())
+}
+
+#[test]
+fn timetable_targets_dma_engines_and_accelerators() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(&timetable_yaml("acc0")).unwrap();
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+
+    assert_eq!(platform.dma_engine("dma0").unwrap().num_dma_copies(), 2);
+    assert_eq!(platform.accelerator("acc0").unwrap().total_graph_nodes(), 1);
+}
+
+#[test]
+fn compute_nodes_cannot_run_on_dma_engines() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(&timetable_yaml("dma0")).unwrap();
+    let err = Timetable::new(engine.top(), timetable_file, &platform).unwrap_err();
+    assert!(
+        format!("{err}").contains("Compute node 'add' cannot run on DMA engine 'dma0'"),
+        "{err}"
+    );
+}
@@ -0,0 +1,212 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::path::Path;
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+dma_engines:
+  - name: dma0
+    memory_map: mm0
+    config:
+      scratchpad:
+        base_address: 0x8000_0000
+        capacity_bytes: 0x1000
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - dma.dma0@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+const GRAPH: &str = r#"
+/* A graph as it might be exported from another tool, with drawing
+   attributes that are ignored */
+strict digraph "copy and add" {
+    rankdir=LR;
+    graph [fontname=Helvetica];
+    node [kind=tensor, dtype=fp32, shape="[64]", color=blue];
+
+    subgraph cluster_inputs {
+        label="inputs";
+        a [addr=0, label=<<b>a</b>>];
+    }
+    a_spm [addr=0x8000_0000];
+    b [config="{addr: 0x1000, dtype: fp32, shape: [64]}"];
+    c [addr=0x2000];
+
+    node [kind=dma, pe=dma0, shape=box];
+    copy_in [op=copy_in];
+    copy_out [op=copy_out];
+    add [kind=compute, op=add, pe=pe0, priority=1];
+
+    a -> copy_in -> a_spm -> copy_out -> b;
+    b -> add:0:n;
+    b -> add:1:s [color=red];
+    add -> c;
+}
+"#;
+
+#[test]
+fn dot_graphs_match_yaml() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+    let yaml = TimetableFile::from_file(&examples.join("tiny.yaml")).unwrap();
+    let dot = TimetableFile::from_file(&examples.join("tiny.dot")).unwrap();
+    assert_eq!(
+        serde_yaml::to_string(&dot).unwrap(),
+        serde_yaml::to_string(&yaml).unwrap()
+    );
+}
+
+#[test]
+fn dot_graphs_are_run() {
+    let timetable_file = TimetableFile::from_dot(GRAPH).unwrap();
+    let ids: Vec<&str> = timetable_file
+        .nodes
+        .iter()
+        .map(|n| n.id().as_str())
+        .collect();
+    assert_eq!(ids, ["a", "a_spm", "b", "c", "copy_in", "copy_out", "add"]);
+    let edges: Vec<(&str, &str)> = timetable_file
+        .edges
+        .iter()
+        .map(|e| (e.from.as_str(), e.to.as_str()))
+        .collect();
+    assert_eq!(
+        edges,
+        [
+            ("a", "copy_in"),
+            ("copy_in", "a_spm"),
+            ("a_spm", "copy_out"),
+            ("copy_out", "b"),
+            ("b", "add.0"),
+            ("b", "add.1"),
+            ("add", "c"),
+        ]
+    );
+
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    assert_eq!(platform.dma_engine("dma0").unwrap().num_dma_copies(), 2);
+}
+
+#[test]
+fn views_are_given_for_data_edges() {
+    let timetable_file = TimetableFile::from_dot(
+        "
+digraph {
+    node [kind=tensor, addr=0, dtype=fp32, shape=\"[64]\"];
+    edge [kind=control];
+    a; b; c;
+    node [kind=compute, op=add, pe=pe0];
+    first; second;
+    first -> second;
+    edge [kind=data];
+    {a b} -> second;
+    second -> c [bytes=256];
+}
+",
+    )
+    .unwrap();
+
+    let yaml = serde_yaml::to_string(&timetable_file.nodes[4]).unwrap();
+    assert!(
+        yaml.contains("input_views:\n- null\n- null\noutput_views:\n- null\n"),
+        "{yaml}"
+    );
+    let kinds: Vec<String> = timetable_file
+        .edges
+        .iter()
+        .map(|e| format!("{} -> {}: {:?}", e.from, e.to, e.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "first -> second: Control",
+            "a -> second: Data",
+            "b -> second: Data",
+            "second -> c: Data",
+        ]
+    );
+    assert_eq!(timetable_file.edges[3].bytes, Some(256));
+}
+
+#[test]
+fn invalid_dot_graphs_are_rejected() {
+    let check = |from: &str, to: &str, expected: &str| {
+        let source = GRAPH.replace(from, to);
+        let err = format!("{}", TimetableFile::from_dot(&source).unwrap_err());
+        assert!(err.contains(expected), "{err}");
+    };
+
+    check(
+        "strict digraph",
+        "graph",
+        "Timetable graphs must be a digraph",
+    );
+    check("rankdir=LR;", "rankdir=LR; d;", "Node 'd' has no kind");
+    check(
+        "copy_in [op=copy_in]",
+        "copy_in [op=copy_in, kind=cache]",
+        "Node 'copy_in' has unknown kind 'cache'",
+    );
+    check(
+        "c [addr=0x2000]",
+        "c [addr=0x2000, shape=\"[64\"]",
+        "Unable to parse attribute 'shape' of 'c' ('[64')",
+    );
+    check("priority=1", "priority=high", "Invalid node 'add'");
+    check(
+        "add -> c;",
+        "add -> c [kind=both];",
+        "Invalid edge 'add' -> 'c'",
+    );
+    check(
+        "add -> c;\n}",
+        "add -> c;\n",
+        "Expected '}' at the end of the DOT graph",
+    );
+    check(
+        "b -> add:1:s",
+        "b -> add:1:s:",
+        "Expected an ID but found '['",
+    );
+}
@@ -0,0 +1,170 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+const TIMETABLE: &str = "
+nodes:
+  - id: a
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [64]
+  - id: b
+    kind: tensor
+    config:
+      addr: 0x1000
+      dtype: fp32
+      shape: [64]
+  - id: c
+    kind: tensor
+    config:
+      addr: 0x2000
+      dtype: fp32
+      shape: [64]
+  - id: add0
+    kind: compute
+    op: add
+    pe: pe0
+    input_views:
+      -
+      -
+    output_views:
+      -
+  - id: add1
+    kind: compute
+    op: add
+    pe: pe1
+    input_views:
+      -
+      -
+    output_views:
+      -
+
+edges:
+  - from: a
+    to: add0.0
+    kind: data
+  - from: a
+    to: add0.1
+    kind: data
+  - from: add0
+    to: b
+    kind: data
+  - from: b
+    to: add1.0
+    kind: data
+    bytes: 256
+  - from: b
+    to: add1.1
+    kind: data
+  - from: add1
+    to: c
+    kind: data
+";
+
+/// Build the timetable and return it with the time at which it finished
+fn run(timetable_yaml: &str) -> (Rc<Timetable>, f64) {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(timetable_yaml).unwrap();
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    (timetable, clock.time_now_ns())
+}
+
+#[test]
+fn edges_between_pes_transfer_their_bytes() {
+    let (timetable, with_transfer_ns) = run(TIMETABLE);
+    let transfers = timetable.transfers();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].id, "b -> add1.0");
+    assert_eq!(transfers[0].addr, 0x1000);
+    assert_eq!(transfers[0].num_bytes, 256);
+    assert_eq!(timetable.total_tasks(), 6);
+
+    // Without the bytes the dependency is free, so the timetable finishes
+    // sooner
+    let (timetable, without_transfer_ns) = run(&TIMETABLE.replace("    bytes: 256\n", ""));
+    assert!(timetable.transfers().is_empty());
+    assert!(
+        without_transfer_ns < with_transfer_ns,
+        "{without_transfer_ns} >= {with_transfer_ns}"
+    );
+}
+
+#[test]
+fn edges_within_a_pe_are_free() {
+    let (timetable, _) = run(&TIMETABLE.replace("pe: pe1", "pe: pe0"));
+    assert!(timetable.transfers().is_empty());
+}
+
+#[test]
+fn invalid_edge_bytes_are_rejected() {
+    let check = |from: &str, to: &str, expected: &str| {
+        let mut engine = start_test(file!());
+        let clock = engine.default_clock();
+        let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+        let timetable_file = TimetableFile::from_string(&TIMETABLE.replace(from, to)).unwrap();
+        let err = Timetable::new(engine.top(), timetable_file, &platform).unwrap_err();
+        assert!(format!("{err}").contains(expected), "{err}");
+    };
+
+    check(
+        "bytes: 256",
+        "bytes: 257",
+        "Edge 'b -> add1.0' carries 257 bytes but tensor 'b' is only 256 bytes",
+    );
+    check(
+        "    to: c\n    kind: data\n",
+        "    to: c\n    kind: data\n    bytes: 4\n",
+        "Edge 'add1 -> c' carries bytes so must be from a Tensor node",
+    );
+}
@@ -153,6 +153,10 @@ fn invalid_node_pe() {
         op: MemoryOp::Store,
         pe: Some("pe1".to_string()),
         config: MemoryConfigSection { view: None },
+        iterations: None,
+        deadline_ns: None,
+        relative_deadline_ns: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
@@ -167,6 +171,10 @@ fn duplicate_node_id() {
         op: MemoryOp::Store,
         pe: Some("pe0".to_string()),
         config: MemoryConfigSection { view: None },
+        iterations: None,
+        deadline_ns: None,
+        relative_deadline_ns: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
@@ -181,6 +189,10 @@ fn load_not_connected_to_tensor() {
         op: MemoryOp::Load,
         pe: Some("pe0".to_string()),
         config: MemoryConfigSection { view: None },
+        iterations: None,
+        deadline_ns: None,
+        relative_deadline_ns: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
@@ -195,6 +207,10 @@ fn store_not_connected_to_tensor() {
         op: MemoryOp::Store,
         pe: Some("pe0".to_string()),
         config: MemoryConfigSection { view: None },
+        iterations: None,
+        deadline_ns: None,
+        relative_deadline_ns: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
@@ -214,11 +230,17 @@ fn load_outside_tensor() {
                 offsets: vec![1, 1, 1],
             }),
         },
+        iterations: None,
+        deadline_ns: None,
+        relative_deadline_ns: None,
+        buffer: None,
     });
     timetable_file.edges.push(EdgeSection {
         from: "tensor0".to_string(),
         to: "node2".to_string(),
         kind: EdgeKind::Data,
+        bytes: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
@@ -238,6 +260,10 @@ fn store_outside_tensor() {
                 offsets: vec![0, 0, 0],
             }),
         },
+        iterations: None,
+        deadline_ns: None,
+        relative_deadline_ns: None,
+        buffer: None,
     });
     timetable_file.nodes.push(NodeSection::Tensor {
         id: "tensor1".to_string(),
@@ -251,11 +277,15 @@ fn store_outside_tensor() {
         from: "load0".to_string(),
         to: "store0".to_string(),
         kind: EdgeKind::Data,
+        bytes: None,
+        buffer: None,
     });
     timetable_file.edges.push(EdgeSection {
         from: "store0".to_string(),
         to: "tensor1".to_string(),
         kind: EdgeKind::Data,
+        bytes: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
@@ -271,6 +301,8 @@ fn invalid_from_edge_pe() {
         from: "node2".to_string(),
         to: "load0".to_string(),
         kind: EdgeKind::Data,
+        bytes: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
@@ -284,12 +316,69 @@ fn invalid_to_edge_pe() {
         from: "load0".to_string(),
         to: "node2".to_string(),
         kind: EdgeKind::Data,
+        bytes: None,
+        buffer: None,
     });
 
     let err = Timetable::new(&top, timetable_file, &platform).unwrap_err();
     assert!(format!("{err}").contains("Edge contains invalid to Node ID 'node2'"));
 }
 
+fn data_edge(from: &str, to: &str) -> EdgeSection {
+    EdgeSection {
+        from: from.to_string(),
+        to: to.to_string(),
+        kind: EdgeKind::Data,
+        bytes: None,
+        buffer: None,
+    }
+}
+
+#[test]
+fn dependency_cycle() {
+    let (top, platform, mut timetable_file) = create_default_timetable_file();
+    timetable_file.nodes.push(NodeSection::Tensor {
+        id: "tensor1".to_string(),
+        config: TensorConfigSection {
+            addr: 0x1000,
+            dtype: DataType::Fp32,
+            shape: vec![10, 10],
+        },
+    });
+    timetable_file.edges.push(data_edge("load0", "load1"));
+    timetable_file.edges.push(data_edge("load1", "load0"));
+    timetable_file.edges.push(data_edge("load1", "tensor1"));
+
+    let err = format!(
+        "{}",
+        Timetable::new(&top, timetable_file, &platform).unwrap_err()
+    );
+    assert!(
+        err.contains("Cycle of dependencies: load0 -> load1 -> load0"),
+        "{err}"
+    );
+    assert!(
+        err.contains("Nodes 'tensor1' can never run as they depend on a cycle"),
+        "{err}"
+    );
+}
+
+#[test]
+fn self_dependency() {
+    let (top, platform, mut timetable_file) = create_default_timetable_file();
+    timetable_file.edges.push(data_edge("load0", "load0"));
+
+    let err = format!(
+        "{}",
+        Timetable::new(&top, timetable_file, &platform).unwrap_err()
+    );
+    assert!(
+        err.contains("Cycle of dependencies: load0 -> load0"),
+        "{err}"
+    );
+    assert!(!err.contains("can never run"), "{err}");
+}
+
 #[test]
 fn memory_op_too_big() {
     let mut engine = start_test(file!());
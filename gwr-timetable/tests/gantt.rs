@@ -0,0 +1,95 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::engine::Engine;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{PLATFORM, add, edge, tensor};
+
+/// x is followed by y, which runs three times
+fn timetable_yaml() -> String {
+    let nodes = [
+        tensor("a", 0, 64),
+        tensor("t_x", 0x1000, 64),
+        tensor("t_y", 0x2000, 64),
+        add("x", "pe0", ""),
+        add("y", "pe0", "\n    iterations: 3"),
+    ];
+    let edges = [
+        edge("a", "x.0"),
+        edge("a", "x.1"),
+        edge("x", "t_x"),
+        edge("t_x", "y.0"),
+        edge("t_x", "y.1"),
+        edge("y", "t_y"),
+    ];
+    format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat())
+}
+
+#[test]
+fn every_run_of_a_task_is_a_slice_on_its_pe() {
+    let (test_tracker, tracker) = gwr_track::test_init!(1000);
+    let mut engine = Engine::new(&tracker);
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(&timetable_yaml()).unwrap();
+    let timetable = Rc::new(
+        Timetable::new(engine.top(), timetable_file, &platform)
+            .unwrap()
+            .with_clock(&clock),
+    );
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    engine.run().unwrap();
+    timetable.check_tasks_complete().unwrap();
+
+    let chart = timetable.gantt_chart().unwrap();
+    assert_eq!(chart.pes.len(), 1);
+    let schedule = &chart.pes[0];
+    assert_eq!(schedule.pe, "pe0");
+    let tasks: Vec<&str> = schedule.tasks.iter().map(|t| t.task.as_str()).collect();
+    assert_eq!(tasks, ["x", "y", "y", "y"]);
+    for pair in schedule.tasks.windows(2) {
+        assert!(pair[0].start_ns < pair[0].end_ns, "{pair:?}");
+        assert!(pair[0].end_ns <= pair[1].start_ns, "{pair:?}");
+    }
+    assert!(schedule.tasks[3].end_ns <= clock.time_now_ns());
+
+    let json: serde_json::Value = serde_json::from_str(&chart.to_json()).unwrap();
+    assert_eq!(json["pes"][0]["pe"], "pe0");
+    assert_eq!(json["pes"][0]["tasks"][1]["task"], "y");
+
+    let events = test_tracker.events();
+    assert!(
+        events
+            .iter()
+            .any(|event| event.contains("created lane")
+                && event.contains("timetable::pe0::tasks::0")),
+        "missing PE task lane create event in {events:#?}"
+    );
+    let num_y_slices = events
+        .iter()
+        .filter(|event| event.contains("activity begin y on lane"))
+        .count();
+    assert_eq!(num_y_slices, 3, "{events:#?}");
+}
+
+#[test]
+fn gantt_charts_need_a_clock() {
+    let mut engine = gwr_engine::test_helpers::start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(&timetable_yaml()).unwrap();
+    let timetable = Timetable::new(engine.top(), timetable_file, &platform).unwrap();
+    let err = timetable.gantt_chart().unwrap_err();
+    assert!(
+        format!("{err}").contains("Tasks are only timed by a timetable created with a clock"),
+        "{err}"
+    );
+}
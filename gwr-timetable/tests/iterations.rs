@@ -0,0 +1,243 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::run;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// x and y form a step that is followed by z
+const TIMETABLE: &str = "
+nodes:
+  - id: a
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [1024]
+  - id: t_x
+    kind: tensor
+    config:
+      addr: 0x1000
+      dtype: fp32
+      shape: [1024]
+  - id: t_y
+    kind: tensor
+    config:
+      addr: 0x2000
+      dtype: fp32
+      shape: [1024]
+  - id: t_z
+    kind: tensor
+    config:
+      addr: 0x3000
+      dtype: fp32
+      shape: [1024]
+  - id: x
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]
+  - id: y
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]
+  - id: z
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]
+
+edges:
+  - from: a
+    to: x.0
+    kind: data
+  - from: a
+    to: x.1
+    kind: data
+  - from: x
+    to: t_x
+    kind: data
+  - from: t_x
+    to: y.0
+    kind: data
+  - from: t_x
+    to: y.1
+    kind: data
+  - from: y
+    to: t_y
+    kind: data
+  - from: t_y
+    to: z.0
+    kind: data
+  - from: t_y
+    to: z.1
+    kind: data
+  - from: z
+    to: t_z
+    kind: data
+";
+
+fn build(timetable_file: TimetableFile) -> Result<Timetable, String> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    Timetable::new(engine.top(), timetable_file, &platform).map_err(|e| format!("{e}"))
+}
+
+fn with_subgraph(iterations: usize) -> TimetableFile {
+    TimetableFile::from_string(&format!(
+        "{TIMETABLE}
+subgraphs:
+  - id: step
+    nodes: [x, y]
+    iterations: {iterations}
+"
+    ))
+    .unwrap()
+}
+
+#[test]
+fn nodes_run_for_their_iterations() {
+    let once_ns = run(
+        file!(),
+        PLATFORM,
+        TimetableFile::from_string(TIMETABLE).unwrap(),
+    );
+    let repeated = TIMETABLE.replace(
+        "  - id: y\n    kind: compute\n",
+        "  - id: y\n    kind: compute\n    iterations: 3\n",
+    );
+    let timetable_file = TimetableFile::from_string(&repeated).unwrap();
+    let repeated_ns = run(file!(), PLATFORM, timetable_file);
+    assert!(repeated_ns > once_ns, "{repeated_ns} <= {once_ns}");
+
+    let timetable = build(TimetableFile::from_string(&repeated).unwrap()).unwrap();
+    assert_eq!(timetable.task_iterations(5), 3);
+    assert_eq!(timetable.task_iterations(6), 1);
+}
+
+#[test]
+fn subgraphs_run_for_their_iterations() {
+    let once_ns = run(file!(), PLATFORM, with_subgraph(1));
+    let twice_ns = run(file!(), PLATFORM, with_subgraph(2));
+    let four_times_ns = run(file!(), PLATFORM, with_subgraph(4));
+    assert!(twice_ns > once_ns, "{twice_ns} <= {once_ns}");
+    assert!(four_times_ns > twice_ns, "{four_times_ns} <= {twice_ns}");
+}
+
+#[test]
+fn nodes_after_a_loop_wait_for_its_last_iteration() {
+    let timetable = build(with_subgraph(2)).unwrap();
+    let ready = |timetable: &Timetable| timetable.ready_task_indices("pe0").unwrap().1;
+    let (x, y, z) = (4, 5, 6);
+
+    for _ in 0..2 {
+        assert_eq!(ready(&timetable), [x]);
+        timetable.set_task_active(x).unwrap();
+        timetable.set_task_completed(x).unwrap();
+        assert_eq!(ready(&timetable), [y]);
+        timetable.set_task_active(y).unwrap();
+        timetable.set_task_completed(y).unwrap();
+    }
+    assert_eq!(ready(&timetable), [z]);
+    timetable.set_task_active(z).unwrap();
+    timetable.set_task_completed(z).unwrap();
+    assert_eq!(timetable.ready_task_indices("pe0").unwrap(), (true, vec![]));
+    timetable.check_tasks_complete().unwrap();
+}
+
+#[test]
+fn invalid_loops_are_rejected() {
+    let check = |source: String, expected: &str| {
+        let err = build(TimetableFile::from_string(&source).unwrap()).unwrap_err();
+        assert!(err.contains(expected), "{err}");
+    };
+    let subgraph = |nodes: &str, iterations: usize| {
+        format!(
+            "{TIMETABLE}\nsubgraphs:\n  - id: step\n    nodes: {nodes}\n    iterations: {iterations}\n"
+        )
+    };
+
+    check(subgraph("[x, w]", 2), "Unknown node 'w' in subgraph 'step'");
+    check(
+        subgraph("[x, y]", 0),
+        "Subgraph 'step' must run at least once",
+    );
+    check(
+        subgraph("[x, y]", 2).replace(
+            "  - id: y\n    kind: compute\n",
+            "  - id: y\n    kind: compute\n    iterations: 2\n",
+        ),
+        "Node 'y' is in more than one loop ('y' and 'step')",
+    );
+}
+
+#[test]
+fn dot_subgraphs_are_loops() {
+    let timetable_file = TimetableFile::from_dot(
+        "digraph {
+            node [kind=tensor, dtype=fp32, shape=\"[1024]\"];
+            a [addr=0];
+            t_x [addr=0x1000];
+            t_y [addr=0x2000];
+            subgraph step {
+                iterations=3;
+                node [kind=compute, op=add, pe=pe0];
+                x; y;
+            }
+            a -> x:0;
+            a -> x:1;
+            x -> t_x;
+            t_x -> y:0;
+            t_x -> y:1;
+            y -> t_y;
+        }",
+    )
+    .unwrap();
+    let subgraphs = &timetable_file.subgraphs;
+    assert_eq!(subgraphs.len(), 1);
+    assert_eq!(subgraphs[0].id, "step");
+    assert_eq!(subgraphs[0].nodes, ["x", "y"]);
+    assert_eq!(subgraphs[0].iterations, 3);
+    run(file!(), PLATFORM, timetable_file);
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::path::{Path, PathBuf};
+
+use gwr_timetable::timetable_file::{TIMETABLE_JSON_SCHEMA, TimetableFile};
+use serde_json::Value;
+
+fn example(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("examples")
+        .join(name)
+}
+
+fn to_yaml(timetable: &TimetableFile) -> String {
+    serde_yaml::to_string(timetable).unwrap()
+}
+
+#[test]
+fn json_timetables_match_yaml() {
+    let yaml = TimetableFile::from_file(&example("tiny.yaml")).unwrap();
+    let json = TimetableFile::from_file(&example("tiny.json")).unwrap();
+    assert_eq!(to_yaml(&json), to_yaml(&yaml));
+}
+
+#[test]
+fn timetables_round_trip_through_json() {
+    for name in ["small.yaml", "cache.yaml"] {
+        let timetable = TimetableFile::from_file(&example(name)).unwrap();
+        let json = timetable.to_json().unwrap();
+        let reparsed = TimetableFile::from_json(&json).unwrap();
+        assert_eq!(to_yaml(&reparsed), to_yaml(&timetable), "{name}");
+    }
+}
+
+/// Check that the fields of each node and edge are described by the schema
+#[test]
+fn schema_describes_timetables() {
+    let schema: Value = serde_json::from_str(TIMETABLE_JSON_SCHEMA).unwrap();
+    let defs = &schema["$defs"];
+    let check_fields = |def: &Value, object: &Value, what: &str| {
+        let properties = def["properties"].as_object().unwrap();
+        let object = object.as_object().unwrap();
+        for key in object.keys() {
+            assert!(properties.contains_key(key), "'{key}' of {what}");
+        }
+        for required in def["required"].as_array().unwrap() {
+            assert!(
+                object.contains_key(required.as_str().unwrap()),
+                "{required} of {what}"
+            );
+        }
+    };
+
+    for name in ["tiny.yaml", "small.yaml", "cache.yaml"] {
+        let timetable = TimetableFile::from_file(&example(name)).unwrap();
+        let json: Value = serde_json::from_str(&timetable.to_json().unwrap()).unwrap();
+        check_fields(&schema, &json, name);
+        for node in json["nodes"].as_array().unwrap() {
+            let kind = node["kind"].as_str().unwrap();
+            let def = &defs[format!("{kind}_node")];
+            assert_eq!(def["properties"]["kind"]["const"], kind);
+            check_fields(def, node, &format!("node {} of {name}", node["id"]));
+        }
+        for edge in json["edges"].as_array().unwrap() {
+            check_fields(&defs["edge"], edge, &format!("edge of {name}"));
+        }
+    }
+}
+
+#[test]
+fn invalid_json_timetables_are_rejected() {
+    let err = TimetableFile::from_json(r#"{"nodes": [], "edges": [], "links": []}"#).unwrap_err();
+    assert!(format!("{err}").contains("unknown field `links`"), "{err}");
+
+    let err = TimetableFile::from_json(
+        r#"{"nodes": [{"id": "a", "kind": "tensor", "config": {"addr": 0, "dtype": "fp7", "shape": [1]}}], "edges": []}"#,
+    )
+    .unwrap_err();
+    assert!(format!("{err}").contains("unknown variant `fp7`"), "{err}");
+}
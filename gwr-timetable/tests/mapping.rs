@@ -0,0 +1,163 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::mapping::MappingPolicy;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{edge, tensor};
+
+/// Two sockets, each with a PE and the memory that is local to it
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+      - name: hbm1
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+  - name: hbm1
+    kind: hbm
+    base_address: 0x1000_0000
+    capacity_bytes: 0x1000_0000
+
+groups:
+  - name: socket0
+    members: [pe0, hbm0]
+  - name: socket1
+    members: [pe1, hbm1]
+";
+
+fn add(id: &str, pe: Option<&str>) -> String {
+    let pe = pe.map(|pe| format!("\n    pe: {pe}")).unwrap_or_default();
+    format!(
+        "
+  - id: {id}
+    kind: compute
+    op: add{pe}
+    input_views: [null]
+    output_views: [null]"
+    )
+}
+
+/// Each add reads its own tensor and writes the next. The tensors of w are in
+/// hbm0 and the others in hbm1, and w is 16 times the size of the others.
+fn timetable_file(w_pe: Option<&str>) -> TimetableFile {
+    let nodes = [
+        tensor("a", 0, 1024),
+        tensor("t_w", 0x1000, 1024),
+        tensor("b", 0x1000_0000, 64),
+        tensor("t_x", 0x1000_1000, 64),
+        tensor("t_y", 0x1000_2000, 64),
+        tensor("t_z", 0x1000_3000, 64),
+        add("w", w_pe),
+        add("x", None),
+        add("y", None),
+        add("z", None),
+    ];
+    let edges = [
+        edge("a", "w"),
+        edge("w", "t_w"),
+        edge("b", "x"),
+        edge("x", "t_x"),
+        edge("t_x", "y"),
+        edge("y", "t_y"),
+        edge("t_y", "z"),
+        edge("z", "t_z"),
+    ];
+    let source = format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat());
+    TimetableFile::from_string(&source).unwrap()
+}
+
+/// Map the nodes and return the PE of each of w, x, y and z
+fn map(mut timetable_file: TimetableFile, policy: MappingPolicy) -> Vec<String> {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let mapping = timetable_file.map_to_pes(&platform, policy).unwrap();
+
+    for (node_id, pe) in &mapping.assignments {
+        let node = timetable_file
+            .nodes
+            .iter()
+            .find(|node| node.id() == node_id)
+            .unwrap();
+        assert_eq!(node.pe().as_deref(), Some(pe.as_str()));
+    }
+    Timetable::new(engine.top(), timetable_file, &platform).unwrap();
+
+    ["w", "x", "y", "z"]
+        .iter()
+        .map(|node_id| {
+            mapping
+                .pe(node_id)
+                .map_or_else(|| "given".to_string(), str::to_string)
+        })
+        .collect()
+}
+
+#[test]
+fn round_robin_takes_each_pe_in_turn() {
+    assert_eq!(
+        map(timetable_file(None), MappingPolicy::RoundRobin),
+        ["pe0", "pe1", "pe0", "pe1"]
+    );
+}
+
+#[test]
+fn load_balancing_counts_the_work_of_given_nodes() {
+    assert_eq!(
+        map(timetable_file(Some("pe0")), MappingPolicy::LoadBalanced),
+        ["given", "pe1", "pe1", "pe1"]
+    );
+    // Without w the work is shared out
+    assert_eq!(
+        map(timetable_file(None), MappingPolicy::LoadBalanced),
+        ["pe0", "pe1", "pe1", "pe1"]
+    );
+}
+
+#[test]
+fn locality_keeps_nodes_in_the_group_of_their_tensors() {
+    assert_eq!(
+        map(timetable_file(None), MappingPolicy::Locality),
+        ["pe0", "pe1", "pe1", "pe1"]
+    );
+    // The tensors are local to pe1 even though it has more work
+    assert_eq!(
+        map(timetable_file(Some("pe1")), MappingPolicy::Locality),
+        ["given", "pe1", "pe1", "pe1"]
+    );
+    assert_eq!(
+        map(timetable_file(Some("pe1")), MappingPolicy::LoadBalanced),
+        ["given", "pe0", "pe0", "pe0"]
+    );
+}
+
+#[test]
+fn unmapped_nodes_are_rejected() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let err = Timetable::new(engine.top(), timetable_file(None), &platform).unwrap_err();
+    assert!(
+        format!("{err}").contains("Node 'x' has no PE, so must be mapped to one before it can run"),
+        "{err}"
+    );
+}
@@ -0,0 +1,331 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_onnx::Message;
+use gwr_onnx::protos::tensor_shape_proto::{Dimension, dimension};
+use gwr_onnx::protos::{
+    AttributeProto, GraphProto, ModelProto, NodeProto, TensorProto, TensorShapeProto, TypeProto,
+    ValueInfoProto, tensor_proto, type_proto,
+};
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::mapping::MappingPolicy;
+use gwr_timetable::onnx::{OnnxImportOptions, import_onnx};
+use gwr_timetable::timetable_file::{NodeSection, TimetableFile};
+
+const FLOAT: i32 = tensor_proto::DataType::Float as i32;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// A float tensor whose dimensions are sized, or named when 0
+fn value_info(name: &str, dims: &[i64]) -> ValueInfoProto {
+    let dim = dims
+        .iter()
+        .map(|dim| Dimension {
+            value: Some(match dim {
+                0 => dimension::Value::DimParam("batch".to_string()),
+                _ => dimension::Value::DimValue(*dim),
+            }),
+            ..Default::default()
+        })
+        .collect();
+    ValueInfoProto {
+        name: Some(name.to_string()),
+        r#type: Some(TypeProto {
+            value: Some(type_proto::Value::TensorType(type_proto::Tensor {
+                elem_type: Some(FLOAT),
+                shape: Some(TensorShapeProto { dim }),
+            })),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn initializer(name: &str, dims: &[i64]) -> TensorProto {
+    TensorProto {
+        dims: dims.to_vec(),
+        data_type: Some(FLOAT),
+        name: Some(name.to_string()),
+        ..Default::default()
+    }
+}
+
+fn node(name: &str, op_type: &str, inputs: &[&str], output: &str) -> NodeProto {
+    NodeProto {
+        input: inputs.iter().map(ToString::to_string).collect(),
+        output: vec![output.to_string()],
+        name: Some(name.to_string()),
+        op_type: Some(op_type.to_string()),
+        ..Default::default()
+    }
+}
+
+fn int_attribute(name: &str, i: i64) -> AttributeProto {
+    AttributeProto {
+        name: Some(name.to_string()),
+        i: Some(i),
+        ..Default::default()
+    }
+}
+
+fn ints_attribute(name: &str, ints: &[i64]) -> AttributeProto {
+    AttributeProto {
+        name: Some(name.to_string()),
+        ints: ints.to_vec(),
+        ..Default::default()
+    }
+}
+
+/// A two layer perceptron, in which the shapes of the hidden tensors are
+/// inferred
+fn mlp() -> Vec<u8> {
+    let mut gemm = node(
+        "/fc2/Gemm",
+        "Gemm",
+        &["h_relu", "fc2.weight", "fc2.bias"],
+        "y",
+    );
+    gemm.attribute.push(int_attribute("transB", 1));
+    ModelProto {
+        graph: Some(GraphProto {
+            name: Some("mlp".to_string()),
+            node: vec![
+                node("/fc1/MatMul", "MatMul", &["x", "fc1.weight"], "h_mm"),
+                node("/fc1/Add", "Add", &["h_mm", "fc1.bias"], "h"),
+                node("", "Relu", &["h"], "h_relu"),
+                gemm,
+            ],
+            initializer: vec![
+                initializer("fc1.weight", &[64, 128]),
+                initializer("fc1.bias", &[128]),
+                initializer("fc2.weight", &[10, 128]),
+                initializer("fc2.bias", &[10]),
+            ],
+            input: vec![value_info("x", &[0, 64])],
+            output: vec![value_info("y", &[0, 10])],
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+    .encode_to_vec()
+}
+
+fn tensor_shape<'a>(timetable_file: &'a TimetableFile, id: &str) -> &'a [usize] {
+    timetable_file
+        .nodes
+        .iter()
+        .find_map(|node| match node {
+            NodeSection::Tensor {
+                id: tensor_id,
+                config,
+            } if tensor_id == id => Some(config.shape.as_slice()),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no tensor {id}"))
+}
+
+#[test]
+fn models_are_imported_as_compute_and_tensor_nodes() {
+    let import = import_onnx(&mlp(), OnnxImportOptions::default().with_batch_size(4)).unwrap();
+    let timetable_file = &import.timetable_file;
+
+    let compute: Vec<(&str, &str)> = timetable_file
+        .nodes
+        .iter()
+        .filter_map(|node| match node {
+            NodeSection::Compute { id, op, pe, .. } => {
+                assert!(pe.is_none());
+                Some((id.as_str(), op.trace_name()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        compute,
+        [
+            ("_fc1_MatMul", "gemm"),
+            ("_fc1_Add", "add"),
+            ("Relu2", "add"),
+            ("_fc2_Gemm", "gemm")
+        ]
+    );
+
+    assert_eq!(tensor_shape(timetable_file, "x"), [4, 64]);
+    assert_eq!(tensor_shape(timetable_file, "h_mm"), [4, 128]);
+    assert_eq!(tensor_shape(timetable_file, "h"), [4, 128]);
+    assert_eq!(tensor_shape(timetable_file, "h_relu"), [4, 128]);
+    // The weights of the Gemm are transposed
+    assert_eq!(tensor_shape(timetable_file, "fc2_weight"), [128, 10]);
+    assert_eq!(tensor_shape(timetable_file, "y"), [4, 10]);
+
+    // The Relu reads its input twice
+    let relu_inputs: Vec<(&str, &str)> = timetable_file
+        .edges
+        .iter()
+        .filter(|edge| edge.to_node_id() == "Relu2")
+        .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+        .collect();
+    assert_eq!(relu_inputs, [("h", "Relu2.0"), ("h", "Relu2.1")]);
+
+    let matmul = &import.estimates[0];
+    assert_eq!(matmul.flops, 4 * 128 * (2 * 64 - 1));
+    assert_eq!(matmul.bytes, 4 * (4 * 64 + 64 * 128 + 4 * 128));
+    let relu = &import.estimates[2];
+    assert_eq!(relu.flops, 4 * 128);
+    assert!(format!("{import}").starts_with("Imported 4 ONNX nodes"));
+}
+
+#[test]
+fn tensors_are_placed_one_after_another() {
+    let import = import_onnx(&mlp(), OnnxImportOptions::default().with_base_addr(0x1000)).unwrap();
+    let addrs: Vec<u64> = import
+        .timetable_file
+        .nodes
+        .iter()
+        .filter_map(|node| match node {
+            NodeSection::Tensor { config, .. } => Some(config.addr),
+            _ => None,
+        })
+        .collect();
+    // x is 256 bytes, fc1.weight 32KiB and h_mm 512 bytes
+    assert_eq!(addrs[..4], [0x1000, 0x1100, 0x9100, 0x9300]);
+}
+
+#[test]
+fn maxpool_attributes_are_imported() {
+    let mut maxpool = node("pool", "MaxPool", &["image"], "pooled");
+    maxpool.attribute = vec![
+        ints_attribute("kernel_shape", &[2, 2]),
+        ints_attribute("strides", &[2, 2]),
+    ];
+    let model = ModelProto {
+        graph: Some(GraphProto {
+            node: vec![maxpool],
+            input: vec![value_info("image", &[1, 3, 8, 8])],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let timetable_file = TimetableFile::from_onnx(&model.encode_to_vec()).unwrap();
+    assert_eq!(tensor_shape(&timetable_file, "pooled"), [1, 3, 4, 4]);
+}
+
+/// Run an imported model across two PEs and return the time taken
+fn run(batch_size: usize) -> f64 {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let mut timetable_file = import_onnx(
+        &mlp(),
+        OnnxImportOptions::default().with_batch_size(batch_size),
+    )
+    .unwrap()
+    .timetable_file;
+    timetable_file
+        .map_to_pes(&platform, MappingPolicy::RoundRobin)
+        .unwrap();
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    clock.time_now_ns()
+}
+
+#[test]
+fn imported_models_run_on_a_platform() {
+    let small_ns = run(1);
+    let large_ns = run(16);
+    assert!(small_ns < large_ns, "{small_ns} >= {large_ns}");
+}
+
+#[test]
+fn onnx_files_are_loaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("mlp.onnx");
+    std::fs::write(&path, mlp()).unwrap();
+    let timetable_file = TimetableFile::from_file(&path).unwrap();
+    assert_eq!(timetable_file.nodes.len(), 9 + 4);
+}
+
+#[test]
+fn unsupported_models_are_rejected() {
+    let check = |graph: GraphProto, expected: &str| {
+        let model = ModelProto {
+            graph: Some(graph),
+            ..Default::default()
+        };
+        let err = TimetableFile::from_onnx(&model.encode_to_vec()).unwrap_err();
+        assert_eq!(format!("{err}"), expected);
+    };
+
+    check(
+        GraphProto {
+            node: vec![node("conv", "Conv", &["x", "w"], "y")],
+            ..Default::default()
+        },
+        "ONNX node 'conv' has op 'Conv' which is not supported",
+    );
+    check(
+        GraphProto {
+            node: vec![node("relu", "Relu", &["x"], "y")],
+            ..Default::default()
+        },
+        "ONNX tensor 'x' used by node 'relu' has no known shape",
+    );
+    check(
+        GraphProto {
+            node: vec![node("add", "Add", &["a", "b"], "c")],
+            input: vec![value_info("a", &[4, 3]), value_info("b", &[4, 5])],
+            ..Default::default()
+        },
+        "ONNX node 'add' has inputs whose shapes do not match",
+    );
+
+    let err = TimetableFile::from_onnx(&[0xff, 0xff]).unwrap_err();
+    assert!(
+        format!("{err}").starts_with("Failed to decode ONNX model"),
+        "{err}"
+    );
+    let err = TimetableFile::from_onnx(&[]).unwrap_err();
+    assert_eq!(format!("{err}"), "ONNX model has no graph");
+}
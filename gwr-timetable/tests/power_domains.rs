@@ -0,0 +1,100 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::run;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 2
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - connect:
+      - pe.pe0
+      - fabric.fabric0@(0,0).0
+  - connect:
+      - mem.hbm0
+      - fabric.fabric0@(0,0).1
+";
+
+const POWER_DOMAINS: &str = "
+power_domains:
+  - name: pd0
+    members: [pe0]
+    state: off
+    transitions:
+      - at_ticks: 5000
+        state: retention
+      - at_ticks: 10000
+        state: on
+";
+
+const TIMETABLE: &str = "
+nodes:
+  - id: a
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [64]
+  - id: b
+    kind: tensor
+    config:
+      addr: 0x1000
+      dtype: fp32
+      shape: [64]
+  - id: add
+    kind: compute
+    op: add
+    pe: pe0
+    input_views:
+      -
+      -
+    output_views:
+      -
+
+edges:
+  - from: a
+    to: add.0
+    kind: data
+  - from: a
+    to: add.1
+    kind: data
+  - from: add
+    to: b
+    kind: data
+";
+
+#[test]
+fn pes_wait_for_their_power_domain_to_turn_on() {
+    let timetable_file = || TimetableFile::from_string(TIMETABLE).unwrap();
+    let ungated_ns = run(file!(), PLATFORM, timetable_file());
+    assert!(ungated_ns < 5000.0, "{ungated_ns}");
+
+    // The PE stays idle while off and in retention and then runs the same
+    // work once its domain is turned on
+    let gated_platform = format!("{PLATFORM}{POWER_DOMAINS}");
+    let gated_ns = run(file!(), &gated_platform, timetable_file());
+    assert_eq!(gated_ns, 10000.0 + ungated_ns);
+}
@@ -0,0 +1,110 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{add, add_edges, tensor};
+
+/// A platform in which every add takes 10 ticks and compute tasks can be
+/// preempted every 20 ticks
+const PLATFORM_YAML: &str = "
+memory_maps:
+  - name: default
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: default
+    config:
+      preemption_ticks: 20
+      cost_model:
+        issue_width: 1
+        pipelined: false
+        add_latency_ticks: 10
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - connect:
+      - pe.pe0
+      - mem.hbm0
+";
+
+/// A long background add runs alongside a short chain of adds. The second add
+/// of the chain only becomes ready once the background add has started.
+fn timetable_yaml(urgent_priority: usize) -> String {
+    let nodes = [
+        tensor("bg_a", 0x0, 64),
+        tensor("bg_b", 0x1000, 64),
+        tensor("bg_out", 0x2000, 64),
+        tensor("a", 0x3000, 2),
+        tensor("b", 0x3100, 2),
+        tensor("c", 0x3200, 2),
+        tensor("d", 0x3300, 2),
+        add("background", "pe0", "\n    priority: 0"),
+        add("first", "pe0", "\n    priority: 0"),
+        add(
+            "urgent",
+            "pe0",
+            &format!("\n    priority: {urgent_priority}"),
+        ),
+    ];
+    let edges = [
+        add_edges(["bg_a", "bg_b"], "background", "bg_out"),
+        add_edges(["a", "b"], "first", "c"),
+        add_edges(["c", "b"], "urgent", "d"),
+    ];
+    format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat())
+}
+
+fn run_preemption_test(platform_yaml: &str, urgent_priority: usize) -> (usize, f64) {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, platform_yaml).unwrap());
+    let timetable_file = TimetableFile::from_string(&timetable_yaml(urgent_priority)).unwrap();
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    (
+        platform.pe("pe0").unwrap().num_preemptions(),
+        engine.time_now_ns(),
+    )
+}
+
+#[test]
+fn high_priority_task_preempts() {
+    let (num_preemptions, _) = run_preemption_test(PLATFORM_YAML, 1);
+    assert_eq!(num_preemptions, 1);
+}
+
+#[test]
+fn equal_priority_task_waits() {
+    let (num_preemptions, _) = run_preemption_test(PLATFORM_YAML, 0);
+    assert_eq!(num_preemptions, 0);
+}
+
+#[test]
+fn tasks_compute_concurrently_without_preemption() {
+    let platform_yaml = PLATFORM_YAML.replace("      preemption_ticks: 20\n", "");
+    let (num_preemptions, concurrent_ns) = run_preemption_test(&platform_yaml, 1);
+    assert_eq!(num_preemptions, 0);
+
+    // Sharing a single compute unit takes longer
+    let (_, shared_ns) = run_preemption_test(PLATFORM_YAML, 1);
+    assert!(concurrent_ns < shared_ns);
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::mapping::MappingPolicy;
+use gwr_timetable::random_graph::RandomGraphOptions;
+use gwr_timetable::timetable_file::{NodeSection, TimetableFile};
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// The number of tensors that each task reads and the number of tasks that
+/// read each tensor
+fn fan_in_out(timetable_file: &TimetableFile) -> (HashMap<&str, usize>, HashMap<&str, usize>) {
+    let mut fan_in: HashMap<&str, usize> = HashMap::new();
+    let mut fan_out: HashMap<&str, usize> = HashMap::new();
+    for node in &timetable_file.nodes {
+        match node {
+            NodeSection::Tensor { id, .. } => fan_out.insert(id, 0),
+            _ => fan_in.insert(node.id(), 0),
+        };
+    }
+    let mut reads = Vec::new();
+    for edge in &timetable_file.edges {
+        let read = (edge.from_node_id(), edge.to_node_id());
+        if fan_out.contains_key(read.0) && !reads.contains(&read) {
+            reads.push(read);
+            *fan_out.get_mut(read.0).unwrap() += 1;
+            *fan_in.get_mut(read.1).unwrap() += 1;
+        }
+    }
+    (fan_in, fan_out)
+}
+
+#[test]
+fn graphs_are_layered_within_the_fan_in_and_fan_out() {
+    let options = RandomGraphOptions::default()
+        .with_num_layers(5)
+        .with_width(6)
+        .with_max_fan_in(4)
+        .with_max_fan_out(3);
+    for seed in 0..10 {
+        let timetable_file = TimetableFile::random(options.with_seed(seed)).unwrap();
+        assert_eq!(timetable_file.nodes.len(), 6 + 5 * 6 * 2);
+
+        let (fan_in, fan_out) = fan_in_out(&timetable_file);
+        assert_eq!(fan_in.len(), 5 * 6);
+        for (id, count) in &fan_in {
+            assert!((1..=4).contains(count), "{id} reads {count} tensors");
+        }
+        for (id, count) in &fan_out {
+            assert!(*count <= 3, "{id} is read by {count} tasks");
+        }
+    }
+}
+
+#[test]
+fn graphs_depend_on_the_seed() {
+    let graph = |seed| {
+        TimetableFile::random(RandomGraphOptions::default().with_seed(seed))
+            .unwrap()
+            .to_json()
+            .unwrap()
+    };
+    assert_eq!(graph(1), graph(1));
+    assert_ne!(graph(1), graph(2));
+}
+
+#[test]
+fn memory_fraction_selects_the_kind_of_tasks() {
+    let count = |memory_fraction| {
+        let timetable_file = TimetableFile::random(
+            RandomGraphOptions::default().with_memory_fraction(memory_fraction),
+        )
+        .unwrap();
+        let num_memory = timetable_file
+            .nodes
+            .iter()
+            .filter(|node| matches!(node, NodeSection::Memory { .. }))
+            .count();
+        let num_compute = timetable_file
+            .nodes
+            .iter()
+            .filter(|node| matches!(node, NodeSection::Compute { .. }))
+            .count();
+        (num_memory, num_compute)
+    };
+    assert_eq!(count(0.0), (0, 16));
+    assert_eq!(count(1.0), (16, 0));
+}
+
+#[test]
+fn generated_graphs_run_on_a_platform() {
+    for seed in 0..5 {
+        let mut engine = start_test(file!());
+        let clock = engine.default_clock();
+        let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+        let mut timetable_file = TimetableFile::random(
+            RandomGraphOptions::default()
+                .with_seed(seed)
+                .with_max_fan_in(3)
+                .with_memory_fraction(0.5),
+        )
+        .unwrap();
+        timetable_file
+            .map_to_pes(&platform, MappingPolicy::LoadBalanced)
+            .unwrap();
+        timetable_file.validate(&platform).unwrap();
+        let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+        let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+        platform.attach_dispatcher(&dispatcher);
+
+        run_simulation!(engine);
+        timetable.check_tasks_complete().unwrap();
+    }
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    let check = |options: RandomGraphOptions, expected: &str| {
+        let err = TimetableFile::random(options).unwrap_err();
+        assert!(format!("{err}").contains(expected), "{err}");
+    };
+    check(
+        RandomGraphOptions::default().with_width(0),
+        "at least 1 layer",
+    );
+    check(
+        RandomGraphOptions::default().with_max_fan_out(0),
+        "fan-out of at least 1",
+    );
+    check(
+        RandomGraphOptions::default().with_memory_fraction(1.5),
+        "memory fraction 1.5 which is not between 0 and 1",
+    );
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::scheduling::SchedulingPolicy;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{edge, tensor};
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+";
+
+fn add(id: &str, extra: &str) -> String {
+    format!(
+        "
+  - id: {id}
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null]
+    output_views: [null]{extra}"
+    )
+}
+
+/// w, x and z are ready at the start and y is ready once x completes:
+///  - z has the highest priority and the earliest deadline
+///  - x has a deadline and y after it, so it is on the critical path
+fn timetable(policy: SchedulingPolicy) -> Timetable {
+    let nodes = [
+        add("y", ""),
+        add("w", ""),
+        add("x", "\n    deadline_ns: 300"),
+        add("z", "\n    priority: 5\n    deadline_ns: 100"),
+        tensor("a", 0, 64),
+        tensor("t_w", 0x1000, 64),
+        tensor("t_x", 0x2000, 64),
+        tensor("t_y", 0x3000, 64),
+        tensor("t_z", 0x4000, 64),
+    ];
+    let edges = [
+        edge("a", "w"),
+        edge("w", "t_w"),
+        edge("a", "x"),
+        edge("x", "t_x"),
+        edge("t_x", "y"),
+        edge("y", "t_y"),
+        edge("a", "z"),
+        edge("z", "t_z"),
+    ];
+    let source = format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat());
+
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(&source).unwrap();
+    Timetable::new(engine.top(), timetable_file, &platform)
+        .unwrap()
+        .with_scheduling_policy(policy)
+}
+
+const Y: usize = 0;
+const W: usize = 1;
+const X: usize = 2;
+const Z: usize = 3;
+
+fn ready(timetable: &Timetable) -> Vec<usize> {
+    let (done, ready) = timetable.ready_task_indices("pe0").unwrap();
+    assert!(!done);
+    ready
+}
+
+#[test]
+fn ready_tasks_are_ordered_by_the_policy() {
+    let check = |policy, expected: [usize; 3]| {
+        let timetable = timetable(policy);
+        assert_eq!(ready(&timetable), expected, "{policy:?}");
+    };
+
+    check(SchedulingPolicy::Priority, [Z, W, X]);
+    check(SchedulingPolicy::Fifo, [W, X, Z]);
+    check(SchedulingPolicy::CriticalPath, [X, W, Z]);
+    check(SchedulingPolicy::EarliestDeadline, [Z, X, W]);
+}
+
+#[test]
+fn fifo_orders_tasks_by_when_they_became_ready() {
+    let fifo = timetable(SchedulingPolicy::Fifo);
+    fifo.set_task_active(X).unwrap();
+    fifo.set_task_completed(X).unwrap();
+    assert_eq!(ready(&fifo), [W, Z, Y]);
+
+    // The priority policy orders the same tasks by priority and then index
+    let priority = timetable(SchedulingPolicy::Priority);
+    priority.set_task_active(X).unwrap();
+    priority.set_task_completed(X).unwrap();
+    assert_eq!(ready(&priority), [Z, Y, W]);
+}
+
+#[test]
+fn priorities_are_only_used_by_the_priority_policy() {
+    assert_eq!(timetable(SchedulingPolicy::Priority).task_priority(Z), 5);
+    assert_eq!(timetable(SchedulingPolicy::Fifo).task_priority(Z), 0);
+}
+
+#[test]
+fn critical_path_lengths_count_tasks() {
+    let timetable = timetable(SchedulingPolicy::CriticalPath);
+    assert_eq!(timetable.critical_path_length(X), 2);
+    assert_eq!(timetable.critical_path_length(Y), 1);
+    assert_eq!(timetable.critical_path_length(W), 1);
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::{add, add_edges, dma, edge, tensor};
+
+const SCRATCHPAD_BASE: u64 = 0x8000_0000;
+
+/// Each tensor is 64 fp32 elements
+const TENSOR_BYTES: usize = 256;
+
+fn scratchpad_config(scratchpad_base: u64, dma_channels: usize) -> String {
+    format!(
+        "
+      scratchpad:
+        base_address: {scratchpad_base}
+        capacity_bytes: 0x1000
+        bw_bytes_per_cycle: 16
+        dma_channels: {dma_channels}"
+    )
+}
+
+fn platform_yaml(pe_config: &str) -> String {
+    format!(
+        "
+memory_maps:
+  - name: default
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: default
+    config:{pe_config}
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - connect:
+      - pe.pe0
+      - mem.hbm0
+"
+    )
+}
+
+/// Copy two tensors into the scratchpad, add them there and copy the result
+/// back out
+fn timetable_yaml(a_spm_addr: u64) -> String {
+    let nodes = [
+        tensor("a", 0x0, 64),
+        tensor("b", 0x1000, 64),
+        tensor("c", 0x2000, 64),
+        tensor("a_spm", a_spm_addr, 64),
+        tensor("b_spm", SCRATCHPAD_BASE + 0x100, 64),
+        tensor("c_spm", SCRATCHPAD_BASE + 0x200, 64),
+        dma("copy_a", "copy_in", "pe0"),
+        dma("copy_b", "copy_in", "pe0"),
+        dma("copy_c", "copy_out", "pe0"),
+        add("add", "pe0", ""),
+    ];
+    let edges = [
+        edge("a", "copy_a"),
+        edge("copy_a", "a_spm"),
+        edge("b", "copy_b"),
+        edge("copy_b", "b_spm"),
+        add_edges(["a_spm", "b_spm"], "add", "c_spm"),
+        edge("c_spm", "copy_c"),
+        edge("copy_c", "c"),
+    ];
+    format!("nodes:{}\n\nedges:{}\n", nodes.concat(), edges.concat())
+}
+
+fn run_scratchpad_test(platform_yaml: &str, timetable_yaml: &str) -> (Rc<Platform>, f64) {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, platform_yaml).unwrap());
+    let timetable_file = TimetableFile::from_string(timetable_yaml).unwrap();
+    let timetable = Rc::new(Timetable::new(engine.top(), timetable_file, &platform).unwrap());
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    (platform, engine.time_now_ns())
+}
+
+#[test]
+fn copy_in_compute_copy_out() {
+    let (platform, _) = run_scratchpad_test(
+        &platform_yaml(&scratchpad_config(SCRATCHPAD_BASE, 1)),
+        &timetable_yaml(SCRATCHPAD_BASE),
+    );
+    let pe = platform.pe("pe0").unwrap();
+    assert_eq!(pe.num_dma_copies(), 3);
+
+    // Both copies in and the add output are written to the scratchpad. The add
+    // inputs and the copy out are read from it.
+    assert_eq!(pe.scratchpad_bytes_written(), 3 * TENSOR_BYTES);
+    assert_eq!(pe.scratchpad_bytes_read(), 3 * TENSOR_BYTES);
+}
+
+#[test]
+fn dma_channels_overlap_copies() {
+    let (_, one_channel_ns) = run_scratchpad_test(
+        &platform_yaml(&scratchpad_config(SCRATCHPAD_BASE, 1)),
+        &timetable_yaml(SCRATCHPAD_BASE),
+    );
+    let (_, two_channels_ns) = run_scratchpad_test(
+        &platform_yaml(&scratchpad_config(SCRATCHPAD_BASE, 2)),
+        &timetable_yaml(SCRATCHPAD_BASE),
+    );
+    assert!(two_channels_ns < one_channel_ns);
+}
+
+#[test]
+#[should_panic(expected = "access of 256 bytes at 0x80000f80 is outside the scratchpad")]
+fn copy_beyond_scratchpad() {
+    run_scratchpad_test(
+        &platform_yaml(&scratchpad_config(SCRATCHPAD_BASE, 1)),
+        &timetable_yaml(SCRATCHPAD_BASE + 0xf80),
+    );
+}
+
+#[test]
+#[should_panic(expected = "DMA task on a PE without a scratchpad")]
+fn dma_without_scratchpad() {
+    run_scratchpad_test(&platform_yaml(" {}"), &timetable_yaml(SCRATCHPAD_BASE));
+}
+
+#[test]
+fn scratchpad_overlapping_memory_map() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    match Platform::from_string(
+        &engine,
+        &clock,
+        &platform_yaml(&scratchpad_config(0x800, 1)),
+    ) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "top::pe0: scratchpad 0x800-0x17ff overlaps the memory map"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
+
+#[test]
+fn mismatched_dma_views() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(
+        Platform::from_string(
+            &engine,
+            &clock,
+            &platform_yaml(&scratchpad_config(SCRATCHPAD_BASE, 1)),
+        )
+        .unwrap(),
+    );
+    let yaml = timetable_yaml(SCRATCHPAD_BASE).replace(
+        "    op: copy_in\n    pe: pe0\n",
+        "    op: copy_in\n    pe: pe0\n    config:\n      input_view:\n        offsets: [0]\n        shape: [32]\n",
+    );
+    let timetable_file = TimetableFile::from_string(&yaml).unwrap();
+    match Timetable::new(engine.top(), timetable_file, &platform) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "DMA node 'copy_a' copies 128 bytes from its input but 256 bytes to its output"
+        ),
+        Ok(_) => panic!("Expected an error"),
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+pub mod common;
+use common::run;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+// pe0 computes b and sends it to pe1, which receives it into c and then
+// computes d
+const TIMETABLE: &str = "
+nodes:
+  - id: a
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [64]
+  - id: b
+    kind: tensor
+    config:
+      addr: 0x1000
+      dtype: fp32
+      shape: [64]
+  - id: c
+    kind: tensor
+    config:
+      addr: 0x2000
+      dtype: fp32
+      shape: [64]
+  - id: d
+    kind: tensor
+    config:
+      addr: 0x3000
+      dtype: fp32
+      shape: [64]
+  - id: add0
+    kind: compute
+    op: add
+    pe: pe0
+    input_views:
+      -
+      -
+    output_views:
+      -
+  - id: send0
+    kind: send
+    pe: pe0
+    config:
+      to: pe1
+      bytes: 256
+  - id: recv0
+    kind: recv
+    pe: pe1
+    config:
+      from: pe0
+      bytes: 256
+  - id: add1
+    kind: compute
+    op: add
+    pe: pe1
+    input_views:
+      -
+      -
+    output_views:
+      -
+
+edges:
+  - from: a
+    to: add0.0
+    kind: data
+  - from: a
+    to: add0.1
+    kind: data
+  - from: add0
+    to: b
+    kind: data
+  - from: b
+    to: send0
+    kind: data
+  - from: recv0
+    to: c
+    kind: data
+  - from: c
+    to: add1.0
+    kind: data
+  - from: c
+    to: add1.1
+    kind: data
+  - from: add1
+    to: d
+    kind: data
+";
+
+#[test]
+fn data_is_sent_between_pes() {
+    let small = TimetableFile::from_string(TIMETABLE).unwrap();
+    let large =
+        TimetableFile::from_string(&TIMETABLE.replace("bytes: 256", "bytes: 64KiB")).unwrap();
+    let small_ns = run(file!(), PLATFORM, small);
+    let large_ns = run(file!(), PLATFORM, large);
+    assert!(small_ns < large_ns, "{small_ns} >= {large_ns}");
+}
+
+#[test]
+fn receives_are_matched_with_sends_in_order() {
+    // Two sends of half the data are received by a single receive
+    let send1 = "
+  - id: send1
+    kind: send
+    pe: pe0
+    config:
+      to: pe1
+      bytes: 128
+  - id: recv0";
+    let split = TIMETABLE
+        .replace("bytes: 256\n  - id: recv0", &format!("bytes: 128{send1}"))
+        .replace(
+            "    to: send0\n",
+            "    to: send0\n    kind: data\n  - from: b\n    to: send1\n",
+        );
+    assert_eq!(split.matches("kind: send").count(), 2);
+    let timetable_file = TimetableFile::from_string(&split).unwrap();
+    run(file!(), PLATFORM, timetable_file);
+}
+
+#[test]
+fn invalid_sends_and_receives_are_rejected() {
+    let check = |from: &str, to: &str, expected: &str| {
+        let mut engine = start_test(file!());
+        let clock = engine.default_clock();
+        let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+        let timetable_file = TimetableFile::from_string(&TIMETABLE.replacen(from, to, 1)).unwrap();
+        let err = Timetable::new(engine.top(), timetable_file, &platform).unwrap_err();
+        assert!(format!("{err}").contains(expected), "{err}");
+    };
+
+    check(
+        "bytes: 256",
+        "bytes: 128",
+        "PE 'pe0' sends 128 bytes to PE 'pe1' but 'pe1' receives 256 bytes from 'pe0'",
+    );
+    check(
+        "to: pe1",
+        "to: pe2",
+        "Send node 'send0' communicates with unknown PE 'pe2'",
+    );
+    check(
+        "from: pe0",
+        "from: pe1",
+        "Recv node 'recv0' communicates with its own PE 'pe1'",
+    );
+    check(
+        "    pe: pe0\n    config:\n      to",
+        "    config:\n      to",
+        "Send node 'send0' must run on a PE",
+    );
+}
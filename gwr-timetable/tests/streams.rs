@@ -0,0 +1,256 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::run_simulation;
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::scheduling::SchedulingPolicy;
+use gwr_timetable::streams::ConcurrentGraph;
+use gwr_timetable::timetable_file::TimetableFile;
+
+/// A platform with a single compute unit in which every add takes 10 ticks
+/// and compute tasks can be preempted every 20 ticks
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+      preemption_ticks: 20
+      cost_model:
+        issue_width: 1
+        pipelined: false
+        add_latency_ticks: 10
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - connect:
+      - pe.pe0
+      - mem.hbm0
+";
+
+/// A graph that adds a to itself twice, in x and then y
+fn graph(node_priority: usize) -> TimetableFile {
+    TimetableFile::from_string(&format!(
+        "
+nodes:
+  - id: a
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [16]
+  - id: x
+    kind: compute
+    op: add
+    pe: pe0
+    priority: {node_priority}
+    input_views: [null, null]
+    output_views: [null]
+  - id: t
+    kind: tensor
+    config:
+      addr: 0x1000
+      dtype: fp32
+      shape: [16]
+  - id: y
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]
+  - id: out
+    kind: tensor
+    config:
+      addr: 0x2000
+      dtype: fp32
+      shape: [16]
+
+edges:
+  - from: a
+    to: x.0
+    kind: data
+  - from: a
+    to: x.1
+    kind: data
+  - from: x
+    to: t
+    kind: data
+  - from: t
+    to: y.0
+    kind: data
+  - from: t
+    to: y.1
+    kind: data
+  - from: y
+    to: out
+    kind: data
+
+subgraphs:
+  - id: step
+    nodes: [x]
+    iterations: 2
+"
+    ))
+    .unwrap()
+}
+
+/// Graph `train` at priority 0 with a high priority node and graph `infer`
+/// at the given priority
+fn concurrent(infer_priority: usize) -> TimetableFile {
+    TimetableFile::concurrent(vec![
+        ConcurrentGraph::new("train", graph(5)),
+        ConcurrentGraph::new("infer", graph(0)).with_priority(infer_priority),
+    ])
+    .unwrap()
+}
+
+fn build(timetable_file: TimetableFile, policy: SchedulingPolicy) -> Timetable {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    Timetable::new(engine.top(), timetable_file, &platform)
+        .unwrap()
+        .with_scheduling_policy(policy)
+}
+
+/// Run the streams and return the times at which `train` and `infer` ended
+fn run(timetable_file: TimetableFile) -> (f64, f64) {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable = Rc::new(
+        Timetable::new(engine.top(), timetable_file, &platform)
+            .unwrap()
+            .with_clock(&clock),
+    );
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    run_simulation!(engine);
+    timetable.check_tasks_complete().unwrap();
+    (
+        timetable.stream_end_ns("train").unwrap().unwrap(),
+        timetable.stream_end_ns("infer").unwrap().unwrap(),
+    )
+}
+
+// The indices of the first add of each graph
+const TRAIN_X: usize = 1;
+const INFER_X: usize = 6;
+
+#[test]
+fn graphs_are_combined_into_streams() {
+    let timetable_file = concurrent(3);
+    let ids: Vec<&str> = timetable_file
+        .nodes
+        .iter()
+        .map(|node| node.id().as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            "train_a",
+            "train_x",
+            "train_t",
+            "train_y",
+            "train_out",
+            "infer_a",
+            "infer_x",
+            "infer_t",
+            "infer_y",
+            "infer_out"
+        ]
+    );
+    assert_eq!(timetable_file.edges[6].from, "infer_a");
+    assert_eq!(timetable_file.edges[6].to, "infer_x.0");
+    assert_eq!(timetable_file.subgraphs[1].id, "infer_step");
+    assert_eq!(timetable_file.subgraphs[1].nodes, ["infer_x"]);
+
+    let streams: Vec<(&str, usize, usize)> = timetable_file
+        .streams
+        .iter()
+        .map(|stream| (stream.id.as_str(), stream.nodes.len(), stream.priority))
+        .collect();
+    assert_eq!(streams, [("train", 5, 0), ("infer", 5, 3)]);
+
+    // The streams are kept when saved
+    let json = timetable_file.to_json().unwrap();
+    let reparsed = TimetableFile::from_json(&json).unwrap();
+    assert_eq!(reparsed.streams.len(), 2);
+}
+
+#[test]
+fn higher_priority_streams_are_started_first() {
+    let ready = |timetable: &Timetable| timetable.ready_task_indices("pe0").unwrap().1;
+
+    // The priority of a stream outranks the priorities of its nodes
+    let timetable = build(concurrent(1), SchedulingPolicy::Priority);
+    assert_eq!(ready(&timetable), [INFER_X, TRAIN_X]);
+    assert!(timetable.task_priority(INFER_X) > timetable.task_priority(TRAIN_X));
+
+    // Other policies order the tasks within each stream
+    let timetable = build(concurrent(1), SchedulingPolicy::Fifo);
+    assert_eq!(ready(&timetable), [INFER_X, TRAIN_X]);
+    assert_eq!(timetable.task_priority(INFER_X), 1);
+    assert_eq!(timetable.task_priority(TRAIN_X), 0);
+
+    // Streams of the same priority leave the order to the policy
+    let timetable = build(concurrent(0), SchedulingPolicy::Priority);
+    assert_eq!(ready(&timetable), [TRAIN_X, INFER_X]);
+    let timetable = build(concurrent(0), SchedulingPolicy::Fifo);
+    assert_eq!(ready(&timetable), [TRAIN_X, INFER_X]);
+}
+
+#[test]
+fn streams_contend_for_the_platform() {
+    let (train_ns, infer_ns) = run(concurrent(1));
+    assert!(infer_ns < train_ns, "{infer_ns} >= {train_ns}");
+
+    // Without its priority, infer waits for the higher priority node of train
+    let (train_ns, infer_ns) = run(concurrent(0));
+    assert!(train_ns < infer_ns, "{train_ns} >= {infer_ns}");
+}
+
+#[test]
+fn invalid_streams_are_rejected() {
+    let err = TimetableFile::concurrent(vec![
+        ConcurrentGraph::new("g", graph(0)),
+        ConcurrentGraph::new("g", graph(0)),
+    ])
+    .unwrap_err();
+    assert_eq!(format!("{err}"), "Graph name 'g' is used more than once");
+
+    let err = TimetableFile::concurrent(vec![ConcurrentGraph::new("g.1", graph(0))]).unwrap_err();
+    assert_eq!(format!("{err}"), "Graph name 'g.1' may not contain '.'");
+
+    let err =
+        TimetableFile::concurrent(vec![ConcurrentGraph::new("g", concurrent(0))]).unwrap_err();
+    assert_eq!(format!("{err}"), "Graph 'g' already has streams");
+
+    let mut timetable_file = concurrent(0);
+    timetable_file.streams[1].nodes.push("train_y".to_string());
+    timetable_file.streams[1].nodes.push("z".to_string());
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let err = Timetable::new(engine.top(), timetable_file, &platform).unwrap_err();
+    let err = format!("{err}");
+    assert!(
+        err.contains("Node 'train_y' is in more than one stream ('train' and 'infer')"),
+        "{err}"
+    );
+    assert!(err.contains("Unknown node 'z' in stream 'infer'"), "{err}");
+}
@@ -0,0 +1,106 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::process::Command;
+
+use gwr_timetable::sweep::{Parameter, ParameterGrid};
+use serde_yaml::Value;
+
+fn point_values(grid: &ParameterGrid, index: usize) -> Vec<String> {
+    grid.point(index)
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn grid_points_vary_last_parameter_fastest() {
+    let grid = ParameterGrid::from_string(
+        "
+parameters:
+  mem0.delay_ticks: [10, 20]
+  pe0.config.lsu_access_bytes: [32, 64, 128]
+",
+    )
+    .unwrap();
+
+    assert_eq!(grid.num_points(), 6);
+    assert_eq!(
+        point_values(&grid, 0),
+        ["mem0.delay_ticks=10", "pe0.config.lsu_access_bytes=32"]
+    );
+    assert_eq!(
+        point_values(&grid, 4),
+        ["mem0.delay_ticks=20", "pe0.config.lsu_access_bytes=64"]
+    );
+    assert!(grid.point(6).is_err());
+}
+
+#[test]
+fn parameter_from_str() {
+    let parameter: Parameter = "l1_0.config.delay_ticks=1,5".parse().unwrap();
+    assert_eq!(parameter.path(), "l1_0.config.delay_ticks");
+    assert_eq!(parameter.values(), [Value::from(1), Value::from(5)]);
+
+    assert!("l1_0.config.delay_ticks".parse::<Parameter>().is_err());
+}
+
+fn run_sweep(jobs: &str) -> String {
+    run_sweep_with_env(jobs, &[])
+}
+
+fn run_sweep_with_env(jobs: &str, env: &[(&str, &str)]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_gwr-sweep"))
+        .envs(env.iter().copied())
+        .arg("--platform")
+        .arg("../gwr-platform/examples/simple_pe_cache_mem.yaml")
+        .arg("--timetable")
+        .arg("examples/cache.yaml")
+        .arg("--param")
+        .arg("mem0.delay_ticks=10,40")
+        .arg("--param")
+        .arg("l1_0.config.delay_ticks=1,5")
+        .arg("--jobs")
+        .arg(jobs)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "gwr-sweep failed\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn sweep_collates_results() {
+    let csv = run_sweep("1");
+    let rows: Vec<_> = csv.lines().collect();
+    assert_eq!(
+        rows,
+        [
+            "platform,timetable,mem0.delay_ticks,l1_0.config.delay_ticks,time_ns,memory_bytes_read,memory_bytes_written,cache_hits,cache_misses,flops",
+            "../gwr-platform/examples/simple_pe_cache_mem.yaml,examples/cache.yaml,10,1,13,32,0,1,1,0",
+            "../gwr-platform/examples/simple_pe_cache_mem.yaml,examples/cache.yaml,10,5,20,32,0,1,1,0",
+            "../gwr-platform/examples/simple_pe_cache_mem.yaml,examples/cache.yaml,40,1,43,32,0,1,1,0",
+            "../gwr-platform/examples/simple_pe_cache_mem.yaml,examples/cache.yaml,40,5,50,32,0,1,1,0",
+        ]
+    );
+}
+
+#[test]
+fn sweep_in_parallel_processes() {
+    assert_eq!(run_sweep("3"), run_sweep("1"));
+}
+
+#[test]
+fn environment_variables_do_not_add_columns() {
+    let csv = run_sweep_with_env("1", &[("GWR_PLATFORM_VAR_unused", "3")]);
+    let mut rows = csv.lines();
+    let num_columns = rows.next().unwrap().split(',').count();
+    for row in rows {
+        assert_eq!(row.split(',').count(), num_columns, "{row}");
+    }
+    assert_eq!(csv, run_sweep("1"));
+}
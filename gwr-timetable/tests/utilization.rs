@@ -0,0 +1,228 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_engine::test_helpers::start_test;
+use gwr_models::processing_element::dispatch::Dispatch;
+use gwr_platform::Platform;
+use gwr_timetable::Timetable;
+use gwr_timetable::timetable_file::TimetableFile;
+
+const PLATFORM: &str = "
+memory_maps:
+  - name: mm0
+    devices:
+      - name: hbm0
+
+processing_elements:
+  - name: pe0
+    memory_map: mm0
+    config:
+  - name: pe1
+    memory_map: mm0
+    config:
+
+fabrics:
+  - name: fabric0
+    kind: routed
+    columns: 1
+    rows: 1
+    fabric_ports_per_node: 3
+
+memories:
+  - name: hbm0
+    kind: hbm
+    base_address: 0
+    capacity_bytes: 0x1000_0000
+
+connections:
+  - fabric: fabric0
+    attach:
+      - pe.pe0@(0,0)
+      - pe.pe1@(0,0)
+      - mem.hbm0@(0,0)
+";
+
+/// A chain of adds on pe0, each reading the output of the one before, with the
+/// extra fields given for each add
+fn chain_yaml(extras: &[&str], rest: &str) -> String {
+    let mut nodes = String::from(
+        "
+  - id: t0
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [64]",
+    );
+    let mut edges = String::new();
+    for (i, extra) in extras.iter().enumerate() {
+        let i = i + 1;
+        nodes.push_str(&format!(
+            "
+  - id: add{i}
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]{extra}
+  - id: t{i}
+    kind: tensor
+    config:
+      addr: {}
+      dtype: fp32
+      shape: [64]",
+            0x1000 * i
+        ));
+        edges.push_str(&format!(
+            "
+  - from: t{0}
+    to: add{i}.0
+    kind: data
+  - from: t{0}
+    to: add{i}.1
+    kind: data
+  - from: add{i}
+    to: t{i}
+    kind: data",
+            i - 1
+        ));
+    }
+    format!("nodes:{nodes}\n\nedges:{edges}\n{rest}")
+}
+
+fn run(source: &str) -> (Rc<Timetable>, f64) {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(source).unwrap();
+    let timetable = Rc::new(
+        Timetable::new(engine.top(), timetable_file, &platform)
+            .unwrap()
+            .with_clock(&clock),
+    );
+    let dispatcher: Rc<dyn Dispatch> = timetable.clone();
+    platform.attach_dispatcher(&dispatcher);
+
+    engine.run().unwrap();
+    timetable.check_tasks_complete().unwrap();
+    timetable.dump_stats().unwrap();
+    (timetable, engine.time_now_ns())
+}
+
+#[test]
+fn pe_time_is_split_into_busy_stalled_and_idle() {
+    let (timetable, end_ns) = run(&chain_yaml(&["", "", ""], ""));
+    let report = timetable.utilization_report().unwrap();
+    assert_eq!(report.total_ns, end_ns);
+    assert_eq!(report.pes.len(), 2);
+
+    let busy_ns: f64 = timetable
+        .gantt_chart()
+        .unwrap()
+        .pes
+        .iter()
+        .flat_map(|pe| &pe.tasks)
+        .map(|slice| slice.end_ns - slice.start_ns)
+        .sum();
+    let pe0 = &report.pes[0];
+    assert_eq!(pe0.pe, "pe0");
+    assert_eq!(pe0.num_tasks, 3);
+    assert_eq!(pe0.busy_ns, busy_ns);
+    assert!(pe0.busy_ns > 0.0);
+    assert_eq!(pe0.busy_ns + pe0.stalled_ns + pe0.idle_ns, end_ns);
+
+    // pe1 ran nothing
+    let pe1 = &report.pes[1];
+    assert_eq!(pe1.pe, "pe1");
+    assert_eq!(pe1.num_tasks, 0);
+    assert_eq!(pe1.busy_ns, 0.0);
+    assert_eq!(pe1.idle_ns, end_ns);
+
+    assert_eq!(report.utilization(), busy_ns / (2.0 * end_ns));
+    let report = format!("{report}");
+    assert!(report.starts_with("PE utilization "), "{report}");
+    assert!(report.contains("  pe1: 0.0% busy 0ns"), "{report}");
+}
+
+#[test]
+fn overlapping_tasks_are_only_busy_once() {
+    // Two adds that both read t0 and can run at the same time
+    let source = "
+nodes:
+  - id: t0
+    kind: tensor
+    config:
+      addr: 0
+      dtype: fp32
+      shape: [64]
+  - id: x
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]
+  - id: y
+    kind: compute
+    op: add
+    pe: pe0
+    input_views: [null, null]
+    output_views: [null]
+  - id: t_x
+    kind: tensor
+    config:
+      addr: 0x1000
+      dtype: fp32
+      shape: [64]
+  - id: t_y
+    kind: tensor
+    config:
+      addr: 0x2000
+      dtype: fp32
+      shape: [64]
+
+edges:
+  - from: t0
+    to: x.0
+    kind: data
+  - from: t0
+    to: x.1
+    kind: data
+  - from: x
+    to: t_x
+    kind: data
+  - from: t0
+    to: y.0
+    kind: data
+  - from: t0
+    to: y.1
+    kind: data
+  - from: y
+    to: t_y
+    kind: data
+";
+    let (timetable, _) = run(source);
+    let slices = &timetable.gantt_chart().unwrap().pes[0].tasks;
+    let [first, second] = &slices[..] else {
+        panic!("{slices:?}");
+    };
+    let union_ns = if second.start_ns < first.end_ns {
+        second.end_ns.max(first.end_ns) - first.start_ns
+    } else {
+        (first.end_ns - first.start_ns) + (second.end_ns - second.start_ns)
+    };
+    let pe0 = &timetable.utilization_report().unwrap().pes[0];
+    assert_eq!(pe0.busy_ns, union_ns);
+    assert!(pe0.utilization() <= 1.0);
+}
+
+#[test]
+fn utilization_needs_a_clock() {
+    let mut engine = start_test(file!());
+    let clock = engine.default_clock();
+    let platform = Rc::new(Platform::from_string(&engine, &clock, PLATFORM).unwrap());
+    let timetable_file = TimetableFile::from_string(&chain_yaml(&[""], "")).unwrap();
+    let timetable = Timetable::new(engine.top(), timetable_file, &platform).unwrap();
+    let err = timetable.utilization_report().unwrap_err();
+    assert!(format!("{err}").contains("created with a clock"), "{err}");
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A [`Writer`](crate::Writer) that writes from a background thread.
+//!
+//! The output of a tracker is gathered into chunks which are passed through a
+//! bounded queue to a thread that writes them, so the simulation does not wait
+//! for the disk. When the queue is full the [`QueueFullPolicy`] decides
+//! whether the simulation waits for space or the chunk is dropped.
+//!
+//! The trackers write each event with a single write, and chunks are only
+//! ended between writes, so a dropped chunk holds whole events and the rest of
+//! the trace can still be read.
+
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The number of bytes gathered before they are passed to the writer thread.
+const CHUNK_BYTES: usize = 64 * 1024;
+
+/// What to do with a chunk of output when the queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueFullPolicy {
+    /// Wait for the writer thread to make space, so no output is lost.
+    #[default]
+    Block,
+
+    /// Drop the chunk, so the simulation never waits for the writer thread.
+    Drop,
+}
+
+enum Message {
+    Data(Vec<u8>),
+    Flush(mpsc::Sender<io::Result<()>>),
+}
+
+/// A writer that passes its output to a thread that writes it.
+pub struct BackgroundWriter {
+    sender: Option<SyncSender<Message>>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+    buffer: Vec<u8>,
+    policy: QueueFullPolicy,
+
+    /// The number of bytes dropped because the queue was full.
+    dropped_bytes: u64,
+}
+
+impl BackgroundWriter {
+    /// Create a new [`BackgroundWriter`] that writes to `writer` from a new
+    /// thread, with a queue of up to `queue_len` chunks.
+    pub fn new<W>(writer: W, queue_len: usize, policy: QueueFullPolicy) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::sync_channel(queue_len.max(1));
+        let thread = thread::Builder::new()
+            .name("gwr-track-writer".to_string())
+            .spawn(move || write_chunks(writer, &receiver))
+            .expect("Failed to start background writer thread");
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+            buffer: Vec::with_capacity(CHUNK_BYTES),
+            policy,
+            dropped_bytes: 0,
+        }
+    }
+
+    /// Return the number of bytes dropped because the queue was full.
+    #[must_use]
+    pub fn dropped_bytes(&self) -> u64 {
+        self.dropped_bytes
+    }
+
+    fn send(&self, message: Message) -> io::Result<()> {
+        let sender = self.sender.as_ref().ok_or_else(stopped)?;
+        sender.send(message).map_err(|_| stopped())
+    }
+
+    /// Pass the gathered output to the writer thread.
+    fn send_chunk(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.buffer, Vec::with_capacity(CHUNK_BYTES));
+        match self.policy {
+            QueueFullPolicy::Block => self.send(Message::Data(chunk)),
+            QueueFullPolicy::Drop => {
+                let sender = self.sender.as_ref().ok_or_else(stopped)?;
+                match sender.try_send(Message::Data(chunk)) {
+                    Ok(()) => Ok(()),
+                    Err(TrySendError::Full(Message::Data(chunk))) => {
+                        self.dropped_bytes += chunk.len() as u64;
+                        Ok(())
+                    }
+                    Err(_) => Err(stopped()),
+                }
+            }
+        }
+    }
+}
+
+impl Write for BackgroundWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= CHUNK_BYTES {
+            self.send_chunk()?;
+        }
+        Ok(buf.len())
+    }
+
+    /// Wait for all output so far to be written.
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_chunk()?;
+        let (ack_sender, ack_receiver) = mpsc::channel();
+        self.send(Message::Flush(ack_sender))?;
+        ack_receiver.recv().map_err(|_| stopped())?
+    }
+}
+
+impl Drop for BackgroundWriter {
+    fn drop(&mut self) {
+        let sent = self.send_chunk();
+        // Closing the queue stops the writer thread once it is empty
+        self.sender = None;
+        let written = match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(io::Error::other("background writer thread panicked")),
+            None => Ok(()),
+        };
+        if let Err(e) = sent.and(written) {
+            eprintln!("ERROR: failed to write trace: {e}");
+        }
+        if self.dropped_bytes > 0 {
+            eprintln!(
+                "WARNING: dropped {} bytes of trace as the writer queue was full",
+                self.dropped_bytes
+            );
+        }
+    }
+}
+
+fn stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "background writer has stopped")
+}
+
+/// The body of the writer thread, which runs until the queue is closed.
+fn write_chunks<W: Write>(mut writer: W, receiver: &Receiver<Message>) -> io::Result<()> {
+    for message in receiver {
+        match message {
+            Message::Data(chunk) => writer.write_all(&chunk)?,
+            Message::Flush(ack) => {
+                // The other end may have given up waiting
+                let _ = ack.send(writer.flush());
+            }
+        }
+    }
+    writer.flush()
+}
@@ -1,22 +1,70 @@
 // Copyright (c) 2025 Graphcore Ltd. All rights reserved.
 
 //! Library functions to build trackers as defined by the user.
+//!
+//! The options of the trackers can be given as command-line arguments, or in a
+//! TOML or YAML file named by `--track-config`. The keys of the file are the
+//! names of the [`TrackerArgs`] fields, for example:
+//!
+//! ```toml
+//! binary = true
+//! binary_filter_regex = "top::fabric.*"
+//! binary_zstd = true
+//! stdout = true
+//! stdout_level = "warn"
+//! monitor_window_ticks = 1000
+//!
+//! [[entity_levels]]
+//! regex = "top::fabric::node_0.*"
+//! level = "trace"
+//! ```
 
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::str::FromStr;
 use std::{fs, io};
 
-use clap::Args;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args};
+use figment::Figment;
+use figment::providers::{Format, Serialized, Toml, Yaml};
+use figment::value::{Dict, Value};
+use serde::{Deserialize, Serialize};
 
+use crate::background_writer::{BackgroundWriter, QueueFullPolicy};
+use crate::compression::{DEFAULT_ZSTD_LEVEL, ZstdWriter};
+use crate::rotation::{OpenFile, RotatingWriter, TraceHeader};
+use crate::socket::{SocketAddress, SocketFormat, SocketWriter};
+use crate::tracker::flight_recorder::FlightRecorderFormat;
 use crate::tracker::multi_tracker::MultiTracker;
 #[cfg(feature = "perfetto")]
 use crate::tracker::perfetto::PerfettoTracker;
-use crate::tracker::{CapnProtoTracker, EntityManager, TextTracker, TrackConfigError};
+use crate::tracker::sharded::ShardFactory;
+use crate::tracker::types::{EventKinds, TimeUnit};
+use crate::tracker::{
+    CapnProtoTracker, CtfTracker, EntityManager, FailOnErrorTracker, FlightRecorderTracker,
+    JsonLinesTracker, LevelControlTracker, ShardedTracker, TextTracker, ThrottleTracker,
+    TrackConfigError,
+};
 use crate::{Tracker, Writer};
 
 /// Standard command-line arguments for tracker configuration.
-#[derive(Clone, Debug, Args)]
+#[derive(Clone, Debug, Args, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct TrackerArgs {
+    /// A TOML or YAML file of tracker options, named as these arguments but
+    /// with `_` rather than `-`. Options given as arguments take precedence
+    /// over those in the file.
+    #[arg(long)]
+    #[serde(skip)]
+    pub track_config: Option<String>,
+
+    /// Whether the `--track-config` file has been applied.
+    #[arg(skip)]
+    #[serde(skip)]
+    track_config_loaded: bool,
+
     /// Enable logging to the console.
     #[arg(long, default_value = "false")]
     pub stdout: bool,
@@ -47,6 +95,75 @@ pub struct TrackerArgs {
     #[arg(long, default_value = "trace.bin")]
     pub binary_file: String,
 
+    /// Compress the binary trace output with zstd.
+    #[arg(long, default_value = "false")]
+    pub binary_zstd: bool,
+
+    /// Split the binary trace into a file per shard, named by the first
+    /// capture group of this regular expression when it matches the full name
+    /// of an entity. For example, `^top::(device\d+)` writes a file per
+    /// device.
+    #[arg(long)]
+    pub binary_shard_regex: Option<String>,
+
+    /// Rotate the binary trace between files `<binary-file>.0`,
+    /// `<binary-file>.1`, ... of up to this many bytes each.
+    #[arg(long)]
+    pub binary_rotate_bytes: Option<u64>,
+
+    /// Enable logging to a JSON Lines file, with one JSON object per event.
+    #[arg(long, default_value = "false")]
+    pub json: bool,
+
+    /// Level of JSON trace events to record.
+    #[arg(long, default_value = "Trace")]
+    pub json_level: log::Level,
+
+    /// Set a regular expression for which entities should have JSON output
+    /// level set to `--json-level`. Others will have level set to `Error`.
+    #[arg(long, default_value = "")]
+    pub json_filter_regex: String,
+
+    /// The filename JSON trace output is written to.
+    #[arg(long, default_value = "trace.jsonl")]
+    pub json_file: String,
+
+    /// Enable logging to a Common Trace Format directory, as read by Trace
+    /// Compass and babeltrace.
+    #[arg(long, default_value = "false")]
+    pub ctf: bool,
+
+    /// Level of CTF trace events to record.
+    #[arg(long, default_value = "Trace")]
+    pub ctf_level: log::Level,
+
+    /// Set a regular expression for which entities should have CTF output
+    /// level set to `--ctf-level`. Others will have level set to `Error`.
+    #[arg(long, default_value = "")]
+    pub ctf_filter_regex: String,
+
+    /// The directory CTF trace output is written to.
+    #[arg(long, default_value = "trace.ctf")]
+    pub ctf_dir: String,
+
+    /// Stream trace events to a viewer listening on this socket, as
+    /// `<host>:<port>` or `unix:<path>`, while the simulation runs.
+    #[arg(long)]
+    pub socket: Option<String>,
+
+    /// Level of trace events streamed to `--socket`.
+    #[arg(long, default_value = "Trace")]
+    pub socket_level: log::Level,
+
+    /// Set a regular expression for which entities should have socket output
+    /// level set to `--socket-level`. Others will have level set to `Error`.
+    #[arg(long, default_value = "")]
+    pub socket_filter_regex: String,
+
+    /// The format of the trace events streamed to `--socket`.
+    #[arg(long, value_enum, default_value_t = SocketFormat::Capnp)]
+    pub socket_format: SocketFormat,
+
     /// Enable logging to Perfetto file used by `gwr-spotter`.
     #[cfg(feature = "perfetto")]
     #[arg(long, default_value = "false")]
@@ -68,6 +185,74 @@ pub struct TrackerArgs {
     #[arg(long, default_value = "trace.pftrace")]
     pub perfetto_file: String,
 
+    /// Compress the Perfetto trace output with zstd.
+    #[cfg(feature = "perfetto")]
+    #[arg(long, default_value = "false")]
+    pub perfetto_zstd: bool,
+
+    /// Rotate the Perfetto trace between files `<perfetto-file>.0`,
+    /// `<perfetto-file>.1`, ... of up to this many bytes each.
+    #[cfg(feature = "perfetto")]
+    #[arg(long)]
+    pub perfetto_rotate_bytes: Option<u64>,
+
+    /// Link the events of each object in the Perfetto trace with flows, so
+    /// that its path through the model can be followed.
+    #[cfg(feature = "perfetto")]
+    #[arg(long, default_value = "false")]
+    pub perfetto_object_flows: bool,
+
+    /// Enable the flight recorder, which keeps the most recent events of each
+    /// entity and writes them to a file when an error occurs.
+    #[arg(long, default_value = "false")]
+    pub flight_recorder: bool,
+
+    /// Level of events kept by the flight recorder.
+    #[arg(long, default_value = "Trace")]
+    pub flight_recorder_level: log::Level,
+
+    /// Set a regular expression for which entities should have flight recorder
+    /// level set to `--flight-recorder-level`. Others will have level set to
+    /// `Error`.
+    #[arg(long, default_value = "")]
+    pub flight_recorder_filter_regex: String,
+
+    /// The filename the flight recorder writes its events to.
+    #[arg(long, default_value = "flight_recorder.log")]
+    pub flight_recorder_file: String,
+
+    /// The number of events the flight recorder keeps for each entity.
+    #[arg(long, default_value = "1000")]
+    pub flight_recorder_events: usize,
+
+    /// The format the flight recorder writes its events in.
+    #[arg(long, value_enum, default_value_t = FlightRecorderFormat::Text)]
+    pub flight_recorder_format: FlightRecorderFormat,
+
+    /// The number of files kept when trace output is rotated. Older files are
+    /// deleted.
+    #[arg(long, default_value = "10")]
+    pub rotate_keep_files: usize,
+
+    /// The zstd compression level used for compressed trace output.
+    #[arg(long, default_value_t = DEFAULT_ZSTD_LEVEL)]
+    pub zstd_level: i32,
+
+    /// The unit that the stdout, binary and Perfetto trackers write time in.
+    #[arg(long, value_enum, default_value_t = TimeUnit::Ns)]
+    pub time_unit: TimeUnit,
+
+    /// Limit trackers to these kinds of event, as a comma-separated list of
+    /// `lifecycle`, `messages`, `values`, `movement`, `activities` and
+    /// `registers`, or `all`.
+    #[arg(long, default_value = "all")]
+    pub event_kinds: EventKinds,
+
+    /// Set a regular expression for which entities should be limited to
+    /// `--event-kinds`. Others will have all kinds of event enabled.
+    #[arg(long, default_value = "")]
+    pub event_kinds_filter_regex: String,
+
     /// Enable monitoring at the specified number of clock ticks.
     #[arg(long)]
     pub monitor_window_ticks: Option<u64>,
@@ -75,13 +260,148 @@ pub struct TrackerArgs {
     /// Set a regular expression for which ports should have monitors enabled.
     #[arg(long, default_value = "")]
     pub monitor_filter_regex: String,
+
+    /// Write trace files from a background thread, with a queue of up to this
+    /// many 64 KiB chunks waiting to be written.
+    #[arg(long)]
+    pub writer_queue_len: Option<usize>,
+
+    /// What to do when the queue of the background writer is full.
+    #[arg(long, value_enum, default_value_t = QueueFullPolicy::Block)]
+    pub writer_queue_full: QueueFullPolicy,
+
+    /// A file of `<regex> <level>` lines that is read again whenever it
+    /// changes, to change entity levels while the simulation runs.
+    #[arg(long)]
+    pub level_control_file: Option<String>,
+
+    /// Set the level of the entities matching a regular expression in all
+    /// trackers, as `<regex>=<level>`. May be given more than once.
+    #[arg(long = "entity-level")]
+    pub entity_levels: Vec<EntityLevel>,
+
+    /// Fail at the end of the simulation if any errors were logged, with a
+    /// summary of them.
+    #[arg(long, default_value = "false")]
+    pub fail_on_error: bool,
+
+    /// Set a regular expression for which errors, as `<entity>: <message>`,
+    /// cause `--fail-on-error` to fail. All errors do if it is not set.
+    #[arg(long, default_value = "")]
+    pub fail_on_error_regex: String,
+
+    /// Write at most this many identical messages in a row from an entity at
+    /// a level, as `<level>=<count>`, and then how many times it was repeated.
+    /// May be given more than once.
+    #[arg(long)]
+    pub throttle_repeats: Vec<RepeatLimit>,
+}
+
+/// A level for the entities whose full names match a regular expression.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EntityLevel {
+    /// Regular expression matched against the full names of entities.
+    pub regex: String,
+
+    /// The level of the matching entities.
+    pub level: log::Level,
+}
+
+impl FromStr for EntityLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The regular expression may itself contain '='
+        let (regex, level) = s
+            .rsplit_once('=')
+            .ok_or_else(|| format!("Expected '<regex>=<level>', found '{s}'"))?;
+        let level = level
+            .parse()
+            .map_err(|_| format!("Invalid level '{level}' in '{s}'"))?;
+        Ok(Self {
+            regex: regex.to_string(),
+            level,
+        })
+    }
+}
+
+/// The number of identical messages in a row that are written at a level.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RepeatLimit {
+    /// The level of the messages to throttle.
+    pub level: log::Level,
+
+    /// The number of identical messages in a row to write.
+    pub max_repeats: usize,
+}
+
+impl FromStr for RepeatLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (level, max_repeats) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Expected '<level>=<count>', found '{s}'"))?;
+        let level = level
+            .parse()
+            .map_err(|_| format!("Invalid level '{level}' in '{s}'"))?;
+        let max_repeats = max_repeats
+            .parse()
+            .map_err(|_| format!("Invalid count '{max_repeats}' in '{s}'"))?;
+        Ok(Self { level, max_repeats })
+    }
 }
 
 impl TrackerArgs {
+    /// Apply the options in the `--track-config` file, if one was given.
+    ///
+    /// The `matches` are those these arguments were parsed from, and any
+    /// option given on the command line takes precedence over the file.
+    ///
+    /// The file is read as YAML if its extension is `.yaml` or `.yml`, and as
+    /// TOML otherwise. It is only applied once, so options changed after it
+    /// was loaded are kept.
+    pub fn load_track_config(&mut self, matches: &ArgMatches) -> Result<(), TrackConfigError> {
+        if self.track_config_loaded {
+            return Ok(());
+        }
+        let Some(path) = self.track_config.take() else {
+            return Ok(());
+        };
+
+        let args = Value::serialize(&*self)
+            .map_err(|e| TrackConfigError(format!("Failed to serialize arguments:\n{e}\n")))?
+            .into_dict()
+            .unwrap_or_default();
+        let given: Dict = matches
+            .ids()
+            .filter(|id| matches.value_source(id.as_str()) == Some(ValueSource::CommandLine))
+            .filter_map(|id| Some((id.to_string(), args.get(id.as_str())?.clone())))
+            .collect();
+
+        let figment = Figment::from(Serialized::defaults(&*self));
+        let figment = match Path::new(&path).extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => figment.merge(Yaml::file_exact(&path)),
+            _ => figment.merge(Toml::file_exact(&path)),
+        };
+        *self = figment
+            .merge(Serialized::defaults(given))
+            .extract()
+            .map_err(|e| TrackConfigError(format!("Failed to load {path}:\n{e}\n")))?;
+        self.track_config = Some(path);
+        self.track_config_loaded = true;
+        Ok(())
+    }
+
     /// Return whether any tracker output has been explicitly requested.
     #[must_use]
     pub fn tracking_requested(&self) -> bool {
-        let requested = self.stdout || self.binary;
+        let requested = self.stdout
+            || self.binary
+            || self.json
+            || self.ctf
+            || self.socket.is_some()
+            || self.flight_recorder;
         #[cfg(feature = "perfetto")]
         let requested = requested || self.perfetto;
         requested
@@ -91,7 +411,11 @@ impl TrackerArgs {
     #[must_use]
     pub fn level_enabled(&self, level: log::Level) -> bool {
         let shown = (self.stdout && self.stdout_level >= level)
-            || (self.binary && self.binary_level >= level);
+            || (self.binary && self.binary_level >= level)
+            || (self.json && self.json_level >= level)
+            || (self.ctf && self.ctf_level >= level)
+            || (self.socket.is_some() && self.socket_level >= level)
+            || (self.flight_recorder && self.flight_recorder_level >= level);
         #[cfg(feature = "perfetto")]
         let shown = shown || (self.perfetto && self.perfetto_level >= level);
         shown
@@ -112,6 +436,13 @@ impl TrackerArgs {
         }
     }
 
+    fn rotation(&self, rotate_bytes: Option<u64>) -> Option<RotationConfig> {
+        rotate_bytes.map(|max_bytes| RotationConfig {
+            max_bytes,
+            max_files: self.rotate_keep_files,
+        })
+    }
+
     /// Convert these command-line arguments into a [`TrackersConfig`].
     #[must_use]
     pub fn trackers_config(&self) -> TrackersConfig<'_> {
@@ -121,25 +452,95 @@ impl TrackerArgs {
                 level: self.stdout_level,
                 filter_regex: &self.stdout_filter_regex,
                 file: None,
+                zstd_level: None,
+                shard_regex: None,
+                rotation: None,
             },
             binary: TrackerConfig {
                 enable: self.binary,
                 level: self.binary_level,
                 filter_regex: &self.binary_filter_regex,
                 file: Some(&self.binary_file),
+                zstd_level: self.binary_zstd.then_some(self.zstd_level),
+                shard_regex: self.binary_shard_regex.as_deref(),
+                rotation: self.rotation(self.binary_rotate_bytes),
+            },
+            json: TrackerConfig {
+                enable: self.json,
+                level: self.json_level,
+                filter_regex: &self.json_filter_regex,
+                file: Some(&self.json_file),
+                zstd_level: None,
+                shard_regex: None,
+                rotation: None,
+            },
+            ctf: TrackerConfig {
+                enable: self.ctf,
+                level: self.ctf_level,
+                filter_regex: &self.ctf_filter_regex,
+                file: Some(&self.ctf_dir),
+                zstd_level: None,
+                shard_regex: None,
+                rotation: None,
+            },
+            socket: SocketConfig {
+                tracker: TrackerConfig {
+                    enable: self.socket.is_some(),
+                    level: self.socket_level,
+                    filter_regex: &self.socket_filter_regex,
+                    file: self.socket.as_deref(),
+                    zstd_level: None,
+                    shard_regex: None,
+                    rotation: None,
+                },
+                format: self.socket_format,
             },
             #[cfg(feature = "perfetto")]
-            perfetto: TrackerConfig {
-                enable: self.perfetto,
-                level: self.perfetto_level,
-                filter_regex: &self.perfetto_filter_regex,
-                file: Some(&self.perfetto_file),
+            perfetto: PerfettoConfig {
+                tracker: TrackerConfig {
+                    enable: self.perfetto,
+                    level: self.perfetto_level,
+                    filter_regex: &self.perfetto_filter_regex,
+                    file: Some(&self.perfetto_file),
+                    zstd_level: self.perfetto_zstd.then_some(self.zstd_level),
+                    shard_regex: None,
+                    rotation: self.rotation(self.perfetto_rotate_bytes),
+                },
+                object_flows: self.perfetto_object_flows,
+            },
+            flight_recorder: FlightRecorderConfig {
+                tracker: TrackerConfig {
+                    enable: self.flight_recorder,
+                    level: self.flight_recorder_level,
+                    filter_regex: &self.flight_recorder_filter_regex,
+                    file: Some(&self.flight_recorder_file),
+                    zstd_level: None,
+                    shard_regex: None,
+                    rotation: None,
+                },
+                events_per_entity: self.flight_recorder_events,
+                format: self.flight_recorder_format,
             },
             monitors: MonitorsConfig {
                 enable: self.monitor_window_ticks.is_some(),
                 window_size_ticks: self.monitor_window_ticks.unwrap_or(0),
                 filter_regex: &self.monitor_filter_regex,
             },
+            event_kinds: EventKindsConfig {
+                kinds: self.event_kinds,
+                filter_regex: &self.event_kinds_filter_regex,
+            },
+            writer: WriterConfig {
+                queue_len: self.writer_queue_len,
+                policy: self.writer_queue_full,
+                time_unit: self.time_unit,
+            },
+            level_control_file: self.level_control_file.as_deref(),
+            entity_levels: &self.entity_levels,
+            fail_on_error: self
+                .fail_on_error
+                .then_some(self.fail_on_error_regex.as_str()),
+            repeat_limits: &self.throttle_repeats,
         }
     }
 }
@@ -158,6 +559,26 @@ pub struct TrackerConfig<'a> {
 
     /// If required, the name of the file to which the tracker will write.
     pub file: Option<&'a str>,
+
+    /// If set, compress the file with zstd at this level.
+    pub zstd_level: Option<i32>,
+
+    /// If set, split the output into a file per shard named by this regular
+    /// expression.
+    pub shard_regex: Option<&'a str>,
+
+    /// If set, rotate the output between a number of files.
+    pub rotation: Option<RotationConfig>,
+}
+
+/// Configuration options for rotating the output of a tracker between files.
+#[derive(Clone, Copy)]
+pub struct RotationConfig {
+    /// The number of bytes written to each file.
+    pub max_bytes: u64,
+
+    /// The number of files kept.
+    pub max_files: usize,
 }
 
 impl Default for TrackerConfig<'_> {
@@ -167,10 +588,45 @@ impl Default for TrackerConfig<'_> {
             level: log::Level::Warn,
             filter_regex: "",
             file: None,
+            zstd_level: None,
+            shard_regex: None,
+            rotation: None,
         }
     }
 }
 
+/// Configuration options for the Perfetto trace.
+#[cfg(feature = "perfetto")]
+pub struct PerfettoConfig<'a> {
+    /// Configuration for the events written and the file they are written to.
+    pub tracker: TrackerConfig<'a>,
+
+    /// Link the events of each object with flows.
+    pub object_flows: bool,
+}
+
+/// Configuration options for streaming events over a socket.
+pub struct SocketConfig<'a> {
+    /// Configuration for the events streamed. The `file` is the address of
+    /// the socket.
+    pub tracker: TrackerConfig<'a>,
+
+    /// The format the events are streamed in.
+    pub format: SocketFormat,
+}
+
+/// Configuration options for the flight recorder.
+pub struct FlightRecorderConfig<'a> {
+    /// Configuration for the events kept and the file they are written to.
+    pub tracker: TrackerConfig<'a>,
+
+    /// The number of events kept for each entity.
+    pub events_per_entity: usize,
+
+    /// The format the events are written in.
+    pub format: FlightRecorderFormat,
+}
+
 /// Configuration options for monitoring.
 #[derive(Default)]
 pub struct MonitorsConfig<'a> {
@@ -184,6 +640,89 @@ pub struct MonitorsConfig<'a> {
     pub filter_regex: &'a str,
 }
 
+/// Configuration options for the kinds of event trackers record.
+#[derive(Default)]
+pub struct EventKindsConfig<'a> {
+    /// The kinds of event recorded for matching entities.
+    pub kinds: EventKinds,
+
+    /// Regular expression for which entities should be limited to `kinds`.
+    pub filter_regex: &'a str,
+}
+
+/// Configuration options for how trackers write their output.
+#[derive(Clone, Copy, Default)]
+pub struct WriterConfig {
+    /// If set, write from a background thread with a queue of up to this many
+    /// chunks.
+    pub queue_len: Option<usize>,
+
+    /// What to do when the queue of the background thread is full.
+    pub policy: QueueFullPolicy,
+
+    /// The unit that time is written in.
+    pub time_unit: TimeUnit,
+}
+
+impl WriterConfig {
+    /// Create the [`Writer`] for a tracker that writes to `writer`.
+    fn writer<W>(&self, writer: W) -> Writer
+    where
+        W: Write + Send + 'static,
+    {
+        let writer = BufWriter::new(writer);
+        match self.queue_len {
+            Some(queue_len) => Box::new(BackgroundWriter::new(writer, queue_len, self.policy)),
+            None => Box::new(writer),
+        }
+    }
+
+    /// Create the [`Writer`] for a tracker that writes to the file in
+    /// `config`, compressing and rotating it if requested.
+    fn file_writer(&self, config: &TrackerConfig) -> Result<Writer, TrackConfigError> {
+        self.file_writer_with_header(config, TraceHeader::default())
+    }
+
+    /// Create the [`Writer`] for a tracker that writes to the file in
+    /// `config`, as [`Self::file_writer`]. If the file is rotated, each new
+    /// file starts with the events of `header`.
+    fn file_writer_with_header(
+        &self,
+        config: &TrackerConfig,
+        header: TraceHeader,
+    ) -> Result<Writer, TrackConfigError> {
+        let filename = config.file.unwrap();
+        if let Some(rotation) = config.rotation {
+            let zstd_level = config.zstd_level;
+            let open_file: OpenFile = Box::new(move |path| {
+                let file = fs::File::create(path)?;
+                let writer: Box<dyn Write + Send> = match zstd_level {
+                    Some(level) => Box::new(ZstdWriter::new(file, level)?),
+                    None => Box::new(file),
+                };
+                Ok(writer)
+            });
+            let rotating =
+                RotatingWriter::new(filename, rotation.max_bytes, rotation.max_files, open_file)
+                    .map_err(|e| TrackConfigError(format!("Failed to create {filename}:\n{e}\n")))?
+                    .with_header(header);
+            return Ok(self.writer(rotating));
+        }
+
+        let file = fs::File::create(filename)
+            .map_err(|e| TrackConfigError(format!("Failed to create {filename}:\n{e}\n")))?;
+        match config.zstd_level {
+            Some(level) => {
+                let compressed = ZstdWriter::new(file, level).map_err(|e| {
+                    TrackConfigError(format!("Failed to compress {filename}:\n{e}\n"))
+                })?;
+                Ok(self.writer(compressed))
+            }
+            None => Ok(self.writer(file)),
+        }
+    }
+}
+
 /// Configuration options for all tracking/monitoring.
 pub struct TrackersConfig<'a> {
     /// Configuration for stdout.
@@ -192,12 +731,43 @@ pub struct TrackersConfig<'a> {
     /// Configuration for binary trace file.
     pub binary: TrackerConfig<'a>,
 
+    /// Configuration for JSON Lines trace file.
+    pub json: TrackerConfig<'a>,
+
+    /// Configuration for CTF trace directory.
+    pub ctf: TrackerConfig<'a>,
+
+    /// Configuration for streaming events over a socket.
+    pub socket: SocketConfig<'a>,
+
     #[cfg(feature = "perfetto")]
     /// Configuration for perfetto trace file.
-    pub perfetto: TrackerConfig<'a>,
+    pub perfetto: PerfettoConfig<'a>,
+
+    /// Configuration for the flight recorder.
+    pub flight_recorder: FlightRecorderConfig<'a>,
 
     /// Configuration for monitoring.
     pub monitors: MonitorsConfig<'a>,
+
+    /// Configuration for the kinds of event recorded.
+    pub event_kinds: EventKindsConfig<'a>,
+
+    /// Configuration for writing the output of the trackers.
+    pub writer: WriterConfig,
+
+    /// If set, a file of entity levels to apply while the simulation runs.
+    pub level_control_file: Option<&'a str>,
+
+    /// Levels of entities to set in all trackers.
+    pub entity_levels: &'a [EntityLevel],
+
+    /// If set, fail at shutdown if errors were logged that match this regular
+    /// expression, or any errors if it is empty.
+    pub fail_on_error: Option<&'a str>,
+
+    /// Limits on identical messages repeated by an entity.
+    pub repeat_limits: &'a [RepeatLimit],
 }
 
 /// Create a tracker that prints to stdout
@@ -207,6 +777,8 @@ pub struct TrackersConfig<'a> {
 fn build_stdout_tracker(
     config: &TrackerConfig,
     monitors: &MonitorsConfig,
+    event_kinds: &EventKindsConfig,
+    writer: &WriterConfig,
 ) -> Result<Tracker, TrackConfigError> {
     let default_level = if config.filter_regex.is_empty() {
         config.level
@@ -224,8 +796,14 @@ fn build_stdout_tracker(
             .set_monitor_window_size_for(monitors.filter_regex, monitors.window_size_ticks)?;
     }
 
-    let stdout_writer = Box::new(std::io::BufWriter::new(io::stdout()));
-    Ok(Rc::new(TextTracker::new(entity_manager, stdout_writer)))
+    if event_kinds.kinds != EventKinds::all() {
+        entity_manager.add_event_kinds_filter(event_kinds.filter_regex, event_kinds.kinds)?;
+    }
+
+    let stdout_writer = writer.writer(io::stdout());
+    Ok(Rc::new(
+        TextTracker::new(entity_manager, stdout_writer).with_time_unit(writer.time_unit),
+    ))
 }
 
 /// Same as the text tracker (see build_stdout_tracker) except will generate a
@@ -233,6 +811,8 @@ fn build_stdout_tracker(
 fn build_binary_tracker(
     config: &TrackerConfig,
     monitors: &MonitorsConfig,
+    event_kinds: &EventKindsConfig,
+    writer: &WriterConfig,
 ) -> Result<Tracker, TrackConfigError> {
     let default_level = if config.filter_regex.is_empty() {
         config.level
@@ -249,10 +829,186 @@ fn build_binary_tracker(
             .set_monitor_window_size_for(monitors.filter_regex, monitors.window_size_ticks)?;
     }
 
-    let bin_writer: Writer = Box::new(BufWriter::new(
-        fs::File::create(config.file.unwrap()).unwrap(),
-    ));
-    Ok(Rc::new(CapnProtoTracker::new(entity_manager, bin_writer)))
+    if event_kinds.kinds != EventKinds::all() {
+        entity_manager.add_event_kinds_filter(event_kinds.filter_regex, event_kinds.kinds)?;
+    }
+
+    if let Some(shard_regex) = config.shard_regex {
+        let file = config.file.unwrap().to_string();
+        let zstd_level = config.zstd_level;
+        let rotation = config.rotation;
+        let writer = *writer;
+        let create_shard: ShardFactory = Box::new(move |shard| {
+            let shard_file = shard_file_name(&file, shard);
+            let shard_config = TrackerConfig {
+                file: Some(&shard_file),
+                zstd_level,
+                rotation,
+                ..TrackerConfig::default()
+            };
+            let header = TraceHeader::default();
+            let shard_writer = writer.file_writer_with_header(&shard_config, header.clone())?;
+            let shard_tracker: Tracker = Rc::new(
+                CapnProtoTracker::new(EntityManager::new(log::Level::Trace), shard_writer)
+                    .with_header(header)
+                    .with_time_unit(writer.time_unit),
+            );
+            Ok(shard_tracker)
+        });
+        return Ok(Rc::new(ShardedTracker::new(
+            entity_manager,
+            shard_regex,
+            create_shard,
+        )?));
+    }
+
+    let header = TraceHeader::default();
+    let bin_writer = writer.file_writer_with_header(config, header.clone())?;
+    Ok(Rc::new(
+        CapnProtoTracker::new(entity_manager, bin_writer)
+            .with_header(header)
+            .with_time_unit(writer.time_unit),
+    ))
+}
+
+/// Return the name of the file for `shard` of the output written to `file`,
+/// by adding the shard name before the extension.
+fn shard_file_name(file: &str, shard: &str) -> String {
+    let shard: String = shard
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let path = Path::new(file);
+    let stem = path
+        .file_stem()
+        .map_or(file.into(), |s| s.to_string_lossy());
+    let shard_file = match path.extension() {
+        Some(extension) => format!("{stem}.{shard}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{shard}"),
+    };
+    path.with_file_name(shard_file)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Same as the text tracker (see build_stdout_tracker) except will generate a
+/// JSON Lines file.
+fn build_json_lines_tracker(
+    config: &TrackerConfig,
+    monitors: &MonitorsConfig,
+    event_kinds: &EventKindsConfig,
+    writer: &WriterConfig,
+) -> Result<Tracker, TrackConfigError> {
+    let default_level = if config.filter_regex.is_empty() {
+        config.level
+    } else {
+        log::Level::Error
+    };
+    let mut entity_manager = EntityManager::new(default_level);
+    if !config.filter_regex.is_empty() {
+        entity_manager.add_entity_level_filter(config.filter_regex, config.level)?;
+    }
+
+    if monitors.enable {
+        entity_manager
+            .set_monitor_window_size_for(monitors.filter_regex, monitors.window_size_ticks)?;
+    }
+
+    if event_kinds.kinds != EventKinds::all() {
+        entity_manager.add_event_kinds_filter(event_kinds.filter_regex, event_kinds.kinds)?;
+    }
+
+    let json_writer = writer.file_writer(config)?;
+    Ok(Rc::new(JsonLinesTracker::new(entity_manager, json_writer)))
+}
+
+/// Same as the text tracker (see build_stdout_tracker) except will generate a
+/// CTF trace directory.
+fn build_ctf_tracker(
+    config: &TrackerConfig,
+    monitors: &MonitorsConfig,
+    event_kinds: &EventKindsConfig,
+    writer: &WriterConfig,
+) -> Result<Tracker, TrackConfigError> {
+    let default_level = if config.filter_regex.is_empty() {
+        config.level
+    } else {
+        log::Level::Error
+    };
+    let mut entity_manager = EntityManager::new(default_level);
+    if !config.filter_regex.is_empty() {
+        entity_manager.add_entity_level_filter(config.filter_regex, config.level)?;
+    }
+
+    if monitors.enable {
+        entity_manager
+            .set_monitor_window_size_for(monitors.filter_regex, monitors.window_size_ticks)?;
+    }
+
+    if event_kinds.kinds != EventKinds::all() {
+        entity_manager.add_event_kinds_filter(event_kinds.filter_regex, event_kinds.kinds)?;
+    }
+
+    let trace_dir = config.file.unwrap();
+    let stream = CtfTracker::create_trace_dir(Path::new(trace_dir))
+        .map_err(|e| TrackConfigError(format!("Failed to create CTF trace {trace_dir}:\n{e}\n")))?;
+    Ok(Rc::new(CtfTracker::from_stream_writer(
+        entity_manager,
+        writer.writer(stream),
+    )))
+}
+
+/// Same as the text tracker (see build_stdout_tracker) except will stream the
+/// events to a viewer listening on a socket.
+fn build_socket_tracker(
+    socket_config: &SocketConfig,
+    monitors: &MonitorsConfig,
+    event_kinds: &EventKindsConfig,
+    writer: &WriterConfig,
+) -> Result<Tracker, TrackConfigError> {
+    let config = &socket_config.tracker;
+    let default_level = if config.filter_regex.is_empty() {
+        config.level
+    } else {
+        log::Level::Error
+    };
+    let mut entity_manager = EntityManager::new(default_level);
+    if !config.filter_regex.is_empty() {
+        entity_manager.add_entity_level_filter(config.filter_regex, config.level)?;
+    }
+
+    if monitors.enable {
+        entity_manager
+            .set_monitor_window_size_for(monitors.filter_regex, monitors.window_size_ticks)?;
+    }
+
+    if event_kinds.kinds != EventKinds::all() {
+        entity_manager.add_event_kinds_filter(event_kinds.filter_regex, event_kinds.kinds)?;
+    }
+
+    let address_str = config.file.unwrap();
+    let address = SocketAddress::from_str(address_str).map_err(TrackConfigError)?;
+    // The socket writer sends its output at regular intervals itself, which a
+    // buffered or background writer would hold back
+    let socket_writer: Writer =
+        Box::new(SocketWriter::connect(&address).map_err(|e| {
+            TrackConfigError(format!("Failed to connect to {address_str}:\n{e}\n"))
+        })?);
+    match socket_config.format {
+        SocketFormat::Capnp => Ok(Rc::new(
+            CapnProtoTracker::new(entity_manager, socket_writer).with_time_unit(writer.time_unit),
+        )),
+        SocketFormat::Json => Ok(Rc::new(JsonLinesTracker::new(
+            entity_manager,
+            socket_writer,
+        ))),
+    }
 }
 
 /// This tracker will produce a Perfetto trace file, which unlike the other
@@ -260,9 +1016,12 @@ fn build_binary_tracker(
 /// gwr-spotter.
 #[cfg(feature = "perfetto")]
 fn build_perfetto_tracker(
-    config: &TrackerConfig,
+    perfetto_config: &PerfettoConfig,
     monitors: &MonitorsConfig,
+    event_kinds: &EventKindsConfig,
+    writer: &WriterConfig,
 ) -> Result<Tracker, TrackConfigError> {
+    let config = &perfetto_config.tracker;
     let default_level = if config.filter_regex.is_empty() {
         config.level
     } else {
@@ -278,48 +1037,252 @@ fn build_perfetto_tracker(
             .set_monitor_window_size_for(monitors.filter_regex, monitors.window_size_ticks)?;
     }
 
-    let bin_writer: Writer = Box::new(BufWriter::new(
-        fs::File::create(config.file.unwrap()).unwrap(),
-    ));
-    Ok(Rc::new(PerfettoTracker::new(entity_manager, bin_writer)))
+    if event_kinds.kinds != EventKinds::all() {
+        entity_manager.add_event_kinds_filter(event_kinds.filter_regex, event_kinds.kinds)?;
+    }
+
+    let header = TraceHeader::default();
+    let bin_writer = writer.file_writer_with_header(config, header.clone())?;
+    Ok(Rc::new(
+        PerfettoTracker::new(entity_manager, bin_writer)
+            .with_header(header)
+            .with_time_unit(writer.time_unit)
+            .with_object_flows(perfetto_config.object_flows)
+            // Each rotated file is read on its own, so cannot refer to names
+            // written to an earlier file
+            .with_interned_names(config.rotation.is_none()),
+    ))
+}
+
+/// Same as the text tracker (see build_stdout_tracker) except will keep the
+/// most recent events of each entity and only write them to a file when an
+/// error is logged.
+fn build_flight_recorder_tracker(
+    config: &FlightRecorderConfig,
+    monitors: &MonitorsConfig,
+    event_kinds: &EventKindsConfig,
+    writer: &WriterConfig,
+) -> Result<Tracker, TrackConfigError> {
+    let tracker_config = &config.tracker;
+    let default_level = if tracker_config.filter_regex.is_empty() {
+        tracker_config.level
+    } else {
+        log::Level::Error
+    };
+    let mut entity_manager = EntityManager::new(default_level);
+    if !tracker_config.filter_regex.is_empty() {
+        entity_manager
+            .add_entity_level_filter(tracker_config.filter_regex, tracker_config.level)?;
+    }
+
+    if monitors.enable {
+        entity_manager
+            .set_monitor_window_size_for(monitors.filter_regex, monitors.window_size_ticks)?;
+    }
+
+    if event_kinds.kinds != EventKinds::all() {
+        entity_manager.add_event_kinds_filter(event_kinds.filter_regex, event_kinds.kinds)?;
+    }
+
+    // The events have already been filtered, so the dump writes all of them
+    let dump_writer = writer.file_writer(tracker_config)?;
+    let dump_entity_manager = EntityManager::new(log::Level::Trace);
+    let dump_to: Tracker = match config.format {
+        FlightRecorderFormat::Text => Rc::new(
+            TextTracker::new(dump_entity_manager, dump_writer).with_time_unit(writer.time_unit),
+        ),
+        FlightRecorderFormat::Capnp => Rc::new(
+            CapnProtoTracker::new(dump_entity_manager, dump_writer)
+                .with_time_unit(writer.time_unit),
+        ),
+    };
+    Ok(Rc::new(FlightRecorderTracker::new(
+        entity_manager,
+        config.events_per_entity,
+        dump_to,
+    )))
 }
 
-/// Set up stdout/binary/Perfetto trackers according the the command-line
+/// Set up stdout/binary/JSON/CTF/socket/Perfetto trackers according the the command-line
 /// arguments
-#[cfg(not(feature = "perfetto"))]
 pub fn setup_trackers(config: &TrackersConfig) -> Result<Tracker, TrackConfigError> {
-    let multi_tracker_required = config.stdout.enable && config.binary.enable;
+    let tracker = build_trackers(config)?;
+    for entity_level in config.entity_levels {
+        tracker.set_entity_level(&entity_level.regex, entity_level.level)?;
+    }
+    let tracker: Tracker = if config.repeat_limits.is_empty() {
+        tracker
+    } else {
+        let throttle = config
+            .repeat_limits
+            .iter()
+            .fold(ThrottleTracker::new(tracker), |throttle, limit| {
+                throttle.with_max_repeats(limit.level, limit.max_repeats)
+            });
+        Rc::new(throttle)
+    };
+    // Errors are checked before they are throttled
+    let tracker: Tracker = match config.fail_on_error {
+        Some(regex_str) => Rc::new(FailOnErrorTracker::new(tracker, regex_str)?),
+        None => tracker,
+    };
+    match config.level_control_file {
+        Some(control_file) => Ok(Rc::new(LevelControlTracker::new(
+            tracker,
+            PathBuf::from(control_file),
+        )?)),
+        None => Ok(tracker),
+    }
+}
+
+/// Set up the trackers requested by the command-line arguments, after applying
+/// any `--track-config` file with [`TrackerArgs::load_track_config`].
+///
+/// The `matches` are those the arguments were parsed from.
+pub fn setup_trackers_from_args(
+    args: &mut TrackerArgs,
+    matches: &ArgMatches,
+) -> Result<Tracker, TrackConfigError> {
+    args.load_track_config(matches)?;
+    setup_trackers(&args.trackers_config())
+}
+
+#[cfg(not(feature = "perfetto"))]
+fn build_trackers(config: &TrackersConfig) -> Result<Tracker, TrackConfigError> {
+    let multi_tracker_required = [
+        config.stdout.enable,
+        config.binary.enable,
+        config.json.enable,
+        config.ctf.enable,
+        config.socket.tracker.enable,
+        config.flight_recorder.tracker.enable,
+    ]
+    .into_iter()
+    .filter(|x| *x)
+    .count()
+        > 1;
 
     if multi_tracker_required {
         let mut tracker = MultiTracker::default();
 
         if config.stdout.enable {
-            let log_tracker: Tracker = build_stdout_tracker(&config.stdout, &config.monitors)?;
+            let log_tracker: Tracker = build_stdout_tracker(
+                &config.stdout,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
             tracker.add_tracker(log_tracker);
         }
         if config.binary.enable {
-            let trace_tracker: Tracker = build_binary_tracker(&config.binary, &config.monitors)?;
+            let trace_tracker: Tracker = build_binary_tracker(
+                &config.binary,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
             tracker.add_tracker(trace_tracker);
         }
+        if config.json.enable {
+            let json_tracker: Tracker = build_json_lines_tracker(
+                &config.json,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(json_tracker);
+        }
+        if config.ctf.enable {
+            let ctf_tracker: Tracker = build_ctf_tracker(
+                &config.ctf,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(ctf_tracker);
+        }
+        if config.socket.tracker.enable {
+            let socket_tracker: Tracker = build_socket_tracker(
+                &config.socket,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(socket_tracker);
+        }
+        if config.flight_recorder.tracker.enable {
+            let flight_recorder_tracker: Tracker = build_flight_recorder_tracker(
+                &config.flight_recorder,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(flight_recorder_tracker);
+        }
 
         Ok(Rc::new(tracker))
     } else if config.stdout.enable {
-        build_stdout_tracker(&config.stdout, &config.monitors)
+        build_stdout_tracker(
+            &config.stdout,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
     } else if config.binary.enable {
-        build_binary_tracker(&config.binary, &config.monitors)
+        build_binary_tracker(
+            &config.binary,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.json.enable {
+        build_json_lines_tracker(
+            &config.json,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.ctf.enable {
+        build_ctf_tracker(
+            &config.ctf,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.socket.tracker.enable {
+        build_socket_tracker(
+            &config.socket,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.flight_recorder.tracker.enable {
+        build_flight_recorder_tracker(
+            &config.flight_recorder,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
     } else {
-        build_stdout_tracker(&TrackerConfig::default(), &MonitorsConfig::default())
+        build_stdout_tracker(
+            &TrackerConfig::default(),
+            &MonitorsConfig::default(),
+            &EventKindsConfig::default(),
+            &WriterConfig::default(),
+        )
     }
 }
 
-/// Set up stdout/binary/Perfetto trackers according the the command-line
-/// arguments
 #[cfg(feature = "perfetto")]
-pub fn setup_trackers(config: &TrackersConfig) -> Result<Tracker, TrackConfigError> {
+fn build_trackers(config: &TrackersConfig) -> Result<Tracker, TrackConfigError> {
     let multi_tracker_required = [
         config.stdout.enable,
         config.binary.enable,
-        config.perfetto.enable,
+        config.json.enable,
+        config.ctf.enable,
+        config.socket.tracker.enable,
+        config.perfetto.tracker.enable,
+        config.flight_recorder.tracker.enable,
     ]
     .into_iter()
     .filter(|x| *x)
@@ -330,27 +1293,125 @@ pub fn setup_trackers(config: &TrackersConfig) -> Result<Tracker, TrackConfigErr
         let mut tracker = MultiTracker::default();
 
         if config.stdout.enable {
-            let log_tracker: Tracker = build_stdout_tracker(&config.stdout, &config.monitors)?;
+            let log_tracker: Tracker = build_stdout_tracker(
+                &config.stdout,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
             tracker.add_tracker(log_tracker);
         }
         if config.binary.enable {
-            let trace_tracker: Tracker = build_binary_tracker(&config.binary, &config.monitors)?;
+            let trace_tracker: Tracker = build_binary_tracker(
+                &config.binary,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
             tracker.add_tracker(trace_tracker);
         }
-        if config.perfetto.enable {
-            let perfetto_tracker: Tracker =
-                build_perfetto_tracker(&config.perfetto, &config.monitors)?;
+        if config.json.enable {
+            let json_tracker: Tracker = build_json_lines_tracker(
+                &config.json,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(json_tracker);
+        }
+        if config.ctf.enable {
+            let ctf_tracker: Tracker = build_ctf_tracker(
+                &config.ctf,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(ctf_tracker);
+        }
+        if config.socket.tracker.enable {
+            let socket_tracker: Tracker = build_socket_tracker(
+                &config.socket,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(socket_tracker);
+        }
+        if config.perfetto.tracker.enable {
+            let perfetto_tracker: Tracker = build_perfetto_tracker(
+                &config.perfetto,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
             tracker.add_tracker(perfetto_tracker);
         }
+        if config.flight_recorder.tracker.enable {
+            let flight_recorder_tracker: Tracker = build_flight_recorder_tracker(
+                &config.flight_recorder,
+                &config.monitors,
+                &config.event_kinds,
+                &config.writer,
+            )?;
+            tracker.add_tracker(flight_recorder_tracker);
+        }
 
         Ok(Rc::new(tracker))
     } else if config.stdout.enable {
-        build_stdout_tracker(&config.stdout, &config.monitors)
+        build_stdout_tracker(
+            &config.stdout,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
     } else if config.binary.enable {
-        build_binary_tracker(&config.binary, &config.monitors)
-    } else if config.perfetto.enable {
-        build_perfetto_tracker(&config.perfetto, &config.monitors)
+        build_binary_tracker(
+            &config.binary,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.json.enable {
+        build_json_lines_tracker(
+            &config.json,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.ctf.enable {
+        build_ctf_tracker(
+            &config.ctf,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.socket.tracker.enable {
+        build_socket_tracker(
+            &config.socket,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.perfetto.tracker.enable {
+        build_perfetto_tracker(
+            &config.perfetto,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
+    } else if config.flight_recorder.tracker.enable {
+        build_flight_recorder_tracker(
+            &config.flight_recorder,
+            &config.monitors,
+            &config.event_kinds,
+            &config.writer,
+        )
     } else {
-        build_stdout_tracker(&TrackerConfig::default(), &MonitorsConfig::default())
+        build_stdout_tracker(
+            &TrackerConfig::default(),
+            &MonitorsConfig::default(),
+            &EventKindsConfig::default(),
+            &WriterConfig::default(),
+        )
     }
 }
@@ -0,0 +1,83 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Optional [zstd](https://facebook.github.io/zstd/) compression of trace
+//! files.
+//!
+//! Traces are compressed as a stream while they are written, and
+//! [`open_trace`] can be used to read a trace whether or not it is compressed.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+/// The default zstd compression level.
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// The bytes at the start of every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// A writer that compresses its output with zstd.
+///
+/// The zstd frame is completed when the writer is dropped, or by
+/// [`finish`](ZstdWriter::finish).
+pub struct ZstdWriter<W: Write> {
+    encoder: Option<zstd::Encoder<'static, W>>,
+}
+
+impl<W: Write> ZstdWriter<W> {
+    /// Create a new [`ZstdWriter`] that writes to `writer` at the given
+    /// compression `level`.
+    pub fn new(writer: W, level: i32) -> io::Result<Self> {
+        Ok(Self {
+            encoder: Some(zstd::Encoder::new(writer, level)?),
+        })
+    }
+
+    /// Complete the zstd frame and return the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.encoder.take().unwrap().finish()
+    }
+}
+
+impl<W: Write> Write for ZstdWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.encoder.as_mut().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.encoder.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: Write> Drop for ZstdWriter<W> {
+    fn drop(&mut self) {
+        if let Some(encoder) = self.encoder.take()
+            && let Err(e) = encoder.finish()
+        {
+            eprintln!("ERROR: failed to complete compressed trace: {e}");
+        }
+    }
+}
+
+/// Open a trace file for reading, decompressing it if it was written with
+/// zstd compression.
+pub fn open_trace(path: &Path) -> io::Result<Box<dyn BufRead + Send>> {
+    read_trace(BufReader::new(File::open(path)?))
+}
+
+/// Read a trace from `reader`, decompressing it if it was written with zstd
+/// compression.
+///
+/// This allows a trace to be read from a source other than a complete file,
+/// such as one that is still being written.
+pub fn read_trace<R>(mut reader: R) -> io::Result<Box<dyn BufRead + Send>>
+where
+    R: BufRead + Send + 'static,
+{
+    if reader.fill_buf()?.starts_with(&ZSTD_MAGIC) {
+        let decoder = zstd::Decoder::with_buffer(reader)?;
+        Ok(Box::new(BufReader::new(decoder)))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
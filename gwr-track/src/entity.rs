@@ -6,10 +6,12 @@
 //! hierarchy of simulation entities. They contain a name and a unique ID
 //! for tracing.
 
+use std::cell::Cell;
 use std::fmt;
 use std::rc::Rc;
 
 use crate::tracker::aka::{Aka, get_alternative_names};
+use crate::tracker::levels_generation;
 use crate::{Id, Tracker, create_id, destroy, trace};
 
 /// A capacity value and its units.
@@ -33,6 +35,70 @@ impl Capacity {
     }
 }
 
+/// A read or write of a register.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisterAccess {
+    /// Whether the register was written rather than read.
+    pub is_write: bool,
+
+    /// Offset of the register within its register file.
+    pub offset: u64,
+
+    /// Name of the register view accessed.
+    pub view: String,
+
+    /// Register value before the access.
+    pub old_value: u64,
+
+    /// Register value after the access.
+    pub new_value: u64,
+}
+
+impl RegisterAccess {
+    /// Construct a [`RegisterAccess`] for a read that returned `value`.
+    #[must_use]
+    pub fn read(offset: u64, view: impl Into<String>, value: u64) -> Self {
+        Self {
+            is_write: false,
+            offset,
+            view: view.into(),
+            old_value: value,
+            new_value: value,
+        }
+    }
+
+    /// Construct a [`RegisterAccess`] for a write that changes the register
+    /// from `old_value` to `new_value`.
+    #[must_use]
+    pub fn write(offset: u64, view: impl Into<String>, old_value: u64, new_value: u64) -> Self {
+        Self {
+            is_write: true,
+            offset,
+            view: view.into(),
+            old_value,
+            new_value,
+        }
+    }
+}
+
+impl fmt::Display for RegisterAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_write {
+            write!(
+                f,
+                "register write {}@0x{:x}: 0x{:x} -> 0x{:x}",
+                self.view, self.offset, self.old_value, self.new_value
+            )
+        } else {
+            write!(
+                f,
+                "register read {}@0x{:x}: 0x{:x}",
+                self.view, self.offset, self.new_value
+            )
+        }
+    }
+}
+
 /// A simulation entity
 ///
 /// An entity is a part of a hierarchical simulation in which it must have a
@@ -55,7 +121,11 @@ pub struct Entity {
     pub tracker: Tracker,
 
     /// Most verbose log level enabled for this entity by any tracker.
-    enabled_level: log::Level,
+    enabled_level: Cell<log::Level>,
+
+    /// The number of changes of entity levels when `enabled_level` was read,
+    /// so that it is read again when levels are changed.
+    levels_generation: Cell<u64>,
 }
 
 static JOIN: &str = "::";
@@ -77,7 +147,8 @@ impl Entity {
 
         let tracker = parent.tracker.clone();
         let id = create_id!(parent);
-        let enabled_level = tracker.add_entity(id, &full_name, alternative_names);
+        let levels_generation = Cell::new(levels_generation());
+        let enabled_level = Cell::new(tracker.add_entity(id, &full_name, alternative_names));
 
         let entity = Self {
             name: String::from(name),
@@ -85,6 +156,7 @@ impl Entity {
             id,
             tracker,
             enabled_level,
+            levels_generation,
         };
         entity.track_create(parent.id, &full_name);
 
@@ -121,7 +193,12 @@ impl Entity {
     /// entity.
     #[must_use]
     pub fn enabled_for(&self, level: log::Level) -> bool {
-        level <= self.enabled_level
+        let generation = levels_generation();
+        if generation != self.levels_generation.get() {
+            self.levels_generation.set(generation);
+            self.enabled_level.set(self.tracker.enabled_level(self.id));
+        }
+        level <= self.enabled_level.get()
     }
 
     /// Emit the capacity represented by this simulation entity.
@@ -129,6 +206,21 @@ impl Entity {
         self.tracker.capacity(self.id, Capacity::new(value, units));
     }
 
+    /// Emit a register access made through this simulation entity.
+    pub fn track_register_access(&self, access: RegisterAccess) {
+        self.tracker.register_access(self.id, access);
+    }
+
+    /// Emit a change of `delta` in a named counter of this simulation entity.
+    pub fn track_counter(&self, name: &str, delta: f64) {
+        self.tracker.counter(self.id, name, delta);
+    }
+
+    /// Emit a sample of a named histogram of this simulation entity.
+    pub fn track_histogram(&self, name: &str, value: f64) {
+        self.tracker.histogram(self.id, name, value);
+    }
+
     /// Emit an enter event for an object.
     pub fn track_enter(&self, entered: Id) {
         self.tracker.enter(self.id, entered);
@@ -139,6 +231,29 @@ impl Entity {
         self.tracker.exit(self.id, exited);
     }
 
+    /// Emit the beginning of a named span of time of this entity.
+    ///
+    /// Returns the ID of the span to pass to [`Entity::track_span_end`].
+    pub fn track_span_begin(&self, name: &str) -> Id {
+        let span = create_id!(self);
+        self.tracker.span_begin(self.id, span, name);
+        span
+    }
+
+    /// Emit the end of a span of this entity.
+    pub fn track_span_end(&self, span: Id) {
+        self.tracker.span_end(self.id, span);
+    }
+
+    /// Begin a named span of time of this entity that ends when the returned
+    /// guard is dropped.
+    pub fn span(&self, name: &str) -> SpanGuard<'_> {
+        SpanGuard {
+            entity: self,
+            span: self.track_span_begin(name),
+        }
+    }
+
     fn track_create(&self, created_by: Id, full_name: &str) {
         self.tracker.create_entity(created_by, self.id, full_name);
     }
@@ -171,17 +286,34 @@ impl fmt::Display for Entity {
     }
 }
 
+/// A span of time of an entity that ends when it is dropped.
+#[must_use = "the span ends as soon as the guard is dropped"]
+pub struct SpanGuard<'a> {
+    entity: &'a Entity,
+
+    /// Unique identifier of the span.
+    pub span: Id,
+}
+
+impl Drop for SpanGuard<'_> {
+    fn drop(&mut self) {
+        self.entity.track_span_end(self.span);
+    }
+}
+
 /// Create the top-level entity. This should be the only entity without a
 /// parent.
 pub fn toplevel(tracker: &Tracker, name: &str) -> Rc<Entity> {
     let id = tracker.unique_id();
-    let enabled_level = tracker.add_entity(id, name, None);
+    let levels_generation = Cell::new(levels_generation());
+    let enabled_level = Cell::new(tracker.add_entity(id, name, None));
     let top = Rc::new(Entity {
         parent: None,
         name: String::from(name),
         id,
         tracker: tracker.clone(),
         enabled_level,
+        levels_generation,
     });
     top.track_create(crate::NO_ID, name);
     top
@@ -229,7 +361,7 @@ impl EntityMonitor {
 
     /// Emit a value event for this monitor.
     pub fn track_value(&self, value: f64) {
-        self.entity.tracker.value(self.entity.id, value);
+        self.entity.tracker.value(self.id, value);
     }
 }
 
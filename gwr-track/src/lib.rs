@@ -14,8 +14,16 @@
 //!
 //!   - a textual output based on the [log](https://docs.rs/log) crate.
 //!   - a packed binary output based on [Cap'n Proto](https://capnproto.org/).
+//!   - a [JSON Lines](https://jsonlines.org/) output with one object per event.
+//!   - a [Common Trace Format](https://diamon.org/ctf/) trace directory, for
+//!     Trace Compass and babeltrace.
 //!   - a packed binary output based output based on [Perfetto TrackEvents](https://perfetto.dev/docs/instrumentation/track-events)
 //!     Protobufs (only avaliable with the `perfetto` feature enabled).
+//!
+//! The binary and Perfetto outputs can be compressed with
+//! [zstd](crate::compression), and read back with
+//! [`open_trace`](crate::compression::open_trace), and can be
+//! [rotated](crate::rotation) between files as they grow.
 
 // Enable warnings for missing documentation
 #![warn(missing_docs)]
@@ -27,13 +35,19 @@ use std::str::FromStr;
 #[doc(hidden)]
 pub use log;
 
+pub mod background_writer;
 pub mod builder;
+pub mod compression;
 pub mod entity;
 pub mod id;
+pub mod metadata;
 
 #[cfg(feature = "perfetto")]
 pub mod perfetto_trace_builder;
 
+pub mod rotation;
+pub mod socket;
+
 /// Include the trackers.
 pub mod tracker;
 pub use tracker::{Track, Tracker};
@@ -133,11 +147,40 @@ macro_rules! connect {
     }};
 }
 
-/// Update the current time.
+/// Begin a named span of time of an entity.
+///
+/// Returns the [`Id`] of the span to pass to [`span_end`]. The name is only
+/// formatted when trace-level events are enabled for the entity, and
+/// [`NO_ID`] is returned otherwise.
+#[macro_export]
+macro_rules! span_begin {
+    ($entity:expr ; $($name:tt)+) => {{
+        let entity = &$entity;
+        if entity.trace_enabled() {
+            entity.track_span_begin(&format!($($name)+))
+        } else {
+            $crate::NO_ID
+        }
+    }};
+}
+
+/// End a span of time of an entity begun with [`span_begin`].
+#[macro_export]
+macro_rules! span_end {
+    ($entity:expr ; $span:expr) => {{
+        let span = $span;
+        if span != $crate::NO_ID {
+            $entity.track_span_end(span);
+        }
+    }};
+}
+
+/// Update the current time to a whole number of ticks of a
+/// [`TimeUnit`](crate::tracker::types::TimeUnit).
 #[macro_export]
 macro_rules! set_time {
-    ($entity:expr ; $time_ns:expr) => {{
-        $entity.tracker.time($entity.id, $time_ns);
+    ($entity:expr ; $ticks:expr, $time_unit:expr) => {{
+        $entity.tracker.time($entity.id, $ticks, $time_unit);
     }};
 }
 
@@ -147,13 +190,23 @@ macro_rules! set_time {
 /// [`Trace`](trait.Trace.html) [message](trait.Trace.html#tymethod.message)
 /// function which will emit `message` tracking events to the Cap'n Proto binary
 /// stream.
+///
+/// The module, file and line of the call are passed to the tracker with the
+/// message.
 #[macro_export]
 macro_rules! log_base {
     ($entity:expr ; $lvl:expr, $($arg:tt)+) => {{
         let entity = &$entity;
         let level = $lvl;
         if entity.enabled_for(level) {
-            entity.tracker.log(entity.id, level, format_args!($($arg)+));
+            let call_site = $crate::tracker::types::CallSite {
+                module_path: module_path!(),
+                file: file!(),
+                line: line!(),
+            };
+            entity
+                .tracker
+                .log_at(entity.id, level, &call_site, format_args!($($arg)+));
         }
     }};
 }
@@ -0,0 +1,182 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Metadata about a simulation run, recorded at the head of its traces.
+//!
+//! A trace is much easier to analyse later if it says how it was made. The
+//! [`RunMetadata`] collects the command line, start time and other details of
+//! a run as `key`/`value` pairs, and [`RunMetadata::record`] passes them to a
+//! [`Tracker`] with [`Track::metadata`](crate::Track::metadata). It should be
+//! recorded before the simulation starts, so that it is at the head of the
+//! traces.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use gwr_track::metadata::RunMetadata;
+//! use gwr_track::tracker::dev_null_tracker;
+//!
+//! let tracker = dev_null_tracker();
+//! RunMetadata::from_process()
+//!     .with_seed(42)
+//!     .with_git_describe()
+//!     .record(&tracker);
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::Tracker;
+
+/// Metadata about a simulation run, as `key`/`value` pairs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunMetadata {
+    entries: Vec<(String, String)>,
+}
+
+impl RunMetadata {
+    /// Create the metadata of the current process, giving its
+    /// `command_line`, the wall-clock `start_time` and the `gwr_track_version`.
+    #[must_use]
+    pub fn from_process() -> Self {
+        let command_line = std::env::args()
+            .map(|arg| quote_arg(&arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let start_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs());
+        Self::default()
+            .with("command_line", command_line)
+            .with("start_time", format_utc(start_time))
+            .with("gwr_track_version", env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Add a `key`/`value` pair.
+    #[must_use]
+    pub fn with(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.entries.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Add the random `seed` of the run.
+    #[must_use]
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.with("seed", seed)
+    }
+
+    /// Add the output of `git describe` for the current directory as
+    /// `git_describe`. Nothing is added if it is not in a git repository.
+    #[must_use]
+    pub fn with_git_describe(self) -> Self {
+        let output = Command::new("git")
+            .args(["describe", "--always", "--dirty", "--tags"])
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                let describe = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                self.with("git_describe", describe)
+            }
+            _ => self,
+        }
+    }
+
+    /// Add the path and a hash of the contents of the file at `path` as
+    /// `key`, so that the inputs of a run can be identified.
+    ///
+    /// The hash is the 64-bit FNV-1a hash of the contents.
+    pub fn with_file_hash(self, key: impl Into<String>, path: &Path) -> io::Result<Self> {
+        let contents = fs::read(path)?;
+        let value = format!("{} fnv1a64:{:016x}", path.display(), fnv1a64(&contents));
+        Ok(self.with(key, value))
+    }
+
+    /// Return the `key`/`value` pairs in the order they were added.
+    #[must_use]
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+
+    /// Pass each `key`/`value` pair to `tracker`.
+    pub fn record(&self, tracker: &Tracker) {
+        for (key, value) in &self.entries {
+            tracker.metadata(key, value);
+        }
+    }
+}
+
+/// Quote an argument of the command line if it would otherwise be split or
+/// lost by a shell.
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./=:,+@%".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Format seconds since the UNIX epoch as an RFC 3339 UTC time.
+fn format_utc(secs: u64) -> String {
+    let days = secs / 86_400;
+    let secs_of_day = secs % 86_400;
+
+    // Convert days since the epoch to a civil date, from
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+/// The 64-bit FNV-1a hash of `bytes`.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utc_times_are_formatted() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(951_827_696), "2000-02-29T12:34:56Z");
+        assert_eq!(format_utc(1_767_225_599), "2025-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn fnv1a64_matches_reference() {
+        assert_eq!(fnv1a64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a64(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn args_are_quoted_when_needed() {
+        assert_eq!(quote_arg("--seed=42"), "--seed=42");
+        assert_eq!(quote_arg("top::node.*"), "'top::node.*'");
+        assert_eq!(quote_arg("it's"), r"'it'\''s'");
+        assert_eq!(quote_arg(""), "''");
+    }
+}
@@ -13,24 +13,64 @@
 //! Multiple TracePackets can included within a single Trace message and
 //! multiple trace messages can be written consecutively to the same Perfetto
 //! trace file.
+//!
+//! The names of events and their arguments are interned as the Trace messages
+//! are serialised, so each name is only written once. The Trace messages must
+//! therefore be written in the order that they are serialised.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use gwr_perfetto::protos::trace_packet::Data;
 use gwr_perfetto::protos::track_descriptor::StaticOrDynamicName;
 use gwr_perfetto::protos::{
-    CounterDescriptor, Trace, TracePacket, TrackDescriptor, TrackEvent, counter_descriptor,
-    trace_packet, track_event,
+    ChromeEventBundle, ChromeMetadata, ClockSnapshot, CounterDescriptor, DebugAnnotation,
+    DebugAnnotationName, EventName, InternedData, Trace, TracePacket, TrackDescriptor, TrackEvent,
+    chrome_metadata, clock_snapshot, counter_descriptor, debug_annotation, trace_packet,
+    track_event,
 };
 use prost::Message;
 use rand::random;
 
 use crate::Id;
+use crate::entity::RegisterAccess;
+use crate::tracker::types::TimeUnit;
+
+/// Set in the UUID of the track that shows the register accesses of an entity
+/// to keep it distinct from the entity's own track.
+const REGISTER_TRACK_UUID_BIT: u64 = 1 << 63;
+
+/// Set in the UUID of the track that shows the spans of an entity, as the
+/// entity's own track is a counter.
+const SPAN_TRACK_UUID_BIT: u64 = 1 << 60;
+
+/// Set in the UUID of the tracks that show the counters and histograms of
+/// entities, which are numbered in the order they are first seen.
+const METRIC_TRACK_UUID_BIT: u64 = 1 << 62;
+
+/// Set in the UUID of the track that shows the objects passing through an
+/// entity, whose events are linked by flows.
+const OBJECT_TRACK_UUID_BIT: u64 = 1 << 61;
+
+/// The ID of the clock defined for timestamps that are not in nanoseconds.
+/// IDs from 64 are defined by the trace rather than built in to Perfetto.
+const SIM_CLOCK_ID: u32 = 64;
 
 /// State for a trace builder instance.
 pub struct PerfettoTraceBuilder {
     trusted_packet_sequence_id: u32,
     id_to_name: HashMap<u64, String>,
+    register_tracks: HashSet<u64>,
+    span_tracks: HashSet<u64>,
+    metric_tracks: HashMap<u64, HashMap<String, u64>>,
+    num_metric_tracks: u64,
+    object_tracks: HashSet<u64>,
+    open_flows: HashSet<u64>,
+    timestamp_clock_id: Option<u32>,
+    ticks_per_timestamp: u64,
+    event_name_iids: HashMap<String, u64>,
+    debug_annotation_name_iids: HashMap<String, u64>,
+    incremental_state_cleared: bool,
+    intern_names: bool,
 }
 
 impl Default for PerfettoTraceBuilder {
@@ -38,6 +78,18 @@ impl Default for PerfettoTraceBuilder {
         Self {
             trusted_packet_sequence_id: random(),
             id_to_name: HashMap::new(),
+            register_tracks: HashSet::new(),
+            span_tracks: HashSet::new(),
+            metric_tracks: HashMap::new(),
+            num_metric_tracks: 0,
+            object_tracks: HashSet::new(),
+            open_flows: HashSet::new(),
+            timestamp_clock_id: None,
+            ticks_per_timestamp: 1,
+            event_name_iids: HashMap::new(),
+            debug_annotation_name_iids: HashMap::new(),
+            incremental_state_cleared: false,
+            intern_names: true,
         }
     }
 }
@@ -52,6 +104,90 @@ impl PerfettoTraceBuilder {
         PerfettoTraceBuilder::default()
     }
 
+    /// Set whether names are interned when TracePackets are serialised.
+    ///
+    /// Names should not be interned if the serialised Trace messages are not
+    /// all read together, such as when they are rotated between files.
+    pub fn set_intern_names(&mut self, intern_names: bool) {
+        self.intern_names = intern_names;
+    }
+
+    /// Build the TracePacket that defines the clock for timestamps in
+    /// `time_unit`, if one is needed. All later TracePackets use the clock.
+    ///
+    /// Perfetto has no clock faster than a nanosecond, so times in
+    /// picoseconds use the default clock and are written to the nanosecond
+    /// by [`Self::timestamp`].
+    pub fn build_clock_trace_packet(&mut self, time_unit: TimeUnit) -> Option<TracePacket> {
+        let unit_multiplier_ns = match time_unit {
+            TimeUnit::Ps => {
+                self.ticks_per_timestamp = 1000;
+                return None;
+            }
+            TimeUnit::Ns => return None,
+            TimeUnit::Us => 1000,
+        };
+        self.timestamp_clock_id = Some(SIM_CLOCK_ID);
+
+        // Both clocks start at zero
+        let clock_snapshot = ClockSnapshot {
+            clocks: vec![
+                clock_snapshot::Clock {
+                    clock_id: Some(clock_snapshot::clock::BuiltinClocks::Boottime as u32),
+                    timestamp: Some(0),
+                    ..Default::default()
+                },
+                clock_snapshot::Clock {
+                    clock_id: Some(SIM_CLOCK_ID),
+                    timestamp: Some(0),
+                    unit_multiplier_ns: Some(unit_multiplier_ns),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        Some(TracePacket {
+            optional_trusted_packet_sequence_id: Some(
+                trace_packet::OptionalTrustedPacketSequenceId::TrustedPacketSequenceId(
+                    self.trusted_packet_sequence_id,
+                ),
+            ),
+            data: Some(trace_packet::Data::ClockSnapshot(clock_snapshot)),
+            ..Default::default()
+        })
+    }
+
+    /// Return the timestamp of TracePackets at a time of `ticks` of the time
+    /// unit passed to [`Self::build_clock_trace_packet`].
+    #[must_use]
+    pub fn timestamp(&self, ticks: u64) -> u64 {
+        ticks / self.ticks_per_timestamp
+    }
+
+    /// Build the TracePacket that records a piece of metadata about the run.
+    ///
+    /// Perfetto shows the metadata in the `metadata` table of the trace,
+    /// with its name prefixed by `cr-`.
+    #[must_use]
+    pub fn build_metadata_trace_packet(&self, key: &str, value: &str) -> TracePacket {
+        let chrome_events = ChromeEventBundle {
+            metadata: vec![ChromeMetadata {
+                name: Some(key.to_string()),
+                value: Some(chrome_metadata::Value::StringValue(value.to_string())),
+            }],
+            ..Default::default()
+        };
+        TracePacket {
+            optional_trusted_packet_sequence_id: Some(
+                trace_packet::OptionalTrustedPacketSequenceId::TrustedPacketSequenceId(
+                    self.trusted_packet_sequence_id,
+                ),
+            ),
+            data: Some(trace_packet::Data::ChromeEvents(chrome_events)),
+            ..Default::default()
+        }
+    }
+
     fn build_incremental_counter_track_descriptor(
         &mut self,
         id: Id,
@@ -279,6 +415,174 @@ impl PerfettoTraceBuilder {
         self.build_track_event_trace_packet(current_time_ns, track_event)
     }
 
+    /// Build the TracePackets for a [crate::tracker::Track::span_begin] as a
+    /// SliceBegin event on the spans track of an entity, so that nested spans
+    /// are shown as nested slices.
+    ///
+    /// The first span of an entity is preceded by the TrackDescriptor of its
+    /// spans track.
+    #[must_use]
+    pub fn build_span_begin_trace_packets(
+        &mut self,
+        current_time_ns: u64,
+        id: Id,
+        name: &str,
+    ) -> Vec<TracePacket> {
+        let track = Id(id.0 | SPAN_TRACK_UUID_BIT);
+        let mut trace_packets = Vec::new();
+        if self.span_tracks.insert(track.0) {
+            let track_descriptor = self.build_track_descriptor(track, id, "spans");
+            trace_packets
+                .push(self.build_track_descriptor_trace_packet(current_time_ns, track_descriptor));
+        }
+
+        let track_event =
+            build_slice_track_event(track, Some(name), track_event::Type::SliceBegin, None);
+        trace_packets.push(self.build_track_event_trace_packet(current_time_ns, track_event));
+        trace_packets
+    }
+
+    /// Build a TracePacket containing the TrackEvent for a
+    /// [crate::tracker::Track::span_end] SliceEnd event, which ends the most
+    /// recent span of an entity.
+    #[must_use]
+    pub fn build_span_end_trace_packet(&self, current_time_ns: u64, id: Id) -> TracePacket {
+        let track = Id(id.0 | SPAN_TRACK_UUID_BIT);
+        let track_event = build_slice_track_event(track, None, track_event::Type::SliceEnd, None);
+
+        self.build_track_event_trace_packet(current_time_ns, track_event)
+    }
+
+    /// Build the TracePackets for a [crate::tracker::Track::register_access]
+    /// as an instant event with the details of the access as arguments.
+    ///
+    /// The first access through an entity is preceded by the TrackDescriptor
+    /// of the track that its register accesses are shown on.
+    #[must_use]
+    pub fn build_register_access_trace_packets(
+        &mut self,
+        current_time_ns: u64,
+        id: Id,
+        access: &RegisterAccess,
+    ) -> Vec<TracePacket> {
+        let track = Id(id.0 | REGISTER_TRACK_UUID_BIT);
+        let mut trace_packets = Vec::new();
+        if self.register_tracks.insert(track.0) {
+            let track_descriptor = self.build_track_descriptor(track, id, "registers");
+            trace_packets
+                .push(self.build_track_descriptor_trace_packet(current_time_ns, track_descriptor));
+        }
+
+        let mut track_event = build_named_track_event(track, &access.to_string());
+        track_event.set_type(track_event::Type::Instant);
+        track_event.debug_annotations = vec![
+            build_debug_annotation("write", debug_annotation::Value::BoolValue(access.is_write)),
+            build_debug_annotation("offset", debug_annotation::Value::UintValue(access.offset)),
+            build_debug_annotation(
+                "view",
+                debug_annotation::Value::StringValue(access.view.clone()),
+            ),
+            build_debug_annotation(
+                "old_value",
+                debug_annotation::Value::UintValue(access.old_value),
+            ),
+            build_debug_annotation(
+                "new_value",
+                debug_annotation::Value::UintValue(access.new_value),
+            ),
+        ];
+        trace_packets.push(self.build_track_event_trace_packet(current_time_ns, track_event));
+        trace_packets
+    }
+
+    /// Build the TracePackets for a [crate::tracker::Track::counter] or
+    /// [crate::tracker::Track::histogram] as a value on a counter track with
+    /// the name of the metric.
+    ///
+    /// The first value of a metric of an entity is preceded by the
+    /// TrackDescriptor of its track.
+    #[must_use]
+    pub fn build_metric_trace_packets(
+        &mut self,
+        current_time_ns: u64,
+        id: Id,
+        name: &str,
+        value: f64,
+    ) -> Vec<TracePacket> {
+        let mut trace_packets = Vec::new();
+        let existing = self
+            .metric_tracks
+            .get(&id.0)
+            .and_then(|tracks| tracks.get(name))
+            .copied();
+        let track = match existing {
+            Some(track) => Id(track),
+            None => {
+                let track = Id(METRIC_TRACK_UUID_BIT | self.num_metric_tracks);
+                self.num_metric_tracks += 1;
+                self.metric_tracks
+                    .entry(id.0)
+                    .or_default()
+                    .insert(name.to_string(), track.0);
+                let track_descriptor =
+                    self.build_absolute_counter_track_descriptor(track, id, name);
+                trace_packets.push(
+                    self.build_track_descriptor_trace_packet(current_time_ns, track_descriptor),
+                );
+                track
+            }
+        };
+
+        let mut track_event = build_named_track_event(track, name);
+        track_event.set_type(track_event::Type::Counter);
+        track_event.counter_value_field =
+            Some(track_event::CounterValueField::DoubleCounterValue(value));
+        trace_packets.push(self.build_track_event_trace_packet(current_time_ns, track_event));
+        trace_packets
+    }
+
+    /// Build the TracePackets for an instant event on the objects track of
+    /// entity `id` that is part of the flow following `object`.
+    ///
+    /// Perfetto draws an arrow between consecutive events of a flow, so
+    /// calling this for the creation of an object and each time it enters or
+    /// exits an entity shows its journey through the model. If `end` is set
+    /// the flow is terminated, and nothing is built if the object has no flow.
+    ///
+    /// The first event on the objects track of an entity is preceded by the
+    /// TrackDescriptor of the track.
+    #[must_use]
+    pub fn build_object_flow_trace_packets(
+        &mut self,
+        current_time_ns: u64,
+        id: Id,
+        object: Id,
+        end: bool,
+    ) -> Vec<TracePacket> {
+        let mut trace_packets = Vec::new();
+        if end && !self.open_flows.remove(&object.0) {
+            return trace_packets;
+        }
+
+        let track = Id(id.0 | OBJECT_TRACK_UUID_BIT);
+        if self.object_tracks.insert(track.0) {
+            let track_descriptor = self.build_track_descriptor(track, id, "objects");
+            trace_packets
+                .push(self.build_track_descriptor_trace_packet(current_time_ns, track_descriptor));
+        }
+
+        let mut track_event = build_named_track_event(track, &self.id_to_name(id, object));
+        track_event.set_type(track_event::Type::Instant);
+        if end {
+            track_event.terminating_flow_ids = vec![object.0];
+        } else {
+            self.open_flows.insert(object.0);
+            track_event.flow_ids = vec![object.0];
+        }
+        trace_packets.push(self.build_track_event_trace_packet(current_time_ns, track_event));
+        trace_packets
+    }
+
     fn build_track_event_trace_packet(
         &self,
         current_time_ns: u64,
@@ -293,6 +597,7 @@ impl PerfettoTraceBuilder {
     fn build_trace_packet(&self, current_time_ns: u64) -> TracePacket {
         TracePacket {
             timestamp: Some(current_time_ns),
+            timestamp_clock_id: self.timestamp_clock_id,
             optional_trusted_packet_sequence_id: Some(
                 trace_packet::OptionalTrustedPacketSequenceId::TrustedPacketSequenceId(
                     self.trusted_packet_sequence_id,
@@ -304,11 +609,72 @@ impl PerfettoTraceBuilder {
 
     /// Build a Trace message containing the passed TracePackets and serialise
     /// it to unsigned bytes.
+    ///
+    /// The names used by the TracePackets are interned, so the bytes must be
+    /// written after those of any earlier call.
     #[must_use]
-    pub fn build_trace_to_bytes(&self, trace_packets: Vec<TracePacket>) -> Vec<u8> {
+    pub fn build_trace_to_bytes(&mut self, mut trace_packets: Vec<TracePacket>) -> Vec<u8> {
+        if self.intern_names {
+            for trace_packet in &mut trace_packets {
+                self.intern_names(trace_packet);
+            }
+        }
         PerfettoTraceBuilder::build_trace(trace_packets).encode_to_vec()
     }
 
+    /// Replace the names of the event and debug annotations in a TracePacket
+    /// with interned IDs. Names that have not been seen before are added to
+    /// the InternedData of the TracePacket.
+    fn intern_names(&mut self, trace_packet: &mut TracePacket) {
+        let Some(Data::TrackEvent(track_event)) = &mut trace_packet.data else {
+            return;
+        };
+
+        let mut interned_data = InternedData::default();
+        let mut needs_incremental_state = false;
+        if let Some(track_event::NameField::Name(name)) = &track_event.name_field {
+            let (iid, is_new) = intern(&mut self.event_name_iids, name);
+            if is_new {
+                interned_data.event_names.push(EventName {
+                    iid: Some(iid),
+                    name: Some(name.clone()),
+                });
+            }
+            track_event.name_field = Some(track_event::NameField::NameIid(iid));
+            needs_incremental_state = true;
+        }
+        for debug_annotation in &mut track_event.debug_annotations {
+            if let Some(debug_annotation::NameField::Name(name)) = &debug_annotation.name_field {
+                let (iid, is_new) = intern(&mut self.debug_annotation_name_iids, name);
+                if is_new {
+                    interned_data
+                        .debug_annotation_names
+                        .push(DebugAnnotationName {
+                            iid: Some(iid),
+                            name: Some(name.clone()),
+                        });
+                }
+                debug_annotation.name_field = Some(debug_annotation::NameField::NameIid(iid));
+                needs_incremental_state = true;
+            }
+        }
+        if !needs_incremental_state {
+            return;
+        }
+
+        // The first TracePacket that uses interned names starts the state that
+        // later TracePackets refer to
+        let mut sequence_flags = trace_packet::SequenceFlags::SeqNeedsIncrementalState as u32;
+        if !self.incremental_state_cleared {
+            self.incremental_state_cleared = true;
+            sequence_flags |= trace_packet::SequenceFlags::SeqIncrementalStateCleared as u32;
+        }
+        trace_packet.sequence_flags = Some(sequence_flags);
+        if interned_data != InternedData::default() {
+            trace_packet.interned_data = Some(interned_data);
+        }
+    }
+
     fn build_trace(trace_packets: Vec<TracePacket>) -> Trace {
         Trace {
             packet: trace_packets,
@@ -338,6 +704,26 @@ fn build_slice_track_event(
     track_event
 }
 
+/// Return the interned ID of `name`, and whether it has just been interned.
+fn intern(iids: &mut HashMap<String, u64>, name: &str) -> (u64, bool) {
+    if let Some(iid) = iids.get(name) {
+        return (*iid, false);
+    }
+
+    // Interned IDs start from 1
+    let iid = iids.len() as u64 + 1;
+    iids.insert(name.to_string(), iid);
+    (iid, true)
+}
+
+fn build_debug_annotation(name: &str, value: debug_annotation::Value) -> DebugAnnotation {
+    DebugAnnotation {
+        name_field: Some(debug_annotation::NameField::Name(name.to_string())),
+        value: Some(value),
+        ..Default::default()
+    }
+}
+
 fn build_named_track_event(id: Id, name: &str) -> TrackEvent {
     TrackEvent {
         track_uuid: Some(id.0),
@@ -352,6 +738,49 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn picosecond_timestamps_are_written_in_nanoseconds() {
+        let mut builder = PerfettoTraceBuilder::new();
+        assert!(builder.build_clock_trace_packet(TimeUnit::Ps).is_none());
+        assert_eq!(builder.timestamp(4350), 4);
+
+        let packet = builder.build_activity_end_trace_packet(builder.timestamp(4350), Id(11));
+        assert_eq!(packet.timestamp, Some(4));
+        assert_eq!(packet.timestamp_clock_id, None);
+    }
+
+    #[test]
+    fn microsecond_timestamps_use_a_clock_in_microseconds() {
+        let mut builder = PerfettoTraceBuilder::new();
+        let Some(Data::ClockSnapshot(clock_snapshot)) = builder
+            .build_clock_trace_packet(TimeUnit::Us)
+            .and_then(|packet| packet.data)
+        else {
+            panic!("expected clock snapshot");
+        };
+        assert_eq!(clock_snapshot.clocks[1].unit_multiplier_ns, Some(1000));
+        assert_eq!(builder.timestamp(3), 3);
+
+        let packet = builder.build_activity_end_trace_packet(3, Id(11));
+        assert_eq!(packet.timestamp_clock_id, Some(SIM_CLOCK_ID));
+    }
+
+    #[test]
+    fn metadata_packet_is_chrome_metadata() {
+        let builder = PerfettoTraceBuilder::new();
+        let packet = builder.build_metadata_trace_packet("seed", "42");
+
+        let Some(Data::ChromeEvents(chrome_events)) = packet.data else {
+            panic!("expected chrome events");
+        };
+        assert_eq!(chrome_events.metadata.len(), 1);
+        assert_eq!(chrome_events.metadata[0].name.as_deref(), Some("seed"));
+        assert_eq!(
+            chrome_events.metadata[0].value,
+            Some(chrome_metadata::Value::StringValue("42".to_string()))
+        );
+    }
+
     #[test]
     fn activity_packets_are_perfetto_slices() {
         let mut builder = PerfettoTraceBuilder::new();
@@ -394,4 +823,189 @@ mod tests {
         assert_eq!(end.track_uuid, Some(11));
         assert_eq!(end.r#type, Some(track_event::Type::SliceEnd as i32));
     }
+
+    #[test]
+    fn spans_are_perfetto_slices() {
+        let mut builder = PerfettoTraceBuilder::new();
+        let first = builder.build_span_begin_trace_packets(42, Id(11), "decode");
+        let nested = builder.build_span_begin_trace_packets(43, Id(11), "lookup");
+        let end = builder.build_span_end_trace_packet(44, Id(11));
+
+        // Only the first span creates the track
+        assert_eq!(first.len(), 2);
+        assert_eq!(nested.len(), 1);
+
+        let Some(Data::TrackDescriptor(descriptor)) = &first[0].data else {
+            panic!("expected span track descriptor");
+        };
+        assert_eq!(descriptor.uuid, Some(11 | SPAN_TRACK_UUID_BIT));
+        assert_eq!(descriptor.parent_uuid, Some(11));
+        assert!(descriptor.counter.is_none());
+
+        let Some(Data::TrackEvent(begin)) = &nested[0].data else {
+            panic!("expected span begin track event");
+        };
+        assert_eq!(begin.track_uuid, Some(11 | SPAN_TRACK_UUID_BIT));
+        assert_eq!(begin.r#type, Some(track_event::Type::SliceBegin as i32));
+        assert_eq!(
+            begin.name_field,
+            Some(track_event::NameField::Name("lookup".to_string()))
+        );
+
+        let Some(Data::TrackEvent(end)) = end.data else {
+            panic!("expected span end track event");
+        };
+        assert_eq!(end.track_uuid, Some(11 | SPAN_TRACK_UUID_BIT));
+        assert_eq!(end.r#type, Some(track_event::Type::SliceEnd as i32));
+    }
+
+    #[test]
+    fn register_accesses_are_perfetto_instants() {
+        let mut builder = PerfettoTraceBuilder::new();
+        let access = RegisterAccess::write(0x4, "ctrl", 0x81, 0xf1);
+        let first = builder.build_register_access_trace_packets(42, Id(11), &access);
+        let second = builder.build_register_access_trace_packets(43, Id(11), &access);
+
+        // Only the first access creates the track
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+
+        let Some(Data::TrackDescriptor(descriptor)) = &first[0].data else {
+            panic!("expected register track descriptor");
+        };
+        assert_eq!(descriptor.uuid, Some(11 | REGISTER_TRACK_UUID_BIT));
+        assert_eq!(descriptor.parent_uuid, Some(11));
+
+        let Some(Data::TrackEvent(event)) = &first[1].data else {
+            panic!("expected register access track event");
+        };
+        assert_eq!(event.track_uuid, Some(11 | REGISTER_TRACK_UUID_BIT));
+        assert_eq!(event.r#type, Some(track_event::Type::Instant as i32));
+        assert_eq!(
+            event.name_field,
+            Some(track_event::NameField::Name(
+                "register write ctrl@0x4: 0x81 -> 0xf1".to_string()
+            ))
+        );
+        assert_eq!(event.debug_annotations.len(), 5);
+        assert_eq!(
+            event.debug_annotations[4].value,
+            Some(debug_annotation::Value::UintValue(0xf1))
+        );
+    }
+
+    #[test]
+    fn metrics_are_perfetto_counter_tracks() {
+        let mut builder = PerfettoTraceBuilder::new();
+        let first = builder.build_metric_trace_packets(42, Id(11), "retries", 1.0);
+        let second = builder.build_metric_trace_packets(43, Id(11), "retries", 2.0);
+        let other = builder.build_metric_trace_packets(44, Id(11), "latency_ns", 7.5);
+
+        // Only the first value of each metric creates its track
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 1);
+        assert_eq!(other.len(), 2);
+
+        let Some(Data::TrackDescriptor(descriptor)) = &first[0].data else {
+            panic!("expected metric track descriptor");
+        };
+        assert_eq!(descriptor.uuid, Some(METRIC_TRACK_UUID_BIT));
+        assert_eq!(descriptor.parent_uuid, Some(11));
+        assert!(descriptor.counter.is_some());
+
+        let Some(Data::TrackEvent(event)) = &second[0].data else {
+            panic!("expected metric track event");
+        };
+        assert_eq!(event.track_uuid, Some(METRIC_TRACK_UUID_BIT));
+        assert_eq!(event.r#type, Some(track_event::Type::Counter as i32));
+        assert_eq!(
+            event.counter_value_field,
+            Some(track_event::CounterValueField::DoubleCounterValue(2.0))
+        );
+
+        let Some(Data::TrackEvent(event)) = &other[1].data else {
+            panic!("expected metric track event");
+        };
+        assert_eq!(event.track_uuid, Some(METRIC_TRACK_UUID_BIT | 1));
+    }
+
+    #[test]
+    fn names_are_interned_when_serialised() {
+        let mut builder = PerfettoTraceBuilder::new();
+        let access = RegisterAccess::write(0x4, "ctrl", 0x81, 0xf1);
+        let mut trace_packets = builder.build_register_access_trace_packets(42, Id(11), &access);
+        trace_packets.extend(builder.build_register_access_trace_packets(43, Id(11), &access));
+        let bytes = builder.build_trace_to_bytes(trace_packets);
+        let trace = Trace::decode(bytes.as_slice()).unwrap();
+
+        // The track descriptor has no interned names
+        assert_eq!(trace.packet.len(), 3);
+        assert!(trace.packet[0].sequence_flags.is_none());
+
+        let first = &trace.packet[1];
+        assert_eq!(first.sequence_flags, Some(3));
+        let interned_data = first.interned_data.as_ref().unwrap();
+        assert_eq!(
+            interned_data.event_names,
+            [EventName {
+                iid: Some(1),
+                name: Some("register write ctrl@0x4: 0x81 -> 0xf1".to_string()),
+            }]
+        );
+        assert_eq!(interned_data.debug_annotation_names.len(), 5);
+
+        // Later uses of the same names only refer to them
+        let second = &trace.packet[2];
+        assert_eq!(second.sequence_flags, Some(2));
+        assert!(second.interned_data.is_none());
+        let Some(Data::TrackEvent(event)) = &second.data else {
+            panic!("expected register access track event");
+        };
+        assert_eq!(event.name_field, Some(track_event::NameField::NameIid(1)));
+        assert_eq!(
+            event.debug_annotations[4].name_field,
+            Some(debug_annotation::NameField::NameIid(5))
+        );
+    }
+
+    #[test]
+    fn objects_are_followed_by_perfetto_flows() {
+        let mut builder = PerfettoTraceBuilder::new();
+        let _ = builder.build_enter_exit_track_descriptor_trace_packet(0, Id(20), Id(10), "frame");
+        let created = builder.build_object_flow_trace_packets(42, Id(10), Id(20), false);
+        let entered = builder.build_object_flow_trace_packets(43, Id(11), Id(20), false);
+        let exited = builder.build_object_flow_trace_packets(44, Id(11), Id(20), false);
+        let destroyed = builder.build_object_flow_trace_packets(45, Id(11), Id(20), true);
+        let destroyed_again = builder.build_object_flow_trace_packets(46, Id(11), Id(20), true);
+
+        // Only the first event on each entity creates its objects track
+        assert_eq!(created.len(), 2);
+        assert_eq!(entered.len(), 2);
+        assert_eq!(exited.len(), 1);
+        assert_eq!(destroyed.len(), 1);
+        assert!(destroyed_again.is_empty());
+
+        let Some(Data::TrackDescriptor(descriptor)) = &entered[0].data else {
+            panic!("expected objects track descriptor");
+        };
+        assert_eq!(descriptor.uuid, Some(11 | OBJECT_TRACK_UUID_BIT));
+        assert_eq!(descriptor.parent_uuid, Some(11));
+
+        let Some(Data::TrackEvent(event)) = &entered[1].data else {
+            panic!("expected object flow track event");
+        };
+        assert_eq!(event.track_uuid, Some(11 | OBJECT_TRACK_UUID_BIT));
+        assert_eq!(event.r#type, Some(track_event::Type::Instant as i32));
+        assert_eq!(
+            event.name_field,
+            Some(track_event::NameField::Name("frame".to_string()))
+        );
+        assert_eq!(event.flow_ids, [20]);
+
+        let Some(Data::TrackEvent(event)) = &destroyed[0].data else {
+            panic!("expected object flow track event");
+        };
+        assert!(event.flow_ids.is_empty());
+        assert_eq!(event.terminating_flow_ids, [20]);
+    }
 }
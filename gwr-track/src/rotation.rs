@@ -0,0 +1,156 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Size-based rotation of trace files.
+//!
+//! A [`RotatingWriter`] writes to `trace.bin.0` until it holds the configured
+//! number of bytes, then moves on to `trace.bin.1` and so on. Only the most
+//! recent files are kept, so a long simulation that is left unattended does
+//! not fill the disk.
+//!
+//! Files are only changed between writes, and each tracker writes every event
+//! with a single write, so no event is split between files. Each file starts
+//! with the [`TraceHeader`] of the trace, such as its unit of time, metadata
+//! and the creation of the entities so far, so that any file can be read on
+//! its own. Groups and objects come and go as the simulation runs, so they are
+//! only named in the file that was being written when they were created.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// A function that opens the writer for a single file.
+pub type OpenFile = Box<dyn FnMut(&Path) -> io::Result<Box<dyn Write + Send>> + Send>;
+
+/// The events that describe a whole trace rather than a point in it, which
+/// are written again at the start of each rotated file.
+///
+/// A tracker adds its events to the header as it writes them, so a clone of
+/// the header shared with a [`RotatingWriter`] always holds the latest events.
+#[derive(Clone, Default)]
+pub struct TraceHeader(Arc<Mutex<Vec<u8>>>);
+
+impl TraceHeader {
+    /// Add the bytes of an event, as written by the tracker, to the header.
+    pub fn extend(&self, bytes: &[u8]) {
+        self.0.lock().unwrap().extend_from_slice(bytes);
+    }
+
+    /// Return the bytes of all the events in the header.
+    #[must_use]
+    pub fn bytes(&self) -> Vec<u8> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+/// A writer that moves on to a new file whenever the current file is full.
+pub struct RotatingWriter {
+    path: PathBuf,
+
+    /// The number of bytes written to a file before moving to the next.
+    max_bytes: u64,
+
+    /// The number of files kept, including the one being written.
+    max_files: usize,
+
+    open_file: OpenFile,
+
+    /// The writer of the current file.
+    writer: Box<dyn Write + Send>,
+
+    /// The index of the current file.
+    index: usize,
+
+    /// The number of bytes written to the current file.
+    bytes_written: u64,
+
+    /// The events written at the start of each new file.
+    header: TraceHeader,
+}
+
+impl RotatingWriter {
+    /// Create a new [`RotatingWriter`] that writes up to `max_bytes` to each
+    /// of the files `<path>.0`, `<path>.1`, ..., keeping only the last
+    /// `max_files` of them.
+    ///
+    /// Each file is opened by `open_file`, so that it can, for example, be
+    /// buffered or compressed. The number of bytes is counted before any
+    /// compression.
+    pub fn new(
+        path: impl Into<PathBuf>,
+        max_bytes: u64,
+        max_files: usize,
+        mut open_file: OpenFile,
+    ) -> io::Result<Self> {
+        let path = path.into();
+        let writer = open_file(&rotated_path(&path, 0))?;
+        Ok(Self {
+            path,
+            max_bytes: max_bytes.max(1),
+            max_files: max_files.max(1),
+            open_file,
+            writer,
+            index: 0,
+            bytes_written: 0,
+            header: TraceHeader::default(),
+        })
+    }
+
+    /// Start each new file with the events of `header`.
+    ///
+    /// The first file is not changed, as the tracker writes the events of the
+    /// header to it as they happen. The header is not counted towards the
+    /// size of a file.
+    #[must_use]
+    pub fn with_header(mut self, header: TraceHeader) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Return the path of the file currently being written.
+    #[must_use]
+    pub fn current_path(&self) -> PathBuf {
+        rotated_path(&self.path, self.index)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        self.index += 1;
+        // Dropping the previous writer completes the file
+        self.writer = (self.open_file)(&self.current_path())?;
+        self.writer.write_all(&self.header.bytes())?;
+        self.bytes_written = 0;
+
+        if let Some(expired) = self.index.checked_sub(self.max_files) {
+            match fs::remove_file(rotated_path(&self.path, expired)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Write for RotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.bytes_written >= self.max_bytes {
+            self.rotate()?;
+        }
+        // The whole buffer goes to one file so that no event is split
+        self.writer.write_all(buf)?;
+        self.bytes_written += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Return the path of file `index` of the output written to `path`.
+#[must_use]
+pub fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{index}"));
+    PathBuf::from(rotated)
+}
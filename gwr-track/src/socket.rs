@@ -0,0 +1,211 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! Streaming of trace events over a socket.
+//!
+//! A [`SocketWriter`] connects to a viewer listening on a TCP or Unix socket
+//! and sends it the output of a tracker as the simulation runs, so the viewer
+//! does not have to wait for a trace file to be closed. The output is either
+//! the Cap'n Proto messages of the binary trace, each of which starts with the
+//! table of its segment lengths, or JSON Lines, so the viewer can always tell
+//! where one event ends and the next begins.
+//!
+//! The output is sent whenever a chunk has been gathered, or it has been held
+//! for longer than [`FLUSH_INTERVAL`], so a slow simulation still appears
+//! promptly. If the viewer goes away the rest of the output is dropped and the
+//! simulation carries on.
+//!
+//! # Examples
+//!
+//! Read the events sent by a simulation run with `--socket 127.0.0.1:9000`.
+//! ```no_run
+//! # use std::error::Error;
+//! use std::io::BufReader;
+//! use std::net::TcpListener;
+//!
+//! use gwr_track::trace_visitor::TraceEvents;
+//!
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let listener = TcpListener::bind("127.0.0.1:9000")?;
+//! let (stream, _) = listener.accept()?;
+//! for event in TraceEvents::new(BufReader::new(stream)) {
+//!     println!("{event:?}");
+//! }
+//! #
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::io::{self, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The number of bytes gathered before they are sent.
+const CHUNK_BYTES: usize = 64 * 1024;
+
+/// The longest time that output is held before it is sent.
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The format of the events sent over a socket.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketFormat {
+    /// Cap'n Proto messages, as written to the binary trace.
+    #[default]
+    Capnp,
+
+    /// One JSON object per line, as written to the JSON Lines trace.
+    Json,
+}
+
+/// The address of a viewer to stream events to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SocketAddress {
+    /// A TCP socket, as `<host>:<port>`.
+    Tcp(String),
+
+    /// A Unix domain socket at a path.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for SocketAddress {
+    type Err = String;
+
+    /// Parse `unix:<path>` as a Unix domain socket, and `tcp:<host>:<port>` or
+    /// `<host>:<port>` as a TCP socket.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            #[cfg(unix)]
+            return Ok(Self::Unix(PathBuf::from(path)));
+            #[cfg(not(unix))]
+            return Err(format!(
+                "Unix domain sockets are not supported on this platform: '{path}'"
+            ));
+        }
+        let address = s.strip_prefix("tcp:").unwrap_or(s);
+        if !address.contains(':') {
+            return Err(format!(
+                "Unable to parse socket address '{s}', expected <host>:<port> or unix:<path>"
+            ));
+        }
+        Ok(Self::Tcp(address.to_string()))
+    }
+}
+
+impl fmt::Display for SocketAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "tcp:{address}"),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A writer that streams its output to a viewer over a socket.
+pub struct SocketWriter {
+    address: SocketAddress,
+
+    /// The connection to the viewer, until it goes away.
+    stream: Option<Box<dyn Write + Send>>,
+
+    buffer: Vec<u8>,
+
+    /// When the output was last sent.
+    last_sent: Instant,
+
+    /// The number of bytes dropped after the viewer went away.
+    dropped_bytes: u64,
+}
+
+impl SocketWriter {
+    /// Create a new [`SocketWriter`] connected to the viewer at `address`.
+    pub fn connect(address: &SocketAddress) -> io::Result<Self> {
+        let stream: Box<dyn Write + Send> = match address {
+            SocketAddress::Tcp(host_port) => {
+                let stream = TcpStream::connect(host_port)?;
+                // Events are already gathered into chunks
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+            #[cfg(unix)]
+            SocketAddress::Unix(path) => Box::new(UnixStream::connect(path)?),
+        };
+        Ok(Self {
+            address: address.clone(),
+            stream: Some(stream),
+            buffer: Vec::with_capacity(CHUNK_BYTES),
+            last_sent: Instant::now(),
+            dropped_bytes: 0,
+        })
+    }
+
+    /// Return whether the viewer is still connected.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Return the number of bytes dropped because the viewer went away.
+    #[must_use]
+    pub fn dropped_bytes(&self) -> u64 {
+        self.dropped_bytes
+    }
+
+    /// Send the gathered output to the viewer.
+    fn send(&mut self) {
+        self.last_sent = Instant::now();
+        let Some(stream) = &mut self.stream else {
+            return;
+        };
+        if let Err(e) = stream.write_all(&self.buffer).and_then(|()| stream.flush()) {
+            eprintln!(
+                "WARNING: stopped streaming trace to {} as it went away: {e}",
+                self.address
+            );
+            self.stream = None;
+            self.dropped_bytes += self.buffer.len() as u64;
+        }
+        self.buffer.clear();
+    }
+}
+
+impl Write for SocketWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.stream.is_none() {
+            self.dropped_bytes += buf.len() as u64;
+            return Ok(buf.len());
+        }
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= CHUNK_BYTES || self.last_sent.elapsed() >= FLUSH_INTERVAL {
+            self.send();
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send();
+        Ok(())
+    }
+}
+
+impl Drop for SocketWriter {
+    fn drop(&mut self) {
+        self.send();
+        if self.dropped_bytes > 0 {
+            eprintln!(
+                "WARNING: dropped {} bytes of trace as {} went away",
+                self.dropped_bytes, self.address
+            );
+        }
+    }
+}
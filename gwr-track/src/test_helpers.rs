@@ -11,14 +11,16 @@
 
 use std::cell::RefCell;
 use std::fs;
-use std::io::BufWriter;
+use std::io::{self, BufWriter, Write};
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
 use regex::Regex;
 
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::TimeUnit;
 use crate::tracker::{CapnProtoTracker, EntityManager};
 use crate::{Id, Track, Tracker, Writer};
 
@@ -101,6 +103,14 @@ impl Track for TestTracker {
         self.add_event(format!("{activity}: activity end"));
     }
 
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        self.add_event(format!("{id}: span {span} begin {name}"));
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        self.add_event(format!("{id}: span {span} end"));
+    }
+
     fn add_to_group(&self, activity: Id, group_id: Id) {
         self.add_event(format!("{activity}: added to group {group_id}"));
     }
@@ -146,6 +156,18 @@ impl Track for TestTracker {
         ));
     }
 
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        self.add_event(format!("{id}: {access}"));
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        self.add_event(format!("{id}: counter {name} {delta}"));
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        self.add_event(format!("{id}: histogram {name} {value}"));
+    }
+
     fn destroy(&self, destroyed_by: Id, id: Id) {
         self.add_event(format!("{destroyed_by}: destroyed {id}"));
     }
@@ -158,7 +180,8 @@ impl Track for TestTracker {
         self.add_event(format!("{id}:{level}: {msg}"));
     }
 
-    fn time(&self, set_by: Id, time_ns: f64) {
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        let time_ns = time_unit.convert(ticks, TimeUnit::Ns);
         self.add_event(format!("{set_by}: set time {time_ns:.1}ns"));
     }
 
@@ -283,3 +306,39 @@ pub fn create_tracker(full_filepath: &str) -> Tracker {
     let tracker: Tracker = Rc::new(CapnProtoTracker::new(entity_manger, bin_writer));
     tracker
 }
+
+/// A writer whose output can be read while a tracker still owns it, even
+/// from another thread.
+#[derive(Clone, Default)]
+pub struct SharedVec(Arc<Mutex<Vec<u8>>>);
+
+impl SharedVec {
+    /// Returns the bytes written so far.
+    #[must_use]
+    pub fn bytes(&self) -> Vec<u8> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Returns the text written so far.
+    #[must_use]
+    pub fn text(&self) -> String {
+        String::from_utf8(self.bytes()).unwrap()
+    }
+
+    /// Returns the lines of text written so far.
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        self.text().lines().map(str::to_string).collect()
+    }
+}
+
+impl Write for SharedVec {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -2,13 +2,27 @@
 
 //! This module provides helper functions for dealing with Cap'n Proto binary
 //! data.
+//!
+//! Traces can be processed by a [`TraceVisitor`] with [`process_capnp`], or
+//! read as an iterator of [`TraceEvent`]s with [`TraceEvents`]. Both read one
+//! event at a time, so traces larger than memory can be processed.
+//! [`ParallelTraceEvents`] and [`process_capnp_parallel`] decode the events on
+//! a number of threads.
 
+use std::collections::HashMap;
 use std::io::BufRead;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::{mem, panic, vec};
 
+use capnp::message::{self, ReaderOptions};
+use capnp::serialize::OwnedSegments;
 use capnp::serialize_packed;
 
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::gwr_track_capnp::log::LogLevel;
+use crate::tracker::types::{CallSite, TimeUnit};
 use crate::{Id, gwr_track_capnp};
 
 /// The `TraceVisitor` trait is the interface that allows a user to see all the
@@ -31,6 +45,22 @@ pub trait TraceVisitor {
         let _ = message;
     }
 
+    /// A log event that records where the message was written from.
+    ///
+    /// By default the `call_site` is dropped and [`TraceVisitor::log`] is
+    /// called.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The originator of this event.
+    /// * `level` - The logging level of the message.
+    /// * `call_site` - Where the message was written from.
+    /// * `message` - The string to emit with this event.
+    fn log_at(&mut self, id: Id, level: log::Level, call_site: &CallSite, message: &str) {
+        let _ = call_site;
+        self.log(id, level, message);
+    }
+
     /// The creation of an entity.
     ///
     /// # Arguments
@@ -214,6 +244,32 @@ pub trait TraceVisitor {
         let _ = activity;
     }
 
+    /// A named span of time of the specified ID has begun.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The originator of this event.
+    /// * `span` - The span identity.
+    /// * `name` - The span name.
+    fn span_begin(&mut self, id: Id, span: Id, name: &str) {
+        // Remove the unused variable warnings
+        let _ = id;
+        let _ = span;
+        let _ = name;
+    }
+
+    /// The specified span of time has ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The originator of this event.
+    /// * `span` - The span identity.
+    fn span_end(&mut self, id: Id, span: Id) {
+        // Remove the unused variable warnings
+        let _ = id;
+        let _ = span;
+    }
+
     /// A capacity has been set for the specified ID.
     ///
     /// # Arguments
@@ -226,21 +282,357 @@ pub trait TraceVisitor {
         let _ = capacity;
     }
 
+    /// A register has been read or written through the specified ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The originator of this event.
+    /// * `access` - The register accessed and its value before and after.
+    fn register_access(&mut self, id: Id, access: RegisterAccess) {
+        // Remove the unused variable warnings
+        let _ = id;
+        let _ = access;
+    }
+
+    /// A named counter of the specified ID has changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The originator of this event.
+    /// * `name` - The name of the counter.
+    /// * `delta` - The change in the counter.
+    fn counter(&mut self, id: Id, name: &str, delta: f64) {
+        // Remove the unused variable warnings
+        let _ = id;
+        let _ = name;
+        let _ = delta;
+    }
+
+    /// A sample of a named histogram of the specified ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The originator of this event.
+    /// * `name` - The name of the histogram.
+    /// * `value` - The value of the sample.
+    fn histogram(&mut self, id: Id, name: &str, value: f64) {
+        // Remove the unused variable warnings
+        let _ = id;
+        let _ = name;
+        let _ = value;
+    }
+
+    /// A piece of metadata about the run, such as the command line, given at
+    /// the start of the trace.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The name of the metadata.
+    /// * `value` - The value of the metadata.
+    fn metadata(&mut self, key: &str, value: &str) {
+        // Remove the unused variable warnings
+        let _ = key;
+        let _ = value;
+    }
+
+    /// The unit that time was written in, given at the start of traces that
+    /// were not written in nanoseconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_unit` - The unit of time in the trace.
+    fn time_unit(&mut self, time_unit: TimeUnit) {
+        // Remove the unused variable warnings
+        let _ = time_unit;
+    }
+
     /// Advance simulation time.
     ///
     /// # Arguments
     ///
     /// * `id` - The originator of this event.
-    /// * `time_ns` - The new simulation time in `ns`.
-    fn time(&mut self, id: Id, time_ns: f64) {
+    /// * `ticks` - The new simulation time, as a whole number of `time_unit`.
+    /// * `time_unit` - The unit of `ticks`, which is the unit the trace was
+    ///   written in unless the time had a fraction of that unit.
+    fn time(&mut self, id: Id, ticks: u64, time_unit: TimeUnit) {
         // Remove the unused variable warnings
         let _ = id;
-        let _ = time_ns;
+        let _ = ticks;
+        let _ = time_unit;
+    }
+}
+
+/// A named span of time of an entity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    /// The entity the span is of.
+    pub id: Id,
+
+    /// The span identity.
+    pub span: Id,
+
+    /// The span name.
+    pub name: String,
+
+    /// The simulation time in `ns` when the span began.
+    pub begin_ns: f64,
+
+    /// The simulation time in `ns` when the span ended.
+    pub end_ns: f64,
+}
+
+impl Span {
+    /// Returns the length of the span in `ns`.
+    #[must_use]
+    pub fn duration_ns(&self) -> f64 {
+        self.end_ns - self.begin_ns
+    }
+}
+
+/// A [`TraceVisitor`] that pairs the beginning and end of each span to give its
+/// duration.
+#[derive(Default)]
+pub struct SpanCollector {
+    time_ns: f64,
+    open_spans: HashMap<Id, Span>,
+
+    /// The spans that have ended, in the order that they ended.
+    pub spans: Vec<Span>,
+}
+
+impl TraceVisitor for SpanCollector {
+    fn span_begin(&mut self, id: Id, span: Id, name: &str) {
+        self.open_spans.insert(
+            span,
+            Span {
+                id,
+                span,
+                name: name.to_owned(),
+                begin_ns: self.time_ns,
+                end_ns: self.time_ns,
+            },
+        );
+    }
+
+    fn span_end(&mut self, _id: Id, span: Id) {
+        if let Some(mut span) = self.open_spans.remove(&span) {
+            span.end_ns = self.time_ns;
+            self.spans.push(span);
+        }
+    }
+
+    fn time(&mut self, _id: Id, ticks: u64, time_unit: TimeUnit) {
+        self.time_ns = time_unit.convert(ticks, TimeUnit::Ns);
+    }
+}
+
+/// Where a log message in a trace was written from.
+///
+/// This is the owned form of a [`CallSite`] so that it can be held by a
+/// [`TraceEvent`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceCallSite {
+    /// The path of the module that the message was written from.
+    pub module_path: String,
+
+    /// The source file that the message was written from.
+    pub file: String,
+
+    /// The line of the source file.
+    pub line: u32,
+}
+
+impl TraceCallSite {
+    /// Borrow this as a [`CallSite`].
+    #[must_use]
+    pub fn as_call_site(&self) -> CallSite<'_> {
+        CallSite {
+            module_path: &self.module_path,
+            file: &self.file,
+            line: self.line,
+        }
+    }
+}
+
+/// A single event read from a trace.
+///
+/// There is one variant for each [`TraceVisitor`] function, with the same
+/// arguments.
+#[derive(Clone, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum TraceEvent {
+    /// See [`TraceVisitor::log`] and [`TraceVisitor::log_at`].
+    Log {
+        id: Id,
+        level: log::Level,
+        call_site: Option<TraceCallSite>,
+        message: String,
+    },
+    /// See [`TraceVisitor::create_entity`].
+    CreateEntity {
+        created_by: Id,
+        id: Id,
+        name: String,
+    },
+    /// See [`TraceVisitor::create_monitor`].
+    CreateMonitor {
+        created_by: Id,
+        id: Id,
+        name: String,
+    },
+    /// See [`TraceVisitor::create_lane`].
+    CreateLane {
+        created_by: Id,
+        id: Id,
+        name: String,
+    },
+    /// See [`TraceVisitor::create_group`].
+    CreateGroup {
+        created_by: Id,
+        id: Id,
+        name: String,
+    },
+    /// See [`TraceVisitor::create_object`].
+    CreateObject {
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: String,
+        req_type: u8,
+        details: String,
+    },
+    /// See [`TraceVisitor::destroy`].
+    Destroy { destroyed_by: Id, id: Id },
+    /// See [`TraceVisitor::connect`].
+    Connect { connect_from: Id, connect_to: Id },
+    /// See [`TraceVisitor::enter`].
+    Enter { id: Id, entered: Id },
+    /// See [`TraceVisitor::exit`].
+    Exit { id: Id, exited: Id },
+    /// See [`TraceVisitor::value`].
+    Value { id: Id, value: f64 },
+    /// See [`TraceVisitor::add_to_group`].
+    AddToGroup { id: Id, group_id: Id },
+    /// See [`TraceVisitor::remove_from_group`].
+    RemoveFromGroup { id: Id, group_id: Id },
+    /// See [`TraceVisitor::begin_activity`].
+    BeginActivity {
+        activity: Id,
+        lane: Id,
+        name: String,
+    },
+    /// See [`TraceVisitor::end_activity`].
+    EndActivity { activity: Id },
+    /// See [`TraceVisitor::span_begin`].
+    SpanBegin { id: Id, span: Id, name: String },
+    /// See [`TraceVisitor::span_end`].
+    SpanEnd { id: Id, span: Id },
+    /// See [`TraceVisitor::capacity`].
+    Capacity { id: Id, capacity: Capacity },
+    /// See [`TraceVisitor::register_access`].
+    RegisterAccess { id: Id, access: RegisterAccess },
+    /// See [`TraceVisitor::counter`].
+    Counter { id: Id, name: String, delta: f64 },
+    /// See [`TraceVisitor::histogram`].
+    Histogram { id: Id, name: String, value: f64 },
+    /// See [`TraceVisitor::metadata`].
+    Metadata { key: String, value: String },
+    /// See [`TraceVisitor::time_unit`].
+    TimeUnit(TimeUnit),
+    /// See [`TraceVisitor::time`].
+    Time {
+        id: Id,
+        ticks: u64,
+        time_unit: TimeUnit,
+    },
+}
+
+impl TraceEvent {
+    /// Call the [`TraceVisitor`] function for this event.
+    pub fn visit(&self, visitor: &mut dyn TraceVisitor) {
+        match self {
+            TraceEvent::Log {
+                id,
+                level,
+                call_site: Some(call_site),
+                message,
+            } => visitor.log_at(*id, *level, &call_site.as_call_site(), message),
+            TraceEvent::Log {
+                id,
+                level,
+                call_site: None,
+                message,
+            } => visitor.log(*id, *level, message),
+            TraceEvent::CreateEntity {
+                created_by,
+                id,
+                name,
+            } => visitor.create_entity(*created_by, *id, name),
+            TraceEvent::CreateMonitor {
+                created_by,
+                id,
+                name,
+            } => visitor.create_monitor(*created_by, *id, name),
+            TraceEvent::CreateLane {
+                created_by,
+                id,
+                name,
+            } => visitor.create_lane(*created_by, *id, name),
+            TraceEvent::CreateGroup {
+                created_by,
+                id,
+                name,
+            } => visitor.create_group(*created_by, *id, name),
+            TraceEvent::CreateObject {
+                created_by,
+                id,
+                size,
+                units,
+                req_type,
+                details,
+            } => visitor.create_object(*created_by, *id, *size, units, *req_type, details),
+            TraceEvent::Destroy { destroyed_by, id } => visitor.destroy(*destroyed_by, *id),
+            TraceEvent::Connect {
+                connect_from,
+                connect_to,
+            } => visitor.connect(*connect_from, *connect_to),
+            TraceEvent::Enter { id, entered } => visitor.enter(*id, *entered),
+            TraceEvent::Exit { id, exited } => visitor.exit(*id, *exited),
+            TraceEvent::Value { id, value } => visitor.value(*id, *value),
+            TraceEvent::AddToGroup { id, group_id } => visitor.add_to_group(*id, *group_id),
+            TraceEvent::RemoveFromGroup { id, group_id } => {
+                visitor.remove_from_group(*id, *group_id);
+            }
+            TraceEvent::BeginActivity {
+                activity,
+                lane,
+                name,
+            } => visitor.begin_activity(*activity, *lane, name),
+            TraceEvent::EndActivity { activity } => visitor.end_activity(*activity),
+            TraceEvent::SpanBegin { id, span, name } => visitor.span_begin(*id, *span, name),
+            TraceEvent::SpanEnd { id, span } => visitor.span_end(*id, *span),
+            TraceEvent::Capacity { id, capacity } => visitor.capacity(*id, capacity.clone()),
+            TraceEvent::RegisterAccess { id, access } => {
+                visitor.register_access(*id, access.clone());
+            }
+            TraceEvent::Counter { id, name, delta } => visitor.counter(*id, name, *delta),
+            TraceEvent::Histogram { id, name, value } => visitor.histogram(*id, name, *value),
+            TraceEvent::Metadata { key, value } => visitor.metadata(key, value),
+            TraceEvent::TimeUnit(time_unit) => visitor.time_unit(*time_unit),
+            TraceEvent::Time {
+                id,
+                ticks,
+                time_unit,
+            } => visitor.time(*id, *ticks, *time_unit),
+        }
     }
 }
 
 /// Process a given Cap'n Proto file calling the visitor for each event found.
 ///
+/// Events are read one at a time, so traces larger than memory can be
+/// processed.
+///
 /// # Examples
 ///
 /// A simple visitor that will count how many IDs are used.
@@ -278,224 +670,514 @@ pub trait TraceVisitor {
 /// # Ok(())
 /// # }
 /// ```
-pub fn process_capnp<R>(mut reader: R, visitor: &mut dyn TraceVisitor)
+pub fn process_capnp<R>(reader: R, visitor: &mut dyn TraceVisitor)
 where
     R: BufRead,
 {
-    while let Ok(event_reader) =
-        serialize_packed::read_message(&mut reader, ::capnp::message::ReaderOptions::new())
+    for event in TraceEvents::new(reader) {
+        event.visit(visitor);
+    }
+}
+
+/// Process a given Cap'n Proto file like [`process_capnp`], but decode the
+/// events on `num_threads` threads.
+///
+/// The visitor is still called on the current thread, for each event in the
+/// order that they were written.
+pub fn process_capnp_parallel<R>(reader: R, visitor: &mut dyn TraceVisitor, num_threads: usize)
+where
+    R: BufRead + Send + 'static,
+{
+    for event in ParallelTraceEvents::new(reader, num_threads) {
+        event.visit(visitor);
+    }
+}
+
+/// An iterator over the events of a Cap'n Proto file.
+///
+/// Only one event is held in memory at a time. Times are given in the
+/// [`TraceEvent::TimeUnit`] of the trace.
+///
+/// # Examples
+///
+/// Count the log messages in a trace.
+/// ```no_run
+/// # use std::error::Error;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// use gwr_track::trace_visitor::{TraceEvent, TraceEvents};
+///
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let events = TraceEvents::new(BufReader::new(File::open("capnp.bin")?));
+/// let num_logs = events
+///     .filter(|event| matches!(event, TraceEvent::Log { .. }))
+///     .count();
+/// println!("{num_logs} log messages");
+/// #
+/// # Ok(())
+/// # }
+/// ```
+pub struct TraceEvents<R> {
+    reader: R,
+    time_unit: TimeUnit,
+}
+
+impl<R> TraceEvents<R>
+where
+    R: BufRead,
+{
+    /// Create an iterator over the events read from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            time_unit: TimeUnit::Ns,
+        }
+    }
+}
+
+impl<R> Iterator for TraceEvents<R>
+where
+    R: BufRead,
+{
+    type Item = TraceEvent;
+
+    fn next(&mut self) -> Option<TraceEvent> {
+        let message =
+            serialize_packed::read_message(&mut self.reader, ReaderOptions::new()).ok()?;
+        Some(convert_time(&mut self.time_unit, decode_message(&message)))
+    }
+}
+
+/// Number of events that are decoded by a thread at a time.
+const EVENTS_PER_BATCH: usize = 1024;
+
+/// Number of batches per thread that can be read but not yet returned by the
+/// iterator.
+const BATCHES_IN_FLIGHT_PER_THREAD: usize = 2;
+
+/// A batch of events in the order it was read.
+type Batch<T> = (usize, Vec<T>);
+
+/// An iterator over the events of a Cap'n Proto file that decodes the events
+/// on a number of threads.
+///
+/// One thread reads the file and hands out batches of messages to be decoded
+/// by the others. The events are returned in the order they were written, and
+/// the number of batches in flight is limited so that memory use is bounded
+/// however large the trace is.
+///
+/// # Panics
+///
+/// Panics from the iterator if a thread fails to decode an event.
+pub struct ParallelTraceEvents {
+    results: Receiver<Batch<Decoded>>,
+
+    /// Decoded batches that arrived before the batch that is next in order.
+    pending: HashMap<usize, Vec<Decoded>>,
+    next_batch: usize,
+
+    /// Allows the reading thread to read another batch each time a batch is
+    /// returned in order, which bounds the size of `pending`.
+    credits: SyncSender<()>,
+    batch: vec::IntoIter<Decoded>,
+
+    time_unit: TimeUnit,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ParallelTraceEvents {
+    /// Create an iterator over the events read from `reader` which are
+    /// decoded on `num_threads` threads.
+    pub fn new<R>(reader: R, num_threads: usize) -> Self
+    where
+        R: BufRead + Send + 'static,
     {
-        let event = event_reader
-            .get_root::<gwr_track_capnp::event::Reader>()
-            .expect("should be able to parse event");
-
-        let id = Id(event.get_id());
-        match event.which() {
-            Ok(gwr_track_capnp::event::Which::Log(builder)) => handle_log(visitor, id, builder),
-            Ok(gwr_track_capnp::event::Which::Create(builder)) => {
-                handle_create(visitor, id, builder);
-            }
-            Ok(gwr_track_capnp::event::Which::Destroy(destroyed_by)) => {
-                handle_destroy(visitor, id, destroyed_by);
-            }
-            Ok(gwr_track_capnp::event::Which::Connect(connect_to)) => {
-                handle_connect(visitor, id, connect_to);
-            }
-            Ok(gwr_track_capnp::event::Which::Enter(entered)) => handle_enter(visitor, id, entered),
-            Ok(gwr_track_capnp::event::Which::Exit(exited)) => handle_exit(visitor, id, exited),
-            Ok(gwr_track_capnp::event::Which::Value(value)) => handle_value(visitor, id, value),
-            Ok(gwr_track_capnp::event::Which::AddToGroup(group_id)) => {
-                handle_add_to_group(visitor, id, group_id);
-            }
-            Ok(gwr_track_capnp::event::Which::RemoveFromGroup(group_id)) => {
-                handle_remove_from_group(visitor, id, group_id);
-            }
-            Ok(gwr_track_capnp::event::Which::BeginActivity(begin_activity)) => {
-                handle_begin_activity(visitor, id, begin_activity);
+        let num_threads = num_threads.max(1);
+        let (message_tx, message_rx) = mpsc::sync_channel(num_threads);
+        let message_rx = Arc::new(Mutex::new(message_rx));
+        let (results_tx, results) = mpsc::sync_channel(num_threads);
+        let max_in_flight = num_threads * BATCHES_IN_FLIGHT_PER_THREAD;
+        let (credits, credits_rx) = mpsc::sync_channel(max_in_flight);
+        for _ in 0..max_in_flight {
+            credits.send(()).unwrap();
+        }
+
+        let mut threads = Vec::with_capacity(num_threads + 1);
+        threads.push(thread::spawn(move || {
+            read_batches(reader, &message_tx, &credits_rx);
+        }));
+        for _ in 0..num_threads {
+            let message_rx = message_rx.clone();
+            let results_tx = results_tx.clone();
+            threads.push(thread::spawn(move || {
+                decode_batches(&message_rx, &results_tx);
+            }));
+        }
+
+        Self {
+            results,
+            pending: HashMap::new(),
+            next_batch: 0,
+            credits,
+            batch: Vec::new().into_iter(),
+            time_unit: TimeUnit::Ns,
+            threads,
+        }
+    }
+
+    fn next_batch(&mut self) -> Option<Vec<Decoded>> {
+        loop {
+            if let Some(batch) = self.pending.remove(&self.next_batch) {
+                self.next_batch += 1;
+                // The reading thread may have finished, so this can fail
+                let _ = self.credits.try_send(());
+                return Some(batch);
             }
-            Ok(gwr_track_capnp::event::Which::EndActivity(())) => {
-                handle_end_activity(visitor, id);
+            match self.results.recv() {
+                Ok((index, batch)) => {
+                    self.pending.insert(index, batch);
+                }
+                Err(_) => {
+                    // All of the threads have finished, pass on any panic
+                    for thread in self.threads.drain(..) {
+                        if let Err(e) = thread.join() {
+                            panic::resume_unwind(e);
+                        }
+                    }
+                    return None;
+                }
             }
-            Ok(gwr_track_capnp::event::Which::Capacity(capacity)) => {
-                handle_capacity(visitor, id, capacity);
+        }
+    }
+}
+
+impl Iterator for ParallelTraceEvents {
+    type Item = TraceEvent;
+
+    fn next(&mut self) -> Option<TraceEvent> {
+        loop {
+            if let Some(event) = self.batch.next() {
+                return Some(convert_time(&mut self.time_unit, event));
             }
-            Ok(gwr_track_capnp::event::Which::Time(time)) => handle_time(visitor, id, time),
-            Err(e) => {
-                panic!("should be able to parse event ({e})");
+            self.batch = self.next_batch()?.into_iter();
+        }
+    }
+}
+
+/// Read batches of messages and send them to be decoded, waiting for a
+/// credit before each batch.
+fn read_batches<R>(
+    mut reader: R,
+    message_tx: &SyncSender<Batch<message::Reader<OwnedSegments>>>,
+    credits: &Receiver<()>,
+) where
+    R: BufRead,
+{
+    let mut index = 0;
+    let mut messages = Vec::with_capacity(EVENTS_PER_BATCH);
+    while let Ok(message) = serialize_packed::read_message(&mut reader, ReaderOptions::new()) {
+        messages.push(message);
+        if messages.len() == EVENTS_PER_BATCH {
+            let batch = mem::replace(&mut messages, Vec::with_capacity(EVENTS_PER_BATCH));
+            if credits.recv().is_err() || message_tx.send((index, batch)).is_err() {
+                // The iterator has been dropped
+                return;
             }
+            index += 1;
         }
     }
+    if !messages.is_empty() && credits.recv().is_ok() {
+        let _ = message_tx.send((index, messages));
+    }
 }
 
-fn handle_log(
-    visitor: &mut dyn TraceVisitor,
-    id: Id,
-    builder: capnp::Result<gwr_track_capnp::log::Reader<'_>>,
+fn decode_batches(
+    message_rx: &Mutex<Receiver<Batch<message::Reader<OwnedSegments>>>>,
+    results_tx: &SyncSender<Batch<Decoded>>,
 ) {
+    loop {
+        let received = message_rx.lock().unwrap().recv();
+        let Ok((index, messages)) = received else {
+            return;
+        };
+        let events = messages.iter().map(decode_message).collect();
+        if results_tx.send((index, events)).is_err() {
+            // The iterator has been dropped
+            return;
+        }
+    }
+}
+
+/// An event as it is decoded, before the unit of its time is known.
+enum Decoded {
+    Event(TraceEvent),
+
+    /// A `time` event, in the unit the trace was written in.
+    Time {
+        id: Id,
+        time: f64,
+    },
+}
+
+/// Give the times the unit of the trace, which is only known in the order the
+/// events were written.
+fn convert_time(time_unit: &mut TimeUnit, decoded: Decoded) -> TraceEvent {
+    match decoded {
+        Decoded::Event(TraceEvent::TimeUnit(unit)) => {
+            *time_unit = unit;
+            TraceEvent::TimeUnit(unit)
+        }
+        Decoded::Event(event) => event,
+        Decoded::Time { id, time } if time.fract() == 0.0 => TraceEvent::Time {
+            id,
+            ticks: time as u64,
+            time_unit: *time_unit,
+        },
+        // Times between two ticks of the unit are kept to the picosecond
+        Decoded::Time { id, time } => TraceEvent::Time {
+            id,
+            ticks: TimeUnit::Ps.ticks_from_ns(time_unit.to_ns(time)),
+            time_unit: TimeUnit::Ps,
+        },
+    }
+}
+
+fn decode_message(message: &message::Reader<OwnedSegments>) -> Decoded {
+    let event = message
+        .get_root::<gwr_track_capnp::event::Reader>()
+        .expect("should be able to parse event");
+
+    let id = Id(event.get_id());
+    Decoded::Event(match event.which() {
+        Ok(gwr_track_capnp::event::Which::Log(builder)) => decode_log(id, builder),
+        Ok(gwr_track_capnp::event::Which::Create(builder)) => decode_create(id, builder),
+        Ok(gwr_track_capnp::event::Which::Destroy(destroyed)) => TraceEvent::Destroy {
+            destroyed_by: id,
+            id: Id(destroyed),
+        },
+        Ok(gwr_track_capnp::event::Which::Connect(connect_to)) => TraceEvent::Connect {
+            connect_from: id,
+            connect_to: Id(connect_to),
+        },
+        Ok(gwr_track_capnp::event::Which::Enter(entered)) => TraceEvent::Enter {
+            id,
+            entered: Id(entered),
+        },
+        Ok(gwr_track_capnp::event::Which::Exit(exited)) => TraceEvent::Exit {
+            id,
+            exited: Id(exited),
+        },
+        Ok(gwr_track_capnp::event::Which::Value(value)) => TraceEvent::Value { id, value },
+        Ok(gwr_track_capnp::event::Which::AddToGroup(group_id)) => TraceEvent::AddToGroup {
+            id,
+            group_id: Id(group_id),
+        },
+        Ok(gwr_track_capnp::event::Which::RemoveFromGroup(group_id)) => {
+            TraceEvent::RemoveFromGroup {
+                id,
+                group_id: Id(group_id),
+            }
+        }
+        Ok(gwr_track_capnp::event::Which::BeginActivity(begin_activity)) => {
+            decode_begin_activity(id, begin_activity)
+        }
+        Ok(gwr_track_capnp::event::Which::EndActivity(())) => {
+            TraceEvent::EndActivity { activity: id }
+        }
+        Ok(gwr_track_capnp::event::Which::Capacity(capacity)) => decode_capacity(id, capacity),
+        Ok(gwr_track_capnp::event::Which::RegisterAccess(access)) => {
+            decode_register_access(id, access)
+        }
+        Ok(gwr_track_capnp::event::Which::SpanBegin(span_begin)) => {
+            decode_span_begin(id, span_begin)
+        }
+        Ok(gwr_track_capnp::event::Which::SpanEnd(span)) => {
+            TraceEvent::SpanEnd { id, span: Id(span) }
+        }
+        Ok(gwr_track_capnp::event::Which::Counter(metric)) => {
+            let (name, delta) = read_metric(metric);
+            TraceEvent::Counter { id, name, delta }
+        }
+        Ok(gwr_track_capnp::event::Which::Histogram(metric)) => {
+            let (name, value) = read_metric(metric);
+            TraceEvent::Histogram { id, name, value }
+        }
+        Ok(gwr_track_capnp::event::Which::Time(time)) => return Decoded::Time { id, time },
+        Ok(gwr_track_capnp::event::Which::Metadata(metadata)) => decode_metadata(metadata),
+        Ok(gwr_track_capnp::event::Which::TimeUnit(unit)) => {
+            TraceEvent::TimeUnit(decode_time_unit(unit))
+        }
+        Err(e) => {
+            panic!("should be able to parse event ({e})");
+        }
+    })
+}
+
+/// Read a text field of an event.
+fn read_text(text: capnp::Result<capnp::text::Reader<'_>>, what: &str) -> String {
+    text.unwrap_or_else(|e| panic!("should be able to parse {what} ({e})"))
+        .to_str()
+        .unwrap_or_else(|e| panic!("{what} should be valid UTF-8 string ({e})"))
+        .to_owned()
+}
+
+fn decode_log(id: Id, builder: capnp::Result<gwr_track_capnp::log::Reader<'_>>) -> TraceEvent {
     let access = builder.expect("should be able to parse Log event");
-    visitor.log(
-        id,
-        to_log_level(
-            access
-                .get_level()
-                .expect("should be able to parse Log level"),
-        ),
+    let level = to_log_level(
         access
-            .get_message()
-            .expect("should be able to parse Log message")
-            .to_str()
-            .expect("Log message should be valid UTF-8 string"),
+            .get_level()
+            .expect("should be able to parse Log level"),
     );
+    let message = read_text(access.get_message(), "Log message");
+
+    // Traces written before call sites were recorded have no file
+    let call_site = access.has_file().then(|| TraceCallSite {
+        module_path: read_text(access.get_module(), "Log module"),
+        file: read_text(access.get_file(), "Log file"),
+        line: access.get_line(),
+    });
+    TraceEvent::Log {
+        id,
+        level,
+        call_site,
+        message,
+    }
 }
 
-fn handle_create(
-    visitor: &mut dyn TraceVisitor,
-    id: Id,
+fn decode_create(
+    created_by: Id,
     builder: capnp::Result<gwr_track_capnp::create::Reader<'_>>,
-) {
+) -> TraceEvent {
     let access = builder.expect("should be able to parse Create event");
-    let created_id = Id(access.get_id());
+    let id = Id(access.get_id());
     match access.which() {
         Ok(gwr_track_capnp::create::Which::Entity(entity)) => {
             let entity = entity.expect("should be able to parse Create Entity");
-            visitor.create_entity(
+            TraceEvent::CreateEntity {
+                created_by,
                 id,
-                created_id,
-                entity
-                    .get_name()
-                    .expect("should be able to parse Entity name")
-                    .to_str()
-                    .expect("Create Entity name should be valid UTF-8 string"),
-            );
+                name: read_text(entity.get_name(), "Create Entity name"),
+            }
         }
         Ok(gwr_track_capnp::create::Which::Monitor(monitor)) => {
             let monitor = monitor.expect("should be able to parse Create Monitor");
-            visitor.create_monitor(
+            TraceEvent::CreateMonitor {
+                created_by,
                 id,
-                created_id,
-                monitor
-                    .get_name()
-                    .expect("should be able to parse Monitor name")
-                    .to_str()
-                    .expect("Create Monitor name should be valid UTF-8 string"),
-            );
+                name: read_text(monitor.get_name(), "Create Monitor name"),
+            }
         }
         Ok(gwr_track_capnp::create::Which::Lane(lane)) => {
             let lane = lane.expect("should be able to parse Create Lane");
-            visitor.create_lane(
+            TraceEvent::CreateLane {
+                created_by,
                 id,
-                created_id,
-                lane.get_name()
-                    .expect("should be able to parse Lane name")
-                    .to_str()
-                    .expect("Create Lane name should be valid UTF-8 string"),
-            );
+                name: read_text(lane.get_name(), "Create Lane name"),
+            }
         }
         Ok(gwr_track_capnp::create::Which::Group(group)) => {
             let group = group.expect("should be able to parse Create Group");
-            visitor.create_group(
+            TraceEvent::CreateGroup {
+                created_by,
                 id,
-                created_id,
-                group
-                    .get_name()
-                    .expect("should be able to parse Group name")
-                    .to_str()
-                    .expect("Create Group name should be valid UTF-8 string"),
-            );
+                name: read_text(group.get_name(), "Create Group name"),
+            }
         }
         Ok(gwr_track_capnp::create::Which::Object(object)) => {
             let object = object.expect("should be able to parse Create Object");
-            visitor.create_object(
+            TraceEvent::CreateObject {
+                created_by,
                 id,
-                created_id,
-                object.get_size() as usize,
-                object
-                    .get_units()
-                    .expect("should be able to parse Object units")
-                    .to_str()
-                    .expect("Create Object units should be valid UTF-8 string"),
-                object.get_type(),
-                object
-                    .get_details()
-                    .expect("should be able to parse Object details")
-                    .to_str()
-                    .expect("Create Object details should be valid UTF-8 string"),
-            );
+                size: object.get_size() as usize,
+                units: read_text(object.get_units(), "Create Object units"),
+                req_type: object.get_type(),
+                details: read_text(object.get_details(), "Create Object details"),
+            }
         }
         Err(e) => panic!("should be able to parse create event ({e})"),
     }
 }
 
-fn handle_destroy(visitor: &mut dyn TraceVisitor, id: Id, destroyed_by: u64) {
-    visitor.destroy(id, Id(destroyed_by));
-}
-
-fn handle_connect(visitor: &mut dyn TraceVisitor, id: Id, connect_to: u64) {
-    visitor.connect(id, Id(connect_to));
-}
-
-fn handle_enter(visitor: &mut dyn TraceVisitor, id: Id, entered: u64) {
-    visitor.enter(id, Id(entered));
-}
-
-fn handle_exit(visitor: &mut dyn TraceVisitor, id: Id, exited: u64) {
-    visitor.exit(id, Id(exited));
-}
-
-fn handle_value(visitor: &mut dyn TraceVisitor, id: Id, value: f64) {
-    visitor.value(id, value);
-}
-
-fn handle_add_to_group(visitor: &mut dyn TraceVisitor, id: Id, group_id: u64) {
-    visitor.add_to_group(id, Id(group_id));
-}
-
-fn handle_remove_from_group(visitor: &mut dyn TraceVisitor, id: Id, group_id: u64) {
-    visitor.remove_from_group(id, Id(group_id));
-}
-
-fn handle_begin_activity(
-    visitor: &mut dyn TraceVisitor,
-    id: Id,
+fn decode_begin_activity(
+    activity: Id,
     begin_activity: capnp::Result<gwr_track_capnp::begin_activity::Reader<'_>>,
-) {
+) -> TraceEvent {
     let begin_activity = begin_activity.expect("should be able to parse BeginActivity event");
-    visitor.begin_activity(
-        id,
-        Id(begin_activity.get_lane()),
-        begin_activity
-            .get_name()
-            .expect("should be able to parse activity name")
-            .to_str()
-            .expect("Activity name should be valid UTF-8 string"),
-    );
+    TraceEvent::BeginActivity {
+        activity,
+        lane: Id(begin_activity.get_lane()),
+        name: read_text(begin_activity.get_name(), "Activity name"),
+    }
 }
 
-fn handle_end_activity(visitor: &mut dyn TraceVisitor, id: Id) {
-    visitor.end_activity(id);
+fn decode_span_begin(
+    id: Id,
+    span_begin: capnp::Result<gwr_track_capnp::span_begin::Reader<'_>>,
+) -> TraceEvent {
+    let span_begin = span_begin.expect("should be able to parse SpanBegin event");
+    TraceEvent::SpanBegin {
+        id,
+        span: Id(span_begin.get_span()),
+        name: read_text(span_begin.get_name(), "Span name"),
+    }
 }
 
-fn handle_capacity(
-    visitor: &mut dyn TraceVisitor,
+fn decode_capacity(
     id: Id,
     capacity: capnp::Result<gwr_track_capnp::capacity::Reader<'_>>,
-) {
+) -> TraceEvent {
     let capacity = capacity.expect("should be able to parse Capacity event");
-    visitor.capacity(
+    TraceEvent::Capacity {
         id,
-        Capacity::new(
+        capacity: Capacity::new(
             capacity.get_value() as usize,
-            capacity
-                .get_units()
-                .expect("should be able to parse Capacity units")
-                .to_str()
-                .expect("Capacity units should be valid UTF-8 string"),
+            read_text(capacity.get_units(), "Capacity units"),
         ),
-    );
+    }
 }
 
-fn handle_time(visitor: &mut dyn TraceVisitor, id: Id, time: f64) {
-    visitor.time(id, time);
+fn decode_register_access(
+    id: Id,
+    access: capnp::Result<gwr_track_capnp::register_access::Reader<'_>>,
+) -> TraceEvent {
+    let access = access.expect("should be able to parse RegisterAccess event");
+    TraceEvent::RegisterAccess {
+        id,
+        access: RegisterAccess {
+            is_write: access.get_is_write(),
+            offset: access.get_offset(),
+            view: read_text(access.get_view(), "RegisterAccess view"),
+            old_value: access.get_old_value(),
+            new_value: access.get_new_value(),
+        },
+    }
+}
+
+fn decode_metadata(metadata: capnp::Result<gwr_track_capnp::metadata::Reader<'_>>) -> TraceEvent {
+    let metadata = metadata.expect("should be able to parse Metadata event");
+    TraceEvent::Metadata {
+        key: read_text(metadata.get_key(), "Metadata key"),
+        value: read_text(metadata.get_value(), "Metadata value"),
+    }
+}
+
+fn read_metric(metric: capnp::Result<gwr_track_capnp::metric::Reader<'_>>) -> (String, f64) {
+    let metric = metric.expect("should be able to parse Metric event");
+    (
+        read_text(metric.get_name(), "Metric name"),
+        metric.get_value(),
+    )
+}
+
+fn decode_time_unit(
+    unit: Result<gwr_track_capnp::event::TimeUnit, capnp::NotInSchema>,
+) -> TimeUnit {
+    match unit.expect("should be able to parse TimeUnit") {
+        gwr_track_capnp::event::TimeUnit::Ps => TimeUnit::Ps,
+        gwr_track_capnp::event::TimeUnit::Ns => TimeUnit::Ns,
+        gwr_track_capnp::event::TimeUnit::Us => TimeUnit::Us,
+    }
 }
 
 fn to_log_level(level: LogLevel) -> log::Level {
@@ -5,17 +5,25 @@ use std::rc::Rc;
 
 use capnp::serialize_packed;
 
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::gwr_track_capnp::event;
 use crate::gwr_track_capnp::log::LogLevel;
+use crate::rotation::TraceHeader;
 use crate::tracker::aka::AlternativeNames;
-use crate::tracker::{EntityManager, Track};
-use crate::{Id, SharedWriter, Writer, gwr_track_capnp};
+use crate::tracker::types::{CallSite, EventKind, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, Track, TrackConfigError};
+use crate::{Id, NO_ID, SharedWriter, Writer, gwr_track_capnp};
 
 /// A tracker that writes Cap'n Proto binary data
 pub struct CapnProtoTracker {
     entity_manager: EntityManager,
     writer: SharedWriter,
+
+    /// The unit that time is written in.
+    time_unit: TimeUnit,
+
+    /// The events that describe the whole trace.
+    header: TraceHeader,
 }
 
 impl CapnProtoTracker {
@@ -24,7 +32,46 @@ impl CapnProtoTracker {
         Self {
             entity_manager,
             writer: Rc::new(RefCell::new(writer)),
+            time_unit: TimeUnit::default(),
+            header: TraceHeader::default(),
+        }
+    }
+
+    /// Add the events that describe the whole trace, such as its unit of time,
+    /// metadata and the creation of entities, to `header` so that they can be
+    /// written again at the start of each rotated file.
+    #[must_use]
+    pub fn with_header(mut self, header: TraceHeader) -> Self {
+        header.extend(&self.header.bytes());
+        self.header = header;
+        self
+    }
+
+    /// Write time in `time_unit` rather than nanoseconds.
+    ///
+    /// The unit is recorded at the start of the trace so that it is read
+    /// correctly by [`process_capnp`](crate::trace_visitor::process_capnp).
+    #[must_use]
+    pub fn with_time_unit(mut self, time_unit: TimeUnit) -> Self {
+        self.time_unit = time_unit;
+        // Traces in nanoseconds are left unchanged for older readers
+        if time_unit != TimeUnit::Ns {
+            self.write_header_event(NO_ID, |mut event| {
+                event.set_time_unit(to_capnp_time_unit(time_unit));
+            });
         }
+        self
+    }
+
+    /// Write an event that describes the whole trace and add it to the
+    /// header.
+    fn write_header_event<F>(&self, id: Id, build: F)
+    where
+        F: FnOnce(gwr_track_capnp::event::Builder<'_>),
+    {
+        let bytes = event_bytes(id, build);
+        self.header.extend(&bytes);
+        self.writer.borrow_mut().write_all(&bytes).unwrap();
     }
 
     /// Helper function to create a _trace_ event
@@ -39,19 +86,30 @@ impl CapnProtoTracker {
     where
         F: FnOnce(gwr_track_capnp::event::Builder<'_>),
     {
-        let mut builder = capnp::message::Builder::new_default();
-        {
-            let mut event = builder.init_root::<event::Builder>();
-            event.set_id(id.0);
+        // Write out the event to the file in a single write so that it is
+        // never split by a BackgroundWriter
+        let bytes = event_bytes(id, build);
+        self.writer.borrow_mut().write_all(&bytes).unwrap();
+    }
+}
 
-            // Call build method to populate the rest of the event
-            build(event);
-        }
+/// Build an event and serialise it to bytes.
+fn event_bytes<F>(id: Id, build: F) -> Vec<u8>
+where
+    F: FnOnce(gwr_track_capnp::event::Builder<'_>),
+{
+    let mut builder = capnp::message::Builder::new_default();
+    {
+        let mut event = builder.init_root::<event::Builder>();
+        event.set_id(id.0);
 
-        // Write out the event to the file
-        let mut writer_ref = self.writer.borrow_mut();
-        serialize_packed::write_message(&mut *writer_ref, &builder).unwrap();
+        // Call build method to populate the rest of the event
+        build(event);
     }
+
+    let mut bytes = Vec::new();
+    serialize_packed::write_message(&mut bytes, &builder).unwrap();
+    bytes
 }
 
 /// Implementation each [`Track`] event
@@ -73,6 +131,10 @@ impl Track for CapnProtoTracker {
         self.entity_manager.monitoring_window_size_for(id)
     }
 
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.entity_manager.enabled_event_kinds(id)
+    }
+
     fn add_entity(
         &self,
         id: Id,
@@ -84,7 +146,7 @@ impl Track for CapnProtoTracker {
     }
 
     fn enter(&self, id: Id, object: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
             self.write_event(id, |mut event| {
                 event.set_enter(object.0);
             });
@@ -92,7 +154,7 @@ impl Track for CapnProtoTracker {
     }
 
     fn exit(&self, id: Id, object: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
             self.write_event(id, |mut event| {
                 event.set_exit(object.0);
             });
@@ -100,7 +162,7 @@ impl Track for CapnProtoTracker {
     }
 
     fn value(&self, id: Id, value: f64) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
             self.write_event(id, |mut event| {
                 event.set_value(value);
             });
@@ -108,7 +170,7 @@ impl Track for CapnProtoTracker {
     }
 
     fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
-        if self.is_entity_enabled(lane, log::Level::Trace) {
+        if self.is_event_enabled(lane, log::Level::Trace, EventKind::Activities) {
             self.write_event(activity, |event| {
                 let mut begin_activity = event.init_begin_activity();
                 begin_activity.set_lane(lane.0);
@@ -135,9 +197,27 @@ impl Track for CapnProtoTracker {
         });
     }
 
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.write_event(id, |event| {
+                let mut span_begin = event.init_span_begin();
+                span_begin.set_span(span.0);
+                span_begin.set_name(name);
+            });
+        }
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.write_event(id, |mut event| {
+                event.set_span_end(span.0);
+            });
+        }
+    }
+
     fn create_entity(&self, created_by: Id, id: Id, name: &str) {
         // Don't filter this event as it could be required by a GUI
-        self.write_event(created_by, |event| {
+        self.write_header_event(created_by, |event| {
             let mut create = event.init_create();
             create.set_id(id.0);
             create.init_entity().set_name(name);
@@ -146,7 +226,7 @@ impl Track for CapnProtoTracker {
 
     fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
         // Don't filter this event as it could be required by a GUI
-        self.write_event(created_by, |event| {
+        self.write_header_event(created_by, |event| {
             let mut create = event.init_create();
             create.set_id(id.0);
             create.init_monitor().set_name(name);
@@ -155,7 +235,7 @@ impl Track for CapnProtoTracker {
 
     fn create_lane(&self, created_by: Id, id: Id, name: &str) {
         // Don't filter this event as it could be required by a GUI
-        self.write_event(created_by, |event| {
+        self.write_header_event(created_by, |event| {
             let mut create = event.init_create();
             create.set_id(id.0);
             create.init_lane().set_name(name);
@@ -180,7 +260,7 @@ impl Track for CapnProtoTracker {
         req_type: u8,
         details: &str,
     ) {
-        if self.is_entity_enabled(created_by, log::Level::Trace) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
             self.write_event(created_by, |event| {
                 let mut create = event.init_create();
                 create.set_id(id.0);
@@ -202,8 +282,41 @@ impl Track for CapnProtoTracker {
         });
     }
 
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Registers) {
+            self.write_event(id, |event| {
+                let mut event_access = event.init_register_access();
+                event_access.set_is_write(access.is_write);
+                event_access.set_offset(access.offset);
+                event_access.set_view(&access.view);
+                event_access.set_old_value(access.old_value);
+                event_access.set_new_value(access.new_value);
+            });
+        }
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_event(id, |event| {
+                let mut event_counter = event.init_counter();
+                event_counter.set_name(name);
+                event_counter.set_value(delta);
+            });
+        }
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_event(id, |event| {
+                let mut event_histogram = event.init_histogram();
+                event_histogram.set_name(name);
+                event_histogram.set_value(value);
+            });
+        }
+    }
+
     fn destroy(&self, destroyed_by: Id, id: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
             self.write_event(destroyed_by, |mut event| {
                 event.set_destroy(id.0);
             });
@@ -211,8 +324,8 @@ impl Track for CapnProtoTracker {
     }
 
     fn connect(&self, connect_from: Id, connect_to: Id) {
-        if self.is_entity_enabled(connect_from, log::Level::Trace)
-            || self.is_entity_enabled(connect_to, log::Level::Trace)
+        if self.is_event_enabled(connect_from, log::Level::Trace, EventKind::Lifecycle)
+            || self.is_event_enabled(connect_to, log::Level::Trace, EventKind::Lifecycle)
         {
             self.write_event(connect_from, |mut event| {
                 event.set_connect(connect_to.0);
@@ -221,7 +334,7 @@ impl Track for CapnProtoTracker {
     }
 
     fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
-        if self.is_entity_enabled(id, level) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
             self.write_event(id, |event| {
                 let mut log = event.init_log();
                 let txt = format!("{msg}");
@@ -231,17 +344,57 @@ impl Track for CapnProtoTracker {
         }
     }
 
-    fn time(&self, set_by: Id, time_ns: f64) {
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
+            self.write_event(id, |event| {
+                let mut log = event.init_log();
+                let txt = format!("{msg}");
+                log.set_message(&txt);
+                log.set_level(to_capnp_log_level(level));
+                log.set_module(call_site.module_path);
+                log.set_file(call_site.file);
+                log.set_line(call_site.line);
+            });
+        }
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        self.write_header_event(NO_ID, |event| {
+            let mut metadata = event.init_metadata();
+            metadata.set_key(key);
+            metadata.set_value(value);
+        });
+    }
+
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        // The time is written as a float, so any fraction of the unit of the
+        // trace is kept
         self.write_event(set_by, |mut event| {
-            event.set_time(time_ns);
+            event.set_time(time_unit.convert(ticks, self.time_unit));
         });
     }
 
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.entity_manager.set_entity_level(regex_str, level)
+    }
+
+    fn flush(&self) {
+        self.writer.borrow_mut().flush().unwrap();
+    }
+
     fn shutdown(&self) {
         self.writer.borrow_mut().flush().unwrap();
     }
 }
 
+fn to_capnp_time_unit(time_unit: TimeUnit) -> event::TimeUnit {
+    match time_unit {
+        TimeUnit::Ps => event::TimeUnit::Ps,
+        TimeUnit::Ns => event::TimeUnit::Ns,
+        TimeUnit::Us => event::TimeUnit::Us,
+    }
+}
+
 fn to_capnp_log_level(level: log::Level) -> LogLevel {
     match level {
         log::Level::Error => LogLevel::Error,
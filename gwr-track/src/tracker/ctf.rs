@@ -0,0 +1,655 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A tracker that writes a [Common Trace Format](https://diamon.org/ctf/v1.8.3/)
+//! (CTF 1.8) trace, which can be opened by Trace Compass and babeltrace.
+//!
+//! A CTF trace is a directory that holds:
+//!
+//!  - `metadata`: the description of the events, in the Trace Stream
+//!    Description Language
+//!  - `stream_0`: the events, as a single packet of little-endian binary data
+//!
+//! There is an event for each [`Track`] event, with the same fields. The
+//! timestamp of each event is the simulation time in picoseconds, so changes
+//! of time are not written as events of their own.
+
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::entity::{Capacity, RegisterAccess};
+use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{EventKind, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, Track, TrackConfigError};
+use crate::{Id, SharedWriter, Writer};
+
+/// The magic number that starts each packet
+const CTF_MAGIC: u32 = 0xC1FC_1FC1;
+
+/// The number of ticks of the CTF clock per nanosecond
+const TICKS_PER_NS: f64 = 1000.0;
+
+const METADATA: &str = r#"/* CTF 1.8 */
+
+typealias integer { size = 8; align = 8; signed = false; } := uint8_t;
+typealias integer { size = 32; align = 8; signed = false; } := uint32_t;
+typealias integer { size = 64; align = 8; signed = false; } := uint64_t;
+typealias floating_point { exp_dig = 11; mant_dig = 53; align = 8; } := double;
+
+trace {
+    major = 1;
+    minor = 8;
+    byte_order = le;
+    packet.header := struct {
+        uint32_t magic;
+        uint32_t stream_id;
+    };
+};
+
+env {
+    domain = "gwr";
+};
+
+clock {
+    name = sim_clock;
+    description = "Simulation time in picoseconds";
+    freq = 1000000000000;
+};
+
+typealias integer {
+    size = 64; align = 8; signed = false;
+    map = clock.sim_clock.value;
+} := sim_clock_t;
+
+typealias enum : uint8_t {
+    ERROR = 1,
+    WARN = 2,
+    INFO = 3,
+    DEBUG = 4,
+    TRACE = 5,
+} := log_level_t;
+
+stream {
+    id = 0;
+    event.header := struct {
+        uint32_t id;
+        sim_clock_t timestamp;
+    };
+};
+
+event {
+    name = "log";
+    id = 0;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        log_level_t level;
+        string message;
+    };
+};
+
+event {
+    name = "enter";
+    id = 1;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        uint64_t object;
+    };
+};
+
+event {
+    name = "exit";
+    id = 2;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        uint64_t object;
+    };
+};
+
+event {
+    name = "value";
+    id = 3;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        double value;
+    };
+};
+
+event {
+    name = "create_entity";
+    id = 4;
+    stream_id = 0;
+    fields := struct {
+        uint64_t created_by;
+        uint64_t id;
+        string name;
+    };
+};
+
+event {
+    name = "create_monitor";
+    id = 5;
+    stream_id = 0;
+    fields := struct {
+        uint64_t created_by;
+        uint64_t id;
+        string name;
+    };
+};
+
+event {
+    name = "create_lane";
+    id = 6;
+    stream_id = 0;
+    fields := struct {
+        uint64_t created_by;
+        uint64_t id;
+        string name;
+    };
+};
+
+event {
+    name = "create_group";
+    id = 7;
+    stream_id = 0;
+    fields := struct {
+        uint64_t created_by;
+        uint64_t id;
+        string name;
+    };
+};
+
+event {
+    name = "add_to_group";
+    id = 8;
+    stream_id = 0;
+    fields := struct {
+        uint64_t activity;
+        uint64_t group;
+    };
+};
+
+event {
+    name = "remove_from_group";
+    id = 9;
+    stream_id = 0;
+    fields := struct {
+        uint64_t activity;
+        uint64_t group;
+    };
+};
+
+event {
+    name = "begin_activity";
+    id = 10;
+    stream_id = 0;
+    fields := struct {
+        uint64_t activity;
+        uint64_t lane;
+        string name;
+    };
+};
+
+event {
+    name = "end_activity";
+    id = 11;
+    stream_id = 0;
+    fields := struct {
+        uint64_t activity;
+    };
+};
+
+event {
+    name = "create_object";
+    id = 12;
+    stream_id = 0;
+    fields := struct {
+        uint64_t created_by;
+        uint64_t id;
+        uint64_t size;
+        string units;
+        uint8_t req_type;
+        string details;
+    };
+};
+
+event {
+    name = "capacity";
+    id = 13;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        uint64_t value;
+        string units;
+    };
+};
+
+event {
+    name = "register_access";
+    id = 14;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        uint8_t is_write;
+        uint64_t offset;
+        string view;
+        uint64_t old_value;
+        uint64_t new_value;
+    };
+};
+
+event {
+    name = "destroy";
+    id = 15;
+    stream_id = 0;
+    fields := struct {
+        uint64_t destroyed_by;
+        uint64_t id;
+    };
+};
+
+event {
+    name = "connect";
+    id = 16;
+    stream_id = 0;
+    fields := struct {
+        uint64_t from;
+        uint64_t to;
+    };
+};
+
+event {
+    name = "span_begin";
+    id = 17;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        uint64_t span;
+        string name;
+    };
+};
+
+event {
+    name = "span_end";
+    id = 18;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        uint64_t span;
+    };
+};
+
+event {
+    name = "counter";
+    id = 19;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        string name;
+        double delta;
+    };
+};
+
+event {
+    name = "histogram";
+    id = 20;
+    stream_id = 0;
+    fields := struct {
+        uint64_t id;
+        string name;
+        double value;
+    };
+};
+"#;
+
+/// The IDs of the events in the metadata
+#[derive(Clone, Copy)]
+enum EventId {
+    Log = 0,
+    Enter,
+    Exit,
+    Value,
+    CreateEntity,
+    CreateMonitor,
+    CreateLane,
+    CreateGroup,
+    AddToGroup,
+    RemoveFromGroup,
+    BeginActivity,
+    EndActivity,
+    CreateObject,
+    Capacity,
+    RegisterAccess,
+    Destroy,
+    Connect,
+    SpanBegin,
+    SpanEnd,
+    Counter,
+    Histogram,
+}
+
+/// The binary encoding of a single event
+struct CtfEvent(Vec<u8>);
+
+impl CtfEvent {
+    fn u8(mut self, value: u8) -> Self {
+        self.0.push(value);
+        self
+    }
+
+    fn u64(mut self, value: u64) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    fn id(self, id: Id) -> Self {
+        self.u64(id.0)
+    }
+
+    fn f64(mut self, value: f64) -> Self {
+        self.0.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Strings are null-terminated, so any null characters are dropped
+    fn string(mut self, value: &str) -> Self {
+        self.0.extend(value.bytes().filter(|byte| *byte != 0));
+        self.0.push(0);
+        self
+    }
+}
+
+/// A tracker that writes a CTF trace directory.
+pub struct CtfTracker {
+    entity_manager: EntityManager,
+    writer: SharedWriter,
+
+    /// The time of the most recent `time` event.
+    time_ns: Cell<f64>,
+}
+
+impl CtfTracker {
+    /// Create a new [`CtfTracker`] with an [`EntityManager`] that writes the
+    /// trace to the directory `trace_dir`, which is created if needed.
+    pub fn new(entity_manager: EntityManager, trace_dir: &Path) -> io::Result<Self> {
+        let writer: Writer = Box::new(BufWriter::new(Self::create_trace_dir(trace_dir)?));
+        Ok(Self::from_stream_writer(entity_manager, writer))
+    }
+
+    /// Create the directory `trace_dir` with its metadata, and return the file
+    /// that the stream is to be written to.
+    pub(crate) fn create_trace_dir(trace_dir: &Path) -> io::Result<fs::File> {
+        fs::create_dir_all(trace_dir)?;
+        fs::write(trace_dir.join("metadata"), METADATA)?;
+        fs::File::create(trace_dir.join("stream_0"))
+    }
+
+    /// Create a new [`CtfTracker`] that writes the binary stream of a trace to
+    /// a Writer. The stream must be placed in a directory with the
+    /// [`metadata`](CtfTracker::metadata) to be read.
+    pub fn from_stream_writer(entity_manager: EntityManager, mut writer: Writer) -> Self {
+        let mut header = Vec::with_capacity(8);
+        header.extend_from_slice(&CTF_MAGIC.to_le_bytes());
+        header.extend_from_slice(&0_u32.to_le_bytes());
+        writer.write_all(&header).unwrap();
+        Self {
+            entity_manager,
+            writer: Rc::new(RefCell::new(writer)),
+            time_ns: Cell::new(0.0),
+        }
+    }
+
+    /// Returns the description of the events of the trace.
+    #[must_use]
+    pub fn metadata() -> &'static str {
+        METADATA
+    }
+
+    /// Start an event, with its header
+    fn event(&self, event_id: EventId) -> CtfEvent {
+        let timestamp = (self.time_ns.get() * TICKS_PER_NS).round() as u64;
+        let mut bytes = Vec::with_capacity(64);
+        bytes.extend_from_slice(&(event_id as u32).to_le_bytes());
+        bytes.extend_from_slice(&timestamp.to_le_bytes());
+        CtfEvent(bytes)
+    }
+
+    fn write_event(&self, event: CtfEvent) {
+        self.writer.borrow_mut().write_all(&event.0).unwrap();
+    }
+}
+
+/// Implementation for each [`Track`] event
+impl Track for CtfTracker {
+    fn unique_id(&self) -> Id {
+        self.entity_manager.unique_id()
+    }
+
+    fn enabled_level(&self, id: Id) -> log::Level {
+        self.entity_manager.enabled_level(id)
+    }
+
+    fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
+        self.entity_manager.monitoring_window_size_for(id)
+    }
+
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.entity_manager.enabled_event_kinds(id)
+    }
+
+    fn add_entity(
+        &self,
+        id: Id,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> log::Level {
+        self.entity_manager
+            .add_entity(id, entity_name, alternative_names)
+    }
+
+    fn enter(&self, id: Id, object: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            self.write_event(self.event(EventId::Enter).id(id).id(object));
+        }
+    }
+
+    fn exit(&self, id: Id, object: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            self.write_event(self.event(EventId::Exit).id(id).id(object));
+        }
+    }
+
+    fn value(&self, id: Id, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_event(self.event(EventId::Value).id(id).f64(value));
+        }
+    }
+
+    fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
+        if self.is_event_enabled(lane, log::Level::Trace, EventKind::Activities) {
+            self.write_event(
+                self.event(EventId::BeginActivity)
+                    .id(activity)
+                    .id(lane)
+                    .string(name),
+            );
+        }
+    }
+
+    fn add_to_group(&self, activity: Id, group_id: Id) {
+        self.write_event(self.event(EventId::AddToGroup).id(activity).id(group_id));
+    }
+
+    fn remove_from_group(&self, activity: Id, group_id: Id) {
+        self.write_event(
+            self.event(EventId::RemoveFromGroup)
+                .id(activity)
+                .id(group_id),
+        );
+    }
+
+    fn end_activity(&self, activity: Id) {
+        self.write_event(self.event(EventId::EndActivity).id(activity));
+    }
+
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.write_event(self.event(EventId::SpanBegin).id(id).id(span).string(name));
+        }
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.write_event(self.event(EventId::SpanEnd).id(id).id(span));
+        }
+    }
+
+    fn create_entity(&self, created_by: Id, id: Id, name: &str) {
+        // Don't filter this event as it could be required by a GUI
+        self.write_event(
+            self.event(EventId::CreateEntity)
+                .id(created_by)
+                .id(id)
+                .string(name),
+        );
+    }
+
+    fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
+        // Don't filter this event as it could be required by a GUI
+        self.write_event(
+            self.event(EventId::CreateMonitor)
+                .id(created_by)
+                .id(id)
+                .string(name),
+        );
+    }
+
+    fn create_lane(&self, created_by: Id, id: Id, name: &str) {
+        // Don't filter this event as it could be required by a GUI
+        self.write_event(
+            self.event(EventId::CreateLane)
+                .id(created_by)
+                .id(id)
+                .string(name),
+        );
+    }
+
+    fn create_group(&self, created_by: Id, id: Id, name: &str) {
+        // Don't filter this event as it could be required by a GUI
+        self.write_event(
+            self.event(EventId::CreateGroup)
+                .id(created_by)
+                .id(id)
+                .string(name),
+        );
+    }
+
+    fn create_object(
+        &self,
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: &str,
+        req_type: u8,
+        details: &str,
+    ) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_event(
+                self.event(EventId::CreateObject)
+                    .id(created_by)
+                    .id(id)
+                    .u64(size as u64)
+                    .string(units)
+                    .u8(req_type)
+                    .string(details),
+            );
+        }
+    }
+
+    fn capacity(&self, id: Id, capacity: Capacity) {
+        // Don't filter this event as it could be required by a GUI
+        self.write_event(
+            self.event(EventId::Capacity)
+                .id(id)
+                .u64(capacity.value as u64)
+                .string(&capacity.units),
+        );
+    }
+
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Registers) {
+            self.write_event(
+                self.event(EventId::RegisterAccess)
+                    .id(id)
+                    .u8(u8::from(access.is_write))
+                    .u64(access.offset)
+                    .string(&access.view)
+                    .u64(access.old_value)
+                    .u64(access.new_value),
+            );
+        }
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_event(self.event(EventId::Counter).id(id).string(name).f64(delta));
+        }
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_event(
+                self.event(EventId::Histogram)
+                    .id(id)
+                    .string(name)
+                    .f64(value),
+            );
+        }
+    }
+
+    fn destroy(&self, destroyed_by: Id, id: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_event(self.event(EventId::Destroy).id(destroyed_by).id(id));
+        }
+    }
+
+    fn connect(&self, connect_from: Id, connect_to: Id) {
+        if self.is_event_enabled(connect_from, log::Level::Trace, EventKind::Lifecycle)
+            || self.is_event_enabled(connect_to, log::Level::Trace, EventKind::Lifecycle)
+        {
+            self.write_event(self.event(EventId::Connect).id(connect_from).id(connect_to));
+        }
+    }
+
+    fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
+            self.write_event(
+                self.event(EventId::Log)
+                    .id(id)
+                    .u8(level as u8)
+                    .string(&format!("{msg}")),
+            );
+        }
+    }
+
+    fn time(&self, _set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        self.time_ns.set(time_unit.convert(ticks, TimeUnit::Ns));
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.entity_manager.set_entity_level(regex_str, level)
+    }
+
+    fn flush(&self) {
+        self.writer.borrow_mut().flush().unwrap();
+    }
+
+    fn shutdown(&self) {
+        self.writer.borrow_mut().flush().unwrap();
+    }
+}
@@ -3,9 +3,10 @@
 use std::str::FromStr;
 
 use crate::Id;
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::tracker::Track;
 use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::TimeUnit;
 
 /// A tracker that does nothing.
 ///
@@ -35,9 +36,15 @@ impl Track for DevNullTracker {
     fn value(&self, _id: Id, _value: f64) {}
     fn begin_activity(&self, _activity: Id, _lane: Id, _name: &str) {}
     fn end_activity(&self, _activity: Id) {}
+    fn span_begin(&self, _id: Id, _span: Id, _name: &str) {}
+    fn span_end(&self, _id: Id, _span: Id) {}
     fn add_to_group(&self, _activity: Id, _group_id: Id) {}
     fn remove_from_group(&self, _activity: Id, _group_id: Id) {}
     fn capacity(&self, _id: Id, _capacity: Capacity) {}
+
+    fn register_access(&self, _id: Id, _access: RegisterAccess) {}
+    fn counter(&self, _id: Id, _name: &str, _delta: f64) {}
+    fn histogram(&self, _id: Id, _name: &str, _value: f64) {}
     fn create_entity(&self, _created_by: Id, _id: Id, _name: &str) {}
     fn create_monitor(&self, _created_by: Id, _id: Id, _name: &str) {}
     fn create_lane(&self, _created_by: Id, _id: Id, _name: &str) {}
@@ -55,7 +62,7 @@ impl Track for DevNullTracker {
     fn destroy(&self, _id: Id, _obj: Id) {}
     fn connect(&self, _connect_from: Id, _connect_to: Id) {}
     fn log(&self, _id: Id, _level: log::Level, _msg: std::fmt::Arguments) {}
-    fn time(&self, _set_by: Id, _time_ns: f64) {}
+    fn time(&self, _set_by: Id, _ticks: u64, _time_unit: TimeUnit) {}
     fn shutdown(&self) {}
 }
 
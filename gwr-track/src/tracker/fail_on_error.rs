@@ -0,0 +1,273 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A tracker that fails the simulation at shutdown if any errors were logged.
+//!
+//! Models can log errors and still run to completion, which is easy to miss in
+//! CI. The [`FailOnErrorTracker`] passes all events on to another tracker and
+//! remembers each error-level log message. When it is shut down it panics with
+//! a summary of those messages, so that the process exits with a failure.
+//!
+//! The messages can be limited to those whose `<entity>: <message>` text
+//! matches a regular expression.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::{mem, thread};
+
+use regex::Regex;
+
+use crate::Id;
+use crate::entity::{Capacity, RegisterAccess};
+use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{CallSite, EventKinds, TimeUnit};
+use crate::tracker::{Track, TrackConfigError, Tracker};
+
+/// A [`Tracker`] that passes all events on to another, and fails at shutdown
+/// if any errors were logged.
+pub struct FailOnErrorTracker {
+    tracker: Tracker,
+
+    /// Only errors matching this are recorded, when set.
+    regex: Option<Regex>,
+
+    /// The time of the most recent `time` event.
+    time_ns: Cell<f64>,
+
+    /// The full names of the entities, to describe the errors.
+    entity_names: RefCell<HashMap<Id, String>>,
+
+    /// A description of each error logged.
+    errors: RefCell<Vec<String>>,
+}
+
+impl FailOnErrorTracker {
+    /// Create a new [`FailOnErrorTracker`] that passes events on to `tracker`.
+    ///
+    /// If `regex_str` is not empty then only errors whose `<entity>: <message>`
+    /// text matches it cause a failure.
+    pub fn new(tracker: Tracker, regex_str: &str) -> Result<Self, TrackConfigError> {
+        let regex = if regex_str.is_empty() {
+            None
+        } else {
+            Some(Regex::new(regex_str).map_err(|e| {
+                TrackConfigError(format!("Failed to parse regex {regex_str}:\n{e}\n"))
+            })?)
+        };
+        Ok(Self {
+            tracker,
+            regex,
+            time_ns: Cell::new(0.0),
+            entity_names: RefCell::new(HashMap::new()),
+            errors: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Return a summary of the errors logged so far, or `None` if there have
+    /// been none.
+    #[must_use]
+    pub fn error_summary(&self) -> Option<String> {
+        let errors = self.errors.borrow();
+        if errors.is_empty() {
+            return None;
+        }
+        let mut summary = format!("{} error(s) logged during simulation:\n", errors.len());
+        for error in errors.iter() {
+            let _ = writeln!(summary, "  {error}");
+        }
+        Some(summary)
+    }
+
+    fn record(
+        &self,
+        id: Id,
+        level: log::Level,
+        call_site: Option<&CallSite>,
+        msg: std::fmt::Arguments,
+    ) {
+        if level != log::Level::Error {
+            return;
+        }
+        let text = match self.entity_names.borrow().get(&id) {
+            Some(name) => format!("{name}: {msg}"),
+            None => format!("{id}: {msg}"),
+        };
+        if let Some(regex) = &self.regex
+            && !regex.is_match(&text)
+        {
+            return;
+        }
+        let mut error = format!("{}ns {text}", self.time_ns.get());
+        if let Some(call_site) = call_site {
+            let _ = write!(error, " ({call_site})");
+        }
+        self.errors.borrow_mut().push(error);
+    }
+}
+
+impl Track for FailOnErrorTracker {
+    fn unique_id(&self) -> Id {
+        self.tracker.unique_id()
+    }
+
+    fn enabled_level(&self, id: Id) -> log::Level {
+        self.tracker.enabled_level(id)
+    }
+
+    fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
+        self.tracker.monitoring_window_size_for(id)
+    }
+
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.tracker.enabled_event_kinds(id)
+    }
+
+    fn add_entity(
+        &self,
+        id: Id,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> log::Level {
+        self.entity_names
+            .borrow_mut()
+            .insert(id, entity_name.to_string());
+        self.tracker.add_entity(id, entity_name, alternative_names)
+    }
+
+    fn enter(&self, id: Id, object: Id) {
+        self.tracker.enter(id, object);
+    }
+
+    fn exit(&self, id: Id, object: Id) {
+        self.tracker.exit(id, object);
+    }
+
+    fn value(&self, id: Id, value: f64) {
+        self.tracker.value(id, value);
+    }
+
+    fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
+        self.tracker.begin_activity(activity, lane, name);
+    }
+
+    fn add_to_group(&self, activity: Id, group_id: Id) {
+        self.tracker.add_to_group(activity, group_id);
+    }
+
+    fn remove_from_group(&self, activity: Id, group_id: Id) {
+        self.tracker.remove_from_group(activity, group_id);
+    }
+
+    fn end_activity(&self, activity: Id) {
+        self.tracker.end_activity(activity);
+    }
+
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        self.tracker.span_begin(id, span, name);
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        self.tracker.span_end(id, span);
+    }
+
+    fn create_entity(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_entity(created_by, id, name);
+    }
+
+    fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_monitor(created_by, id, name);
+    }
+
+    fn create_lane(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_lane(created_by, id, name);
+    }
+
+    fn create_group(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_group(created_by, id, name);
+    }
+
+    fn create_object(
+        &self,
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: &str,
+        req_type: u8,
+        details: &str,
+    ) {
+        self.tracker
+            .create_object(created_by, id, size, units, req_type, details);
+    }
+
+    fn capacity(&self, id: Id, capacity: Capacity) {
+        self.tracker.capacity(id, capacity);
+    }
+
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        self.tracker.register_access(id, access);
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        self.tracker.counter(id, name, delta);
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        self.tracker.histogram(id, name, value);
+    }
+
+    fn destroy(&self, destroyed_by: Id, id: Id) {
+        self.tracker.destroy(destroyed_by, id);
+    }
+
+    fn connect(&self, connect_from: Id, connect_to: Id) {
+        self.tracker.connect(connect_from, connect_to);
+    }
+
+    fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
+        self.record(id, level, None, msg);
+        self.tracker.log(id, level, msg);
+    }
+
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        self.record(id, level, Some(call_site), msg);
+        self.tracker.log_at(id, level, call_site, msg);
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        self.tracker.metadata(key, value);
+    }
+
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        self.time_ns.set(time_ns);
+        self.tracker.time(set_by, ticks, time_unit);
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.tracker.set_entity_level(regex_str, level)
+    }
+
+    fn wants_errors(&self) -> bool {
+        self.tracker.wants_errors()
+    }
+
+    fn flush(&self) {
+        self.tracker.flush();
+    }
+
+    fn shutdown(&self) {
+        // Make sure the trace is complete before failing
+        self.tracker.shutdown();
+
+        let Some(summary) = self.error_summary() else {
+            return;
+        };
+        // Only fail once, however many times the tracker is shut down
+        mem::take(&mut *self.errors.borrow_mut());
+        if thread::panicking() {
+            // Panicking again would abort without the summary
+            eprintln!("{summary}");
+        } else {
+            panic!("{summary}");
+        }
+    }
+}
@@ -0,0 +1,539 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A tracker that keeps the most recent events of each entity in memory.
+//!
+//! Full tracing of a long run is often impractical, but when something goes
+//! wrong the events leading up to it are what is needed. The
+//! [`FlightRecorderTracker`] keeps the last few events of each entity, and
+//! when an error is logged it replays them into another [`Tracker`], such as
+//! a [`TextTracker`](crate::tracker::TextTracker) or a
+//! [`CapnProtoTracker`](crate::tracker::CapnProtoTracker).
+//!
+//! The creation of entities, monitors and lanes is kept so that the names of
+//! the entities are part of each dump. Groups, activities and objects come and
+//! go as the simulation runs, so their events are kept with the entity that
+//! creates or ends them and are dropped along with its other old events.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::Id;
+use crate::entity::{Capacity, RegisterAccess};
+use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{CallSite, EventKind, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, Track, TrackConfigError, Tracker};
+
+/// The format that a [`FlightRecorderTracker`] dumps its events in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlightRecorderFormat {
+    /// Text, as written by the [`TextTracker`](crate::tracker::TextTracker).
+    #[default]
+    Text,
+
+    /// Cap'n Proto, as written by the
+    /// [`CapnProtoTracker`](crate::tracker::CapnProtoTracker).
+    Capnp,
+}
+
+/// A copy of a single [`Track`] event.
+enum Event {
+    Enter(Id, Id),
+    Exit(Id, Id),
+    Value(Id, f64),
+    CreateEntity(Id, Id, String),
+    CreateMonitor(Id, Id, String),
+    CreateLane(Id, Id, String),
+    CreateGroup(Id, Id, String),
+    AddToGroup(Id, Id),
+    RemoveFromGroup(Id, Id),
+    BeginActivity(Id, Id, String),
+    EndActivity(Id),
+    SpanBegin(Id, Id, String),
+    SpanEnd(Id, Id),
+    CreateObject {
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: String,
+        req_type: u8,
+        details: String,
+    },
+    Capacity(Id, Capacity),
+    RegisterAccess(Id, RegisterAccess),
+    Counter(Id, String, f64),
+    Histogram(Id, String, f64),
+    Destroy(Id, Id),
+    Connect(Id, Id),
+    Log(Id, log::Level, Option<OwnedCallSite>, String),
+}
+
+/// A copy of the [`CallSite`] of a log message.
+struct OwnedCallSite {
+    module_path: String,
+    file: String,
+    line: u32,
+}
+
+impl OwnedCallSite {
+    fn new(call_site: &CallSite) -> Self {
+        Self {
+            module_path: call_site.module_path.to_string(),
+            file: call_site.file.to_string(),
+            line: call_site.line,
+        }
+    }
+
+    fn as_call_site(&self) -> CallSite<'_> {
+        CallSite {
+            module_path: &self.module_path,
+            file: &self.file,
+            line: self.line,
+        }
+    }
+}
+
+impl Event {
+    fn kind(&self) -> EventKind {
+        match self {
+            Event::Enter(..) | Event::Exit(..) => EventKind::Movement,
+            Event::Value(..) | Event::Capacity(..) | Event::Counter(..) | Event::Histogram(..) => {
+                EventKind::Values
+            }
+            Event::AddToGroup(..)
+            | Event::RemoveFromGroup(..)
+            | Event::BeginActivity(..)
+            | Event::EndActivity(..)
+            | Event::SpanBegin(..)
+            | Event::SpanEnd(..) => EventKind::Activities,
+            Event::CreateEntity(..)
+            | Event::CreateMonitor(..)
+            | Event::CreateLane(..)
+            | Event::CreateGroup(..)
+            | Event::CreateObject { .. }
+            | Event::Destroy(..)
+            | Event::Connect(..) => EventKind::Lifecycle,
+            Event::RegisterAccess(..) => EventKind::Registers,
+            Event::Log(..) => EventKind::Messages,
+        }
+    }
+
+    /// Pass this event on to `tracker`.
+    fn replay(&self, tracker: &Tracker) {
+        match self {
+            Event::Enter(id, object) => tracker.enter(*id, *object),
+            Event::Exit(id, object) => tracker.exit(*id, *object),
+            Event::Value(id, value) => tracker.value(*id, *value),
+            Event::CreateEntity(created_by, id, name) => {
+                tracker.create_entity(*created_by, *id, name);
+            }
+            Event::CreateMonitor(created_by, id, name) => {
+                tracker.create_monitor(*created_by, *id, name);
+            }
+            Event::CreateLane(created_by, id, name) => {
+                tracker.create_lane(*created_by, *id, name);
+            }
+            Event::CreateGroup(created_by, id, name) => {
+                tracker.create_group(*created_by, *id, name);
+            }
+            Event::AddToGroup(activity, group_id) => tracker.add_to_group(*activity, *group_id),
+            Event::RemoveFromGroup(activity, group_id) => {
+                tracker.remove_from_group(*activity, *group_id);
+            }
+            Event::BeginActivity(activity, lane, name) => {
+                tracker.begin_activity(*activity, *lane, name);
+            }
+            Event::EndActivity(activity) => tracker.end_activity(*activity),
+            Event::SpanBegin(id, span, name) => tracker.span_begin(*id, *span, name),
+            Event::SpanEnd(id, span) => tracker.span_end(*id, *span),
+            Event::CreateObject {
+                created_by,
+                id,
+                size,
+                units,
+                req_type,
+                details,
+            } => tracker.create_object(*created_by, *id, *size, units, *req_type, details),
+            Event::Capacity(id, capacity) => tracker.capacity(*id, capacity.clone()),
+            Event::RegisterAccess(id, access) => tracker.register_access(*id, access.clone()),
+            Event::Counter(id, name, delta) => tracker.counter(*id, name, *delta),
+            Event::Histogram(id, name, value) => tracker.histogram(*id, name, *value),
+            Event::Destroy(destroyed_by, id) => tracker.destroy(*destroyed_by, *id),
+            Event::Connect(connect_from, connect_to) => tracker.connect(*connect_from, *connect_to),
+            Event::Log(id, level, None, message) => {
+                tracker.log(*id, *level, format_args!("{message}"));
+            }
+            Event::Log(id, level, Some(call_site), message) => {
+                tracker.log_at(
+                    *id,
+                    *level,
+                    &call_site.as_call_site(),
+                    format_args!("{message}"),
+                );
+            }
+        }
+    }
+}
+
+/// The number of creation events that are kept until the end of the
+/// simulation. Any later creations are kept with the other events of their
+/// creator.
+const MAX_CREATED: usize = 1 << 16;
+
+/// An event and when it happened.
+struct Recorded {
+    /// The order of the event, to restore it when the events of all entities
+    /// are merged.
+    seq: u64,
+
+    /// The entity the event is kept for.
+    id: Id,
+
+    /// The time of the event, in ticks of a unit.
+    time: (u64, TimeUnit),
+    event: Event,
+}
+
+/// A tracker that keeps the last events of each entity and dumps them when an
+/// error is logged.
+pub struct FlightRecorderTracker {
+    entity_manager: EntityManager,
+
+    /// The number of events kept for each entity.
+    events_per_entity: usize,
+
+    /// The tracker that events are dumped to.
+    dump_to: Tracker,
+
+    /// The time of the most recent `time` event.
+    time: Cell<(u64, TimeUnit)>,
+
+    next_seq: Cell<u64>,
+
+    /// The creation events, which are never dropped.
+    created: RefCell<Vec<Recorded>>,
+
+    /// The most recent events of each entity.
+    recent: RefCell<HashMap<Id, VecDeque<Recorded>>>,
+
+    /// The lane of each activity that has begun and not yet ended, so that
+    /// the events of the activity are kept with those of its lane.
+    activity_lanes: RefCell<HashMap<Id, Id>>,
+}
+
+impl FlightRecorderTracker {
+    /// Create a new [`FlightRecorderTracker`] that keeps up to
+    /// `events_per_entity` events for each entity and dumps them to
+    /// `dump_to`.
+    pub fn new(entity_manager: EntityManager, events_per_entity: usize, dump_to: Tracker) -> Self {
+        Self {
+            entity_manager,
+            events_per_entity: events_per_entity.max(1),
+            dump_to,
+            time: Cell::new((0, TimeUnit::default())),
+            next_seq: Cell::new(0),
+            created: RefCell::new(Vec::new()),
+            recent: RefCell::new(HashMap::new()),
+            activity_lanes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn recorded(&self, id: Id, event: Event) -> Recorded {
+        let seq = self.next_seq.get();
+        self.next_seq.set(seq + 1);
+        Recorded {
+            seq,
+            id,
+            time: self.time.get(),
+            event,
+        }
+    }
+
+    /// Keep the creation of an entity until the end of the simulation, unless
+    /// too many have already been kept.
+    fn record_created(&self, created_by: Id, event: Event) {
+        if self.created.borrow().len() == MAX_CREATED {
+            self.record(created_by, log::Level::Trace, event);
+        } else if self.is_event_enabled(created_by, log::Level::Trace, event.kind()) {
+            let recorded = self.recorded(created_by, event);
+            self.created.borrow_mut().push(recorded);
+        }
+    }
+
+    /// The entity whose events the events of `activity` are kept with.
+    fn lane_of(&self, activity: Id) -> Id {
+        self.activity_lanes
+            .borrow()
+            .get(&activity)
+            .copied()
+            .unwrap_or(activity)
+    }
+
+    /// Keep an event for `id`, dropping its oldest event if it has too many.
+    fn record(&self, id: Id, level: log::Level, event: Event) {
+        if self.is_event_enabled(id, level, event.kind()) {
+            let recorded = self.recorded(id, event);
+            let mut recent = self.recent.borrow_mut();
+            let events = recent.entry(id).or_default();
+            if events.len() == self.events_per_entity {
+                events.pop_front();
+            }
+            events.push_back(recorded);
+        }
+    }
+
+    /// Keep a log message, and dump the kept events if it is an error.
+    fn record_log(
+        &self,
+        id: Id,
+        level: log::Level,
+        call_site: Option<&CallSite>,
+        msg: std::fmt::Arguments,
+    ) {
+        let call_site = call_site.map(OwnedCallSite::new);
+        self.record(id, level, Event::Log(id, level, call_site, msg.to_string()));
+        if level == log::Level::Error {
+            self.dump();
+        }
+    }
+
+    /// Write the kept events of all entities, in the order they happened.
+    ///
+    /// The events of each entity are cleared so that a later dump only
+    /// contains the events that follow this one. The output is flushed, but
+    /// is only ended when this tracker is shut down.
+    pub fn dump(&self) {
+        let recent: Vec<Recorded> = self
+            .recent
+            .borrow_mut()
+            .drain()
+            .flat_map(|(_, events)| events)
+            .collect();
+        let created = self.created.borrow();
+        let mut events: Vec<&Recorded> = created.iter().chain(recent.iter()).collect();
+        events.sort_by_key(|recorded| recorded.seq);
+
+        let mut time = None;
+        for recorded in events {
+            if time != Some(recorded.time) {
+                time = Some(recorded.time);
+                let (ticks, time_unit) = recorded.time;
+                self.dump_to.time(recorded.id, ticks, time_unit);
+            }
+            recorded.event.replay(&self.dump_to);
+        }
+        self.dump_to.flush();
+    }
+}
+
+impl Track for FlightRecorderTracker {
+    fn unique_id(&self) -> Id {
+        self.entity_manager.unique_id()
+    }
+
+    fn enabled_level(&self, id: Id) -> log::Level {
+        self.entity_manager.enabled_level(id)
+    }
+
+    fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
+        self.entity_manager.monitoring_window_size_for(id)
+    }
+
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.entity_manager.enabled_event_kinds(id)
+    }
+
+    fn add_entity(
+        &self,
+        id: Id,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> log::Level {
+        self.entity_manager
+            .add_entity(id, entity_name, alternative_names)
+    }
+
+    fn enter(&self, id: Id, object: Id) {
+        self.record(id, log::Level::Trace, Event::Enter(id, object));
+    }
+
+    fn exit(&self, id: Id, object: Id) {
+        self.record(id, log::Level::Trace, Event::Exit(id, object));
+    }
+
+    fn value(&self, id: Id, value: f64) {
+        self.record(id, log::Level::Trace, Event::Value(id, value));
+    }
+
+    fn add_to_group(&self, activity: Id, group_id: Id) {
+        self.record(
+            self.lane_of(activity),
+            log::Level::Trace,
+            Event::AddToGroup(activity, group_id),
+        );
+    }
+
+    fn remove_from_group(&self, activity: Id, group_id: Id) {
+        self.record(
+            self.lane_of(activity),
+            log::Level::Trace,
+            Event::RemoveFromGroup(activity, group_id),
+        );
+    }
+
+    fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
+        self.activity_lanes.borrow_mut().insert(activity, lane);
+        self.record(
+            lane,
+            log::Level::Trace,
+            Event::BeginActivity(activity, lane, name.to_string()),
+        );
+    }
+
+    fn end_activity(&self, activity: Id) {
+        let lane = self
+            .activity_lanes
+            .borrow_mut()
+            .remove(&activity)
+            .unwrap_or(activity);
+        self.record(lane, log::Level::Trace, Event::EndActivity(activity));
+    }
+
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        self.record(
+            id,
+            log::Level::Trace,
+            Event::SpanBegin(id, span, name.to_string()),
+        );
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        self.record(id, log::Level::Trace, Event::SpanEnd(id, span));
+    }
+
+    fn create_entity(&self, created_by: Id, id: Id, name: &str) {
+        self.record_created(
+            created_by,
+            Event::CreateEntity(created_by, id, name.to_string()),
+        );
+    }
+
+    fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
+        self.record_created(
+            created_by,
+            Event::CreateMonitor(created_by, id, name.to_string()),
+        );
+    }
+
+    fn create_lane(&self, created_by: Id, id: Id, name: &str) {
+        self.record_created(
+            created_by,
+            Event::CreateLane(created_by, id, name.to_string()),
+        );
+    }
+
+    fn create_group(&self, created_by: Id, id: Id, name: &str) {
+        self.record(
+            created_by,
+            log::Level::Trace,
+            Event::CreateGroup(created_by, id, name.to_string()),
+        );
+    }
+
+    fn create_object(
+        &self,
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: &str,
+        req_type: u8,
+        details: &str,
+    ) {
+        self.record(
+            created_by,
+            log::Level::Trace,
+            Event::CreateObject {
+                created_by,
+                id,
+                size,
+                units: units.to_string(),
+                req_type,
+                details: details.to_string(),
+            },
+        );
+    }
+
+    fn capacity(&self, id: Id, capacity: Capacity) {
+        self.record(id, log::Level::Trace, Event::Capacity(id, capacity));
+    }
+
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        self.record(id, log::Level::Trace, Event::RegisterAccess(id, access));
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        self.record(
+            id,
+            log::Level::Trace,
+            Event::Counter(id, name.to_string(), delta),
+        );
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        self.record(
+            id,
+            log::Level::Trace,
+            Event::Histogram(id, name.to_string(), value),
+        );
+    }
+
+    fn destroy(&self, destroyed_by: Id, id: Id) {
+        // Nothing more happens to the object, so any events kept for it go
+        self.recent.borrow_mut().remove(&id);
+        self.record(
+            destroyed_by,
+            log::Level::Trace,
+            Event::Destroy(destroyed_by, id),
+        );
+    }
+
+    fn connect(&self, connect_from: Id, connect_to: Id) {
+        self.record(
+            connect_from,
+            log::Level::Trace,
+            Event::Connect(connect_from, connect_to),
+        );
+    }
+
+    fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
+        self.record_log(id, level, None, msg);
+    }
+
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        self.record_log(id, level, Some(call_site), msg);
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        // Written straight away so that it is at the head of the dumps
+        self.dump_to.metadata(key, value);
+    }
+
+    fn time(&self, _set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        self.time.set((ticks, time_unit));
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.entity_manager.set_entity_level(regex_str, level)
+    }
+
+    fn wants_errors(&self) -> bool {
+        true
+    }
+
+    fn shutdown(&self) {
+        self.dump_to.shutdown();
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A tracker that writes one JSON object per line for each event.
+//!
+//! Each object has the fields:
+//!
+//!  - `id`: the [`Id`] of the entity or object that the event is for
+//!  - `time_ns`: the simulation time of the event
+//!  - `level`: the level of a log message, or `TRACE` for trace events
+//!  - `kind`: the kind of event, such as `log`, `enter` or `create_entity`, or
+//!    `metadata` for the metadata of the run
+//!  - `message`: the text of a log message, or a description of a trace event
+//!  - `entity`: the full name of the entity, when the event is for an entity
+//!  - `file` and `line`: where a log message was written from, when known
+//!
+//! so traces can be read by tools such as `jq` or `pandas` without Cap'n
+//! Proto. Changes of time are not written as events of their own, but set the
+//! `time_ns` of the events that follow.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::Serialize;
+
+use crate::entity::{Capacity, RegisterAccess};
+use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{CallSite, EventKind, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, Track, TrackConfigError};
+use crate::{Id, NO_ID, SharedWriter, Writer};
+
+/// A single line of the output
+#[derive(Serialize)]
+struct JsonEvent<'a> {
+    id: u64,
+    time_ns: f64,
+    level: &'a str,
+    kind: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+}
+
+/// A tracker that writes JSON Lines to a Writer.
+pub struct JsonLinesTracker {
+    entity_manager: EntityManager,
+    writer: SharedWriter,
+
+    /// The time of the most recent `time` event.
+    time_ns: Cell<f64>,
+
+    /// The full names of the entities, for the `entity` field.
+    entity_names: RefCell<HashMap<Id, String>>,
+}
+
+impl JsonLinesTracker {
+    /// Create a new [`JsonLinesTracker`] with an [`EntityManager`].
+    pub fn new(entity_manager: EntityManager, writer: Writer) -> Self {
+        Self {
+            entity_manager,
+            writer: Rc::new(RefCell::new(writer)),
+            time_ns: Cell::new(0.0),
+            entity_names: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn write_event(
+        &self,
+        id: Id,
+        level: log::Level,
+        kind: &str,
+        message: &str,
+        call_site: Option<&CallSite>,
+    ) {
+        let entity_names = self.entity_names.borrow();
+        let event = JsonEvent {
+            id: id.0,
+            time_ns: self.time_ns.get(),
+            level: level.as_str(),
+            kind,
+            message,
+            entity: entity_names.get(&id).map(String::as_str),
+            file: call_site.map(|call_site| call_site.file),
+            line: call_site.map(|call_site| call_site.line),
+        };
+        // Each line is a single write so that it is never split by a
+        // BackgroundWriter
+        let mut line = serde_json::to_vec(&event).unwrap();
+        line.push(b'\n');
+        self.writer.borrow_mut().write_all(&line).unwrap();
+    }
+
+    fn write_trace_event(&self, id: Id, kind: &str, message: &str) {
+        self.write_event(id, log::Level::Trace, kind, message, None);
+    }
+}
+
+/// Implementation for each [`Track`] event
+impl Track for JsonLinesTracker {
+    fn unique_id(&self) -> Id {
+        self.entity_manager.unique_id()
+    }
+
+    fn enabled_level(&self, id: Id) -> log::Level {
+        self.entity_manager.enabled_level(id)
+    }
+
+    fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
+        self.entity_manager.monitoring_window_size_for(id)
+    }
+
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.entity_manager.enabled_event_kinds(id)
+    }
+
+    fn add_entity(
+        &self,
+        id: Id,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> log::Level {
+        self.entity_names
+            .borrow_mut()
+            .insert(id, entity_name.to_string());
+        self.entity_manager
+            .add_entity(id, entity_name, alternative_names)
+    }
+
+    fn enter(&self, id: Id, object: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            self.write_trace_event(id, "enter", &format!("enter {object}"));
+        }
+    }
+
+    fn exit(&self, id: Id, object: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            self.write_trace_event(id, "exit", &format!("exit {object}"));
+        }
+    }
+
+    fn value(&self, id: Id, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_trace_event(id, "value", &format!("value {value}"));
+        }
+    }
+
+    fn add_to_group(&self, activity: Id, group_id: Id) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
+            self.write_trace_event(
+                activity,
+                "add_to_group",
+                &format!("added to group {group_id}"),
+            );
+        }
+    }
+
+    fn remove_from_group(&self, activity: Id, group_id: Id) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
+            self.write_trace_event(
+                activity,
+                "remove_from_group",
+                &format!("removed from group {group_id}"),
+            );
+        }
+    }
+
+    fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
+        if self.is_event_enabled(lane, log::Level::Trace, EventKind::Activities) {
+            self.write_trace_event(
+                activity,
+                "begin_activity",
+                &format!("activity begin {name} on lane {lane}"),
+            );
+        }
+    }
+
+    fn end_activity(&self, activity: Id) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
+            self.write_trace_event(activity, "end_activity", "activity end");
+        }
+    }
+
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.write_trace_event(id, "span_begin", &format!("span {span} begin {name}"));
+        }
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.write_trace_event(id, "span_end", &format!("span {span} end"));
+        }
+    }
+
+    fn create_entity(&self, created_by: Id, id: Id, name: &str) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_trace_event(
+                created_by,
+                "create_entity",
+                &format!("created entity {id}, {name}"),
+            );
+        }
+    }
+
+    fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_trace_event(
+                created_by,
+                "create_monitor",
+                &format!("created monitor {id}, {name}"),
+            );
+        }
+    }
+
+    fn create_lane(&self, created_by: Id, id: Id, name: &str) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_trace_event(
+                created_by,
+                "create_lane",
+                &format!("created lane {id}, {name}"),
+            );
+        }
+    }
+
+    fn create_group(&self, created_by: Id, id: Id, name: &str) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_trace_event(
+                created_by,
+                "create_group",
+                &format!("created group {id}, {name}"),
+            );
+        }
+    }
+
+    fn create_object(
+        &self,
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: &str,
+        req_type: u8,
+        details: &str,
+    ) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_trace_event(
+                created_by,
+                "create_object",
+                &format!("created object {id}, {req_type}, {size}, {units}, {details}"),
+            );
+        }
+    }
+
+    fn capacity(&self, id: Id, capacity: Capacity) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_trace_event(
+                id,
+                "capacity",
+                &format!("capacity {} {}", capacity.value, capacity.units),
+            );
+        }
+    }
+
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Registers) {
+            self.write_trace_event(id, "register_access", &format!("{access}"));
+        }
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_trace_event(id, "counter", &format!("counter {name} {delta}"));
+        }
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_trace_event(id, "histogram", &format!("histogram {name} {value}"));
+        }
+    }
+
+    fn destroy(&self, destroyed_by: Id, id: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_trace_event(destroyed_by, "destroy", &format!("destroyed {id}"));
+        }
+    }
+
+    fn connect(&self, connect_from: Id, connect_to: Id) {
+        if self.is_event_enabled(connect_from, log::Level::Trace, EventKind::Lifecycle)
+            || self.is_event_enabled(connect_to, log::Level::Trace, EventKind::Lifecycle)
+        {
+            self.write_trace_event(connect_from, "connect", &format!("connect to {connect_to}"));
+        }
+    }
+
+    fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
+            self.write_event(id, level, "log", &format!("{msg}"), None);
+        }
+    }
+
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
+            self.write_event(id, level, "log", &format!("{msg}"), Some(call_site));
+        }
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        self.write_event(
+            NO_ID,
+            log::Level::Info,
+            "metadata",
+            &format!("{key}: {value}"),
+            None,
+        );
+    }
+
+    fn time(&self, _set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        self.time_ns.set(time_unit.convert(ticks, TimeUnit::Ns));
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.entity_manager.set_entity_level(regex_str, level)
+    }
+
+    fn flush(&self) {
+        self.writer.borrow_mut().flush().unwrap();
+    }
+
+    fn shutdown(&self) {
+        self.writer.borrow_mut().flush().unwrap();
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A tracker that changes entity levels from a control file while the
+//! simulation runs.
+//!
+//! Each line of the control file gives a regular expression and a level:
+//!
+//! ```text
+//! # Trace node 3 of the fabric
+//! .*fabric::node3.* trace
+//! ```
+//!
+//! The file is read again whenever it is modified, and each line is applied
+//! with [`Track::set_entity_level`], with later lines taking priority. Blank
+//! lines and lines starting with `#` are ignored.
+
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::Id;
+use crate::entity::{Capacity, RegisterAccess};
+use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{CallSite, EventKinds, TimeUnit};
+use crate::tracker::{Track, TrackConfigError, Tracker};
+
+/// The number of `time` events between checks of the wall-clock time.
+const TIME_EVENTS_PER_CHECK: u64 = 256;
+
+/// The minimum wall-clock time between reads of the control file.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A [`Tracker`] that passes all events on to another, and applies the levels
+/// in a control file to it as the simulation runs.
+pub struct LevelControlTracker {
+    tracker: Tracker,
+    control_file: PathBuf,
+
+    /// The number of `time` events seen since the last check.
+    time_events: Cell<u64>,
+
+    /// When the control file was last checked.
+    last_poll: Cell<Instant>,
+
+    /// The modification time of the control file when it was last read.
+    last_modified: RefCell<Option<SystemTime>>,
+}
+
+impl LevelControlTracker {
+    /// Create a new [`LevelControlTracker`] that applies the levels in
+    /// `control_file` to `tracker`.
+    ///
+    /// The control file is read immediately if it exists, so that any errors
+    /// in it are reported.
+    pub fn new(tracker: Tracker, control_file: PathBuf) -> Result<Self, TrackConfigError> {
+        let level_control = Self {
+            tracker,
+            control_file,
+            time_events: Cell::new(0),
+            last_poll: Cell::new(Instant::now()),
+            last_modified: RefCell::new(None),
+        };
+        level_control.apply_if_modified()?;
+        Ok(level_control)
+    }
+
+    /// Read the control file again if it has changed since it was last read.
+    pub fn apply_if_modified(&self) -> Result<(), TrackConfigError> {
+        let Ok(modified) = fs::metadata(&self.control_file).and_then(|m| m.modified()) else {
+            // The control file does not need to exist until it is wanted
+            return Ok(());
+        };
+        if self.last_modified.borrow().as_ref() == Some(&modified) {
+            return Ok(());
+        }
+        *self.last_modified.borrow_mut() = Some(modified);
+
+        let contents = fs::read_to_string(&self.control_file).map_err(|e| {
+            TrackConfigError(format!(
+                "Failed to read {}:\n{e}\n",
+                self.control_file.display()
+            ))
+        })?;
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((regex_str, level_str)) = line.rsplit_once(char::is_whitespace) else {
+                return Err(TrackConfigError(format!(
+                    "Expected '<regex> <level>' in {} but found '{line}'",
+                    self.control_file.display()
+                )));
+            };
+            let level = log::Level::from_str(level_str).map_err(|_| {
+                TrackConfigError(format!(
+                    "Unknown level '{level_str}' in {}",
+                    self.control_file.display()
+                ))
+            })?;
+            self.tracker.set_entity_level(regex_str.trim_end(), level)?;
+        }
+        Ok(())
+    }
+
+    fn poll(&self) {
+        let time_events = self.time_events.get() + 1;
+        if time_events < TIME_EVENTS_PER_CHECK {
+            self.time_events.set(time_events);
+            return;
+        }
+        self.time_events.set(0);
+
+        let now = Instant::now();
+        if now.duration_since(self.last_poll.get()) < POLL_INTERVAL {
+            return;
+        }
+        self.last_poll.set(now);
+
+        if let Err(TrackConfigError(e)) = self.apply_if_modified() {
+            eprintln!("WARNING: {e}");
+        }
+    }
+}
+
+impl Track for LevelControlTracker {
+    fn unique_id(&self) -> Id {
+        self.tracker.unique_id()
+    }
+
+    fn enabled_level(&self, id: Id) -> log::Level {
+        self.tracker.enabled_level(id)
+    }
+
+    fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
+        self.tracker.monitoring_window_size_for(id)
+    }
+
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.tracker.enabled_event_kinds(id)
+    }
+
+    fn add_entity(
+        &self,
+        id: Id,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> log::Level {
+        self.tracker.add_entity(id, entity_name, alternative_names)
+    }
+
+    fn enter(&self, id: Id, object: Id) {
+        self.tracker.enter(id, object);
+    }
+
+    fn exit(&self, id: Id, object: Id) {
+        self.tracker.exit(id, object);
+    }
+
+    fn value(&self, id: Id, value: f64) {
+        self.tracker.value(id, value);
+    }
+
+    fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
+        self.tracker.begin_activity(activity, lane, name);
+    }
+
+    fn add_to_group(&self, activity: Id, group_id: Id) {
+        self.tracker.add_to_group(activity, group_id);
+    }
+
+    fn remove_from_group(&self, activity: Id, group_id: Id) {
+        self.tracker.remove_from_group(activity, group_id);
+    }
+
+    fn end_activity(&self, activity: Id) {
+        self.tracker.end_activity(activity);
+    }
+
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        self.tracker.span_begin(id, span, name);
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        self.tracker.span_end(id, span);
+    }
+
+    fn create_entity(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_entity(created_by, id, name);
+    }
+
+    fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_monitor(created_by, id, name);
+    }
+
+    fn create_lane(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_lane(created_by, id, name);
+    }
+
+    fn create_group(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_group(created_by, id, name);
+    }
+
+    fn create_object(
+        &self,
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: &str,
+        req_type: u8,
+        details: &str,
+    ) {
+        self.tracker
+            .create_object(created_by, id, size, units, req_type, details);
+    }
+
+    fn capacity(&self, id: Id, capacity: Capacity) {
+        self.tracker.capacity(id, capacity);
+    }
+
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        self.tracker.register_access(id, access);
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        self.tracker.counter(id, name, delta);
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        self.tracker.histogram(id, name, value);
+    }
+
+    fn destroy(&self, destroyed_by: Id, id: Id) {
+        self.tracker.destroy(destroyed_by, id);
+    }
+
+    fn connect(&self, connect_from: Id, connect_to: Id) {
+        self.tracker.connect(connect_from, connect_to);
+    }
+
+    fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
+        self.tracker.log(id, level, msg);
+    }
+
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        self.tracker.log_at(id, level, call_site, msg);
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        self.tracker.metadata(key, value);
+    }
+
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        self.poll();
+        self.tracker.time(set_by, ticks, time_unit);
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.tracker.set_entity_level(regex_str, level)
+    }
+
+    fn wants_errors(&self) -> bool {
+        self.tracker.wants_errors()
+    }
+
+    fn flush(&self) {
+        self.tracker.flush();
+    }
+
+    fn shutdown(&self) {
+        self.tracker.shutdown();
+    }
+}
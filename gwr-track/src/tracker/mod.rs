@@ -6,13 +6,27 @@
 pub mod aka;
 /// Include the CapnProto tracker.
 pub mod capnp;
+/// Include the Common Trace Format tracker.
+pub mod ctf;
 /// Include the /dev/null tracker.
 pub mod dev_null;
+/// Include the tracker that fails if errors were logged.
+pub mod fail_on_error;
+/// Include the flight-recorder tracker.
+pub mod flight_recorder;
+/// Include the JSON Lines tracker.
+pub mod json_lines;
+/// Include the tracker that reads entity levels from a control file.
+pub mod level_control;
 /// Include the Perfetto tracker.
 #[cfg(feature = "perfetto")]
 pub mod perfetto;
+/// Include the tracker that splits its output into shards.
+pub mod sharded;
 /// Include the text-based tracker.
 pub mod text;
+/// Include the tracker that throttles repeated messages.
+pub mod throttle;
 /// Include the types required for tracker.
 pub mod types;
 
@@ -21,22 +35,49 @@ pub mod multi_tracker;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::io;
+use std::error::Error;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{fmt, io};
 
 pub use capnp::CapnProtoTracker;
+pub use ctf::CtfTracker;
 pub use dev_null::DevNullTracker;
+pub use fail_on_error::FailOnErrorTracker;
+pub use flight_recorder::FlightRecorderTracker;
+pub use json_lines::JsonLinesTracker;
+pub use level_control::LevelControlTracker;
 use regex::Regex;
+pub use sharded::ShardedTracker;
 pub use text::TextTracker;
+pub use throttle::ThrottleTracker;
 
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{CallSite, EventKind, EventKinds, TimeUnit};
 use crate::{Id, ROOT};
 
 /// Error used to return configuration errors
 #[derive(Debug)]
 pub struct TrackConfigError(pub String);
 
+impl fmt::Display for TrackConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for TrackConfigError {}
+
+/// Counts the changes of entity levels made while the simulation runs, so that
+/// [`Entity`](crate::entity::Entity) can tell when its cached level is stale.
+static LEVELS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Return the number of changes of entity levels made so far.
+pub(crate) fn levels_generation() -> u64 {
+    LEVELS_GENERATION.load(Ordering::Relaxed)
+}
+
 /// This is the interface that is supported by all [`Tracker`]s.
 pub trait Track {
     /// Allocate a new global ID
@@ -50,6 +91,18 @@ pub trait Track {
         level <= self.enabled_level(id)
     }
 
+    /// Determine which kinds of event are enabled for an entity.
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        let _ = id;
+        EventKinds::all()
+    }
+
+    /// Determine whether an event of the given kind is enabled at a given
+    /// level for an entity.
+    fn is_event_enabled(&self, id: Id, level: log::Level, kind: EventKind) -> bool {
+        self.is_entity_enabled(id, level) && self.enabled_event_kinds(id).contains(kind)
+    }
+
     /// Return the monitoring window size if it is to be enabled.
     /// Entity looked up by its ID.
     fn monitoring_window_size_for(&self, id: Id) -> Option<u64>;
@@ -95,6 +148,16 @@ pub trait Track {
     /// Track the end of the current activity on a lane.
     fn end_activity(&self, activity: Id);
 
+    /// Track the beginning of a named span of time of an entity, which is
+    /// identified by `span` until it ends.
+    ///
+    /// Unlike [`Track::enter`], a span need not be of an object contained by
+    /// the entity. The spans of an entity are expected to nest.
+    fn span_begin(&self, id: Id, span: Id, name: &str);
+
+    /// Track the end of a span begun with [`Track::span_begin`].
+    fn span_end(&self, id: Id, span: Id);
+
     /// Track when an object with the given ID is created.
     fn create_object(
         &self,
@@ -109,6 +172,15 @@ pub trait Track {
     /// Track the capacity available in an entity.
     fn capacity(&self, id: Id, capacity: Capacity);
 
+    /// Track a register read or write made through an entity.
+    fn register_access(&self, id: Id, access: RegisterAccess);
+
+    /// Track a change of `delta` in the named counter of an entity.
+    fn counter(&self, id: Id, name: &str, delta: f64);
+
+    /// Track a sample of `value` for the named histogram of an entity.
+    fn histogram(&self, id: Id, name: &str, value: f64);
+
     /// Track when an entity with the given ID is destroyed.
     fn destroy(&self, destroyed_by: Id, destroyed_obj: Id);
 
@@ -118,8 +190,57 @@ pub trait Track {
     /// Track a log message of the given level.
     fn log(&self, msg_by: Id, level: log::Level, msg: std::fmt::Arguments);
 
-    /// Advance the time to the time specified in `ns`.
-    fn time(&self, set_by: Id, time_ns: f64);
+    /// Track a log message of the given level written from `call_site`.
+    ///
+    /// Trackers that do not record where messages come from drop the
+    /// `call_site` and track the message as [`Track::log`] does.
+    fn log_at(
+        &self,
+        msg_by: Id,
+        level: log::Level,
+        call_site: &CallSite,
+        msg: std::fmt::Arguments,
+    ) {
+        let _ = call_site;
+        self.log(msg_by, level, msg);
+    }
+
+    /// Track a piece of metadata about the run, such as the command line.
+    ///
+    /// Metadata is given before the simulation starts, so that it is at the
+    /// head of the output. Trackers that cannot record it ignore it.
+    fn metadata(&self, key: &str, value: &str) {
+        let _ = (key, value);
+    }
+
+    /// Advance the time to `ticks` of `time_unit`.
+    ///
+    /// Trackers write the time in their own [`TimeUnit`], so the time is only
+    /// converted by trackers that write a different unit.
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit);
+
+    /// Change the level of all entities whose names match `regex_str`, both
+    /// those that already exist and those created later.
+    ///
+    /// This can be called while the simulation runs, so that detailed tracking
+    /// is only enabled once the interesting behaviour starts. Trackers that do
+    /// not filter by entity ignore this.
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        let _ = (regex_str, level);
+        Ok(())
+    }
+
+    /// Whether errors that end the simulation should be logged to this
+    /// tracker.
+    ///
+    /// Most trackers only see the errors that models log themselves, but some,
+    /// such as the [`FlightRecorderTracker`], act on any error.
+    fn wants_errors(&self) -> bool {
+        false
+    }
+
+    /// Write out any buffered events without ending the output.
+    fn flush(&self) {}
 
     /// Perform any pre-exit shutdown/cleanup
     fn shutdown(&self);
@@ -159,9 +280,17 @@ pub struct EntityManager {
     /// List of regular expressions mapping entity names to log levels.
     regex_to_entity_level: Vec<(Regex, log::Level)>,
 
+    /// List of regular expressions mapping entity names to log levels that
+    /// were set while the simulation runs, most recent first.
+    runtime_regex_to_entity_level: RefCell<Vec<(Regex, log::Level)>>,
+
     /// List of regular expressions mapping entity names to log levels.
     regex_to_enable_monitors_for: Vec<(Regex, u64)>,
 
+    /// List of regular expressions mapping entity names to the kinds of event
+    /// enabled for them.
+    regex_to_event_kinds: Vec<(Regex, EventKinds)>,
+
     /// Used to assign unique IDs.
     unique_id: RefCell<u64>,
 
@@ -171,6 +300,12 @@ pub struct EntityManager {
 
     /// Keep track of the window size for entities.
     monitor_window_size_lookup: RefCell<HashMap<Id, u64>>,
+
+    /// Keep track of entities that do not have all kinds of event enabled.
+    event_kinds_lookup: RefCell<HashMap<Id, EventKinds>>,
+
+    /// The names of each entity, so levels can be changed by name later.
+    entity_names: RefCell<HashMap<Id, Vec<String>>>,
 }
 
 impl EntityManager {
@@ -180,10 +315,14 @@ impl EntityManager {
         Self {
             default_entity_level,
             regex_to_entity_level: Vec::new(),
+            runtime_regex_to_entity_level: RefCell::new(Vec::new()),
             regex_to_enable_monitors_for: Vec::new(),
+            regex_to_event_kinds: Vec::new(),
             unique_id: RefCell::new(ROOT.0 + 1),
             log_entity_lookup: RefCell::new(HashMap::new()),
             monitor_window_size_lookup: RefCell::new(HashMap::new()),
+            event_kinds_lookup: RefCell::new(HashMap::new()),
+            entity_names: RefCell::new(HashMap::new()),
         }
     }
 
@@ -205,6 +344,14 @@ impl EntityManager {
         self.monitor_window_size_lookup.borrow().get(&id).copied()
     }
 
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.event_kinds_lookup
+            .borrow()
+            .get(&id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     fn add_entity(
         &self,
         id: Id,
@@ -230,11 +377,25 @@ impl EntityManager {
                 .insert(id, window_size_ticks);
         }
 
+        if let Some(event_kinds) = self.event_kinds_for(entity_name, alternative_names) {
+            self.event_kinds_lookup.borrow_mut().insert(id, event_kinds);
+        }
+
+        let mut names = vec![entity_name.to_string()];
+        if let Some(alternative_names) = alternative_names {
+            names.extend(alternative_names.iter().cloned());
+        }
+        self.entity_names.borrow_mut().insert(id, names);
+
         entity_level
     }
 
     fn log_level_for(&self, entity_name: &str, alternative_names: AlternativeNames) -> log::Level {
-        for (regex, level) in &self.regex_to_entity_level {
+        let runtime_regex_to_entity_level = self.runtime_regex_to_entity_level.borrow();
+        for (regex, level) in runtime_regex_to_entity_level
+            .iter()
+            .chain(&self.regex_to_entity_level)
+        {
             if regex.is_match(entity_name) {
                 return *level;
             }
@@ -249,6 +410,44 @@ impl EntityManager {
         self.default_entity_level
     }
 
+    /// Change the level of the entities that match a regular expression while
+    /// the simulation runs.
+    ///
+    /// This takes priority over all earlier filters, and applies to the
+    /// entities that already exist as well as those created later.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gwr_track::tracker::EntityManager;
+    /// let manager = EntityManager::new(log::Level::Warn);
+    /// manager.set_entity_level(".*fabric::node3.*", log::Level::Trace);
+    /// ```
+    pub fn set_entity_level(
+        &self,
+        regex_str: &str,
+        level: log::Level,
+    ) -> Result<(), TrackConfigError> {
+        let regex = Regex::new(regex_str)
+            .map_err(|e| TrackConfigError(format!("Failed to parse regex {regex_str}:\n{e}\n")))?;
+
+        {
+            let mut log_entity_lookup = self.log_entity_lookup.borrow_mut();
+            for (id, names) in self.entity_names.borrow().iter() {
+                if names.iter().any(|name| regex.is_match(name)) {
+                    log_entity_lookup.insert(*id, level);
+                }
+            }
+        }
+
+        let mut runtime_regex_to_entity_level = self.runtime_regex_to_entity_level.borrow_mut();
+        runtime_regex_to_entity_level.retain(|(r, _)| r.as_str() != regex_str);
+        runtime_regex_to_entity_level.insert(0, (regex, level));
+
+        LEVELS_GENERATION.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
     fn monitor_window_size_for(
         &self,
         entity_name: &str,
@@ -269,6 +468,53 @@ impl EntityManager {
         None
     }
 
+    fn event_kinds_for(
+        &self,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> Option<EventKinds> {
+        for (regex, event_kinds) in &self.regex_to_event_kinds {
+            if regex.is_match(entity_name) {
+                return Some(*event_kinds);
+            }
+            if let Some(alternative_names) = alternative_names {
+                for name in alternative_names {
+                    if regex.is_match(name.as_str()) {
+                        return Some(*event_kinds);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Add a filter regular expression to limit matching entities to the
+    /// given kinds of event, as well as their level.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use gwr_track::tracker::EntityManager;
+    /// use gwr_track::tracker::types::{EventKind, EventKinds};
+    /// let mut manager = EntityManager::new(log::Level::Trace);
+    /// manager.add_event_kinds_filter(".*", EventKinds::from_iter([EventKind::Lifecycle]));
+    /// ```
+    pub fn add_event_kinds_filter(
+        &mut self,
+        regex_str: &str,
+        event_kinds: EventKinds,
+    ) -> Result<(), TrackConfigError> {
+        match Regex::new(regex_str) {
+            Ok(regex) => self.regex_to_event_kinds.push((regex, event_kinds)),
+            Err(e) => {
+                return Err(TrackConfigError(format!(
+                    "Failed to parse regex {regex_str}:\n{e}\n"
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Add a filter regular expression to set matching entities to a given
     /// level.
     ///
@@ -424,6 +670,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn set_level_while_running() {
+        let mut manager = EntityManager::new(Level::Error);
+        manager
+            .add_entity_level_filter(r".*dev.*", Level::Info)
+            .unwrap();
+        for (i, p) in entity_paths().iter().enumerate() {
+            manager.add_entity(Id(i as u64), p, None);
+        }
+
+        manager.set_entity_level(r".*node1", Level::Trace).unwrap();
+        let expected_levels = [Level::Error, Level::Info, Level::Info, Level::Trace];
+        for (i, level) in expected_levels.iter().enumerate() {
+            assert_eq!(manager.enabled_level(Id(i as u64)), *level);
+        }
+
+        // Later changes take priority and apply to new entities
+        manager.set_entity_level(r".*node.*", Level::Warn).unwrap();
+        assert_eq!(manager.enabled_level(Id(3)), Level::Warn);
+        assert_eq!(
+            manager.add_entity(Id(4), "top::dev::node2", None),
+            Level::Warn
+        );
+        assert!(manager.set_entity_level(r"(", Level::Warn).is_err());
+    }
+
     #[test]
     fn ids() {
         let manager = EntityManager::new(Level::Error);
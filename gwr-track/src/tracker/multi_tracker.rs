@@ -1,9 +1,10 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
 use crate::Id;
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::tracker::aka::AlternativeNames;
-use crate::tracker::{EntityManager, Track, Tracker};
+use crate::tracker::types::{CallSite, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, Track, TrackConfigError, Tracker};
 
 /// Container for multiple [`Tracker`]s
 pub struct MultiTracker {
@@ -42,6 +43,13 @@ impl Track for MultiTracker {
             .unwrap_or(log::Level::Error)
     }
 
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.trackers
+            .iter()
+            .map(|tracker| tracker.enabled_event_kinds(id))
+            .fold(EventKinds::none(), EventKinds::union)
+    }
+
     fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
         for tracker in &self.trackers {
             if let Some(window_size_ticks) = tracker.monitoring_window_size_for(id) {
@@ -106,6 +114,18 @@ impl Track for MultiTracker {
         }
     }
 
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        for tracker in &self.trackers {
+            tracker.span_begin(id, span, name);
+        }
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        for tracker in &self.trackers {
+            tracker.span_end(id, span);
+        }
+    }
+
     fn create_entity(&self, created_by: Id, id: Id, name: &str) {
         for tracker in &self.trackers {
             tracker.create_entity(created_by, id, name);
@@ -150,6 +170,24 @@ impl Track for MultiTracker {
         }
     }
 
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        for tracker in &self.trackers {
+            tracker.register_access(id, access.clone());
+        }
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        for tracker in &self.trackers {
+            tracker.counter(id, name, delta);
+        }
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        for tracker in &self.trackers {
+            tracker.histogram(id, name, value);
+        }
+    }
+
     fn destroy(&self, destroyed_by: Id, id: Id) {
         for tracker in &self.trackers {
             tracker.destroy(destroyed_by, id);
@@ -168,9 +206,38 @@ impl Track for MultiTracker {
         }
     }
 
-    fn time(&self, set_by: Id, time_ns: f64) {
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        for tracker in &self.trackers {
+            tracker.log_at(id, level, call_site, msg);
+        }
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        for tracker in &self.trackers {
+            tracker.metadata(key, value);
+        }
+    }
+
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        for tracker in &self.trackers {
+            tracker.time(set_by, ticks, time_unit);
+        }
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        for tracker in &self.trackers {
+            tracker.set_entity_level(regex_str, level)?;
+        }
+        Ok(())
+    }
+
+    fn wants_errors(&self) -> bool {
+        self.trackers.iter().any(|tracker| tracker.wants_errors())
+    }
+
+    fn flush(&self) {
         for tracker in &self.trackers {
-            tracker.time(set_by, time_ns);
+            tracker.flush();
         }
     }
 
@@ -4,10 +4,12 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::perfetto_trace_builder::PerfettoTraceBuilder;
-use crate::tracker::EntityManager;
+use crate::rotation::TraceHeader;
 use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{EventKind, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, TrackConfigError};
 use crate::{Id, SharedWriter, Track, Writer};
 
 /// A tracker that writes Perfetto binary data
@@ -18,6 +20,10 @@ pub struct PerfettoTracker {
     trace_builder: RefCell<PerfettoTraceBuilder>,
     group_memberships: RefCell<HashMap<Id, Id>>,
     activity_lanes: RefCell<HashMap<Id, Id>>,
+    counter_totals: RefCell<HashMap<(Id, String), f64>>,
+    time_unit: TimeUnit,
+    object_flows: bool,
+    header: TraceHeader,
 }
 
 impl PerfettoTracker {
@@ -30,8 +36,82 @@ impl PerfettoTracker {
             trace_builder: RefCell::new(PerfettoTraceBuilder::new()),
             group_memberships: RefCell::new(HashMap::new()),
             activity_lanes: RefCell::new(HashMap::new()),
+            counter_totals: RefCell::new(HashMap::new()),
+            time_unit: TimeUnit::default(),
+            object_flows: false,
+            header: TraceHeader::default(),
         }
     }
+
+    /// Add the packets that describe the whole trace, such as its clock,
+    /// metadata and the track descriptors of entities, to `header` so that
+    /// they can be written again at the start of each rotated file.
+    #[must_use]
+    pub fn with_header(mut self, header: TraceHeader) -> Self {
+        header.extend(&self.header.bytes());
+        self.header = header;
+        self
+    }
+
+    /// Write timestamps in `time_unit` rather than nanoseconds.
+    ///
+    /// Perfetto has no clock faster than a nanosecond, so times in
+    /// picoseconds are written to the nanosecond.
+    #[must_use]
+    pub fn with_time_unit(mut self, time_unit: TimeUnit) -> Self {
+        self.time_unit = time_unit;
+        let trace_builder = self.trace_builder.get_mut();
+        if let Some(trace_packet) = trace_builder.build_clock_trace_packet(time_unit) {
+            let buf = trace_builder.build_trace_to_bytes(vec![trace_packet]);
+            self.write_header(&buf);
+        }
+        self
+    }
+
+    /// Write packets that describe the whole trace and add them to the
+    /// header.
+    fn write_header(&self, buf: &[u8]) {
+        self.header.extend(buf);
+        self.writer.borrow_mut().write_all(buf).unwrap();
+    }
+
+    /// Link the creation, entering, exiting and destruction of each object
+    /// with Perfetto flows, so that its path through the model is shown.
+    #[must_use]
+    pub fn with_object_flows(mut self, object_flows: bool) -> Self {
+        self.object_flows = object_flows;
+        self
+    }
+
+    /// Write the names of events once and refer to them by ID, which shrinks
+    /// the trace. This is enabled by default, but should be disabled if the
+    /// trace is rotated between files.
+    #[must_use]
+    pub fn with_interned_names(mut self, intern_names: bool) -> Self {
+        self.trace_builder.get_mut().set_intern_names(intern_names);
+        self
+    }
+
+    fn write_object_flow(&self, id: Id, object: Id, end: bool) {
+        if !self.object_flows {
+            return;
+        }
+        let mut guard = self.trace_builder.borrow_mut();
+        let trace_packets =
+            guard.build_object_flow_trace_packets(*self.current_time_ns.borrow(), id, object, end);
+        if !trace_packets.is_empty() {
+            let buf = guard.build_trace_to_bytes(trace_packets);
+            self.writer.borrow_mut().write_all(&buf).unwrap();
+        }
+    }
+
+    fn write_metric(&self, id: Id, name: &str, value: f64) {
+        let mut guard = self.trace_builder.borrow_mut();
+        let trace_packets =
+            guard.build_metric_trace_packets(*self.current_time_ns.borrow(), id, name, value);
+        let buf = guard.build_trace_to_bytes(trace_packets);
+        self.writer.borrow_mut().write_all(&buf).unwrap();
+    }
 }
 
 impl Track for PerfettoTracker {
@@ -47,6 +127,10 @@ impl Track for PerfettoTracker {
         self.entity_manager.monitoring_window_size_for(id)
     }
 
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.entity_manager.enabled_event_kinds(id)
+    }
+
     fn add_entity(
         &self,
         id: Id,
@@ -58,8 +142,8 @@ impl Track for PerfettoTracker {
     }
 
     fn enter(&self, id: Id, entered: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
-            let guard = self.trace_builder.borrow_mut();
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            let mut guard = self.trace_builder.borrow_mut();
             let trace_packet = guard.build_enter_track_event_trace_packet(
                 *self.current_time_ns.borrow(),
                 id,
@@ -67,12 +151,14 @@ impl Track for PerfettoTracker {
             );
             let buf = guard.build_trace_to_bytes(vec![trace_packet]);
             self.writer.borrow_mut().write_all(&buf).unwrap();
+            drop(guard);
+            self.write_object_flow(id, entered, false);
         }
     }
 
     fn exit(&self, id: Id, exited: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
-            let guard = self.trace_builder.borrow_mut();
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            let mut guard = self.trace_builder.borrow_mut();
             let trace_packet = guard.build_exit_track_event_trace_packet(
                 *self.current_time_ns.borrow(),
                 id,
@@ -80,12 +166,14 @@ impl Track for PerfettoTracker {
             );
             let buf = guard.build_trace_to_bytes(vec![trace_packet]);
             self.writer.borrow_mut().write_all(&buf).unwrap();
+            drop(guard);
+            self.write_object_flow(id, exited, false);
         }
     }
 
     fn value(&self, id: Id, value: f64) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
-            let guard = self.trace_builder.borrow_mut();
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            let mut guard = self.trace_builder.borrow_mut();
             let trace_packet = guard.build_value_track_event_trace_packet(
                 *self.current_time_ns.borrow(),
                 id,
@@ -97,9 +185,9 @@ impl Track for PerfettoTracker {
     }
 
     fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
-        if self.is_entity_enabled(lane, log::Level::Trace) {
+        if self.is_event_enabled(lane, log::Level::Trace, EventKind::Activities) {
             self.activity_lanes.borrow_mut().insert(activity, lane);
-            let guard = self.trace_builder.borrow_mut();
+            let mut guard = self.trace_builder.borrow_mut();
             let correlation_id = self
                 .group_memberships
                 .borrow()
@@ -131,9 +219,9 @@ impl Track for PerfettoTracker {
 
     fn end_activity(&self, activity: Id) {
         if let Some(lane) = self.activity_lanes.borrow_mut().remove(&activity)
-            && self.is_entity_enabled(lane, log::Level::Trace)
+            && self.is_event_enabled(lane, log::Level::Trace, EventKind::Activities)
         {
-            let guard = self.trace_builder.borrow_mut();
+            let mut guard = self.trace_builder.borrow_mut();
             let trace_packet =
                 guard.build_activity_end_trace_packet(*self.current_time_ns.borrow(), lane);
             let buf = guard.build_trace_to_bytes(vec![trace_packet]);
@@ -141,8 +229,29 @@ impl Track for PerfettoTracker {
         }
     }
 
+    fn span_begin(&self, id: Id, _span: Id, name: &str) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            let mut guard = self.trace_builder.borrow_mut();
+            let trace_packets =
+                guard.build_span_begin_trace_packets(*self.current_time_ns.borrow(), id, name);
+            let buf = guard.build_trace_to_bytes(trace_packets);
+            self.writer.borrow_mut().write_all(&buf).unwrap();
+        }
+    }
+
+    fn span_end(&self, id: Id, _span: Id) {
+        // Spans nest, so the end of the most recent one is the end of this one
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            let guard = self.trace_builder.borrow_mut();
+            let trace_packet =
+                guard.build_span_end_trace_packet(*self.current_time_ns.borrow(), id);
+            let buf = guard.build_trace_to_bytes(vec![trace_packet]);
+            self.writer.borrow_mut().write_all(&buf).unwrap();
+        }
+    }
+
     fn create_entity(&self, created_by: Id, id: Id, name: &str) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
             let mut guard = self.trace_builder.borrow_mut();
             let trace_packet = guard.build_enter_exit_track_descriptor_trace_packet(
                 *self.current_time_ns.borrow(),
@@ -151,12 +260,12 @@ impl Track for PerfettoTracker {
                 name,
             );
             let buf = guard.build_trace_to_bytes(vec![trace_packet]);
-            self.writer.borrow_mut().write_all(&buf).unwrap();
+            self.write_header(&buf);
         }
     }
 
     fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
             let mut guard = self.trace_builder.borrow_mut();
             let trace_packet = guard.build_value_track_descriptor_trace_packet(
                 *self.current_time_ns.borrow(),
@@ -165,12 +274,12 @@ impl Track for PerfettoTracker {
                 name,
             );
             let buf = guard.build_trace_to_bytes(vec![trace_packet]);
-            self.writer.borrow_mut().write_all(&buf).unwrap();
+            self.write_header(&buf);
         }
     }
 
     fn create_lane(&self, created_by: Id, id: Id, name: &str) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
             let mut guard = self.trace_builder.borrow_mut();
             let trace_packet = guard.build_activity_track_descriptor_trace_packet(
                 *self.current_time_ns.borrow(),
@@ -179,7 +288,7 @@ impl Track for PerfettoTracker {
                 name,
             );
             let buf = guard.build_trace_to_bytes(vec![trace_packet]);
-            self.writer.borrow_mut().write_all(&buf).unwrap();
+            self.write_header(&buf);
         }
     }
 
@@ -194,7 +303,7 @@ impl Track for PerfettoTracker {
         _req_type: u8,
         details: &str,
     ) {
-        if self.is_entity_enabled(created_by, log::Level::Trace) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
             let mut guard = self.trace_builder.borrow_mut();
             let trace_packet = guard.build_enter_exit_track_descriptor_trace_packet(
                 *self.current_time_ns.borrow(),
@@ -204,6 +313,8 @@ impl Track for PerfettoTracker {
             );
             let buf = guard.build_trace_to_bytes(vec![trace_packet]);
             self.writer.borrow_mut().write_all(&buf).unwrap();
+            drop(guard);
+            self.write_object_flow(created_by, id, false);
         }
     }
 
@@ -211,8 +322,42 @@ impl Track for PerfettoTracker {
         // todo!()
     }
 
-    fn destroy(&self, _destroyed_by: Id, _destroyed_obj: Id) {
-        // todo!()
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Registers) {
+            let mut guard = self.trace_builder.borrow_mut();
+            let trace_packets = guard.build_register_access_trace_packets(
+                *self.current_time_ns.borrow(),
+                id,
+                &access,
+            );
+            let buf = guard.build_trace_to_bytes(trace_packets);
+            self.writer.borrow_mut().write_all(&buf).unwrap();
+        }
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            // Counter tracks show the total rather than each change
+            let total = {
+                let mut counter_totals = self.counter_totals.borrow_mut();
+                let total = counter_totals.entry((id, name.to_string())).or_default();
+                *total += delta;
+                *total
+            };
+            self.write_metric(id, name, total);
+        }
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.write_metric(id, name, value);
+        }
+    }
+
+    fn destroy(&self, destroyed_by: Id, destroyed_obj: Id) {
+        if self.is_event_enabled(destroyed_obj, log::Level::Trace, EventKind::Lifecycle) {
+            self.write_object_flow(destroyed_by, destroyed_obj, true);
+        }
     }
 
     fn connect(&self, _connect_from: Id, _connect_to: Id) {
@@ -223,8 +368,24 @@ impl Track for PerfettoTracker {
         // todo!()
     }
 
-    fn time(&self, _set_by: Id, time_ns: f64) {
-        *self.current_time_ns.borrow_mut() = time_ns as u64;
+    fn metadata(&self, key: &str, value: &str) {
+        let mut guard = self.trace_builder.borrow_mut();
+        let trace_packet = guard.build_metadata_trace_packet(key, value);
+        let buf = guard.build_trace_to_bytes(vec![trace_packet]);
+        self.write_header(&buf);
+    }
+
+    fn time(&self, _set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        let ticks = time_unit.convert_ticks(ticks, self.time_unit);
+        *self.current_time_ns.borrow_mut() = self.trace_builder.borrow().timestamp(ticks);
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.entity_manager.set_entity_level(regex_str, level)
+    }
+
+    fn flush(&self) {
+        self.writer.borrow_mut().flush().unwrap();
     }
 
     fn shutdown(&self) {
@@ -0,0 +1,363 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A tracker that splits its output between a number of other trackers.
+//!
+//! Large simulations of several devices create traces that are slow to load
+//! and hard to compare. The [`ShardedTracker`] matches a regular expression
+//! against the full name of each entity, and sends the events of that entity
+//! to the tracker for the shard named by the match. For example, the regular
+//! expression `^top::(device\d+)` writes the events of each device, and of
+//! everything inside it, to a separate shard.
+//!
+//! The shard is named by the first capture group of the regular expression,
+//! or by the whole match if there is no capture group. Entities that do not
+//! match are written to the [`COMMON_SHARD`].
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::Id;
+use crate::entity::{Capacity, RegisterAccess};
+use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{CallSite, EventKind, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, Track, TrackConfigError, Tracker};
+
+/// The name of the shard for entities that do not match the regular
+/// expression.
+pub const COMMON_SHARD: &str = "common";
+
+/// A function that creates the [`Tracker`] for the named shard.
+pub type ShardFactory = Box<dyn Fn(&str) -> Result<Tracker, TrackConfigError>>;
+
+/// A tracker that sends the events of each entity to the tracker of its shard.
+pub struct ShardedTracker {
+    entity_manager: EntityManager,
+    shard_regex: Regex,
+    create_shard: ShardFactory,
+
+    /// The tracker of each shard.
+    shards: RefCell<Vec<Tracker>>,
+
+    /// The index in `shards` of each named shard.
+    shard_indices: RefCell<HashMap<String, usize>>,
+
+    /// The index in `shards` of each entity, lane, group and activity.
+    shard_of: RefCell<HashMap<Id, usize>>,
+
+    /// The most recent `time` event, to pass on to shards as they are created.
+    last_time: Cell<Option<(Id, u64, TimeUnit)>>,
+
+    /// The metadata of the run, to pass on to shards as they are created.
+    metadata: RefCell<Vec<(String, String)>>,
+}
+
+impl ShardedTracker {
+    /// Create a new [`ShardedTracker`] that splits events into shards named
+    /// by `shard_regex`, and calls `create_shard` to create the tracker for
+    /// each shard when it is first used.
+    ///
+    /// The events are filtered by `entity_manager` before they are passed on,
+    /// so the trackers of the shards should write all of the events they are
+    /// given.
+    pub fn new(
+        entity_manager: EntityManager,
+        shard_regex: &str,
+        create_shard: ShardFactory,
+    ) -> Result<Self, TrackConfigError> {
+        let shard_regex = Regex::new(shard_regex).map_err(|e| {
+            TrackConfigError(format!("Invalid shard regex '{shard_regex}':\n{e}\n"))
+        })?;
+        Ok(Self {
+            entity_manager,
+            shard_regex,
+            create_shard,
+            shards: RefCell::new(Vec::new()),
+            shard_indices: RefCell::new(HashMap::new()),
+            shard_of: RefCell::new(HashMap::new()),
+            last_time: Cell::new(None),
+            metadata: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Return the names of the shards created so far.
+    #[must_use]
+    pub fn shard_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.shard_indices.borrow().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Return the name of the shard for the entity called `entity_name`.
+    fn shard_name<'a>(&self, entity_name: &'a str) -> &'a str {
+        match self.shard_regex.captures(entity_name) {
+            Some(captures) => captures
+                .get(1)
+                .or_else(|| captures.get(0))
+                .map_or(COMMON_SHARD, |m| m.as_str()),
+            None => COMMON_SHARD,
+        }
+    }
+
+    /// Return the index of the named shard, creating it if required.
+    fn shard_index(&self, name: &str) -> usize {
+        if let Some(index) = self.shard_indices.borrow().get(name) {
+            return *index;
+        }
+
+        let tracker = (self.create_shard)(name)
+            .unwrap_or_else(|TrackConfigError(e)| panic!("Failed to create shard {name}: {e}"));
+        for (key, value) in self.metadata.borrow().iter() {
+            tracker.metadata(key, value);
+        }
+        if let Some((set_by, ticks, time_unit)) = self.last_time.get() {
+            tracker.time(set_by, ticks, time_unit);
+        }
+        let mut shards = self.shards.borrow_mut();
+        let index = shards.len();
+        shards.push(tracker);
+        self.shard_indices
+            .borrow_mut()
+            .insert(name.to_string(), index);
+        index
+    }
+
+    /// Return the index of the shard that `id` belongs to.
+    fn index_of(&self, id: Id) -> usize {
+        let index = self.shard_of.borrow().get(&id).copied();
+        index.unwrap_or_else(|| self.shard_index(COMMON_SHARD))
+    }
+
+    /// Return the tracker of the shard that `id` belongs to.
+    fn shard_for(&self, id: Id) -> Tracker {
+        let index = self.index_of(id);
+        self.shards.borrow()[index].clone()
+    }
+
+    /// Put `id` in the same shard as `owner`.
+    fn assign_to_shard_of(&self, id: Id, owner: Id) {
+        let index = self.index_of(owner);
+        self.shard_of.borrow_mut().insert(id, index);
+    }
+}
+
+impl Track for ShardedTracker {
+    fn unique_id(&self) -> Id {
+        self.entity_manager.unique_id()
+    }
+
+    fn enabled_level(&self, id: Id) -> log::Level {
+        self.entity_manager.enabled_level(id)
+    }
+
+    fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
+        self.entity_manager.monitoring_window_size_for(id)
+    }
+
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.entity_manager.enabled_event_kinds(id)
+    }
+
+    fn add_entity(
+        &self,
+        id: Id,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> log::Level {
+        let index = self.shard_index(self.shard_name(entity_name));
+        self.shard_of.borrow_mut().insert(id, index);
+        self.shard_for(id)
+            .add_entity(id, entity_name, alternative_names);
+        self.entity_manager
+            .add_entity(id, entity_name, alternative_names)
+    }
+
+    fn enter(&self, id: Id, object: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            self.shard_for(id).enter(id, object);
+        }
+    }
+
+    fn exit(&self, id: Id, object: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
+            self.shard_for(id).exit(id, object);
+        }
+    }
+
+    fn value(&self, id: Id, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.shard_for(id).value(id, value);
+        }
+    }
+
+    fn add_to_group(&self, activity: Id, group_id: Id) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
+            self.shard_for(activity).add_to_group(activity, group_id);
+        }
+    }
+
+    fn remove_from_group(&self, activity: Id, group_id: Id) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
+            self.shard_for(activity)
+                .remove_from_group(activity, group_id);
+        }
+    }
+
+    fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
+        self.assign_to_shard_of(activity, lane);
+        if self.is_event_enabled(lane, log::Level::Trace, EventKind::Activities) {
+            self.shard_for(lane).begin_activity(activity, lane, name);
+        }
+    }
+
+    fn end_activity(&self, activity: Id) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
+            self.shard_for(activity).end_activity(activity);
+        }
+    }
+
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.shard_for(id).span_begin(id, span, name);
+        }
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.shard_for(id).span_end(id, span);
+        }
+    }
+
+    fn create_entity(&self, created_by: Id, id: Id, name: &str) {
+        // The creation is written to the shard of the new entity so that each
+        // shard names all of its entities
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.shard_for(id).create_entity(created_by, id, name);
+        }
+    }
+
+    fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.shard_for(id).create_monitor(created_by, id, name);
+        }
+    }
+
+    fn create_lane(&self, created_by: Id, id: Id, name: &str) {
+        self.assign_to_shard_of(id, created_by);
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.shard_for(id).create_lane(created_by, id, name);
+        }
+    }
+
+    fn create_group(&self, created_by: Id, id: Id, name: &str) {
+        self.assign_to_shard_of(id, created_by);
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.shard_for(id).create_group(created_by, id, name);
+        }
+    }
+
+    fn create_object(
+        &self,
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: &str,
+        req_type: u8,
+        details: &str,
+    ) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
+            self.shard_for(created_by)
+                .create_object(created_by, id, size, units, req_type, details);
+        }
+    }
+
+    fn capacity(&self, id: Id, capacity: Capacity) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.shard_for(id).capacity(id, capacity);
+        }
+    }
+
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Registers) {
+            self.shard_for(id).register_access(id, access);
+        }
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.shard_for(id).counter(id, name, delta);
+        }
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.shard_for(id).histogram(id, name, value);
+        }
+    }
+
+    fn destroy(&self, destroyed_by: Id, id: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
+            self.shard_for(id).destroy(destroyed_by, id);
+        }
+    }
+
+    fn connect(&self, connect_from: Id, connect_to: Id) {
+        if self.is_event_enabled(connect_from, log::Level::Trace, EventKind::Lifecycle)
+            || self.is_event_enabled(connect_to, log::Level::Trace, EventKind::Lifecycle)
+        {
+            self.shard_for(connect_from)
+                .connect(connect_from, connect_to);
+        }
+    }
+
+    fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
+            self.shard_for(id).log(id, level, msg);
+        }
+    }
+
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
+            self.shard_for(id).log_at(id, level, call_site, msg);
+        }
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        // Every shard is given the metadata of the run
+        self.metadata
+            .borrow_mut()
+            .push((key.to_string(), value.to_string()));
+        let shards = self.shards.borrow().clone();
+        for shard in shards {
+            shard.metadata(key, value);
+        }
+    }
+
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        // Every shard needs the time of the events that follow
+        self.last_time.set(Some((set_by, ticks, time_unit)));
+        let shards = self.shards.borrow().clone();
+        for shard in shards {
+            shard.time(set_by, ticks, time_unit);
+        }
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.entity_manager.set_entity_level(regex_str, level)
+    }
+
+    fn flush(&self) {
+        let shards = self.shards.borrow().clone();
+        for shard in shards {
+            shard.flush();
+        }
+    }
+
+    fn shutdown(&self) {
+        let shards = self.shards.borrow().clone();
+        for shard in shards {
+            shard.shutdown();
+        }
+    }
+}
@@ -6,9 +6,10 @@ use std::rc::Rc;
 #[doc(hidden)]
 pub use log;
 
-use crate::entity::Capacity;
+use crate::entity::{Capacity, RegisterAccess};
 use crate::tracker::aka::AlternativeNames;
-use crate::tracker::{EntityManager, Track};
+use crate::tracker::types::{EventKind, EventKinds, TimeUnit};
+use crate::tracker::{EntityManager, Track, TrackConfigError};
 use crate::{Id, SharedWriter, Writer};
 
 /// A simple text logger to output messages to a Writer.
@@ -17,6 +18,9 @@ pub struct TextTracker {
 
     /// Writer to which all _log_ events will be written.
     writer: SharedWriter,
+
+    /// The unit that time is written in.
+    time_unit: TimeUnit,
 }
 
 impl TextTracker {
@@ -25,8 +29,16 @@ impl TextTracker {
         Self {
             entity_manager,
             writer: Rc::new(RefCell::new(writer)),
+            time_unit: TimeUnit::default(),
         }
     }
+
+    /// Write time in `time_unit` rather than nanoseconds.
+    #[must_use]
+    pub fn with_time_unit(mut self, time_unit: TimeUnit) -> Self {
+        self.time_unit = time_unit;
+        self
+    }
 }
 
 /// Implementation for each [`Track`] event
@@ -43,6 +55,10 @@ impl Track for TextTracker {
         self.entity_manager.monitoring_window_size_for(id)
     }
 
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.entity_manager.enabled_event_kinds(id)
+    }
+
     fn add_entity(
         &self,
         id: Id,
@@ -54,7 +70,7 @@ impl Track for TextTracker {
     }
 
     fn enter(&self, id: Id, object: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{id}: enter {object}\n").as_bytes())
@@ -63,7 +79,7 @@ impl Track for TextTracker {
     }
 
     fn exit(&self, id: Id, object: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Movement) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{id}: exit {object}\n").as_bytes())
@@ -72,7 +88,7 @@ impl Track for TextTracker {
     }
 
     fn value(&self, id: Id, value: f64) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{id}: value {value}\n").as_bytes())
@@ -81,7 +97,7 @@ impl Track for TextTracker {
     }
 
     fn add_to_group(&self, activity: Id, group_id: Id) {
-        if self.is_entity_enabled(activity, log::Level::Trace) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{activity}: added to group {group_id}\n").as_bytes())
@@ -90,7 +106,7 @@ impl Track for TextTracker {
     }
 
     fn remove_from_group(&self, activity: Id, group_id: Id) {
-        if self.is_entity_enabled(activity, log::Level::Trace) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{activity}: removed from group {group_id}\n").as_bytes())
@@ -99,7 +115,7 @@ impl Track for TextTracker {
     }
 
     fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
-        if self.is_entity_enabled(lane, log::Level::Trace) {
+        if self.is_event_enabled(lane, log::Level::Trace, EventKind::Activities) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{activity}: activity begin {name} on lane {lane}\n").as_bytes())
@@ -108,7 +124,7 @@ impl Track for TextTracker {
     }
 
     fn end_activity(&self, activity: Id) {
-        if self.is_entity_enabled(activity, log::Level::Trace) {
+        if self.is_event_enabled(activity, log::Level::Trace, EventKind::Activities) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{activity}: activity end\n").as_bytes())
@@ -116,8 +132,26 @@ impl Track for TextTracker {
         }
     }
 
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.writer
+                .borrow_mut()
+                .write_all(format!("{id}: span {span} begin {name}\n").as_bytes())
+                .unwrap();
+        }
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Activities) {
+            self.writer
+                .borrow_mut()
+                .write_all(format!("{id}: span {span} end\n").as_bytes())
+                .unwrap();
+        }
+    }
+
     fn create_entity(&self, created_by: Id, id: Id, name: &str) {
-        if self.is_entity_enabled(created_by, log::Level::Trace) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{created_by}: created entity {id}, {name}\n").as_bytes())
@@ -126,7 +160,7 @@ impl Track for TextTracker {
     }
 
     fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
-        if self.is_entity_enabled(created_by, log::Level::Trace) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{created_by}: created monitor {id}, {name}\n").as_bytes())
@@ -135,7 +169,7 @@ impl Track for TextTracker {
     }
 
     fn create_lane(&self, created_by: Id, id: Id, name: &str) {
-        if self.is_entity_enabled(created_by, log::Level::Trace) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{created_by}: created lane {id}, {name}\n").as_bytes())
@@ -144,7 +178,7 @@ impl Track for TextTracker {
     }
 
     fn create_group(&self, created_by: Id, id: Id, name: &str) {
-        if self.is_entity_enabled(created_by, log::Level::Trace) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{created_by}: created group {id}, {name}\n").as_bytes())
@@ -161,7 +195,7 @@ impl Track for TextTracker {
         req_type: u8,
         details: &str,
     ) {
-        if self.is_entity_enabled(created_by, log::Level::Trace) {
+        if self.is_event_enabled(created_by, log::Level::Trace, EventKind::Lifecycle) {
             self.writer
                 .borrow_mut()
                 .write_all(
@@ -175,7 +209,7 @@ impl Track for TextTracker {
     }
 
     fn capacity(&self, id: Id, capacity: Capacity) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
             self.writer
                 .borrow_mut()
                 .write_all(
@@ -185,8 +219,35 @@ impl Track for TextTracker {
         }
     }
 
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Registers) {
+            self.writer
+                .borrow_mut()
+                .write_all(format!("{id}: {access}\n").as_bytes())
+                .unwrap();
+        }
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.writer
+                .borrow_mut()
+                .write_all(format!("{id}: counter {name} {delta}\n").as_bytes())
+                .unwrap();
+        }
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Values) {
+            self.writer
+                .borrow_mut()
+                .write_all(format!("{id}: histogram {name} {value}\n").as_bytes())
+                .unwrap();
+        }
+    }
+
     fn destroy(&self, destroyed_by: Id, id: Id) {
-        if self.is_entity_enabled(id, log::Level::Trace) {
+        if self.is_event_enabled(id, log::Level::Trace, EventKind::Lifecycle) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{destroyed_by}: destroyed {id}\n").as_bytes())
@@ -195,8 +256,8 @@ impl Track for TextTracker {
     }
 
     fn connect(&self, connect_from: Id, connect_to: Id) {
-        if self.is_entity_enabled(connect_from, log::Level::Trace)
-            || self.is_entity_enabled(connect_to, log::Level::Trace)
+        if self.is_event_enabled(connect_from, log::Level::Trace, EventKind::Lifecycle)
+            || self.is_event_enabled(connect_to, log::Level::Trace, EventKind::Lifecycle)
         {
             self.writer
                 .borrow_mut()
@@ -206,7 +267,7 @@ impl Track for TextTracker {
     }
 
     fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
-        if self.is_entity_enabled(id, level) {
+        if self.is_event_enabled(id, level, EventKind::Messages) {
             self.writer
                 .borrow_mut()
                 .write_all(format!("{id}:{level}: {msg}\n").as_bytes())
@@ -214,15 +275,37 @@ impl Track for TextTracker {
         }
     }
 
-    fn time(&self, set_by: Id, time_ns: f64) {
+    fn metadata(&self, key: &str, value: &str) {
+        self.writer
+            .borrow_mut()
+            .write_all(format!("# {key}: {value}\n").as_bytes())
+            .unwrap();
+    }
+
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
         if self.is_entity_enabled(set_by, log::Level::Trace) {
             self.writer
                 .borrow_mut()
-                .write_all(format!("{set_by}: set time to {time_ns:.1}ns\n").as_bytes())
+                .write_all(
+                    format!(
+                        "{set_by}: set time to {:.1}{}\n",
+                        time_unit.convert(ticks, self.time_unit),
+                        self.time_unit
+                    )
+                    .as_bytes(),
+                )
                 .unwrap();
         }
     }
 
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.entity_manager.set_entity_level(regex_str, level)
+    }
+
+    fn flush(&self) {
+        self.writer.borrow_mut().flush().unwrap();
+    }
+
     fn shutdown(&self) {
         self.writer.borrow_mut().flush().unwrap();
     }
@@ -0,0 +1,273 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+//! A tracker that throttles identical messages repeated by an entity.
+//!
+//! A component that logs the same warning every tick can make a text log or
+//! trace unusable. The [`ThrottleTracker`] passes all events on to another
+//! tracker, but once an entity has logged the same message at a throttled
+//! level a number of times in a row, the repeats are dropped. When the entity
+//! logs a different message, or the tracker is shut down, a single
+//! `last message repeated N times` message is written in their place.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::Id;
+use crate::entity::{Capacity, RegisterAccess};
+use crate::tracker::aka::AlternativeNames;
+use crate::tracker::types::{CallSite, EventKinds, TimeUnit};
+use crate::tracker::{Track, TrackConfigError, Tracker};
+
+/// The most recent message of an entity.
+struct Repeated {
+    level: log::Level,
+    message: String,
+
+    /// The number of times in a row that the message has been logged.
+    count: usize,
+
+    /// The number of those that were dropped.
+    dropped: usize,
+}
+
+/// A [`Tracker`] that passes all events on to another, and drops identical
+/// messages repeated by an entity.
+pub struct ThrottleTracker {
+    tracker: Tracker,
+
+    /// The number of identical messages in a row written at each level before
+    /// the rest are dropped. Levels not included are not throttled.
+    max_repeats: HashMap<log::Level, usize>,
+
+    /// The most recent message of each entity.
+    last_messages: RefCell<HashMap<Id, Repeated>>,
+}
+
+impl ThrottleTracker {
+    /// Create a new [`ThrottleTracker`] that passes events on to `tracker`.
+    ///
+    /// No levels are throttled until [`ThrottleTracker::with_max_repeats`] is
+    /// used.
+    #[must_use]
+    pub fn new(tracker: Tracker) -> Self {
+        Self {
+            tracker,
+            max_repeats: HashMap::new(),
+            last_messages: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Write at most `max_repeats` identical messages in a row from an entity
+    /// at `level`.
+    #[must_use]
+    pub fn with_max_repeats(mut self, level: log::Level, max_repeats: usize) -> Self {
+        self.max_repeats.insert(level, max_repeats.max(1));
+        self
+    }
+
+    /// Returns whether a message should be written, having recorded it as the
+    /// most recent message of the entity.
+    fn should_write(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) -> bool {
+        let Some(&max_repeats) = self.max_repeats.get(&level) else {
+            self.write_repeated(id);
+            return true;
+        };
+
+        let message = msg.to_string();
+        if let Some(last) = self.last_messages.borrow_mut().get_mut(&id)
+            && last.level == level
+            && last.message == message
+        {
+            last.count += 1;
+            if last.count <= max_repeats {
+                return true;
+            }
+            last.dropped += 1;
+            return false;
+        }
+
+        self.write_repeated(id);
+        self.last_messages.borrow_mut().insert(
+            id,
+            Repeated {
+                level,
+                message,
+                count: 1,
+                dropped: 0,
+            },
+        );
+        true
+    }
+
+    /// Write how many times the most recent message of an entity was dropped,
+    /// if it was.
+    fn write_repeated(&self, id: Id) {
+        let Some(last) = self.last_messages.borrow_mut().remove(&id) else {
+            return;
+        };
+        if last.dropped > 0 {
+            self.tracker.log(
+                id,
+                last.level,
+                format_args!("last message repeated {} times", last.dropped),
+            );
+        }
+    }
+}
+
+impl Track for ThrottleTracker {
+    fn unique_id(&self) -> Id {
+        self.tracker.unique_id()
+    }
+
+    fn enabled_level(&self, id: Id) -> log::Level {
+        self.tracker.enabled_level(id)
+    }
+
+    fn monitoring_window_size_for(&self, id: Id) -> Option<u64> {
+        self.tracker.monitoring_window_size_for(id)
+    }
+
+    fn enabled_event_kinds(&self, id: Id) -> EventKinds {
+        self.tracker.enabled_event_kinds(id)
+    }
+
+    fn add_entity(
+        &self,
+        id: Id,
+        entity_name: &str,
+        alternative_names: AlternativeNames,
+    ) -> log::Level {
+        self.tracker.add_entity(id, entity_name, alternative_names)
+    }
+
+    fn enter(&self, id: Id, object: Id) {
+        self.tracker.enter(id, object);
+    }
+
+    fn exit(&self, id: Id, object: Id) {
+        self.tracker.exit(id, object);
+    }
+
+    fn value(&self, id: Id, value: f64) {
+        self.tracker.value(id, value);
+    }
+
+    fn begin_activity(&self, activity: Id, lane: Id, name: &str) {
+        self.tracker.begin_activity(activity, lane, name);
+    }
+
+    fn add_to_group(&self, activity: Id, group_id: Id) {
+        self.tracker.add_to_group(activity, group_id);
+    }
+
+    fn remove_from_group(&self, activity: Id, group_id: Id) {
+        self.tracker.remove_from_group(activity, group_id);
+    }
+
+    fn end_activity(&self, activity: Id) {
+        self.tracker.end_activity(activity);
+    }
+
+    fn span_begin(&self, id: Id, span: Id, name: &str) {
+        self.tracker.span_begin(id, span, name);
+    }
+
+    fn span_end(&self, id: Id, span: Id) {
+        self.tracker.span_end(id, span);
+    }
+
+    fn create_entity(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_entity(created_by, id, name);
+    }
+
+    fn create_monitor(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_monitor(created_by, id, name);
+    }
+
+    fn create_lane(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_lane(created_by, id, name);
+    }
+
+    fn create_group(&self, created_by: Id, id: Id, name: &str) {
+        self.tracker.create_group(created_by, id, name);
+    }
+
+    fn create_object(
+        &self,
+        created_by: Id,
+        id: Id,
+        size: usize,
+        units: &str,
+        req_type: u8,
+        details: &str,
+    ) {
+        self.tracker
+            .create_object(created_by, id, size, units, req_type, details);
+    }
+
+    fn capacity(&self, id: Id, capacity: Capacity) {
+        self.tracker.capacity(id, capacity);
+    }
+
+    fn register_access(&self, id: Id, access: RegisterAccess) {
+        self.tracker.register_access(id, access);
+    }
+
+    fn counter(&self, id: Id, name: &str, delta: f64) {
+        self.tracker.counter(id, name, delta);
+    }
+
+    fn histogram(&self, id: Id, name: &str, value: f64) {
+        self.tracker.histogram(id, name, value);
+    }
+
+    fn destroy(&self, destroyed_by: Id, id: Id) {
+        self.tracker.destroy(destroyed_by, id);
+    }
+
+    fn connect(&self, connect_from: Id, connect_to: Id) {
+        self.tracker.connect(connect_from, connect_to);
+    }
+
+    fn log(&self, id: Id, level: log::Level, msg: std::fmt::Arguments) {
+        if self.should_write(id, level, msg) {
+            self.tracker.log(id, level, msg);
+        }
+    }
+
+    fn log_at(&self, id: Id, level: log::Level, call_site: &CallSite, msg: std::fmt::Arguments) {
+        if self.should_write(id, level, msg) {
+            self.tracker.log_at(id, level, call_site, msg);
+        }
+    }
+
+    fn metadata(&self, key: &str, value: &str) {
+        self.tracker.metadata(key, value);
+    }
+
+    fn time(&self, set_by: Id, ticks: u64, time_unit: TimeUnit) {
+        self.tracker.time(set_by, ticks, time_unit);
+    }
+
+    fn set_entity_level(&self, regex_str: &str, level: log::Level) -> Result<(), TrackConfigError> {
+        self.tracker.set_entity_level(regex_str, level)
+    }
+
+    fn wants_errors(&self) -> bool {
+        self.tracker.wants_errors()
+    }
+
+    fn flush(&self) {
+        self.tracker.flush();
+    }
+
+    fn shutdown(&self) {
+        let mut ids: Vec<Id> = self.last_messages.borrow().keys().copied().collect();
+        ids.sort_by_key(|id| id.0);
+        for id in ids {
+            self.write_repeated(id);
+        }
+        self.tracker.shutdown();
+    }
+}
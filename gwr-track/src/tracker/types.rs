@@ -1,8 +1,11 @@
 // Copyright (c) 2023 Graphcore Ltd. All rights reserved.
 
 use std::fmt;
+use std::str::FromStr;
 
+use clap::ValueEnum;
 use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
 
 // Generic packet request types
 #[derive(Copy, Clone, Debug, Default, FromPrimitive, PartialEq, ToPrimitive)]
@@ -33,3 +36,272 @@ impl fmt::Display for ReqType {
         }
     }
 }
+
+/// The unit that trackers write simulation time in.
+///
+/// Simulation time is passed to [`Track::time`](crate::Track::time) as a whole
+/// number of ticks of a unit, and each tracker only converts it if it writes
+/// time in a different unit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeUnit {
+    /// Picoseconds, for clocks faster than 1GHz.
+    Ps,
+
+    /// Nanoseconds.
+    #[default]
+    Ns,
+
+    /// Microseconds, for long simulations.
+    Us,
+}
+
+impl TimeUnit {
+    /// Return the number of picoseconds in this unit.
+    fn ps_per_unit(self) -> u64 {
+        match self {
+            TimeUnit::Ps => 1,
+            TimeUnit::Ns => 1000,
+            TimeUnit::Us => 1_000_000,
+        }
+    }
+
+    /// Convert a whole number of this unit to `unit`.
+    #[must_use]
+    pub fn convert(self, ticks: u64, unit: TimeUnit) -> f64 {
+        if self == unit {
+            return ticks as f64;
+        }
+        ticks as f64 * self.ps_per_unit() as f64 / unit.ps_per_unit() as f64
+    }
+
+    /// Convert a whole number of this unit to a whole number of `unit`.
+    ///
+    /// The time is rounded to the nearest `unit` if `unit` is coarser than
+    /// this one.
+    #[must_use]
+    pub fn convert_ticks(self, ticks: u64, unit: TimeUnit) -> u64 {
+        let ps = u128::from(ticks) * u128::from(self.ps_per_unit());
+        let per_unit = u128::from(unit.ps_per_unit());
+        ((ps + per_unit / 2) / per_unit) as u64
+    }
+
+    /// Return the number of this unit in a nanosecond.
+    #[must_use]
+    pub fn per_ns(self) -> f64 {
+        match self {
+            TimeUnit::Ps => 1000.0,
+            TimeUnit::Ns => 1.0,
+            TimeUnit::Us => 0.001,
+        }
+    }
+
+    /// Convert a time in nanoseconds to this unit.
+    #[must_use]
+    pub fn from_ns(self, time_ns: f64) -> f64 {
+        time_ns * self.per_ns()
+    }
+
+    /// Convert a time in nanoseconds to a whole number of this unit.
+    ///
+    /// The time is rounded to the nearest unit, so that a time such as
+    /// 2.01ns is 2010ps even though it is not exactly representable.
+    #[must_use]
+    pub fn ticks_from_ns(self, time_ns: f64) -> u64 {
+        self.from_ns(time_ns).round() as u64
+    }
+
+    /// Convert a time in this unit to nanoseconds.
+    #[must_use]
+    pub fn to_ns(self, time: f64) -> f64 {
+        time / self.per_ns()
+    }
+}
+
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeUnit::Ps => write!(f, "ps"),
+            TimeUnit::Ns => write!(f, "ns"),
+            TimeUnit::Us => write!(f, "us"),
+        }
+    }
+}
+
+/// The kinds of event that trackers can be limited to, in addition to the
+/// level of each entity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// The creation and destruction of entities and objects, and connections
+    /// between entities.
+    Lifecycle,
+
+    /// Log messages.
+    Messages,
+
+    /// Values, capacities, counters and histograms.
+    Values,
+
+    /// Objects entering and exiting entities.
+    Movement,
+
+    /// Activities on lanes and their membership of groups, and spans of
+    /// entities.
+    Activities,
+
+    /// Register reads and writes.
+    Registers,
+}
+
+impl EventKind {
+    const ALL: [EventKind; 6] = [
+        EventKind::Lifecycle,
+        EventKind::Messages,
+        EventKind::Values,
+        EventKind::Movement,
+        EventKind::Activities,
+        EventKind::Registers,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            EventKind::Lifecycle => "lifecycle",
+            EventKind::Messages => "messages",
+            EventKind::Values => "values",
+            EventKind::Movement => "movement",
+            EventKind::Activities => "activities",
+            EventKind::Registers => "registers",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A set of [`EventKind`]s.
+///
+/// Sets are parsed from a comma-separated list of kinds such as
+/// `lifecycle,values`, or `all`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EventKinds(u8);
+
+impl EventKinds {
+    /// The set of all kinds of event.
+    #[must_use]
+    pub fn all() -> Self {
+        EventKind::ALL.into_iter().collect()
+    }
+
+    /// The empty set.
+    #[must_use]
+    pub fn none() -> Self {
+        Self(0)
+    }
+
+    /// Return whether the set contains `kind`.
+    #[must_use]
+    pub fn contains(self, kind: EventKind) -> bool {
+        self.0 & kind.bit() != 0
+    }
+
+    /// Return the kinds of event in either set.
+    #[must_use]
+    pub fn union(self, other: EventKinds) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl Default for EventKinds {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromIterator<EventKind> for EventKinds {
+    fn from_iter<I: IntoIterator<Item = EventKind>>(kinds: I) -> Self {
+        Self(kinds.into_iter().fold(0, |bits, kind| bits | kind.bit()))
+    }
+}
+
+impl FromStr for EventKinds {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut kinds = Self::none();
+        for name in s.split(',').map(str::trim) {
+            if name == "all" {
+                return Ok(Self::all());
+            }
+            match EventKind::ALL.into_iter().find(|kind| kind.name() == name) {
+                Some(kind) => kinds.0 |= kind.bit(),
+                None => {
+                    let names: Vec<&str> = EventKind::ALL.iter().map(|k| k.name()).collect();
+                    return Err(format!(
+                        "Unknown event kind '{name}', expected 'all' or one of: {}",
+                        names.join(", ")
+                    ));
+                }
+            }
+        }
+        Ok(kinds)
+    }
+}
+
+impl TryFrom<String> for EventKinds {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<EventKinds> for String {
+    fn from(kinds: EventKinds) -> Self {
+        kinds.to_string()
+    }
+}
+
+impl fmt::Display for EventKinds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self == Self::all() {
+            return write!(f, "all");
+        }
+        let names: Vec<&str> = EventKind::ALL
+            .into_iter()
+            .filter(|kind| self.contains(*kind))
+            .map(EventKind::name)
+            .collect();
+        write!(f, "{}", names.join(","))
+    }
+}
+
+/// The place in the source code that a log message was written from.
+///
+/// The log macros such as [`info!`](crate::info) capture the call site of each
+/// message so that it can be shown with the message, as the
+/// [`log`](https://docs.rs/log) crate does.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CallSite<'a> {
+    /// The path of the module that the message was written from.
+    pub module_path: &'a str,
+
+    /// The source file that the message was written from.
+    pub file: &'a str,
+
+    /// The line of the source file.
+    pub line: u32,
+}
+
+impl fmt::Display for CallSite<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
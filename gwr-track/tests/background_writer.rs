@@ -0,0 +1,81 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use gwr_track::background_writer::{BackgroundWriter, QueueFullPolicy};
+use gwr_track::test_helpers::SharedVec;
+
+const CHUNK_BYTES: usize = 64 * 1024;
+
+/// A writer that signals when it is first written to and then waits to be
+/// released
+struct GatedWriter {
+    output: SharedVec,
+    started: Option<Sender<()>>,
+    release: Receiver<()>,
+}
+
+impl Write for GatedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(started) = self.started.take() {
+            started.send(()).unwrap();
+            self.release.recv().unwrap();
+        }
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn block_policy_keeps_all_output_in_order() {
+    let output = SharedVec::default();
+    let mut expected = Vec::new();
+    {
+        let mut writer = BackgroundWriter::new(output.clone(), 1, QueueFullPolicy::Block);
+        for i in 0..10_000_u32 {
+            let line = format!("event {i}\n");
+            writer.write_all(line.as_bytes()).unwrap();
+            expected.extend_from_slice(line.as_bytes());
+        }
+        writer.flush().unwrap();
+        assert_eq!(output.bytes(), expected);
+        writer.write_all(b"last\n").unwrap();
+        expected.extend_from_slice(b"last\n");
+        assert_eq!(writer.dropped_bytes(), 0);
+    }
+    // Dropping the writer writes everything that is left
+    assert_eq!(output.bytes(), expected);
+}
+
+#[test]
+fn drop_policy_drops_chunks_when_the_queue_is_full() {
+    let output = SharedVec::default();
+    let (started_sender, started) = mpsc::channel();
+    let (release, release_receiver) = mpsc::channel();
+    let gated = GatedWriter {
+        output: output.clone(),
+        started: Some(started_sender),
+        release: release_receiver,
+    };
+    let mut writer = BackgroundWriter::new(gated, 1, QueueFullPolicy::Drop);
+
+    // The first chunk is taken by the writer thread, which then waits
+    writer.write_all(&[1; CHUNK_BYTES]).unwrap();
+    started.recv().unwrap();
+
+    // The second chunk fills the queue and the third is dropped
+    writer.write_all(&[2; CHUNK_BYTES]).unwrap();
+    writer.write_all(&[3; CHUNK_BYTES]).unwrap();
+    assert_eq!(writer.dropped_bytes(), CHUNK_BYTES as u64);
+
+    release.send(()).unwrap();
+    writer.flush().unwrap();
+
+    let mut expected = vec![1; CHUNK_BYTES];
+    expected.extend_from_slice(&[2; CHUNK_BYTES]);
+    assert_eq!(output.bytes(), expected);
+}
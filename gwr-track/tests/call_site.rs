@@ -0,0 +1,77 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::rc::Rc;
+
+use gwr_track::entity::toplevel;
+use gwr_track::trace_visitor::{TraceVisitor, process_capnp};
+use gwr_track::tracker::types::CallSite;
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Id, Tracker, Writer, info};
+
+#[derive(Default)]
+struct LogVisitor {
+    /// The message and call site of each log event
+    logs: Vec<(String, Option<(String, String, u32)>)>,
+}
+
+impl TraceVisitor for LogVisitor {
+    fn log(&mut self, _id: Id, _level: log::Level, message: &str) {
+        self.logs.push((message.to_string(), None));
+    }
+
+    fn log_at(&mut self, _id: Id, _level: log::Level, call_site: &CallSite, message: &str) {
+        self.logs.push((
+            message.to_string(),
+            Some((
+                call_site.module_path.to_string(),
+                call_site.file.to_string(),
+                call_site.line,
+            )),
+        ));
+    }
+}
+
+#[test]
+fn capnp_call_sites_are_read_back() {
+    let path = std::env::temp_dir().join(format!("gwr-track-call-site-{}.bin", std::process::id()));
+    let writer: Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(CapnProtoTracker::new(
+        EntityManager::new(log::Level::Trace),
+        writer,
+    ));
+    let line;
+    {
+        let top = toplevel(&tracker, "top");
+        info!(top ; "from macro");
+        line = line!() - 1;
+        tracker.log(top.id, log::Level::Info, format_args!("without call site"));
+    }
+    tracker.shutdown();
+
+    let mut visitor = LogVisitor::default();
+    process_capnp(BufReader::new(fs::File::open(&path).unwrap()), &mut visitor);
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(
+        visitor.logs,
+        [
+            (
+                "from macro".to_string(),
+                Some((module_path!().to_string(), file!().to_string(), line))
+            ),
+            ("without call site".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn call_site_is_displayed_as_file_and_line() {
+    let call_site = CallSite {
+        module_path: "model::cache",
+        file: "src/cache.rs",
+        line: 42,
+    };
+    assert_eq!(call_site.to_string(), "src/cache.rs:42");
+}
@@ -0,0 +1,68 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::BufWriter;
+use std::path::Path;
+use std::rc::Rc;
+
+use gwr_track::compression::{DEFAULT_ZSTD_LEVEL, ZstdWriter, open_trace};
+use gwr_track::entity::toplevel;
+use gwr_track::trace_visitor::{TraceVisitor, process_capnp};
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Id, Tracker, Writer};
+
+#[derive(Default)]
+struct ValueVisitor {
+    values: Vec<f64>,
+}
+
+impl TraceVisitor for ValueVisitor {
+    fn value(&mut self, _id: Id, value: f64) {
+        self.values.push(value);
+    }
+}
+
+/// Write a trace of values and return the values read back from it
+fn round_trip(path: &Path, writer: Writer) -> Vec<f64> {
+    let tracker: Tracker = Rc::new(CapnProtoTracker::new(
+        EntityManager::new(log::Level::Trace),
+        writer,
+    ));
+    {
+        let top = toplevel(&tracker, "top");
+        for i in 0..1000 {
+            tracker.value(top.id, f64::from(i % 10));
+        }
+    }
+    // Dropping the tracker completes the compressed stream
+    drop(tracker);
+
+    let mut visitor = ValueVisitor::default();
+    process_capnp(open_trace(path).unwrap(), &mut visitor);
+    visitor.values
+}
+
+#[test]
+fn compressed_traces_are_read_transparently() {
+    let temp_dir = std::env::temp_dir();
+    let id = std::process::id();
+    let plain_path = temp_dir.join(format!("gwr-track-plain-{id}.bin"));
+    let zstd_path = temp_dir.join(format!("gwr-track-zstd-{id}.bin"));
+
+    let plain: Writer = Box::new(BufWriter::new(fs::File::create(&plain_path).unwrap()));
+    let plain_values = round_trip(&plain_path, plain);
+
+    let compressed: Writer = Box::new(
+        ZstdWriter::new(fs::File::create(&zstd_path).unwrap(), DEFAULT_ZSTD_LEVEL).unwrap(),
+    );
+    let zstd_values = round_trip(&zstd_path, compressed);
+
+    let plain_size = fs::metadata(&plain_path).unwrap().len();
+    let zstd_size = fs::metadata(&zstd_path).unwrap().len();
+    fs::remove_file(plain_path).unwrap();
+    fs::remove_file(zstd_path).unwrap();
+
+    assert_eq!(plain_values.len(), 1000);
+    assert_eq!(zstd_values, plain_values);
+    assert!(zstd_size < plain_size / 4);
+}
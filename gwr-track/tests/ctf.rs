@@ -0,0 +1,51 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::rc::Rc;
+
+use gwr_track::entity::toplevel;
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{CtfTracker, EntityManager};
+use gwr_track::{Tracker, info};
+
+/// The header of an event and its ID fields
+fn event(event_id: u32, timestamp: u64, ids: &[u64]) -> Vec<u8> {
+    let mut bytes = event_id.to_le_bytes().to_vec();
+    bytes.extend(timestamp.to_le_bytes());
+    for id in ids {
+        bytes.extend(id.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn events_are_written_as_a_ctf_trace() {
+    let trace_dir = std::env::temp_dir().join(format!("gwr-track-ctf-{}", std::process::id()));
+    let tracker: Tracker =
+        Rc::new(CtfTracker::new(EntityManager::new(log::Level::Trace), &trace_dir).unwrap());
+
+    {
+        let top = toplevel(&tracker, "top");
+        tracker.time(top.id, 2500, TimeUnit::Ps);
+        info!(top ; "hi");
+    }
+    tracker.shutdown();
+
+    let metadata = fs::read_to_string(trace_dir.join("metadata")).unwrap();
+    let stream = fs::read(trace_dir.join("stream_0")).unwrap();
+    fs::remove_dir_all(trace_dir).unwrap();
+    assert!(metadata.starts_with("/* CTF 1.8 */"));
+    assert_eq!(metadata, CtfTracker::metadata());
+
+    let mut expected = vec![0xC1, 0x1F, 0xFC, 0xC1, 0, 0, 0, 0];
+    // create_entity by NO_ID of the top-level entity
+    expected.extend(event(4, 0, &[0, 2]));
+    expected.extend(b"top\0");
+    // log at INFO after the time has advanced to 2500ps
+    expected.extend(event(0, 2500, &[2]));
+    expected.push(3);
+    expected.extend(b"hi\0");
+    // destroy of the top-level entity as it is dropped
+    expected.extend(event(15, 2500, &[2, 0]));
+    assert_eq!(stream, expected);
+}
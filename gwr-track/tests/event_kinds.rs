@@ -0,0 +1,49 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::test_helpers::SharedVec;
+use gwr_track::tracker::types::{EventKind, EventKinds};
+use gwr_track::tracker::{EntityManager, TextTracker};
+use gwr_track::{Tracker, info};
+
+#[test]
+fn only_lifecycle_events_are_written() {
+    let output = SharedVec::default();
+    let mut entity_manager = EntityManager::new(log::Level::Trace);
+    entity_manager
+        .add_event_kinds_filter(r"top::dev.*", "lifecycle".parse().unwrap())
+        .unwrap();
+    let tracker: Tracker = Rc::new(TextTracker::new(entity_manager, Box::new(output.clone())));
+
+    let top = toplevel(&tracker, "top");
+    let dev = Rc::new(Entity::new(&top, "dev"));
+    let node = Entity::new(&dev, "node");
+    tracker.value(node.id, 1.0);
+    tracker.create_object(node.id, tracker.unique_id(), 8, "bytes", 0, "packet");
+    info!(node ; "from node");
+    info!(top ; "from top");
+
+    let text = output.text();
+    assert!(text.contains(&format!("created entity {}, top::dev::node", node.id)));
+    assert!(text.contains("created object"));
+    assert!(!text.contains("value"));
+    assert!(!text.contains("from node"));
+
+    // Entities that do not match have all kinds of event enabled
+    assert!(text.contains("from top"));
+    assert_eq!(tracker.enabled_event_kinds(top.id), EventKinds::all());
+}
+
+#[test]
+fn event_kinds_are_parsed_from_a_list() {
+    let kinds: EventKinds = "lifecycle, values".parse().unwrap();
+    assert!(kinds.contains(EventKind::Lifecycle));
+    assert!(kinds.contains(EventKind::Values));
+    assert!(!kinds.contains(EventKind::Messages));
+    assert_eq!(kinds.to_string(), "lifecycle,values");
+
+    assert_eq!("all".parse::<EventKinds>().unwrap(), EventKinds::all());
+    assert!("lifecycle,objects".parse::<EventKinds>().is_err());
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{EntityManager, FailOnErrorTracker, TextTracker};
+use gwr_track::{Tracker, error, warn};
+
+fn fail_on_error_tracker(regex_str: &str) -> Rc<FailOnErrorTracker> {
+    let tracker: Tracker = Rc::new(TextTracker::new(
+        EntityManager::new(log::Level::Info),
+        Box::new(std::io::sink()),
+    ));
+    Rc::new(FailOnErrorTracker::new(tracker, regex_str).unwrap())
+}
+
+#[test]
+fn no_errors_does_not_fail() {
+    let fail_on_error = fail_on_error_tracker("");
+    let tracker: Tracker = fail_on_error.clone();
+    let top = toplevel(&tracker, "top");
+    warn!(top ; "only a warning");
+
+    assert_eq!(fail_on_error.error_summary(), None);
+    tracker.shutdown();
+}
+
+#[test]
+fn errors_are_summarised() {
+    let fail_on_error = fail_on_error_tracker("");
+    let tracker: Tracker = fail_on_error.clone();
+    let top = toplevel(&tracker, "top");
+    let node = Entity::new(&top, "node");
+    tracker.time(top.id, 10, TimeUnit::Ns);
+    error!(node ; "queue overflow");
+
+    let summary = fail_on_error.error_summary().unwrap();
+    assert!(summary.starts_with("1 error(s) logged during simulation:\n"));
+    assert!(summary.contains("10ns top::node: queue overflow"));
+    assert!(summary.contains(file!()));
+}
+
+#[test]
+fn only_errors_matching_regex_are_recorded() {
+    let fail_on_error = fail_on_error_tracker("node.*overflow");
+    let tracker: Tracker = fail_on_error.clone();
+    let top = toplevel(&tracker, "top");
+    let node = Entity::new(&top, "node");
+    error!(top ; "queue overflow");
+    error!(node ; "bad address");
+
+    assert_eq!(fail_on_error.error_summary(), None);
+
+    error!(node ; "queue overflow");
+    assert!(
+        fail_on_error
+            .error_summary()
+            .unwrap()
+            .starts_with("1 error(s)")
+    );
+}
+
+#[test]
+#[should_panic(expected = "1 error(s) logged during simulation")]
+fn shutdown_fails_after_errors() {
+    let tracker: Tracker = fail_on_error_tracker("");
+    let top = toplevel(&tracker, "top");
+    error!(top ; "deadlock detected");
+    tracker.shutdown();
+}
+
+#[test]
+fn invalid_regex_is_an_error() {
+    let tracker: Tracker = Rc::new(TextTracker::new(
+        EntityManager::new(log::Level::Info),
+        Box::new(std::io::sink()),
+    ));
+    assert!(FailOnErrorTracker::new(tracker, "(").is_err());
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::test_helpers::SharedVec;
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{EntityManager, FlightRecorderTracker, TextTracker};
+use gwr_track::{Tracker, error};
+
+#[test]
+fn only_recent_events_are_dumped_on_error() {
+    let output = SharedVec::default();
+    let dump_to: Tracker = Rc::new(TextTracker::new(
+        EntityManager::new(log::Level::Trace),
+        Box::new(output.clone()),
+    ));
+    let tracker: Tracker = Rc::new(FlightRecorderTracker::new(
+        EntityManager::new(log::Level::Trace),
+        3,
+        dump_to,
+    ));
+
+    let top = toplevel(&tracker, "top");
+    let node = Entity::new(&top, "node");
+    for i in 0..5 {
+        tracker.time(node.id, u64::from(i), TimeUnit::Ns);
+        tracker.value(node.id, f64::from(i));
+    }
+    assert!(output.lines().is_empty());
+
+    error!(node ; "failed");
+    let lines = output.lines();
+    let node_id = node.id;
+
+    // The creation of the entity is kept even though it is the oldest event
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.contains(&format!("created entity {node_id}")))
+    );
+
+    let node_events: Vec<&str> = lines
+        .iter()
+        .map(String::as_str)
+        .filter(|l| l.starts_with(&format!("{node_id}:")) && !l.contains("set time"))
+        .collect();
+    assert_eq!(
+        node_events,
+        [
+            format!("{node_id}: value 3"),
+            format!("{node_id}: value 4"),
+            format!("{node_id}:ERROR: failed"),
+        ]
+    );
+
+    // A second error only dumps the events since the first
+    tracker.value(node.id, 5.0);
+    error!(node ; "failed again");
+    let lines = output.lines();
+    assert_eq!(lines.iter().filter(|l| l.contains("value 4")).count(), 1);
+    assert!(lines.contains(&format!("{node_id}: value 5")));
+}
+
+#[test]
+fn object_events_are_kept_with_their_entity() {
+    let output = SharedVec::default();
+    let dump_to: Tracker = Rc::new(TextTracker::new(
+        EntityManager::new(log::Level::Trace),
+        Box::new(output.clone()),
+    ));
+    let tracker: Tracker = Rc::new(FlightRecorderTracker::new(
+        EntityManager::new(log::Level::Trace),
+        3,
+        dump_to,
+    ));
+
+    let top = toplevel(&tracker, "top");
+    let node = Entity::new(&top, "node");
+    let mut objects = Vec::new();
+    for _ in 0..5 {
+        let object = tracker.unique_id();
+        tracker.create_object(node.id, object, 1, "bytes", 0, "");
+        tracker.destroy(node.id, object);
+        objects.push(object);
+    }
+
+    // Only the most recent events of the node are dumped, rather than the
+    // destruction of every object
+    error!(node ; "failed");
+    let lines = output.lines();
+    let node_id = node.id;
+    let last = objects[4];
+    let node_events: Vec<&str> = lines
+        .iter()
+        .map(String::as_str)
+        .filter(|l| l.starts_with(&format!("{node_id}:")) && !l.contains("set time"))
+        .collect();
+    assert_eq!(
+        node_events,
+        [
+            format!("{node_id}: created object {last}, 0, 1, bytes, "),
+            format!("{node_id}: destroyed {last}"),
+            format!("{node_id}:ERROR: failed"),
+        ]
+    );
+}
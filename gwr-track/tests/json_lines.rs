@@ -0,0 +1,50 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::BufWriter;
+use std::rc::Rc;
+
+use gwr_track::entity::toplevel;
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{EntityManager, JsonLinesTracker};
+use gwr_track::{Tracker, info};
+use serde_json::Value;
+
+#[test]
+fn events_are_written_as_json_lines() {
+    let path = std::env::temp_dir().join(format!("gwr-track-json-{}.jsonl", std::process::id()));
+    let writer: gwr_track::Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(JsonLinesTracker::new(
+        EntityManager::new(log::Level::Trace),
+        writer,
+    ));
+
+    {
+        let top = toplevel(&tracker, "top");
+        tracker.time(top.id, 12_500, TimeUnit::Ps);
+        info!(top ; "hello \"world\"");
+        tracker.value(top.id, 3.0);
+    }
+    tracker.shutdown();
+
+    let contents = fs::read_to_string(&path).unwrap();
+    fs::remove_file(path).unwrap();
+    let events: Vec<Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let log = events.iter().find(|event| event["kind"] == "log").unwrap();
+    assert_eq!(log["level"], "INFO");
+    assert_eq!(log["message"], "hello \"world\"");
+    assert_eq!(log["entity"], "top");
+    assert_eq!(log["time_ns"], 12.5);
+
+    let value = events
+        .iter()
+        .find(|event| event["kind"] == "value")
+        .unwrap();
+    assert_eq!(value["level"], "TRACE");
+    assert_eq!(value["message"], "value 3");
+    assert_eq!(value["id"], log["id"]);
+}
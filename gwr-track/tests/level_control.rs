@@ -0,0 +1,81 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use gwr_track::Tracker;
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::tracker::{EntityManager, LevelControlTracker, TextTracker};
+
+fn text_tracker(level: log::Level) -> Tracker {
+    Rc::new(TextTracker::new(
+        EntityManager::new(level),
+        Box::new(std::io::sink()),
+    ))
+}
+
+#[test]
+fn levels_can_be_changed_while_running() {
+    let tracker = text_tracker(log::Level::Warn);
+    let top = toplevel(&tracker, "top");
+    let node0 = Entity::new(&top, "node0");
+    let node1 = Entity::new(&top, "node1");
+    assert!(!node0.trace_enabled());
+
+    tracker
+        .set_entity_level(r".*node0", log::Level::Trace)
+        .unwrap();
+    assert!(node0.trace_enabled());
+    assert!(!node1.trace_enabled());
+    assert!(top.enabled_for(log::Level::Warn));
+
+    // Entities created later also use the new level
+    let node0_child = Entity::new(&Rc::new(node0), "node0");
+    assert!(node0_child.trace_enabled());
+
+    tracker
+        .set_entity_level(r"top.*", log::Level::Error)
+        .unwrap();
+    assert!(!node0_child.enabled_for(log::Level::Warn));
+    assert!(!top.enabled_for(log::Level::Warn));
+}
+
+#[test]
+fn levels_are_read_from_a_control_file() {
+    let control_file =
+        std::env::temp_dir().join(format!("gwr-track-levels-{}.txt", std::process::id()));
+    fs::write(&control_file, "# Comment\n\ntop::node0 debug\n").unwrap();
+
+    let level_control = Rc::new(
+        LevelControlTracker::new(text_tracker(log::Level::Warn), control_file.clone()).unwrap(),
+    );
+    let tracker: Tracker = level_control.clone();
+    let top = toplevel(&tracker, "top");
+    let node0 = Entity::new(&top, "node0");
+    assert!(node0.enabled_for(log::Level::Debug));
+    assert!(!node0.trace_enabled());
+
+    fs::write(&control_file, "top::node0 debug\ntop.* trace\n").unwrap();
+    // Ensure the change is seen even if the clock is coarse
+    fs::File::options()
+        .write(true)
+        .open(&control_file)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+    level_control.apply_if_modified().unwrap();
+    assert!(node0.trace_enabled());
+    assert!(top.trace_enabled());
+
+    fs::write(&control_file, "top::node0 loud\n").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&control_file)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(20))
+        .unwrap();
+    let error = level_control.apply_if_modified().unwrap_err();
+    fs::remove_file(control_file).unwrap();
+    assert!(error.0.contains("Unknown level 'loud'"));
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::rc::Rc;
+
+use gwr_track::metadata::RunMetadata;
+use gwr_track::trace_visitor::{TraceEvent, TraceEvents};
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Tracker, Writer};
+
+#[test]
+fn metadata_is_read_back_from_capnp() {
+    let path = std::env::temp_dir().join(format!("gwr-track-metadata-{}.bin", std::process::id()));
+    let writer: Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(CapnProtoTracker::new(
+        EntityManager::new(log::Level::Info),
+        writer,
+    ));
+    RunMetadata::default()
+        .with("command_line", "sim-ring --seed=42")
+        .with_seed(42)
+        .record(&tracker);
+    tracker.shutdown();
+
+    let metadata: Vec<TraceEvent> =
+        TraceEvents::new(BufReader::new(fs::File::open(&path).unwrap()))
+            .filter(|event| matches!(event, TraceEvent::Metadata { .. }))
+            .collect();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        metadata,
+        [
+            TraceEvent::Metadata {
+                key: "command_line".to_string(),
+                value: "sim-ring --seed=42".to_string(),
+            },
+            TraceEvent::Metadata {
+                key: "seed".to_string(),
+                value: "42".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn process_metadata_is_recorded() {
+    let metadata = RunMetadata::from_process();
+    let keys: Vec<&str> = metadata
+        .entries()
+        .iter()
+        .map(|(key, _)| key.as_str())
+        .collect();
+    assert_eq!(keys, ["command_line", "start_time", "gwr_track_version"]);
+}
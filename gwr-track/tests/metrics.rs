@@ -0,0 +1,58 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::trace_visitor::{TraceVisitor, process_capnp};
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Id, Tracker};
+
+#[derive(Default)]
+struct MetricVisitor {
+    events: Vec<String>,
+}
+
+impl TraceVisitor for MetricVisitor {
+    fn counter(&mut self, id: Id, name: &str, delta: f64) {
+        self.events.push(format!("{id}: counter {name} {delta}"));
+    }
+
+    fn histogram(&mut self, id: Id, name: &str, value: f64) {
+        self.events.push(format!("{id}: histogram {name} {value}"));
+    }
+}
+
+#[test]
+fn metrics_round_trip_through_capnp_trace() {
+    let path = std::env::temp_dir().join(format!("gwr-track-metrics-{}.bin", std::process::id()));
+    let writer: gwr_track::Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(CapnProtoTracker::new(
+        EntityManager::new(log::Level::Trace),
+        writer,
+    ));
+
+    {
+        let top = toplevel(&tracker, "top");
+        let link = Entity::new(&top, "link");
+        link.track_counter("retries", 1.0);
+        link.track_counter("retries", 2.0);
+        link.track_histogram("latency_ns", 12.5);
+    }
+    tracker.shutdown();
+
+    let mut visitor = MetricVisitor::default();
+    let reader = BufReader::new(fs::File::open(&path).unwrap());
+    process_capnp(reader, &mut visitor);
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(
+        visitor.events,
+        [
+            "3: counter retries 1",
+            "3: counter retries 2",
+            "3: histogram latency_ns 12.5",
+        ]
+    );
+}
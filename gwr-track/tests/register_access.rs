@@ -0,0 +1,64 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, RegisterAccess, toplevel};
+use gwr_track::trace_visitor::{TraceVisitor, process_capnp};
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Id, Tracker};
+
+#[derive(Default)]
+struct RegisterAccessVisitor {
+    accesses: Vec<(Id, RegisterAccess)>,
+}
+
+impl TraceVisitor for RegisterAccessVisitor {
+    fn register_access(&mut self, id: Id, access: RegisterAccess) {
+        self.accesses.push((id, access));
+    }
+}
+
+#[test]
+fn register_accesses_round_trip_through_capnp_trace() {
+    let path = std::env::temp_dir().join(format!(
+        "gwr-track-register-access-{}.bin",
+        std::process::id()
+    ));
+    let writer: gwr_track::Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(CapnProtoTracker::new(
+        EntityManager::new(log::Level::Trace),
+        writer,
+    ));
+
+    let write = RegisterAccess::write(0x4, "ctrl", 0x81, 0xf1);
+    let read = RegisterAccess::read(0x8, "status", 0x1200);
+    let regs_id = {
+        let top = toplevel(&tracker, "top");
+        let regs = Entity::new(&top, "regs");
+        regs.track_register_access(write.clone());
+        regs.track_register_access(read.clone());
+        regs.id
+    };
+    tracker.shutdown();
+
+    let mut visitor = RegisterAccessVisitor::default();
+    let reader = BufReader::new(fs::File::open(&path).unwrap());
+    process_capnp(reader, &mut visitor);
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(visitor.accesses, [(regs_id, write), (regs_id, read)]);
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        RegisterAccess::write(0x4, "ctrl", 0x81, 0xf1).to_string(),
+        "register write ctrl@0x4: 0x81 -> 0xf1"
+    );
+    assert_eq!(
+        RegisterAccess::read(0x8, "status", 0x1200).to_string(),
+        "register read status@0x8: 0x1200"
+    );
+}
@@ -0,0 +1,167 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::rotation::{OpenFile, RotatingWriter, TraceHeader, rotated_path};
+use gwr_track::trace_visitor::{TraceEvent, TraceEvents};
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Id, Tracker, Writer};
+
+fn open_file() -> OpenFile {
+    Box::new(|path| {
+        let file = fs::File::create(path)?;
+        let writer: Box<dyn Write + Send> = Box::new(file);
+        Ok(writer)
+    })
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("gwr-track-rotation-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn old_files_are_removed_as_files_fill() {
+    let dir = temp_dir("sizes");
+    let path = dir.join("trace.bin");
+
+    let mut writer = RotatingWriter::new(&path, 10, 2, open_file()).unwrap();
+    for event in ["0123456", "789", "abcdef", "ghijklmnopqrstuvwxyz", "end"] {
+        writer.write_all(event.as_bytes()).unwrap();
+    }
+    writer.flush().unwrap();
+    assert_eq!(writer.current_path(), rotated_path(&path, 2));
+    drop(writer);
+
+    let contents: Vec<Option<String>> = (0..3)
+        .map(|i| fs::read_to_string(rotated_path(&path, i)).ok())
+        .collect();
+    fs::remove_dir_all(dir).unwrap();
+
+    // Writes are never split between files, even when they overfill them
+    assert_eq!(
+        contents,
+        [
+            None,
+            Some("abcdefghijklmnopqrstuvwxyz".to_string()),
+            Some("end".to_string()),
+        ]
+    );
+}
+
+fn header_events(path: &Path) -> Vec<TraceEvent> {
+    TraceEvents::new(BufReader::new(fs::File::open(path).unwrap()))
+        .filter(|event| matches!(event, TraceEvent::TimeUnit(_) | TraceEvent::Metadata { .. }))
+        .collect()
+}
+
+#[test]
+fn every_file_starts_with_the_header() {
+    let dir = temp_dir("header");
+    let path = dir.join("trace.bin");
+
+    let header = TraceHeader::default();
+    let writer: Writer = Box::new(
+        RotatingWriter::new(&path, 256, 2, open_file())
+            .unwrap()
+            .with_header(header.clone()),
+    );
+    let tracker: Tracker = Rc::new(
+        CapnProtoTracker::new(EntityManager::new(log::Level::Trace), writer)
+            .with_header(header)
+            .with_time_unit(TimeUnit::Ps),
+    );
+    {
+        let top = toplevel(&tracker, "top");
+        tracker.metadata("seed", "42");
+        for i in 0..100 {
+            tracker.time(top.id, u64::from(i), TimeUnit::Ns);
+            tracker.value(top.id, f64::from(i));
+        }
+    }
+    tracker.shutdown();
+    drop(tracker);
+
+    let expected = [
+        TraceEvent::TimeUnit(TimeUnit::Ps),
+        TraceEvent::Metadata {
+            key: "seed".to_string(),
+            value: "42".to_string(),
+        },
+    ];
+    // The first file has been removed, so the remaining files only have the
+    // header because it was written again
+    assert!(!rotated_path(&path, 0).exists());
+    let remaining: Vec<_> = (1..1000)
+        .map(|index| rotated_path(&path, index))
+        .filter(|rotated| rotated.exists())
+        .collect();
+    let headers: Vec<_> = remaining
+        .iter()
+        .map(|rotated| header_events(rotated))
+        .collect();
+    fs::remove_dir_all(dir).unwrap();
+
+    assert_eq!(headers, [expected.clone(), expected]);
+}
+
+#[test]
+fn later_files_name_the_entities_created_before_them() {
+    let dir = temp_dir("entities");
+    let path = dir.join("trace.bin");
+
+    let header = TraceHeader::default();
+    let writer: Writer = Box::new(
+        RotatingWriter::new(&path, 256, 4, open_file())
+            .unwrap()
+            .with_header(header.clone()),
+    );
+    let tracker: Tracker = Rc::new(
+        CapnProtoTracker::new(EntityManager::new(log::Level::Trace), writer).with_header(header),
+    );
+    let node_id;
+    {
+        let top = toplevel(&tracker, "top");
+        let node = Entity::new(&top, "node");
+        node_id = node.id;
+        for i in 0..20 {
+            tracker.time(node.id, u64::from(i), TimeUnit::Ns);
+            tracker.value(node.id, f64::from(i));
+        }
+    }
+    tracker.shutdown();
+    drop(tracker);
+
+    // The entities were created while the first file was written, but the
+    // second file can be read on its own
+    let second = rotated_path(&path, 1);
+    let events: Vec<TraceEvent> =
+        TraceEvents::new(BufReader::new(fs::File::open(&second).unwrap())).collect();
+    fs::remove_dir_all(dir).unwrap();
+
+    let names: HashMap<Id, String> = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::CreateEntity { id, name, .. } => Some((*id, name.clone())),
+            _ => None,
+        })
+        .collect();
+    let values: Vec<Id> = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Value { id, .. } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    assert!(!values.is_empty());
+    assert!(values.iter().all(|id| *id == node_id));
+    assert_eq!(names[&node_id], "top::node");
+}
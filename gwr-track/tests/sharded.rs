@@ -0,0 +1,86 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::test_helpers::SharedVec;
+use gwr_track::tracker::sharded::ShardFactory;
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{EntityManager, ShardedTracker, TextTracker};
+use gwr_track::{Tracker, info};
+
+type Outputs = Rc<RefCell<HashMap<String, SharedVec>>>;
+
+fn sharded_tracker(shard_regex: &str, outputs: &Outputs) -> Rc<ShardedTracker> {
+    let outputs = outputs.clone();
+    let create_shard: ShardFactory = Box::new(move |shard| {
+        let output = SharedVec::default();
+        outputs
+            .borrow_mut()
+            .insert(shard.to_string(), output.clone());
+        let tracker: Tracker = Rc::new(TextTracker::new(
+            EntityManager::new(log::Level::Trace),
+            Box::new(output),
+        ));
+        Ok(tracker)
+    });
+    Rc::new(
+        ShardedTracker::new(
+            EntityManager::new(log::Level::Trace),
+            shard_regex,
+            create_shard,
+        )
+        .unwrap(),
+    )
+}
+
+fn output_of(outputs: &Outputs, shard: &str) -> String {
+    outputs.borrow()[shard].text()
+}
+
+#[test]
+fn events_are_split_by_device() {
+    let outputs = Outputs::default();
+    let sharded = sharded_tracker(r"^top::(device\d+)", &outputs);
+    let tracker: Tracker = sharded.clone();
+
+    let top = toplevel(&tracker, "top");
+    let device0 = Rc::new(Entity::new(&top, "device0"));
+    let device1 = Rc::new(Entity::new(&top, "device1"));
+    let port = Entity::new(&device1, "port");
+    tracker.time(top.id, 10, TimeUnit::Ns);
+    info!(top ; "starting");
+    info!(device0 ; "zero");
+    info!(port ; "one");
+
+    assert_eq!(sharded.shard_names(), ["common", "device0", "device1"]);
+
+    let common = output_of(&outputs, "common");
+    let device0_output = output_of(&outputs, "device0");
+    let device1_output = output_of(&outputs, "device1");
+
+    assert!(common.contains("starting"));
+    assert!(!common.contains("zero") && !common.contains("one"));
+
+    assert!(device0_output.contains("created entity"));
+    assert!(device0_output.contains("set time to 10.0ns"));
+    assert!(device0_output.contains("zero"));
+    assert!(!device0_output.contains("one"));
+
+    // Entities inside a device are written to the shard of the device
+    assert!(device1_output.contains(&format!("created entity {}, top::device1::port", port.id)));
+    assert!(device1_output.contains("one"));
+}
+
+#[test]
+fn invalid_shard_regex_is_reported() {
+    let create_shard: ShardFactory = Box::new(|_| unreachable!());
+    let result = ShardedTracker::new(
+        EntityManager::new(log::Level::Trace),
+        "top::(",
+        create_shard,
+    );
+    assert!(result.is_err());
+}
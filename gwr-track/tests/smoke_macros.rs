@@ -10,9 +10,10 @@ use gwr_track::entity::{Entity, EntityLane, toplevel};
 use gwr_track::id::Unique;
 use gwr_track::tracker::Tracker;
 use gwr_track::tracker::multi_tracker::MultiTracker;
+use gwr_track::tracker::types::TimeUnit;
 use gwr_track::{
-    Id, create_id, debug, destroy_id, error, info, set_time, test_helpers, test_init, trace,
-    track_create_object, warn,
+    Id, NO_ID, create_id, debug, destroy_id, error, info, set_time, span_begin, span_end,
+    test_helpers, test_init, trace, track_create_object, warn,
 };
 
 #[derive(Debug)]
@@ -213,6 +214,42 @@ fn activity_basics() {
     test_helpers::check_and_clear(&test_tracker, &["70: destroyed"]);
 }
 
+#[test]
+fn span_basics() {
+    let (test_tracker, tracker) = test_init!(90);
+
+    let top = toplevel(&tracker, "top");
+    test_helpers::check_and_clear(&test_tracker, &["0: created entity 90, top"]);
+
+    let span = span_begin!(top ; "decode {}", 3);
+    test_helpers::check_and_clear(&test_tracker, &["90: span 91 begin decode 3"]);
+
+    {
+        let _guard = top.span("lookup");
+        test_helpers::check_and_clear(&test_tracker, &["90: span 92 begin lookup"]);
+    }
+    test_helpers::check_and_clear(&test_tracker, &["90: span 92 end"]);
+
+    span_end!(top ; span);
+    test_helpers::check_and_clear(&test_tracker, &["90: span 91 end"]);
+}
+
+#[test]
+fn disabled_span_macro_does_not_evaluate_name() {
+    let test_tracker = Rc::new(test_helpers::TestTracker::new(800, log::Level::Error));
+    let tracker: Tracker = test_tracker.clone();
+
+    let top = toplevel(&tracker, "top");
+    let evaluations = Cell::new(0);
+
+    let span = span_begin!(top ; "{}", count_format_evaluation(&evaluations));
+    assert_eq!(span, NO_ID);
+    span_end!(top ; span);
+
+    assert_eq!(evaluations.get(), 0);
+    test_helpers::check_and_clear(&test_tracker, &["0: created entity 800, top"]);
+}
+
 #[test]
 fn num_bytes() {
     let (test_tracker, tracker) = test_init!(121);
@@ -234,6 +271,6 @@ fn set_time() {
     let top = toplevel(&tracker, "top");
     test_helpers::check_and_clear(&test_tracker, &["0: created entity 321, top"]);
 
-    set_time!(top ; 10.0);
+    set_time!(top ; 10, TimeUnit::Ns);
     test_helpers::check_and_clear(&test_tracker, &["321: set time 10.0ns"]);
 }
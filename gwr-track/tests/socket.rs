@@ -0,0 +1,131 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use clap::Parser;
+use gwr_track::builder::{TrackerArgs, setup_trackers};
+use gwr_track::entity::toplevel;
+use gwr_track::info;
+use gwr_track::socket::{SocketAddress, SocketWriter};
+use gwr_track::trace_visitor::{TraceEvent, TraceEvents};
+use gwr_track::tracker::types::TimeUnit;
+
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    tracker: TrackerArgs,
+}
+
+/// Run a small simulation that streams its events to `address`
+fn stream_events(args: &[&str]) {
+    let cli = Cli::parse_from([&["test"][..], args].concat());
+    let tracker = setup_trackers(&cli.tracker.trackers_config()).unwrap();
+    {
+        let top = toplevel(&tracker, "top");
+        for i in 0..100 {
+            tracker.time(top.id, u64::from(i), TimeUnit::Ns);
+            tracker.value(top.id, f64::from(i));
+        }
+        info!(top ; "done");
+    }
+    tracker.shutdown();
+}
+
+#[test]
+fn capnp_events_are_streamed_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let viewer = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        TraceEvents::new(BufReader::new(stream)).collect::<Vec<_>>()
+    });
+
+    stream_events(&["--socket", &address]);
+
+    let events = viewer.join().unwrap();
+    let values: Vec<f64> = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Value { value, .. } => Some(*value),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(values, (0..100).map(f64::from).collect::<Vec<_>>());
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, TraceEvent::Log { message, .. } if message == "done"))
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn json_events_are_streamed_over_a_unix_socket() {
+    use std::os::unix::net::UnixListener;
+
+    let path = std::env::temp_dir().join(format!("gwr-track-socket-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    let viewer = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        BufReader::new(stream)
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(&line.unwrap()).unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    let address = format!("unix:{}", path.display());
+    stream_events(&["--socket", &address, "--socket-format", "json"]);
+
+    let events = viewer.join().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| event["kind"] == "value")
+            .count(),
+        100
+    );
+    let log = events.iter().find(|event| event["kind"] == "log").unwrap();
+    assert_eq!(log["message"], "done");
+    assert_eq!(log["time_ns"], 99.0);
+}
+
+#[test]
+fn output_is_dropped_when_the_viewer_goes_away() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address: SocketAddress = listener.local_addr().unwrap().to_string().parse().unwrap();
+    let mut writer = SocketWriter::connect(&address).unwrap();
+    drop(listener.accept().unwrap());
+
+    let chunk = vec![0u8; 64 * 1024];
+    for _ in 0..100 {
+        writer.write_all(&chunk).unwrap();
+        writer.flush().unwrap();
+        if !writer.is_connected() {
+            break;
+        }
+    }
+    assert!(!writer.is_connected());
+    assert!(writer.dropped_bytes() > 0);
+}
+
+#[test]
+fn socket_addresses_are_parsed() {
+    assert_eq!(
+        "localhost:9000".parse::<SocketAddress>(),
+        Ok(SocketAddress::Tcp("localhost:9000".to_string()))
+    );
+    assert_eq!(
+        "tcp:127.0.0.1:9000".parse::<SocketAddress>(),
+        Ok(SocketAddress::Tcp("127.0.0.1:9000".to_string()))
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        "unix:/tmp/gwr.sock".parse::<SocketAddress>(),
+        Ok(SocketAddress::Unix("/tmp/gwr.sock".into()))
+    );
+    assert!("9000".parse::<SocketAddress>().is_err());
+}
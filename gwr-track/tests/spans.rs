@@ -0,0 +1,53 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::trace_visitor::{SpanCollector, process_capnp};
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Tracker, set_time, span_begin, span_end};
+
+#[test]
+fn span_durations_are_read_from_capnp_trace() {
+    let path = std::env::temp_dir().join(format!("gwr-track-spans-{}.bin", std::process::id()));
+    let writer: gwr_track::Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(CapnProtoTracker::new(
+        EntityManager::new(log::Level::Trace),
+        writer,
+    ));
+
+    {
+        let top = toplevel(&tracker, "top");
+        let decoder = Entity::new(&top, "decoder");
+        let decode = span_begin!(decoder ; "decode");
+        set_time!(top ; 2000, TimeUnit::Ps);
+        {
+            let _lookup = decoder.span("lookup");
+            set_time!(top ; 5500, TimeUnit::Ps);
+        }
+        set_time!(top ; 10_000, TimeUnit::Ps);
+        span_end!(decoder ; decode);
+    }
+    tracker.shutdown();
+
+    let mut collector = SpanCollector::default();
+    let reader = BufReader::new(fs::File::open(&path).unwrap());
+    process_capnp(reader, &mut collector);
+    fs::remove_file(path).unwrap();
+
+    let spans: Vec<(&str, f64, f64)> = collector
+        .spans
+        .iter()
+        .map(|span| (span.name.as_str(), span.begin_ns, span.duration_ns()))
+        .collect();
+    assert_eq!(spans, [("lookup", 2.0, 3.5), ("decode", 0.0, 10.0)]);
+    assert!(
+        collector
+            .spans
+            .iter()
+            .all(|span| span.id == collector.spans[0].id)
+    );
+}
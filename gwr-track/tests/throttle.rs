@@ -0,0 +1,89 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::rc::Rc;
+
+use gwr_track::entity::{Entity, toplevel};
+use gwr_track::test_helpers::SharedVec;
+use gwr_track::tracker::{EntityManager, TextTracker, ThrottleTracker};
+use gwr_track::{Tracker, info, warn};
+
+/// Returns the messages written, without the entity that wrote them
+fn messages(output: &SharedVec) -> Vec<String> {
+    output
+        .lines()
+        .iter()
+        .map(|line| line.split_once(": ").unwrap().1.to_string())
+        .collect()
+}
+
+fn throttle_tracker(output: &SharedVec) -> Tracker {
+    let text: Tracker = Rc::new(TextTracker::new(
+        EntityManager::new(log::Level::Info),
+        Box::new(output.clone()),
+    ));
+    Rc::new(ThrottleTracker::new(text).with_max_repeats(log::Level::Warn, 2))
+}
+
+#[test]
+fn repeated_messages_are_dropped() {
+    let output = SharedVec::default();
+    let tracker = throttle_tracker(&output);
+    let top = toplevel(&tracker, "top");
+    for _ in 0..5 {
+        warn!(top ; "queue full");
+    }
+    warn!(top ; "queue empty");
+
+    assert_eq!(
+        messages(&output),
+        [
+            "queue full",
+            "queue full",
+            "last message repeated 3 times",
+            "queue empty"
+        ]
+    );
+}
+
+#[test]
+fn repeats_are_written_at_shutdown() {
+    let output = SharedVec::default();
+    let tracker = throttle_tracker(&output);
+    let top = toplevel(&tracker, "top");
+    for _ in 0..4 {
+        warn!(top ; "queue full");
+    }
+    tracker.shutdown();
+
+    assert_eq!(
+        messages(&output),
+        ["queue full", "queue full", "last message repeated 2 times"]
+    );
+}
+
+#[test]
+fn entities_are_throttled_separately() {
+    let output = SharedVec::default();
+    let tracker = throttle_tracker(&output);
+    let top = toplevel(&tracker, "top");
+    let node = Entity::new(&top, "node");
+    for _ in 0..3 {
+        warn!(top ; "queue full");
+        warn!(node ; "queue full");
+    }
+
+    // Each entity writes the message twice before it is dropped
+    assert_eq!(messages(&output), ["queue full"; 4]);
+}
+
+#[test]
+fn other_levels_are_not_throttled() {
+    let output = SharedVec::default();
+    let tracker = throttle_tracker(&output);
+    let top = toplevel(&tracker, "top");
+    for _ in 0..3 {
+        info!(top ; "tick");
+    }
+
+    assert_eq!(messages(&output), ["tick"; 3]);
+}
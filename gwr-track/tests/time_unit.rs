@@ -0,0 +1,120 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::rc::Rc;
+
+use gwr_track::entity::toplevel;
+use gwr_track::test_helpers::SharedVec;
+use gwr_track::trace_visitor::{TraceVisitor, process_capnp};
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{CapnProtoTracker, EntityManager, TextTracker};
+use gwr_track::{Id, Tracker, Writer};
+
+#[derive(Default)]
+struct TimeVisitor {
+    time_unit: Option<TimeUnit>,
+    times: Vec<(u64, TimeUnit)>,
+}
+
+impl TraceVisitor for TimeVisitor {
+    fn time_unit(&mut self, time_unit: TimeUnit) {
+        self.time_unit = Some(time_unit);
+    }
+
+    fn time(&mut self, _id: Id, ticks: u64, time_unit: TimeUnit) {
+        self.times.push((ticks, time_unit));
+    }
+}
+
+#[test]
+fn text_time_is_written_in_time_unit() {
+    let output = SharedVec::default();
+    let tracker: Tracker = Rc::new(
+        TextTracker::new(
+            EntityManager::new(log::Level::Trace),
+            Box::new(output.clone()),
+        )
+        .with_time_unit(TimeUnit::Ps),
+    );
+    let top = toplevel(&tracker, "top");
+    tracker.time(top.id, 250, TimeUnit::Ps);
+
+    let text = output.text();
+    assert!(text.contains(&format!("{}: set time to 250.0ps", top.id)));
+}
+
+#[test]
+fn capnp_time_unit_is_read_back() {
+    let path = std::env::temp_dir().join(format!("gwr-track-time-unit-{}.bin", std::process::id()));
+    let writer: Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(
+        CapnProtoTracker::new(EntityManager::new(log::Level::Trace), writer)
+            .with_time_unit(TimeUnit::Ps),
+    );
+    {
+        let top = toplevel(&tracker, "top");
+        tracker.time(top.id, 250, TimeUnit::Ps);
+        tracker.time(top.id, 1500, TimeUnit::Ps);
+    }
+    tracker.shutdown();
+
+    let mut visitor = TimeVisitor::default();
+    process_capnp(BufReader::new(fs::File::open(&path).unwrap()), &mut visitor);
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(visitor.time_unit, Some(TimeUnit::Ps));
+    assert_eq!(visitor.times, [(250, TimeUnit::Ps), (1500, TimeUnit::Ps)]);
+}
+
+#[test]
+fn capnp_times_between_ticks_are_read_in_ps() {
+    let path = std::env::temp_dir().join(format!(
+        "gwr-track-time-fraction-{}.bin",
+        std::process::id()
+    ));
+    let writer: Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(CapnProtoTracker::new(
+        EntityManager::new(log::Level::Trace),
+        writer,
+    ));
+    {
+        let top = toplevel(&tracker, "top");
+        tracker.time(top.id, 2000, TimeUnit::Ps);
+        tracker.time(top.id, 2500, TimeUnit::Ps);
+    }
+    tracker.shutdown();
+
+    let mut visitor = TimeVisitor::default();
+    process_capnp(BufReader::new(fs::File::open(&path).unwrap()), &mut visitor);
+    fs::remove_file(path).unwrap();
+
+    // The trace is in ns, so only the time with a fraction of a ns is in ps
+    assert_eq!(visitor.time_unit, None);
+    assert_eq!(visitor.times, [(2, TimeUnit::Ns), (2500, TimeUnit::Ps)]);
+}
+
+#[test]
+fn conversions_round_trip() {
+    for time_unit in [TimeUnit::Ps, TimeUnit::Ns, TimeUnit::Us] {
+        assert_eq!(time_unit.to_ns(time_unit.from_ns(2.0)), 2.0);
+    }
+    assert_eq!(TimeUnit::Us.from_ns(1500.0), 1.5);
+}
+
+#[test]
+fn ticks_are_converted_between_units() {
+    assert_eq!(TimeUnit::Ps.convert(2500, TimeUnit::Ns), 2.5);
+    assert_eq!(TimeUnit::Us.convert(3, TimeUnit::Ps), 3_000_000.0);
+    assert_eq!(TimeUnit::Ns.convert_ticks(3, TimeUnit::Ps), 3000);
+    assert_eq!(TimeUnit::Ps.convert_ticks(2500, TimeUnit::Ns), 3);
+    assert_eq!(TimeUnit::Ps.convert_ticks(2499, TimeUnit::Ns), 2);
+}
+
+#[test]
+fn ticks_are_rounded_to_the_nearest_unit() {
+    // 2.01 * 1000.0 is just below 2010.0
+    assert_eq!(TimeUnit::Ps.ticks_from_ns(2.01), 2010);
+    assert_eq!(TimeUnit::Ns.ticks_from_ns(2.6), 3);
+    assert_eq!(TimeUnit::Us.ticks_from_ns(1499.0), 1);
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use gwr_track::entity::toplevel;
+use gwr_track::trace_visitor::{
+    ParallelTraceEvents, TraceEvent, TraceEvents, TraceVisitor, process_capnp,
+    process_capnp_parallel,
+};
+use gwr_track::tracker::types::TimeUnit;
+use gwr_track::tracker::{CapnProtoTracker, EntityManager};
+use gwr_track::{Id, Tracker, Writer};
+
+/// Write a trace with enough events to be split into several batches
+fn write_trace(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "gwr-track-trace-events-{name}-{}.bin",
+        std::process::id()
+    ));
+    let writer: Writer = Box::new(BufWriter::new(fs::File::create(&path).unwrap()));
+    let tracker: Tracker = Rc::new(
+        CapnProtoTracker::new(EntityManager::new(log::Level::Trace), writer)
+            .with_time_unit(TimeUnit::Ps),
+    );
+    {
+        let top = toplevel(&tracker, "top");
+        for i in 0..5000 {
+            tracker.time(top.id, u64::from(i), TimeUnit::Ns);
+            tracker.value(top.id, f64::from(i));
+        }
+    }
+    tracker.shutdown();
+    path
+}
+
+fn open(path: &Path) -> BufReader<fs::File> {
+    BufReader::new(fs::File::open(path).unwrap())
+}
+
+#[derive(Default)]
+struct ValueVisitor {
+    values: Vec<f64>,
+    times: Vec<(u64, TimeUnit)>,
+}
+
+impl TraceVisitor for ValueVisitor {
+    fn value(&mut self, _id: Id, value: f64) {
+        self.values.push(value);
+    }
+
+    fn time(&mut self, _id: Id, ticks: u64, time_unit: TimeUnit) {
+        self.times.push((ticks, time_unit));
+    }
+}
+
+#[test]
+fn events_are_read_in_order() {
+    let path = write_trace("order");
+    let events: Vec<TraceEvent> = TraceEvents::new(open(&path)).collect();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(events[0], TraceEvent::TimeUnit(TimeUnit::Ps));
+    let values: Vec<f64> = events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Value { value, .. } => Some(*value),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(values, (0..5000).map(f64::from).collect::<Vec<_>>());
+}
+
+#[test]
+fn parallel_events_match_sequential_events() {
+    let path = write_trace("parallel");
+    let sequential: Vec<TraceEvent> = TraceEvents::new(open(&path)).collect();
+    let parallel: Vec<TraceEvent> = ParallelTraceEvents::new(open(&path), 4).collect();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn parallel_events_are_read_with_few_batches_in_flight() {
+    // A single thread may only read a couple of batches ahead of the
+    // iterator, which must not stop it reading the rest of the trace
+    let path = write_trace("in-flight");
+    let sequential: Vec<TraceEvent> = TraceEvents::new(open(&path)).collect();
+    let parallel: Vec<TraceEvent> = ParallelTraceEvents::new(open(&path), 1).collect();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn parallel_visitor_matches_visitor() {
+    let path = write_trace("visitor");
+    let mut sequential = ValueVisitor::default();
+    process_capnp(open(&path), &mut sequential);
+    let mut parallel = ValueVisitor::default();
+    process_capnp_parallel(open(&path), &mut parallel, 3);
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(sequential.values.len(), 5000);
+    assert_eq!(parallel.values, sequential.values);
+    assert_eq!(parallel.times, sequential.times);
+
+    // Times are given in ns but visited in the ps they were written in
+    assert_eq!(
+        parallel.times,
+        (0..5000)
+            .map(|i| (i * 1000, TimeUnit::Ps))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn parallel_events_can_be_dropped_early() {
+    let path = write_trace("dropped");
+    let first: Vec<TraceEvent> = ParallelTraceEvents::new(open(&path), 2).take(10).collect();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(first.len(), 10);
+}
@@ -0,0 +1,148 @@
+// Copyright (c) 2026 Graphcore Ltd. All rights reserved.
+
+use std::fs;
+use std::path::PathBuf;
+
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
+use gwr_track::builder::{TrackerArgs, setup_trackers_from_args};
+use gwr_track::tracker::types::{EventKind, TimeUnit};
+
+#[derive(Parser)]
+struct Cli {
+    #[command(flatten)]
+    tracker: TrackerArgs,
+}
+
+fn config_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gwr-track-{}-{name}", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn parse(args: &[&str]) -> (TrackerArgs, ArgMatches) {
+    let matches = Cli::command().get_matches_from([&["test"][..], args].concat());
+    let cli = Cli::from_arg_matches(&matches).unwrap();
+    (cli.tracker, matches)
+}
+
+fn load(args: &[&str]) -> TrackerArgs {
+    let (mut args, matches) = parse(args);
+    args.load_track_config(&matches).unwrap();
+    args
+}
+
+#[test]
+fn arguments_take_precedence_over_toml_options() {
+    let path = config_file(
+        "config.toml",
+        r#"
+binary = true
+binary_filter_regex = "top::fabric.*"
+stdout_level = "warn"
+time_unit = "us"
+event_kinds = "lifecycle,values"
+
+[[entity_levels]]
+regex = "top::fabric::node_0.*"
+level = "trace"
+"#,
+    );
+    let args = load(&[
+        "--track-config",
+        path.to_str().unwrap(),
+        "--stdout",
+        "--stdout-level",
+        "debug",
+    ]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(args.stdout);
+    assert_eq!(args.stdout_level, log::Level::Debug);
+    assert!(args.binary);
+    assert_eq!(args.binary_filter_regex, "top::fabric.*");
+    assert_eq!(args.binary_file, "trace.bin");
+    assert_eq!(args.time_unit, TimeUnit::Us);
+    assert!(args.event_kinds.contains(EventKind::Values));
+    assert!(!args.event_kinds.contains(EventKind::Messages));
+    assert_eq!(args.entity_levels.len(), 1);
+    assert_eq!(args.entity_levels[0].regex, "top::fabric::node_0.*");
+    assert_eq!(args.entity_levels[0].level, log::Level::Trace);
+    assert_eq!(args.track_config.as_deref(), path.to_str());
+}
+
+#[test]
+fn arguments_equal_to_their_default_take_precedence() {
+    let path = config_file("default.toml", "stdout_level = \"warn\"\nbinary = true\n");
+    let args = load(&[
+        "--track-config",
+        path.to_str().unwrap(),
+        "--stdout-level",
+        "info",
+    ]);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(args.stdout_level, log::Level::Info);
+    assert!(args.binary);
+}
+
+#[test]
+fn yaml_options_are_loaded() {
+    let path = config_file(
+        "config.yaml",
+        "json: true\njson_level: info\nmonitor_window_ticks: 1000\n",
+    );
+    let args = load(&["--track-config", path.to_str().unwrap()]);
+    fs::remove_file(&path).unwrap();
+
+    assert!(args.json);
+    assert_eq!(args.json_level, log::Level::Info);
+    assert_eq!(args.monitor_window_ticks, Some(1000));
+    assert!(!args.stdout);
+}
+
+#[test]
+fn entity_levels_are_given_as_arguments() {
+    let args = load(&[
+        "--entity-level",
+        "top::a=b.*=debug",
+        "--entity-level",
+        "x=warn",
+    ]);
+    assert_eq!(args.entity_levels.len(), 2);
+    assert_eq!(args.entity_levels[0].regex, "top::a=b.*");
+    assert_eq!(args.entity_levels[0].level, log::Level::Debug);
+    assert_eq!(args.entity_levels[1].level, log::Level::Warn);
+
+    assert!(Cli::try_parse_from(["test", "--entity-level", "top"]).is_err());
+}
+
+#[test]
+fn unknown_config_file_is_an_error() {
+    let (mut args, matches) = parse(&["--track-config", "does-not-exist.toml"]);
+    assert!(args.load_track_config(&matches).is_err());
+    assert!(setup_trackers_from_args(&mut args, &matches).is_err());
+}
+
+#[test]
+fn trackers_are_set_up_with_the_config_file() {
+    let path = config_file("setup.toml", "stdout_level = \"warn\"\n");
+    let (mut args, matches) = parse(&["--track-config", path.to_str().unwrap()]);
+    let result = setup_trackers_from_args(&mut args, &matches);
+    fs::remove_file(&path).unwrap();
+
+    assert!(result.is_ok());
+    assert_eq!(args.stdout_level, log::Level::Warn);
+}
+
+#[test]
+fn options_changed_after_loading_are_kept() {
+    let path = config_file("changed.toml", "stdout_level = \"warn\"\n");
+    let (mut args, matches) = parse(&["--track-config", path.to_str().unwrap()]);
+    args.load_track_config(&matches).unwrap();
+    args.stdout_level = log::Level::Error;
+    let result = setup_trackers_from_args(&mut args, &matches);
+    fs::remove_file(&path).unwrap();
+
+    assert!(result.is_ok());
+    assert_eq!(args.stdout_level, log::Level::Error);
+}